@@ -0,0 +1,466 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A TSBS style benchmark. Generates the `cpu-only` workload of the
+//! [Time Series Benchmark Suite](https://github.com/timescale/tsbs), loads it through the
+//! client and then runs a canned mix of queries against it, reporting throughput and
+//! p50/p95/p99 latency of both phases.
+
+#![allow(clippy::print_stdout)]
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use client::api::v1::column::{SemanticType, Values};
+use client::api::v1::{Column, ColumnDataType, ColumnDef, CreateTableExpr, InsertRequest, TableId};
+use client::{Client, Database};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::task::JoinSet;
+
+const DATABASE_NAME: &str = "greptime";
+const CATALOG_NAME: &str = "greptime";
+const SCHEMA_NAME: &str = "public";
+const TABLE_NAME: &str = "cpu";
+
+const REGIONS: [&str; 4] = ["us-east-1", "us-west-1", "eu-central-1", "ap-southeast-1"];
+const FIELDS: [&str; 10] = [
+    "usage_user",
+    "usage_system",
+    "usage_idle",
+    "usage_nice",
+    "usage_iowait",
+    "usage_irq",
+    "usage_softirq",
+    "usage_steal",
+    "usage_guest",
+    "usage_guest_nice",
+];
+
+#[derive(Parser, Clone)]
+#[command(name = "TSBS benchmark runner")]
+struct Args {
+    /// Number of hosts to simulate, which is the cardinality of the series.
+    #[arg(short = 'c', long = "scale", default_value_t = 100)]
+    scale: usize,
+
+    /// Number of points generated for each host.
+    #[arg(short = 'p', long = "points-per-host", default_value_t = 1000)]
+    points_per_host: usize,
+
+    /// Interval between two points of the same host, in milliseconds.
+    #[arg(long = "interval-ms", default_value_t = 10_000)]
+    interval_ms: i64,
+
+    /// Timestamp of the first point, in milliseconds.
+    #[arg(long = "start-ts", default_value_t = 1_672_531_200_000)]
+    start_ts: i64,
+
+    /// Batch size of insert request.
+    #[arg(short = 's', long = "batch-size", default_value_t = 1000)]
+    batch_size: usize,
+
+    /// Number of concurrent clients, on both write and read.
+    #[arg(short = 't', long = "concurrency", default_value_t = 4)]
+    concurrency: usize,
+
+    /// Number of iterations of each query.
+    #[arg(short = 'i', long = "iter-num", default_value_t = 10)]
+    iter_num: usize,
+
+    /// Seed of the data generator.
+    #[arg(long = "seed", default_value_t = 42)]
+    seed: u64,
+
+    #[arg(long = "skip-write")]
+    skip_write: bool,
+
+    #[arg(long = "skip-read")]
+    skip_read: bool,
+
+    #[arg(short, long, default_value_t = String::from("127.0.0.1:3001"))]
+    endpoint: String,
+}
+
+/// Latencies of a set of requests.
+#[derive(Default)]
+struct Latencies {
+    samples: Vec<Duration>,
+}
+
+impl Latencies {
+    fn record(&mut self, elapsed: Duration) {
+        self.samples.push(elapsed);
+    }
+
+    fn merge(&mut self, other: Latencies) {
+        self.samples.extend(other.samples);
+    }
+
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns the `p`-th percentile (0 ~ 100) by nearest rank, `samples` must be sorted.
+    fn percentile(&self, p: f64) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = ((p / 100.0) * self.samples.len() as f64).ceil() as usize;
+        self.samples[rank.clamp(1, self.samples.len()) - 1]
+    }
+
+    fn report(&mut self, name: &str, total_elapsed: Duration, rows: Option<usize>) {
+        self.samples.sort_unstable();
+        let secs = total_elapsed.as_secs_f64();
+        let rows_throughput = rows
+            .map(|rows| format!(", {:.2} rows/s", rows as f64 / secs))
+            .unwrap_or_default();
+        println!(
+            "{name}: {} requests in {:.2}s, {:.2} req/s{rows_throughput}, p50 {:.2}ms, p95 {:.2}ms, p99 {:.2}ms, max {:.2}ms",
+            self.len(),
+            secs,
+            self.len() as f64 / secs,
+            as_millis_f64(self.percentile(50.0)),
+            as_millis_f64(self.percentile(95.0)),
+            as_millis_f64(self.percentile(99.0)),
+            as_millis_f64(self.samples.last().copied().unwrap_or_default()),
+        );
+    }
+}
+
+fn as_millis_f64(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn host_name(host: usize) -> String {
+    format!("host_{host}")
+}
+
+/// Generates the rows of the hosts assigned to one client.
+struct DataGenerator {
+    hosts: Vec<usize>,
+    /// Last value of each field of each host, values do a random walk.
+    values: Vec<[f64; FIELDS.len()]>,
+    rng: StdRng,
+    start_ts: i64,
+    interval_ms: i64,
+    points_per_host: usize,
+    /// Index of the next point to generate.
+    point: usize,
+    /// Index of the next host to generate in current point.
+    host_idx: usize,
+}
+
+impl DataGenerator {
+    fn new(hosts: Vec<usize>, args: &Args, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let values = hosts
+            .iter()
+            .map(|_| [0.0; FIELDS.len()].map(|_: f64| rng.gen_range(0.0..100.0)))
+            .collect();
+        DataGenerator {
+            hosts,
+            values,
+            rng,
+            start_ts: args.start_ts,
+            interval_ms: args.interval_ms,
+            points_per_host: args.points_per_host,
+            point: 0,
+            host_idx: 0,
+        }
+    }
+
+    /// Generates next batch of at most `batch_size` rows, returns `None` if all rows are
+    /// generated.
+    fn next_batch(&mut self, batch_size: usize) -> Option<InsertRequest> {
+        if self.hosts.is_empty() || self.point >= self.points_per_host {
+            return None;
+        }
+
+        let mut hostnames = Vec::with_capacity(batch_size);
+        let mut regions = Vec::with_capacity(batch_size);
+        let mut datacenters = Vec::with_capacity(batch_size);
+        let mut timestamps = Vec::with_capacity(batch_size);
+        let mut fields = vec![Vec::with_capacity(batch_size); FIELDS.len()];
+
+        while timestamps.len() < batch_size && self.point < self.points_per_host {
+            let host = self.hosts[self.host_idx];
+            let region = REGIONS[host % REGIONS.len()];
+            hostnames.push(host_name(host));
+            regions.push(region.to_string());
+            datacenters.push(format!(
+                "{region}{}",
+                ["a", "b", "c"][(host / REGIONS.len()) % 3]
+            ));
+            timestamps.push(self.start_ts + self.point as i64 * self.interval_ms);
+            for (field, value) in fields.iter_mut().zip(self.values[self.host_idx].iter_mut()) {
+                *value = (*value + self.rng.gen_range(-5.0..5.0)).clamp(0.0, 100.0);
+                field.push(*value);
+            }
+
+            self.host_idx += 1;
+            if self.host_idx == self.hosts.len() {
+                self.host_idx = 0;
+                self.point += 1;
+            }
+        }
+
+        let row_count = timestamps.len();
+        let mut columns = vec![
+            string_column("hostname", hostnames),
+            string_column("region", regions),
+            string_column("datacenter", datacenters),
+            Column {
+                column_name: "ts".to_string(),
+                semantic_type: SemanticType::Timestamp as i32,
+                values: Some(Values {
+                    ts_millisecond_values: timestamps,
+                    ..Default::default()
+                }),
+                null_mask: vec![],
+                datatype: ColumnDataType::TimestampMillisecond as i32,
+            },
+        ];
+        columns.extend(FIELDS.iter().zip(fields).map(|(name, values)| Column {
+            column_name: name.to_string(),
+            semantic_type: SemanticType::Field as i32,
+            values: Some(Values {
+                f64_values: values,
+                ..Default::default()
+            }),
+            null_mask: vec![],
+            datatype: ColumnDataType::Float64 as i32,
+        }));
+
+        Some(InsertRequest {
+            schema_name: SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            region_number: 0,
+            columns,
+            row_count: row_count as _,
+        })
+    }
+}
+
+fn string_column(name: &str, values: Vec<String>) -> Column {
+    Column {
+        column_name: name.to_string(),
+        semantic_type: SemanticType::Tag as i32,
+        values: Some(Values {
+            string_values: values,
+            ..Default::default()
+        }),
+        null_mask: vec![],
+        datatype: ColumnDataType::String as i32,
+    }
+}
+
+fn create_table_expr() -> CreateTableExpr {
+    let mut column_defs = ["hostname", "region", "datacenter"]
+        .iter()
+        .map(|name| ColumnDef {
+            name: name.to_string(),
+            datatype: ColumnDataType::String as i32,
+            is_nullable: true,
+            default_constraint: vec![],
+            semantic_type: None,
+        })
+        .collect::<Vec<_>>();
+    column_defs.push(ColumnDef {
+        name: "ts".to_string(),
+        datatype: ColumnDataType::TimestampMillisecond as i32,
+        is_nullable: false,
+        default_constraint: vec![],
+        semantic_type: None,
+    });
+    column_defs.extend(FIELDS.iter().map(|name| ColumnDef {
+        name: name.to_string(),
+        datatype: ColumnDataType::Float64 as i32,
+        is_nullable: true,
+        default_constraint: vec![],
+        semantic_type: None,
+    }));
+
+    CreateTableExpr {
+        catalog_name: CATALOG_NAME.to_string(),
+        schema_name: SCHEMA_NAME.to_string(),
+        table_name: TABLE_NAME.to_string(),
+        desc: "".to_string(),
+        column_defs,
+        time_index: "ts".to_string(),
+        primary_keys: vec![
+            "hostname".to_string(),
+            "region".to_string(),
+            "datacenter".to_string(),
+        ],
+        create_if_not_exists: true,
+        table_options: Default::default(),
+        region_ids: vec![0],
+        table_id: Some(TableId { id: 0 }),
+    }
+}
+
+/// Canned query mix, modeled after the TSBS `cpu-only` queries.
+fn query_set(args: &Args) -> Vec<(&'static str, String)> {
+    let start = args.start_ts;
+    let end = start + args.points_per_host as i64 * args.interval_ms;
+    // Queries touching a time window scan the first hour, or the whole data if shorter.
+    let hour_end = end.min(start + 3_600_000);
+    let host = host_name(0);
+
+    vec![
+        (
+            "single-groupby-1-1-1",
+            format!(
+                "SELECT MAX(usage_user) FROM {TABLE_NAME} WHERE hostname = '{host}' AND ts >= {start} AND ts < {hour_end}"
+            ),
+        ),
+        (
+            "single-groupby-5-1-1",
+            format!(
+                "SELECT MAX(usage_user), MAX(usage_system), MAX(usage_idle), MAX(usage_nice), MAX(usage_iowait) FROM {TABLE_NAME} WHERE hostname = '{host}' AND ts >= {start} AND ts < {hour_end}"
+            ),
+        ),
+        (
+            "double-groupby-1",
+            format!(
+                "SELECT hostname, AVG(usage_user) FROM {TABLE_NAME} WHERE ts >= {start} AND ts < {hour_end} GROUP BY hostname"
+            ),
+        ),
+        (
+            "high-cpu-1",
+            format!(
+                "SELECT * FROM {TABLE_NAME} WHERE usage_user > 90.0 AND hostname = '{host}' AND ts >= {start} AND ts < {end}"
+            ),
+        ),
+        (
+            "lastpoint",
+            format!("SELECT hostname, MAX(ts) FROM {TABLE_NAME} GROUP BY hostname"),
+        ),
+        ("count-all", format!("SELECT COUNT(*) FROM {TABLE_NAME}")),
+    ]
+}
+
+async fn write_data(
+    db: Database,
+    mut generator: DataGenerator,
+    batch_size: usize,
+    progress_bar: ProgressBar,
+) -> Latencies {
+    let mut latencies = Latencies::default();
+    while let Some(request) = generator.next_batch(batch_size) {
+        let row_count = request.row_count;
+        let now = Instant::now();
+        db.insert(request).await.unwrap();
+        latencies.record(now.elapsed());
+        progress_bar.inc(row_count as _);
+    }
+    latencies
+}
+
+async fn do_write(args: &Args, db: &Database) {
+    let create_table_result = db.create(create_table_expr()).await;
+    println!("Create table result: {create_table_result:?}");
+
+    let total_rows = args.scale * args.points_per_host;
+    let progress_bar = ProgressBar::new(total_rows as _);
+    progress_bar.set_style(
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:60.cyan/blue} {pos:>7}/{len:7}")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    // Hosts are assigned to clients in a round robin way.
+    let concurrency = args.concurrency.max(1);
+    let mut write_jobs = JoinSet::new();
+    let now = Instant::now();
+    for i in 0..concurrency {
+        let hosts = (i..args.scale).step_by(concurrency).collect();
+        let generator = DataGenerator::new(hosts, args, args.seed.wrapping_add(i as u64));
+        let db = db.clone();
+        let batch_size = args.batch_size.max(1);
+        let progress_bar = progress_bar.clone();
+        write_jobs.spawn(write_data(db, generator, batch_size, progress_bar));
+    }
+
+    let mut latencies = Latencies::default();
+    while let Some(res) = write_jobs.join_next().await {
+        latencies.merge(res.unwrap());
+    }
+    let elapsed = now.elapsed();
+    progress_bar.finish();
+
+    latencies.report("insert", elapsed, Some(total_rows));
+}
+
+async fn run_query(db: Database, query: String, iter_num: usize) -> Latencies {
+    let mut latencies = Latencies::default();
+    for _ in 0..iter_num {
+        let now = Instant::now();
+        let _res = db.sql(&query).await.unwrap();
+        latencies.record(now.elapsed());
+    }
+    latencies
+}
+
+async fn do_query(args: &Args, db: &Database) {
+    let concurrency = args.concurrency.max(1);
+    let mut overall = Latencies::default();
+    let overall_now = Instant::now();
+    for (query_name, query) in query_set(args) {
+        println!("Running query: {query}");
+        // Spreads the iterations of the query over the clients.
+        let mut query_jobs = JoinSet::new();
+        let now = Instant::now();
+        for i in 0..concurrency {
+            let iter_num =
+                args.iter_num / concurrency + usize::from(i < args.iter_num % concurrency);
+            if iter_num > 0 {
+                query_jobs.spawn(run_query(db.clone(), query.clone(), iter_num));
+            }
+        }
+
+        let mut latencies = Latencies::default();
+        while let Some(res) = query_jobs.join_next().await {
+            latencies.merge(res.unwrap());
+        }
+        latencies.report(query_name, now.elapsed(), None);
+        overall.merge(latencies);
+    }
+    overall.report("all queries", overall_now.elapsed(), None);
+}
+
+fn main() {
+    let args = Args::parse();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(args.concurrency.max(1))
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let client = Client::with_urls(vec![&args.endpoint]);
+            let db = Database::new(DATABASE_NAME, client);
+
+            if !args.skip_write {
+                do_write(&args, &db).await;
+            }
+
+            if !args.skip_read {
+                do_query(&args, &db).await;
+            }
+        })
+}
@@ -21,9 +21,11 @@ fn main() {
         .compile(
             &[
                 "greptime/v1/greptime.proto",
+                "greptime/v1/meta/cluster.proto",
                 "greptime/v1/meta/common.proto",
                 "greptime/v1/meta/heartbeat.proto",
                 "greptime/v1/meta/route.proto",
+                "greptime/v1/meta/sequence.proto",
                 "greptime/v1/meta/store.proto",
                 "prometheus/remote/remote.proto",
             ],
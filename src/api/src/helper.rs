@@ -54,6 +54,8 @@ impl From<ColumnDataTypeWrapper> for ConcreteDataType {
             ColumnDataType::Float32 => ConcreteDataType::float32_datatype(),
             ColumnDataType::Float64 => ConcreteDataType::float64_datatype(),
             ColumnDataType::Binary => ConcreteDataType::binary_datatype(),
+            ColumnDataType::Json => ConcreteDataType::json_datatype(),
+            ColumnDataType::Uuid => ConcreteDataType::uuid_datatype(),
             ColumnDataType::String => ConcreteDataType::string_datatype(),
             ColumnDataType::Date => ConcreteDataType::date_datatype(),
             ColumnDataType::Datetime => ConcreteDataType::datetime_datatype(),
@@ -88,6 +90,8 @@ impl TryFrom<ConcreteDataType> for ColumnDataTypeWrapper {
             ConcreteDataType::Float32(_) => ColumnDataType::Float32,
             ConcreteDataType::Float64(_) => ColumnDataType::Float64,
             ConcreteDataType::Binary(_) => ColumnDataType::Binary,
+            ConcreteDataType::Json(_) => ColumnDataType::Json,
+            ConcreteDataType::Uuid(_) => ColumnDataType::Uuid,
             ConcreteDataType::String(_) => ColumnDataType::String,
             ConcreteDataType::Date(_) => ColumnDataType::Date,
             ConcreteDataType::DateTime(_) => ColumnDataType::Datetime,
@@ -152,7 +156,7 @@ impl Values {
                 f64_values: Vec::with_capacity(capacity),
                 ..Default::default()
             },
-            ColumnDataType::Binary => Values {
+            ColumnDataType::Binary | ColumnDataType::Json | ColumnDataType::Uuid => Values {
                 binary_values: Vec::with_capacity(capacity),
                 ..Default::default()
             },
@@ -346,6 +350,10 @@ mod tests {
             ConcreteDataType::binary_datatype(),
             ColumnDataTypeWrapper(ColumnDataType::Binary).into()
         );
+        assert_eq!(
+            ConcreteDataType::uuid_datatype(),
+            ColumnDataTypeWrapper(ColumnDataType::Uuid).into()
+        );
         assert_eq!(
             ConcreteDataType::string_datatype(),
             ColumnDataTypeWrapper(ColumnDataType::String).into()
@@ -414,6 +422,10 @@ mod tests {
             ColumnDataTypeWrapper(ColumnDataType::Binary),
             ConcreteDataType::binary_datatype().try_into().unwrap()
         );
+        assert_eq!(
+            ColumnDataTypeWrapper(ColumnDataType::Uuid),
+            ConcreteDataType::uuid_datatype().try_into().unwrap()
+        );
         assert_eq!(
             ColumnDataTypeWrapper(ColumnDataType::String),
             ConcreteDataType::string_datatype().try_into().unwrap()
@@ -15,7 +15,7 @@
 pub use prost::DecodeError;
 use prost::Message;
 
-use crate::v1::meta::TableRouteValue;
+use crate::v1::meta::{Instruction, TableRouteValue};
 
 macro_rules! impl_convert_with_bytes {
     ($data_type: ty) => {
@@ -36,3 +36,4 @@ macro_rules! impl_convert_with_bytes {
 }
 
 impl_convert_with_bytes!(TableRouteValue);
+impl_convert_with_bytes!(Instruction);
@@ -62,6 +62,7 @@ impl RequestHeader {
             protocol_version: PROTOCOL_VERSION,
             cluster_id,
             member_id,
+            ..Default::default()
         }
     }
 }
@@ -118,6 +119,19 @@ impl Error {
             err_msg: "Current server is not leader".to_string(),
         }
     }
+
+    /// Returns a `NotLeader` error, with the address of the leader as a hint if known.
+    #[inline]
+    pub fn not_leader(leader: Option<&str>) -> Self {
+        let err_msg = match leader {
+            Some(leader) => format!("Current server is not leader, leader: {leader}"),
+            None => "Current server is not leader".to_string(),
+        };
+        Self {
+            code: ErrorCode::NotLeader as i32,
+            err_msg,
+        }
+    }
 }
 
 impl HeartbeatResponse {
@@ -146,11 +160,35 @@ gen_set_header!(RouteRequest);
 gen_set_header!(CreateRequest);
 gen_set_header!(RangeRequest);
 gen_set_header!(DeleteRequest);
+gen_set_header!(RenameRequest);
 gen_set_header!(PutRequest);
 gen_set_header!(BatchPutRequest);
 gen_set_header!(CompareAndPutRequest);
 gen_set_header!(DeleteRangeRequest);
 gen_set_header!(MoveValueRequest);
+gen_set_header!(BatchMoveRequest);
+gen_set_header!(NextRequest);
+gen_set_header!(ListNodesRequest);
+
+macro_rules! gen_set_tenant {
+    ($req: ty) => {
+        impl $req {
+            /// Sets the tenant of the request, it should be called after the header is set.
+            #[inline]
+            pub fn set_tenant(&mut self, tenant: impl Into<String>) {
+                self.header.get_or_insert_with(Default::default).tenant = tenant.into();
+            }
+        }
+    };
+}
+
+gen_set_tenant!(RangeRequest);
+gen_set_tenant!(PutRequest);
+gen_set_tenant!(BatchPutRequest);
+gen_set_tenant!(CompareAndPutRequest);
+gen_set_tenant!(DeleteRangeRequest);
+gen_set_tenant!(MoveValueRequest);
+gen_set_tenant!(BatchMoveRequest);
 
 #[cfg(test)]
 mod tests {
@@ -206,4 +244,18 @@ mod tests {
             dict.into_peers()
         );
     }
+
+    #[test]
+    fn test_not_leader_error() {
+        let header = ResponseHeader::failed(1, Error::not_leader(Some("127.0.0.1:3002")));
+        assert!(header.is_not_leader());
+        assert_eq!(
+            "Current server is not leader, leader: 127.0.0.1:3002",
+            header.error.unwrap().err_msg
+        );
+
+        let header = ResponseHeader::failed(1, Error::not_leader(None));
+        assert!(header.is_not_leader());
+        assert_eq!(Error::is_not_leader(), header.error.unwrap());
+    }
 }
@@ -71,6 +71,17 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "System catalog version {} is newer than the supported version {}",
+        version,
+        supported
+    ))]
+    UnsupportedCatalogVersion {
+        version: u32,
+        supported: u32,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Catalog value is not present"))]
     EmptyValue { backtrace: Backtrace },
 
@@ -95,6 +106,9 @@ pub enum Error {
     #[snafu(display("Table `{}` already exists", table))]
     TableExists { table: String, backtrace: Backtrace },
 
+    #[snafu(display("Table `{}` not exist", table))]
+    TableNotExist { table: String, backtrace: Backtrace },
+
     #[snafu(display("Schema {} already exists", schema))]
     SchemaExists {
         schema: String,
@@ -132,6 +146,12 @@ pub enum Error {
         source: common_recordbatch::error::Error,
     },
 
+    #[snafu(display("Failed to create record batch, source: {}", source))]
+    CreateRecordBatch {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
     #[snafu(display(
         "Failed to insert table creation record to system catalog, source: {}",
         source
@@ -224,10 +244,13 @@ impl ErrorExt for Error {
                 StatusCode::Internal
             }
 
-            Error::ReadSystemCatalog { source, .. } => source.status_code(),
+            Error::ReadSystemCatalog { source, .. } | Error::CreateRecordBatch { source, .. } => {
+                source.status_code()
+            }
             Error::InvalidCatalogValue { source, .. } => source.status_code(),
 
             Error::TableExists { .. } => StatusCode::TableAlreadyExists,
+            Error::TableNotExist { .. } => StatusCode::TableNotFound,
             Error::SchemaExists { .. } => StatusCode::InvalidArguments,
 
             Error::OpenSystemCatalog { source, .. }
@@ -242,7 +265,9 @@ impl ErrorExt for Error {
             Error::InvalidTableInfoInCatalog { .. } => StatusCode::Unexpected,
             Error::Internal { source, .. } => source.status_code(),
 
-            Error::Unimplemented { .. } => StatusCode::Unsupported,
+            Error::Unimplemented { .. } | Error::UnsupportedCatalogVersion { .. } => {
+                StatusCode::Unsupported
+            }
         }
     }
 
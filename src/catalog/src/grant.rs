@@ -0,0 +1,99 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Privileges of the users on the schemas, granted by `GRANT` and revoked by `REVOKE`.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Privilege on all tables of a schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Privilege {
+    /// Queries the tables, e.g. `SELECT`, `SHOW TABLES` and `DESCRIBE TABLE`.
+    Read,
+    /// Writes the tables, e.g. `INSERT` and `DELETE`.
+    Write,
+    /// Creates, alters and drops the tables.
+    Ddl,
+}
+
+impl Privilege {
+    pub const ALL: [Privilege; 3] = [Privilege::Read, Privilege::Write, Privilege::Ddl];
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Privilege::Read => write!(f, "READ"),
+            Privilege::Write => write!(f, "WRITE"),
+            Privilege::Ddl => write!(f, "DDL"),
+        }
+    }
+}
+
+/// Privileges of a user on a schema.
+pub type Privileges = BTreeSet<Privilege>;
+
+/// Grants the privileges on a schema to a user, or revokes them from the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantRequest {
+    pub catalog: String,
+    pub schema: String,
+    pub user: String,
+    pub privileges: Vec<Privilege>,
+    /// Revokes the privileges instead of granting them.
+    pub revoke: bool,
+}
+
+impl GrantRequest {
+    /// Applies the request to the `privileges` the user has.
+    pub fn apply(&self, privileges: &mut Privileges) {
+        for privilege in &self.privileges {
+            if self.revoke {
+                let _ = privileges.remove(privilege);
+            } else {
+                let _ = privileges.insert(*privilege);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_grant_request() {
+        let mut request = GrantRequest {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            user: "foo".to_string(),
+            privileges: Privilege::ALL.to_vec(),
+            revoke: false,
+        };
+        let mut privileges = Privileges::new();
+        request.apply(&mut privileges);
+        assert_eq!(Privileges::from(Privilege::ALL), privileges);
+
+        request.privileges = vec![Privilege::Write, Privilege::Ddl];
+        request.revoke = true;
+        request.apply(&mut privileges);
+        assert_eq!(Privileges::from([Privilege::Read]), privileges);
+
+        assert_eq!(r#"["read"]"#, serde_json::to_string(&privileges).unwrap());
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod versioned;
+
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
@@ -24,10 +26,16 @@ use serde::{Deserialize, Serialize, Serializer};
 use snafu::{ensure, OptionExt, ResultExt};
 use table::metadata::{RawTableInfo, TableId, TableVersion};
 
+use crate::grant::Privileges;
+use crate::helper::versioned::{Migration, VersionedValue};
+
 const CATALOG_KEY_PREFIX: &str = "__c";
 const SCHEMA_KEY_PREFIX: &str = "__s";
 const TABLE_GLOBAL_KEY_PREFIX: &str = "__tg";
 const TABLE_REGIONAL_KEY_PREFIX: &str = "__tr";
+const GRANT_KEY_PREFIX: &str = "__grant";
+/// Prefix of the table route keys, which are stored by metasrv.
+pub const TABLE_ROUTE_KEY_PREFIX: &str = "__meta_table_route";
 
 const ALPHANUMERICS_NAME_PATTERN: &str = "[a-zA-Z_][a-zA-Z0-9_]*";
 
@@ -67,6 +75,11 @@ pub fn build_schema_prefix(catalog_name: impl AsRef<str>) -> String {
     format!("{SCHEMA_KEY_PREFIX}-{}-", catalog_name.as_ref())
 }
 
+/// Builds the prefix of the global keys of all tables.
+pub fn build_all_table_global_prefix() -> String {
+    format!("{TABLE_GLOBAL_KEY_PREFIX}-")
+}
+
 pub fn build_table_global_prefix(
     catalog_name: impl AsRef<str>,
     schema_name: impl AsRef<str>,
@@ -78,6 +91,21 @@ pub fn build_table_global_prefix(
     )
 }
 
+/// Builds the key of the route of the table with `table_id`.
+pub fn build_table_route_key(
+    catalog_name: impl AsRef<str>,
+    schema_name: impl AsRef<str>,
+    table_name: impl AsRef<str>,
+    table_id: u64,
+) -> String {
+    format!(
+        "{TABLE_ROUTE_KEY_PREFIX}-{}-{}-{}-{table_id}",
+        catalog_name.as_ref(),
+        schema_name.as_ref(),
+        table_name.as_ref()
+    )
+}
+
 pub fn build_table_regional_prefix(
     catalog_name: impl AsRef<str>,
     schema_name: impl AsRef<str>,
@@ -141,6 +169,32 @@ impl TableGlobalValue {
     pub fn table_id(&self) -> TableId {
         self.table_info.ident.table_id
     }
+
+    pub fn parse(s: impl AsRef<str>) -> Result<Self, Error> {
+        Self::parse_with_version(s).map(|(value, _)| value)
+    }
+
+    /// Parses the value, returns it with the version it was encoded in.
+    pub fn parse_with_version(s: impl AsRef<str>) -> Result<(Self, u32), Error> {
+        versioned::decode(s.as_ref())
+    }
+
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, Error> {
+        Self::parse(String::from_utf8_lossy(bytes.as_ref()))
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(versioned::encode(self)?.into_bytes())
+    }
+}
+
+impl VersionedValue for TableGlobalValue {
+    const VERSION: u32 = 1;
+
+    fn migrations() -> &'static [Migration] {
+        // Version 1 has the same layout as the unversioned encoding.
+        &[versioned::identity]
+    }
 }
 
 /// Table regional info that varies between datanode, so it contains a `node_id` field.
@@ -253,6 +307,30 @@ impl SchemaKey {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SchemaValue;
 
+/// Key of the privileges of a user on a schema.
+pub struct GrantKey {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub user: String,
+}
+
+impl Display for GrantKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(GRANT_KEY_PREFIX)?;
+        f.write_str("-")?;
+        f.write_str(&self.catalog_name)?;
+        f.write_str("-")?;
+        f.write_str(&self.schema_name)?;
+        f.write_str("-")?;
+        f.write_str(&self.user)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GrantValue {
+    pub privileges: Privileges,
+}
+
 macro_rules! define_catalog_value {
     ( $($val_ty: ty), *) => {
             $(
@@ -276,12 +354,7 @@ macro_rules! define_catalog_value {
         }
 }
 
-define_catalog_value!(
-    TableRegionalValue,
-    TableGlobalValue,
-    CatalogValue,
-    SchemaValue
-);
+define_catalog_value!(TableRegionalValue, CatalogValue, SchemaValue, GrantValue);
 
 #[cfg(test)]
 mod tests {
@@ -326,6 +399,7 @@ mod tests {
             "__tg-CATALOG-SCHEMA-",
             build_table_global_prefix("CATALOG", "SCHEMA")
         );
+        assert_eq!("__tg-", build_all_table_global_prefix());
     }
 
     #[test]
@@ -369,5 +443,12 @@ mod tests {
         let serialized = serde_json::to_string(&value).unwrap();
         let deserialized = TableGlobalValue::parse(serialized).unwrap();
         assert_eq!(value, deserialized);
+
+        let (deserialized, version) = TableGlobalValue::parse_with_version(
+            String::from_utf8(value.as_bytes().unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(value, deserialized);
+        assert_eq!(TableGlobalValue::VERSION, version);
     }
 }
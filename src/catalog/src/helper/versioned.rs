@@ -0,0 +1,199 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Versioned json encoding of the catalog values stored in the meta kv store.
+//!
+//! A value is encoded as `{"version": <version>, "value": <value>}`. Values written
+//! before the encoding is versioned are bare json objects, which are treated as
+//! version 0. When decoding, a value in an old version is upgraded to the current
+//! version by applying the migrations of the value type one by one.
+
+use common_catalog::error::{
+    DeserializeCatalogEntryValueSnafu, Error, MigrateCatalogEntryValueSnafu,
+    SerializeCatalogEntryValueSnafu, UnsupportedValueVersionSnafu,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use snafu::{ensure, OptionExt, ResultExt};
+
+const VERSION_FIELD: &str = "version";
+const VALUE_FIELD: &str = "value";
+
+/// Upgrades a json value from one version to the next.
+pub type Migration = fn(Value) -> std::result::Result<Value, String>;
+
+/// Catalog value encoded with a version.
+pub trait VersionedValue: Serialize + DeserializeOwned {
+    /// Current version of the encoding.
+    const VERSION: u32;
+
+    /// Migrations of the encoding. The `i`-th migration upgrades version `i` to
+    /// `i + 1`, so there must be exactly [VERSION](Self::VERSION) migrations.
+    fn migrations() -> &'static [Migration];
+}
+
+/// Migration that keeps the value unchanged, for versions that only change the
+/// encoding but not the layout of the value.
+pub fn identity(value: Value) -> std::result::Result<Value, String> {
+    Ok(value)
+}
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    value: &'a T,
+}
+
+/// Encodes the value in current version.
+pub fn encode<T: VersionedValue>(value: &T) -> Result<String, Error> {
+    serde_json::to_string(&Versioned {
+        version: T::VERSION,
+        value,
+    })
+    .context(SerializeCatalogEntryValueSnafu)
+}
+
+/// Decodes the value and upgrades it to the current version, returns the value with
+/// the version it was encoded in.
+pub fn decode<T: VersionedValue>(s: &str) -> Result<(T, u32), Error> {
+    let json: Value =
+        serde_json::from_str(s).context(DeserializeCatalogEntryValueSnafu { raw: s })?;
+    let (version, mut value) = split_version(json);
+    ensure!(
+        version <= T::VERSION,
+        UnsupportedValueVersionSnafu {
+            version,
+            current: T::VERSION,
+        }
+    );
+
+    for v in version..T::VERSION {
+        let migration = T::migrations()
+            .get(v as usize)
+            .context(MigrateCatalogEntryValueSnafu {
+                version: v,
+                reason: "migration not found",
+            })?;
+        value = migration(value)
+            .map_err(|reason| MigrateCatalogEntryValueSnafu { version: v, reason }.build())?;
+    }
+
+    let value =
+        serde_json::from_value(value).context(DeserializeCatalogEntryValueSnafu { raw: s })?;
+    Ok((value, version))
+}
+
+/// Splits the json into the version and the value, json without a version is
+/// treated as a version 0 value.
+fn split_version(json: Value) -> (u32, Value) {
+    match json {
+        Value::Object(mut map) if is_versioned(&map) => {
+            let version = map[VERSION_FIELD].as_u64().unwrap_or_default() as u32;
+            let value = map.remove(VALUE_FIELD).unwrap_or_default();
+            (version, value)
+        }
+        json => (0, json),
+    }
+}
+
+fn is_versioned(map: &Map<String, Value>) -> bool {
+    map.len() == 2
+        && map.get(VERSION_FIELD).map_or(false, Value::is_u64)
+        && map.contains_key(VALUE_FIELD)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Foo {
+        name: String,
+        count: u64,
+    }
+
+    impl VersionedValue for Foo {
+        const VERSION: u32 = 2;
+
+        fn migrations() -> &'static [Migration] {
+            &[rename_id, add_count]
+        }
+    }
+
+    /// Version 1 renames `id` to `name`.
+    fn rename_id(mut value: Value) -> std::result::Result<Value, String> {
+        let map = value.as_object_mut().ok_or("not an object")?;
+        let id = map.remove("id").ok_or("missing id")?;
+        let _ = map.insert("name".to_string(), id);
+        Ok(value)
+    }
+
+    /// Version 2 adds `count`.
+    fn add_count(mut value: Value) -> std::result::Result<Value, String> {
+        let map = value.as_object_mut().ok_or("not an object")?;
+        let _ = map.insert("count".to_string(), Value::from(0));
+        Ok(value)
+    }
+
+    fn foo(count: u64) -> Foo {
+        Foo {
+            name: "foo".to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let value = foo(3);
+        let encoded = encode(&value).unwrap();
+        assert_eq!(r#"{"version":2,"value":{"name":"foo","count":3}}"#, encoded);
+        assert_eq!((value, 2), decode::<Foo>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn test_decode_old_versions() {
+        // Unversioned encoding.
+        assert_eq!((foo(0), 0), decode::<Foo>(r#"{"id":"foo"}"#).unwrap());
+        assert_eq!(
+            (foo(0), 1),
+            decode::<Foo>(r#"{"version":1,"value":{"name":"foo"}}"#).unwrap()
+        );
+
+        // A version 0 value in the new layout can't be migrated.
+        let err = decode::<Foo>(r#"{"version":0,"value":{"name":"foo"}}"#).unwrap_err();
+        assert!(
+            matches!(err, Error::MigrateCatalogEntryValue { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_decode_newer_version() {
+        let err = decode::<Foo>(r#"{"version":3,"value":{"name":"foo","count":1}}"#).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::UnsupportedValueVersion {
+                    version: 3,
+                    current: 2,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+    }
+}
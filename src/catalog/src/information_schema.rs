@@ -0,0 +1,627 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Virtual tables in the `information_schema` of each catalog.
+//!
+//! Tools connected through the MySQL or PostgreSQL protocol introspect schemas by
+//! querying `information_schema`. The tables here have no storage, their rows are
+//! synthesized from the metadata of the catalog on each scan.
+//!
+//! Besides the standard tables, `region_metrics` lists the metrics of the read and
+//! write path of each region of the tables.
+
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use common_catalog::consts::{
+    INFORMATION_SCHEMA_COLUMNS_TABLE_ID, INFORMATION_SCHEMA_ENGINES_TABLE_ID,
+    INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_REGION_METRICS_TABLE_ID,
+    INFORMATION_SCHEMA_TABLES_TABLE_ID,
+};
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::{RecordBatch, RecordBatches};
+use datatypes::prelude::{ConcreteDataType, DataType, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, UInt32Vector, UInt64Vector};
+use snafu::ResultExt;
+use table::error::TablesRecordBatchSnafu;
+use table::metadata::{TableId, TableInfoBuilder, TableInfoRef, TableMetaBuilder, TableType};
+use table::table::scan::SimpleTableScan;
+use table::{Table, TableRef};
+
+use crate::error::{CreateRecordBatchSnafu, Result, UnimplementedSnafu};
+use crate::{CatalogProviderRef, SchemaProvider, SchemaProviderRef};
+
+pub const TABLES: &str = "tables";
+pub const COLUMNS: &str = "columns";
+pub const ENGINES: &str = "engines";
+pub const REGION_METRICS: &str = "region_metrics";
+
+/// The default table engine, the same as `mito::engine::MITO_ENGINE`.
+const DEFAULT_ENGINE: &str = "mito";
+
+const SEMANTIC_TYPE_PRIMARY_KEY: &str = "PRIMARY KEY";
+const SEMANTIC_TYPE_VALUE: &str = "VALUE";
+const SEMANTIC_TYPE_TIME_INDEX: &str = "TIME INDEX";
+
+const YES: &str = "YES";
+const NO: &str = "NO";
+
+/// Provides `information_schema` of the catalog `catalog_name`.
+pub struct InformationSchemaProvider {
+    catalog_name: String,
+    catalog_provider: CatalogProviderRef,
+}
+
+impl InformationSchemaProvider {
+    pub fn new(catalog_name: String, catalog_provider: CatalogProviderRef) -> Self {
+        Self {
+            catalog_name,
+            catalog_provider,
+        }
+    }
+
+    /// Returns the schemas of the catalog, including the `information_schema` itself.
+    fn schemas(&self) -> Result<Vec<(String, SchemaProviderRef)>> {
+        let mut schemas = Vec::new();
+        for schema_name in self.catalog_provider.schema_names()? {
+            if schema_name == INFORMATION_SCHEMA_NAME {
+                continue;
+            }
+            if let Some(schema) = self.catalog_provider.schema(&schema_name)? {
+                schemas.push((schema_name, schema));
+            }
+        }
+
+        let information_schema = Arc::new(InformationSchemaProvider::new(
+            self.catalog_name.clone(),
+            self.catalog_provider.clone(),
+        ));
+        schemas.push((INFORMATION_SCHEMA_NAME.to_string(), information_schema));
+        Ok(schemas)
+    }
+
+    /// Returns all tables in the catalog with their schema names.
+    fn tables(&self) -> Result<Vec<(String, TableRef)>> {
+        let mut tables = Vec::new();
+        for (schema_name, schema) in self.schemas()? {
+            for table_name in schema.table_names()? {
+                if let Some(table) = schema.table(&table_name)? {
+                    tables.push((schema_name.clone(), table));
+                }
+            }
+        }
+        Ok(tables)
+    }
+
+    fn build_tables(&self) -> Result<RecordBatch> {
+        let tables = self.tables()?;
+        let mut table_schemas = Vec::with_capacity(tables.len());
+        let mut table_names = Vec::with_capacity(tables.len());
+        let mut table_types = Vec::with_capacity(tables.len());
+        let mut table_ids = Vec::with_capacity(tables.len());
+        let mut engines = Vec::with_capacity(tables.len());
+        for (schema_name, table) in tables {
+            let table_info = table.table_info();
+            table_schemas.push(schema_name);
+            table_names.push(table_info.name.clone());
+            table_types.push(table.table_type().name());
+            table_ids.push(Some(table_info.ident.table_id));
+            engines.push(non_empty(&table_info.meta.engine));
+        }
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![
+                self.catalog_name.as_str();
+                table_names.len()
+            ])),
+            Arc::new(StringVector::from(table_schemas)),
+            Arc::new(StringVector::from(table_names)),
+            Arc::new(StringVector::from(table_types)),
+            Arc::new(UInt32Vector::from(table_ids)),
+            Arc::new(StringVector::from(engines)),
+        ];
+        RecordBatch::new(Arc::new(tables_schema()), columns).context(CreateRecordBatchSnafu)
+    }
+
+    fn build_columns(&self) -> Result<RecordBatch> {
+        let mut table_schemas = Vec::new();
+        let mut table_names = Vec::new();
+        let mut column_names = Vec::new();
+        let mut ordinal_positions = Vec::new();
+        let mut data_types = Vec::new();
+        let mut nullables = Vec::new();
+        let mut column_defaults = Vec::new();
+        let mut semantic_types = Vec::new();
+        for (schema_name, table) in self.tables()? {
+            let table_info = table.table_info();
+            let primary_key_indices = &table_info.meta.primary_key_indices;
+            let schema = table.schema();
+            for (idx, column_schema) in schema.column_schemas().iter().enumerate() {
+                table_schemas.push(schema_name.clone());
+                table_names.push(table_info.name.clone());
+                column_names.push(column_schema.name.clone());
+                // Positions start from 1, like MySQL.
+                ordinal_positions.push(Some(idx as u32 + 1));
+                data_types.push(column_schema.data_type.name().to_string());
+                nullables.push(if column_schema.is_nullable() { YES } else { NO });
+                column_defaults.push(
+                    column_schema
+                        .default_constraint()
+                        .map(|constraint| constraint.to_string()),
+                );
+                let semantic_type = if primary_key_indices.contains(&idx) {
+                    SEMANTIC_TYPE_PRIMARY_KEY
+                } else if column_schema.is_time_index() {
+                    SEMANTIC_TYPE_TIME_INDEX
+                } else {
+                    SEMANTIC_TYPE_VALUE
+                };
+                semantic_types.push(semantic_type);
+            }
+        }
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![
+                self.catalog_name.as_str();
+                column_names.len()
+            ])),
+            Arc::new(StringVector::from(table_schemas)),
+            Arc::new(StringVector::from(table_names)),
+            Arc::new(StringVector::from(column_names)),
+            Arc::new(UInt32Vector::from(ordinal_positions)),
+            Arc::new(StringVector::from(data_types)),
+            Arc::new(StringVector::from(nullables)),
+            Arc::new(StringVector::from(column_defaults)),
+            Arc::new(StringVector::from(semantic_types)),
+        ];
+        RecordBatch::new(Arc::new(columns_schema()), columns).context(CreateRecordBatchSnafu)
+    }
+
+    fn build_engines(&self) -> Result<RecordBatch> {
+        // The default engine is always listed, other engines are listed if they are
+        // used by any table.
+        let mut other_engines = BTreeSet::new();
+        for (_, table) in self.tables()? {
+            let engine = &table.table_info().meta.engine;
+            if !engine.is_empty() && engine != DEFAULT_ENGINE {
+                other_engines.insert(engine.clone());
+            }
+        }
+
+        let mut engines = vec![DEFAULT_ENGINE.to_string()];
+        let mut supports = vec!["DEFAULT"];
+        let mut comments = vec![Some("Storage engine for time-series data")];
+        for engine in other_engines {
+            engines.push(engine);
+            supports.push(YES);
+            comments.push(None);
+        }
+        let num_engines = engines.len();
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(engines)),
+            Arc::new(StringVector::from(supports)),
+            Arc::new(StringVector::from(comments)),
+            // Transactions, XA and savepoints are not supported by any engine.
+            Arc::new(StringVector::from(vec![NO; num_engines])),
+            Arc::new(StringVector::from(vec![NO; num_engines])),
+            Arc::new(StringVector::from(vec![NO; num_engines])),
+        ];
+        RecordBatch::new(Arc::new(engines_schema()), columns).context(CreateRecordBatchSnafu)
+    }
+
+    fn build_region_metrics(&self) -> Result<RecordBatch> {
+        let mut table_schemas = Vec::new();
+        let mut table_names = Vec::new();
+        let mut region_numbers = Vec::new();
+        let mut write_rows = Vec::new();
+        let mut write_batches = Vec::new();
+        let mut write_elapsed = Vec::new();
+        let mut scans = Vec::new();
+        let mut scan_elapsed = Vec::new();
+        let mut memtable_bytes = Vec::new();
+        let mut num_rows = Vec::new();
+        let mut sst_files = Vec::new();
+        let mut sst_bytes = Vec::new();
+        let mut wal_bytes = Vec::new();
+        for (schema_name, table) in self.tables()? {
+            let table_name = &table.table_info().name;
+            for (region_number, metrics) in table.region_metrics() {
+                table_schemas.push(schema_name.clone());
+                table_names.push(table_name.clone());
+                region_numbers.push(Some(region_number));
+                write_rows.push(Some(metrics.write_rows));
+                write_batches.push(Some(metrics.write_batches));
+                write_elapsed.push(Some(metrics.write_elapsed_micros));
+                scans.push(Some(metrics.scans));
+                scan_elapsed.push(Some(metrics.scan_elapsed_micros));
+                memtable_bytes.push(Some(metrics.memtable_bytes));
+                num_rows.push(Some(metrics.num_rows));
+                // Number of files from the lowest level, e.g. `2,1`.
+                sst_files.push(
+                    metrics
+                        .sst_files_per_level
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+                sst_bytes.push(Some(metrics.sst_bytes));
+                wal_bytes.push(Some(metrics.wal_bytes));
+            }
+        }
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![
+                self.catalog_name.as_str();
+                table_names.len()
+            ])),
+            Arc::new(StringVector::from(table_schemas)),
+            Arc::new(StringVector::from(table_names)),
+            Arc::new(UInt32Vector::from(region_numbers)),
+            Arc::new(UInt64Vector::from(write_rows)),
+            Arc::new(UInt64Vector::from(write_batches)),
+            Arc::new(UInt64Vector::from(write_elapsed)),
+            Arc::new(UInt64Vector::from(scans)),
+            Arc::new(UInt64Vector::from(scan_elapsed)),
+            Arc::new(UInt64Vector::from(memtable_bytes)),
+            Arc::new(UInt64Vector::from(num_rows)),
+            Arc::new(StringVector::from(sst_files)),
+            Arc::new(UInt64Vector::from(sst_bytes)),
+            Arc::new(UInt64Vector::from(wal_bytes)),
+        ];
+        RecordBatch::new(Arc::new(region_metrics_schema()), columns).context(CreateRecordBatchSnafu)
+    }
+
+    fn build_table(&self, name: &str) -> Option<TableRef> {
+        let (table_name, table_id, schema) = if name.eq_ignore_ascii_case(TABLES) {
+            (TABLES, INFORMATION_SCHEMA_TABLES_TABLE_ID, tables_schema())
+        } else if name.eq_ignore_ascii_case(COLUMNS) {
+            (
+                COLUMNS,
+                INFORMATION_SCHEMA_COLUMNS_TABLE_ID,
+                columns_schema(),
+            )
+        } else if name.eq_ignore_ascii_case(ENGINES) {
+            (
+                ENGINES,
+                INFORMATION_SCHEMA_ENGINES_TABLE_ID,
+                engines_schema(),
+            )
+        } else if name.eq_ignore_ascii_case(REGION_METRICS) {
+            (
+                REGION_METRICS,
+                INFORMATION_SCHEMA_REGION_METRICS_TABLE_ID,
+                region_metrics_schema(),
+            )
+        } else {
+            return None;
+        };
+
+        let schema = Arc::new(schema);
+        let table_info = build_table_info(&self.catalog_name, table_name, table_id, &schema);
+        Some(Arc::new(InformationTable {
+            schema,
+            table_info,
+            provider: InformationSchemaProvider::new(
+                self.catalog_name.clone(),
+                self.catalog_provider.clone(),
+            ),
+        }))
+    }
+}
+
+impl SchemaProvider for InformationSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Result<Vec<String>> {
+        Ok(vec![
+            TABLES.to_string(),
+            COLUMNS.to_string(),
+            ENGINES.to_string(),
+            REGION_METRICS.to_string(),
+        ])
+    }
+
+    fn table(&self, name: &str) -> Result<Option<TableRef>> {
+        Ok(self.build_table(name))
+    }
+
+    fn register_table(&self, _name: String, _table: TableRef) -> Result<Option<TableRef>> {
+        UnimplementedSnafu {
+            operation: "register table to information_schema",
+        }
+        .fail()
+    }
+
+    fn deregister_table(&self, _name: &str) -> Result<Option<TableRef>> {
+        UnimplementedSnafu {
+            operation: "deregister table from information_schema",
+        }
+        .fail()
+    }
+
+    fn table_exist(&self, name: &str) -> Result<bool> {
+        Ok([TABLES, COLUMNS, ENGINES, REGION_METRICS]
+            .iter()
+            .any(|table| name.eq_ignore_ascii_case(table)))
+    }
+}
+
+/// A virtual table in `information_schema`.
+struct InformationTable {
+    schema: SchemaRef,
+    table_info: TableInfoRef,
+    provider: InformationSchemaProvider,
+}
+
+impl InformationTable {
+    fn records(&self) -> Result<RecordBatch> {
+        match self.table_info.name.as_str() {
+            TABLES => self.provider.build_tables(),
+            COLUMNS => self.provider.build_columns(),
+            ENGINES => self.provider.build_engines(),
+            REGION_METRICS => self.provider.build_region_metrics(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for InformationTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.table_info.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::Result<PhysicalPlanRef> {
+        let records = self
+            .records()
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        let records = match projection {
+            Some(indices) => {
+                let column_schemas = indices
+                    .iter()
+                    .map(|i| self.schema.column_schemas()[*i].clone())
+                    .collect();
+                let columns = indices.iter().map(|i| records.column(*i).clone());
+                RecordBatch::new(Arc::new(Schema::new(column_schemas)), columns)
+                    .map_err(BoxedError::new)
+                    .context(TablesRecordBatchSnafu)?
+            }
+            None => records,
+        };
+
+        let records = RecordBatches::try_new(records.schema.clone(), vec![records])
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+        Ok(Arc::new(SimpleTableScan::new(records.as_stream())))
+    }
+}
+
+fn build_table_info(
+    catalog_name: &str,
+    table_name: &str,
+    table_id: TableId,
+    schema: &SchemaRef,
+) -> TableInfoRef {
+    let table_meta = TableMetaBuilder::default()
+        .schema(schema.clone())
+        .primary_key_indices(vec![])
+        .next_column_id(schema.num_columns() as u32)
+        .build()
+        .unwrap();
+    let table_info = TableInfoBuilder::default()
+        .table_id(table_id)
+        .name(table_name)
+        .catalog_name(catalog_name)
+        .schema_name(INFORMATION_SCHEMA_NAME)
+        .table_version(0)
+        .table_type(TableType::View)
+        .meta(table_meta)
+        .build()
+        .unwrap();
+    Arc::new(table_info)
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
+fn string_column(name: &str, nullable: bool) -> ColumnSchema {
+    ColumnSchema::new(name, ConcreteDataType::string_datatype(), nullable)
+}
+
+fn tables_schema() -> Schema {
+    Schema::new(vec![
+        string_column("table_catalog", false),
+        string_column("table_schema", false),
+        string_column("table_name", false),
+        string_column("table_type", false),
+        ColumnSchema::new("table_id", ConcreteDataType::uint32_datatype(), true),
+        string_column("engine", true),
+    ])
+}
+
+fn columns_schema() -> Schema {
+    Schema::new(vec![
+        string_column("table_catalog", false),
+        string_column("table_schema", false),
+        string_column("table_name", false),
+        string_column("column_name", false),
+        ColumnSchema::new(
+            "ordinal_position",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        ),
+        string_column("data_type", false),
+        string_column("is_nullable", false),
+        string_column("column_default", true),
+        string_column("semantic_type", false),
+    ])
+}
+
+fn engines_schema() -> Schema {
+    Schema::new(vec![
+        string_column("engine", false),
+        string_column("support", false),
+        string_column("comment", true),
+        string_column("transactions", false),
+        string_column("xa", false),
+        string_column("savepoints", false),
+    ])
+}
+
+fn region_metrics_schema() -> Schema {
+    let uint64_column =
+        |name: &str| ColumnSchema::new(name, ConcreteDataType::uint64_datatype(), false);
+    Schema::new(vec![
+        string_column("table_catalog", false),
+        string_column("table_schema", false),
+        string_column("table_name", false),
+        ColumnSchema::new("region_number", ConcreteDataType::uint32_datatype(), false),
+        uint64_column("write_rows"),
+        uint64_column("write_batches"),
+        uint64_column("write_elapsed_micros"),
+        uint64_column("scans"),
+        uint64_column("scan_elapsed_micros"),
+        uint64_column("memtable_bytes"),
+        uint64_column("num_rows"),
+        string_column("sst_files_per_level", false),
+        uint64_column("sst_bytes"),
+        uint64_column("wal_bytes"),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_query::physical_plan::SessionContext;
+    use common_recordbatch::util;
+    use table::table::numbers::NumbersTable;
+
+    use super::*;
+    use crate::local::memory::new_memory_catalog_list;
+    use crate::CatalogList;
+
+    fn new_provider() -> InformationSchemaProvider {
+        let catalog_list = new_memory_catalog_list().unwrap();
+        let catalog = catalog_list.catalog(DEFAULT_CATALOG_NAME).unwrap().unwrap();
+        catalog
+            .schema(DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap()
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::new(1024)))
+            .unwrap();
+        InformationSchemaProvider::new(DEFAULT_CATALOG_NAME.to_string(), catalog)
+    }
+
+    async fn scan(provider: &InformationSchemaProvider, table_name: &str) -> String {
+        let table = provider.table(table_name).unwrap().unwrap();
+        let plan = table.scan(None, &[], None).await.unwrap();
+        let stream = plan.execute(0, SessionContext::new().task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        RecordBatches::try_new(table.schema(), batches)
+            .unwrap()
+            .pretty_print(None)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_information_tables() {
+        let provider = new_provider();
+        assert!(provider.table_exist("TABLES").unwrap());
+        assert!(provider.table("not_exists").unwrap().is_none());
+
+        let expected = "\
++---------------+--------------------+----------------+------------+----------+--------+
+| table_catalog | table_schema       | table_name     | table_type | table_id | engine |
++---------------+--------------------+----------------+------------+----------+--------+
+| greptime      | public             | numbers        | BASE TABLE | 1024     |        |
+| greptime      | information_schema | tables         | VIEW       | 2        |        |
+| greptime      | information_schema | columns        | VIEW       | 3        |        |
+| greptime      | information_schema | engines        | VIEW       | 4        |        |
+| greptime      | information_schema | region_metrics | VIEW       | 5        |        |
++---------------+--------------------+----------------+------------+----------+--------+";
+        assert_eq!(expected, scan(&provider, TABLES).await);
+
+        let expected = "\
++---------+---------+-------------------------------------+--------------+----+------------+
+| engine  | support | comment                             | transactions | xa | savepoints |
++---------+---------+-------------------------------------+--------------+----+------------+
+| mito    | DEFAULT | Storage engine for time-series data | NO           | NO | NO         |
++---------+---------+-------------------------------------+--------------+----+------------+";
+        assert_eq!(expected, scan(&provider, ENGINES).await);
+
+        // The numbers table has no regions.
+        let records = provider.build_region_metrics().unwrap();
+        assert_eq!(14, records.num_columns());
+        assert_eq!(0, records.num_rows());
+    }
+
+    #[tokio::test]
+    async fn test_information_columns() {
+        let provider = new_provider();
+        let table = provider.table(COLUMNS).unwrap().unwrap();
+        // Scans the table_name, column_name and data_type.
+        let plan = table.scan(Some(&vec![2, 3, 5]), &[], None).await.unwrap();
+        let stream = plan.execute(0, SessionContext::new().task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        let batch = &batches[0];
+        assert_eq!(3, batch.num_columns());
+        assert_eq!("table_name", batch.schema.column_name_by_index(0));
+
+        let rows: Vec<_> = batch
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(vec!["numbers", "number", "UInt32"], rows[0]);
+        // Columns of the information_schema tables are also listed.
+        assert!(rows
+            .iter()
+            .any(|row| row[0] == COLUMNS && row[1] == "semantic_type"));
+    }
+}
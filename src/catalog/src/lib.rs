@@ -25,17 +25,23 @@ use table::metadata::TableId;
 use table::requests::CreateTableRequest;
 use table::TableRef;
 
-use crate::error::{CreateTableSnafu, Result};
+use crate::error::{CreateTableSnafu, Result, UnimplementedSnafu};
+use crate::grant::{GrantRequest, Privileges};
 pub use crate::schema::{SchemaProvider, SchemaProviderRef};
 
 pub mod error;
+pub mod grant;
 pub mod helper;
+pub mod information_schema;
 pub mod local;
 pub mod remote;
 pub mod schema;
 pub mod system;
 pub mod tables;
 
+/// Default max number of tables to open concurrently while starting a catalog manager.
+pub const DEFAULT_OPEN_TABLE_CONCURRENCY: usize = 16;
+
 /// Represent a list of named catalogs
 pub trait CatalogList: Sync + Send {
     /// Returns the catalog list as [`Any`](std::any::Any)
@@ -93,6 +99,10 @@ pub trait CatalogManager: CatalogList {
     /// returns whether the table deregistered.
     async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<bool>;
 
+    /// Renames a table within given catalog/schema, returns whether the table renamed.
+    /// Fails if a table with the new name already exists.
+    async fn rename_table(&self, request: RenameTableRequest) -> Result<bool>;
+
     /// Register a schema with catalog name and schema name. Retuens whether the
     /// schema registered.
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool>;
@@ -105,6 +115,20 @@ pub trait CatalogManager: CatalogList {
 
     /// Returns the table by catalog, schema and table name.
     fn table(&self, catalog: &str, schema: &str, table_name: &str) -> Result<Option<TableRef>>;
+
+    /// Returns the privileges of the user on the schema.
+    async fn privileges(&self, _catalog: &str, _schema: &str, _user: &str) -> Result<Privileges> {
+        UnimplementedSnafu {
+            operation: "privileges",
+        }
+        .fail()
+    }
+
+    /// Grants or revokes the privileges of a user on a schema, returns the privileges
+    /// of the user after that. The privileges are persisted in the catalog.
+    async fn grant(&self, _request: GrantRequest) -> Result<Privileges> {
+        UnimplementedSnafu { operation: "grant" }.fail()
+    }
 }
 
 pub type CatalogManagerRef = Arc<dyn CatalogManager>;
@@ -149,6 +173,14 @@ pub struct DeregisterTableRequest {
     pub table_name: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct RenameTableRequest {
+    pub catalog: String,
+    pub schema: String,
+    pub table_name: String,
+    pub new_table_name: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct RegisterSchemaRequest {
     pub catalog: String,
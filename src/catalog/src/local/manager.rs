@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use std::any::Any;
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 
 use common_catalog::consts::{
     DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, MIN_USER_TABLE_ID,
@@ -25,6 +26,7 @@ use common_telemetry::{error, info};
 use datatypes::prelude::ScalarVector;
 use datatypes::vectors::{BinaryVector, UInt8Vector};
 use futures_util::lock::Mutex;
+use futures_util::StreamExt;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
@@ -36,18 +38,21 @@ use table::TableRef;
 use crate::error::{
     CatalogNotFoundSnafu, IllegalManagerStateSnafu, OpenTableSnafu, ReadSystemCatalogSnafu, Result,
     SchemaExistsSnafu, SchemaNotFoundSnafu, SystemCatalogSnafu, SystemCatalogTypeMismatchSnafu,
-    TableExistsSnafu, TableNotFoundSnafu, UnimplementedSnafu,
+    TableExistsSnafu, TableNotExistSnafu, TableNotFoundSnafu, UnimplementedSnafu,
+    UnsupportedCatalogVersionSnafu,
 };
+use crate::grant::{GrantRequest, Privileges};
 use crate::local::memory::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
 use crate::system::{
-    decode_system_catalog, Entry, SystemCatalogTable, TableEntry, ENTRY_TYPE_INDEX, KEY_INDEX,
-    VALUE_INDEX,
+    decode_system_catalog, Entry, GrantEntry, SystemCatalogTable, TableEntry,
+    CATALOG_FORMAT_VERSION, ENTRY_TYPE_INDEX, KEY_INDEX, VALUE_INDEX,
 };
 use crate::tables::SystemCatalog;
 use crate::{
     format_full_table_name, handle_system_table_request, CatalogList, CatalogManager,
     CatalogProvider, CatalogProviderRef, DeregisterTableRequest, RegisterSchemaRequest,
-    RegisterSystemTableRequest, RegisterTableRequest, SchemaProvider, SchemaProviderRef,
+    RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest, SchemaProvider,
+    SchemaProviderRef, DEFAULT_OPEN_TABLE_CONCURRENCY,
 };
 
 /// A `CatalogManager` consists of a system catalog and a bunch of user catalogs.
@@ -59,6 +64,10 @@ pub struct LocalCatalogManager {
     init_lock: Mutex<bool>,
     register_lock: Mutex<()>,
     system_table_requests: Mutex<Vec<RegisterSystemTableRequest>>,
+    /// Grant entries by catalog, schema and user.
+    grants: RwLock<HashMap<(String, String, String), GrantEntry>>,
+    /// Max number of tables to open concurrently on start.
+    open_table_concurrency: usize,
 }
 
 impl LocalCatalogManager {
@@ -79,14 +88,23 @@ impl LocalCatalogManager {
             init_lock: Mutex::new(false),
             register_lock: Mutex::new(()),
             system_table_requests: Mutex::new(Vec::default()),
+            grants: RwLock::new(HashMap::new()),
+            open_table_concurrency: DEFAULT_OPEN_TABLE_CONCURRENCY,
         })
     }
 
+    /// Sets the max number of tables to open concurrently on start.
+    pub fn with_open_table_concurrency(mut self, concurrency: usize) -> Self {
+        self.open_table_concurrency = concurrency.max(1);
+        self
+    }
+
     /// Scan all entries from system catalog table
     pub async fn init(&self) -> Result<()> {
         self.init_system_catalog()?;
         let system_records = self.system.information_schema.system.records().await?;
         let entries = self.collect_system_catalog_entries(system_records).await?;
+        let entries = self.upgrade_system_catalog(entries).await?;
         let max_table_id = self.handle_system_catalog_entries(entries).await?;
 
         info!(
@@ -187,11 +205,67 @@ impl LocalCatalogManager {
         Ok(res)
     }
 
+    /// Upgrades `entries` of the system catalog table to [CATALOG_FORMAT_VERSION] and
+    /// persists the new version. Returns the upgraded entries without the version entry.
+    async fn upgrade_system_catalog(&self, entries: Vec<Entry>) -> Result<Vec<Entry>> {
+        // The system catalog table written before the version entry is introduced
+        // doesn't have one, which is version 0.
+        let mut version = 0;
+        let mut entries_to_upgrade = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match entry {
+                // Version entries may be appended more than once, the latest is the max.
+                Entry::Version(v) => version = version.max(v.version),
+                entry => entries_to_upgrade.push(entry),
+            }
+        }
+        ensure!(
+            version <= CATALOG_FORMAT_VERSION,
+            UnsupportedCatalogVersionSnafu {
+                version,
+                supported: CATALOG_FORMAT_VERSION,
+            }
+        );
+        if version == CATALOG_FORMAT_VERSION {
+            return Ok(entries_to_upgrade);
+        }
+
+        info!(
+            "Upgrading system catalog from version {} to {}",
+            version, CATALOG_FORMAT_VERSION
+        );
+        let mut entries = entries_to_upgrade;
+        while version < CATALOG_FORMAT_VERSION {
+            entries = Self::upgrade_entries(version, entries)?;
+            version += 1;
+        }
+        self.system.update_version(version).await?;
+        info!("System catalog upgraded to version {}", version);
+
+        Ok(entries)
+    }
+
+    /// Upgrades `entries` from `version` to the next version, each format change adds
+    /// a step here.
+    fn upgrade_entries(version: u32, entries: Vec<Entry>) -> Result<Vec<Entry>> {
+        match version {
+            // Version 1 only adds the version entry.
+            0 => Ok(entries),
+            // Version 2 only adds the grant entries.
+            1 => Ok(entries),
+            v => SystemCatalogSnafu {
+                msg: format!("Unable to upgrade from version {v}"),
+            }
+            .fail(),
+        }
+    }
+
     /// Processes records from system catalog table and returns the max table id persisted
     /// in system catalog table.
     async fn handle_system_catalog_entries(&self, entries: Vec<Entry>) -> Result<TableId> {
         let entries = Self::sort_entries(entries);
         let mut max_table_id = 0;
+        let mut tables = Vec::new();
         for entry in entries {
             match entry {
                 Entry::Catalog(c) => {
@@ -215,15 +289,70 @@ impl LocalCatalogManager {
                     info!("Registered schema: {:?}", s);
                 }
                 Entry::Table(t) => {
-                    self.open_and_register_table(&t).await?;
-                    info!("Registered table: {:?}", t);
                     max_table_id = max_table_id.max(t.table_id);
+                    tables.push(t);
                 }
+                // Version entries are consumed while upgrading the system catalog.
+                Entry::Version(_) => {}
+                Entry::Grant(g) => self.load_grant(g),
             }
         }
+        self.open_and_register_tables(tables).await;
         Ok(max_table_id)
     }
 
+    /// Opens and registers tables concurrently. Tables failed to open are skipped with
+    /// an error logged, so they won't prevent other tables from serving.
+    async fn open_and_register_tables(&self, tables: Vec<TableEntry>) {
+        let total = tables.len();
+        info!(
+            "Opening {} tables, concurrency: {}",
+            total, self.open_table_concurrency
+        );
+        let processed = AtomicUsize::new(0);
+        let failed = AtomicUsize::new(0);
+        futures::stream::iter(tables)
+            .for_each_concurrent(self.open_table_concurrency, |t| {
+                let (processed, failed) = (&processed, &failed);
+                async move {
+                    let result = self.open_and_register_table(&t).await;
+                    let progress = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                    match result {
+                        Ok(true) => info!("Registered table: {:?}, {}/{}", t, progress, total),
+                        Ok(false) => {}
+                        Err(e) => {
+                            failed.fetch_add(1, Ordering::Relaxed);
+                            error!(e; "Failed to open table: {:?}, {}/{}", t, progress, total);
+                        }
+                    }
+                }
+            })
+            .await;
+
+        let failed = failed.into_inner();
+        if failed > 0 {
+            error!("Failed to open {} of {} tables", failed, total);
+        } else {
+            info!("All {} tables opened", total);
+        }
+    }
+
+    /// Loads the grant entry if it's the latest one of the user on the schema.
+    fn load_grant(&self, grant: GrantEntry) {
+        let key = (
+            grant.catalog_name.clone(),
+            grant.schema_name.clone(),
+            grant.user.clone(),
+        );
+        let mut grants = self.grants.write().unwrap();
+        match grants.get(&key) {
+            Some(existing) if existing.updated_at > grant.updated_at => {}
+            _ => {
+                let _ = grants.insert(key, grant);
+            }
+        }
+    }
+
     /// Sort catalog entries to ensure catalog entries comes first, then schema entries,
     /// and table entries is the last.
     fn sort_entries(mut entries: Vec<Entry>) -> Vec<Entry> {
@@ -231,7 +360,9 @@ impl LocalCatalogManager {
         entries
     }
 
-    async fn open_and_register_table(&self, t: &TableEntry) -> Result<()> {
+    /// Opens and registers the table of the entry, returns false if the entry is
+    /// outdated since the table has been renamed.
+    async fn open_and_register_table(&self, t: &TableEntry) -> Result<bool> {
         let catalog = self
             .catalogs
             .catalog(&t.catalog_name)?
@@ -270,8 +401,19 @@ impl LocalCatalogManager {
                 ),
             })?;
 
+        // The entry with the old name is kept after renaming the table, the table
+        // is registered by the entry with its new name instead.
+        let table_name = &option.table_info().name;
+        if *table_name != t.table_name {
+            info!(
+                "Skip outdated table entry {:?}, the table has been renamed to {}",
+                t, table_name
+            );
+            return Ok(false);
+        }
+
         schema.register_table(t.table_name.clone(), option)?;
-        Ok(())
+        Ok(true)
     }
 }
 
@@ -384,6 +526,58 @@ impl CatalogManager for LocalCatalogManager {
         .fail()
     }
 
+    async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
+        let started = self.init_lock.lock().await;
+        ensure!(
+            *started,
+            IllegalManagerStateSnafu {
+                msg: "Catalog manager not started",
+            }
+        );
+
+        let catalog_name = &request.catalog;
+        let schema_name = &request.schema;
+        let schema = self
+            .catalogs
+            .catalog(catalog_name)?
+            .context(CatalogNotFoundSnafu { catalog_name })?
+            .schema(schema_name)?
+            .with_context(|| SchemaNotFoundSnafu {
+                schema_info: format!("{catalog_name}.{schema_name}"),
+            })?;
+
+        {
+            let _lock = self.register_lock.lock().await;
+            ensure!(
+                !schema.table_exist(&request.new_table_name)?,
+                TableExistsSnafu {
+                    table: format_full_table_name(
+                        catalog_name,
+                        schema_name,
+                        &request.new_table_name
+                    ),
+                }
+            );
+            let table = schema
+                .table(&request.table_name)?
+                .with_context(|| TableNotExistSnafu {
+                    table: format_full_table_name(catalog_name, schema_name, &request.table_name),
+                })?;
+            // The entry with the old name is outdated and skipped on startup, since the
+            // table engine opens the table with its new name.
+            self.system
+                .register_table(
+                    catalog_name.clone(),
+                    schema_name.clone(),
+                    request.new_table_name.clone(),
+                    table.table_info().ident.table_id,
+                )
+                .await?;
+            schema.rename_table(&request.table_name, request.new_table_name)?;
+            Ok(true)
+        }
+    }
+
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
         let started = self.init_lock.lock().await;
         ensure!(
@@ -456,6 +650,51 @@ impl CatalogManager for LocalCatalogManager {
             })?;
         schema.table(table_name)
     }
+
+    async fn privileges(&self, catalog: &str, schema: &str, user: &str) -> Result<Privileges> {
+        let key = (catalog.to_string(), schema.to_string(), user.to_string());
+        Ok(self
+            .grants
+            .read()
+            .unwrap()
+            .get(&key)
+            .map(|grant| grant.privileges.clone())
+            .unwrap_or_default())
+    }
+
+    async fn grant(&self, request: GrantRequest) -> Result<Privileges> {
+        let started = self.init_lock.lock().await;
+        ensure!(
+            *started,
+            IllegalManagerStateSnafu {
+                msg: "Catalog manager not started",
+            }
+        );
+        let catalog_name = &request.catalog;
+        let schema_name = &request.schema;
+        ensure!(
+            self.schema(catalog_name, schema_name)?.is_some(),
+            SchemaNotFoundSnafu {
+                schema_info: format!("{catalog_name}.{schema_name}"),
+            }
+        );
+
+        let _lock = self.register_lock.lock().await;
+        let mut privileges = self
+            .privileges(catalog_name, schema_name, &request.user)
+            .await?;
+        request.apply(&mut privileges);
+        let grant = GrantEntry {
+            catalog_name: request.catalog,
+            schema_name: request.schema,
+            user: request.user,
+            privileges: privileges.clone(),
+            updated_at: common_time::util::current_time_millis(),
+        };
+        self.system.grant(&grant).await?;
+        self.load_grant(grant);
+        Ok(privileges)
+    }
 }
 
 #[cfg(test)]
@@ -20,16 +20,19 @@ use std::sync::{Arc, RwLock};
 
 use common_catalog::consts::MIN_USER_TABLE_ID;
 use common_telemetry::error;
-use snafu::OptionExt;
+use snafu::{ensure, OptionExt};
 use table::metadata::TableId;
 use table::table::TableIdProvider;
 use table::TableRef;
 
-use crate::error::{CatalogNotFoundSnafu, Result, SchemaNotFoundSnafu, TableExistsSnafu};
+use crate::error::{
+    CatalogNotFoundSnafu, Result, SchemaNotFoundSnafu, TableExistsSnafu, TableNotExistSnafu,
+};
 use crate::schema::SchemaProvider;
 use crate::{
     CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef, DeregisterTableRequest,
-    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, SchemaProviderRef,
+    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest,
+    SchemaProviderRef,
 };
 
 /// Simple in-memory list of catalogs
@@ -106,6 +109,24 @@ impl CatalogManager for MemoryCatalogManager {
             .map(|v| v.is_some())
     }
 
+    async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
+        let catalogs = self.catalogs.write().unwrap();
+        let catalog = catalogs
+            .get(&request.catalog)
+            .context(CatalogNotFoundSnafu {
+                catalog_name: &request.catalog,
+            })?
+            .clone();
+        let schema = catalog
+            .schema(&request.schema)?
+            .with_context(|| SchemaNotFoundSnafu {
+                schema_info: format!("{}.{}", &request.catalog, &request.schema),
+            })?;
+        schema
+            .rename_table(&request.table_name, request.new_table_name)
+            .map(|_| true)
+    }
+
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
         let catalogs = self.catalogs.write().unwrap();
         let catalog = catalogs
@@ -293,6 +314,19 @@ impl SchemaProvider for MemorySchemaProvider {
         Ok(tables.remove(name))
     }
 
+    fn rename_table(&self, name: &str, new_name: String) -> Result<TableRef> {
+        let mut tables = self.tables.write().unwrap();
+        ensure!(
+            !tables.contains_key(&new_name),
+            TableExistsSnafu { table: new_name }
+        );
+        let table = tables
+            .remove(name)
+            .with_context(|| TableNotExistSnafu { table: name })?;
+        tables.insert(new_name, table.clone());
+        Ok(table)
+    }
+
     fn table_exist(&self, name: &str) -> Result<bool> {
         let tables = self.tables.read().unwrap();
         Ok(tables.contains_key(name))
@@ -400,4 +434,48 @@ mod tests {
             .unwrap();
         assert!(!schema.table_exist("numbers").unwrap());
     }
+
+    #[tokio::test]
+    pub async fn test_catalog_rename_table() {
+        let catalog = MemoryCatalogManager::default();
+        let schema = catalog
+            .schema(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME)
+            .unwrap()
+            .unwrap();
+        for (table_name, table_id) in [("numbers", 2333), ("other_numbers", 2334)] {
+            let register_table_req = RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: table_name.to_string(),
+                table_id,
+                table: Arc::new(NumbersTable::new(table_id)),
+            };
+            catalog.register_table(register_table_req).await.unwrap();
+        }
+
+        let new_rename_req = |table_name: &str, new_table_name: &str| RenameTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_name.to_string(),
+            new_table_name: new_table_name.to_string(),
+        };
+        let err = catalog
+            .rename_table(new_rename_req("numbers", "other_numbers"))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::TableAlreadyExists, err.status_code());
+        let err = catalog
+            .rename_table(new_rename_req("not_exists", "new_numbers"))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::TableNotFound, err.status_code());
+
+        assert!(catalog
+            .rename_table(new_rename_req("numbers", "new_numbers"))
+            .await
+            .unwrap());
+        assert!(!schema.table_exist("numbers").unwrap());
+        let table = schema.table("new_numbers").unwrap().unwrap();
+        assert_eq!(2333, table.table_info().ident.table_id);
+    }
 }
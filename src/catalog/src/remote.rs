@@ -16,7 +16,7 @@ use std::fmt::Debug;
 use std::pin::Pin;
 use std::sync::Arc;
 
-pub use client::MetaKvBackend;
+pub use client::{MetaKvBackend, MetaTableIdProvider};
 use futures::Stream;
 use futures_util::StreamExt;
 pub use manager::{RemoteCatalogManager, RemoteCatalogProvider, RemoteSchemaProvider};
@@ -59,6 +59,10 @@ pub trait KvBackend: Send + Sync {
         self.delete_range(key, &[]).await
     }
 
+    /// Moves the value of `from_key` to `to_key` atomically, returns the moved value. If
+    /// `from_key` does not exist, nothing is moved and the value of `to_key` is returned.
+    async fn move_value(&self, from_key: &[u8], to_key: &[u8]) -> Result<Option<Kv>, Error>;
+
     /// Default get is implemented based on `range` method.
     async fn get(&self, key: &[u8]) -> Result<Option<Kv>, Error> {
         let mut iter = self.range(key);
@@ -114,6 +118,10 @@ mod tests {
         async fn delete_range(&self, _key: &[u8], _end: &[u8]) -> Result<(), Error> {
             unimplemented!()
         }
+
+        async fn move_value(&self, _from_key: &[u8], _to_key: &[u8]) -> Result<Option<Kv>, Error> {
+            unimplemented!()
+        }
     }
 
     #[tokio::test]
@@ -16,13 +16,23 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 use async_stream::stream;
+use common_error::prelude::BoxedError;
 use common_telemetry::info;
-use meta_client::client::MetaClient;
-use meta_client::rpc::{CompareAndPutRequest, DeleteRangeRequest, PutRequest, RangeRequest};
+use meta_client::client::{IdAllocator, MetaClient, TABLE_ID_SEQ};
+use meta_client::rpc::{
+    CompareAndPutRequest, DeleteRangeRequest, MoveValueRequest, PutRequest, RangeRequest,
+};
 use snafu::ResultExt;
+use table::error::AllocateTableIdSnafu;
+use table::metadata::TableId;
+use table::table::TableIdProvider;
 
 use crate::error::{Error, MetaSrvSnafu};
 use crate::remote::{Kv, KvBackend, ValueIter};
+
+/// Number of table ids a node fetches from `metasrv` at once.
+const TABLE_ID_BATCH_SIZE: u64 = 16;
+
 #[derive(Debug)]
 pub struct MetaKvBackend {
     pub client: Arc<MetaClient>,
@@ -105,4 +115,44 @@ impl KvBackend for MetaKvBackend {
             Ok(Err(response.take_prev_kv().map(|v| v.value().to_vec())))
         }
     }
+
+    async fn move_value(&self, from_key: &[u8], to_key: &[u8]) -> Result<Option<Kv>, Error> {
+        let mut response = self
+            .client
+            .move_value(MoveValueRequest::new(from_key, to_key))
+            .await
+            .context(MetaSrvSnafu)?;
+        Ok(response
+            .take_kv()
+            .map(|mut kv| Kv(kv.take_key(), kv.take_value())))
+    }
+}
+
+/// Allocates table ids from the table id sequence of `metasrv`, which is shared with the
+/// ids allocated for tables created by frontends, so tables created concurrently on
+/// different nodes never get the same id.
+#[derive(Debug)]
+pub struct MetaTableIdProvider {
+    allocator: IdAllocator,
+}
+
+impl MetaTableIdProvider {
+    pub fn new(client: Arc<MetaClient>) -> Self {
+        Self {
+            allocator: IdAllocator::new((*client).clone(), TABLE_ID_SEQ, TABLE_ID_BATCH_SIZE),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TableIdProvider for MetaTableIdProvider {
+    async fn next_table_id(&self) -> table::Result<TableId> {
+        let id = self
+            .allocator
+            .next()
+            .await
+            .map_err(BoxedError::new)
+            .context(AllocateTableIdSnafu)?;
+        Ok(id as TableId)
+    }
 }
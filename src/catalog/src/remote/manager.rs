@@ -20,10 +20,10 @@ use std::sync::Arc;
 use arc_swap::ArcSwap;
 use async_stream::stream;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
-use common_telemetry::{debug, info};
+use common_telemetry::{debug, error, info};
 use futures::Stream;
 use futures_util::StreamExt;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::TableId;
 use table::requests::{CreateTableRequest, OpenTableRequest};
@@ -33,7 +33,7 @@ use tokio::sync::Mutex;
 
 use crate::error::{
     CatalogNotFoundSnafu, CreateTableSnafu, InvalidCatalogValueSnafu, InvalidTableSchemaSnafu,
-    OpenTableSnafu, Result, SchemaNotFoundSnafu, TableExistsSnafu, UnimplementedSnafu,
+    OpenTableSnafu, Result, SchemaNotFoundSnafu, TableExistsSnafu, TableNotExistSnafu,
 };
 use crate::helper::{
     build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, CatalogValue,
@@ -43,7 +43,8 @@ use crate::remote::{Kv, KvBackendRef};
 use crate::{
     handle_system_table_request, CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef,
     DeregisterTableRequest, RegisterSchemaRequest, RegisterSystemTableRequest,
-    RegisterTableRequest, SchemaProvider, SchemaProviderRef,
+    RegisterTableRequest, RenameTableRequest, SchemaProvider, SchemaProviderRef,
+    DEFAULT_OPEN_TABLE_CONCURRENCY,
 };
 
 /// Catalog manager based on metasrv.
@@ -54,6 +55,8 @@ pub struct RemoteCatalogManager {
     engine: TableEngineRef,
     system_table_requests: Mutex<Vec<RegisterSystemTableRequest>>,
     mutex: Arc<Mutex<()>>,
+    /// Max number of tables to open concurrently on start.
+    open_table_concurrency: usize,
 }
 
 impl RemoteCatalogManager {
@@ -65,9 +68,16 @@ impl RemoteCatalogManager {
             catalogs: Default::default(),
             system_table_requests: Default::default(),
             mutex: Default::default(),
+            open_table_concurrency: DEFAULT_OPEN_TABLE_CONCURRENCY,
         }
     }
 
+    /// Sets the max number of tables to open concurrently on start.
+    pub fn with_open_table_concurrency(mut self, concurrency: usize) -> Self {
+        self.open_table_concurrency = concurrency.max(1);
+        self
+    }
+
     fn build_catalog_key(&self, catalog_name: impl AsRef<str>) -> CatalogKey {
         CatalogKey {
             catalog_name: catalog_name.as_ref().to_string(),
@@ -243,19 +253,44 @@ impl RemoteCatalogManager {
         mut max_table_id: TableId,
     ) -> Result<()> {
         info!("initializing tables in {}.{}", catalog_name, schema_name);
-        let mut table_num = 0;
-        let mut tables = self.iter_remote_tables(catalog_name, schema_name).await;
-        while let Some(r) = tables.next().await {
+        let mut tables = Vec::new();
+        let mut remote_tables = self.iter_remote_tables(catalog_name, schema_name).await;
+        while let Some(r) = remote_tables.next().await {
             let (table_key, table_value) = r?;
-            let table_ref = self.open_or_create_table(&table_key, &table_value).await?;
-            schema.register_table(table_key.table_name.to_string(), table_ref)?;
-            info!("Registered table {}", &table_key.table_name);
             max_table_id = max_table_id.max(table_value.table_id());
-            table_num += 1;
+            tables.push((table_key, table_value));
+        }
+
+        // Tables failed to open are skipped, so they won't prevent other tables from
+        // serving.
+        let total = tables.len();
+        let mut processed = 0;
+        let mut failed = 0;
+        let mut opened = futures::stream::iter(tables)
+            .map(|(table_key, table_value)| async move {
+                let result = self.open_or_create_table(&table_key, &table_value).await;
+                (table_key, result)
+            })
+            .buffer_unordered(self.open_table_concurrency);
+        while let Some((table_key, result)) = opened.next().await {
+            processed += 1;
+            let registered = result.and_then(|table_ref| {
+                schema.register_table(table_key.table_name.to_string(), table_ref)
+            });
+            match registered {
+                Ok(_) => info!(
+                    "Registered table {}, {}/{}",
+                    &table_key.table_name, processed, total
+                ),
+                Err(e) => {
+                    failed += 1;
+                    error!(e; "Failed to open table {}, {}/{}", table_key, processed, total);
+                }
+            }
         }
         info!(
-            "initialized tables in {}.{}, total: {}",
-            catalog_name, schema_name, table_num
+            "initialized tables in {}.{}, total: {}, failed: {}",
+            catalog_name, schema_name, total, failed
         );
         Ok(())
     }
@@ -430,11 +465,33 @@ impl CatalogManager for RemoteCatalogManager {
         Ok(true)
     }
 
-    async fn deregister_table(&self, _request: DeregisterTableRequest) -> Result<bool> {
-        UnimplementedSnafu {
-            operation: "deregister table",
-        }
-        .fail()
+    async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<bool> {
+        let catalog_name = &request.catalog;
+        let schema_name = &request.schema;
+        let schema_provider = self
+            .catalog(catalog_name)?
+            .context(CatalogNotFoundSnafu { catalog_name })?
+            .schema(schema_name)?
+            .with_context(|| SchemaNotFoundSnafu {
+                schema_info: format!("{catalog_name}.{schema_name}"),
+            })?;
+        schema_provider
+            .deregister_table(&request.table_name)
+            .map(|v| v.is_some())
+    }
+
+    async fn rename_table(&self, request: RenameTableRequest) -> Result<bool> {
+        let catalog_name = &request.catalog;
+        let schema_name = &request.schema;
+        let schema_provider = self
+            .catalog(catalog_name)?
+            .context(CatalogNotFoundSnafu { catalog_name })?
+            .schema(schema_name)?
+            .with_context(|| SchemaNotFoundSnafu {
+                schema_info: format!("{}.{}", catalog_name, schema_name),
+            })?;
+        schema_provider.rename_table(&request.table_name, request.new_table_name)?;
+        Ok(true)
     }
 
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
@@ -765,6 +822,56 @@ impl SchemaProvider for RemoteSchemaProvider {
         prev
     }
 
+    /// Renames the table and moves its regional key to the new name.
+    ///
+    /// The new name is checked under the same lock as the move, so concurrent renames
+    /// and registrations can't take the name in between. In distributed mode the keys
+    /// are already moved by metasrv with the global key and the route in one transaction,
+    /// moving an absent key is a no-op.
+    fn rename_table(&self, name: &str, new_name: String) -> Result<TableRef> {
+        let table_name = name.to_string();
+        let table_key = self.build_regional_table_key(&table_name).to_string();
+        let new_table_key = self.build_regional_table_key(&new_name).to_string();
+        let table_info = format!("{}.{}.{}", self.catalog_name, self.schema_name, name);
+        let new_table_info = format!("{}.{}.{}", self.catalog_name, self.schema_name, new_name);
+        let backend = self.backend.clone();
+        let mutex = self.mutex.clone();
+        let tables = self.tables.clone();
+        std::thread::spawn(move || {
+            common_runtime::block_on_write(async move {
+                let _guard = mutex.lock().await;
+                let prev_tables = tables.load();
+                let table = prev_tables
+                    .get(&table_name)
+                    .cloned()
+                    .context(TableNotExistSnafu { table: table_info })?;
+                ensure!(
+                    !prev_tables.contains_key(&new_name),
+                    TableExistsSnafu {
+                        table: new_table_info
+                    }
+                );
+
+                backend
+                    .move_value(table_key.as_bytes(), new_table_key.as_bytes())
+                    .await?;
+                debug!(
+                    "Successfully renamed catalog table entry, key: {}, new key: {}",
+                    table_key, new_table_key
+                );
+
+                let mut new_tables = HashMap::with_capacity(prev_tables.len());
+                new_tables.clone_from(&prev_tables);
+                new_tables.remove(&table_name);
+                new_tables.insert(new_name, table.clone());
+                tables.store(Arc::new(new_tables));
+                Ok(table)
+            })
+        })
+        .join()
+        .unwrap()
+    }
+
     /// Checks if table exists in schema provider based on locally opened table map.
     fn table_exist(&self, name: &str) -> Result<bool> {
         Ok(self.tables.load().contains_key(name))
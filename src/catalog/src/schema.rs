@@ -17,7 +17,7 @@ use std::sync::Arc;
 
 use table::TableRef;
 
-use crate::error::Result;
+use crate::error::{Result, UnimplementedSnafu};
 
 /// Represents a schema, comprising a number of named tables.
 pub trait SchemaProvider: Sync + Send {
@@ -39,6 +39,15 @@ pub trait SchemaProvider: Sync + Send {
     /// If no table of that name exists, returns Ok(None).
     fn deregister_table(&self, name: &str) -> Result<Option<TableRef>>;
 
+    /// If supported by the implementation, renames an existing table in this schema and
+    /// returns it. Fails if the table doesn't exist or the new name is taken.
+    fn rename_table(&self, _name: &str, _new_name: String) -> Result<TableRef> {
+        UnimplementedSnafu {
+            operation: "rename table",
+        }
+        .fail()
+    }
+
     /// If supported by the implementation, checks the table exist in the schema provider or not.
     /// If no matched table in the schema provider, return false.
     /// Otherwise, return true.
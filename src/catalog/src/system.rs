@@ -39,11 +39,24 @@ use crate::error::{
     self, CreateSystemCatalogSnafu, EmptyValueSnafu, Error, InvalidEntryTypeSnafu, InvalidKeySnafu,
     OpenSystemCatalogSnafu, Result, ValueDeserializeSnafu,
 };
+use crate::grant::Privileges;
 
 pub const ENTRY_TYPE_INDEX: usize = 0;
 pub const KEY_INDEX: usize = 1;
 pub const VALUE_INDEX: usize = 3;
 
+/// Version of the format of the system catalog table. The version is persisted in the
+/// table and entries in an older format are upgraded on startup, so it must be bumped
+/// once the format changes.
+///
+/// History:
+/// - 0: Catalog, schema and table entries, without the version entry.
+/// - 1: Adds the version entry.
+/// - 2: Adds the grant entries.
+pub const CATALOG_FORMAT_VERSION: u32 = 2;
+
+const VERSION_KEY: &str = "version";
+
 pub struct SystemCatalogTable {
     table_info: TableInfoRef,
     pub table: TableRef,
@@ -207,6 +220,33 @@ pub fn build_schema_insert_request(catalog_name: String, schema_name: String) ->
     )
 }
 
+pub fn build_version_insert_request(version: u32) -> InsertRequest {
+    build_insert_request(
+        EntryType::Version,
+        VERSION_KEY.as_bytes(),
+        serde_json::to_string(&VersionEntryValue { version })
+            .unwrap()
+            .as_bytes(),
+    )
+}
+
+pub fn build_grant_insert_request(grant: &GrantEntry) -> InsertRequest {
+    let key = format!(
+        "{}.{}.{}",
+        grant.catalog_name, grant.schema_name, grant.user
+    );
+    build_insert_request(
+        EntryType::Grant,
+        key.as_bytes(),
+        serde_json::to_string(&GrantEntryValue {
+            privileges: grant.privileges.clone(),
+            updated_at: grant.updated_at,
+        })
+        .unwrap()
+        .as_bytes(),
+    )
+}
+
 pub fn build_insert_request(entry_type: EntryType, key: &[u8], value: &[u8]) -> InsertRequest {
     let mut columns_values = HashMap::with_capacity(6);
     columns_values.insert(
@@ -305,6 +345,45 @@ pub fn decode_system_catalog(
                 table_id: table_meta.table_id,
             }))
         }
+
+        EntryType::Version => {
+            // As for version entry, the key is always `version` and the value is a JSON
+            // string with format: `{"version": <version>}`
+            ensure!(
+                key == VERSION_KEY,
+                InvalidKeySnafu {
+                    key: Some(key.to_string())
+                }
+            );
+            let value = value.context(EmptyValueSnafu)?;
+            let version: VersionEntryValue =
+                serde_json::from_slice(value).context(ValueDeserializeSnafu)?;
+            Ok(Entry::Version(VersionEntry {
+                version: version.version,
+            }))
+        }
+
+        EntryType::Grant => {
+            // As for grant entry, the key is a string with format: `<catalog_name>.<schema_name>.<user>`
+            // and the value is a JSON string with format: `{"privileges": [<privilege>], "updated_at": <millis>}`
+            let grant_parts = key.splitn(3, '.').collect::<Vec<_>>();
+            ensure!(
+                grant_parts.len() == 3,
+                InvalidKeySnafu {
+                    key: Some(key.to_string())
+                }
+            );
+            let value = value.context(EmptyValueSnafu)?;
+            let grant: GrantEntryValue =
+                serde_json::from_slice(value).context(ValueDeserializeSnafu)?;
+            Ok(Entry::Grant(GrantEntry {
+                catalog_name: grant_parts[0].to_string(),
+                schema_name: grant_parts[1].to_string(),
+                user: grant_parts[2].to_string(),
+                privileges: grant.privileges,
+                updated_at: grant.updated_at,
+            }))
+        }
     }
 }
 
@@ -313,6 +392,8 @@ pub enum EntryType {
     Catalog = 1,
     Schema = 2,
     Table = 3,
+    Version = 4,
+    Grant = 5,
 }
 
 impl TryFrom<u8> for EntryType {
@@ -323,6 +404,8 @@ impl TryFrom<u8> for EntryType {
             b if b == Self::Catalog as u8 => Ok(Self::Catalog),
             b if b == Self::Schema as u8 => Ok(Self::Schema),
             b if b == Self::Table as u8 => Ok(Self::Table),
+            b if b == Self::Version as u8 => Ok(Self::Version),
+            b if b == Self::Grant as u8 => Ok(Self::Grant),
             b => InvalidEntryTypeSnafu {
                 entry_type: Some(b),
             }
@@ -336,6 +419,8 @@ pub enum Entry {
     Catalog(CatalogEntry),
     Schema(SchemaEntry),
     Table(TableEntry),
+    Version(VersionEntry),
+    Grant(GrantEntry),
 }
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
@@ -365,6 +450,34 @@ pub struct TableEntryValue {
     pub table_id: TableId,
 }
 
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
+pub struct VersionEntry {
+    pub version: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VersionEntryValue {
+    pub version: u32,
+}
+
+/// Privileges of a user on a schema. A grant entry is appended each time the
+/// privileges are changed, the latest one takes effect.
+#[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
+pub struct GrantEntry {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub user: String,
+    pub privileges: Privileges,
+    /// Time in millis the privileges were updated.
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GrantEntryValue {
+    pub privileges: Privileges,
+    pub updated_at: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use log_store::fs::noop::NoopLogStore;
@@ -378,6 +491,7 @@ mod tests {
     use tempdir::TempDir;
 
     use super::*;
+    use crate::grant::Privilege;
 
     #[test]
     pub fn test_decode_catalog_entry() {
@@ -430,6 +544,44 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_decode_version() {
+        let entry = decode_system_catalog(
+            Some(EntryType::Version as u8),
+            Some(VERSION_KEY.as_bytes()),
+            Some("{\"version\":1}".as_bytes()),
+        )
+        .unwrap();
+        assert_eq!(Entry::Version(VersionEntry { version: 1 }), entry);
+
+        assert!(decode_system_catalog(
+            Some(EntryType::Version as u8),
+            Some("some_key".as_bytes()),
+            Some("{\"version\":1}".as_bytes()),
+        )
+        .is_err());
+    }
+
+    #[test]
+    pub fn test_decode_grant() {
+        let entry = decode_system_catalog(
+            Some(EntryType::Grant as u8),
+            Some("some_catalog.some_schema.some.user".as_bytes()),
+            Some("{\"privileges\":[\"read\",\"write\"],\"updated_at\":42}".as_bytes()),
+        )
+        .unwrap();
+        assert_eq!(
+            Entry::Grant(GrantEntry {
+                catalog_name: "some_catalog".to_string(),
+                schema_name: "some_schema".to_string(),
+                user: "some.user".to_string(),
+                privileges: Privileges::from([Privilege::Read, Privilege::Write]),
+                updated_at: 42,
+            }),
+            entry
+        );
+    }
+
     #[test]
     #[should_panic]
     pub fn test_decode_mismatch() {
@@ -446,7 +598,9 @@ mod tests {
         assert_eq!(EntryType::Catalog, EntryType::try_from(1).unwrap());
         assert_eq!(EntryType::Schema, EntryType::try_from(2).unwrap());
         assert_eq!(EntryType::Table, EntryType::try_from(3).unwrap());
-        assert!(EntryType::try_from(4).is_err());
+        assert_eq!(EntryType::Version, EntryType::try_from(4).unwrap());
+        assert_eq!(EntryType::Grant, EntryType::try_from(5).unwrap());
+        assert!(EntryType::try_from(6).is_err());
     }
 
     pub async fn prepare_table_engine() -> (TempDir, TableEngineRef) {
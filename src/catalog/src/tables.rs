@@ -39,7 +39,10 @@ use table::table::scan::SimpleTableScan;
 use table::{Table, TableRef};
 
 use crate::error::{Error, InsertCatalogRecordSnafu};
-use crate::system::{build_schema_insert_request, build_table_insert_request, SystemCatalogTable};
+use crate::system::{
+    build_grant_insert_request, build_schema_insert_request, build_table_insert_request,
+    build_version_insert_request, GrantEntry, SystemCatalogTable,
+};
 use crate::{
     format_full_table_name, CatalogListRef, CatalogProvider, SchemaProvider, SchemaProviderRef,
 };
@@ -290,6 +293,25 @@ impl SystemCatalog {
             .await
             .context(InsertCatalogRecordSnafu)
     }
+
+    pub async fn grant(&self, grant: &GrantEntry) -> crate::error::Result<usize> {
+        let request = build_grant_insert_request(grant);
+        self.information_schema
+            .system
+            .insert(request)
+            .await
+            .context(InsertCatalogRecordSnafu)
+    }
+
+    /// Persists the format `version` of the system catalog table.
+    pub async fn update_version(&self, version: u32) -> crate::error::Result<usize> {
+        let request = build_version_insert_request(version);
+        self.information_schema
+            .system
+            .insert(request)
+            .await
+            .context(InsertCatalogRecordSnafu)
+    }
 }
 
 impl CatalogProvider for SystemCatalog {
@@ -14,30 +14,246 @@
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
+    use catalog::grant::{GrantRequest, Privilege, Privileges};
     use catalog::local::LocalCatalogManager;
-    use catalog::{CatalogManager, RegisterTableRequest};
+    use catalog::system::{
+        build_version_insert_request, EntryType, SystemCatalogTable, CATALOG_FORMAT_VERSION,
+        ENTRY_TYPE_INDEX,
+    };
+    use catalog::{CatalogManager, RegisterTableRequest, RenameTableRequest};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_recordbatch::util;
     use common_telemetry::{error, info};
+    use datatypes::prelude::ScalarVector;
+    use datatypes::vectors::UInt8Vector;
     use mito::config::EngineConfig;
+    use table::engine::{EngineContext, TableEngineRef};
+    use table::requests::CreateTableRequest;
     use table::table::numbers::NumbersTable;
-    use table::TableRef;
+    use table::{Table, TableRef};
     use tokio::sync::Mutex;
 
-    async fn create_local_catalog_manager() -> Result<LocalCatalogManager, catalog::error::Error> {
+    async fn create_mock_engine() -> TableEngineRef {
         let (_dir, object_store) =
             mito::table::test_util::new_test_object_store("setup_mock_engine_and_table").await;
-        let mock_engine = Arc::new(mito::table::test_util::MockMitoEngine::new(
+        Arc::new(mito::table::test_util::MockMitoEngine::new(
             EngineConfig::default(),
             mito::table::test_util::MockEngine::default(),
             object_store,
-        ));
+        ))
+    }
+
+    async fn create_table(engine: &TableEngineRef, table_name: &str, table_id: u32) -> TableRef {
+        let request = CreateTableRequest {
+            id: table_id,
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_name.to_string(),
+            desc: None,
+            schema: Arc::new(mito::table::test_util::schema_for_test()),
+            region_numbers: vec![0],
+            create_if_not_exists: false,
+            primary_key_indices: vec![0],
+            table_options: HashMap::new(),
+        };
+        engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap()
+    }
+
+    async fn create_local_catalog_manager() -> Result<LocalCatalogManager, catalog::error::Error> {
+        let mock_engine = create_mock_engine().await;
         let catalog_manager = LocalCatalogManager::try_new(mock_engine).await.unwrap();
         catalog_manager.start().await?;
         Ok(catalog_manager)
     }
 
+    #[tokio::test]
+    async fn test_catalog_version() {
+        let engine = create_mock_engine().await;
+        let catalog_manager = LocalCatalogManager::try_new(engine.clone()).await.unwrap();
+        catalog_manager.start().await.unwrap();
+        // Tables are reopened by the engine on restart, so the table must be created by it.
+        let request = RegisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "test_table".to_string(),
+            table_id: 42,
+            table: create_table(&engine, "test_table", 42).await,
+        };
+        assert!(catalog_manager.register_table(request).await.unwrap());
+
+        // The version is persisted on first start.
+        let system_table = SystemCatalogTable::new(engine.clone()).await.unwrap();
+        let records = util::collect(system_table.records().await.unwrap())
+            .await
+            .unwrap();
+        assert!(records.iter().any(|rb| {
+            let entry_types = rb
+                .column(ENTRY_TYPE_INDEX)
+                .as_any()
+                .downcast_ref::<UInt8Vector>()
+                .unwrap();
+            entry_types
+                .iter_data()
+                .any(|t| t == Some(EntryType::Version as u8))
+        }));
+
+        // Restarts with the persisted version.
+        let catalog_manager = LocalCatalogManager::try_new(engine.clone()).await.unwrap();
+        catalog_manager.start().await.unwrap();
+        assert!(catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "test_table")
+            .unwrap()
+            .is_some());
+
+        // Catalogs written by a newer version are rejected.
+        system_table
+            .insert(build_version_insert_request(CATALOG_FORMAT_VERSION + 1))
+            .await
+            .unwrap();
+        let catalog_manager = LocalCatalogManager::try_new(engine).await.unwrap();
+        let err = catalog_manager.start().await.unwrap_err();
+        assert!(
+            matches!(err, catalog::error::Error::UnsupportedCatalogVersion { .. }),
+            "Actual error: {err}",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grant() {
+        let engine = create_mock_engine().await;
+        let catalog_manager = LocalCatalogManager::try_new(engine.clone()).await.unwrap();
+        catalog_manager.start().await.unwrap();
+
+        let new_request = |privileges: &[Privilege], revoke| GrantRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            user: "foo".to_string(),
+            privileges: privileges.to_vec(),
+            revoke,
+        };
+        let privileges = catalog_manager
+            .grant(new_request(&Privilege::ALL, false))
+            .await
+            .unwrap();
+        assert_eq!(Privileges::from(Privilege::ALL), privileges);
+        let privileges = catalog_manager
+            .grant(new_request(&[Privilege::Ddl], true))
+            .await
+            .unwrap();
+        assert_eq!(
+            Privileges::from([Privilege::Read, Privilege::Write]),
+            privileges
+        );
+        assert!(catalog_manager
+            .privileges(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "bar")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let mut request = new_request(&[Privilege::Read], false);
+        request.schema = "no_such_schema".to_string();
+        let err = catalog_manager.grant(request).await.unwrap_err();
+        assert!(
+            matches!(err, catalog::error::Error::SchemaNotFound { .. }),
+            "Actual error: {err}",
+        );
+
+        // The latest privileges are loaded after restart.
+        let catalog_manager = LocalCatalogManager::try_new(engine).await.unwrap();
+        catalog_manager.start().await.unwrap();
+        assert_eq!(
+            Privileges::from([Privilege::Read, Privilege::Write]),
+            catalog_manager
+                .privileges(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "foo")
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_table() {
+        common_telemetry::init_default_ut_logging();
+        let engine = create_mock_engine().await;
+        let catalog_manager = LocalCatalogManager::try_new(engine.clone()).await.unwrap();
+        catalog_manager.start().await.unwrap();
+        for (table_name, table_id) in [("test_table", 42), ("another_table", 43)] {
+            let request = RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: table_name.to_string(),
+                table_id,
+                table: create_table(&engine, table_name, table_id).await,
+            };
+            assert!(catalog_manager.register_table(request).await.unwrap());
+        }
+
+        let new_rename_request = |table_name: &str, new_table_name: &str| RenameTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_name.to_string(),
+            new_table_name: new_table_name.to_string(),
+        };
+        let err = catalog_manager
+            .rename_table(new_rename_request("test_table", "another_table"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, catalog::error::Error::TableExists { .. }),
+            "Actual error: {err}",
+        );
+        let err = catalog_manager
+            .rename_table(new_rename_request("no_such_table", "new_table"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, catalog::error::Error::TableNotExist { .. }),
+            "Actual error: {err}",
+        );
+
+        // Only the catalog is renamed here, the table itself is renamed by the engine.
+        let alter_request = table::requests::AlterTableRequest {
+            catalog_name: Some(DEFAULT_CATALOG_NAME.to_string()),
+            schema_name: Some(DEFAULT_SCHEMA_NAME.to_string()),
+            table_name: "test_table".to_string(),
+            alter_kind: table::requests::AlterKind::RenameTable {
+                new_table_name: "new_table".to_string(),
+            },
+        };
+        engine
+            .alter_table(&EngineContext::default(), alter_request)
+            .await
+            .unwrap();
+        assert!(catalog_manager
+            .rename_table(new_rename_request("test_table", "new_table"))
+            .await
+            .unwrap());
+
+        let assert_renamed = |catalog_manager: &LocalCatalogManager| {
+            assert!(catalog_manager
+                .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "test_table")
+                .unwrap()
+                .is_none());
+            let table = catalog_manager
+                .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "new_table")
+                .unwrap()
+                .unwrap();
+            assert_eq!(42, table.table_info().ident.table_id);
+            assert_eq!("new_table", table.table_info().name);
+        };
+        assert_renamed(&catalog_manager);
+
+        // The outdated entry of the old name is skipped after restart.
+        let catalog_manager = LocalCatalogManager::try_new(engine).await.unwrap();
+        catalog_manager.start().await.unwrap();
+        assert_renamed(&catalog_manager);
+    }
+
     #[tokio::test]
     async fn test_duplicate_register() {
         let catalog_manager = create_local_catalog_manager().await.unwrap();
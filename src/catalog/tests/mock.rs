@@ -29,7 +29,9 @@ use datatypes::vectors::StringVector;
 use serde::Serializer;
 use table::engine::{EngineContext, TableEngine, TableReference};
 use table::metadata::TableId;
-use table::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
+use table::requests::{
+    AlterTableRequest, CloseTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
+};
 use table::test_util::MemTable;
 use table::TableRef;
 use tokio::sync::RwLock;
@@ -120,6 +122,17 @@ impl KvBackend for MockKvBackend {
         map.retain(|k, _| !range.contains(k));
         Ok(())
     }
+
+    async fn move_value(&self, from_key: &[u8], to_key: &[u8]) -> Result<Option<Kv>, Error> {
+        let mut map = self.map.write().await;
+        match map.remove(from_key) {
+            Some(val) => {
+                map.insert(to_key.to_vec(), val.clone());
+                Ok(Some(Kv(to_key.to_vec(), val)))
+            }
+            None => Ok(map.get(to_key).map(|val| Kv(to_key.to_vec(), val.clone()))),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -220,4 +233,17 @@ impl TableEngine for MockTableEngine {
     ) -> table::Result<bool> {
         unimplemented!()
     }
+
+    async fn close_table(
+        &self,
+        _ctx: &EngineContext,
+        request: CloseTableRequest,
+    ) -> table::Result<bool> {
+        Ok(self
+            .tables
+            .write()
+            .await
+            .remove(&request.table_name)
+            .is_some())
+    }
 }
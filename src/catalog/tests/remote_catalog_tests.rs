@@ -22,11 +22,14 @@ mod tests {
     use std::collections::HashSet;
     use std::sync::Arc;
 
-    use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue};
+    use catalog::helper::{CatalogKey, CatalogValue, SchemaKey, SchemaValue, TableRegionalKey};
     use catalog::remote::{
         KvBackend, KvBackendRef, RemoteCatalogManager, RemoteCatalogProvider, RemoteSchemaProvider,
     };
-    use catalog::{CatalogList, CatalogManager, RegisterTableRequest};
+    use catalog::{
+        CatalogList, CatalogManager, DeregisterTableRequest, RegisterTableRequest,
+        RenameTableRequest,
+    };
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use datatypes::schema::Schema;
     use futures_util::StreamExt;
@@ -213,6 +216,159 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rename_table() {
+        let node_id = 42;
+        let (backend, table_engine, catalog_manager) = prepare_components(node_id).await;
+        let catalog_name = DEFAULT_CATALOG_NAME.to_string();
+        let schema_name = DEFAULT_SCHEMA_NAME.to_string();
+        let table_name = "test_table".to_string();
+        let table_id = 1;
+        let table = table_engine
+            .create_table(
+                &EngineContext {},
+                CreateTableRequest {
+                    id: table_id,
+                    catalog_name: catalog_name.clone(),
+                    schema_name: schema_name.clone(),
+                    table_name: table_name.clone(),
+                    desc: None,
+                    schema: Arc::new(Schema::new(vec![])),
+                    region_numbers: vec![0],
+                    primary_key_indices: vec![],
+                    create_if_not_exists: false,
+                    table_options: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        let reg_req = RegisterTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: table_name.clone(),
+            table_id,
+            table,
+        };
+        assert!(catalog_manager.register_table(reg_req).await.unwrap());
+
+        let rename_req = RenameTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: table_name.clone(),
+            new_table_name: "numbers".to_string(),
+        };
+        assert_matches!(
+            catalog_manager
+                .rename_table(rename_req)
+                .await
+                .err()
+                .unwrap(),
+            catalog::error::Error::TableExists { .. }
+        );
+
+        let rename_req = RenameTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: table_name.clone(),
+            new_table_name: "new_table".to_string(),
+        };
+        assert!(catalog_manager.rename_table(rename_req).await.unwrap());
+        assert!(catalog_manager
+            .table(&catalog_name, &schema_name, &table_name)
+            .unwrap()
+            .is_none());
+        assert!(catalog_manager
+            .table(&catalog_name, &schema_name, "new_table")
+            .unwrap()
+            .is_some());
+
+        // The regional key of the table is moved to the new name.
+        let regional_key = |table_name: &str| {
+            TableRegionalKey {
+                catalog_name: catalog_name.clone(),
+                schema_name: schema_name.clone(),
+                table_name: table_name.to_string(),
+                node_id,
+            }
+            .to_string()
+        };
+        assert!(backend
+            .get(regional_key(&table_name).as_bytes())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(backend
+            .get(regional_key("new_table").as_bytes())
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deregister_table() {
+        let node_id = 42;
+        let (backend, table_engine, catalog_manager) = prepare_components(node_id).await;
+        let catalog_name = DEFAULT_CATALOG_NAME.to_string();
+        let schema_name = DEFAULT_SCHEMA_NAME.to_string();
+        let table_name = "test_table".to_string();
+        let table_id = 1;
+        let table = table_engine
+            .create_table(
+                &EngineContext {},
+                CreateTableRequest {
+                    id: table_id,
+                    catalog_name: catalog_name.clone(),
+                    schema_name: schema_name.clone(),
+                    table_name: table_name.clone(),
+                    desc: None,
+                    schema: Arc::new(Schema::new(vec![])),
+                    region_numbers: vec![0],
+                    primary_key_indices: vec![],
+                    create_if_not_exists: false,
+                    table_options: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        let reg_req = RegisterTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: table_name.clone(),
+            table_id,
+            table,
+        };
+        assert!(catalog_manager.register_table(reg_req).await.unwrap());
+
+        let dereg_req = DeregisterTableRequest {
+            catalog: catalog_name.clone(),
+            schema: schema_name.clone(),
+            table_name: table_name.clone(),
+        };
+        assert!(catalog_manager
+            .deregister_table(dereg_req.clone())
+            .await
+            .unwrap());
+        assert!(catalog_manager
+            .table(&catalog_name, &schema_name, &table_name)
+            .unwrap()
+            .is_none());
+        let regional_key = TableRegionalKey {
+            catalog_name,
+            schema_name,
+            table_name,
+            node_id,
+        }
+        .to_string();
+        assert!(backend
+            .get(regional_key.as_bytes())
+            .await
+            .unwrap()
+            .is_none());
+
+        // Deregistering a nonexistent table is a no-op.
+        assert!(!catalog_manager.deregister_table(dereg_req).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_register_catalog_schema_table() {
         let node_id = 42;
@@ -0,0 +1,114 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use snafu::ResultExt;
+use tonic::metadata::{AsciiMetadataValue, MetadataMap};
+
+use crate::{error, Result};
+
+const AUTHORIZATION_METADATA: &str = "authorization";
+
+/// Credential sent to the server in the `authorization` metadata of every request.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Auth {
+    Basic { username: String, password: String },
+    Token(String),
+}
+
+impl Auth {
+    pub fn basic(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Auth::Basic {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    pub fn token(token: impl Into<String>) -> Self {
+        Auth::Token(token.into())
+    }
+
+    fn header_value(&self) -> String {
+        match self {
+            Auth::Basic { username, password } => {
+                format!("Basic {}", base64::encode(format!("{username}:{password}")))
+            }
+            Auth::Token(token) => format!("Bearer {token}"),
+        }
+    }
+
+    pub(crate) fn inject(&self, metadata: &mut MetadataMap) -> Result<()> {
+        let value = AsciiMetadataValue::try_from(self.header_value()).map_err(|e| {
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: format!("Invalid auth credential: {e}"),
+            }
+            .build()
+        })?;
+        let _ = metadata.insert(AUTHORIZATION_METADATA, value);
+        Ok(())
+    }
+}
+
+// Never prints the secrets.
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Auth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Auth::Token(_) => f.debug_tuple("Token").field(&"<redacted>").finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_auth() {
+        let mut metadata = MetadataMap::new();
+        // base64encode("greptime:greptime") == "Z3JlcHRpbWU6Z3JlcHRpbWU="
+        Auth::basic("greptime", "greptime")
+            .inject(&mut metadata)
+            .unwrap();
+        assert_eq!(
+            "Basic Z3JlcHRpbWU6Z3JlcHRpbWU=",
+            metadata
+                .get(AUTHORIZATION_METADATA)
+                .unwrap()
+                .to_str()
+                .unwrap()
+        );
+
+        Auth::token("abcdef").inject(&mut metadata).unwrap();
+        assert_eq!(
+            "Bearer abcdef",
+            metadata
+                .get(AUTHORIZATION_METADATA)
+                .unwrap()
+                .to_str()
+                .unwrap()
+        );
+
+        assert!(Auth::token("abc\ndef").inject(&mut metadata).is_err());
+        assert_eq!(
+            r#"Basic { username: "greptime", password: "<redacted>" }"#,
+            format!("{:?}", Auth::basic("greptime", "greptime"))
+        );
+    }
+}
@@ -12,28 +12,86 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 use api::v1::greptime_client::GreptimeClient;
+use api::v1::query_request::Query;
 use api::v1::*;
-use common_grpc::channel_manager::ChannelManager;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::{FlightData, Ticket};
+use common_error::prelude::ErrorExt;
+use common_grpc::channel_manager::{ChannelManager, Compression};
+use common_grpc::flight;
+use futures::{Stream, StreamExt};
 use parking_lot::RwLock;
-use snafu::{OptionExt, ResultExt};
+use prost::Message;
+use snafu::{ensure, IntoError, OptionExt, ResultExt};
 use tonic::transport::Channel;
+use tonic::Request;
 
+use crate::auth::Auth;
+use crate::health::{PeerHealth, DEFAULT_UNAVAILABLE_TIMEOUT};
 use crate::load_balance::{LoadBalance, Loadbalancer};
+use crate::metric::{MetricsHookRef, RequestOutcome};
+use crate::options::RequestOptions;
 use crate::{error, Result};
 
+const DEFAULT_CHANNELS_PER_PEER: usize = 1;
+
+pub type FlightDataStream = Pin<Box<dyn Stream<Item = Result<FlightData>> + Send>>;
+
 #[derive(Clone, Debug, Default)]
 pub struct Client {
     inner: Arc<Inner>,
 }
 
-#[derive(Debug, Default)]
 struct Inner {
     channel_manager: ChannelManager,
     peers: Arc<RwLock<Vec<String>>>,
     load_balance: Loadbalancer,
+    metrics_hook: RwLock<Option<MetricsHookRef>>,
+    channels_per_peer: usize,
+    next_channel: AtomicUsize,
+    request_options: RequestOptions,
+    health: PeerHealth,
+    auth: Option<Auth>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self {
+            channel_manager: ChannelManager::default(),
+            peers: Arc::default(),
+            load_balance: Loadbalancer::default(),
+            metrics_hook: RwLock::default(),
+            channels_per_peer: DEFAULT_CHANNELS_PER_PEER,
+            next_channel: AtomicUsize::new(0),
+            request_options: RequestOptions::default(),
+            health: PeerHealth::default(),
+            auth: None,
+        }
+    }
+}
+
+impl fmt::Debug for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inner")
+            .field("channel_manager", &self.channel_manager)
+            .field("peers", &self.peers)
+            .field("load_balance", &self.load_balance)
+            .field("metrics_hook", &self.metrics_hook.read().is_some())
+            .field("channels_per_peer", &self.channels_per_peer)
+            .field("request_options", &self.request_options)
+            .field("health", &self.health)
+            .field("auth", &self.auth)
+            .finish()
+    }
 }
 
 impl Inner {
@@ -50,8 +108,226 @@ impl Inner {
     }
 
     fn get_peer(&self) -> Option<String> {
-        let guard = self.peers.read();
-        self.load_balance.get_peer(&guard).cloned()
+        self.candidate_peers().into_iter().next()
+    }
+
+    /// Returns peers in the order they should be tried, the first one is chosen by the
+    /// load balancer among available peers, unavailable peers are tried last.
+    fn candidate_peers(&self) -> Vec<String> {
+        let peers = self.peers.read();
+        let (available, unavailable): (Vec<_>, Vec<_>) = peers
+            .iter()
+            .cloned()
+            .partition(|peer| self.health.is_available(peer));
+
+        let first = self.load_balance.get_peer(&available).cloned();
+        let mut candidates = Vec::with_capacity(peers.len());
+        candidates.extend(first.clone());
+        candidates.extend(
+            available
+                .into_iter()
+                .filter(|peer| Some(peer) != first.as_ref()),
+        );
+        candidates.extend(unavailable);
+        candidates
+    }
+
+    fn metrics_hook(&self) -> Option<MetricsHookRef> {
+        self.metrics_hook.read().clone()
+    }
+
+    /// Creates a client of the Flight service of `addr`.
+    fn make_flight_client(&self, addr: &str) -> Result<FlightServiceClient<Channel>> {
+        let mut client = FlightServiceClient::new(self.make_channel(addr)?);
+        if let Some(encoding) = self.channel_manager.config().grpc_compression() {
+            client = client.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(client)
+    }
+
+    fn make_greptime_client(&self, addr: &str) -> Result<GreptimeClient<Channel>> {
+        let mut client = GreptimeClient::new(self.make_channel(addr)?);
+        if let Some(encoding) = self.channel_manager.config().grpc_compression() {
+            client = client.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(client)
+    }
+
+    /// Returns one of the channels to `addr` in a round-robin way.
+    fn make_channel(&self, addr: &str) -> Result<Channel> {
+        let index = if self.channels_per_peer > 1 {
+            self.next_channel.fetch_add(1, Ordering::Relaxed) % self.channels_per_peer
+        } else {
+            0
+        };
+        self.channel_manager
+            .get_nth(addr, index)
+            .context(error::CreateChannelSnafu { addr })
+    }
+}
+
+/// Builder of [Client] connecting to multiple peers.
+///
+/// Requests are sent to one of the peers chosen by the load balancer. Queries that
+/// are safe to be sent again are retried on other peers if the chosen one is
+/// unavailable.
+#[derive(Debug)]
+pub struct ClientBuilder {
+    peers: Vec<String>,
+    channel_manager: Option<ChannelManager>,
+    compression: Option<Compression>,
+    channels_per_peer: usize,
+    request_options: RequestOptions,
+    unavailable_timeout: Duration,
+    health_check_interval: Option<Duration>,
+    auth: Option<Auth>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            peers: Vec::new(),
+            channel_manager: None,
+            compression: None,
+            channels_per_peer: DEFAULT_CHANNELS_PER_PEER,
+            request_options: RequestOptions::default(),
+            unavailable_timeout: DEFAULT_UNAVAILABLE_TIMEOUT,
+            health_check_interval: None,
+            auth: None,
+        }
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn peers<U, A>(self, urls: A) -> Self
+    where
+        U: AsRef<str>,
+        A: AsRef<[U]>,
+    {
+        Self {
+            peers: urls
+                .as_ref()
+                .iter()
+                .map(|peer| peer.as_ref().to_string())
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn channel_manager(self, channel_manager: ChannelManager) -> Self {
+        Self {
+            channel_manager: Some(channel_manager),
+            ..self
+        }
+    }
+
+    /// Compresses messages sent to peers. It overrides the compression in the config of
+    /// the channel manager, a new channel manager with the overridden config is used in
+    /// that case.
+    ///
+    /// Defaults to the compression of the channel manager.
+    pub fn compression(self, compression: Compression) -> Self {
+        Self {
+            compression: Some(compression),
+            ..self
+        }
+    }
+
+    /// Number of channels, each with its own connection, kept to every peer.
+    ///
+    /// Defaults to 1.
+    pub fn channels_per_peer(self, channels_per_peer: usize) -> Self {
+        assert!(channels_per_peer > 0, "channels_per_peer must be positive");
+        Self {
+            channels_per_peer,
+            ..self
+        }
+    }
+
+    /// Max times a query is retried if the peer it was sent to is unavailable, other
+    /// peers are tried first. Only read-only queries are retried.
+    ///
+    /// Defaults to 2.
+    pub fn max_retries(self, max_retries: usize) -> Self {
+        Self {
+            request_options: self.request_options.clone().max_retries(max_retries),
+            ..self
+        }
+    }
+
+    /// Default deadline and retry policy of requests, could be overridden by
+    /// [Client::database_with_options].
+    pub fn request_options(self, request_options: RequestOptions) -> Self {
+        Self {
+            request_options,
+            ..self
+        }
+    }
+
+    /// How long a peer is tried after others once a request to it failed because it
+    /// was unavailable.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn unavailable_timeout(self, timeout: Duration) -> Self {
+        Self {
+            unavailable_timeout: timeout,
+            ..self
+        }
+    }
+
+    /// Checks the health of all peers in the background with the `interval`.
+    ///
+    /// Defaults to no health check, peers are only marked unavailable by failed requests.
+    pub fn health_check_interval(self, interval: Duration) -> Self {
+        Self {
+            health_check_interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Credential sent with every request, for servers that enable authentication.
+    ///
+    /// Defaults to none.
+    pub fn auth(self, auth: Auth) -> Self {
+        Self {
+            auth: Some(auth),
+            ..self
+        }
+    }
+
+    pub fn build(self) -> Client {
+        let channel_manager = match (self.channel_manager, self.compression) {
+            (mgr, Some(compression)) => {
+                let config = mgr
+                    .map(|mgr| mgr.config().clone())
+                    .unwrap_or_default()
+                    .compression(compression);
+                ChannelManager::with_config(config)
+            }
+            (mgr, None) => mgr.unwrap_or_default(),
+        };
+        let inner = Inner {
+            channel_manager,
+            channels_per_peer: self.channels_per_peer,
+            request_options: self.request_options,
+            health: PeerHealth::new(self.unavailable_timeout),
+            auth: self.auth,
+            ..Default::default()
+        };
+        inner.set_peers(self.peers);
+        let inner = Arc::new(inner);
+
+        if let Some(interval) = self.health_check_interval {
+            let inner = Arc::downgrade(&inner);
+            common_runtime::spawn_bg(async move {
+                health_check_in_loop(inner, interval).await;
+            });
+        }
+        Client { inner }
     }
 }
 
@@ -60,6 +336,10 @@ impl Client {
         Default::default()
     }
 
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
     pub fn with_manager(channel_manager: ChannelManager) -> Self {
         let inner = Arc::new(Inner::with_manager(channel_manager));
         Self { inner }
@@ -104,13 +384,31 @@ impl Client {
         self.inner.set_peers(urls);
     }
 
+    /// Sets the hook to observe requests sent by this client, the hook is shared by all
+    /// clones of the client.
+    pub fn set_metrics_hook(&self, hook: MetricsHookRef) {
+        let mut guard = self.inner.metrics_hook.write();
+        *guard = Some(hook);
+    }
+
     pub async fn database(&self, req: DatabaseRequest) -> Result<DatabaseResponse> {
+        self.database_with_options(req, &self.inner.request_options)
+            .await
+    }
+
+    /// Sends the `req` with the deadline and retry policy in `options` instead of the
+    /// default ones of the client.
+    pub async fn database_with_options(
+        &self,
+        req: DatabaseRequest,
+        options: &RequestOptions,
+    ) -> Result<DatabaseResponse> {
         let req = BatchRequest {
             databases: vec![req],
             ..Default::default()
         };
 
-        let mut res = self.batch(req).await?;
+        let mut res = self.batch_with_options(req, options).await?;
         res.databases.pop().context(error::MissingResultSnafu {
             name: "database",
             expected: 1_usize,
@@ -119,36 +417,313 @@ impl Client {
     }
 
     pub async fn batch(&self, req: BatchRequest) -> Result<BatchResponse> {
-        let peer = self
-            .inner
-            .get_peer()
-            .context(error::IllegalGrpcClientStateSnafu {
+        self.batch_with_options(req, &self.inner.request_options)
+            .await
+    }
+
+    pub async fn batch_with_options(
+        &self,
+        req: BatchRequest,
+        options: &RequestOptions,
+    ) -> Result<BatchResponse> {
+        let retryable = is_retryable_batch(&req);
+        self.call_with_retry(req, retryable, options, |peer, req| {
+            self.observed_batch_to(peer, req)
+        })
+        .await
+    }
+
+    /// Sends the `ticket` to the Arrow Flight service of a peer, returns the stream of
+    /// [FlightData] as it arrives.
+    pub async fn do_get(&self, ticket: Ticket) -> Result<FlightDataStream> {
+        self.do_get_with_options(ticket, &self.inner.request_options)
+            .await
+    }
+
+    pub async fn do_get_with_options(
+        &self,
+        ticket: Ticket,
+        options: &RequestOptions,
+    ) -> Result<FlightDataStream> {
+        let retryable = ObjectExpr::decode(ticket.ticket.as_slice())
+            .map(|expr| is_retryable_expr(&expr))
+            .unwrap_or(false);
+        self.call_with_retry(ticket, retryable, options, |peer, ticket| {
+            self.do_get_from(peer, ticket)
+        })
+        .await
+    }
+
+    /// Calls `call` with peers in the order of [Inner::candidate_peers] until it
+    /// succeeds. The `req` is only sent again if it's `retryable` and the error is
+    /// retried by the `options`.
+    ///
+    /// Peers are tried in turn, a retry to a peer that has been tried waits for the
+    /// backoff first. No more retries are made once the deadline is reached.
+    async fn call_with_retry<R, T, F, Fut>(
+        &self,
+        req: R,
+        retryable: bool,
+        options: &RequestOptions,
+        call: F,
+    ) -> Result<T>
+    where
+        R: Clone,
+        F: Fn(String, Request<R>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+        let max_attempts = if retryable {
+            options.max_retries + 1
+        } else {
+            1
+        };
+        let peers = self.inner.candidate_peers();
+        ensure!(
+            !peers.is_empty(),
+            error::IllegalGrpcClientStateSnafu {
                 err_msg: "No available peer found",
-            })?;
-        let mut client = self.make_client(&peer)?;
-        let result = client
-            .batch(req)
+            }
+        );
+        let mut req = Some(req);
+
+        let mut attempt = 0;
+        loop {
+            let peer = &peers[attempt % peers.len()];
+            let is_last = attempt + 1 == max_attempts;
+            let attempt_req = if is_last { req.take() } else { req.clone() };
+            // Safety: `req` is only taken in the last attempt.
+            let mut request = Request::new(attempt_req.unwrap());
+            if let Some(deadline) = deadline {
+                request.set_timeout(deadline.saturating_duration_since(Instant::now()));
+            }
+            common_grpc::tracing::inject_trace_context(request.metadata_mut());
+            if let Some(auth) = &self.inner.auth {
+                auth.inject(request.metadata_mut())?;
+            }
+
+            let e = match call(peer.clone(), request).await {
+                Ok(result) => {
+                    self.inner.health.mark_available(peer);
+                    return Ok(result);
+                }
+                Err(e) => e,
+            };
+            if e.is_unavailable() {
+                self.inner.health.mark_unavailable(peer);
+            }
+            if is_last || !options.should_retry(&e) {
+                return Err(e);
+            }
+
+            attempt += 1;
+            if attempt >= peers.len() {
+                let backoff = options.backoff_of((attempt - peers.len()) as u32);
+                if matches!(deadline, Some(deadline) if Instant::now() + backoff >= deadline) {
+                    return Err(e);
+                }
+                tokio::time::sleep(backoff).await;
+            }
+        }
+    }
+
+    async fn observed_batch_to(
+        &self,
+        peer: String,
+        req: Request<BatchRequest>,
+    ) -> Result<BatchResponse> {
+        let Some(hook) = self.inner.metrics_hook() else {
+            return batch_to(&self.inner, &peer, req).await;
+        };
+
+        hook.on_request_start(&peer, req.get_ref().encoded_len());
+        let start = Instant::now();
+        let result = batch_to(&self.inner, &peer, req).await;
+        let (response_bytes, outcome) = match &result {
+            Ok(res) => (res.encoded_len(), RequestOutcome::Success),
+            Err(e) => (0, RequestOutcome::Failure(e.status_code())),
+        };
+        hook.on_request_end(&peer, response_bytes, start.elapsed(), outcome);
+        result
+    }
+
+    async fn do_get_from(
+        &self,
+        peer: String,
+        mut ticket: Request<Ticket>,
+    ) -> Result<FlightDataStream> {
+        let mut client = self.inner.make_flight_client(&peer)?;
+        if self.inner.channel_manager.config().compress_flight_data() {
+            flight::request_compression(ticket.metadata_mut());
+        }
+
+        let hook = self.inner.metrics_hook();
+        if let Some(hook) = &hook {
+            hook.on_request_start(&peer, ticket.get_ref().encoded_len());
+        }
+        let start = Instant::now();
+        let stream = match client
+            .do_get(ticket)
             .await
-            .context(error::TonicStatusSnafu { addr: peer })?;
-        Ok(result.into_inner())
+            .context(error::TonicStatusSnafu { addr: &peer })
+        {
+            Ok(response) => response.into_inner(),
+            Err(e) => {
+                if let Some(hook) = &hook {
+                    let outcome = RequestOutcome::Failure(e.status_code());
+                    hook.on_request_end(&peer, 0, start.elapsed(), outcome);
+                }
+                return Err(e);
+            }
+        };
+
+        let addr = peer.clone();
+        let stream: FlightDataStream = Box::pin(stream.map(move |flight_data| {
+            flight_data.map_err(|e| error::TonicStatusSnafu { addr: &addr }.into_error(e))
+        }));
+        match hook {
+            Some(hook) => Ok(Box::pin(ObservedFlightStream {
+                inner: stream,
+                hook,
+                peer,
+                start,
+                response_bytes: 0,
+                finished: false,
+            })),
+            None => Ok(stream),
+        }
+    }
+}
+
+/// Stream of [FlightData] that reports the request to the metrics hook once the stream
+/// finishes, fails or is dropped by the caller.
+struct ObservedFlightStream {
+    inner: FlightDataStream,
+    hook: MetricsHookRef,
+    peer: String,
+    start: Instant,
+    response_bytes: usize,
+    finished: bool,
+}
+
+impl ObservedFlightStream {
+    fn finish(&mut self, outcome: RequestOutcome) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        let response_bytes = match outcome {
+            RequestOutcome::Success => self.response_bytes,
+            RequestOutcome::Failure(_) => 0,
+        };
+        self.hook
+            .on_request_end(&self.peer, response_bytes, self.start.elapsed(), outcome);
+    }
+}
+
+impl Stream for ObservedFlightStream {
+    type Item = Result<FlightData>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.inner.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(flight_data))) => {
+                self.response_bytes += flight_data.encoded_len();
+            }
+            Poll::Ready(Some(Err(e))) => self.finish(RequestOutcome::Failure(e.status_code())),
+            Poll::Ready(None) => self.finish(RequestOutcome::Success),
+            Poll::Pending => {}
+        }
+        poll
     }
+}
 
-    fn make_client(&self, addr: impl AsRef<str>) -> Result<GreptimeClient<Channel>> {
-        let addr = addr.as_ref();
-        let channel = self
-            .inner
-            .channel_manager
-            .get(addr)
-            .context(error::CreateChannelSnafu { addr })?;
-        Ok(GreptimeClient::new(channel))
+impl Drop for ObservedFlightStream {
+    fn drop(&mut self) {
+        // The caller stops reading before the end of the stream, what is received so far
+        // is still a successful response.
+        self.finish(RequestOutcome::Success);
     }
 }
 
+async fn batch_to(inner: &Inner, peer: &str, req: Request<BatchRequest>) -> Result<BatchResponse> {
+    let mut client = inner.make_greptime_client(peer)?;
+    let result = client
+        .batch(req)
+        .await
+        .context(error::TonicStatusSnafu { addr: peer })?;
+    Ok(result.into_inner())
+}
+
+/// Sends an empty batch to every peer with the `interval` and updates their health,
+/// until the client is dropped.
+async fn health_check_in_loop(inner: Weak<Inner>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+
+        let peers = inner.peers.read().clone();
+        for peer in peers {
+            match batch_to(&inner, &peer, Request::new(BatchRequest::default())).await {
+                Ok(_) => inner.health.mark_available(&peer),
+                Err(e) if e.is_unavailable() => inner.health.mark_unavailable(&peer),
+                // The peer responds, though the request fails.
+                Err(_) => inner.health.mark_available(&peer),
+            }
+        }
+    }
+}
+
+/// Returns true if the `req` is safe to be sent again, i.e. it only contains read-only
+/// queries.
+fn is_retryable_batch(req: &BatchRequest) -> bool {
+    req.databases
+        .iter()
+        .flat_map(|db| db.exprs.iter())
+        .all(is_retryable_expr)
+}
+
+fn is_retryable_expr(expr: &ObjectExpr) -> bool {
+    match &expr.request {
+        Some(object_expr::Request::Query(QueryRequest {
+            query: Some(query), ..
+        })) => match query {
+            Query::Sql(sql) => is_read_only_sql(sql),
+            Query::LogicalPlan(_) => true,
+        },
+        Some(object_expr::Request::Diagnostic(_)) => true,
+        _ => false,
+    }
+}
+
+/// Returns true if the `sql` is a single statement that never modifies data.
+fn is_read_only_sql(sql: &str) -> bool {
+    const READ_ONLY_KEYWORDS: [&str; 5] = ["SELECT", "SHOW", "DESC", "DESCRIBE", "EXPLAIN"];
+
+    let sql = sql.trim().trim_end_matches(';');
+    if sql.contains(';') {
+        return false;
+    }
+    let keyword = sql
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    READ_ONLY_KEYWORDS
+        .iter()
+        .any(|k| keyword.eq_ignore_ascii_case(k))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
+    use std::sync::Mutex;
 
-    use super::Inner;
+    use super::*;
     use crate::load_balance::Loadbalancer;
 
     fn mock_peers() -> Vec<String> {
@@ -177,4 +752,183 @@ mod tests {
             assert!(all.contains(&inner.get_peer().unwrap()));
         }
     }
+
+    #[test]
+    fn test_candidate_peers() {
+        let inner = Inner::default();
+        let peers = mock_peers();
+        inner.set_peers(peers.clone());
+
+        let candidates = inner.candidate_peers();
+        assert_eq!(
+            peers.iter().collect::<HashSet<_>>(),
+            candidates.iter().collect::<HashSet<_>>()
+        );
+
+        // Unavailable peers are tried last.
+        inner.health.mark_unavailable(&peers[0]);
+        for _ in 0..20 {
+            let candidates = inner.candidate_peers();
+            assert_eq!(3, candidates.len());
+            assert_eq!(peers[0], candidates[2]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_with_compression() {
+        let client = Client::builder().peers(mock_peers()).build();
+        assert_eq!(None, client.inner.channel_manager.config().compression);
+
+        let client = Client::builder()
+            .peers(mock_peers())
+            .compression(Compression::Gzip)
+            .build();
+        assert_eq!(
+            Some(Compression::Gzip),
+            client.inner.channel_manager.config().compression
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_read_only_sql("select * from demo"));
+        assert!(is_read_only_sql("  SHOW TABLES;"));
+        assert!(is_read_only_sql("desc table demo"));
+        assert!(is_read_only_sql("EXPLAIN SELECT 1"));
+        assert!(!is_read_only_sql("INSERT INTO demo VALUES (1)"));
+        assert!(!is_read_only_sql("SELECT 1; DROP TABLE demo"));
+        assert!(!is_read_only_sql("selectx"));
+
+        let query = |sql: &str| ObjectExpr {
+            request: Some(object_expr::Request::Query(QueryRequest {
+                query: Some(Query::Sql(sql.to_string())),
+                ..Default::default()
+            })),
+        };
+        let batch = |exprs| BatchRequest {
+            databases: vec![DatabaseRequest {
+                name: "greptime".to_string(),
+                exprs,
+            }],
+            ..Default::default()
+        };
+        assert!(is_retryable_batch(&batch(vec![
+            query("SELECT 1"),
+            query("SHOW DATABASES")
+        ])));
+        assert!(!is_retryable_batch(&batch(vec![
+            query("SELECT 1"),
+            query("DELETE FROM demo")
+        ])));
+        assert!(!is_retryable_batch(&batch(vec![ObjectExpr {
+            request: Some(object_expr::Request::Insert(InsertRequest::default())),
+        }])));
+    }
+
+    fn unavailable(peer: &str) -> error::Error {
+        error::TonicStatusSnafu { addr: peer }.into_error(tonic::Status::unavailable("refused"))
+    }
+
+    #[tokio::test]
+    async fn test_call_with_retry() {
+        let client = Client::builder().peers(mock_peers()).max_retries(1).build();
+        let options = &client.inner.request_options;
+
+        // The query is retried on another peer.
+        let called = Mutex::new(Vec::new());
+        let result = client
+            .call_with_retry((), true, options, |peer, _| {
+                let mut called = called.lock().unwrap();
+                called.push(peer.clone());
+                let result = if called.len() == 1 {
+                    Err(unavailable(&peer))
+                } else {
+                    Ok(peer)
+                };
+                async move { result }
+            })
+            .await
+            .unwrap();
+        let called = called.into_inner().unwrap();
+        assert_eq!(2, called.len());
+        assert_eq!(called[1], result);
+        assert!(!client.inner.health.is_available(&called[0]));
+        assert!(client.inner.health.is_available(&called[1]));
+
+        // Fails after max retries.
+        let called = Mutex::new(Vec::new());
+        let err = client
+            .call_with_retry((), true, options, |peer, _| {
+                called.lock().unwrap().push(peer.clone());
+                async move { Err::<(), _>(unavailable(&peer)) }
+            })
+            .await
+            .unwrap_err();
+        assert!(err.is_unavailable());
+        assert_eq!(2, called.into_inner().unwrap().len());
+
+        // Requests that are not retryable are only sent once.
+        let called = Mutex::new(Vec::new());
+        let err = client
+            .call_with_retry((), false, options, |peer, _| {
+                called.lock().unwrap().push(peer.clone());
+                async move { Err::<(), _>(unavailable(&peer)) }
+            })
+            .await
+            .unwrap_err();
+        assert!(err.is_unavailable());
+        assert_eq!(1, called.into_inner().unwrap().len());
+
+        // Other errors are never retried.
+        let called = Mutex::new(Vec::new());
+        let err =
+            client
+                .call_with_retry((), true, options, |peer, _| {
+                    called.lock().unwrap().push(peer.clone());
+                    let status = tonic::Status::internal("boom");
+                    async move {
+                        Err::<(), _>(error::TonicStatusSnafu { addr: peer }.into_error(status))
+                    }
+                })
+                .await
+                .unwrap_err();
+        assert!(!err.is_unavailable());
+        assert_eq!(1, called.into_inner().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff() {
+        let client = Client::builder().peers(["127.0.0.1:3001"]).build();
+
+        // Retries the only peer.
+        let options = RequestOptions::new()
+            .max_retries(3)
+            .backoff(Duration::from_millis(1), Duration::from_millis(2));
+        let called = Mutex::new(0);
+        let err = client
+            .call_with_retry((), true, &options, |peer, _| {
+                *called.lock().unwrap() += 1;
+                async move { Err::<(), _>(unavailable(&peer)) }
+            })
+            .await
+            .unwrap_err();
+        assert!(err.is_unavailable());
+        assert_eq!(4, called.into_inner().unwrap());
+
+        // The remaining time is sent as timeout, stops retrying once the deadline is reached.
+        let options = RequestOptions::new()
+            .timeout(Duration::from_secs(1))
+            .max_retries(3)
+            .backoff(Duration::from_millis(400), Duration::from_secs(1));
+        let called = Mutex::new(0);
+        let _ = client
+            .call_with_retry((), true, &options, |peer, request| {
+                *called.lock().unwrap() += 1;
+                assert!(request.metadata().get("grpc-timeout").is_some());
+                async move { Err::<(), _>(unavailable(&peer)) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(2, called.into_inner().unwrap());
+    }
 }
@@ -12,26 +12,47 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::diagnostic_request::Request as DiagnosticExpr;
 use api::v1::{
     object_expr, query_request, AlterExpr, CreateTableExpr, DatabaseRequest, DdlRequest,
-    DropTableExpr, InsertRequest, ObjectExpr, ObjectResult as GrpcObjectResult, QueryRequest,
+    DiagnosticRequest, DropTableExpr, InsertRequest, ObjectExpr, ObjectResult as GrpcObjectResult,
+    QueryRequest, ScanAtSequenceRequest, UpdateRuntimeConfigRequest,
 };
+use arrow_flight::{FlightData, Ticket};
+use async_stream::try_stream;
+use common_error::prelude::BoxedError;
 use common_error::status_code::StatusCode;
 use common_grpc::flight::{
-    flight_messages_to_recordbatches, raw_flight_data_to_message, FlightMessage,
+    flight_messages_to_recordbatches, raw_flight_data_to_message, FlightDecoder, FlightMessage,
 };
 use common_query::Output;
-use common_recordbatch::RecordBatches;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{
+    RecordBatch, RecordBatchStream, RecordBatches, SendableRecordBatchStream,
+};
+use datatypes::schema::SchemaRef;
+use futures::{Stream, StreamExt};
+use prost::Message;
 use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::error::{ConvertFlightDataSnafu, DatanodeSnafu, IllegalFlightMessagesSnafu};
-use crate::{error, Client, Result};
+use crate::{error, from_record_batches, Client, FromRow, InsertBuilder, RequestOptions, Result};
 
 #[derive(Clone, Debug)]
 pub struct Database {
     name: String,
     client: Client,
+    /// Options of requests sent by this database, uses the default options of the
+    /// client if not set.
+    request_options: Option<RequestOptions>,
+    /// Session states sent with the queries, the defaults of the server are used
+    /// if empty.
+    schema_name: String,
+    time_zone: String,
 }
 
 impl Database {
@@ -39,6 +60,34 @@ impl Database {
         Self {
             name: name.into(),
             client,
+            request_options: None,
+            schema_name: String::new(),
+            time_zone: String::new(),
+        }
+    }
+
+    /// Runs the queries in the schema, like `USE <schema>` in a session.
+    pub fn with_schema(self, schema_name: impl Into<String>) -> Self {
+        Self {
+            schema_name: schema_name.into(),
+            ..self
+        }
+    }
+
+    /// Runs the queries in the time zone, like `SET time_zone = <time_zone>` in a
+    /// session.
+    pub fn with_time_zone(self, time_zone: impl Into<String>) -> Self {
+        Self {
+            time_zone: time_zone.into(),
+            ..self
+        }
+    }
+
+    /// Sends requests with the deadline and retry policy in `request_options`.
+    pub fn with_request_options(self, request_options: RequestOptions) -> Self {
+        Self {
+            request_options: Some(request_options),
+            ..self
         }
     }
 
@@ -53,20 +102,124 @@ impl Database {
         self.object(expr).await?.try_into()
     }
 
+    /// Sends the requests built by the `builder` one by one, returns the affected rows
+    /// of each request.
+    ///
+    /// Stops at the first failed request, requests before it are not rolled back.
+    pub async fn bulk_insert(&self, builder: InsertBuilder) -> Result<Vec<usize>> {
+        let requests = builder.finish();
+        let mut affected_rows = Vec::with_capacity(requests.len());
+        for (index, request) in requests.into_iter().enumerate() {
+            let rows = match self.insert(request).await {
+                Ok(RpcOutput::AffectedRows(rows)) => Ok(rows),
+                Ok(RpcOutput::RecordBatches(_)) => IllegalFlightMessagesSnafu {
+                    reason: "Expect 'AffectedRows' Flight message for insert",
+                }
+                .fail(),
+                Err(e) => Err(e),
+            }
+            .context(error::InsertChunkSnafu {
+                index,
+                inserted_rows: affected_rows.iter().sum::<usize>(),
+            })?;
+            affected_rows.push(rows);
+        }
+        Ok(affected_rows)
+    }
+
     pub async fn sql(&self, sql: &str) -> Result<RpcOutput> {
-        let query = QueryRequest {
-            query: Some(query_request::Query::Sql(sql.to_string())),
-        };
+        let query = self.query_request(query_request::Query::Sql(sql.to_string()));
         self.do_query(query).await
     }
 
-    pub async fn logical_plan(&self, logical_plan: Vec<u8>) -> Result<RpcOutput> {
-        let query = QueryRequest {
-            query: Some(query_request::Query::LogicalPlan(logical_plan)),
+    /// Executes the `sql` query and converts the rows of its result into `T`, each
+    /// field is read from the column of the same name.
+    ///
+    /// ```ignore
+    /// struct Cpu {
+    ///     host: String,
+    ///     usage: f64,
+    /// }
+    /// client::impl_from_row!(Cpu { host, usage });
+    ///
+    /// let cpus = db.query_as::<Cpu>("SELECT host, usage FROM cpu").await?;
+    /// ```
+    pub async fn query_as<T: FromRow>(&self, sql: &str) -> Result<Vec<T>> {
+        match self.sql(sql).await? {
+            RpcOutput::RecordBatches(batches) => from_record_batches(&batches),
+            RpcOutput::AffectedRows(_) => IllegalFlightMessagesSnafu {
+                reason: "Expect 'RecordBatches' Flight messages for query",
+            }
+            .fail(),
+        }
+    }
+
+    /// Executes the `sql` query and streams its result, record batches are decoded as
+    /// they arrive instead of being buffered in one response.
+    ///
+    /// Only queries that return record batches are supported.
+    pub async fn sql_stream(&self, sql: &str) -> Result<SendableRecordBatchStream> {
+        let expr = ObjectExpr {
+            request: Some(object_expr::Request::Query(
+                self.query_request(query_request::Query::Sql(sql.to_string())),
+            )),
+        };
+        let ticket = Ticket {
+            ticket: expr.encode_to_vec(),
         };
+        let mut flight_data = match &self.request_options {
+            Some(options) => self.client.do_get_with_options(ticket, options).await?,
+            None => self.client.do_get(ticket).await?,
+        };
+
+        let mut decoder = FlightDecoder::default();
+        let schema = match flight_data.next().await {
+            Some(data) => match decoder.try_decode(data?).context(ConvertFlightDataSnafu)? {
+                Some(FlightMessage::Schema(schema)) => schema,
+                _ => {
+                    return IllegalFlightMessagesSnafu {
+                        reason: "Expect the first Flight message to be schema",
+                    }
+                    .fail()
+                }
+            },
+            None => {
+                return IllegalFlightMessagesSnafu {
+                    reason: "Flight data stream is empty",
+                }
+                .fail()
+            }
+        };
+
+        let stream = try_stream! {
+            while let Some(data) = flight_data.next().await {
+                let recordbatch = decode_recordbatch(&mut decoder, data)
+                    .map_err(BoxedError::new)
+                    .context(ExternalSnafu)?;
+                if let Some(recordbatch) = recordbatch {
+                    yield recordbatch;
+                }
+            }
+        };
+        Ok(Box::pin(FlightRecordBatchStream {
+            schema,
+            stream: Box::pin(stream),
+        }))
+    }
+
+    pub async fn logical_plan(&self, logical_plan: Vec<u8>) -> Result<RpcOutput> {
+        let query = self.query_request(query_request::Query::LogicalPlan(logical_plan));
         self.do_query(query).await
     }
 
+    fn query_request(&self, query: query_request::Query) -> QueryRequest {
+        QueryRequest {
+            query: Some(query),
+            schema_name: self.schema_name.clone(),
+            time_zone: self.time_zone.clone(),
+        }
+    }
+
     async fn do_query(&self, request: QueryRequest) -> Result<RpcOutput> {
         let expr = ObjectExpr {
             request: Some(object_expr::Request::Query(request)),
@@ -103,6 +256,33 @@ impl Database {
         self.object(expr).await?.try_into()
     }
 
+    /// Reads a table as of a committed sequence, for diagnostic purpose only.
+    ///
+    /// The request is only served by datanode.
+    pub async fn scan_at_sequence(&self, request: ScanAtSequenceRequest) -> Result<RpcOutput> {
+        let expr = ObjectExpr {
+            request: Some(object_expr::Request::Diagnostic(DiagnosticRequest {
+                request: Some(DiagnosticExpr::ScanAtSequence(request)),
+            })),
+        };
+        self.object(expr).await?.try_into()
+    }
+
+    /// Updates the settings of the datanode that can be changed without restart.
+    ///
+    /// The request is only served by datanode.
+    pub async fn update_runtime_config(
+        &self,
+        request: UpdateRuntimeConfigRequest,
+    ) -> Result<RpcOutput> {
+        let expr = ObjectExpr {
+            request: Some(object_expr::Request::Diagnostic(DiagnosticRequest {
+                request: Some(DiagnosticExpr::UpdateRuntimeConfig(request)),
+            })),
+        };
+        self.object(expr).await?.try_into()
+    }
+
     pub async fn object(&self, expr: ObjectExpr) -> Result<GrpcObjectResult> {
         let res = self.objects(vec![expr]).await?.pop().unwrap();
         Ok(res)
@@ -115,7 +295,10 @@ impl Database {
             exprs,
         };
 
-        let res = self.client.database(req).await?;
+        let res = match &self.request_options {
+            Some(options) => self.client.database_with_options(req, options).await?,
+            None => self.client.database(req).await?,
+        };
         let res = res.results;
 
         ensure!(
@@ -131,6 +314,41 @@ impl Database {
     }
 }
 
+/// Decodes the record batch in `data`, returns `None` if `data` is a dictionary batch.
+fn decode_recordbatch(
+    decoder: &mut FlightDecoder,
+    data: Result<FlightData>,
+) -> Result<Option<RecordBatch>> {
+    match decoder.try_decode(data?).context(ConvertFlightDataSnafu)? {
+        Some(FlightMessage::Recordbatch(recordbatch)) => Ok(Some(recordbatch)),
+        None => Ok(None),
+        _ => IllegalFlightMessagesSnafu {
+            reason: "Expect only record batches after the schema",
+        }
+        .fail(),
+    }
+}
+
+/// A [RecordBatchStream] decoded from a stream of [FlightData].
+struct FlightRecordBatchStream {
+    schema: SchemaRef,
+    stream: Pin<Box<dyn Stream<Item = common_recordbatch::error::Result<RecordBatch>> + Send>>,
+}
+
+impl RecordBatchStream for FlightRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for FlightRecordBatchStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
 #[derive(Debug)]
 pub enum RpcOutput {
     RecordBatches(RecordBatches),
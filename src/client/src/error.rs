@@ -57,6 +57,61 @@ pub enum Error {
         source: api::error::Error,
     },
 
+    #[snafu(display("Illegal row to insert, reason: {}", reason))]
+    IllegalInsertRow {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write row into insert request, source: {}", source))]
+    WriteRow {
+        #[snafu(backtrace)]
+        source: common_grpc::Error,
+    },
+
+    #[snafu(display(
+        "Failed to insert the request at index {}, {} rows of previous requests were inserted, source: {}",
+        index,
+        inserted_rows,
+        source
+    ))]
+    InsertChunk {
+        index: usize,
+        inserted_rows: usize,
+        #[snafu(source(from(Error, Box::new)))]
+        source: Box<Error>,
+    },
+
+    #[snafu(display("Column {} not found in the query result", column))]
+    ColumnNotFound {
+        column: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to convert column {} of type {} into {}",
+        column,
+        data_type,
+        target
+    ))]
+    ConvertColumn {
+        column: String,
+        data_type: String,
+        target: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to convert row {} of the query result, source: {}",
+        row,
+        source
+    ))]
+    ConvertRow {
+        row: usize,
+        #[snafu(source(from(Error, Box::new)))]
+        source: Box<Error>,
+    },
+
     #[snafu(display("Illegal GRPC client state: {}", err_msg))]
     IllegalGrpcClientState {
         err_msg: String,
@@ -80,6 +135,14 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Returns true if the error is caused by the peer being unavailable, e.g. the
+    /// connection to it is refused or broken.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, Error::TonicStatus { source, .. } if source.code() == tonic::Code::Unavailable)
+    }
+}
+
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -90,7 +153,13 @@ impl ErrorExt for Error {
             | Error::Datanode { .. }
             | Error::ColumnDataType { .. }
             | Error::MissingField { .. } => StatusCode::Internal,
-            Error::CreateChannel { source, .. } | Error::ConvertFlightData { source } => {
+            Error::CreateChannel { source, .. }
+            | Error::ConvertFlightData { source }
+            | Error::WriteRow { source } => source.status_code(),
+            Error::IllegalInsertRow { .. }
+            | Error::ColumnNotFound { .. }
+            | Error::ConvertColumn { .. } => StatusCode::InvalidArguments,
+            Error::InsertChunk { source, .. } | Error::ConvertRow { source, .. } => {
                 source.status_code()
             }
             Error::IllegalGrpcClientState { .. } => StatusCode::Unexpected,
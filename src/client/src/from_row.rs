@@ -0,0 +1,313 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed deserialization of the query results into user structs.
+
+use common_recordbatch::{RecordBatch, RecordBatches};
+use common_time::{Date, DateTime, Timestamp};
+use datatypes::data_type::DataType;
+use datatypes::prelude::Vector;
+use datatypes::value::Value;
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+
+/// Types that could be converted from the [Value] of a column.
+///
+/// Integers and floats are also converted from narrower types without loss, e.g. an
+/// `i64` from an `Int32` column.
+pub trait FromValue: Sized {
+    /// Name of the type in the conversion errors.
+    fn type_name() -> String;
+
+    /// Converts the `value`, returns `None` if the value is not of this type.
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+macro_rules! impl_from_value {
+    ($($Type: ty => [$($Variant: ident),+]),* $(,)?) => {
+        $(
+            impl FromValue for $Type {
+                fn type_name() -> String {
+                    stringify!($Type).to_string()
+                }
+
+                fn from_value(value: Value) -> Option<Self> {
+                    match value {
+                        $(Value::$Variant(v) => Some(v.into()),)+
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value!(
+    bool => [Boolean],
+    i8 => [Int8],
+    i16 => [Int8, Int16, UInt8],
+    i32 => [Int8, Int16, Int32, UInt8, UInt16],
+    i64 => [Int8, Int16, Int32, Int64, UInt8, UInt16, UInt32],
+    u8 => [UInt8],
+    u16 => [UInt8, UInt16],
+    u32 => [UInt8, UInt16, UInt32],
+    u64 => [UInt8, UInt16, UInt32, UInt64],
+    Date => [Date],
+    DateTime => [DateTime],
+    Timestamp => [Timestamp],
+);
+
+impl FromValue for f32 {
+    fn type_name() -> String {
+        "f32".to_string()
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Float32(v) => Some(v.into_inner()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn type_name() -> String {
+        "f64".to_string()
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Float32(v) => Some(v.into_inner().into()),
+            Value::Float64(v) => Some(v.into_inner()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for String {
+    fn type_name() -> String {
+        "String".to_string()
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(v) => Some(v.as_utf8().to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn type_name() -> String {
+        "Vec<u8>".to_string()
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Binary(v) => Some(v.to_vec()),
+            _ => None,
+        }
+    }
+}
+
+/// Null values are only converted into `Option`s.
+impl<T: FromValue> FromValue for Option<T> {
+    fn type_name() -> String {
+        format!("Option<{}>", T::type_name())
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+/// A row of the query result.
+#[derive(Debug, Clone, Copy)]
+pub struct ResultRow<'a> {
+    batch: &'a RecordBatch,
+    row: usize,
+}
+
+impl ResultRow<'_> {
+    /// Gets the value of the `column` in this row, converted into `T`.
+    pub fn get<T: FromValue>(&self, column: &str) -> Result<T> {
+        let index = self
+            .batch
+            .schema
+            .column_index_by_name(column)
+            .context(error::ColumnNotFoundSnafu { column })?;
+        let value = self.batch.column(index).get(self.row);
+        let data_type = value.data_type();
+        T::from_value(value).with_context(|| error::ConvertColumnSnafu {
+            column,
+            data_type: data_type.name(),
+            target: T::type_name(),
+        })
+    }
+}
+
+/// Types that could be built from a row of the query result, see [impl_from_row]
+/// to implement it by the names of the struct fields.
+pub trait FromRow: Sized {
+    fn from_row(row: &ResultRow<'_>) -> Result<Self>;
+}
+
+/// Implements [FromRow] for a struct, each field is read from the column of the
+/// same name.
+///
+/// ```ignore
+/// struct Cpu {
+///     host: String,
+///     usage: Option<f64>,
+/// }
+///
+/// client::impl_from_row!(Cpu { host, usage });
+/// ```
+#[macro_export]
+macro_rules! impl_from_row {
+    ($Struct: ident { $($field: ident),* $(,)? }) => {
+        impl $crate::FromRow for $Struct {
+            fn from_row(row: &$crate::ResultRow<'_>) -> $crate::Result<Self> {
+                Ok(Self {
+                    $($field: row.get(stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Converts all rows in `batches` into `T`, in order.
+pub fn from_record_batches<T: FromRow>(batches: &RecordBatches) -> Result<Vec<T>> {
+    let mut rows = Vec::with_capacity(batches.iter().map(|b| b.num_rows()).sum());
+    for batch in batches.iter() {
+        for row in 0..batch.num_rows() {
+            let row = T::from_row(&ResultRow { batch, row })
+                .context(error::ConvertRowSnafu { row: rows.len() })?;
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::prelude::{ConcreteDataType, VectorRef};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{
+        Float64Vector, Int32Vector, StringVector, TimestampMillisecondVector,
+    };
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Cpu {
+        host: String,
+        usage: i64,
+        idle: Option<f64>,
+        ts: Timestamp,
+    }
+
+    impl_from_row!(Cpu {
+        host,
+        usage,
+        idle,
+        ts,
+    });
+
+    #[derive(Debug)]
+    struct Host {
+        #[allow(unused)]
+        host: i64,
+    }
+
+    impl_from_row!(Host { host });
+
+    #[derive(Debug)]
+    struct Memory {
+        #[allow(unused)]
+        memory: f64,
+    }
+
+    impl_from_row!(Memory { memory });
+
+    fn new_batches() -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("usage", ConcreteDataType::int32_datatype(), false),
+            ColumnSchema::new("idle", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["a", "b"])),
+            Arc::new(Int32Vector::from_slice(&[1, 2])),
+            Arc::new(Float64Vector::from(vec![Some(0.5), None])),
+            Arc::new(TimestampMillisecondVector::from_slice(&[1000, 2000])),
+        ];
+        RecordBatches::try_from_columns(schema, columns).unwrap()
+    }
+
+    #[test]
+    fn test_from_record_batches() {
+        let rows = from_record_batches::<Cpu>(&new_batches()).unwrap();
+        assert_eq!(
+            vec![
+                Cpu {
+                    host: "a".to_string(),
+                    usage: 1,
+                    idle: Some(0.5),
+                    ts: Timestamp::new_millisecond(1000),
+                },
+                Cpu {
+                    host: "b".to_string(),
+                    usage: 2,
+                    idle: None,
+                    ts: Timestamp::new_millisecond(2000),
+                },
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        let err = from_record_batches::<Host>(&new_batches()).unwrap_err();
+        assert_eq!(
+            "Failed to convert row 0 of the query result, source: Failed to convert column host of type String into i64",
+            err.to_string()
+        );
+
+        let err = from_record_batches::<Memory>(&new_batches()).unwrap_err();
+        assert_eq!(
+            "Failed to convert row 0 of the query result, source: Column memory not found in the query result",
+            err.to_string()
+        );
+
+        // Null values are only converted into options.
+        assert_eq!(None, f64::from_value(Value::Null));
+        assert_eq!(Some(None), Option::<f64>::from_value(Value::Null));
+        // Integers are not narrowed.
+        assert_eq!(None, i32::from_value(Value::Int64(1)));
+        assert_eq!(Some(1), i64::from_value(Value::UInt32(1)));
+    }
+}
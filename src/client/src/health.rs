@@ -0,0 +1,93 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+
+/// Default time a peer is considered unavailable after a request to it failed.
+pub const DEFAULT_UNAVAILABLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracks the peers that are found unavailable.
+///
+/// A peer is marked unavailable once a request to it fails because of connection
+/// errors, the mark is cleared once a request or health check to it succeeds, or it
+/// expires after `unavailable_timeout`, so the peer is tried again.
+#[derive(Debug)]
+pub(crate) struct PeerHealth {
+    unavailable_timeout: Duration,
+    unavailable_since: RwLock<HashMap<String, Instant>>,
+}
+
+impl Default for PeerHealth {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNAVAILABLE_TIMEOUT)
+    }
+}
+
+impl PeerHealth {
+    pub(crate) fn new(unavailable_timeout: Duration) -> Self {
+        Self {
+            unavailable_timeout,
+            unavailable_since: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn is_available(&self, peer: &str) -> bool {
+        self.unavailable_since
+            .read()
+            .get(peer)
+            .map(|since| since.elapsed() >= self.unavailable_timeout)
+            .unwrap_or(true)
+    }
+
+    pub(crate) fn mark_unavailable(&self, peer: &str) {
+        let _ = self
+            .unavailable_since
+            .write()
+            .insert(peer.to_string(), Instant::now());
+    }
+
+    pub(crate) fn mark_available(&self, peer: &str) {
+        // Most requests are sent to available peers, checks with the read lock first.
+        if !self.unavailable_since.read().contains_key(peer) {
+            return;
+        }
+        let _ = self.unavailable_since.write().remove(peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_health() {
+        let health = PeerHealth::default();
+        assert!(health.is_available("127.0.0.1:3001"));
+
+        health.mark_unavailable("127.0.0.1:3001");
+        assert!(!health.is_available("127.0.0.1:3001"));
+        assert!(health.is_available("127.0.0.1:3002"));
+
+        health.mark_available("127.0.0.1:3001");
+        assert!(health.is_available("127.0.0.1:3001"));
+
+        // Marks expire after the timeout.
+        let health = PeerHealth::new(Duration::ZERO);
+        health.mark_unavailable("127.0.0.1:3001");
+        assert!(health.is_available("127.0.0.1:3001"));
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed builder of [InsertRequest]s.
+
+use std::collections::HashMap;
+
+use api::v1::column::SemanticType;
+use api::v1::{ColumnDataType, InsertRequest};
+use common_grpc::writer::LinesWriter;
+pub use common_grpc::writer::Precision;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+
+/// Default max number of rows in one [InsertRequest].
+pub const DEFAULT_MAX_ROWS_PER_REQUEST: usize = 4096;
+
+/// Value of a field column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Int64(i64),
+    UInt64(u64),
+    Float64(f64),
+    Boolean(bool),
+    String(String),
+}
+
+impl FieldValue {
+    fn datatype(&self) -> ColumnDataType {
+        match self {
+            FieldValue::Int64(_) => ColumnDataType::Int64,
+            FieldValue::UInt64(_) => ColumnDataType::Uint64,
+            FieldValue::Float64(_) => ColumnDataType::Float64,
+            FieldValue::Boolean(_) => ColumnDataType::Boolean,
+            FieldValue::String(_) => ColumnDataType::String,
+        }
+    }
+}
+
+macro_rules! impl_from_for_field_value {
+    ($($Type: ty => $Variant: ident),*) => {
+        $(
+            impl From<$Type> for FieldValue {
+                fn from(value: $Type) -> Self {
+                    FieldValue::$Variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_field_value!(
+    i64 => Int64,
+    u64 => UInt64,
+    f64 => Float64,
+    bool => Boolean,
+    String => String,
+    &str => String
+);
+
+/// A row to insert, made up of tags, fields and a timestamp.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Row {
+    tags: Vec<(String, String)>,
+    fields: Vec<(String, FieldValue)>,
+    timestamp: Option<(String, i64, Precision)>,
+}
+
+impl Row {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn tag(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the time index of the row, the `value` is stored in milliseconds.
+    pub fn timestamp(mut self, name: impl Into<String>, value: i64, precision: Precision) -> Self {
+        self.timestamp = Some((name.into(), value, precision));
+        self
+    }
+}
+
+/// Accumulates [Row]s of a table into the columns of [InsertRequest]s.
+///
+/// Rows are split into a new request once the current one has `max_rows_per_request`
+/// rows. Columns absent in a row are null.
+pub struct InsertBuilder {
+    schema_name: String,
+    table_name: String,
+    max_rows_per_request: usize,
+    /// Data type and semantic type of columns that have been written.
+    columns: HashMap<String, (ColumnDataType, SemanticType)>,
+    writer: Option<LinesWriter>,
+    rows: usize,
+    requests: Vec<InsertRequest>,
+}
+
+impl InsertBuilder {
+    pub fn new(schema_name: impl Into<String>, table_name: impl Into<String>) -> Self {
+        Self {
+            schema_name: schema_name.into(),
+            table_name: table_name.into(),
+            max_rows_per_request: DEFAULT_MAX_ROWS_PER_REQUEST,
+            columns: HashMap::new(),
+            writer: None,
+            rows: 0,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Sets the max number of rows in one request, defaults to 4096.
+    pub fn max_rows_per_request(self, max_rows_per_request: usize) -> Self {
+        assert!(
+            max_rows_per_request > 0,
+            "max_rows_per_request must be positive"
+        );
+        Self {
+            max_rows_per_request,
+            ..self
+        }
+    }
+
+    /// Adds the `row`, returns an error without changing the builder if the `row` is
+    /// invalid, e.g. it has no timestamp or a column's type differs from previous rows.
+    pub fn push(&mut self, row: Row) -> Result<()> {
+        self.check_row(&row)?;
+
+        let max_rows = self.max_rows_per_request;
+        let writer = self
+            .writer
+            .get_or_insert_with(|| LinesWriter::with_lines(max_rows));
+        for (name, value) in &row.tags {
+            writer
+                .write_tag(name, value)
+                .context(error::WriteRowSnafu)?;
+        }
+        for (name, value) in &row.fields {
+            match value {
+                FieldValue::Int64(v) => writer.write_i64(name, *v),
+                FieldValue::UInt64(v) => writer.write_u64(name, *v),
+                FieldValue::Float64(v) => writer.write_f64(name, *v),
+                FieldValue::Boolean(v) => writer.write_bool(name, *v),
+                FieldValue::String(v) => writer.write_string(name, v),
+            }
+            .context(error::WriteRowSnafu)?;
+        }
+        // Safety: rows without timestamp are rejected by `check_row`.
+        let (name, value, precision) = row.timestamp.as_ref().unwrap();
+        writer
+            .write_ts(name, (*value, *precision))
+            .context(error::WriteRowSnafu)?;
+        writer.commit();
+
+        self.rows += 1;
+        if self.rows == self.max_rows_per_request {
+            self.finish_request();
+        }
+        Ok(())
+    }
+
+    /// Number of rows added.
+    pub fn num_rows(&self) -> usize {
+        self.requests
+            .iter()
+            .map(|request| request.row_count as usize)
+            .sum::<usize>()
+            + self.rows
+    }
+
+    /// Returns the requests containing all added rows.
+    pub fn finish(mut self) -> Vec<InsertRequest> {
+        self.finish_request();
+        self.requests
+    }
+
+    fn finish_request(&mut self) {
+        let Some(writer) = self.writer.take() else {
+            return;
+        };
+        let (columns, row_count) = writer.finish();
+        self.requests.push(InsertRequest {
+            schema_name: self.schema_name.clone(),
+            table_name: self.table_name.clone(),
+            columns,
+            row_count,
+            region_number: 0,
+        });
+        self.rows = 0;
+    }
+
+    /// Checks the `row` before writing it, so a bad row never leaves partial values
+    /// in the columns.
+    fn check_row(&mut self, row: &Row) -> Result<()> {
+        let (ts_name, _, _) = row
+            .timestamp
+            .as_ref()
+            .context(error::IllegalInsertRowSnafu {
+                reason: "the timestamp is not set",
+            })?;
+
+        let columns = row
+            .tags
+            .iter()
+            .map(|(name, _)| (name, ColumnDataType::String, SemanticType::Tag))
+            .chain(
+                row.fields
+                    .iter()
+                    .map(|(name, value)| (name, value.datatype(), SemanticType::Field)),
+            )
+            .chain(std::iter::once((
+                ts_name,
+                ColumnDataType::TimestampMillisecond,
+                SemanticType::Timestamp,
+            )));
+
+        let mut new_columns = HashMap::new();
+        let mut row_columns = Vec::with_capacity(row.tags.len() + row.fields.len() + 1);
+        for (name, datatype, semantic_type) in columns {
+            ensure!(
+                !row_columns.contains(&name),
+                error::IllegalInsertRowSnafu {
+                    reason: format!("column {name} appears more than once"),
+                }
+            );
+            row_columns.push(name);
+
+            match self.columns.get(name) {
+                Some(expected) => ensure!(
+                    *expected == (datatype, semantic_type),
+                    error::IllegalInsertRowSnafu {
+                        reason: format!(
+                            "column {name} is {:?} {:?}, but was {:?} {:?} in previous rows",
+                            semantic_type, datatype, expected.1, expected.0
+                        ),
+                    }
+                ),
+                None => {
+                    let _ = new_columns.insert(name.clone(), (datatype, semantic_type));
+                }
+            }
+        }
+        self.columns.extend(new_columns);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_base::BitVec;
+
+    use super::*;
+
+    fn new_row(host: &str, ts: i64) -> Row {
+        Row::new()
+            .tag("host", host)
+            .field("cpu", 0.5)
+            .timestamp("ts", ts, Precision::Millisecond)
+    }
+
+    #[test]
+    fn test_insert_builder() {
+        let mut builder = InsertBuilder::new("public", "demo").max_rows_per_request(2);
+        builder.push(new_row("host1", 1000)).unwrap();
+        builder
+            .push(
+                Row::new()
+                    .tag("host", "host2")
+                    .field("memory", 1024u64)
+                    .timestamp("ts", 2, Precision::Second),
+            )
+            .unwrap();
+        builder.push(new_row("host3", 3000)).unwrap();
+        assert_eq!(3, builder.num_rows());
+
+        let requests = builder.finish();
+        assert_eq!(2, requests.len());
+        assert_eq!(2, requests[0].row_count);
+        assert_eq!(1, requests[1].row_count);
+        assert_eq!("demo", requests[0].table_name);
+        assert_eq!("public", requests[0].schema_name);
+
+        let columns = &requests[0].columns;
+        let names: Vec<_> = columns.iter().map(|c| c.column_name.as_str()).collect();
+        assert_eq!(vec!["host", "cpu", "ts", "memory"], names);
+        assert_eq!(
+            vec![1000, 2000],
+            columns[2].values.as_ref().unwrap().ts_millisecond_values
+        );
+        let cpu_null_mask = BitVec::from_vec(columns[1].null_mask.clone());
+        assert!(!cpu_null_mask[0]);
+        assert!(cpu_null_mask[1]);
+
+        let columns = &requests[1].columns;
+        assert_eq!(3, columns.len());
+        assert_eq!(
+            vec!["host3".to_string()],
+            columns[0].values.as_ref().unwrap().string_values
+        );
+    }
+
+    #[test]
+    fn test_push_illegal_row() {
+        let mut builder = InsertBuilder::new("public", "demo");
+        builder.push(new_row("host1", 1000)).unwrap();
+
+        let err = builder
+            .push(Row::new().tag("host", "host2").field("cpu", 0.5))
+            .unwrap_err();
+        assert!(matches!(err, error::Error::IllegalInsertRow { .. }));
+
+        // The type of cpu differs.
+        let err = builder
+            .push(
+                Row::new()
+                    .field("cpu", 1i64)
+                    .timestamp("ts", 2000, Precision::Millisecond),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("column cpu"), "{err}");
+
+        let err = builder
+            .push(new_row("host2", 2000).tag("host", "host3"))
+            .unwrap_err();
+        assert!(err.to_string().contains("more than once"), "{err}");
+
+        // Illegal rows are not added.
+        assert_eq!(1, builder.num_rows());
+        let requests = builder.finish();
+        assert_eq!(1, requests.len());
+        assert_eq!(1, requests[0].row_count);
+        assert_eq!(3, requests[0].columns.len());
+    }
+}
@@ -12,13 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod auth;
 mod client;
 mod database;
 mod error;
+mod from_row;
+mod health;
+mod insert;
 pub mod load_balance;
+pub mod metric;
+mod options;
 
 pub use api;
 
-pub use self::client::Client;
+pub use self::auth::Auth;
+pub use self::client::{Client, ClientBuilder};
 pub use self::database::{Database, RpcOutput};
 pub use self::error::{Error, Result};
+pub use self::from_row::{from_record_batches, FromRow, FromValue, ResultRow};
+pub use self::insert::{FieldValue, InsertBuilder, Precision, Row};
+pub use self::options::RequestOptions;
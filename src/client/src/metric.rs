@@ -0,0 +1,250 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client side metrics hooks.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_error::status_code::StatusCode;
+use metrics::{histogram, increment_counter};
+
+pub const METRIC_CLIENT_REQUESTS_TOTAL: &str = "client.requests_total";
+pub const METRIC_CLIENT_REQUEST_ELAPSED: &str = "client.request_elapsed";
+pub const METRIC_CLIENT_REQUEST_BYTES: &str = "client.request_bytes";
+pub const METRIC_CLIENT_RESPONSE_BYTES: &str = "client.response_bytes";
+
+/// Outcome of a request sent by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// The request was served by the endpoint.
+    Success,
+    /// The request failed with the status code.
+    Failure(StatusCode),
+}
+
+impl RequestOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RequestOutcome::Success => "success",
+            RequestOutcome::Failure(_) => "failure",
+        }
+    }
+}
+
+/// Hook to observe the requests sent by the client, applications embedding the client
+/// could implement it to monitor the health of database calls.
+///
+/// Hook methods are called in the request path, so they should be cheap and never block.
+pub trait MetricsHook: Send + Sync {
+    /// Called before the request of `request_bytes` is sent to the `endpoint`.
+    fn on_request_start(&self, endpoint: &str, request_bytes: usize) {
+        let _ = (endpoint, request_bytes);
+    }
+
+    /// Called after the request to the `endpoint` is finished.
+    ///
+    /// `response_bytes` is zero if the request failed.
+    fn on_request_end(
+        &self,
+        endpoint: &str,
+        response_bytes: usize,
+        latency: Duration,
+        outcome: RequestOutcome,
+    ) {
+        let _ = (endpoint, response_bytes, latency, outcome);
+    }
+}
+
+pub type MetricsHookRef = Arc<dyn MetricsHook>;
+
+/// [MetricsHook] that records requests into the global [metrics] recorder, which is exposed
+/// in Prometheus format once a Prometheus recorder is installed, e.g. by
+/// `common_telemetry::metric::init_default_metrics_recorder`.
+#[derive(Debug, Default)]
+pub struct PrometheusMetricsHook;
+
+impl MetricsHook for PrometheusMetricsHook {
+    fn on_request_start(&self, endpoint: &str, request_bytes: usize) {
+        histogram!(
+            METRIC_CLIENT_REQUEST_BYTES,
+            request_bytes as f64,
+            "endpoint" => endpoint.to_string()
+        );
+    }
+
+    fn on_request_end(
+        &self,
+        endpoint: &str,
+        response_bytes: usize,
+        latency: Duration,
+        outcome: RequestOutcome,
+    ) {
+        let endpoint = endpoint.to_string();
+        increment_counter!(
+            METRIC_CLIENT_REQUESTS_TOTAL,
+            "endpoint" => endpoint.clone(),
+            "outcome" => outcome.as_str()
+        );
+        histogram!(
+            METRIC_CLIENT_REQUEST_ELAPSED,
+            latency,
+            "endpoint" => endpoint.clone()
+        );
+        if outcome == RequestOutcome::Success {
+            histogram!(
+                METRIC_CLIENT_RESPONSE_BYTES,
+                response_bytes as f64,
+                "endpoint" => endpoint
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use api::v1::{greptime_server, BatchRequest, BatchResponse, DatabaseRequest};
+    use common_grpc::channel_manager::ChannelManager;
+    use prost::Message;
+    use tonic::transport::Server;
+    use tonic::{Request, Response, Status};
+    use tower::service_fn;
+
+    use super::*;
+    use crate::Client;
+
+    #[derive(Default)]
+    struct RecordingHook {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl MetricsHook for RecordingHook {
+        fn on_request_start(&self, endpoint: &str, request_bytes: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {endpoint} {request_bytes}"));
+        }
+
+        fn on_request_end(
+            &self,
+            endpoint: &str,
+            response_bytes: usize,
+            _latency: Duration,
+            outcome: RequestOutcome,
+        ) {
+            self.events.lock().unwrap().push(format!(
+                "end {endpoint} {response_bytes} {}",
+                outcome.as_str()
+            ));
+        }
+    }
+
+    /// Peer that serves every batch with an empty response.
+    struct MockPeer;
+
+    #[tonic::async_trait]
+    impl greptime_server::Greptime for MockPeer {
+        async fn batch(
+            &self,
+            _req: Request<BatchRequest>,
+        ) -> std::result::Result<Response<BatchResponse>, Status> {
+            Ok(Response::new(BatchResponse::default()))
+        }
+    }
+
+    /// Creates a client connected to a [MockPeer] listening on `addr`.
+    fn mock_client(addr: &str) -> Client {
+        let (client, server) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(greptime_server::GreptimeServer::new(MockPeer))
+                .serve_with_incoming(futures::stream::iter(vec![Ok::<_, std::io::Error>(server)]))
+                .await
+        });
+
+        // Move client to an option so we can _move_ the inner value
+        // on the first attempt to connect. All other attempts will fail.
+        let mut client = Some(client);
+        let channel_manager = ChannelManager::new();
+        channel_manager
+            .reset_with_connector(
+                addr,
+                service_fn(move |_| {
+                    let client = client.take();
+
+                    async move {
+                        if let Some(client) = client {
+                            Ok(client)
+                        } else {
+                            Err(std::io::Error::new(
+                                std::io::ErrorKind::Other,
+                                "Client already taken",
+                            ))
+                        }
+                    }
+                }),
+            )
+            .unwrap();
+        Client::with_manager_and_urls(channel_manager, [addr])
+    }
+
+    #[tokio::test]
+    async fn test_metrics_hook() {
+        // "127.0.0.1:3001" is just a placeholder, does not actually connect to it.
+        let addr = "127.0.0.1:3001";
+        let client = mock_client(addr);
+        let hook = Arc::new(RecordingHook::default());
+        client.set_metrics_hook(hook.clone());
+
+        let req = BatchRequest {
+            databases: vec![DatabaseRequest {
+                name: "greptime".to_string(),
+                exprs: vec![],
+            }],
+            ..Default::default()
+        };
+        let request_bytes = req.encoded_len();
+        let _ = client.batch(req).await.unwrap();
+
+        assert_eq!(
+            vec![
+                format!("start {addr} {request_bytes}"),
+                format!("end {addr} 0 success"),
+            ],
+            *hook.events.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prometheus_metrics_hook() {
+        common_telemetry::metric::init_default_metrics_recorder();
+
+        let hook = PrometheusMetricsHook;
+        hook.on_request_start("127.0.0.1:3001", 10);
+        hook.on_request_end(
+            "127.0.0.1:3001",
+            20,
+            Duration::from_millis(1),
+            RequestOutcome::Success,
+        );
+
+        let handle = common_telemetry::metric::try_handle().unwrap();
+        let text = handle.render();
+        assert!(text.contains("client_requests_total"));
+        assert!(text.contains("client_request_elapsed"));
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use tonic::Code;
+
+use crate::error::Error;
+
+const DEFAULT_MAX_RETRIES: usize = 2;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Deadline and retry policy of requests sent by the client.
+///
+/// Only requests that are safe to be sent again, i.e. read-only queries, are retried.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Deadline of a request including all its retries, `None` means no deadline.
+    ///
+    /// The remaining time is sent to the server as the gRPC timeout of each attempt.
+    pub timeout: Option<Duration>,
+    /// Max times a failed request is retried.
+    pub max_retries: usize,
+    /// gRPC status codes of errors that are retried.
+    pub retry_on: Vec<Code>,
+    /// Backoff before the first retry to a peer that has been tried, it's doubled
+    /// after each retry.
+    pub initial_backoff: Duration,
+    /// Max backoff between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_on: vec![Code::Unavailable],
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    pub fn max_retries(self, max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+
+    pub fn retry_on(self, codes: Vec<Code>) -> Self {
+        Self {
+            retry_on: codes,
+            ..self
+        }
+    }
+
+    pub fn backoff(self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            initial_backoff,
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Returns true if the request failed with `error` should be retried.
+    pub(crate) fn should_retry(&self, error: &Error) -> bool {
+        match error {
+            Error::TonicStatus { source, .. } => self.retry_on.contains(&source.code()),
+            _ => false,
+        }
+    }
+
+    /// Returns the backoff before the `n`-th retry to the same peer, starts from 0.
+    pub(crate) fn backoff_of(&self, n: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(n))
+            .map(|backoff| backoff.min(self.max_backoff))
+            .unwrap_or(self.max_backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use snafu::IntoError;
+
+    use super::*;
+    use crate::error::TonicStatusSnafu;
+
+    #[test]
+    fn test_backoff() {
+        let options =
+            RequestOptions::new().backoff(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(Duration::from_millis(100), options.backoff_of(0));
+        assert_eq!(Duration::from_millis(200), options.backoff_of(1));
+        assert_eq!(Duration::from_millis(800), options.backoff_of(3));
+        assert_eq!(Duration::from_secs(1), options.backoff_of(4));
+        assert_eq!(Duration::from_secs(1), options.backoff_of(u32::MAX));
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let error = |status| {
+            TonicStatusSnafu {
+                addr: "127.0.0.1:3001",
+            }
+            .into_error(status)
+        };
+
+        let options = RequestOptions::default();
+        assert!(options.should_retry(&error(tonic::Status::unavailable("refused"))));
+        assert!(!options.should_retry(&error(tonic::Status::internal("boom"))));
+
+        let options = options.retry_on(vec![Code::Unavailable, Code::ResourceExhausted]);
+        assert!(options.should_retry(&error(tonic::Status::resource_exhausted("busy"))));
+        assert!(!options.should_retry(&Error::MissingHeader));
+    }
+}
@@ -16,8 +16,9 @@ use std::fmt;
 
 use clap::Parser;
 use cmd::error::Result;
-use cmd::{datanode, frontend, metasrv, standalone};
+use cmd::{cli, datanode, frontend, metasrv, schema_compat, standalone};
 use common_telemetry::logging::{error, info};
+use common_telemetry::TracingExporter;
 
 #[derive(Parser)]
 #[clap(name = "greptimedb", version = print_version())]
@@ -26,6 +27,10 @@ struct Command {
     log_dir: String,
     #[clap(long, default_value = "info")]
     log_level: String,
+    /// Exports the tracing spans to the OTLP collector at the endpoint, e.g.
+    /// `http://localhost:4317`.
+    #[clap(long)]
+    otlp_endpoint: Option<String>,
     #[clap(subcommand)]
     subcmd: SubCommand,
 }
@@ -46,6 +51,10 @@ enum SubCommand {
     Metasrv(metasrv::Command),
     #[clap(name = "standalone")]
     Standalone(standalone::Command),
+    #[clap(name = "schema-compat")]
+    SchemaCompat(schema_compat::Command),
+    #[clap(name = "cli")]
+    Cli(cli::Command),
 }
 
 impl SubCommand {
@@ -55,6 +64,8 @@ impl SubCommand {
             SubCommand::Frontend(cmd) => cmd.run().await,
             SubCommand::Metasrv(cmd) => cmd.run().await,
             SubCommand::Standalone(cmd) => cmd.run().await,
+            SubCommand::SchemaCompat(cmd) => cmd.run().await,
+            SubCommand::Cli(cmd) => cmd.run().await,
         }
     }
 }
@@ -66,6 +77,8 @@ impl fmt::Display for SubCommand {
             SubCommand::Frontend(..) => write!(f, "greptime-frontend"),
             SubCommand::Metasrv(..) => write!(f, "greptime-metasrv"),
             SubCommand::Standalone(..) => write!(f, "greptime-standalone"),
+            SubCommand::SchemaCompat(..) => write!(f, "greptime-schema-compat"),
+            SubCommand::Cli(..) => write!(f, "greptime-cli"),
         }
     }
 }
@@ -91,10 +104,16 @@ async fn main() -> Result<()> {
     let app_name = &cmd.subcmd.to_string();
     let log_dir = &cmd.log_dir;
     let log_level = &cmd.log_level;
+    let tracing_exporter = cmd
+        .otlp_endpoint
+        .clone()
+        .map(|endpoint| TracingExporter::Otlp { endpoint });
 
+    common_function::scalars::system::set_build_features(::datanode::BUILD_FEATURES);
     common_telemetry::set_panic_hook();
     common_telemetry::init_default_metrics_recorder();
-    let _guard = common_telemetry::init_global_logging(app_name, log_dir, log_level, false);
+    let _guard =
+        common_telemetry::init_global_logging(app_name, log_dir, log_level, tracing_exporter);
 
     tokio::select! {
         result = cmd.run() => {
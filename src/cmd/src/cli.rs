@@ -0,0 +1,436 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::Parser;
+use client::{Client, Database, RpcOutput};
+use common_recordbatch::RecordBatches;
+use datatypes::value::Value;
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+
+const HISTORY_FILE: &str = ".greptimedb_history";
+
+const HELP: &str = r"Statements are terminated by ';' and may span multiple lines.
+
+Commands:
+  \q, exit, quit  Quit the shell
+  \timing         Toggle printing the elapsed time of statements
+  \c <database>   Switch to the database, same as `USE <database>;`
+  \?              Show this help";
+
+/// Interactive SQL shell connected to a running frontend or standalone node through gRPC.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// gRPC address of the node to connect to.
+    #[clap(long, default_value = "127.0.0.1:4001")]
+    grpc_addr: String,
+    /// Database to run statements in.
+    #[clap(short, long, default_value = "public")]
+    database: String,
+    /// Values longer than it are truncated in the output, 0 for unlimited.
+    #[clap(long, default_value = "40")]
+    max_column_width: usize,
+    /// Prints the elapsed time of each statement.
+    #[clap(long)]
+    timing: bool,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let client = Client::with_urls(vec![&self.grpc_addr]);
+        let mut repl = Repl {
+            client: Database::new("greptime", client).with_schema(&self.database),
+            database: self.database,
+            max_column_width: self.max_column_width,
+            timing: self.timing,
+        };
+        println!("Connected to {}, type \\? for help.", self.grpc_addr);
+        repl.run().await
+    }
+}
+
+struct Repl {
+    client: Database,
+    /// Name of the current database.
+    database: String,
+    max_column_width: usize,
+    timing: bool,
+}
+
+impl Repl {
+    async fn run(&mut self) -> Result<()> {
+        let mut editor = Editor::<()>::new().context(error::ReadlineSnafu)?;
+        let history = history_file();
+        if let Some(path) = &history {
+            // The history file doesn't exist on the first run.
+            let _ = editor.load_history(path);
+        }
+
+        // Lines of the statement not terminated yet.
+        let mut buffer = String::new();
+        loop {
+            let prompt = if buffer.is_empty() {
+                format!("{}> ", self.database)
+            } else {
+                format!("{:>width$}> ", "-", width = self.database.len())
+            };
+            let line = match editor.readline(&prompt) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => {
+                    // Discards the input like most shells do on Ctrl-C.
+                    buffer.clear();
+                    continue;
+                }
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(e).context(error::ReadlineSnafu),
+            };
+
+            if buffer.is_empty() {
+                match MetaCommand::parse(&line) {
+                    Some(MetaCommand::Quit) => break,
+                    Some(command) => {
+                        editor.add_history_entry(line.trim());
+                        self.execute_meta(command);
+                        continue;
+                    }
+                    None => (),
+                }
+            }
+
+            buffer.push_str(&line);
+            buffer.push('\n');
+            let (statements, rest) = split_statements(&buffer);
+            buffer = rest;
+            for statement in statements {
+                editor.add_history_entry(&statement);
+                self.execute(&statement).await;
+            }
+        }
+
+        if let Some(path) = &history {
+            if let Err(e) = editor.save_history(path) {
+                eprintln!("Failed to save history to {}: {e}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    fn execute_meta(&mut self, command: MetaCommand) {
+        match command {
+            MetaCommand::Quit => (),
+            MetaCommand::Timing => {
+                self.timing = !self.timing;
+                println!("Timing is {}", if self.timing { "on" } else { "off" });
+            }
+            MetaCommand::Use(database) => self.use_database(database),
+            MetaCommand::Help => println!("{HELP}"),
+            MetaCommand::Unknown(command) => {
+                eprintln!("Unknown command: {command}, type \\? for help")
+            }
+        }
+    }
+
+    async fn execute(&mut self, sql: &str) {
+        if let Some(database) = parse_use(sql) {
+            self.use_database(database);
+            return;
+        }
+
+        let start = Instant::now();
+        let result = self.client.sql(sql).await;
+        let elapsed = start.elapsed();
+        match result {
+            Ok(RpcOutput::RecordBatches(batches)) => {
+                println!("{}", format_table(&batches, self.max_column_width));
+                println!("{} rows in set", batches.num_rows());
+            }
+            Ok(RpcOutput::AffectedRows(rows)) => println!("Affected Rows: {rows}"),
+            Err(e) => eprintln!("Error: {e}"),
+        }
+        if self.timing {
+            println!("Time: {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+        }
+    }
+
+    /// Runs the following statements in `database`. The database is not checked
+    /// until a statement is sent to the node.
+    fn use_database(&mut self, database: String) {
+        self.client = self.client.clone().with_schema(&database);
+        self.database = database;
+        println!("Database changed");
+    }
+}
+
+/// Commands handled by the shell instead of sent to the node.
+#[derive(Debug, PartialEq, Eq)]
+enum MetaCommand {
+    Quit,
+    Timing,
+    Use(String),
+    Help,
+    Unknown(String),
+}
+
+impl MetaCommand {
+    fn parse(line: &str) -> Option<MetaCommand> {
+        let line = line.trim();
+        if line.eq_ignore_ascii_case("exit") || line.eq_ignore_ascii_case("quit") {
+            return Some(MetaCommand::Quit);
+        }
+
+        let mut parts = line.strip_prefix('\\')?.split_whitespace();
+        let command = match (parts.next(), parts.next(), parts.next()) {
+            (Some("q"), None, None) => MetaCommand::Quit,
+            (Some("timing"), None, None) => MetaCommand::Timing,
+            (Some("c"), Some(database), None) => MetaCommand::Use(unquote(database).to_string()),
+            (Some("?"), None, None) => MetaCommand::Help,
+            _ => MetaCommand::Unknown(line.to_string()),
+        };
+        Some(command)
+    }
+}
+
+/// Returns the database of a `USE <database>` statement.
+fn parse_use(sql: &str) -> Option<String> {
+    let sql = sql.trim().trim_end_matches(';');
+    let mut parts = sql.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(keyword), Some(database), None) if keyword.eq_ignore_ascii_case("use") => {
+            Some(unquote(database).to_string())
+        }
+        _ => None,
+    }
+}
+
+fn unquote(ident: &str) -> &str {
+    for quote in ['`', '"'] {
+        if let Some(ident) = ident
+            .strip_prefix(quote)
+            .and_then(|ident| ident.strip_suffix(quote))
+        {
+            return ident;
+        }
+    }
+    ident
+}
+
+/// Splits the statements terminated by ';' from `input`, semicolons in quoted strings
+/// or identifiers are ignored. Returns the complete statements and the rest of the
+/// input.
+fn split_statements(input: &str) -> (Vec<String>, String) {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            // An escaped quote is treated as two quoted strings, which is fine for
+            // splitting.
+            (Some(q), c) if q == c => quote = None,
+            (None, ';') => {
+                let statement = input[start..=i].trim();
+                if statement != ";" {
+                    statements.push(statement.to_string());
+                }
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    let rest = input[start..].trim_start();
+    (statements, rest.to_string())
+}
+
+fn history_file() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+/// Formats the batches as a table, values longer than `max_width` characters are
+/// truncated if `max_width` is positive.
+fn format_table(batches: &RecordBatches, max_width: usize) -> String {
+    let header = batches
+        .schema()
+        .column_schemas()
+        .iter()
+        .map(|column| truncate(&column.name, max_width))
+        .collect::<Vec<_>>();
+    let rows = batches
+        .iter()
+        .flat_map(|batch| batch.rows())
+        .map(|row| {
+            row.iter()
+                .map(|value| truncate(&format_value(value), max_width))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header
+        .iter()
+        .map(|name| name.chars().count())
+        .collect::<Vec<_>>();
+    for row in &rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(width + 2))
+        .collect::<Vec<_>>()
+        .join("+");
+    let separator = format!("+{separator}+");
+    let format_row = |row: &[String]| {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| {
+                let padding = width - value.chars().count();
+                format!(" {value}{} ", " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join("|");
+        format!("|{cells}|")
+    };
+
+    let mut lines = vec![separator.clone(), format_row(&header), separator.clone()];
+    if !rows.is_empty() {
+        lines.extend(rows.iter().map(|row| format_row(row)));
+        lines.push(separator);
+    }
+    lines.join("\n")
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        // Keeps the table in shape.
+        value => value.to_string().replace('\n', "\\n"),
+    }
+}
+
+fn truncate(value: &str, max_width: usize) -> String {
+    const ELLIPSIS: &str = "...";
+
+    if max_width == 0 || value.chars().count() <= max_width {
+        return value.to_string();
+    }
+    let keep = max_width.saturating_sub(ELLIPSIS.len());
+    let mut truncated = value.chars().take(keep).collect::<String>();
+    truncated.push_str(&ELLIPSIS[..max_width - keep]);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int32Vector, StringVector, VectorRef};
+
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let (statements, rest) = split_statements("select 1;\n");
+        assert_eq!(vec!["select 1;"], statements);
+        assert!(rest.is_empty());
+
+        let (statements, rest) = split_statements("select\n1");
+        assert!(statements.is_empty());
+        assert_eq!("select\n1", rest);
+
+        let (statements, rest) =
+            split_statements("select ';' from `a;b`; ;insert into t values(\"x;\");\nselect");
+        assert_eq!(
+            vec!["select ';' from `a;b`;", "insert into t values(\"x;\");"],
+            statements
+        );
+        assert_eq!("select", rest);
+
+        let (statements, rest) = split_statements("select 'it''s;\n");
+        assert!(statements.is_empty());
+        assert_eq!("select 'it''s;\n", rest);
+    }
+
+    #[test]
+    fn test_parse_meta_command() {
+        assert_eq!(Some(MetaCommand::Quit), MetaCommand::parse("\\q"));
+        assert_eq!(Some(MetaCommand::Quit), MetaCommand::parse(" EXIT "));
+        assert_eq!(Some(MetaCommand::Timing), MetaCommand::parse("\\timing"));
+        assert_eq!(
+            Some(MetaCommand::Use("test".to_string())),
+            MetaCommand::parse("\\c `test`")
+        );
+        assert_eq!(
+            Some(MetaCommand::Unknown("\\c".to_string())),
+            MetaCommand::parse("\\c")
+        );
+        assert_eq!(None, MetaCommand::parse("select 1;"));
+    }
+
+    #[test]
+    fn test_parse_use() {
+        assert_eq!(Some("test".to_string()), parse_use("use test;"));
+        assert_eq!(Some("test".to_string()), parse_use("USE `test`;"));
+        assert_eq!(None, parse_use("use;"));
+        assert_eq!(None, parse_use("select * from use;"));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!("hello", truncate("hello", 0));
+        assert_eq!("hello", truncate("hello", 5));
+        assert_eq!("h...", truncate("hello", 4));
+        assert_eq!("..", truncate("hello", 2));
+        assert_eq!("你好世界啊", truncate("你好世界啊", 5));
+        assert_eq!("你...", truncate("你好世界啊", 4));
+    }
+
+    #[test]
+    fn test_format_table() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("n", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("s", ConcreteDataType::string_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from(vec![Some(1), None])),
+            Arc::new(StringVector::from(vec![Some("a\nb"), Some("long value")])),
+        ];
+        let batches = RecordBatches::try_from_columns(schema.clone(), columns).unwrap();
+        let expected = "\
++------+--------+
+| n    | s      |
++------+--------+
+| 1    | a\\nb   |
+| NULL | lon... |
++------+--------+";
+        assert_eq!(expected, format_table(&batches, 6));
+
+        let batches = RecordBatches::try_new(schema, vec![]).unwrap();
+        let expected = "\
++---+---+
+| n | s |
++---+---+";
+        assert_eq!(expected, format_table(&batches, 6));
+    }
+}
@@ -16,10 +16,15 @@ use clap::Parser;
 use common_telemetry::logging;
 use datanode::datanode::{Datanode, DatanodeOptions, ObjectStoreConfig};
 use meta_client::MetaClientOpts;
+use servers::http::HttpOptions;
 use servers::Mode;
 use snafu::ResultExt;
+use tokio::signal::unix::{signal, SignalKind};
 
-use crate::error::{Error, MissingConfigSnafu, Result, StartDatanodeSnafu};
+use crate::error::{
+    Error, MissingConfigSnafu, RegisterSignalSnafu, ReloadDatanodeSnafu, Result,
+    ShutdownDatanodeSnafu, StartDatanodeSnafu,
+};
 use crate::toml_loader;
 
 #[derive(Parser)]
@@ -56,6 +61,8 @@ struct StartCommand {
     #[clap(long)]
     mysql_addr: Option<String>,
     #[clap(long)]
+    http_addr: Option<String>,
+    #[clap(long)]
     metasrv_addr: Option<String>,
     #[clap(short, long)]
     config_file: Option<String>,
@@ -69,19 +76,64 @@ impl StartCommand {
     async fn run(self) -> Result<()> {
         logging::info!("Datanode start command: {:#?}", self);
 
+        let config_file = self.config_file.clone();
         let opts: DatanodeOptions = self.try_into()?;
 
         logging::info!("Datanode options: {:#?}", opts);
 
-        Datanode::new(opts)
-            .await
-            .context(StartDatanodeSnafu)?
-            .start()
-            .await
-            .context(StartDatanodeSnafu)
+        let datanode = Datanode::new(opts).await.context(StartDatanodeSnafu)?;
+
+        let mut terminate = signal(SignalKind::terminate()).context(RegisterSignalSnafu)?;
+        let mut hangup = signal(SignalKind::hangup()).context(RegisterSignalSnafu)?;
+        // The instance is started along with the HTTP server, whose readiness probe
+        // fails until the instance is started.
+        let serve = datanode.start();
+        tokio::pin!(serve);
+        loop {
+            tokio::select! {
+                result = &mut serve => return result.context(StartDatanodeSnafu),
+                _ = terminate.recv() => {
+                    logging::info!("Received SIGTERM, shutting down datanode");
+                    break;
+                }
+                _ = hangup.recv() => reload(&datanode, config_file.as_deref()),
+            }
+        }
+
+        datanode.shutdown().await.context(ShutdownDatanodeSnafu)?;
+        // Servers are shutdown, wait for requests in flight to finish.
+        let timeout = datanode.shutdown_timeout();
+        match tokio::time::timeout(timeout, serve).await {
+            Ok(result) => result.context(StartDatanodeSnafu),
+            Err(_) => {
+                logging::warn!(
+                    "Requests in flight are not finished in {:?}, exit anyway",
+                    timeout
+                );
+                Ok(())
+            }
+        }
     }
 }
 
+/// Reloads the options that can be changed without restart from the config file.
+fn reload(datanode: &Datanode, config_file: Option<&str>) {
+    let Some(path) = config_file else {
+        logging::warn!("Received SIGHUP but datanode is started without a config file");
+        return;
+    };
+    logging::info!("Received SIGHUP, reloading config from {}", path);
+
+    if let Err(e) = reload_from_file(datanode, path) {
+        logging::error!(e; "Failed to reload config from {}", path);
+    }
+}
+
+fn reload_from_file(datanode: &Datanode, path: &str) -> Result<()> {
+    let opts: DatanodeOptions = toml_loader::from_file!(path)?;
+    datanode.reload(&opts).context(ReloadDatanodeSnafu)
+}
+
 impl TryFrom<StartCommand> for DatanodeOptions {
     type Error = Error;
     fn try_from(cmd: StartCommand) -> Result<Self> {
@@ -97,6 +149,9 @@ impl TryFrom<StartCommand> for DatanodeOptions {
         if let Some(addr) = cmd.mysql_addr {
             opts.mysql_addr = addr;
         }
+        if let Some(addr) = cmd.http_addr {
+            opts.http_opts.get_or_insert_with(HttpOptions::default).addr = addr;
+        }
 
         if let Some(node_id) = cmd.node_id {
             opts.node_id = Some(node_id);
@@ -135,7 +190,7 @@ impl TryFrom<StartCommand> for DatanodeOptions {
 mod tests {
     use std::assert_matches::assert_matches;
 
-    use datanode::datanode::ObjectStoreConfig;
+    use datanode::datanode::{ObjectStoreConfig, WalSyncMode};
     use servers::Mode;
 
     use super::*;
@@ -154,6 +209,8 @@ mod tests {
         assert_eq!("/tmp/greptimedb/wal".to_string(), options.wal_dir);
         assert_eq!("127.0.0.1:4406".to_string(), options.mysql_addr);
         assert_eq!(4, options.mysql_runtime_size);
+        assert_eq!(30000, options.shutdown_timeout_millis);
+        assert_eq!(32 * 1024 * 1024, options.flush.max_write_buffer_size);
         let MetaClientOpts {
             metasrv_addrs: metasrv_addr,
             timeout_millis,
@@ -170,8 +227,12 @@ mod tests {
             ObjectStoreConfig::File { data_dir } => {
                 assert_eq!("/tmp/greptimedb/data/".to_string(), data_dir)
             }
-            ObjectStoreConfig::S3 { .. } => unreachable!(),
+            _ => unreachable!(),
         };
+        assert_eq!(WalSyncMode::Group, options.wal.sync_mode);
+        assert_eq!(3, options.storage_retry.max_times);
+        assert_eq!(1000, options.storage_retry.min_delay_millis);
+        assert_eq!(60000, options.storage_retry.max_delay_millis);
     }
 
     #[test]
@@ -204,6 +265,19 @@ mod tests {
             ..Default::default()
         })
         .unwrap();
+
+        assert!(DatanodeOptions::try_from(StartCommand::default())
+            .unwrap()
+            .http_opts
+            .is_none());
+        let http_opts = DatanodeOptions::try_from(StartCommand {
+            http_addr: Some("127.0.0.1:4001".to_string()),
+            ..Default::default()
+        })
+        .unwrap()
+        .http_opts
+        .unwrap();
+        assert_eq!("127.0.0.1:4001", http_opts.addr);
     }
 
     #[test]
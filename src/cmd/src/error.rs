@@ -25,12 +25,36 @@ pub enum Error {
         source: datanode::error::Error,
     },
 
+    #[snafu(display("Failed to shutdown datanode, source: {}", source))]
+    ShutdownDatanode {
+        #[snafu(backtrace)]
+        source: datanode::error::Error,
+    },
+
+    #[snafu(display("Failed to reload datanode config, source: {}", source))]
+    ReloadDatanode {
+        #[snafu(backtrace)]
+        source: datanode::error::Error,
+    },
+
+    #[snafu(display("Failed to register signal handler, source: {}", source))]
+    RegisterSignal {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to start frontend, source: {}", source))]
     StartFrontend {
         #[snafu(backtrace)]
         source: frontend::error::Error,
     },
 
+    #[snafu(display("Failed to shutdown frontend, source: {}", source))]
+    ShutdownFrontend {
+        #[snafu(backtrace)]
+        source: frontend::error::Error,
+    },
+
     #[snafu(display("Failed to start meta server, source: {}", source))]
     StartMetaServer {
         #[snafu(backtrace)]
@@ -61,6 +85,35 @@ pub enum Error {
         #[snafu(backtrace)]
         source: servers::auth::Error,
     },
+
+    #[snafu(display("Failed to read file: {}, source: {}", path, source))]
+    ReadFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("No table info found in file: {}", path))]
+    DecodeTableInfo { path: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to convert table meta in file: {}, source: {}", path, source))]
+    ConvertTableMeta {
+        path: String,
+        #[snafu(backtrace)]
+        source: table::metadata::ConvertError,
+    },
+
+    #[snafu(display("Found {} breaking schema changes", num_changes))]
+    IncompatibleSchema {
+        num_changes: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to read line, source: {}", source))]
+    Readline {
+        source: rustyline::error::ReadlineError,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -68,14 +121,23 @@ pub type Result<T> = std::result::Result<T, Error>;
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
         match self {
-            Error::StartDatanode { source } => source.status_code(),
-            Error::StartFrontend { source } => source.status_code(),
+            Error::StartDatanode { source }
+            | Error::ShutdownDatanode { source }
+            | Error::ReloadDatanode { source } => source.status_code(),
+            Error::RegisterSignal { .. } => StatusCode::Internal,
+            Error::StartFrontend { source } | Error::ShutdownFrontend { source } => {
+                source.status_code()
+            }
             Error::StartMetaServer { source } => source.status_code(),
             Error::ReadConfig { .. } | Error::ParseConfig { .. } | Error::MissingConfig { .. } => {
                 StatusCode::InvalidArguments
             }
             Error::IllegalConfig { .. } => StatusCode::InvalidArguments,
             Error::IllegalAuthConfig { .. } => StatusCode::InvalidArguments,
+            Error::ReadFile { .. } | Error::DecodeTableInfo { .. } => StatusCode::InvalidArguments,
+            Error::ConvertTableMeta { source, .. } => source.status_code(),
+            Error::IncompatibleSchema { .. } => StatusCode::InvalidArguments,
+            Error::Readline { .. } => StatusCode::Internal,
         }
     }
 
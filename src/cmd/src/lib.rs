@@ -14,9 +14,11 @@
 
 #![feature(assert_matches)]
 
+pub mod cli;
 pub mod datanode;
 pub mod error;
 pub mod frontend;
 pub mod metasrv;
+pub mod schema_compat;
 pub mod standalone;
 mod toml_loader;
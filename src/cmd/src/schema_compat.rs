@@ -0,0 +1,193 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::Parser;
+use datatypes::schema::compat::CompatReport;
+use snafu::{ensure, OptionExt, ResultExt};
+use table::metadata::{RawTableInfo, TableMeta};
+
+use crate::error::{self, Result};
+
+/// Compares two versions of a table and reports breaking and compatible changes
+/// between them.
+///
+/// Each file could be either a json serialized table info or a table manifest file,
+/// the last table info in the manifest file is used.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// File of the old version.
+    #[clap(long)]
+    old: String,
+    /// File of the new version.
+    #[clap(long)]
+    new: String,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let old = load_table_meta(&self.old)?;
+        let new = load_table_meta(&self.new)?;
+        let report = old.check_compat(&new);
+
+        print_report(&report);
+
+        ensure!(
+            report.is_compatible(),
+            error::IncompatibleSchemaSnafu {
+                num_changes: report.breaking_changes().count(),
+            }
+        );
+        Ok(())
+    }
+}
+
+fn print_report(report: &CompatReport) {
+    if report.changes.is_empty() {
+        println!("No changes");
+        return;
+    }
+
+    for change in report.breaking_changes() {
+        println!("[breaking] {change}");
+    }
+    for change in report.compatible_changes() {
+        println!("[compatible] {change}");
+    }
+}
+
+fn load_table_meta(path: &str) -> Result<TableMeta> {
+    let content = std::fs::read_to_string(path).context(error::ReadFileSnafu { path })?;
+    let raw = parse_table_info(&content).context(error::DecodeTableInfoSnafu { path })?;
+    TableMeta::try_from(raw.meta).context(error::ConvertTableMetaSnafu { path })
+}
+
+/// Parses the table info from a json serialized [RawTableInfo] or the content of a
+/// table manifest file.
+fn parse_table_info(content: &str) -> Option<RawTableInfo> {
+    if let Ok(info) = serde_json::from_str::<RawTableInfo>(content) {
+        return Some(info);
+    }
+
+    // Each line of the manifest file is an action, table info is stored in the
+    // `Change` action.
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut action: serde_json::Value = serde_json::from_str(line).ok()?;
+            let info = action.get_mut("Change")?.get_mut("table_info")?.take();
+            serde_json::from_value::<RawTableInfo>(info).ok()
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use datatypes::data_type::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, SchemaBuilder};
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn new_table_info(columns: Vec<ColumnSchema>) -> RawTableInfo {
+        let schema = SchemaBuilder::try_from(columns).unwrap().build().unwrap();
+        let meta = TableMetaBuilder::default()
+            .schema(Arc::new(schema))
+            .primary_key_indices(vec![])
+            .engine("mito")
+            .next_column_id(1)
+            .build()
+            .unwrap();
+        let info = TableInfoBuilder::default()
+            .name("demo")
+            .meta(meta)
+            .build()
+            .unwrap();
+        RawTableInfo::from(info)
+    }
+
+    fn ts_column() -> ColumnSchema {
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true)
+    }
+
+    #[test]
+    fn test_parse_table_info() {
+        let info = new_table_info(vec![ts_column()]);
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(Some(info.clone()), parse_table_info(&json));
+
+        let manifest = format!(
+            "{{\"prev_version\":0}}\n{{\"Change\":{{\"table_info\":{json}}}}}\n{{\"Protocol\":{{}}}}"
+        );
+        assert_eq!(Some(info), parse_table_info(&manifest));
+
+        assert_eq!(None, parse_table_info("{\"prev_version\":0}"));
+    }
+
+    #[tokio::test]
+    async fn test_schema_compat_command() {
+        let dir = TempDir::new("test_schema_compat_command").unwrap();
+        let write_info = |name: &str, info: &RawTableInfo| {
+            let path = dir.path().join(name);
+            let mut file = std::fs::File::create(&path).unwrap();
+            file.write_all(serde_json::to_string(info).unwrap().as_bytes())
+                .unwrap();
+            path.to_str().unwrap().to_string()
+        };
+
+        let old = write_info(
+            "old.json",
+            &new_table_info(vec![
+                ts_column(),
+                ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ]),
+        );
+        let widened = write_info(
+            "widened.json",
+            &new_table_info(vec![
+                ts_column(),
+                ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+                ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), true),
+            ]),
+        );
+        let narrowed = write_info(
+            "narrowed.json",
+            &new_table_info(vec![
+                ts_column(),
+                ColumnSchema::new("cpu", ConcreteDataType::float32_datatype(), true),
+            ]),
+        );
+
+        let cmd = Command {
+            old: old.clone(),
+            new: widened,
+        };
+        cmd.run().await.unwrap();
+
+        let cmd = Command { old, new: narrowed };
+        let err = cmd.run().await.unwrap_err();
+        assert!(matches!(
+            err,
+            error::Error::IncompatibleSchema { num_changes: 1, .. }
+        ));
+    }
+}
@@ -15,8 +15,12 @@
 use std::sync::Arc;
 
 use clap::Parser;
-use common_telemetry::info;
-use datanode::datanode::{Datanode, DatanodeOptions, ObjectStoreConfig};
+use common_runtime::create_runtime;
+use common_telemetry::{info, warn};
+use datanode::datanode::{
+    Datanode, DatanodeOptions, FlushOptions, ObjectStoreConfig, ObjectStoreLimitOptions,
+    ObjectStoreRetryOptions, WalOptions,
+};
 use datanode::instance::InstanceRef;
 use frontend::frontend::{Frontend, FrontendOptions};
 use frontend::grpc::GrpcOptions;
@@ -24,6 +28,7 @@ use frontend::influxdb::InfluxdbOptions;
 use frontend::instance::Instance as FeInstance;
 use frontend::mysql::MysqlOptions;
 use frontend::opentsdb::OpentsdbOptions;
+use frontend::otlp::OtlpOptions;
 use frontend::postgres::PostgresOptions;
 use frontend::prometheus::PrometheusOptions;
 use frontend::Plugins;
@@ -32,8 +37,12 @@ use servers::http::HttpOptions;
 use servers::tls::{TlsMode, TlsOption};
 use servers::Mode;
 use snafu::ResultExt;
+use tokio::signal::unix::{signal, SignalKind};
 
-use crate::error::{Error, IllegalConfigSnafu, Result, StartDatanodeSnafu, StartFrontendSnafu};
+use crate::error::{
+    Error, IllegalConfigSnafu, RegisterSignalSnafu, Result, ShutdownDatanodeSnafu,
+    ShutdownFrontendSnafu, StartDatanodeSnafu, StartFrontendSnafu,
+};
 use crate::frontend::load_frontend_plugins;
 use crate::toml_loader;
 
@@ -62,7 +71,10 @@ impl SubCommand {
     }
 }
 
+/// Options of standalone mode, containing options of both the frontend and the
+/// datanode running in the same process.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StandaloneOptions {
     pub http_options: Option<HttpOptions>,
     pub grpc_options: Option<GrpcOptions>,
@@ -71,14 +83,57 @@ pub struct StandaloneOptions {
     pub opentsdb_options: Option<OpentsdbOptions>,
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
+    pub otlp_options: Option<OtlpOptions>,
     pub mode: Mode,
     pub wal_dir: String,
+    pub wal: WalOptions,
     pub storage: ObjectStoreConfig,
+    pub storage_retry: ObjectStoreRetryOptions,
+    pub storage_limit: ObjectStoreLimitOptions,
+    pub file_storage: ObjectStoreConfig,
     pub enable_memory_catalog: bool,
+    /// Max number of tables to open concurrently on startup.
+    pub open_table_concurrency: usize,
+    pub flush: FlushOptions,
+    /// Max time to wait for requests in flight to finish during shutdown.
+    pub shutdown_timeout_millis: u64,
+    pub runtime: RuntimeOptions,
+}
+
+/// Worker threads of the global runtimes, which are shared by the frontend and
+/// the datanode.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeOptions {
+    pub read_worker_threads: usize,
+    pub write_worker_threads: usize,
+    pub bg_worker_threads: usize,
+}
+
+impl Default for RuntimeOptions {
+    fn default() -> Self {
+        Self {
+            read_worker_threads: 8,
+            write_worker_threads: 8,
+            bg_worker_threads: 8,
+        }
+    }
+}
+
+impl RuntimeOptions {
+    /// Initializes the global runtimes, must be called before any global runtime is used.
+    fn init_global_runtimes(&self) {
+        common_runtime::init_global_runtimes(
+            Some(create_runtime("read-worker", self.read_worker_threads)),
+            Some(create_runtime("write-worker", self.write_worker_threads)),
+            Some(create_runtime("bg-worker", self.bg_worker_threads)),
+        );
+    }
 }
 
 impl Default for StandaloneOptions {
     fn default() -> Self {
+        let dn_opts = DatanodeOptions::default();
         Self {
             http_options: Some(HttpOptions::default()),
             grpc_options: Some(GrpcOptions::default()),
@@ -87,10 +142,19 @@ impl Default for StandaloneOptions {
             opentsdb_options: Some(OpentsdbOptions::default()),
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
+            otlp_options: Some(OtlpOptions::default()),
             mode: Mode::Standalone,
-            wal_dir: "/tmp/greptimedb/wal".to_string(),
-            storage: ObjectStoreConfig::default(),
-            enable_memory_catalog: false,
+            wal_dir: dn_opts.wal_dir,
+            wal: dn_opts.wal,
+            storage: dn_opts.storage,
+            storage_retry: dn_opts.storage_retry,
+            storage_limit: dn_opts.storage_limit,
+            file_storage: dn_opts.file_storage,
+            enable_memory_catalog: dn_opts.enable_memory_catalog,
+            open_table_concurrency: dn_opts.open_table_concurrency,
+            flush: dn_opts.flush,
+            shutdown_timeout_millis: dn_opts.shutdown_timeout_millis,
+            runtime: RuntimeOptions::default(),
         }
     }
 }
@@ -105,16 +169,26 @@ impl StandaloneOptions {
             opentsdb_options: self.opentsdb_options,
             influxdb_options: self.influxdb_options,
             prometheus_options: self.prometheus_options,
+            otlp_options: self.otlp_options,
             mode: self.mode,
             meta_client_opts: None,
+            datanode_client_tls: None,
         }
     }
 
     fn datanode_options(self) -> DatanodeOptions {
         DatanodeOptions {
             wal_dir: self.wal_dir,
+            wal: self.wal,
             storage: self.storage,
+            storage_retry: self.storage_retry,
+            storage_limit: self.storage_limit,
+            file_storage: self.file_storage,
             enable_memory_catalog: self.enable_memory_catalog,
+            open_table_concurrency: self.open_table_concurrency,
+            mode: Mode::Standalone,
+            shutdown_timeout_millis: self.shutdown_timeout_millis,
+            flush: self.flush,
             ..Default::default()
         }
     }
@@ -161,6 +235,8 @@ impl StartCommand {
                 StandaloneOptions::default()
             };
             opts.enable_memory_catalog = enable_memory_catalog;
+            // Both the frontend and the datanode spawn tasks on the global runtimes.
+            opts.runtime.init_global_runtimes();
             opts.datanode_options()
         };
 
@@ -169,7 +245,7 @@ impl StartCommand {
             fe_opts, dn_opts
         );
 
-        let mut datanode = Datanode::new(dn_opts.clone())
+        let datanode = Datanode::new(dn_opts.clone())
             .await
             .context(StartDatanodeSnafu)?;
         let mut frontend = build_frontend(fe_opts, plugins, datanode.get_instance()).await?;
@@ -181,8 +257,31 @@ impl StartCommand {
             .context(StartDatanodeSnafu)?;
         info!("Datanode instance started");
 
-        frontend.start().await.context(StartFrontendSnafu)?;
-        Ok(())
+        frontend
+            .start_instance()
+            .await
+            .context(StartFrontendSnafu)?;
+
+        let mut terminate = signal(SignalKind::terminate()).context(RegisterSignalSnafu)?;
+        let serve = frontend.start_services();
+        tokio::pin!(serve);
+        tokio::select! {
+            result = &mut serve => return result.context(StartFrontendSnafu),
+            _ = terminate.recv() => info!("Received SIGTERM, shutting down standalone"),
+        }
+
+        // Stops accepting new requests first, then waits for requests in flight to
+        // finish before the datanode persists its data.
+        frontend.shutdown().await.context(ShutdownFrontendSnafu)?;
+        let timeout = datanode.shutdown_timeout();
+        match tokio::time::timeout(timeout, serve).await {
+            Ok(result) => result.context(StartFrontendSnafu)?,
+            Err(_) => warn!(
+                "Requests in flight are not finished in {:?}, shutdown datanode anyway",
+                timeout
+            ),
+        }
+        datanode.shutdown().await.context(ShutdownDatanodeSnafu)
     }
 }
 
@@ -325,6 +424,24 @@ mod tests {
         assert!(fe_opts.influxdb_options.as_ref().unwrap().enable);
     }
 
+    #[test]
+    fn test_read_datanode_options() {
+        let path = format!(
+            "{}/../../config/standalone.example.toml",
+            std::env::current_dir().unwrap().as_path().to_str().unwrap()
+        );
+        let opts: StandaloneOptions = toml_loader::from_file!(&path).unwrap();
+        assert_eq!(RuntimeOptions::default(), opts.runtime);
+
+        let dn_opts = opts.datanode_options();
+        assert_eq!(Mode::Standalone, dn_opts.mode);
+        assert_eq!("/tmp/greptimedb/wal/", dn_opts.wal_dir);
+        assert_eq!(16, dn_opts.open_table_concurrency);
+        assert_eq!(30000, dn_opts.shutdown_timeout_millis);
+        assert_eq!(32 * 1024 * 1024, dn_opts.flush.max_write_buffer_size);
+        assert!(matches!(dn_opts.storage, ObjectStoreConfig::File { .. }));
+    }
+
     #[tokio::test]
     async fn test_try_from_start_command_to_anymap() {
         let command = StartCommand {
@@ -25,3 +25,13 @@ pub const MIN_USER_TABLE_ID: u32 = 1024;
 pub const SYSTEM_CATALOG_TABLE_ID: u32 = 0;
 /// scripts table id
 pub const SCRIPTS_TABLE_ID: u32 = 1;
+/// information_schema.tables table id
+pub const INFORMATION_SCHEMA_TABLES_TABLE_ID: u32 = 2;
+/// information_schema.columns table id
+pub const INFORMATION_SCHEMA_COLUMNS_TABLE_ID: u32 = 3;
+/// information_schema.engines table id
+pub const INFORMATION_SCHEMA_ENGINES_TABLE_ID: u32 = 4;
+/// information_schema.region_metrics table id
+pub const INFORMATION_SCHEMA_REGION_METRICS_TABLE_ID: u32 = 5;
+/// tasks table id
+pub const TASKS_TABLE_ID: u32 = 6;
@@ -39,6 +39,28 @@ pub enum Error {
 
     #[snafu(display("Failed to parse node id: {}", key))]
     ParseNodeId { key: String, backtrace: Backtrace },
+
+    #[snafu(display(
+        "Unsupported catalog entry value version: {}, current version: {}",
+        version,
+        current
+    ))]
+    UnsupportedValueVersion {
+        version: u32,
+        current: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to migrate catalog entry value from version {}, reason: {}",
+        version,
+        reason
+    ))]
+    MigrateCatalogEntryValue {
+        version: u32,
+        reason: String,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for Error {
@@ -46,7 +68,9 @@ impl ErrorExt for Error {
         match self {
             Error::InvalidCatalog { .. }
             | Error::DeserializeCatalogEntryValue { .. }
-            | Error::SerializeCatalogEntryValue { .. } => StatusCode::Unexpected,
+            | Error::SerializeCatalogEntryValue { .. }
+            | Error::UnsupportedValueVersion { .. }
+            | Error::MigrateCatalogEntryValue { .. } => StatusCode::Unexpected,
             Error::ParseNodeId { .. } => StatusCode::InvalidArguments,
         }
     }
@@ -43,6 +43,8 @@ pub enum StatusCode {
     PlanQuery = 3000,
     /// The query engine fail to execute query.
     EngineExecuteQuery = 3001,
+    /// The query is cancelled.
+    Cancelled = 3002,
     // ====== End of query related status code =========
 
     // ====== Begin of catalog related status code =====
@@ -75,6 +77,12 @@ pub enum StatusCode {
     AuthHeaderNotFound = 7003,
     /// Invalid http authorization header
     InvalidAuthHeader = 7004,
+    /// Bearer token is not valid
+    InvalidToken = 7005,
+    /// User to create already exists
+    UserAlreadyExists = 7006,
+    /// User has no privilege to access the schema
+    AccessDenied = 7007,
     // ====== End of auth related status code =====
 }
 
@@ -16,11 +16,13 @@ pub mod aggregate;
 pub mod expression;
 pub mod function;
 pub mod function_registry;
+pub mod json;
 pub mod math;
 pub mod numpy;
+pub mod system;
 #[cfg(test)]
 pub(crate) mod test;
-mod timestamp;
+pub mod timestamp;
 pub mod udf;
 
 pub use function::{Function, FunctionRef};
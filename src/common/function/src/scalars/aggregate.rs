@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod approx_count_distinct;
+mod approx_percentile;
 mod argmax;
 mod argmin;
+mod counter;
 mod diff;
 mod mean;
 mod percentile;
@@ -23,9 +26,12 @@ mod scipy_stats_norm_pdf;
 
 use std::sync::Arc;
 
+pub use approx_count_distinct::ApproxCountDistinctAccumulatorCreator;
+pub use approx_percentile::ApproxPercentileAccumulatorCreator;
 pub use argmax::ArgmaxAccumulatorCreator;
 pub use argmin::ArgminAccumulatorCreator;
-use common_query::logical_plan::AggregateFunctionCreatorRef;
+use common_query::logical_plan::{AggregateFunctionCreatorRef, FunctionLimits};
+pub use counter::{DeltaAccumulatorCreator, IncreaseAccumulatorCreator, RateAccumulatorCreator};
 pub use diff::DiffAccumulatorCreator;
 pub use mean::MeanAccumulatorCreator;
 pub use percentile::PercentileAccumulatorCreator;
@@ -46,6 +52,9 @@ pub struct AggregateFunctionMeta {
     name: String,
     args_count: u8,
     creator: AggregatorCreatorFunction,
+    /// Limits of executing the accumulators, builtin functions are executed without
+    /// limits.
+    limits: Option<FunctionLimits>,
 }
 
 pub type AggregateFunctionMetaRef = Arc<AggregateFunctionMeta>;
@@ -56,9 +65,15 @@ impl AggregateFunctionMeta {
             name: name.to_string(),
             args_count,
             creator,
+            limits: None,
         }
     }
 
+    pub fn with_limits(mut self, limits: FunctionLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
     pub fn name(&self) -> String {
         self.name.to_string()
     }
@@ -70,6 +85,10 @@ impl AggregateFunctionMeta {
     pub fn create(&self) -> AggregateFunctionCreatorRef {
         (self.creator)()
     }
+
+    pub fn limits(&self) -> Option<FunctionLimits> {
+        self.limits
+    }
 }
 
 pub(crate) struct AggregateFunctions;
@@ -94,5 +113,14 @@ impl AggregateFunctions {
         register_aggr_func!("percentile", 2, PercentileAccumulatorCreator);
         register_aggr_func!("scipystatsnormcdf", 2, ScipyStatsNormCdfAccumulatorCreator);
         register_aggr_func!("scipystatsnormpdf", 2, ScipyStatsNormPdfAccumulatorCreator);
+        register_aggr_func!("rate", 2, RateAccumulatorCreator);
+        register_aggr_func!("increase", 2, IncreaseAccumulatorCreator);
+        register_aggr_func!("delta", 2, DeltaAccumulatorCreator);
+        register_aggr_func!(
+            "approx_count_distinct",
+            1,
+            ApproxCountDistinctAccumulatorCreator
+        );
+        register_aggr_func!("approx_percentile", 2, ApproxPercentileAccumulatorCreator);
     }
 }
@@ -0,0 +1,318 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::value::ValueRef;
+use datatypes::vectors::BinaryVector;
+use snafu::{ensure, OptionExt};
+
+/// Number of bits of the hash used to select a register.
+const PRECISION: u32 = 12;
+/// Number of registers, the standard error of the estimate is `1.04 / sqrt(NUM_REGISTERS)`,
+/// about 1.6%.
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// https://en.wikipedia.org/wiki/HyperLogLog
+// Estimates the number of distinct values by the max number of leading zeros of hashes
+// of values. Registers are merged by taking the max of each register, so the state of
+// partial aggregations in different partitions or regions could be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    fn add_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // Sets the lowest bit so the rank is at most `64 - PRECISION + 1`.
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn merge(&mut self, registers: &[u8]) -> Result<()> {
+        ensure!(
+            registers.len() == NUM_REGISTERS,
+            BadAccumulatorImplSnafu {
+                err_msg: format!(
+                    "expect {} registers in `merge_batch`, got {}",
+                    NUM_REGISTERS,
+                    registers.len()
+                ),
+            }
+        );
+        for (register, other) in self.registers.iter_mut().zip(registers) {
+            *register = (*register).max(*other);
+        }
+        Ok(())
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|r| 2f64.powi(-i32::from(*r)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Uses linear counting for small cardinalities.
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+/// Returns the hash of `value`, or `None` if the value is null or can't be hashed.
+///
+/// The hash must be the same in all nodes of a cluster, so hashers of the std library
+/// which might be randomized or changed across versions are not used.
+fn hash_value(value: ValueRef) -> Option<u64> {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+    match value {
+        ValueRef::Null | ValueRef::List(_) => return None,
+        ValueRef::Boolean(v) => write(&[v as u8]),
+        ValueRef::UInt8(v) => write(&v.to_le_bytes()),
+        ValueRef::UInt16(v) => write(&v.to_le_bytes()),
+        ValueRef::UInt32(v) => write(&v.to_le_bytes()),
+        ValueRef::UInt64(v) => write(&v.to_le_bytes()),
+        ValueRef::Int8(v) => write(&v.to_le_bytes()),
+        ValueRef::Int16(v) => write(&v.to_le_bytes()),
+        ValueRef::Int32(v) => write(&v.to_le_bytes()),
+        ValueRef::Int64(v) => write(&v.to_le_bytes()),
+        ValueRef::Float32(v) => write(&v.0.to_bits().to_le_bytes()),
+        ValueRef::Float64(v) => write(&v.0.to_bits().to_le_bytes()),
+        ValueRef::String(v) => write(v.as_bytes()),
+        ValueRef::Binary(v) => write(v),
+        ValueRef::Date(v) => write(&v.val().to_le_bytes()),
+        ValueRef::DateTime(v) => write(&v.val().to_le_bytes()),
+        ValueRef::Timestamp(v) => write(&v.value().to_le_bytes()),
+    }
+
+    // Finalizer of splitmix64, spreads bits of the FNV hash as HyperLogLog relies on
+    // the leading bits of hashes.
+    let mut x = hash;
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    Some(x ^ (x >> 31))
+}
+
+#[derive(Debug, Default)]
+pub struct ApproxCountDistinct {
+    hll: HyperLogLog,
+}
+
+impl Accumulator for ApproxCountDistinct {
+    fn state(&self) -> Result<Vec<Value>> {
+        Ok(vec![Value::from(self.hll.registers.clone())])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(values.len() == 1, InvalidInputStateSnafu);
+        let column = &values[0];
+        if column.is_const() {
+            // All values are the same, adding it once is enough.
+            if let Some(hash) = hash_value(column.get_ref(0)) {
+                self.hll.add_hash(hash);
+            }
+            return Ok(());
+        }
+        for i in 0..column.len() {
+            if let Some(hash) = hash_value(column.get_ref(i)) {
+                self.hll.add_hash(hash);
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            states.len() == 1,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 1 states in `merge_batch`",
+            }
+        );
+
+        let registers = &states[0];
+        let registers = registers
+            .as_any()
+            .downcast_ref::<BinaryVector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect BinaryVector, got vector type {}",
+                    registers.vector_type_name()
+                ),
+            })?;
+        for registers in registers.iter_data().flatten() {
+            self.hll.merge(registers)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        Ok(self.hll.estimate().into())
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct ApproxCountDistinctAccumulatorCreator {}
+
+impl AggregateFunctionCreator for ApproxCountDistinctAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            let input_type = &types[0];
+            if matches!(input_type, ConcreteDataType::List(_)) {
+                let err_msg = format!(
+                    "\"APPROX_COUNT_DISTINCT\" aggregate function not support data type {:?}",
+                    input_type.logical_type_id(),
+                );
+                return CreateAccumulatorSnafu { err_msg }.fail();
+            }
+            Ok(Box::new(ApproxCountDistinct::default()))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::uint64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![ConcreteDataType::binary_datatype()])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::{ConstantVector, Int64Vector, StringVector};
+
+    use super::*;
+
+    fn assert_estimate(expect: u64, actual: Value) {
+        let Value::UInt64(actual) = actual else {
+            panic!("unexpected value {actual:?}");
+        };
+        let error = (actual as f64 - expect as f64).abs() / expect as f64;
+        assert!(error < 0.05, "expect: {expect}, actual: {actual}");
+    }
+
+    #[test]
+    fn test_update_batch() {
+        // test update empty batch, expect not updating anything
+        let mut acc = ApproxCountDistinct::default();
+        assert!(acc.update_batch(&[]).is_ok());
+        assert_eq!(Value::from(0u64), acc.evaluate().unwrap());
+
+        // test update null values
+        let v: Vec<VectorRef> = vec![Arc::new(Int64Vector::from(vec![Option::<i64>::None, None]))];
+        assert!(acc.update_batch(&v).is_ok());
+        assert_eq!(Value::from(0u64), acc.evaluate().unwrap());
+
+        // test update duplicate values
+        let v: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec![
+            Some("a"),
+            None,
+            Some("b"),
+            Some("a"),
+        ]))];
+        assert!(acc.update_batch(&v).is_ok());
+        assert_eq!(Value::from(2u64), acc.evaluate().unwrap());
+
+        // test update with constant vector
+        let mut acc = ApproxCountDistinct::default();
+        let v: Vec<VectorRef> = vec![Arc::new(ConstantVector::new(
+            Arc::new(Int64Vector::from_vec(vec![4])),
+            10,
+        ))];
+        assert!(acc.update_batch(&v).is_ok());
+        assert_eq!(Value::from(1u64), acc.evaluate().unwrap());
+
+        // test update many distinct values
+        let mut acc = ApproxCountDistinct::default();
+        for _ in 0..2 {
+            let v: Vec<VectorRef> = vec![Arc::new(Int64Vector::from_vec(
+                (0..100_000).collect::<Vec<i64>>(),
+            ))];
+            assert!(acc.update_batch(&v).is_ok());
+        }
+        assert_estimate(100_000, acc.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let mut partials = Vec::new();
+        for i in 0..4 {
+            let mut acc = ApproxCountDistinct::default();
+            let v: Vec<VectorRef> = vec![Arc::new(Int64Vector::from_vec(
+                (i * 10_000..(i + 2) * 10_000).collect::<Vec<i64>>(),
+            ))];
+            acc.update_batch(&v).unwrap();
+            let Value::Binary(registers) = acc.state().unwrap().remove(0) else {
+                unreachable!()
+            };
+            partials.push(Some(registers.to_vec()));
+        }
+        partials.push(None);
+
+        let mut acc = ApproxCountDistinct::default();
+        let states: Vec<VectorRef> = vec![Arc::new(BinaryVector::from(partials))];
+        acc.merge_batch(&states).unwrap();
+        assert_estimate(50_000, acc.evaluate().unwrap());
+
+        // Registers in a bad state.
+        let states: Vec<VectorRef> = vec![Arc::new(BinaryVector::from(vec![Some(vec![1u8])]))];
+        assert!(acc.merge_batch(&states).is_err());
+    }
+}
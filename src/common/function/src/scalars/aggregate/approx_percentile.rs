@@ -0,0 +1,460 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    self, BadAccumulatorImplSnafu, CreateAccumulatorSnafu, DowncastVectorSnafu,
+    InvalidFuncArgsSnafu, InvalidInputColSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::prelude::*;
+use datatypes::types::WrapperType;
+use datatypes::value::OrderedFloat;
+use datatypes::vectors::{BinaryVector, ConstantVector, Float64Vector, Helper};
+use datatypes::with_match_primitive_type_id;
+use num_traits::AsPrimitive;
+use snafu::{ensure, OptionExt, ResultExt};
+
+/// Compression of the digest, the digest keeps at most about `2 * COMPRESSION` centroids.
+const COMPRESSION: f64 = 100.0;
+/// Max number of unmerged centroids before compressing them into the digest.
+const BUFFER_SIZE: usize = 10 * COMPRESSION as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+// https://github.com/tdunning/t-digest
+// A t-digest summarizes values into centroids, centroids near the tails hold fewer values
+// so quantiles near the tails are more accurate. Digests are merged by merging their
+// centroids, so the state of partial aggregations in different partitions or regions
+// could be merged.
+//
+// Quantiles are interpolated between centroids in the same way as the linear method of
+// `PERCENTILE`, so the result is exact if the digest is built from a few values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TDigest {
+    /// Compressed centroids sorted by mean.
+    centroids: Vec<Centroid>,
+    /// Centroids not compressed yet.
+    unmerged: Vec<Centroid>,
+    min: f64,
+    max: f64,
+}
+
+impl TDigest {
+    fn push(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.update_min_max(value, value);
+        self.add(Centroid {
+            mean: value,
+            weight: 1.0,
+        });
+    }
+
+    fn update_min_max(&mut self, min: f64, max: f64) {
+        if self.is_empty() {
+            self.min = min;
+            self.max = max;
+        } else {
+            self.min = self.min.min(min);
+            self.max = self.max.max(max);
+        }
+    }
+
+    fn add(&mut self, centroid: Centroid) {
+        self.unmerged.push(centroid);
+        if self.unmerged.len() >= BUFFER_SIZE {
+            self.centroids = self.compressed();
+            self.unmerged.clear();
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.centroids.is_empty() && self.unmerged.is_empty()
+    }
+
+    /// Merges all centroids in the digest into at most about `2 * COMPRESSION` centroids.
+    fn compressed(&self) -> Vec<Centroid> {
+        let mut all: Vec<_> = self
+            .centroids
+            .iter()
+            .chain(self.unmerged.iter())
+            .copied()
+            .collect();
+        if all.is_empty() {
+            return all;
+        }
+        all.sort_unstable_by(|a, b| a.mean.total_cmp(&b.mean));
+        let total: f64 = all.iter().map(|c| c.weight).sum();
+
+        let mut result = Vec::with_capacity(all.len().min(2 * COMPRESSION as usize));
+        let mut current = all[0];
+        // Total weight of centroids before the current one.
+        let mut weight_so_far = 0.0;
+        for centroid in &all[1..] {
+            let proposed = current.weight + centroid.weight;
+            let q0 = weight_so_far / total;
+            let q2 = (weight_so_far + proposed) / total;
+            let limit = total * 4.0 * (q0 * (1.0 - q0)).min(q2 * (1.0 - q2)) / COMPRESSION;
+            if proposed <= limit {
+                current.mean += (centroid.mean - current.mean) * centroid.weight / proposed;
+                current.weight = proposed;
+            } else {
+                weight_so_far += current.weight;
+                result.push(current);
+                current = *centroid;
+            }
+        }
+        result.push(current);
+        result
+    }
+
+    /// Returns the `q`-th quantile (`0 <= q <= 1`) of values in the digest.
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let centroids = self.compressed();
+        if centroids.is_empty() {
+            return None;
+        }
+        let total: f64 = centroids.iter().map(|c| c.weight).sum();
+        // A centroid with weight `w` holds values ranked `[start, start + w - 1]`, its mean
+        // is the value at the center of the ranks.
+        let rank = q * (total - 1.0);
+
+        let mut prev = (0.0, self.min);
+        let mut start = 0.0;
+        for centroid in &centroids {
+            let center = start + (centroid.weight - 1.0) / 2.0;
+            if rank <= center {
+                return Some(interpolate(prev, (center, centroid.mean), rank));
+            }
+            prev = (center, centroid.mean);
+            start += centroid.weight;
+        }
+        Some(interpolate(prev, (total - 1.0, self.max), rank))
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let centroids = self.compressed();
+        if centroids.is_empty() {
+            return Vec::new();
+        }
+        let mut buf = Vec::with_capacity(16 * (centroids.len() + 1));
+        buf.extend_from_slice(&self.min.to_le_bytes());
+        buf.extend_from_slice(&self.max.to_le_bytes());
+        for centroid in centroids {
+            buf.extend_from_slice(&centroid.mean.to_le_bytes());
+            buf.extend_from_slice(&centroid.weight.to_le_bytes());
+        }
+        buf
+    }
+
+    fn merge(&mut self, data: &[u8]) -> Result<()> {
+        ensure!(
+            data.len() % 16 == 0,
+            BadAccumulatorImplSnafu {
+                err_msg: format!("invalid t-digest state of {} bytes", data.len()),
+            }
+        );
+        let mut values = data
+            .chunks_exact(8)
+            .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap()));
+        let (Some(min), Some(max)) = (values.next(), values.next()) else {
+            // The digest is empty.
+            return Ok(());
+        };
+        self.update_min_max(min, max);
+        while let (Some(mean), Some(weight)) = (values.next(), values.next()) {
+            self.add(Centroid { mean, weight });
+        }
+        Ok(())
+    }
+}
+
+/// Linear interpolates the value at `rank` between two points of `(rank, value)`.
+fn interpolate(left: (f64, f64), right: (f64, f64), rank: f64) -> f64 {
+    if right.0 <= left.0 {
+        return right.1;
+    }
+    let fract = ((rank - left.0) / (right.0 - left.0)).clamp(0.0, 1.0);
+    left.1 + (right.1 - left.1) * fract
+}
+
+#[derive(Debug, Default)]
+pub struct ApproxPercentile<T> {
+    digest: TDigest,
+    p: Option<f64>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Accumulator for ApproxPercentile<T>
+where
+    T: WrapperType,
+    T::Native: AsPrimitive<f64>,
+{
+    fn state(&self) -> Result<Vec<Value>> {
+        Ok(vec![Value::from(self.digest.encode()), self.p.into()])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+        ensure!(values[0].len() == values[1].len(), InvalidInputStateSnafu);
+
+        if values[0].len() == 0 {
+            return Ok(());
+        }
+
+        let column = &values[0];
+        let mut len = 1;
+        let column: &<T as Scalar>::VectorType = if column.is_const() {
+            len = column.len();
+            let column: &ConstantVector = unsafe { Helper::static_cast(column) };
+            unsafe { Helper::static_cast(column.inner()) }
+        } else {
+            unsafe { Helper::static_cast(column) }
+        };
+
+        let x = &values[1];
+        let x = Helper::check_get_scalar::<f64>(x).context(error::InvalidInputTypeSnafu {
+            err_msg: "expecting \"APPROX_PERCENTILE\" function's second argument to be float64",
+        })?;
+        // `get(0)` is safe because we have checked `values[1].len() == values[0].len() != 0`
+        let first = x.get(0);
+        ensure!(!first.is_null(), InvalidInputColSnafu);
+
+        for i in 1..x.len() {
+            ensure!(first == x.get(i), InvalidInputColSnafu);
+        }
+
+        let first = match first {
+            Value::Float64(OrderedFloat(v)) => v,
+            // unreachable because we have checked `first` is not null and is f64 above
+            _ => unreachable!(),
+        };
+        ensure!(
+            (0.0..=100.0).contains(&first),
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "the percentile of \"APPROX_PERCENTILE\" must be in [0, 100], got {first}"
+                ),
+            }
+        );
+        if let Some(p) = self.p {
+            ensure!(p == first, InvalidInputColSnafu);
+        } else {
+            self.p = Some(first);
+        };
+
+        (0..len).for_each(|_| {
+            for v in column.iter_data().flatten() {
+                self.digest.push(v.into_native().as_());
+            }
+        });
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            states.len() == 2,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 2 states in `merge_batch`"
+            }
+        );
+
+        let digests = &states[0];
+        let digests = digests
+            .as_any()
+            .downcast_ref::<BinaryVector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect BinaryVector, got vector type {}",
+                    digests.vector_type_name()
+                ),
+            })?;
+        let p = &states[1];
+        let p = p
+            .as_any()
+            .downcast_ref::<Float64Vector>()
+            .with_context(|| DowncastVectorSnafu {
+                err_msg: format!(
+                    "expect Float64Vector, got vector type {}",
+                    p.vector_type_name()
+                ),
+            })?;
+
+        for (digest, p) in digests.iter_data().zip(p.iter_data()) {
+            // The state of an accumulator without any input.
+            let (Some(digest), Some(p)) = (digest, p) else {
+                continue;
+            };
+            self.p = Some(p);
+            self.digest.merge(digest)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        let Some(p) = self.p else {
+            return Ok(Value::Null);
+        };
+        Ok(self.digest.quantile(p / 100.0).into())
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct ApproxPercentileAccumulatorCreator {}
+
+impl AggregateFunctionCreator for ApproxPercentileAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            let input_type = &types[0];
+            with_match_primitive_type_id!(
+                input_type.logical_type_id(),
+                |$S| {
+                    Ok(Box::new(ApproxPercentile::<<$S as LogicalPrimitiveType>::Wrapper>::default()))
+                },
+                {
+                    let err_msg = format!(
+                        "\"APPROX_PERCENTILE\" aggregate function not support data type {:?}",
+                        input_type.logical_type_id(),
+                    );
+                    CreateAccumulatorSnafu { err_msg }.fail()?
+                }
+            )
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+        Ok(vec![
+            ConcreteDataType::binary_datatype(),
+            ConcreteDataType::float64_datatype(),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datatypes::vectors::Int32Vector;
+
+    use super::*;
+
+    fn new_input(values: Vec<Option<i32>>, p: f64) -> Vec<VectorRef> {
+        let len = values.len();
+        vec![
+            Arc::new(Int32Vector::from(values)),
+            Arc::new(Float64Vector::from_vec(vec![p; len])),
+        ]
+    }
+
+    fn evaluate_f64(acc: &ApproxPercentile<i32>) -> f64 {
+        match acc.evaluate().unwrap() {
+            Value::Float64(OrderedFloat(v)) => v,
+            v => panic!("unexpected value {v:?}"),
+        }
+    }
+
+    #[test]
+    fn test_update_batch() {
+        // test update empty batch, expect not updating anything
+        let mut acc = ApproxPercentile::<i32>::default();
+        assert!(acc.update_batch(&[]).is_ok());
+        assert_eq!(Value::Null, acc.evaluate().unwrap());
+
+        // test update null values
+        let mut acc = ApproxPercentile::<i32>::default();
+        assert!(acc.update_batch(&new_input(vec![None], 50.0)).is_ok());
+        assert_eq!(Value::Null, acc.evaluate().unwrap());
+
+        // the result is exact for a few values
+        for (p, expect) in [(0.0, -1.0), (50.0, 1.5), (88.0, 3.28), (100.0, 4.0)] {
+            let mut acc = ApproxPercentile::<i32>::default();
+            let input = new_input(vec![Some(-1), Some(2), None, Some(1), Some(4)], p);
+            assert!(acc.update_batch(&input).is_ok());
+            let value = evaluate_f64(&acc);
+            assert!((value - expect).abs() < 1e-9, "p: {p}, value: {value}");
+        }
+
+        // the percentile is out of range
+        let mut acc = ApproxPercentile::<i32>::default();
+        assert!(acc.update_batch(&new_input(vec![Some(1)], 101.0)).is_err());
+
+        // test update many values
+        let mut acc = ApproxPercentile::<i32>::default();
+        for values in (0..100_000).collect::<Vec<_>>().chunks(1000) {
+            let values = values.iter().map(|v| Some(*v)).collect();
+            assert!(acc.update_batch(&new_input(values, 99.0)).is_ok());
+        }
+        let value = evaluate_f64(&acc);
+        assert!((value - 98_999.01).abs() < 100.0, "value: {value}");
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let mut digests = Vec::new();
+        let mut ps = Vec::new();
+        for i in 0..4 {
+            let mut acc = ApproxPercentile::<i32>::default();
+            let values = (i * 25_000..(i + 1) * 25_000).map(Some).collect();
+            acc.update_batch(&new_input(values, 50.0)).unwrap();
+            let mut state = acc.state().unwrap();
+            let Value::Binary(digest) = state.remove(0) else {
+                unreachable!()
+            };
+            digests.push(Some(digest.to_vec()));
+            ps.push(Some(50.0));
+        }
+        // The state of an accumulator without any input.
+        let acc = ApproxPercentile::<i32>::default();
+        let mut state = acc.state().unwrap();
+        let Value::Binary(digest) = state.remove(0) else {
+            unreachable!()
+        };
+        digests.push(Some(digest.to_vec()));
+        ps.push(None);
+
+        let mut acc = ApproxPercentile::<i32>::default();
+        let states: Vec<VectorRef> = vec![
+            Arc::new(BinaryVector::from(digests)),
+            Arc::new(Float64Vector::from(ps)),
+        ];
+        acc.merge_batch(&states).unwrap();
+        let value = evaluate_f64(&acc);
+        assert!((value - 49_999.5).abs() < 500.0, "value: {value}");
+    }
+}
@@ -0,0 +1,437 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Time series aware aggregate functions `rate`, `increase` and `delta`, which
+//! work like the functions of the same name in Prometheus.
+
+use std::sync::Arc;
+
+use common_function_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    CreateAccumulatorSnafu, DowncastVectorSnafu, FromScalarValueSnafu, InvalidInputStateSnafu,
+    Result, TypeCastSnafu,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+use datatypes::arrow::compute::kernels::cast;
+use datatypes::arrow::datatypes::{DataType, TimeUnit};
+use datatypes::prelude::*;
+use datatypes::value::ListValue;
+use datatypes::vectors::ListVector;
+use snafu::{ensure, OptionExt, ResultExt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CounterKind {
+    /// Per-second average rate of increase of a counter.
+    Rate,
+    /// Increase of a counter.
+    Increase,
+    /// Difference between the last and the first value of a gauge.
+    Delta,
+}
+
+impl CounterKind {
+    fn name(&self) -> &'static str {
+        match self {
+            CounterKind::Rate => "RATE",
+            CounterKind::Increase => "INCREASE",
+            CounterKind::Delta => "DELTA",
+        }
+    }
+
+    fn is_counter(&self) -> bool {
+        matches!(self, CounterKind::Rate | CounterKind::Increase)
+    }
+}
+
+/// Accumulates samples of `(timestamp in milliseconds, value)` in the group and
+/// computes the result when evaluating.
+///
+/// A decrease of the counter is treated as a counter reset. The result is extrapolated
+/// by half of the average sample interval on both sides of the sampled interval, as
+/// the group, e.g. a time window, usually covers more than the samples do. A counter
+/// is never extrapolated below zero.
+#[derive(Debug)]
+struct CounterAccumulator {
+    kind: CounterKind,
+    samples: Vec<(i64, f64)>,
+}
+
+impl CounterAccumulator {
+    fn new(kind: CounterKind) -> Self {
+        Self {
+            kind,
+            samples: Vec::new(),
+        }
+    }
+
+    fn compute(&self) -> Option<f64> {
+        if self.samples.len() < 2 {
+            return None;
+        }
+        let mut samples = self.samples.clone();
+        samples.sort_unstable_by_key(|(ts, _)| *ts);
+
+        let (first_ts, first_value) = samples[0];
+        let (last_ts, last_value) = samples[samples.len() - 1];
+        let sampled_millis = (last_ts - first_ts) as f64;
+        if sampled_millis <= 0.0 {
+            return None;
+        }
+
+        let mut result = last_value - first_value;
+        if self.kind.is_counter() {
+            for pair in samples.windows(2) {
+                if pair[1].1 < pair[0].1 {
+                    // The counter is reset, the value before reset is also increased.
+                    result += pair[0].1;
+                }
+            }
+        }
+
+        let avg_interval = sampled_millis / (samples.len() - 1) as f64;
+        let mut to_start = avg_interval / 2.0;
+        let to_end = avg_interval / 2.0;
+        if self.kind.is_counter() && result > 0.0 && first_value >= 0.0 {
+            // Don't extrapolate to the time the counter would be negative.
+            let to_zero = sampled_millis * (first_value / result);
+            to_start = to_start.min(to_zero);
+        }
+        let extrapolated_millis = sampled_millis + to_start + to_end;
+        result *= extrapolated_millis / sampled_millis;
+
+        if self.kind == CounterKind::Rate {
+            result /= extrapolated_millis / 1000.0;
+        }
+        Some(result)
+    }
+}
+
+impl Accumulator for CounterAccumulator {
+    fn state(&self) -> Result<Vec<Value>> {
+        let (timestamps, values): (Vec<_>, Vec<_>) = self
+            .samples
+            .iter()
+            .map(|(ts, v)| (Value::from(*ts), Value::from(*v)))
+            .unzip();
+        Ok(vec![
+            Value::List(ListValue::new(
+                Some(Box::new(values)),
+                ConcreteDataType::float64_datatype(),
+            )),
+            Value::List(ListValue::new(
+                Some(Box::new(timestamps)),
+                ConcreteDataType::int64_datatype(),
+            )),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(values.len() == 2, InvalidInputStateSnafu);
+        ensure!(values[0].len() == values[1].len(), InvalidInputStateSnafu);
+
+        let counters = cast_array(&values[0].to_arrow_array(), &DataType::Float64)?;
+        let counters = counters.as_any().downcast_ref::<Float64Array>().unwrap();
+        let timestamps = timestamps_to_millis(values[1].to_arrow_array())?;
+        let timestamps = timestamps.as_any().downcast_ref::<Int64Array>().unwrap();
+
+        for (ts, value) in timestamps.iter().zip(counters.iter()) {
+            if let (Some(ts), Some(value)) = (ts, value) {
+                self.samples.push((ts, value));
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(states.len() == 2, InvalidInputStateSnafu);
+        let values = downcast_list(&states[0])?;
+        let timestamps = downcast_list(&states[1])?;
+        for (values, timestamps) in values.values_iter().zip(timestamps.values_iter()) {
+            let values = values.context(FromScalarValueSnafu)?;
+            let timestamps = timestamps.context(FromScalarValueSnafu)?;
+            if let (Some(values), Some(timestamps)) = (values, timestamps) {
+                self.update_batch(&[values, timestamps])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        Ok(self.compute().map(Value::from).unwrap_or(Value::Null))
+    }
+}
+
+fn cast_array(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
+    cast::cast(array, to_type).context(TypeCastSnafu {
+        typ: to_type.clone(),
+    })
+}
+
+/// Converts timestamps to an array of milliseconds, integers are treated as
+/// milliseconds.
+fn timestamps_to_millis(array: ArrayRef) -> Result<ArrayRef> {
+    let array = match array.data_type() {
+        DataType::Timestamp(TimeUnit::Millisecond, _) => array,
+        DataType::Timestamp(_, _) => {
+            cast_array(&array, &DataType::Timestamp(TimeUnit::Millisecond, None))?
+        }
+        _ => array,
+    };
+    cast_array(&array, &DataType::Int64)
+}
+
+fn downcast_list(vector: &VectorRef) -> Result<&ListVector> {
+    vector
+        .as_any()
+        .downcast_ref::<ListVector>()
+        .with_context(|| DowncastVectorSnafu {
+            err_msg: format!(
+                "expect ListVector, got vector type {}",
+                vector.vector_type_name()
+            ),
+        })
+}
+
+fn counter_creator(kind: CounterKind) -> AccumulatorCreatorFunction {
+    Arc::new(move |types: &[ConcreteDataType]| {
+        let valid = types.len() == 2
+            && ConcreteDataType::numerics().contains(&types[0])
+            && matches!(
+                types[1],
+                ConcreteDataType::Timestamp(_) | ConcreteDataType::Int64(_)
+            );
+        ensure!(
+            valid,
+            CreateAccumulatorSnafu {
+                err_msg: format!(
+                    "\"{}\" aggregate function not support data types {:?}, expect a numeric value and a timestamp",
+                    kind.name(),
+                    types.iter().map(|t| t.logical_type_id()).collect::<Vec<_>>(),
+                ),
+            }
+        );
+        Ok(Box::new(CounterAccumulator::new(kind)))
+    })
+}
+
+fn counter_state_types() -> Vec<ConcreteDataType> {
+    vec![
+        ConcreteDataType::list_datatype(ConcreteDataType::float64_datatype()),
+        ConcreteDataType::list_datatype(ConcreteDataType::int64_datatype()),
+    ]
+}
+
+// The derive macro of `AggrFuncTypeStore` adds imports to the module, so each creator
+// is defined in its own module.
+macro_rules! impl_counter_creator {
+    ($module: ident, $creator: ident, $kind: expr) => {
+        mod $module {
+            use super::*;
+
+            #[as_aggr_func_creator]
+            #[derive(Debug, Default, AggrFuncTypeStore)]
+            pub struct $creator {}
+
+            impl AggregateFunctionCreator for $creator {
+                fn creator(&self) -> AccumulatorCreatorFunction {
+                    counter_creator($kind)
+                }
+
+                fn output_type(&self) -> Result<ConcreteDataType> {
+                    let input_types = self.input_types()?;
+                    ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+                    Ok(ConcreteDataType::float64_datatype())
+                }
+
+                fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+                    let input_types = self.input_types()?;
+                    ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+                    Ok(counter_state_types())
+                }
+            }
+        }
+
+        pub use $module::$creator;
+    };
+}
+
+impl_counter_creator!(rate, RateAccumulatorCreator, CounterKind::Rate);
+impl_counter_creator!(increase, IncreaseAccumulatorCreator, CounterKind::Increase);
+impl_counter_creator!(delta, DeltaAccumulatorCreator, CounterKind::Delta);
+
+#[cfg(test)]
+mod test {
+    use common_query::logical_plan::accumulator::AggrFuncTypeStore;
+    use datatypes::vectors::{
+        ConstantVector, Float64Vector, Int32Vector, Int64Vector, TimestampSecondVector,
+    };
+
+    use super::*;
+
+    fn evaluate(kind: CounterKind, values: Vec<i32>, timestamps: Vec<i64>) -> Value {
+        let mut accumulator = CounterAccumulator::new(kind);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from_vec(values)),
+            Arc::new(Int64Vector::from_vec(timestamps)),
+        ];
+        accumulator.update_batch(&v).unwrap();
+        accumulator.evaluate().unwrap()
+    }
+
+    #[test]
+    fn test_update_batch() {
+        // test update empty batch
+        let mut accumulator = CounterAccumulator::new(CounterKind::Rate);
+        assert!(accumulator.update_batch(&[]).is_ok());
+        assert_eq!(Value::Null, accumulator.evaluate().unwrap());
+
+        // test update one sample
+        assert_eq!(
+            Value::Null,
+            evaluate(CounterKind::Increase, vec![1], vec![1000])
+        );
+
+        // test update null values
+        let mut accumulator = CounterAccumulator::new(CounterKind::Delta);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from(vec![Some(1), None, Some(3)])),
+            Arc::new(Int64Vector::from(vec![Some(0), Some(1000), None])),
+        ];
+        accumulator.update_batch(&v).unwrap();
+        assert_eq!(Value::Null, accumulator.evaluate().unwrap());
+
+        // test update timestamp in seconds and constant vector
+        let mut accumulator = CounterAccumulator::new(CounterKind::Delta);
+        let v: Vec<VectorRef> = vec![
+            Arc::new(ConstantVector::new(
+                Arc::new(Float64Vector::from_vec(vec![2.0])),
+                2,
+            )),
+            Arc::new(TimestampSecondVector::from_vec(vec![1, 2])),
+        ];
+        accumulator.update_batch(&v).unwrap();
+        assert_eq!(vec![(1000, 2.0), (2000, 2.0)], accumulator.samples);
+    }
+
+    #[test]
+    fn test_delta() {
+        // Extrapolated from 2 in 2 seconds to 3 in 3 seconds.
+        assert_eq!(
+            Value::from(3.0),
+            evaluate(CounterKind::Delta, vec![1, 2, 3], vec![0, 1000, 2000])
+        );
+        // Gauges could decrease.
+        assert_eq!(
+            Value::from(-3.0),
+            evaluate(CounterKind::Delta, vec![3, 2, 1], vec![0, 1000, 2000])
+        );
+        // Samples out of order.
+        assert_eq!(
+            Value::from(3.0),
+            evaluate(CounterKind::Delta, vec![3, 1, 2], vec![2000, 0, 1000])
+        );
+    }
+
+    #[test]
+    fn test_increase_and_rate() {
+        assert_eq!(
+            Value::from(30.0),
+            evaluate(CounterKind::Increase, vec![10, 20, 30], vec![0, 1000, 2000])
+        );
+        assert_eq!(
+            Value::from(10.0),
+            evaluate(CounterKind::Rate, vec![10, 20, 30], vec![0, 1000, 2000])
+        );
+
+        // Counter reset.
+        assert_eq!(
+            Value::from(22.5),
+            evaluate(CounterKind::Increase, vec![10, 20, 5], vec![0, 1000, 2000])
+        );
+        assert_eq!(
+            Value::from(7.5),
+            evaluate(CounterKind::Rate, vec![10, 20, 5], vec![0, 1000, 2000])
+        );
+
+        // Not extrapolated below zero: 20 in 2 seconds, extrapolated 0.5 seconds at the end.
+        assert_eq!(
+            Value::from(25.0),
+            evaluate(CounterKind::Increase, vec![0, 10, 20], vec![0, 1000, 2000])
+        );
+    }
+
+    #[test]
+    fn test_merge_batch() {
+        let mut first = CounterAccumulator::new(CounterKind::Increase);
+        first
+            .update_batch(&[
+                Arc::new(Int32Vector::from_vec(vec![10, 20])),
+                Arc::new(Int64Vector::from_vec(vec![0, 1000])),
+            ])
+            .unwrap();
+        let mut second = CounterAccumulator::new(CounterKind::Increase);
+        second
+            .update_batch(&[
+                Arc::new(Int32Vector::from_vec(vec![30])),
+                Arc::new(Int64Vector::from_vec(vec![2000])),
+            ])
+            .unwrap();
+
+        let states = second.state().unwrap();
+        let states: Vec<VectorRef> = states
+            .into_iter()
+            .map(|state| {
+                let data_type = state.data_type();
+                let mut builder = data_type.create_mutable_vector(1);
+                builder.push_value_ref(state.as_value_ref()).unwrap();
+                builder.to_vector()
+            })
+            .collect();
+        first.merge_batch(&states).unwrap();
+        assert_eq!(Value::from(30.0), first.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_creator() {
+        let creator = RateAccumulatorCreator::default();
+        let types = vec![
+            ConcreteDataType::float64_datatype(),
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        ];
+        assert!((creator.creator())(&types).is_ok());
+        assert!((creator.creator())(&[ConcreteDataType::float64_datatype()]).is_err());
+        assert!((creator.creator())(&[
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        ])
+        .is_err());
+
+        creator.set_input_types(types).unwrap();
+        assert_eq!(
+            ConcreteDataType::float64_datatype(),
+            creator.output_type().unwrap()
+        );
+        assert_eq!(counter_state_types(), creator.state_types().unwrap());
+    }
+}
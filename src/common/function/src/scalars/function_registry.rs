@@ -20,8 +20,10 @@ use once_cell::sync::Lazy;
 
 use crate::scalars::aggregate::{AggregateFunctionMetaRef, AggregateFunctions};
 use crate::scalars::function::FunctionRef;
+use crate::scalars::json::JsonFunction;
 use crate::scalars::math::MathFunction;
 use crate::scalars::numpy::NumpyFunction;
+use crate::scalars::system::SystemFunction;
 use crate::scalars::timestamp::TimestampFunction;
 
 #[derive(Default)]
@@ -73,6 +75,8 @@ pub static FUNCTION_REGISTRY: Lazy<Arc<FunctionRegistry>> = Lazy::new(|| {
     MathFunction::register(&function_registry);
     NumpyFunction::register(&function_registry);
     TimestampFunction::register(&function_registry);
+    JsonFunction::register(&function_registry);
+    SystemFunction::register(&function_registry);
 
     AggregateFunctions::register(&function_registry);
 
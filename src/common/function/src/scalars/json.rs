@@ -0,0 +1,214 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod json_extract;
+mod json_get;
+
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::types::JsonType;
+use datatypes::value::ValueRef;
+use datatypes::vectors::VectorRef;
+pub use json_extract::JsonExtractFunction;
+pub use json_get::JsonGetFunction;
+use snafu::{ensure, OptionExt};
+
+use crate::scalars::function_registry::FunctionRegistry;
+
+pub(crate) struct JsonFunction;
+
+impl JsonFunction {
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register(Arc::new(JsonGetFunction::default()));
+        for function in JsonExtractFunction::all() {
+            registry.register(Arc::new(function));
+        }
+    }
+}
+
+/// Signature of functions accepting a JSON document, either encoded or as text, and a
+/// path.
+fn json_path_signature() -> Signature {
+    Signature::one_of(
+        vec![
+            TypeSignature::Exact(vec![
+                ConcreteDataType::json_datatype(),
+                ConcreteDataType::string_datatype(),
+            ]),
+            TypeSignature::Exact(vec![
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::string_datatype(),
+            ]),
+        ],
+        Volatility::Immutable,
+    )
+}
+
+/// Looks up the `path` in each document of `columns[0]` where the path is in
+/// `columns[1]`, then maps the found values by `f`.
+///
+/// Documents are either encoded binary, e.g. values of JSON columns, or JSON text. The
+/// result is `None` if the document or the path is null, or the path is not found.
+fn eval_json_path<T>(
+    function: &str,
+    columns: &[VectorRef],
+    f: impl Fn(&serde_json::Value) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    ensure!(
+        columns.len() == 2,
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 2, have: {}",
+                columns.len()
+            ),
+        }
+    );
+
+    let len = columns[0].len();
+    let mut paths: Option<(String, JsonPath)> = None;
+    let mut results = Vec::with_capacity(len);
+    for i in 0..len {
+        let ValueRef::String(path) = columns[1].get_ref(i) else {
+            results.push(None);
+            continue;
+        };
+        let doc = match columns[0].get_ref(i) {
+            ValueRef::Binary(bytes) => JsonType::decode(bytes),
+            ValueRef::String(text) => JsonType::decode(text.as_bytes()),
+            _ => {
+                results.push(None);
+                continue;
+            }
+        }
+        .map_err(|e| {
+            InvalidFuncArgsSnafu {
+                err_msg: format!("Invalid JSON document in {function}, {e}"),
+            }
+            .build()
+        })?;
+
+        // Paths are mostly constants, so the parsed one is reused.
+        if !matches!(&paths, Some((s, _)) if s == path) {
+            let parsed = JsonPath::parse(path).with_context(|| InvalidFuncArgsSnafu {
+                err_msg: format!("Invalid JSON path {path} in {function}"),
+            })?;
+            paths = Some((path.to_string(), parsed));
+        }
+        let (_, json_path) = paths.as_ref().unwrap();
+        results.push(json_path.lookup(&doc).and_then(&f));
+    }
+    Ok(results)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Path to a value in a JSON document, e.g. `$.tags[0]` or `$["user name"].id`.
+///
+/// The leading `$` stands for the whole document and could be omitted, so `tags[0]`
+/// is the same as `$.tags[0]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct JsonPath(Vec<PathSegment>);
+
+impl JsonPath {
+    fn parse(path: &str) -> Option<JsonPath> {
+        let path = path.trim();
+        let normalized;
+        let mut rest = match path.strip_prefix('$') {
+            Some(rest) => rest,
+            None if path.starts_with('[') => path,
+            None => {
+                normalized = format!(".{path}");
+                &normalized
+            }
+        };
+
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(r) = rest.strip_prefix('[') {
+                let end = r.find(']')?;
+                let inner = r[..end].trim();
+                let segment = match inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')) {
+                    Some(key) => PathSegment::Key(key.to_string()),
+                    None => PathSegment::Index(inner.parse().ok()?),
+                };
+                segments.push(segment);
+                rest = &r[end + 1..];
+            } else {
+                let r = rest.strip_prefix('.')?;
+                let end = r.find(['.', '[']).unwrap_or(r.len());
+                if end == 0 {
+                    return None;
+                }
+                segments.push(PathSegment::Key(r[..end].to_string()));
+                rest = &r[end..];
+            }
+        }
+        Some(JsonPath(segments))
+    }
+
+    fn lookup<'a>(&self, doc: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+        self.0.iter().try_fold(doc, |value, segment| match segment {
+            PathSegment::Key(key) => value.get(key),
+            PathSegment::Index(index) => value.get(index),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_json_path() {
+        let key = |k: &str| PathSegment::Key(k.to_string());
+        assert_eq!(Some(JsonPath(vec![])), JsonPath::parse("$"));
+        assert_eq!(
+            Some(JsonPath(vec![key("a"), PathSegment::Index(1), key("b c")])),
+            JsonPath::parse(r#"$.a[1]["b c"]"#)
+        );
+        assert_eq!(JsonPath::parse("$.a.b"), JsonPath::parse("a.b"));
+        assert_eq!(
+            Some(JsonPath(vec![PathSegment::Index(0), key("a")])),
+            JsonPath::parse("[0].a")
+        );
+
+        assert!(JsonPath::parse("$.").is_none());
+        assert!(JsonPath::parse("$a").is_none());
+        assert!(JsonPath::parse("$.a[").is_none());
+        assert!(JsonPath::parse("$.a[-1]").is_none());
+    }
+
+    #[test]
+    fn test_lookup_json_path() {
+        let doc = json!({"a": [1, {"b": "x"}], "c": null});
+        let lookup = |path: &str| JsonPath::parse(path).unwrap().lookup(&doc).cloned();
+
+        assert_eq!(Some(doc.clone()), lookup("$"));
+        assert_eq!(Some(json!(1)), lookup("$.a[0]"));
+        assert_eq!(Some(json!("x")), lookup("a[1].b"));
+        assert_eq!(Some(json!(null)), lookup("$.c"));
+        assert_eq!(None, lookup("$.a[2]"));
+        assert_eq!(None, lookup("$.c.d"));
+        assert_eq!(None, lookup("$[0]"));
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! json_extract_* functions.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::Result;
+use common_query::prelude::Signature;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::vectors::{BooleanVector, Float64Vector, Int64Vector, StringVector, VectorRef};
+
+use crate::scalars::function::{Function, FunctionContext};
+use crate::scalars::json::{eval_json_path, json_path_signature};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExtractType {
+    Int,
+    Float,
+    Bool,
+    String,
+}
+
+/// `json_extract_<type>(doc, path)` returns the value at `path` of the JSON document
+/// `doc` as a typed value, or null if the value is not of the type:
+/// - `json_extract_int` returns integers as `Int64`
+/// - `json_extract_float` returns numbers as `Float64`
+/// - `json_extract_bool` returns booleans as `Boolean`
+/// - `json_extract_string` returns strings as `String` without quotes
+#[derive(Clone, Debug)]
+pub struct JsonExtractFunction {
+    extract_type: ExtractType,
+}
+
+impl JsonExtractFunction {
+    /// Returns the functions of all supported types.
+    pub fn all() -> Vec<JsonExtractFunction> {
+        [
+            ExtractType::Int,
+            ExtractType::Float,
+            ExtractType::Bool,
+            ExtractType::String,
+        ]
+        .into_iter()
+        .map(|extract_type| JsonExtractFunction { extract_type })
+        .collect()
+    }
+}
+
+impl Function for JsonExtractFunction {
+    fn name(&self) -> &str {
+        match self.extract_type {
+            ExtractType::Int => "json_extract_int",
+            ExtractType::Float => "json_extract_float",
+            ExtractType::Bool => "json_extract_bool",
+            ExtractType::String => "json_extract_string",
+        }
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        let data_type = match self.extract_type {
+            ExtractType::Int => ConcreteDataType::int64_datatype(),
+            ExtractType::Float => ConcreteDataType::float64_datatype(),
+            ExtractType::Bool => ConcreteDataType::boolean_datatype(),
+            ExtractType::String => ConcreteDataType::string_datatype(),
+        };
+        Ok(data_type)
+    }
+
+    fn signature(&self) -> Signature {
+        json_path_signature()
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        let name = self.name();
+        let vector: VectorRef = match self.extract_type {
+            ExtractType::Int => Arc::new(Int64Vector::from(eval_json_path(name, columns, |v| {
+                v.as_i64()
+            })?)),
+            ExtractType::Float => {
+                Arc::new(Float64Vector::from(eval_json_path(name, columns, |v| {
+                    v.as_f64()
+                })?))
+            }
+            ExtractType::Bool => {
+                Arc::new(BooleanVector::from(eval_json_path(name, columns, |v| {
+                    v.as_bool()
+                })?))
+            }
+            ExtractType::String => {
+                Arc::new(StringVector::from(eval_json_path(name, columns, |v| {
+                    v.as_str().map(|s| s.to_string())
+                })?))
+            }
+        };
+        Ok(vector)
+    }
+}
+
+impl fmt::Display for JsonExtractFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name().to_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+
+    use super::*;
+
+    fn extract(name: &str, doc: &str, path: &str) -> Value {
+        let f = JsonExtractFunction::all()
+            .into_iter()
+            .find(|f| f.name() == name)
+            .unwrap();
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec![doc])),
+            Arc::new(StringVector::from(vec![path])),
+        ];
+        f.eval(FunctionContext::default(), &columns).unwrap().get(0)
+    }
+
+    #[test]
+    fn test_json_extract() {
+        let doc = r#"{"i": 1, "f": 1.5, "b": true, "s": "x", "a": [1]}"#;
+
+        assert_eq!(Value::Int64(1), extract("json_extract_int", doc, "$.i"));
+        assert_eq!(Value::Null, extract("json_extract_int", doc, "$.f"));
+        assert_eq!(
+            Value::Float64(1.5.into()),
+            extract("json_extract_float", doc, "$.f")
+        );
+        assert_eq!(
+            Value::Float64(1.0.into()),
+            extract("json_extract_float", doc, "$.i")
+        );
+        assert_eq!(
+            Value::Boolean(true),
+            extract("json_extract_bool", doc, "$.b")
+        );
+        assert_eq!(Value::Null, extract("json_extract_bool", doc, "$.s"));
+        assert_eq!(
+            Value::String("x".into()),
+            extract("json_extract_string", doc, "$.s")
+        );
+        assert_eq!(Value::Null, extract("json_extract_string", doc, "$.a"));
+        assert_eq!(Value::Int64(1), extract("json_extract_int", doc, "$.a[0]"));
+        assert_eq!(Value::Null, extract("json_extract_int", doc, "$.missing"));
+    }
+}
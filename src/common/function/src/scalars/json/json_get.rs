@@ -0,0 +1,123 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! json_get function.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::Result;
+use common_query::prelude::Signature;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::vectors::{StringVector, VectorRef};
+
+use crate::scalars::function::{Function, FunctionContext};
+use crate::scalars::json::{eval_json_path, json_path_signature};
+
+const NAME: &str = "json_get";
+
+/// `json_get(doc, path)` returns the value at `path` of the JSON document `doc` as
+/// JSON text, e.g. `json_get('{"a": [1, 2]}', '$.a')` is `[1,2]`.
+#[derive(Clone, Debug, Default)]
+pub struct JsonGetFunction;
+
+impl Function for JsonGetFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        json_path_signature()
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        let values = eval_json_path(NAME, columns, |value| Some(value.to_string()))?;
+        Ok(Arc::new(StringVector::from(values)))
+    }
+}
+
+impl fmt::Display for JsonGetFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JSON_GET")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::scalars::ScalarVectorBuilder;
+    use datatypes::value::ValueRef;
+    use datatypes::vectors::{ConstantVector, JsonVectorBuilder, MutableVector};
+
+    use super::*;
+
+    #[test]
+    fn test_json_get() {
+        let f = JsonGetFunction::default();
+        assert_eq!("json_get", f.name());
+
+        let mut builder = JsonVectorBuilder::with_capacity(3);
+        for doc in [r#"{"a": [1, {"b": "x"}]}"#, r#"{"a": 2}"#, "[]"] {
+            builder.push_value_ref(ValueRef::String(doc)).unwrap();
+        }
+        let docs = builder.to_vector();
+        let path = |p: &str| -> VectorRef {
+            Arc::new(ConstantVector::new(
+                Arc::new(StringVector::from(vec![p])),
+                3,
+            ))
+        };
+
+        let result = f
+            .eval(FunctionContext::default(), &[docs.clone(), path("$.a")])
+            .unwrap();
+        let expect: VectorRef = Arc::new(StringVector::from(vec![
+            Some(r#"[1,{"b":"x"}]"#),
+            Some("2"),
+            None,
+        ]));
+        assert_eq!(expect, result);
+
+        let result = f
+            .eval(FunctionContext::default(), &[docs.clone(), path("a[1].b")])
+            .unwrap();
+        let expect: VectorRef = Arc::new(StringVector::from(vec![Some(r#""x""#), None, None]));
+        assert_eq!(expect, result);
+
+        // JSON text.
+        let text: VectorRef = Arc::new(StringVector::from(vec![r#"{"a": true}"#]));
+        let result = f
+            .eval(
+                FunctionContext::default(),
+                &[text, Arc::new(StringVector::from(vec!["$.a"]))],
+            )
+            .unwrap();
+        let expect: VectorRef = Arc::new(StringVector::from(vec!["true"]));
+        assert_eq!(expect, result);
+
+        assert!(f
+            .eval(FunctionContext::default(), &[docs.clone(), path("$.")])
+            .is_err());
+        let invalid: VectorRef = Arc::new(StringVector::from(vec!["{"]));
+        assert!(f
+            .eval(
+                FunctionContext::default(),
+                &[invalid, Arc::new(StringVector::from(vec!["$"]))]
+            )
+            .is_err());
+    }
+}
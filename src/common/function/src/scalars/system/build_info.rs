@@ -0,0 +1,116 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! build_info function.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::Result;
+use common_query::prelude::{Signature, Volatility};
+use datatypes::prelude::{ConcreteDataType, Vector};
+use datatypes::vectors::{ConstantVector, StringVector, VectorRef};
+use once_cell::sync::OnceCell;
+use serde_json::json;
+
+use crate::scalars::function::{Function, FunctionContext};
+
+const NAME: &str = "build_info";
+
+/// Cargo features enabled in the build, which are only known by the binary.
+static BUILD_FEATURES: OnceCell<Vec<String>> = OnceCell::new();
+
+/// Sets the cargo features reported by `build_info()`, only the first call takes effect.
+pub fn set_build_features(features: &[&str]) {
+    let _ = BUILD_FEATURES.set(features.iter().map(|f| f.to_string()).collect());
+}
+
+/// `build_info()` returns the version, git commit and branch, and the enabled cargo
+/// features of the running server as JSON text, e.g.
+/// `{"branch":"main","commit":"b6c1a5e","features":["python"],"version":"0.1.0"}`.
+#[derive(Clone, Debug, Default)]
+pub struct BuildInfoFunction;
+
+impl BuildInfoFunction {
+    fn build_info() -> String {
+        json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "commit": env!("GIT_COMMIT"),
+            "branch": env!("GIT_BRANCH"),
+            "features": BUILD_FEATURES.get().cloned().unwrap_or_default(),
+        })
+        .to_string()
+    }
+}
+
+impl Function for BuildInfoFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(vec![], Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        // Functions without arguments are evaluated with a column of nulls, whose length
+        // is the number of rows.
+        let rows = columns.first().map(|column| column.len()).unwrap_or(1);
+        Ok(Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec![Self::build_info()])),
+            rows,
+        )))
+    }
+}
+
+impl fmt::Display for BuildInfoFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BUILD_INFO")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+    use datatypes::vectors::NullVector;
+
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let f = BuildInfoFunction::default();
+        assert_eq!("build_info", f.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+
+        set_build_features(&["python"]);
+        let result = f
+            .eval(FunctionContext::default(), &[Arc::new(NullVector::new(2))])
+            .unwrap();
+        assert_eq!(2, result.len());
+        let Value::String(info) = result.get(1) else {
+            unreachable!()
+        };
+        let info: serde_json::Value = serde_json::from_str(info.as_utf8()).unwrap();
+        assert_eq!(env!("CARGO_PKG_VERSION"), info["version"]);
+        assert_eq!(env!("GIT_COMMIT"), info["commit"]);
+        assert_eq!(json!(["python"]), info["features"]);
+    }
+}
@@ -0,0 +1,440 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! time_bucket function.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, Result, UnsupportedInputDataTypeSnafu};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
+use common_time::timestamp::{TimeUnit, Timestamp};
+use common_time::TimeZone;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{
+    TimestampMicrosecondVector, TimestampMillisecondVector, TimestampNanosecondVector,
+    TimestampSecondVector, VectorRef,
+};
+use snafu::{ensure, OptionExt};
+
+use crate::scalars::function::{Function, FunctionContext};
+
+/// Name of the time bucket function.
+pub const TIME_BUCKET: &str = "time_bucket";
+
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// `time_bucket(interval, ts [, origin])` truncates the timestamp `ts` to the start of
+/// the bucket of size `interval` it falls in, e.g. `time_bucket('5m', ts)`.
+///
+/// Buckets are aligned to `origin`, which is either a timestamp or a timestamp string
+/// in the time zone of the query. Without an `origin`, buckets are aligned to the unix
+/// epoch as `DATE_BIN` does, except that buckets of whole days are aligned to the local
+/// midnight of the time zone of the query. The result has the same time unit as `ts`.
+#[derive(Clone, Debug, Default)]
+pub struct TimeBucketFunction;
+
+impl Function for TimeBucketFunction {
+    fn name(&self) -> &str {
+        TIME_BUCKET
+    }
+
+    fn return_type(&self, input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        match input_types.get(1) {
+            Some(data_type @ ConcreteDataType::Timestamp(_)) => Ok(data_type.clone()),
+            _ => UnsupportedInputDataTypeSnafu {
+                function: TIME_BUCKET,
+                datatypes: input_types.to_vec(),
+            }
+            .fail(),
+        }
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::one_of(
+            vec![TypeSignature::Any(2), TypeSignature::Any(3)],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2 || columns.len() == 3,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect 2 or 3, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let ConcreteDataType::Timestamp(ts_type) = columns[1].data_type() else {
+            return UnsupportedInputDataTypeSnafu {
+                function: TIME_BUCKET,
+                datatypes: columns.iter().map(|c| c.data_type()).collect::<Vec<_>>(),
+            }
+            .fail();
+        };
+        let unit = ts_type.unit();
+        let time_zone = TimeZone::Named(func_ctx.tz);
+
+        let len = columns[1].len();
+        let mut intervals = IntervalCache::default();
+        let mut buckets = Vec::with_capacity(len);
+        for i in 0..len {
+            let ValueRef::Timestamp(ts) = columns[1].get_ref(i) else {
+                buckets.push(None);
+                continue;
+            };
+            let Some(interval) = intervals.get(columns[0].get_ref(i), unit)? else {
+                buckets.push(None);
+                continue;
+            };
+            let bucket = match columns.get(2).map(|origin| origin.get_ref(i)) {
+                None => local_bucket(ts.value(), interval, unit, &time_zone),
+                Some(ValueRef::Null) => None,
+                Some(origin) => {
+                    let origin = origin_in_unit(origin, unit, &time_zone)?;
+                    bucket(ts.value(), interval, origin)
+                }
+            };
+            buckets.push(bucket);
+        }
+
+        let vector: VectorRef = match unit {
+            TimeUnit::Second => Arc::new(TimestampSecondVector::from(buckets)),
+            TimeUnit::Millisecond => Arc::new(TimestampMillisecondVector::from(buckets)),
+            TimeUnit::Microsecond => Arc::new(TimestampMicrosecondVector::from(buckets)),
+            TimeUnit::Nanosecond => Arc::new(TimestampNanosecondVector::from(buckets)),
+        };
+        Ok(vector)
+    }
+}
+
+impl fmt::Display for TimeBucketFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TIME_BUCKET")
+    }
+}
+
+/// Parses the interval string, e.g. `5m`, `1 hour` or `1h30m`, into nanoseconds.
+/// Returns `None` if the string is not a valid positive interval.
+///
+/// Months and years are not supported as their lengths vary.
+pub fn parse_interval(s: &str) -> Option<i64> {
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total: i64 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let value = rest[..digits].parse::<i64>().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let letters = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let nanos = unit_nanos(&rest[..letters].to_ascii_lowercase())?;
+        rest = rest[letters..].trim_start();
+
+        total = total.checked_add(value.checked_mul(nanos)?)?;
+    }
+
+    (total > 0).then_some(total)
+}
+
+fn unit_nanos(unit: &str) -> Option<i64> {
+    let nanos = match unit {
+        "ns" | "nanosecond" | "nanoseconds" => 1,
+        "us" | "microsecond" | "microseconds" => 1_000,
+        "ms" | "millisecond" | "milliseconds" => 1_000_000,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1_000_000_000,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60_000_000_000,
+        "h" | "hour" | "hours" => 3_600_000_000_000,
+        "d" | "day" | "days" => NANOS_PER_DAY,
+        "w" | "week" | "weeks" => 7 * NANOS_PER_DAY,
+        _ => return None,
+    };
+    Some(nanos)
+}
+
+/// Caches the last parsed interval as the interval is a constant in most queries.
+#[derive(Default)]
+struct IntervalCache {
+    last: Option<(String, i64)>,
+}
+
+impl IntervalCache {
+    /// Returns the interval in the time `unit`, or `None` if the interval is null.
+    fn get(&mut self, value: ValueRef, unit: TimeUnit) -> Result<Option<i64>> {
+        let s = match value {
+            ValueRef::Null => return Ok(None),
+            ValueRef::String(s) => s,
+            other => {
+                return InvalidFuncArgsSnafu {
+                    err_msg: format!(
+                        "The interval of {TIME_BUCKET} must be a string, have: {other:?}"
+                    ),
+                }
+                .fail()
+            }
+        };
+        if let Some((last, interval)) = &self.last {
+            if last == s {
+                return Ok(Some(*interval));
+            }
+        }
+
+        let nanos = parse_interval(s).context(InvalidFuncArgsSnafu {
+            err_msg: format!("Invalid interval: {s}"),
+        })?;
+        ensure!(
+            nanos % unit.factor() == 0,
+            InvalidFuncArgsSnafu {
+                err_msg: format!("Interval {s} is finer than the timestamp unit {unit}"),
+            }
+        );
+        let interval = nanos / unit.factor();
+        self.last = Some((s.to_string(), interval));
+        Ok(Some(interval))
+    }
+}
+
+fn origin_in_unit(origin: ValueRef, unit: TimeUnit, time_zone: &TimeZone) -> Result<i64> {
+    match origin {
+        ValueRef::Timestamp(ts) => Ok(ts.convert_to(unit)),
+        ValueRef::String(s) => {
+            let ts = Timestamp::from_str_with_time_zone(s, Some(time_zone))
+                .ok()
+                .context(InvalidFuncArgsSnafu {
+                    err_msg: format!("Invalid origin: {s}"),
+                })?;
+            Ok(ts.convert_to(unit))
+        }
+        other => InvalidFuncArgsSnafu {
+            err_msg: format!("The origin of {TIME_BUCKET} must be a timestamp, have: {other:?}"),
+        }
+        .fail(),
+    }
+}
+
+/// Returns the start of the bucket `ts` falls in, buckets are aligned to `origin`.
+fn bucket(ts: i64, interval: i64, origin: i64) -> Option<i64> {
+    let (ts, interval, origin) = (ts as i128, interval as i128, origin as i128);
+    let start = origin + (ts - origin).div_euclid(interval) * interval;
+    i64::try_from(start).ok()
+}
+
+/// Returns the start of the bucket `ts` falls in when no origin is given. Buckets of
+/// whole days start at the local midnight in `time_zone`, others are aligned to the
+/// unix epoch.
+fn local_bucket(ts: i64, interval: i64, unit: TimeUnit, time_zone: &TimeZone) -> Option<i64> {
+    let units_per_day = NANOS_PER_DAY / unit.factor();
+    if interval % units_per_day != 0 {
+        return bucket(ts, interval, 0);
+    }
+
+    let units_per_sec = TimeUnit::Second.factor() / unit.factor();
+    let utc = Timestamp::new(ts, unit).to_chrono_datetime()?.naive_utc();
+    let offset = (time_zone.to_local(&utc) - utc).num_seconds() * units_per_sec;
+    let local_start = bucket(ts.checked_add(offset)?, interval, 0)?;
+
+    // The offset at the start of the bucket may differ from the one of `ts` due to
+    // daylight saving time.
+    let local = Timestamp::new(local_start, unit)
+        .to_chrono_datetime()?
+        .naive_utc();
+    let offset = match time_zone.to_utc(&local) {
+        Some(utc) => (local - utc).num_seconds() * units_per_sec,
+        None => offset,
+    };
+    local_start.checked_sub(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::value::Value;
+    use datatypes::vectors::{ConstantVector, StringVector};
+
+    use super::*;
+
+    fn interval_vector(interval: &str, len: usize) -> VectorRef {
+        Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec![interval])),
+            len,
+        ))
+    }
+
+    fn eval(func_ctx: FunctionContext, args: &[VectorRef]) -> Vec<Value> {
+        let f = TimeBucketFunction::default();
+        let vector = f.eval(func_ctx, args).unwrap();
+        assert_eq!(args[1].data_type(), vector.data_type());
+        (0..vector.len()).map(|i| vector.get(i)).collect()
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        let minute = 60_000_000_000;
+        assert_eq!(Some(5 * minute), parse_interval("5m"));
+        assert_eq!(Some(5 * minute), parse_interval(" 5 minutes "));
+        assert_eq!(Some(60 * minute), parse_interval("1 Hour"));
+        assert_eq!(Some(90 * minute), parse_interval("1h30m"));
+        assert_eq!(Some(NANOS_PER_DAY), parse_interval("1d"));
+        assert_eq!(Some(1_000_000), parse_interval("1ms"));
+        assert_eq!(None, parse_interval(""));
+        assert_eq!(None, parse_interval("0s"));
+        assert_eq!(None, parse_interval("5"));
+        assert_eq!(None, parse_interval("1 month"));
+        assert_eq!(None, parse_interval("h"));
+    }
+
+    #[test]
+    fn test_return_type() {
+        let f = TimeBucketFunction::default();
+        assert_eq!("time_bucket", f.name());
+        for data_type in [
+            ConcreteDataType::timestamp_second_datatype(),
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            ConcreteDataType::timestamp_microsecond_datatype(),
+            ConcreteDataType::timestamp_nanosecond_datatype(),
+        ] {
+            assert_eq!(
+                data_type,
+                f.return_type(&[ConcreteDataType::string_datatype(), data_type.clone()])
+                    .unwrap()
+            );
+        }
+        assert!(f
+            .return_type(&[
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::int64_datatype()
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_time_bucket() {
+        let ts: VectorRef = Arc::new(TimestampMillisecondVector::from(vec![
+            Some(0),
+            Some(299_999),
+            Some(300_000),
+            None,
+            Some(-1),
+        ]));
+        let values = eval(
+            FunctionContext::default(),
+            &[interval_vector("5m", ts.len()), ts],
+        );
+        let expect = vec![
+            Value::Timestamp(Timestamp::new_millisecond(0)),
+            Value::Timestamp(Timestamp::new_millisecond(0)),
+            Value::Timestamp(Timestamp::new_millisecond(300_000)),
+            Value::Null,
+            Value::Timestamp(Timestamp::new_millisecond(-300_000)),
+        ];
+        assert_eq!(expect, values);
+    }
+
+    #[test]
+    fn test_time_bucket_units() {
+        let ts: VectorRef = Arc::new(TimestampSecondVector::from(vec![Some(3661)]));
+        let values = eval(FunctionContext::default(), &[interval_vector("1h", 1), ts]);
+        assert_eq!(vec![Value::Timestamp(Timestamp::new_second(3600))], values);
+
+        let ts: VectorRef = Arc::new(TimestampNanosecondVector::from(vec![Some(1_500)]));
+        let values = eval(FunctionContext::default(), &[interval_vector("1us", 1), ts]);
+        assert_eq!(
+            vec![Value::Timestamp(Timestamp::new_nanosecond(1_000))],
+            values
+        );
+
+        // The interval is finer than the unit of the timestamp.
+        let ts: VectorRef = Arc::new(TimestampSecondVector::from(vec![Some(1)]));
+        let f = TimeBucketFunction::default();
+        assert!(f
+            .eval(
+                FunctionContext::default(),
+                &[interval_vector("500ms", 1), ts]
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_time_bucket_origin() {
+        let ts: VectorRef = Arc::new(TimestampMillisecondVector::from(vec![
+            Some(0),
+            Some(100_000),
+            Some(400_000),
+        ]));
+        let origin: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(TimestampSecondVector::from(vec![Some(60)])),
+            ts.len(),
+        ));
+        let values = eval(
+            FunctionContext::default(),
+            &[interval_vector("5m", ts.len()), ts.clone(), origin],
+        );
+        let expect = vec![
+            Value::Timestamp(Timestamp::new_millisecond(-240_000)),
+            Value::Timestamp(Timestamp::new_millisecond(60_000)),
+            Value::Timestamp(Timestamp::new_millisecond(360_000)),
+        ];
+        assert_eq!(expect, values);
+
+        let origin: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec!["1970-01-01 00:01:00Z"])),
+            ts.len(),
+        ));
+        let values = eval(
+            FunctionContext::default(),
+            &[interval_vector("5m", ts.len()), ts, origin],
+        );
+        assert_eq!(expect, values);
+    }
+
+    #[test]
+    fn test_time_bucket_time_zone() {
+        let func_ctx = FunctionContext {
+            tz: "Asia/Shanghai".parse().unwrap(),
+        };
+        // 2023-01-01 20:00:00 UTC is 2023-01-02 04:00:00 in Asia/Shanghai.
+        let ts: VectorRef = Arc::new(TimestampSecondVector::from(vec![Some(1672603200)]));
+        let values = eval(func_ctx.clone(), &[interval_vector("1d", 1), ts.clone()]);
+        // 2023-01-02 00:00:00 in Asia/Shanghai.
+        assert_eq!(
+            vec![Value::Timestamp(Timestamp::new_second(1672588800))],
+            values
+        );
+
+        // Buckets shorter than a day are still aligned to the epoch.
+        let values = eval(func_ctx, &[interval_vector("1h", 1), ts.clone()]);
+        assert_eq!(
+            vec![Value::Timestamp(Timestamp::new_second(1672603200))],
+            values
+        );
+
+        let values = eval(FunctionContext::default(), &[interval_vector("1d", 1), ts]);
+        // 2023-01-01 00:00:00 UTC.
+        assert_eq!(
+            vec![Value::Timestamp(Timestamp::new_second(1672531200))],
+            values
+        );
+    }
+}
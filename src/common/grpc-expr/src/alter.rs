@@ -15,7 +15,8 @@
 use std::sync::Arc;
 
 use api::v1::alter_expr::Kind;
-use api::v1::{AlterExpr, CreateTableExpr, DropColumns};
+use api::v1::column::SemanticType;
+use api::v1::{AlterExpr, ColumnDef, CreateTableExpr, DropColumns, RenameTable};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
 use snafu::{ensure, OptionExt, ResultExt};
@@ -23,8 +24,8 @@ use table::metadata::TableId;
 use table::requests::{AddColumnRequest, AlterKind, AlterTableRequest, CreateTableRequest};
 
 use crate::error::{
-    ColumnNotFoundSnafu, CreateSchemaSnafu, InvalidColumnDefSnafu, MissingFieldSnafu,
-    MissingTimestampColumnSnafu, Result,
+    ColumnNotFoundSnafu, CreateSchemaSnafu, DuplicatedTimestampColumnSnafu, InvalidColumnDefSnafu,
+    InvalidSemanticTypeSnafu, MissingFieldSnafu, MissingTimestampColumnSnafu, Result,
 };
 
 /// Convert an [`AlterExpr`] to an optional [`AlterTableRequest`]
@@ -55,9 +56,30 @@ pub fn alter_expr_to_request(expr: AlterExpr) -> Result<Option<AlterTableRequest
                             .context(InvalidColumnDefSnafu {
                                 column: &column_def.name,
                             })?;
+                    let is_key = match semantic_type(&column_def)? {
+                        Some(SemanticType::Tag) => true,
+                        Some(SemanticType::Field) => {
+                            ensure!(
+                                !ac.is_key,
+                                InvalidSemanticTypeSnafu {
+                                    column: &column_def.name,
+                                    reason: "field column can't be a key",
+                                }
+                            );
+                            false
+                        }
+                        Some(SemanticType::Timestamp) => {
+                            return InvalidSemanticTypeSnafu {
+                                column: &column_def.name,
+                                reason: "can't add a time index column",
+                            }
+                            .fail();
+                        }
+                        None => ac.is_key,
+                    };
                     Ok(AddColumnRequest {
                         column_schema: schema,
-                        is_key: ac.is_key,
+                        is_key,
                     })
                 })
                 .collect::<Result<Vec<_>>>()?;
@@ -87,40 +109,129 @@ pub fn alter_expr_to_request(expr: AlterExpr) -> Result<Option<AlterTableRequest
             };
             Ok(Some(request))
         }
+        Some(Kind::RenameTable(RenameTable { new_table_name })) => {
+            let alter_kind = AlterKind::RenameTable { new_table_name };
+
+            let request = AlterTableRequest {
+                catalog_name,
+                schema_name,
+                table_name: expr.table_name,
+                alter_kind,
+            };
+            Ok(Some(request))
+        }
         None => Ok(None),
     }
 }
 
+/// Returns the semantic type of the column, `None` if it's not specified.
+fn semantic_type(column_def: &ColumnDef) -> Result<Option<SemanticType>> {
+    column_def
+        .semantic_type
+        .map(|value| {
+            SemanticType::from_i32(value).with_context(|| InvalidSemanticTypeSnafu {
+                column: &column_def.name,
+                reason: format!("unknown semantic type {value}"),
+            })
+        })
+        .transpose()
+}
+
+/// Returns the time index of the table to create, which is the column with the timestamp
+/// semantic type, or the `time_index` of the `expr`. There must be exactly one time index.
+fn resolve_time_index(expr: &CreateTableExpr) -> Result<&str> {
+    let mut time_index: Option<&str> = None;
+    for column_def in &expr.column_defs {
+        if semantic_type(column_def)? == Some(SemanticType::Timestamp) {
+            if let Some(exists) = time_index {
+                return DuplicatedTimestampColumnSnafu {
+                    exists,
+                    duplicated: &column_def.name,
+                }
+                .fail();
+            }
+            time_index = Some(&column_def.name);
+        }
+    }
+
+    match time_index {
+        Some(time_index) => {
+            ensure!(
+                expr.time_index.is_empty() || expr.time_index == time_index,
+                DuplicatedTimestampColumnSnafu {
+                    exists: time_index,
+                    duplicated: &expr.time_index,
+                }
+            );
+            Ok(time_index)
+        }
+        None => {
+            ensure!(
+                expr.column_defs
+                    .iter()
+                    .any(|column_def| column_def.name == expr.time_index),
+                MissingTimestampColumnSnafu {
+                    msg: format!("CreateExpr: {expr:?}")
+                }
+            );
+            Ok(&expr.time_index)
+        }
+    }
+}
+
+/// Returns the primary keys of the table to create. They are the columns with the tag
+/// semantic type, in the order of the columns, if the `expr` has no primary keys.
+fn resolve_primary_keys(expr: &CreateTableExpr) -> Result<Vec<String>> {
+    let mut tags = Vec::new();
+    for column_def in &expr.column_defs {
+        match semantic_type(column_def)? {
+            Some(SemanticType::Tag) => {
+                ensure!(
+                    expr.primary_keys.is_empty() || expr.primary_keys.contains(&column_def.name),
+                    InvalidSemanticTypeSnafu {
+                        column: &column_def.name,
+                        reason: "tag column must be a primary key",
+                    }
+                );
+                tags.push(column_def.name.clone());
+            }
+            Some(SemanticType::Field) => ensure!(
+                !expr.primary_keys.contains(&column_def.name),
+                InvalidSemanticTypeSnafu {
+                    column: &column_def.name,
+                    reason: "field column can't be a primary key",
+                }
+            ),
+            _ => {}
+        }
+    }
+
+    if expr.primary_keys.is_empty() {
+        Ok(tags)
+    } else {
+        Ok(expr.primary_keys.clone())
+    }
+}
+
 pub fn create_table_schema(expr: &CreateTableExpr) -> Result<SchemaRef> {
+    let time_index = resolve_time_index(expr)?;
     let column_schemas = expr
         .column_defs
         .iter()
         .map(|x| {
-            x.try_as_column_schema()
-                .context(InvalidColumnDefSnafu { column: &x.name })
+            let is_time_index = x.name == time_index;
+            // The time index is not nullable, the same as tables created by SQL.
+            let column_def = ColumnDef {
+                is_nullable: x.is_nullable && !is_time_index,
+                ..x.clone()
+            };
+            let column_schema = column_def
+                .try_as_column_schema()
+                .context(InvalidColumnDefSnafu { column: &x.name })?;
+            Ok(column_schema.with_time_index(is_time_index))
         })
         .collect::<Result<Vec<ColumnSchema>>>()?;
 
-    ensure!(
-        column_schemas
-            .iter()
-            .any(|column| column.name == expr.time_index),
-        MissingTimestampColumnSnafu {
-            msg: format!("CreateExpr: {expr:?}")
-        }
-    );
-
-    let column_schemas = column_schemas
-        .into_iter()
-        .map(|column_schema| {
-            if column_schema.name == expr.time_index {
-                column_schema.with_time_index(true)
-            } else {
-                column_schema
-            }
-        })
-        .collect::<Vec<_>>();
-
     Ok(Arc::new(
         SchemaBuilder::try_from(column_schemas)
             .context(CreateSchemaSnafu)?
@@ -129,13 +240,23 @@ pub fn create_table_schema(expr: &CreateTableExpr) -> Result<SchemaRef> {
     ))
 }
 
+/// Sets the `time_index` and `primary_keys` of the `expr` resolved from the semantic
+/// types of its columns, so the table created by the normalized expr is the same
+/// wherever the expr is handled, e.g. by the frontend and datanodes in distributed mode.
+pub fn normalize_create_expr(expr: &mut CreateTableExpr) -> Result<()> {
+    let time_index = resolve_time_index(expr)?.to_string();
+    let primary_keys = resolve_primary_keys(expr)?;
+    expr.time_index = time_index;
+    expr.primary_keys = primary_keys;
+    Ok(())
+}
+
 pub fn create_expr_to_request(
     table_id: TableId,
     expr: CreateTableExpr,
 ) -> Result<CreateTableRequest> {
     let schema = create_table_schema(&expr)?;
-    let primary_key_indices = expr
-        .primary_keys
+    let primary_key_indices = resolve_primary_keys(&expr)?
         .iter()
         .map(|key| {
             schema
@@ -183,10 +304,11 @@ pub fn create_expr_to_request(
 
 #[cfg(test)]
 mod tests {
-    use api::v1::{AddColumn, AddColumns, ColumnDataType, ColumnDef, DropColumn};
+    use api::v1::{AddColumn, AddColumns, ColumnDataType, DropColumn};
     use datatypes::prelude::ConcreteDataType;
 
     use super::*;
+    use crate::error::Error;
 
     #[test]
     fn test_alter_expr_to_request() {
@@ -202,6 +324,7 @@ mod tests {
                         datatype: ColumnDataType::Float64 as i32,
                         is_nullable: false,
                         default_constraint: vec![],
+                        semantic_type: None,
                     }),
                     is_key: false,
                 }],
@@ -251,4 +374,189 @@ mod tests {
         assert_eq!(1, drop_names.len());
         assert_eq!("mem_usage".to_string(), drop_names.pop().unwrap());
     }
+
+    #[test]
+    fn test_rename_table_expr() {
+        let expr = AlterExpr {
+            catalog_name: "test_catalog".to_string(),
+            schema_name: "test_schema".to_string(),
+            table_name: "monitor".to_string(),
+
+            kind: Some(Kind::RenameTable(RenameTable {
+                new_table_name: "new_monitor".to_string(),
+            })),
+        };
+
+        let alter_request = alter_expr_to_request(expr).unwrap().unwrap();
+        assert_eq!(Some("test_catalog".to_string()), alter_request.catalog_name);
+        assert_eq!(Some("test_schema".to_string()), alter_request.schema_name);
+        assert_eq!("monitor".to_string(), alter_request.table_name);
+        match alter_request.alter_kind {
+            AlterKind::RenameTable { new_table_name } => {
+                assert_eq!("new_monitor", new_table_name)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn column_def(
+        name: &str,
+        datatype: ColumnDataType,
+        semantic_type: Option<SemanticType>,
+    ) -> ColumnDef {
+        ColumnDef {
+            name: name.to_string(),
+            datatype: datatype as i32,
+            is_nullable: true,
+            default_constraint: vec![],
+            semantic_type: semantic_type.map(|t| t as i32),
+        }
+    }
+
+    fn create_expr_with_semantic_types() -> CreateTableExpr {
+        CreateTableExpr {
+            catalog_name: "".to_string(),
+            schema_name: "".to_string(),
+            table_name: "monitor".to_string(),
+            desc: "".to_string(),
+            column_defs: vec![
+                column_def("cpu", ColumnDataType::Float64, Some(SemanticType::Field)),
+                column_def("idc", ColumnDataType::String, Some(SemanticType::Tag)),
+                column_def(
+                    "ts",
+                    ColumnDataType::TimestampMillisecond,
+                    Some(SemanticType::Timestamp),
+                ),
+                column_def("host", ColumnDataType::String, Some(SemanticType::Tag)),
+            ],
+            time_index: "".to_string(),
+            primary_keys: vec![],
+            create_if_not_exists: false,
+            table_options: Default::default(),
+            table_id: None,
+            region_ids: vec![],
+        }
+    }
+
+    #[test]
+    fn test_create_expr_with_semantic_types() {
+        let request = create_expr_to_request(1024, create_expr_with_semantic_types()).unwrap();
+        let schema = request.schema;
+        let time_index = schema.timestamp_column().unwrap();
+        assert_eq!("ts", time_index.name);
+        // The time index is not nullable.
+        assert!(!time_index.is_nullable());
+        // Primary keys are built from tags.
+        assert_eq!(vec![1, 3], request.primary_key_indices);
+
+        // Explicit primary keys must contain all tags.
+        let mut expr = create_expr_with_semantic_types();
+        expr.primary_keys = vec!["host".to_string(), "idc".to_string()];
+        let request = create_expr_to_request(1024, expr).unwrap();
+        assert_eq!(vec![3, 1], request.primary_key_indices);
+
+        let mut expr = create_expr_with_semantic_types();
+        expr.primary_keys = vec!["host".to_string()];
+        let err = create_expr_to_request(1024, expr).unwrap_err();
+        assert!(
+            err.to_string().contains("tag column must be a primary key"),
+            "{err}"
+        );
+
+        let mut expr = create_expr_with_semantic_types();
+        expr.primary_keys = vec!["host".to_string(), "idc".to_string(), "cpu".to_string()];
+        let err = create_expr_to_request(1024, expr).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("field column can't be a primary key"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_normalize_create_expr() {
+        let mut expr = create_expr_with_semantic_types();
+        normalize_create_expr(&mut expr).unwrap();
+        assert_eq!("ts", expr.time_index);
+        assert_eq!(vec!["idc", "host"], expr.primary_keys);
+        // Normalizing is idempotent.
+        let normalized = expr.clone();
+        normalize_create_expr(&mut expr).unwrap();
+        assert_eq!(normalized, expr);
+
+        let mut expr = create_expr_with_semantic_types();
+        expr.column_defs[2].semantic_type = None;
+        assert!(normalize_create_expr(&mut expr).is_err());
+    }
+
+    #[test]
+    fn test_create_expr_time_index() {
+        // The time index conflicts with the timestamp column.
+        let mut expr = create_expr_with_semantic_types();
+        expr.time_index = "host".to_string();
+        let err = create_table_schema(&expr).unwrap_err();
+        assert!(matches!(err, Error::DuplicatedTimestampColumn { .. }));
+
+        // The same time index is allowed.
+        expr.time_index = "ts".to_string();
+        assert!(create_table_schema(&expr).is_ok());
+
+        let mut expr = create_expr_with_semantic_types();
+        expr.column_defs.push(column_def(
+            "ts2",
+            ColumnDataType::TimestampMillisecond,
+            Some(SemanticType::Timestamp),
+        ));
+        let err = create_table_schema(&expr).unwrap_err();
+        assert!(matches!(err, Error::DuplicatedTimestampColumn { .. }));
+
+        let mut expr = create_expr_with_semantic_types();
+        expr.column_defs[2].semantic_type = None;
+        let err = create_table_schema(&expr).unwrap_err();
+        assert!(matches!(err, Error::MissingTimestampColumn { .. }));
+
+        let mut expr = create_expr_with_semantic_types();
+        expr.column_defs[0].semantic_type = Some(100);
+        let err = create_table_schema(&expr).unwrap_err();
+        assert!(matches!(err, Error::InvalidSemanticType { .. }));
+    }
+
+    #[test]
+    fn test_add_columns_with_semantic_types() {
+        let add_columns_expr = |column_def, is_key| AlterExpr {
+            catalog_name: "".to_string(),
+            schema_name: "".to_string(),
+            table_name: "monitor".to_string(),
+            kind: Some(Kind::AddColumns(AddColumns {
+                add_columns: vec![AddColumn {
+                    column_def: Some(column_def),
+                    is_key,
+                }],
+            })),
+        };
+        let is_key = |request: AlterTableRequest| match request.alter_kind {
+            AlterKind::AddColumns { columns } => columns[0].is_key,
+            _ => unreachable!(),
+        };
+
+        let tag = column_def("idc", ColumnDataType::String, Some(SemanticType::Tag));
+        let request = alter_expr_to_request(add_columns_expr(tag, false))
+            .unwrap()
+            .unwrap();
+        assert!(is_key(request));
+
+        let field = column_def("cpu", ColumnDataType::Float64, Some(SemanticType::Field));
+        let request = alter_expr_to_request(add_columns_expr(field.clone(), false))
+            .unwrap()
+            .unwrap();
+        assert!(!is_key(request));
+        assert!(alter_expr_to_request(add_columns_expr(field, true)).is_err());
+
+        let timestamp = column_def(
+            "ts",
+            ColumnDataType::TimestampMillisecond,
+            Some(SemanticType::Timestamp),
+        );
+        assert!(alter_expr_to_request(add_columns_expr(timestamp, false)).is_err());
+    }
 }
@@ -90,6 +90,13 @@ pub enum Error {
         #[snafu(backtrace)]
         source: api::error::Error,
     },
+
+    #[snafu(display("Invalid semantic type of column {}, reason: {}", column, reason))]
+    InvalidSemanticType {
+        column: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -104,7 +111,8 @@ impl ErrorExt for Error {
             Error::ColumnDataType { .. } => StatusCode::Internal,
             Error::CreateSchema { .. }
             | Error::DuplicatedTimestampColumn { .. }
-            | Error::MissingTimestampColumn { .. } => StatusCode::InvalidArguments,
+            | Error::MissingTimestampColumn { .. }
+            | Error::InvalidSemanticType { .. } => StatusCode::InvalidArguments,
             Error::InvalidColumnProto { .. } => StatusCode::InvalidArguments,
             Error::CreateVector { .. } => StatusCode::InvalidArguments,
             Error::MissingField { .. } => StatusCode::InvalidArguments,
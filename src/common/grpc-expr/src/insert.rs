@@ -41,12 +41,18 @@ const TAG_SEMANTIC_TYPE: i32 = SemanticType::Tag as i32;
 const TIMESTAMP_SEMANTIC_TYPE: i32 = SemanticType::Timestamp as i32;
 
 #[inline]
-fn build_column_def(column_name: &str, datatype: i32, nullable: bool) -> ColumnDef {
+fn build_column_def(
+    column_name: &str,
+    datatype: i32,
+    nullable: bool,
+    semantic_type: i32,
+) -> ColumnDef {
     ColumnDef {
         name: column_name.to_string(),
         datatype,
         is_nullable: nullable,
         default_constraint: vec![],
+        semantic_type: Some(semantic_type),
     }
 }
 
@@ -63,7 +69,12 @@ pub fn find_new_columns(schema: &SchemaRef, columns: &[Column]) -> Result<Option
     {
         if schema.column_schema_by_name(column_name).is_none() && !new_columns.contains(column_name)
         {
-            let column_def = Some(build_column_def(column_name, *datatype, true));
+            let column_def = Some(build_column_def(
+                column_name,
+                *datatype,
+                true,
+                *semantic_type,
+            ));
             columns_to_add.push(AddColumn {
                 column_def,
                 is_key: *semantic_type == TAG_SEMANTIC_TYPE,
@@ -171,7 +182,7 @@ fn collect_column_values(column_datatype: ColumnDataType, values: &Values) -> Ve
         }
         ColumnDataType::Float32 => collect_values!(values.f32_values, |v| ValueRef::from(*v)),
         ColumnDataType::Float64 => collect_values!(values.f64_values, |v| ValueRef::from(*v)),
-        ColumnDataType::Binary => {
+        ColumnDataType::Binary | ColumnDataType::Json | ColumnDataType::Uuid => {
             collect_values!(values.binary_values, |v| ValueRef::from(v.as_slice()))
         }
         ColumnDataType::String => {
@@ -247,7 +258,7 @@ pub fn build_create_expr_from_insertion(
                 _ => {}
             }
 
-            let column_def = build_column_def(column_name, *datatype, is_nullable);
+            let column_def = build_column_def(column_name, *datatype, is_nullable, *semantic_type);
             column_defs.push(column_def);
             new_columns.insert(column_name.to_string());
         }
@@ -424,11 +435,13 @@ fn convert_values(data_type: &ConcreteDataType, values: Values) -> Vec<Value> {
             .into_iter()
             .map(|val| val.into())
             .collect(),
-        ConcreteDataType::Binary(_) => values
-            .binary_values
-            .into_iter()
-            .map(|val| val.into())
-            .collect(),
+        ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) | ConcreteDataType::Uuid(_) => {
+            values
+                .binary_values
+                .into_iter()
+                .map(|val| val.into())
+                .collect()
+        }
         ConcreteDataType::DateTime(_) => values
             .i64_values
             .into_iter()
@@ -518,6 +531,8 @@ mod tests {
         let column_defs = create_expr.column_defs;
         assert_eq!(column_defs[3].name, create_expr.time_index);
         assert_eq!(4, column_defs.len());
+        assert_eq!(Some(TAG_SEMANTIC_TYPE), column_defs[0].semantic_type);
+        assert_eq!(Some(TIMESTAMP_SEMANTIC_TYPE), column_defs[3].semantic_type);
 
         assert_eq!(
             ConcreteDataType::string_datatype(),
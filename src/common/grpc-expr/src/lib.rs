@@ -16,7 +16,9 @@ mod alter;
 pub mod error;
 pub mod insert;
 
-pub use alter::{alter_expr_to_request, create_expr_to_request, create_table_schema};
+pub use alter::{
+    alter_expr_to_request, create_expr_to_request, create_table_schema, normalize_create_expr,
+};
 pub use insert::{
     build_alter_table_request, build_create_expr_from_insertion, column_to_vector, find_new_columns,
 };
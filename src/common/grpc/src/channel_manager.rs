@@ -12,14 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
-use tonic::transport::{Channel as InnerChannel, Endpoint, Uri};
+use tonic::codec::CompressionEncoding;
+use tonic::transport::{
+    Certificate, Channel as InnerChannel, ClientTlsConfig, Endpoint, Identity, Uri,
+};
 use tower::make::MakeConnection;
 
 use crate::error;
@@ -60,14 +65,21 @@ impl ChannelManager {
     }
 
     pub fn get(&self, addr: impl AsRef<str>) -> Result<InnerChannel> {
+        self.get_nth(addr, 0)
+    }
+
+    /// Gets the `index`-th channel to `addr`, channels of different indices use their
+    /// own connections. The channel of index 0 is the one returned by [ChannelManager::get].
+    pub fn get_nth(&self, addr: impl AsRef<str>, index: usize) -> Result<InnerChannel> {
         let addr = addr.as_ref();
+        let key = channel_key(addr, index);
         // It will acquire the read lock.
-        if let Some(inner_ch) = self.pool.get(addr) {
+        if let Some(inner_ch) = self.pool.get(&key) {
             return Ok(inner_ch);
         }
 
         // It will acquire the write lock.
-        let entry = match self.pool.entry(addr.to_string()) {
+        let entry = match self.pool.entry(key.into_owned()) {
             Entry::Occupied(entry) => {
                 entry.get().increase_access();
                 entry.into_ref()
@@ -119,8 +131,19 @@ impl ChannelManager {
     }
 
     fn build_endpoint(&self, addr: &str) -> Result<Endpoint> {
+        let scheme = if self.config.client_tls.is_some() {
+            "https"
+        } else {
+            "http"
+        };
         let mut endpoint =
-            Endpoint::new(format!("http://{addr}")).context(error::CreateChannelSnafu)?;
+            Endpoint::new(format!("{scheme}://{addr}")).context(error::CreateChannelSnafu)?;
+
+        if let Some(tls) = &self.config.client_tls {
+            endpoint = endpoint
+                .tls_config(load_client_tls_config(tls)?)
+                .context(error::CreateChannelSnafu)?;
+        }
 
         if let Some(dur) = self.config.timeout {
             endpoint = endpoint.timeout(dur);
@@ -160,6 +183,63 @@ impl ChannelManager {
     }
 }
 
+/// Loads the certificates of the client side TLS config, the files are read when
+/// the channel is created, so new channels pick up the rotated certificates.
+fn load_client_tls_config(tls: &ClientTlsOption) -> Result<ClientTlsConfig> {
+    let read = |path: &str| std::fs::read(path).context(error::ReadTlsFileSnafu { path });
+
+    let mut config = ClientTlsConfig::new();
+    if !tls.server_ca_cert_path.is_empty() {
+        config = config.ca_certificate(Certificate::from_pem(read(&tls.server_ca_cert_path)?));
+    }
+    if !tls.client_cert_path.is_empty() && !tls.client_key_path.is_empty() {
+        let cert = read(&tls.client_cert_path)?;
+        let key = read(&tls.client_key_path)?;
+        config = config.identity(Identity::from_pem(cert, key));
+    }
+    if !tls.domain_name.is_empty() {
+        config = config.domain_name(&tls.domain_name);
+    }
+    Ok(config)
+}
+
+/// Returns the key of the `index`-th channel to `addr` in the pool.
+fn channel_key(addr: &str, index: usize) -> Cow<str> {
+    if index == 0 {
+        Cow::Borrowed(addr)
+    } else {
+        Cow::Owned(format!("{addr}#{index}"))
+    }
+}
+
+/// Compression of messages sent through a channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Compresses all gRPC messages with gzip. Responses are compressed only if the
+    /// server accepts it.
+    Gzip,
+    /// Compresses record batches in the Arrow Flight data with zstd, other messages
+    /// are sent as is. Record batches smaller than
+    /// [COMPRESSION_THRESHOLD](crate::flight::COMPRESSION_THRESHOLD) are not compressed.
+    Zstd,
+}
+
+/// TLS options of the client side of channels.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientTlsOption {
+    /// CA certificates to verify the certificate of the server with. The system roots
+    /// are not trusted.
+    pub server_ca_cert_path: String,
+    /// Certificate and key presented to the server, required if the server enables
+    /// mutual TLS.
+    pub client_cert_path: String,
+    pub client_key_path: String,
+    /// Name to verify the certificate of the server with, defaults to the host of the
+    /// address. Required if servers are addressed by IP.
+    pub domain_name: String,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChannelConfig {
     pub timeout: Option<Duration>,
@@ -174,6 +254,8 @@ pub struct ChannelConfig {
     pub http2_adaptive_window: Option<bool>,
     pub tcp_keepalive: Option<Duration>,
     pub tcp_nodelay: bool,
+    pub compression: Option<Compression>,
+    pub client_tls: Option<ClientTlsOption>,
 }
 
 impl Default for ChannelConfig {
@@ -191,6 +273,8 @@ impl Default for ChannelConfig {
             http2_adaptive_window: None,
             tcp_keepalive: None,
             tcp_nodelay: true,
+            compression: None,
+            client_tls: None,
         }
     }
 }
@@ -307,6 +391,41 @@ impl ChannelConfig {
             ..self
         }
     }
+
+    /// Set the compression of messages sent through the channel.
+    ///
+    /// Default is no compression.
+    pub fn compression(self, compression: Compression) -> Self {
+        Self {
+            compression: Some(compression),
+            ..self
+        }
+    }
+
+    /// Connect to the server with TLS.
+    ///
+    /// Default is plain text.
+    pub fn client_tls_config(self, client_tls: ClientTlsOption) -> Self {
+        Self {
+            client_tls: Some(client_tls),
+            ..self
+        }
+    }
+
+    /// Returns the encoding gRPC clients of the channel should send and accept messages
+    /// with, if the messages are compressed by gRPC.
+    pub fn grpc_compression(&self) -> Option<CompressionEncoding> {
+        match self.compression {
+            Some(Compression::Gzip) => Some(CompressionEncoding::Gzip),
+            Some(Compression::Zstd) | None => None,
+        }
+    }
+
+    /// Returns true if record batches in the Flight data sent through the channel
+    /// should be compressed.
+    pub fn compress_flight_data(&self) -> bool {
+        self.compression == Some(Compression::Zstd)
+    }
 }
 
 #[derive(Debug)]
@@ -426,6 +545,24 @@ mod tests {
         assert_eq!(0, mgr.pool.get_access(addr).unwrap());
     }
 
+    #[tokio::test]
+    async fn test_get_nth() {
+        let pool = Arc::new(Pool::default());
+        let config = ChannelConfig::new();
+        let mgr = ChannelManager { pool, config };
+        let addr = "test_addr";
+
+        for index in 0..3 {
+            let _ = mgr.get_nth(addr, index).unwrap();
+        }
+        let _ = mgr.get(addr).unwrap();
+
+        assert_eq!(2, mgr.pool.get_access(addr).unwrap());
+        assert_eq!(1, mgr.pool.get_access("test_addr#1").unwrap());
+        assert_eq!(1, mgr.pool.get_access("test_addr#2").unwrap());
+        assert_eq!(3, mgr.pool.channels.len());
+    }
+
     #[test]
     fn test_config() {
         let default_cfg = ChannelConfig::new();
@@ -443,6 +580,8 @@ mod tests {
                 http2_adaptive_window: None,
                 tcp_keepalive: None,
                 tcp_nodelay: true,
+                compression: None,
+                client_tls: None,
             },
             default_cfg
         );
@@ -459,7 +598,8 @@ mod tests {
             .http2_keep_alive_while_idle(true)
             .http2_adaptive_window(true)
             .tcp_keepalive(Duration::from_secs(2))
-            .tcp_nodelay(false);
+            .tcp_nodelay(false)
+            .compression(Compression::Gzip);
 
         assert_eq!(
             ChannelConfig {
@@ -475,9 +615,17 @@ mod tests {
                 http2_adaptive_window: Some(true),
                 tcp_keepalive: Some(Duration::from_secs(2)),
                 tcp_nodelay: false,
+                compression: Some(Compression::Gzip),
+                client_tls: None,
             },
             cfg
         );
+        assert_eq!(Some(CompressionEncoding::Gzip), cfg.grpc_compression());
+        assert!(!cfg.compress_flight_data());
+
+        let cfg = cfg.compression(Compression::Zstd);
+        assert_eq!(None, cfg.grpc_compression());
+        assert!(cfg.compress_flight_data());
     }
 
     #[test]
@@ -503,6 +651,27 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn test_build_endpoint_with_tls() {
+        let pool = Arc::new(Pool::default());
+        let config = ChannelConfig::new().client_tls_config(ClientTlsOption {
+            domain_name: "localhost".to_string(),
+            ..Default::default()
+        });
+        let mgr = ChannelManager { pool, config };
+        let endpoint = mgr.build_endpoint("127.0.0.1:3001").unwrap();
+        assert_eq!("https", endpoint.uri().scheme_str().unwrap());
+
+        let pool = Arc::new(Pool::default());
+        let config = ChannelConfig::new().client_tls_config(ClientTlsOption {
+            server_ca_cert_path: "/not/exist/ca.crt".to_string(),
+            ..Default::default()
+        });
+        let mgr = ChannelManager { pool, config };
+        let err = mgr.build_endpoint("127.0.0.1:3001").unwrap_err();
+        assert!(matches!(err, error::Error::ReadTlsFile { .. }), "{err:?}");
+    }
+
     #[tokio::test]
     async fn test_channel_with_connector() {
         let pool = Pool {
@@ -44,6 +44,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to read TLS file: {}, source: {}", path, source))]
+    ReadTlsFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to create RecordBatch, source: {}", source))]
     CreateRecordBatch {
         #[snafu(backtrace)]
@@ -83,7 +90,8 @@ impl ErrorExt for Error {
         match self {
             Error::MissingField { .. }
             | Error::TypeMismatch { .. }
-            | Error::InvalidFlightData { .. } => StatusCode::InvalidArguments,
+            | Error::InvalidFlightData { .. }
+            | Error::ReadTlsFile { .. } => StatusCode::InvalidArguments,
 
             Error::CreateChannel { .. }
             | Error::Conversion { .. }
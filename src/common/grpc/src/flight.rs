@@ -18,19 +18,23 @@ use std::sync::Arc;
 
 use api::result::ObjectResultBuilder;
 use api::v1::{FlightDataExt, ObjectResult};
-use arrow_flight::utils::{flight_data_from_arrow_batch, flight_data_to_arrow_batch};
+use arrow_flight::utils::flight_data_to_arrow_batch;
 use arrow_flight::{FlightData, IpcMessage, SchemaAsIpc};
 use common_error::prelude::StatusCode;
 use common_recordbatch::{RecordBatch, RecordBatches};
 use datatypes::arrow;
+use datatypes::arrow::array::ArrayRef;
+use datatypes::arrow::buffer::Buffer;
 use datatypes::arrow::datatypes::Schema as ArrowSchema;
-use datatypes::arrow::ipc::{root_as_message, writer, MessageHeader};
+use datatypes::arrow::ipc::{reader, root_as_message, writer, CompressionType, MessageHeader};
 use datatypes::schema::{Schema, SchemaRef};
+use datatypes::vectors::Helper;
 use flatbuffers::FlatBufferBuilder;
 use futures::TryStreamExt;
 use prost::Message;
 use snafu::{OptionExt, ResultExt};
 use tonic::codegen::futures_core::Stream;
+use tonic::metadata::{MetadataMap, MetadataValue};
 use tonic::Response;
 
 use crate::error::{
@@ -48,50 +52,170 @@ pub enum FlightMessage {
     AffectedRows(usize),
 }
 
-#[derive(Default)]
+/// Record batches whose buffers are smaller than this are not compressed, as the
+/// bytes saved are not worth the time.
+pub const COMPRESSION_THRESHOLD: usize = 64 * 1024;
+
+/// Default max size of the record batch in a [FlightData], which is well below the
+/// default 4 MiB max message size of gRPC.
+pub const DEFAULT_MAX_FLIGHT_DATA_SIZE: usize = 2 * 1024 * 1024;
+
+/// Metadata key of a `DoGet` request asking the server to compress record batches in
+/// the returned [FlightData].
+const FLIGHT_COMPRESSION_KEY: &str = "x-greptime-flight-compression";
+const FLIGHT_COMPRESSION_ZSTD: &str = "zstd";
+
+/// Asks the server to compress record batches in the response of the request with
+/// the `metadata`.
+pub fn request_compression(metadata: &mut MetadataMap) {
+    let _ = metadata.insert(
+        FLIGHT_COMPRESSION_KEY,
+        MetadataValue::from_static(FLIGHT_COMPRESSION_ZSTD),
+    );
+}
+
+/// Returns true if the request with the `metadata` accepts compressed record batches.
+pub fn is_compression_requested(metadata: &MetadataMap) -> bool {
+    metadata
+        .get(FLIGHT_COMPRESSION_KEY)
+        .map(|value| value == FLIGHT_COMPRESSION_ZSTD)
+        .unwrap_or(false)
+}
+
 pub struct FlightEncoder {
     write_options: writer::IpcWriteOptions,
+    /// Options to write record batches larger than [COMPRESSION_THRESHOLD].
+    compressed_write_options: Option<writer::IpcWriteOptions>,
+    /// Record batches larger than this are split into several [FlightData].
+    max_flight_data_size: usize,
+    data_gen: writer::IpcDataGenerator,
+    /// Tracks the dictionaries sent, so a dictionary is only sent again when it's
+    /// replaced by another one.
+    dictionary_tracker: writer::DictionaryTracker,
+}
+
+impl Default for FlightEncoder {
+    fn default() -> Self {
+        Self {
+            write_options: writer::IpcWriteOptions::default(),
+            compressed_write_options: None,
+            max_flight_data_size: DEFAULT_MAX_FLIGHT_DATA_SIZE,
+            data_gen: writer::IpcDataGenerator::default(),
+            dictionary_tracker: writer::DictionaryTracker::new(false),
+        }
+    }
 }
 
 impl FlightEncoder {
-    pub fn encode(&self, flight_message: FlightMessage) -> FlightData {
+    /// Creates an encoder that compresses record batches larger than
+    /// [COMPRESSION_THRESHOLD] with zstd. The decoder decompresses them transparently.
+    pub fn with_compression() -> Self {
+        // Safety: The default metadata version V5 supports compression.
+        let compressed_write_options = writer::IpcWriteOptions::default()
+            .try_with_compression(Some(CompressionType::ZSTD))
+            .unwrap();
+        Self {
+            compressed_write_options: Some(compressed_write_options),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the max size of the record batch in a [FlightData], defaults to
+    /// [DEFAULT_MAX_FLIGHT_DATA_SIZE].
+    ///
+    /// The size is estimated from the memory size of the record batches, and a single
+    /// row larger than it is still sent in one [FlightData].
+    pub fn with_max_flight_data_size(mut self, max_flight_data_size: usize) -> Self {
+        self.max_flight_data_size = max_flight_data_size.max(1);
+        self
+    }
+
+    /// Returns the options to write the `recordbatch`.
+    fn write_options_of(&self, recordbatch: &RecordBatch) -> &writer::IpcWriteOptions {
+        match &self.compressed_write_options {
+            Some(options) if recordbatch_size(recordbatch) >= COMPRESSION_THRESHOLD => options,
+            _ => &self.write_options,
+        }
+    }
+
+    /// Encodes the `flight_message` into [FlightData]s.
+    ///
+    /// A record batch is encoded as the dictionaries not sent yet, followed by one or more
+    /// record batches no larger than the max flight data size. Other messages are always
+    /// encoded into one [FlightData].
+    pub fn encode(&mut self, flight_message: FlightMessage) -> Vec<FlightData> {
         match flight_message {
             FlightMessage::Schema(schema) => {
-                SchemaAsIpc::new(schema.arrow_schema(), &self.write_options).into()
+                // Dictionaries of the previous schema are useless to the decoder.
+                self.dictionary_tracker = writer::DictionaryTracker::new(false);
+                vec![SchemaAsIpc::new(schema.arrow_schema(), &self.write_options).into()]
             }
             FlightMessage::Recordbatch(recordbatch) => {
-                let (flight_dictionaries, flight_batch) = flight_data_from_arrow_batch(
-                    recordbatch.df_record_batch(),
-                    &self.write_options,
-                );
-
-                // TODO(LFC): Handle dictionary as FlightData here, when we supported Arrow's Dictionary DataType.
-                // Currently we don't have a datatype corresponding to Arrow's Dictionary DataType,
-                // so there won't be any "dictionaries" here. Assert to be sure about it, and
-                // perform a "testing guard" in case we forgot to handle the possible "dictionaries"
-                // here in the future.
-                debug_assert_eq!(flight_dictionaries.len(), 0);
-
-                flight_batch
+                let mut flight_data = Vec::new();
+                for chunk in self.split_recordbatch(&recordbatch) {
+                    let options = self.write_options_of(&chunk).clone();
+                    let (encoded_dictionaries, encoded_batch) = self
+                        .data_gen
+                        .encoded_batch(
+                            chunk.df_record_batch(),
+                            &mut self.dictionary_tracker,
+                            &options,
+                        )
+                        // Safety: The dictionary tracker doesn't error on replacement.
+                        .unwrap();
+                    flight_data.extend(encoded_dictionaries.into_iter().map(Into::into));
+                    flight_data.push(encoded_batch.into());
+                }
+                flight_data
             }
             FlightMessage::AffectedRows(rows) => {
                 let ext_data = FlightDataExt {
                     affected_rows: rows as _,
                 }
                 .encode_to_vec();
-                FlightData::new(None, IpcMessage(build_none_flight_msg()), vec![], ext_data)
+                vec![FlightData::new(
+                    None,
+                    IpcMessage(build_none_flight_msg()),
+                    vec![],
+                    ext_data,
+                )]
             }
         }
     }
+
+    /// Splits the `recordbatch` into chunks with the same number of rows, each of which
+    /// is estimated to be no larger than the max flight data size.
+    fn split_recordbatch(&self, recordbatch: &RecordBatch) -> Vec<RecordBatch> {
+        let num_rows = recordbatch.num_rows();
+        let size = recordbatch_size(recordbatch);
+        let num_chunks = ((size + self.max_flight_data_size - 1) / self.max_flight_data_size)
+            .clamp(1, num_rows.max(1));
+        if num_chunks == 1 {
+            return vec![recordbatch.clone()];
+        }
+
+        let rows_per_chunk = (num_rows + num_chunks - 1) / num_chunks;
+        (0..num_rows)
+            .step_by(rows_per_chunk)
+            .map(|offset| recordbatch.slice(offset, rows_per_chunk.min(num_rows - offset)))
+            .collect()
+    }
 }
 
 #[derive(Default)]
 pub struct FlightDecoder {
     schema: Option<SchemaRef>,
+    /// Dictionaries decoded from the dictionary batches, which are referenced by the
+    /// record batches that follow.
+    dictionaries_by_id: HashMap<i64, ArrayRef>,
 }
 
 impl FlightDecoder {
-    pub fn try_decode(&mut self, flight_data: FlightData) -> Result<FlightMessage> {
+    /// Decodes the `flight_data` into a [FlightMessage].
+    ///
+    /// Returns `None` for dictionary batches, which are kept by the decoder to decode the
+    /// record batches that follow.
+    pub fn try_decode(&mut self, flight_data: FlightData) -> Result<Option<FlightMessage>> {
         let message = root_as_message(flight_data.data_header.as_slice()).map_err(|e| {
             InvalidFlightDataSnafu {
                 reason: e.to_string(),
@@ -102,7 +226,9 @@ impl FlightDecoder {
             MessageHeader::NONE => {
                 let ext_data = FlightDataExt::decode(flight_data.data_body.as_slice())
                     .context(DecodeFlightDataSnafu)?;
-                Ok(FlightMessage::AffectedRows(ext_data.affected_rows as _))
+                Ok(Some(FlightMessage::AffectedRows(
+                    ext_data.affected_rows as _,
+                )))
             }
             MessageHeader::Schema => {
                 let arrow_schema = ArrowSchema::try_from(&flight_data).map_err(|e| {
@@ -111,12 +237,56 @@ impl FlightDecoder {
                     }
                     .build()
                 })?;
-                let schema =
-                    Arc::new(Schema::try_from(arrow_schema).context(ConvertArrowSchemaSnafu)?);
+                let schema = Arc::new(
+                    Schema::try_from(arrow_schema.clone()).context(ConvertArrowSchemaSnafu)?,
+                );
+                // Some columns of supported types still can't be converted into vectors,
+                // e.g. lists of large strings, so rejects them before decoding any record
+                // batch.
+                if let Some(field) = arrow_schema
+                    .fields()
+                    .iter()
+                    .find(|field| !Helper::is_supported(field.data_type()))
+                {
+                    return InvalidFlightDataSnafu {
+                        reason: format!(
+                            "Unsupported data type {:?} of column {}",
+                            field.data_type(),
+                            field.name()
+                        ),
+                    }
+                    .fail();
+                }
 
                 self.schema = Some(schema.clone());
+                self.dictionaries_by_id.clear();
 
-                Ok(FlightMessage::Schema(schema))
+                Ok(Some(FlightMessage::Schema(schema)))
+            }
+            MessageHeader::DictionaryBatch => {
+                let schema = self.schema.as_ref().context(InvalidFlightDataSnafu {
+                    reason: "Should have decoded schema first!",
+                })?;
+                let dictionary_batch =
+                    message
+                        .header_as_dictionary_batch()
+                        .context(InvalidFlightDataSnafu {
+                            reason: "Invalid dictionary batch",
+                        })?;
+                reader::read_dictionary(
+                    &Buffer::from(flight_data.data_body.as_slice()),
+                    dictionary_batch,
+                    schema.arrow_schema(),
+                    &mut self.dictionaries_by_id,
+                    &message.version(),
+                )
+                .map_err(|e| {
+                    InvalidFlightDataSnafu {
+                        reason: e.to_string(),
+                    }
+                    .build()
+                })?;
+                Ok(None)
             }
             MessageHeader::RecordBatch => {
                 let schema = self.schema.clone().context(InvalidFlightDataSnafu {
@@ -124,17 +294,20 @@ impl FlightDecoder {
                 })?;
                 let arrow_schema = schema.arrow_schema().clone();
 
-                let arrow_batch =
-                    flight_data_to_arrow_batch(&flight_data, arrow_schema, &HashMap::new())
-                        .map_err(|e| {
-                            InvalidFlightDataSnafu {
-                                reason: e.to_string(),
-                            }
-                            .build()
-                        })?;
+                let arrow_batch = flight_data_to_arrow_batch(
+                    &flight_data,
+                    arrow_schema,
+                    &self.dictionaries_by_id,
+                )
+                .map_err(|e| {
+                    InvalidFlightDataSnafu {
+                        reason: e.to_string(),
+                    }
+                    .build()
+                })?;
                 let recordbatch = RecordBatch::try_from_df_record_batch(schema, arrow_batch)
                     .context(CreateRecordBatchSnafu)?;
-                Ok(FlightMessage::Recordbatch(recordbatch))
+                Ok(Some(FlightMessage::Recordbatch(recordbatch)))
             }
             other => {
                 let name = other.variant_name().unwrap_or("UNKNOWN");
@@ -174,7 +347,7 @@ pub fn raw_flight_data_to_message(raw_data: Vec<Vec<u8>>) -> Result<Vec<FlightMe
     let decoder = &mut FlightDecoder::default();
     flight_data
         .into_iter()
-        .map(|x| decoder.try_decode(x))
+        .filter_map(|x| decoder.try_decode(x).transpose())
         .collect()
 }
 
@@ -210,6 +383,14 @@ pub fn flight_messages_to_recordbatches(messages: Vec<FlightMessage>) -> Result<
     }
 }
 
+fn recordbatch_size(recordbatch: &RecordBatch) -> usize {
+    recordbatch
+        .columns()
+        .iter()
+        .map(|vector| vector.memory_size())
+        .sum()
+}
+
 fn build_none_flight_msg() -> Vec<u8> {
     let mut builder = FlatBufferBuilder::new();
 
@@ -227,7 +408,8 @@ fn build_none_flight_msg() -> Vec<u8> {
 #[cfg(test)]
 mod test {
     use arrow_flight::utils::batches_to_flight_data;
-    use datatypes::arrow::datatypes::{DataType, Field};
+    use common_error::prelude::ErrorExt;
+    use datatypes::arrow::datatypes::{DataType, Field, TimeUnit};
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::ColumnSchema;
     use datatypes::vectors::Int32Vector;
@@ -260,7 +442,9 @@ mod test {
         )
         .unwrap();
         assert_eq!(flight_data.len(), 3);
-        let [d1, d2, d3] = flight_data.as_slice() else { unreachable!() };
+        let [d1, d2, d3] = flight_data.as_slice() else {
+            unreachable!()
+        };
 
         let decoder = &mut FlightDecoder::default();
         assert!(decoder.schema.is_none());
@@ -272,24 +456,145 @@ mod test {
             .to_string()
             .contains("Should have decoded schema first!"));
 
-        let message = decoder.try_decode(d1.clone()).unwrap();
+        let message = decoder.try_decode(d1.clone()).unwrap().unwrap();
         assert!(matches!(message, FlightMessage::Schema(_)));
-        let FlightMessage::Schema(decoded_schema) = message else { unreachable!() };
+        let FlightMessage::Schema(decoded_schema) = message else {
+            unreachable!()
+        };
         assert_eq!(decoded_schema, schema);
 
         assert!(decoder.schema.is_some());
 
-        let message = decoder.try_decode(d2.clone()).unwrap();
+        let message = decoder.try_decode(d2.clone()).unwrap().unwrap();
         assert!(matches!(message, FlightMessage::Recordbatch(_)));
-        let FlightMessage::Recordbatch(actual_batch) = message else { unreachable!() };
+        let FlightMessage::Recordbatch(actual_batch) = message else {
+            unreachable!()
+        };
         assert_eq!(actual_batch, batch1);
 
-        let message = decoder.try_decode(d3.clone()).unwrap();
+        let message = decoder.try_decode(d3.clone()).unwrap().unwrap();
         assert!(matches!(message, FlightMessage::Recordbatch(_)));
-        let FlightMessage::Recordbatch(actual_batch) = message else { unreachable!() };
+        let FlightMessage::Recordbatch(actual_batch) = message else {
+            unreachable!()
+        };
         assert_eq!(actual_batch, batch2);
     }
 
+    #[test]
+    fn test_encode_compressed() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]));
+        let new_batch = |num_rows: i32| {
+            RecordBatch::new(
+                schema.clone(),
+                vec![Arc::new(Int32Vector::from_values(0..num_rows)) as _],
+            )
+            .unwrap()
+        };
+        let small_batch = new_batch(10);
+        let large_batch = new_batch(COMPRESSION_THRESHOLD as i32);
+
+        let mut encoder = FlightEncoder::default();
+        let mut compressed_encoder = FlightEncoder::with_compression();
+        let decoder = &mut FlightDecoder::default();
+        let _ = decoder
+            .try_decode(compressed_encoder.encode(FlightMessage::Schema(schema.clone()))[0].clone())
+            .unwrap();
+
+        for batch in [small_batch, large_batch] {
+            let plain = encoder
+                .encode(FlightMessage::Recordbatch(batch.clone()))
+                .remove(0);
+            let compressed = compressed_encoder
+                .encode(FlightMessage::Recordbatch(batch.clone()))
+                .remove(0);
+            if recordbatch_size(&batch) < COMPRESSION_THRESHOLD {
+                assert_eq!(plain.data_body.len(), compressed.data_body.len());
+            } else {
+                assert!(compressed.data_body.len() < plain.data_body.len());
+            }
+
+            let Some(FlightMessage::Recordbatch(decoded)) = decoder.try_decode(compressed).unwrap()
+            else {
+                unreachable!()
+            };
+            assert_eq!(batch, decoded);
+        }
+    }
+
+    #[test]
+    fn test_encode_chunked() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "n",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]));
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(Int32Vector::from_values(0..1000)) as _],
+        )
+        .unwrap();
+
+        // 4000 bytes of values are split into 4 chunks.
+        let mut encoder = FlightEncoder::default().with_max_flight_data_size(1024);
+        let decoder = &mut FlightDecoder::default();
+        let schema_data = encoder.encode(FlightMessage::Schema(schema.clone()));
+        assert_eq!(1, schema_data.len());
+        let _ = decoder.try_decode(schema_data[0].clone()).unwrap();
+
+        let flight_data = encoder.encode(FlightMessage::Recordbatch(batch.clone()));
+        assert_eq!(4, flight_data.len());
+        for (i, data) in flight_data.into_iter().enumerate() {
+            let Some(FlightMessage::Recordbatch(chunk)) = decoder.try_decode(data).unwrap() else {
+                unreachable!()
+            };
+            assert_eq!(batch.slice(i * 250, 250), chunk);
+        }
+    }
+
+    #[test]
+    fn test_request_compression() {
+        let mut metadata = MetadataMap::new();
+        assert!(!is_compression_requested(&metadata));
+
+        request_compression(&mut metadata);
+        assert!(is_compression_requested(&metadata));
+    }
+
+    #[test]
+    fn test_decode_unsupported_schema() {
+        let arrow_schema = ArrowSchema::new(vec![
+            Field::new("n", DataType::Int32, true),
+            Field::new("t", DataType::Time32(TimeUnit::Second), true),
+        ]);
+        let flight_data = batches_to_flight_data(arrow_schema, vec![]).unwrap();
+        assert_eq!(flight_data.len(), 1);
+
+        let decoder = &mut FlightDecoder::default();
+        let result = decoder.try_decode(flight_data[0].clone());
+        assert!(matches!(result, Err(Error::ConvertArrowSchema { .. })));
+        assert_eq!(StatusCode::Unsupported, result.unwrap_err().status_code());
+        assert!(decoder.schema.is_none());
+
+        // Lists of large strings have a data type, but can't be converted into vectors.
+        let arrow_schema = ArrowSchema::new(vec![Field::new(
+            "l",
+            DataType::List(Box::new(Field::new("item", DataType::LargeUtf8, true))),
+            true,
+        )]);
+        let flight_data = batches_to_flight_data(arrow_schema, vec![]).unwrap();
+        let result = decoder.try_decode(flight_data[0].clone());
+        assert!(matches!(result, Err(Error::InvalidFlightData { .. })));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported data type"));
+        assert!(decoder.schema.is_none());
+    }
+
     #[test]
     fn test_flight_messages_to_recordbatches() {
         let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
@@ -16,6 +16,7 @@ pub mod channel_manager;
 pub mod error;
 pub mod flight;
 pub mod select;
+pub mod tracing;
 pub mod writer;
 
 pub use error::Error;
@@ -17,9 +17,10 @@ use common_base::BitVec;
 use datatypes::types::{TimestampType, WrapperType};
 use datatypes::vectors::{
     BinaryVector, BooleanVector, DateTimeVector, DateVector, Float32Vector, Float64Vector,
-    Int16Vector, Int32Vector, Int64Vector, Int8Vector, StringVector, TimestampMicrosecondVector,
-    TimestampMillisecondVector, TimestampNanosecondVector, TimestampSecondVector, UInt16Vector,
-    UInt32Vector, UInt64Vector, UInt8Vector, VectorRef,
+    Int16Vector, Int32Vector, Int64Vector, Int8Vector, JsonVector, StringVector,
+    TimestampMicrosecondVector, TimestampMillisecondVector, TimestampNanosecondVector,
+    TimestampSecondVector, UInt16Vector, UInt32Vector, UInt64Vector, UInt8Vector, UuidVector,
+    VectorRef,
 };
 use snafu::OptionExt;
 
@@ -129,6 +130,12 @@ pub fn values(arrays: &[VectorRef]) -> Result<Values> {
             binary_values,
             |x| { x.into() }
         ),
+        (ConcreteDataType::Json(_), JsonVector, binary_values, |x| {
+            x.into()
+        }),
+        (ConcreteDataType::Uuid(_), UuidVector, binary_values, |x| {
+            x.into()
+        }),
         (
             ConcreteDataType::String(_),
             StringVector,
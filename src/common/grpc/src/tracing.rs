@@ -0,0 +1,62 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Propagation of the tracing context in the metadata of gRPC requests.
+
+use common_telemetry::tracing_context::{self, TraceHeaders};
+use tonic::metadata::{Ascii, KeyAndValueRef, MetadataKey, MetadataMap, MetadataValue};
+
+/// Injects the tracing context of the current span into the `metadata` of a request.
+pub fn inject_trace_context(metadata: &mut MetadataMap) {
+    for (key, value) in tracing_context::current_trace_headers() {
+        let key = MetadataKey::<Ascii>::from_bytes(key.as_bytes());
+        let value = value.parse::<MetadataValue<Ascii>>();
+        let (Ok(key), Ok(value)) = (key, value) else {
+            continue;
+        };
+        let _ = metadata.insert(key, value);
+    }
+}
+
+/// Returns the headers of the tracing context in the `metadata` of a request, see
+/// [tracing_context::set_remote_parent].
+pub fn extract_trace_headers(metadata: &MetadataMap) -> TraceHeaders {
+    metadata
+        .iter()
+        .filter_map(|kv| match kv {
+            KeyAndValueRef::Ascii(key, value) => value
+                .to_str()
+                .ok()
+                .map(|value| (key.as_str().to_string(), value.to_string())),
+            KeyAndValueRef::Binary(..) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_trace_headers() {
+        let mut metadata = MetadataMap::new();
+        let traceparent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+        let _ = metadata.insert("traceparent", traceparent.parse().unwrap());
+        let _ = metadata.insert_bin("x-bin", MetadataValue::from_bytes(b"bin"));
+
+        let headers = extract_trace_headers(&metadata);
+        assert_eq!(1, headers.len());
+        assert_eq!(traceparent, headers["traceparent"]);
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::time::Duration;
 
 use arrow::error::ArrowError;
 use common_error::prelude::*;
@@ -160,6 +161,40 @@ pub enum Error {
         err_msg: String,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Function {} panicked: {}", name, message))]
+    FunctionPanicked {
+        name: String,
+        message: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Function {} did not finish in {:?}", name, timeout))]
+    FunctionTimeout {
+        name: String,
+        timeout: Duration,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Function {} exceeded memory limit {} bytes, source: {}",
+        name,
+        limit,
+        source
+    ))]
+    FunctionMemoryExceeded {
+        name: String,
+        limit: usize,
+        source: DataFusionError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to join task of function {}, source: {}", name, source))]
+    JoinFunctionTask {
+        name: String,
+        source: tokio::task::JoinError,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -176,7 +211,14 @@ impl ErrorExt for Error {
             | Error::BadAccumulatorImpl { .. }
             | Error::ToScalarValue { .. }
             | Error::GetScalarVector { .. }
-            | Error::ArrowCompute { .. } => StatusCode::EngineExecuteQuery,
+            | Error::ArrowCompute { .. }
+            | Error::FunctionPanicked { .. } => StatusCode::EngineExecuteQuery,
+
+            Error::FunctionTimeout { .. } | Error::FunctionMemoryExceeded { .. } => {
+                StatusCode::RuntimeResourcesExhausted
+            }
+
+            Error::JoinFunctionTask { .. } => StatusCode::Internal,
 
             Error::InvalidInputType { source, .. }
             | Error::IntoVector { source, .. }
@@ -14,6 +14,7 @@
 
 pub mod accumulator;
 mod expr;
+pub mod guard;
 mod udaf;
 mod udf;
 
@@ -23,6 +24,7 @@ use datatypes::prelude::ConcreteDataType;
 
 pub use self::accumulator::{Accumulator, AggregateFunctionCreator, AggregateFunctionCreatorRef};
 pub use self::expr::{DfExpr, Expr};
+pub use self::guard::FunctionLimits;
 pub use self::udaf::AggregateFunction;
 pub use self::udf::ScalarUdf;
 use crate::function::{ReturnTypeFunction, ScalarFunctionImplementation};
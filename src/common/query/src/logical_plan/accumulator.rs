@@ -53,6 +53,12 @@ pub trait Accumulator: Send + Sync + Debug {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<Value>;
+
+    /// Returns the allocated size of the accumulator in bytes, including the memory
+    /// held by its state.
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
 }
 
 /// An `AggregateFunctionCreator` dynamically creates `Accumulator`.
@@ -172,7 +178,6 @@ impl DfAccumulator for DfAccumulatorAdaptor {
     }
 
     fn size(&self) -> usize {
-        // TODO(LFC): Implement new "size" method for Accumulator.
-        0
+        self.accumulator.size()
     }
 }
@@ -0,0 +1,580 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resource guards for user-defined functions.
+//!
+//! User-defined functions (e.g. scripted or WASM functions) are executed inside the
+//! query executor, a buggy one may loop forever, allocate too much memory or panic.
+//! The guards here run the function with limits and turn these failures into query
+//! errors.
+//!
+//! Memory of a function is accounted in a DataFusion [MemoryPool] of the function.
+//! Allocations inside the function can't be intercepted, so an invocation of a scalar
+//! function is accounted by the size of its arguments and result, and an accumulator
+//! by the size it reports.
+
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use datafusion::execution::memory_pool::{
+    GreedyMemoryPool, MemoryConsumer, MemoryPool, MemoryReservation,
+};
+use datatypes::prelude::*;
+use datatypes::vectors::VectorRef;
+use snafu::ResultExt;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::error::{self, Result};
+use crate::function::{AccumulatorFunctionImpl, ScalarFunctionImplementation};
+use crate::logical_plan::Accumulator;
+use crate::prelude::{ColumnarValue, ScalarValue};
+
+/// Default time limit of a single invocation.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default memory limit of a function (1 GiB).
+const DEFAULT_MEMORY_LIMIT: usize = 1024 * 1024 * 1024;
+
+/// Limits of executing a user-defined function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionLimits {
+    /// Time limit of a single invocation, `None` means no limit.
+    ///
+    /// A function with time limit is executed in the blocking thread pool of tokio while
+    /// the caller waits for it asynchronously. A function can't be interrupted, so the
+    /// one that times out keeps running in its thread until it returns, only its result
+    /// is dropped.
+    pub timeout: Option<Duration>,
+    /// Max bytes of memory held by the running invocations and the accumulators of a
+    /// function, `None` means no limit. The function fails once it exceeds the limit.
+    pub memory_limit: Option<usize>,
+}
+
+impl Default for FunctionLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Some(DEFAULT_TIMEOUT),
+            memory_limit: Some(DEFAULT_MEMORY_LIMIT),
+        }
+    }
+}
+
+impl FunctionLimits {
+    /// Limits that never stop the function.
+    pub fn unlimited() -> Self {
+        Self {
+            timeout: None,
+            memory_limit: None,
+        }
+    }
+
+    /// Returns the memory pool to account the memory of a function, `None` if the
+    /// memory is unlimited.
+    fn memory_pool(&self) -> Option<Arc<dyn MemoryPool>> {
+        self.memory_limit
+            .map(|limit| Arc::new(GreedyMemoryPool::new(limit)) as _)
+    }
+}
+
+/// Wraps the implementation of scalar function `name` with `limits`.
+pub fn guard_scalar_function(
+    name: &str,
+    fun: ScalarFunctionImplementation,
+    limits: FunctionLimits,
+) -> ScalarFunctionImplementation {
+    let name = name.to_string();
+    let memory_pool = limits.memory_pool();
+    Arc::new(move |args: &[ColumnarValue]| {
+        // Memory of the arguments and the result is released once the invocation
+        // returns, the caller takes over the result.
+        let mut reservation = memory_pool
+            .as_ref()
+            .map(|pool| MemoryConsumer::new(&name).register(pool));
+        let args_size = args.iter().map(columnar_value_size).sum();
+        reserve(&name, limits, reservation.as_mut(), args_size)?;
+
+        let fun = fun.clone();
+        let args = args.to_vec();
+        let result = run_guarded(&name, limits.timeout, move || fun(&args))?;
+        reserve(
+            &name,
+            limits,
+            reservation.as_mut(),
+            columnar_value_size(&result),
+        )?;
+        Ok(result)
+    })
+}
+
+/// Wraps the accumulators created by `accumulator` into [GuardedAccumulator]s.
+pub fn guard_accumulator_function(
+    name: &str,
+    accumulator: AccumulatorFunctionImpl,
+    limits: FunctionLimits,
+) -> AccumulatorFunctionImpl {
+    let name = name.to_string();
+    let memory_pool = limits.memory_pool();
+    Arc::new(move || {
+        let inner = accumulator()?;
+        let reservation = memory_pool
+            .as_ref()
+            .map(|pool| MemoryConsumer::new(&name).register(pool));
+        Ok(Box::new(GuardedAccumulator::new(&name, inner, limits, reservation)) as _)
+    })
+}
+
+/// An accumulator runs every call of the inner accumulator under [FunctionLimits].
+///
+/// The memory of the accumulator is reserved until it's dropped, and is resized to
+/// the size of the inner accumulator after each update.
+pub struct GuardedAccumulator {
+    name: String,
+    inner: Arc<Mutex<Box<dyn Accumulator>>>,
+    limits: FunctionLimits,
+    reservation: Option<MemoryReservation>,
+}
+
+impl fmt::Debug for GuardedAccumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GuardedAccumulator")
+            .field("name", &self.name)
+            .field("inner", &self.inner)
+            .field("limits", &self.limits)
+            .field(
+                "reserved",
+                &self.reservation.as_ref().map(MemoryReservation::size),
+            )
+            .finish()
+    }
+}
+
+impl GuardedAccumulator {
+    pub fn new(
+        name: &str,
+        inner: Box<dyn Accumulator>,
+        limits: FunctionLimits,
+        reservation: Option<MemoryReservation>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            inner: Arc::new(Mutex::new(inner)),
+            limits,
+            reservation,
+        }
+    }
+
+    fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn Accumulator) -> Result<T> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        let name = self.name.clone();
+        run_guarded(&self.name, self.limits.timeout, move || {
+            // The lock is poisoned if the accumulator panicked before, its state is no
+            // longer reliable.
+            let mut accumulator = inner.lock().map_err(|_| {
+                error::FunctionPanickedSnafu {
+                    name,
+                    message: "accumulator is poisoned by a previous panic",
+                }
+                .build()
+            })?;
+            f(accumulator.as_mut())
+        })
+    }
+
+    /// Calls `f` to update the inner accumulator and resizes the reservation to the
+    /// new size of the accumulator.
+    fn update<F>(&mut self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut dyn Accumulator) -> Result<()> + Send + 'static,
+    {
+        let size = self.call(move |acc| {
+            f(acc)?;
+            Ok(acc.size())
+        })?;
+        let Some(reservation) = &mut self.reservation else {
+            return Ok(());
+        };
+        reservation
+            .try_resize(size)
+            .context(error::FunctionMemoryExceededSnafu {
+                name: &self.name,
+                limit: self.limits.memory_limit.unwrap_or_default(),
+            })
+    }
+}
+
+impl Accumulator for GuardedAccumulator {
+    fn state(&self) -> Result<Vec<Value>> {
+        self.call(|acc| acc.state())
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        let values = values.to_vec();
+        self.update(move |acc| acc.update_batch(&values))
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        let states = states.to_vec();
+        self.update(move |acc| acc.merge_batch(&states))
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        self.call(|acc| acc.evaluate())
+    }
+
+    fn size(&self) -> usize {
+        self.reservation
+            .as_ref()
+            .map(MemoryReservation::size)
+            .unwrap_or_else(|| std::mem::size_of_val(self))
+    }
+}
+
+/// Grows the `reservation` of function `name` by `size` bytes.
+fn reserve(
+    name: &str,
+    limits: FunctionLimits,
+    reservation: Option<&mut MemoryReservation>,
+    size: usize,
+) -> Result<()> {
+    let Some(reservation) = reservation else {
+        return Ok(());
+    };
+    reservation
+        .try_grow(size)
+        .context(error::FunctionMemoryExceededSnafu {
+            name,
+            limit: limits.memory_limit.unwrap_or_default(),
+        })
+}
+
+fn columnar_value_size(value: &ColumnarValue) -> usize {
+    match value {
+        ColumnarValue::Vector(vector) => vector.memory_size(),
+        ColumnarValue::Scalar(_) => std::mem::size_of::<ScalarValue>(),
+    }
+}
+
+/// Runs `f` of function `name`, converts panics into errors. If `timeout` is set, `f`
+/// is executed in a blocking thread and an error is returned once it times out.
+fn run_guarded<T, F>(name: &str, timeout: Option<Duration>, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let Some(timeout) = timeout else {
+        return catch_panic(name, f);
+    };
+
+    let task = run_with_timeout(name.to_string(), timeout, f);
+    match Handle::try_current() {
+        // Functions are called by the executor in a worker thread of the runtime, lets
+        // the runtime move other tasks off the worker while waiting.
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(task))
+        }
+        // The timer of a current thread runtime can't fire while its only thread is
+        // waiting, so the function is waited in the global runtime.
+        _ => futures::executor::block_on(common_runtime::spawn_bg(task))
+            .context(error::JoinFunctionTaskSnafu { name })?,
+    }
+}
+
+async fn run_with_timeout<T, F>(name: String, timeout: Duration, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let job_cancelled = cancelled.clone();
+    let job_name = name.clone();
+    let job = tokio::task::spawn_blocking(move || {
+        // Skips the function if the caller has stopped waiting before a thread is free.
+        if job_cancelled.load(Ordering::Relaxed) {
+            return error::FunctionTimeoutSnafu {
+                name: job_name,
+                timeout,
+            }
+            .fail();
+        }
+        catch_panic(&job_name, f)
+    });
+
+    match tokio::time::timeout(timeout, job).await {
+        Ok(result) => result.context(error::JoinFunctionTaskSnafu { name })?,
+        Err(_) => {
+            cancelled.store(true, Ordering::Relaxed);
+            error::FunctionTimeoutSnafu { name, timeout }.fail()
+        }
+    }
+}
+
+fn catch_panic<T, F>(name: &str, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        error::FunctionPanickedSnafu {
+            name,
+            message: panic_message(payload.as_ref()),
+        }
+        .fail()
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use common_error::prelude::*;
+    use datatypes::vectors::Int32Vector;
+
+    use super::*;
+
+    fn new_vector(len: usize) -> ColumnarValue {
+        ColumnarValue::Vector(Arc::new(Int32Vector::from_vec(vec![1; len])))
+    }
+
+    fn sleep_function(duration: Duration) -> ScalarFunctionImplementation {
+        Arc::new(move |args| {
+            thread::sleep(duration);
+            Ok(args[0].clone())
+        })
+    }
+
+    #[test]
+    fn test_guard_scalar_function() {
+        let limits = FunctionLimits {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+
+        let fun: ScalarFunctionImplementation = Arc::new(|args| Ok(args[0].clone()));
+        let guarded = guard_scalar_function("echo", fun, limits);
+        assert!(guarded(&[new_vector(10)]).is_ok());
+
+        let fun: ScalarFunctionImplementation = Arc::new(|_| panic!("boom"));
+        let guarded = guard_scalar_function("boom", fun, limits);
+        let err = guarded(&[]).unwrap_err();
+        assert!(
+            matches!(&err, error::Error::FunctionPanicked { name, message, .. } if name == "boom" && message == "boom")
+        );
+
+        let guarded =
+            guard_scalar_function("sleep", sleep_function(Duration::from_secs(1)), limits);
+        let err = guarded(&[new_vector(1)]).unwrap_err();
+        assert!(matches!(err, error::Error::FunctionTimeout { .. }));
+        assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_hung_functions_not_starve_others() {
+        let limits = FunctionLimits {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let hung = guard_scalar_function("hung", sleep_function(Duration::from_secs(1)), limits);
+
+        // Hangs more functions than the CPUs, each keeps a blocking thread after timeout.
+        let num_cpus = thread::available_parallelism().unwrap().get();
+        let hung_calls = (0..num_cpus * 2)
+            .map(|_| {
+                let hung = hung.clone();
+                tokio::spawn(async move { hung(&[new_vector(1)]) })
+            })
+            .collect::<Vec<_>>();
+        for call in hung_calls {
+            let err = call.await.unwrap().unwrap_err();
+            assert!(matches!(err, error::Error::FunctionTimeout { .. }));
+        }
+
+        let fun: ScalarFunctionImplementation = Arc::new(|args| Ok(args[0].clone()));
+        let quick = guard_scalar_function("quick", fun, limits);
+        assert!(quick(&[new_vector(1)]).is_ok());
+    }
+
+    #[test]
+    fn test_scalar_function_memory_limit() {
+        let limits = FunctionLimits {
+            memory_limit: Some(1024),
+            ..Default::default()
+        };
+        let fun: ScalarFunctionImplementation = Arc::new(|args| Ok(args[0].clone()));
+        let guarded = guard_scalar_function("echo", fun.clone(), limits);
+        assert!(guarded(&[new_vector(10)]).is_ok());
+        // Memory is released after each invocation.
+        assert!(guarded(&[new_vector(10)]).is_ok());
+
+        let err = guarded(&[new_vector(1024)]).unwrap_err();
+        assert!(
+            matches!(&err, error::Error::FunctionMemoryExceeded { name, limit, .. } if name == "echo" && *limit == 1024)
+        );
+        assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+
+        // The result is accounted too.
+        let fun: ScalarFunctionImplementation = Arc::new(|_| Ok(new_vector(1024)));
+        let guarded = guard_scalar_function("large", fun, limits);
+        let err = guarded(&[]).unwrap_err();
+        assert!(matches!(err, error::Error::FunctionMemoryExceeded { .. }));
+    }
+
+    #[derive(Debug, Default)]
+    struct PanicAccumulator {
+        rows: usize,
+    }
+
+    impl Accumulator for PanicAccumulator {
+        fn state(&self) -> Result<Vec<Value>> {
+            Ok(vec![Value::from(self.rows as u64)])
+        }
+
+        fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+            self.rows += values[0].len();
+            if self.rows > 3 {
+                panic!("too many rows: {}", self.rows);
+            }
+            Ok(())
+        }
+
+        fn merge_batch(&mut self, _: &[VectorRef]) -> Result<()> {
+            thread::sleep(Duration::from_secs(1));
+            Ok(())
+        }
+
+        fn evaluate(&self) -> Result<Value> {
+            Ok(Value::from(self.rows as u64))
+        }
+    }
+
+    #[test]
+    fn test_guarded_accumulator() {
+        let limits = FunctionLimits {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let accumulator: AccumulatorFunctionImpl =
+            Arc::new(|| Ok(Box::new(PanicAccumulator::default()) as _));
+        let accumulator = guard_accumulator_function("my_udaf", accumulator, limits);
+
+        let mut acc = accumulator().unwrap();
+        let values: Vec<VectorRef> = vec![Arc::new(Int32Vector::from_vec(vec![1, 2]))];
+        acc.update_batch(&values).unwrap();
+        assert_eq!(Value::from(2u64), acc.evaluate().unwrap());
+
+        let err = acc.update_batch(&values).unwrap_err();
+        assert!(
+            matches!(&err, error::Error::FunctionPanicked { name, message, .. } if name == "my_udaf" && message == "too many rows: 4")
+        );
+        // The accumulator is not usable after panic.
+        let err = acc.evaluate().unwrap_err();
+        assert!(matches!(&err, error::Error::FunctionPanicked { name, .. } if name == "my_udaf"));
+
+        let mut acc = accumulator().unwrap();
+        let err = acc.merge_batch(&values).unwrap_err();
+        assert!(matches!(err, error::Error::FunctionTimeout { .. }));
+    }
+
+    /// An accumulator keeps all the values it receives.
+    #[derive(Debug, Default)]
+    struct CollectAccumulator {
+        values: Vec<VectorRef>,
+    }
+
+    impl Accumulator for CollectAccumulator {
+        fn state(&self) -> Result<Vec<Value>> {
+            Ok(vec![])
+        }
+
+        fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+            self.values.extend_from_slice(values);
+            Ok(())
+        }
+
+        fn merge_batch(&mut self, _: &[VectorRef]) -> Result<()> {
+            Ok(())
+        }
+
+        fn evaluate(&self) -> Result<Value> {
+            Ok(Value::from(self.values.len() as u64))
+        }
+
+        fn size(&self) -> usize {
+            self.values.iter().map(|v| v.memory_size()).sum()
+        }
+    }
+
+    #[test]
+    fn test_accumulator_memory_limit() {
+        let limits = FunctionLimits {
+            memory_limit: Some(1024),
+            ..Default::default()
+        };
+        let accumulator: AccumulatorFunctionImpl =
+            Arc::new(|| Ok(Box::new(CollectAccumulator::default()) as _));
+        let accumulator = guard_accumulator_function("collect", accumulator, limits);
+
+        let values: Vec<VectorRef> = vec![Arc::new(Int32Vector::from_vec(vec![1; 64]))];
+        let mut acc = accumulator().unwrap();
+        acc.update_batch(&values).unwrap();
+        let size = acc.size();
+        assert!(size > 0);
+
+        // Accumulators of the same function share the limit.
+        let mut another = accumulator().unwrap();
+        for _ in 0..(1024 / size) {
+            if let Err(err) = another.update_batch(&values) {
+                assert!(matches!(err, error::Error::FunctionMemoryExceeded { .. }));
+                break;
+            }
+        }
+        let err = acc.update_batch(&values).unwrap_err();
+        assert!(matches!(err, error::Error::FunctionMemoryExceeded { .. }));
+
+        // Memory is released once the accumulators are dropped.
+        drop(another);
+        drop(acc);
+        let mut acc = accumulator().unwrap();
+        acc.update_batch(&values).unwrap();
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let fun: ScalarFunctionImplementation = Arc::new(|_| panic!("boom"));
+        let guarded = guard_scalar_function("boom", fun, FunctionLimits::unlimited());
+        let err = guarded(&[]).unwrap_err();
+        assert!(matches!(err, error::Error::FunctionPanicked { .. }));
+        assert!(guard_scalar_function(
+            "echo",
+            Arc::new(|args| Ok(args[0].clone())),
+            FunctionLimits::unlimited()
+        )(&[new_vector(1 << 20)])
+        .is_ok());
+    }
+}
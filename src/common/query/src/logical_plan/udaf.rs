@@ -30,6 +30,7 @@ use crate::function::{
     to_df_return_type, AccumulatorFunctionImpl, ReturnTypeFunction, StateTypeFunction,
 };
 use crate::logical_plan::accumulator::DfAccumulatorAdaptor;
+use crate::logical_plan::guard::{guard_accumulator_function, FunctionLimits};
 use crate::logical_plan::AggregateFunctionCreatorRef;
 use crate::signature::Signature;
 
@@ -86,6 +87,15 @@ impl AggregateFunction {
             creator,
         }
     }
+
+    /// Runs the accumulators of this UDAF under `limits`.
+    pub fn with_limits(self, limits: FunctionLimits) -> Self {
+        let accumulator = guard_accumulator_function(&self.name, self.accumulator, limits);
+        Self {
+            accumulator,
+            ..self
+        }
+    }
 }
 
 impl From<AggregateFunction> for DfAggregateUdf {
@@ -25,6 +25,7 @@ use datafusion_expr::{
 
 use crate::error::Result;
 use crate::function::{ReturnTypeFunction, ScalarFunctionImplementation};
+use crate::logical_plan::guard::{guard_scalar_function, FunctionLimits};
 use crate::prelude::to_df_return_type;
 use crate::signature::Signature;
 
@@ -67,6 +68,12 @@ impl ScalarUdf {
         }
     }
 
+    /// Runs the implementation of this UDF under `limits`.
+    pub fn with_limits(self, limits: FunctionLimits) -> Self {
+        let fun = guard_scalar_function(&self.name, self.fun, limits);
+        Self { fun, ..self }
+    }
+
     /// Cast self into datafusion UDF.
     pub fn into_df_udf(self) -> DfScalarUDF {
         DfScalarUDF::new(
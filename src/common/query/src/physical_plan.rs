@@ -21,9 +21,9 @@ use common_recordbatch::{DfSendableRecordBatchStream, SendableRecordBatchStream}
 use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
 use datafusion::error::Result as DfResult;
 pub use datafusion::execution::context::{SessionContext, TaskContext};
-use datafusion::physical_plan::expressions::PhysicalSortExpr;
-pub use datafusion::physical_plan::Partitioning;
-use datafusion::physical_plan::Statistics;
+use datafusion::physical_expr::EquivalenceProperties;
+use datafusion::physical_plan::expressions::{Column, PhysicalSortExpr};
+pub use datafusion::physical_plan::{ColumnStatistics, Partitioning, Statistics};
 use datatypes::schema::SchemaRef;
 use snafu::ResultExt;
 
@@ -49,6 +49,20 @@ pub trait PhysicalPlan: Debug + Send + Sync {
     /// Specifies the output partitioning scheme of this plan
     fn output_partitioning(&self) -> Partitioning;
 
+    /// Returns the order of rows in each partition of the output, `None` if the rows
+    /// are not sorted. The optimizer could skip sorting the rows if they are already
+    /// in the expected order.
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    /// Returns the columns known to have equal values in the output, e.g. columns joined
+    /// by an equi-join. Sorting by one of the columns also sorts the others. No columns
+    /// are equivalent by default.
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        EquivalenceProperties::new(self.schema().arrow_schema().clone())
+    }
+
     /// Get a list of child physical plans that provide the input for this plan. The returned list
     /// will be empty for leaf nodes, will contain a single value for unary nodes, or two
     /// values for binary nodes (such as joins).
@@ -64,17 +78,32 @@ pub trait PhysicalPlan: Debug + Send + Sync {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream>;
+
+    /// Returns statistics of the output of this plan, used by the optimizer to choose
+    /// plans (e.g. the build side of a join). Statistics are unknown by default.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
 }
 
 #[derive(Debug)]
 pub struct PhysicalPlanAdapter {
     schema: SchemaRef,
     df_plan: Arc<dyn DfPhysicalPlan>,
+    /// Output ordering of `df_plan`, with columns mapped to `schema`.
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
 }
 
 impl PhysicalPlanAdapter {
     pub fn new(schema: SchemaRef, df_plan: Arc<dyn DfPhysicalPlan>) -> Self {
-        Self { schema, df_plan }
+        let output_ordering = df_plan
+            .output_ordering()
+            .and_then(|ordering| map_sort_exprs(ordering, &schema));
+        Self {
+            schema,
+            df_plan,
+            output_ordering,
+        }
     }
 
     pub fn df_plan(&self) -> Arc<dyn DfPhysicalPlan> {
@@ -95,6 +124,14 @@ impl PhysicalPlan for PhysicalPlanAdapter {
         self.df_plan.output_partitioning()
     }
 
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_ordering.as_deref()
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.df_plan.equivalence_properties()
+    }
+
     fn children(&self) -> Vec<PhysicalPlanRef> {
         self.df_plan
             .children()
@@ -130,6 +167,10 @@ impl PhysicalPlan for PhysicalPlanAdapter {
 
         Ok(Box::pin(adapter))
     }
+
+    fn statistics(&self) -> Statistics {
+        self.df_plan.statistics()
+    }
 }
 
 #[derive(Debug)]
@@ -149,7 +190,11 @@ impl DfPhysicalPlan for DfPhysicalPlanAdapter {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.0.output_ordering()
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.0.equivalence_properties()
     }
 
     fn children(&self) -> Vec<Arc<dyn DfPhysicalPlan>> {
@@ -188,8 +233,33 @@ impl DfPhysicalPlan for DfPhysicalPlanAdapter {
     }
 
     fn statistics(&self) -> Statistics {
-        // TODO(LFC): impl statistics
-        Statistics::default()
+        self.0.statistics()
+    }
+}
+
+/// Maps the columns of `sort_exprs` to the columns with the same names in `schema`, as
+/// the index of a column may differ between our schema and the schema of DataFusion.
+///
+/// Returns the leading sort expressions that could be mapped, `None` if there is none.
+fn map_sort_exprs(
+    sort_exprs: &[PhysicalSortExpr],
+    schema: &SchemaRef,
+) -> Option<Vec<PhysicalSortExpr>> {
+    let mapped = sort_exprs
+        .iter()
+        .map_while(|sort_expr| {
+            let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+            let index = schema.column_index_by_name(column.name())?;
+            Some(PhysicalSortExpr {
+                expr: Arc::new(Column::new(column.name(), index)),
+                options: sort_expr.options,
+            })
+        })
+        .collect::<Vec<_>>();
+    if mapped.is_empty() {
+        None
+    } else {
+        Some(mapped)
     }
 }
 
@@ -197,10 +267,12 @@ impl DfPhysicalPlan for DfPhysicalPlanAdapter {
 mod test {
     use async_trait::async_trait;
     use common_recordbatch::{RecordBatch, RecordBatches};
+    use datafusion::arrow::compute::SortOptions;
     use datafusion::datasource::{DefaultTableSource, TableProvider as DfTableProvider, TableType};
     use datafusion::execution::context::{SessionContext, SessionState};
     use datafusion::physical_plan::collect;
     use datafusion::physical_plan::empty::EmptyExec;
+    use datafusion::physical_plan::memory::MemoryExec;
     use datafusion_expr::logical_plan::builder::LogicalPlanBuilder;
     use datafusion_expr::{Expr, TableSource};
     use datatypes::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
@@ -345,8 +417,61 @@ mod test {
             Arc::new(EmptyExec::new(true, df_schema.clone())),
         );
         assert!(plan.df_plan.as_any().downcast_ref::<EmptyExec>().is_some());
+        // Statistics are forwarded through adapters.
+        assert_eq!(Some(1), plan.statistics().num_rows);
 
         let df_plan = DfPhysicalPlanAdapter(Arc::new(plan));
         assert_eq!(df_schema, df_plan.schema());
+        assert_eq!(Some(1), df_plan.statistics().num_rows);
+    }
+
+    #[test]
+    fn test_adapter_output_ordering() {
+        let df_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let sort_expr = |name, index| PhysicalSortExpr {
+            expr: Arc::new(Column::new(name, index)),
+            options: SortOptions::default(),
+        };
+        let df_plan = Arc::new(
+            MemoryExec::try_new(&[vec![]], df_schema.clone(), None)
+                .unwrap()
+                .with_sort_information(vec![sort_expr("b", 1), sort_expr("a", 0)]),
+        );
+
+        // Columns of the sort expressions are mapped to our schema by name.
+        let schema = Arc::new(
+            Schema::try_from(Arc::new(ArrowSchema::new(vec![
+                Field::new("b", DataType::Int32, false),
+                Field::new("a", DataType::Int32, false),
+            ])))
+            .unwrap(),
+        );
+        let plan = Arc::new(PhysicalPlanAdapter::new(schema, df_plan.clone()));
+        let ordering = plan.output_ordering().unwrap();
+        assert_eq!("b@0", ordering[0].expr.to_string());
+        assert_eq!("a@1", ordering[1].expr.to_string());
+
+        // Only the leading columns existing in our schema are kept.
+        let schema = Arc::new(
+            Schema::try_from(Arc::new(ArrowSchema::new(vec![Field::new(
+                "b",
+                DataType::Int32,
+                false,
+            )])))
+            .unwrap(),
+        );
+        let ordering = PhysicalPlanAdapter::new(schema, df_plan)
+            .output_ordering()
+            .unwrap()
+            .to_vec();
+        assert_eq!(1, ordering.len());
+        assert_eq!("b@0", ordering[0].expr.to_string());
+
+        let df_plan = DfPhysicalPlanAdapter(plan);
+        assert_eq!(2, df_plan.output_ordering().unwrap().len());
+        assert!(df_plan.equivalence_properties().classes().is_empty());
     }
 }
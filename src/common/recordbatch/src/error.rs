@@ -70,6 +70,24 @@ pub enum Error {
         source: datafusion_common::DataFusionError,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("IO error on spill file, source: {}", source))]
+    SpillIo {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write record batches to spill file, source: {}", source))]
+    WriteSpill {
+        source: datatypes::arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to read record batches from spill file, source: {}", source))]
+    ReadSpill {
+        source: datatypes::arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for Error {
@@ -81,7 +99,11 @@ impl ErrorExt for Error {
             | Error::CreateRecordBatches { .. }
             | Error::PollStream { .. }
             | Error::Format { .. }
-            | Error::InitRecordbatchStream { .. } => StatusCode::Internal,
+            | Error::InitRecordbatchStream { .. }
+            | Error::WriteSpill { .. }
+            | Error::ReadSpill { .. } => StatusCode::Internal,
+
+            Error::SpillIo { .. } => StatusCode::StorageUnavailable,
 
             Error::External { source } => source.status_code(),
 
@@ -15,6 +15,7 @@
 pub mod adapter;
 pub mod error;
 mod recordbatch;
+pub mod spill;
 pub mod util;
 
 use std::pin::Pin;
@@ -99,14 +100,50 @@ impl RecordBatches {
         self.batches.iter()
     }
 
-    pub fn pretty_print(&self) -> Result<String> {
-        let df_batches = &self
-            .iter()
-            .map(|x| x.df_record_batch().clone())
-            .collect::<Vec<_>>();
-        let result = pretty::pretty_format_batches(df_batches).context(error::FormatSnafu)?;
+    /// Returns the total number of rows.
+    pub fn num_rows(&self) -> usize {
+        self.batches.iter().map(|batch| batch.num_rows()).sum()
+    }
 
-        Ok(result.to_string())
+    /// Formats the batches as a table. Only the first `max_rows` rows are formatted if
+    /// `max_rows` is set, with a line telling the number of rows omitted.
+    pub fn pretty_print(&self, max_rows: Option<usize>) -> Result<String> {
+        let num_rows = self.num_rows();
+        let Some(max_rows) = max_rows.filter(|max_rows| *max_rows < num_rows) else {
+            return format_batches(&self.batches);
+        };
+
+        let head = if max_rows == 0 {
+            Vec::new()
+        } else {
+            self.pages(max_rows)
+                .next()
+                .map(RecordBatches::take)
+                .unwrap_or_default()
+        };
+        let table = format_batches(&head)?;
+        Ok(format!(
+            "{table}\n... {} more rows not shown",
+            num_rows - max_rows
+        ))
+    }
+
+    /// Returns an iterator that yields pages with `page_size` rows, except the last
+    /// page which may be smaller. Rows in a page may come from multiple batches, batches
+    /// are sliced instead of copied.
+    ///
+    /// # Panics
+    /// Panics if `page_size` is 0.
+    pub fn pages(&self, page_size: usize) -> RecordBatchPages<'_> {
+        assert!(page_size > 0, "page size must be positive");
+
+        RecordBatchPages {
+            schema: self.schema.clone(),
+            batches: &self.batches,
+            page_size,
+            batch_index: 0,
+            offset: 0,
+        }
     }
 
     pub fn try_new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Result<Self> {
@@ -156,6 +193,61 @@ impl RecordBatches {
     }
 }
 
+fn format_batches(batches: &[RecordBatch]) -> Result<String> {
+    let df_batches = batches
+        .iter()
+        .map(|x| x.df_record_batch().clone())
+        .collect::<Vec<_>>();
+    let result = pretty::pretty_format_batches(&df_batches).context(error::FormatSnafu)?;
+
+    Ok(result.to_string())
+}
+
+/// Iterator over pages of [RecordBatches], created by [RecordBatches::pages].
+pub struct RecordBatchPages<'a> {
+    schema: SchemaRef,
+    batches: &'a [RecordBatch],
+    page_size: usize,
+    /// Index of the batch to read next.
+    batch_index: usize,
+    /// Offset of the next row in the batch to read.
+    offset: usize,
+}
+
+impl<'a> Iterator for RecordBatchPages<'a> {
+    type Item = RecordBatches;
+
+    fn next(&mut self) -> Option<RecordBatches> {
+        let mut page = Vec::new();
+        let mut remaining = self.page_size;
+        while remaining > 0 && self.batch_index < self.batches.len() {
+            let batch = &self.batches[self.batch_index];
+            let num_rows = (batch.num_rows() - self.offset).min(remaining);
+            if num_rows == batch.num_rows() {
+                page.push(batch.clone());
+            } else if num_rows > 0 {
+                page.push(batch.slice(self.offset, num_rows));
+            }
+
+            remaining -= num_rows;
+            self.offset += num_rows;
+            if self.offset == batch.num_rows() {
+                self.batch_index += 1;
+                self.offset = 0;
+            }
+        }
+
+        if page.is_empty() {
+            None
+        } else {
+            Some(RecordBatches {
+                schema: self.schema.clone(),
+                batches: page,
+            })
+        }
+    }
+}
+
 pub struct SimpleRecordBatchStream {
     inner: RecordBatches,
     index: usize,
@@ -187,6 +279,7 @@ mod tests {
 
     use datatypes::prelude::{ConcreteDataType, VectorRef};
     use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::value::Value;
     use datatypes::vectors::{BooleanVector, Int32Vector, StringVector};
 
     use super::*;
@@ -243,7 +336,7 @@ mod tests {
 | 1 | hello |
 | 2 | world |
 +---+-------+";
-        assert_eq!(batches.pretty_print().unwrap(), expected);
+        assert_eq!(batches.pretty_print(None).unwrap(), expected);
 
         assert_eq!(schema1, batches.schema());
         assert_eq!(vec![batch1], batches.take());
@@ -271,4 +364,86 @@ mod tests {
         assert_eq!(collected[0], batch1);
         assert_eq!(collected[1], batch2);
     }
+
+    fn new_batches(schema: &SchemaRef, sizes: &[usize]) -> RecordBatches {
+        let mut start = 0;
+        let batches = sizes
+            .iter()
+            .map(|size| {
+                let values: Vec<i32> = (start..start + *size as i32).collect();
+                start += *size as i32;
+                RecordBatch::new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Vector::from_vec(values)) as _],
+                )
+                .unwrap()
+            })
+            .collect();
+        RecordBatches::try_new(schema.clone(), batches).unwrap()
+    }
+
+    fn collect_values(batches: &RecordBatches) -> Vec<i32> {
+        batches
+            .iter()
+            .flat_map(|batch| batch.rows())
+            .map(|row| match row[0] {
+                Value::Int32(v) => v,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recordbatches_pages() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batches = new_batches(&schema, &[3, 0, 2, 4]);
+        assert_eq!(9, batches.num_rows());
+
+        let pages: Vec<_> = batches.pages(4).collect();
+        assert_eq!(3, pages.len());
+        assert_eq!(vec![0, 1, 2, 3], collect_values(&pages[0]));
+        assert_eq!(vec![4, 5, 6, 7], collect_values(&pages[1]));
+        assert_eq!(vec![8], collect_values(&pages[2]));
+        for page in &pages {
+            assert_eq!(schema, page.schema());
+        }
+
+        // Batches are kept as they are if they fit in a page.
+        let pages: Vec<_> = batches.pages(100).collect();
+        assert_eq!(1, pages.len());
+        assert_eq!(3, pages[0].iter().count());
+        assert_eq!((0..9).collect::<Vec<_>>(), collect_values(&pages[0]));
+
+        assert_eq!(0, RecordBatches::empty().pages(1).count());
+    }
+
+    #[test]
+    fn test_pretty_print_max_rows() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        let batches = new_batches(&schema, &[2, 2]);
+
+        let expected = "\
++---+
+| a |
++---+
+| 0 |
+| 1 |
+| 2 |
++---+
+... 1 more rows not shown";
+        assert_eq!(expected, batches.pretty_print(Some(3)).unwrap());
+
+        assert_eq!(
+            batches.pretty_print(None).unwrap(),
+            batches.pretty_print(Some(4)).unwrap()
+        );
+    }
 }
@@ -108,6 +108,25 @@ impl RecordBatch {
     pub fn rows(&self) -> RecordBatchRowIterator<'_> {
         RecordBatchRowIterator::new(self)
     }
+
+    /// Returns a zero-copy slice of this batch with `length` rows starting from
+    /// `offset`.
+    ///
+    /// # Panics
+    /// Panics if `offset + length` is greater than the number of rows.
+    pub fn slice(&self, offset: usize, length: usize) -> RecordBatch {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| column.slice(offset, length))
+            .collect();
+
+        RecordBatch {
+            schema: self.schema.clone(),
+            columns,
+            df_record_batch: self.df_record_batch.slice(offset, length),
+        }
+    }
 }
 
 impl Serialize for RecordBatch {
@@ -212,6 +231,40 @@ mod tests {
         assert_eq!(*batch.df_record_batch(), converted.into_df_record_batch());
     }
 
+    #[test]
+    fn test_slice_record_batch() {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("c1", DataType::UInt32, false),
+            Field::new("c2", DataType::Utf8, true),
+        ]));
+        let schema = Arc::new(Schema::try_from(arrow_schema).unwrap());
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_slice(&[1, 2, 3, 4])),
+            Arc::new(StringVector::from(vec![
+                Some("a"),
+                None,
+                Some("c"),
+                Some("d"),
+            ])),
+        ];
+        let batch = RecordBatch::new(schema.clone(), columns).unwrap();
+
+        let sliced = batch.slice(1, 2);
+        let expect = RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(UInt32Vector::from_slice(&[2, 3])) as _,
+                Arc::new(StringVector::from(vec![None, Some("c")])) as _,
+            ],
+        )
+        .unwrap();
+        assert_eq!(2, sliced.num_rows());
+        assert_eq!(expect.columns(), sliced.columns());
+        assert_eq!(expect.df_record_batch(), sliced.df_record_batch());
+
+        assert_eq!(0, batch.slice(4, 0).num_rows());
+    }
+
     #[test]
     pub fn test_serialize_recordbatch() {
         let column_schemas = vec![ColumnSchema::new(
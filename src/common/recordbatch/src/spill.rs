@@ -0,0 +1,252 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Collects record batches under a memory budget, batches exceeding the budget are
+//! spilled to temporary Arrow IPC files.
+
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::path::PathBuf;
+
+use datatypes::arrow::ipc::reader::FileReader;
+use datatypes::arrow::ipc::writer::FileWriter;
+use datatypes::schema::SchemaRef;
+use futures::TryStreamExt;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::{RecordBatch, SendableRecordBatchStream};
+
+/// A collector that buffers record batches in memory until their size exceeds
+/// `memory_limit`, then writes buffered batches to a temporary file under `spill_dir`.
+///
+/// Spill files are anonymous temporary files, they are removed once the collector or
+/// the iterator returned by [SpillableCollector::into_batches] is dropped. Note that
+/// spill files are written and read synchronously.
+pub struct SpillableCollector {
+    schema: SchemaRef,
+    memory_limit: usize,
+    spill_dir: PathBuf,
+    /// Memory size of batches in `buffered`.
+    memory_used: usize,
+    /// Batches not spilled yet, they come after batches in spill files.
+    buffered: Vec<RecordBatch>,
+    /// Spill files in the order they are written, each file is rewound to the start.
+    spilled: Vec<File>,
+}
+
+impl SpillableCollector {
+    pub fn new(schema: SchemaRef, memory_limit: usize, spill_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            schema,
+            memory_limit,
+            spill_dir: spill_dir.into(),
+            memory_used: 0,
+            buffered: Vec::new(),
+            spilled: Vec::new(),
+        }
+    }
+
+    /// Collects all batches from the `stream`.
+    pub async fn try_collect(
+        mut stream: SendableRecordBatchStream,
+        memory_limit: usize,
+        spill_dir: impl Into<PathBuf>,
+    ) -> Result<Self> {
+        let mut collector = Self::new(stream.schema(), memory_limit, spill_dir);
+        while let Some(batch) = stream.try_next().await? {
+            collector.push(batch)?;
+        }
+        Ok(collector)
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Returns the memory size of batches not spilled.
+    pub fn memory_used(&self) -> usize {
+        self.memory_used
+    }
+
+    pub fn num_spill_files(&self) -> usize {
+        self.spilled.len()
+    }
+
+    /// Adds a batch to the collector, spills buffered batches if the memory used
+    /// exceeds the limit.
+    pub fn push(&mut self, batch: RecordBatch) -> Result<()> {
+        self.memory_used += batch
+            .columns()
+            .iter()
+            .map(|column| column.memory_size())
+            .sum::<usize>();
+        self.buffered.push(batch);
+
+        if self.memory_used > self.memory_limit {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Writes all buffered batches into a new spill file.
+    fn spill(&mut self) -> Result<()> {
+        let mut file = tempfile::tempfile_in(&self.spill_dir).context(error::SpillIoSnafu)?;
+
+        let mut writer = FileWriter::try_new(&mut file, self.schema.arrow_schema())
+            .context(error::WriteSpillSnafu)?;
+        for batch in &self.buffered {
+            writer
+                .write(batch.df_record_batch())
+                .context(error::WriteSpillSnafu)?;
+        }
+        writer.finish().context(error::WriteSpillSnafu)?;
+        drop(writer);
+
+        file.seek(SeekFrom::Start(0)).context(error::SpillIoSnafu)?;
+        self.spilled.push(file);
+        self.buffered.clear();
+        self.memory_used = 0;
+
+        Ok(())
+    }
+
+    /// Returns an iterator that yields collected batches in the order they are pushed.
+    /// Spilled batches are read back from files lazily.
+    pub fn into_batches(self) -> SpilledBatches {
+        SpilledBatches {
+            schema: self.schema,
+            files: self.spilled.into_iter(),
+            reader: None,
+            buffered: self.buffered.into_iter(),
+        }
+    }
+}
+
+/// Iterator over batches of a [SpillableCollector].
+pub struct SpilledBatches {
+    schema: SchemaRef,
+    files: std::vec::IntoIter<File>,
+    reader: Option<FileReader<File>>,
+    buffered: std::vec::IntoIter<RecordBatch>,
+}
+
+impl SpilledBatches {
+    fn next_spilled(&mut self) -> Option<Result<RecordBatch>> {
+        loop {
+            if let Some(reader) = &mut self.reader {
+                if let Some(batch) = reader.next() {
+                    return Some(batch.context(error::ReadSpillSnafu).and_then(|batch| {
+                        RecordBatch::try_from_df_record_batch(self.schema.clone(), batch)
+                    }));
+                }
+                self.reader = None;
+            }
+
+            let file = self.files.next()?;
+            match FileReader::try_new(file, None).context(error::ReadSpillSnafu) {
+                Ok(reader) => self.reader = Some(reader),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl Iterator for SpilledBatches {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Result<RecordBatch>> {
+        self.next_spilled().or_else(|| self.buffered.next().map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int64Vector, StringVector};
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::RecordBatches;
+
+    fn new_batch(schema: &SchemaRef, start: i64, len: usize) -> RecordBatch {
+        let values: Vec<i64> = (start..start + len as i64).collect();
+        let names: Vec<String> = values.iter().map(|v| format!("name-{v:04}")).collect();
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(Int64Vector::from_vec(values)),
+            Arc::new(StringVector::from(names)),
+        ];
+        RecordBatch::new(schema.clone(), columns).unwrap()
+    }
+
+    fn new_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new("v", ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new("name", ConcreteDataType::string_datatype(), true),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_spillable_collector() {
+        let dir = TempDir::new("spill").unwrap();
+        let schema = new_schema();
+        let batches: Vec<_> = (0..10).map(|i| new_batch(&schema, i * 100, 100)).collect();
+        let batch_size: usize = batches[0]
+            .columns()
+            .iter()
+            .map(|column| column.memory_size())
+            .sum();
+
+        // Batches have the same size, spills every 3 batches.
+        let recordbatches = RecordBatches::try_new(schema.clone(), batches.clone()).unwrap();
+        let collector = SpillableCollector::try_collect(
+            recordbatches.as_stream(),
+            batch_size * 3 - 1,
+            dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(3, collector.num_spill_files());
+        assert_eq!(batch_size, collector.memory_used());
+        assert_eq!(schema, collector.schema());
+
+        let collected = collector
+            .into_batches()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(batches, collected);
+    }
+
+    #[test]
+    fn test_collect_in_memory() {
+        let dir = TempDir::new("spill-in-memory").unwrap();
+        let schema = new_schema();
+        let collector = SpillableCollector::new(schema.clone(), usize::MAX, dir.path());
+        assert_eq!(0, collector.into_batches().count());
+
+        let mut collector = SpillableCollector::new(schema.clone(), usize::MAX, dir.path());
+        let batch = new_batch(&schema, 0, 10);
+        collector.push(batch.clone()).unwrap();
+        assert_eq!(0, collector.num_spill_files());
+
+        let collected = collector
+            .into_batches()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(vec![batch], collected);
+    }
+}
@@ -22,7 +22,11 @@ use datafusion::arrow::datatypes::SchemaRef as ArrowSchemaRef;
 use datafusion::common::{DFField, DFSchema};
 use datafusion::datasource::DefaultTableSource;
 use datafusion::physical_plan::project_schema;
-use datafusion_expr::{Filter, LogicalPlan, TableScan, TableSource};
+use datafusion_expr::expr::Sort;
+use datafusion_expr::{
+    Expr, Filter, Limit, LogicalPlan, LogicalPlanBuilder, TableScan, TableSource,
+};
+use datatypes::schema::Schema;
 use prost::Message;
 use snafu::{ensure, OptionExt, ResultExt};
 use substrait_proto::protobuf::expression::mask_expression::{StructItem, StructSelect};
@@ -31,7 +35,10 @@ use substrait_proto::protobuf::extensions::simple_extension_declaration::Mapping
 use substrait_proto::protobuf::plan_rel::RelType as PlanRelType;
 use substrait_proto::protobuf::read_rel::{NamedTable, ReadType};
 use substrait_proto::protobuf::rel::RelType;
-use substrait_proto::protobuf::{FilterRel, Plan, PlanRel, ReadRel, Rel};
+use substrait_proto::protobuf::sort_field::{SortDirection, SortKind};
+use substrait_proto::protobuf::{
+    FetchRel, FilterRel, Plan, PlanRel, ReadRel, Rel, SortField, SortRel,
+};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::context::ConvertorContext;
@@ -146,18 +153,63 @@ impl DFLogicalSubstraitConvertor {
 
                 LogicalPlan::Filter(Filter::try_new(predicate, input).context(DFInternalSnafu)?)
             }
-            RelType::Fetch(_fetch_rel) => UnsupportedPlanSnafu {
-                name: "Fetch Relation",
+            RelType::Fetch(fetch) => {
+                let FetchRel {
+                    common: _,
+                    input,
+                    offset,
+                    count,
+                    advanced_extension: _,
+                } = *fetch;
+
+                let input = input.context(MissingFieldSnafu {
+                    field: "input",
+                    plan: "Fetch",
+                })?;
+                let input = self.rel_to_logical_plan(ctx, input, catalog_manager)?;
+
+                // A negative count means no limit.
+                let fetch = usize::try_from(count).ok();
+                LogicalPlanBuilder::from(input)
+                    .limit(offset.max(0) as usize, fetch)
+                    .and_then(|builder| builder.build())
+                    .context(DFInternalSnafu)?
             }
-            .fail()?,
             RelType::Aggregate(_aggr_rel) => UnsupportedPlanSnafu {
                 name: "Fetch Relation",
             }
             .fail()?,
-            RelType::Sort(_sort_rel) => UnsupportedPlanSnafu {
-                name: "Sort Relation",
+            RelType::Sort(sort) => {
+                let SortRel {
+                    common: _,
+                    input,
+                    sorts,
+                    advanced_extension: _,
+                } = *sort;
+
+                let input = input.context(MissingFieldSnafu {
+                    field: "input",
+                    plan: "Sort",
+                })?;
+                let input = self.rel_to_logical_plan(ctx, input, catalog_manager)?;
+
+                let schema = ctx.df_schema().context(InvalidParametersSnafu {
+                    reason: "the underlying TableScan plan should have included a table schema",
+                })?;
+                let schema = schema
+                    .clone()
+                    .try_into()
+                    .context(error::ConvertDfSchemaSnafu)?;
+                let exprs = sorts
+                    .into_iter()
+                    .map(|sort_field| to_df_sort_expr(ctx, sort_field, &schema))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                LogicalPlanBuilder::from(input)
+                    .sort(exprs)
+                    .and_then(|builder| builder.build())
+                    .context(DFInternalSnafu)?
             }
-            .fail()?,
             RelType::Join(_join_rel) => UnsupportedPlanSnafu {
                 name: "Join Relation",
             }
@@ -346,10 +398,30 @@ impl DFLogicalSubstraitConvertor {
                 name: "DataFusion Logical Aggregate",
             }
             .fail()?,
-            LogicalPlan::Sort(_) => UnsupportedPlanSnafu {
-                name: "DataFusion Logical Sort",
+            LogicalPlan::Sort(sort) => {
+                let input = Some(Box::new(self.logical_plan_to_rel(ctx, sort.input.clone())?));
+
+                let schema = plan
+                    .schema()
+                    .clone()
+                    .try_into()
+                    .context(error::ConvertDfSchemaSnafu)?;
+                let sorts = sort
+                    .expr
+                    .iter()
+                    .map(|expr| sort_field_from_df_expr(ctx, expr, &schema))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let rel = SortRel {
+                    common: None,
+                    input,
+                    sorts,
+                    advanced_extension: None,
+                };
+                Rel {
+                    rel_type: Some(RelType::Sort(Box::new(rel))),
+                }
             }
-            .fail()?,
             LogicalPlan::Join(_) => UnsupportedPlanSnafu {
                 name: "DataFusion Logical Join",
             }
@@ -376,10 +448,20 @@ impl DFLogicalSubstraitConvertor {
                 name: "DataFusion Logical EmptyRelation",
             }
             .fail()?,
-            LogicalPlan::Limit(_) => UnsupportedPlanSnafu {
-                name: "DataFusion Logical Limit",
+            LogicalPlan::Limit(Limit { skip, fetch, input }) => {
+                let input = Some(Box::new(self.logical_plan_to_rel(ctx, input.clone())?));
+
+                let rel = FetchRel {
+                    common: None,
+                    input,
+                    offset: *skip as i64,
+                    count: fetch.map(|fetch| fetch as i64).unwrap_or(-1),
+                    advanced_extension: None,
+                };
+                Rel {
+                    rel_type: Some(RelType::Fetch(Box::new(rel))),
+                }
             }
-            .fail()?,
 
             LogicalPlan::Subquery(_)
             | LogicalPlan::SubqueryAlias(_)
@@ -507,6 +589,74 @@ impl DFLogicalSubstraitConvertor {
     }
 }
 
+/// Converts a sort expression of DataFusion to substrait's [SortField].
+fn sort_field_from_df_expr(
+    ctx: &mut ConvertorContext,
+    expr: &Expr,
+    schema: &Schema,
+) -> Result<SortField, Error> {
+    let Expr::Sort(Sort {
+        expr,
+        asc,
+        nulls_first,
+    }) = expr
+    else {
+        return UnsupportedExprSnafu {
+            name: expr.to_string(),
+        }
+        .fail();
+    };
+
+    let direction = match (asc, nulls_first) {
+        (true, true) => SortDirection::AscNullsFirst,
+        (true, false) => SortDirection::AscNullsLast,
+        (false, true) => SortDirection::DescNullsFirst,
+        (false, false) => SortDirection::DescNullsLast,
+    };
+    Ok(SortField {
+        expr: Some(expression_from_df_expr(ctx, expr, schema)?),
+        sort_kind: Some(SortKind::Direction(direction as i32)),
+    })
+}
+
+/// Converts substrait's [SortField] to a sort expression of DataFusion.
+fn to_df_sort_expr(
+    ctx: &ConvertorContext,
+    sort_field: SortField,
+    schema: &Schema,
+) -> Result<Expr, Error> {
+    let expr = sort_field.expr.context(MissingFieldSnafu {
+        field: "expr",
+        plan: "Sort",
+    })?;
+    let (asc, nulls_first) = match sort_field.sort_kind {
+        Some(SortKind::Direction(direction)) => match SortDirection::from_i32(direction) {
+            Some(SortDirection::AscNullsFirst) => (true, true),
+            Some(SortDirection::AscNullsLast) => (true, false),
+            Some(SortDirection::DescNullsFirst) => (false, true),
+            Some(SortDirection::DescNullsLast) => (false, false),
+            _ => {
+                return UnsupportedPlanSnafu {
+                    name: format!("Sort direction {direction}"),
+                }
+                .fail()
+            }
+        },
+        _ => {
+            return UnsupportedPlanSnafu {
+                name: "Sort without direction",
+            }
+            .fail()
+        }
+    };
+
+    Ok(Expr::Sort(Sort {
+        expr: Box::new(to_df_expr(ctx, expr, schema)?),
+        asc,
+        nulls_first,
+    }))
+}
+
 fn same_schema_without_metadata(lhs: &ArrowSchemaRef, rhs: &ArrowSchemaRef) -> bool {
     lhs.fields.len() == rhs.fields.len()
         && lhs.fields.iter().zip(rhs.fields.iter()).all(|(x, y)| {
@@ -522,7 +672,6 @@ mod test {
     use catalog::{CatalogList, CatalogProvider, RegisterTableRequest};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
     use datafusion::common::{DFSchema, ToDFSchema};
-    use datatypes::schema::Schema;
     use table::requests::CreateTableRequest;
     use table::test_util::{EmptyTable, MockTableEngine};
 
@@ -575,9 +724,7 @@ mod test {
         assert_eq!(format!("{plan:?}"), format!("{tripped_plan:?}"));
     }
 
-    #[tokio::test]
-    async fn test_table_scan() {
-        let catalog_manager = build_mock_catalog_manager().await;
+    async fn register_table(catalog_manager: &CatalogManagerRef) -> Arc<DefaultTableSource> {
         let table_ref = Arc::new(EmptyTable::new(build_create_table_request(
             DEFAULT_TABLE_NAME,
         )));
@@ -591,9 +738,15 @@ mod test {
             })
             .await
             .unwrap();
-        let adapter = Arc::new(DefaultTableSource::new(Arc::new(
+        Arc::new(DefaultTableSource::new(Arc::new(
             DfTableProviderAdapter::new(table_ref),
-        )));
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_table_scan() {
+        let catalog_manager = build_mock_catalog_manager().await;
+        let adapter = register_table(&catalog_manager).await;
 
         let projection = vec![1, 3, 5];
         let df_schema = adapter.schema().to_dfschema().unwrap();
@@ -617,4 +770,28 @@ mod test {
 
         logical_plan_round_trip(table_scan_plan, catalog_manager).await;
     }
+
+    #[tokio::test]
+    async fn test_sort_and_limit() {
+        let catalog_manager = build_mock_catalog_manager().await;
+        let adapter = register_table(&catalog_manager).await;
+        let column = adapter.schema().field(5).name().clone();
+
+        for (asc, nulls_first, fetch) in [(true, false, Some(10)), (false, true, None)] {
+            let plan = LogicalPlanBuilder::scan(
+                format!("{DEFAULT_CATALOG_NAME}.{DEFAULT_SCHEMA_NAME}.{DEFAULT_TABLE_NAME}"),
+                adapter.clone(),
+                Some(vec![1, 3, 5]),
+            )
+            .unwrap()
+            .sort(vec![datafusion_expr::col(&column).sort(asc, nulls_first)])
+            .unwrap()
+            .limit(5, fetch)
+            .unwrap()
+            .build()
+            .unwrap();
+
+            logical_plan_round_trip(plan, catalog_manager.clone()).await;
+        }
+    }
 }
@@ -17,6 +17,7 @@
 //! Substrait use [type variation](https://substrait.io/types/type_variations/) to express different "logical types".
 //! Current we only have variations on integer types. Variation 0 (system preferred) are the same with base types, which
 //! are signed integer (i.e. I8 -> [i8]), and Variation 1 stands for unsigned integer (i.e. I8 -> [u8]).
+//! Variation 1 of binary stands for JSON documents.
 
 use datafusion::scalar::ScalarValue;
 use datatypes::prelude::ConcreteDataType;
@@ -68,17 +69,17 @@ pub fn to_concrete_type(ty: &SType) -> Result<(ConcreteDataType, bool)> {
         Kind::Fp32(desc) => substrait_kind!(desc, float32_datatype),
         Kind::Fp64(desc) => substrait_kind!(desc, float64_datatype),
         Kind::String(desc) => substrait_kind!(desc, string_datatype),
-        Kind::Binary(desc) => substrait_kind!(desc, binary_datatype),
+        Kind::Binary(desc) => substrait_kind!(desc, binary_datatype, json_datatype),
         Kind::Timestamp(desc) => substrait_kind!(
             desc,
             ConcreteDataType::timestamp_datatype(Default::default())
         ),
         Kind::Date(desc) => substrait_kind!(desc, date_datatype),
+        Kind::Uuid(desc) => substrait_kind!(desc, uuid_datatype),
         Kind::Time(_)
         | Kind::IntervalYear(_)
         | Kind::IntervalDay(_)
         | Kind::TimestampTz(_)
-        | Kind::Uuid(_)
         | Kind::FixedChar(_)
         | Kind::Varchar(_)
         | Kind::FixedBinary(_)
@@ -125,7 +126,9 @@ pub fn from_concrete_type(ty: ConcreteDataType, nullability: Option<bool>) -> Re
         ConcreteDataType::Float32(_) => build_substrait_kind!(Fp32, Fp32, nullability, 0),
         ConcreteDataType::Float64(_) => build_substrait_kind!(Fp64, Fp64, nullability, 0),
         ConcreteDataType::Binary(_) => build_substrait_kind!(Binary, Binary, nullability, 0),
+        ConcreteDataType::Json(_) => build_substrait_kind!(Binary, Binary, nullability, 1),
         ConcreteDataType::String(_) => build_substrait_kind!(String, String, nullability, 0),
+        ConcreteDataType::Uuid(_) => build_substrait_kind!(Uuid, Uuid, nullability, 0),
         ConcreteDataType::Date(_) => build_substrait_kind!(Date, Date, nullability, 0),
         ConcreteDataType::DateTime(_) => UnsupportedConcreteTypeSnafu { ty }.fail()?,
         ConcreteDataType::Timestamp(_) => {
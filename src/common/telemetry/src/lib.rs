@@ -16,8 +16,9 @@ pub mod logging;
 mod macros;
 pub mod metric;
 mod panic_hook;
+pub mod tracing_context;
 
-pub use logging::{init_default_ut_logging, init_global_logging};
+pub use logging::{init_default_ut_logging, init_global_logging, TracingExporter};
 pub use metric::init_default_metrics_recorder;
 pub use panic_hook::set_panic_hook;
 pub use {common_error, tracing, tracing_appender, tracing_futures, tracing_subscriber};
@@ -17,8 +17,9 @@ use std::env;
 use std::sync::{Arc, Mutex, Once};
 
 use once_cell::sync::Lazy;
-use opentelemetry::global;
 use opentelemetry::sdk::propagation::TraceContextPropagator;
+use opentelemetry::sdk::{trace, Resource};
+use opentelemetry::{global, KeyValue};
 pub use tracing::{event, span, Level};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
@@ -45,7 +46,7 @@ pub fn init_default_ut_logging() {
         let dir =
             env::var("UNITTEST_LOG_DIR").unwrap_or_else(|_| "/tmp/__unittest_logs".to_string());
 
-        *g = Some(init_global_logging("unittest", &dir, "DEBUG", false));
+        *g = Some(init_global_logging("unittest", &dir, "DEBUG", None));
 
         info!("logs dir = {}", dir);
     });
@@ -54,11 +55,24 @@ pub fn init_default_ut_logging() {
 static GLOBAL_UT_LOG_GUARD: Lazy<Arc<Mutex<Option<Vec<WorkerGuard>>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
 
+/// Exporter of the tracing spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TracingExporter {
+    /// Exports the spans to the Jaeger agent configured by the `OTEL_EXPORTER_JAEGER_*`
+    /// environment variables.
+    Jaeger,
+    /// Exports the spans to the OTLP collector at `endpoint` over gRPC, e.g.
+    /// `http://localhost:4317`.
+    Otlp { endpoint: String },
+}
+
+/// Initializes the global logging, spans are exported by the `tracing_exporter` if
+/// it's set, and the tracing context of requests is propagated across components.
 pub fn init_global_logging(
     app_name: &str,
     dir: &str,
     level: &str,
-    enable_jaeger_tracing: bool,
+    tracing_exporter: Option<TracingExporter>,
 ) -> Vec<WorkerGuard> {
     let mut guards = vec![];
 
@@ -104,15 +118,28 @@ pub fn init_global_logging(
     #[cfg(feature = "console")]
     let subscriber = subscriber.with(console_subscriber::spawn());
 
-    if enable_jaeger_tracing {
-        // Jaeger layer.
+    if let Some(tracing_exporter) = tracing_exporter {
         global::set_text_map_propagator(TraceContextPropagator::new());
-        let tracer = opentelemetry_jaeger::new_pipeline()
-            .with_service_name(app_name)
-            .install_batch(opentelemetry::runtime::Tokio)
-            .expect("install");
-        let jaeger_layer = Some(tracing_opentelemetry::layer().with_tracer(tracer));
-        let subscriber = subscriber.with(jaeger_layer);
+        let tracer = match tracing_exporter {
+            TracingExporter::Jaeger => opentelemetry_jaeger::new_pipeline()
+                .with_service_name(app_name)
+                .install_batch(opentelemetry::runtime::Tokio)
+                .expect("install"),
+            TracingExporter::Otlp { endpoint } => opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", app_name.to_string()),
+                ])))
+                .install_batch(opentelemetry::runtime::Tokio)
+                .expect("install"),
+        };
+        let tracing_layer = tracing_opentelemetry::layer().with_tracer(tracer);
+        let subscriber = subscriber.with(tracing_layer);
         tracing::subscriber::set_global_default(subscriber)
             .expect("error setting global tracing subscriber");
     } else {
@@ -14,10 +14,10 @@
 
 // metric stuffs, inspired by databend
 
-use std::sync::{Arc, Once, RwLock};
+use std::sync::{Arc, Mutex, Once, RwLock};
 use std::time::{Duration, Instant};
 
-use metrics::histogram;
+use metrics::{describe_counter, describe_gauge, describe_histogram, histogram};
 use metrics_exporter_prometheus::PrometheusBuilder;
 pub use metrics_exporter_prometheus::PrometheusHandle;
 use once_cell::sync::Lazy;
@@ -25,6 +25,77 @@ use once_cell::sync::Lazy;
 static PROMETHEUS_HANDLE: Lazy<Arc<RwLock<Option<PrometheusHandle>>>> =
     Lazy::new(|| Arc::new(RwLock::new(None)));
 
+/// Metrics registered by the subsystems.
+static METRIC_REGISTRY: Lazy<Mutex<Vec<MetricDesc>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Kind of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+/// Description of a metric exported by a subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricDesc {
+    pub name: &'static str,
+    pub kind: MetricKind,
+    pub help: &'static str,
+}
+
+impl MetricDesc {
+    pub const fn counter(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Counter,
+            help,
+        }
+    }
+
+    pub const fn gauge(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Gauge,
+            help,
+        }
+    }
+
+    pub const fn histogram(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            kind: MetricKind::Histogram,
+            help,
+        }
+    }
+
+    fn describe(&self) {
+        match self.kind {
+            MetricKind::Counter => describe_counter!(self.name, self.help),
+            MetricKind::Gauge => describe_gauge!(self.name, self.help),
+            MetricKind::Histogram => describe_histogram!(self.name, self.help),
+        }
+    }
+}
+
+/// Registers the metrics of a subsystem, their descriptions are exported as the
+/// `HELP` of the metrics in Prometheus format. Registering a metric more than once
+/// is a no-op.
+pub fn register_metrics(descs: &[MetricDesc]) {
+    let mut registry = METRIC_REGISTRY.lock().unwrap();
+    for desc in descs {
+        if !registry.iter().any(|d| d.name == desc.name) {
+            desc.describe();
+            registry.push(*desc);
+        }
+    }
+}
+
+/// Returns all metrics registered by the subsystems.
+pub fn registered_metrics() -> Vec<MetricDesc> {
+    METRIC_REGISTRY.lock().unwrap().clone()
+}
+
 pub fn init_default_metrics_recorder() {
     static START: Once = Once::new();
     START.call_once(init_prometheus_recorder)
@@ -49,6 +120,11 @@ fn init_prometheus_recorder() {
         Ok(_) => (),
         Err(err) => crate::warn!("Install prometheus recorder failed, cause: {}", err),
     };
+
+    // Metrics registered before the recorder is installed are not described yet.
+    for desc in METRIC_REGISTRY.lock().unwrap().iter() {
+        desc.describe();
+    }
 }
 
 pub fn try_handle() -> Option<PrometheusHandle> {
@@ -109,4 +185,27 @@ mod tests {
         assert!(text.contains("test_elapsed_timer_a"));
         assert!(text.contains("test_elapsed_timer_b"));
     }
+
+    #[test]
+    fn test_register_metrics() {
+        init_default_metrics_recorder();
+        const METRICS: &[MetricDesc] = &[
+            MetricDesc::counter("test_register_metrics_a", "Counter a"),
+            MetricDesc::histogram("test_register_metrics_b", "Histogram b"),
+        ];
+        register_metrics(METRICS);
+        register_metrics(&METRICS[..1]);
+
+        let registered = registered_metrics();
+        for desc in METRICS {
+            assert_eq!(1, registered.iter().filter(|d| *d == desc).count());
+        }
+
+        metrics::increment_counter!("test_register_metrics_a");
+        let text = try_handle().unwrap().render();
+        assert!(
+            text.contains("# HELP test_register_metrics_a Counter a"),
+            "{text}"
+        );
+    }
 }
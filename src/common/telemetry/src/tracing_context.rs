@@ -0,0 +1,85 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Propagation of the tracing context across processes.
+//!
+//! The context of the current span is injected into a map of headers in the W3C
+//! `traceparent` format, which is sent along with the request, e.g. as gRPC metadata.
+//! The receiver extracts the context from the headers and makes it the parent of its
+//! span, so the spans of a request in all components are in one trace.
+//!
+//! Contexts are only propagated when a tracing exporter is enabled in
+//! [init_global_logging](crate::init_global_logging).
+
+use std::collections::HashMap;
+
+use opentelemetry::global;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Headers carrying the tracing context.
+pub type TraceHeaders = HashMap<String, String>;
+
+/// Returns the headers carrying the context of the current span, they are empty if
+/// the span is not traced.
+pub fn current_trace_headers() -> TraceHeaders {
+    let mut headers = TraceHeaders::new();
+    let context = Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut headers);
+    });
+    headers
+}
+
+/// Makes the context carried by the `headers` the parent of the `span`, the `span`
+/// is unchanged if there is no context in the `headers`.
+pub fn set_remote_parent(span: &Span, headers: &TraceHeaders) {
+    let context = global::get_text_map_propagator(|propagator| propagator.extract(headers));
+    span.set_parent(context);
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::sdk::trace::TracerProvider;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use tracing::subscriber;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::Registry;
+
+    use super::*;
+
+    #[test]
+    fn test_propagate_trace_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = TracerProvider::builder().build().tracer("test");
+        let subscriber =
+            Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        subscriber::with_default(subscriber, || {
+            let sender = tracing::info_span!("sender");
+            let headers = sender.in_scope(current_trace_headers);
+            assert!(headers.contains_key("traceparent"), "{headers:?}");
+
+            let receiver = tracing::info_span!("receiver");
+            set_remote_parent(&receiver, &headers);
+            let sender_trace_id = sender.context().span().span_context().trace_id();
+            let receiver_trace_id = receiver.context().span().span_context().trace_id();
+            assert_eq!(sender_trace_id, receiver_trace_id);
+        });
+
+        // Nothing to propagate without a traced span.
+        assert!(current_trace_headers().is_empty());
+    }
+}
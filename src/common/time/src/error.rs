@@ -22,6 +22,19 @@ pub enum Error {
     ParseDateStr { raw: String, source: ParseError },
     #[snafu(display("Failed to parse a string into Timestamp, raw string: {}", raw))]
     ParseTimestamp { raw: String, backtrace: Backtrace },
+    #[snafu(display(
+        "Failed to parse a string into Timestamp in format {}, raw string: {}, source: {}",
+        format,
+        raw,
+        source
+    ))]
+    ParseTimestampWithFormat {
+        raw: String,
+        format: String,
+        source: ParseError,
+    },
+    #[snafu(display("Invalid time zone: {}", raw))]
+    ParseTimeZone { raw: String, backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -18,10 +18,12 @@ pub mod error;
 pub mod range;
 pub mod timestamp;
 pub mod timestamp_millis;
+pub mod timezone;
 pub mod util;
 
 pub use date::Date;
 pub use datetime::DateTime;
-pub use range::RangeMillis;
+pub use range::{RangeMillis, TimestampRange};
 pub use timestamp::Timestamp;
 pub use timestamp_millis::TimestampMillis;
+pub use timezone::TimeZone;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::timestamp::Timestamp;
 use crate::timestamp_millis::TimestampMillis;
 
 /// A half-open time range.
@@ -74,6 +75,9 @@ impl<T: PartialOrd> TimeRange<T> {
 /// Time range in milliseconds.
 pub type RangeMillis = TimeRange<TimestampMillis>;
 
+/// Time range of [Timestamp]s, bounds may have different time units.
+pub type TimestampRange = TimeRange<Timestamp>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
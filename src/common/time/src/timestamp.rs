@@ -19,10 +19,12 @@ use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 use chrono::offset::Local;
-use chrono::{DateTime, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeZone as _, Utc};
 use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
 
-use crate::error::{Error, ParseTimestampSnafu};
+use crate::error::{Error, ParseTimestampSnafu, ParseTimestampWithFormatSnafu};
+use crate::timezone::TimeZone;
 
 #[derive(Debug, Clone, Default, Copy, Serialize, Deserialize)]
 pub struct Timestamp {
@@ -79,6 +81,23 @@ impl Timestamp {
     /// Format timestamp to ISO8601 string. If the timestamp exceeds what chrono timestamp can
     /// represent, this function simply print the timestamp unit and value in plain string.
     pub fn to_iso8601_string(&self) -> String {
+        self.to_timezone_aware_string(None)
+    }
+
+    /// Format timestamp to ISO8601 string in the `time_zone`, or in UTC if it's `None`.
+    pub fn to_timezone_aware_string(&self, time_zone: Option<&TimeZone>) -> String {
+        const FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%z";
+
+        match (self.to_chrono_datetime(), time_zone) {
+            (Some(datetime), Some(time_zone)) => time_zone.format(&datetime.naive_utc(), FORMAT),
+            (Some(datetime), None) => format!("{}", datetime.format(FORMAT)),
+            (None, _) => format!("[Timestamp{}: {}]", self.unit, self.value),
+        }
+    }
+
+    /// Converts the timestamp to the date time in UTC, returns `None` if it exceeds what
+    /// chrono can represent.
+    pub fn to_chrono_datetime(&self) -> Option<DateTime<Utc>> {
         let nano_factor = TimeUnit::Second.factor() / TimeUnit::Nanosecond.factor();
 
         let mut secs = self.convert_to(TimeUnit::Second);
@@ -89,29 +108,21 @@ impl Timestamp {
             nsecs += nano_factor;
         }
 
-        if let LocalResult::Single(datetime) = Utc.timestamp_opt(secs, nsecs as u32) {
-            format!("{}", datetime.format("%Y-%m-%d %H:%M:%S%.f%z"))
-        } else {
-            format!("[Timestamp{}: {}]", self.unit, self.value)
+        match Utc.timestamp_opt(secs, nsecs as u32) {
+            LocalResult::Single(datetime) => Some(datetime),
+            _ => None,
         }
     }
-}
 
-impl FromStr for Timestamp {
-    type Err = Error;
+    /// Parses the timestamp string in the formats accepted by [Timestamp::from_str], date
+    /// times without an explicit offset are regarded as in the `time_zone`, or in the local
+    /// time zone of the server if it's `None`.
+    pub fn from_str_with_time_zone(s: &str, time_zone: Option<&TimeZone>) -> Result<Self, Error> {
+        let s = s.trim();
+        if let Some(ts) = parse_epoch(s) {
+            return Ok(ts);
+        }
 
-    /// Accepts a string in RFC3339 / ISO8601 standard format and some variants and converts it to a nanosecond precision timestamp.
-    /// This code is copied from [arrow-datafusion](https://github.com/apache/arrow-datafusion/blob/arrow2/datafusion-physical-expr/src/arrow_temporal_util.rs#L71)
-    /// with some bugfixes.
-    /// Supported format:
-    /// - `2022-09-20T14:16:43.012345Z` (Zulu timezone)
-    /// - `2022-09-20T14:16:43.012345+08:00` (Explicit offset)
-    /// - `2022-09-20T14:16:43.012345` (local timezone, with T)
-    /// - `2022-09-20T14:16:43` (local timezone, no fractional seconds, with T)
-    /// - `2022-09-20 14:16:43.012345Z` (Zulu timezone, without T)
-    /// - `2022-09-20 14:16:43` (local timezone, without T)
-    /// - `2022-09-20 14:16:43.012345` (local timezone, without T)
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
         // RFC3339 timestamp (with a T)
         if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
             return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
@@ -122,34 +133,153 @@ impl FromStr for Timestamp {
         if let Ok(ts) = Utc.datetime_from_str(s, "%Y-%m-%d %H:%M:%S%.fZ") {
             return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
         }
-
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
-            return naive_datetime_to_timestamp(s, ts);
+        if let Ok(ts) = DateTime::parse_from_rfc2822(s) {
+            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
         }
 
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
-            return naive_datetime_to_timestamp(s, ts);
+        for format in NAIVE_DATETIME_FORMATS {
+            if let Ok(ts) = NaiveDateTime::parse_from_str(s, format) {
+                return naive_datetime_to_timestamp(s, ts, time_zone);
+            }
         }
-
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-            return naive_datetime_to_timestamp(s, ts);
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return naive_datetime_to_timestamp(s, start_of_day(date), time_zone);
         }
 
-        if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
-            return naive_datetime_to_timestamp(s, ts);
+        ParseTimestampSnafu { raw: s }.fail()
+    }
+
+    /// Parses the timestamp string in the [chrono format](chrono::format::strftime) `format`.
+    ///
+    /// Date times without an offset in the `format` are regarded as in the `time_zone`, or
+    /// in the local time zone of the server if it's `None`. Dates without time are regarded
+    /// as the start of the day.
+    pub fn from_str_with_format(
+        s: &str,
+        format: &str,
+        time_zone: Option<&TimeZone>,
+    ) -> Result<Self, Error> {
+        if let Ok(ts) = DateTime::parse_from_str(s, format) {
+            return Ok(Timestamp::new(ts.timestamp_nanos(), TimeUnit::Nanosecond));
         }
+        let datetime = match NaiveDateTime::parse_from_str(s, format) {
+            Ok(datetime) => datetime,
+            // The format may only contain the date.
+            Err(e) => match NaiveDate::parse_from_str(s, format) {
+                Ok(date) => start_of_day(date),
+                Err(_) => {
+                    return Err(e).context(ParseTimestampWithFormatSnafu { raw: s, format });
+                }
+            },
+        };
+        naive_datetime_to_timestamp(s, datetime, time_zone)
+    }
+}
 
-        ParseTimestampSnafu { raw: s }.fail()
+/// Formats of the date times without an offset.
+const NAIVE_DATETIME_FORMATS: [&str; 8] = [
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M:%S%.f",
+];
+
+/// Max number of digits of an epoch in each unit, epochs with more digits are in
+/// nanoseconds. 10 digits of seconds covers dates until 2286.
+const EPOCH_DIGITS: [(usize, TimeUnit); 3] = [
+    (10, TimeUnit::Second),
+    (13, TimeUnit::Millisecond),
+    (16, TimeUnit::Microsecond),
+];
+
+/// Parses a bare unix epoch like `1645459261` or `1645459261.555`.
+///
+/// The unit of an integer epoch is inferred from its number of digits, see [EPOCH_DIGITS],
+/// and an epoch with fractional part is in seconds.
+fn parse_epoch(s: &str) -> Option<Timestamp> {
+    let unsigned = s.strip_prefix('-').unwrap_or(s);
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let (int, frac) = match unsigned.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (unsigned, None),
+    };
+    if !is_digits(int) {
+        return None;
+    }
+
+    let Some(frac) = frac else {
+        let unit = EPOCH_DIGITS
+            .iter()
+            .find(|(digits, _)| int.len() <= *digits)
+            .map_or(TimeUnit::Nanosecond, |(_, unit)| *unit);
+        return s.parse().ok().map(|value| Timestamp::new(value, unit));
+    };
+
+    // Fractional part beyond nanoseconds is not supported.
+    if !is_digits(frac) || frac.len() > 9 {
+        return None;
+    }
+    let secs: i64 = int.parse().ok()?;
+    let nanos: i64 = format!("{frac:0<9}").parse().ok()?;
+    let value = secs.checked_mul(1_000_000_000)?.checked_add(nanos)?;
+    let value = if unsigned.len() < s.len() {
+        -value
+    } else {
+        value
+    };
+    Some(Timestamp::new(value, TimeUnit::Nanosecond))
+}
+
+fn start_of_day(date: NaiveDate) -> NaiveDateTime {
+    // Safety: Midnight is always valid.
+    date.and_hms_opt(0, 0, 0).unwrap()
+}
+
+impl FromStr for Timestamp {
+    type Err = Error;
+
+    /// Accepts a string in RFC3339 / ISO8601 standard format and some variants and converts it to a nanosecond precision timestamp.
+    /// This code is copied from [arrow-datafusion](https://github.com/apache/arrow-datafusion/blob/arrow2/datafusion-physical-expr/src/arrow_temporal_util.rs#L71)
+    /// with some bugfixes.
+    /// Supported format:
+    /// - `2022-09-20T14:16:43.012345Z` (Zulu timezone)
+    /// - `2022-09-20T14:16:43.012345+08:00` (Explicit offset)
+    /// - `2022-09-20T14:16:43.012345` (local timezone, with T)
+    /// - `2022-09-20T14:16:43` (local timezone, no fractional seconds, with T)
+    /// - `2022-09-20 14:16:43.012345Z` (Zulu timezone, without T)
+    /// - `2022-09-20 14:16:43` (local timezone, without T)
+    /// - `2022-09-20 14:16:43.012345` (local timezone, without T)
+    /// - `2022-09-20 14:16` (local timezone, without seconds)
+    /// - `2022/09/20 14:16:43.012345` (local timezone, with slashes)
+    /// - `2022-09-20` (start of the day in local timezone)
+    /// - `Tue, 20 Sep 2022 14:16:43 +0800` (RFC2822)
+    /// - `1663654603`, `1663654603012` (unix epoch, the unit is inferred from the number of digits)
+    /// - `1663654603.012345` (unix epoch in seconds with fractional part)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Timestamp::from_str_with_time_zone(s, None)
     }
 }
 
 /// Converts the naive datetime (which has no specific timezone) to a
-/// nanosecond epoch timestamp relative to UTC.
+/// nanosecond epoch timestamp relative to UTC, the naive datetime is in the
+/// `time_zone`, or in the local timezone if it's `None`.
 /// This code is copied from [arrow-datafusion](https://github.com/apache/arrow-datafusion/blob/arrow2/datafusion-physical-expr/src/arrow_temporal_util.rs#L137).
 fn naive_datetime_to_timestamp(
     s: &str,
     datetime: NaiveDateTime,
+    time_zone: Option<&TimeZone>,
 ) -> crate::error::Result<Timestamp> {
+    if let Some(time_zone) = time_zone {
+        return match time_zone.to_utc(&datetime) {
+            Some(utc) => Ok(Timestamp::new(utc.timestamp_nanos(), TimeUnit::Nanosecond)),
+            None => ParseTimestampSnafu { raw: s }.fail(),
+        };
+    }
+
     let l = Local {};
 
     match l.from_local_datetime(&datetime) {
@@ -254,7 +384,7 @@ impl Hash for Timestamp {
 
 #[cfg(test)]
 mod tests {
-    use chrono::Offset;
+    use chrono::{Offset, TimeZone as _};
     use serde_json::Value;
 
     use super::*;
@@ -380,6 +510,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_str_with_time_zone() {
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        for s in [
+            "2020-09-08 13:42:29",
+            "2020-09-08T13:42:29",
+            "2020-09-08 13:42:29.000",
+            "2020-09-08T13:42:29.000",
+            "2020-09-08 05:42:29Z",
+            "2020-09-08T13:42:29+08:00",
+        ] {
+            let ts = Timestamp::from_str_with_time_zone(s, Some(&time_zone)).unwrap();
+            assert_eq!(Timestamp::new_second(1599543749), ts, "{s}");
+        }
+
+        let time_zone: TimeZone = "America/New_York".parse().unwrap();
+        assert!(
+            Timestamp::from_str_with_time_zone("2022-03-13 02:30:00", Some(&time_zone)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_from_str_epoch() {
+        for (s, expect) in [
+            ("1645459261", Timestamp::new_second(1645459261)),
+            ("-1645459261", Timestamp::new_second(-1645459261)),
+            ("0", Timestamp::new_second(0)),
+            ("1645459261555", Timestamp::new_millisecond(1645459261555)),
+            (
+                "1645459261555666",
+                Timestamp::new_microsecond(1645459261555666),
+            ),
+            (
+                "1645459261555666777",
+                Timestamp::new_nanosecond(1645459261555666777),
+            ),
+            ("1645459261.555", Timestamp::new_millisecond(1645459261555)),
+            ("-1645459261.5", Timestamp::new_millisecond(-1645459261500)),
+            (
+                " 1645459261.123456789 ",
+                Timestamp::new_nanosecond(1645459261123456789),
+            ),
+        ] {
+            let ts = Timestamp::from_str(s).unwrap();
+            assert_eq!(expect, ts, "{s}");
+        }
+
+        for s in [
+            "1645459261.",
+            ".5",
+            "1645459261.1234567891",
+            "16454a",
+            "--1",
+        ] {
+            assert!(Timestamp::from_str(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_from_str_more_formats() {
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        for (s, expect) in [
+            ("2022-02-22 00:01:01.555", 1645459261555),
+            ("2022/02/22 00:01:01.555", 1645459261555),
+            ("2022/02/22 00:01:01", 1645459261000),
+            ("2022-02-22 00:01", 1645459260000),
+            ("2022-02-22T00:01", 1645459260000),
+            ("2022-02-22", 1645459200000),
+            ("Tue, 22 Feb 2022 00:01:01 +0800", 1645459261000),
+        ] {
+            let ts = Timestamp::from_str_with_time_zone(s, Some(&time_zone)).unwrap();
+            assert_eq!(Timestamp::new_millisecond(expect), ts, "{s}");
+        }
+    }
+
+    #[test]
+    fn test_from_str_with_format() {
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        for (s, format, expect) in [
+            ("22/02/2022 00:01:01", "%d/%m/%Y %H:%M:%S", 1645459261000),
+            ("20220222", "%Y%m%d", 1645459200000),
+            (
+                "2022-02-21 16:01:01.5 +0000",
+                "%Y-%m-%d %H:%M:%S%.f %z",
+                1645459261500,
+            ),
+        ] {
+            let ts = Timestamp::from_str_with_format(s, format, Some(&time_zone)).unwrap();
+            assert_eq!(Timestamp::new_millisecond(expect), ts, "{s}");
+        }
+
+        let err = Timestamp::from_str_with_format("2022-02-22", "%d/%m/%Y", None).unwrap_err();
+        assert!(
+            matches!(err, Error::ParseTimestampWithFormat { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_to_timezone_aware_string() {
+        let ts = Timestamp::new_millisecond(1668070237000);
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        assert_eq!(
+            "2022-11-10 16:50:37+0800",
+            ts.to_timezone_aware_string(Some(&time_zone))
+        );
+        let time_zone: TimeZone = "Europe/London".parse().unwrap();
+        assert_eq!(
+            "2022-11-10 08:50:37+0000",
+            ts.to_timezone_aware_string(Some(&time_zone))
+        );
+        assert_eq!(ts.to_iso8601_string(), ts.to_timezone_aware_string(None));
+    }
+
     #[test]
     fn test_to_iso8601_string() {
         let datetime_str = "2020-09-08 13:42:29.042+0000";
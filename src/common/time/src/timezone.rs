@@ -0,0 +1,178 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{FixedOffset, LocalResult, NaiveDateTime, TimeZone as _};
+use chrono_tz::Tz;
+use snafu::OptionExt;
+
+use crate::error::{Error, ParseTimeZoneSnafu};
+
+/// Time zone of a session, in which the timestamps are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    /// Fixed offset from UTC, e.g. `+08:00`.
+    Offset(FixedOffset),
+    /// Time zone in the IANA database, e.g. `Asia/Shanghai`.
+    Named(Tz),
+}
+
+impl TimeZone {
+    /// Converts the `datetime` in UTC to the local date time in this time zone.
+    pub fn to_local(&self, datetime: &NaiveDateTime) -> NaiveDateTime {
+        match self {
+            TimeZone::Offset(offset) => offset.from_utc_datetime(datetime).naive_local(),
+            TimeZone::Named(tz) => tz.from_utc_datetime(datetime).naive_local(),
+        }
+    }
+
+    /// Converts the local `datetime` in this time zone to the date time in UTC, the
+    /// earlier one is chosen if the local date time is ambiguous. Returns `None` if
+    /// the local date time doesn't exist, e.g. skipped by daylight saving time.
+    pub fn to_utc(&self, datetime: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let utc = match self {
+            TimeZone::Offset(offset) => earliest(offset.from_local_datetime(datetime))?.naive_utc(),
+            TimeZone::Named(tz) => earliest(tz.from_local_datetime(datetime))?.naive_utc(),
+        };
+        Some(utc)
+    }
+
+    /// Formats the `datetime` in UTC as the local date time in this time zone.
+    pub fn format(&self, datetime: &NaiveDateTime, fmt: &str) -> String {
+        match self {
+            TimeZone::Offset(offset) => offset.from_utc_datetime(datetime).format(fmt).to_string(),
+            TimeZone::Named(tz) => tz.from_utc_datetime(datetime).format(fmt).to_string(),
+        }
+    }
+}
+
+fn earliest<T>(result: LocalResult<T>) -> Option<T> {
+    match result {
+        LocalResult::None => None,
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Some(t),
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = Error;
+
+    /// Accepts an offset in the form of `[+-]HH:MM`, or a name in the IANA time zone
+    /// database, e.g. `UTC` or `Asia/Shanghai`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with(['+', '-']) {
+            return parse_offset(s)
+                .map(TimeZone::Offset)
+                .context(ParseTimeZoneSnafu { raw: s });
+        }
+        s.parse::<Tz>()
+            .ok()
+            .map(TimeZone::Named)
+            .context(ParseTimeZoneSnafu { raw: s })
+    }
+}
+
+impl Display for TimeZone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeZone::Offset(offset) => write!(f, "{offset}"),
+            TimeZone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, offset) = s.split_at(1);
+    let (hours, minutes) = offset.split_once(':')?;
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    let seconds = (hours * 60 + minutes) * 60;
+    if sign == "-" {
+        FixedOffset::west_opt(seconds)
+    } else {
+        FixedOffset::east_opt(seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_zone() {
+        let tz: TimeZone = "+08:00".parse().unwrap();
+        assert_eq!(
+            TimeZone::Offset(FixedOffset::east_opt(8 * 3600).unwrap()),
+            tz
+        );
+        assert_eq!("+08:00", tz.to_string());
+        let tz: TimeZone = "-05:30".parse().unwrap();
+        assert_eq!("-05:30", tz.to_string());
+
+        let tz: TimeZone = "Asia/Shanghai".parse().unwrap();
+        assert_eq!(TimeZone::Named(Tz::Asia__Shanghai), tz);
+        assert_eq!("Asia/Shanghai", tz.to_string());
+        assert_eq!("UTC", "UTC".parse::<TimeZone>().unwrap().to_string());
+
+        assert!("+8".parse::<TimeZone>().is_err());
+        assert!("+15:00".parse::<TimeZone>().is_err());
+        assert!("Mars/Olympus".parse::<TimeZone>().is_err());
+    }
+
+    #[test]
+    fn test_to_local() {
+        let datetime = NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let tz: TimeZone = "+08:00".parse().unwrap();
+        assert_eq!("1970-01-01 08:00:00", tz.to_local(&datetime).to_string());
+        let tz: TimeZone = "America/New_York".parse().unwrap();
+        assert_eq!("1969-12-31 19:00:00", tz.to_local(&datetime).to_string());
+    }
+
+    #[test]
+    fn test_to_utc() {
+        let datetime = NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let tz: TimeZone = "+08:00".parse().unwrap();
+        assert_eq!(datetime, tz.to_utc(&tz.to_local(&datetime)).unwrap());
+        assert_eq!(
+            "1969-12-31 16:00:00",
+            tz.to_utc(&datetime).unwrap().to_string()
+        );
+
+        let tz: TimeZone = "America/New_York".parse().unwrap();
+        // Skipped by daylight saving time.
+        let skipped =
+            NaiveDateTime::parse_from_str("2022-03-13 02:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert!(tz.to_utc(&skipped).is_none());
+        // Repeated by the end of daylight saving time.
+        let repeated =
+            NaiveDateTime::parse_from_str("2022-11-06 01:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            "2022-11-06 05:30:00",
+            tz.to_utc(&repeated).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        let datetime = NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let tz: TimeZone = "Asia/Shanghai".parse().unwrap();
+        assert_eq!(
+            "1970-01-01 08:00:00+0800",
+            tz.format(&datetime, "%Y-%m-%d %H:%M:%S%z")
+        );
+    }
+}
@@ -13,16 +13,26 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
+use catalog::DEFAULT_OPEN_TABLE_CONCURRENCY;
 use common_telemetry::info;
+use log_store::fs::config::{LogConfig, SyncMode};
 use meta_client::MetaClientOpts;
 use serde::{Deserialize, Serialize};
+use servers::http::HttpOptions;
+use servers::tls::TlsOption;
 use servers::Mode;
+use storage::config::DEFAULT_MAX_WRITE_BUFFER_SIZE;
 
 use crate::error::Result;
 use crate::instance::{Instance, InstanceRef};
 use crate::server::Services;
 
+/// Backend of the object store that the storage engine stores data in.
+///
+/// Credentials of the cloud backends are optional, they are loaded from the environment
+/// (e.g. environment variables, config files and instance metadata) if absent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ObjectStoreConfig {
@@ -32,9 +42,79 @@ pub enum ObjectStoreConfig {
     S3 {
         bucket: String,
         root: String,
-        access_key_id: String,
-        secret_access_key: String,
+        #[serde(default)]
+        access_key_id: Option<String>,
+        #[serde(default)]
+        secret_access_key: Option<String>,
+        /// Endpoint of S3 compatible services, defaults to AWS S3.
+        #[serde(default)]
+        endpoint: Option<String>,
+        #[serde(default)]
+        region: Option<String>,
+        /// Addresses the bucket by virtual host style (`bucket.endpoint/path`) instead of
+        /// path style (`endpoint/bucket/path`).
+        #[serde(default)]
+        enable_virtual_host_style: bool,
     },
+    /// Azure Blob Storage.
+    Azblob {
+        container: String,
+        root: String,
+        #[serde(default)]
+        account_name: Option<String>,
+        #[serde(default)]
+        account_key: Option<String>,
+        /// Endpoint of the storage account, e.g. `https://<account>.blob.core.windows.net`.
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    /// Google Cloud Storage.
+    Gcs {
+        bucket: String,
+        root: String,
+        /// Base64 encoded json of the service account credential.
+        #[serde(default)]
+        credential: Option<String>,
+        /// OAuth scope of the credential, defaults to read-write access.
+        #[serde(default)]
+        scope: Option<String>,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+}
+
+/// Retry policy of the requests to the object store, retries with exponential backoff.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectStoreRetryOptions {
+    /// Max times to retry a failed request, `0` disables retrying.
+    pub max_times: usize,
+    /// Delay before the first retry.
+    pub min_delay_millis: u64,
+    /// Max delay between retries.
+    pub max_delay_millis: u64,
+}
+
+impl Default for ObjectStoreRetryOptions {
+    fn default() -> Self {
+        Self {
+            max_times: 3,
+            min_delay_millis: 1000,
+            max_delay_millis: 60_000,
+        }
+    }
+}
+
+/// Limits of the requests to the object store.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectStoreLimitOptions {
+    /// Timeout of each attempt of a request, timed out requests are retried.
+    pub timeout_millis: Option<u64>,
+    /// Max requests per second.
+    pub ops_per_second: Option<u64>,
+    /// Max bytes read and written per second.
+    pub bytes_per_second: Option<u64>,
 }
 
 impl Default for ObjectStoreConfig {
@@ -52,11 +132,119 @@ pub struct DatanodeOptions {
     pub rpc_runtime_size: usize,
     pub mysql_addr: String,
     pub mysql_runtime_size: usize,
+    /// TLS of the gRPC server, enables mutual TLS with frontends if the CA to verify
+    /// their certificates is set.
+    #[serde(default)]
+    pub rpc_tls: TlsOption,
+    #[serde(default)]
+    pub mysql_tls: TlsOption,
+    /// Options of the HTTP server, which serves the health and readiness probes. It's
+    /// only started in distributed mode, and disabled if absent.
+    #[serde(default)]
+    pub http_opts: Option<HttpOptions>,
     pub meta_client_opts: Option<MetaClientOpts>,
     pub wal_dir: String,
+    #[serde(default)]
+    pub wal: WalOptions,
     pub storage: ObjectStoreConfig,
+    #[serde(default)]
+    pub storage_retry: ObjectStoreRetryOptions,
+    #[serde(default)]
+    pub storage_limit: ObjectStoreLimitOptions,
+    /// Object store of the files imported by `COPY FROM` and external tables, and
+    /// exported by `COPY TO`. It's separated from the `storage` of the table data, so
+    /// these statements never touch the files of the tables.
+    #[serde(default = "default_file_storage")]
+    pub file_storage: ObjectStoreConfig,
     pub enable_memory_catalog: bool,
+    /// Max number of tables to open concurrently on startup.
+    #[serde(default = "default_open_table_concurrency")]
+    pub open_table_concurrency: usize,
     pub mode: Mode,
+    /// Max time to wait for requests in flight to finish during shutdown.
+    #[serde(default = "default_shutdown_timeout_millis")]
+    pub shutdown_timeout_millis: u64,
+    /// Options of flushing memtables, reloadable at runtime.
+    #[serde(default)]
+    pub flush: FlushOptions,
+}
+
+/// How entries written to the WAL are persisted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalSyncMode {
+    /// Syncs the WAL file after each entry is written.
+    Sync,
+    /// Syncs entries written concurrently together.
+    #[default]
+    Group,
+    /// Never syncs the WAL file on writing, entries might be lost if the host crashes.
+    Async,
+}
+
+impl From<WalSyncMode> for SyncMode {
+    fn from(mode: WalSyncMode) -> Self {
+        match mode {
+            WalSyncMode::Sync => SyncMode::Sync,
+            WalSyncMode::Group => SyncMode::Group,
+            WalSyncMode::Async => SyncMode::Async,
+        }
+    }
+}
+
+/// Options of the WAL.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalOptions {
+    pub sync_mode: WalSyncMode,
+    /// Time to wait for more entries to commit together in group mode.
+    pub group_commit_window_millis: u64,
+    /// Max bytes of entries to commit together in group mode.
+    pub group_commit_max_bytes: usize,
+}
+
+impl Default for WalOptions {
+    fn default() -> Self {
+        let config = LogConfig::default();
+        Self {
+            sync_mode: WalSyncMode::default(),
+            group_commit_window_millis: config.group_commit_window.as_millis() as u64,
+            group_commit_max_bytes: config.group_commit_max_bytes,
+        }
+    }
+}
+
+/// Options of flushing memtables of regions to SST files.
+///
+/// These options can be changed without restart, see [RuntimeConfig](crate::reload::RuntimeConfig).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlushOptions {
+    /// Max size in bytes of the memtables of a region, the region is flushed once
+    /// it's exceeded.
+    pub max_write_buffer_size: usize,
+}
+
+impl Default for FlushOptions {
+    fn default() -> Self {
+        Self {
+            max_write_buffer_size: DEFAULT_MAX_WRITE_BUFFER_SIZE,
+        }
+    }
+}
+
+fn default_file_storage() -> ObjectStoreConfig {
+    ObjectStoreConfig::File {
+        data_dir: "/tmp/greptimedb/files/".to_string(),
+    }
+}
+
+fn default_shutdown_timeout_millis() -> u64 {
+    30_000
+}
+
+fn default_open_table_concurrency() -> usize {
+    DEFAULT_OPEN_TABLE_CONCURRENCY
 }
 
 impl Default for DatanodeOptions {
@@ -67,11 +255,21 @@ impl Default for DatanodeOptions {
             rpc_runtime_size: 8,
             mysql_addr: "127.0.0.1:4406".to_string(),
             mysql_runtime_size: 2,
+            rpc_tls: TlsOption::default(),
+            mysql_tls: TlsOption::default(),
+            http_opts: None,
             meta_client_opts: None,
             wal_dir: "/tmp/greptimedb/wal".to_string(),
+            wal: WalOptions::default(),
             storage: ObjectStoreConfig::default(),
+            storage_retry: ObjectStoreRetryOptions::default(),
+            storage_limit: ObjectStoreLimitOptions::default(),
+            file_storage: default_file_storage(),
             enable_memory_catalog: false,
+            open_table_concurrency: default_open_table_concurrency(),
             mode: Mode::Standalone,
+            shutdown_timeout_millis: default_shutdown_timeout_millis(),
+            flush: FlushOptions::default(),
         }
     }
 }
@@ -94,23 +292,58 @@ impl Datanode {
         })
     }
 
-    pub async fn start(&mut self) -> Result<()> {
+    /// Start the datanode, this method call will block until services are shutdown.
+    ///
+    /// The HTTP server is started along with the instance, so the readiness probe
+    /// reports the progress of the startup.
+    pub async fn start(&self) -> Result<()> {
         info!("Starting datanode instance...");
-        self.start_instance().await?;
-        self.start_services().await
+        let _ = futures::future::try_join(self.start_http(), async {
+            self.start_instance().await?;
+            self.start_services().await
+        })
+        .await?;
+        Ok(())
     }
 
     /// Start only the internal component of datanode.
-    pub async fn start_instance(&mut self) -> Result<()> {
+    pub async fn start_instance(&self) -> Result<()> {
         self.instance.start().await
     }
 
     /// Start services of datanode. This method call will block until services are shutdown.
-    pub async fn start_services(&mut self) -> Result<()> {
+    pub async fn start_services(&self) -> Result<()> {
         self.services.start(&self.opts).await
     }
 
+    /// Start the HTTP server of datanode if it's enabled. This method call will block
+    /// until the server is shutdown.
+    pub async fn start_http(&self) -> Result<()> {
+        self.services.start_http(&self.opts).await
+    }
+
+    /// Gracefully shutdown the datanode, the instance stops accepting writes and
+    /// persists its data before servers are shutdown.
+    ///
+    /// Requests in flight are still served after this method returns, callers may
+    /// wait for [Datanode::start_services] to return.
+    pub async fn shutdown(&self) -> Result<()> {
+        info!("Shutting down datanode...");
+        self.instance.shutdown().await?;
+        self.services.shutdown().await
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.opts.shutdown_timeout_millis)
+    }
+
     pub fn get_instance(&self) -> InstanceRef {
         self.instance.clone()
     }
+
+    /// Applies the options that can be changed without restart, other options in
+    /// `opts` are ignored.
+    pub fn reload(&self, opts: &DatanodeOptions) -> Result<()> {
+        self.instance.runtime_config().reload(opts)
+    }
 }
@@ -72,6 +72,19 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display("Failed to rename table {} in catalog, source: {}", table_name, source))]
+    RenameTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: catalog::error::Error,
+    },
+
+    #[snafu(display("Table already exists: {}", table_name))]
+    TableExists {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to drop table {}, source: {}", table_name, source))]
     DropTable {
         table_name: String,
@@ -82,6 +95,26 @@ pub enum Error {
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound { table_name: String },
 
+    #[snafu(display("Failed to open table {}, source: {}", table_name, source))]
+    OpenTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display("Failed to close table {}, source: {}", table_name, source))]
+    CloseTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display("Invalid instruction from metasrv: {}", reason))]
+    InvalidInstruction {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Column {} not found in table {}", column_name, table_name))]
     ColumnNotFound {
         column_name: String,
@@ -111,6 +144,33 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display("Failed to delete from table: {}, source: {}", table_name, source))]
+    Delete {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to truncate table: {}, source: {}", table_name, source))]
+    TruncateTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display(
+        "Failed to scan table {} at sequence {}, source: {}",
+        table_name,
+        sequence,
+        source
+    ))]
+    ScanAtSequence {
+        table_name: String,
+        sequence: u64,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
     #[snafu(display("Failed to start server, source: {}", source))]
     StartServer {
         #[snafu(backtrace)]
@@ -147,6 +207,47 @@ pub enum Error {
         source: log_store::error::Error,
     },
 
+    #[snafu(display("Failed to stop log store, source: {}", source))]
+    StopLogStore {
+        #[snafu(backtrace)]
+        source: log_store::error::Error,
+    },
+
+    #[snafu(display("Failed to shutdown server, source: {}", source))]
+    ShutdownServer {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Failed to flush table {}, source: {}", table_name, source))]
+    FlushTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to fence region {} of table {}, source: {}",
+        region_number,
+        table_name,
+        source
+    ))]
+    FenceRegion {
+        table_name: String,
+        region_number: u32,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
+
+    #[snafu(display("Failed to send heartbeat to metasrv, source: {}", source))]
+    SendHeartbeat {
+        #[snafu(backtrace)]
+        source: meta_client::error::Error,
+    },
+
+    #[snafu(display("Datanode is shutting down, writes are not accepted"))]
+    ShuttingDown { backtrace: Backtrace },
+
     #[snafu(display("Failed to storage engine, source: {}", source))]
     OpenStorageEngine { source: StorageError },
 
@@ -193,6 +294,9 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Not supported: {}", feat))]
+    NotSupported { feat: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to insert into system catalog table, source: {}", source))]
     InsertSystemCatalog {
         #[snafu(backtrace)]
@@ -240,6 +344,12 @@ pub enum Error {
         source: common_time::error::Error,
     },
 
+    #[snafu(display("Invalid time zone, source: {}", source))]
+    ParseTimeZone {
+        #[snafu(backtrace)]
+        source: common_time::error::Error,
+    },
+
     #[snafu(display("Failed to access catalog, source: {}", source))]
     Catalog {
         #[snafu(backtrace)]
@@ -313,6 +423,88 @@ pub enum Error {
         source: tonic::Status,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Failed to receive Flight data to put, source: {}", source))]
+    FlightPut {
+        source: tonic::Status,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid Flight put, reason: {}", reason))]
+    InvalidFlightPut {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid runtime config, reason: {}", reason))]
+    InvalidRuntimeConfig {
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to register tasks table, source: {}", source))]
+    RegisterTasksTable {
+        #[snafu(backtrace)]
+        source: catalog::error::Error,
+    },
+
+    #[snafu(display("Tasks table not found"))]
+    TasksTableNotFound { backtrace: Backtrace },
+
+    #[snafu(display("Task already exists: {}", name))]
+    TaskExists { name: String, backtrace: Backtrace },
+
+    #[snafu(display("Task not found: {}", name))]
+    TaskNotFound { name: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid task {}, reason: {}", name, reason))]
+    InvalidTask {
+        name: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid option {} of COPY TO, reason: {}", option, reason))]
+    InvalidCopyOption {
+        option: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to partition the results to export, source: {}", source))]
+    PartitionExportData {
+        source: datatypes::arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to encode the exported file {} in csv, source: {}",
+        path,
+        source
+    ))]
+    EncodeCsvFile {
+        path: String,
+        source: datatypes::arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to encode the exported file {} in parquet, source: {}",
+        path,
+        source
+    ))]
+    EncodeParquetFile {
+        path: String,
+        source: parquet::errors::ParquetError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to write the exported file {}, source: {}", path, source))]
+    WriteExportFile {
+        path: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -328,8 +520,16 @@ impl ErrorExt for Error {
             | Error::GetTable { source, .. }
             | Error::AlterTable { source, .. } => source.status_code(),
             Error::DropTable { source, .. } => source.status_code(),
+            Error::OpenTable { source, .. } | Error::CloseTable { source, .. } => {
+                source.status_code()
+            }
+            Error::RenameTable { source, .. } => source.status_code(),
+            Error::TableExists { .. } => StatusCode::TableAlreadyExists,
 
-            Error::Insert { source, .. } => source.status_code(),
+            Error::Insert { source, .. }
+            | Error::Delete { source, .. }
+            | Error::TruncateTable { source, .. }
+            | Error::ScanAtSequence { source, .. } => source.status_code(),
 
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
             Error::ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
@@ -356,7 +556,15 @@ impl ErrorExt for Error {
             | Error::CatalogNotFound { .. }
             | Error::SchemaNotFound { .. }
             | Error::ConstraintNotSupported { .. }
-            | Error::ParseTimestamp { .. } => StatusCode::InvalidArguments,
+            | Error::InvalidFlightPut { .. }
+            | Error::InvalidRuntimeConfig { .. }
+            | Error::TaskExists { .. }
+            | Error::TaskNotFound { .. }
+            | Error::InvalidTask { .. }
+            | Error::InvalidCopyOption { .. }
+            | Error::ParseTimestamp { .. }
+            | Error::ParseTimeZone { .. }
+            | Error::InvalidInstruction { .. } => StatusCode::InvalidArguments,
 
             // TODO(yingwen): Further categorize http error.
             Error::StartServer { .. }
@@ -369,20 +577,38 @@ impl ErrorExt for Error {
             | Error::Catalog { .. }
             | Error::MissingRequiredField { .. }
             | Error::FlightGet { .. }
+            | Error::FlightPut { .. }
             | Error::InvalidFlightTicket { .. }
+            | Error::TasksTableNotFound { .. }
+            | Error::PartitionExportData { .. }
+            | Error::EncodeCsvFile { .. }
+            | Error::EncodeParquetFile { .. }
             | Error::IncorrectInternalState { .. } => StatusCode::Internal,
 
-            Error::InitBackend { .. } => StatusCode::StorageUnavailable,
+            Error::InitBackend { .. } | Error::WriteExportFile { .. } => {
+                StatusCode::StorageUnavailable
+            }
             Error::OpenLogStore { source } => source.status_code(),
             Error::StartScriptManager { source } => source.status_code(),
+            Error::RegisterTasksTable { source } => source.status_code(),
             Error::OpenStorageEngine { source } => source.status_code(),
             Error::RuntimeResource { .. } => StatusCode::RuntimeResourcesExhausted,
             Error::MetaClientInit { source, .. } => source.status_code(),
-            Error::TableIdProviderNotFound { .. } => StatusCode::Unsupported,
+            Error::TableIdProviderNotFound { .. } | Error::NotSupported { .. } => {
+                StatusCode::Unsupported
+            }
             Error::BumpTableId { source, .. } => source.status_code(),
             Error::MissingNodeId { .. } => StatusCode::InvalidArguments,
             Error::MissingMetasrvOpts { .. } => StatusCode::InvalidArguments,
-            Error::StartLogStore { source, .. } => source.status_code(),
+            Error::StartLogStore { source, .. } | Error::StopLogStore { source, .. } => {
+                source.status_code()
+            }
+            Error::ShutdownServer { source, .. } => source.status_code(),
+            Error::FlushTable { source, .. } | Error::FenceRegion { source, .. } => {
+                source.status_code()
+            }
+            Error::SendHeartbeat { source, .. } => source.status_code(),
+            Error::ShuttingDown { .. } => StatusCode::StorageUnavailable,
             Error::PollRecordbatchStream { source } => source.status_code(),
         }
     }
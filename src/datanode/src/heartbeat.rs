@@ -12,26 +12,50 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod instruction;
+
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, Peer};
+use api::v1::meta::{
+    HeartbeatRequest, HeartbeatResponse, Instruction, InstructionReply, NodeStat, Peer, RegionStat,
+    TableName,
+};
+use catalog::CatalogManagerRef;
 use common_telemetry::{error, info, warn};
 use meta_client::client::{HeartbeatSender, MetaClient};
 use snafu::ResultExt;
+use store_api::storage::RegionId;
+use table::engine::TableEngineRef;
 
-use crate::error::{MetaClientInitSnafu, Result};
+use crate::error::{CatalogSnafu, MetaClientInitSnafu, Result, SendHeartbeatSnafu};
+use crate::heartbeat::instruction::InstructionExecutor;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Clone)]
 pub struct HeartbeatTask {
     node_id: u64,
     server_addr: String,
     running: Arc<AtomicBool>,
+    /// Whether the heartbeat stream to metasrv is established, i.e. the last heartbeat
+    /// was sent and metasrv responded.
+    established: Arc<AtomicBool>,
     meta_client: Arc<MetaClient>,
+    catalog_manager: CatalogManagerRef,
+    executor: InstructionExecutor,
+    /// Replies of the executed instructions, sent to metasrv in the next heartbeat.
+    replies: Arc<Mutex<Vec<InstructionReply>>>,
     interval: u64,
 }
 
+/// Counters of a region reported in the last heartbeat.
+#[derive(Debug, Clone, Copy, Default)]
+struct ReportedCounters {
+    write_rows: u64,
+    scans: u64,
+}
+
 impl Drop for HeartbeatTask {
     fn drop(&mut self) {
         self.running.store(false, Ordering::Release);
@@ -40,19 +64,37 @@ impl Drop for HeartbeatTask {
 
 impl HeartbeatTask {
     /// Create a new heartbeat task instance.
-    pub fn new(node_id: u64, server_addr: String, meta_client: Arc<MetaClient>) -> Self {
+    pub fn new(
+        node_id: u64,
+        server_addr: String,
+        meta_client: Arc<MetaClient>,
+        catalog_manager: CatalogManagerRef,
+        table_engine: TableEngineRef,
+    ) -> Self {
         Self {
             node_id,
             server_addr,
             running: Arc::new(AtomicBool::new(false)),
+            established: Arc::new(AtomicBool::new(false)),
             meta_client,
+            executor: InstructionExecutor::new(catalog_manager.clone(), table_engine),
+            catalog_manager,
+            replies: Arc::new(Mutex::new(Vec::new())),
             interval: 5_000, // default interval is set to 5 secs
         }
     }
 
-    pub async fn create_streams(
+    /// Returns whether the heartbeat stream to metasrv is established.
+    pub fn is_established(&self) -> bool {
+        self.established.load(Ordering::Acquire)
+    }
+
+    async fn create_streams(
         meta_client: &MetaClient,
         running: Arc<AtomicBool>,
+        established: Arc<AtomicBool>,
+        executor: InstructionExecutor,
+        replies: Arc<Mutex<Vec<InstructionReply>>>,
     ) -> Result<HeartbeatSender> {
         let (tx, mut rx) = meta_client.heartbeat().await.context(MetaClientInitSnafu)?;
         common_runtime::spawn_bg(async move {
@@ -63,18 +105,38 @@ impl HeartbeatTask {
                     None
                 }
             } {
-                Self::handle_response(res).await;
+                established.store(true, Ordering::Release);
+                Self::handle_response(res, &executor, &replies).await;
                 if !running.load(Ordering::Acquire) {
                     info!("Heartbeat task shutdown");
                 }
             }
+            established.store(false, Ordering::Release);
             info!("Heartbeat handling loop exit.")
         });
         Ok(tx)
     }
 
-    async fn handle_response(resp: HeartbeatResponse) {
+    /// Executes the instructions in the response, their replies are sent in the next
+    /// heartbeat.
+    async fn handle_response(
+        resp: HeartbeatResponse,
+        executor: &InstructionExecutor,
+        replies: &Mutex<Vec<InstructionReply>>,
+    ) {
         info!("heartbeat response: {:?}", resp);
+
+        for payload in &resp.payload {
+            let instruction = match Instruction::try_from(payload.as_slice()) {
+                Ok(instruction) => instruction,
+                Err(e) => {
+                    error!("Failed to decode instruction from metasrv, error: {}", e);
+                    continue;
+                }
+            };
+            let reply = executor.execute(instruction).await;
+            replies.lock().unwrap().push(reply);
+        }
     }
 
     /// Start heartbeat task, spawn background task.
@@ -90,21 +152,53 @@ impl HeartbeatTask {
         let interval = self.interval;
         let node_id = self.node_id;
         let server_addr = self.server_addr.clone();
+        let established = self.established.clone();
         let meta_client = self.meta_client.clone();
+        let catalog_manager = self.catalog_manager.clone();
+        let executor = self.executor.clone();
+        let replies = self.replies.clone();
 
-        let mut tx = Self::create_streams(&meta_client, running.clone()).await?;
+        let mut tx = Self::create_streams(
+            &meta_client,
+            running.clone(),
+            established.clone(),
+            executor.clone(),
+            replies.clone(),
+        )
+        .await?;
         common_runtime::spawn_bg(async move {
+            let mut reported = HashMap::new();
             while running.load(Ordering::Acquire) {
+                let (node_stat, region_stats) = match collect_stats(&catalog_manager, &mut reported)
+                {
+                    Ok((node_stat, region_stats)) => (Some(node_stat), region_stats),
+                    Err(e) => {
+                        error!(e; "Failed to collect region stats");
+                        (None, Vec::new())
+                    }
+                };
                 let req = HeartbeatRequest {
                     peer: Some(Peer {
                         id: node_id,
                         addr: server_addr.clone(),
                     }),
+                    node_stat,
+                    region_stats,
+                    instruction_replies: std::mem::take(&mut *replies.lock().unwrap()),
                     ..Default::default()
                 };
                 if let Err(e) = tx.send(req).await {
                     error!("Failed to send heartbeat to metasrv, error: {:?}", e);
-                    match Self::create_streams(&meta_client, running.clone()).await {
+                    established.store(false, Ordering::Release);
+                    match Self::create_streams(
+                        &meta_client,
+                        running.clone(),
+                        established.clone(),
+                        executor.clone(),
+                        replies.clone(),
+                    )
+                    .await
+                    {
                         Ok(new_tx) => {
                             info!("Reconnected to metasrv");
                             tx = new_tx;
@@ -120,4 +214,121 @@ impl HeartbeatTask {
 
         Ok(())
     }
+
+    /// Stop the heartbeat task and send the last heartbeat to tell metasrv that this
+    /// node is leaving.
+    pub async fn stop(&self) -> Result<()> {
+        if self
+            .running
+            .compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Heartbeat task is not running");
+            return Ok(());
+        }
+
+        let tx = Self::create_streams(
+            &self.meta_client,
+            self.running.clone(),
+            self.established.clone(),
+            self.executor.clone(),
+            self.replies.clone(),
+        )
+        .await?;
+        let req = HeartbeatRequest {
+            peer: Some(Peer {
+                id: self.node_id,
+                addr: self.server_addr.clone(),
+            }),
+            is_leaving: true,
+            ..Default::default()
+        };
+        tx.send(req).await.context(SendHeartbeatSnafu)?;
+        info!("Sent the last heartbeat, node id: {}", self.node_id);
+
+        Ok(())
+    }
+}
+
+/// Collects stats of the regions on this node. The read and write capacity units are
+/// the number of scans and rows written since the last heartbeat, whose counters are
+/// kept in `reported`.
+fn collect_stats(
+    catalog_manager: &CatalogManagerRef,
+    reported: &mut HashMap<RegionId, ReportedCounters>,
+) -> Result<(NodeStat, Vec<RegionStat>)> {
+    let mut node_stat = NodeStat::default();
+    let mut region_stats = Vec::new();
+    let mut counters = HashMap::with_capacity(reported.len());
+    for catalog_name in catalog_manager.catalog_names().context(CatalogSnafu)? {
+        let Some(catalog) = catalog_manager
+            .catalog(&catalog_name)
+            .context(CatalogSnafu)?
+        else {
+            continue;
+        };
+        for schema_name in catalog.schema_names().context(CatalogSnafu)? {
+            let Some(schema) = catalog.schema(&schema_name).context(CatalogSnafu)? else {
+                continue;
+            };
+            for table_name in schema.table_names().context(CatalogSnafu)? {
+                let Some(table) = schema.table(&table_name).context(CatalogSnafu)? else {
+                    continue;
+                };
+                let region_metrics = table.region_metrics();
+                if region_metrics.is_empty() {
+                    continue;
+                }
+                node_stat.table_num += 1;
+
+                let table_id = table.table_info().ident.table_id;
+                for (region_number, metrics) in region_metrics {
+                    // Same as the region id allocated by the table engine.
+                    let region_id = (u64::from(table_id) << 32) | u64::from(region_number);
+                    let last = reported.get(&region_id).copied().unwrap_or_default();
+                    // Counters are reset if the region is reopened.
+                    let wcus = metrics.write_rows.saturating_sub(last.write_rows);
+                    let rcus = metrics.scans.saturating_sub(last.scans);
+                    let _ = counters.insert(
+                        region_id,
+                        ReportedCounters {
+                            write_rows: metrics.write_rows,
+                            scans: metrics.scans,
+                        },
+                    );
+
+                    node_stat.region_num += 1;
+                    node_stat.wcus += wcus;
+                    node_stat.rcus += rcus;
+                    region_stats.push(RegionStat {
+                        region_id,
+                        table_name: Some(TableName {
+                            catalog_name: catalog_name.clone(),
+                            schema_name: schema_name.clone(),
+                            table_name: table_name.clone(),
+                        }),
+                        rcus,
+                        wcus,
+                        approximate_size: metrics.approximate_bytes(),
+                        approximate_rows: metrics.num_rows,
+                        attrs: HashMap::from([
+                            (
+                                "memtable_bytes".to_string(),
+                                metrics.memtable_bytes.to_string(),
+                            ),
+                            (
+                                "sst_files_per_level".to_string(),
+                                format!("{:?}", metrics.sst_files_per_level),
+                            ),
+                            ("wal_bytes".to_string(), metrics.wal_bytes.to_string()),
+                        ]),
+                    });
+                }
+            }
+        }
+    }
+    // Dropped regions are removed from the counters.
+    *reported = counters;
+
+    Ok((node_stat, region_stats))
 }
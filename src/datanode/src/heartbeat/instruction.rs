@@ -0,0 +1,382 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::{instruction, Instruction, InstructionReply, TableName};
+use catalog::{CatalogManagerRef, DeregisterTableRequest, RegisterTableRequest};
+use common_telemetry::{error, info};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::RegionNumber;
+use table::engine::{EngineContext, TableEngineRef};
+use table::metadata::TableId;
+use table::requests::{CloseTableRequest, OpenTableRequest};
+use table::TableRef;
+
+use crate::error::{self, CatalogSnafu, Result};
+
+/// Executes the instructions from metasrv to migrate regions.
+///
+/// Regions are opened from the object store, so the datanodes must share the object
+/// store. The source fences the region from writes before flushing it, so no rows are
+/// written to the source after the snapshot, and the target opens all rows of the
+/// region from the object store. Writes to the region fail until its route is flipped
+/// to the target, or until the source unfences it if the migration fails.
+#[derive(Clone)]
+pub(crate) struct InstructionExecutor {
+    catalog_manager: CatalogManagerRef,
+    table_engine: TableEngineRef,
+}
+
+impl InstructionExecutor {
+    pub(crate) fn new(catalog_manager: CatalogManagerRef, table_engine: TableEngineRef) -> Self {
+        Self {
+            catalog_manager,
+            table_engine,
+        }
+    }
+
+    /// Executes the instruction and returns the reply to metasrv. Metasrv sends the
+    /// instruction again until it receives the reply, so executing an instruction that
+    /// is already executed does nothing.
+    pub(crate) async fn execute(&self, instruction: Instruction) -> InstructionReply {
+        let result = self.try_execute(&instruction).await;
+        let error = match &result {
+            Ok(()) => String::new(),
+            Err(e) => {
+                error!(e; "Failed to execute instruction: {:?}", instruction);
+                e.to_string()
+            }
+        };
+
+        InstructionReply {
+            instruction: Some(instruction),
+            success: result.is_ok(),
+            error,
+        }
+    }
+
+    async fn try_execute(&self, instruction: &Instruction) -> Result<()> {
+        let (Some(region), Some(kind)) = (&instruction.region, &instruction.kind) else {
+            return error::InvalidInstructionSnafu {
+                reason: "region and kind are required",
+            }
+            .fail();
+        };
+        let table_name = region
+            .table_name
+            .as_ref()
+            .context(error::InvalidInstructionSnafu {
+                reason: "table name is required",
+            })?;
+        info!(
+            "Execute instruction of region migration {}, region: {:?}, kind: {:?}",
+            instruction.migration_id, region, kind
+        );
+
+        match kind {
+            instruction::Kind::SnapshotRegion(_) => {
+                self.snapshot_region(table_name, region.region_number).await
+            }
+            instruction::Kind::OpenRegion(_) => {
+                self.open_region(table_name, region.table_id, region.region_number)
+                    .await
+            }
+            instruction::Kind::CloseRegion(_) => {
+                self.close_region(table_name, region.region_number).await
+            }
+            instruction::Kind::UnfenceRegion(_) => {
+                self.unfence_region(table_name, region.region_number).await
+            }
+        }
+    }
+
+    /// Fences the region from writes and flushes the table, so all rows of the region
+    /// could be opened from the object store.
+    async fn snapshot_region(
+        &self,
+        table_name: &TableName,
+        region_number: RegionNumber,
+    ) -> Result<()> {
+        let full_table_name = full_table_name(table_name);
+        let table = self
+            .table(table_name)?
+            .with_context(|| error::TableNotFoundSnafu {
+                table_name: &full_table_name,
+            })?;
+        ensure!(
+            table
+                .table_info()
+                .meta
+                .region_numbers
+                .contains(&region_number),
+            error::InvalidInstructionSnafu {
+                reason: format!("region {region_number} of table {full_table_name} is not opened"),
+            }
+        );
+
+        table
+            .fence_region(region_number, true)
+            .await
+            .context(error::FenceRegionSnafu {
+                table_name: &full_table_name,
+                region_number,
+            })?;
+        table.flush().await.context(error::FlushTableSnafu {
+            table_name: full_table_name,
+        })
+    }
+
+    /// Accepts writes to the region again, does nothing if the region is not opened.
+    async fn unfence_region(
+        &self,
+        table_name: &TableName,
+        region_number: RegionNumber,
+    ) -> Result<()> {
+        let Some(table) = self.table(table_name)? else {
+            return Ok(());
+        };
+        if !table
+            .table_info()
+            .meta
+            .region_numbers
+            .contains(&region_number)
+        {
+            return Ok(());
+        }
+
+        table
+            .fence_region(region_number, false)
+            .await
+            .context(error::FenceRegionSnafu {
+                table_name: full_table_name(table_name),
+                region_number,
+            })
+    }
+
+    async fn open_region(
+        &self,
+        table_name: &TableName,
+        table_id: TableId,
+        region_number: RegionNumber,
+    ) -> Result<()> {
+        let mut region_numbers = match self.table(table_name)? {
+            Some(table) => table.table_info().meta.region_numbers.clone(),
+            None => vec![],
+        };
+        if region_numbers.contains(&region_number) {
+            return Ok(());
+        }
+        region_numbers.push(region_number);
+        region_numbers.sort_unstable();
+
+        self.reopen_table(table_name, table_id, region_numbers)
+            .await
+    }
+
+    /// Closes the region after its route is flipped to the target. The region is fenced
+    /// and flushed by the snapshot, so the flush has marked its WAL obsolete and the
+    /// node keeps nothing of the region. Its data in the object store is left to the
+    /// target.
+    async fn close_region(
+        &self,
+        table_name: &TableName,
+        region_number: RegionNumber,
+    ) -> Result<()> {
+        let Some(table) = self.table(table_name)? else {
+            return Ok(());
+        };
+        let table_info = table.table_info();
+        let mut region_numbers = table_info.meta.region_numbers.clone();
+        if !region_numbers.contains(&region_number) {
+            return Ok(());
+        }
+        region_numbers.retain(|r| *r != region_number);
+
+        if region_numbers.is_empty() {
+            self.close_table(table_name).await
+        } else {
+            self.reopen_table(table_name, table_info.ident.table_id, region_numbers)
+                .await
+        }
+    }
+
+    /// Reopens the table with the regions and registers it to the catalog. Opening more
+    /// than one region of a table requires the partition rule of the table.
+    ///
+    /// Regions opened before are fenced first, so no rows are written to them once they
+    /// are closed, and unflushed rows of the regions kept are recovered by replaying the
+    /// WAL from their flushed sequences. If the table fails to reopen, the previous
+    /// regions are opened and registered again, with the regions to close still fenced.
+    async fn reopen_table(
+        &self,
+        table_name: &TableName,
+        table_id: TableId,
+        region_numbers: Vec<RegionNumber>,
+    ) -> Result<()> {
+        let full_table_name = full_table_name(table_name);
+        let prev_regions = match self.table(table_name)? {
+            Some(table) => {
+                let prev_regions = table.table_info().meta.region_numbers.clone();
+                for region_number in &prev_regions {
+                    table.fence_region(*region_number, true).await.context(
+                        error::FenceRegionSnafu {
+                            table_name: &full_table_name,
+                            region_number: *region_number,
+                        },
+                    )?;
+                }
+                prev_regions
+            }
+            None => vec![],
+        };
+        self.close_table(table_name).await?;
+
+        let result = self
+            .open_table(table_name, table_id, region_numbers.clone())
+            .await;
+        if result.is_err() && !prev_regions.is_empty() {
+            if let Err(e) = self
+                .restore_table(table_name, table_id, prev_regions, &region_numbers)
+                .await
+            {
+                error!(e; "Failed to restore table {} after failing to reopen it", full_table_name);
+            }
+        }
+        result
+    }
+
+    /// Opens the previous regions of the table that failed to reopen, and fences the
+    /// regions that aren't in `region_numbers`.
+    async fn restore_table(
+        &self,
+        table_name: &TableName,
+        table_id: TableId,
+        prev_regions: Vec<RegionNumber>,
+        region_numbers: &[RegionNumber],
+    ) -> Result<()> {
+        // The table might be opened but not registered.
+        self.close_table(table_name).await?;
+        self.open_table(table_name, table_id, prev_regions).await?;
+
+        let table = self
+            .table(table_name)?
+            .with_context(|| error::TableNotFoundSnafu {
+                table_name: full_table_name(table_name),
+            })?;
+        let regions = table.table_info().meta.region_numbers.clone();
+        for region_number in regions.into_iter().filter(|r| !region_numbers.contains(r)) {
+            table
+                .fence_region(region_number, true)
+                .await
+                .context(error::FenceRegionSnafu {
+                    table_name: full_table_name(table_name),
+                    region_number,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Opens the table with the regions and registers it to the catalog.
+    async fn open_table(
+        &self,
+        table_name: &TableName,
+        table_id: TableId,
+        region_numbers: Vec<RegionNumber>,
+    ) -> Result<()> {
+        let full_table_name = full_table_name(table_name);
+        let request = OpenTableRequest {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+            table_id,
+            region_numbers,
+        };
+        let table = self
+            .table_engine
+            .open_table(&EngineContext::default(), request)
+            .await
+            .context(error::OpenTableSnafu {
+                table_name: &full_table_name,
+            })?
+            .context(error::TableNotFoundSnafu {
+                table_name: &full_table_name,
+            })?;
+        let region_numbers = table.table_info().meta.region_numbers.clone();
+
+        let request = RegisterTableRequest {
+            catalog: table_name.catalog_name.clone(),
+            schema: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+            table_id,
+            table,
+        };
+        let _ = self
+            .catalog_manager
+            .register_table(request)
+            .await
+            .context(CatalogSnafu)?;
+        info!(
+            "Table {} opened with regions {:?}",
+            full_table_name, region_numbers
+        );
+
+        Ok(())
+    }
+
+    /// Deregisters the table from the catalog and closes it, the data of the table is
+    /// kept in the object store.
+    async fn close_table(&self, table_name: &TableName) -> Result<()> {
+        let request = DeregisterTableRequest {
+            catalog: table_name.catalog_name.clone(),
+            schema: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+        };
+        let _ = self
+            .catalog_manager
+            .deregister_table(request)
+            .await
+            .context(CatalogSnafu)?;
+
+        let request = CloseTableRequest {
+            catalog_name: table_name.catalog_name.clone(),
+            schema_name: table_name.schema_name.clone(),
+            table_name: table_name.table_name.clone(),
+        };
+        let _ = self
+            .table_engine
+            .close_table(&EngineContext::default(), request)
+            .await
+            .context(error::CloseTableSnafu {
+                table_name: full_table_name(table_name),
+            })?;
+
+        Ok(())
+    }
+
+    fn table(&self, table_name: &TableName) -> Result<Option<TableRef>> {
+        self.catalog_manager
+            .table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            )
+            .context(CatalogSnafu)
+    }
+}
+
+fn full_table_name(table_name: &TableName) -> String {
+    format!(
+        "{}.{}.{}",
+        table_name.catalog_name, table_name.schema_name, table_name.table_name
+    )
+}
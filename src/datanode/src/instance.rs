@@ -12,26 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{fs, path};
 
 use backon::ExponentialBackoff;
-use catalog::remote::MetaKvBackend;
+use catalog::remote::{MetaKvBackend, MetaTableIdProvider};
 use catalog::CatalogManagerRef;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
-use common_telemetry::logging::info;
+use common_telemetry::logging::{info, warn};
 use log_store::fs::config::LogConfig;
 use log_store::fs::log::LocalFileLogStore;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_client::MetaClientOpts;
 use mito::config::EngineConfig as TableEngineConfig;
 use mito::engine::MitoEngine;
-use object_store::layers::{LoggingLayer, MetricsLayer, RetryLayer, TracingLayer};
+use object_store::layers::{
+    LoggingLayer, MetricsLayer, RateLimitLayer, RetryLayer, TimeoutLayer, TracingLayer,
+};
+use object_store::services::azblob::Builder as AzblobBuilder;
 use object_store::services::fs::Builder as FsBuilder;
+use object_store::services::gcs::Builder as GcsBuilder;
 use object_store::services::s3::Builder as S3Builder;
 use object_store::{util, ObjectStore};
 use query::query_engine::{QueryEngineFactory, QueryEngineRef};
+use servers::query_handler::ReadinessHandler;
 use servers::Mode;
 use snafu::prelude::*;
 use storage::config::EngineConfig as StorageEngineConfig;
@@ -39,14 +46,17 @@ use storage::EngineImpl;
 use store_api::logstore::LogStore;
 use table::table::TableIdProviderRef;
 
-use crate::datanode::{DatanodeOptions, ObjectStoreConfig};
+use crate::datanode::{DatanodeOptions, ObjectStoreConfig, WalOptions};
 use crate::error::{
-    self, CatalogSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu,
-    NewCatalogSnafu, Result, StartLogStoreSnafu,
+    self, CatalogSnafu, FlushTableSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu,
+    MissingNodeIdSnafu, NewCatalogSnafu, Result, ShuttingDownSnafu, StartLogStoreSnafu,
+    StopLogStoreSnafu,
 };
 use crate::heartbeat::HeartbeatTask;
+use crate::reload::{validate_flush_options, RuntimeConfig};
 use crate::script::ScriptExecutor;
 use crate::sql::SqlHandler;
+use crate::task::TaskManager;
 
 mod flight;
 mod grpc;
@@ -61,17 +71,27 @@ pub struct Instance {
     pub(crate) sql_handler: SqlHandler,
     pub(crate) catalog_manager: CatalogManagerRef,
     pub(crate) script_executor: ScriptExecutor,
+    pub(crate) task_manager: TaskManager,
     pub(crate) table_id_provider: Option<TableIdProviderRef>,
     pub(crate) heartbeat_task: Option<HeartbeatTask>,
-    pub(crate) logstore: Arc<LocalFileLogStore>,
+    /// The WAL of the instance, `None` if the instance doesn't persist its WAL.
+    pub(crate) logstore: Option<Arc<LocalFileLogStore>>,
+    /// Whether the catalog is loaded, tables and their regions are opened while loading.
+    pub(crate) catalog_loaded: AtomicBool,
+    /// Whether all components of the instance are started.
+    pub(crate) started: AtomicBool,
+    /// Whether the instance is shutting down, writes are rejected once it is set.
+    pub(crate) shutting_down: AtomicBool,
+    pub(crate) runtime_config: RuntimeConfig,
 }
 
 pub type InstanceRef = Arc<Instance>;
 
 impl Instance {
     pub async fn new(opts: &DatanodeOptions) -> Result<Self> {
-        let object_store = new_object_store(&opts.storage).await?;
-        let logstore = Arc::new(create_local_file_log_store(&opts.wal_dir).await?);
+        let object_store = new_object_store(opts).await?;
+        let file_store = new_file_store(opts).await?;
+        let logstore = Arc::new(create_local_file_log_store(&opts.wal_dir, &opts.wal).await?);
 
         let meta_client = match opts.mode {
             Mode::Standalone => None,
@@ -87,14 +107,20 @@ impl Instance {
             }
         };
 
-        let table_engine = Arc::new(DefaultEngine::new(
+        validate_flush_options(&opts.flush)?;
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig {
+                max_write_buffer_size: opts.flush.max_write_buffer_size,
+                ..Default::default()
+            },
+            logstore.clone(),
+            object_store.clone(),
+        );
+        let table_engine = Arc::new(DefaultEngine::with_file_store(
             TableEngineConfig::default(),
-            EngineImpl::new(
-                StorageEngineConfig::default(),
-                logstore.clone(),
-                object_store.clone(),
-            ),
+            storage_engine.clone(),
             object_store,
+            file_store.clone(),
         ));
 
         // create remote catalog manager
@@ -113,7 +139,8 @@ impl Instance {
                     let catalog = Arc::new(
                         catalog::local::LocalCatalogManager::try_new(table_engine.clone())
                             .await
-                            .context(CatalogSnafu)?,
+                            .context(CatalogSnafu)?
+                            .with_open_table_concurrency(opts.open_table_concurrency),
                     );
                     let factory = QueryEngineFactory::new(catalog.clone());
 
@@ -126,21 +153,34 @@ impl Instance {
             }
 
             Mode::Distributed => {
-                let catalog = Arc::new(catalog::remote::RemoteCatalogManager::new(
-                    table_engine.clone(),
-                    opts.node_id.context(MissingNodeIdSnafu)?,
-                    Arc::new(MetaKvBackend {
-                        client: meta_client.as_ref().unwrap().clone(),
-                    }),
-                ));
+                let catalog = Arc::new(
+                    catalog::remote::RemoteCatalogManager::new(
+                        table_engine.clone(),
+                        opts.node_id.context(MissingNodeIdSnafu)?,
+                        Arc::new(MetaKvBackend {
+                            client: meta_client.as_ref().unwrap().clone(),
+                        }),
+                    )
+                    .with_open_table_concurrency(opts.open_table_concurrency),
+                );
                 let factory = QueryEngineFactory::new(catalog.clone());
-                (catalog as CatalogManagerRef, factory, None)
+                // Table ids are allocated by metasrv, so they never collide with the ones
+                // of the tables created on other nodes.
+                let table_id_provider = Arc::new(MetaTableIdProvider::new(
+                    meta_client.as_ref().unwrap().clone(),
+                ));
+                (
+                    catalog as CatalogManagerRef,
+                    factory,
+                    Some(table_id_provider as TableIdProviderRef),
+                )
             }
         };
 
         let query_engine = factory.query_engine();
         let script_executor =
             ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?;
+        let task_manager = TaskManager::new(catalog_manager.clone(), query_engine.clone()).await?;
 
         let heartbeat_task = match opts.mode {
             Mode::Standalone => None,
@@ -148,6 +188,8 @@ impl Instance {
                 opts.node_id.context(MissingNodeIdSnafu)?,
                 opts.rpc_addr.clone(),
                 meta_client.as_ref().unwrap().clone(),
+                catalog_manager.clone(),
+                table_engine.clone(),
             )),
         };
         Ok(Self {
@@ -156,12 +198,18 @@ impl Instance {
                 table_engine,
                 catalog_manager.clone(),
                 query_engine.clone(),
+                file_store,
             ),
             catalog_manager,
             script_executor,
+            task_manager,
             heartbeat_task,
             table_id_provider,
-            logstore,
+            logstore: Some(logstore),
+            catalog_loaded: AtomicBool::new(false),
+            started: AtomicBool::new(false),
+            shutting_down: AtomicBool::new(false),
+            runtime_config: RuntimeConfig::new(opts.flush.clone(), storage_engine),
         })
     }
 
@@ -170,10 +218,77 @@ impl Instance {
             .start()
             .await
             .context(NewCatalogSnafu)?;
-        self.logstore.start().await.context(StartLogStoreSnafu)?;
+        self.catalog_loaded.store(true, Ordering::Release);
+        if let Some(logstore) = &self.logstore {
+            logstore.start().await.context(StartLogStoreSnafu)?;
+        }
+        // Tasks may call the functions defined by scripts.
+        self.script_executor.start().await?;
+        self.task_manager.start().await?;
         if let Some(task) = &self.heartbeat_task {
             task.start().await?;
         }
+        self.started.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Gracefully shutdown the instance.
+    ///
+    /// New writes are rejected, data in memtables of all tables are flushed, then
+    /// the WAL is stopped and the node tells metasrv that it is leaving.
+    pub async fn shutdown(&self) -> Result<()> {
+        if self.shutting_down.swap(true, Ordering::AcqRel) {
+            warn!("Datanode instance is already shutting down");
+            return Ok(());
+        }
+        info!("Shutting down datanode instance");
+
+        self.task_manager.stop();
+        self.flush_tables().await?;
+        if let Some(logstore) = &self.logstore {
+            logstore.stop().await.context(StopLogStoreSnafu)?;
+        }
+        if let Some(task) = &self.heartbeat_task {
+            task.stop().await?;
+        }
+
+        info!("Datanode instance is shutdown");
+        Ok(())
+    }
+
+    async fn flush_tables(&self) -> Result<()> {
+        for catalog_name in self.catalog_manager.catalog_names().context(CatalogSnafu)? {
+            let Some(catalog) = self
+                .catalog_manager
+                .catalog(&catalog_name)
+                .context(CatalogSnafu)?
+            else {
+                continue;
+            };
+            for schema_name in catalog.schema_names().context(CatalogSnafu)? {
+                let Some(schema) = catalog.schema(&schema_name).context(CatalogSnafu)? else {
+                    continue;
+                };
+                for table_name in schema.table_names().context(CatalogSnafu)? {
+                    let Some(table) = schema.table(&table_name).context(CatalogSnafu)? else {
+                        continue;
+                    };
+                    table.flush().await.context(FlushTableSnafu {
+                        table_name: format!("{catalog_name}.{schema_name}.{table_name}"),
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns error if the instance no longer accepts writes.
+    pub(crate) fn ensure_writable(&self) -> Result<()> {
+        ensure!(
+            !self.shutting_down.load(Ordering::Acquire),
+            ShuttingDownSnafu
+        );
         Ok(())
     }
 
@@ -184,46 +299,204 @@ impl Instance {
     pub fn catalog_manager(&self) -> &CatalogManagerRef {
         &self.catalog_manager
     }
+
+    pub fn runtime_config(&self) -> &RuntimeConfig {
+        &self.runtime_config
+    }
+}
+
+/// The instance is ready once its catalog is loaded and all its components are started,
+/// and in distributed mode, the heartbeat to metasrv is established.
+impl ReadinessHandler for Instance {
+    fn readiness(&self) -> BTreeMap<String, bool> {
+        let mut checks = BTreeMap::from([
+            (
+                "catalog_loaded".to_string(),
+                self.catalog_loaded.load(Ordering::Acquire),
+            ),
+            ("started".to_string(), self.started.load(Ordering::Acquire)),
+            (
+                "not_shutting_down".to_string(),
+                !self.shutting_down.load(Ordering::Acquire),
+            ),
+        ]);
+        if let Some(task) = &self.heartbeat_task {
+            let _ = checks.insert("heartbeat_established".to_string(), task.is_established());
+        }
+        checks
+    }
+}
+
+pub(crate) async fn new_object_store(opts: &DatanodeOptions) -> Result<ObjectStore> {
+    build_object_store(&opts.storage, opts).await
 }
 
-pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+/// Creates the object store of the files to import and export, see
+/// [DatanodeOptions::file_storage].
+pub(crate) async fn new_file_store(opts: &DatanodeOptions) -> Result<ObjectStore> {
+    build_object_store(&opts.file_storage, opts).await
+}
+
+/// Creates the object store by `store_config`, with the limits and retry policy of
+/// `opts`.
+async fn build_object_store(
+    store_config: &ObjectStoreConfig,
+    opts: &DatanodeOptions,
+) -> Result<ObjectStore> {
     let object_store = match store_config {
         ObjectStoreConfig::File { data_dir } => new_fs_object_store(data_dir).await,
-        ObjectStoreConfig::S3 { .. } => new_s3_object_store(store_config).await,
+        ObjectStoreConfig::S3 { .. } => new_s3_object_store(store_config),
+        ObjectStoreConfig::Azblob { .. } => new_azblob_object_store(store_config),
+        ObjectStoreConfig::Gcs { .. } => new_gcs_object_store(store_config),
+    }?;
+
+    // Layers added later wrap the former ones, so each retry is limited and timed out
+    // separately.
+    let limit = &opts.storage_limit;
+    let object_store = match limit.timeout_millis {
+        Some(timeout) => object_store.layer(TimeoutLayer::new(Duration::from_millis(timeout))),
+        None => object_store,
+    };
+    let object_store = if limit.ops_per_second.is_some() || limit.bytes_per_second.is_some() {
+        let mut layer = RateLimitLayer::new();
+        if let Some(ops) = limit.ops_per_second {
+            layer = layer.with_ops_per_second(ops);
+        }
+        if let Some(bytes) = limit.bytes_per_second {
+            layer = layer.with_bytes_per_second(bytes);
+        }
+        object_store.layer(layer)
+    } else {
+        object_store
     };
 
-    object_store.map(|object_store| {
+    let retry = &opts.storage_retry;
+    let object_store = if retry.max_times > 0 {
+        let backoff = ExponentialBackoff::default()
+            .with_max_times(retry.max_times)
+            .with_min_delay(Duration::from_millis(retry.min_delay_millis))
+            .with_max_delay(Duration::from_millis(retry.max_delay_millis))
+            .with_jitter();
+        object_store.layer(RetryLayer::new(backoff))
+    } else {
         object_store
-            .layer(RetryLayer::new(ExponentialBackoff::default().with_jitter()))
-            .layer(MetricsLayer)
-            .layer(LoggingLayer::default())
-            .layer(TracingLayer)
-    })
+    };
+
+    Ok(object_store
+        .layer(MetricsLayer)
+        .layer(LoggingLayer::default())
+        .layer(TracingLayer))
 }
 
-pub(crate) async fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
-    let (root, secret_key, key_id, bucket) = match store_config {
-        ObjectStoreConfig::S3 {
-            bucket,
-            root,
-            access_key_id,
-            secret_access_key,
-        } => (root, secret_access_key, access_key_id, bucket),
-        _ => unreachable!(),
+fn new_s3_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+    let ObjectStoreConfig::S3 {
+        bucket,
+        root,
+        access_key_id,
+        secret_access_key,
+        endpoint,
+        region,
+        enable_virtual_host_style,
+    } = store_config
+    else {
+        unreachable!()
     };
 
     let root = util::normalize_dir(root);
     info!("The s3 storage bucket is: {}, root is: {}", bucket, &root);
 
-    let accessor = S3Builder::default()
-        .root(&root)
-        .bucket(bucket)
-        .access_key_id(key_id)
-        .secret_access_key(secret_key)
-        .build()
-        .with_context(|_| error::InitBackendSnafu {
-            config: store_config.clone(),
-        })?;
+    let mut builder = S3Builder::default();
+    builder.root(&root).bucket(bucket);
+    if let Some(access_key_id) = access_key_id {
+        builder.access_key_id(access_key_id);
+    }
+    if let Some(secret_access_key) = secret_access_key {
+        builder.secret_access_key(secret_access_key);
+    }
+    if let Some(endpoint) = endpoint {
+        builder.endpoint(endpoint);
+    }
+    if let Some(region) = region {
+        builder.region(region);
+    }
+    if *enable_virtual_host_style {
+        builder.enable_virtual_host_style();
+    }
+
+    let accessor = builder.build().with_context(|_| error::InitBackendSnafu {
+        config: store_config.clone(),
+    })?;
+
+    Ok(ObjectStore::new(accessor))
+}
+
+fn new_azblob_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+    let ObjectStoreConfig::Azblob {
+        container,
+        root,
+        account_name,
+        account_key,
+        endpoint,
+    } = store_config
+    else {
+        unreachable!()
+    };
+
+    let root = util::normalize_dir(root);
+    info!(
+        "The azblob storage container is: {}, root is: {}",
+        container, &root
+    );
+
+    let mut builder = AzblobBuilder::default();
+    builder.root(&root).container(container);
+    if let Some(account_name) = account_name {
+        builder.account_name(account_name);
+    }
+    if let Some(account_key) = account_key {
+        builder.account_key(account_key);
+    }
+    if let Some(endpoint) = endpoint {
+        builder.endpoint(endpoint);
+    }
+
+    let accessor = builder.build().with_context(|_| error::InitBackendSnafu {
+        config: store_config.clone(),
+    })?;
+
+    Ok(ObjectStore::new(accessor))
+}
+
+fn new_gcs_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
+    let ObjectStoreConfig::Gcs {
+        bucket,
+        root,
+        credential,
+        scope,
+        endpoint,
+    } = store_config
+    else {
+        unreachable!()
+    };
+
+    let root = util::normalize_dir(root);
+    info!("The gcs storage bucket is: {}, root is: {}", bucket, &root);
+
+    let mut builder = GcsBuilder::default();
+    builder.root(&root).bucket(bucket);
+    if let Some(credential) = credential {
+        builder.credential(credential);
+    }
+    if let Some(scope) = scope {
+        builder.scope(scope);
+    }
+    if let Some(endpoint) = endpoint {
+        builder.endpoint(endpoint);
+    }
+
+    let accessor = builder.build().with_context(|_| error::InitBackendSnafu {
+        config: store_config.clone(),
+    })?;
 
     Ok(ObjectStore::new(accessor))
 }
@@ -261,6 +534,7 @@ async fn new_metasrv_client(node_id: u64, meta_config: &MetaClientOpts) -> Resul
         .enable_heartbeat()
         .enable_router()
         .enable_store()
+        .enable_sequence()
         .channel_manager(channel_manager)
         .build();
     meta_client
@@ -278,6 +552,7 @@ async fn new_metasrv_client(node_id: u64, meta_config: &MetaClientOpts) -> Resul
 
 pub(crate) async fn create_local_file_log_store(
     path: impl AsRef<str>,
+    wal: &WalOptions,
 ) -> Result<LocalFileLogStore> {
     let path = path.as_ref();
     // create WAL directory
@@ -287,6 +562,9 @@ pub(crate) async fn create_local_file_log_store(
 
     let log_config = LogConfig {
         log_file_dir: path.to_string(),
+        sync_mode: wal.sync_mode.into(),
+        group_commit_window: Duration::from_millis(wal.group_commit_window_millis),
+        group_commit_max_bytes: wal.group_commit_max_bytes,
         ..Default::default()
     };
 
@@ -12,14 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod put;
 mod stream;
 
 use std::pin::Pin;
+use std::sync::Arc;
 
 use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::diagnostic_request::Request as DiagnosticExpr;
 use api::v1::object_expr::Request as GrpcRequest;
 use api::v1::query_request::Query;
-use api::v1::{DdlRequest, InsertRequest, ObjectExpr};
+use api::v1::{
+    DdlRequest, DiagnosticRequest, InsertRequest, ObjectExpr, QueryRequest, ScanAtSequenceRequest,
+    UpdateRuntimeConfigRequest,
+};
 use arrow_flight::flight_service_server::FlightService;
 use arrow_flight::{
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
@@ -27,18 +33,21 @@ use arrow_flight::{
 };
 use async_trait::async_trait;
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
-use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_grpc::flight::{self, FlightEncoder, FlightMessage};
 use common_query::Output;
-use futures::Stream;
+use common_time::TimeZone;
+use futures::{Stream, StreamExt};
 use prost::Message;
-use session::context::QueryContext;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::{OptionExt, ResultExt};
 use tonic::{Request, Response, Streaming};
 
 use crate::error::{
-    CatalogSnafu, ExecuteSqlSnafu, InsertDataSnafu, InsertSnafu, InvalidFlightTicketSnafu,
-    MissingRequiredFieldSnafu, Result, TableNotFoundSnafu,
+    CatalogSnafu, ColumnNotFoundSnafu, ExecuteSqlSnafu, FlightPutSnafu, InsertDataSnafu,
+    InsertSnafu, InvalidFlightPutSnafu, InvalidFlightTicketSnafu, MissingRequiredFieldSnafu,
+    ParseTimeZoneSnafu, Result, ScanAtSequenceSnafu, TableNotFoundSnafu,
 };
+use crate::instance::flight::put::FlightPutWriter;
 use crate::instance::flight::stream::FlightRecordBatchStream;
 use crate::instance::Instance;
 
@@ -80,6 +89,11 @@ impl FlightService for Instance {
     type DoGetStream = TonicStream<FlightData>;
 
     async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        let encoder = if flight::is_compression_requested(request.metadata()) {
+            FlightEncoder::with_compression()
+        } else {
+            FlightEncoder::default()
+        };
         let ticket = request.into_inner().ticket;
         let request = ObjectExpr::decode(ticket.as_slice())
             .context(InvalidFlightTicketSnafu)?
@@ -88,14 +102,16 @@ impl FlightService for Instance {
         let output = match request {
             GrpcRequest::Insert(request) => self.handle_insert(request).await?,
             GrpcRequest::Query(query_request) => {
+                let query_ctx = query_context(&query_request)?;
                 let query = query_request
                     .query
                     .context(MissingRequiredFieldSnafu { name: "query" })?;
-                self.handle_query(query).await?
+                self.handle_query(query, query_ctx).await?
             }
             GrpcRequest::Ddl(request) => self.handle_ddl(request).await?,
+            GrpcRequest::Diagnostic(request) => self.handle_diagnostic(request).await?,
         };
-        let stream = to_flight_data_stream(output);
+        let stream = to_flight_data_stream(output, encoder);
         Ok(Response::new(stream))
     }
 
@@ -103,9 +119,10 @@ impl FlightService for Instance {
 
     async fn do_put(
         &self,
-        _request: Request<Streaming<FlightData>>,
+        request: Request<Streaming<FlightData>>,
     ) -> TonicResult<Response<Self::DoPutStream>> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let stream = self.handle_put(request.into_inner()).await?;
+        Ok(Response::new(stream))
     }
 
     type DoExchangeStream = TonicStream<FlightData>;
@@ -137,20 +154,22 @@ impl FlightService for Instance {
 }
 
 impl Instance {
-    async fn handle_query(&self, query: Query) -> Result<Output> {
+    async fn handle_query(&self, query: Query, query_ctx: QueryContextRef) -> Result<Output> {
         Ok(match query {
             Query::Sql(sql) => {
                 let stmt = self
                     .query_engine
                     .sql_to_statement(&sql)
                     .context(ExecuteSqlSnafu)?;
-                self.execute_stmt(stmt, QueryContext::arc()).await?
+                self.execute_stmt(stmt, query_ctx).await?
             }
             Query::LogicalPlan(plan) => self.execute_logical(plan).await?,
         })
     }
 
     pub async fn handle_insert(&self, request: InsertRequest) -> Result<Output> {
+        self.ensure_writable()?;
+
         let table_name = &request.table_name.clone();
         // TODO(LFC): InsertRequest should carry catalog name, too.
         let table = self
@@ -169,6 +188,50 @@ impl Instance {
         Ok(Output::AffectedRows(affected_rows))
     }
 
+    /// Streams record batches into a table. The first [FlightData] carries the schema of
+    /// the record batches and a descriptor whose path is `[catalog, schema, table]`,
+    /// each record batch that follows is inserted and acknowledged by a [PutResult].
+    pub(crate) async fn handle_put<S>(&self, mut stream: S) -> Result<TonicStream<PutResult>>
+    where
+        S: Stream<Item = TonicResult<FlightData>> + Send + Unpin + 'static,
+    {
+        self.ensure_writable()?;
+
+        let flight_data = stream
+            .next()
+            .await
+            .transpose()
+            .context(FlightPutSnafu)?
+            .context(MissingRequiredFieldSnafu {
+                name: "flight_data",
+            })?;
+        let path = flight_data
+            .flight_descriptor
+            .as_ref()
+            .context(MissingRequiredFieldSnafu {
+                name: "flight_descriptor",
+            })?
+            .path
+            .clone();
+        let [catalog_name, schema_name, table_name] = path.as_slice() else {
+            return InvalidFlightPutSnafu {
+                reason: format!("Expect descriptor path [catalog, schema, table], found {path:?}"),
+            }
+            .fail();
+        };
+        let table = self
+            .catalog_manager
+            .table(catalog_name, schema_name, table_name)
+            .context(CatalogSnafu)?
+            .context(TableNotFoundSnafu {
+                table_name: format!("{catalog_name}.{schema_name}.{table_name}"),
+            })?;
+
+        let writer =
+            FlightPutWriter::try_new(table, catalog_name, schema_name, table_name, flight_data)?;
+        Ok(Box::pin(writer.start(stream)))
+    }
+
     async fn handle_ddl(&self, request: DdlRequest) -> Result<Output> {
         let expr = request
             .expr
@@ -180,22 +243,103 @@ impl Instance {
             DdlExpr::DropTable(expr) => self.handle_drop_table(expr).await,
         }
     }
+
+    async fn handle_diagnostic(&self, request: DiagnosticRequest) -> Result<Output> {
+        let request = request
+            .request
+            .context(MissingRequiredFieldSnafu { name: "request" })?;
+        match request {
+            DiagnosticExpr::ScanAtSequence(request) => self.handle_scan_at_sequence(request).await,
+            DiagnosticExpr::UpdateRuntimeConfig(request) => {
+                self.handle_update_runtime_config(request)
+            }
+        }
+    }
+
+    fn handle_update_runtime_config(&self, request: UpdateRuntimeConfigRequest) -> Result<Output> {
+        let runtime_config = self.runtime_config();
+        let mut flush = runtime_config.flush_options();
+        if request.max_write_buffer_size > 0 {
+            flush.max_write_buffer_size = request.max_write_buffer_size as usize;
+        }
+        runtime_config.update_flush_options(flush)?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    async fn handle_scan_at_sequence(&self, request: ScanAtSequenceRequest) -> Result<Output> {
+        let table_name = &format!(
+            "{}.{}.{}",
+            request.catalog_name, request.schema_name, request.table_name
+        );
+        let table = self
+            .catalog_manager
+            .table(
+                &request.catalog_name,
+                &request.schema_name,
+                &request.table_name,
+            )
+            .context(CatalogSnafu)?
+            .context(TableNotFoundSnafu { table_name })?;
+
+        let projection = if request.projection.is_empty() {
+            None
+        } else {
+            let schema = table.schema();
+            let projection = request
+                .projection
+                .iter()
+                .map(|column_name| {
+                    schema
+                        .column_index_by_name(column_name)
+                        .context(ColumnNotFoundSnafu {
+                            column_name,
+                            table_name,
+                        })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(projection)
+        };
+
+        let stream = table
+            .scan_at_sequence(projection.as_ref(), request.sequence)
+            .await
+            .context(ScanAtSequenceSnafu {
+                table_name,
+                sequence: request.sequence,
+            })?;
+        Ok(Output::Stream(stream))
+    }
 }
 
-fn to_flight_data_stream(output: Output) -> TonicStream<FlightData> {
+/// Creates the context of the query by the session states carried in the request.
+fn query_context(request: &QueryRequest) -> Result<QueryContextRef> {
+    let query_ctx = QueryContext::new();
+    if !request.schema_name.is_empty() {
+        query_ctx.set_current_schema(&request.schema_name);
+    }
+    if !request.time_zone.is_empty() {
+        let time_zone = request
+            .time_zone
+            .parse::<TimeZone>()
+            .context(ParseTimeZoneSnafu)?;
+        query_ctx.set_time_zone(Some(time_zone));
+    }
+    Ok(Arc::new(query_ctx))
+}
+
+fn to_flight_data_stream(output: Output, mut encoder: FlightEncoder) -> TonicStream<FlightData> {
     match output {
         Output::Stream(stream) => {
-            let stream = FlightRecordBatchStream::new(stream);
+            let stream = FlightRecordBatchStream::new(stream, encoder);
             Box::pin(stream) as _
         }
         Output::RecordBatches(x) => {
-            let stream = FlightRecordBatchStream::new(x.as_stream());
+            let stream = FlightRecordBatchStream::new(x.as_stream(), encoder);
             Box::pin(stream) as _
         }
         Output::AffectedRows(rows) => {
-            let stream = tokio_stream::once(Ok(
-                FlightEncoder::default().encode(FlightMessage::AffectedRows(rows))
-            ));
+            let flight_data = encoder.encode(FlightMessage::AffectedRows(rows));
+            let stream = tokio_stream::iter(flight_data.into_iter().map(Ok));
             Box::pin(stream) as _
         }
     }
@@ -206,12 +350,14 @@ mod test {
     use api::v1::column::{SemanticType, Values};
     use api::v1::{
         alter_expr, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef,
-        CreateDatabaseExpr, CreateTableExpr, QueryRequest,
+        CreateDatabaseExpr, CreateTableExpr, FlightDataExt, QueryRequest,
     };
     use client::RpcOutput;
     use common_grpc::flight;
-    use common_recordbatch::RecordBatches;
+    use common_recordbatch::{RecordBatch, RecordBatches};
     use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
 
     use super::*;
     use crate::tests::test_util::{self, MockInstance};
@@ -256,12 +402,14 @@ mod test {
                                 datatype: ColumnDataType::String as i32,
                                 is_nullable: true,
                                 default_constraint: vec![],
+                                semantic_type: None,
                             },
                             ColumnDef {
                                 name: "ts".to_string(),
                                 datatype: ColumnDataType::TimestampMillisecond as i32,
                                 is_nullable: false,
                                 default_constraint: vec![],
+                                semantic_type: None,
                             },
                         ],
                         time_index: "ts".to_string(),
@@ -289,6 +437,7 @@ mod test {
                                     datatype: ColumnDataType::Int32 as i32,
                                     is_nullable: true,
                                     default_constraint: vec![],
+                                    semantic_type: None,
                                 }),
                                 is_key: true,
                             }],
@@ -328,7 +477,23 @@ mod test {
 +---------------------+---+---+
 | 2022-12-30T07:09:00 | s | 1 |
 +---------------------+---+---+";
-        assert_eq!(recordbatches.pretty_print().unwrap(), expected);
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected);
+
+        // Queries in the schema carried by the request.
+        let ticket = Request::new(Ticket {
+            ticket: ObjectExpr {
+                request: Some(GrpcRequest::Query(QueryRequest {
+                    query: Some(Query::Sql("SELECT ts, a, b FROM my_table".to_string())),
+                    schema_name: "my_database".to_string(),
+                    ..Default::default()
+                })),
+            }
+            .encode_to_vec(),
+        });
+        let RpcOutput::RecordBatches(recordbatches) = boarding(&instance, ticket).await else {
+            unreachable!()
+        };
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -409,7 +574,105 @@ mod test {
 | 2022-12-30T07:09:01 | host2 |     |
 | 2022-12-30T07:09:02 | host3 | 3   |
 +---------------------+-------+-----+";
-        assert_eq!(recordbatches.pretty_print().unwrap(), expected);
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_scan_at_sequence() {
+        let instance = MockInstance::new("test_handle_scan_at_sequence").await;
+        test_util::create_test_table(
+            &instance,
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        )
+        .await
+        .unwrap();
+
+        let output = instance
+            .inner()
+            .execute_sql(
+                "INSERT INTO demo(host, cpu, memory, ts) VALUES \
+                    ('host1', 66.6, 1024, 1672201025000),\
+                    ('host2', 88.8, 333.3, 1672201026000)",
+                QueryContext::arc(),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(2)));
+
+        let scan_at = |sequence| {
+            Request::new(Ticket {
+                ticket: ObjectExpr {
+                    request: Some(GrpcRequest::Diagnostic(DiagnosticRequest {
+                        request: Some(DiagnosticExpr::ScanAtSequence(ScanAtSequenceRequest {
+                            catalog_name: "greptime".to_string(),
+                            schema_name: "public".to_string(),
+                            table_name: "demo".to_string(),
+                            sequence,
+                            projection: vec!["ts".to_string(), "host".to_string()],
+                        })),
+                    })),
+                }
+                .encode_to_vec(),
+            })
+        };
+
+        let output = boarding(&instance, scan_at(u64::MAX)).await;
+        let RpcOutput::RecordBatches(recordbatches) = output else { unreachable!() };
+        let expected = "\
++---------------------+-------+
+| ts                  | host  |
++---------------------+-------+
+| 2022-12-28T04:17:05 | host1 |
+| 2022-12-28T04:17:06 | host2 |
++---------------------+-------+";
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected);
+
+        let output = boarding(&instance, scan_at(0)).await;
+        let RpcOutput::RecordBatches(recordbatches) = output else { unreachable!() };
+        assert!(recordbatches.iter().all(|batch| batch.num_rows() == 0));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_update_runtime_config() {
+        let instance = MockInstance::new("test_handle_update_runtime_config").await;
+
+        let update = |max_write_buffer_size| {
+            Request::new(Ticket {
+                ticket: ObjectExpr {
+                    request: Some(GrpcRequest::Diagnostic(DiagnosticRequest {
+                        request: Some(DiagnosticExpr::UpdateRuntimeConfig(
+                            UpdateRuntimeConfigRequest {
+                                max_write_buffer_size,
+                            },
+                        )),
+                    })),
+                }
+                .encode_to_vec(),
+            })
+        };
+
+        let output = boarding(&instance, update(64 * 1024 * 1024)).await;
+        assert!(matches!(output, RpcOutput::AffectedRows(0)));
+        let runtime_config = instance.inner().runtime_config();
+        assert_eq!(
+            64 * 1024 * 1024,
+            runtime_config.flush_options().max_write_buffer_size
+        );
+
+        // Zero leaves the option unchanged.
+        let _ = boarding(&instance, update(0)).await;
+        assert_eq!(
+            64 * 1024 * 1024,
+            runtime_config.flush_options().max_write_buffer_size
+        );
+
+        // Invalid options are rejected.
+        let result = instance.inner().do_get(update(1024)).await;
+        assert!(result.is_err());
+        assert_eq!(
+            64 * 1024 * 1024,
+            runtime_config.flush_options().max_write_buffer_size
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -431,6 +694,7 @@ mod test {
                             ('host2', 88.8, 333.3, 1672201026000)"
                             .to_string(),
                     )),
+                    ..Default::default()
                 })),
             }
             .encode_to_vec(),
@@ -445,6 +709,7 @@ mod test {
                     query: Some(Query::Sql(
                         "SELECT ts, host, cpu, memory FROM demo".to_string(),
                     )),
+                    ..Default::default()
                 })),
             }
             .encode_to_vec(),
@@ -466,7 +731,169 @@ mod test {
 | 2022-12-28T04:17:05 | host1 | 66.6 | 1024   |
 | 2022-12-28T04:17:06 | host2 | 88.8 | 333.3  |
 +---------------------+-------+------+--------+";
-        let actual = recordbatch.pretty_print().unwrap();
+        let actual = recordbatch.pretty_print(None).unwrap();
         assert_eq!(actual, expected);
     }
+
+    /// Encodes the `batches` to [FlightData] to put into the table `demo`.
+    fn put_flight_data(schema: Arc<Schema>, batches: Vec<RecordBatch>) -> Vec<FlightData> {
+        let mut encoder = FlightEncoder::default();
+        let mut schema_data = encoder.encode(FlightMessage::Schema(schema)).remove(0);
+        schema_data.flight_descriptor = Some(FlightDescriptor::new_path(vec![
+            "greptime".to_string(),
+            "public".to_string(),
+            "demo".to_string(),
+        ]));
+
+        let mut flight_data = vec![schema_data];
+        flight_data.extend(
+            batches
+                .into_iter()
+                .flat_map(|batch| encoder.encode(FlightMessage::Recordbatch(batch))),
+        );
+        flight_data
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_put() {
+        let instance = MockInstance::new("test_handle_put").await;
+        test_util::create_test_table(
+            &instance,
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        )
+        .await
+        .unwrap();
+
+        // Column "memory" is nullable so it could be absent.
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+        ]));
+        let new_batch = |hosts: Vec<&str>, cpus: Vec<Option<f64>>, ts: Vec<i64>| {
+            let columns: Vec<VectorRef> = vec![
+                Arc::new(StringVector::from(hosts)),
+                Arc::new(Float64Vector::from(cpus)),
+                Arc::new(TimestampMillisecondVector::from_vec(ts)),
+            ];
+            RecordBatch::new(schema.clone(), columns).unwrap()
+        };
+        let batches = vec![
+            new_batch(
+                vec!["host1", "host2"],
+                vec![Some(1.0), None],
+                vec![1672384140000, 1672384141000],
+            ),
+            new_batch(vec![], vec![], vec![]),
+            new_batch(vec!["host3"], vec![Some(3.0)], vec![1672384142000]),
+        ];
+
+        let flight_data = put_flight_data(schema.clone(), batches);
+        let stream = futures::stream::iter(flight_data.into_iter().map(Ok));
+        let results = instance.inner().handle_put(stream).await.unwrap();
+        let affected_rows: Vec<_> = results
+            .map(|result| {
+                let metadata = result.unwrap().app_metadata;
+                FlightDataExt::decode(metadata.as_slice())
+                    .unwrap()
+                    .affected_rows
+            })
+            .collect()
+            .await;
+        assert_eq!(vec![2, 0, 1], affected_rows);
+
+        let output = instance
+            .inner()
+            .execute_sql(
+                "SELECT ts, host, cpu, memory FROM demo",
+                QueryContext::arc(),
+            )
+            .await
+            .unwrap();
+        let Output::Stream(stream) = output else { unreachable!() };
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected = "\
++---------------------+-------+-----+--------+
+| ts                  | host  | cpu | memory |
++---------------------+-------+-----+--------+
+| 2022-12-30T07:09:00 | host1 | 1   |        |
+| 2022-12-30T07:09:01 | host2 |     |        |
+| 2022-12-30T07:09:02 | host3 | 3   |        |
++---------------------+-------+-----+--------+";
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_handle_invalid_put() {
+        let instance = MockInstance::new("test_handle_invalid_put").await;
+        test_util::create_test_table(
+            &instance,
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        )
+        .await
+        .unwrap();
+
+        let put = |flight_data: Vec<FlightData>| {
+            let stream = futures::stream::iter(flight_data.into_iter().map(Ok));
+            instance.inner().handle_put(stream)
+        };
+
+        // Missing descriptor.
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "host",
+            ConcreteDataType::string_datatype(),
+            false,
+        )]));
+        let mut flight_data = put_flight_data(schema, vec![]);
+        flight_data[0].flight_descriptor = None;
+        let result = put(flight_data).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::MissingRequiredField { .. })
+        ));
+
+        // Mismatched data type.
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::int64_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+        ]));
+        let result = put(put_flight_data(schema, vec![])).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::InvalidFlightPut { .. })
+        ));
+
+        // Column "host" is not nullable and must be put.
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            true,
+        )]));
+        let result = put(put_flight_data(schema, vec![])).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::InvalidFlightPut { .. })
+        ));
+
+        // Unknown column.
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "disk",
+            ConcreteDataType::float64_datatype(),
+            true,
+        )]));
+        let result = put(put_flight_data(schema, vec![])).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::ColumnNotFound { .. })
+        ));
+    }
 }
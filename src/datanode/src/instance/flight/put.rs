@@ -0,0 +1,202 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::FlightDataExt;
+use arrow_flight::{FlightData, PutResult};
+use common_grpc::flight::{FlightDecoder, FlightMessage};
+use common_telemetry::warn;
+use datatypes::schema::Schema;
+use futures::channel::mpsc::{self, Sender};
+use futures::{SinkExt, Stream, StreamExt};
+use prost::Message;
+use snafu::{ensure, OptionExt, ResultExt};
+use table::requests::InsertRequest;
+use table::TableRef;
+
+use crate::error::{
+    ColumnNotFoundSnafu, FlightPutSnafu, InsertSnafu, InvalidFlightDataSnafu,
+    InvalidFlightPutSnafu, Result,
+};
+use crate::instance::flight::TonicResult;
+
+/// Size of the channel that buffers the [PutResult]s. Once it is full, no more
+/// [FlightData] are received until the client consumes the results, so a slow
+/// client applies backpressure to the writes.
+const PUT_RESULT_CHANNEL_SIZE: usize = 8;
+
+/// Writes record batches of a Flight `DoPut` call into a table.
+pub(super) struct FlightPutWriter {
+    table: TableRef,
+    catalog_name: String,
+    schema_name: String,
+    table_name: String,
+    decoder: FlightDecoder,
+}
+
+impl FlightPutWriter {
+    /// Creates a writer for the `table`, the `flight_data` must be the first message of
+    /// the put, which carries the schema of the record batches.
+    pub(super) fn try_new(
+        table: TableRef,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        flight_data: FlightData,
+    ) -> Result<Self> {
+        let mut decoder = FlightDecoder::default();
+        let message = decoder
+            .try_decode(flight_data)
+            .context(InvalidFlightDataSnafu)?;
+        let Some(FlightMessage::Schema(schema)) = message else {
+            return InvalidFlightPutSnafu {
+                reason: "The first Flight data must be schema",
+            }
+            .fail();
+        };
+
+        let writer = Self {
+            table,
+            catalog_name: catalog_name.to_string(),
+            schema_name: schema_name.to_string(),
+            table_name: table_name.to_string(),
+            decoder,
+        };
+        writer.validate_schema(&schema)?;
+        Ok(writer)
+    }
+
+    /// Checks the columns to put exist in the table with the same data types, and
+    /// columns absent from the put are nullable or have default values.
+    fn validate_schema(&self, schema: &Schema) -> Result<()> {
+        let table_schema = self.table.schema();
+        for column_schema in schema.column_schemas() {
+            let table_column = table_schema
+                .column_schema_by_name(&column_schema.name)
+                .context(ColumnNotFoundSnafu {
+                    column_name: &column_schema.name,
+                    table_name: &self.table_name,
+                })?;
+            ensure!(
+                table_column.data_type == column_schema.data_type,
+                InvalidFlightPutSnafu {
+                    reason: format!(
+                        "Column {} of table {} is {:?}, but {:?} is put",
+                        column_schema.name,
+                        self.table_name,
+                        table_column.data_type,
+                        column_schema.data_type
+                    ),
+                }
+            );
+        }
+
+        for table_column in table_schema.column_schemas() {
+            ensure!(
+                schema.contains_column(&table_column.name)
+                    || table_column.is_nullable()
+                    || table_column.default_constraint().is_some(),
+                InvalidFlightPutSnafu {
+                    reason: format!(
+                        "Column {} of table {} has no default value and must be put",
+                        table_column.name, self.table_name
+                    ),
+                }
+            );
+        }
+        Ok(())
+    }
+
+    /// Inserts the record batch in `flight_data`, returns the [PutResult] whose metadata
+    /// is the encoded [FlightDataExt] with the affected rows, or `None` if the
+    /// `flight_data` is a dictionary batch.
+    async fn put(&mut self, flight_data: FlightData) -> Result<Option<PutResult>> {
+        let Some(message) = self
+            .decoder
+            .try_decode(flight_data)
+            .context(InvalidFlightDataSnafu)?
+        else {
+            return Ok(None);
+        };
+        let FlightMessage::Recordbatch(recordbatch) = message else {
+            return InvalidFlightPutSnafu {
+                reason: "Expect record batches after the schema",
+            }
+            .fail();
+        };
+
+        let affected_rows = if recordbatch.num_rows() == 0 {
+            0
+        } else {
+            let columns_values = recordbatch
+                .schema
+                .column_schemas()
+                .iter()
+                .zip(recordbatch.columns())
+                .map(|(column_schema, vector)| (column_schema.name.clone(), vector.clone()))
+                .collect();
+            let request = InsertRequest {
+                catalog_name: self.catalog_name.clone(),
+                schema_name: self.schema_name.clone(),
+                table_name: self.table_name.clone(),
+                columns_values,
+            };
+            self.table.insert(request).await.context(InsertSnafu {
+                table_name: &self.table_name,
+            })?
+        };
+
+        Ok(Some(PutResult {
+            app_metadata: FlightDataExt {
+                affected_rows: affected_rows as _,
+            }
+            .encode_to_vec(),
+        }))
+    }
+
+    /// Writes record batches of the `stream` in the background, returns the stream of
+    /// [PutResult]s, one for each record batch. Writing stops at the first error, which
+    /// is the last item of the returned stream.
+    pub(super) fn start<S>(self, stream: S) -> mpsc::Receiver<TonicResult<PutResult>>
+    where
+        S: Stream<Item = TonicResult<FlightData>> + Send + Unpin + 'static,
+    {
+        let (tx, rx) = mpsc::channel(PUT_RESULT_CHANNEL_SIZE);
+        let _handle = common_runtime::spawn_write(async move { self.write(stream, tx).await });
+        rx
+    }
+
+    async fn write<S>(mut self, mut stream: S, mut tx: Sender<TonicResult<PutResult>>)
+    where
+        S: Stream<Item = TonicResult<FlightData>> + Send + Unpin + 'static,
+    {
+        while let Some(flight_data) = stream.next().await {
+            let result = match flight_data.context(FlightPutSnafu) {
+                Ok(flight_data) => match self.put(flight_data).await {
+                    Ok(Some(put_result)) => Ok(put_result),
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            let is_err = result.is_err();
+            if let Err(e) = tx.send(result.map_err(Into::into)).await {
+                warn!("stop putting Flight data, err: {e}");
+                return;
+            }
+            if is_err {
+                return;
+            }
+        }
+    }
+}
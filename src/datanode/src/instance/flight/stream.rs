@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::VecDeque;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
@@ -36,10 +37,13 @@ pub(super) struct FlightRecordBatchStream {
     join_handle: JoinHandle<()>,
     done: bool,
     encoder: FlightEncoder,
+    /// [FlightData] encoded but not yet polled, as a message could be encoded into
+    /// several [FlightData].
+    pending: VecDeque<FlightData>,
 }
 
 impl FlightRecordBatchStream {
-    pub(super) fn new(recordbatches: SendableRecordBatchStream) -> Self {
+    pub(super) fn new(recordbatches: SendableRecordBatchStream, encoder: FlightEncoder) -> Self {
         let (tx, rx) = mpsc::channel::<TonicResult<FlightMessage>>(1);
         let join_handle =
             common_runtime::spawn_read(
@@ -49,7 +53,8 @@ impl FlightRecordBatchStream {
             rx,
             join_handle,
             done: false,
-            encoder: FlightEncoder::default(),
+            encoder,
+            pending: VecDeque::new(),
         }
     }
 
@@ -94,26 +99,33 @@ impl Stream for FlightRecordBatchStream {
     type Item = TonicResult<FlightData>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
+        let mut this = self.project();
+        if let Some(flight_data) = this.pending.pop_front() {
+            return Poll::Ready(Some(Ok(flight_data)));
+        }
         if *this.done {
-            Poll::Ready(None)
-        } else {
-            match this.rx.poll_next(cx) {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            match this.rx.as_mut().poll_next(cx) {
                 Poll::Ready(None) => {
                     *this.done = true;
-                    Poll::Ready(None)
+                    return Poll::Ready(None);
                 }
                 Poll::Ready(Some(result)) => match result {
                     Ok(flight_message) => {
-                        let flight_data = this.encoder.encode(flight_message);
-                        Poll::Ready(Some(Ok(flight_data)))
+                        this.pending.extend(this.encoder.encode(flight_message));
+                        if let Some(flight_data) = this.pending.pop_front() {
+                            return Poll::Ready(Some(Ok(flight_data)));
+                        }
                     }
                     Err(e) => {
                         *this.done = true;
-                        Poll::Ready(Some(Err(e)))
+                        return Poll::Ready(Some(Err(e)));
                     }
                 },
-                Poll::Pending => Poll::Pending,
+                Poll::Pending => return Poll::Pending,
             }
         }
     }
@@ -146,7 +158,7 @@ mod test {
         let recordbatches = RecordBatches::try_new(schema.clone(), vec![recordbatch.clone()])
             .unwrap()
             .as_stream();
-        let mut stream = FlightRecordBatchStream::new(recordbatches);
+        let mut stream = FlightRecordBatchStream::new(recordbatches, FlightEncoder::default());
 
         let mut raw_data = Vec::with_capacity(2);
         raw_data.push(stream.next().await.unwrap().unwrap());
@@ -157,7 +169,7 @@ mod test {
         let decoder = &mut FlightDecoder::default();
         let mut flight_messages = raw_data
             .into_iter()
-            .map(|x| decoder.try_decode(x).unwrap())
+            .map(|x| decoder.try_decode(x).unwrap().unwrap())
             .collect::<Vec<FlightMessage>>();
         assert_eq!(flight_messages.len(), 2);
 
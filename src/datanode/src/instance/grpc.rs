@@ -14,18 +14,19 @@
 
 use api::v1::{CreateDatabaseExpr, ObjectExpr, ObjectResult};
 use arrow_flight::flight_service_server::FlightService;
-use arrow_flight::Ticket;
+use arrow_flight::{FlightData, Ticket};
 use async_trait::async_trait;
 use common_error::prelude::BoxedError;
 use common_grpc::flight;
 use common_query::Output;
 use prost::Message;
 use query::plan::LogicalPlan;
-use servers::query_handler::GrpcQueryHandler;
+use servers::query_handler::{FlightDataStream, GrpcQueryHandler, PutResultStream};
+use session::context::QueryContextRef;
 use snafu::prelude::*;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::requests::CreateDatabaseRequest;
-use tonic::Request;
+use tonic::{Request, Streaming};
 
 use crate::error::{
     DecodeLogicalPlanSnafu, ExecuteSqlSnafu, FlightGetSnafu, InvalidFlightDataSnafu, Result,
@@ -61,7 +62,11 @@ impl Instance {
 
 #[async_trait]
 impl GrpcQueryHandler for Instance {
-    async fn do_query(&self, query: ObjectExpr) -> servers::error::Result<ObjectResult> {
+    async fn do_query(
+        &self,
+        query: ObjectExpr,
+        _query_ctx: QueryContextRef,
+    ) -> servers::error::Result<ObjectResult> {
         let ticket = Request::new(Ticket {
             ticket: query.encode_to_vec(),
         });
@@ -73,4 +78,38 @@ impl GrpcQueryHandler for Instance {
                 query: format!("{query:?}"),
             })
     }
+
+    async fn do_query_stream(
+        &self,
+        query: ObjectExpr,
+        compressed: bool,
+        _query_ctx: QueryContextRef,
+    ) -> servers::error::Result<FlightDataStream> {
+        let mut ticket = Request::new(Ticket {
+            ticket: query.encode_to_vec(),
+        });
+        if compressed {
+            flight::request_compression(ticket.metadata_mut());
+        }
+        let response = self
+            .do_get(ticket)
+            .await
+            .context(FlightGetSnafu)
+            .map_err(BoxedError::new)
+            .with_context(|_| servers::error::ExecuteQuerySnafu {
+                query: format!("{query:?}"),
+            })?;
+        Ok(response.into_inner())
+    }
+
+    async fn do_put_stream(
+        &self,
+        stream: Streaming<FlightData>,
+        _query_ctx: QueryContextRef,
+    ) -> servers::error::Result<PutResultStream> {
+        self.handle_put(stream)
+            .await
+            .map_err(BoxedError::new)
+            .context(servers::error::ExecuteQuerySnafu { query: "DoPut" })
+    }
 }
@@ -19,18 +19,21 @@ use common_query::Output;
 use common_recordbatch::RecordBatches;
 use common_telemetry::logging::{error, info};
 use common_telemetry::timer;
+use common_time::TimeZone;
+use datatypes::schema::Schema;
 use servers::query_handler::SqlQueryHandler;
-use session::context::QueryContextRef;
+use session::context::{QueryContextRef, TIME_ZONE_VARIABLE};
 use snafu::prelude::*;
 use sql::ast::ObjectName;
 use sql::statements::statement::Statement;
 use table::engine::TableReference;
-use table::requests::CreateDatabaseRequest;
+use table::requests::{CreateDatabaseRequest, TruncateTableRequest};
 
 use crate::error::{self, BumpTableIdSnafu, ExecuteSqlSnafu, Result, TableIdProviderNotFoundSnafu};
 use crate::instance::Instance;
 use crate::metric;
 use crate::sql::SqlRequest;
+use crate::task::CreateTaskRequest;
 
 impl Instance {
     pub async fn execute_stmt(
@@ -51,6 +54,7 @@ impl Instance {
                     .context(ExecuteSqlSnafu)
             }
             Statement::Insert(i) => {
+                self.ensure_writable()?;
                 let (catalog, schema, table) =
                     table_idents_to_full_name(i.table_name(), query_ctx.clone())?;
                 let table_ref = TableReference::full(&catalog, &schema, &table);
@@ -58,9 +62,34 @@ impl Instance {
                     self.catalog_manager.clone(),
                     *i,
                     table_ref,
+                    query_ctx.time_zone().as_ref(),
                 )?;
                 self.sql_handler.execute(request, query_ctx).await
             }
+            Statement::Delete(d) => {
+                self.ensure_writable()?;
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&d.table_name, query_ctx.clone())?;
+                let table_ref = TableReference::full(&catalog, &schema, &table);
+                let request = self.sql_handler.delete_to_request(
+                    *d,
+                    table_ref,
+                    query_ctx.time_zone().as_ref(),
+                )?;
+                self.sql_handler.execute(request, query_ctx).await
+            }
+            Statement::Copy(c) => {
+                self.ensure_writable()?;
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&c.table_name, query_ctx.clone())?;
+                let table_ref = TableReference::full(&catalog, &schema, &table);
+                let request = self.sql_handler.copy_to_request(c, table_ref)?;
+                self.sql_handler.execute(request, query_ctx).await
+            }
+            Statement::CopyTo(c) => {
+                let request = self.sql_handler.copy_query_to_request(c)?;
+                self.sql_handler.execute(request, query_ctx).await
+            }
 
             Statement::CreateDatabase(c) => {
                 let request = CreateDatabaseRequest {
@@ -108,6 +137,19 @@ impl Instance {
                     .execute(SqlRequest::Alter(req), query_ctx)
                     .await
             }
+            Statement::Truncate(truncate) => {
+                self.ensure_writable()?;
+                let (catalog, schema, table) =
+                    table_idents_to_full_name(&truncate.table_name, query_ctx.clone())?;
+                let request = TruncateTableRequest {
+                    catalog_name: catalog,
+                    schema_name: schema,
+                    table_name: table,
+                };
+                self.sql_handler
+                    .execute(SqlRequest::TruncateTable(request), query_ctx)
+                    .await
+            }
             Statement::DropTable(drop_table) => {
                 let req = self.sql_handler.drop_table_to_request(drop_table);
                 self.sql_handler
@@ -150,6 +192,67 @@ impl Instance {
 
                 Ok(Output::RecordBatches(RecordBatches::empty()))
             }
+            Statement::SetVariable(set) => {
+                if set.name == TIME_ZONE_VARIABLE {
+                    let time_zone = if set.value.eq_ignore_ascii_case("DEFAULT") {
+                        None
+                    } else {
+                        Some(
+                            set.value
+                                .parse::<TimeZone>()
+                                .context(error::ParseTimeZoneSnafu)?,
+                        )
+                    };
+                    query_ctx.set_time_zone(time_zone);
+                } else {
+                    query_ctx.set_variable(&set.name, set.value);
+                }
+
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::CreateTask(create) => {
+                let (catalog, schema, name) =
+                    table_idents_to_full_name(&create.name, query_ctx.clone())?;
+                let (sink_catalog, sink_schema, sink_table) =
+                    table_idents_to_full_name(&create.sink_table, query_ctx)?;
+                ensure!(
+                    sink_catalog == catalog && sink_schema == schema,
+                    error::InvalidTaskSnafu {
+                        name: create.name.to_string(),
+                        reason: "the sink table must be in the same schema as the task",
+                    }
+                );
+                self.task_manager
+                    .create_task(CreateTaskRequest {
+                        catalog,
+                        schema,
+                        name,
+                        interval: create.interval,
+                        sink_table,
+                        query: create.query.inner.to_string(),
+                        if_not_exists: create.if_not_exists,
+                    })
+                    .await?;
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::DropTask(drop) => {
+                let (catalog, schema, name) = table_idents_to_full_name(&drop.name, query_ctx)?;
+                self.task_manager
+                    .drop_task(&catalog, &schema, &name, drop.if_exists)
+                    .await?;
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::ShowNodes(_) => error::NotSupportedSnafu {
+                feat: "SHOW NODES in datanode",
+            }
+            .fail(),
+            // Users and their privileges are managed by the frontend.
+            Statement::CreateUser(_) | Statement::DropUser(_) | Statement::Grant(_) => {
+                error::NotSupportedSnafu {
+                    feat: "managing users in datanode",
+                }
+                .fail()
+            }
         }
     }
 
@@ -228,6 +331,23 @@ impl SqlQueryHandler for Instance {
             .context(servers::error::ExecuteStatementSnafu)
     }
 
+    async fn do_describe(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> servers::error::Result<Option<Schema>> {
+        if let Statement::Query(_) = stmt {
+            self.query_engine
+                .statement_to_plan(stmt, query_ctx)
+                .and_then(|plan| plan.schema())
+                .map(Some)
+                .map_err(BoxedError::new)
+                .context(servers::error::DescribeStatementSnafu)
+        } else {
+            Ok(None)
+        }
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> servers::error::Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
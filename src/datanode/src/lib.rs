@@ -20,8 +20,18 @@ mod heartbeat;
 pub mod instance;
 mod metric;
 mod mock;
+pub mod reload;
 mod script;
 pub mod server;
 pub mod sql;
+mod task;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 #[cfg(test)]
 mod tests;
+
+/// Cargo features enabled in the build of datanode.
+pub const BUILD_FEATURES: &[&str] = &[
+    #[cfg(feature = "python")]
+    "python",
+];
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 
 use catalog::remote::MetaKvBackend;
@@ -29,9 +29,13 @@ use table::table::TableIdProvider;
 use crate::datanode::DatanodeOptions;
 use crate::error::Result;
 use crate::heartbeat::HeartbeatTask;
-use crate::instance::{create_local_file_log_store, new_object_store, DefaultEngine, Instance};
+use crate::instance::{
+    create_local_file_log_store, new_file_store, new_object_store, DefaultEngine, Instance,
+};
+use crate::reload::{validate_flush_options, RuntimeConfig};
 use crate::script::ScriptExecutor;
 use crate::sql::SqlHandler;
+use crate::task::TaskManager;
 
 impl Instance {
     pub async fn with_mock_meta_client(opts: &DatanodeOptions) -> Result<Self> {
@@ -40,17 +44,24 @@ impl Instance {
     }
 
     pub async fn with_mock_meta_server(opts: &DatanodeOptions, meta_srv: MockInfo) -> Result<Self> {
-        let object_store = new_object_store(&opts.storage).await?;
-        let logstore = Arc::new(create_local_file_log_store(&opts.wal_dir).await?);
+        let object_store = new_object_store(opts).await?;
+        let file_store = new_file_store(opts).await?;
+        let logstore = Arc::new(create_local_file_log_store(&opts.wal_dir, &opts.wal).await?);
         let meta_client = Arc::new(mock_meta_client(meta_srv, opts.node_id.unwrap_or(42)).await);
-        let table_engine = Arc::new(DefaultEngine::new(
+        validate_flush_options(&opts.flush)?;
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig {
+                max_write_buffer_size: opts.flush.max_write_buffer_size,
+                ..Default::default()
+            },
+            logstore.clone(),
+            object_store.clone(),
+        );
+        let table_engine = Arc::new(DefaultEngine::with_file_store(
             TableEngineConfig::default(),
-            EngineImpl::new(
-                StorageEngineConfig::default(),
-                logstore.clone(),
-                object_store.clone(),
-            ),
+            storage_engine.clone(),
             object_store,
+            file_store.clone(),
         ));
 
         // create remote catalog manager
@@ -66,11 +77,14 @@ impl Instance {
         let query_engine = factory.query_engine();
         let script_executor =
             ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?;
+        let task_manager = TaskManager::new(catalog_manager.clone(), query_engine.clone()).await?;
 
         let heartbeat_task = HeartbeatTask::new(
             opts.node_id.unwrap_or(42),
             opts.rpc_addr.clone(),
             meta_client.clone(),
+            catalog_manager.clone(),
+            table_engine.clone(),
         );
         Ok(Self {
             query_engine: query_engine.clone(),
@@ -78,12 +92,18 @@ impl Instance {
                 table_engine,
                 catalog_manager.clone(),
                 query_engine.clone(),
+                file_store,
             ),
             catalog_manager,
             script_executor,
+            task_manager,
             table_id_provider: Some(Arc::new(LocalTableIdProvider::default())),
             heartbeat_task: Some(heartbeat_task),
-            logstore,
+            logstore: Some(logstore),
+            catalog_loaded: AtomicBool::new(false),
+            started: AtomicBool::new(false),
+            shutting_down: AtomicBool::new(false),
+            runtime_config: RuntimeConfig::new(opts.flush.clone(), storage_engine),
         })
     }
 }
@@ -0,0 +1,118 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Settings of the datanode that can be changed without restart.
+//!
+//! Settings are reloaded from the config file on `SIGHUP`, or updated by the
+//! `UpdateRuntimeConfig` diagnostic request. Only the [FlushOptions] are reloadable
+//! now, changes of other options take effect after restart.
+
+use std::sync::Mutex;
+
+use common_telemetry::info;
+use snafu::ensure;
+use storage::EngineImpl;
+use store_api::logstore::LogStore;
+
+use crate::datanode::{DatanodeOptions, FlushOptions};
+use crate::error::{InvalidRuntimeConfigSnafu, Result};
+
+/// Min write buffer size, regions with smaller buffers are flushed too frequently.
+const MIN_WRITE_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Current values of the reloadable settings, and the components they are
+/// applied to.
+pub struct RuntimeConfig {
+    flush: Mutex<FlushOptions>,
+    storage_engine: Box<dyn WriteBufferSizeSetter>,
+}
+
+impl RuntimeConfig {
+    pub(crate) fn new<S: LogStore>(flush: FlushOptions, storage_engine: EngineImpl<S>) -> Self {
+        Self {
+            flush: Mutex::new(flush),
+            storage_engine: Box::new(storage_engine),
+        }
+    }
+
+    pub fn flush_options(&self) -> FlushOptions {
+        self.flush.lock().unwrap().clone()
+    }
+
+    /// Applies the reloadable options in `opts`.
+    pub fn reload(&self, opts: &DatanodeOptions) -> Result<()> {
+        self.update_flush_options(opts.flush.clone())
+    }
+
+    /// Validates and applies the flush options, nothing is changed if they are
+    /// invalid.
+    pub fn update_flush_options(&self, flush: FlushOptions) -> Result<()> {
+        validate_flush_options(&flush)?;
+
+        let mut current = self.flush.lock().unwrap();
+        if *current == flush {
+            return Ok(());
+        }
+        info!("Update flush options from {:?} to {:?}", *current, flush);
+        self.storage_engine
+            .set_max_write_buffer_size(flush.max_write_buffer_size);
+        *current = flush;
+        Ok(())
+    }
+}
+
+/// Erases the log store type of the storage engine, so the config doesn't depend
+/// on which WAL the instance uses.
+trait WriteBufferSizeSetter: Send + Sync {
+    fn set_max_write_buffer_size(&self, max_write_buffer_size: usize);
+}
+
+impl<S: LogStore> WriteBufferSizeSetter for EngineImpl<S> {
+    fn set_max_write_buffer_size(&self, max_write_buffer_size: usize) {
+        EngineImpl::set_max_write_buffer_size(self, max_write_buffer_size)
+    }
+}
+
+pub(crate) fn validate_flush_options(flush: &FlushOptions) -> Result<()> {
+    ensure!(
+        flush.max_write_buffer_size >= MIN_WRITE_BUFFER_SIZE,
+        InvalidRuntimeConfigSnafu {
+            reason: format!(
+                "max_write_buffer_size {} is less than {}",
+                flush.max_write_buffer_size, MIN_WRITE_BUFFER_SIZE
+            ),
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_flush_options() {
+        validate_flush_options(&FlushOptions::default()).unwrap();
+
+        let flush = FlushOptions {
+            max_write_buffer_size: 1024,
+        };
+        let err = validate_flush_options(&flush).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("max_write_buffer_size 1024 is less than"),
+            "{err}"
+        );
+    }
+}
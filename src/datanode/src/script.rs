@@ -32,6 +32,10 @@ mod dummy {
             Ok(Self {})
         }
 
+        pub async fn start(&self) -> Result<()> {
+            Ok(())
+        }
+
         pub async fn insert_script(
             &self,
             _name: &str,
@@ -71,6 +75,14 @@ mod python {
             })
         }
 
+        /// Loads the persisted scripts, should be called once the catalog is started.
+        pub async fn start(&self) -> Result<()> {
+            self.script_manager
+                .start()
+                .await
+                .context(crate::error::StartScriptManagerSnafu)
+        }
+
         pub async fn insert_script(&self, name: &str, script: &str) -> servers::error::Result<()> {
             let _s = self
                 .script_manager
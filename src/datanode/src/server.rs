@@ -19,13 +19,16 @@ use std::sync::Arc;
 use common_runtime::Builder as RuntimeBuilder;
 use common_telemetry::tracing::log::info;
 use servers::grpc::GrpcServer;
+use servers::http::HttpServer;
 use servers::mysql::server::MysqlServer;
 use servers::server::Server;
 use servers::Mode;
 use snafu::ResultExt;
 
 use crate::datanode::DatanodeOptions;
-use crate::error::{ParseAddrSnafu, Result, RuntimeResourceSnafu, StartServerSnafu};
+use crate::error::{
+    ParseAddrSnafu, Result, RuntimeResourceSnafu, ShutdownServerSnafu, StartServerSnafu,
+};
 use crate::instance::InstanceRef;
 
 pub mod grpc;
@@ -34,6 +37,7 @@ pub mod grpc;
 pub struct Services {
     grpc_server: GrpcServer,
     mysql_server: Option<Box<dyn Server>>,
+    http_server: Option<HttpServer>,
 }
 
 impl Services {
@@ -46,10 +50,10 @@ impl Services {
                 .context(RuntimeResourceSnafu)?,
         );
 
-        let mysql_server = match opts.mode {
+        let (mysql_server, http_server) = match opts.mode {
             Mode::Standalone => {
-                info!("Disable MySQL server on datanode when running in standalone mode");
-                None
+                info!("Disable MySQL and HTTP server on datanode when running in standalone mode");
+                (None, None)
             }
             Mode::Distributed => {
                 let mysql_io_runtime = Arc::new(
@@ -59,22 +63,32 @@ impl Services {
                         .build()
                         .context(RuntimeResourceSnafu)?,
                 );
-                Some(MysqlServer::create_server(
+                let mysql_server = MysqlServer::create_server(
                     instance.clone(),
                     mysql_io_runtime,
-                    Default::default(),
+                    opts.mysql_tls.clone(),
                     None,
-                ))
+                );
+                let http_server = opts.http_opts.as_ref().map(|http_opts| {
+                    let mut http_server = HttpServer::new(instance.clone(), http_opts.clone());
+                    http_server.set_readiness_handler(instance.clone());
+                    http_server
+                });
+                (Some(mysql_server), http_server)
             }
         };
 
+        let mut grpc_server = GrpcServer::new(instance, grpc_runtime);
+        grpc_server.set_tls_option(opts.rpc_tls.clone());
+
         Ok(Self {
-            grpc_server: GrpcServer::new(instance, grpc_runtime),
+            grpc_server,
             mysql_server,
+            http_server,
         })
     }
 
-    pub async fn start(&mut self, opts: &DatanodeOptions) -> Result<()> {
+    pub async fn start(&self, opts: &DatanodeOptions) -> Result<()> {
         let grpc_addr: SocketAddr = opts.rpc_addr.parse().context(ParseAddrSnafu {
             addr: &opts.rpc_addr,
         })?;
@@ -93,4 +107,34 @@ impl Services {
             .context(StartServerSnafu)?;
         Ok(())
     }
+
+    pub async fn start_http(&self, opts: &DatanodeOptions) -> Result<()> {
+        let (Some(http_server), Some(http_opts)) = (&self.http_server, &opts.http_opts) else {
+            return Ok(());
+        };
+        let http_addr: SocketAddr = http_opts.addr.parse().context(ParseAddrSnafu {
+            addr: &http_opts.addr,
+        })?;
+        let _ = http_server
+            .start(http_addr)
+            .await
+            .context(StartServerSnafu)?;
+        Ok(())
+    }
+
+    /// Shutdown all servers, the gRPC server stops accepting new connections but
+    /// still serves requests in flight.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.grpc_server
+            .shutdown()
+            .await
+            .context(ShutdownServerSnafu)?;
+        if let Some(mysql_server) = &self.mysql_server {
+            mysql_server.shutdown().await.context(ShutdownServerSnafu)?;
+        }
+        if let Some(http_server) = &self.http_server {
+            http_server.shutdown().await.context(ShutdownServerSnafu)?;
+        }
+        Ok(())
+    }
 }
@@ -39,7 +39,8 @@ impl Instance {
         // Also merge this mod with mod instance::grpc.
 
         // Respect CreateExpr's table id and region ids if present, or allocate table id
-        // from local table id provider and set region id to 0.
+        // from the table id provider, which is backed by metasrv in distributed mode, and
+        // set region id to 0.
         let table_id = if let Some(table_id) = &expr.table_id {
             info!(
                 "Creating table {table_name} with table id {} from Frontend",
@@ -51,7 +52,7 @@ impl Instance {
                 self.table_id_provider
                     .as_ref()
                     .context(IncorrectInternalStateSnafu {
-                        state: "Table id provider absent in standalone mode",
+                        state: "Table id provider absent",
                     })?;
             let table_id = provider.next_table_id().await.context(BumpTableIdSnafu)?;
             info!("Creating table {table_name} with table id {table_id} from TableIdProvider");
@@ -67,7 +68,9 @@ impl Instance {
 
     pub(crate) async fn handle_alter(&self, expr: AlterExpr) -> Result<Output> {
         let request = alter_expr_to_request(expr).context(AlterExprToRequestSnafu)?;
-        let Some(request) = request else { return Ok(Output::AffectedRows(0)) };
+        let Some(request) = request else {
+            return Ok(Output::AffectedRows(0));
+        };
 
         self.sql_handler()
             .execute(SqlRequest::Alter(request), QueryContext::arc())
@@ -147,6 +150,7 @@ mod tests {
             datatype: 1024,
             is_nullable: true,
             default_constraint: vec![],
+            semantic_type: None,
         };
         let result = column_def.try_as_column_schema();
         assert!(matches!(
@@ -159,6 +163,7 @@ mod tests {
             datatype: ColumnDataType::String as i32,
             is_nullable: true,
             default_constraint: vec![],
+            semantic_type: None,
         };
         let column_schema = column_def.try_as_column_schema().unwrap();
         assert_eq!(column_schema.name, "a");
@@ -171,6 +176,7 @@ mod tests {
             datatype: ColumnDataType::String as i32,
             is_nullable: true,
             default_constraint: default_constraint.clone().try_into().unwrap(),
+            semantic_type: None,
         };
         let column_schema = column_def.try_as_column_schema().unwrap();
         assert_eq!(column_schema.name, "a");
@@ -189,24 +195,28 @@ mod tests {
                 datatype: ColumnDataType::String as i32,
                 is_nullable: false,
                 default_constraint: vec![],
+                semantic_type: None,
             },
             ColumnDef {
                 name: "ts".to_string(),
                 datatype: ColumnDataType::TimestampMillisecond as i32,
                 is_nullable: false,
                 default_constraint: vec![],
+                semantic_type: None,
             },
             ColumnDef {
                 name: "cpu".to_string(),
                 datatype: ColumnDataType::Float32 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                semantic_type: None,
             },
             ColumnDef {
                 name: "memory".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                semantic_type: None,
             },
         ];
         CreateTableExpr {
@@ -15,6 +15,7 @@
 use catalog::CatalogManagerRef;
 use common_query::Output;
 use common_telemetry::error;
+use object_store::ObjectStore;
 use query::query_engine::QueryEngineRef;
 use query::sql::{describe_table, explain, show_databases, show_tables};
 use session::context::QueryContextRef;
@@ -27,19 +28,27 @@ use table::requests::*;
 use table::TableRef;
 
 use crate::error::{ExecuteSqlSnafu, GetTableSnafu, Result, TableNotFoundSnafu};
+use crate::sql::copy::{CopyQueryToRequest, CopyTableRequest};
 
 mod alter;
+mod copy;
 mod create;
+mod delete;
 mod drop_table;
 mod insert;
+mod truncate;
 
 #[derive(Debug)]
 pub enum SqlRequest {
     Insert(InsertRequest),
+    DeleteRange(DeleteRangeRequest),
+    CopyTable(CopyTableRequest),
+    CopyQueryTo(Box<CopyQueryToRequest>),
     CreateTable(CreateTableRequest),
     CreateDatabase(CreateDatabaseRequest),
     Alter(AlterTableRequest),
     DropTable(DropTableRequest),
+    TruncateTable(TruncateTableRequest),
     ShowDatabases(ShowDatabases),
     ShowTables(ShowTables),
     DescribeTable(DescribeTable),
@@ -51,6 +60,8 @@ pub struct SqlHandler {
     table_engine: TableEngineRef,
     catalog_manager: CatalogManagerRef,
     query_engine: QueryEngineRef,
+    /// Object store of the files to import by `COPY FROM` and export by `COPY TO`.
+    file_store: ObjectStore,
 }
 
 impl SqlHandler {
@@ -58,11 +69,13 @@ impl SqlHandler {
         table_engine: TableEngineRef,
         catalog_manager: CatalogManagerRef,
         query_engine: QueryEngineRef,
+        file_store: ObjectStore,
     ) -> Self {
         Self {
             table_engine,
             catalog_manager,
             query_engine,
+            file_store,
         }
     }
 
@@ -71,17 +84,30 @@ impl SqlHandler {
     // we could create a new struct called `Planner` that stores context and handle these queries
     // there, instead of executing here in a "static" fashion.
     pub async fn execute(&self, request: SqlRequest, query_ctx: QueryContextRef) -> Result<Output> {
+        let is_ddl = matches!(
+            request,
+            SqlRequest::CreateTable(_)
+                | SqlRequest::CreateDatabase(_)
+                | SqlRequest::Alter(_)
+                | SqlRequest::DropTable(_)
+        );
         let result = match request {
             SqlRequest::Insert(req) => self.insert(req).await,
+            SqlRequest::DeleteRange(req) => self.delete_range(req).await,
+            SqlRequest::CopyTable(req) => self.copy_table(req).await,
+            SqlRequest::CopyQueryTo(req) => self.copy_query_to(*req, query_ctx).await,
             SqlRequest::CreateTable(req) => self.create_table(req).await,
             SqlRequest::CreateDatabase(req) => self.create_database(req).await,
             SqlRequest::Alter(req) => self.alter(req).await,
             SqlRequest::DropTable(req) => self.drop_table(req).await,
-            SqlRequest::ShowDatabases(stmt) => {
-                show_databases(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
-            }
+            SqlRequest::TruncateTable(req) => self.truncate_table(req).await,
+            SqlRequest::ShowDatabases(stmt) => show_databases(stmt, self.catalog_manager.clone())
+                .await
+                .context(ExecuteSqlSnafu),
             SqlRequest::ShowTables(stmt) => {
-                show_tables(stmt, self.catalog_manager.clone(), query_ctx).context(ExecuteSqlSnafu)
+                show_tables(stmt, self.catalog_manager.clone(), query_ctx)
+                    .await
+                    .context(ExecuteSqlSnafu)
             }
             SqlRequest::DescribeTable(stmt) => {
                 describe_table(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
@@ -90,6 +116,10 @@ impl SqlHandler {
                 .await
                 .context(ExecuteSqlSnafu),
         };
+        if is_ddl {
+            // Cached plans may reference the tables changed.
+            self.query_engine.invalidate_plan_cache();
+        }
         if let Err(e) = &result {
             error!("Datanode execution error: {:?}", e);
         }
@@ -234,7 +264,7 @@ mod tests {
                 Arc::new(NoopLogStore::default()),
                 object_store.clone(),
             ),
-            object_store,
+            object_store.clone(),
         ));
 
         let catalog_list = Arc::new(
@@ -252,7 +282,12 @@ mod tests {
 
         let factory = QueryEngineFactory::new(catalog_list.clone());
         let query_engine = factory.query_engine();
-        let sql_handler = SqlHandler::new(table_engine, catalog_list.clone(), query_engine.clone());
+        let sql_handler = SqlHandler::new(
+            table_engine,
+            catalog_list.clone(),
+            query_engine.clone(),
+            object_store,
+        );
 
         let stmt = match query_engine.sql_to_statement(sql).unwrap() {
             Statement::Insert(i) => i,
@@ -261,7 +296,12 @@ mod tests {
             }
         };
         let request = sql_handler
-            .insert_to_request(catalog_list.clone(), *stmt, TableReference::bare("demo"))
+            .insert_to_request(
+                catalog_list.clone(),
+                *stmt,
+                TableReference::bare("demo"),
+                None,
+            )
             .unwrap();
 
         match request {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use catalog::RenameTableRequest;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
 use snafu::prelude::*;
@@ -43,12 +44,44 @@ impl SqlHandler {
                 table_name: &full_table_name,
             }
         );
+
+        let rename_request = if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
+            // Also checks the catalog, since it might contain tables not opened by the engine.
+            let new_table = self
+                .catalog_manager
+                .table(catalog_name, schema_name, new_table_name)
+                .context(error::CatalogSnafu)?;
+            ensure!(
+                new_table.is_none(),
+                error::TableExistsSnafu {
+                    table_name: new_table_name,
+                }
+            );
+            Some(RenameTableRequest {
+                catalog: catalog_name.to_string(),
+                schema: schema_name.to_string(),
+                table_name: table_name.to_string(),
+                new_table_name: new_table_name.to_string(),
+            })
+        } else {
+            None
+        };
+
         self.table_engine
             .alter_table(&ctx, req)
             .await
             .context(error::AlterTableSnafu {
-                table_name: full_table_name,
+                table_name: &full_table_name,
             })?;
+
+        if let Some(rename_request) = rename_request {
+            self.catalog_manager
+                .rename_table(rename_request)
+                .await
+                .context(error::RenameTableSnafu {
+                    table_name: full_table_name,
+                })?;
+        }
         // Tried in MySQL, it really prints "Affected Rows: 0".
         Ok(Output::AffectedRows(0))
     }
@@ -76,13 +109,9 @@ impl SqlHandler {
             AlterTableOperation::DropColumn { name } => AlterKind::DropColumns {
                 names: vec![name.value.clone()],
             },
-            AlterTableOperation::RenameTable { .. } => {
-                // TODO update proto to support alter table name
-                return error::InvalidSqlSnafu {
-                    msg: "rename table not unsupported yet".to_string(),
-                }
-                .fail();
-            }
+            AlterTableOperation::RenameTable { new_table_name } => AlterKind::RenameTable {
+                new_table_name: new_table_name.clone(),
+            },
         };
         Ok(AlterTableRequest {
             catalog_name: Some(table_ref.catalog.to_string()),
@@ -145,9 +174,18 @@ mod tests {
     async fn test_alter_to_request_with_renaming_table() {
         let handler = create_mock_sql_handler().await;
         let alter_table = parse_sql("ALTER TABLE test_table RENAME table_t;");
-        let err = handler
+        let req = handler
             .alter_to_request(alter_table, TableReference::bare("test_table"))
-            .unwrap_err();
-        assert_matches!(err, crate::error::Error::InvalidSql { .. });
+            .unwrap();
+        assert_eq!(req.table_name, "test_table");
+
+        let alter_kind = req.alter_kind;
+        assert_matches!(alter_kind, AlterKind::RenameTable { .. });
+        match alter_kind {
+            AlterKind::RenameTable { new_table_name } => {
+                assert_eq!(new_table_name, "table_t");
+            }
+            _ => unreachable!(),
+        }
     }
 }
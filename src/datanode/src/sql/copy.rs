@@ -0,0 +1,444 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::{BTreeMap, HashMap};
+
+use common_function::scalars::timestamp::parse_interval;
+use common_query::Output;
+use datatypes::arrow::array::{Array, BooleanArray, TimestampMillisecondArray};
+use datatypes::arrow::datatypes::{DataType, SchemaRef as ArrowSchemaRef, TimeUnit};
+use datatypes::arrow::record_batch::RecordBatch as DfRecordBatch;
+use datatypes::arrow::{compute, csv};
+use futures::TryStreamExt;
+use mito::external::{self, FileFormat};
+use object_store::ObjectStore;
+use parquet::arrow::ArrowWriter;
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use sql::statements::copy::{CopyQueryTo, CopyTable};
+use sql::statements::query::Query;
+use sql::statements::statement::Statement;
+use table::engine::TableReference;
+use table::requests::InsertRequest;
+
+use crate::error::{
+    EncodeCsvFileSnafu, EncodeParquetFileSnafu, ExecuteSqlSnafu, InsertSnafu,
+    InvalidCopyOptionSnafu, InvalidSqlSnafu, PartitionExportDataSnafu, PollRecordbatchStreamSnafu,
+    Result, WriteExportFileSnafu,
+};
+use crate::sql::{SqlHandler, SqlRequest};
+
+/// Request to import the rows in the files at `location` into the table.
+#[derive(Debug)]
+pub struct CopyTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub location: String,
+    pub format: FileFormat,
+}
+
+/// Request to export the results of `query` into files at `location`.
+#[derive(Debug)]
+pub struct CopyQueryToRequest {
+    pub query: Box<Query>,
+    pub location: String,
+    pub format: FileFormat,
+    /// Max size of each file in bytes, estimated by the in-memory size of the rows.
+    pub max_file_size: Option<usize>,
+    /// Interval of the time buckets in milliseconds.
+    pub time_bucket: Option<i64>,
+}
+
+impl SqlHandler {
+    /// Imports the files into the table batch by batch, returns the number of imported
+    /// rows. All columns of the table must be present in the files.
+    pub(crate) async fn copy_table(&self, req: CopyTableRequest) -> Result<Output> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table = self.get_table(&table_ref)?;
+        let schema = table.schema();
+
+        let mut stream = external::read_files(
+            self.file_store.clone(),
+            req.location,
+            req.format,
+            schema.clone(),
+        );
+        let mut affected_rows = 0;
+        while let Some(batch) = stream
+            .try_next()
+            .await
+            .context(PollRecordbatchStreamSnafu)?
+        {
+            let columns_values = schema
+                .column_schemas()
+                .iter()
+                .zip(batch.columns())
+                .map(|(column, vector)| (column.name.clone(), vector.clone()))
+                .collect::<HashMap<_, _>>();
+            affected_rows += table
+                .insert(InsertRequest {
+                    catalog_name: req.catalog_name.clone(),
+                    schema_name: req.schema_name.clone(),
+                    table_name: req.table_name.clone(),
+                    columns_values,
+                })
+                .await
+                .with_context(|_| InsertSnafu {
+                    table_name: table_ref.to_string(),
+                })?;
+        }
+
+        Ok(Output::AffectedRows(affected_rows))
+    }
+
+    pub(crate) fn copy_to_request(
+        &self,
+        stmt: CopyTable,
+        table_ref: TableReference,
+    ) -> Result<SqlRequest> {
+        external::check_location(&stmt.location).map_err(|reason| {
+            InvalidSqlSnafu {
+                msg: format!(
+                    "invalid location to copy from '{}': {reason}",
+                    stmt.location
+                ),
+            }
+            .build()
+        })?;
+        let format = match &stmt.format {
+            Some(format) => FileFormat::from_name(format),
+            None => FileFormat::from_path(&stmt.location),
+        }
+        .with_context(|| InvalidSqlSnafu {
+            msg: format!(
+                "unknown format of the files to copy from: {}",
+                stmt.location
+            ),
+        })?;
+
+        Ok(SqlRequest::CopyTable(CopyTableRequest {
+            catalog_name: table_ref.catalog.to_string(),
+            schema_name: table_ref.schema.to_string(),
+            table_name: table_ref.table.to_string(),
+            location: stmt.location,
+            format,
+        }))
+    }
+
+    /// Executes the query and writes its results into the files, returns the number of
+    /// exported rows.
+    pub(crate) async fn copy_query_to(
+        &self,
+        req: CopyQueryToRequest,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let plan = self
+            .query_engine
+            .statement_to_plan(Statement::Query(req.query), query_ctx)
+            .context(ExecuteSqlSnafu)?;
+        let mut stream = match self
+            .query_engine
+            .execute(&plan)
+            .await
+            .context(ExecuteSqlSnafu)?
+        {
+            Output::Stream(stream) => stream,
+            Output::RecordBatches(batches) => batches.as_stream(),
+            Output::AffectedRows(_) => unreachable!(),
+        };
+
+        let mut writer = ExportWriter {
+            object_store: self.file_store.clone(),
+            location: req.location,
+            format: req.format,
+            max_file_size: req.max_file_size,
+            time_bucket: req.time_bucket,
+            parts: BTreeMap::new(),
+            exported_rows: 0,
+        };
+        while let Some(batch) = stream
+            .try_next()
+            .await
+            .context(PollRecordbatchStreamSnafu)?
+        {
+            writer.write(batch.into_df_record_batch()).await?;
+        }
+        let exported_rows = writer.finish().await?;
+
+        Ok(Output::AffectedRows(exported_rows))
+    }
+
+    pub(crate) fn copy_query_to_request(&self, stmt: CopyQueryTo) -> Result<SqlRequest> {
+        // The files are exported into the object store of files, the location mustn't
+        // escape its root.
+        external::check_location(&stmt.location).map_err(|reason| {
+            InvalidCopyOptionSnafu {
+                option: "location",
+                reason,
+            }
+            .build()
+        })?;
+        let partitioned = stmt.max_file_size.is_some() || stmt.time_bucket.is_some();
+        ensure!(
+            !partitioned || stmt.location.ends_with('/'),
+            InvalidCopyOptionSnafu {
+                option: "location",
+                reason: "the location must be a directory ending with '/' to export multiple files",
+            }
+        );
+
+        let format = match &stmt.format {
+            Some(format) => FileFormat::from_name(format),
+            None if stmt.location.ends_with('/') => Some(FileFormat::Parquet),
+            None => FileFormat::from_path(&stmt.location),
+        }
+        .with_context(|| InvalidCopyOptionSnafu {
+            option: external::EXTERNAL_FORMAT_KEY,
+            reason: format!("unknown format of the files to copy to: {}", stmt.location),
+        })?;
+        let max_file_size = stmt
+            .max_file_size
+            .map(|size| {
+                parse_file_size(&size).with_context(|| InvalidCopyOptionSnafu {
+                    option: "max_file_size",
+                    reason: format!("expect a positive size like '64MB', found: {size}"),
+                })
+            })
+            .transpose()?;
+        let time_bucket = stmt
+            .time_bucket
+            .map(|interval| {
+                parse_interval(&interval)
+                    .map(|nanos| nanos / 1_000_000)
+                    .filter(|millis| *millis > 0)
+                    .with_context(|| InvalidCopyOptionSnafu {
+                        option: "time_bucket",
+                        reason: format!("invalid interval '{interval}'"),
+                    })
+            })
+            .transpose()?;
+
+        Ok(SqlRequest::CopyQueryTo(Box::new(CopyQueryToRequest {
+            query: stmt.query,
+            location: stmt.location,
+            format,
+            max_file_size,
+            time_bucket,
+        })))
+    }
+}
+
+/// Parses the size like `1024`, `512KB` or `64MB` into bytes.
+fn parse_file_size(s: &str) -> Option<usize> {
+    let s = s.trim();
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let value = s[..digits].parse::<usize>().ok()?;
+    let unit = match s[digits..].trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" | "KIB" => 1 << 10,
+        "MB" | "MIB" => 1 << 20,
+        "GB" | "GIB" => 1 << 30,
+        _ => return None,
+    };
+    value.checked_mul(unit).filter(|size| *size > 0)
+}
+
+/// Rows buffered for a file to export.
+#[derive(Default)]
+struct FilePart {
+    batches: Vec<DfRecordBatch>,
+    size: usize,
+    /// Number of files of the partition written.
+    written_files: usize,
+}
+
+/// Writes the exported rows into files, partitioned by the time buckets of the rows
+/// and split by the size of the files.
+struct ExportWriter {
+    object_store: ObjectStore,
+    location: String,
+    format: FileFormat,
+    max_file_size: Option<usize>,
+    time_bucket: Option<i64>,
+    /// Buffered rows of each time bucket, keyed by the start of the bucket.
+    parts: BTreeMap<Option<i64>, FilePart>,
+    exported_rows: usize,
+}
+
+impl ExportWriter {
+    async fn write(&mut self, batch: DfRecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        self.exported_rows += batch.num_rows();
+
+        let Some(interval) = self.time_bucket else {
+            return self.write_part(None, batch).await;
+        };
+        let buckets = time_buckets(&batch, interval)?;
+        let mut keys = buckets.clone();
+        keys.sort_unstable();
+        keys.dedup();
+        for key in keys {
+            let mask = buckets
+                .iter()
+                .map(|bucket| Some(*bucket == key))
+                .collect::<BooleanArray>();
+            let rows =
+                compute::filter_record_batch(&batch, &mask).context(PartitionExportDataSnafu)?;
+            self.write_part(key, rows).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_part(&mut self, key: Option<i64>, batch: DfRecordBatch) -> Result<()> {
+        let part = self.parts.entry(key).or_default();
+        part.size += batch
+            .columns()
+            .iter()
+            .map(|array| array.get_array_memory_size())
+            .sum::<usize>();
+        part.batches.push(batch);
+        if self
+            .max_file_size
+            .map(|max_size| part.size >= max_size)
+            .unwrap_or(false)
+        {
+            self.flush_part(key).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered rows of the partition into a new file.
+    async fn flush_part(&mut self, key: Option<i64>) -> Result<()> {
+        let Some(part) = self.parts.get_mut(&key) else {
+            return Ok(());
+        };
+        if part.batches.is_empty() {
+            return Ok(());
+        }
+
+        let index = part.written_files;
+        let batches = std::mem::take(&mut part.batches);
+        part.size = 0;
+        part.written_files += 1;
+
+        let path = self.file_path(key, index);
+        let buf = encode_file(self.format, &path, &batches)?;
+        self.object_store
+            .object(&path)
+            .write(buf)
+            .await
+            .context(WriteExportFileSnafu { path })
+    }
+
+    /// Returns the path of the `index`th file of the partition. A single file is written
+    /// to the location directly if it isn't a directory.
+    fn file_path(&self, key: Option<i64>, index: usize) -> String {
+        if !self.location.ends_with('/') {
+            return self.location.clone();
+        }
+        let extension = match self.format {
+            FileFormat::Csv => "csv",
+            FileFormat::Parquet => "parquet",
+        };
+        let file_name = format!("part-{index:05}.{extension}");
+        match (self.time_bucket, key) {
+            (None, _) => format!("{}{file_name}", self.location),
+            (Some(_), Some(bucket)) => format!("{}{bucket}/{file_name}", self.location),
+            (Some(_), None) => format!("{}null/{file_name}", self.location),
+        }
+    }
+
+    /// Writes all the buffered rows, returns the number of exported rows.
+    async fn finish(mut self) -> Result<usize> {
+        let keys = self.parts.keys().cloned().collect::<Vec<_>>();
+        for key in keys {
+            self.flush_part(key).await?;
+        }
+        Ok(self.exported_rows)
+    }
+}
+
+/// Returns the start of the time bucket of each row, in milliseconds, by the first
+/// timestamp column of the `batch`.
+fn time_buckets(batch: &DfRecordBatch, interval: i64) -> Result<Vec<Option<i64>>> {
+    let column = batch
+        .schema()
+        .fields()
+        .iter()
+        .position(|field| matches!(field.data_type(), DataType::Timestamp(_, _)))
+        .with_context(|| InvalidCopyOptionSnafu {
+            option: "time_bucket",
+            reason: "the results to export have no timestamp column",
+        })?;
+    let millis = compute::cast(
+        batch.column(column),
+        &DataType::Timestamp(TimeUnit::Millisecond, None),
+    )
+    .context(PartitionExportDataSnafu)?;
+    // Safety: the array is cast to timestamps in millisecond above.
+    let millis = millis
+        .as_any()
+        .downcast_ref::<TimestampMillisecondArray>()
+        .unwrap();
+    Ok(millis
+        .iter()
+        .map(|ts| ts.map(|ts| ts.div_euclid(interval) * interval))
+        .collect())
+}
+
+fn encode_file(format: FileFormat, path: &str, batches: &[DfRecordBatch]) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    match format {
+        FileFormat::Csv => {
+            let mut writer = csv::Writer::new(&mut buf);
+            for batch in batches {
+                writer.write(batch).context(EncodeCsvFileSnafu { path })?;
+            }
+        }
+        FileFormat::Parquet => {
+            let schema: ArrowSchemaRef = batches[0].schema();
+            let mut writer = ArrowWriter::try_new(&mut buf, schema, None)
+                .context(EncodeParquetFileSnafu { path })?;
+            for batch in batches {
+                writer
+                    .write(batch)
+                    .context(EncodeParquetFileSnafu { path })?;
+            }
+            writer.close().context(EncodeParquetFileSnafu { path })?;
+        }
+    }
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_size() {
+        assert_eq!(Some(1024), parse_file_size("1024"));
+        assert_eq!(Some(512 << 10), parse_file_size("512KB"));
+        assert_eq!(Some(64 << 20), parse_file_size("64mb"));
+        assert_eq!(Some(1 << 30), parse_file_size("1 GiB"));
+        assert_eq!(None, parse_file_size("0MB"));
+        assert_eq!(None, parse_file_size("MB"));
+        assert_eq!(None, parse_file_size("1TB"));
+    }
+}
@@ -21,8 +21,9 @@ use common_query::Output;
 use common_telemetry::tracing::info;
 use common_telemetry::tracing::log::error;
 use datatypes::schema::SchemaBuilder;
+use mito::{engine, external};
 use snafu::{ensure, OptionExt, ResultExt};
-use sql::ast::TableConstraint;
+use sql::ast::{TableConstraint, Value as SqlValue};
 use sql::statements::column_def_to_schema;
 use sql::statements::create::CreateTable;
 use store_api::storage::consts::TIME_INDEX_NAME;
@@ -198,6 +199,28 @@ impl SqlHandler {
                 .context(CreateSchemaSnafu)?,
         );
 
+        // Only options of SST files, the dedup policy and options of external tables are
+        // passed to the table engine, the number of regions is not supported by the
+        // datanode yet.
+        let table_options = stmt
+            .options
+            .iter()
+            .filter_map(|option| {
+                let name = option.name.value.to_lowercase();
+                if !engine::is_sst_option(&name)
+                    && name != engine::DEDUP_POLICY_KEY
+                    && !external::is_external_option(&name)
+                {
+                    return None;
+                }
+                let value = match &option.value {
+                    SqlValue::SingleQuotedString(s) => s.clone(),
+                    value => value.to_string(),
+                };
+                Some((name, value))
+            })
+            .collect();
+
         let request = CreateTableRequest {
             id: table_id,
             catalog_name: table_ref.catalog.to_string(),
@@ -208,7 +231,7 @@ impl SqlHandler {
             region_numbers: vec![0],
             primary_key_indices: primary_keys,
             create_if_not_exists: stmt.if_not_exists,
-            table_options: HashMap::new(),
+            table_options,
         };
         Ok(request)
     }
@@ -259,6 +282,45 @@ mod tests {
         assert_eq!(vec![0], c.primary_key_indices);
         assert_eq!(1, c.schema.timestamp_index().unwrap());
         assert_eq!(4, c.schema.column_schemas().len());
+        assert!(c.table_options.is_empty());
+
+        let parsed_stmt = sql_to_statement(
+            r#"create table demo_table(
+                       ts timestamp time index,
+                       cpu double) engine=mito
+                       with(regions=1, sst_format='arrow_ipc', sst_row_group_size=1024,
+                       dedup_policy='keep_all');"#,
+        );
+        let c = handler
+            .create_to_request(42, parsed_stmt, TableReference::bare("demo_table"))
+            .unwrap();
+        let expect = HashMap::from([
+            (engine::SST_FORMAT_KEY.to_string(), "arrow_ipc".to_string()),
+            (
+                engine::SST_ROW_GROUP_SIZE_KEY.to_string(),
+                "1024".to_string(),
+            ),
+            (engine::DEDUP_POLICY_KEY.to_string(), "keep_all".to_string()),
+        ]);
+        assert_eq!(expect, c.table_options);
+
+        let parsed_stmt = sql_to_statement(
+            r#"create external table demo_table(
+                       ts timestamp time index,
+                       cpu double)
+                       with(location='data/demo.csv', format='csv');"#,
+        );
+        let c = handler
+            .create_to_request(42, parsed_stmt, TableReference::bare("demo_table"))
+            .unwrap();
+        let expect = HashMap::from([
+            (
+                external::EXTERNAL_LOCATION_KEY.to_string(),
+                "data/demo.csv".to_string(),
+            ),
+            (external::EXTERNAL_FORMAT_KEY.to_string(), "csv".to_string()),
+        ]);
+        assert_eq!(expect, c.table_options);
     }
 
     /// Time index not specified in sql
@@ -0,0 +1,223 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::Output;
+use common_time::timestamp::TimeUnit;
+use common_time::{TimeZone, Timestamp, TimestampRange};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::ColumnSchema;
+use datatypes::value::Value;
+use snafu::{OptionExt, ResultExt};
+use sql::ast::{BinaryOperator, Expr, Value as SqlValue};
+use sql::statements::delete::Delete;
+use sql::statements::{self};
+use table::engine::TableReference;
+use table::requests::*;
+
+use crate::error::{
+    DeleteSnafu, InvalidSqlSnafu, MissingTimestampColumnSnafu, ParseSqlValueSnafu, Result,
+};
+use crate::sql::{SqlHandler, SqlRequest};
+
+impl SqlHandler {
+    pub(crate) async fn delete_range(&self, req: DeleteRangeRequest) -> Result<Output> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name.to_string(),
+            schema: &req.schema_name.to_string(),
+            table: &req.table_name.to_string(),
+        };
+
+        let table = self.get_table(&table_ref)?;
+
+        table
+            .delete_range(req)
+            .await
+            .with_context(|_| DeleteSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+
+        // Rows are deleted by a range tombstone without scanning them, so the number
+        // of deleted rows is unknown.
+        Ok(Output::AffectedRows(0))
+    }
+
+    /// Converts a `DELETE` statement into a [DeleteRangeRequest].
+    ///
+    /// Only conditions on the time index are supported, e.g. `ts BETWEEN a AND b`
+    /// and `ts >= a AND ts < b`, other conditions are rejected. Timestamp strings
+    /// without an explicit offset are in the `time_zone` of the session.
+    pub(crate) fn delete_to_request(
+        &self,
+        stmt: Delete,
+        table_ref: TableReference,
+        time_zone: Option<&TimeZone>,
+    ) -> Result<SqlRequest> {
+        let table = self.get_table(&table_ref)?;
+        let schema = table.schema();
+        let ts_column = schema
+            .timestamp_column()
+            .context(MissingTimestampColumnSnafu)?;
+        let selection = stmt.selection.context(InvalidSqlSnafu {
+            msg: "DELETE without a time range on the time index is not supported",
+        })?;
+
+        let mut bounds = TimeBounds::default();
+        bounds.collect(&selection, ts_column, time_zone)?;
+
+        Ok(SqlRequest::DeleteRange(DeleteRangeRequest {
+            catalog_name: table_ref.catalog.to_string(),
+            schema_name: table_ref.schema.to_string(),
+            table_name: table_ref.table.to_string(),
+            range: bounds.into_range(ts_column),
+        }))
+    }
+}
+
+/// Bounds of the time index, in the unit of the time index.
+#[derive(Debug, Default)]
+struct TimeBounds {
+    /// Inclusive lower bound.
+    start: Option<i64>,
+    /// Exclusive upper bound.
+    end: Option<i64>,
+}
+
+impl TimeBounds {
+    /// Collects bounds from `expr`, all conditions in `expr` must be on the time index
+    /// and joined by `AND`.
+    fn collect(
+        &mut self,
+        expr: &Expr,
+        ts_column: &ColumnSchema,
+        time_zone: Option<&TimeZone>,
+    ) -> Result<()> {
+        match expr {
+            Expr::Nested(expr) => self.collect(expr, ts_column, time_zone),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => {
+                self.collect(left, ts_column, time_zone)?;
+                self.collect(right, ts_column, time_zone)
+            }
+            Expr::BinaryOp { left, op, right } => match (&**left, &**right) {
+                (column, Expr::Value(value)) if is_time_index(column, ts_column) => {
+                    self.apply(op, ts_value(value, ts_column, time_zone)?, expr)
+                }
+                (Expr::Value(value), column) if is_time_index(column, ts_column) => {
+                    // `value op ts` is the same as `ts reversed_op value`.
+                    let op = match op {
+                        BinaryOperator::Gt => BinaryOperator::Lt,
+                        BinaryOperator::GtEq => BinaryOperator::LtEq,
+                        BinaryOperator::Lt => BinaryOperator::Gt,
+                        BinaryOperator::LtEq => BinaryOperator::GtEq,
+                        op => op.clone(),
+                    };
+                    self.apply(&op, ts_value(value, ts_column, time_zone)?, expr)
+                }
+                _ => unsupported_condition(expr),
+            },
+            Expr::Between {
+                expr: column,
+                negated: false,
+                low,
+                high,
+            } if is_time_index(column, ts_column) => match (&**low, &**high) {
+                (Expr::Value(low), Expr::Value(high)) => {
+                    self.apply(
+                        &BinaryOperator::GtEq,
+                        ts_value(low, ts_column, time_zone)?,
+                        expr,
+                    )?;
+                    self.apply(
+                        &BinaryOperator::LtEq,
+                        ts_value(high, ts_column, time_zone)?,
+                        expr,
+                    )
+                }
+                _ => unsupported_condition(expr),
+            },
+            _ => unsupported_condition(expr),
+        }
+    }
+
+    /// Narrows the bounds by condition `ts op value`.
+    fn apply(&mut self, op: &BinaryOperator, value: i64, expr: &Expr) -> Result<()> {
+        let (start, end) = match op {
+            BinaryOperator::Eq => (Some(value), Some(value.saturating_add(1))),
+            BinaryOperator::Gt => (Some(value.saturating_add(1)), None),
+            BinaryOperator::GtEq => (Some(value), None),
+            BinaryOperator::Lt => (None, Some(value)),
+            BinaryOperator::LtEq => (None, Some(value.saturating_add(1))),
+            _ => return unsupported_condition(expr),
+        };
+        if let Some(start) = start {
+            self.start = Some(self.start.map_or(start, |s| s.max(start)));
+        }
+        if let Some(end) = end {
+            self.end = Some(self.end.map_or(end, |e| e.min(end)));
+        }
+        Ok(())
+    }
+
+    fn into_range(self, ts_column: &ColumnSchema) -> TimestampRange {
+        let unit = time_unit(ts_column);
+        // Unbounded sides are limited to timestamps that could be compared with timestamps
+        // of other units without overflow.
+        let start = self.start.unwrap_or(i64::MIN / unit.factor());
+        let end = self.end.unwrap_or(i64::MAX / unit.factor()).max(start);
+
+        // Safety: `start <= end`.
+        TimestampRange::new(Timestamp::new(start, unit), Timestamp::new(end, unit)).unwrap()
+    }
+}
+
+fn is_time_index(expr: &Expr, ts_column: &ColumnSchema) -> bool {
+    matches!(expr, Expr::Identifier(ident) if ident.value == ts_column.name)
+}
+
+/// Returns the raw value of `value` in the unit of the time index.
+fn ts_value(
+    value: &SqlValue,
+    ts_column: &ColumnSchema,
+    time_zone: Option<&TimeZone>,
+) -> Result<i64> {
+    let value =
+        statements::sql_value_to_value(&ts_column.name, &ts_column.data_type, value, time_zone)
+            .context(ParseSqlValueSnafu)?;
+    match value {
+        Value::Timestamp(ts) => Ok(ts.convert_to(time_unit(ts_column))),
+        Value::Int64(v) => Ok(v),
+        value => InvalidSqlSnafu {
+            msg: format!("Invalid value {value:?} for time index {}", ts_column.name),
+        }
+        .fail(),
+    }
+}
+
+fn time_unit(ts_column: &ColumnSchema) -> TimeUnit {
+    match &ts_column.data_type {
+        ConcreteDataType::Timestamp(t) => t.unit(),
+        // Other types of time index are treated as milliseconds.
+        _ => TimeUnit::Millisecond,
+    }
+}
+
+fn unsupported_condition<T>(expr: &Expr) -> Result<T> {
+    InvalidSqlSnafu {
+        msg: format!("Unsupported condition {expr} in DELETE, only time range on the time index is supported"),
+    }
+    .fail()
+}
@@ -14,13 +14,13 @@
 
 use catalog::CatalogManagerRef;
 use common_query::Output;
+use common_time::TimeZone;
 use datatypes::data_type::DataType;
-use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::ColumnSchema;
 use datatypes::vectors::MutableVector;
 use snafu::{ensure, OptionExt, ResultExt};
-use sql::ast::Value as SqlValue;
-use sql::statements::insert::Insert;
-use sql::statements::{self};
+use sql::ast::Expr;
+use sql::statements::insert::{self, Insert};
 use table::engine::TableReference;
 use table::requests::*;
 
@@ -54,9 +54,10 @@ impl SqlHandler {
         catalog_manager: CatalogManagerRef,
         stmt: Insert,
         table_ref: TableReference,
+        time_zone: Option<&TimeZone>,
     ) -> Result<SqlRequest> {
         let columns = stmt.columns();
-        let values = stmt.values().context(ParseSqlValueSnafu)?;
+        let rows = stmt.rows().context(ParseSqlValueSnafu)?;
 
         let table = catalog_manager
             .table(table_ref.catalog, table_ref.schema, table_ref.table)
@@ -70,19 +71,15 @@ impl SqlHandler {
         } else {
             columns.len()
         };
-        let rows_num = values.len();
+        let rows_num = rows.len();
 
-        let mut columns_builders: Vec<(&String, &ConcreteDataType, Box<dyn MutableVector>)> =
+        let mut columns_builders: Vec<(&ColumnSchema, Box<dyn MutableVector>)> =
             Vec::with_capacity(columns_num);
 
         if columns.is_empty() {
             for column_schema in schema.column_schemas() {
                 let data_type = &column_schema.data_type;
-                columns_builders.push((
-                    &column_schema.name,
-                    data_type,
-                    data_type.create_mutable_vector(rows_num),
-                ));
+                columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
             }
         } else {
             for column_name in columns {
@@ -94,16 +91,12 @@ impl SqlHandler {
                         }
                     })?;
                 let data_type = &column_schema.data_type;
-                columns_builders.push((
-                    column_name,
-                    data_type,
-                    data_type.create_mutable_vector(rows_num),
-                ));
+                columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
             }
         }
 
         // Convert rows into columns
-        for row in values {
+        for (row_index, row) in rows.iter().enumerate() {
             ensure!(
                 row.len() == columns_num,
                 ColumnValuesNumberMismatchSnafu {
@@ -112,10 +105,8 @@ impl SqlHandler {
                 }
             );
 
-            for (sql_val, (column_name, data_type, builder)) in
-                row.iter().zip(columns_builders.iter_mut())
-            {
-                add_row_to_vector(column_name, data_type, sql_val, builder)?;
+            for (expr, (column_schema, builder)) in row.iter().zip(columns_builders.iter_mut()) {
+                add_row_to_vector(row_index, column_schema, expr, builder, time_zone)?;
             }
         }
 
@@ -125,19 +116,20 @@ impl SqlHandler {
             table_name: table_ref.table.to_string(),
             columns_values: columns_builders
                 .into_iter()
-                .map(|(c, _, mut b)| (c.to_owned(), b.to_vector()))
+                .map(|(c, mut b)| (c.name.clone(), b.to_vector()))
                 .collect(),
         }))
     }
 }
 
 fn add_row_to_vector(
-    column_name: &str,
-    data_type: &ConcreteDataType,
-    sql_val: &SqlValue,
+    row_index: usize,
+    column_schema: &ColumnSchema,
+    expr: &Expr,
     builder: &mut Box<dyn MutableVector>,
+    time_zone: Option<&TimeZone>,
 ) -> Result<()> {
-    let value = statements::sql_value_to_value(column_name, data_type, sql_val)
+    let value = insert::value_of_column(row_index, column_schema, expr, time_zone)
         .context(ParseSqlValueSnafu)?;
     builder.push_value_ref(value.as_value_ref()).unwrap();
 
@@ -0,0 +1,576 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Continuous aggregation tasks.
+//!
+//! A task runs its query once per interval over the data arrived since its last
+//! run, and writes the results into the sink table. Tasks and their states are
+//! persisted in the `tasks` system table, which could be queried to inspect them.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use catalog::{CatalogManagerRef, RegisterSystemTableRequest};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, TASKS_TABLE_ID};
+use common_function::scalars::timestamp::parse_interval;
+use common_query::Output;
+use common_recordbatch::{util as record_util, RecordBatch};
+use common_telemetry::logging::{error, info, warn};
+use common_time::timestamp::TimeUnit;
+use common_time::util as time_util;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaBuilder};
+use datatypes::value::Value;
+use datatypes::vectors::{Int64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+use futures::StreamExt;
+use query::QueryEngineRef;
+use session::context::QueryContext;
+use snafu::{ensure, OptionExt, ResultExt};
+use table::requests::{CreateTableRequest, InsertRequest};
+use table::TableRef;
+use tokio::sync::Mutex;
+
+use crate::error::{
+    CatalogSnafu, ExecuteSqlSnafu, IncorrectInternalStateSnafu, InsertSnafu, InvalidTaskSnafu,
+    PollRecordbatchStreamSnafu, RegisterTasksTableSnafu, Result, TableNotFoundSnafu,
+    TaskExistsSnafu, TaskNotFoundSnafu, TasksTableNotFoundSnafu,
+};
+
+pub const TASKS_TABLE_NAME: &str = "tasks";
+
+const STATE_RUNNING: &str = "RUNNING";
+const STATE_DROPPED: &str = "DROPPED";
+
+/// Interval of checking whether any task is due.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// Max number of intervals the first run of a task aggregates, older data is never
+/// aggregated by the task.
+const MAX_FIRST_RUN_INTERVALS: i64 = 10;
+
+#[derive(Debug, Clone)]
+struct Task {
+    catalog: String,
+    schema: String,
+    name: String,
+    query: String,
+    sink_table: String,
+    interval_millis: i64,
+    /// Data before the watermark, in milliseconds, has been aggregated.
+    watermark: Option<i64>,
+    last_run_time: Option<i64>,
+    last_error: Option<String>,
+    gmt_created: i64,
+}
+
+impl Task {
+    fn full_name(&self) -> String {
+        catalog::format_full_table_name(&self.catalog, &self.schema, &self.name)
+    }
+}
+
+/// The definition of a task to create.
+pub(crate) struct CreateTaskRequest {
+    pub catalog: String,
+    pub schema: String,
+    pub name: String,
+    pub interval: String,
+    pub sink_table: String,
+    pub query: String,
+    pub if_not_exists: bool,
+}
+
+/// Schedules the continuous aggregation tasks.
+#[derive(Clone)]
+pub struct TaskManager {
+    catalog_manager: CatalogManagerRef,
+    query_engine: QueryEngineRef,
+    /// Tasks by their full names.
+    tasks: Arc<Mutex<HashMap<String, Task>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl TaskManager {
+    /// Creates the manager and registers the tasks table, the table is opened when
+    /// the catalog manager starts.
+    pub async fn new(
+        catalog_manager: CatalogManagerRef,
+        query_engine: QueryEngineRef,
+    ) -> Result<Self> {
+        let request = CreateTableRequest {
+            id: TASKS_TABLE_ID,
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TASKS_TABLE_NAME.to_string(),
+            desc: Some("Continuous aggregation tasks".to_string()),
+            schema: Arc::new(build_tasks_schema()),
+            region_numbers: vec![0],
+            // task_catalog, task_schema and task_name as primary key
+            primary_key_indices: vec![0, 1, 2],
+            create_if_not_exists: true,
+            table_options: HashMap::default(),
+        };
+        catalog_manager
+            .register_system_table(RegisterSystemTableRequest {
+                create_table_request: request,
+                open_hook: None,
+            })
+            .await
+            .context(RegisterTasksTableSnafu)?;
+
+        Ok(Self {
+            catalog_manager,
+            query_engine,
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+            running: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Loads the persisted tasks and spawns the background scheduler.
+    pub async fn start(&self) -> Result<()> {
+        if self
+            .running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            warn!("Task manager started multiple times");
+            return Ok(());
+        }
+
+        let loaded = self.load_tasks().await?;
+        info!("Loaded {} continuous aggregation tasks", loaded.len());
+        *self.tasks.lock().await = loaded;
+
+        let manager = self.clone();
+        common_runtime::spawn_bg(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !manager.running.load(Ordering::Acquire) {
+                    break;
+                }
+                manager
+                    .run_due_tasks(time_util::current_time_millis())
+                    .await;
+            }
+            info!("Task scheduler stopped");
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+
+    pub(crate) async fn create_task(&self, request: CreateTaskRequest) -> Result<()> {
+        let full_name =
+            catalog::format_full_table_name(&request.catalog, &request.schema, &request.name);
+        let mut tasks = self.tasks.lock().await;
+        if tasks.contains_key(&full_name) {
+            ensure!(request.if_not_exists, TaskExistsSnafu { name: full_name });
+            return Ok(());
+        }
+
+        let interval_millis = parse_interval(&request.interval)
+            .map(|nanos| nanos / 1_000_000)
+            .filter(|millis| *millis > 0)
+            .with_context(|| InvalidTaskSnafu {
+                name: &full_name,
+                reason: format!("invalid interval '{}'", request.interval),
+            })?;
+        self.validate_sink(&request, &full_name)?;
+
+        let task = Task {
+            catalog: request.catalog,
+            schema: request.schema,
+            name: request.name,
+            query: request.query,
+            sink_table: request.sink_table,
+            interval_millis,
+            watermark: None,
+            last_run_time: None,
+            last_error: None,
+            gmt_created: time_util::current_time_millis(),
+        };
+        self.persist(&task, STATE_RUNNING).await?;
+        info!("Created continuous aggregation task {}", full_name);
+        tasks.insert(full_name, task);
+        Ok(())
+    }
+
+    pub(crate) async fn drop_task(
+        &self,
+        catalog: &str,
+        schema: &str,
+        name: &str,
+        if_exists: bool,
+    ) -> Result<()> {
+        let full_name = catalog::format_full_table_name(catalog, schema, name);
+        let mut tasks = self.tasks.lock().await;
+        let Some(task) = tasks.get(&full_name) else {
+            ensure!(if_exists, TaskNotFoundSnafu { name: full_name });
+            return Ok(());
+        };
+        self.persist(task, STATE_DROPPED).await?;
+        let _ = tasks.remove(&full_name);
+        info!("Dropped continuous aggregation task {}", full_name);
+        Ok(())
+    }
+
+    /// Runs the tasks whose next run is due at `now`, in milliseconds.
+    ///
+    /// A run covers the data in `[watermark, end)`, where `end` is `now` aligned down
+    /// to the interval, so buckets no wider than the interval are complete once
+    /// aggregated as long as data arrives in time. Data arrives after its range has
+    /// been aggregated is not aggregated again. The first run of a task covers at most
+    /// [MAX_FIRST_RUN_INTERVALS] intervals before `end`.
+    pub(crate) async fn run_due_tasks(&self, now: i64) {
+        let due = self
+            .tasks
+            .lock()
+            .await
+            .values()
+            .filter(|task| {
+                let end = now.div_euclid(task.interval_millis) * task.interval_millis;
+                task.watermark.map_or(true, |watermark| end > watermark)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for mut task in due {
+            let end = now.div_euclid(task.interval_millis) * task.interval_millis;
+            let start = task
+                .watermark
+                .unwrap_or(end - MAX_FIRST_RUN_INTERVALS * task.interval_millis);
+            match self.run_task(&task, start, end).await {
+                Ok(rows) => {
+                    info!(
+                        "Task {} aggregated data before {}, rows: {}",
+                        task.full_name(),
+                        end,
+                        rows
+                    );
+                    task.watermark = Some(end);
+                    task.last_error = None;
+                }
+                Err(e) => {
+                    error!(e; "Failed to run task {}", task.full_name());
+                    task.last_error = Some(e.to_string());
+                }
+            }
+            task.last_run_time = Some(now);
+
+            let mut tasks = self.tasks.lock().await;
+            // The task may be dropped, or dropped and created again, during the run.
+            match tasks.get_mut(&task.full_name()) {
+                Some(current) if current.gmt_created == task.gmt_created => {
+                    if let Err(e) = self.persist(&task, STATE_RUNNING).await {
+                        error!(e; "Failed to persist the state of task {}", task.full_name());
+                    }
+                    *current = task;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Aggregates the data in `[start, end)` and writes the results into the sink table
+    /// batch by batch, returns the number of rows written.
+    async fn run_task(&self, task: &Task, start: i64, end: i64) -> Result<usize> {
+        let table = self
+            .catalog_manager
+            .table(&task.catalog, &task.schema, &task.sink_table)
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: &task.sink_table,
+            })?;
+
+        let query_ctx = Arc::new(QueryContext::with_current_schema(task.schema.clone()));
+        let plan = self
+            .query_engine
+            .sql_to_plan(&task.query, query_ctx)
+            .context(ExecuteSqlSnafu)?
+            .with_time_range(Some(start), Some(end))
+            .context(ExecuteSqlSnafu)?;
+        let mut rows = 0;
+        match self
+            .query_engine
+            .execute(&plan)
+            .await
+            .context(ExecuteSqlSnafu)?
+        {
+            Output::Stream(mut stream) => {
+                while let Some(batch) = stream.next().await {
+                    let batch = batch.context(PollRecordbatchStreamSnafu)?;
+                    rows += insert_batch(&table, task, batch).await?;
+                }
+            }
+            Output::RecordBatches(batches) => {
+                for batch in batches.take() {
+                    rows += insert_batch(&table, task, batch).await?;
+                }
+            }
+            Output::AffectedRows(_) => unreachable!(),
+        }
+        Ok(rows)
+    }
+
+    /// Checks the sink table exists and has all the output columns of the query, in
+    /// the same types.
+    fn validate_sink(&self, request: &CreateTaskRequest, full_name: &str) -> Result<()> {
+        let table = self
+            .catalog_manager
+            .table(&request.catalog, &request.schema, &request.sink_table)
+            .context(CatalogSnafu)?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: &request.sink_table,
+            })?;
+        let sink_schema = table.schema();
+
+        let query_ctx = Arc::new(QueryContext::with_current_schema(request.schema.clone()));
+        let output_schema = self
+            .query_engine
+            .sql_to_plan(&request.query, query_ctx)
+            .and_then(|plan| plan.schema())
+            .context(ExecuteSqlSnafu)?;
+        for column in output_schema.column_schemas() {
+            let sink_column = sink_schema
+                .column_schema_by_name(&column.name)
+                .with_context(|| InvalidTaskSnafu {
+                    name: full_name,
+                    reason: format!(
+                        "column {} not found in sink table {}",
+                        column.name, request.sink_table
+                    ),
+                })?;
+            ensure!(
+                sink_column.data_type == column.data_type,
+                InvalidTaskSnafu {
+                    name: full_name,
+                    reason: format!(
+                        "column {} is {:?} in the query but {:?} in sink table {}",
+                        column.name, column.data_type, sink_column.data_type, request.sink_table
+                    ),
+                }
+            );
+        }
+        Ok(())
+    }
+
+    async fn load_tasks(&self) -> Result<HashMap<String, Task>> {
+        let sql = format!(
+            "select * from {} where state = '{}'",
+            catalog::format_full_table_name(
+                DEFAULT_CATALOG_NAME,
+                DEFAULT_SCHEMA_NAME,
+                TASKS_TABLE_NAME
+            ),
+            STATE_RUNNING
+        );
+        let plan = self
+            .query_engine
+            .sql_to_plan(&sql, Arc::new(QueryContext::new()))
+            .context(ExecuteSqlSnafu)?;
+        let batches = match self
+            .query_engine
+            .execute(&plan)
+            .await
+            .context(ExecuteSqlSnafu)?
+        {
+            Output::Stream(stream) => record_util::collect(stream)
+                .await
+                .context(PollRecordbatchStreamSnafu)?,
+            Output::RecordBatches(batches) => batches.take(),
+            Output::AffectedRows(_) => unreachable!(),
+        };
+
+        let mut tasks = HashMap::new();
+        for batch in &batches {
+            for row in 0..batch.num_rows() {
+                let task = Task {
+                    catalog: string_at(batch, "task_catalog", row)?,
+                    schema: string_at(batch, "task_schema", row)?,
+                    name: string_at(batch, "task_name", row)?,
+                    query: string_at(batch, "query", row)?,
+                    sink_table: string_at(batch, "sink_table", row)?,
+                    interval_millis: millis_at(batch, "interval_millis", row)?.context(
+                        IncorrectInternalStateSnafu {
+                            state: "task without interval",
+                        },
+                    )?,
+                    watermark: millis_at(batch, "watermark", row)?,
+                    last_run_time: millis_at(batch, "last_run_time", row)?,
+                    last_error: match value_at(batch, "last_error", row)? {
+                        Value::String(s) => Some(s.as_utf8().to_string()),
+                        _ => None,
+                    },
+                    gmt_created: millis_at(batch, "gmt_created", row)?.unwrap_or_default(),
+                };
+                tasks.insert(task.full_name(), task);
+            }
+        }
+        Ok(tasks)
+    }
+
+    async fn persist(&self, task: &Task, state: &str) -> Result<()> {
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(13);
+        let mut put_string = |name: &str, value: Option<&str>| {
+            columns_values.insert(
+                name.to_string(),
+                Arc::new(StringVector::from(vec![value])) as _,
+            );
+        };
+        put_string("task_catalog", Some(&task.catalog));
+        put_string("task_schema", Some(&task.schema));
+        put_string("task_name", Some(&task.name));
+        put_string("query", Some(&task.query));
+        put_string("sink_table", Some(&task.sink_table));
+        put_string("state", Some(state));
+        put_string("last_error", task.last_error.as_deref());
+        columns_values.insert(
+            "interval_millis".to_string(),
+            Arc::new(Int64Vector::from_slice([task.interval_millis])) as _,
+        );
+        columns_values.insert(
+            "watermark".to_string(),
+            Arc::new(Int64Vector::from(vec![task.watermark])) as _,
+        );
+        // Timestamp in key part is intentionally left to 0
+        columns_values.insert(
+            "timestamp".to_string(),
+            Arc::new(TimestampMillisecondVector::from_slice([0])) as _,
+        );
+        columns_values.insert(
+            "last_run_time".to_string(),
+            Arc::new(TimestampMillisecondVector::from(vec![task.last_run_time])) as _,
+        );
+        columns_values.insert(
+            "gmt_created".to_string(),
+            Arc::new(TimestampMillisecondVector::from_slice([task.gmt_created])) as _,
+        );
+        columns_values.insert(
+            "gmt_modified".to_string(),
+            Arc::new(TimestampMillisecondVector::from_slice([
+                time_util::current_time_millis(),
+            ])) as _,
+        );
+
+        let table = self
+            .catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, TASKS_TABLE_NAME)
+            .context(CatalogSnafu)?
+            .context(TasksTableNotFoundSnafu)?;
+        let _ = table
+            .insert(InsertRequest {
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                table_name: TASKS_TABLE_NAME.to_string(),
+                columns_values,
+            })
+            .await
+            .context(InsertSnafu {
+                table_name: TASKS_TABLE_NAME,
+            })?;
+        Ok(())
+    }
+}
+
+/// Inserts the output `batch` of the `task` into the sink `table`.
+async fn insert_batch(table: &TableRef, task: &Task, batch: RecordBatch) -> Result<usize> {
+    if batch.num_rows() == 0 {
+        return Ok(0);
+    }
+    let columns_values = batch
+        .schema
+        .column_schemas()
+        .iter()
+        .zip(batch.columns())
+        .map(|(column_schema, vector)| (column_schema.name.clone(), vector.clone()))
+        .collect();
+    table
+        .insert(InsertRequest {
+            catalog_name: task.catalog.clone(),
+            schema_name: task.schema.clone(),
+            table_name: task.sink_table.clone(),
+            columns_values,
+        })
+        .await
+        .context(InsertSnafu {
+            table_name: &task.sink_table,
+        })
+}
+
+fn value_at(batch: &RecordBatch, column: &str, row: usize) -> Result<Value> {
+    let vector = batch
+        .column_by_name(column)
+        .with_context(|| IncorrectInternalStateSnafu {
+            state: format!("column {column} not found in tasks table"),
+        })?;
+    Ok(vector.get(row))
+}
+
+fn string_at(batch: &RecordBatch, column: &str, row: usize) -> Result<String> {
+    match value_at(batch, column, row)? {
+        Value::String(s) => Ok(s.as_utf8().to_string()),
+        value => IncorrectInternalStateSnafu {
+            state: format!("unexpected value {value:?} of column {column} in tasks table"),
+        }
+        .fail(),
+    }
+}
+
+fn millis_at(batch: &RecordBatch, column: &str, row: usize) -> Result<Option<i64>> {
+    match value_at(batch, column, row)? {
+        Value::Null => Ok(None),
+        Value::Int64(v) => Ok(Some(v)),
+        Value::Timestamp(ts) => Ok(Some(ts.convert_to(TimeUnit::Millisecond))),
+        value => IncorrectInternalStateSnafu {
+            state: format!("unexpected value {value:?} of column {column} in tasks table"),
+        }
+        .fail(),
+    }
+}
+
+/// Build tasks table
+fn build_tasks_schema() -> Schema {
+    let string_column = |name: &str, nullable| {
+        ColumnSchema::new(name, ConcreteDataType::string_datatype(), nullable)
+    };
+    let timestamp_column = |name: &str, nullable| {
+        ColumnSchema::new(
+            name,
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            nullable,
+        )
+    };
+    let cols = vec![
+        string_column("task_catalog", false),
+        string_column("task_schema", false),
+        string_column("task_name", false),
+        timestamp_column("timestamp", false).with_time_index(true),
+        string_column("query", false),
+        string_column("sink_table", false),
+        ColumnSchema::new("interval_millis", ConcreteDataType::int64_datatype(), false),
+        string_column("state", false),
+        ColumnSchema::new("watermark", ConcreteDataType::int64_datatype(), true),
+        timestamp_column("last_run_time", true),
+        string_column("last_error", true),
+        timestamp_column("gmt_created", false),
+        timestamp_column("gmt_modified", false),
+    ];
+
+    // Schema is always valid here
+    SchemaBuilder::try_from(cols).unwrap().build().unwrap()
+}
@@ -0,0 +1,201 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-memory [Instance] for integration tests of upper layers.
+//!
+//! The instance uses a noop WAL, a memory object store and a memory catalog, so
+//! crates like frontend and client can run tests against a real datanode without
+//! touching the disk. Enable the `test-util` feature to use it outside this crate.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use catalog::local::MemoryCatalogManager;
+use catalog::CatalogManagerRef;
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_query::Output;
+use log_store::fs::noop::NoopLogStore;
+use mito::config::EngineConfig as TableEngineConfig;
+use mito::engine::MitoEngine;
+use object_store::backend::memory;
+use object_store::ObjectStore;
+use query::QueryEngineFactory;
+use session::context::QueryContext;
+use storage::config::EngineConfig as StorageEngineConfig;
+use storage::EngineImpl;
+use store_api::storage::{RegionMetrics, RegionNumber};
+use table::table::TableIdProviderRef;
+use table::TableRef;
+
+use crate::datanode::FlushOptions;
+use crate::error::Result;
+use crate::instance::Instance;
+use crate::reload::RuntimeConfig;
+use crate::script::ScriptExecutor;
+use crate::sql::SqlHandler;
+use crate::task::TaskManager;
+
+impl Instance {
+    /// Creates a standalone instance that keeps everything in memory.
+    ///
+    /// Data written to the instance is lost once it is dropped, as the WAL
+    /// discards all entries.
+    pub async fn new_in_memory() -> Result<Self> {
+        let object_store = ObjectStore::new(
+            memory::Builder::default()
+                .build()
+                .expect("memory backend should always build"),
+        );
+        let logstore = Arc::new(NoopLogStore::default());
+
+        let flush = FlushOptions::default();
+        let storage_engine = EngineImpl::new(
+            StorageEngineConfig {
+                max_write_buffer_size: flush.max_write_buffer_size,
+                ..Default::default()
+            },
+            logstore,
+            object_store.clone(),
+        );
+        let file_store = ObjectStore::new(
+            memory::Builder::default()
+                .build()
+                .expect("memory backend should always build"),
+        );
+        let table_engine = Arc::new(MitoEngine::with_file_store(
+            TableEngineConfig::default(),
+            storage_engine.clone(),
+            object_store,
+            file_store.clone(),
+        ));
+
+        let catalog = Arc::new(MemoryCatalogManager::default());
+        let catalog_manager = catalog.clone() as CatalogManagerRef;
+        let query_engine = QueryEngineFactory::new(catalog.clone()).query_engine();
+        let script_executor =
+            ScriptExecutor::new(catalog_manager.clone(), query_engine.clone()).await?;
+        let task_manager = TaskManager::new(catalog_manager.clone(), query_engine.clone()).await?;
+
+        Ok(Self {
+            query_engine: query_engine.clone(),
+            sql_handler: SqlHandler::new(
+                table_engine,
+                catalog_manager.clone(),
+                query_engine,
+                file_store,
+            ),
+            catalog_manager,
+            script_executor,
+            task_manager,
+            table_id_provider: Some(catalog as TableIdProviderRef),
+            heartbeat_task: None,
+            logstore: None,
+            catalog_loaded: AtomicBool::new(false),
+            started: AtomicBool::new(false),
+            shutting_down: AtomicBool::new(false),
+            runtime_config: RuntimeConfig::new(flush, storage_engine),
+        })
+    }
+}
+
+/// Executes `sql` in the default schema, panics on error.
+pub async fn execute_sql(instance: &Instance, sql: &str) -> Output {
+    instance
+        .execute_sql(sql, QueryContext::arc())
+        .await
+        .unwrap_or_else(|e| panic!("failed to execute {sql}: {e}"))
+}
+
+/// Creates a table by `create_sql` then runs the `inserts` against it, returns the
+/// number of rows inserted.
+pub async fn seed_table(instance: &Instance, create_sql: &str, inserts: &[&str]) -> usize {
+    let Output::AffectedRows(_) = execute_sql(instance, create_sql).await else {
+        panic!("expect affected rows output of {create_sql}");
+    };
+
+    let mut rows = 0;
+    for insert in inserts {
+        let Output::AffectedRows(n) = execute_sql(instance, insert).await else {
+            panic!("expect affected rows output of {insert}");
+        };
+        rows += n;
+    }
+    rows
+}
+
+/// Returns the table in the default schema, panics if it doesn't exist.
+pub fn table(instance: &Instance, table_name: &str) -> TableRef {
+    instance
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, table_name)
+        .unwrap()
+        .unwrap_or_else(|| panic!("table {table_name} not found"))
+}
+
+/// Returns metrics of the regions of a table in the default schema, ordered by
+/// region number.
+pub fn region_metrics(instance: &Instance, table_name: &str) -> Vec<(RegionNumber, RegionMetrics)> {
+    let mut metrics = table(instance, table_name).region_metrics();
+    metrics.sort_unstable_by_key(|(region_number, _)| *region_number);
+    metrics
+}
+
+/// Asserts the table in the default schema has exactly the regions in `expected`,
+/// given as pairs of region number and row number.
+pub fn assert_region_rows(instance: &Instance, table_name: &str, expected: &[(RegionNumber, u64)]) {
+    let actual = region_metrics(instance, table_name)
+        .into_iter()
+        .map(|(region_number, metrics)| (region_number, metrics.num_rows))
+        .collect::<Vec<_>>();
+    assert_eq!(expected, actual, "regions of table {table_name}");
+}
+
+#[cfg(test)]
+mod tests {
+    use common_recordbatch::util;
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_in_memory_instance() {
+        let instance = Instance::new_in_memory().await.unwrap();
+        instance.start().await.unwrap();
+
+        let rows = seed_table(
+            &instance,
+            "CREATE TABLE demo(host STRING, cpu DOUBLE, ts TIMESTAMP TIME INDEX, PRIMARY KEY(host))",
+            &[
+                "INSERT INTO demo VALUES ('host1', 1.0, 1000), ('host2', 2.0, 2000)",
+                "INSERT INTO demo VALUES ('host3', 3.0, 3000)",
+            ],
+        )
+        .await;
+        assert_eq!(3, rows);
+        assert_region_rows(&instance, "demo", &[(0, 3)]);
+
+        let output = execute_sql(&instance, "SELECT count(*) FROM demo").await;
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(1, batches[0].num_rows());
+
+        // Flushing writes SSTs to the memory object store.
+        instance.shutdown().await.unwrap();
+        let metrics = region_metrics(&instance, "demo");
+        assert!(metrics[0].1.sst_bytes > 0);
+        assert_eq!(3, metrics[0].1.num_rows);
+    }
+}
@@ -19,8 +19,10 @@ use common_query::Output;
 use common_recordbatch::util;
 use datatypes::data_type::ConcreteDataType;
 use datatypes::vectors::{Int64Vector, StringVector, UInt64Vector, VectorRef};
+use servers::query_handler::ReadinessHandler;
 use session::context::QueryContext;
 
+use crate::error::Error;
 use crate::tests::test_util::{self, MockInstance};
 
 #[tokio::test(flavor = "multi_thread")]
@@ -185,6 +187,152 @@ async fn test_execute_insert() {
     assert!(matches!(output, Output::AffectedRows(2)));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_truncate() {
+    let instance = setup_test_instance("test_execute_truncate").await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 1.1, 100, 1000),
+                           ('host2', 2.2, 200, 2000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let output = execute_sql(&instance, "truncate table demo").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // The schema is kept, so the table is still writable.
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, memory, ts) values ('host3', 3.3, 300, 3000)",
+    )
+    .await;
+    let output = execute_sql(&instance, "select host, ts from demo order by ts").await;
+    let expected = "\
++-------+---------------------+
+| host  | ts                  |
++-------+---------------------+
+| host3 | 1970-01-01T00:00:03 |
++-------+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_delete_range() {
+    let instance = setup_test_instance("test_execute_delete_range").await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 1.1, 100, 1000),
+                           ('host2', 2.2, 200, 2000),
+                           ('host3', 3.3, 300, 3000),
+                           ('host4', 4.4, 400, 4000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(4)));
+
+    let output = execute_sql(&instance, "delete from demo where ts between 2000 and 3000").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = execute_sql(&instance, "select host, ts from demo order by ts").await;
+    let expected = "\
++-------+---------------------+
+| host  | ts                  |
++-------+---------------------+
+| host1 | 1970-01-01T00:00:01 |
+| host4 | 1970-01-01T00:00:04 |
++-------+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // Rows inserted after the delete are not deleted.
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, memory, ts) values ('host5', 5.5, 500, 2000)",
+    )
+    .await;
+    execute_sql(&instance, "delete from demo where 4000 <= ts").await;
+
+    let output = execute_sql(&instance, "select host, ts from demo order by ts").await;
+    let expected = "\
++-------+---------------------+
+| host  | ts                  |
++-------+---------------------+
+| host1 | 1970-01-01T00:00:01 |
+| host5 | 1970-01-01T00:00:02 |
++-------+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let query_ctx = Arc::new(QueryContext::new());
+    for sql in [
+        "delete from demo",
+        "delete from demo where host = 'host1'",
+        "delete from demo where ts > 1000 or ts < 500",
+    ] {
+        let result = instance.inner().execute_sql(sql, query_ctx.clone()).await;
+        assert!(result.is_err(), "{sql} should fail");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shutdown() {
+    let instance = setup_test_instance("test_shutdown").await;
+    let output = execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 1.1, 100, 1000),
+                           ('host2', 2.2, 200, 2000)
+                           "#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    let checks = instance.inner().readiness();
+    assert!(checks["catalog_loaded"]);
+    assert!(checks["started"]);
+    assert!(checks["not_shutting_down"]);
+
+    instance.inner().shutdown().await.unwrap();
+    // Shutdown again does nothing.
+    instance.inner().shutdown().await.unwrap();
+    // Not ready once it's shutting down.
+    assert!(!instance.inner().readiness()["not_shutting_down"]);
+
+    let query_ctx = Arc::new(QueryContext::new());
+    for sql in [
+        "insert into demo(host, cpu, memory, ts) values ('host3', 3.3, 300, 3000)",
+        "delete from demo where ts < 2000",
+    ] {
+        let err = instance
+            .inner()
+            .execute_sql(sql, query_ctx.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ShuttingDown { .. }), "{sql}: {err:?}");
+    }
+
+    // Data written before shutdown is still readable.
+    let output = execute_sql(&instance, "select host, ts from demo order by ts").await;
+    let expected = "\
++-------+---------------------+
+| host  | ts                  |
++-------+---------------------+
+| host1 | 1970-01-01T00:00:01 |
+| host2 | 1970-01-01T00:00:02 |
++-------+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_execute_insert_query_with_i64_timestamp() {
     let instance = MockInstance::new("insert_query_i64_timestamp").await;
@@ -333,6 +481,41 @@ async fn test_execute_show_databases_tables() {
         }
         _ => unreachable!(),
     }
+
+    // show tables where [expr]
+    let output = execute_sql(&instance, "show tables where Tables = 'demo'").await;
+    let expected = "\
++--------+
+| Tables |
++--------+
+| demo   |
++--------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(&instance, "show databases where schemas like 'pub%'").await;
+    let expected = "\
++---------+
+| Schemas |
++---------+
+| public  |
++---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    // show full tables
+    let output = execute_sql(&instance, "show full tables like 'de%'").await;
+    let expected = "\
++--------+------------+
+| Tables | Table_type |
++--------+------------+
+| demo   | BASE TABLE |
++--------+------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -354,13 +537,177 @@ pub async fn test_execute_create() {
     assert!(matches!(output, Output::AffectedRows(1)));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_external_table_and_copy_from() {
+    let instance = setup_test_instance("test_external_table_and_copy_from").await;
+    let import_dir = instance.file_dir().join("import");
+    std::fs::create_dir_all(&import_dir).unwrap();
+    std::fs::write(
+        import_dir.join("cpu.csv"),
+        "host,cpu,memory,ts
+host1,1.5,100,1970-01-01T00:00:01Z
+host2,2.5,,1970-01-01T00:00:02Z
+",
+    )
+    .unwrap();
+
+    let output = execute_sql(
+        &instance,
+        r#"create external table cpu_ext(
+                host string,
+                cpu double,
+                ts timestamp time index
+            ) with(location='import/cpu.csv')"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = execute_sql(&instance, "select host, cpu, ts from cpu_ext order by ts").await;
+    let expected = "\
++-------+-----+---------------------+
+| host  | cpu | ts                  |
++-------+-----+---------------------+
+| host1 | 1.5 | 1970-01-01T00:00:01 |
+| host2 | 2.5 | 1970-01-01T00:00:02 |
++-------+-----+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let query_ctx = Arc::new(QueryContext::new());
+    let result = instance
+        .inner()
+        .execute_sql(
+            "insert into cpu_ext(host, cpu, ts) values ('host3', 3.5, 3000)",
+            query_ctx,
+        )
+        .await;
+    assert!(result.is_err());
+
+    let output = execute_sql(&instance, "copy demo from 'import/cpu.csv'").await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+
+    // Only the files in the object store of files could be imported.
+    for location in ["/etc/passwd", "../import/cpu.csv"] {
+        let result = instance
+            .inner()
+            .execute_sql(
+                &format!("copy demo from '{location}' with (format = 'csv')"),
+                Arc::new(QueryContext::new()),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(Error::InvalidSql { .. })),
+            "{location}"
+        );
+    }
+
+    let output = execute_sql(
+        &instance,
+        "select host, cpu, memory, ts from demo order by ts",
+    )
+    .await;
+    let expected = "\
++-------+-----+--------+---------------------+
+| host  | cpu | memory | ts                  |
++-------+-----+--------+---------------------+
+| host1 | 1.5 | 100    | 1970-01-01T00:00:01 |
+| host2 | 2.5 |        | 1970-01-01T00:00:02 |
++-------+-----+--------+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copy_query_to() {
+    let instance = setup_test_instance("test_copy_query_to").await;
+    execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, memory, ts) values
+                ('host1', 1.5, 100, 1000),
+                ('host2', 2.5, 200, 1500),
+                ('host1', 3.5, 300, 2000)"#,
+    )
+    .await;
+
+    let output = execute_sql(
+        &instance,
+        "copy (select host, cpu, ts from demo where host = 'host1') to 'export/host1.csv'",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+    let content = std::fs::read_to_string(instance.file_dir().join("export/host1.csv")).unwrap();
+    assert_eq!(
+        "host,cpu,ts\nhost1,1.5,1970-01-01T00:00:01\nhost1,3.5,1970-01-01T00:00:02\n",
+        content
+    );
+
+    let output = execute_sql(
+        &instance,
+        "copy demo to 'export/demo/' with (time_bucket = '1s')",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(3)));
+    let export_dir = instance.file_dir().join("export/demo");
+    assert!(export_dir.join("1000/part-00000.parquet").exists());
+    assert!(export_dir.join("2000/part-00000.parquet").exists());
+
+    // Reads the exported files back by an external table.
+    execute_sql(
+        &instance,
+        r#"create external table demo_1s(
+                host string,
+                cpu double,
+                ts timestamp time index
+            ) with(location='export/demo/1000/', format='parquet')"#,
+    )
+    .await;
+    let output = execute_sql(&instance, "select host, cpu, ts from demo_1s order by ts").await;
+    let expected = "\
++-------+-----+-------------------------+
+| host  | cpu | ts                      |
++-------+-----+-------------------------+
+| host1 | 1.5 | 1970-01-01T00:00:01     |
+| host2 | 2.5 | 1970-01-01T00:00:01.500 |
++-------+-----+-------------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let query_ctx = Arc::new(QueryContext::new());
+    let result = instance
+        .inner()
+        .execute_sql(
+            "copy demo to 'export/demo.parquet' with (max_file_size = '1MB')",
+            query_ctx,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::InvalidCopyOption { .. })));
+
+    // Files are never exported out of the object store of files.
+    for location in ["/tmp/demo.csv", "../demo.csv", "export/../../demo.csv"] {
+        let result = instance
+            .inner()
+            .execute_sql(
+                &format!("copy demo to '{location}'"),
+                Arc::new(QueryContext::new()),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(Error::InvalidCopyOption { .. })),
+            "{location}"
+        );
+    }
+}
+
 async fn check_output_stream(output: Output, expected: String) {
     let recordbatches = match output {
         Output::Stream(stream) => util::collect_batches(stream).await.unwrap(),
         Output::RecordBatches(recordbatches) => recordbatches,
         _ => unreachable!(),
     };
-    let pretty_print = recordbatches.pretty_print().unwrap();
+    let pretty_print = recordbatches.pretty_print(None).unwrap();
     assert_eq!(pretty_print, expected);
 }
 
@@ -445,6 +792,220 @@ async fn test_alter_table() {
     check_output_stream(output, expected).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rename_table() {
+    let instance = setup_test_instance("test_rename_table").await;
+
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, memory, ts) values ('host1', 1.1, 100, 1000)",
+    )
+    .await;
+
+    // The new name is used by the numbers table.
+    let query_ctx = Arc::new(QueryContext::new());
+    let err = instance
+        .inner()
+        .execute_sql("alter table demo rename to numbers", query_ctx.clone())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TableExists { .. }), "{err:?}");
+
+    let output = execute_sql(&instance, "alter table demo rename to new_demo").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let result = instance
+        .inner()
+        .execute_sql("select * from demo", query_ctx)
+        .await;
+    assert!(result.is_err());
+
+    let output = execute_sql(&instance, "select host, cpu, ts from new_demo").await;
+    let expected = "\
++-------+-----+---------------------+
+| host  | cpu | ts                  |
++-------+-----+---------------------+
+| host1 | 1.1 | 1970-01-01T00:00:01 |
++-------+-----+---------------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_continuous_aggregation_task() {
+    let instance = setup_test_instance("test_continuous_aggregation_task").await;
+    // Runs the tasks by hand instead.
+    instance.inner().task_manager.stop();
+
+    execute_sql(
+        &instance,
+        r#"create table demo_1m(
+                host string,
+                bucket timestamp,
+                max_cpu double,
+                TIME INDEX (bucket),
+                PRIMARY KEY(host)
+            )"#,
+    )
+    .await;
+    execute_sql(
+        &instance,
+        r#"insert into demo(host, cpu, ts) values
+                ('host1', 1.5, 0),
+                ('host1', 2.5, 30000),
+                ('host2', 3.5, 10000),
+                ('host1', 4.5, 60000)"#,
+    )
+    .await;
+
+    let create_task = "create task cpu_1m every '1m' into demo_1m as \
+        select host, time_bucket('1m', ts) as bucket, max(cpu) as max_cpu from demo \
+        group by host, time_bucket('1m', ts)";
+    let output = execute_sql(&instance, create_task).await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let query_ctx = Arc::new(QueryContext::new());
+    let err = instance
+        .inner()
+        .execute_sql(create_task, query_ctx.clone())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TaskExists { .. }), "{err:?}");
+    let err = instance
+        .inner()
+        .execute_sql(
+            "create task cpu_sum every '1m' into demo_1m as \
+             select host, time_bucket('1m', ts) as bucket, sum(memory) as memory from demo \
+             group by host, time_bucket('1m', ts)",
+            query_ctx.clone(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidTask { .. }), "{err:?}");
+
+    // Only the complete bucket before 00:01:00 is aggregated.
+    let task_manager = &instance.inner().task_manager;
+    task_manager.run_due_tasks(90_000).await;
+    execute_sql(
+        &instance,
+        "insert into demo(host, cpu, ts) values ('host1', 5.5, 70000)",
+    )
+    .await;
+    task_manager.run_due_tasks(100_000).await;
+    task_manager.run_due_tasks(120_000).await;
+
+    let output = execute_sql(
+        &instance,
+        "select host, bucket, max_cpu from demo_1m order by host, bucket",
+    )
+    .await;
+    let expected = "\
++-------+---------------------+---------+
+| host  | bucket              | max_cpu |
++-------+---------------------+---------+
+| host1 | 1970-01-01T00:00:00 | 2.5     |
+| host1 | 1970-01-01T00:01:00 | 5.5     |
+| host2 | 1970-01-01T00:00:00 | 3.5     |
++-------+---------------------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let sql = "select task_name, state, watermark from greptime.public.tasks";
+    let output = execute_sql(&instance, sql).await;
+    let expected = "\
++-----------+---------+-----------+
+| task_name | state   | watermark |
++-----------+---------+-----------+
+| cpu_1m    | RUNNING | 120000    |
++-----------+---------+-----------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(&instance, "drop task cpu_1m").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+    let output = execute_sql(
+        &instance,
+        "select task_name, state from greptime.public.tasks",
+    )
+    .await;
+    let expected = "\
++-----------+---------+
+| task_name | state   |
++-----------+---------+
+| cpu_1m    | DROPPED |
++-----------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let err = instance
+        .inner()
+        .execute_sql("drop task cpu_1m", query_ctx.clone())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TaskNotFound { .. }), "{err:?}");
+    let output = execute_sql(&instance, "drop task if exists cpu_1m").await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_information_schema() {
+    let instance = setup_test_instance("test_information_schema").await;
+
+    let output = execute_sql(
+        &instance,
+        "select table_catalog, table_schema, table_name, table_type, engine \
+        from information_schema.tables where table_name = 'demo'",
+    )
+    .await;
+    let expected = "\
++---------------+--------------+------------+------------+--------+
+| table_catalog | table_schema | table_name | table_type | engine |
++---------------+--------------+------------+------------+--------+
+| greptime      | public       | demo       | BASE TABLE | mito   |
++---------------+--------------+------------+------------+--------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select column_name, data_type, is_nullable, semantic_type \
+        from information_schema.columns where table_name = 'demo' order by ordinal_position",
+    )
+    .await;
+    let expected = "\
++-------------+----------------------+-------------+---------------+
+| column_name | data_type            | is_nullable | semantic_type |
++-------------+----------------------+-------------+---------------+
+| host        | String               | NO          | PRIMARY KEY   |
+| cpu         | Float64              | YES         | VALUE         |
+| memory      | Float64              | YES         | VALUE         |
+| ts          | TimestampMillisecond | YES         | TIME INDEX    |
++-------------+----------------------+-------------+---------------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        "select engine, support from information_schema.engines",
+    )
+    .await;
+    let expected = "\
++--------+---------+
+| engine | support |
++--------+---------+
+| mito   | DEFAULT |
++--------+---------+\
+    "
+    .to_string();
+    check_output_stream(output, expected).await;
+}
+
 async fn test_insert_with_default_value_for_type(type_name: &str) {
     let instance = MockInstance::new("execute_create").await;
 
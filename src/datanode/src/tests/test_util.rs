@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, MIN_USER_TABLE_ID};
@@ -50,21 +51,31 @@ impl MockInstance {
     pub(crate) fn inner(&self) -> &Instance {
         &self.instance
     }
+
+    /// Returns the root directory of the object store of the files to import and export.
+    pub(crate) fn file_dir(&self) -> &Path {
+        self._guard.file_tmp_dir.path()
+    }
 }
 
 struct TestGuard {
     _wal_tmp_dir: TempDir,
     _data_tmp_dir: TempDir,
+    file_tmp_dir: TempDir,
 }
 
 fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard) {
     let wal_tmp_dir = TempDir::new(&format!("gt_wal_{name}")).unwrap();
     let data_tmp_dir = TempDir::new(&format!("gt_data_{name}")).unwrap();
+    let file_tmp_dir = TempDir::new(&format!("gt_file_{name}")).unwrap();
     let opts = DatanodeOptions {
         wal_dir: wal_tmp_dir.path().to_str().unwrap().to_string(),
         storage: ObjectStoreConfig::File {
             data_dir: data_tmp_dir.path().to_str().unwrap().to_string(),
         },
+        file_storage: ObjectStoreConfig::File {
+            data_dir: file_tmp_dir.path().to_str().unwrap().to_string(),
+        },
         mode: Mode::Standalone,
         ..Default::default()
     };
@@ -73,6 +84,7 @@ fn create_tmp_dir_and_datanode_opts(name: &str) -> (DatanodeOptions, TestGuard)
         TestGuard {
             _wal_tmp_dir: wal_tmp_dir,
             _data_tmp_dir: data_tmp_dir,
+            file_tmp_dir,
         },
     )
 }
@@ -131,7 +143,7 @@ pub async fn create_mock_sql_handler() -> SqlHandler {
     let mock_engine = Arc::new(MockMitoEngine::new(
         EngineConfig::default(),
         MockEngine::default(),
-        object_store,
+        object_store.clone(),
     ));
     let catalog_manager = Arc::new(
         catalog::local::LocalCatalogManager::try_new(mock_engine.clone())
@@ -142,5 +154,10 @@ pub async fn create_mock_sql_handler() -> SqlHandler {
     let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
     let factory = QueryEngineFactory::new(catalog_list);
 
-    SqlHandler::new(mock_engine, catalog_manager, factory.query_engine())
+    SqlHandler::new(
+        mock_engine,
+        catalog_manager,
+        factory.query_engine(),
+        object_store,
+    )
 }
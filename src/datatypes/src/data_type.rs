@@ -23,9 +23,9 @@ use crate::error::{self, Error, Result};
 use crate::type_id::LogicalTypeId;
 use crate::types::{
     BinaryType, BooleanType, DateTimeType, DateType, Float32Type, Float64Type, Int16Type,
-    Int32Type, Int64Type, Int8Type, ListType, NullType, StringType, TimestampMicrosecondType,
-    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, TimestampType,
-    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    Int32Type, Int64Type, Int8Type, JsonType, ListType, NullType, StringType,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, TimestampType, UInt16Type, UInt32Type, UInt64Type, UInt8Type, UuidType,
 };
 use crate::value::Value;
 use crate::vectors::MutableVector;
@@ -51,6 +51,7 @@ pub enum ConcreteDataType {
     // String types:
     Binary(BinaryType),
     String(StringType),
+    Uuid(UuidType),
 
     // Date types:
     Date(DateType),
@@ -59,6 +60,9 @@ pub enum ConcreteDataType {
 
     // Compound types:
     List(ListType),
+
+    // Semi-structured types:
+    Json(JsonType),
 }
 
 // TODO(yingwen): Refactor these `is_xxx()` methods, such as adding a `properties()` method
@@ -79,6 +83,7 @@ impl ConcreteDataType {
         matches!(
             self,
             ConcreteDataType::String(_)
+                | ConcreteDataType::Uuid(_)
                 | ConcreteDataType::Date(_)
                 | ConcreteDataType::DateTime(_)
                 | ConcreteDataType::Timestamp(_)
@@ -131,6 +136,14 @@ impl ConcreteDataType {
         ConcreteDataType::try_from(dt).expect("Unimplemented type")
     }
 
+    pub fn is_json(&self) -> bool {
+        matches!(self, ConcreteDataType::Json(_))
+    }
+
+    pub fn is_uuid(&self) -> bool {
+        matches!(self, ConcreteDataType::Uuid(_))
+    }
+
     pub fn is_null(&self) -> bool {
         matches!(self, ConcreteDataType::Null(NullType))
     }
@@ -164,11 +177,15 @@ impl TryFrom<&ArrowDataType> for ConcreteDataType {
             ArrowDataType::Date32 => Self::date_datatype(),
             ArrowDataType::Date64 => Self::datetime_datatype(),
             ArrowDataType::Timestamp(u, _) => ConcreteDataType::from_arrow_time_unit(u),
-            ArrowDataType::Binary | ArrowDataType::LargeBinary => Self::binary_datatype(),
+            // UUIDs are the only fixed size binary we have, other sizes are read as binary.
+            ArrowDataType::FixedSizeBinary(UuidType::BYTE_WIDTH) => Self::uuid_datatype(),
+            ArrowDataType::Binary
+            | ArrowDataType::LargeBinary
+            | ArrowDataType::FixedSizeBinary(_) => Self::binary_datatype(),
             ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 => Self::string_datatype(),
-            ArrowDataType::List(field) => Self::List(ListType::new(
-                ConcreteDataType::from_arrow_type(field.data_type()),
-            )),
+            ArrowDataType::List(field) => Self::List(ListType::new(ConcreteDataType::try_from(
+                field.data_type(),
+            )?)),
             _ => {
                 return error::UnsupportedArrowTypeSnafu {
                     arrow_type: dt.clone(),
@@ -197,7 +214,7 @@ macro_rules! impl_new_concrete_type_functions {
 
 impl_new_concrete_type_functions!(
     Null, Boolean, UInt8, UInt16, UInt32, UInt64, Int8, Int16, Int32, Int64, Float32, Float64,
-    Binary, Date, DateTime, String
+    Binary, Date, DateTime, String, Json, Uuid
 );
 
 impl ConcreteDataType {
@@ -304,6 +321,14 @@ mod tests {
             ConcreteDataType::from_arrow_type(&ArrowDataType::LargeBinary),
             ConcreteDataType::Binary(_)
         ));
+        assert!(matches!(
+            ConcreteDataType::from_arrow_type(&ArrowDataType::FixedSizeBinary(8)),
+            ConcreteDataType::Binary(_)
+        ));
+        assert!(matches!(
+            ConcreteDataType::from_arrow_type(&ArrowDataType::FixedSizeBinary(16)),
+            ConcreteDataType::Uuid(_)
+        ));
         assert!(matches!(
             ConcreteDataType::from_arrow_type(&ArrowDataType::Int8),
             ConcreteDataType::Int8(_)
@@ -434,6 +459,7 @@ mod tests {
         assert!(!ConcreteDataType::int32_datatype().is_stringifiable());
         assert!(!ConcreteDataType::float32_datatype().is_stringifiable());
         assert!(ConcreteDataType::string_datatype().is_stringifiable());
+        assert!(ConcreteDataType::uuid_datatype().is_stringifiable());
         assert!(ConcreteDataType::date_datatype().is_stringifiable());
         assert!(ConcreteDataType::datetime_datatype().is_stringifiable());
         assert!(ConcreteDataType::timestamp_second_datatype().is_stringifiable());
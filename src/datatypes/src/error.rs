@@ -85,7 +85,10 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
-    #[snafu(display("Unsupported column default constraint expression: {}", expr))]
+    #[snafu(display(
+        "Unsupported column default constraint expression: {}, supported functions: current_timestamp(), now(), uuid()",
+        expr
+    ))]
     UnsupportedDefaultExpr { expr: String, backtrace: Backtrace },
 
     #[snafu(display("Default value should not be null for non null column"))]
@@ -100,6 +103,20 @@ pub enum Error {
     #[snafu(display("Duplicated metadata for {}", key))]
     DuplicateMeta { key: String, backtrace: Backtrace },
 
+    #[snafu(display("Invalid JSON: {}, source: {}", value, source))]
+    InvalidJson {
+        value: String,
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid UUID: {}, source: {}", value, source))]
+    InvalidUuid {
+        value: String,
+        source: uuid::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to convert value into scalar value, reason: {}", reason))]
     ToScalarValue {
         reason: String,
@@ -109,8 +126,12 @@ pub enum Error {
 
 impl ErrorExt for Error {
     fn status_code(&self) -> StatusCode {
-        // Inner encoding and decoding error should not be exposed to users.
-        StatusCode::Internal
+        match self {
+            Error::UnsupportedArrowType { .. } => StatusCode::Unsupported,
+            Error::InvalidJson { .. } | Error::InvalidUuid { .. } => StatusCode::InvalidArguments,
+            // Inner encoding and decoding error should not be exposed to users.
+            _ => StatusCode::Internal,
+        }
     }
 
     fn backtrace_opt(&self) -> Option<&Backtrace> {
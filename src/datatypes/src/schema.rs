@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod column_schema;
+pub mod compat;
 mod constraint;
 mod raw;
 
@@ -20,7 +21,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use arrow::datatypes::{Field, Schema as ArrowSchema};
-pub use column_schema::TIME_INDEX_KEY;
+pub use column_schema::{TIME_INDEX_KEY, TIME_ZONE_KEY};
 use datafusion_common::DFSchemaRef;
 use snafu::{ensure, ResultExt};
 
@@ -14,7 +14,8 @@
 
 use std::collections::HashMap;
 
-use arrow::datatypes::Field;
+use arrow::datatypes::{DataType as ArrowDataType, Field};
+use common_time::TimeZone;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 
@@ -29,6 +30,10 @@ pub type Metadata = HashMap<String, String>;
 pub const TIME_INDEX_KEY: &str = "greptime:time_index";
 /// Key used to store default constraint in arrow field's metadata.
 const DEFAULT_CONSTRAINT_KEY: &str = "greptime:default_constraint";
+/// Key used to store the time zone of the timestamp column in arrow field's metadata.
+pub const TIME_ZONE_KEY: &str = "greptime:time_zone";
+/// Key used to mark the binary column stores JSON documents in arrow field's metadata.
+const JSON_TYPE_KEY: &str = "greptime:json";
 
 /// Schema of a column, used as an immutable struct.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -77,6 +82,14 @@ impl ColumnSchema {
         &self.metadata
     }
 
+    /// Returns the time zone of the timestamp column, which comes from the arrow
+    /// timestamp type of the field the column is converted from.
+    pub fn time_zone(&self) -> Option<TimeZone> {
+        self.metadata
+            .get(TIME_ZONE_KEY)
+            .and_then(|time_zone| time_zone.parse().ok())
+    }
+
     pub fn with_time_index(mut self, is_time_index: bool) -> Self {
         self.is_time_index = is_time_index;
         if is_time_index {
@@ -130,8 +143,11 @@ impl TryFrom<&Field> for ColumnSchema {
     type Error = Error;
 
     fn try_from(field: &Field) -> Result<ColumnSchema> {
-        let data_type = ConcreteDataType::try_from(field.data_type())?;
+        let mut data_type = ConcreteDataType::try_from(field.data_type())?;
         let mut metadata = field.metadata().clone();
+        if metadata.remove(JSON_TYPE_KEY).is_some() {
+            data_type = ConcreteDataType::json_datatype();
+        }
         let default_constraint = match metadata.remove(DEFAULT_CONSTRAINT_KEY) {
             Some(json) => {
                 Some(serde_json::from_str(&json).context(error::DeserializeSnafu { json })?)
@@ -139,6 +155,11 @@ impl TryFrom<&Field> for ColumnSchema {
             None => None,
         };
         let is_time_index = metadata.contains_key(TIME_INDEX_KEY);
+        // Timestamp types have no time zone, so the time zone of the arrow type is kept
+        // in the metadata.
+        if let ArrowDataType::Timestamp(_, Some(time_zone)) = field.data_type() {
+            metadata.insert(TIME_ZONE_KEY.to_string(), time_zone.clone());
+        }
 
         Ok(ColumnSchema {
             name: field.name().clone(),
@@ -170,6 +191,9 @@ impl TryFrom<&ColumnSchema> for Field {
                 }
             );
         }
+        if column_schema.data_type.is_json() {
+            metadata.insert(JSON_TYPE_KEY.to_string(), String::new());
+        }
 
         Ok(Field::new(
             &column_schema.name,
@@ -182,7 +206,7 @@ impl TryFrom<&ColumnSchema> for Field {
 
 #[cfg(test)]
 mod tests {
-    use arrow::datatypes::DataType as ArrowDataType;
+    use arrow::datatypes::TimeUnit as ArrowTimeUnit;
 
     use super::*;
     use crate::value::Value;
@@ -199,6 +223,44 @@ mod tests {
         assert_eq!(column_schema, new_column_schema);
     }
 
+    #[test]
+    fn test_column_schema_with_time_zone() {
+        let field = Field::new(
+            "ts",
+            ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, Some("+08:00".to_string())),
+            false,
+        );
+        let column_schema = ColumnSchema::try_from(&field).unwrap();
+        assert_eq!(
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            column_schema.data_type
+        );
+        assert_eq!(Some("+08:00".parse().unwrap()), column_schema.time_zone());
+
+        let new_field = Field::try_from(&column_schema).unwrap();
+        assert_eq!(
+            ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+            *new_field.data_type()
+        );
+        assert_eq!("+08:00", new_field.metadata().get(TIME_ZONE_KEY).unwrap());
+        assert_eq!(column_schema, ColumnSchema::try_from(&new_field).unwrap());
+
+        let column_schema = ColumnSchema::new("ts", ConcreteDataType::int32_datatype(), true);
+        assert!(column_schema.time_zone().is_none());
+    }
+
+    #[test]
+    fn test_column_schema_json() {
+        let column_schema = ColumnSchema::new("payload", ConcreteDataType::json_datatype(), true);
+        let field = Field::try_from(&column_schema).unwrap();
+        assert_eq!(ArrowDataType::LargeBinary, *field.data_type());
+        assert!(field.metadata().contains_key(JSON_TYPE_KEY));
+
+        let new_column_schema = ColumnSchema::try_from(&field).unwrap();
+        assert_eq!(column_schema, new_column_schema);
+        assert!(new_column_schema.metadata().is_empty());
+    }
+
     #[test]
     fn test_column_schema_with_default_constraint() {
         let column_schema = ColumnSchema::new("test", ConcreteDataType::int32_datatype(), true)
@@ -0,0 +1,463 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks compatibility between two versions of a [Schema].
+
+use std::fmt;
+
+use crate::data_type::{ConcreteDataType, DataType};
+use crate::schema::Schema;
+
+/// Kind of a change between two versions of a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChangeKind {
+    /// A column is added.
+    ColumnAdded { nullable: bool, has_default: bool },
+    /// A column is dropped.
+    ColumnDropped,
+    /// Type of the column is changed to a type that could hold all values of the old type.
+    TypeWidened {
+        from: ConcreteDataType,
+        to: ConcreteDataType,
+    },
+    /// Type of the column is changed to a type that may not hold values of the old type.
+    TypeNarrowed {
+        from: ConcreteDataType,
+        to: ConcreteDataType,
+    },
+    /// Nullability of the column is changed.
+    NullabilityChanged { from: bool, to: bool },
+    /// Default constraint of the column is changed.
+    DefaultChanged,
+    /// The time index column is dropped.
+    TimeIndexDropped,
+    /// The time index is moved to another column.
+    TimeIndexChanged { from: String, to: String },
+    /// A column is changed to a primary key column or vice versa.
+    PrimaryKeyChanged { is_key: bool },
+}
+
+/// A change of a column between two versions of a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub column_name: String,
+    pub kind: SchemaChangeKind,
+}
+
+impl SchemaChange {
+    pub fn new(column_name: impl Into<String>, kind: SchemaChangeKind) -> SchemaChange {
+        SchemaChange {
+            column_name: column_name.into(),
+            kind,
+        }
+    }
+
+    /// Returns true if data written under the old schema can't be read by the new schema.
+    pub fn is_breaking(&self) -> bool {
+        match &self.kind {
+            SchemaChangeKind::ColumnAdded {
+                nullable,
+                has_default,
+            } => !nullable && !has_default,
+            SchemaChangeKind::NullabilityChanged { from, to } => *from && !*to,
+            SchemaChangeKind::TypeNarrowed { .. }
+            | SchemaChangeKind::TimeIndexDropped
+            | SchemaChangeKind::TimeIndexChanged { .. }
+            | SchemaChangeKind::PrimaryKeyChanged { .. } => true,
+            SchemaChangeKind::ColumnDropped
+            | SchemaChangeKind::TypeWidened { .. }
+            | SchemaChangeKind::DefaultChanged => false,
+        }
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.column_name;
+        match &self.kind {
+            SchemaChangeKind::ColumnAdded {
+                nullable,
+                has_default,
+            } => write!(
+                f,
+                "column {name} added, nullable: {nullable}, has default: {has_default}"
+            ),
+            SchemaChangeKind::ColumnDropped => write!(f, "column {name} dropped"),
+            SchemaChangeKind::TypeWidened { from, to } => write!(
+                f,
+                "type of column {name} widened from {} to {}",
+                from.name(),
+                to.name()
+            ),
+            SchemaChangeKind::TypeNarrowed { from, to } => write!(
+                f,
+                "type of column {name} changed from {} to {}",
+                from.name(),
+                to.name()
+            ),
+            SchemaChangeKind::NullabilityChanged { from, to } => write!(
+                f,
+                "nullability of column {name} changed from {from} to {to}"
+            ),
+            SchemaChangeKind::DefaultChanged => write!(f, "default of column {name} changed"),
+            SchemaChangeKind::TimeIndexDropped => write!(f, "time index column {name} dropped"),
+            SchemaChangeKind::TimeIndexChanged { from, to } => {
+                write!(f, "time index changed from column {from} to {to}")
+            }
+            SchemaChangeKind::PrimaryKeyChanged { is_key } => {
+                if *is_key {
+                    write!(f, "column {name} added to primary key")
+                } else {
+                    write!(f, "column {name} removed from primary key")
+                }
+            }
+        }
+    }
+}
+
+/// Changes between two versions of a schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    pub changes: Vec<SchemaChange>,
+}
+
+impl CompatReport {
+    /// Returns true if there is no breaking change.
+    pub fn is_compatible(&self) -> bool {
+        !self.changes.iter().any(SchemaChange::is_breaking)
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    pub fn compatible_changes(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|c| !c.is_breaking())
+    }
+
+    pub fn push(&mut self, change: SchemaChange) {
+        self.changes.push(change);
+    }
+}
+
+/// Compares the `old` schema with the `new` schema and reports changes between them.
+///
+/// Columns are matched by name, so a renamed column is reported as dropped and added.
+pub fn check_schema_compat(old: &Schema, new: &Schema) -> CompatReport {
+    let mut report = CompatReport::default();
+
+    for old_column in old.column_schemas() {
+        let name = &old_column.name;
+        let Some(new_column) = new.column_schema_by_name(name) else {
+            let kind = if old_column.is_time_index() {
+                SchemaChangeKind::TimeIndexDropped
+            } else {
+                SchemaChangeKind::ColumnDropped
+            };
+            report.push(SchemaChange::new(name, kind));
+            continue;
+        };
+
+        if old_column.data_type != new_column.data_type {
+            let from = old_column.data_type.clone();
+            let to = new_column.data_type.clone();
+            let kind = if is_widening(&from, &to) {
+                SchemaChangeKind::TypeWidened { from, to }
+            } else {
+                SchemaChangeKind::TypeNarrowed { from, to }
+            };
+            report.push(SchemaChange::new(name, kind));
+        }
+        if old_column.is_nullable() != new_column.is_nullable() {
+            report.push(SchemaChange::new(
+                name,
+                SchemaChangeKind::NullabilityChanged {
+                    from: old_column.is_nullable(),
+                    to: new_column.is_nullable(),
+                },
+            ));
+        }
+        if old_column.default_constraint() != new_column.default_constraint() {
+            report.push(SchemaChange::new(name, SchemaChangeKind::DefaultChanged));
+        }
+    }
+
+    for new_column in new.column_schemas() {
+        if !old.contains_column(&new_column.name) {
+            report.push(SchemaChange::new(
+                &new_column.name,
+                SchemaChangeKind::ColumnAdded {
+                    nullable: new_column.is_nullable(),
+                    has_default: new_column.default_constraint().is_some(),
+                },
+            ));
+        }
+    }
+
+    match (old.timestamp_column(), new.timestamp_column()) {
+        (Some(old_ts), Some(new_ts)) if old_ts.name != new_ts.name => {
+            report.push(SchemaChange::new(
+                &new_ts.name,
+                SchemaChangeKind::TimeIndexChanged {
+                    from: old_ts.name.clone(),
+                    to: new_ts.name.clone(),
+                },
+            ));
+        }
+        (Some(old_ts), None) if new.contains_column(&old_ts.name) => {
+            report.push(SchemaChange::new(
+                &old_ts.name,
+                SchemaChangeKind::TimeIndexDropped,
+            ));
+        }
+        _ => (),
+    }
+
+    report
+}
+
+/// Returns true if every value of type `from` could be represented by type `to`
+/// without loss.
+///
+/// Timestamps converted to a finer unit are the only exception: they keep their
+/// precision, but overflow if they are too far from the epoch, e.g. nanosecond
+/// timestamps only cover years 1677 to 2262. Such changes are still treated as
+/// widening since this function doesn't see the values.
+fn is_widening(from: &ConcreteDataType, to: &ConcreteDataType) -> bool {
+    use ConcreteDataType as T;
+
+    match (from, to) {
+        (T::Null(_), _) => true,
+        (T::Int8(_), T::Int16(_) | T::Int32(_) | T::Int64(_) | T::Float32(_) | T::Float64(_))
+        | (T::Int16(_), T::Int32(_) | T::Int64(_) | T::Float32(_) | T::Float64(_))
+        | (T::Int32(_), T::Int64(_) | T::Float64(_))
+        | (
+            T::UInt8(_),
+            T::UInt16(_)
+            | T::UInt32(_)
+            | T::UInt64(_)
+            | T::Int16(_)
+            | T::Int32(_)
+            | T::Int64(_)
+            | T::Float32(_)
+            | T::Float64(_),
+        )
+        | (
+            T::UInt16(_),
+            T::UInt32(_) | T::UInt64(_) | T::Int32(_) | T::Int64(_) | T::Float32(_) | T::Float64(_),
+        )
+        | (T::UInt32(_), T::UInt64(_) | T::Int64(_) | T::Float64(_))
+        | (T::Float32(_), T::Float64(_))
+        // Binary values may be invalid UTF-8, so only strings could be converted to binary.
+        | (T::String(_), T::Binary(_)) => true,
+        (T::Timestamp(from), T::Timestamp(to)) => from.unit().factor() >= to.unit().factor(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ColumnDefaultConstraint, ColumnSchema, SchemaBuilder};
+    use crate::value::Value;
+
+    fn new_schema(column_schemas: Vec<ColumnSchema>) -> Schema {
+        SchemaBuilder::try_from(column_schemas)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn ts_column(name: &str) -> ColumnSchema {
+        ColumnSchema::new(
+            name,
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true)
+    }
+
+    fn kinds(report: &CompatReport) -> Vec<SchemaChangeKind> {
+        report.changes.iter().map(|c| c.kind.clone()).collect()
+    }
+
+    #[test]
+    fn test_same_schema() {
+        let schema = new_schema(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ts_column("ts"),
+        ]);
+        let report = check_schema_compat(&schema, &schema);
+        assert!(report.changes.is_empty());
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn test_add_and_drop_columns() {
+        let old = new_schema(vec![
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ts_column("ts"),
+        ]);
+        let new = new_schema(vec![
+            ts_column("ts"),
+            ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), true),
+        ]);
+        let report = check_schema_compat(&old, &new);
+        assert_eq!(
+            vec![
+                SchemaChangeKind::ColumnDropped,
+                SchemaChangeKind::ColumnAdded {
+                    nullable: true,
+                    has_default: false
+                }
+            ],
+            kinds(&report)
+        );
+        assert!(report.is_compatible());
+
+        let new = new_schema(vec![
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ts_column("ts"),
+            ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), false),
+        ]);
+        let report = check_schema_compat(&old, &new);
+        assert!(!report.is_compatible());
+        assert_eq!(1, report.breaking_changes().count());
+
+        let new = new_schema(vec![
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ts_column("ts"),
+            ColumnSchema::new("memory", ConcreteDataType::float64_datatype(), false)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value(Value::Float64(
+                    0.0.into(),
+                ))))
+                .unwrap(),
+        ]);
+        assert!(check_schema_compat(&old, &new).is_compatible());
+    }
+
+    #[test]
+    fn test_type_changes() {
+        let old = new_schema(vec![
+            ColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("b", ConcreteDataType::int64_datatype(), true),
+        ]);
+        let new = new_schema(vec![
+            ColumnSchema::new("a", ConcreteDataType::int64_datatype(), true),
+            ColumnSchema::new("b", ConcreteDataType::int32_datatype(), true),
+        ]);
+        let report = check_schema_compat(&old, &new);
+        assert_eq!(
+            vec![
+                SchemaChangeKind::TypeWidened {
+                    from: ConcreteDataType::int32_datatype(),
+                    to: ConcreteDataType::int64_datatype(),
+                },
+                SchemaChangeKind::TypeNarrowed {
+                    from: ConcreteDataType::int64_datatype(),
+                    to: ConcreteDataType::int32_datatype(),
+                },
+            ],
+            kinds(&report)
+        );
+        assert_eq!(
+            vec!["b"],
+            report
+                .breaking_changes()
+                .map(|c| c.column_name.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        assert!(is_widening(
+            &ConcreteDataType::timestamp_second_datatype(),
+            &ConcreteDataType::timestamp_millisecond_datatype()
+        ));
+        assert!(!is_widening(
+            &ConcreteDataType::timestamp_nanosecond_datatype(),
+            &ConcreteDataType::timestamp_millisecond_datatype()
+        ));
+        assert!(is_widening(
+            &ConcreteDataType::string_datatype(),
+            &ConcreteDataType::binary_datatype()
+        ));
+        assert!(!is_widening(
+            &ConcreteDataType::binary_datatype(),
+            &ConcreteDataType::string_datatype()
+        ));
+    }
+
+    #[test]
+    fn test_nullability_changes() {
+        let old = new_schema(vec![
+            ColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("b", ConcreteDataType::int32_datatype(), false),
+        ]);
+        let new = new_schema(vec![
+            ColumnSchema::new("a", ConcreteDataType::int32_datatype(), false),
+            ColumnSchema::new("b", ConcreteDataType::int32_datatype(), true),
+        ]);
+        let report = check_schema_compat(&old, &new);
+        let breaking: Vec<_> = report.breaking_changes().cloned().collect();
+        assert_eq!(
+            vec![SchemaChange::new(
+                "a",
+                SchemaChangeKind::NullabilityChanged {
+                    from: true,
+                    to: false
+                }
+            )],
+            breaking
+        );
+        assert_eq!(1, report.compatible_changes().count());
+    }
+
+    #[test]
+    fn test_time_index_changes() {
+        let old = new_schema(vec![ts_column("ts")]);
+        let new = new_schema(vec![ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )]);
+        let report = check_schema_compat(&old, &new);
+        assert_eq!(vec![SchemaChangeKind::TimeIndexDropped], kinds(&report));
+        assert!(!report.is_compatible());
+
+        let new = new_schema(vec![]);
+        let report = check_schema_compat(&old, &new);
+        assert_eq!(vec![SchemaChangeKind::TimeIndexDropped], kinds(&report));
+
+        let old = new_schema(vec![
+            ts_column("ts"),
+            ColumnSchema::new(
+                "ts2",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]);
+        let new = new_schema(vec![
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ts_column("ts2"),
+        ]);
+        let report = check_schema_compat(&old, &new);
+        assert_eq!(
+            "time index changed from column ts to ts2",
+            report.breaking_changes().next().unwrap().to_string()
+        );
+    }
+}
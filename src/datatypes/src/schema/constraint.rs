@@ -22,9 +22,13 @@ use snafu::{ensure, ResultExt};
 use crate::data_type::{ConcreteDataType, DataType};
 use crate::error::{self, Result};
 use crate::value::Value;
-use crate::vectors::{Int64Vector, TimestampMillisecondVector, VectorRef};
+use crate::vectors::{Int64Vector, StringVector, TimestampMillisecondVector, VectorRef};
 
 const CURRENT_TIMESTAMP: &str = "current_timestamp()";
+/// `CURRENT_TIMESTAMP` without parentheses, as the SQL standard writes it.
+const CURRENT_TIMESTAMP_KEYWORD: &str = "current_timestamp";
+const NOW: &str = "now()";
+const UUID: &str = "uuid()";
 
 /// Column's default constraint.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -76,23 +80,17 @@ impl ColumnDefaultConstraint {
 
         match self {
             ColumnDefaultConstraint::Function(expr) => {
-                ensure!(
-                    expr == CURRENT_TIMESTAMP,
-                    error::UnsupportedDefaultExprSnafu { expr }
-                );
-                ensure!(
-                    data_type.is_timestamp_compatible(),
-                    error::DefaultValueTypeSnafu {
-                        reason: "return value of the function must has timestamp type",
-                    }
-                );
+                DefaultFunction::parse(expr)?.validate(data_type)?;
             }
             ColumnDefaultConstraint::Value(v) => {
                 if !v.is_null() {
                     // Whether the value could be nullable has been checked before, only need
-                    // to check the type compatibility here.
+                    // to check the type compatibility here. JSON documents and UUIDs are
+                    // binary values.
+                    let is_binary_value = (data_type.is_json() || data_type.is_uuid())
+                        && matches!(v, Value::Binary(_));
                     ensure!(
-                        data_type.logical_type_id() == v.logical_type_id(),
+                        data_type.logical_type_id() == v.logical_type_id() || is_binary_value,
                         error::DefaultValueTypeSnafu {
                             reason: format!(
                                 "column has type {:?} but default value has type {:?}",
@@ -127,12 +125,7 @@ impl ColumnDefaultConstraint {
             ColumnDefaultConstraint::Function(expr) => {
                 // Functions should also ensure its return value is not null when
                 // is_nullable is true.
-                match &expr[..] {
-                    // TODO(dennis): we only supports current_timestamp right now,
-                    //   it's better to use a expression framework in future.
-                    CURRENT_TIMESTAMP => create_current_timestamp_vector(data_type, num_rows),
-                    _ => error::UnsupportedDefaultExprSnafu { expr }.fail(),
-                }
+                DefaultFunction::parse(expr)?.create_vector(data_type, num_rows)
             }
             ColumnDefaultConstraint::Value(v) => {
                 ensure!(is_nullable || !v.is_null(), error::NullDefaultSnafu);
@@ -158,6 +151,61 @@ impl ColumnDefaultConstraint {
     }
 }
 
+/// Functions that could be used as the default constraint of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DefaultFunction {
+    /// Current timestamp in milliseconds, `now()` is an alias.
+    CurrentTimestamp,
+    /// A random UUID string for each row.
+    Uuid,
+}
+
+impl DefaultFunction {
+    fn parse(expr: &str) -> Result<DefaultFunction> {
+        match expr {
+            CURRENT_TIMESTAMP | CURRENT_TIMESTAMP_KEYWORD | NOW => {
+                Ok(DefaultFunction::CurrentTimestamp)
+            }
+            UUID => Ok(DefaultFunction::Uuid),
+            _ => error::UnsupportedDefaultExprSnafu { expr }.fail(),
+        }
+    }
+
+    /// Checks whether the function could return values of `data_type`.
+    fn validate(&self, data_type: &ConcreteDataType) -> Result<()> {
+        match self {
+            DefaultFunction::CurrentTimestamp => ensure!(
+                data_type.is_timestamp_compatible(),
+                error::DefaultValueTypeSnafu {
+                    reason: "return value of the function must has timestamp type",
+                }
+            ),
+            DefaultFunction::Uuid => ensure!(
+                matches!(data_type, ConcreteDataType::String(_)),
+                error::DefaultValueTypeSnafu {
+                    reason: "return value of the function must has string type",
+                }
+            ),
+        }
+        Ok(())
+    }
+
+    fn create_vector(&self, data_type: &ConcreteDataType, num_rows: usize) -> Result<VectorRef> {
+        match self {
+            DefaultFunction::CurrentTimestamp => {
+                create_current_timestamp_vector(data_type, num_rows)
+            }
+            DefaultFunction::Uuid => {
+                self.validate(data_type)?;
+                let values = (0..num_rows)
+                    .map(|_| uuid::Uuid::new_v4().to_string())
+                    .collect::<Vec<_>>();
+                Ok(Arc::new(StringVector::from(values)))
+            }
+        }
+    }
+}
+
 fn create_current_timestamp_vector(
     data_type: &ConcreteDataType,
     num_rows: usize,
@@ -223,9 +271,27 @@ mod tests {
             .unwrap_err();
 
         let constraint = ColumnDefaultConstraint::Function("hello()".to_string());
-        constraint
+        let err = constraint
             .validate(&ConcreteDataType::timestamp_millisecond_datatype(), false)
             .unwrap_err();
+        assert!(
+            matches!(err, Error::UnsupportedDefaultExpr { .. }),
+            "{err:?}"
+        );
+
+        for expr in [NOW, CURRENT_TIMESTAMP_KEYWORD] {
+            ColumnDefaultConstraint::Function(expr.to_string())
+                .validate(&ConcreteDataType::int64_datatype(), false)
+                .unwrap();
+        }
+
+        let constraint = ColumnDefaultConstraint::Function(UUID.to_string());
+        constraint
+            .validate(&ConcreteDataType::string_datatype(), false)
+            .unwrap();
+        constraint
+            .validate(&ConcreteDataType::int64_datatype(), false)
+            .unwrap_err();
     }
 
     #[test]
@@ -291,6 +357,23 @@ mod tests {
             .unwrap_err();
     }
 
+    #[test]
+    fn test_create_default_vector_by_uuid() {
+        let constraint = ColumnDefaultConstraint::Function(UUID.to_string());
+        let v = constraint
+            .create_default_vector(&ConcreteDataType::string_datatype(), false, 3)
+            .unwrap();
+        assert_eq!(3, v.len());
+        let Value::String(first) = v.get(0) else { unreachable!() };
+        assert!(uuid::Uuid::parse_str(first.as_utf8()).is_ok());
+        assert_ne!(v.get(0), v.get(1));
+
+        let err = constraint
+            .create_default_vector(&ConcreteDataType::int32_datatype(), false, 3)
+            .unwrap_err();
+        assert!(matches!(err, Error::DefaultValueType { .. }), "{err:?}");
+    }
+
     #[test]
     fn test_create_by_func_and_invalid_type() {
         let constraint = ColumnDefaultConstraint::Function(CURRENT_TIMESTAMP.to_string());
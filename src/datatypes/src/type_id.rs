@@ -33,6 +33,11 @@ pub enum LogicalTypeId {
     // String types:
     String,
     Binary,
+    /// Universally unique identifier in 16 bytes.
+    Uuid,
+
+    // Semi-structured types:
+    Json,
 
     // Date & Time types:
     /// Date representing the elapsed time since UNIX epoch (1970-01-01)
@@ -75,6 +80,8 @@ impl LogicalTypeId {
             LogicalTypeId::Float64 => ConcreteDataType::float64_datatype(),
             LogicalTypeId::String => ConcreteDataType::string_datatype(),
             LogicalTypeId::Binary => ConcreteDataType::binary_datatype(),
+            LogicalTypeId::Json => ConcreteDataType::json_datatype(),
+            LogicalTypeId::Uuid => ConcreteDataType::uuid_datatype(),
             LogicalTypeId::Date => ConcreteDataType::date_datatype(),
             LogicalTypeId::DateTime => ConcreteDataType::datetime_datatype(),
             LogicalTypeId::TimestampSecond => ConcreteDataType::timestamp_second_datatype(),
@@ -16,10 +16,12 @@ mod binary_type;
 mod boolean_type;
 mod date_type;
 mod datetime_type;
+mod json_type;
 mod list_type;
 mod null_type;
 mod primitive_type;
 mod string_type;
+mod uuid_type;
 
 mod timestamp_type;
 
@@ -27,6 +29,7 @@ pub use binary_type::BinaryType;
 pub use boolean_type::BooleanType;
 pub use date_type::DateType;
 pub use datetime_type::DateTimeType;
+pub use json_type::JsonType;
 pub use list_type::ListType;
 pub use null_type::NullType;
 pub use primitive_type::{
@@ -38,3 +41,4 @@ pub use timestamp_type::{
     TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
     TimestampSecondType, TimestampType,
 };
+pub use uuid_type::UuidType;
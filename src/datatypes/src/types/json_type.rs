@@ -0,0 +1,109 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::DataType as ArrowDataType;
+use common_base::bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::data_type::{DataType, DataTypeRef};
+use crate::error::{self, Result};
+use crate::scalars::ScalarVectorBuilder;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{JsonVectorBuilder, MutableVector};
+
+/// Semi-structured JSON documents, stored as binary.
+///
+/// Documents are encoded by [JsonType::encode] as compact JSON text, so values could
+/// be decoded without parsing the type again and equal documents have the same bytes
+/// as long as their objects have the same key order.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonType;
+
+impl JsonType {
+    pub fn arc() -> DataTypeRef {
+        Arc::new(Self)
+    }
+
+    /// Parses the JSON text `s` and encodes the document.
+    pub fn parse_str(s: &str) -> Result<Vec<u8>> {
+        let value: serde_json::Value =
+            serde_json::from_str(s).context(error::InvalidJsonSnafu { value: s })?;
+        Self::encode(&value)
+    }
+
+    /// Encodes the JSON document `value`.
+    pub fn encode(value: &serde_json::Value) -> Result<Vec<u8>> {
+        serde_json::to_vec(value).context(error::SerializeSnafu)
+    }
+
+    /// Decodes the document encoded by [JsonType::encode].
+    pub fn decode(bytes: &[u8]) -> Result<serde_json::Value> {
+        serde_json::from_slice(bytes).context(error::DeserializeSnafu {
+            json: String::from_utf8_lossy(bytes),
+        })
+    }
+}
+
+impl DataType for JsonType {
+    fn name(&self) -> &str {
+        "Json"
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Json
+    }
+
+    fn default_value(&self) -> Value {
+        Value::Binary(Bytes::from(b"null".as_slice()))
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::LargeBinary
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(JsonVectorBuilder::with_capacity(capacity))
+    }
+
+    fn is_timestamp_compatible(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_encode_json() {
+        let bytes = JsonType::parse_str(r#"{ "a": [1, 2.5, "b"], "c": null }"#).unwrap();
+        assert_eq!(
+            br#"{"a":[1,2.5,"b"],"c":null}"#.as_slice(),
+            bytes.as_slice()
+        );
+        assert_eq!(
+            json!({"a": [1, 2.5, "b"], "c": null}),
+            JsonType::decode(&bytes).unwrap()
+        );
+
+        assert!(JsonType::parse_str("{").is_err());
+        assert!(JsonType::decode(b"{").is_err());
+    }
+}
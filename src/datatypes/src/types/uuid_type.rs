@@ -0,0 +1,108 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use arrow::datatypes::DataType as ArrowDataType;
+use common_base::bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use uuid::Uuid;
+
+use crate::data_type::{DataType, DataTypeRef};
+use crate::error::{self, Result};
+use crate::scalars::ScalarVectorBuilder;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{MutableVector, UuidVectorBuilder};
+
+/// Universally unique identifiers, stored as their 16 bytes in big-endian order.
+///
+/// UUID values are binary values, their string form is only used for parsing and
+/// displaying.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UuidType;
+
+impl UuidType {
+    /// Number of bytes of a UUID.
+    pub const BYTE_WIDTH: i32 = 16;
+
+    pub fn arc() -> DataTypeRef {
+        Arc::new(Self)
+    }
+
+    /// Parses the UUID string `s`, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`, into bytes.
+    pub fn parse_str(s: &str) -> Result<[u8; 16]> {
+        Uuid::parse_str(s)
+            .map(|uuid| uuid.into_bytes())
+            .context(error::InvalidUuidSnafu { value: s })
+    }
+
+    /// Formats the UUID `bytes` as a hyphenated string.
+    pub fn format(bytes: &[u8]) -> Result<String> {
+        Uuid::from_slice(bytes)
+            .map(|uuid| uuid.hyphenated().to_string())
+            .context(error::InvalidUuidSnafu {
+                value: format!("{bytes:?}"),
+            })
+    }
+}
+
+impl DataType for UuidType {
+    fn name(&self) -> &str {
+        "Uuid"
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Uuid
+    }
+
+    fn default_value(&self) -> Value {
+        Value::Binary(Bytes::from(Uuid::nil().as_bytes().as_slice()))
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::FixedSizeBinary(Self::BYTE_WIDTH)
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(UuidVectorBuilder::with_capacity(capacity))
+    }
+
+    fn is_timestamp_compatible(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uuid() {
+        let bytes = UuidType::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        assert_eq!(0x67, bytes[0]);
+        assert_eq!(0xc8, bytes[15]);
+        assert_eq!(
+            bytes,
+            UuidType::parse_str("67E5504410B1426F9247BB680E5FE0C8").unwrap()
+        );
+        assert_eq!(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            UuidType::format(&bytes).unwrap()
+        );
+
+        assert!(UuidType::parse_str("67e55044").is_err());
+        assert!(UuidType::format(&bytes[..8]).is_err());
+    }
+}
@@ -28,7 +28,7 @@ use snafu::ensure;
 use crate::error::{self, Result};
 use crate::prelude::*;
 use crate::type_id::LogicalTypeId;
-use crate::types::ListType;
+use crate::types::{ListType, UuidType};
 use crate::vectors::ListVector;
 
 pub type OrderedF32 = OrderedFloat<f32>;
@@ -210,8 +210,11 @@ impl Value {
         // Compare logical type, since value might not contains full type information.
         let value_type_id = self.logical_type_id();
         let output_type_id = output_type.logical_type_id();
+        // JSON documents and UUIDs are stored as binary values.
+        let is_binary_value =
+            (output_type.is_json() || output_type.is_uuid()) && matches!(self, Value::Binary(_));
         ensure!(
-            output_type_id == value_type_id || self.is_null(),
+            output_type_id == value_type_id || self.is_null() || is_binary_value,
             error::ToScalarValueSnafu {
                 reason: format!(
                     "expect value to return output_type {output_type_id:?}, actual: {value_type_id:?}",
@@ -232,6 +235,9 @@ impl Value {
             Value::Float32(v) => ScalarValue::Float32(Some(v.0)),
             Value::Float64(v) => ScalarValue::Float64(Some(v.0)),
             Value::String(v) => ScalarValue::Utf8(Some(v.as_utf8().to_string())),
+            Value::Binary(v) if output_type.is_uuid() => {
+                ScalarValue::FixedSizeBinary(UuidType::BYTE_WIDTH, Some(v.to_vec()))
+            }
             Value::Binary(v) => ScalarValue::LargeBinary(Some(v.to_vec())),
             Value::Date(v) => ScalarValue::Date32(Some(v.val())),
             Value::DateTime(v) => ScalarValue::Date64(Some(v.val())),
@@ -262,8 +268,9 @@ fn to_null_value(output_type: &ConcreteDataType) -> ScalarValue {
         ConcreteDataType::UInt64(_) => ScalarValue::UInt64(None),
         ConcreteDataType::Float32(_) => ScalarValue::Float32(None),
         ConcreteDataType::Float64(_) => ScalarValue::Float64(None),
-        ConcreteDataType::Binary(_) => ScalarValue::LargeBinary(None),
+        ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) => ScalarValue::LargeBinary(None),
         ConcreteDataType::String(_) => ScalarValue::Utf8(None),
+        ConcreteDataType::Uuid(_) => ScalarValue::FixedSizeBinary(UuidType::BYTE_WIDTH, None),
         ConcreteDataType::Date(_) => ScalarValue::Date32(None),
         ConcreteDataType::DateTime(_) => ScalarValue::Date64(None),
         ConcreteDataType::Timestamp(t) => timestamp_to_scalar_value(t.unit(), None),
@@ -1462,6 +1469,12 @@ mod tests {
                 .try_to_scalar_value(&ConcreteDataType::binary_datatype())
                 .unwrap()
         );
+        assert_eq!(
+            ScalarValue::FixedSizeBinary(16, Some(vec![1; 16])),
+            Value::Binary(Bytes::from(vec![1; 16]))
+                .try_to_scalar_value(&ConcreteDataType::uuid_datatype())
+                .unwrap()
+        );
     }
 
     #[test]
@@ -1544,6 +1557,12 @@ mod tests {
                 .try_to_scalar_value(&ConcreteDataType::binary_datatype())
                 .unwrap()
         );
+        assert_eq!(
+            ScalarValue::FixedSizeBinary(16, None),
+            Value::Null
+                .try_to_scalar_value(&ConcreteDataType::uuid_datatype())
+                .unwrap()
+        );
     }
 
     #[test]
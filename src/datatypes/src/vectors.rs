@@ -32,12 +32,14 @@ mod date;
 mod datetime;
 mod eq;
 mod helper;
+mod json;
 mod list;
 mod null;
 mod operations;
 mod primitive;
 mod string;
 mod timestamp;
+mod uuid;
 mod validity;
 
 pub use binary::{BinaryVector, BinaryVectorBuilder};
@@ -46,6 +48,7 @@ pub use constant::ConstantVector;
 pub use date::{DateVector, DateVectorBuilder};
 pub use datetime::{DateTimeVector, DateTimeVectorBuilder};
 pub use helper::Helper;
+pub use json::{JsonVector, JsonVectorBuilder};
 pub use list::{ListIter, ListVector, ListVectorBuilder};
 pub use null::{NullVector, NullVectorBuilder};
 pub use primitive::{
@@ -61,6 +64,7 @@ pub use timestamp::{
     TimestampMillisecondVectorBuilder, TimestampNanosecondVector, TimestampNanosecondVectorBuilder,
     TimestampSecondVector, TimestampSecondVectorBuilder,
 };
+pub use uuid::{UuidVector, UuidVectorBuilder};
 pub use validity::Validity;
 
 // TODO(yingwen): arrow 28.0 implements Clone for all arrays, we could upgrade to it and simplify
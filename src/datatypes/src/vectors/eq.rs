@@ -18,9 +18,9 @@ use crate::data_type::DataType;
 use crate::types::TimestampType;
 use crate::vectors::constant::ConstantVector;
 use crate::vectors::{
-    BinaryVector, BooleanVector, DateTimeVector, DateVector, ListVector, PrimitiveVector,
-    StringVector, TimestampMicrosecondVector, TimestampMillisecondVector,
-    TimestampNanosecondVector, TimestampSecondVector, Vector,
+    BinaryVector, BooleanVector, DateTimeVector, DateVector, JsonVector, ListVector,
+    PrimitiveVector, StringVector, TimestampMicrosecondVector, TimestampMillisecondVector,
+    TimestampNanosecondVector, TimestampSecondVector, UuidVector, Vector,
 };
 use crate::with_match_primitive_type_id;
 
@@ -76,6 +76,8 @@ fn equal(lhs: &dyn Vector, rhs: &dyn Vector) -> bool {
         Null(_) => true,
         Boolean(_) => is_vector_eq!(BooleanVector, lhs, rhs),
         Binary(_) => is_vector_eq!(BinaryVector, lhs, rhs),
+        Json(_) => is_vector_eq!(JsonVector, lhs, rhs),
+        Uuid(_) => is_vector_eq!(UuidVector, lhs, rhs),
         String(_) => is_vector_eq!(StringVector, lhs, rhs),
         Date(_) => is_vector_eq!(DateVector, lhs, rhs),
         DateTime(_) => is_vector_eq!(DateTimeVector, lhs, rhs),
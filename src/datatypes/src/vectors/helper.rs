@@ -17,23 +17,25 @@
 use std::any::Any;
 use std::sync::Arc;
 
-use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::array::{Array, ArrayRef, FixedSizeBinaryArray, StringArray};
 use arrow::compute;
 use arrow::compute::kernels::comparison;
 use arrow::datatypes::{DataType as ArrowDataType, TimeUnit};
 use datafusion_common::ScalarValue;
 use snafu::{OptionExt, ResultExt};
 
+use crate::arrow_array::BinaryArray;
 use crate::data_type::ConcreteDataType;
 use crate::error::{self, Result};
-use crate::scalars::{Scalar, ScalarVectorBuilder};
+use crate::scalars::{Scalar, ScalarVector, ScalarVectorBuilder};
+use crate::types::UuidType;
 use crate::value::{ListValue, ListValueRef};
 use crate::vectors::{
     BinaryVector, BooleanVector, ConstantVector, DateTimeVector, DateVector, Float32Vector,
     Float64Vector, Int16Vector, Int32Vector, Int64Vector, Int8Vector, ListVector,
     ListVectorBuilder, MutableVector, NullVector, StringVector, TimestampMicrosecondVector,
     TimestampMillisecondVector, TimestampNanosecondVector, TimestampSecondVector, UInt16Vector,
-    UInt32Vector, UInt64Vector, UInt8Vector, Vector, VectorRef,
+    UInt32Vector, UInt64Vector, UInt8Vector, UuidVector, Vector, VectorRef,
 };
 
 /// Helper functions for `Vector`.
@@ -151,6 +153,10 @@ impl Helper {
             ScalarValue::Utf8(v) | ScalarValue::LargeUtf8(v) => {
                 ConstantVector::new(Arc::new(StringVector::from(vec![v])), length)
             }
+            ScalarValue::FixedSizeBinary(UuidType::BYTE_WIDTH, v) => ConstantVector::new(
+                Arc::new(UuidVector::from_owned_iterator(std::iter::once(v))),
+                length,
+            ),
             ScalarValue::Binary(v)
             | ScalarValue::LargeBinary(v)
             | ScalarValue::FixedSizeBinary(_, v) => {
@@ -178,20 +184,18 @@ impl Helper {
             ScalarValue::Date64(v) => {
                 ConstantVector::new(Arc::new(DateTimeVector::from(vec![v])), length)
             }
+            // Timestamps with a time zone are also stored as the elapsed time since the
+            // epoch in UTC, the time zone only affects how they are displayed.
             ScalarValue::TimestampSecond(v, _) => {
-                // Timezone is unimplemented now.
                 ConstantVector::new(Arc::new(TimestampSecondVector::from(vec![v])), length)
             }
             ScalarValue::TimestampMillisecond(v, _) => {
-                // Timezone is unimplemented now.
                 ConstantVector::new(Arc::new(TimestampMillisecondVector::from(vec![v])), length)
             }
             ScalarValue::TimestampMicrosecond(v, _) => {
-                // Timezone is unimplemented now.
                 ConstantVector::new(Arc::new(TimestampMicrosecondVector::from(vec![v])), length)
             }
             ScalarValue::TimestampNanosecond(v, _) => {
-                // Timezone is unimplemented now.
                 ConstantVector::new(Arc::new(TimestampNanosecondVector::from(vec![v])), length)
             }
             ScalarValue::Decimal128(_, _, _)
@@ -216,13 +220,32 @@ impl Helper {
 
     /// Try to cast an arrow array into vector
     ///
-    /// # Panics
-    /// Panic if given arrow data type is not supported.
+    /// Supports the same arrow types as converting them into [ConcreteDataType], returns
+    /// [UnsupportedArrowType](error::Error::UnsupportedArrowType) error otherwise.
     pub fn try_into_vector(array: impl AsRef<dyn Array>) -> Result<VectorRef> {
         Ok(match array.as_ref().data_type() {
             ArrowDataType::Null => Arc::new(NullVector::try_from_arrow_array(array)?),
             ArrowDataType::Boolean => Arc::new(BooleanVector::try_from_arrow_array(array)?),
             ArrowDataType::LargeBinary => Arc::new(BinaryVector::try_from_arrow_array(array)?),
+            ArrowDataType::Binary => {
+                let array = compute::cast(array.as_ref(), &ArrowDataType::LargeBinary)
+                    .context(error::ArrowComputeSnafu)?;
+                Arc::new(BinaryVector::try_from_arrow_array(array)?)
+            }
+            ArrowDataType::FixedSizeBinary(UuidType::BYTE_WIDTH) => {
+                Arc::new(UuidVector::try_from_arrow_array(array)?)
+            }
+            // Other fixed size binaries are converted into variable length binaries.
+            ArrowDataType::FixedSizeBinary(_) => {
+                let array = array
+                    .as_ref()
+                    .as_any()
+                    .downcast_ref::<FixedSizeBinaryArray>()
+                    .with_context(|| error::ConversionSnafu {
+                        from: format!("{:?}", array.as_ref().data_type()),
+                    })?;
+                Arc::new(BinaryVector::from(array.iter().collect::<BinaryArray>()))
+            }
             ArrowDataType::Int8 => Arc::new(Int8Vector::try_from_arrow_array(array)?),
             ArrowDataType::Int16 => Arc::new(Int16Vector::try_from_arrow_array(array)?),
             ArrowDataType::Int32 => Arc::new(Int32Vector::try_from_arrow_array(array)?),
@@ -234,9 +257,16 @@ impl Helper {
             ArrowDataType::Float32 => Arc::new(Float32Vector::try_from_arrow_array(array)?),
             ArrowDataType::Float64 => Arc::new(Float64Vector::try_from_arrow_array(array)?),
             ArrowDataType::Utf8 => Arc::new(StringVector::try_from_arrow_array(array)?),
+            ArrowDataType::LargeUtf8 => {
+                let array = compute::cast(array.as_ref(), &ArrowDataType::Utf8)
+                    .context(error::ArrowComputeSnafu)?;
+                Arc::new(StringVector::try_from_arrow_array(array)?)
+            }
             ArrowDataType::Date32 => Arc::new(DateVector::try_from_arrow_array(array)?),
             ArrowDataType::Date64 => Arc::new(DateTimeVector::try_from_arrow_array(array)?),
-            ArrowDataType::List(_) => Arc::new(ListVector::try_from_arrow_array(array)?),
+            ArrowDataType::List(field) if is_supported_list_item(field.data_type()) => {
+                Arc::new(ListVector::try_from_arrow_array(array)?)
+            }
             ArrowDataType::Timestamp(unit, _) => match unit {
                 TimeUnit::Second => Arc::new(TimestampSecondVector::try_from_arrow_array(array)?),
                 TimeUnit::Millisecond => {
@@ -254,9 +284,6 @@ impl Helper {
             | ArrowDataType::Time64(_)
             | ArrowDataType::Duration(_)
             | ArrowDataType::Interval(_)
-            | ArrowDataType::Binary
-            | ArrowDataType::FixedSizeBinary(_)
-            | ArrowDataType::LargeUtf8
             | ArrowDataType::LargeList(_)
             | ArrowDataType::FixedSizeList(_, _)
             | ArrowDataType::Struct(_)
@@ -264,12 +291,30 @@ impl Helper {
             | ArrowDataType::Dictionary(_, _)
             | ArrowDataType::Decimal128(_, _)
             | ArrowDataType::Decimal256(_, _)
-            | ArrowDataType::Map(_, _) => {
-                unimplemented!("Arrow array datatype: {:?}", array.as_ref().data_type())
+            | ArrowDataType::Map(_, _)
+            | ArrowDataType::List(_) => {
+                return error::UnsupportedArrowTypeSnafu {
+                    arrow_type: array.as_ref().data_type().clone(),
+                }
+                .fail()
             }
         })
     }
 
+    /// Returns true if arrays of `data_type` can be converted into vectors by
+    /// [Helper::try_into_vector].
+    ///
+    /// Callers accepting arrow data from outside (e.g. Arrow Flight) could use this
+    /// to reject unsupported inputs before decoding them.
+    pub fn is_supported(data_type: &ArrowDataType) -> bool {
+        match data_type {
+            // Only top level arrays of these types are cast into the arrow type of
+            // their [ConcreteDataType].
+            ArrowDataType::Binary | ArrowDataType::LargeUtf8 => true,
+            _ => is_supported_list_item(data_type),
+        }
+    }
+
     /// Try to cast slice of `arrays` to vectors.
     pub fn try_into_vectors(arrays: &[ArrayRef]) -> Result<Vec<VectorRef>> {
         arrays.iter().map(Self::try_into_vector).collect()
@@ -286,13 +331,41 @@ impl Helper {
     }
 }
 
+/// Returns true if lists of items of `data_type` can be converted into list vectors,
+/// the items must be of the arrow type of their [ConcreteDataType].
+fn is_supported_list_item(data_type: &ArrowDataType) -> bool {
+    match data_type {
+        ArrowDataType::Null
+        | ArrowDataType::Boolean
+        | ArrowDataType::LargeBinary
+        | ArrowDataType::FixedSizeBinary(_)
+        | ArrowDataType::Int8
+        | ArrowDataType::Int16
+        | ArrowDataType::Int32
+        | ArrowDataType::Int64
+        | ArrowDataType::UInt8
+        | ArrowDataType::UInt16
+        | ArrowDataType::UInt32
+        | ArrowDataType::UInt64
+        | ArrowDataType::Float32
+        | ArrowDataType::Float64
+        | ArrowDataType::Utf8
+        | ArrowDataType::Date32
+        | ArrowDataType::Date64
+        | ArrowDataType::Timestamp(_, _) => true,
+        ArrowDataType::List(field) => is_supported_list_item(field.data_type()),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::array::{
         ArrayRef, BooleanArray, Date32Array, Date64Array, Float32Array, Float64Array, Int16Array,
-        Int32Array, Int64Array, Int8Array, LargeBinaryArray, ListArray, NullArray,
-        TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
-        TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        Int32Array, Int64Array, Int8Array, LargeBinaryArray, LargeStringArray, LargeStringBuilder,
+        ListArray, ListBuilder, NullArray, Time32SecondArray, TimestampMicrosecondArray,
+        TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray, UInt16Array,
+        UInt32Array, UInt64Array, UInt8Array,
     };
     use arrow::datatypes::{Field, Int32Type};
     use common_time::{Date, DateTime};
@@ -317,6 +390,64 @@ mod tests {
         assert_eq!(Value::Int32(3), vectors[2].get(0));
     }
 
+    #[test]
+    fn test_try_into_unsupported_vector() {
+        // List of unsupported items.
+        let mut builder = ListBuilder::new(LargeStringBuilder::new());
+        builder.values().append_value("a");
+        builder.append(true);
+
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Time32SecondArray::from(vec![1])),
+            Arc::new(builder.finish()),
+        ];
+        for array in arrays {
+            assert!(!Helper::is_supported(array.data_type()));
+            let err = Helper::try_into_vector(array).unwrap_err();
+            assert!(
+                matches!(err, error::Error::UnsupportedArrowType { .. }),
+                "unexpected error: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_supported() {
+        let list_of =
+            |data_type| ArrowDataType::List(Box::new(Field::new("item", data_type, true)));
+        for data_type in [
+            ArrowDataType::Binary,
+            ArrowDataType::LargeUtf8,
+            ArrowDataType::FixedSizeBinary(3),
+            ArrowDataType::Timestamp(TimeUnit::Nanosecond, None),
+            list_of(ArrowDataType::Int32),
+            list_of(list_of(ArrowDataType::Utf8)),
+        ] {
+            assert!(Helper::is_supported(&data_type), "{data_type:?}");
+        }
+        for data_type in [
+            ArrowDataType::Time32(TimeUnit::Second),
+            ArrowDataType::LargeList(Box::new(Field::new("item", ArrowDataType::Int32, true))),
+            list_of(ArrowDataType::Binary),
+            list_of(ArrowDataType::LargeUtf8),
+        ] {
+            assert!(!Helper::is_supported(&data_type), "{data_type:?}");
+        }
+    }
+
+    #[test]
+    fn test_try_into_large_string_and_binary_vector() {
+        let array: ArrayRef = Arc::new(LargeStringArray::from(vec!["a"]));
+        let vector = Helper::try_into_vector(array).unwrap();
+        assert_eq!(ConcreteDataType::string_datatype(), vector.data_type());
+        assert_eq!(Value::from("a"), vector.get(0));
+
+        let array: ArrayRef = Arc::new(arrow::array::BinaryArray::from(vec![b"a".as_ref()]));
+        let vector = Helper::try_into_vector(array).unwrap();
+        assert_eq!(ConcreteDataType::binary_datatype(), vector.data_type());
+        assert_eq!(Value::from(b"a".as_ref()), vector.get(0));
+    }
+
     #[test]
     fn test_try_into_date_vector() {
         let vector = DateVector::from(vec![Some(1), Some(2), None]);
@@ -430,5 +561,29 @@ mod tests {
         check_try_into_vector(TimestampMillisecondArray::from(vec![1, 2, 3]));
         check_try_into_vector(TimestampMicrosecondArray::from(vec![1, 2, 3]));
         check_try_into_vector(TimestampNanosecondArray::from(vec![1, 2, 3]));
+        check_try_into_vector(
+            FixedSizeBinaryArray::try_from_sparse_iter(
+                vec![Some([1u8; 16]), None, Some([2u8; 16])].into_iter(),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_try_fixed_size_binary_into_vector() {
+        let array: ArrayRef = Arc::new(
+            FixedSizeBinaryArray::try_from_sparse_iter(
+                vec![Some(b"abc"), None, Some(b"def")].into_iter(),
+            )
+            .unwrap(),
+        );
+        let vector = Helper::try_into_vector(array).unwrap();
+        assert_eq!(ConcreteDataType::binary_datatype(), vector.data_type());
+        let expect: VectorRef = Arc::new(BinaryVector::from(vec![
+            Some(b"abc".to_vec()),
+            None,
+            Some(b"def".to_vec()),
+        ]));
+        assert_eq!(expect, vector);
     }
 }
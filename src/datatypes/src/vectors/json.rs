@@ -0,0 +1,284 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayBuilder, ArrayData, ArrayIter, ArrayRef};
+
+use crate::arrow_array::{BinaryArray, MutableBinaryArray};
+use crate::data_type::ConcreteDataType;
+use crate::error::Result;
+use crate::scalars::{ScalarVector, ScalarVectorBuilder};
+use crate::serialize::Serializable;
+use crate::types::JsonType;
+use crate::value::{Value, ValueRef};
+use crate::vectors::{self, BinaryVector, MutableVector, Validity, Vector, VectorRef};
+
+/// Vector of JSON documents encoded by [JsonType::encode].
+#[derive(Debug, PartialEq)]
+pub struct JsonVector {
+    array: BinaryArray,
+}
+
+impl JsonVector {
+    pub(crate) fn as_arrow(&self) -> &dyn Array {
+        &self.array
+    }
+
+    fn to_array_data(&self) -> ArrayData {
+        self.array.data().clone()
+    }
+
+    fn from_array_data(data: ArrayData) -> JsonVector {
+        JsonVector {
+            array: BinaryArray::from(data),
+        }
+    }
+}
+
+impl From<BinaryArray> for JsonVector {
+    fn from(array: BinaryArray) -> Self {
+        Self { array }
+    }
+}
+
+impl From<BinaryVector> for JsonVector {
+    fn from(vector: BinaryVector) -> Self {
+        Self::from_array_data(vector.as_arrow().data().clone())
+    }
+}
+
+impl Vector for JsonVector {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::json_datatype()
+    }
+
+    fn vector_type_name(&self) -> String {
+        "JsonVector".to_string()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    fn to_arrow_array(&self) -> ArrayRef {
+        let data = self.to_array_data();
+        Arc::new(BinaryArray::from(data))
+    }
+
+    fn to_boxed_arrow_array(&self) -> Box<dyn Array> {
+        let data = self.to_array_data();
+        Box::new(BinaryArray::from(data))
+    }
+
+    fn validity(&self) -> Validity {
+        vectors::impl_validity_for_vector!(self.array)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.array.get_buffer_memory_size()
+    }
+
+    fn null_count(&self) -> usize {
+        self.array.null_count()
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        self.array.is_null(row)
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> VectorRef {
+        let data = self.array.data().slice(offset, length);
+        Arc::new(Self::from_array_data(data))
+    }
+
+    fn get(&self, index: usize) -> Value {
+        vectors::impl_get_for_vector!(self.array, index)
+    }
+
+    fn get_ref(&self, index: usize) -> ValueRef {
+        vectors::impl_get_ref_for_vector!(self.array, index)
+    }
+}
+
+impl ScalarVector for JsonVector {
+    type OwnedItem = Vec<u8>;
+    type RefItem<'a> = &'a [u8];
+    type Iter<'a> = ArrayIter<&'a BinaryArray>;
+    type Builder = JsonVectorBuilder;
+
+    fn get_data(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        if self.array.is_valid(idx) {
+            Some(self.array.value(idx))
+        } else {
+            None
+        }
+    }
+
+    fn iter_data(&self) -> Self::Iter<'_> {
+        self.array.iter()
+    }
+}
+
+pub struct JsonVectorBuilder {
+    mutable_array: MutableBinaryArray,
+}
+
+impl MutableVector for JsonVectorBuilder {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::json_datatype()
+    }
+
+    fn len(&self) -> usize {
+        self.mutable_array.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn to_vector(&mut self) -> VectorRef {
+        Arc::new(self.finish())
+    }
+
+    /// Pushes an encoded document, or a JSON text which would be parsed and encoded.
+    fn push_value_ref(&mut self, value: ValueRef) -> Result<()> {
+        if let ValueRef::String(s) = value {
+            let bytes = JsonType::parse_str(s)?;
+            self.mutable_array.append_value(bytes);
+            return Ok(());
+        }
+
+        match value.as_binary()? {
+            Some(v) => self.mutable_array.append_value(v),
+            None => self.mutable_array.append_null(),
+        }
+        Ok(())
+    }
+
+    fn extend_slice_of(&mut self, vector: &dyn Vector, offset: usize, length: usize) -> Result<()> {
+        // Documents read from arrow arrays are in binary vectors.
+        if vector.as_any().is::<BinaryVector>() {
+            return vectors::impl_extend_for_builder!(self, vector, BinaryVector, offset, length);
+        }
+        vectors::impl_extend_for_builder!(self, vector, JsonVector, offset, length)
+    }
+}
+
+impl ScalarVectorBuilder for JsonVectorBuilder {
+    type VectorType = JsonVector;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            mutable_array: MutableBinaryArray::with_capacity(capacity, 0),
+        }
+    }
+
+    fn push(&mut self, value: Option<<Self::VectorType as ScalarVector>::RefItem<'_>>) {
+        match value {
+            Some(v) => self.mutable_array.append_value(v),
+            None => self.mutable_array.append_null(),
+        }
+    }
+
+    fn finish(&mut self) -> Self::VectorType {
+        JsonVector {
+            array: self.mutable_array.finish(),
+        }
+    }
+}
+
+impl Serializable for JsonVector {
+    fn serialize_to_json(&self) -> Result<Vec<serde_json::Value>> {
+        self.iter_data()
+            .map(|v| match v {
+                None => Ok(serde_json::Value::Null),
+                Some(bytes) => JsonType::decode(bytes),
+            })
+            .collect()
+    }
+}
+
+vectors::impl_try_from_arrow_array_for_vector!(BinaryArray, JsonVector);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_type::DataType;
+
+    fn new_json_vector(docs: &[Option<&str>]) -> JsonVector {
+        let mut builder = JsonVectorBuilder::with_capacity(docs.len());
+        for doc in docs {
+            match doc {
+                Some(doc) => builder.push_value_ref(ValueRef::String(doc)).unwrap(),
+                None => builder.push(None),
+            }
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_json_vector_misc() {
+        let v = new_json_vector(&[Some(r#"{"a": 1}"#), None, Some("[1, 2]")]);
+
+        assert_eq!(3, v.len());
+        assert_eq!("JsonVector", v.vector_type_name());
+        assert_eq!(ConcreteDataType::json_datatype(), v.data_type());
+        assert_eq!(1, v.null_count());
+        assert_eq!(Some(br#"{"a":1}"#.as_slice()), v.get_data(0));
+        assert_eq!(Value::Null, v.get(1));
+        assert_eq!(ValueRef::Binary(b"[1,2]"), v.get_ref(2));
+
+        let json = v.serialize_to_json().unwrap();
+        assert_eq!(
+            r#"[{"a":1},null,[1,2]]"#,
+            serde_json::to_string(&json).unwrap()
+        );
+
+        let v2 = JsonVector::try_from_arrow_array(v.to_arrow_array()).unwrap();
+        assert_eq!(v, v2);
+    }
+
+    #[test]
+    fn test_json_vector_builder() {
+        let mut builder = JsonType::default().create_mutable_vector(3);
+        builder.push_value_ref(ValueRef::String("true")).unwrap();
+        assert!(builder.push_value_ref(ValueRef::String("{")).is_err());
+        assert!(builder.push_value_ref(ValueRef::Int32(123)).is_err());
+        builder.push_value_ref(ValueRef::Binary(b"\"a\"")).unwrap();
+
+        let binary = BinaryVector::from(vec![Some(b"1".to_vec()), Some(b"2".to_vec())]);
+        builder.extend_slice_of(&binary, 1, 1).unwrap();
+        let json = new_json_vector(&[Some("3"), Some("4")]);
+        builder.extend_slice_of(&json, 0, 1).unwrap();
+
+        let vector = builder.to_vector();
+        let expect: VectorRef = Arc::new(new_json_vector(&[
+            Some("true"),
+            Some("\"a\""),
+            Some("2"),
+            Some("3"),
+        ]));
+        assert_eq!(expect, vector);
+    }
+}
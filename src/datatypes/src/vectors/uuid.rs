@@ -0,0 +1,279 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayBuilder, ArrayData, ArrayIter, ArrayRef, FixedSizeBinaryArray,
+    FixedSizeBinaryBuilder,
+};
+use snafu::ResultExt;
+
+use crate::data_type::ConcreteDataType;
+use crate::error::{self, Result};
+use crate::scalars::{ScalarVector, ScalarVectorBuilder};
+use crate::serialize::Serializable;
+use crate::types::UuidType;
+use crate::value::{Value, ValueRef};
+use crate::vectors::{self, MutableVector, Validity, Vector, VectorRef};
+
+/// Vector of UUIDs in their 16 bytes.
+#[derive(Debug, PartialEq)]
+pub struct UuidVector {
+    array: FixedSizeBinaryArray,
+}
+
+impl UuidVector {
+    pub(crate) fn as_arrow(&self) -> &dyn Array {
+        &self.array
+    }
+
+    fn to_array_data(&self) -> ArrayData {
+        self.array.data().clone()
+    }
+
+    fn from_array_data(data: ArrayData) -> UuidVector {
+        UuidVector {
+            array: FixedSizeBinaryArray::from(data),
+        }
+    }
+}
+
+impl From<FixedSizeBinaryArray> for UuidVector {
+    fn from(array: FixedSizeBinaryArray) -> Self {
+        Self { array }
+    }
+}
+
+impl Vector for UuidVector {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::uuid_datatype()
+    }
+
+    fn vector_type_name(&self) -> String {
+        "UuidVector".to_string()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    fn to_arrow_array(&self) -> ArrayRef {
+        let data = self.to_array_data();
+        Arc::new(FixedSizeBinaryArray::from(data))
+    }
+
+    fn to_boxed_arrow_array(&self) -> Box<dyn Array> {
+        let data = self.to_array_data();
+        Box::new(FixedSizeBinaryArray::from(data))
+    }
+
+    fn validity(&self) -> Validity {
+        vectors::impl_validity_for_vector!(self.array)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.array.get_buffer_memory_size()
+    }
+
+    fn null_count(&self) -> usize {
+        self.array.null_count()
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        self.array.is_null(row)
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> VectorRef {
+        let data = self.array.data().slice(offset, length);
+        Arc::new(Self::from_array_data(data))
+    }
+
+    fn get(&self, index: usize) -> Value {
+        vectors::impl_get_for_vector!(self.array, index)
+    }
+
+    fn get_ref(&self, index: usize) -> ValueRef {
+        vectors::impl_get_ref_for_vector!(self.array, index)
+    }
+}
+
+impl ScalarVector for UuidVector {
+    type OwnedItem = Vec<u8>;
+    type RefItem<'a> = &'a [u8];
+    type Iter<'a> = ArrayIter<&'a FixedSizeBinaryArray>;
+    type Builder = UuidVectorBuilder;
+
+    fn get_data(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        if self.array.is_valid(idx) {
+            Some(self.array.value(idx))
+        } else {
+            None
+        }
+    }
+
+    fn iter_data(&self) -> Self::Iter<'_> {
+        self.array.iter()
+    }
+}
+
+pub struct UuidVectorBuilder {
+    mutable_array: FixedSizeBinaryBuilder,
+}
+
+impl UuidVectorBuilder {
+    fn try_push(&mut self, value: Option<&[u8]>) -> Result<()> {
+        match value {
+            Some(v) => self
+                .mutable_array
+                .append_value(v)
+                .context(error::ArrowComputeSnafu),
+            None => {
+                self.mutable_array.append_null();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl MutableVector for UuidVectorBuilder {
+    fn data_type(&self) -> ConcreteDataType {
+        ConcreteDataType::uuid_datatype()
+    }
+
+    fn len(&self) -> usize {
+        self.mutable_array.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn to_vector(&mut self) -> VectorRef {
+        Arc::new(self.finish())
+    }
+
+    /// Pushes the bytes of a UUID, or a UUID string which would be parsed.
+    fn push_value_ref(&mut self, value: ValueRef) -> Result<()> {
+        if let ValueRef::String(s) = value {
+            let bytes = UuidType::parse_str(s)?;
+            return self.try_push(Some(&bytes));
+        }
+
+        self.try_push(value.as_binary()?)
+    }
+
+    fn extend_slice_of(&mut self, vector: &dyn Vector, offset: usize, length: usize) -> Result<()> {
+        vectors::impl_extend_for_builder!(self, vector, UuidVector, offset, length)
+    }
+}
+
+impl ScalarVectorBuilder for UuidVectorBuilder {
+    type VectorType = UuidVector;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            mutable_array: FixedSizeBinaryBuilder::with_capacity(capacity, UuidType::BYTE_WIDTH),
+        }
+    }
+
+    /// # Panics
+    /// Panics if the length of `value` is not 16.
+    fn push(&mut self, value: Option<<Self::VectorType as ScalarVector>::RefItem<'_>>) {
+        self.try_push(value).unwrap()
+    }
+
+    fn finish(&mut self) -> Self::VectorType {
+        UuidVector {
+            array: self.mutable_array.finish(),
+        }
+    }
+}
+
+impl Serializable for UuidVector {
+    fn serialize_to_json(&self) -> Result<Vec<serde_json::Value>> {
+        self.iter_data()
+            .map(|v| match v {
+                None => Ok(serde_json::Value::Null),
+                Some(bytes) => UuidType::format(bytes).map(serde_json::Value::String),
+            })
+            .collect()
+    }
+}
+
+vectors::impl_try_from_arrow_array_for_vector!(FixedSizeBinaryArray, UuidVector);
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::DataType as ArrowDataType;
+
+    use super::*;
+    use crate::data_type::DataType;
+
+    const UUID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    #[test]
+    fn test_uuid_vector_misc() {
+        let uuid = UuidType::parse_str(UUID).unwrap();
+        let v = UuidVector::from_owned_iterator(vec![Some(uuid.to_vec()), None].into_iter());
+
+        assert_eq!(2, v.len());
+        assert_eq!("UuidVector", v.vector_type_name());
+        assert_eq!(ConcreteDataType::uuid_datatype(), v.data_type());
+        assert_eq!(1, v.null_count());
+        assert_eq!(Some(uuid.as_slice()), v.get_data(0));
+        assert_eq!(Value::Binary(uuid.as_slice().into()), v.get(0));
+        assert_eq!(ValueRef::Null, v.get_ref(1));
+        assert_eq!(
+            ArrowDataType::FixedSizeBinary(16),
+            *v.to_arrow_array().data_type()
+        );
+
+        let json = v.serialize_to_json().unwrap();
+        assert_eq!(
+            format!(r#"["{UUID}",null]"#),
+            serde_json::to_string(&json).unwrap()
+        );
+
+        let v2 = UuidVector::try_from_arrow_array(v.to_arrow_array()).unwrap();
+        assert_eq!(v, v2);
+    }
+
+    #[test]
+    fn test_uuid_vector_builder() {
+        let uuid = UuidType::parse_str(UUID).unwrap();
+        let mut builder = UuidType::default().create_mutable_vector(3);
+        builder.push_value_ref(ValueRef::String(UUID)).unwrap();
+        builder.push_value_ref(ValueRef::Null).unwrap();
+        assert!(builder.push_value_ref(ValueRef::String("abc")).is_err());
+        assert!(builder.push_value_ref(ValueRef::Binary(b"abc")).is_err());
+        builder.push_value_ref(ValueRef::Binary(&uuid)).unwrap();
+
+        let other = UuidVector::from_slice(&[uuid.as_slice()]);
+        builder.extend_slice_of(&other, 0, 1).unwrap();
+
+        let vector = builder.to_vector();
+        assert_eq!(4, vector.len());
+        assert_eq!(1, vector.null_count());
+        assert_eq!(vector.get(0), vector.get(3));
+    }
+}
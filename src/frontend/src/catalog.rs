@@ -17,15 +17,16 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use catalog::error::{self as catalog_err, InvalidCatalogValueSnafu};
+use catalog::grant::{GrantRequest, Privileges};
 use catalog::helper::{
-    build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, SchemaKey,
-    TableGlobalKey, TableGlobalValue,
+    build_catalog_prefix, build_schema_prefix, build_table_global_prefix, CatalogKey, GrantKey,
+    GrantValue, SchemaKey, TableGlobalKey, TableGlobalValue,
 };
 use catalog::remote::{Kv, KvBackendRef};
 use catalog::{
     CatalogList, CatalogManager, CatalogProvider, CatalogProviderRef, DeregisterTableRequest,
-    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, SchemaProvider,
-    SchemaProviderRef,
+    RegisterSchemaRequest, RegisterSystemTableRequest, RegisterTableRequest, RenameTableRequest,
+    SchemaProvider, SchemaProviderRef,
 };
 use futures::StreamExt;
 use meta_client::rpc::TableName;
@@ -90,6 +91,10 @@ impl CatalogManager for FrontendCatalogManager {
         unimplemented!()
     }
 
+    async fn rename_table(&self, _request: RenameTableRequest) -> catalog::error::Result<bool> {
+        unimplemented!()
+    }
+
     async fn register_schema(
         &self,
         _request: RegisterSchemaRequest,
@@ -124,6 +129,59 @@ impl CatalogManager for FrontendCatalogManager {
     ) -> catalog::error::Result<Option<TableRef>> {
         unimplemented!()
     }
+
+    async fn privileges(
+        &self,
+        catalog: &str,
+        schema: &str,
+        user: &str,
+    ) -> catalog::error::Result<Privileges> {
+        let key = grant_key(catalog, schema, user);
+        match self.backend.get(key.as_bytes()).await? {
+            Some(Kv(_, value)) => Ok(GrantValue::from_bytes(value)
+                .context(InvalidCatalogValueSnafu)?
+                .privileges),
+            None => Ok(Privileges::default()),
+        }
+    }
+
+    async fn grant(&self, request: GrantRequest) -> catalog::error::Result<Privileges> {
+        ensure!(
+            self.schema(&request.catalog, &request.schema)?.is_some(),
+            catalog_err::SchemaNotFoundSnafu {
+                schema_info: format!("{}.{}", request.catalog, request.schema),
+            }
+        );
+
+        let key = grant_key(&request.catalog, &request.schema, &request.user);
+        // Retries if the privileges are changed by another frontend concurrently.
+        loop {
+            let existing = self.backend.get(key.as_bytes()).await?.map(|kv| kv.1);
+            let mut value = match &existing {
+                Some(bytes) => GrantValue::from_bytes(bytes).context(InvalidCatalogValueSnafu)?,
+                None => GrantValue::default(),
+            };
+            request.apply(&mut value.privileges);
+            let bytes = value.as_bytes().context(InvalidCatalogValueSnafu)?;
+            if self
+                .backend
+                .compare_and_set(key.as_bytes(), &existing.unwrap_or_default(), &bytes)
+                .await?
+                .is_ok()
+            {
+                return Ok(value.privileges);
+            }
+        }
+    }
+}
+
+fn grant_key(catalog: &str, schema: &str, user: &str) -> String {
+    GrantKey {
+        catalog_name: catalog.to_string(),
+        schema_name: schema.to_string(),
+        user: user.to_string(),
+    }
+    .to_string()
 }
 
 impl CatalogList for FrontendCatalogManager {
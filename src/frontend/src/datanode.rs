@@ -26,8 +26,12 @@ pub(crate) struct DatanodeClients {
 
 impl DatanodeClients {
     pub(crate) fn new() -> Self {
+        Self::with_channel_manager(ChannelManager::new())
+    }
+
+    pub(crate) fn with_channel_manager(channel_manager: ChannelManager) -> Self {
         Self {
-            channel_manager: ChannelManager::new(),
+            channel_manager,
             clients: CacheBuilder::new(1024)
                 .time_to_live(Duration::from_secs(30 * 60))
                 .time_to_idle(Duration::from_secs(5 * 60))
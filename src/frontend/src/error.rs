@@ -18,7 +18,7 @@ use common_error::prelude::*;
 use common_query::logical_plan::Expr;
 use datafusion_common::ScalarValue;
 use datatypes::prelude::Value;
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, RegionNumber};
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
@@ -48,6 +48,12 @@ pub enum Error {
         source: servers::error::Error,
     },
 
+    #[snafu(display("Failed to shutdown server, source: {}", source))]
+    ShutdownServer {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
     #[snafu(display("Failed to parse address {}, source: {}", addr, source))]
     ParseAddr {
         addr: String,
@@ -66,17 +72,6 @@ pub enum Error {
         source: api::error::Error,
     },
 
-    #[snafu(display(
-        "Invalid column proto definition, column: {}, source: {}",
-        column,
-        source
-    ))]
-    InvalidColumnDef {
-        column: String,
-        #[snafu(backtrace)]
-        source: api::error::Error,
-    },
-
     #[snafu(display(
         "Failed to convert column default constraint, column: {}, source: {}",
         column_name,
@@ -184,6 +179,20 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Failed to insert into regions {:?} of {} regions, {} rows are inserted into other regions, source: {}",
+        failed_regions,
+        total_regions,
+        affected_rows,
+        source
+    ))]
+    PartialInsert {
+        failed_regions: Vec<RegionNumber>,
+        total_regions: usize,
+        affected_rows: usize,
+        source: Box<Error>,
+    },
+
     #[snafu(display("Failed to join task, source: {}", source))]
     JoinTask {
         source: common_runtime::JoinError,
@@ -214,12 +223,6 @@ pub enum Error {
         source: meta_client::error::Error,
     },
 
-    #[snafu(display("Failed to get cache, error: {}", err_msg))]
-    GetCache {
-        err_msg: String,
-        backtrace: Backtrace,
-    },
-
     #[snafu(display("Failed to find table routes for table {}", table_name))]
     FindTableRoutes {
         table_name: String,
@@ -369,6 +372,12 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display("Failed to create record batches, source: {}", source))]
+    CreateRecordBatches {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
     #[snafu(display("Failed to do vector computation, source: {}", source))]
     VectorComputation {
         #[snafu(backtrace)]
@@ -390,6 +399,12 @@ pub enum Error {
     #[snafu(display("Missing meta_client_opts section in config"))]
     MissingMetasrvOpts { backtrace: Backtrace },
 
+    #[snafu(display("Invalid CreateTableExpr, source: {}", source))]
+    InvalidCreateTableExpr {
+        #[snafu(backtrace)]
+        source: common_grpc_expr::error::Error,
+    },
+
     #[snafu(display("Failed to convert AlterExpr to AlterRequest, source: {}", source))]
     AlterExprToRequest {
         #[snafu(backtrace)]
@@ -424,6 +439,15 @@ pub enum Error {
         #[snafu(backtrace)]
         source: servers::error::Error,
     },
+
+    #[snafu(display("Not supported: {}", feat))]
+    NotSupported { feat: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid time zone, source: {}", source))]
+    ParseTimeZone {
+        #[snafu(backtrace)]
+        source: common_time::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -443,9 +467,9 @@ impl ErrorExt for Error {
 
             Error::RuntimeResource { source, .. } => source.status_code(),
 
-            Error::StartServer { source, .. } | Error::InvokeGrpcServer { source } => {
-                source.status_code()
-            }
+            Error::StartServer { source, .. }
+            | Error::ShutdownServer { source }
+            | Error::InvokeGrpcServer { source } => source.status_code(),
 
             Error::ParseSql { source } => source.status_code(),
 
@@ -462,12 +486,11 @@ impl ErrorExt for Error {
                 source.status_code()
             }
 
-            Error::ColumnDataType { source } | Error::InvalidColumnDef { source, .. } => {
-                source.status_code()
-            }
+            Error::PartialInsert { source, .. } => source.status_code(),
+
+            Error::ColumnDataType { source } => source.status_code(),
 
             Error::FindDatanode { .. }
-            | Error::GetCache { .. }
             | Error::FindTableRoutes { .. }
             | Error::SerializeJson { .. }
             | Error::DeserializeJson { .. }
@@ -495,6 +518,7 @@ impl ErrorExt for Error {
             Error::BumpTableId { source, .. } => source.status_code(),
             Error::SchemaNotFound { .. } => StatusCode::InvalidArguments,
             Error::CatalogNotFound { .. } => StatusCode::InvalidArguments,
+            Error::ParseTimeZone { .. } => StatusCode::InvalidArguments,
             Error::CreateDatabase { source, .. }
             | Error::CreateTableOnInsertion { source, .. }
             | Error::Insert { source, .. } => source.status_code(),
@@ -504,13 +528,16 @@ impl ErrorExt for Error {
             Error::PrimaryKeyNotFound { .. } => StatusCode::InvalidArguments,
             Error::ExecuteSql { source, .. } => source.status_code(),
             Error::ExecuteStatement { source, .. } => source.status_code(),
+            Error::CreateRecordBatches { source } => source.status_code(),
             Error::InsertBatchToRequest { source, .. } => source.status_code(),
             Error::MissingMetasrvOpts { .. } => StatusCode::InvalidArguments,
-            Error::AlterExprToRequest { source, .. } => source.status_code(),
+            Error::AlterExprToRequest { source, .. }
+            | Error::InvalidCreateTableExpr { source, .. } => source.status_code(),
             Error::LeaderNotFound { .. } => StatusCode::StorageUnavailable,
             Error::TableAlreadyExist { .. } => StatusCode::TableAlreadyExists,
             Error::EncodeSubstraitLogicalPlan { source } => source.status_code(),
             Error::BuildVector { source, .. } => source.status_code(),
+            Error::NotSupported { .. } => StatusCode::Unsupported,
         }
     }
 
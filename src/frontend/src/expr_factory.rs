@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
+use api::v1::column::SemanticType;
 use api::v1::{Column, ColumnDataType, CreateTableExpr};
 use datatypes::schema::ColumnSchema;
 use snafu::{ensure, ResultExt};
@@ -83,14 +84,15 @@ fn create_to_expr(
         table_idents_to_full_name(&create.name).context(ParseSqlSnafu)?;
 
     let time_index = find_time_index(&create.constraints)?;
+    let primary_keys = find_primary_keys(&create.constraints)?;
     let expr = CreateTableExpr {
         catalog_name,
         schema_name,
         table_name,
         desc: "".to_string(),
-        column_defs: columns_to_expr(&create.columns, &time_index)?,
+        column_defs: columns_to_expr(&create.columns, &time_index, &primary_keys)?,
         time_index,
-        primary_keys: find_primary_keys(&create.constraints)?,
+        primary_keys,
         create_if_not_exists: create.if_not_exists,
         // TODO(LFC): Fill in other table options.
         table_options: HashMap::from([("engine".to_string(), create.engine.clone())]),
@@ -147,6 +149,7 @@ pub fn find_time_index(constraints: &[TableConstraint]) -> crate::error::Result<
 fn columns_to_expr(
     column_defs: &[ColumnDef],
     time_index: &str,
+    primary_keys: &[String],
 ) -> crate::error::Result<Vec<api::v1::ColumnDef>> {
     let column_schemas = column_defs
         .iter()
@@ -166,6 +169,13 @@ fn columns_to_expr(
         .iter()
         .zip(column_datatypes.into_iter())
         .map(|(schema, datatype)| {
+            let semantic_type = if schema.name == time_index {
+                SemanticType::Timestamp
+            } else if primary_keys.contains(&schema.name) {
+                SemanticType::Tag
+            } else {
+                SemanticType::Field
+            };
             Ok(api::v1::ColumnDef {
                 name: schema.name.clone(),
                 datatype: datatype as i32,
@@ -180,6 +190,7 @@ fn columns_to_expr(
                             })?
                     }
                 },
+                semantic_type: Some(semantic_type as i32),
             })
         })
         .collect()
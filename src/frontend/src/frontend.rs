@@ -14,6 +14,8 @@
 
 use std::sync::Arc;
 
+use common_grpc::channel_manager::ClientTlsOption;
+use common_telemetry::info;
 use meta_client::MetaClientOpts;
 use serde::{Deserialize, Serialize};
 use servers::auth::UserProviderRef;
@@ -27,6 +29,7 @@ use crate::influxdb::InfluxdbOptions;
 use crate::instance::FrontendInstance;
 use crate::mysql::MysqlOptions;
 use crate::opentsdb::OpentsdbOptions;
+use crate::otlp::OtlpOptions;
 use crate::postgres::PostgresOptions;
 use crate::prometheus::PrometheusOptions;
 use crate::server::Services;
@@ -41,8 +44,11 @@ pub struct FrontendOptions {
     pub opentsdb_options: Option<OpentsdbOptions>,
     pub influxdb_options: Option<InfluxdbOptions>,
     pub prometheus_options: Option<PrometheusOptions>,
+    pub otlp_options: Option<OtlpOptions>,
     pub mode: Mode,
     pub meta_client_opts: Option<MetaClientOpts>,
+    /// TLS of the connections to datanodes in distributed mode.
+    pub datanode_client_tls: Option<ClientTlsOption>,
 }
 
 impl Default for FrontendOptions {
@@ -55,8 +61,10 @@ impl Default for FrontendOptions {
             opentsdb_options: Some(OpentsdbOptions::default()),
             influxdb_options: Some(InfluxdbOptions::default()),
             prometheus_options: Some(PrometheusOptions::default()),
+            otlp_options: Some(OtlpOptions::default()),
             mode: Mode::Standalone,
             meta_client_opts: None,
+            datanode_client_tls: None,
         }
     }
 }
@@ -68,6 +76,7 @@ where
     opts: FrontendOptions,
     instance: Option<T>,
     plugins: Arc<Plugins>,
+    services: Option<Services>,
 }
 
 impl<T: FrontendInstance> Frontend<T> {
@@ -76,10 +85,18 @@ impl<T: FrontendInstance> Frontend<T> {
             opts,
             instance: Some(instance),
             plugins,
+            services: None,
         }
     }
 
     pub async fn start(&mut self) -> Result<()> {
+        self.start_instance().await?;
+        self.start_services().await
+    }
+
+    /// Starts the frontend instance and builds its servers, the servers are not
+    /// started until [Frontend::start_services] is called.
+    pub async fn start_instance(&mut self) -> Result<()> {
         let mut instance = self
             .instance
             .take()
@@ -93,6 +110,27 @@ impl<T: FrontendInstance> Frontend<T> {
         // TODO(sunng87): merge this into instance
         let provider = self.plugins.get::<UserProviderRef>().cloned();
 
-        Services::start(&self.opts, instance, provider).await
+        self.services = Some(Services::try_new(&self.opts, instance, provider)?);
+        Ok(())
+    }
+
+    /// Starts servers of the frontend. This method call will block until servers are shutdown.
+    pub async fn start_services(&self) -> Result<()> {
+        self.services()?.start().await
+    }
+
+    /// Shutdown servers of the frontend, requests in flight are still served after
+    /// this method returns, callers may wait for [Frontend::start_services] to return.
+    pub async fn shutdown(&self) -> Result<()> {
+        info!("Shutting down frontend...");
+        self.services()?.shutdown().await
+    }
+
+    fn services(&self) -> Result<&Services> {
+        self.services
+            .as_ref()
+            .context(error::IllegalFrontendStateSnafu {
+                err_msg: "Frontend instance not started",
+            })
     }
 }
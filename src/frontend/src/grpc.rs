@@ -13,11 +13,20 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use servers::grpc::limit::GrpcLimitOptions;
+use servers::tls::TlsOption;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GrpcOptions {
     pub addr: String,
     pub runtime_size: usize,
+    #[serde(default)]
+    pub tls: TlsOption,
+    #[serde(default)]
+    pub limit: GrpcLimitOptions,
+    /// Queries slower than it are logged as slow queries, 0 disables the slow query log.
+    #[serde(default)]
+    pub slow_query_threshold_millis: u64,
 }
 
 impl Default for GrpcOptions {
@@ -25,6 +34,9 @@ impl Default for GrpcOptions {
         Self {
             addr: "127.0.0.1:4001".to_string(),
             runtime_size: 8,
+            tls: TlsOption::default(),
+            limit: GrpcLimitOptions::default(),
+            slow_query_threshold_millis: 0,
         }
     }
 }
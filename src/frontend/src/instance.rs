@@ -15,8 +15,11 @@
 pub(crate) mod distributed;
 mod influxdb;
 mod opentsdb;
+mod otlp;
+mod privilege;
 mod prometheus;
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -38,24 +41,32 @@ use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_grpc::flight::{FlightEncoder, FlightMessage};
 use common_query::Output;
 use common_recordbatch::RecordBatches;
+use common_telemetry::tracing::{info_span, Instrument};
 use common_telemetry::{debug, info};
+use common_time::TimeZone;
 use datanode::instance::InstanceRef as DnInstanceRef;
+use datatypes::schema::Schema;
 use distributed::DistInstance;
-use meta_client::client::{MetaClient, MetaClientBuilder};
+use meta_client::client::{MetaClient, MetaClientBuilder, RouteCacheConfig};
 use meta_client::MetaClientOpts;
+use servers::auth::user_store::KvUserStore;
+use servers::auth::{self, UserProviderRef};
 use servers::interceptor::{SqlQueryInterceptor, SqlQueryInterceptorRef};
 use servers::query_handler::{
-    GrpcQueryHandler, GrpcQueryHandlerRef, InfluxdbLineProtocolHandler, OpentsdbProtocolHandler,
-    PrometheusProtocolHandler, ScriptHandler, ScriptHandlerRef, SqlQueryHandler,
+    FlightDataStream, GrpcQueryHandler, GrpcQueryHandlerRef, InfluxdbLineProtocolHandler,
+    OpenTelemetryProtocolHandler, OpentsdbProtocolHandler, PrometheusProtocolHandler,
+    ReadinessHandler, ReadinessHandlerRef, ScriptHandler, ScriptHandlerRef, SqlQueryHandler,
     SqlQueryHandlerRef,
 };
 use servers::{error as server_error, Mode};
-use session::context::QueryContextRef;
+use session::context::{QueryContext, QueryContextRef, TIME_ZONE_VARIABLE};
 use snafu::prelude::*;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
-use sql::statements::create::Partitions;
+use sql::statements::create::{CreateUser, Partitions};
+use sql::statements::drop::DropUser;
 use sql::statements::insert::Insert;
+use sql::statements::set::SetVariable;
 use sql::statements::statement::Statement;
 use table::TableRef;
 
@@ -78,7 +89,9 @@ pub trait FrontendInstance:
     + OpentsdbProtocolHandler
     + InfluxdbLineProtocolHandler
     + PrometheusProtocolHandler
+    + OpenTelemetryProtocolHandler
     + ScriptHandler
+    + ReadinessHandler
     + Send
     + Sync
     + 'static
@@ -104,6 +117,8 @@ pub struct Instance {
 
     sql_handler: SqlQueryHandlerRef,
     grpc_query_handler: GrpcQueryHandlerRef,
+    /// Readiness of the datanode instance in standalone mode.
+    readiness_handler: Option<ReadinessHandlerRef>,
 
     /// plugins: this map holds extensions to customize query or auth
     /// behaviours.
@@ -118,7 +133,13 @@ impl Instance {
             client: meta_client.clone(),
         });
         let table_routes = Arc::new(TableRoutes::new(meta_client.clone()));
-        let datanode_clients = Arc::new(DatanodeClients::new());
+        let mut channel_config = ChannelConfig::new();
+        if let Some(tls) = &opts.datanode_client_tls {
+            channel_config = channel_config.client_tls_config(tls.clone());
+        }
+        let datanode_clients = Arc::new(DatanodeClients::with_channel_manager(
+            ChannelManager::with_config(channel_config),
+        ));
         let catalog_manager = Arc::new(FrontendCatalogManager::new(
             meta_backend,
             table_routes,
@@ -137,6 +158,7 @@ impl Instance {
             dist_instance: Some(dist_instance),
             sql_handler: dist_instance_ref.clone(),
             grpc_query_handler: dist_instance_ref,
+            readiness_handler: None,
             plugins: Default::default(),
         })
     }
@@ -162,7 +184,9 @@ impl Instance {
         let mut meta_client = MetaClientBuilder::new(0, 0)
             .enable_router()
             .enable_store()
+            .enable_cluster()
             .channel_manager(channel_manager)
+            .route_cache(RouteCacheConfig::default())
             .build();
         meta_client
             .start(metasrv_addr)
@@ -180,6 +204,7 @@ impl Instance {
             dist_instance: None,
             sql_handler: dn_instance.clone(),
             grpc_query_handler: dn_instance.clone(),
+            readiness_handler: Some(dn_instance.clone()),
             plugins: Default::default(),
         }
     }
@@ -207,11 +232,14 @@ impl Instance {
         } else {
             let result = self
                 .grpc_query_handler
-                .do_query(ObjectExpr {
-                    request: Some(Request::Ddl(DdlRequest {
-                        expr: Some(DdlExpr::CreateTable(expr)),
-                    })),
-                })
+                .do_query(
+                    ObjectExpr {
+                        request: Some(Request::Ddl(DdlRequest {
+                            expr: Some(DdlExpr::CreateTable(expr)),
+                        })),
+                    },
+                    QueryContext::arc(),
+                )
                 .await
                 .context(error::InvokeGrpcServerSnafu)?;
             let output: RpcOutput = result.try_into().context(RequestDatanodeSnafu)?;
@@ -246,9 +274,10 @@ impl Instance {
         let query = ObjectExpr {
             request: Some(Request::Insert(request)),
         };
-        let result = GrpcQueryHandler::do_query(&*self.grpc_query_handler, query)
-            .await
-            .context(error::InvokeGrpcServerSnafu)?;
+        let result =
+            GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, QueryContext::arc())
+                .await
+                .context(error::InvokeGrpcServerSnafu)?;
         let result: RpcOutput = result.try_into().context(InsertSnafu)?;
         Ok(result.into())
     }
@@ -345,11 +374,14 @@ impl Instance {
 
         let result = self
             .grpc_query_handler
-            .do_query(ObjectExpr {
-                request: Some(Request::Ddl(DdlRequest {
-                    expr: Some(DdlExpr::Alter(expr)),
-                })),
-            })
+            .do_query(
+                ObjectExpr {
+                    request: Some(Request::Ddl(DdlRequest {
+                        expr: Some(DdlExpr::Alter(expr)),
+                    })),
+                },
+                QueryContext::arc(),
+            )
             .await
             .context(error::InvokeGrpcServerSnafu)?;
         let output: RpcOutput = result.try_into().context(RequestDatanodeSnafu)?;
@@ -378,13 +410,18 @@ impl Instance {
             .context(CatalogSnafu)
     }
 
-    async fn sql_dist_insert(&self, insert: Box<Insert>) -> Result<usize> {
+    async fn sql_dist_insert(
+        &self,
+        insert: Box<Insert>,
+        query_ctx: &QueryContextRef,
+    ) -> Result<usize> {
         let (catalog, schema, table) = insert.full_table_name().context(error::ParseSqlSnafu)?;
 
         let catalog_provider = self.get_catalog(&catalog)?;
         let schema_provider = Self::get_schema(catalog_provider, &schema)?;
 
-        let insert_request = insert_to_request(&schema_provider, *insert)?;
+        let insert_request =
+            insert_to_request(&schema_provider, *insert, query_ctx.time_zone().as_ref())?;
 
         let (columns, _row_count) =
             crate::table::insert::insert_request_to_insert_batch(&insert_request)?;
@@ -417,7 +454,33 @@ impl Instance {
         Ok(Output::RecordBatches(RecordBatches::empty()))
     }
 
+    fn handle_set_variable(&self, set: SetVariable, query_ctx: QueryContextRef) -> Result<Output> {
+        if set.name == TIME_ZONE_VARIABLE {
+            let time_zone = if set.value.eq_ignore_ascii_case("DEFAULT") {
+                None
+            } else {
+                Some(
+                    set.value
+                        .parse::<TimeZone>()
+                        .context(error::ParseTimeZoneSnafu)?,
+                )
+            };
+            query_ctx.set_time_zone(time_zone);
+        } else {
+            query_ctx.set_variable(&set.name, set.value);
+        }
+
+        Ok(Output::AffectedRows(0))
+    }
+
     pub fn set_plugins(&mut self, map: Arc<Plugins>) {
+        if let (Some(dist_instance), Some(provider)) =
+            (&self.dist_instance, map.get::<UserProviderRef>())
+        {
+            // Users created by SQL are shared by all frontends through the metadata.
+            let backend = dist_instance.catalog_manager().backend();
+            provider.set_user_store(Arc::new(KvUserStore::new(backend)));
+        }
         self.plugins = map;
     }
 
@@ -439,11 +502,49 @@ fn parse_stmt(sql: &str) -> Result<Vec<Statement>> {
 }
 
 impl Instance {
+    /// Creates the user in the user provider, which authenticates the clients of all
+    /// protocols.
+    async fn create_user(&self, stmt: CreateUser) -> server_error::Result<Output> {
+        let result = self
+            .user_provider()?
+            .create_user(&stmt.name, &stmt.password)
+            .await;
+        match result {
+            Err(auth::Error::UserAlreadyExists { .. }) if stmt.if_not_exists => {
+                Ok(Output::AffectedRows(0))
+            }
+            result => result
+                .map(|_| Output::AffectedRows(1))
+                .context(server_error::AuthSnafu),
+        }
+    }
+
+    async fn drop_user(&self, stmt: DropUser) -> server_error::Result<Output> {
+        let result = self.user_provider()?.drop_user(&stmt.name).await;
+        match result {
+            Err(auth::Error::UserNotFound { .. }) if stmt.if_exists => Ok(Output::AffectedRows(0)),
+            result => result
+                .map(|_| Output::AffectedRows(1))
+                .context(server_error::AuthSnafu),
+        }
+    }
+
+    fn user_provider(&self) -> server_error::Result<UserProviderRef> {
+        self.plugins
+            .get::<UserProviderRef>()
+            .cloned()
+            .context(server_error::NotSupportedSnafu {
+                feat: "managing users without a user provider",
+            })
+    }
+
     async fn query_statement(
         &self,
         stmt: Statement,
         query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
+        self.check_privileges(&stmt, &query_ctx).await?;
+
         // TODO(sunng87): provide a better form to log or track statement
         let query = &format!("{:?}", &stmt);
         match stmt.clone() {
@@ -462,7 +563,7 @@ impl Instance {
                 }
                 Mode::Distributed => {
                     let affected = self
-                        .sql_dist_insert(insert)
+                        .sql_dist_insert(insert, &query_ctx)
                         .await
                         .map_err(BoxedError::new)
                         .context(server_error::ExecuteInsertSnafu {
@@ -471,17 +572,33 @@ impl Instance {
                     Ok(Output::AffectedRows(affected))
                 }
             },
+            Statement::Delete(_)
+            | Statement::Copy(_)
+            | Statement::CopyTo(_)
+            | Statement::CreateTask(_)
+            | Statement::DropTask(_)
+            | Statement::Truncate(_) => match self.mode {
+                Mode::Standalone => {
+                    return self.sql_handler.do_statement_query(stmt, query_ctx).await
+                }
+                Mode::Distributed => {
+                    return server_error::NotSupportedSnafu { feat: query }.fail();
+                }
+            },
             Statement::Alter(alter_stmt) => {
                 let expr = AlterExpr::try_from(alter_stmt)
                     .map_err(BoxedError::new)
                     .context(server_error::ExecuteAlterSnafu { query })?;
                 let result = self
                     .grpc_query_handler
-                    .do_query(ObjectExpr {
-                        request: Some(Request::Ddl(DdlRequest {
-                            expr: Some(DdlExpr::Alter(expr)),
-                        })),
-                    })
+                    .do_query(
+                        ObjectExpr {
+                            request: Some(Request::Ddl(DdlRequest {
+                                expr: Some(DdlExpr::Alter(expr)),
+                            })),
+                        },
+                        query_ctx,
+                    )
                     .await?;
                 let output: RpcOutput = result
                     .try_into()
@@ -497,11 +614,14 @@ impl Instance {
                 };
                 let result = self
                     .grpc_query_handler
-                    .do_query(ObjectExpr {
-                        request: Some(Request::Ddl(DdlRequest {
-                            expr: Some(DdlExpr::DropTable(expr)),
-                        })),
-                    })
+                    .do_query(
+                        ObjectExpr {
+                            request: Some(Request::Ddl(DdlRequest {
+                                expr: Some(DdlExpr::DropTable(expr)),
+                            })),
+                        },
+                        query_ctx,
+                    )
                     .await?;
                 let output: RpcOutput = result
                     .try_into()
@@ -512,7 +632,22 @@ impl Instance {
             Statement::ShowCreateTable(_) => {
                 return server_error::NotSupportedSnafu { feat: query }.fail();
             }
+            Statement::ShowNodes(_) => match self.mode {
+                Mode::Standalone => {
+                    return server_error::NotSupportedSnafu {
+                        feat: "SHOW NODES in standalone mode",
+                    }
+                    .fail();
+                }
+                Mode::Distributed => {
+                    return self.sql_handler.do_statement_query(stmt, query_ctx).await
+                }
+            },
+            Statement::CreateUser(stmt) => return self.create_user(stmt).await,
+            Statement::DropUser(stmt) => return self.drop_user(stmt).await,
+            Statement::Grant(stmt) => return self.grant(stmt).await,
             Statement::Use(db) => self.handle_use(db, query_ctx),
+            Statement::SetVariable(set) => self.handle_set_variable(set, query_ctx),
         }
         .map_err(BoxedError::new)
         .context(server_error::ExecuteQuerySnafu { query })
@@ -547,7 +682,12 @@ impl SqlQueryHandler for Instance {
                         results.push(Err(e));
                         break;
                     }
-                    match self.query_statement(stmt, query_ctx.clone()).await {
+                    let span = info_span!("query_statement");
+                    match self
+                        .query_statement(stmt, query_ctx.clone())
+                        .instrument(span)
+                        .await
+                    {
                         Ok(output) => {
                             let output_result =
                                 query_interceptor.post_execute(output, query_ctx.clone());
@@ -583,6 +723,15 @@ impl SqlQueryHandler for Instance {
             .and_then(|output| query_interceptor.post_execute(output, query_ctx.clone()))
     }
 
+    async fn do_describe(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Option<Schema>> {
+        self.check_privileges(&stmt, &query_ctx).await?;
+        self.sql_handler.do_describe(stmt, query_ctx).await
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> server_error::Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
@@ -616,15 +765,29 @@ impl ScriptHandler for Instance {
     }
 }
 
+impl ReadinessHandler for Instance {
+    fn readiness(&self) -> BTreeMap<String, bool> {
+        self.readiness_handler
+            .as_ref()
+            .map(|handler| handler.readiness())
+            .unwrap_or_default()
+    }
+}
+
 #[async_trait]
 impl GrpcQueryHandler for Instance {
-    async fn do_query(&self, query: ObjectExpr) -> server_error::Result<GrpcObjectResult> {
+    async fn do_query(
+        &self,
+        query: ObjectExpr,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<GrpcObjectResult> {
         let request = query
             .clone()
             .request
             .context(server_error::InvalidQuerySnafu {
                 reason: "empty expr",
             })?;
+        self.check_grpc_privileges(&request, &query_ctx).await?;
         match request {
             Request::Insert(request) => {
                 let output = self
@@ -636,15 +799,39 @@ impl GrpcQueryHandler for Instance {
                     })?;
                 let object_result = match output {
                     Output::AffectedRows(rows) => ObjectResultBuilder::default()
-                        .flight_data(vec![
-                            FlightEncoder::default().encode(FlightMessage::AffectedRows(rows))
-                        ])
+                        .flight_data(
+                            FlightEncoder::default().encode(FlightMessage::AffectedRows(rows)),
+                        )
                         .build(),
                     _ => unreachable!(),
                 };
                 Ok(object_result)
             }
-            _ => GrpcQueryHandler::do_query(&*self.grpc_query_handler, query).await,
+            Request::Diagnostic(_) => server_error::NotSupportedSnafu {
+                feat: "Diagnostic requests in Frontend",
+            }
+            .fail(),
+            _ => GrpcQueryHandler::do_query(&*self.grpc_query_handler, query, query_ctx).await,
+        }
+    }
+
+    async fn do_query_stream(
+        &self,
+        query: ObjectExpr,
+        compressed: bool,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<FlightDataStream> {
+        match &query.request {
+            Some(request @ Request::Query(_)) => {
+                self.check_grpc_privileges(request, &query_ctx).await?;
+                self.grpc_query_handler
+                    .do_query_stream(query, compressed, query_ctx)
+                    .await
+            }
+            _ => server_error::NotSupportedSnafu {
+                feat: "Streaming results of non-query requests",
+            }
+            .fail(),
         }
     }
 }
@@ -665,7 +852,7 @@ mod tests {
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema};
     use datatypes::value::Value;
     use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
-    use session::context::QueryContext;
+    use session::context::{QueryContext, UserInfo};
 
     use super::*;
     use crate::tests;
@@ -722,7 +909,7 @@ mod tests {
             }
             Output::Stream(s) => {
                 let batches = common_recordbatch::util::collect_batches(s).await.unwrap();
-                let pretty_print = batches.pretty_print().unwrap();
+                let pretty_print = batches.pretty_print(None).unwrap();
                 let expected = "\
 +----------------+---------------------+-----+--------+-----------+
 | host           | ts                  | cpu | memory | disk_util |
@@ -750,7 +937,7 @@ mod tests {
             }
             Output::Stream(s) => {
                 let recordbatches = common_recordbatch::util::collect_batches(s).await.unwrap();
-                let pretty = recordbatches.pretty_print().unwrap();
+                let pretty = recordbatches.pretty_print(None).unwrap();
                 let expected = "\
 +----------------+---------------------+-----+--------+-----------+
 | host           | ts                  | cpu | memory | disk_util |
@@ -822,6 +1009,7 @@ mod tests {
                     expr: Some(DdlExpr::CreateTable(create_expr())),
                 })),
             },
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -846,7 +1034,7 @@ mod tests {
         let object_expr = ObjectExpr {
             request: Some(Request::Insert(request)),
         };
-        let result = GrpcQueryHandler::do_query(&*instance, object_expr)
+        let result = GrpcQueryHandler::do_query(&*instance, object_expr, QueryContext::arc())
             .await
             .unwrap();
         let raw_data = result.flight_data;
@@ -859,9 +1047,10 @@ mod tests {
         let object_expr = ObjectExpr {
             request: Some(Request::Query(QueryRequest {
                 query: Some(query_request::Query::Sql("select * from demo".to_string())),
+                ..Default::default()
             })),
         };
-        let result = GrpcQueryHandler::do_query(&*instance, object_expr)
+        let result = GrpcQueryHandler::do_query(&*instance, object_expr, QueryContext::arc())
             .await
             .unwrap();
         let raw_data = result.flight_data;
@@ -927,6 +1116,75 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_grpc_privileges() {
+        let (mut instance, _guard) = tests::create_frontend_instance("test_grpc_privileges").await;
+        let user_provider =
+            auth::user_provider_from_option(&"static_user_provider:cmd:alice=pwd".to_string())
+                .unwrap();
+        let mut plugins = Plugins::new();
+        plugins.insert::<UserProviderRef>(user_provider);
+        Arc::make_mut(&mut instance).set_plugins(Arc::new(plugins));
+
+        let create = ObjectExpr {
+            request: Some(Request::Ddl(DdlRequest {
+                expr: Some(DdlExpr::CreateTable(create_expr())),
+            })),
+        };
+        let _ = GrpcQueryHandler::do_query(&*instance, create, QueryContext::arc())
+            .await
+            .unwrap();
+
+        let alice = QueryContext::arc();
+        alice.set_current_user(UserInfo::new("alice"));
+        let select = ObjectExpr {
+            request: Some(Request::Query(QueryRequest {
+                query: Some(query_request::Query::Sql("select * from demo".to_string())),
+                ..Default::default()
+            })),
+        };
+        let insert = ObjectExpr {
+            request: Some(Request::Insert(InsertRequest {
+                schema_name: "public".to_string(),
+                table_name: "demo".to_string(),
+                ..Default::default()
+            })),
+        };
+        for expr in [select.clone(), insert.clone()] {
+            let err = GrpcQueryHandler::do_query(&*instance, expr, alice.clone())
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, server_error::Error::PermissionDenied { .. }),
+                "{err:?}"
+            );
+        }
+        let err =
+            GrpcQueryHandler::do_query_stream(&*instance, select.clone(), false, alice.clone())
+                .await
+                .err()
+                .unwrap();
+        assert!(matches!(err, server_error::Error::PermissionDenied { .. }));
+
+        let output = SqlQueryHandler::do_query(
+            &*instance,
+            "GRANT READ ON public TO alice",
+            QueryContext::arc(),
+        )
+        .await
+        .remove(0)
+        .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let _ = GrpcQueryHandler::do_query(&*instance, select, alice.clone())
+            .await
+            .unwrap();
+        let err = GrpcQueryHandler::do_query(&*instance, insert, alice)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, server_error::Error::PermissionDenied { .. }));
+    }
+
     fn create_expr() -> CreateTableExpr {
         let column_defs = vec![
             GrpcColumnDef {
@@ -934,18 +1192,21 @@ mod tests {
                 datatype: ColumnDataType::String as i32,
                 is_nullable: false,
                 default_constraint: vec![],
+                semantic_type: None,
             },
             GrpcColumnDef {
                 name: "cpu".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                semantic_type: None,
             },
             GrpcColumnDef {
                 name: "memory".to_string(),
                 datatype: ColumnDataType::Float64 as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                semantic_type: None,
             },
             GrpcColumnDef {
                 name: "disk_util".to_string(),
@@ -954,12 +1215,14 @@ mod tests {
                 default_constraint: ColumnDefaultConstraint::Value(Value::from(9.9f64))
                     .try_into()
                     .unwrap(),
+                semantic_type: None,
             },
             GrpcColumnDef {
                 name: "ts".to_string(),
                 datatype: ColumnDataType::TimestampMillisecond as i32,
                 is_nullable: true,
                 default_constraint: vec![],
+                semantic_type: None,
             },
         ];
         CreateTableExpr {
@@ -12,27 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod create_table;
+
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
 use api::result::ObjectResultBuilder;
+use api::v1::alter_expr::Kind;
 use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::meta::{NodeInfo, NodeStatus};
 use api::v1::object_expr::Request as GrpcRequest;
 use api::v1::{AlterExpr, CreateDatabaseExpr, CreateTableExpr, ObjectExpr, ObjectResult, TableId};
 use async_trait::async_trait;
 use catalog::helper::{SchemaKey, SchemaValue, TableGlobalKey, TableGlobalValue};
 use catalog::{CatalogList, CatalogManager};
 use chrono::DateTime;
-use client::Database;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::prelude::BoxedError;
 use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_grpc_expr::{create_table_schema, normalize_create_expr};
 use common_query::Output;
-use common_telemetry::{debug, error, info};
+use common_recordbatch::RecordBatches;
+use common_telemetry::{error, info, warn};
 use datatypes::prelude::ConcreteDataType;
-use datatypes::schema::RawSchema;
+use datatypes::schema::{ColumnSchema, RawSchema, Schema};
+use datatypes::vectors::{StringVector, TimestampMillisecondVector, UInt64Vector, VectorRef};
 use meta_client::client::MetaClient;
+use meta_client::rpc::router::RenameRequest as MetaRenameRequest;
 use meta_client::rpc::{
     CreateRequest as MetaCreateRequest, Partition as MetaPartition, PutRequest, RouteResponse,
     TableName, TableRoute,
@@ -48,13 +55,14 @@ use sql::statements::create::Partitions;
 use sql::statements::sql_value_to_value;
 use sql::statements::statement::Statement;
 use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
+use table::TableRef;
 
 use crate::catalog::FrontendCatalogManager;
 use crate::datanode::DatanodeClients;
 use crate::error::{
     self, CatalogEntrySerdeSnafu, CatalogNotFoundSnafu, CatalogSnafu, ColumnDataTypeSnafu,
-    PrimaryKeyNotFoundSnafu, RequestDatanodeSnafu, RequestMetaSnafu, Result, SchemaNotFoundSnafu,
-    StartMetaClientSnafu, TableNotFoundSnafu,
+    PrimaryKeyNotFoundSnafu, RequestMetaSnafu, Result, SchemaNotFoundSnafu, StartMetaClientSnafu,
+    TableNotFoundSnafu,
 };
 use crate::expr_factory::{CreateExprFactory, DefaultCreateExprFactory};
 use crate::instance::parse_stmt;
@@ -89,7 +97,23 @@ impl DistInstance {
         create_table: &mut CreateTableExpr,
         partitions: Option<Partitions>,
     ) -> Result<Output> {
-        let response = self.create_table_in_meta(create_table, partitions).await?;
+        // The time index and primary keys may be given by the semantic types of the
+        // columns, which are resolved once for the table info and the datanodes.
+        normalize_create_expr(create_table).context(error::InvalidCreateTableExprSnafu)?;
+        let table_name = create_table_name(create_table);
+        if self.get_table_global_value(&table_name).await?.is_some() {
+            if create_table.create_if_not_exists {
+                return Ok(Output::AffectedRows(0));
+            }
+            return error::TableAlreadyExistSnafu {
+                table: table_name.to_string(),
+            }
+            .fail();
+        }
+
+        let response = self
+            .create_table_in_meta(create_table, table_name, partitions)
+            .await?;
         let table_routes = response.table_routes;
         ensure!(
             table_routes.len() == 1,
@@ -115,28 +139,9 @@ impl DistInstance {
         create_table.table_id = Some(TableId {
             id: table_route.table.id as u32,
         });
-        self.put_table_global_meta(create_table, table_route)
+        self.create_table_in_datanodes(create_table, table_route)
             .await?;
 
-        for datanode in table_route.find_leaders() {
-            let client = self.datanode_clients.get_client(&datanode).await;
-            let client = Database::new("greptime", client);
-
-            let regions = table_route.find_leader_regions(&datanode);
-            let mut create_expr_for_region = create_table.clone();
-            create_expr_for_region.region_ids = regions;
-
-            debug!(
-                "Creating table {:?} on Datanode {:?} with regions {:?}",
-                create_table, datanode, create_expr_for_region.region_ids,
-            );
-
-            client
-                .create(create_expr_for_region)
-                .await
-                .context(RequestDatanodeSnafu)?;
-        }
-
         // Checked in real MySQL, it truly returns "0 rows affected".
         Ok(Output::AffectedRows(0))
     }
@@ -162,13 +167,22 @@ impl DistInstance {
                 Ok(Output::AffectedRows(1))
             }
             Statement::CreateTable(stmt) => {
+                ensure!(
+                    !stmt.external,
+                    error::NotSupportedSnafu {
+                        feat: "external tables in distributed mode",
+                    }
+                );
                 let create_expr = &mut DefaultCreateExprFactory.create_expr_by_stmt(&stmt).await?;
                 Ok(self.create_table(create_expr, stmt.partitions).await?)
             }
-            Statement::ShowDatabases(stmt) => show_databases(stmt, self.catalog_manager.clone()),
+            Statement::ShowDatabases(stmt) => {
+                show_databases(stmt, self.catalog_manager.clone()).await
+            }
             Statement::ShowTables(stmt) => {
-                show_tables(stmt, self.catalog_manager.clone(), query_ctx)
+                show_tables(stmt, self.catalog_manager.clone(), query_ctx).await
             }
+            Statement::ShowNodes(_) => Ok(self.show_nodes().await?),
             Statement::DescribeTable(stmt) => describe_table(stmt, self.catalog_manager.clone()),
             Statement::Explain(stmt) => {
                 explain(Box::new(stmt), self.query_engine.clone(), query_ctx).await
@@ -220,6 +234,72 @@ impl DistInstance {
         Ok(())
     }
 
+    /// Lists the datanodes of the cluster with the stats in their last heartbeats.
+    async fn show_nodes(&self) -> Result<Output> {
+        let nodes = self
+            .meta_client
+            .list_nodes()
+            .await
+            .context(RequestMetaSnafu)?;
+
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("node_id", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("addr", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("status", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(
+                "last_heartbeat",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("region_num", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("table_num", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("rcus", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new("wcus", ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(
+                "approximate_size",
+                ConcreteDataType::uint64_datatype(),
+                false,
+            ),
+        ]));
+        let peers = nodes
+            .iter()
+            .map(|node| node.peer.clone().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let stats = |f: fn(&NodeInfo) -> u64| -> VectorRef {
+            Arc::new(UInt64Vector::from_values(nodes.iter().map(f)))
+        };
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_values(peers.iter().map(|peer| peer.id))),
+            Arc::new(StringVector::from(
+                peers
+                    .iter()
+                    .map(|peer| peer.addr.as_str())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringVector::from(
+                nodes
+                    .iter()
+                    .map(|node| match NodeStatus::from_i32(node.status) {
+                        Some(NodeStatus::Alive) => "ALIVE",
+                        Some(NodeStatus::Expired) => "EXPIRED",
+                        None => "UNKNOWN",
+                    })
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(TimestampMillisecondVector::from_values(
+                nodes.iter().map(|node| node.last_heartbeat_millis),
+            )),
+            stats(|node| node.region_num),
+            stats(|node| node.table_num),
+            stats(|node| node.rcus),
+            stats(|node| node.wcus),
+            stats(|node| node.approximate_size),
+        ];
+        let records = RecordBatches::try_from_columns(schema, columns)
+            .context(error::CreateRecordBatchesSnafu)?;
+        Ok(Output::RecordBatches(records))
+    }
+
     async fn handle_alter_table(&self, expr: AlterExpr) -> Result<()> {
         let catalog_name = if expr.catalog_name.is_empty() {
             DEFAULT_CATALOG_NAME
@@ -232,8 +312,57 @@ impl DistInstance {
             expr.schema_name.as_str()
         };
         let table_name = expr.table_name.as_str();
-        let table = self
-            .catalog_manager
+        let table = self.find_table(catalog_name, schema_name, table_name)?;
+        let rename = match &expr.kind {
+            Some(Kind::RenameTable(rename)) => Some((
+                TableName::new(catalog_name, schema_name, table_name),
+                rename.new_table_name.clone(),
+            )),
+            _ => None,
+        };
+        // Renames the table in meta first, which fails if the new name is taken. Datanodes
+        // rename their regions by the route of the table, that is moved to the new name.
+        let table = match &rename {
+            Some((table_name, new_table_name)) => {
+                self.rename_table_in_meta(table_name.clone(), new_table_name)
+                    .await?;
+                self.find_table(catalog_name, schema_name, new_table_name)?
+            }
+            None => table,
+        };
+
+        let dist_table = table
+            .as_any()
+            .downcast_ref::<DistTable>()
+            .expect("Table impl must be DistTable in distributed mode");
+        if let Err(e) = dist_table.alter_by_expr(expr).await {
+            if let Some((table_name, new_table_name)) = rename {
+                let new_table_name = TableName::new(
+                    &table_name.catalog_name,
+                    &table_name.schema_name,
+                    new_table_name,
+                );
+                if let Err(e) = self
+                    .rename_table_in_meta(new_table_name, &table_name.table_name)
+                    .await
+                {
+                    warn!("Failed to restore the name of table {table_name} in meta: {e}");
+                }
+            }
+            return Err(e);
+        }
+        // Cached plans may reference the altered table.
+        self.query_engine.invalidate_plan_cache();
+        Ok(())
+    }
+
+    fn find_table(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Result<TableRef> {
+        self.catalog_manager
             .catalog(catalog_name)
             .context(CatalogSnafu)?
             .context(CatalogNotFoundSnafu { catalog_name })?
@@ -246,30 +375,28 @@ impl DistInstance {
             .context(CatalogSnafu)?
             .context(TableNotFoundSnafu {
                 table_name: format!("{catalog_name}.{schema_name}.{table_name}"),
-            })?;
+            })
+    }
 
-        let dist_table = table
-            .as_any()
-            .downcast_ref::<DistTable>()
-            .expect("Table impl must be DistTable in distributed mode");
-        dist_table.alter_by_expr(expr).await
+    /// Moves the catalog entries and the route of the table to the new name atomically.
+    async fn rename_table_in_meta(
+        &self,
+        table_name: TableName,
+        new_table_name: &str,
+    ) -> Result<RouteResponse> {
+        let request = MetaRenameRequest::new(table_name, new_table_name);
+        self.meta_client
+            .rename_route(request)
+            .await
+            .context(error::RequestMetaSnafu)
     }
 
     async fn create_table_in_meta(
         &self,
         create_table: &CreateTableExpr,
+        table_name: TableName,
         partitions: Option<Partitions>,
     ) -> Result<RouteResponse> {
-        let mut catalog_name = create_table.catalog_name.clone();
-        if catalog_name.is_empty() {
-            catalog_name = DEFAULT_CATALOG_NAME.to_string();
-        }
-        let mut schema_name = create_table.schema_name.clone();
-        if schema_name.is_empty() {
-            schema_name = DEFAULT_SCHEMA_NAME.to_string();
-        }
-        let table_name = TableName::new(catalog_name, schema_name, create_table.table_name.clone());
-
         let partitions = parse_partitions(create_table, partitions)?;
         let request = MetaCreateRequest {
             table_name,
@@ -281,19 +408,28 @@ impl DistInstance {
             .context(error::RequestMetaSnafu)
     }
 
+    async fn get_table_global_value(
+        &self,
+        table_name: &TableName,
+    ) -> Result<Option<TableGlobalValue>> {
+        let key = table_global_key(table_name).to_string();
+        let kv = self
+            .catalog_manager
+            .backend()
+            .get(key.as_bytes())
+            .await
+            .context(CatalogSnafu)?;
+        kv.map(|kv| TableGlobalValue::from_bytes(kv.1).context(CatalogEntrySerdeSnafu))
+            .transpose()
+    }
+
     // TODO(LFC): Maybe move this to FrontendCatalogManager's "register_table" method?
     async fn put_table_global_meta(
         &self,
         create_table: &CreateTableExpr,
         table_route: &TableRoute,
     ) -> Result<()> {
-        let table_name = &table_route.table.table_name;
-        let key = TableGlobalKey {
-            catalog_name: table_name.catalog_name.clone(),
-            schema_name: table_name.schema_name.clone(),
-            table_name: table_name.table_name.clone(),
-        }
-        .to_string();
+        let key = table_global_key(&table_route.table.table_name).to_string();
 
         let value = create_table_global_value(create_table, table_route)?
             .as_bytes()
@@ -322,7 +458,6 @@ impl DistInstance {
         Ok(())
     }
 
-    #[cfg(test)]
     pub(crate) fn catalog_manager(&self) -> Arc<FrontendCatalogManager> {
         self.catalog_manager.clone()
     }
@@ -356,6 +491,23 @@ impl SqlQueryHandler for DistInstance {
             .context(server_error::ExecuteStatementSnafu)
     }
 
+    async fn do_describe(
+        &self,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Option<Schema>> {
+        if let Statement::Query(_) = stmt {
+            self.query_engine
+                .statement_to_plan(stmt, query_ctx)
+                .and_then(|plan| plan.schema())
+                .map(Some)
+                .map_err(BoxedError::new)
+                .context(server_error::DescribeStatementSnafu)
+        } else {
+            Ok(None)
+        }
+    }
+
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> server_error::Result<bool> {
         self.catalog_manager
             .schema(catalog, schema)
@@ -366,7 +518,11 @@ impl SqlQueryHandler for DistInstance {
 
 #[async_trait]
 impl GrpcQueryHandler for DistInstance {
-    async fn do_query(&self, expr: ObjectExpr) -> server_error::Result<ObjectResult> {
+    async fn do_query(
+        &self,
+        expr: ObjectExpr,
+        _query_ctx: QueryContextRef,
+    ) -> server_error::Result<ObjectResult> {
         let request = expr.request.context(server_error::InvalidQuerySnafu {
             reason: "empty expr",
         })?;
@@ -385,13 +541,15 @@ impl GrpcQueryHandler for DistInstance {
                     query: format!("{expr:?}"),
                 })?;
                 Ok(ObjectResultBuilder::new()
-                    .flight_data(vec![
-                        FlightEncoder::default().encode(FlightMessage::AffectedRows(1))
-                    ])
+                    .flight_data(FlightEncoder::default().encode(FlightMessage::AffectedRows(1)))
                     .build())
             }
             // TODO(LFC): Implement Flight for DistInstance.
             GrpcRequest::Query(_) | GrpcRequest::Insert(_) => unimplemented!(),
+            GrpcRequest::Diagnostic(_) => server_error::NotSupportedSnafu {
+                feat: "Diagnostic requests in Frontend",
+            }
+            .fail(),
         }
     }
 }
@@ -428,48 +586,24 @@ fn create_table_global_value(
             .push(route.region.id as u32);
     }
 
-    let mut column_schemas = Vec::with_capacity(create_table.column_defs.len());
-    let mut column_name_to_index_map = HashMap::new();
-
-    for (idx, column) in create_table.column_defs.iter().enumerate() {
-        let schema = column
-            .try_as_column_schema()
-            .context(error::InvalidColumnDefSnafu {
-                column: &column.name,
-            })?;
-        let schema = schema.with_time_index(column.name == create_table.time_index);
-
-        column_schemas.push(schema);
-        column_name_to_index_map.insert(column.name.clone(), idx);
-    }
-
-    let timestamp_index = column_name_to_index_map
-        .get(&create_table.time_index)
-        .cloned();
-
-    let raw_schema = RawSchema {
-        column_schemas: column_schemas.clone(),
-        timestamp_index,
-        version: 0,
-    };
-
+    // Builds the schema the same as datanodes, e.g. the time index is not nullable.
+    let schema = create_table_schema(create_table).context(error::InvalidCreateTableExprSnafu)?;
     let primary_key_indices = create_table
         .primary_keys
         .iter()
         .map(|name| {
-            column_name_to_index_map
-                .get(name)
-                .cloned()
+            schema
+                .column_index_by_name(name)
                 .context(PrimaryKeyNotFoundSnafu { msg: name })
         })
         .collect::<Result<Vec<_>>>()?;
 
     let meta = RawTableMeta {
-        schema: raw_schema,
+        schema: RawSchema::from(&*schema),
         primary_key_indices,
         value_indices: vec![],
         engine: "mito".to_string(),
-        next_column_id: column_schemas.len() as u32,
+        next_column_id: schema.num_columns() as u32,
         region_numbers: vec![],
         engine_options: HashMap::new(),
         options: HashMap::new(),
@@ -552,7 +686,7 @@ fn find_partition_entries(
                 let v = match v {
                     SqlValue::Number(n, _) if n == "MAXVALUE" => PartitionBound::MaxValue,
                     _ => PartitionBound::Value(
-                        sql_value_to_value(column_name, data_type, v)
+                        sql_value_to_value(column_name, data_type, v, None)
                             .context(error::ParseSqlSnafu)?,
                     ),
                 };
@@ -567,6 +701,26 @@ fn find_partition_entries(
     Ok(entries)
 }
 
+fn create_table_name(create_table: &CreateTableExpr) -> TableName {
+    let mut catalog_name = create_table.catalog_name.clone();
+    if catalog_name.is_empty() {
+        catalog_name = DEFAULT_CATALOG_NAME.to_string();
+    }
+    let mut schema_name = create_table.schema_name.clone();
+    if schema_name.is_empty() {
+        schema_name = DEFAULT_SCHEMA_NAME.to_string();
+    }
+    TableName::new(catalog_name, schema_name, create_table.table_name.clone())
+}
+
+fn table_global_key(table_name: &TableName) -> TableGlobalKey {
+    TableGlobalKey {
+        catalog_name: table_name.catalog_name.clone(),
+        schema_name: table_name.schema_name.clone(),
+        table_name: table_name.table_name.clone(),
+    }
+}
+
 fn find_partition_columns(
     create_table: &CreateTableExpr,
     partitions: &Option<Partitions>,
@@ -585,6 +739,7 @@ fn find_partition_columns(
 
 #[cfg(test)]
 mod test {
+    use datatypes::value::Value;
     use itertools::Itertools;
     use servers::query_handler::SqlQueryHandlerRef;
     use session::context::QueryContext;
@@ -596,6 +751,55 @@ mod test {
     use crate::expr_factory::{CreateExprFactory, DefaultCreateExprFactory};
     use crate::tests::create_dist_instance;
 
+    #[test]
+    fn test_create_table_info_with_semantic_types() {
+        use api::v1::{ColumnDataType, ColumnDef, SemanticType};
+        use meta_client::rpc::router::RegionRoute;
+        use meta_client::rpc::{Peer, Region, Table};
+
+        let column_def =
+            |name: &str, datatype: ColumnDataType, semantic_type: SemanticType| ColumnDef {
+                name: name.to_string(),
+                datatype: datatype as i32,
+                is_nullable: true,
+                default_constraint: vec![],
+                semantic_type: Some(semantic_type as i32),
+            };
+        let mut create_table = CreateTableExpr {
+            table_name: "demo".to_string(),
+            column_defs: vec![
+                column_def("host", ColumnDataType::String, SemanticType::Tag),
+                column_def("cpu", ColumnDataType::Float64, SemanticType::Field),
+                column_def(
+                    "ts",
+                    ColumnDataType::TimestampMillisecond,
+                    SemanticType::Timestamp,
+                ),
+            ],
+            ..Default::default()
+        };
+        normalize_create_expr(&mut create_table).unwrap();
+
+        let table_route = TableRoute {
+            table: Table {
+                id: 1024,
+                table_name: TableName::new(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo"),
+                table_schema: vec![],
+            },
+            region_routes: vec![RegionRoute {
+                region: Region::default(),
+                leader_peer: Some(Peer::new(1, "127.0.0.1:3001")),
+                follower_peers: vec![],
+            }],
+        };
+        let value = create_table_global_value(&create_table, &table_route).unwrap();
+        let meta = value.table_info.meta;
+        assert_eq!(Some(2), meta.schema.timestamp_index);
+        // The same as the time index of tables created by datanodes.
+        assert!(!meta.schema.column_schemas[2].is_nullable());
+        assert_eq!(vec![0], meta.primary_key_indices);
+    }
+
     #[tokio::test]
     async fn test_parse_partitions() {
         common_telemetry::init_default_ut_logging();
@@ -683,7 +887,7 @@ ENGINE=mito",
                 ]
                 .into_iter()
                 .join("\n");
-                let lines = r.pretty_print().unwrap();
+                let lines = r.pretty_print(None).unwrap();
                 assert!(lines == expected1 || lines == expected2)
             }
             _ => unreachable!(),
@@ -734,7 +938,7 @@ ENGINE=mito",
 +--------------+
 | dist_numbers |
 +--------------+"#;
-                    assert_eq!(r.pretty_print().unwrap(), expected);
+                    assert_eq!(r.pretty_print(None).unwrap(), expected);
                 }
                 _ => unreachable!(),
             }
@@ -747,4 +951,126 @@ ENGINE=mito",
             assert_show_tables(x.clone()).await
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_show_nodes() {
+        let (dist_instance, _) = create_dist_instance().await;
+
+        let output = dist_instance
+            .handle_sql("show nodes", QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let Output::RecordBatches(r) = output else { unreachable!() };
+        let batches = r.take();
+        assert_eq!(1, batches.len());
+        let batch = &batches[0];
+        assert_eq!(9, batch.num_columns());
+        assert_eq!(4, batch.num_rows());
+
+        let node_ids = batch.column(0);
+        let status = batch.column(2);
+        for (i, node_id) in (1..=4u64).enumerate() {
+            assert_eq!(Value::UInt64(node_id), node_ids.get(i));
+            assert_eq!(Value::from("ALIVE"), status.get(i));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_table_twice() {
+        let (dist_instance, _) = create_dist_instance().await;
+
+        let sql = "
+            CREATE TABLE dist_twice (
+                ts BIGINT,
+                n INT,
+                TIME INDEX (ts),
+            )
+            PARTITION BY RANGE COLUMNS (n) (
+                PARTITION r0 VALUES LESS THAN (10),
+                PARTITION r1 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+
+        let err = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .err()
+            .unwrap();
+        assert!(
+            matches!(err, error::Error::TableAlreadyExist { .. }),
+            "{err:?}"
+        );
+
+        let sql = "CREATE TABLE IF NOT EXISTS dist_twice (ts BIGINT, TIME INDEX (ts))";
+        let output = dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_create_table_with_leftovers() {
+        let (dist_instance, _) = create_dist_instance().await;
+
+        let sql = "
+            CREATE TABLE dist_leftovers (
+                ts BIGINT,
+                n INT,
+                TIME INDEX (ts),
+            )
+            PARTITION BY RANGE COLUMNS (n) (
+                PARTITION r0 VALUES LESS THAN (10),
+                PARTITION r1 VALUES LESS THAN (20),
+                PARTITION r2 VALUES LESS THAN (MAXVALUE),
+            )
+            ENGINE=mito";
+        dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+
+        // Removes the committed table global value, as if the creation failed before
+        // committing, leaving the regions on datanodes.
+        let table_name = TableName::new(
+            DEFAULT_CATALOG_NAME.to_string(),
+            DEFAULT_SCHEMA_NAME.to_string(),
+            "dist_leftovers".to_string(),
+        );
+        let key = table_global_key(&table_name).to_string();
+        dist_instance
+            .catalog_manager
+            .backend()
+            .delete(key.as_bytes())
+            .await
+            .unwrap();
+        assert!(dist_instance
+            .get_table_global_value(&table_name)
+            .await
+            .unwrap()
+            .is_none());
+
+        // The leftovers are dropped and the table is created again.
+        dist_instance
+            .handle_sql(sql, QueryContext::arc())
+            .await
+            .remove(0)
+            .unwrap();
+        let value = dist_instance
+            .get_table_global_value(&table_name)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("dist_leftovers", value.table_info.name);
+    }
 }
@@ -0,0 +1,199 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Two-phase creation of a table in distributed mode.
+//!
+//! The regions of the table are created on the datanodes first, then the table global
+//! value is committed to metasrv by compare-and-put, which makes the table visible. If
+//! any of them fails, the created regions and the table route allocated from metasrv
+//! are rolled back.
+//!
+//! A table on a datanode without a committed table global value is garbage, left by a
+//! failed creation that is not rolled back (e.g. the frontend crashed in the middle),
+//! or by a previous attempt whose response was lost. So it's dropped and created again,
+//! which also makes retrying the creation idempotent.
+
+use std::time::Duration;
+
+use api::v1::{CreateTableExpr, DropTableExpr};
+use catalog::helper::build_table_route_key;
+use client::Database;
+use common_error::status_code::StatusCode;
+use common_telemetry::{debug, warn};
+use meta_client::rpc::{DeleteRangeRequest, Peer, TableName, TableRoute};
+use snafu::ResultExt;
+
+use crate::error::{self, RequestDatanodeSnafu, Result};
+use crate::instance::distributed::DistInstance;
+
+/// Max attempts to create the regions on a datanode.
+const MAX_CREATE_ATTEMPTS: u32 = 3;
+const CREATE_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+impl DistInstance {
+    /// Creates the regions of the table on the datanodes of the `table_route`, and commits
+    /// the table global value when all of them are created. Rolls back the created regions
+    /// and the table route on failure.
+    pub(super) async fn create_table_in_datanodes(
+        &self,
+        create_table: &CreateTableExpr,
+        table_route: &TableRoute,
+    ) -> Result<()> {
+        let table_name = &table_route.table.table_name;
+        let mut prepared = Vec::new();
+        let result = self
+            .prepare_create_table(create_table, table_route, &mut prepared)
+            .await;
+        let result = match result {
+            Ok(()) => self.put_table_global_meta(create_table, table_route).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = &result {
+            warn!(
+                "Failed to create table {} with id {}, rolling back, error: {}",
+                table_name, table_route.table.id, e
+            );
+            // The table is created by another frontend concurrently, the regions on the
+            // datanodes belong to it now.
+            if !matches!(e, error::Error::TableAlreadyExist { .. }) {
+                self.drop_regions(table_name, &prepared).await;
+            }
+            self.remove_table_route(table_name, table_route.table.id)
+                .await;
+        }
+        result
+    }
+
+    /// Creates the regions on each datanode, the datanodes requested are pushed into
+    /// `prepared`, including the one that fails, whose result is unknown.
+    async fn prepare_create_table(
+        &self,
+        create_table: &CreateTableExpr,
+        table_route: &TableRoute,
+        prepared: &mut Vec<Peer>,
+    ) -> Result<()> {
+        let table_name = &table_route.table.table_name;
+        for datanode in table_route.find_leaders() {
+            let mut create_expr_for_region = create_table.clone();
+            create_expr_for_region.region_ids = table_route.find_leader_regions(&datanode);
+            // Always creates the table from scratch, leftovers of the same name are dropped.
+            create_expr_for_region.create_if_not_exists = false;
+
+            prepared.push(datanode.clone());
+            self.create_regions(table_name, &datanode, create_expr_for_region)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn create_regions(
+        &self,
+        table_name: &TableName,
+        datanode: &Peer,
+        expr: CreateTableExpr,
+    ) -> Result<()> {
+        let client = self.datanode_clients.get_client(datanode).await;
+        let client = Database::new("greptime", client);
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            debug!(
+                "Creating table {:?} on Datanode {:?} with regions {:?}, attempt {}",
+                expr, datanode, expr.region_ids, attempts,
+            );
+
+            let result = match client.create(expr.clone()).await {
+                Err(e) if is_table_already_exists(&e) => {
+                    // Checks the commit again in case the table was just created by
+                    // another frontend.
+                    if self.get_table_global_value(table_name).await?.is_some() {
+                        return error::TableAlreadyExistSnafu {
+                            table: table_name.to_string(),
+                        }
+                        .fail();
+                    }
+
+                    warn!(
+                        "Dropping leftover table {} on Datanode {:?}",
+                        table_name, datanode
+                    );
+                    match client.drop_table(drop_table_expr(table_name)).await {
+                        Ok(_) => client.create(expr.clone()).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                result => result,
+            };
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if e.is_unavailable() && attempts < MAX_CREATE_ATTEMPTS => {
+                    warn!(
+                        "Failed to create table {} on Datanode {:?}, retrying, error: {}",
+                        table_name, datanode, e
+                    );
+                    tokio::time::sleep(CREATE_RETRY_INTERVAL * attempts).await;
+                }
+                Err(e) => return Err(e).context(RequestDatanodeSnafu),
+            }
+        }
+    }
+
+    /// Drops the table on the datanodes, best effort.
+    async fn drop_regions(&self, table_name: &TableName, datanodes: &[Peer]) {
+        for datanode in datanodes {
+            let client = self.datanode_clients.get_client(datanode).await;
+            let client = Database::new("greptime", client);
+            // The table may be not created on the datanode at all, leftovers are
+            // cleaned up by the next creation of the same table anyway.
+            if let Err(e) = client.drop_table(drop_table_expr(table_name)).await {
+                warn!(
+                    "Failed to drop table {} on Datanode {:?} in rollback, error: {}",
+                    table_name, datanode, e
+                );
+            }
+        }
+    }
+
+    /// Removes the uncommitted table route from metasrv, best effort.
+    async fn remove_table_route(&self, table_name: &TableName, table_id: u64) {
+        let key = build_table_route_key(
+            &table_name.catalog_name,
+            &table_name.schema_name,
+            &table_name.table_name,
+            table_id,
+        );
+        let req = DeleteRangeRequest::new().with_key(key);
+        if let Err(e) = self.meta_client.delete_range(req).await {
+            warn!(
+                "Failed to remove route of table {} with id {} in rollback, error: {}",
+                table_name, table_id, e
+            );
+        }
+    }
+}
+
+fn is_table_already_exists(e: &client::Error) -> bool {
+    matches!(e, client::Error::Datanode { code, .. } if *code == StatusCode::TableAlreadyExists as u32)
+}
+
+fn drop_table_expr(table_name: &TableName) -> DropTableExpr {
+    DropTableExpr {
+        catalog_name: table_name.catalog_name.clone(),
+        schema_name: table_name.schema_name.clone(),
+        table_name: table_name.table_name.clone(),
+    }
+}
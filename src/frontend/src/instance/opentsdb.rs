@@ -24,8 +24,6 @@ use crate::instance::Instance;
 #[async_trait]
 impl OpentsdbProtocolHandler for Instance {
     async fn exec(&self, data_point: &DataPoint) -> server_error::Result<()> {
-        // TODO(LFC): Insert metrics in batch, then make OpentsdbLineProtocolHandler::exec received multiple data points, when
-        // metric table and tags can be created upon insertion.
         match self.mode {
             Mode::Standalone => {
                 self.insert_opentsdb_metric(data_point).await?;
@@ -42,6 +40,30 @@ impl OpentsdbProtocolHandler for Instance {
 
         Ok(())
     }
+
+    async fn exec_batch(&self, data_points: &[DataPoint]) -> server_error::Result<()> {
+        let inserts = DataPoint::to_grpc_inserts(data_points)?;
+        match self.mode {
+            Mode::Standalone => {
+                self.handle_inserts(inserts)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(server_error::ExecuteInsertSnafu {
+                        msg: "execute insert failed",
+                    })?;
+            }
+            Mode::Distributed => {
+                self.dist_insert(inserts)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(server_error::ExecuteInsertSnafu {
+                        msg: "execute insert failed",
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Instance {
@@ -135,7 +157,7 @@ mod tests {
         match output {
             Output::Stream(stream) => {
                 let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
-                let pretty_print = recordbatches.pretty_print().unwrap();
+                let pretty_print = recordbatches.pretty_print(None).unwrap();
                 let expected = vec![
                     "+---------------------+----------------+-------+-------+-------+",
                     "| greptime_timestamp  | greptime_value | tagk1 | tagk2 | tagk3 |",
@@ -152,4 +174,53 @@ mod tests {
             _ => unreachable!(),
         };
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_exec_batch() {
+        let (instance, _guard) = tests::create_frontend_instance("test_exec_batch").await;
+
+        let data_points = vec![
+            DataPoint::new(
+                "my_metric_2".to_string(),
+                1000,
+                1.0,
+                vec![("host".to_string(), "web01".to_string())],
+            ),
+            DataPoint::new("my_metric_3".to_string(), 1000, 2.0, vec![]),
+            DataPoint::new(
+                "my_metric_2".to_string(),
+                2000,
+                3.0,
+                vec![("host".to_string(), "web02".to_string())],
+            ),
+        ];
+        instance.exec_batch(&data_points).await.unwrap();
+
+        let output = instance
+            .do_query(
+                "select * from my_metric_2 order by greptime_timestamp",
+                Arc::new(QueryContext::new()),
+            )
+            .await
+            .remove(0)
+            .unwrap();
+        match output {
+            Output::Stream(stream) => {
+                let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+                let pretty_print = recordbatches.pretty_print(None).unwrap();
+                let expected = vec![
+                    "+---------------------+----------------+-------+",
+                    "| greptime_timestamp  | greptime_value | host  |",
+                    "+---------------------+----------------+-------+",
+                    "| 1970-01-01T00:00:01 | 1              | web01 |",
+                    "| 1970-01-01T00:00:02 | 3              | web02 |",
+                    "+---------------------+----------------+-------+",
+                ]
+                .into_iter()
+                .join("\n");
+                assert_eq!(pretty_print, expected);
+            }
+            _ => unreachable!(),
+        };
+    }
 }
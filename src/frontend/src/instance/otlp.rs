@@ -0,0 +1,58 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_error::prelude::BoxedError;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use servers::error::{self, Result as ServerResult};
+use servers::query_handler::OpenTelemetryProtocolHandler;
+use servers::{otlp, Mode};
+use snafu::ResultExt;
+
+use crate::instance::Instance;
+
+#[async_trait]
+impl OpenTelemetryProtocolHandler for Instance {
+    async fn write_metrics(
+        &self,
+        database: &str,
+        request: ExportMetricsServiceRequest,
+    ) -> ServerResult<()> {
+        let requests = otlp::to_grpc_insert_requests(database, request)?;
+        let tables = requests
+            .iter()
+            .map(|request| request.table_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match self.mode {
+            Mode::Standalone => {
+                self.handle_inserts(requests)
+                    .await
+                    .map_err(BoxedError::new)
+                    .with_context(|_| error::ExecuteInsertSnafu {
+                        msg: format!("OTLP metrics of tables: {tables}"),
+                    })?;
+            }
+            Mode::Distributed => {
+                self.dist_insert(requests)
+                    .await
+                    .map_err(BoxedError::new)
+                    .with_context(|_| error::ExecuteInsertSnafu {
+                        msg: format!("OTLP metrics of tables: {tables}"),
+                    })?;
+            }
+        }
+        Ok(())
+    }
+}
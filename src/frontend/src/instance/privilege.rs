@@ -0,0 +1,371 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Schema-level access control of the SQL statements.
+//!
+//! Users are granted the privileges on schemas by `GRANT`, which are stored in the
+//! catalog. The privileges are only checked if there is a user provider. The default
+//! user is the superuser, who has all privileges and is the only one allowed to manage
+//! the users, the databases and the privileges, and to show the nodes of the cluster.
+
+use std::sync::Arc;
+
+use api::v1::ddl_request::Expr as DdlExpr;
+use api::v1::object_expr::Request;
+use api::v1::query_request::Query;
+use api::v1::QueryRequest;
+use catalog::grant::{GrantRequest, Privilege};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use servers::auth::UserProviderRef;
+use servers::error as server_error;
+use session::context::{QueryContext, QueryContextRef, UserInfo, DEFAULT_USERNAME};
+use snafu::prelude::*;
+use sql::ast::{Ident, ObjectName};
+use sql::statements::grant::Grant;
+use sql::statements::statement::Statement;
+
+use crate::instance::{parse_stmt, Instance};
+
+/// A privilege required on a schema.
+#[derive(Debug, PartialEq, Eq)]
+struct Requirement {
+    privilege: Privilege,
+    catalog: String,
+    schema: String,
+}
+
+impl Instance {
+    /// Checks whether the user running the query has the privileges the statement
+    /// requires on the schemas it accesses.
+    pub(super) async fn check_privileges(
+        &self,
+        stmt: &Statement,
+        query_ctx: &QueryContextRef,
+    ) -> server_error::Result<()> {
+        let Some(user) = self.checked_user(query_ctx) else {
+            return Ok(());
+        };
+        self.check_requirements(user.username(), requirements(stmt, query_ctx))
+            .await
+    }
+
+    /// Checks whether the user issuing the gRPC `request` has the privileges it requires,
+    /// the statements of SQL queries are checked like the ones of the SQL protocols.
+    pub(super) async fn check_grpc_privileges(
+        &self,
+        request: &Request,
+        query_ctx: &QueryContextRef,
+    ) -> server_error::Result<()> {
+        let Some(user) = self.checked_user(query_ctx) else {
+            return Ok(());
+        };
+
+        let Request::Query(QueryRequest {
+            query: Some(Query::Sql(sql)),
+            schema_name,
+            ..
+        }) = request
+        else {
+            return self
+                .check_requirements(user.username(), grpc_requirements(request))
+                .await;
+        };
+
+        // The statements are resolved in the schema of the request.
+        let query_ctx = if schema_name.is_empty() {
+            query_ctx.clone()
+        } else {
+            let query_ctx = Arc::new(QueryContext::with_current_schema(schema_name.clone()));
+            query_ctx.set_current_user(UserInfo::clone(&user));
+            query_ctx
+        };
+        let stmts = parse_stmt(sql)
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteQuerySnafu { query: sql })?;
+        for stmt in &stmts {
+            self.check_requirements(user.username(), requirements(stmt, &query_ctx))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the user whose privileges should be checked, `None` if the query is
+    /// allowed to do anything.
+    fn checked_user(&self, query_ctx: &QueryContextRef) -> Option<Arc<UserInfo>> {
+        self.plugins.get::<UserProviderRef>()?;
+        // Queries without a user are issued internally.
+        let user = query_ctx.current_user()?;
+        (user.username() != DEFAULT_USERNAME).then_some(user)
+    }
+
+    async fn check_requirements(
+        &self,
+        user: &str,
+        requirements: Option<Vec<Requirement>>,
+    ) -> server_error::Result<()> {
+        let Some(requirements) = requirements else {
+            return server_error::PermissionDeniedSnafu {
+                user,
+                privilege: "SUPERUSER",
+                schema: "*",
+            }
+            .fail();
+        };
+        for requirement in requirements {
+            let privileges = self
+                .catalog_manager
+                .privileges(&requirement.catalog, &requirement.schema, user)
+                .await
+                .context(server_error::CatalogSnafu)?;
+            ensure!(
+                privileges.contains(&requirement.privilege),
+                server_error::PermissionDeniedSnafu {
+                    user,
+                    privilege: requirement.privilege.to_string(),
+                    schema: requirement.schema,
+                }
+            );
+        }
+        Ok(())
+    }
+
+    pub(super) async fn grant(&self, stmt: Grant) -> server_error::Result<Output> {
+        let request = GrantRequest {
+            catalog: stmt.catalog_name,
+            schema: stmt.schema_name,
+            user: stmt.user,
+            privileges: stmt.privileges,
+            revoke: stmt.revoke,
+        };
+        let query = format!("{request:?}");
+        let _ = self
+            .catalog_manager
+            .grant(request)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteQuerySnafu { query })?;
+        Ok(Output::AffectedRows(0))
+    }
+}
+
+/// Returns the privileges required by the statement, `None` if only the superuser is
+/// allowed to run it, including the queries whose tables can't be collected.
+fn requirements(stmt: &Statement, query_ctx: &QueryContextRef) -> Option<Vec<Requirement>> {
+    let current_schema = || {
+        query_ctx
+            .current_schema()
+            .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string())
+    };
+    let on_table = |privilege, name: &ObjectName| {
+        let (catalog, schema) = match &name.0[..] {
+            [catalog, schema, _] => (catalog.value.clone(), schema.value.clone()),
+            [schema, _] => (DEFAULT_CATALOG_NAME.to_string(), schema.value.clone()),
+            _ => (DEFAULT_CATALOG_NAME.to_string(), current_schema()),
+        };
+        Requirement {
+            privilege,
+            catalog,
+            schema,
+        }
+    };
+    let on_schema = |privilege, catalog: &str, schema: String| Requirement {
+        privilege,
+        catalog: catalog.to_string(),
+        schema,
+    };
+
+    let on_tables = |privilege, names: Vec<&ObjectName>| {
+        names
+            .into_iter()
+            .map(|name| on_table(privilege, name))
+            .collect::<Vec<_>>()
+    };
+
+    let requirements = match stmt {
+        Statement::Query(query) => on_tables(Privilege::Read, query.table_names()?),
+        Statement::Explain(explain) => on_tables(Privilege::Read, explain.table_names()?),
+        Statement::ShowTables(show) => {
+            let schema = show.database.clone().unwrap_or_else(current_schema);
+            vec![on_schema(Privilege::Read, DEFAULT_CATALOG_NAME, schema)]
+        }
+        Statement::ShowCreateTable(show) => {
+            let name = ObjectName(show.table_name.split('.').map(Ident::new).collect());
+            vec![on_table(Privilege::Read, &name)]
+        }
+        Statement::DescribeTable(describe) => vec![on_schema(
+            Privilege::Read,
+            &describe.catalog_name,
+            describe.schema_name.clone(),
+        )],
+        Statement::Insert(insert) => vec![on_table(Privilege::Write, insert.table_name())],
+        Statement::Delete(delete) => vec![on_table(Privilege::Write, &delete.table_name)],
+        Statement::Copy(copy) => vec![on_table(Privilege::Write, &copy.table_name)],
+        Statement::CopyTo(copy) => on_tables(Privilege::Read, copy.query.table_names()?),
+        Statement::CreateTable(create) => vec![on_table(Privilege::Ddl, &create.name)],
+        Statement::Alter(alter) => vec![on_table(Privilege::Ddl, alter.table_name())],
+        Statement::CreateTask(create) => {
+            let mut requirements = vec![
+                on_table(Privilege::Ddl, &create.name),
+                on_table(Privilege::Write, &create.sink_table),
+            ];
+            requirements.extend(on_tables(Privilege::Read, create.query.table_names()?));
+            requirements
+        }
+        Statement::DropTask(drop) => vec![on_table(Privilege::Ddl, &drop.name)],
+        Statement::Truncate(truncate) => vec![on_table(Privilege::Ddl, &truncate.table_name)],
+        Statement::DropTable(drop) => vec![on_schema(
+            Privilege::Ddl,
+            &drop.catalog_name,
+            drop.schema_name.clone(),
+        )],
+        Statement::ShowDatabases(_) | Statement::Use(_) | Statement::SetVariable(_) => vec![],
+        Statement::CreateDatabase(_)
+        | Statement::ShowNodes(_)
+        | Statement::CreateUser(_)
+        | Statement::DropUser(_)
+        | Statement::Grant(_) => return None,
+    };
+    Some(requirements)
+}
+
+/// Returns the privileges required by the gRPC `request` other than SQL queries, `None`
+/// if only the superuser is allowed to run it, including the logical plans whose tables
+/// can't be collected.
+fn grpc_requirements(request: &Request) -> Option<Vec<Requirement>> {
+    let on_schema = |privilege, catalog: &str, schema: &str| {
+        let catalog = if catalog.is_empty() {
+            DEFAULT_CATALOG_NAME
+        } else {
+            catalog
+        };
+        let schema = if schema.is_empty() {
+            DEFAULT_SCHEMA_NAME
+        } else {
+            schema
+        };
+        Requirement {
+            privilege,
+            catalog: catalog.to_string(),
+            schema: schema.to_string(),
+        }
+    };
+
+    let requirements = match request {
+        Request::Query(query) => match &query.query {
+            Some(Query::Sql(_)) | None => vec![],
+            Some(Query::LogicalPlan(_)) => return None,
+        },
+        Request::Insert(insert) => vec![on_schema(
+            Privilege::Write,
+            DEFAULT_CATALOG_NAME,
+            &insert.schema_name,
+        )],
+        Request::Ddl(ddl) => match ddl.expr.as_ref() {
+            Some(DdlExpr::CreateTable(expr)) => vec![on_schema(
+                Privilege::Ddl,
+                &expr.catalog_name,
+                &expr.schema_name,
+            )],
+            Some(DdlExpr::Alter(expr)) => vec![on_schema(
+                Privilege::Ddl,
+                &expr.catalog_name,
+                &expr.schema_name,
+            )],
+            Some(DdlExpr::DropTable(expr)) => vec![on_schema(
+                Privilege::Ddl,
+                &expr.catalog_name,
+                &expr.schema_name,
+            )],
+            Some(DdlExpr::CreateDatabase(_)) => return None,
+            None => vec![],
+        },
+        Request::Diagnostic(_) => return None,
+    };
+    Some(requirements)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use session::context::QueryContext;
+    use sql::dialect::GenericDialect;
+    use sql::parser::ParserContext;
+
+    use super::*;
+
+    fn requirements_of(sql: &str) -> Option<Vec<(Privilege, String)>> {
+        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0);
+        let query_ctx = Arc::new(QueryContext::with_current_schema("s".to_string()));
+        requirements(&stmt, &query_ctx).map(|requirements| {
+            requirements
+                .into_iter()
+                .map(|r| (r.privilege, format!("{}.{}", r.catalog, r.schema)))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_requirements() {
+        let read = |schema: &str| (Privilege::Read, format!("greptime.{schema}"));
+        assert_eq!(
+            Some(vec![read("s"), read("t")]),
+            requirements_of("SELECT * FROM a, t.b")
+        );
+        assert_eq!(Some(vec![]), requirements_of("SELECT 1"));
+        assert_eq!(Some(vec![read("s")]), requirements_of("SHOW TABLES"));
+        assert_eq!(
+            Some(vec![read("t")]),
+            requirements_of("SHOW CREATE TABLE t.a")
+        );
+        assert_eq!(
+            Some(vec![(Privilege::Write, "greptime.s".to_string())]),
+            requirements_of("INSERT INTO a VALUES (1)")
+        );
+        assert_eq!(
+            Some(vec![(Privilege::Ddl, "c.t".to_string())]),
+            requirements_of("ALTER TABLE c.t.a ADD COLUMN b INT")
+        );
+        assert_eq!(
+            Some(vec![(Privilege::Ddl, "greptime.t".to_string())]),
+            requirements_of("TRUNCATE TABLE t.a")
+        );
+        assert_eq!(
+            Some(vec![
+                (Privilege::Ddl, "greptime.s".to_string()),
+                (Privilege::Write, "greptime.t".to_string()),
+                read("s"),
+            ]),
+            requirements_of("CREATE TASK k EVERY '1m' INTO t.b AS SELECT max(v) FROM a")
+        );
+        assert_eq!(
+            Some(vec![read("t")]),
+            requirements_of("COPY (SELECT * FROM t.a) TO 'export/'")
+        );
+        assert_eq!(
+            Some(vec![(Privilege::Write, "greptime.s".to_string())]),
+            requirements_of("COPY a FROM 'import/a.csv'")
+        );
+        assert_eq!(None, requirements_of("CREATE DATABASE d"));
+        assert_eq!(None, requirements_of("GRANT ALL ON s TO u"));
+        assert_eq!(None, requirements_of("SHOW NODES"));
+        // Tables of table functions can't be collected.
+        assert_eq!(None, requirements_of("SELECT * FROM t.f(1)"));
+        assert_eq!(None, requirements_of("EXPLAIN SELECT * FROM t.f(1)"));
+    }
+}
@@ -25,6 +25,7 @@ use servers::error::{self, Result as ServerResult};
 use servers::prometheus::{self, Metrics};
 use servers::query_handler::{PrometheusProtocolHandler, PrometheusResponse};
 use servers::Mode;
+use session::context::QueryContext;
 use snafu::{OptionExt, ResultExt};
 
 use crate::instance::Instance;
@@ -61,7 +62,9 @@ fn negotiate_response_type(accepted_response_types: &[i32]) -> ServerResult<Resp
 }
 
 fn to_query_result(table_name: &str, object_result: RpcOutput) -> ServerResult<QueryResult> {
-    let RpcOutput::RecordBatches(recordbatches) = object_result else { unreachable!() };
+    let RpcOutput::RecordBatches(recordbatches) = object_result else {
+        unreachable!()
+    };
     Ok(QueryResult {
         timeseries: prometheus::recordbatches_to_timeseries(table_name, recordbatches)?,
     })
@@ -86,11 +89,12 @@ impl Instance {
             let query = ObjectExpr {
                 request: Some(Request::Query(QueryRequest {
                     query: Some(query_request::Query::Sql(sql.to_string())),
+                    ..Default::default()
                 })),
             };
             let object_result = self
                 .grpc_query_handler
-                .do_query(query)
+                .do_query(query, QueryContext::arc())
                 .await?
                 .try_into()
                 .map_err(BoxedError::new)
@@ -26,6 +26,7 @@ pub mod influxdb;
 pub mod instance;
 pub mod mysql;
 pub mod opentsdb;
+pub mod otlp;
 pub mod partitioning;
 pub mod postgres;
 pub mod prometheus;
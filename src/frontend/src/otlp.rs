@@ -0,0 +1,38 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+/// Options of the OTLP/gRPC metrics receiver, which is served by the gRPC server.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OtlpOptions {
+    pub enable: bool,
+}
+
+impl Default for OtlpOptions {
+    fn default() -> Self {
+        Self { enable: true }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OtlpOptions;
+
+    #[test]
+    fn test_otlp_options() {
+        let default = OtlpOptions::default();
+        assert!(default.enable);
+    }
+}
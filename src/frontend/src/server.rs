@@ -14,9 +14,11 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_runtime::Builder as RuntimeBuilder;
 use common_telemetry::info;
+use futures::future::try_join_all;
 use servers::auth::UserProviderRef;
 use servers::grpc::GrpcServer;
 use servers::http::HttpServer;
@@ -25,26 +27,29 @@ use servers::opentsdb::OpentsdbServer;
 use servers::postgres::PostgresServer;
 use servers::server::Server;
 use snafu::ResultExt;
-use tokio::try_join;
 
 use crate::error::{self, Result};
 use crate::frontend::FrontendOptions;
 use crate::influxdb::InfluxdbOptions;
 use crate::instance::FrontendInstance;
+use crate::otlp::OtlpOptions;
 use crate::prometheus::PrometheusOptions;
 
-pub(crate) struct Services;
+/// Servers of the frontend.
+pub(crate) struct Services {
+    servers: Vec<(Box<dyn Server>, SocketAddr)>,
+}
 
 impl Services {
-    pub(crate) async fn start<T>(
+    pub(crate) fn try_new<T>(
         opts: &FrontendOptions,
         instance: Arc<T>,
         user_provider: Option<UserProviderRef>,
-    ) -> Result<()>
+    ) -> Result<Self>
     where
         T: FrontendInstance,
     {
-        info!("Starting frontend servers");
+        let otlp_enabled = matches!(opts.otlp_options, Some(OtlpOptions { enable: true }));
         let grpc_server_and_addr = if let Some(opts) = &opts.grpc_options {
             let grpc_addr = parse_addr(&opts.addr)?;
 
@@ -56,7 +61,19 @@ impl Services {
                     .context(error::RuntimeResourceSnafu)?,
             );
 
-            let grpc_server = GrpcServer::new(instance.clone(), grpc_runtime);
+            let mut grpc_server = GrpcServer::new(instance.clone(), grpc_runtime);
+            grpc_server.set_tls_option(opts.tls.clone());
+            grpc_server.set_limit_options(&opts.limit);
+            grpc_server.set_slow_query_threshold(
+                (opts.slow_query_threshold_millis > 0)
+                    .then(|| Duration::from_millis(opts.slow_query_threshold_millis)),
+            );
+            if let Some(user_provider) = &user_provider {
+                grpc_server.set_user_provider(user_provider.clone());
+            }
+            if otlp_enabled {
+                grpc_server.set_otlp_handler(instance.clone());
+            }
 
             Some((Box::new(grpc_server) as _, grpc_addr))
         } else {
@@ -152,35 +169,52 @@ impl Services {
                 http_server.set_prom_handler(instance.clone());
             }
             http_server.set_script_handler(instance.clone());
+            http_server.set_readiness_handler(instance.clone());
 
             Some((Box::new(http_server) as _, http_addr))
         } else {
             None
         };
 
-        try_join!(
-            start_server(http_server_and_addr),
-            start_server(grpc_server_and_addr),
-            start_server(mysql_server_and_addr),
-            start_server(postgres_server_and_addr),
-            start_server(opentsdb_server_and_addr)
-        )
+        let servers = [
+            http_server_and_addr,
+            grpc_server_and_addr,
+            mysql_server_and_addr,
+            postgres_server_and_addr,
+            opentsdb_server_and_addr,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Ok(Self { servers })
+    }
+
+    /// Starts all servers, returns after all of them are shutdown.
+    pub(crate) async fn start(&self) -> Result<()> {
+        info!("Starting frontend servers");
+        try_join_all(self.servers.iter().map(|(server, addr)| {
+            info!("Starting server at {}", addr);
+            server.start(*addr)
+        }))
+        .await
         .context(error::StartServerSnafu)?;
         Ok(())
     }
+
+    /// Shutdown all servers, they stop accepting new connections but requests in
+    /// flight are still served.
+    pub(crate) async fn shutdown(&self) -> Result<()> {
+        for (server, addr) in &self.servers {
+            info!("Shutting down server at {}", addr);
+            server
+                .shutdown()
+                .await
+                .context(error::ShutdownServerSnafu)?;
+        }
+        Ok(())
+    }
 }
 
 fn parse_addr(addr: &str) -> Result<SocketAddr> {
     addr.parse().context(error::ParseAddrSnafu { addr })
 }
-
-async fn start_server(
-    server_and_addr: Option<(Box<dyn Server>, SocketAddr)>,
-) -> servers::error::Result<Option<SocketAddr>> {
-    if let Some((server, addr)) = server_and_addr {
-        info!("Starting server at {}", addr);
-        server.start(addr).await.map(Some)
-    } else {
-        Ok(None)
-    }
-}
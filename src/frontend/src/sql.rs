@@ -14,12 +14,13 @@
 
 use catalog::SchemaProviderRef;
 use common_error::snafu::ensure;
+use common_time::TimeZone;
 use datatypes::data_type::DataType;
-use datatypes::prelude::{ConcreteDataType, MutableVector};
+use datatypes::prelude::MutableVector;
+use datatypes::schema::ColumnSchema;
 use snafu::{OptionExt, ResultExt};
-use sql::ast::Value as SqlValue;
-use sql::statements;
-use sql::statements::insert::Insert;
+use sql::ast::Expr;
+use sql::statements::insert::{self, Insert};
 use table::requests::InsertRequest;
 
 use crate::error::{self, BuildVectorSnafu, Result};
@@ -29,9 +30,10 @@ use crate::error::{self, BuildVectorSnafu, Result};
 pub(crate) fn insert_to_request(
     schema_provider: &SchemaProviderRef,
     stmt: Insert,
+    time_zone: Option<&TimeZone>,
 ) -> Result<InsertRequest> {
     let columns = stmt.columns();
-    let values = stmt.values().context(error::ParseSqlSnafu)?;
+    let rows = stmt.rows().context(error::ParseSqlSnafu)?;
     let (catalog_name, schema_name, table_name) =
         stmt.full_table_name().context(error::ParseSqlSnafu)?;
 
@@ -47,19 +49,15 @@ pub(crate) fn insert_to_request(
     } else {
         columns.len()
     };
-    let rows_num = values.len();
+    let rows_num = rows.len();
 
-    let mut columns_builders: Vec<(&String, &ConcreteDataType, Box<dyn MutableVector>)> =
+    let mut columns_builders: Vec<(&ColumnSchema, Box<dyn MutableVector>)> =
         Vec::with_capacity(columns_num);
 
     if columns.is_empty() {
         for column_schema in schema.column_schemas() {
             let data_type = &column_schema.data_type;
-            columns_builders.push((
-                &column_schema.name,
-                data_type,
-                data_type.create_mutable_vector(rows_num),
-            ));
+            columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
         }
     } else {
         for column_name in columns {
@@ -70,15 +68,11 @@ pub(crate) fn insert_to_request(
                 }
             })?;
             let data_type = &column_schema.data_type;
-            columns_builders.push((
-                column_name,
-                data_type,
-                data_type.create_mutable_vector(rows_num),
-            ));
+            columns_builders.push((column_schema, data_type.create_mutable_vector(rows_num)));
         }
     }
 
-    for row in values {
+    for (row_index, row) in rows.iter().enumerate() {
         ensure!(
             row.len() == columns_num,
             error::ColumnValuesNumberMismatchSnafu {
@@ -87,10 +81,8 @@ pub(crate) fn insert_to_request(
             }
         );
 
-        for (sql_val, (column_name, data_type, builder)) in
-            row.iter().zip(columns_builders.iter_mut())
-        {
-            add_row_to_vector(column_name, data_type, sql_val, builder)?;
+        for (expr, (column_schema, builder)) in row.iter().zip(columns_builders.iter_mut()) {
+            add_row_to_vector(row_index, column_schema, expr, builder, time_zone)?;
         }
     }
 
@@ -100,18 +92,19 @@ pub(crate) fn insert_to_request(
         table_name,
         columns_values: columns_builders
             .into_iter()
-            .map(|(c, _, mut b)| (c.to_owned(), b.to_vector()))
+            .map(|(c, mut b)| (c.name.clone(), b.to_vector()))
             .collect(),
     })
 }
 
 fn add_row_to_vector(
-    column_name: &str,
-    data_type: &ConcreteDataType,
-    sql_val: &SqlValue,
+    row_index: usize,
+    column_schema: &ColumnSchema,
+    expr: &Expr,
     builder: &mut Box<dyn MutableVector>,
+    time_zone: Option<&TimeZone>,
 ) -> Result<()> {
-    let value = statements::sql_value_to_value(column_name, data_type, sql_val)
+    let value = insert::value_of_column(row_index, column_schema, expr, time_zone)
         .context(error::ParseSqlSnafu)?;
     builder
         .push_value_ref(value.as_value_ref())
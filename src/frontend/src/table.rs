@@ -28,7 +28,11 @@ use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef};
 use common_recordbatch::adapter::AsyncRecordBatchStreamAdapter;
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 use common_telemetry::debug;
+use datafusion::arrow::compute::{self, SortOptions};
+use datafusion::arrow::record_batch::RecordBatch as DfRecordBatch;
 use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::expressions::PhysicalSortExpr;
+use datafusion::physical_plan::memory::MemoryStream;
 use datafusion::physical_plan::{
     Partitioning, SendableRecordBatchStream as DfSendableRecordBatchStream,
 };
@@ -39,10 +43,12 @@ use datatypes::prelude::Value;
 use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use meta_client::rpc::{Peer, TableName};
 use snafu::prelude::*;
-use store_api::storage::RegionNumber;
+use store_api::storage::{RegionNumber, ScanOrder};
 use table::error::Error as TableError;
 use table::metadata::{FilterPushDownType, TableInfoRef};
+use table::predicate::ColumnFilter;
 use table::requests::InsertRequest;
+use table::table::scan::time_index_ordering;
 use table::Table;
 use tokio::sync::RwLock;
 
@@ -89,7 +95,9 @@ impl Table for DistTable {
         let inserts = spliter.split(request).map_err(TableError::new)?;
 
         let output = self.dist_insert(inserts).await.map_err(TableError::new)?;
-        let RpcOutput::AffectedRows(rows) = output else { unreachable!() };
+        let RpcOutput::AffectedRows(rows) = output else {
+            unreachable!()
+        };
         Ok(rows)
     }
 
@@ -99,6 +107,78 @@ impl Table for DistTable {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> table::Result<PhysicalPlanRef> {
+        self.scan_partitions(projection, filters, limit, None).await
+    }
+
+    async fn scan_ordered(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: ScanOrder,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.scan_partitions(projection, filters, limit, Some(order))
+            .await
+    }
+
+    fn supports_filter_pushdown(&self, filter: &Expr) -> table::Result<FilterPushDownType> {
+        // Datanodes remove rows not matching filters on the row key before the limit.
+        let meta = &self.table_info.meta;
+        if ColumnFilter::is_row_key_filter(filter, &meta.schema, &meta.primary_key_indices) {
+            Ok(FilterPushDownType::Exact)
+        } else {
+            Ok(FilterPushDownType::Inexact)
+        }
+    }
+
+    fn scan_in_time_index_order(&self) -> bool {
+        // Datanodes sort rows by the time index for an ordered scan, the scan merges
+        // them in order.
+        true
+    }
+}
+
+impl DistTable {
+    pub(crate) fn new(
+        table_name: TableName,
+        table_info: TableInfoRef,
+        table_routes: Arc<TableRoutes>,
+        datanode_clients: Arc<DatanodeClients>,
+    ) -> Self {
+        Self {
+            table_name,
+            table_info,
+            table_routes,
+            datanode_clients,
+        }
+    }
+
+    async fn scan_partitions(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: Option<ScanOrder>,
+    ) -> table::Result<PhysicalPlanRef> {
+        let schema = project_schema(self.schema(), projection);
+        // Datanodes return rows sorted by the time index for an ordered scan or if the
+        // table has no primary key, the scan merges them in order.
+        let time_index = if order.is_some() || self.table_info.meta.primary_key_indices.is_empty() {
+            schema.timestamp_index()
+        } else {
+            None
+        };
+        if order.is_some() && time_index.is_none() {
+            return table::error::UnsupportedSnafu {
+                operation: "scan_ordered",
+                table_name: &self.table_name.table_name,
+            }
+            .fail()
+            .map_err(Into::into);
+        }
+        let descending = order.map(|order| order.is_desc()).unwrap_or(false);
+        let output_ordering = time_index.and_then(|_| time_index_ordering(&schema, descending));
+
         let partition_rule = self.find_partition_rule().await.map_err(TableError::new)?;
 
         let regions = self
@@ -122,37 +202,21 @@ impl Table for DistTable {
                 projection: projection.cloned(),
                 filters: filters.to_vec(),
                 limit,
+                order,
                 batches: Arc::new(RwLock::new(None)),
             }));
         }
 
         let dist_scan = DistTableScan {
-            schema: project_schema(self.schema(), projection),
+            schema,
             partition_execs,
+            time_index,
+            descending,
+            output_ordering,
         };
         Ok(Arc::new(dist_scan))
     }
 
-    fn supports_filter_pushdown(&self, _filter: &Expr) -> table::Result<FilterPushDownType> {
-        Ok(FilterPushDownType::Inexact)
-    }
-}
-
-impl DistTable {
-    pub(crate) fn new(
-        table_name: TableName,
-        table_info: TableInfoRef,
-        table_routes: Arc<TableRoutes>,
-        datanode_clients: Arc<DatanodeClients>,
-    ) -> Self {
-        Self {
-            table_name,
-            table_info,
-            table_routes,
-            datanode_clients,
-        }
-    }
-
     // TODO(LFC): Finding regions now seems less efficient, should be further looked into.
     fn find_regions(
         &self,
@@ -418,6 +482,13 @@ fn reverse_operator(op: &Operator) -> Operator {
 struct DistTableScan {
     schema: SchemaRef,
     partition_execs: Vec<Arc<PartitionExec>>,
+    /// Index of the time index column if the rows from datanodes are sorted by it. The
+    /// scan has only one partition that merges the rows from all datanodes in order then.
+    time_index: Option<usize>,
+    /// Whether the rows are sorted by the time index in descending order.
+    descending: bool,
+    /// Order of the time index if the rows are sorted by it.
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
 }
 
 impl PhysicalPlan for DistTableScan {
@@ -430,7 +501,15 @@ impl PhysicalPlan for DistTableScan {
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(self.partition_execs.len())
+        if self.time_index.is_some() {
+            Partitioning::UnknownPartitioning(1)
+        } else {
+            Partitioning::UnknownPartitioning(self.partition_execs.len())
+        }
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_ordering.as_deref()
     }
 
     fn children(&self) -> Vec<PhysicalPlanRef> {
@@ -446,6 +525,32 @@ impl PhysicalPlan for DistTableScan {
         partition: usize,
         _context: Arc<TaskContext>,
     ) -> QueryResult<SendableRecordBatchStream> {
+        if let Some(time_index) = self.time_index {
+            let partition_execs = self.partition_execs.clone();
+            let schema = self.schema.clone();
+            let descending = self.descending;
+            let stream = Box::pin(async move {
+                // Requests all datanodes concurrently.
+                futures::future::try_join_all(partition_execs.iter().map(|x| x.maybe_init()))
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+                let mut batches = Vec::new();
+                for exec in partition_execs.iter() {
+                    batches.extend(
+                        exec.take_batches()
+                            .await
+                            .take()
+                            .into_iter()
+                            .map(|x| x.into_df_record_batch()),
+                    );
+                }
+                merge_sorted_batches(&schema, &batches, time_index, descending)
+            });
+            let stream = AsyncRecordBatchStreamAdapter::new(self.schema(), stream);
+            return Ok(Box::pin(stream));
+        }
+
         let exec = self.partition_execs[partition].clone();
         let stream = Box::pin(async move {
             exec.maybe_init()
@@ -465,6 +570,7 @@ struct PartitionExec {
     projection: Option<Vec<usize>>,
     filters: Vec<Expr>,
     limit: Option<usize>,
+    order: Option<ScanOrder>,
     batches: Arc<RwLock<Option<RecordBatches>>>,
 }
 
@@ -484,6 +590,7 @@ impl PartitionExec {
             projection: self.projection.clone(),
             filters: self.filters.clone(),
             limit: self.limit,
+            order: self.order,
         };
         let result = self.datanode_instance.grpc_table_scan(plan).await?;
         let _ = batches.insert(result);
@@ -492,14 +599,47 @@ impl PartitionExec {
 
     /// Notice: the record batch will be consumed.
     async fn as_stream(&self) -> std::result::Result<DfSendableRecordBatchStream, DataFusionError> {
+        Ok(self.take_batches().await.into_df_stream())
+    }
+
+    /// Notice: the record batch will be consumed.
+    async fn take_batches(&self) -> RecordBatches {
         let mut batches = self.batches.write().await;
-        Ok(batches
+        batches
             .take()
             .expect("should have been initialized in \"maybe_init\"")
-            .into_df_stream())
     }
 }
 
+/// Merges `batches` into one batch sorted by the column `time_index`, in descending
+/// order if `descending` is true.
+///
+/// The rows from datanodes are already in memory, so they are simply sorted instead
+/// of merged batch by batch.
+fn merge_sorted_batches(
+    schema: &SchemaRef,
+    batches: &[DfRecordBatch],
+    time_index: usize,
+    descending: bool,
+) -> std::result::Result<DfSendableRecordBatchStream, DataFusionError> {
+    let arrow_schema = schema.arrow_schema().clone();
+    let batch = compute::concat_batches(&arrow_schema, batches)?;
+    let options = SortOptions {
+        descending,
+        nulls_first: descending,
+    };
+    let indices = compute::sort_to_indices(batch.column(time_index), Some(options), None)?;
+    let columns = batch
+        .columns()
+        .iter()
+        .map(|column| compute::take(column.as_ref(), &indices, None))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let batch = DfRecordBatch::try_new(arrow_schema.clone(), columns)?;
+
+    let stream = MemoryStream::try_new(vec![batch], arrow_schema, None)?;
+    Ok(Box::pin(stream))
+}
+
 #[cfg(test)]
 mod test {
     use api::v1::column::SemanticType;
@@ -515,11 +655,10 @@ mod test {
     use datafusion_expr::expr_fn::{and, binary_expr, col, or};
     use datafusion_expr::lit;
     use datanode::instance::Instance;
-    use datatypes::arrow::compute::SortOptions;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, Schema};
     use itertools::Itertools;
-    use meta_client::client::MetaClient;
+    use meta_client::client::{MetaClient, MetaClientBuilder, RouteCacheConfig};
     use meta_client::rpc::router::RegionRoute;
     use meta_client::rpc::{Region, Table, TableRoute};
     use sql::parser::ParserContext;
@@ -553,7 +692,11 @@ mod test {
             .build()
             .unwrap();
 
-        let table_routes = Arc::new(TableRoutes::new(Arc::new(MetaClient::default())));
+        let meta_client = MetaClientBuilder::new(0, 0)
+            .enable_router()
+            .route_cache(RouteCacheConfig::default())
+            .build();
+        let table_routes = Arc::new(TableRoutes::new(Arc::new(meta_client)));
         let table = DistTable {
             table_name: table_name.clone(),
             table_info: Arc::new(table_info),
@@ -849,6 +992,94 @@ mod test {
         exec_table_scan(table.clone(), projection, filters, 4, expected_output).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dist_table_scan_in_time_index_order() {
+        common_telemetry::init_default_ut_logging();
+        let table = Arc::new(new_dist_table().await);
+        assert!(table.scan_in_time_index_order());
+
+        // Rows from all regions are merged into one partition in time index order.
+        // select ts, a from numbers where a >= 10
+        let projection = Some(vec![0, 1]);
+        let filters = vec![binary_expr(col("a"), Operator::GtEq, lit(10)).into()];
+        let table_scan = table
+            .scan(projection.as_ref(), filters.as_slice(), None)
+            .await
+            .unwrap();
+        assert_eq!(table_scan.output_partitioning().partition_count(), 1);
+        let ordering = table_scan.output_ordering().unwrap();
+        assert_eq!("ts@0", ordering[0].expr.to_string());
+
+        let session_ctx = SessionContext::new();
+        let stream = table_scan.execute(0, session_ctx.task_ctx()).unwrap();
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected_output = vec![
+            "+----+-----+",
+            "| ts | a   |",
+            "+----+-----+",
+            "| 6  | 10  |",
+            "| 7  | 11  |",
+            "| 8  | 12  |",
+            "| 9  | 13  |",
+            "| 10 | 14  |",
+            "| 11 | 30  |",
+            "| 12 | 31  |",
+            "| 13 | 32  |",
+            "| 14 | 33  |",
+            "| 15 | 34  |",
+            "| 16 | 100 |",
+            "| 17 | 101 |",
+            "| 18 | 102 |",
+            "| 19 | 103 |",
+            "| 20 | 104 |",
+            "+----+-----+",
+        ]
+        .into_iter()
+        .join("\n");
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected_output);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dist_table_scan_ordered() {
+        common_telemetry::init_default_ut_logging();
+        let table = Arc::new(new_dist_table().await);
+
+        // Each datanode only returns its latest rows, which are merged in descending order.
+        // select ts, a from numbers where a >= 10 order by ts desc limit 3
+        let projection = Some(vec![0, 1]);
+        let filters = vec![binary_expr(col("a"), Operator::GtEq, lit(10)).into()];
+        let table_scan = table
+            .scan_ordered(
+                projection.as_ref(),
+                filters.as_slice(),
+                Some(3),
+                ScanOrder::Desc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(table_scan.output_partitioning().partition_count(), 1);
+        let ordering = table_scan.output_ordering().unwrap();
+        assert_eq!("ts@0", ordering[0].expr.to_string());
+        assert!(ordering[0].options.descending);
+
+        let session_ctx = SessionContext::new();
+        let stream = table_scan.execute(0, session_ctx.task_ctx()).unwrap();
+        let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+        let expected_output = vec![
+            "+----+-----+",
+            "| ts | a   |",
+            "+----+-----+",
+            "| 20 | 104 |",
+            "| 19 | 103 |",
+            "| 18 | 102 |",
+            "+----+-----+",
+        ]
+        .into_iter()
+        .join("\n");
+        let first_rows = recordbatches.pages(3).next().unwrap();
+        assert_eq!(first_rows.pretty_print(None).unwrap(), expected_output);
+    }
+
     async fn exec_table_scan(
         table: TableRef,
         projection: Option<Vec<usize>>,
@@ -885,12 +1116,13 @@ mod test {
         let stream = Box::pin(RecordBatchStreamAdapter::try_new(stream).unwrap());
 
         let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
-        assert_eq!(recordbatches.pretty_print().unwrap(), expected_output);
+        assert_eq!(recordbatches.pretty_print(None).unwrap(), expected_output);
     }
 
     async fn new_dist_table() -> DistTable {
         let column_schemas = vec![
-            ColumnSchema::new("ts", ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new("ts", ConcreteDataType::int64_datatype(), false)
+                .with_time_index(true),
             ColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
             ColumnSchema::new("row_id", ConcreteDataType::int32_datatype(), true),
         ];
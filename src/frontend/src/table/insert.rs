@@ -19,6 +19,7 @@ use api::helper::ColumnDataTypeWrapper;
 use api::v1::column::SemanticType;
 use api::v1::{Column, InsertRequest as GrpcInsertRequest};
 use client::{Database, RpcOutput};
+use common_telemetry::logging;
 use datatypes::prelude::ConcreteDataType;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::RegionNumber;
@@ -62,16 +63,45 @@ impl DistTable {
                     .context(error::RequestDatanodeSnafu)
             });
 
-            joins.push(join);
+            joins.push((region_id, join));
         }
 
+        // Waits for all sub-inserts even if some of them fail, so the error tells which
+        // regions are not written.
+        let total_regions = joins.len();
         let mut success = 0;
-        for join in joins {
-            let object_result = join.await.context(error::JoinTaskSnafu)??;
-            let RpcOutput::AffectedRows(rows) = object_result else { unreachable!() };
-            success += rows;
+        let mut failed_regions = Vec::new();
+        let mut first_error = None;
+        for (region_id, join) in joins {
+            match join.await.context(error::JoinTaskSnafu).and_then(|r| r) {
+                Ok(object_result) => {
+                    let RpcOutput::AffectedRows(rows) = object_result else { unreachable!() };
+                    success += rows;
+                }
+                Err(e) => {
+                    logging::error!(e; "Failed to insert into region {} of table {}", region_id, self.table_name);
+                    failed_regions.push(region_id);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        // The route may be stale if the leader of a region is moved, so it's fetched
+        // again on next insertion.
+        if first_error.is_some() {
+            self.table_routes.invalidate_route(&self.table_name).await;
+        }
+
+        match first_error {
+            None => Ok(RpcOutput::AffectedRows(success)),
+            // Nothing is inserted, returns the error as is.
+            Some(e) if failed_regions.len() == total_regions => Err(e),
+            Some(e) => Err(Box::new(e)).context(error::PartialInsertSnafu {
+                failed_regions,
+                total_regions,
+                affected_rows: success,
+            }),
         }
-        Ok(RpcOutput::AffectedRows(success))
     }
 }
 
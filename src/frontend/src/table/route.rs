@@ -13,59 +13,38 @@
 // limitations under the License.
 
 use std::sync::Arc;
-use std::time::Duration;
 
 use meta_client::client::MetaClient;
-use meta_client::rpc::{RouteRequest, TableName, TableRoute};
-use moka::future::{Cache, CacheBuilder};
-use snafu::{ensure, ResultExt};
+use meta_client::rpc::{TableName, TableRoute};
+use snafu::{OptionExt, ResultExt};
 
 use crate::error::{self, Result};
 
+/// Routes of tables, which are cached by the route cache of the meta client if it's
+/// enabled.
 pub(crate) struct TableRoutes {
     meta_client: Arc<MetaClient>,
-    cache: Cache<TableName, Arc<TableRoute>>,
 }
 
 impl TableRoutes {
     pub(crate) fn new(meta_client: Arc<MetaClient>) -> Self {
-        Self {
-            meta_client,
-            cache: CacheBuilder::new(1024)
-                .time_to_live(Duration::from_secs(30 * 60))
-                .time_to_idle(Duration::from_secs(5 * 60))
-                .build(),
-        }
+        Self { meta_client }
     }
 
     pub(crate) async fn get_route(&self, table_name: &TableName) -> Result<Arc<TableRoute>> {
-        self.cache
-            .try_get_with_by_ref(table_name, self.get_from_meta(table_name))
+        self.meta_client
+            .table_route(table_name)
             .await
-            .map_err(|e| {
-                error::GetCacheSnafu {
-                    err_msg: format!("{e:?}"),
-                }
-                .build()
+            .context(error::RequestMetaSnafu)?
+            .context(error::FindTableRoutesSnafu {
+                table_name: table_name.to_string(),
             })
     }
 
-    async fn get_from_meta(&self, table_name: &TableName) -> Result<Arc<TableRoute>> {
-        let mut resp = self
-            .meta_client
-            .route(RouteRequest {
-                table_names: vec![table_name.clone()],
-            })
-            .await
-            .context(error::RequestMetaSnafu)?;
-        ensure!(
-            !resp.table_routes.is_empty(),
-            error::FindTableRoutesSnafu {
-                table_name: table_name.to_string()
-            }
-        );
-        let route = resp.table_routes.swap_remove(0);
-        Ok(Arc::new(route))
+    /// Drops the cached route of the table, the route is fetched from metasrv again
+    /// on next access.
+    pub(crate) async fn invalidate_route(&self, table_name: &TableName) {
+        self.meta_client.invalidate_table_route(table_name).await
     }
 
     #[cfg(test)]
@@ -74,6 +53,8 @@ impl TableRoutes {
         table_name: TableName,
         table_route: Arc<TableRoute>,
     ) {
-        self.cache.insert(table_name, table_route).await
+        self.meta_client
+            .cache_table_route(table_name, table_route)
+            .await
     }
 }
@@ -20,9 +20,10 @@ use client::{Database, RpcOutput};
 use common_query::prelude::Expr;
 use common_recordbatch::RecordBatches;
 use datafusion::datasource::DefaultTableSource;
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder};
+use datafusion_expr::{col, LogicalPlan, LogicalPlanBuilder};
 use meta_client::rpc::TableName;
 use snafu::ResultExt;
+use store_api::storage::ScanOrder;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::table::adapter::DfTableProviderAdapter;
 use table::TableRef;
@@ -93,6 +94,16 @@ impl DatanodeInstance {
                 .context(error::BuildDfLogicalPlanSnafu)?;
         }
 
+        let schema = self.table.schema();
+        if let (Some(order), Some(time_index)) = (table_scan.order, schema.timestamp_column()) {
+            // Sorts rows before the limit, so each datanode only returns the first rows
+            // in the order.
+            let desc = order.is_desc();
+            builder = builder
+                .sort(vec![col(&time_index.name).sort(!desc, desc)])
+                .context(error::BuildDfLogicalPlanSnafu)?;
+        }
+
         if table_scan.limit.is_some() {
             builder = builder
                 .limit(0, table_scan.limit)
@@ -109,4 +120,6 @@ pub(crate) struct TableScanPlan {
     pub projection: Option<Vec<usize>>,
     pub filters: Vec<Expr>,
     pub limit: Option<usize>,
+    /// Order of the time index to sort rows in before the limit, if any.
+    pub order: Option<ScanOrder>,
 }
@@ -22,7 +22,7 @@ use common_grpc::channel_manager::ChannelManager;
 use common_runtime::Builder as RuntimeBuilder;
 use datanode::datanode::{DatanodeOptions, ObjectStoreConfig};
 use datanode::instance::Instance as DatanodeInstance;
-use meta_client::client::MetaClientBuilder;
+use meta_client::client::{MetaClientBuilder, RouteCacheConfig};
 use meta_client::rpc::Peer;
 use meta_srv::metasrv::MetaSrvOptions;
 use meta_srv::mocks::MockInfo;
@@ -195,7 +195,9 @@ pub(crate) async fn create_dist_instance() -> (DistInstance, HashMap<u64, Arc<Da
     let mut meta_client = MetaClientBuilder::new(1000, 0)
         .enable_router()
         .enable_store()
+        .enable_cluster()
         .channel_manager(channel_manager)
+        .route_cache(RouteCacheConfig::default())
         .build();
     meta_client.start(&[&server_addr]).await.unwrap();
     let meta_client = Arc::new(meta_client);
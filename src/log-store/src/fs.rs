@@ -19,6 +19,7 @@ mod chunk;
 pub mod config;
 mod crc;
 mod entry;
+pub mod fault;
 mod file;
 mod file_name;
 mod index;
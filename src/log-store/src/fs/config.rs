@@ -14,12 +14,30 @@
 
 use std::time::Duration;
 
+/// How appended entries are persisted to log files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Writes and syncs each entry separately.
+    Sync,
+    /// Writes entries appended concurrently in one write and syncs them once.
+    #[default]
+    Group,
+    /// Writes entries without syncing, entries written might be lost if the host crashes.
+    Async,
+}
+
 #[derive(Debug, Clone)]
 pub struct LogConfig {
     pub append_buffer_size: usize,
     pub max_log_file_size: usize,
     pub log_file_dir: String,
     pub gc_interval: Duration,
+    pub sync_mode: SyncMode,
+    /// Time to wait for more entries to commit together in [SyncMode::Group], entries
+    /// already pending are committed together even if it's zero.
+    pub group_commit_window: Duration,
+    /// Max bytes of entries to commit together in [SyncMode::Group].
+    pub group_commit_max_bytes: usize,
 }
 
 impl Default for LogConfig {
@@ -31,6 +49,9 @@ impl Default for LogConfig {
             max_log_file_size: 1024 * 1024 * 1024,
             log_file_dir: "/tmp/greptimedb".to_string(),
             gc_interval: Duration::from_secs(10 * 60),
+            sync_mode: SyncMode::Group,
+            group_commit_window: Duration::ZERO,
+            group_commit_max_bytes: 1024 * 1024,
         }
     }
 }
@@ -49,5 +70,7 @@ mod tests {
         assert_eq!(1024 * 1024 * 1024, default.max_log_file_size);
         assert_eq!(128, default.append_buffer_size);
         assert_eq!(Duration::from_secs(600), default.gc_interval);
+        assert_eq!(SyncMode::Group, default.sync_mode);
+        assert_eq!(Duration::ZERO, default.group_commit_window);
     }
 }
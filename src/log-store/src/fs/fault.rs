@@ -0,0 +1,263 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A log store wrapper injecting faults into appends, for testing the crash consistency
+//! of components built on log stores.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use bytes::BytesMut;
+use store_api::logstore::entry::Id;
+use store_api::logstore::entry_stream::SendableEntryStream;
+use store_api::logstore::namespace::Id as NamespaceId;
+use store_api::logstore::LogStore;
+
+use crate::error::{Error, InternalSnafu, Result};
+use crate::fs::entry::EntryImpl;
+use crate::fs::file_name::FileName;
+
+/// A fault injected into an append of [FaultLogStore].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendFault {
+    /// Fails the append without writing anything.
+    Fail,
+    /// Fails the append and keeps the first `n` bytes of the encoded entries, which
+    /// could be written to the log file by [append_to_last_file] after the log store is
+    /// closed, like crashing in the middle of writing the file. `n` is clamped to be less
+    /// than the size of the encoded entries.
+    Torn(usize),
+}
+
+/// Wraps a log store and injects faults into its appends.
+///
+/// Once a fault is injected, the log store is considered crashed and all following
+/// appends fail. Reads are never affected.
+#[derive(Debug)]
+pub struct FaultLogStore<S> {
+    inner: S,
+    /// Number of appends counted, a batch is counted as one append.
+    appends: AtomicUsize,
+    /// Faults to inject, keyed by the index of the append.
+    faults: Mutex<HashMap<usize, AppendFault>>,
+    crashed: AtomicBool,
+    /// Bytes left by the torn append.
+    torn: Mutex<Option<Vec<u8>>>,
+}
+
+impl<S> FaultLogStore<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            appends: AtomicUsize::new(0),
+            faults: Mutex::new(HashMap::new()),
+            crashed: AtomicBool::new(false),
+            torn: Mutex::new(None),
+        }
+    }
+
+    /// Injects `fault` into the `nth` append counted from the creation of the store,
+    /// starting from 0.
+    pub fn inject(&self, nth: usize, fault: AppendFault) {
+        self.faults.lock().unwrap().insert(nth, fault);
+    }
+
+    /// Returns the number of appends counted.
+    pub fn appends(&self) -> usize {
+        self.appends.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if a fault has been injected and the store has crashed.
+    pub fn crashed(&self) -> bool {
+        self.crashed.load(Ordering::Relaxed)
+    }
+
+    /// Takes the bytes left by the torn append.
+    pub fn take_torn_bytes(&self) -> Option<Vec<u8>> {
+        self.torn.lock().unwrap().take()
+    }
+
+    /// Counts an append of `entries`, returns an error if the store has crashed or a
+    /// fault is injected into it.
+    fn check_append(&self, entries: &[&EntryImpl]) -> Result<()> {
+        if self.crashed() {
+            return InternalSnafu {
+                msg: "log store crashed",
+            }
+            .fail();
+        }
+
+        let nth = self.appends.fetch_add(1, Ordering::Relaxed);
+        let Some(fault) = self.faults.lock().unwrap().remove(&nth) else {
+            return Ok(());
+        };
+        self.crashed.store(true, Ordering::Relaxed);
+        if let AppendFault::Torn(n) = fault {
+            let mut bytes = Vec::new();
+            for entry in entries {
+                bytes.extend_from_slice(&BytesMut::from(*entry));
+            }
+            bytes.truncate(n.min(bytes.len().saturating_sub(1)));
+            *self.torn.lock().unwrap() = Some(bytes);
+        }
+        InternalSnafu {
+            msg: format!("injected fault {fault:?} into append {nth}"),
+        }
+        .fail()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> LogStore for FaultLogStore<S>
+where
+    S: LogStore<Error = Error, Entry = EntryImpl>,
+{
+    type Error = Error;
+    type Namespace = S::Namespace;
+    type Entry = EntryImpl;
+    type AppendResponse = S::AppendResponse;
+
+    async fn start(&self) -> Result<()> {
+        self.inner.start().await
+    }
+
+    async fn stop(&self) -> Result<()> {
+        self.inner.stop().await
+    }
+
+    async fn append(&self, e: Self::Entry) -> Result<Self::AppendResponse> {
+        self.check_append(&[&e])?;
+        self.inner.append(e).await
+    }
+
+    async fn append_batch(&self, ns: &Self::Namespace, e: Vec<Self::Entry>) -> Result<Id> {
+        self.check_append(&e.iter().collect::<Vec<_>>())?;
+        self.inner.append_batch(ns, e).await
+    }
+
+    async fn read(
+        &self,
+        ns: &Self::Namespace,
+        id: Id,
+    ) -> Result<SendableEntryStream<'_, Self::Entry, Self::Error>> {
+        self.inner.read(ns, id).await
+    }
+
+    async fn create_namespace(&mut self, ns: &Self::Namespace) -> Result<()> {
+        self.inner.create_namespace(ns).await
+    }
+
+    async fn delete_namespace(&mut self, ns: &Self::Namespace) -> Result<()> {
+        self.inner.delete_namespace(ns).await
+    }
+
+    async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>> {
+        self.inner.list_namespaces().await
+    }
+
+    fn entry<D: AsRef<[u8]>>(&self, data: D, id: Id, ns: Self::Namespace) -> Self::Entry {
+        self.inner.entry(data, id, ns)
+    }
+
+    fn namespace(&self, id: NamespaceId) -> Self::Namespace {
+        self.inner.namespace(id)
+    }
+
+    async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<()> {
+        self.inner.obsolete(namespace, id).await
+    }
+}
+
+/// Appends `bytes` to the last log file under `dir`.
+///
+/// # Panics
+/// Panics if there is no log file under `dir` or the file can't be written.
+pub fn append_to_last_file(dir: impl AsRef<Path>, bytes: &[u8]) {
+    let last_file = std::fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            let file_name = FileName::try_from(path.to_str()?).ok()?;
+            Some((file_name.entry_id(), path))
+        })
+        .max_by_key(|(entry_id, _)| *entry_id)
+        .map(|(_, path)| path)
+        .expect("no log file found");
+
+    let mut file = OpenOptions::new().append(true).open(last_file).unwrap();
+    file.write_all(bytes).unwrap();
+    file.sync_all().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use store_api::logstore::entry::Entry;
+
+    use super::*;
+    use crate::fs::config::LogConfig;
+    use crate::fs::log::LocalFileLogStore;
+
+    async fn read_all(store: &impl LogStore<Entry = EntryImpl>) -> Vec<Vec<u8>> {
+        let ns = store.namespace(0);
+        let mut stream = store.read(&ns, 0).await.unwrap();
+        let mut data = Vec::new();
+        while let Some(entries) = stream.next().await {
+            for entry in entries.unwrap() {
+                data.push(entry.data().to_vec());
+            }
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_torn_append() {
+        let dir = tempdir::TempDir::new("fault-log-store").unwrap();
+        let config = LogConfig {
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        let store = FaultLogStore::new(LocalFileLogStore::open(&config).await.unwrap());
+        let ns = store.namespace(0);
+        store.inject(1, AppendFault::Torn(30));
+
+        store
+            .append(store.entry(b"0", 0, ns.clone()))
+            .await
+            .unwrap();
+        assert!(store
+            .append(store.entry(b"1", 1, ns.clone()))
+            .await
+            .is_err());
+        assert!(store.crashed());
+        assert!(store.append(store.entry(b"2", 1, ns)).await.is_err());
+        assert_eq!(2, store.appends());
+        let torn = store.take_torn_bytes().unwrap();
+        assert_eq!(30, torn.len());
+        drop(store);
+
+        append_to_last_file(dir.path(), &torn);
+        // The torn entry is ignored on recovery, new entries are appended after the
+        // last complete entry.
+        let store = LocalFileLogStore::open(&config).await.unwrap();
+        let ns = store.namespace(0);
+        assert_eq!(vec![b"0".to_vec()], read_all(&store).await);
+        store.append(store.entry(b"1", 1, ns)).await.unwrap();
+        assert_eq!(vec![b"0".to_vec(), b"1".to_vec()], read_all(&store).await);
+    }
+}
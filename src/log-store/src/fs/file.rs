@@ -17,6 +17,7 @@ use std::fs::{File, OpenOptions};
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_stream::stream;
 use byteorder::{ByteOrder, LittleEndian};
@@ -42,7 +43,7 @@ use crate::error::{
     AppendSnafu, Error, InternalSnafu, IoSnafu, OpenLogSnafu, Result, WaitWriteSnafu, WriteSnafu,
 };
 use crate::fs::chunk::{Chunk, ChunkList};
-use crate::fs::config::LogConfig;
+use crate::fs::config::{LogConfig, SyncMode};
 use crate::fs::crc::CRC_ALGO;
 use crate::fs::entry::{EntryImpl, StreamImpl};
 use crate::fs::file_name::FileName;
@@ -71,28 +72,27 @@ impl FileWriter {
         handle.await.context(WriteSnafu)?
     }
 
-    /// Writes a batch of `AppendRequest` to file.
-    pub async fn write_batch(self: &Arc<Self>, batch: &Vec<AppendRequest>) -> Result<usize> {
-        let mut futures = Vec::with_capacity(batch.len());
-
-        let mut max_offset = 0;
-        for req in batch {
-            let offset = req.offset;
-            let end = req.data.len() + offset;
-            max_offset = max_offset.max(end);
-            let future = self.write(req.data.clone(), offset as u64);
-            futures.push(future);
-        }
+    /// Writes a non-empty batch of `AppendRequest` to file in one write, returns the max
+    /// offset written. Offsets of requests in the batch must be contiguous.
+    pub async fn write_batch(&self, batch: &[AppendRequest]) -> Result<usize> {
+        let start = batch[0].offset;
+        let data = if batch.len() == 1 {
+            batch[0].data.clone()
+        } else {
+            let mut buf = BytesMut::with_capacity(batch.iter().map(|r| r.data.len()).sum());
+            for req in batch {
+                debug_assert_eq!(start + buf.len(), req.offset);
+                buf.extend_from_slice(&req.data);
+            }
+            buf.freeze()
+        };
+        let max_offset = start + data.len();
         debug!(
             "Write batch, size: {}, max offset: {}",
             batch.len(),
             max_offset
         );
-        futures::future::join_all(futures)
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>>>()
-            .map(|_| max_offset)
+        self.write(data, start as u64).await.map(|_| max_offset)
     }
 
     pub async fn flush(&self) -> Result<()> {
@@ -129,6 +129,12 @@ pub struct LogFile {
     max_file_size: usize,
     // buffer size for append request channel. read from config on start.
     append_buffer_size: usize,
+    // how appended entries are persisted.
+    sync_mode: SyncMode,
+    // time to wait for more entries to commit together.
+    group_commit_window: Duration,
+    // max bytes of entries to commit together.
+    group_commit_max_bytes: usize,
 }
 
 impl Drop for LogFile {
@@ -162,6 +168,9 @@ impl LogFile {
             join_handle: Mutex::new(None),
             state: Arc::new(State::default()),
             append_buffer_size: config.append_buffer_size,
+            sync_mode: config.sync_mode,
+            group_commit_window: config.group_commit_window,
+            group_commit_max_bytes: config.group_commit_max_bytes,
         };
 
         let metadata = log.writer.inner.metadata().context(IoSnafu)?;
@@ -205,22 +214,32 @@ impl LogFile {
         let notify = self.notify.clone();
         let writer = self.writer.clone();
         let state = self.state.clone();
+        let sync_mode = self.sync_mode;
+        let window = match sync_mode {
+            SyncMode::Group => self.group_commit_window,
+            SyncMode::Sync | SyncMode::Async => Duration::ZERO,
+        };
+        let max_bytes = self.group_commit_max_bytes;
 
         let (tx, mut rx) = tokio::sync::mpsc::channel(self.append_buffer_size);
 
         let handle = tokio::spawn(async move {
             while !state.is_stopped() {
-                let batch = Self::recv_batch(&mut rx, &state, &notify, true).await;
+                let batch =
+                    Self::recv_batch(&mut rx, &state, &notify, true, window, max_bytes).await;
                 debug!("Receive write request, size: {}", batch.len());
                 if !batch.is_empty() {
-                    Self::handle_batch(batch, &state, &writer).await;
+                    Self::handle_batch(batch, &state, &writer, sync_mode).await;
                 }
             }
 
             // log file stopped
-            let batch = Self::recv_batch(&mut rx, &state, &notify, false).await;
+            let batch = Self::recv_batch(&mut rx, &state, &notify, false, window, max_bytes).await;
             if !batch.is_empty() {
-                Self::handle_batch(batch, &state, &writer).await;
+                Self::handle_batch(batch, &state, &writer, sync_mode).await;
+            }
+            if sync_mode == SyncMode::Async {
+                writer.flush().await?;
             }
             info!("Writer task finished");
             Ok(())
@@ -255,9 +274,29 @@ impl LogFile {
     }
 
     async fn handle_batch(
+        batch: Vec<AppendRequest>,
+        state: &Arc<State>,
+        writer: &Arc<FileWriter>,
+        sync_mode: SyncMode,
+    ) {
+        match sync_mode {
+            SyncMode::Sync => {
+                for req in batch {
+                    Self::commit_batch(vec![req], state, writer, true).await;
+                }
+            }
+            SyncMode::Group => Self::commit_batch(batch, state, writer, true).await,
+            SyncMode::Async => Self::commit_batch(batch, state, writer, false).await,
+        }
+    }
+
+    /// Writes the batch in one write and syncs it if `sync` is true, requests in the batch
+    /// are completed after that.
+    async fn commit_batch(
         mut batch: Vec<AppendRequest>,
         state: &Arc<State>,
         writer: &Arc<FileWriter>,
+        sync: bool,
     ) {
         // preserve previous write offset
         let prev_write_offset = state.write_offset();
@@ -271,29 +310,24 @@ impl LogFile {
             debug!("Entry id: {}, offset: {}", req.id, req.offset,);
         }
 
-        match writer.write_batch(&batch).await {
-            Ok(max_offset) => match writer.flush().await {
-                Ok(_) => {
-                    let prev_ofs = state.flush_offset.swap(max_offset, Ordering::Acquire);
-                    let prev_id = state.last_entry_id.swap(last_id, Ordering::Acquire);
-                    debug!(
-                        "Flush offset: {} -> {}, max offset in batch: {}, entry id: {}->{}",
-                        prev_ofs,
-                        state.flush_offset.load(Ordering::Acquire),
-                        max_offset,
-                        prev_id,
-                        state.last_entry_id.load(Ordering::Acquire),
-                    );
-                    batch.into_iter().for_each(AppendRequest::complete);
-                }
-                Err(e) => {
-                    error!(e; "Failed to flush log file");
-                    batch.into_iter().for_each(|r| r.fail());
-                    state
-                        .write_offset
-                        .store(prev_write_offset, Ordering::Release);
-                }
-            },
+        let result = match writer.write_batch(&batch).await {
+            Ok(max_offset) if sync => writer.flush().await.map(|_| max_offset),
+            result => result,
+        };
+        match result {
+            Ok(max_offset) => {
+                let prev_ofs = state.flush_offset.swap(max_offset, Ordering::Acquire);
+                let prev_id = state.last_entry_id.swap(last_id, Ordering::Acquire);
+                debug!(
+                    "Flush offset: {} -> {}, max offset in batch: {}, entry id: {}->{}",
+                    prev_ofs,
+                    state.flush_offset.load(Ordering::Acquire),
+                    max_offset,
+                    prev_id,
+                    state.last_entry_id.load(Ordering::Acquire),
+                );
+                batch.into_iter().for_each(AppendRequest::complete);
+            }
             Err(e) => {
                 error!(e; "Failed to write append requests");
                 batch.into_iter().for_each(|r| r.fail());
@@ -309,6 +343,8 @@ impl LogFile {
         state: &Arc<State>,
         notify: &Arc<Notify>,
         wait_on_empty: bool,
+        window: Duration,
+        max_bytes: usize,
     ) -> Vec<AppendRequest> {
         let mut batch: Vec<AppendRequest> = Vec::with_capacity(LOG_WRITER_BATCH_SIZE);
         for _ in 0..LOG_WRITER_BATCH_SIZE {
@@ -334,6 +370,23 @@ impl LogFile {
                 },
             }
         }
+
+        if batch.is_empty() || window.is_zero() {
+            return batch;
+        }
+        // Waits for more requests to commit together until the window elapses.
+        let deadline = time::Instant::now() + window;
+        let mut bytes: usize = batch.iter().map(|r| r.data.len()).sum();
+        while batch.len() < LOG_WRITER_BATCH_SIZE && bytes < max_bytes {
+            match time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(req)) => {
+                    bytes += req.data.len();
+                    batch.push(req);
+                }
+                // The window elapses or the channel is closed.
+                Ok(None) | Err(_) => break,
+            }
+        }
         batch
     }
 
@@ -299,7 +299,19 @@ impl LogStore for LocalFileLogStore {
                 msg: "Logstore gc task not spawned",
             })?;
         token.cancel();
-        Ok(handle.await.context(WaitGcTaskStopSnafu)?)
+        handle.await.context(WaitGcTaskStopSnafu)?;
+
+        // Stopping the active file waits until all pending append requests are written
+        // and synced to disk.
+        let active_file = self.active_file();
+        if !active_file.is_stopped() {
+            active_file.stop().await?;
+        }
+        info!(
+            "Log store stopped, active file: {}",
+            active_file.file_name()
+        );
+        Ok(())
     }
 
     async fn append(&self, mut entry: Self::Entry) -> Result<Self::AppendResponse> {
@@ -416,6 +428,7 @@ mod tests {
     use tempdir::TempDir;
 
     use super::*;
+    use crate::fs::config::SyncMode;
 
     #[tokio::test]
     pub async fn test_roll_file() {
@@ -555,6 +568,46 @@ mod tests {
         assert_eq!(43, entries[0].namespace_id);
     }
 
+    #[tokio::test]
+    async fn test_sync_modes() {
+        common_telemetry::logging::init_default_ut_logging();
+        for sync_mode in [SyncMode::Sync, SyncMode::Group, SyncMode::Async] {
+            let dir = TempDir::new("greptimedb-sync-mode").unwrap();
+            let config = LogConfig {
+                append_buffer_size: 128,
+                max_log_file_size: 1024 * 1024,
+                log_file_dir: dir.path().to_str().unwrap().to_string(),
+                sync_mode,
+                group_commit_window: Duration::from_millis(10),
+                ..Default::default()
+            };
+            let logstore = LocalFileLogStore::open(&config).await.unwrap();
+            let appends = (0..10).map(|id| {
+                logstore.append(EntryImpl::new(
+                    generate_data(96),
+                    id,
+                    LocalNamespace::new(42),
+                ))
+            });
+            for (id, resp) in futures::future::join_all(appends)
+                .await
+                .into_iter()
+                .enumerate()
+            {
+                assert_eq!(id as u64, resp.unwrap().entry_id, "{sync_mode:?}");
+            }
+
+            let stream = logstore.read(&LocalNamespace::new(42), 0).await.unwrap();
+            tokio::pin!(stream);
+            let mut ids = Vec::new();
+            while let Some(entries) = stream.next().await {
+                ids.extend(entries.unwrap().iter().map(|e| e.id()));
+            }
+            ids.sort_unstable();
+            assert_eq!((0..10).collect::<Vec<_>>(), ids, "{sync_mode:?}");
+        }
+    }
+
     #[test]
     fn test_find_files_to_delete() {
         let file_map = vec![(1u64, ()), (11u64, ()), (21u64, ()), (31u64, ())]
@@ -12,37 +12,61 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cluster;
 mod heartbeat;
+mod leader;
 mod load_balance;
+mod route_cache;
 mod router;
+mod sequence;
 mod store;
 
-use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
+use std::ops::Range;
+use std::sync::Arc;
+
+use api::v1::meta::{ListNodesRequest, NextRequest, NodeInfo};
+use cluster::Client as ClusterClient;
+use common_grpc::channel_manager::{ChannelConfig, ChannelManager, Compression};
 use common_telemetry::info;
 use heartbeat::Client as HeartbeatClient;
+use route_cache::RouteCache;
 use router::Client as RouterClient;
+use sequence::Client as SequenceClient;
 use snafu::OptionExt;
 use store::Client as StoreClient;
 
 pub use self::heartbeat::{HeartbeatSender, HeartbeatStream};
+pub use self::route_cache::{
+    RouteCacheConfig, METRIC_ROUTE_CACHE_HITS_TOTAL, METRIC_ROUTE_CACHE_MISSES_TOTAL,
+};
+pub use self::sequence::IdAllocator;
 use crate::error;
 use crate::error::Result;
-use crate::rpc::router::DeleteRequest;
+use crate::rpc::router::{DeleteRequest, RenameRequest};
 use crate::rpc::{
-    BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse, CreateRequest,
-    DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest, MoveValueResponse, PutRequest,
-    PutResponse, RangeRequest, RangeResponse, RouteRequest, RouteResponse,
+    util, BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
+    CreateRequest, DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest, MoveValueResponse,
+    PutRequest, PutResponse, RangeRequest, RangeResponse, RouteRequest, RouteResponse, TableName,
+    TableRoute,
 };
 
 pub type Id = (u64, u64);
 
+/// Name of the sequence of table ids in `metasrv`.
+pub const TABLE_ID_SEQ: &str = "table_id";
+
 #[derive(Clone, Debug, Default)]
 pub struct MetaClientBuilder {
     id: Id,
     enable_heartbeat: bool,
     enable_router: bool,
     enable_store: bool,
+    enable_sequence: bool,
+    enable_cluster: bool,
     channel_manager: Option<ChannelManager>,
+    compression: Option<Compression>,
+    route_cache: Option<RouteCacheConfig>,
+    tenant: Option<String>,
 }
 
 impl MetaClientBuilder {
@@ -74,6 +98,20 @@ impl MetaClientBuilder {
         }
     }
 
+    pub fn enable_sequence(self) -> Self {
+        Self {
+            enable_sequence: true,
+            ..self
+        }
+    }
+
+    pub fn enable_cluster(self) -> Self {
+        Self {
+            enable_cluster: true,
+            ..self
+        }
+    }
+
     pub fn channel_manager(self, channel_manager: ChannelManager) -> Self {
         Self {
             channel_manager: Some(channel_manager),
@@ -81,16 +119,58 @@ impl MetaClientBuilder {
         }
     }
 
+    /// Compresses messages sent to the meta server. It overrides the compression in the
+    /// config of the channel manager, a new channel manager with the overridden config
+    /// is used in that case.
+    pub fn compression(self, compression: Compression) -> Self {
+        Self {
+            compression: Some(compression),
+            ..self
+        }
+    }
+
+    /// Caches the routes of tables looked up by [MetaClient::table_route], so they are
+    /// not fetched from `metasrv` for every request.
+    pub fn route_cache(self, config: RouteCacheConfig) -> Self {
+        Self {
+            route_cache: Some(config),
+            ..self
+        }
+    }
+
+    /// Isolates the keys of the store client in the namespace of the `tenant`, so
+    /// tenants sharing one `metasrv` never see keys of each other.
+    pub fn tenant(self, tenant: impl Into<String>) -> Self {
+        Self {
+            tenant: Some(tenant.into()),
+            ..self
+        }
+    }
+
     pub fn build(self) -> MetaClient {
-        let mut client = if let Some(mgr) = self.channel_manager {
+        let channel_manager = match (self.channel_manager, self.compression) {
+            (mgr, Some(compression)) => {
+                let config = mgr
+                    .map(|mgr| mgr.config().clone())
+                    .unwrap_or_default()
+                    .compression(compression);
+                Some(ChannelManager::with_config(config))
+            }
+            (mgr, None) => mgr,
+        };
+        let mut client = if let Some(mgr) = channel_manager {
             MetaClient::with_channel_manager(self.id, mgr)
         } else {
             MetaClient::new(self.id)
         };
 
-        if let (false, false, false) =
-            (self.enable_heartbeat, self.enable_router, self.enable_store)
-        {
+        if let (false, false, false, false, false) = (
+            self.enable_heartbeat,
+            self.enable_router,
+            self.enable_store,
+            self.enable_sequence,
+            self.enable_cluster,
+        ) {
             panic!("At least one client needs to be enabled.")
         }
 
@@ -103,8 +183,15 @@ impl MetaClientBuilder {
             client.router = Some(RouterClient::new(self.id, mgr.clone()));
         }
         if self.enable_store {
-            client.store = Some(StoreClient::new(self.id, mgr));
+            client.store = Some(StoreClient::with_tenant(self.id, mgr.clone(), self.tenant));
         }
+        if self.enable_sequence {
+            client.sequence = Some(SequenceClient::new(self.id, mgr.clone()));
+        }
+        if self.enable_cluster {
+            client.cluster = Some(ClusterClient::new(self.id, mgr));
+        }
+        client.route_cache = self.route_cache.as_ref().map(RouteCache::new);
 
         client
     }
@@ -117,6 +204,9 @@ pub struct MetaClient {
     heartbeat: Option<HeartbeatClient>,
     router: Option<RouterClient>,
     store: Option<StoreClient>,
+    sequence: Option<SequenceClient>,
+    cluster: Option<ClusterClient>,
+    route_cache: Option<RouteCache>,
 }
 
 impl MetaClient {
@@ -151,9 +241,17 @@ impl MetaClient {
             info!("Router client started");
         }
         if let Some(client) = &mut self.store {
-            client.start(urls).await?;
+            client.start(urls.clone()).await?;
             info!("Store client started");
         }
+        if let Some(client) = &mut self.sequence {
+            client.start(urls.clone()).await?;
+            info!("Sequence client started");
+        }
+        if let Some(client) = &mut self.cluster {
+            client.start(urls).await?;
+            info!("Cluster client started");
+        }
 
         Ok(())
     }
@@ -181,7 +279,25 @@ impl MetaClient {
     /// information contained in the request and using some intelligent policies,
     /// such as load-based.
     pub async fn create_route(&self, req: CreateRequest) -> Result<RouteResponse> {
-        self.router_client()?.create(req.into()).await?.try_into()
+        let table_name = req.table_name.clone();
+        let res = self.router_client()?.create(req.into()).await?.try_into();
+        self.invalidate_table_route(&table_name).await;
+        res
+    }
+
+    /// Renames the table, the route of the table is moved to the new name atomically
+    /// with the catalog entries of the table.
+    pub async fn rename_route(&self, req: RenameRequest) -> Result<RouteResponse> {
+        let table_name = req.table_name.clone();
+        let new_table_name = TableName::new(
+            &table_name.catalog_name,
+            &table_name.schema_name,
+            &req.new_table_name,
+        );
+        let res = self.router_client()?.rename(req.into()).await?.try_into();
+        self.invalidate_table_route(&table_name).await;
+        self.invalidate_table_route(&new_table_name).await;
+        res
     }
 
     /// Fetch routing information for tables. The smallest unit is the complete
@@ -211,7 +327,54 @@ impl MetaClient {
     /// table of routing information, the nth call can still return the
     /// deleted route information.
     pub async fn delete_route(&self, req: DeleteRequest) -> Result<RouteResponse> {
-        self.router_client()?.delete(req.into()).await?.try_into()
+        let table_name = req.table_name.clone();
+        let res = self.router_client()?.delete(req.into()).await?.try_into();
+        self.invalidate_table_route(&table_name).await;
+        res
+    }
+
+    /// Returns the route of a table, or `None` if the table has no route.
+    ///
+    /// The route is served from the route cache if it's enabled and the route is
+    /// cached, otherwise it's fetched from `metasrv` and then cached.
+    pub async fn table_route(&self, table_name: &TableName) -> Result<Option<Arc<TableRoute>>> {
+        if let Some(route) = self
+            .route_cache
+            .as_ref()
+            .and_then(|cache| cache.get(table_name))
+        {
+            return Ok(Some(route));
+        }
+
+        let req = RouteRequest::new().add_table_name(table_name.clone());
+        let mut res = self.route(req).await?;
+        if res.table_routes.is_empty() {
+            return Ok(None);
+        }
+        let route = Arc::new(res.table_routes.swap_remove(0));
+        self.cache_table_route(table_name.clone(), route.clone())
+            .await;
+        Ok(Some(route))
+    }
+
+    /// Puts the route of a table into the route cache, e.g. to warm up the cache
+    /// with a route known by other means. It's a no-op if the route cache is disabled.
+    pub async fn cache_table_route(&self, table_name: TableName, route: Arc<TableRoute>) {
+        if let Some(cache) = &self.route_cache {
+            cache.insert(table_name, route).await;
+        }
+    }
+
+    /// Invalidates the cached route of a table. It should be called once the route
+    /// is known to be changed, e.g. on route change notifications from `metasrv`, or
+    /// when requests sent by the cached route are rejected by datanodes.
+    ///
+    /// Routes changed by [MetaClient::create_route], [MetaClient::delete_route] and
+    /// [MetaClient::rename_route] of this client are invalidated automatically.
+    pub async fn invalidate_table_route(&self, table_name: &TableName) {
+        if let Some(cache) = &self.route_cache {
+            cache.invalidate(table_name).await;
+        }
     }
 
     /// Range gets the keys in the range from the key-value store.
@@ -257,6 +420,30 @@ impl MetaClient {
             .try_into()
     }
 
+    /// Allocates `size` consecutive values of the sequence `name`, which are never
+    /// allocated again. [IdAllocator] caches the values to reduce the requests.
+    pub async fn next_sequence(&self, name: &str, size: u64) -> Result<Range<u64>> {
+        let req = NextRequest {
+            name: name.to_string(),
+            size,
+            ..Default::default()
+        };
+        let res = self.sequence_client()?.next(req).await?;
+        util::check_response_header(res.header.as_ref())?;
+        Ok(res.start..res.end)
+    }
+
+    /// Lists the datanodes known by `metasrv`, with the stats reported in their last
+    /// heartbeats.
+    pub async fn list_nodes(&self) -> Result<Vec<NodeInfo>> {
+        let res = self
+            .cluster_client()?
+            .list_nodes(ListNodesRequest::default())
+            .await?;
+        util::check_response_header(res.header.as_ref())?;
+        Ok(res.nodes)
+    }
+
     #[inline]
     pub fn heartbeat_client(&self) -> Result<HeartbeatClient> {
         self.heartbeat.clone().context(error::NotStartedSnafu {
@@ -278,6 +465,20 @@ impl MetaClient {
         })
     }
 
+    #[inline]
+    pub fn sequence_client(&self) -> Result<SequenceClient> {
+        self.sequence.clone().context(error::NotStartedSnafu {
+            name: "sequence_client",
+        })
+    }
+
+    #[inline]
+    pub fn cluster_client(&self) -> Result<ClusterClient> {
+        self.cluster.clone().context(error::NotStartedSnafu {
+            name: "cluster_client",
+        })
+    }
+
     #[inline]
     pub fn channel_config(&self) -> &ChannelConfig {
         self.channel_manager.config()
@@ -292,15 +493,17 @@ impl MetaClient {
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
-    use api::v1::meta::{HeartbeatRequest, Peer};
+    use api::v1::meta::{HeartbeatRequest, NodeStat, NodeStatus, Peer};
     use meta_srv::metasrv::Context;
+    use meta_srv::mocks::MockInfo;
     use meta_srv::selector::{Namespace, Selector};
     use meta_srv::Result as MetaResult;
 
     use super::*;
     use crate::mocks;
-    use crate::rpc::{Partition, TableName};
+    use crate::rpc::{Partition, Table};
 
     const TEST_KEY_PREFIX: &str = "__unit_test__meta__";
 
@@ -389,6 +592,30 @@ mod tests {
         assert!(meta_client.store_client().unwrap().is_started().await);
     }
 
+    #[tokio::test]
+    async fn test_meta_client_builder_compression() {
+        let meta_client = MetaClientBuilder::new(0, 0)
+            .enable_store()
+            .compression(Compression::Gzip)
+            .build();
+        assert_eq!(
+            Some(Compression::Gzip),
+            meta_client.channel_config().compression
+        );
+
+        // Other options of the given channel manager are kept.
+        let channel_manager =
+            ChannelManager::with_config(ChannelConfig::new().timeout(Duration::from_secs(3)));
+        let meta_client = MetaClientBuilder::new(0, 0)
+            .enable_store()
+            .channel_manager(channel_manager)
+            .compression(Compression::Zstd)
+            .build();
+        let config = meta_client.channel_config();
+        assert_eq!(Some(Duration::from_secs(3)), config.timeout);
+        assert_eq!(Some(Compression::Zstd), config.compression);
+    }
+
     #[tokio::test]
     async fn test_not_start_heartbeat_client() {
         let urls = &["127.0.0.1:3001", "127.0.0.1:3002"];
@@ -465,6 +692,34 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn test_list_nodes() {
+        let client = mocks::mock_client_with_memstore().await;
+        assert!(client.list_nodes().await.unwrap().is_empty());
+
+        let (sender, mut receiver) = client.heartbeat().await.unwrap();
+        let req = HeartbeatRequest {
+            peer: Some(Peer {
+                id: 1,
+                addr: "meta_client_peer".to_string(),
+            }),
+            node_stat: Some(NodeStat {
+                region_num: 3,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        sender.send(req).await.unwrap();
+        // The lease is updated before the response is sent.
+        let _ = receiver.message().await.unwrap();
+
+        let nodes = client.list_nodes().await.unwrap();
+        assert_eq!(1, nodes.len());
+        assert_eq!("meta_client_peer", nodes[0].peer.as_ref().unwrap().addr);
+        assert_eq!(NodeStatus::Alive as i32, nodes[0].status);
+        assert_eq!(3, nodes[0].region_num);
+    }
+
     struct MockSelector;
 
     #[async_trait::async_trait]
@@ -522,6 +777,81 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[tokio::test]
+    async fn test_table_route_cache() {
+        let table_name = TableName::new("test_catalog", "test_schema", "test_table");
+        let route = Arc::new(TableRoute {
+            table: Table {
+                id: 1,
+                table_name: table_name.clone(),
+                table_schema: vec![],
+            },
+            region_routes: vec![],
+        });
+
+        // Routes are always fetched from metasrv without the route cache.
+        let meta_client = MetaClientBuilder::new(0, 0).enable_router().build();
+        meta_client
+            .cache_table_route(table_name.clone(), route.clone())
+            .await;
+        assert!(meta_client.table_route(&table_name).await.is_err());
+
+        // The client is not started, so the route could only be served from the cache.
+        let meta_client = MetaClientBuilder::new(0, 0)
+            .enable_router()
+            .route_cache(RouteCacheConfig::default())
+            .build();
+        meta_client
+            .cache_table_route(table_name.clone(), route.clone())
+            .await;
+        let cached = meta_client.table_route(&table_name).await.unwrap().unwrap();
+        assert!(Arc::ptr_eq(&route, &cached));
+
+        meta_client.invalidate_table_route(&table_name).await;
+        assert!(meta_client.table_route(&table_name).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tenant_isolation() {
+        async fn tenant_client(mock_info: &MockInfo, tenant: &str) -> MetaClient {
+            let mut client = MetaClientBuilder::new(0, 0)
+                .enable_store()
+                .channel_manager(mock_info.channel_manager.clone())
+                .tenant(tenant)
+                .build();
+            client.start(&[&mock_info.server_addr]).await.unwrap();
+            client
+        }
+
+        let mock_info = meta_srv::mocks::mock_with_memstore().await;
+        let client1 = tenant_client(&mock_info, "tenant1").await;
+        let client2 = tenant_client(&mock_info, "tenant2").await;
+
+        for (client, value) in [(&client1, b"value1"), (&client2, b"value2")] {
+            let req = PutRequest::new()
+                .with_key(b"key".to_vec())
+                .with_value(value.to_vec());
+            let _ = client.put(req).await.unwrap();
+        }
+
+        for (client, value) in [(&client1, b"value1"), (&client2, b"value2")] {
+            let req = RangeRequest::new().with_prefix(b"k".to_vec());
+            let mut kvs = client.range(req).await.unwrap().take_kvs();
+            assert_eq!(1, kvs.len());
+            let mut kv = kvs.pop().unwrap();
+            assert_eq!(b"key".to_vec(), kv.take_key());
+            assert_eq!(value.to_vec(), kv.take_value());
+        }
+
+        let res = client1
+            .delete_range(DeleteRangeRequest::new().with_prefix(b"k".to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(1, res.deleted());
+        let req = RangeRequest::new().with_key(b"key".to_vec());
+        assert_eq!(1, client2.range(req).await.unwrap().take_kvs().len());
+    }
+
     #[tokio::test]
     async fn test_range_get() {
         let tc = new_client("test_range_get").await;
@@ -789,4 +1119,28 @@ mod tests {
         assert_eq!(from_key, kv.take_key());
         assert_eq!(b"value2".to_vec(), kv.take_value());
     }
+
+    #[tokio::test]
+    async fn test_id_allocator() {
+        let client = mocks::mock_client_with_memstore().await;
+        let range = client.next_sequence(TABLE_ID_SEQ, 3).await.unwrap();
+        assert_eq!(3, range.end - range.start);
+        assert!(client.next_sequence("unknown", 1).await.is_err());
+
+        // Allocators sharing a sequence, like the ones of different frontends, never
+        // allocate the same ids.
+        let a = IdAllocator::new(client.clone(), TABLE_ID_SEQ, 10);
+        let b = IdAllocator::new(client, TABLE_ID_SEQ, 10);
+        let mut ids = vec![];
+        for _ in 0..15 {
+            ids.push(a.next().await.unwrap());
+            ids.push(b.next().await.unwrap());
+        }
+        ids.extend(a.next_n(20).await.unwrap());
+        assert!(ids.iter().all(|id| *id >= range.end));
+        let len = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(len, ids.len());
+    }
 }
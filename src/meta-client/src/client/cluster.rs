@@ -0,0 +1,159 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use api::v1::meta::cluster_client::ClusterClient;
+use api::v1::meta::{ListNodesRequest, ListNodesResponse};
+use common_grpc::channel_manager::ChannelManager;
+use snafu::{ensure, ResultExt};
+use tokio::sync::RwLock;
+use tonic::transport::Channel;
+
+use crate::client::leader::LeaderCache;
+use crate::client::Id;
+use crate::error;
+use crate::error::Result;
+
+#[derive(Clone, Debug)]
+pub struct Client {
+    inner: Arc<RwLock<Inner>>,
+}
+
+impl Client {
+    pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        let inner = Arc::new(RwLock::new(Inner {
+            id,
+            channel_manager,
+            peers: vec![],
+            leader: LeaderCache::default(),
+        }));
+
+        Self { inner }
+    }
+
+    pub async fn start<U, A>(&mut self, urls: A) -> Result<()>
+    where
+        U: AsRef<str>,
+        A: AsRef<[U]>,
+    {
+        let mut inner = self.inner.write().await;
+        inner.start(urls).await
+    }
+
+    pub async fn is_started(&self) -> bool {
+        let inner = self.inner.read().await;
+        inner.is_started()
+    }
+
+    pub async fn list_nodes(&self, req: ListNodesRequest) -> Result<ListNodesResponse> {
+        let inner = self.inner.read().await;
+        inner.list_nodes(req).await
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    id: Id,
+    channel_manager: ChannelManager,
+    peers: Vec<String>,
+    leader: LeaderCache,
+}
+
+impl Inner {
+    async fn start<U, A>(&mut self, urls: A) -> Result<()>
+    where
+        U: AsRef<str>,
+        A: AsRef<[U]>,
+    {
+        ensure!(
+            !self.is_started(),
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: "Cluster client already started",
+            }
+        );
+
+        self.peers = urls
+            .as_ref()
+            .iter()
+            .map(|url| url.as_ref().to_string())
+            .collect::<HashSet<_>>()
+            .drain()
+            .collect::<Vec<_>>();
+
+        Ok(())
+    }
+
+    async fn list_nodes(&self, mut req: ListNodesRequest) -> Result<ListNodesResponse> {
+        let mut client = self.leader_client().await?;
+        req.set_header(self.id);
+        let res = self
+            .leader
+            .check_status(client.list_nodes(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
+    }
+
+    async fn leader_client(&self) -> Result<ClusterClient<Channel>> {
+        ensure!(
+            self.is_started(),
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: "Empty peers, cluster client may not start yet",
+            }
+        );
+
+        let leader = self
+            .leader
+            .get_or_ask(self.id, &self.channel_manager, &self.peers)
+            .await?;
+
+        self.make_client(leader)
+    }
+
+    fn make_client(&self, addr: impl AsRef<str>) -> Result<ClusterClient<Channel>> {
+        let channel = self
+            .channel_manager
+            .get(addr)
+            .context(error::CreateChannelSnafu)?;
+
+        let mut client = ClusterClient::new(channel);
+        if let Some(encoding) = self.channel_manager.config().grpc_compression() {
+            client = client.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(client)
+    }
+
+    #[inline]
+    fn is_started(&self) -> bool {
+        !self.peers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_start_client() {
+        let mut client = Client::new((0, 0), ChannelManager::default());
+        assert!(!client.is_started().await);
+        client
+            .start(&["127.0.0.1:1000", "127.0.0.1:1001"])
+            .await
+            .unwrap();
+        assert!(client.is_started().await);
+    }
+}
@@ -167,26 +167,8 @@ impl Inner {
             }
         );
 
-        let header = RequestHeader::new(self.id);
-        let mut leader = None;
-        for addr in &self.peers {
-            let req = AskLeaderRequest {
-                header: Some(header.clone()),
-            };
-            let mut client = self.make_client(addr)?;
-            match client.ask_leader(req).await {
-                Ok(res) => {
-                    if let Some(endpoint) = res.into_inner().leader {
-                        leader = Some(endpoint.addr);
-                        break;
-                    }
-                }
-                Err(status) => {
-                    debug!("Failed to ask leader from: {}, {}", addr, status);
-                }
-            }
-        }
-        self.leader = Some(leader.context(error::AskLeaderSnafu)?);
+        let leader = ask_leader(self.id, &self.channel_manager, &self.peers).await?;
+        self.leader = Some(leader);
         Ok(())
     }
 
@@ -227,12 +209,7 @@ impl Inner {
     }
 
     fn make_client(&self, addr: impl AsRef<str>) -> Result<HeartbeatClient<Channel>> {
-        let channel = self
-            .channel_manager
-            .get(addr)
-            .context(error::CreateChannelSnafu)?;
-
-        Ok(HeartbeatClient::new(channel))
+        make_client(&self.channel_manager, addr)
     }
 
     #[inline]
@@ -241,6 +218,47 @@ impl Inner {
     }
 }
 
+/// Asks the leader address of `metasrv` from `peers`, returns the first answer.
+pub(crate) async fn ask_leader<'a>(
+    id: Id,
+    channel_manager: &ChannelManager,
+    peers: impl IntoIterator<Item = &'a String>,
+) -> Result<String> {
+    let header = RequestHeader::new(id);
+    for addr in peers {
+        let req = AskLeaderRequest {
+            header: Some(header.clone()),
+        };
+        let mut client = make_client(channel_manager, addr)?;
+        match client.ask_leader(req).await {
+            Ok(res) => {
+                if let Some(endpoint) = res.into_inner().leader {
+                    return Ok(endpoint.addr);
+                }
+            }
+            Err(status) => {
+                debug!("Failed to ask leader from: {}, {}", addr, status);
+            }
+        }
+    }
+    error::AskLeaderSnafu.fail()
+}
+
+fn make_client(
+    channel_manager: &ChannelManager,
+    addr: impl AsRef<str>,
+) -> Result<HeartbeatClient<Channel>> {
+    let channel = channel_manager
+        .get(addr)
+        .context(error::CreateChannelSnafu)?;
+
+    let mut client = HeartbeatClient::new(channel);
+    if let Some(encoding) = channel_manager.config().grpc_compression() {
+        client = client.send_compressed(encoding).accept_compressed(encoding);
+    }
+    Ok(client)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
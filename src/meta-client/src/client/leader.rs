@@ -0,0 +1,109 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::RwLock;
+
+use api::v1::meta::ResponseHeader;
+use common_grpc::channel_manager::ChannelManager;
+use common_telemetry::info;
+use snafu::ResultExt;
+
+use crate::client::{heartbeat, Id};
+use crate::error;
+use crate::error::Result;
+
+/// Caches the leader of `metasrv`. Only the leader serves mutations, so the
+/// clients send them to the leader instead of a random peer.
+#[derive(Debug, Default)]
+pub(crate) struct LeaderCache {
+    leader: RwLock<Option<String>>,
+}
+
+impl LeaderCache {
+    /// Returns the cached leader, asks it from `peers` if there is none.
+    pub(crate) async fn get_or_ask<'a>(
+        &self,
+        id: Id,
+        channel_manager: &ChannelManager,
+        peers: impl IntoIterator<Item = &'a String>,
+    ) -> Result<String> {
+        let leader = self.leader.read().unwrap().clone();
+        if let Some(leader) = leader {
+            return Ok(leader);
+        }
+
+        let leader = heartbeat::ask_leader(id, channel_manager, peers).await?;
+        info!("Metasrv leader: {}", leader);
+        *self.leader.write().unwrap() = Some(leader.clone());
+        Ok(leader)
+    }
+
+    /// Invalidates the cached leader on rpc failure, since the leader may be down.
+    pub(crate) fn check_status<T>(&self, res: std::result::Result<T, tonic::Status>) -> Result<T> {
+        if res.is_err() {
+            self.invalidate();
+        }
+        res.context(error::TonicStatusSnafu)
+    }
+
+    /// Invalidates the cached leader if the peer responds it is not the leader,
+    /// the next request will ask the leader again.
+    pub(crate) fn check_header(&self, header: Option<&ResponseHeader>) {
+        if header.map_or(false, |h| h.is_not_leader()) {
+            self.invalidate();
+        }
+    }
+
+    pub(crate) fn invalidate(&self) {
+        self.leader.write().unwrap().take();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get(&self) -> Option<String> {
+        self.leader.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::meta::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_check_header() {
+        let cache = LeaderCache::default();
+        *cache.leader.write().unwrap() = Some("127.0.0.1:3002".to_string());
+
+        cache.check_header(Some(&ResponseHeader::success(0)));
+        cache.check_header(None);
+        assert_eq!(Some("127.0.0.1:3002".to_string()), cache.get());
+
+        cache.check_header(Some(&ResponseHeader::failed(0, Error::not_leader(None))));
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn test_check_status() {
+        let cache = LeaderCache::default();
+        *cache.leader.write().unwrap() = Some("127.0.0.1:3002".to_string());
+
+        assert_eq!(1, cache.check_status(Ok(1)).unwrap());
+        assert_eq!(Some("127.0.0.1:3002".to_string()), cache.get());
+
+        let res: Result<()> = cache.check_status(Err(tonic::Status::unavailable("down")));
+        assert!(matches!(res, Err(error::Error::TonicStatus { .. })));
+        assert!(cache.get().is_none());
+    }
+}
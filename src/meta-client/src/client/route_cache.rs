@@ -0,0 +1,158 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_telemetry::metric::{self, MetricDesc};
+use metrics::increment_counter;
+use moka::future::{Cache, CacheBuilder};
+
+use crate::rpc::{TableName, TableRoute};
+
+pub const METRIC_ROUTE_CACHE_HITS_TOTAL: &str = "meta_client.route_cache_hits_total";
+pub const METRIC_ROUTE_CACHE_MISSES_TOTAL: &str = "meta_client.route_cache_misses_total";
+
+const METRICS: &[MetricDesc] = &[
+    MetricDesc::counter(
+        METRIC_ROUTE_CACHE_HITS_TOTAL,
+        "Number of table route lookups served by the route cache",
+    ),
+    MetricDesc::counter(
+        METRIC_ROUTE_CACHE_MISSES_TOTAL,
+        "Number of table route lookups that have to ask metasrv",
+    ),
+];
+
+/// Config of the table route cache of [MetaClient](crate::client::MetaClient).
+#[derive(Clone, Debug)]
+pub struct RouteCacheConfig {
+    /// Max number of tables whose routes are cached.
+    pub capacity: u64,
+    /// A cached route expires after this duration since it's fetched.
+    pub time_to_live: Duration,
+    /// A cached route expires if it's not read for this duration.
+    pub time_to_idle: Duration,
+}
+
+impl Default for RouteCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            time_to_live: Duration::from_secs(30 * 60),
+            time_to_idle: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Routes of tables keyed by the table name, lookups are recorded as hits or misses
+/// so the hit rate could be derived from the metrics.
+#[derive(Clone)]
+pub(crate) struct RouteCache {
+    cache: Cache<TableName, Arc<TableRoute>>,
+}
+
+impl RouteCache {
+    pub(crate) fn new(config: &RouteCacheConfig) -> Self {
+        metric::register_metrics(METRICS);
+        Self {
+            cache: CacheBuilder::new(config.capacity)
+                .time_to_live(config.time_to_live)
+                .time_to_idle(config.time_to_idle)
+                .build(),
+        }
+    }
+
+    pub(crate) fn get(&self, table_name: &TableName) -> Option<Arc<TableRoute>> {
+        let route = self.cache.get(table_name);
+        if route.is_some() {
+            increment_counter!(METRIC_ROUTE_CACHE_HITS_TOTAL);
+        } else {
+            increment_counter!(METRIC_ROUTE_CACHE_MISSES_TOTAL);
+        }
+        route
+    }
+
+    pub(crate) async fn insert(&self, table_name: TableName, route: Arc<TableRoute>) {
+        self.cache.insert(table_name, route).await
+    }
+
+    pub(crate) async fn invalidate(&self, table_name: &TableName) {
+        self.cache.invalidate(table_name).await
+    }
+}
+
+impl Debug for RouteCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteCache")
+            .field("entry_count", &self.cache.entry_count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::Table;
+
+    fn table_route(table_name: &TableName, id: u64) -> Arc<TableRoute> {
+        Arc::new(TableRoute {
+            table: Table {
+                id,
+                table_name: table_name.clone(),
+                table_schema: vec![],
+            },
+            region_routes: vec![],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_route_cache() {
+        let cache = RouteCache::new(&RouteCacheConfig::default());
+        let table_name = TableName::new("greptime", "public", "test_table");
+        assert!(cache.get(&table_name).is_none());
+
+        cache
+            .insert(table_name.clone(), table_route(&table_name, 1))
+            .await;
+        assert_eq!(1, cache.get(&table_name).unwrap().table.id);
+
+        // The newer route replaces the cached one.
+        cache
+            .insert(table_name.clone(), table_route(&table_name, 2))
+            .await;
+        assert_eq!(2, cache.get(&table_name).unwrap().table.id);
+
+        cache.invalidate(&table_name).await;
+        assert!(cache.get(&table_name).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_route_cache_expired() {
+        let config = RouteCacheConfig {
+            time_to_live: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let cache = RouteCache::new(&config);
+        let table_name = TableName::new("greptime", "public", "test_table");
+        cache
+            .insert(table_name.clone(), table_route(&table_name, 1))
+            .await;
+        assert!(cache.get(&table_name).is_some());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(cache.get(&table_name).is_none());
+    }
+}
@@ -16,12 +16,13 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use api::v1::meta::router_client::RouterClient;
-use api::v1::meta::{CreateRequest, DeleteRequest, RouteRequest, RouteResponse};
+use api::v1::meta::{CreateRequest, DeleteRequest, RenameRequest, RouteRequest, RouteResponse};
 use common_grpc::channel_manager::ChannelManager;
 use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
 
+use crate::client::leader::LeaderCache;
 use crate::client::{load_balance as lb, Id};
 use crate::error;
 use crate::error::Result;
@@ -37,6 +38,7 @@ impl Client {
             id,
             channel_manager,
             peers: vec![],
+            leader: LeaderCache::default(),
         }));
 
         Self { inner }
@@ -70,6 +72,11 @@ impl Client {
         let inner = self.inner.read().await;
         inner.delete(req).await
     }
+
+    pub async fn rename(&self, req: RenameRequest) -> Result<RouteResponse> {
+        let inner = self.inner.read().await;
+        inner.rename(req).await
+    }
 }
 
 #[derive(Debug)]
@@ -77,6 +84,7 @@ struct Inner {
     id: Id,
     channel_manager: ChannelManager,
     peers: Vec<String>,
+    leader: LeaderCache,
 }
 
 impl Inner {
@@ -104,11 +112,15 @@ impl Inner {
     }
 
     async fn create(&self, mut req: CreateRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client.create(req).await.context(error::TonicStatusSnafu)?;
+        let res = self
+            .leader
+            .check_status(client.create(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
 
-        Ok(res.into_inner())
+        Ok(res)
     }
 
     async fn route(&self, mut req: RouteRequest) -> Result<RouteResponse> {
@@ -120,11 +132,27 @@ impl Inner {
     }
 
     async fn delete(&self, mut req: DeleteRequest) -> Result<RouteResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client.delete(req).await.context(error::TonicStatusSnafu)?;
+        let res = self
+            .leader
+            .check_status(client.delete(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
 
-        Ok(res.into_inner())
+        Ok(res)
+    }
+
+    async fn rename(&self, mut req: RenameRequest) -> Result<RouteResponse> {
+        let mut client = self.leader_client().await?;
+        req.set_header(self.id);
+        let res = self
+            .leader
+            .check_status(client.rename(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
     }
 
     fn random_client(&self) -> Result<RouterClient<Channel>> {
@@ -138,13 +166,33 @@ impl Inner {
         self.make_client(peer)
     }
 
+    async fn leader_client(&self) -> Result<RouterClient<Channel>> {
+        ensure!(
+            self.is_started(),
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: "Empty peers, router client may not start yet",
+            }
+        );
+
+        let leader = self
+            .leader
+            .get_or_ask(self.id, &self.channel_manager, &self.peers)
+            .await?;
+
+        self.make_client(leader)
+    }
+
     fn make_client(&self, addr: impl AsRef<str>) -> Result<RouterClient<Channel>> {
         let channel = self
             .channel_manager
             .get(addr)
             .context(error::CreateChannelSnafu)?;
 
-        Ok(RouterClient::new(channel))
+        let mut client = RouterClient::new(channel);
+        if let Some(encoding) = self.channel_manager.config().grpc_compression() {
+            client = client.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(client)
     }
 
     #[inline]
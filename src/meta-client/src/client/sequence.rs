@@ -0,0 +1,212 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use api::v1::meta::sequence_client::SequenceClient;
+use api::v1::meta::{NextRequest, NextResponse};
+use common_grpc::channel_manager::ChannelManager;
+use snafu::{ensure, ResultExt};
+use tokio::sync::{Mutex, RwLock};
+use tonic::transport::Channel;
+
+use crate::client::leader::LeaderCache;
+use crate::client::{Id, MetaClient};
+use crate::error;
+use crate::error::Result;
+
+#[derive(Clone, Debug)]
+pub struct Client {
+    inner: Arc<RwLock<Inner>>,
+}
+
+impl Client {
+    pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        let inner = Arc::new(RwLock::new(Inner {
+            id,
+            channel_manager,
+            peers: vec![],
+            leader: LeaderCache::default(),
+        }));
+
+        Self { inner }
+    }
+
+    pub async fn start<U, A>(&mut self, urls: A) -> Result<()>
+    where
+        U: AsRef<str>,
+        A: AsRef<[U]>,
+    {
+        let mut inner = self.inner.write().await;
+        inner.start(urls).await
+    }
+
+    pub async fn is_started(&self) -> bool {
+        let inner = self.inner.read().await;
+        inner.is_started()
+    }
+
+    pub async fn next(&self, req: NextRequest) -> Result<NextResponse> {
+        let inner = self.inner.read().await;
+        inner.next(req).await
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    id: Id,
+    channel_manager: ChannelManager,
+    peers: Vec<String>,
+    leader: LeaderCache,
+}
+
+impl Inner {
+    async fn start<U, A>(&mut self, urls: A) -> Result<()>
+    where
+        U: AsRef<str>,
+        A: AsRef<[U]>,
+    {
+        ensure!(
+            !self.is_started(),
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: "Sequence client already started",
+            }
+        );
+
+        self.peers = urls
+            .as_ref()
+            .iter()
+            .map(|url| url.as_ref().to_string())
+            .collect::<HashSet<_>>()
+            .drain()
+            .collect::<Vec<_>>();
+
+        Ok(())
+    }
+
+    async fn next(&self, mut req: NextRequest) -> Result<NextResponse> {
+        let mut client = self.leader_client().await?;
+        req.set_header(self.id);
+        let res = self
+            .leader
+            .check_status(client.next(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
+    }
+
+    async fn leader_client(&self) -> Result<SequenceClient<Channel>> {
+        ensure!(
+            self.is_started(),
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: "Empty peers, sequence client may not start yet",
+            }
+        );
+
+        let leader = self
+            .leader
+            .get_or_ask(self.id, &self.channel_manager, &self.peers)
+            .await?;
+
+        self.make_client(leader)
+    }
+
+    fn make_client(&self, addr: impl AsRef<str>) -> Result<SequenceClient<Channel>> {
+        let channel = self
+            .channel_manager
+            .get(addr)
+            .context(error::CreateChannelSnafu)?;
+
+        let mut client = SequenceClient::new(channel);
+        if let Some(encoding) = self.channel_manager.config().grpc_compression() {
+            client = client.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(client)
+    }
+
+    #[inline]
+    fn is_started(&self) -> bool {
+        !self.peers.is_empty()
+    }
+}
+
+/// Allocates ids from a sequence of `metasrv`. It fetches ids in batches and serves the
+/// following allocations from the ids cached locally, so ids allocated by different
+/// clients never collide while most allocations don't need a request to `metasrv`.
+///
+/// Ids cached but not allocated are lost once the allocator is dropped, so the ids
+/// allocated are unique and increasing, but not continuous.
+#[derive(Debug)]
+pub struct IdAllocator {
+    client: MetaClient,
+    name: String,
+    batch_size: u64,
+    cached: Mutex<Range<u64>>,
+}
+
+impl IdAllocator {
+    pub fn new(client: MetaClient, name: impl Into<String>, batch_size: u64) -> Self {
+        Self {
+            client,
+            name: name.into(),
+            batch_size: batch_size.max(1),
+            cached: Mutex::new(0..0),
+        }
+    }
+
+    pub async fn next(&self) -> Result<u64> {
+        Ok(self.next_n(1).await?.start)
+    }
+
+    /// Allocates `n` consecutive ids.
+    pub async fn next_n(&self, n: u64) -> Result<Range<u64>> {
+        let mut cached = self.cached.lock().await;
+        if cached.end - cached.start >= n {
+            let start = cached.start;
+            cached.start += n;
+            return Ok(start..cached.start);
+        }
+
+        let range = self
+            .client
+            .next_sequence(&self.name, n.max(self.batch_size))
+            .await?;
+        ensure!(
+            range.end >= range.start && range.end - range.start >= n,
+            error::InvalidSequenceRangeSnafu {
+                err_msg: format!("{range:?} of sequence {}, expect {n} values", self.name),
+            }
+        );
+        *cached = range.start + n..range.end;
+        Ok(range.start..range.start + n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_start_client() {
+        let mut client = Client::new((0, 0), ChannelManager::default());
+        assert!(!client.is_started().await);
+        client
+            .start(&["127.0.0.1:1000", "127.0.0.1:1001"])
+            .await
+            .unwrap();
+        assert!(client.is_started().await);
+    }
+}
@@ -26,6 +26,7 @@ use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::RwLock;
 use tonic::transport::Channel;
 
+use crate::client::leader::LeaderCache;
 use crate::client::{load_balance as lb, Id};
 use crate::error;
 use crate::error::Result;
@@ -37,10 +38,18 @@ pub struct Client {
 
 impl Client {
     pub fn new(id: Id, channel_manager: ChannelManager) -> Self {
+        Self::with_tenant(id, channel_manager, None)
+    }
+
+    /// Creates a client whose keys are isolated in the namespace of the `tenant` by
+    /// `metasrv`, or in the default namespace if `tenant` is `None`.
+    pub fn with_tenant(id: Id, channel_manager: ChannelManager, tenant: Option<String>) -> Self {
         let inner = Arc::new(RwLock::new(Inner {
             id,
+            tenant,
             channel_manager,
             peers: vec![],
+            leader: LeaderCache::default(),
         }));
 
         Self { inner }
@@ -97,8 +106,10 @@ impl Client {
 #[derive(Debug)]
 struct Inner {
     id: Id,
+    tenant: Option<String>,
     channel_manager: ChannelManager,
     peers: Vec<String>,
+    leader: LeaderCache,
 }
 
 impl Inner {
@@ -128,64 +139,90 @@ impl Inner {
     async fn range(&self, mut req: RangeRequest) -> Result<RangeResponse> {
         let mut client = self.random_client()?;
         req.set_header(self.id);
+        if let Some(tenant) = &self.tenant {
+            req.set_tenant(tenant.as_str());
+        }
         let res = client.range(req).await.context(error::TonicStatusSnafu)?;
 
         Ok(res.into_inner())
     }
 
     async fn put(&self, mut req: PutRequest) -> Result<PutResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client.put(req).await.context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        if let Some(tenant) = &self.tenant {
+            req.set_tenant(tenant.as_str());
+        }
+        let res = self
+            .leader
+            .check_status(client.put(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
     }
 
     async fn batch_put(&self, mut req: BatchPutRequest) -> Result<BatchPutResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client
-            .batch_put(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        if let Some(tenant) = &self.tenant {
+            req.set_tenant(tenant.as_str());
+        }
+        let res = self
+            .leader
+            .check_status(client.batch_put(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
     }
 
     async fn compare_and_put(
         &self,
         mut req: CompareAndPutRequest,
     ) -> Result<CompareAndPutResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client
-            .compare_and_put(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        if let Some(tenant) = &self.tenant {
+            req.set_tenant(tenant.as_str());
+        }
+        let res = self
+            .leader
+            .check_status(client.compare_and_put(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
     }
 
     async fn delete_range(&self, mut req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client
-            .delete_range(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        if let Some(tenant) = &self.tenant {
+            req.set_tenant(tenant.as_str());
+        }
+        let res = self
+            .leader
+            .check_status(client.delete_range(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
     }
 
     async fn move_value(&self, mut req: MoveValueRequest) -> Result<MoveValueResponse> {
-        let mut client = self.random_client()?;
+        let mut client = self.leader_client().await?;
         req.set_header(self.id);
-        let res = client
-            .move_value(req)
-            .await
-            .context(error::TonicStatusSnafu)?;
-
-        Ok(res.into_inner())
+        if let Some(tenant) = &self.tenant {
+            req.set_tenant(tenant.as_str());
+        }
+        let res = self
+            .leader
+            .check_status(client.move_value(req).await)?
+            .into_inner();
+        self.leader.check_header(res.header.as_ref());
+
+        Ok(res)
     }
 
     fn random_client(&self) -> Result<StoreClient<Channel>> {
@@ -199,13 +236,33 @@ impl Inner {
         self.make_client(peer)
     }
 
+    async fn leader_client(&self) -> Result<StoreClient<Channel>> {
+        ensure!(
+            self.is_started(),
+            error::IllegalGrpcClientStateSnafu {
+                err_msg: "Empty peers, store client may not start yet",
+            }
+        );
+
+        let leader = self
+            .leader
+            .get_or_ask(self.id, &self.channel_manager, &self.peers)
+            .await?;
+
+        self.make_client(leader)
+    }
+
     fn make_client(&self, addr: impl AsRef<str>) -> Result<StoreClient<Channel>> {
         let channel = self
             .channel_manager
             .get(addr)
             .context(error::CreateChannelSnafu)?;
 
-        Ok(StoreClient::new(channel))
+        let mut client = StoreClient::new(channel);
+        if let Some(encoding) = self.channel_manager.config().grpc_compression() {
+            client = client.send_compressed(encoding).accept_compressed(encoding);
+        }
+        Ok(client)
     }
 
     #[inline]
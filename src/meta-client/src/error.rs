@@ -66,6 +66,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Invalid sequence range from server: {}", err_msg))]
+    InvalidSequenceRange {
+        err_msg: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Illegal state from server, code: {}, error: {}", code, err_msg))]
     IllegalServerState {
         code: i32,
@@ -98,7 +104,9 @@ impl ErrorExt for Error {
             | Error::CreateHeartbeatStream { .. }
             | Error::CreateChannel { .. }
             | Error::IllegalServerState { .. } => StatusCode::Internal,
-            Error::RouteInfoCorrupted { .. } => StatusCode::Unexpected,
+            Error::RouteInfoCorrupted { .. } | Error::InvalidSequenceRange { .. } => {
+                StatusCode::Unexpected
+            }
         }
     }
 }
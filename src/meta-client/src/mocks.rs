@@ -45,6 +45,8 @@ pub async fn mock_client_by(mock_info: MockInfo) -> MetaClient {
         .enable_heartbeat()
         .enable_router()
         .enable_store()
+        .enable_sequence()
+        .enable_cluster()
         .channel_manager(channel_manager)
         .build();
     meta_client.start(&[&server_addr]).await.unwrap();
@@ -16,8 +16,8 @@ use std::collections::HashMap;
 
 use api::v1::meta::{
     CreateRequest as PbCreateRequest, DeleteRequest as PbDeleteRequest, Partition as PbPartition,
-    Region as PbRegion, RouteRequest as PbRouteRequest, RouteResponse as PbRouteResponse,
-    Table as PbTable,
+    Region as PbRegion, RenameRequest as PbRenameRequest, RouteRequest as PbRouteRequest,
+    RouteResponse as PbRouteResponse, Table as PbTable,
 };
 use serde::{Deserialize, Serialize, Serializer};
 use snafu::OptionExt;
@@ -108,6 +108,32 @@ impl DeleteRequest {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RenameRequest {
+    pub table_name: TableName,
+    pub new_table_name: String,
+}
+
+impl From<RenameRequest> for PbRenameRequest {
+    fn from(req: RenameRequest) -> Self {
+        Self {
+            header: None,
+            table_name: Some(req.table_name.into()),
+            new_table_name: req.new_table_name,
+        }
+    }
+}
+
+impl RenameRequest {
+    #[inline]
+    pub fn new(table_name: TableName, new_table_name: impl Into<String>) -> Self {
+        Self {
+            table_name,
+            new_table_name: new_table_name.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RouteResponse {
     pub table_routes: Vec<TableRoute>,
@@ -298,9 +324,9 @@ impl From<PbPartition> for Partition {
 mod tests {
     use api::v1::meta::{
         DeleteRequest as PbDeleteRequest, Partition as PbPartition, Peer as PbPeer,
-        Region as PbRegion, RegionRoute as PbRegionRoute, RouteRequest as PbRouteRequest,
-        RouteResponse as PbRouteResponse, Table as PbTable, TableName as PbTableName,
-        TableRoute as PbTableRoute,
+        Region as PbRegion, RegionRoute as PbRegionRoute, RenameRequest as PbRenameRequest,
+        RouteRequest as PbRouteRequest, RouteResponse as PbRouteResponse, Table as PbTable,
+        TableName as PbTableName, TableRoute as PbTableRoute,
     };
 
     use super::*;
@@ -380,6 +406,17 @@ mod tests {
         assert_eq!("t1", into_req.table_name.as_ref().unwrap().table_name);
     }
 
+    #[test]
+    fn test_rename_request_trans() {
+        let req = RenameRequest::new(TableName::new("c1", "s1", "t1"), "t2");
+
+        let into_req: PbRenameRequest = req.into();
+
+        assert!(into_req.header.is_none());
+        assert_eq!("t1", into_req.table_name.as_ref().unwrap().table_name);
+        assert_eq!("t2", into_req.new_table_name);
+    }
+
     #[test]
     fn test_route_response_trans() {
         let res = PbRouteResponse {
@@ -36,7 +36,51 @@ pub trait Election: Send + Sync {
     /// Returns the leader value for the current election.
     async fn leader(&self) -> Result<Self::Leader>;
 
+    /// Observe watches the leader changes of the election and caches the
+    /// latest leader, so followers can tell the leader without asking the
+    /// backing store every time.
+    ///
+    /// Returns when the watch is broken.
+    async fn observe(&self) -> Result<()>;
+
     /// Releases election leadership so other campaigners may
     /// acquire leadership on the election.
     async fn resign(&self) -> Result<()>;
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::metasrv::LeaderValue;
+
+    /// An election whose state is fixed, for tests.
+    pub(crate) struct MockElection {
+        pub(crate) is_leader: bool,
+        pub(crate) leader: String,
+    }
+
+    #[async_trait::async_trait]
+    impl Election for MockElection {
+        type Leader = LeaderValue;
+
+        fn is_leader(&self) -> bool {
+            self.is_leader
+        }
+
+        async fn campaign(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn leader(&self) -> Result<LeaderValue> {
+            Ok(LeaderValue(self.leader.clone()))
+        }
+
+        async fn observe(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn resign(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -17,7 +17,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use common_telemetry::{info, warn};
-use etcd_client::Client;
+use etcd_client::{Client, LeaderKey, ResignOptions};
+use parking_lot::{Mutex, RwLock};
 use snafu::{OptionExt, ResultExt};
 
 use crate::election::{Election, ELECTION_KEY, LEASE_SECS, PROCLAIM_PERIOD_SECS};
@@ -29,6 +30,10 @@ pub struct EtcdElection {
     leader_value: String,
     client: Client,
     is_leader: AtomicBool,
+    /// Key of the leadership held by current node, which is required to resign.
+    leader_key: Mutex<Option<LeaderKey>>,
+    /// The latest leader observed from the election.
+    observed_leader: RwLock<Option<String>>,
 }
 
 impl EtcdElection {
@@ -46,8 +51,44 @@ impl EtcdElection {
             leader_value,
             client,
             is_leader: AtomicBool::new(false),
+            leader_key: Mutex::new(None),
+            observed_leader: RwLock::new(None),
         }))
     }
+
+    /// Keeps the lease of the leadership alive until the lease is lost or
+    /// the leadership is resigned.
+    async fn keep_alive(&self, lease_id: i64) -> Result<()> {
+        let (mut keeper, mut receiver) = self
+            .client
+            .lease_client()
+            .keep_alive(lease_id)
+            .await
+            .context(error::EtcdFailedSnafu)?;
+
+        let mut interval = tokio::time::interval(Duration::from_secs(PROCLAIM_PERIOD_SECS));
+        loop {
+            interval.tick().await;
+            if self.leader_key.lock().is_none() {
+                info!("[{}] leadership resigned", &self.leader_value);
+                return Ok(());
+            }
+
+            keeper.keep_alive().await.context(error::EtcdFailedSnafu)?;
+
+            if let Some(res) = receiver.message().await.context(error::EtcdFailedSnafu)? {
+                if res.ttl() > 0 {
+                    self.is_leader.store(true, Ordering::Relaxed);
+                } else {
+                    warn!(
+                        "Already lost leader status, lease: {}, will re-initiate election",
+                        lease_id
+                    );
+                    return Ok(());
+                }
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -83,32 +124,22 @@ impl Election for EtcdElection {
                 leader.lease()
             );
 
-            let (mut keeper, mut receiver) = self
-                .client
-                .lease_client()
-                .keep_alive(lease_id)
-                .await
-                .context(error::EtcdFailedSnafu)?;
+            *self.leader_key.lock() = Some(leader.clone());
 
-            let mut interval = tokio::time::interval(Duration::from_secs(PROCLAIM_PERIOD_SECS));
-            loop {
-                interval.tick().await;
-                keeper.keep_alive().await.context(error::EtcdFailedSnafu)?;
-
-                if let Some(res) = receiver.message().await.context(error::EtcdFailedSnafu)? {
-                    if res.ttl() > 0 {
-                        self.is_leader.store(true, Ordering::Relaxed);
-                    } else {
-                        warn!(
-                            "Already lost leader status, lease: {}, will re-initiate election",
-                            leader.lease()
-                        );
-                        break;
-                    }
-                }
-            }
+            let res = self.keep_alive(lease_id).await;
 
             self.is_leader.store(false, Ordering::Relaxed);
+            self.leader_key.lock().take();
+            // Revokes the lease so the other campaigners don't need to wait
+            // for it to expire.
+            if let Err(e) = lease_client.revoke(lease_id).await {
+                warn!(
+                    "Failed to revoke election lease: {}, error: {}",
+                    lease_id, e
+                );
+            }
+
+            res?;
         }
 
         Ok(())
@@ -116,7 +147,12 @@ impl Election for EtcdElection {
 
     async fn leader(&self) -> Result<LeaderValue> {
         if self.is_leader.load(Ordering::Relaxed) {
-            Ok(LeaderValue(self.leader_value.clone()))
+            return Ok(LeaderValue(self.leader_value.clone()));
+        }
+
+        let observed_leader = self.observed_leader.read().clone();
+        if let Some(leader_value) = observed_leader {
+            Ok(LeaderValue(leader_value))
         } else {
             let res = self
                 .client
@@ -130,7 +166,48 @@ impl Election for EtcdElection {
         }
     }
 
+    async fn observe(&self) -> Result<()> {
+        let mut stream = self
+            .client
+            .election_client()
+            .observe(ELECTION_KEY)
+            .await
+            .context(error::EtcdFailedSnafu)?;
+
+        let res = async {
+            while let Some(res) = stream.message().await.context(error::EtcdFailedSnafu)? {
+                let leader_value = res
+                    .kv()
+                    .map(|kv| String::from_utf8_lossy(kv.value()).to_string());
+                info!(
+                    "[{}] observed leader: {:?}",
+                    &self.leader_value, leader_value
+                );
+                *self.observed_leader.write() = leader_value;
+            }
+            Ok(())
+        }
+        .await;
+
+        // The cached leader can't be trusted once the watch is broken.
+        self.observed_leader.write().take();
+
+        res
+    }
+
     async fn resign(&self) -> Result<()> {
-        todo!()
+        let leader_key = self.leader_key.lock().take();
+        if let Some(leader_key) = leader_key {
+            self.is_leader.store(false, Ordering::Relaxed);
+            self.client
+                .election_client()
+                .resign(Some(ResignOptions::new().with_leader(leader_key)))
+                .await
+                .context(error::EtcdFailedSnafu)?;
+
+            info!("[{}] resigned leadership", &self.leader_value);
+        }
+
+        Ok(())
     }
 }
@@ -24,6 +24,15 @@ pub enum Error {
     #[snafu(display("Empty key is not allowed"))]
     EmptyKey { backtrace: Backtrace },
 
+    #[snafu(display(
+        "Invalid tenant: {}, only ASCII alphanumerics, '_' and '-' are allowed",
+        tenant
+    ))]
+    InvalidTenant {
+        tenant: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to execute via Etcd, source: {}", source))]
     EtcdFailed {
         source: etcd_client::Error,
@@ -112,6 +121,19 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display(
+        "Unsupported {} value version: {}, current version: {}",
+        name,
+        version,
+        current
+    ))]
+    UnsupportedValueVersion {
+        name: String,
+        version: u32,
+        current: u32,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Table route not found: {}", key))]
     TableRouteNotFound { key: String, backtrace: Backtrace },
 
@@ -121,17 +143,46 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Sequence {} not found", name))]
+    SequenceNotFound { name: String, backtrace: Backtrace },
+
     #[snafu(display("MetaSrv has no leader at this moment"))]
     NoLeader { backtrace: Backtrace },
 
     #[snafu(display("Table {} not found", name))]
     TableNotFound { name: String, backtrace: Backtrace },
 
+    #[snafu(display("Table {} already exists", name))]
+    TableAlreadyExists { name: String, backtrace: Backtrace },
+
     #[snafu(display(
         "Failed to move the value of {} because other clients caused a race condition",
         key
     ))]
     MoveValue { key: String, backtrace: Backtrace },
+
+    #[snafu(display("Region {} of table {} not found", region_number, table))]
+    RegionNotFound {
+        table: String,
+        region_number: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Region {} of table {} is being migrated, state: {}",
+        region_number,
+        table,
+        state
+    ))]
+    RegionMigrating {
+        table: String,
+        region_number: u32,
+        state: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Datanode {} is not alive", node_id))]
+    DatanodeNotAlive { node_id: u64, backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -160,13 +211,19 @@ impl ErrorExt for Error {
             | Error::SerializeToJson { .. }
             | Error::DeserializeFromJson { .. }
             | Error::DecodeTableRoute { .. }
+            | Error::UnsupportedValueVersion { .. }
             | Error::NoLeader { .. }
             | Error::StartGrpc { .. } => StatusCode::Internal,
             Error::EmptyKey { .. }
+            | Error::InvalidTenant { .. }
             | Error::EmptyTableName { .. }
             | Error::InvalidLeaseKey { .. }
             | Error::ParseNum { .. }
-            | Error::InvalidArguments { .. } => StatusCode::InvalidArguments,
+            | Error::InvalidArguments { .. }
+            | Error::RegionNotFound { .. }
+            | Error::RegionMigrating { .. }
+            | Error::SequenceNotFound { .. }
+            | Error::DatanodeNotAlive { .. } => StatusCode::InvalidArguments,
             Error::LeaseKeyFromUtf8 { .. }
             | Error::UnexceptedSequenceValue { .. }
             | Error::TableRouteNotFound { .. }
@@ -174,6 +231,7 @@ impl ErrorExt for Error {
             | Error::MoveValue { .. }
             | Error::InvalidTxnResult { .. } => StatusCode::Unexpected,
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
+            Error::TableAlreadyExists { .. } => StatusCode::TableAlreadyExists,
             Error::InvalidCatalogValue { source, .. } => source.status_code(),
         }
     }
@@ -14,12 +14,13 @@
 
 pub(crate) mod check_leader;
 pub(crate) mod datanode_lease;
+pub(crate) mod region_migration;
 pub(crate) mod response_header;
 
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, ResponseHeader};
+use api::v1::meta::{HeartbeatRequest, HeartbeatResponse, Instruction, ResponseHeader};
 use common_telemetry::info;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
@@ -46,17 +47,13 @@ pub struct HeartbeatAccumulator {
 
 impl HeartbeatAccumulator {
     pub fn into_payload(self) -> Vec<Vec<u8>> {
-        // TODO(jiachun): to HeartbeatResponse payload
-        vec![]
+        self.instructions.into_iter().map(Into::into).collect()
     }
 }
 
 #[derive(Debug)]
 pub enum State {}
 
-#[derive(Debug)]
-pub enum Instruction {}
-
 pub type Pusher = Sender<std::result::Result<HeartbeatResponse, tonic::Status>>;
 
 #[derive(Clone, Default)]
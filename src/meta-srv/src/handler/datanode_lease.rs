@@ -12,13 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api::v1::meta::{HeartbeatRequest, PutRequest};
+use api::v1::meta::{DeleteRangeRequest, HeartbeatRequest, PutRequest};
 use common_telemetry::info;
 use common_time::util as time_util;
 
 use crate::error::Result;
 use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
-use crate::keys::{LeaseKey, LeaseValue};
+use crate::keys::{DatanodeLoad, LeaseKey, LeaseValue};
 use crate::metasrv::Context;
 
 pub struct DatanodeLeaseHandler;
@@ -35,15 +35,43 @@ impl HeartbeatHandler for DatanodeLeaseHandler {
             return Ok(());
         }
 
-        let HeartbeatRequest { header, peer, .. } = req;
+        let HeartbeatRequest {
+            header,
+            peer,
+            is_leaving,
+            node_stat,
+            region_stats,
+            ..
+        } = req;
         if let Some(peer) = &peer {
             let key = LeaseKey {
                 cluster_id: header.as_ref().map_or(0, |h| h.cluster_id),
                 node_id: peer.id,
             };
+
+            if *is_leaving {
+                // The datanode is shutting down, remove its lease so it won't be
+                // selected any more.
+                info!("Datanode is leaving: {:?}, addr: {}", key, peer.addr);
+
+                let delete = DeleteRangeRequest {
+                    key: key.try_into()?,
+                    ..Default::default()
+                };
+                ctx.kv_store.delete_range(delete).await?;
+                return Ok(());
+            }
+
             let value = LeaseValue {
                 timestamp_millis: time_util::current_time_millis(),
                 node_addr: peer.addr.clone(),
+                load: DatanodeLoad {
+                    region_num: node_stat.as_ref().map_or(0, |s| s.region_num),
+                    table_num: node_stat.as_ref().map_or(0, |s| s.table_num),
+                    wcus: node_stat.as_ref().map_or(0, |s| s.wcus),
+                    rcus: node_stat.as_ref().map_or(0, |s| s.rcus),
+                    approximate_size: region_stats.iter().map(|s| s.approximate_size).sum(),
+                },
             };
 
             info!("Receive a heartbeat: {:?}, {:?}", key, value);
@@ -68,7 +96,7 @@ mod tests {
     use std::sync::atomic::AtomicBool;
     use std::sync::Arc;
 
-    use api::v1::meta::{Peer, RangeRequest, RequestHeader};
+    use api::v1::meta::{NodeStat, Peer, RangeRequest, RegionStat, RequestHeader};
 
     use super::*;
     use crate::service::store::memory::MemStore;
@@ -90,6 +118,22 @@ mod tests {
                 id: 3,
                 addr: "127.0.0.1:1111".to_string(),
             }),
+            node_stat: Some(NodeStat {
+                region_num: 2,
+                table_num: 1,
+                wcus: 10,
+                ..Default::default()
+            }),
+            region_stats: vec![
+                RegionStat {
+                    approximate_size: 100,
+                    ..Default::default()
+                },
+                RegionStat {
+                    approximate_size: 200,
+                    ..Default::default()
+                },
+            ],
             ..Default::default()
         };
         let mut acc = HeartbeatAccumulator::default();
@@ -107,8 +151,37 @@ mod tests {
             ..Default::default()
         };
 
-        let res = ctx.kv_store.range(req).await.unwrap();
+        let res = ctx.kv_store.range(req.clone()).await.unwrap();
 
         assert_eq!(1, res.kvs.len());
+        let value: LeaseValue = res.kvs[0].value.clone().try_into().unwrap();
+        assert_eq!(
+            DatanodeLoad {
+                region_num: 2,
+                table_num: 1,
+                wcus: 10,
+                rcus: 0,
+                approximate_size: 300,
+            },
+            value.load
+        );
+
+        // The lease is removed once the datanode is leaving.
+        let leaving_req = HeartbeatRequest {
+            header: Some(RequestHeader::new((1, 2))),
+            peer: Some(Peer {
+                id: 3,
+                addr: "127.0.0.1:1111".to_string(),
+            }),
+            is_leaving: true,
+            ..Default::default()
+        };
+        lease_handler
+            .handle(&leaving_req, &ctx, &mut acc)
+            .await
+            .unwrap();
+
+        let res = ctx.kv_store.range(req).await.unwrap();
+        assert!(res.kvs.is_empty());
     }
 }
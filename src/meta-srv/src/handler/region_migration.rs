@@ -0,0 +1,93 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::HeartbeatRequest;
+
+use crate::error::Result;
+use crate::handler::{HeartbeatAccumulator, HeartbeatHandler};
+use crate::metasrv::Context;
+use crate::region_migration;
+
+/// Advances the region migrations by the instruction replies in heartbeats, and sends
+/// the instructions of the migrations to the datanodes.
+pub struct RegionMigrationHandler;
+
+#[async_trait::async_trait]
+impl HeartbeatHandler for RegionMigrationHandler {
+    async fn handle(
+        &self,
+        req: &HeartbeatRequest,
+        ctx: &Context,
+        acc: &mut HeartbeatAccumulator,
+    ) -> Result<()> {
+        if ctx.is_skip_all() {
+            return Ok(());
+        }
+
+        let Some(peer) = &req.peer else {
+            return Ok(());
+        };
+        for reply in &req.instruction_replies {
+            region_migration::handle_reply(&ctx.kv_store, peer.id, reply).await?;
+        }
+        // A leaving datanode won't execute instructions any more.
+        if !req.is_leaving {
+            let instructions = region_migration::instructions_for(&ctx.kv_store, peer.id).await?;
+            acc.instructions.extend(instructions);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::{Instruction, Peer};
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_handle_without_migrations() {
+        let ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            kv_store: Arc::new(MemStore::new()),
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+        };
+        let req = HeartbeatRequest {
+            peer: Some(Peer {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+            }),
+            ..Default::default()
+        };
+        let mut acc = HeartbeatAccumulator {
+            instructions: vec![Instruction::default()],
+            ..Default::default()
+        };
+
+        RegionMigrationHandler
+            .handle(&req, &ctx, &mut acc)
+            .await
+            .unwrap();
+
+        assert_eq!(1, acc.instructions.len());
+        assert_eq!(1, acc.into_payload().len());
+    }
+}
@@ -27,7 +27,8 @@ use crate::error::Result;
 pub(crate) const REMOVED_PREFIX: &str = "__removed";
 pub(crate) const DN_LEASE_PREFIX: &str = "__meta_dnlease";
 pub(crate) const SEQ_PREFIX: &str = "__meta_seq";
-pub(crate) const TABLE_ROUTE_PREFIX: &str = "__meta_table_route";
+pub(crate) const REGION_MIGRATION_PREFIX: &str = "__meta_region_migration";
+pub(crate) const TABLE_ROUTE_PREFIX: &str = catalog::helper::TABLE_ROUTE_KEY_PREFIX;
 
 lazy_static! {
     static ref DATANODE_KEY_PATTERN: Regex =
@@ -92,6 +93,23 @@ pub struct LeaseValue {
     // last activity
     pub timestamp_millis: i64,
     pub node_addr: String,
+    // load reported in the last heartbeat, absent in values written by older versions
+    #[serde(default)]
+    pub load: DatanodeLoad,
+}
+
+/// Load of a datanode reported in its heartbeat.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DatanodeLoad {
+    pub region_num: u64,
+    #[serde(default)]
+    pub table_num: u64,
+    /// Rows written since the last heartbeat.
+    pub wcus: u64,
+    /// Scans since the last heartbeat.
+    pub rcus: u64,
+    /// Approximate size of the regions on the datanode.
+    pub approximate_size: u64,
 }
 
 impl FromStr for LeaseValue {
@@ -191,11 +209,36 @@ mod tests {
         let value = LeaseValue {
             timestamp_millis: 111,
             node_addr: "127.0.0.1:3002".to_string(),
+            load: DatanodeLoad {
+                region_num: 2,
+                ..Default::default()
+            },
         };
 
         let value_bytes: Vec<u8> = value.clone().try_into().unwrap();
         let new_value: LeaseValue = value_bytes.try_into().unwrap();
 
         assert_eq!(new_value, value);
+
+        // Values without the load.
+        let value_bytes = br#"{"timestamp_millis":111,"node_addr":"127.0.0.1:3002"}"#.to_vec();
+        let new_value: LeaseValue = value_bytes.try_into().unwrap();
+        assert_eq!(DatanodeLoad::default(), new_value.load);
+    }
+
+    #[test]
+    fn test_table_route_key() {
+        let key = TableRouteKey {
+            table_id: 1024,
+            catalog_name: "greptime",
+            schema_name: "public",
+            table_name: "demo",
+        };
+
+        // Frontend builds the key by itself to clean up the route of a failed creation.
+        assert_eq!(
+            catalog::helper::build_table_route_key("greptime", "public", "demo", 1024),
+            key.key()
+        );
     }
 }
@@ -20,8 +20,10 @@ pub mod handler;
 mod keys;
 pub mod lease;
 pub mod metasrv;
+pub mod migration;
 #[cfg(feature = "mock")]
 pub mod mocks;
+pub mod region_migration;
 pub mod selector;
 mod sequence;
 pub mod service;
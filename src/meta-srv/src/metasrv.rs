@@ -14,20 +14,23 @@
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use api::v1::meta::Peer;
+use api::v1::meta::{Error, Peer, ResponseHeader};
 use common_telemetry::{info, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::election::Election;
 use crate::handler::check_leader::CheckLeaderHandler;
 use crate::handler::datanode_lease::DatanodeLeaseHandler;
+use crate::handler::region_migration::RegionMigrationHandler;
 use crate::handler::response_header::ResponseHeaderHandler;
 use crate::handler::HeartbeatHandlerGroup;
 use crate::selector::lease_based::LeaseBasedSelector;
 use crate::selector::Selector;
 use crate::sequence::{Sequence, SequenceRef};
 use crate::service::store::kv::KvStoreRef;
+use crate::service::store::tenant::TenantKvStore;
 
 pub const TABLE_ID_SEQ: &str = "table_id";
 
@@ -79,6 +82,7 @@ pub struct MetaSrv {
     started: Arc<AtomicBool>,
     options: MetaSrvOptions,
     kv_store: KvStoreRef,
+    tenant_kv_store: KvStoreRef,
     table_id_sequence: SequenceRef,
     selector: SelectorRef,
     handler_group: HeartbeatHandlerGroup,
@@ -93,17 +97,20 @@ impl MetaSrv {
         election: Option<ElectionRef>,
     ) -> Self {
         let started = Arc::new(AtomicBool::new(false));
+        let tenant_kv_store = Arc::new(TenantKvStore::new(kv_store.clone()));
         let table_id_sequence = Arc::new(Sequence::new(TABLE_ID_SEQ, 1024, 10, kv_store.clone()));
         let selector = selector.unwrap_or_else(|| Arc::new(LeaseBasedSelector {}));
         let handler_group = HeartbeatHandlerGroup::default();
         handler_group.add_handler(ResponseHeaderHandler).await;
         handler_group.add_handler(CheckLeaderHandler).await;
         handler_group.add_handler(DatanodeLeaseHandler).await;
+        handler_group.add_handler(RegionMigrationHandler).await;
 
         Self {
             started,
             options,
             kv_store,
+            tenant_kv_store,
             table_id_sequence,
             selector,
             handler_group,
@@ -134,6 +141,18 @@ impl MetaSrv {
                 }
                 info!("MetaSrv stopped");
             });
+
+            let election = election.clone();
+            let started = self.started.clone();
+            common_runtime::spawn_bg(async move {
+                while started.load(Ordering::Relaxed) {
+                    if let Err(e) = election.observe().await {
+                        warn!("MetaSrv observe election error: {}", e);
+                    }
+                    // Avoids busy looping when the backing store is unavailable.
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            });
         }
 
         info!("MetaSrv started");
@@ -141,6 +160,46 @@ impl MetaSrv {
 
     pub fn shutdown(&self) {
         self.started.store(false, Ordering::Relaxed);
+
+        if let Some(election) = self.election() {
+            // Resigns the leadership so the followers can take over at once
+            // instead of waiting for the lease to expire.
+            common_runtime::spawn_bg(async move {
+                if let Err(e) = election.resign().await {
+                    warn!("MetaSrv resign error: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Returns `true` if current node is the leader, a node without election
+    /// is always the leader.
+    #[inline]
+    pub fn is_leader(&self) -> bool {
+        self.election
+            .as_ref()
+            .map_or(true, |election| election.is_leader())
+    }
+
+    /// Returns the header of a `NotLeader` response, with the leader as a hint,
+    /// if current node is not the leader. Only the leader serves mutations.
+    pub async fn not_leader_header(&self, cluster_id: u64) -> Option<ResponseHeader> {
+        let election = self.election.as_ref()?;
+        if election.is_leader() {
+            return None;
+        }
+
+        let leader = match election.leader().await {
+            Ok(leader) => Some(leader.0),
+            Err(e) => {
+                warn!("Failed to get leader of MetaSrv: {}", e);
+                None
+            }
+        };
+        Some(ResponseHeader::failed(
+            cluster_id,
+            Error::not_leader(leader.as_deref()),
+        ))
     }
 
     #[inline]
@@ -153,11 +212,27 @@ impl MetaSrv {
         self.kv_store.clone()
     }
 
+    /// Returns the kv store serving the store API, which isolates the keys of tenants
+    /// specified in request headers.
+    #[inline]
+    pub fn tenant_kv_store(&self) -> KvStoreRef {
+        self.tenant_kv_store.clone()
+    }
+
     #[inline]
     pub fn table_id_sequence(&self) -> SequenceRef {
         self.table_id_sequence.clone()
     }
 
+    /// Returns the sequence served by the sequence API, or `None` if there is no
+    /// sequence of the `name`.
+    pub fn sequence(&self, name: &str) -> Option<SequenceRef> {
+        match name {
+            TABLE_ID_SEQ => Some(self.table_id_sequence()),
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn selector(&self) -> SelectorRef {
         self.selector.clone()
@@ -0,0 +1,285 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Versioned encoding of the table routes, and the runner that upgrades the values
+//! stored in the kv store to the current encodings.
+//!
+//! Values in old encodings are upgraded when they are read, and always written in
+//! the current encodings. The runner rewrites all values still stored in old
+//! encodings, so the migrations of those encodings can be removed eventually.
+
+use api::v1::meta::{CompareAndPutRequest, RangeRequest, TableRouteValue};
+use catalog::helper::versioned::VersionedValue;
+use catalog::helper::{self, TableGlobalValue};
+use common_telemetry::info;
+use serde::Serialize;
+use snafu::{ensure, ResultExt};
+
+use crate::error::Result;
+use crate::keys::TABLE_ROUTE_PREFIX;
+use crate::service::store::kv::KvStoreRef;
+use crate::{error, util};
+
+/// Current version of [TableRouteValue]. Version 0 is the encoding before the
+/// `version` field was added.
+pub const TABLE_ROUTE_VALUE_VERSION: u32 = 1;
+
+/// Upgrades a table route value from one version to the next.
+type TableRouteMigration = fn(TableRouteValue) -> Result<TableRouteValue>;
+
+/// The `i`-th migration upgrades version `i` to `i + 1`.
+const TABLE_ROUTE_MIGRATIONS: [TableRouteMigration; TABLE_ROUTE_VALUE_VERSION as usize] = [
+    // Version 1 has the same layout as version 0.
+    Ok,
+];
+
+/// Encodes the table route value in current version.
+pub fn encode_table_route_value(mut value: TableRouteValue) -> Vec<u8> {
+    value.version = TABLE_ROUTE_VALUE_VERSION;
+    value.into()
+}
+
+/// Decodes the table route value and upgrades it to the current version, returns
+/// the value with the version it was encoded in.
+pub fn decode_table_route_value(bytes: &[u8]) -> Result<(TableRouteValue, u32)> {
+    let mut value: TableRouteValue = bytes.try_into().context(error::DecodeTableRouteSnafu)?;
+    let version = value.version;
+    ensure!(
+        version <= TABLE_ROUTE_VALUE_VERSION,
+        error::UnsupportedValueVersionSnafu {
+            name: "table route",
+            version,
+            current: TABLE_ROUTE_VALUE_VERSION,
+        }
+    );
+
+    for migration in &TABLE_ROUTE_MIGRATIONS[version as usize..] {
+        value = migration(value)?;
+    }
+    value.version = TABLE_ROUTE_VALUE_VERSION;
+
+    Ok((value, version))
+}
+
+/// Number of values upgraded by [migrate].
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    pub table_global_values: usize,
+    pub table_routes: usize,
+}
+
+/// Rewrites the table global values and the table routes stored in old encodings
+/// with the current encodings.
+///
+/// Values are rewritten by compare-and-put, so a value updated concurrently is
+/// left as it is, since it's already written in the current encoding.
+pub async fn migrate(kv_store: &KvStoreRef) -> Result<MigrationReport> {
+    let mut report = MigrationReport::default();
+
+    for (key, bytes) in range_prefix(kv_store, helper::build_all_table_global_prefix()).await? {
+        let (value, version) =
+            TableGlobalValue::parse_with_version(String::from_utf8_lossy(&bytes))
+                .context(error::InvalidCatalogValueSnafu)?;
+        if version < TableGlobalValue::VERSION {
+            let new_bytes = value.as_bytes().context(error::InvalidCatalogValueSnafu)?;
+            if compare_and_put(kv_store, key, bytes, new_bytes).await? {
+                report.table_global_values += 1;
+            }
+        }
+    }
+
+    for (key, bytes) in range_prefix(kv_store, format!("{TABLE_ROUTE_PREFIX}-")).await? {
+        let (value, version) = decode_table_route_value(&bytes)?;
+        if version < TABLE_ROUTE_VALUE_VERSION {
+            let new_bytes = encode_table_route_value(value);
+            if compare_and_put(kv_store, key, bytes, new_bytes).await? {
+                report.table_routes += 1;
+            }
+        }
+    }
+
+    info!("Meta kv store migrated: {:?}", report);
+
+    Ok(report)
+}
+
+pub(crate) async fn range_prefix(
+    kv_store: &KvStoreRef,
+    prefix: String,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let key = prefix.into_bytes();
+    let range_end = util::get_prefix_end_key(&key);
+    let req = RangeRequest {
+        key,
+        range_end,
+        ..Default::default()
+    };
+    let res = kv_store.range(req).await?;
+
+    Ok(res.kvs.into_iter().map(|kv| (kv.key, kv.value)).collect())
+}
+
+pub(crate) async fn compare_and_put(
+    kv_store: &KvStoreRef,
+    key: Vec<u8>,
+    expect: Vec<u8>,
+    value: Vec<u8>,
+) -> Result<bool> {
+    let req = CompareAndPutRequest {
+        key,
+        expect,
+        value,
+        ..Default::default()
+    };
+    let res = kv_store.compare_and_put(req).await?;
+
+    Ok(res.success)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use api::v1::meta::{Peer, PutRequest};
+    use catalog::helper::TableGlobalKey;
+    use datatypes::schema::RawSchema;
+    use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    fn new_table_global_value() -> TableGlobalValue {
+        let meta = RawTableMeta {
+            schema: RawSchema {
+                column_schemas: vec![],
+                timestamp_index: None,
+                version: 0,
+            },
+            primary_key_indices: vec![],
+            value_indices: vec![],
+            engine: "mito".to_string(),
+            next_column_id: 0,
+            region_numbers: vec![0],
+            engine_options: Default::default(),
+            options: Default::default(),
+            created_on: Default::default(),
+        };
+        let table_info = RawTableInfo {
+            ident: TableIdent {
+                table_id: 1024,
+                version: 0,
+            },
+            name: "t".to_string(),
+            desc: None,
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            meta,
+            table_type: TableType::Base,
+        };
+        TableGlobalValue {
+            node_id: 1,
+            regions_id_map: HashMap::from([(1, vec![0])]),
+            table_info,
+        }
+    }
+
+    fn new_table_route_value() -> TableRouteValue {
+        TableRouteValue {
+            peers: vec![Peer {
+                id: 1,
+                addr: "127.0.0.1:3001".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_table_route_value() {
+        let value = new_table_route_value();
+        let bytes = encode_table_route_value(value.clone());
+        let (decoded, version) = decode_table_route_value(&bytes).unwrap();
+        assert_eq!(TABLE_ROUTE_VALUE_VERSION, version);
+        assert_eq!(value.peers, decoded.peers);
+
+        // Unversioned value.
+        let bytes: Vec<u8> = value.clone().into();
+        let (decoded, version) = decode_table_route_value(&bytes).unwrap();
+        assert_eq!(0, version);
+        assert_eq!(TABLE_ROUTE_VALUE_VERSION, decoded.version);
+
+        let newer = TableRouteValue {
+            version: TABLE_ROUTE_VALUE_VERSION + 1,
+            ..value
+        };
+        let bytes: Vec<u8> = newer.into();
+        let err = decode_table_route_value(&bytes).unwrap_err();
+        assert!(
+            matches!(err, error::Error::UnsupportedValueVersion { .. }),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+
+        let tgk = TableGlobalKey {
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            table_name: "t".to_string(),
+        };
+        let tgv = new_table_global_value();
+        let trk = format!("{TABLE_ROUTE_PREFIX}-c-s-t-1024");
+        // Writes values in the unversioned encodings.
+        let puts = [
+            (
+                tgk.to_string().into_bytes(),
+                serde_json::to_vec(&tgv).unwrap(),
+            ),
+            (trk.clone().into_bytes(), new_table_route_value().into()),
+        ];
+        for (key, value) in puts {
+            let req = PutRequest {
+                key,
+                value,
+                ..Default::default()
+            };
+            let _ = kv_store.put(req).await.unwrap();
+        }
+
+        let report = migrate(&kv_store).await.unwrap();
+        assert_eq!(
+            MigrationReport {
+                table_global_values: 1,
+                table_routes: 1,
+            },
+            report
+        );
+
+        let kvs = range_prefix(&kv_store, tgk.to_string()).await.unwrap();
+        let (value, version) =
+            TableGlobalValue::parse_with_version(String::from_utf8_lossy(&kvs[0].1)).unwrap();
+        assert_eq!(tgv, value);
+        assert_eq!(TableGlobalValue::VERSION, version);
+
+        let kvs = range_prefix(&kv_store, trk).await.unwrap();
+        let (_, version) = decode_table_route_value(&kvs[0].1).unwrap();
+        assert_eq!(TABLE_ROUTE_VALUE_VERSION, version);
+
+        // Nothing to migrate any more.
+        let report = migrate(&kv_store).await.unwrap();
+        assert_eq!(MigrationReport::default(), report);
+    }
+}
@@ -14,8 +14,10 @@
 
 use std::sync::Arc;
 
+use api::v1::meta::cluster_server::ClusterServer;
 use api::v1::meta::heartbeat_server::HeartbeatServer;
 use api::v1::meta::router_server::RouterServer;
+use api::v1::meta::sequence_server::SequenceServer;
 use api::v1::meta::store_server::StoreServer;
 use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use tower::service_fn;
@@ -59,6 +61,8 @@ pub async fn mock(
             .add_service(HeartbeatServer::new(meta_srv.clone()))
             .add_service(RouterServer::new(meta_srv.clone()))
             .add_service(StoreServer::new(meta_srv.clone()))
+            .add_service(SequenceServer::new(meta_srv.clone()))
+            .add_service(ClusterServer::new(meta_srv.clone()))
             .serve_with_incoming(futures::stream::iter(vec![Ok::<_, std::io::Error>(server)]))
             .await
     });
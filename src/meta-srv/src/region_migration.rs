@@ -0,0 +1,794 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migration of a region from a datanode to another.
+//!
+//! A migration is driven by the heartbeats of the datanodes. Metasrv sends instructions
+//! to the datanodes in heartbeat responses, and advances the migration by their replies
+//! in later heartbeats:
+//!
+//! 1. [Snapshotting](MigrationState::Snapshotting): the source datanode fences the
+//!    region from writes and flushes it, so all rows of the region could be opened from
+//!    the object store shared by the datanodes. Writes to the region fail from now on
+//!    until the route is flipped, instead of being lost with the source.
+//! 2. [Opening](MigrationState::Opening): the target datanode opens the region.
+//! 3. [FlippingRoute](MigrationState::FlippingRoute): the leader of the region in the
+//!    table route is flipped to the target datanode by compare-and-put, then the region
+//!    is moved to the target in the table global value.
+//! 4. [Closing](MigrationState::Closing): the source datanode closes the region and
+//!    removes it from the node. The migration is removed once the source confirms.
+//!
+//! If a datanode fails to execute the instruction before the route is flipped, the
+//! migration is [aborted](MigrationState::Aborting): the source unfences the region and
+//! keeps serving it, then the migration stops in the [Failed](MigrationState::Failed)
+//! state. A failure after the route is flipped stops the migration directly, as the
+//! region is served by the target.
+
+use api::v1::meta::{
+    instruction, CloseRegion, DeleteRangeRequest, Instruction, InstructionReply, OpenRegion, Peer,
+    RangeRequest, RegionIdent, SnapshotRegion, TableName, TableRouteValue, UnfenceRegion,
+};
+use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use common_telemetry::{info, warn};
+use common_time::util as time_util;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::error::{self, Result};
+use crate::keys::{TableRouteKey, REGION_MIGRATION_PREFIX};
+use crate::migration::{self, compare_and_put, range_prefix};
+use crate::service::store::kv::KvStoreRef;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationPeer {
+    pub id: u64,
+    pub addr: String,
+}
+
+impl From<&Peer> for MigrationPeer {
+    fn from(peer: &Peer) -> Self {
+        Self {
+            id: peer.id,
+            addr: peer.addr.clone(),
+        }
+    }
+}
+
+impl From<&MigrationPeer> for Peer {
+    fn from(peer: &MigrationPeer) -> Self {
+        Self {
+            id: peer.id,
+            addr: peer.addr.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrationState {
+    /// The source datanode is fencing and flushing the region.
+    Snapshotting,
+    /// The target datanode is opening the region.
+    Opening,
+    /// The target datanode has opened the region, the route of the region is being
+    /// moved to it.
+    FlippingRoute,
+    /// The source datanode is closing the region.
+    Closing,
+    /// The migration failed before the route is flipped, the source datanode is
+    /// unfencing the region.
+    Aborting { reason: String },
+    /// The migration is stopped as a datanode failed to execute the instruction.
+    Failed { reason: String },
+}
+
+/// A region migration in progress, stored in the kv store until it completes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionMigration {
+    pub id: u64,
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    pub table_id: u32,
+    pub region_number: u32,
+    pub from: MigrationPeer,
+    pub to: MigrationPeer,
+    pub state: MigrationState,
+}
+
+impl RegionMigration {
+    fn key(&self) -> String {
+        migration_key(self.table_id, self.region_number)
+    }
+
+    fn full_table_name(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.catalog_name, self.schema_name, self.table_name
+        )
+    }
+
+    fn table_global_key(&self) -> TableGlobalKey {
+        TableGlobalKey {
+            catalog_name: self.catalog_name.clone(),
+            schema_name: self.schema_name.clone(),
+            table_name: self.table_name.clone(),
+        }
+    }
+
+    /// Returns the instruction to the datanode `peer_id` in current state, `None` if the
+    /// datanode has nothing to do.
+    fn instruction_to(&self, peer_id: u64) -> Option<Instruction> {
+        let kind = match self.state {
+            MigrationState::Snapshotting if peer_id == self.from.id => {
+                instruction::Kind::SnapshotRegion(SnapshotRegion {})
+            }
+            MigrationState::Opening if peer_id == self.to.id => {
+                instruction::Kind::OpenRegion(OpenRegion {})
+            }
+            MigrationState::Closing if peer_id == self.from.id => {
+                instruction::Kind::CloseRegion(CloseRegion {})
+            }
+            MigrationState::Aborting { .. } if peer_id == self.from.id => {
+                instruction::Kind::UnfenceRegion(UnfenceRegion {})
+            }
+            _ => return None,
+        };
+
+        Some(Instruction {
+            migration_id: self.id,
+            region: Some(RegionIdent {
+                table_name: Some(TableName {
+                    catalog_name: self.catalog_name.clone(),
+                    schema_name: self.schema_name.clone(),
+                    table_name: self.table_name.clone(),
+                }),
+                table_id: self.table_id,
+                region_number: self.region_number,
+            }),
+            kind: Some(kind),
+        })
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).context(error::SerializeToJsonSnafu {
+            input: format!("{self:?}"),
+        })
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).context(error::DeserializeFromJsonSnafu {
+            input: String::from_utf8_lossy(bytes),
+        })
+    }
+}
+
+fn migration_key(table_id: u32, region_number: u32) -> String {
+    format!("{REGION_MIGRATION_PREFIX}-{table_id}-{region_number}")
+}
+
+/// Starts to migrate the region `region_number` of the table to the datanode `to`. A
+/// region could be migrated again only after its last migration completes or fails.
+pub async fn start_migration(
+    kv_store: &KvStoreRef,
+    table_name: &TableName,
+    region_number: u32,
+    to: &Peer,
+) -> Result<RegionMigration> {
+    let table = format!(
+        "{}.{}.{}",
+        table_name.catalog_name, table_name.schema_name, table_name.table_name
+    );
+    let global_key = TableGlobalKey {
+        catalog_name: table_name.catalog_name.clone(),
+        schema_name: table_name.schema_name.clone(),
+        table_name: table_name.table_name.clone(),
+    };
+    let global_value = get(kv_store, global_key.to_string())
+        .await?
+        .context(error::TableNotFoundSnafu { name: &table })?;
+    let global_value =
+        TableGlobalValue::from_bytes(global_value).context(error::InvalidCatalogValueSnafu)?;
+    let table_id = global_value.table_id();
+
+    let route_key = TableRouteKey::with_table_global_key(table_id as u64, &global_key).key();
+    let route_value = get(kv_store, route_key.clone())
+        .await?
+        .context(error::TableRouteNotFoundSnafu { key: &route_key })?;
+    let (route_value, _) = migration::decode_table_route_value(&route_value)?;
+    let from = leader_of(&route_value, region_number).context(error::RegionNotFoundSnafu {
+        table: &table,
+        region_number,
+    })?;
+    ensure!(
+        from.id != to.id,
+        error::InvalidArgumentsSnafu {
+            err_msg: format!(
+                "region {region_number} of table {table} is already on datanode {}",
+                to.id
+            ),
+        }
+    );
+
+    let migration = RegionMigration {
+        id: time_util::current_time_millis() as u64,
+        catalog_name: table_name.catalog_name.clone(),
+        schema_name: table_name.schema_name.clone(),
+        table_name: table_name.table_name.clone(),
+        table_id,
+        region_number,
+        from: from.into(),
+        to: to.into(),
+        state: MigrationState::Snapshotting,
+    };
+    let key = migration.key();
+    // A failed migration is replaced by the new one.
+    let expect = match get(kv_store, key.clone()).await? {
+        Some(bytes) => {
+            let prev = RegionMigration::from_bytes(&bytes)?;
+            ensure!(
+                matches!(prev.state, MigrationState::Failed { .. }),
+                error::RegionMigratingSnafu {
+                    table: &table,
+                    region_number,
+                    state: format!("{:?}", prev.state),
+                }
+            );
+            bytes
+        }
+        None => vec![],
+    };
+    let started =
+        compare_and_put(kv_store, key.into_bytes(), expect, migration.to_bytes()?).await?;
+    ensure!(
+        started,
+        error::RegionMigratingSnafu {
+            table: &table,
+            region_number,
+            state: "started concurrently",
+        }
+    );
+
+    info!("Region migration started: {:?}", migration);
+
+    Ok(migration)
+}
+
+/// Returns the migrations in progress or failed.
+pub async fn list_migrations(kv_store: &KvStoreRef) -> Result<Vec<RegionMigration>> {
+    range_prefix(kv_store, format!("{REGION_MIGRATION_PREFIX}-"))
+        .await?
+        .iter()
+        .map(|(_, bytes)| RegionMigration::from_bytes(bytes))
+        .collect()
+}
+
+/// Advances the migration by the reply of the datanode `peer_id`. Replies not expected
+/// in current state of the migration are ignored, as an instruction is sent repeatedly
+/// until the datanode replies.
+pub async fn handle_reply(
+    kv_store: &KvStoreRef,
+    peer_id: u64,
+    reply: &InstructionReply,
+) -> Result<()> {
+    let Some(Instruction {
+        migration_id,
+        region: Some(region),
+        kind: Some(kind),
+    }) = &reply.instruction
+    else {
+        return Ok(());
+    };
+    let key = migration_key(region.table_id, region.region_number);
+    let Some(bytes) = get(kv_store, key.clone()).await? else {
+        return Ok(());
+    };
+    let mut migration = RegionMigration::from_bytes(&bytes)?;
+    let expected = migration
+        .instruction_to(peer_id)
+        .and_then(|instruction| instruction.kind);
+    if migration.id != *migration_id || expected.as_ref() != Some(kind) {
+        return Ok(());
+    }
+
+    if !reply.success {
+        warn!(
+            "Region migration failed on datanode {}: {:?}, error: {}",
+            peer_id, migration, reply.error
+        );
+        let reason = reply.error.clone();
+        migration.state = match migration.state {
+            MigrationState::Snapshotting | MigrationState::Opening => {
+                MigrationState::Aborting { reason }
+            }
+            // The region is served by the target since the route is flipped.
+            MigrationState::Closing => MigrationState::Failed { reason },
+            // Unfencing is retried until the source succeeds.
+            MigrationState::Aborting { .. }
+            | MigrationState::FlippingRoute
+            | MigrationState::Failed { .. } => return Ok(()),
+        };
+        let _ = update(kv_store, bytes, &migration).await?;
+        return Ok(());
+    }
+
+    migration.state = match migration.state {
+        MigrationState::Snapshotting => MigrationState::Opening,
+        MigrationState::Opening => MigrationState::FlippingRoute,
+        MigrationState::Aborting { reason } => {
+            info!(
+                "Region migration {} aborted, reason: {}",
+                migration.id, reason
+            );
+            MigrationState::Failed { reason }
+        }
+        MigrationState::Closing => {
+            let req = DeleteRangeRequest {
+                key: key.into_bytes(),
+                ..Default::default()
+            };
+            let _ = kv_store.delete_range(req).await?;
+            info!("Region migration completed: {:?}", migration);
+            return Ok(());
+        }
+        // Datanodes are not instructed in other states.
+        MigrationState::FlippingRoute | MigrationState::Failed { .. } => return Ok(()),
+    };
+    // The reply will be handled again if the migration is updated concurrently.
+    let _ = update(kv_store, bytes, &migration).await?;
+
+    Ok(())
+}
+
+/// Returns the instructions to the datanode `peer_id` of all migrations, the routes of
+/// the regions opened by their targets are flipped meanwhile.
+pub async fn instructions_for(kv_store: &KvStoreRef, peer_id: u64) -> Result<Vec<Instruction>> {
+    let mut instructions = vec![];
+    for (key, bytes) in range_prefix(kv_store, format!("{REGION_MIGRATION_PREFIX}-")).await? {
+        let mut migration = match RegionMigration::from_bytes(&bytes) {
+            Ok(migration) => migration,
+            Err(e) => {
+                warn!(
+                    "Invalid region migration {}: {}",
+                    String::from_utf8_lossy(&key),
+                    e
+                );
+                continue;
+            }
+        };
+
+        if migration.state == MigrationState::FlippingRoute {
+            match flip_route(kv_store, &migration).await {
+                Ok(true) => migration.state = MigrationState::Closing,
+                // Retries in the next heartbeat.
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!(
+                        "Failed to flip route of region migration {:?}: {}",
+                        migration, e
+                    );
+                    migration.state = MigrationState::Aborting {
+                        reason: e.to_string(),
+                    };
+                }
+            }
+            if !update(kv_store, bytes, &migration).await? {
+                continue;
+            }
+            if migration.state == MigrationState::Closing {
+                info!("Region migration route flipped: {:?}", migration);
+            }
+        }
+
+        instructions.extend(migration.instruction_to(peer_id));
+    }
+
+    Ok(instructions)
+}
+
+/// Moves the region to the target datanode in the table route and the table global
+/// value, returns false if any value is updated concurrently.
+///
+/// Each value is updated atomically by compare-and-put, and a value already moved is
+/// left as it is, so the flipping could be retried until both values are moved.
+async fn flip_route(kv_store: &KvStoreRef, migration: &RegionMigration) -> Result<bool> {
+    let table = migration.full_table_name();
+    let region_number = migration.region_number;
+    let global_key = migration.table_global_key();
+
+    let route_key =
+        TableRouteKey::with_table_global_key(migration.table_id as u64, &global_key).key();
+    let route_bytes = get(kv_store, route_key.clone())
+        .await?
+        .context(error::TableRouteNotFoundSnafu { key: &route_key })?;
+    let (mut route_value, _) = migration::decode_table_route_value(&route_bytes)?;
+    let leader = leader_of(&route_value, region_number).context(error::RegionNotFoundSnafu {
+        table: &table,
+        region_number,
+    })?;
+    if leader.id != migration.to.id {
+        let peer_index = match route_value
+            .peers
+            .iter()
+            .position(|peer| peer.id == migration.to.id)
+        {
+            Some(index) => index,
+            None => {
+                route_value.peers.push((&migration.to).into());
+                route_value.peers.len() - 1
+            }
+        };
+        // Safety: The region route is found by `leader_of` above.
+        let region_route = route_value
+            .table_route
+            .as_mut()
+            .and_then(|route| {
+                route
+                    .region_routes
+                    .iter_mut()
+                    .find(|r| r.region.as_ref().map(|r| r.id) == Some(region_number as u64))
+            })
+            .unwrap();
+        region_route.leader_peer_index = peer_index as u64;
+
+        let value = migration::encode_table_route_value(route_value);
+        if !compare_and_put(kv_store, route_key.into_bytes(), route_bytes, value).await? {
+            return Ok(false);
+        }
+    }
+
+    let global_bytes = get(kv_store, global_key.to_string())
+        .await?
+        .context(error::TableNotFoundSnafu { name: &table })?;
+    let mut global_value =
+        TableGlobalValue::from_bytes(&global_bytes).context(error::InvalidCatalogValueSnafu)?;
+    let regions_id_map = &mut global_value.regions_id_map;
+    let mut moved = false;
+    if let Some(regions) = regions_id_map.get_mut(&migration.from.id) {
+        if let Some(index) = regions.iter().position(|r| *r == region_number) {
+            let _ = regions.remove(index);
+            moved = true;
+        }
+        if regions.is_empty() {
+            let _ = regions_id_map.remove(&migration.from.id);
+        }
+    }
+    let regions = regions_id_map.entry(migration.to.id).or_default();
+    if !regions.contains(&region_number) {
+        regions.push(region_number);
+        regions.sort_unstable();
+        moved = true;
+    }
+    if !moved {
+        return Ok(true);
+    }
+    let value = global_value
+        .as_bytes()
+        .context(error::InvalidCatalogValueSnafu)?;
+    compare_and_put(
+        kv_store,
+        global_key.to_string().into_bytes(),
+        global_bytes,
+        value,
+    )
+    .await
+}
+
+fn leader_of(route_value: &TableRouteValue, region_number: u32) -> Option<&Peer> {
+    let region_route = route_value
+        .table_route
+        .as_ref()?
+        .region_routes
+        .iter()
+        .find(|r| r.region.as_ref().map(|r| r.id) == Some(region_number as u64))?;
+    route_value
+        .peers
+        .get(region_route.leader_peer_index as usize)
+}
+
+/// Updates the migration if it's not updated since it's read as `expect`.
+async fn update(
+    kv_store: &KvStoreRef,
+    expect: Vec<u8>,
+    migration: &RegionMigration,
+) -> Result<bool> {
+    compare_and_put(
+        kv_store,
+        migration.key().into_bytes(),
+        expect,
+        migration.to_bytes()?,
+    )
+    .await
+}
+
+async fn get(kv_store: &KvStoreRef, key: String) -> Result<Option<Vec<u8>>> {
+    let req = RangeRequest {
+        key: key.into_bytes(),
+        ..Default::default()
+    };
+    let res = kv_store.range(req).await?;
+
+    Ok(res.kvs.into_iter().next().map(|kv| kv.value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use api::v1::meta::{PutRequest, Region, RegionRoute, TableRoute};
+    use datatypes::schema::RawSchema;
+    use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    fn new_peer(id: u64) -> Peer {
+        Peer {
+            id,
+            addr: format!("127.0.0.1:300{id}"),
+        }
+    }
+
+    fn new_table_name() -> TableName {
+        TableName {
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            table_name: "t".to_string(),
+        }
+    }
+
+    /// Puts a table with regions 0 and 1 on datanode 1.
+    async fn put_table(kv_store: &KvStoreRef) {
+        let meta = RawTableMeta {
+            schema: RawSchema {
+                column_schemas: vec![],
+                timestamp_index: None,
+                version: 0,
+            },
+            primary_key_indices: vec![],
+            value_indices: vec![],
+            engine: "mito".to_string(),
+            next_column_id: 0,
+            region_numbers: vec![0, 1],
+            engine_options: Default::default(),
+            options: Default::default(),
+            created_on: Default::default(),
+        };
+        let table_info = RawTableInfo {
+            ident: TableIdent {
+                table_id: 1024,
+                version: 0,
+            },
+            name: "t".to_string(),
+            desc: None,
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            meta,
+            table_type: TableType::Base,
+        };
+        let global_value = TableGlobalValue {
+            node_id: 1,
+            regions_id_map: HashMap::from([(1, vec![0, 1])]),
+            table_info,
+        };
+        let region_routes = (0..2)
+            .map(|id| RegionRoute {
+                region: Some(Region {
+                    id,
+                    ..Default::default()
+                }),
+                leader_peer_index: 0,
+                follower_peer_indexes: vec![],
+            })
+            .collect();
+        let route_value = TableRouteValue {
+            peers: vec![new_peer(1)],
+            table_route: Some(TableRoute {
+                table: None,
+                region_routes,
+            }),
+            ..Default::default()
+        };
+
+        let puts = [
+            (
+                global_key().to_string().into_bytes(),
+                global_value.as_bytes().unwrap(),
+            ),
+            (
+                route_key().into_bytes(),
+                migration::encode_table_route_value(route_value),
+            ),
+        ];
+        for (key, value) in puts {
+            let req = PutRequest {
+                key,
+                value,
+                ..Default::default()
+            };
+            let _ = kv_store.put(req).await.unwrap();
+        }
+    }
+
+    fn global_key() -> TableGlobalKey {
+        TableGlobalKey {
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            table_name: "t".to_string(),
+        }
+    }
+
+    fn route_key() -> String {
+        TableRouteKey::with_table_global_key(1024, &global_key()).key()
+    }
+
+    fn reply(instruction: Instruction, success: bool) -> InstructionReply {
+        InstructionReply {
+            instruction: Some(instruction),
+            success,
+            error: if success {
+                String::new()
+            } else {
+                "mock error".to_string()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migrate_region() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        put_table(&kv_store).await;
+
+        let migration = start_migration(&kv_store, &new_table_name(), 1, &new_peer(2))
+            .await
+            .unwrap();
+        assert_eq!(1, migration.from.id);
+        assert_eq!(MigrationState::Snapshotting, migration.state);
+        // Only one migration of a region at a time.
+        let err = start_migration(&kv_store, &new_table_name(), 1, &new_peer(2))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, error::Error::RegionMigrating { .. }),
+            "{err:?}"
+        );
+
+        // The source snapshots the region.
+        assert!(instructions_for(&kv_store, 2).await.unwrap().is_empty());
+        let mut instructions = instructions_for(&kv_store, 1).await.unwrap();
+        assert_eq!(1, instructions.len());
+        let snapshot = instructions.remove(0);
+        assert_eq!(
+            Some(instruction::Kind::SnapshotRegion(SnapshotRegion {})),
+            snapshot.kind
+        );
+        handle_reply(&kv_store, 1, &reply(snapshot.clone(), true))
+            .await
+            .unwrap();
+
+        // The target opens the region, a duplicate reply of the snapshot is ignored.
+        handle_reply(&kv_store, 1, &reply(snapshot, true))
+            .await
+            .unwrap();
+        assert!(instructions_for(&kv_store, 1).await.unwrap().is_empty());
+        let open = instructions_for(&kv_store, 2).await.unwrap().remove(0);
+        assert_eq!(
+            Some(instruction::Kind::OpenRegion(OpenRegion {})),
+            open.kind
+        );
+        handle_reply(&kv_store, 2, &reply(open, true))
+            .await
+            .unwrap();
+
+        // The route is flipped before the source closes the region.
+        let close = instructions_for(&kv_store, 1).await.unwrap().remove(0);
+        assert_eq!(
+            Some(instruction::Kind::CloseRegion(CloseRegion {})),
+            close.kind
+        );
+        let route_value = get(&kv_store, route_key()).await.unwrap().unwrap();
+        let (route_value, _) = migration::decode_table_route_value(&route_value).unwrap();
+        assert_eq!(1, leader_of(&route_value, 0).unwrap().id);
+        assert_eq!(2, leader_of(&route_value, 1).unwrap().id);
+        let global_value = get(&kv_store, global_key().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        let global_value = TableGlobalValue::from_bytes(global_value).unwrap();
+        assert_eq!(
+            HashMap::from([(1, vec![0]), (2, vec![1])]),
+            global_value.regions_id_map
+        );
+
+        // The migration is removed once the source closes the region.
+        handle_reply(&kv_store, 1, &reply(close, true))
+            .await
+            .unwrap();
+        assert!(list_migrations(&kv_store).await.unwrap().is_empty());
+        assert!(instructions_for(&kv_store, 1).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_region_failed() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        put_table(&kv_store).await;
+
+        let err = start_migration(&kv_store, &new_table_name(), 2, &new_peer(2))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, error::Error::RegionNotFound { .. }),
+            "{err:?}"
+        );
+        let err = start_migration(&kv_store, &new_table_name(), 1, &new_peer(1))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, error::Error::InvalidArguments { .. }),
+            "{err:?}"
+        );
+
+        let _ = start_migration(&kv_store, &new_table_name(), 1, &new_peer(2))
+            .await
+            .unwrap();
+        let snapshot = instructions_for(&kv_store, 1).await.unwrap().remove(0);
+        handle_reply(&kv_store, 1, &reply(snapshot, false))
+            .await
+            .unwrap();
+
+        // The source unfences the region, which is retried until it succeeds.
+        let unfence = instructions_for(&kv_store, 1).await.unwrap().remove(0);
+        assert_eq!(
+            Some(instruction::Kind::UnfenceRegion(UnfenceRegion {})),
+            unfence.kind
+        );
+        handle_reply(&kv_store, 1, &reply(unfence.clone(), false))
+            .await
+            .unwrap();
+        let migrations = list_migrations(&kv_store).await.unwrap();
+        assert_eq!(
+            MigrationState::Aborting {
+                reason: "mock error".to_string()
+            },
+            migrations[0].state
+        );
+        // A failed migration could only be started again after it's aborted.
+        assert!(
+            start_migration(&kv_store, &new_table_name(), 1, &new_peer(2))
+                .await
+                .is_err()
+        );
+        handle_reply(&kv_store, 1, &reply(unfence, true))
+            .await
+            .unwrap();
+
+        let migrations = list_migrations(&kv_store).await.unwrap();
+        assert_eq!(
+            MigrationState::Failed {
+                reason: "mock error".to_string()
+            },
+            migrations[0].state
+        );
+        assert!(instructions_for(&kv_store, 1).await.unwrap().is_empty());
+        let route_value = get(&kv_store, route_key()).await.unwrap().unwrap();
+        let (route_value, _) = migration::decode_table_route_value(&route_value).unwrap();
+        assert_eq!(1, leader_of(&route_value, 1).unwrap().id);
+
+        // A failed migration could be started again.
+        let migration = start_migration(&kv_store, &new_table_name(), 1, &new_peer(2))
+            .await
+            .unwrap();
+        assert_eq!(MigrationState::Snapshotting, migration.state);
+    }
+}
@@ -34,8 +34,8 @@ impl Selector for LeaseBasedSelector {
             time_util::current_time_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
         };
         let mut lease_kvs = lease::alive_datanodes(ns, &ctx.kv_store, lease_filter).await?;
-        // TODO(jiachun): At the moment we are just pushing the latest to the forefront,
-        // and it is better to use load-based strategies in the future.
+        // Pushes the latest to the forefront, see `LoadBasedSelector` for a strategy
+        // based on the load of the datanodes.
         lease_kvs.sort_by(|a, b| b.1.timestamp_millis.cmp(&a.1.timestamp_millis));
 
         let peers = lease_kvs
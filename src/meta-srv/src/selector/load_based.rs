@@ -0,0 +1,104 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::Peer;
+use common_time::util as time_util;
+
+use crate::error::Result;
+use crate::keys::{LeaseKey, LeaseValue};
+use crate::lease;
+use crate::metasrv::Context;
+use crate::selector::{Namespace, Selector};
+
+/// Selects the alive datanodes with the least load first, the load is reported by
+/// the datanodes in their heartbeats.
+///
+/// Datanodes are ordered by the number of regions, then by the rows written since
+/// the last heartbeat and then by the size of their regions.
+pub struct LoadBasedSelector;
+
+#[async_trait::async_trait]
+impl Selector for LoadBasedSelector {
+    type Context = Context;
+    type Output = Vec<Peer>;
+
+    async fn select(&self, ns: Namespace, ctx: &Self::Context) -> Result<Self::Output> {
+        // filter out the nodes out lease
+        let lease_filter = |_: &LeaseKey, v: &LeaseValue| {
+            time_util::current_time_millis() - v.timestamp_millis < ctx.datanode_lease_secs * 1000
+        };
+        let mut lease_kvs = lease::alive_datanodes(ns, &ctx.kv_store, lease_filter).await?;
+        lease_kvs.sort_by_key(|(_, v)| (v.load.region_num, v.load.wcus, v.load.approximate_size));
+
+        let peers = lease_kvs
+            .into_iter()
+            .map(|(k, v)| Peer {
+                id: k.node_id,
+                addr: v.node_addr,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(peers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use api::v1::meta::PutRequest;
+
+    use super::*;
+    use crate::keys::DatanodeLoad;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_select_least_loaded() {
+        let ctx = Context {
+            datanode_lease_secs: 30,
+            server_addr: "127.0.0.1:0000".to_string(),
+            kv_store: Arc::new(MemStore::new()),
+            election: None,
+            skip_all: Arc::new(AtomicBool::new(false)),
+        };
+
+        let loads = [(1, 3, 0), (2, 1, 100), (3, 1, 10)];
+        for (node_id, region_num, wcus) in loads {
+            let key = LeaseKey {
+                cluster_id: 0,
+                node_id,
+            };
+            let value = LeaseValue {
+                timestamp_millis: time_util::current_time_millis(),
+                node_addr: format!("127.0.0.1:300{node_id}"),
+                load: DatanodeLoad {
+                    region_num,
+                    wcus,
+                    ..Default::default()
+                },
+            };
+            let req = PutRequest {
+                key: key.try_into().unwrap(),
+                value: value.try_into().unwrap(),
+                ..Default::default()
+            };
+            let _ = ctx.kv_store.put(req).await.unwrap();
+        }
+
+        let peers = LoadBasedSelector.select(0, &ctx).await.unwrap();
+        let node_ids: Vec<_> = peers.iter().map(|p| p.id).collect();
+        assert_eq!(vec![3, 2, 1], node_ids);
+    }
+}
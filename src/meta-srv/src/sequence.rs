@@ -50,6 +50,19 @@ impl Sequence {
         let mut inner = self.inner.lock().await;
         inner.next().await
     }
+
+    /// Returns `n` consecutive values, the values left in the local cache are skipped if
+    /// they are not enough.
+    pub async fn next_n(&self, n: u64) -> Result<Range<u64>> {
+        ensure!(
+            n > 0,
+            error::InvalidArgumentsSnafu {
+                err_msg: "the number of sequence values to allocate must be positive",
+            }
+        );
+        let mut inner = self.inner.lock().await;
+        inner.next_n(n).await
+    }
 }
 
 struct Inner {
@@ -84,7 +97,7 @@ impl Inner {
                     self.range = None;
                 }
                 None => {
-                    let range = self.next_range().await?;
+                    let range = self.next_range(self.step).await?;
                     self.next = range.start;
                     self.range = Some(range);
                 }
@@ -97,7 +110,26 @@ impl Inner {
         .fail()
     }
 
-    pub async fn next_range(&self) -> Result<Range<u64>> {
+    /// Returns `[next, next + n)` if it is in the `range`, otherwise fetches a new
+    /// range of at least `n` values from the `generator`.
+    pub async fn next_n(&mut self, n: u64) -> Result<Range<u64>> {
+        if let Some(range) = &self.range {
+            if range.contains(&self.next) && self.next + n <= range.end {
+                let start = self.next;
+                self.next += n;
+                return Ok(start..self.next);
+            }
+        }
+
+        let range = self.next_range(n.max(self.step)).await?;
+        let start = range.start;
+        self.next = start + n;
+        self.range = Some(range);
+        Ok(start..start + n)
+    }
+
+    /// Fetches `[start, start + step)` from the `generator`.
+    pub async fn next_range(&self, step: u64) -> Result<Range<u64>> {
         let key = self.name.as_bytes();
         let mut start = self.next;
         for _ in 0..self.force_quit {
@@ -106,7 +138,7 @@ impl Inner {
             } else {
                 u64::to_le_bytes(start).to_vec()
             };
-            let value = u64::to_le_bytes(start + self.step);
+            let value = u64::to_le_bytes(start + step);
 
             let req = CompareAndPutRequest {
                 key: key.to_vec(),
@@ -135,7 +167,7 @@ impl Inner {
 
             return Ok(Range {
                 start,
-                end: start + self.step,
+                end: start + step,
             });
         }
 
@@ -165,6 +197,24 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_sequence_next_n() {
+        let kv_store = Arc::new(MemStore::new());
+        let seq = Sequence::new("test_seq", 1024, 10, kv_store.clone());
+
+        assert_eq!(1024, seq.next().await.unwrap());
+        assert_eq!(1025..1030, seq.next_n(5).await.unwrap());
+        // Not enough values are left in the local cache.
+        assert_eq!(1034..1054, seq.next_n(20).await.unwrap());
+        assert_eq!(1054, seq.next().await.unwrap());
+        assert!(seq.next_n(0).await.is_err());
+
+        // Another sequence of the same name, e.g. on a new leader, skips the values
+        // cached by others.
+        let other = Sequence::new("test_seq", 1024, 10, kv_store);
+        assert_eq!(1064, other.next().await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_sequence_fouce_quit() {
         struct Noop;
@@ -212,6 +262,13 @@ mod tests {
             ) -> Result<api::v1::meta::MoveValueResponse> {
                 unreachable!()
             }
+
+            async fn batch_move(
+                &self,
+                _: api::v1::meta::BatchMoveRequest,
+            ) -> Result<api::v1::meta::BatchMoveResponse> {
+                unreachable!()
+            }
         }
 
         let kv_store = Arc::new(Noop {});
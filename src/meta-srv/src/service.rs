@@ -18,8 +18,10 @@ use futures::Stream;
 use tonic::{Response, Status};
 
 pub mod admin;
+mod cluster;
 mod heartbeat;
 pub mod router;
+mod sequence;
 pub mod store;
 
 pub type GrpcResult<T> = std::result::Result<Response<T>, Status>;
@@ -13,6 +13,9 @@
 // limitations under the License.
 
 mod health;
+mod migrate;
+mod region_migration;
+mod tenant;
 
 use std::collections::HashMap;
 use std::convert::Infallible;
@@ -25,8 +28,31 @@ use tonic::transport::NamedService;
 
 use crate::metasrv::MetaSrv;
 
-pub fn make_admin_service(_: MetaSrv) -> Admin {
-    let router = Router::new().route("/health", health::HealthHandler);
+pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
+    let router = Router::new()
+        .route("/health", health::HealthHandler)
+        .route(
+            "/tenant-usage",
+            tenant::TenantUsageHandler {
+                kv_store: meta_srv.kv_store(),
+            },
+        )
+        .route(
+            "/migrate",
+            migrate::MigrateHandler {
+                meta_srv: meta_srv.clone(),
+            },
+        )
+        .route(
+            "/migrate-region",
+            region_migration::MigrateRegionHandler {
+                meta_srv: meta_srv.clone(),
+            },
+        )
+        .route(
+            "/region-migrations",
+            region_migration::RegionMigrationsHandler { meta_srv },
+        );
 
     let router = Router::nest("/admin", router);
 
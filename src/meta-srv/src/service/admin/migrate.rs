@@ -0,0 +1,90 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use snafu::ResultExt;
+use tonic::codegen::http;
+
+use crate::error::Result;
+use crate::metasrv::MetaSrv;
+use crate::service::admin::HttpHandler;
+use crate::{error, migration};
+
+/// Upgrades the values stored in old encodings, responds with the numbers of
+/// upgraded values. Only the leader runs the migration.
+pub struct MigrateHandler {
+    pub meta_srv: MetaSrv,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for MigrateHandler {
+    async fn handle(&self, _: &str, _: &HashMap<String, String>) -> Result<http::Response<String>> {
+        if !self.meta_srv.is_leader() {
+            return Ok(http::Response::builder()
+                .status(http::StatusCode::SERVICE_UNAVAILABLE)
+                .body("Current server is not leader".to_string())
+                .unwrap());
+        }
+
+        let report = migration::migrate(&self.meta_srv.kv_store()).await?;
+        let body = serde_json::to_string(&report).context(error::SerializeToJsonSnafu {
+            input: format!("{report:?}"),
+        })?;
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::election::tests::MockElection;
+    use crate::metasrv::MetaSrvOptions;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_migrate_handle() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None).await;
+        let handler = MigrateHandler { meta_srv };
+        let res = handler.handle("", &HashMap::default()).await.unwrap();
+
+        assert!(res.status().is_success());
+        assert_eq!(
+            r#"{"table_global_values":0,"table_routes":0}"#,
+            res.body().as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_handle_on_follower() {
+        let kv_store = Arc::new(MemStore::new());
+        let election = Arc::new(MockElection {
+            is_leader: false,
+            leader: "127.0.0.1:3003".to_string(),
+        });
+        let meta_srv =
+            MetaSrv::new(MetaSrvOptions::default(), kv_store, None, Some(election)).await;
+        let handler = MigrateHandler { meta_srv };
+        let res = handler.handle("", &HashMap::default()).await.unwrap();
+
+        assert_eq!(http::StatusCode::SERVICE_UNAVAILABLE, res.status());
+    }
+}
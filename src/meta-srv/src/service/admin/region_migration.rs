@@ -0,0 +1,148 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use api::v1::meta::{Peer, TableName};
+use snafu::{ensure, OptionExt, ResultExt};
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::metasrv::MetaSrv;
+use crate::service::admin::HttpHandler;
+use crate::{lease, region_migration};
+
+/// Starts to migrate the region in `region` param of the table in `catalog`, `schema`
+/// and `table` params to the alive datanode in `to` param, responds with the migration.
+/// Only the leader starts migrations.
+pub struct MigrateRegionHandler {
+    pub meta_srv: MetaSrv,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for MigrateRegionHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        if !self.meta_srv.is_leader() {
+            return Ok(http::Response::builder()
+                .status(http::StatusCode::SERVICE_UNAVAILABLE)
+                .body("Current server is not leader".to_string())
+                .unwrap());
+        }
+
+        let param = |name: &'static str| {
+            params.get(name).context(error::InvalidArgumentsSnafu {
+                err_msg: format!("`{name}` is required"),
+            })
+        };
+        let table_name = TableName {
+            catalog_name: param("catalog")?.clone(),
+            schema_name: param("schema")?.clone(),
+            table_name: param("table")?.clone(),
+        };
+        let region = param("region")?;
+        let region_number = region.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid region: {region}"),
+        })?;
+        let to = param("to")?;
+        let node_id = to.parse().context(error::ParseNumSnafu {
+            err_msg: format!("invalid to: {to}"),
+        })?;
+
+        let kv_store = self.meta_srv.kv_store();
+        let datanodes = lease::alive_datanodes(0, &kv_store, |k, _| k.node_id == node_id).await?;
+        ensure!(
+            !datanodes.is_empty(),
+            error::DatanodeNotAliveSnafu { node_id }
+        );
+        let to = Peer {
+            id: node_id,
+            addr: datanodes[0].1.node_addr.clone(),
+        };
+
+        let migration =
+            region_migration::start_migration(&kv_store, &table_name, region_number, &to).await?;
+        let body = serde_json::to_string(&migration).context(error::SerializeToJsonSnafu {
+            input: format!("{migration:?}"),
+        })?;
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+}
+
+/// Responds with the region migrations in progress or failed.
+pub struct RegionMigrationsHandler {
+    pub meta_srv: MetaSrv,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for RegionMigrationsHandler {
+    async fn handle(&self, _: &str, _: &HashMap<String, String>) -> Result<http::Response<String>> {
+        let migrations = region_migration::list_migrations(&self.meta_srv.kv_store()).await?;
+        let body = serde_json::to_string(&migrations).context(error::SerializeToJsonSnafu {
+            input: format!("{migrations:?}"),
+        })?;
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::metasrv::MetaSrvOptions;
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_migrate_region_handle() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None).await;
+
+        let handler = MigrateRegionHandler {
+            meta_srv: meta_srv.clone(),
+        };
+        let params = HashMap::from(
+            [
+                ("catalog", "greptime"),
+                ("schema", "public"),
+                ("table", "demo"),
+                ("region", "0"),
+                ("to", "2"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+        // Datanode 2 is not alive.
+        let err = handler.handle("", &params).await.unwrap_err();
+        assert!(
+            matches!(err, error::Error::DatanodeNotAlive { node_id: 2, .. }),
+            "{err:?}"
+        );
+
+        let handler = RegionMigrationsHandler { meta_srv };
+        let res = handler.handle("", &HashMap::default()).await.unwrap();
+        assert!(res.status().is_success());
+        assert_eq!("[]", res.body().as_str());
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use snafu::{OptionExt, ResultExt};
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::service::admin::HttpHandler;
+use crate::service::store::kv::KvStoreRef;
+use crate::service::store::tenant;
+
+/// Responds with the number of keys and bytes used by the tenant in `tenant` param.
+pub struct TenantUsageHandler {
+    pub kv_store: KvStoreRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for TenantUsageHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let tenant = params.get("tenant").context(error::InvalidArgumentsSnafu {
+            err_msg: "`tenant` is required",
+        })?;
+
+        let usage = tenant::tenant_usage(&self.kv_store, tenant).await?;
+        let body = serde_json::to_string(&usage).context(error::SerializeToJsonSnafu {
+            input: format!("{usage:?}"),
+        })?;
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::{PutRequest, RequestHeader};
+
+    use super::*;
+    use crate::service::store::kv::KvStore;
+    use crate::service::store::memory::MemStore;
+    use crate::service::store::tenant::TenantKvStore;
+
+    #[tokio::test]
+    async fn test_tenant_usage_handle() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        let req = PutRequest {
+            header: Some(RequestHeader {
+                tenant: "t1".to_string(),
+                ..Default::default()
+            }),
+            key: b"key".to_vec(),
+            value: b"value".to_vec(),
+            ..Default::default()
+        };
+        let _ = TenantKvStore::new(kv_store.clone()).put(req).await.unwrap();
+
+        let handler = TenantUsageHandler { kv_store };
+        let params = HashMap::from([("tenant".to_string(), "t1".to_string())]);
+        let res = handler.handle("", &params).await.unwrap();
+        assert!(res.status().is_success());
+        assert_eq!(
+            r#"{"tenant":"t1","key_count":1,"bytes":8}"#,
+            res.body().as_str()
+        );
+
+        assert!(handler.handle("", &HashMap::new()).await.is_err());
+    }
+}
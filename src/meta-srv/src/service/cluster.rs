@@ -0,0 +1,155 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::meta::{
+    cluster_server, ListNodesRequest, ListNodesResponse, NodeInfo, NodeStatus, Peer, ResponseHeader,
+};
+use common_time::util as time_util;
+use tonic::{Request, Response};
+
+use crate::error::Result;
+use crate::lease;
+use crate::metasrv::MetaSrv;
+use crate::service::GrpcResult;
+
+#[async_trait::async_trait]
+impl cluster_server::Cluster for MetaSrv {
+    async fn list_nodes(&self, req: Request<ListNodesRequest>) -> GrpcResult<ListNodesResponse> {
+        let req = req.into_inner();
+        let res = handle_list_nodes(self, req).await?;
+
+        Ok(Response::new(res))
+    }
+}
+
+/// Lists the datanodes from their leases, which are updated by their heartbeats and
+/// removed once they are leaving.
+async fn handle_list_nodes(meta_srv: &MetaSrv, req: ListNodesRequest) -> Result<ListNodesResponse> {
+    let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+    let lease_millis = meta_srv.options().datanode_lease_secs * 1000;
+    let now = time_util::current_time_millis();
+
+    // The prefix of the leases of cluster 1 also matches the ones of cluster 10.
+    let leases = lease::alive_datanodes(cluster_id, &meta_srv.kv_store(), |key, _| {
+        key.cluster_id == cluster_id
+    })
+    .await?;
+    let mut nodes = leases
+        .into_iter()
+        .map(|(key, value)| {
+            let status = if now - value.timestamp_millis < lease_millis {
+                NodeStatus::Alive
+            } else {
+                NodeStatus::Expired
+            };
+            NodeInfo {
+                peer: Some(Peer {
+                    id: key.node_id,
+                    addr: value.node_addr,
+                }),
+                status: status as i32,
+                last_heartbeat_millis: value.timestamp_millis,
+                region_num: value.load.region_num,
+                table_num: value.load.table_num,
+                rcus: value.load.rcus,
+                wcus: value.load.wcus,
+                approximate_size: value.load.approximate_size,
+            }
+        })
+        .collect::<Vec<_>>();
+    nodes.sort_unstable_by_key(|node| node.peer.as_ref().map(|peer| peer.id));
+
+    Ok(ListNodesResponse {
+        header: Some(ResponseHeader::success(cluster_id)),
+        nodes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::cluster_server::Cluster;
+    use api::v1::meta::{PutRequest, RequestHeader};
+    use tonic::IntoRequest;
+
+    use super::*;
+    use crate::keys::{DatanodeLoad, LeaseKey, LeaseValue};
+    use crate::metasrv::MetaSrvOptions;
+    use crate::service::store::memory::MemStore;
+
+    async fn put_lease(meta_srv: &MetaSrv, node_id: u64, timestamp_millis: i64) {
+        let key = LeaseKey {
+            cluster_id: 1,
+            node_id,
+        };
+        let value = LeaseValue {
+            timestamp_millis,
+            node_addr: format!("127.0.0.1:300{node_id}"),
+            load: DatanodeLoad {
+                region_num: node_id,
+                table_num: 1,
+                ..Default::default()
+            },
+        };
+        let req = PutRequest {
+            key: key.try_into().unwrap(),
+            value: value.try_into().unwrap(),
+            ..Default::default()
+        };
+        meta_srv.kv_store().put(req).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_nodes() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None).await;
+
+        let now = time_util::current_time_millis();
+        put_lease(&meta_srv, 2, now).await;
+        // The lease of node 1 is expired.
+        put_lease(&meta_srv, 1, now - 60_000).await;
+
+        let req = ListNodesRequest {
+            header: Some(RequestHeader::new((1, 1))),
+        };
+        let res = meta_srv
+            .list_nodes(req.into_request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(1, res.header.unwrap().cluster_id);
+
+        let nodes = res.nodes;
+        assert_eq!(2, nodes.len());
+        assert_eq!(1, nodes[0].peer.as_ref().unwrap().id);
+        assert_eq!(NodeStatus::Expired as i32, nodes[0].status);
+        assert_eq!(now - 60_000, nodes[0].last_heartbeat_millis);
+        assert_eq!("127.0.0.1:3002", nodes[1].peer.as_ref().unwrap().addr);
+        assert_eq!(NodeStatus::Alive as i32, nodes[1].status);
+        assert_eq!(2, nodes[1].region_num);
+        assert_eq!(1, nodes[1].table_num);
+
+        // Nodes of other clusters are not listed.
+        let req = ListNodesRequest {
+            header: Some(RequestHeader::new((2, 1))),
+        };
+        let res = meta_srv
+            .list_nodes(req.into_request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(res.nodes.is_empty());
+    }
+}
@@ -150,6 +150,7 @@ mod tests {
     use tonic::IntoRequest;
 
     use super::*;
+    use crate::election::tests::MockElection;
     use crate::metasrv::MetaSrvOptions;
     use crate::service::store::memory::MemStore;
 
@@ -167,4 +168,23 @@ mod tests {
         assert_eq!(1, res.header.unwrap().cluster_id);
         assert_eq!(meta_srv.options().bind_addr, res.leader.unwrap().addr);
     }
+
+    #[tokio::test]
+    async fn test_ask_leader_on_follower() {
+        let kv_store = Arc::new(MemStore::new());
+        let election = Arc::new(MockElection {
+            is_leader: false,
+            leader: "127.0.0.1:3003".to_string(),
+        });
+        let meta_srv =
+            MetaSrv::new(MetaSrvOptions::default(), kv_store, None, Some(election)).await;
+
+        let req = AskLeaderRequest {
+            header: Some(RequestHeader::new((1, 1))),
+        };
+
+        let res = meta_srv.ask_leader(req.into_request()).await.unwrap();
+        let res = res.into_inner();
+        assert_eq!("127.0.0.1:3003", res.leader.unwrap().addr);
+    }
 }
@@ -13,27 +13,34 @@
 // limitations under the License.
 
 use api::v1::meta::{
-    router_server, CreateRequest, DeleteRequest, Error, MoveValueRequest, Peer, PeerDict,
-    PutRequest, RangeRequest, Region, RegionRoute, ResponseHeader, RouteRequest, RouteResponse,
-    Table, TableRoute, TableRouteValue,
+    router_server, BatchMoveRequest, CreateRequest, DeleteRequest, Error, KeyMove,
+    MoveValueRequest, Peer, PeerDict, PutRequest, RangeRequest, Region, RegionRoute, RenameRequest,
+    ResponseHeader, RouteRequest, RouteResponse, Table, TableRoute, TableRouteValue,
 };
-use catalog::helper::{TableGlobalKey, TableGlobalValue};
+use catalog::helper::{TableGlobalKey, TableGlobalValue, TableRegionalKey};
 use common_telemetry::warn;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use tonic::{Request, Response};
 
-use crate::error;
 use crate::error::Result;
 use crate::keys::TableRouteKey;
 use crate::metasrv::{Context, MetaSrv, SelectorRef};
 use crate::sequence::SequenceRef;
 use crate::service::store::kv::KvStoreRef;
 use crate::service::GrpcResult;
+use crate::{error, migration};
 
 #[async_trait::async_trait]
 impl router_server::Router for MetaSrv {
     async fn create(&self, req: Request<CreateRequest>) -> GrpcResult<RouteResponse> {
         let req = req.into_inner();
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(RouteResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
         let ctx = self.new_ctx();
         let selector = self.selector();
         let table_id_sequence = self.table_id_sequence();
@@ -52,11 +59,33 @@ impl router_server::Router for MetaSrv {
 
     async fn delete(&self, req: Request<DeleteRequest>) -> GrpcResult<RouteResponse> {
         let req = req.into_inner();
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(RouteResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
         let ctx = self.new_ctx();
         let res = handle_delete(req, ctx).await?;
 
         Ok(Response::new(res))
     }
+
+    async fn rename(&self, req: Request<RenameRequest>) -> GrpcResult<RouteResponse> {
+        let req = req.into_inner();
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(RouteResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let ctx = self.new_ctx();
+        let res = handle_rename(req, ctx).await?;
+
+        Ok(Response::new(res))
+    }
 }
 
 async fn handle_create(
@@ -117,8 +146,14 @@ async fn handle_create(
     let table_route_value = TableRouteValue {
         peers: peers.clone(),
         table_route: Some(table_route.clone()),
+        ..Default::default()
     };
-    put_into_store(&ctx.kv_store, table_route_key, table_route_value).await?;
+    put_into_store(
+        &ctx.kv_store,
+        table_route_key,
+        migration::encode_table_route_value(table_route_value),
+    )
+    .await?;
 
     let header = Some(ResponseHeader::success(cluster_id));
     Ok(RouteResponse {
@@ -178,6 +213,120 @@ async fn handle_delete(req: DeleteRequest, ctx: Context) -> Result<RouteResponse
     })
 }
 
+/// Moves the global key, regional keys and route of the table to the new name in one
+/// transaction, which fails if any of them is changed concurrently or the new name is
+/// taken, so datanodes and frontends never see a half renamed table.
+async fn handle_rename(req: RenameRequest, ctx: Context) -> Result<RouteResponse> {
+    let RenameRequest {
+        header,
+        table_name,
+        new_table_name,
+    } = req;
+    let cluster_id = header.as_ref().map_or(0, |h| h.cluster_id);
+    let tgk = table_name
+        .map(|t| TableGlobalKey {
+            catalog_name: t.catalog_name,
+            schema_name: t.schema_name,
+            table_name: t.table_name,
+        })
+        .context(error::EmptyTableNameSnafu)?;
+    ensure!(!new_table_name.is_empty(), error::EmptyTableNameSnafu);
+    let new_tgk = TableGlobalKey {
+        catalog_name: tgk.catalog_name.clone(),
+        schema_name: tgk.schema_name.clone(),
+        table_name: new_table_name.clone(),
+    };
+    ensure!(
+        get_from_store(&ctx.kv_store, format!("{new_tgk}").into_bytes())
+            .await?
+            .is_none(),
+        error::TableAlreadyExistsSnafu {
+            name: format!("{new_tgk}"),
+        }
+    );
+
+    let tgv_bytes = get_from_store(&ctx.kv_store, format!("{tgk}").into_bytes())
+        .await?
+        .with_context(|| error::TableNotFoundSnafu {
+            name: format!("{tgk}"),
+        })?;
+    let mut tgv =
+        TableGlobalValue::from_bytes(&tgv_bytes).context(error::InvalidCatalogValueSnafu)?;
+    tgv.table_info.name = new_table_name.clone();
+    let mut moves = vec![KeyMove {
+        from_key: format!("{tgk}").into_bytes(),
+        to_key: format!("{new_tgk}").into_bytes(),
+        expect: tgv_bytes,
+        value: tgv.as_bytes().context(error::InvalidCatalogValueSnafu)?,
+    }];
+
+    // Regional values don't contain the table name, so they are moved as is.
+    for node_id in tgv.regions_id_map.keys() {
+        let regional_key = |table_name: &str| {
+            TableRegionalKey {
+                catalog_name: tgk.catalog_name.clone(),
+                schema_name: tgk.schema_name.clone(),
+                table_name: table_name.to_string(),
+                node_id: *node_id,
+            }
+            .to_string()
+            .into_bytes()
+        };
+        let from_key = regional_key(&tgk.table_name);
+        let Some(value) = get_from_store(&ctx.kv_store, from_key.clone()).await? else {
+            // The datanode hasn't registered the table yet.
+            continue;
+        };
+        moves.push(KeyMove {
+            from_key,
+            to_key: regional_key(&new_table_name),
+            expect: value.clone(),
+            value,
+        });
+    }
+
+    let trk = TableRouteKey::with_table_global_key(tgv.table_id() as u64, &tgk);
+    let trv_bytes = get_from_store(&ctx.kv_store, trk.key().into_bytes())
+        .await?
+        .context(error::TableRouteNotFoundSnafu { key: trk.key() })?;
+    let (mut trv, _) = migration::decode_table_route_value(&trv_bytes)?;
+    if let Some(table_name) = trv
+        .table_route
+        .as_mut()
+        .and_then(|r| r.table.as_mut())
+        .and_then(|t| t.table_name.as_mut())
+    {
+        table_name.table_name = new_table_name.clone();
+    }
+    let new_trk = TableRouteKey::with_table_global_key(tgv.table_id() as u64, &new_tgk);
+    moves.push(KeyMove {
+        from_key: trk.key().into_bytes(),
+        to_key: new_trk.key().into_bytes(),
+        expect: trv_bytes,
+        value: migration::encode_table_route_value(trv.clone()),
+    });
+
+    let req = BatchMoveRequest {
+        moves,
+        ..Default::default()
+    };
+    let res = ctx.kv_store.batch_move(req).await?;
+    ensure!(
+        res.success,
+        error::MoveValueSnafu {
+            key: format!("{tgk}"),
+        }
+    );
+
+    let (peers, table_routes) = fill_table_routes(vec![(tgv, trv)])?;
+    let header = Some(ResponseHeader::success(cluster_id));
+    Ok(RouteResponse {
+        header,
+        peers,
+        table_routes,
+    })
+}
+
 fn fill_table_routes(
     tables: Vec<(TableGlobalValue, TableRouteValue)>,
 ) -> Result<(Vec<Peer>, Vec<TableRoute>)> {
@@ -187,6 +336,7 @@ fn fill_table_routes(
         let TableRouteValue {
             peers,
             mut table_route,
+            ..
         } = trv;
         if let Some(table_route) = &mut table_route {
             for rr in &mut table_route.region_routes {
@@ -243,10 +393,7 @@ async fn get_table_route_value(
     let trv = get_from_store(kv_store, key.key().into_bytes())
         .await?
         .context(error::TableRouteNotFoundSnafu { key: key.key() })?;
-    let trv: TableRouteValue = trv
-        .as_slice()
-        .try_into()
-        .context(error::DecodeTableRouteSnafu)?;
+    let (trv, _) = migration::decode_table_route_value(&trv)?;
 
     Ok(trv)
 }
@@ -260,10 +407,7 @@ async fn remove_table_route_value(
     let v = move_value(kv_store, from_key, to_key)
         .await?
         .context(error::TableRouteNotFoundSnafu { key: key.key() })?;
-    let trv: TableRouteValue =
-        v.1.as_slice()
-            .try_into()
-            .context(error::DecodeTableRouteSnafu)?;
+    let (trv, _) = migration::decode_table_route_value(&v.1)?;
 
     Ok((v.0, trv))
 }
@@ -330,3 +474,201 @@ async fn get_from_store(kv_store: &KvStoreRef, key: Vec<u8>) -> Result<Option<Ve
         Ok(Some(kvs.pop().unwrap().value))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use api::v1::meta::router_server::Router;
+    use api::v1::meta::TableName;
+    use datatypes::schema::RawSchema;
+    use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
+    use tonic::IntoRequest;
+
+    use super::*;
+    use crate::metasrv::MetaSrvOptions;
+    use crate::service::store::memory::MemStore;
+
+    fn new_table_global_value() -> TableGlobalValue {
+        let meta = RawTableMeta {
+            schema: RawSchema {
+                column_schemas: vec![],
+                timestamp_index: None,
+                version: 0,
+            },
+            primary_key_indices: vec![],
+            value_indices: vec![],
+            engine: "mito".to_string(),
+            next_column_id: 0,
+            region_numbers: vec![0, 1],
+            engine_options: Default::default(),
+            options: Default::default(),
+            created_on: Default::default(),
+        };
+        let table_info = RawTableInfo {
+            ident: TableIdent {
+                table_id: 1024,
+                version: 0,
+            },
+            name: "t".to_string(),
+            desc: None,
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            meta,
+            table_type: TableType::Base,
+        };
+        TableGlobalValue {
+            node_id: 1,
+            regions_id_map: HashMap::from([(1, vec![0]), (2, vec![1])]),
+            table_info,
+        }
+    }
+
+    fn global_key(table_name: &str) -> TableGlobalKey {
+        TableGlobalKey {
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            table_name: table_name.to_string(),
+        }
+    }
+
+    fn regional_key(table_name: &str, node_id: u64) -> Vec<u8> {
+        TableRegionalKey {
+            catalog_name: "c".to_string(),
+            schema_name: "s".to_string(),
+            table_name: table_name.to_string(),
+            node_id,
+        }
+        .to_string()
+        .into_bytes()
+    }
+
+    fn route_key(table_name: &str) -> Vec<u8> {
+        TableRouteKey::with_table_global_key(1024, &global_key(table_name))
+            .key()
+            .into_bytes()
+    }
+
+    fn rename_request(new_table_name: &str) -> RenameRequest {
+        RenameRequest {
+            table_name: Some(TableName {
+                catalog_name: "c".to_string(),
+                schema_name: "s".to_string(),
+                table_name: "t".to_string(),
+            }),
+            new_table_name: new_table_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn prepare_table(kv_store: &KvStoreRef) {
+        let route_value = TableRouteValue {
+            table_route: Some(TableRoute {
+                table: Some(Table {
+                    id: 1024,
+                    table_name: rename_request("").table_name,
+                    ..Default::default()
+                }),
+                region_routes: vec![],
+            }),
+            ..Default::default()
+        };
+        put_into_store(
+            kv_store,
+            format!("{}", global_key("t")),
+            new_table_global_value().as_bytes().unwrap(),
+        )
+        .await
+        .unwrap();
+        // Only the datanode 1 has registered the table.
+        put_into_store(kv_store, regional_key("t", 1), b"regional".to_vec())
+            .await
+            .unwrap();
+        put_into_store(
+            kv_store,
+            route_key("t"),
+            migration::encode_table_route_value(route_value),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rename() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        prepare_table(&kv_store).await;
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store.clone(), None, None).await;
+
+        let res = meta_srv
+            .rename(rename_request("t2").into_request())
+            .await
+            .unwrap()
+            .into_inner();
+        let table = res.table_routes[0].table.as_ref().unwrap();
+        assert_eq!("t2", table.table_name.as_ref().unwrap().table_name);
+
+        for key in [
+            format!("{}", global_key("t")).into_bytes(),
+            regional_key("t", 1),
+            route_key("t"),
+        ] {
+            assert!(get_from_store(&kv_store, key).await.unwrap().is_none());
+        }
+        let tgv = get_table_global_value(&kv_store, &global_key("t2"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("t2", tgv.table_info.name);
+        assert_eq!(
+            b"regional".to_vec(),
+            get_from_store(&kv_store, regional_key("t2", 1))
+                .await
+                .unwrap()
+                .unwrap()
+        );
+        assert!(get_from_store(&kv_store, regional_key("t2", 2))
+            .await
+            .unwrap()
+            .is_none());
+        let trk = TableRouteKey::with_table_global_key(1024, &global_key("t2"));
+        let trv = get_table_route_value(&kv_store, &trk).await.unwrap();
+        let table = trv.table_route.unwrap().table.unwrap();
+        assert_eq!("t2", table.table_name.unwrap().table_name);
+
+        // The old table is gone.
+        assert!(meta_srv
+            .rename(rename_request("t3").into_request())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rename_to_existing_table() {
+        let kv_store: KvStoreRef = Arc::new(MemStore::new());
+        prepare_table(&kv_store).await;
+        put_into_store(
+            &kv_store,
+            format!("{}", global_key("t2")),
+            b"other".to_vec(),
+        )
+        .await
+        .unwrap();
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store.clone(), None, None).await;
+
+        let err = meta_srv
+            .rename(rename_request("t2").into_request())
+            .await
+            .unwrap_err();
+        assert!(err.message().contains("already exists"));
+        // Nothing is moved.
+        assert!(get_from_store(&kv_store, regional_key("t", 1))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(get_from_store(&kv_store, route_key("t"))
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use api::v1::meta::{sequence_server, NextRequest, NextResponse, ResponseHeader};
+use snafu::OptionExt;
+use tonic::{Request, Response};
+
+use crate::error::{self, Result};
+use crate::metasrv::MetaSrv;
+use crate::service::GrpcResult;
+
+#[async_trait::async_trait]
+impl sequence_server::Sequence for MetaSrv {
+    async fn next(&self, req: Request<NextRequest>) -> GrpcResult<NextResponse> {
+        let req = req.into_inner();
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        // Only the leader allocates values, so values cached by a follower are never
+        // wasted by the sequence of the leader.
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(NextResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = handle_next(self, req).await?;
+
+        Ok(Response::new(res))
+    }
+}
+
+async fn handle_next(meta_srv: &MetaSrv, req: NextRequest) -> Result<NextResponse> {
+    let NextRequest { header, name, size } = req;
+    let cluster_id = header.as_ref().map_or(0, |h| h.cluster_id);
+    let sequence = meta_srv
+        .sequence(&name)
+        .context(error::SequenceNotFoundSnafu { name })?;
+    let range = sequence.next_n(size).await?;
+
+    Ok(NextResponse {
+        header: Some(ResponseHeader::success(cluster_id)),
+        start: range.start,
+        end: range.end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::sequence_server::Sequence;
+    use tonic::IntoRequest;
+
+    use super::*;
+    use crate::metasrv::{MetaSrvOptions, TABLE_ID_SEQ};
+    use crate::service::store::memory::MemStore;
+
+    #[tokio::test]
+    async fn test_next() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None).await;
+        let req = NextRequest {
+            name: TABLE_ID_SEQ.to_string(),
+            size: 3,
+            ..Default::default()
+        };
+        let res = meta_srv
+            .next(req.into_request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(3, res.end - res.start);
+
+        // Table ids allocated by creating routes never collide with the ones allocated.
+        let id = meta_srv.table_id_sequence().next().await.unwrap();
+        assert_eq!(res.end, id);
+
+        let req = NextRequest {
+            name: "unknown".to_string(),
+            size: 1,
+            ..Default::default()
+        };
+        assert!(meta_srv.next(req.into_request()).await.is_err());
+    }
+}
@@ -15,11 +15,12 @@
 pub mod etcd;
 pub mod kv;
 pub mod memory;
+pub mod tenant;
 
 use api::v1::meta::{
-    store_server, BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
-    DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest, MoveValueResponse, PutRequest,
-    PutResponse, RangeRequest, RangeResponse,
+    store_server, BatchMoveRequest, BatchMoveResponse, BatchPutRequest, BatchPutResponse,
+    CompareAndPutRequest, CompareAndPutResponse, DeleteRangeRequest, DeleteRangeResponse,
+    MoveValueRequest, MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse,
 };
 use tonic::{Request, Response};
 
@@ -30,21 +31,35 @@ use crate::service::GrpcResult;
 impl store_server::Store for MetaSrv {
     async fn range(&self, req: Request<RangeRequest>) -> GrpcResult<RangeResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().range(req).await?;
+        let res = self.tenant_kv_store().range(req).await?;
 
         Ok(Response::new(res))
     }
 
     async fn put(&self, req: Request<PutRequest>) -> GrpcResult<PutResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().put(req).await?;
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(PutResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = self.tenant_kv_store().put(req).await?;
 
         Ok(Response::new(res))
     }
 
     async fn batch_put(&self, req: Request<BatchPutRequest>) -> GrpcResult<BatchPutResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().batch_put(req).await?;
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(BatchPutResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = self.tenant_kv_store().batch_put(req).await?;
 
         Ok(Response::new(res))
     }
@@ -54,7 +69,14 @@ impl store_server::Store for MetaSrv {
         req: Request<CompareAndPutRequest>,
     ) -> GrpcResult<CompareAndPutResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().compare_and_put(req).await?;
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(CompareAndPutResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = self.tenant_kv_store().compare_and_put(req).await?;
 
         Ok(Response::new(res))
     }
@@ -64,14 +86,42 @@ impl store_server::Store for MetaSrv {
         req: Request<DeleteRangeRequest>,
     ) -> GrpcResult<DeleteRangeResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().delete_range(req).await?;
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(DeleteRangeResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = self.tenant_kv_store().delete_range(req).await?;
 
         Ok(Response::new(res))
     }
 
     async fn move_value(&self, req: Request<MoveValueRequest>) -> GrpcResult<MoveValueResponse> {
         let req = req.into_inner();
-        let res = self.kv_store().move_value(req).await?;
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(MoveValueResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = self.tenant_kv_store().move_value(req).await?;
+
+        Ok(Response::new(res))
+    }
+
+    async fn batch_move(&self, req: Request<BatchMoveRequest>) -> GrpcResult<BatchMoveResponse> {
+        let req = req.into_inner();
+        let cluster_id = req.header.as_ref().map_or(0, |h| h.cluster_id);
+        if let Some(header) = self.not_leader_header(cluster_id).await {
+            return Ok(Response::new(BatchMoveResponse {
+                header: Some(header),
+                ..Default::default()
+            }));
+        }
+        let res = self.tenant_kv_store().batch_move(req).await?;
 
         Ok(Response::new(res))
     }
@@ -86,6 +136,7 @@ mod tests {
     use tonic::IntoRequest;
 
     use super::*;
+    use crate::election::tests::MockElection;
     use crate::metasrv::MetaSrvOptions;
     use crate::service::store::memory::MemStore;
 
@@ -148,4 +199,47 @@ mod tests {
 
         assert!(res.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_batch_move() {
+        let kv_store = Arc::new(MemStore::new());
+        let meta_srv = MetaSrv::new(MetaSrvOptions::default(), kv_store, None, None).await;
+        let req = BatchMoveRequest::default();
+        let res = meta_srv.batch_move(req.into_request()).await;
+
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mutation_on_follower() {
+        let kv_store = Arc::new(MemStore::new());
+        let election = Arc::new(MockElection {
+            is_leader: false,
+            leader: "127.0.0.1:3003".to_string(),
+        });
+        let meta_srv =
+            MetaSrv::new(MetaSrvOptions::default(), kv_store, None, Some(election)).await;
+
+        let req = PutRequest {
+            key: b"key".to_vec(),
+            value: b"value".to_vec(),
+            ..Default::default()
+        };
+        let res = meta_srv.put(req.into_request()).await.unwrap().into_inner();
+        let header = res.header.unwrap();
+        assert!(header.is_not_leader());
+        assert!(header.error.unwrap().err_msg.contains("127.0.0.1:3003"));
+
+        // Followers still serve reads, but nothing has been written.
+        let req = RangeRequest {
+            key: b"key".to_vec(),
+            ..Default::default()
+        };
+        let res = meta_srv
+            .range(req.into_request())
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(res.kvs.is_empty());
+    }
 }
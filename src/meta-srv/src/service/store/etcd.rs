@@ -15,9 +15,9 @@
 use std::sync::Arc;
 
 use api::v1::meta::{
-    BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
-    DeleteRangeRequest, DeleteRangeResponse, KeyValue, MoveValueRequest, MoveValueResponse,
-    PutRequest, PutResponse, RangeRequest, RangeResponse, ResponseHeader,
+    BatchMoveRequest, BatchMoveResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
+    CompareAndPutResponse, DeleteRangeRequest, DeleteRangeResponse, KeyValue, MoveValueRequest,
+    MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse, ResponseHeader,
 };
 use common_error::prelude::*;
 use common_telemetry::warn;
@@ -284,6 +284,43 @@ impl KvStore for EtcdStore {
         }
         .fail()
     }
+
+    async fn batch_move(&self, req: BatchMoveRequest) -> Result<BatchMoveResponse> {
+        let BatchMoveRequest { header, moves } = req;
+        let cluster_id = header.map_or(0, |h| h.cluster_id);
+
+        let mut compares = Vec::with_capacity(moves.len() * 2);
+        let mut ops = Vec::with_capacity(moves.len() * 2);
+        for m in moves {
+            // revision 0 means key was not exist
+            compares.push(Compare::value(
+                m.from_key.clone(),
+                CompareOp::Equal,
+                m.expect,
+            ));
+            compares.push(Compare::create_revision(
+                m.to_key.clone(),
+                CompareOp::Equal,
+                0,
+            ));
+            ops.push(TxnOp::delete(m.from_key, None));
+            ops.push(TxnOp::put(m.to_key, m.value, None));
+        }
+        let txn = Txn::new().when(compares).and_then(ops);
+
+        let txn_res = self
+            .client
+            .kv_client()
+            .txn(txn)
+            .await
+            .context(error::EtcdFailedSnafu)?;
+
+        let header = Some(ResponseHeader::success(cluster_id));
+        Ok(BatchMoveResponse {
+            header,
+            success: txn_res.succeeded(),
+        })
+    }
 }
 
 struct Get {
@@ -15,9 +15,9 @@
 use std::sync::Arc;
 
 use api::v1::meta::{
-    BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
-    DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest, MoveValueResponse, PutRequest,
-    PutResponse, RangeRequest, RangeResponse,
+    BatchMoveRequest, BatchMoveResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
+    CompareAndPutResponse, DeleteRangeRequest, DeleteRangeResponse, MoveValueRequest,
+    MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse,
 };
 
 use crate::error::Result;
@@ -37,4 +37,6 @@ pub trait KvStore: Send + Sync {
     async fn delete_range(&self, req: DeleteRangeRequest) -> Result<DeleteRangeResponse>;
 
     async fn move_value(&self, req: MoveValueRequest) -> Result<MoveValueResponse>;
+
+    async fn batch_move(&self, req: BatchMoveRequest) -> Result<BatchMoveResponse>;
 }
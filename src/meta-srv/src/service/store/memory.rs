@@ -18,9 +18,9 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use api::v1::meta::{
-    BatchPutRequest, BatchPutResponse, CompareAndPutRequest, CompareAndPutResponse,
-    DeleteRangeRequest, DeleteRangeResponse, KeyValue, MoveValueRequest, MoveValueResponse,
-    PutRequest, PutResponse, RangeRequest, RangeResponse, ResponseHeader,
+    BatchMoveRequest, BatchMoveResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
+    CompareAndPutResponse, DeleteRangeRequest, DeleteRangeResponse, KeyValue, MoveValueRequest,
+    MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse, ResponseHeader,
 };
 use parking_lot::RwLock;
 
@@ -243,4 +243,24 @@ impl KvStore for MemStore {
         let header = Some(ResponseHeader::success(cluster_id));
         Ok(MoveValueResponse { header, kv })
     }
+
+    async fn batch_move(&self, req: BatchMoveRequest) -> Result<BatchMoveResponse> {
+        let BatchMoveRequest { header, moves } = req;
+
+        let mut memory = self.inner.write();
+
+        let success = moves
+            .iter()
+            .all(|m| memory.get(&m.from_key) == Some(&m.expect) && !memory.contains_key(&m.to_key));
+        if success {
+            for m in moves {
+                memory.remove(&m.from_key);
+                memory.insert(m.to_key, m.value);
+            }
+        }
+
+        let cluster_id = header.map_or(0, |h| h.cluster_id);
+        let header = Some(ResponseHeader::success(cluster_id));
+        Ok(BatchMoveResponse { header, success })
+    }
 }
@@ -0,0 +1,357 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Isolation of the keys of tenants sharing one metasrv.
+//!
+//! Keys of a tenant are transparently stored under the prefix `__tenant/{tenant}/`,
+//! requests of the tenant can't reach any key out of its prefix. Requests without a
+//! tenant are served in the default namespace, which covers all keys.
+
+use api::v1::meta::{
+    BatchMoveRequest, BatchMoveResponse, BatchPutRequest, BatchPutResponse, CompareAndPutRequest,
+    CompareAndPutResponse, DeleteRangeRequest, DeleteRangeResponse, KeyValue, MoveValueRequest,
+    MoveValueResponse, PutRequest, PutResponse, RangeRequest, RangeResponse, RequestHeader,
+};
+use serde::Serialize;
+use snafu::ensure;
+
+use crate::error::{self, Result};
+use crate::service::store::kv::{KvStore, KvStoreRef};
+use crate::util;
+
+pub const TENANT_KEY_PREFIX: &str = "__tenant";
+
+/// Keys of one tenant.
+#[derive(Debug, Clone)]
+struct Namespace {
+    prefix: Vec<u8>,
+}
+
+impl Namespace {
+    /// Returns the namespace of the tenant in the request header, or `None` if the
+    /// request is in the default namespace.
+    fn from_header(header: Option<&RequestHeader>) -> Result<Option<Self>> {
+        match header.map(|h| h.tenant.as_str()) {
+            None | Some("") => Ok(None),
+            Some(tenant) => Self::new(tenant).map(Some),
+        }
+    }
+
+    /// Tenant names are restricted to ASCII alphanumerics, `_` and `-`, so the prefix
+    /// of a tenant is never a prefix of another tenant's.
+    fn new(tenant: &str) -> Result<Self> {
+        ensure!(
+            !tenant.is_empty()
+                && tenant
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+            error::InvalidTenantSnafu { tenant }
+        );
+        Ok(Self {
+            prefix: format!("{TENANT_KEY_PREFIX}/{tenant}/").into_bytes(),
+        })
+    }
+
+    fn key(&self, key: &[u8]) -> Result<Vec<u8>> {
+        ensure!(!key.is_empty(), error::EmptyKeySnafu);
+        Ok([self.prefix.as_slice(), key].concat())
+    }
+
+    /// Maps the range end in the namespace, the open range end `\0` is bounded by
+    /// the end of the prefix.
+    fn range_end(&self, range_end: &[u8]) -> Vec<u8> {
+        match range_end {
+            [] => vec![],
+            [0] => self.prefix_end(),
+            _ => [self.prefix.as_slice(), range_end].concat(),
+        }
+    }
+
+    fn prefix_end(&self) -> Vec<u8> {
+        util::get_prefix_end_key(&self.prefix)
+    }
+
+    fn strip(&self, mut kv: KeyValue) -> KeyValue {
+        if kv.key.starts_with(&self.prefix) {
+            let _ = kv.key.drain(..self.prefix.len());
+        }
+        kv
+    }
+}
+
+/// A [KvStore] that isolates the keys of tenants by the tenant in request headers.
+pub struct TenantKvStore {
+    inner: KvStoreRef,
+}
+
+impl TenantKvStore {
+    pub fn new(inner: KvStoreRef) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl KvStore for TenantKvStore {
+    async fn range(&self, mut req: RangeRequest) -> Result<RangeResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.range(req).await;
+        };
+        req.key = ns.key(&req.key)?;
+        req.range_end = ns.range_end(&req.range_end);
+
+        let mut res = self.inner.range(req).await?;
+        res.kvs = res.kvs.into_iter().map(|kv| ns.strip(kv)).collect();
+        Ok(res)
+    }
+
+    async fn put(&self, mut req: PutRequest) -> Result<PutResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.put(req).await;
+        };
+        req.key = ns.key(&req.key)?;
+
+        let mut res = self.inner.put(req).await?;
+        res.prev_kv = res.prev_kv.map(|kv| ns.strip(kv));
+        Ok(res)
+    }
+
+    async fn batch_put(&self, mut req: BatchPutRequest) -> Result<BatchPutResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.batch_put(req).await;
+        };
+        for kv in req.kvs.iter_mut() {
+            kv.key = ns.key(&kv.key)?;
+        }
+
+        let mut res = self.inner.batch_put(req).await?;
+        res.prev_kvs = res.prev_kvs.into_iter().map(|kv| ns.strip(kv)).collect();
+        Ok(res)
+    }
+
+    async fn compare_and_put(
+        &self,
+        mut req: CompareAndPutRequest,
+    ) -> Result<CompareAndPutResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.compare_and_put(req).await;
+        };
+        req.key = ns.key(&req.key)?;
+
+        let mut res = self.inner.compare_and_put(req).await?;
+        res.prev_kv = res.prev_kv.map(|kv| ns.strip(kv));
+        Ok(res)
+    }
+
+    async fn delete_range(&self, mut req: DeleteRangeRequest) -> Result<DeleteRangeResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.delete_range(req).await;
+        };
+        req.key = ns.key(&req.key)?;
+        req.range_end = ns.range_end(&req.range_end);
+
+        let mut res = self.inner.delete_range(req).await?;
+        res.prev_kvs = res.prev_kvs.into_iter().map(|kv| ns.strip(kv)).collect();
+        Ok(res)
+    }
+
+    async fn move_value(&self, mut req: MoveValueRequest) -> Result<MoveValueResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.move_value(req).await;
+        };
+        req.from_key = ns.key(&req.from_key)?;
+        req.to_key = ns.key(&req.to_key)?;
+
+        let mut res = self.inner.move_value(req).await?;
+        res.kv = res.kv.map(|kv| ns.strip(kv));
+        Ok(res)
+    }
+
+    async fn batch_move(&self, mut req: BatchMoveRequest) -> Result<BatchMoveResponse> {
+        let Some(ns) = Namespace::from_header(req.header.as_ref())? else {
+            return self.inner.batch_move(req).await;
+        };
+        for m in req.moves.iter_mut() {
+            m.from_key = ns.key(&m.from_key)?;
+            m.to_key = ns.key(&m.to_key)?;
+        }
+
+        self.inner.batch_move(req).await
+    }
+}
+
+/// Resources of the kv store used by a tenant.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TenantUsage {
+    pub tenant: String,
+    /// Number of keys of the tenant.
+    pub key_count: u64,
+    /// Total bytes of the keys and values of the tenant, keys are counted without the
+    /// tenant prefix.
+    pub bytes: u64,
+}
+
+/// Accounts the keys and bytes used by the `tenant`, by scanning the keys of the tenant.
+pub async fn tenant_usage(kv_store: &KvStoreRef, tenant: &str) -> Result<TenantUsage> {
+    let ns = Namespace::new(tenant)?;
+    let req = RangeRequest {
+        key: ns.prefix.clone(),
+        range_end: ns.prefix_end(),
+        ..Default::default()
+    };
+    let res = kv_store.range(req).await?;
+
+    let mut usage = TenantUsage {
+        tenant: tenant.to_string(),
+        ..Default::default()
+    };
+    for kv in res.kvs {
+        let kv = ns.strip(kv);
+        usage.key_count += 1;
+        usage.bytes += (kv.key.len() + kv.value.len()) as u64;
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::meta::KeyMove;
+
+    use super::*;
+    use crate::service::store::memory::MemStore;
+
+    fn header(tenant: &str) -> Option<RequestHeader> {
+        Some(RequestHeader {
+            tenant: tenant.to_string(),
+            ..Default::default()
+        })
+    }
+
+    async fn put(store: &TenantKvStore, tenant: &str, key: &str, value: &str) {
+        let req = PutRequest {
+            header: header(tenant),
+            key: key.as_bytes().to_vec(),
+            value: value.as_bytes().to_vec(),
+            ..Default::default()
+        };
+        let _ = store.put(req).await.unwrap();
+    }
+
+    async fn range_all(store: &TenantKvStore, tenant: &str) -> Vec<KeyValue> {
+        let req = RangeRequest {
+            header: header(tenant),
+            key: vec![0],
+            range_end: vec![0xff],
+            ..Default::default()
+        };
+        store.range(req).await.unwrap().kvs
+    }
+
+    #[test]
+    fn test_namespace() {
+        assert!(Namespace::new("tenant_1-a").is_ok());
+        assert!(Namespace::new("").is_err());
+        assert!(Namespace::new("a/b").is_err());
+        assert!(Namespace::new("a b").is_err());
+
+        let ns = Namespace::new("t").unwrap();
+        assert_eq!(b"__tenant/t/k".to_vec(), ns.key(b"k").unwrap());
+        assert!(ns.key(b"").is_err());
+        assert!(ns.range_end(b"").is_empty());
+        assert_eq!(ns.prefix_end(), ns.range_end(&[0]));
+        assert_eq!(b"__tenant/t/z".to_vec(), ns.range_end(b"z"));
+        assert_eq!(b"__tenant/t0".to_vec(), ns.prefix_end());
+
+        let kv = ns.strip(KeyValue {
+            key: b"__tenant/t/k".to_vec(),
+            value: vec![],
+        });
+        assert_eq!(b"k".to_vec(), kv.key);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_isolation() {
+        let inner: KvStoreRef = Arc::new(MemStore::new());
+        let store = TenantKvStore::new(inner.clone());
+
+        put(&store, "t1", "key", "v1").await;
+        put(&store, "t2", "key", "v2").await;
+        put(&store, "", "key", "v0").await;
+
+        let kvs = range_all(&store, "t1").await;
+        assert_eq!(1, kvs.len());
+        assert_eq!(b"key".to_vec(), kvs[0].key);
+        assert_eq!(b"v1".to_vec(), kvs[0].value);
+
+        // The default namespace sees all keys.
+        assert_eq!(3, range_all(&store, "").await.len());
+
+        // Deleting all keys of a tenant doesn't touch other tenants.
+        let req = DeleteRangeRequest {
+            header: header("t1"),
+            key: vec![0],
+            range_end: vec![0],
+            prev_kv: true,
+        };
+        let res = store.delete_range(req).await.unwrap();
+        assert_eq!(1, res.deleted);
+        assert_eq!(b"key".to_vec(), res.prev_kvs[0].key);
+        assert!(range_all(&store, "t1").await.is_empty());
+        assert_eq!(1, range_all(&store, "t2").await.len());
+
+        let req = MoveValueRequest {
+            header: header("t2"),
+            from_key: b"key".to_vec(),
+            to_key: b"new_key".to_vec(),
+        };
+        let res = store.move_value(req).await.unwrap();
+        assert_eq!(b"key".to_vec(), res.kv.unwrap().key);
+        assert_eq!(b"new_key".to_vec(), range_all(&store, "t2").await[0].key);
+
+        let usage = tenant_usage(&inner, "t2").await.unwrap();
+        assert_eq!(
+            TenantUsage {
+                tenant: "t2".to_string(),
+                key_count: 1,
+                bytes: 9,
+            },
+            usage
+        );
+        assert_eq!(0, tenant_usage(&inner, "t1").await.unwrap().key_count);
+
+        let batch_move = |expect: &str| BatchMoveRequest {
+            header: header("t2"),
+            moves: vec![KeyMove {
+                from_key: b"new_key".to_vec(),
+                to_key: b"key".to_vec(),
+                expect: expect.as_bytes().to_vec(),
+                value: b"v3".to_vec(),
+            }],
+        };
+        // Nothing is moved if the value is not the expected one.
+        assert!(!store.batch_move(batch_move("v1")).await.unwrap().success);
+        assert_eq!(b"new_key".to_vec(), range_all(&store, "t2").await[0].key);
+        assert!(store.batch_move(batch_move("v2")).await.unwrap().success);
+        let kvs = range_all(&store, "t2").await;
+        assert_eq!(1, kvs.len());
+        assert_eq!(b"key".to_vec(), kvs[0].key);
+        assert_eq!(b"v3".to_vec(), kvs[0].value);
+        // The key of the default namespace is not touched.
+        assert!(range_all(&store, "")
+            .await
+            .iter()
+            .any(|kv| kv.key == b"key" && kv.value == b"v0"));
+    }
+}
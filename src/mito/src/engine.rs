@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
@@ -20,16 +20,25 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_error::ext::BoxedError;
 use common_telemetry::logging;
 use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use futures::future;
 use object_store::ObjectStore;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::{
     ColumnDescriptorBuilder, ColumnFamilyDescriptor, ColumnFamilyDescriptorBuilder, ColumnId,
-    CreateOptions, EngineContext as StorageEngineContext, OpenOptions, RegionDescriptorBuilder,
-    RegionId, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
+    Compression, CreateOptions, DedupPolicy, EngineContext as StorageEngineContext,
+    MergeRegionsRequest, OpenOptions, ParquetOptions, Region, RegionDescriptorBuilder, RegionId,
+    RegionNumber, RowKeyDescriptor, RowKeyDescriptorBuilder, SplitRegionRequest, SstFormat,
+    StorageEngine,
 };
 use table::engine::{EngineContext, TableEngine, TableReference};
-use table::metadata::{TableId, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion};
-use table::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
+use table::metadata::{
+    TableId, TableInfo, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion,
+};
+use table::requests::{
+    AlterKind, AlterTableRequest, CloseTableRequest, CreateTableRequest, DropTableRequest,
+    OpenTableRequest,
+};
 use table::table::TableRef;
 use table::{Result as TableResult, Table};
 use tokio::sync::Mutex;
@@ -37,13 +46,34 @@ use tokio::sync::Mutex;
 use crate::config::EngineConfig;
 use crate::error::{
     self, BuildColumnDescriptorSnafu, BuildColumnFamilyDescriptorSnafu, BuildRegionDescriptorSnafu,
-    BuildRowKeyDescriptorSnafu, InvalidPrimaryKeySnafu, MissingTimestampIndexSnafu, Result,
+    BuildRowKeyDescriptorSnafu, InvalidDedupPolicySnafu, InvalidPrimaryKeySnafu,
+    InvalidSstFormatSnafu, InvalidSstOptionSnafu, MissingTimestampIndexSnafu, Result,
     TableExistsSnafu,
 };
-use crate::table::MitoTable;
+use crate::external::{self, ExternalTable};
+use crate::manifest::TableManifest;
+use crate::partition::{self, RegionPartitionRule};
+use crate::table::{MitoTable, TableRegions};
 
 pub const MITO_ENGINE: &str = "mito";
 pub const INIT_COLUMN_ID: ColumnId = 0;
+/// Table option of the format of SST files, e.g. `parquet` or `arrow_ipc`.
+pub const SST_FORMAT_KEY: &str = "sst_format";
+/// Table option of the compression codec of parquet SST files, one of `none`, `snappy`,
+/// `lz4` and `zstd`.
+pub const SST_COMPRESSION_KEY: &str = "sst_compression";
+/// Table option of the compression codecs of specific columns in parquet SST files,
+/// e.g. `host:lz4,cpu:none`.
+pub const SST_COLUMN_COMPRESSION_KEY: &str = "sst_column_compression";
+/// Table option of whether to enable dictionary encoding in parquet SST files.
+pub const SST_DICTIONARY_KEY: &str = "sst_dictionary";
+/// Table option of the max number of rows in a row group of parquet SST files.
+pub const SST_ROW_GROUP_SIZE_KEY: &str = "sst_row_group_size";
+/// Table option of tag columns to build inverted index for in SST files, e.g. `host,idc`.
+pub const SST_INDEX_COLUMNS_KEY: &str = "sst_index_columns";
+/// Table option of how rows with the same keys and timestamp are deduplicated, either
+/// `last_write_wins` or `keep_all`.
+pub const DEDUP_POLICY_KEY: &str = "dedup_policy";
 const INIT_TABLE_VERSION: TableVersion = 0;
 
 /// Generate region name in the form of "{TABLE_ID}_{REGION_NUMBER}"
@@ -62,6 +92,126 @@ fn table_dir(schema_name: &str, table_id: TableId) -> String {
     format!("{schema_name}/{table_id}/")
 }
 
+/// Returns the format of SST files in table `options`, or the default format if the
+/// option [SST_FORMAT_KEY] is absent.
+pub fn sst_format(table_name: &str, options: &HashMap<String, String>) -> Result<SstFormat> {
+    let Some(format) = options.get(SST_FORMAT_KEY) else {
+        return Ok(SstFormat::default());
+    };
+    SstFormat::from_name(format).context(InvalidSstFormatSnafu { table_name, format })
+}
+
+/// Returns the dedup policy in table `options`, or the default policy if the option
+/// [DEDUP_POLICY_KEY] is absent.
+pub fn dedup_policy(table_name: &str, options: &HashMap<String, String>) -> Result<DedupPolicy> {
+    let Some(policy) = options.get(DEDUP_POLICY_KEY) else {
+        return Ok(DedupPolicy::default());
+    };
+    DedupPolicy::from_name(policy).context(InvalidDedupPolicySnafu { table_name, policy })
+}
+
+/// Returns true if `key` is a table option of SST files.
+pub fn is_sst_option(key: &str) -> bool {
+    matches!(
+        key,
+        SST_FORMAT_KEY
+            | SST_COMPRESSION_KEY
+            | SST_COLUMN_COMPRESSION_KEY
+            | SST_DICTIONARY_KEY
+            | SST_ROW_GROUP_SIZE_KEY
+            | SST_INDEX_COLUMNS_KEY
+    )
+}
+
+/// Returns tag columns to build inverted index for in table `options`.
+pub fn sst_index_columns(options: &HashMap<String, String>) -> Vec<String> {
+    options
+        .get(SST_INDEX_COLUMNS_KEY)
+        .map(|value| {
+            value
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .filter(|column| !column.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns options of parquet SST files in table `options`.
+pub fn parquet_options(
+    table_name: &str,
+    options: &HashMap<String, String>,
+) -> Result<ParquetOptions> {
+    let mut parquet_options = ParquetOptions::default();
+    for (key, value) in options {
+        if let Err(reason) = set_parquet_option(&mut parquet_options, key, value) {
+            return InvalidSstOptionSnafu {
+                table_name,
+                option: key,
+                reason,
+            }
+            .fail();
+        }
+    }
+    Ok(parquet_options)
+}
+
+/// Sets the option `key` of `parquet_options` to `value`, returns the reason if the
+/// value is invalid. Keys of other options are ignored.
+pub fn set_parquet_option(
+    parquet_options: &mut ParquetOptions,
+    key: &str,
+    value: &str,
+) -> std::result::Result<(), String> {
+    let parse_compression = |name: &str| {
+        Compression::from_name(name.trim())
+            .ok_or_else(|| format!("unknown compression codec: {name}"))
+    };
+
+    match key {
+        SST_COMPRESSION_KEY => parquet_options.compression = Some(parse_compression(value)?),
+        SST_COLUMN_COMPRESSION_KEY => {
+            for item in value.split(',') {
+                let (column, compression) = item
+                    .split_once(':')
+                    .ok_or_else(|| format!("expect column:codec, found: {item}"))?;
+                let _ = parquet_options
+                    .column_compression
+                    .insert(column.trim().to_string(), parse_compression(compression)?);
+            }
+        }
+        SST_DICTIONARY_KEY => {
+            let enabled = value
+                .parse()
+                .map_err(|_| format!("expect true or false, found: {value}"))?;
+            parquet_options.dictionary_enabled = Some(enabled);
+        }
+        SST_ROW_GROUP_SIZE_KEY => {
+            let size = value
+                .parse::<usize>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| format!("expect a positive integer, found: {value}"))?;
+            parquet_options.max_row_group_size = Some(size);
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Returns options to create regions of the table with `table_info`.
+fn region_create_options(table_info: &TableInfo) -> Result<CreateOptions> {
+    let table_name = &table_info.name;
+    let options = &table_info.meta.options;
+    Ok(CreateOptions {
+        parent_dir: table_dir(&table_info.schema_name, table_info.ident.table_id),
+        sst_format: sst_format(table_name, options)?,
+        parquet_options: parquet_options(table_name, options)?,
+        sst_index_columns: sst_index_columns(options),
+        dedup_policy: dedup_policy(table_name, options)?,
+    })
+}
+
 /// [TableEngine] implementation.
 ///
 /// About mito <https://en.wikipedia.org/wiki/Alfa_Romeo_MiTo>.
@@ -74,9 +224,65 @@ pub struct MitoEngine<S: StorageEngine> {
 impl<S: StorageEngine> MitoEngine<S> {
     pub fn new(config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
         Self {
-            inner: Arc::new(MitoEngineInner::new(config, storage_engine, object_store)),
+            inner: Arc::new(MitoEngineInner::new(
+                config,
+                storage_engine,
+                object_store,
+                None,
+            )),
         }
     }
+
+    /// Creates the engine that also supports external tables, whose files are read from
+    /// `file_store` instead of the `object_store` of the table data.
+    pub fn with_file_store(
+        config: EngineConfig,
+        storage_engine: S,
+        object_store: ObjectStore,
+        file_store: ObjectStore,
+    ) -> Self {
+        Self {
+            inner: Arc::new(MitoEngineInner::new(
+                config,
+                storage_engine,
+                object_store,
+                Some(file_store),
+            )),
+        }
+    }
+
+    /// Splits the region `region_number` of the table at `split_key` of the partition
+    /// column into two new regions, which share the SSTs of the region.
+    ///
+    /// A table without partition rule is partitioned by its first primary key column,
+    /// or the time index if it has no primary key. Writes to the table are fenced until
+    /// the new regions replace the region, so every row written to the region is in the
+    /// new regions. The region is closed after being replaced.
+    pub async fn split_region(
+        &self,
+        table_ref: &TableReference<'_>,
+        region_number: RegionNumber,
+        split_key: Value,
+    ) -> Result<()> {
+        self.inner
+            .split_region(table_ref, region_number, split_key)
+            .await
+    }
+
+    /// Merges the region `left` and its next region `right` of the table into a new
+    /// region, which shares the SSTs of both regions.
+    ///
+    /// Only regions split from the same region could be merged. Writes to the table are
+    /// fenced until the new region replaces the regions, so every row written to them
+    /// is in the new region. The regions are closed after being replaced.
+    pub async fn merge_regions(
+        &self,
+        table_ref: &TableReference<'_>,
+        left: RegionNumber,
+        right: RegionNumber,
+    ) -> Result<()> {
+        self.inner.merge_regions(table_ref, left, right).await
+    }
 }
 
 #[async_trait]
@@ -128,6 +334,14 @@ impl<S: StorageEngine> TableEngine for MitoEngine<S> {
     ) -> TableResult<bool> {
         Ok(self.inner.drop_table(request).await?)
     }
+
+    async fn close_table(
+        &self,
+        _ctx: &EngineContext,
+        request: CloseTableRequest,
+    ) -> TableResult<bool> {
+        Ok(self.inner.close_table(request).await?)
+    }
 }
 
 struct MitoEngineInner<S: StorageEngine> {
@@ -136,10 +350,15 @@ struct MitoEngineInner<S: StorageEngine> {
     /// Writing to `tables` should also hold the `table_mutex`.
     tables: RwLock<HashMap<String, TableRef>>,
     object_store: ObjectStore,
+    /// Object store of the files of external tables, external tables are not supported
+    /// if absent.
+    file_store: Option<ObjectStore>,
     storage_engine: S,
     /// Table mutex is used to protect the operations such as creating/opening/closing
-    /// a table, to avoid things like opening the same table simultaneously.
+    /// a table, to avoid things like creating the same table simultaneously.
     table_mutex: Mutex<()>,
+    /// Locks of the tables being opened, to avoid opening the same table simultaneously.
+    opening_tables: std::sync::Mutex<HashMap<TableId, Arc<Mutex<()>>>>,
 }
 
 fn build_row_key_desc(
@@ -267,6 +486,30 @@ fn validate_create_table_request(request: &CreateTableRequest) -> Result<()> {
         }
     );
 
+    let _ = partition::load_partition_rule(
+        &request.table_name,
+        &request.schema,
+        &request.table_options,
+        &request.region_numbers,
+    )?;
+    let _ = sst_format(&request.table_name, &request.table_options)?;
+    let _ = parquet_options(&request.table_name, &request.table_options)?;
+
+    let column_schemas = request.schema.column_schemas();
+    for column in sst_index_columns(&request.table_options) {
+        ensure!(
+            request
+                .primary_key_indices
+                .iter()
+                .any(|index| column_schemas[*index].name == column),
+            InvalidSstOptionSnafu {
+                table_name: &request.table_name,
+                option: SST_INDEX_COLUMNS_KEY,
+                reason: format!("{column} is not a tag column"),
+            }
+        );
+    }
+
     Ok(())
 }
 
@@ -298,6 +541,10 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             }
         }
 
+        if external::is_external_table(&request.table_options) {
+            return self.create_external_table(request).await;
+        }
+
         let table_schema = &request.schema;
         let primary_key_indices = &request.primary_key_indices;
         let (next_column_id, default_cf) = build_column_family(
@@ -314,22 +561,23 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         )?;
 
         let table_id = request.id;
-        // TODO(dennis): supports multi regions;
-        assert_eq!(1, request.region_numbers.len());
-        let region_number = request.region_numbers[0];
-        let region_id = region_id(table_id, region_number);
-
-        let region_name = region_name(table_id, region_number);
-        let region_descriptor = RegionDescriptorBuilder::default()
-            .id(region_id)
-            .name(&region_name)
-            .row_key(row_key)
-            .default_cf(default_cf)
-            .build()
-            .context(BuildRegionDescriptorSnafu {
-                table_name,
-                region_name,
-            })?;
+        let region_descriptors = request
+            .region_numbers
+            .iter()
+            .map(|region_number| {
+                let region_name = region_name(table_id, *region_number);
+                RegionDescriptorBuilder::default()
+                    .id(region_id(table_id, *region_number))
+                    .name(&region_name)
+                    .row_key(row_key.clone())
+                    .default_cf(default_cf.clone())
+                    .build()
+                    .context(BuildRegionDescriptorSnafu {
+                        table_name,
+                        region_name,
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let _lock = self.table_mutex.lock().await;
         // Checks again, read lock should be enough since we are guarded by the mutex.
@@ -344,21 +592,32 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         let table_dir = table_dir(schema_name, table_id);
         let opts = CreateOptions {
             parent_dir: table_dir.clone(),
+            sst_format: sst_format(table_name, &request.table_options)?,
+            parquet_options: parquet_options(table_name, &request.table_options)?,
+            sst_index_columns: sst_index_columns(&request.table_options),
+            dedup_policy: dedup_policy(table_name, &request.table_options)?,
         };
 
-        let region = self
-            .storage_engine
-            .create_region(&StorageEngineContext::default(), region_descriptor, &opts)
-            .await
-            .map_err(BoxedError::new)
-            .context(error::CreateRegionSnafu)?;
+        let mut regions = BTreeMap::new();
+        for (region_number, region_descriptor) in
+            request.region_numbers.iter().zip(region_descriptors)
+        {
+            let region = self
+                .storage_engine
+                .create_region(&StorageEngineContext::default(), region_descriptor, &opts)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::CreateRegionSnafu)?;
+            let _ = regions.insert(*region_number, region);
+        }
 
         let table_meta = TableMetaBuilder::default()
             .schema(request.schema)
             .engine(MITO_ENGINE)
             .next_column_id(next_column_id)
             .primary_key_indices(request.primary_key_indices.clone())
-            .region_numbers(vec![region_number])
+            .region_numbers(request.region_numbers)
+            .options(request.table_options)
             .build()
             .context(error::BuildTableMetaSnafu { table_name })?;
 
@@ -377,7 +636,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 table_name,
                 &table_dir,
                 table_info,
-                region,
+                regions,
                 self.object_store.clone(),
             )
             .await?,
@@ -393,6 +652,80 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         Ok(table)
     }
 
+    async fn create_external_table(&self, request: CreateTableRequest) -> Result<TableRef> {
+        let catalog_name = &request.catalog_name;
+        let schema_name = &request.schema_name;
+        let table_name = &request.table_name;
+        let table_ref = TableReference {
+            catalog: catalog_name,
+            schema: schema_name,
+            table: table_name,
+        };
+        let file_store = self.file_store(table_name)?;
+
+        let _lock = self.table_mutex.lock().await;
+        if let Some(table) = self.get_table(&table_ref) {
+            if request.create_if_not_exists {
+                return Ok(table);
+            } else {
+                return TableExistsSnafu { table_name }.fail();
+            }
+        }
+
+        // External tables have no regions, all the columns are stored in the files.
+        let table_id = request.id;
+        let next_column_id = INIT_COLUMN_ID + request.schema.num_columns() as ColumnId;
+        let table_meta = TableMetaBuilder::default()
+            .schema(request.schema)
+            .engine(MITO_ENGINE)
+            .next_column_id(next_column_id)
+            .primary_key_indices(request.primary_key_indices.clone())
+            .region_numbers(vec![])
+            .options(request.table_options)
+            .build()
+            .context(error::BuildTableMetaSnafu { table_name })?;
+
+        let table_info = TableInfoBuilder::new(table_name.clone(), table_meta)
+            .ident(table_id)
+            .table_version(INIT_TABLE_VERSION)
+            .table_type(TableType::Base)
+            .catalog_name(catalog_name.to_string())
+            .schema_name(schema_name.to_string())
+            .desc(request.desc)
+            .build()
+            .context(error::BuildTableInfoSnafu { table_name })?;
+
+        let table_dir = table_dir(schema_name, table_id);
+        let table = Arc::new(
+            ExternalTable::create(
+                &table_dir,
+                table_info,
+                self.object_store.clone(),
+                file_store,
+            )
+            .await?,
+        );
+
+        logging::info!(
+            "Mito engine created external table: {:?}.",
+            table.table_info()
+        );
+
+        self.tables
+            .write()
+            .unwrap()
+            .insert(table_ref.to_string(), table.clone());
+
+        Ok(table)
+    }
+
+    /// Returns the object store of the files of the external table `table_name`.
+    fn file_store(&self, table_name: &str) -> Result<ObjectStore> {
+        self.file_store
+            .clone()
+            .context(error::ExternalTableNotSupportedSnafu { table_name })
+    }
+
     async fn open_table(
         &self,
         _ctx: &EngineContext,
@@ -412,51 +745,165 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             return Ok(Some(table));
         }
 
-        // Acquires the mutex before opening a new table.
-        let table = {
-            let _lock = self.table_mutex.lock().await;
-            // Checks again, read lock should be enough since we are guarded by the mutex.
-            if let Some(table) = self.get_table(&table_ref) {
-                return Ok(Some(table));
-            }
+        // Only opening of the same table is serialized, so different tables could be
+        // recovered concurrently.
+        let table_id = request.table_id;
+        let opening_lock = self.opening_lock(table_id);
+        let result = {
+            let _opening = opening_lock.lock().await;
+            self.open_table_exclusively(&request, &table_ref).await
+        };
+        self.release_opening_lock(table_id, opening_lock);
 
-            let table_id = request.table_id;
-            let engine_ctx = StorageEngineContext::default();
-            let table_dir = table_dir(schema_name, table_id);
-            let opts = OpenOptions {
-                parent_dir: table_dir.to_string(),
-            };
+        let table = result?;
+        if table.is_some() {
+            logging::info!("Mito engine opened table {}", table_name);
+        }
 
-            // TODO(dennis): supports multi regions;
-            assert_eq!(request.region_numbers.len(), 1);
-            let region_number = request.region_numbers[0];
-            let region_name = region_name(table_id, region_number);
+        Ok(table)
+    }
 
-            let region = match self
-                .storage_engine
-                .open_region(&engine_ctx, &region_name, &opts)
-                .await
-                .map_err(BoxedError::new)
-                .context(error::OpenRegionSnafu { region_name })?
-            {
-                None => return Ok(None),
-                Some(region) => region,
+    /// Returns the lock to open the table with `table_id`, keyed by id as the table
+    /// might be opened by its names before and after renaming.
+    fn opening_lock(&self, table_id: TableId) -> Arc<Mutex<()>> {
+        self.opening_tables
+            .lock()
+            .unwrap()
+            .entry(table_id)
+            .or_default()
+            .clone()
+    }
+
+    fn release_opening_lock(&self, table_id: TableId, lock: Arc<Mutex<()>>) {
+        let mut opening_tables = self.opening_tables.lock().unwrap();
+        drop(lock);
+        // Nobody else is waiting for the lock except the map.
+        if opening_tables
+            .get(&table_id)
+            .map(|lock| Arc::strong_count(lock) == 1)
+            .unwrap_or(false)
+        {
+            opening_tables.remove(&table_id);
+        }
+    }
+
+    /// Opens the table while holding the opening lock of the table.
+    async fn open_table_exclusively(
+        &self,
+        request: &OpenTableRequest,
+        table_ref: &TableReference<'_>,
+    ) -> TableResult<Option<TableRef>> {
+        let catalog_name = &request.catalog_name;
+        let schema_name = &request.schema_name;
+        let table_name = &request.table_name;
+        // Checks again, the table might be opened while waiting for the lock.
+        if let Some(table) = self.get_table(table_ref) {
+            return Ok(Some(table));
+        }
+
+        let table_dir = table_dir(schema_name, request.table_id);
+        let Some((table_info, manifest)) =
+            MitoTable::<S::Region>::recover(table_name, &table_dir, self.object_store.clone())
+                .await?
+        else {
+            return Ok(None);
+        };
+        let table: TableRef = if external::is_external_table(&table_info.meta.options) {
+            let file_store = self.file_store(table_name)?;
+            Arc::new(ExternalTable::open(table_info, file_store)?)
+        } else {
+            let Some(table) = self
+                .open_regions(request, &table_dir, table_info, manifest)
+                .await?
+            else {
+                return Ok(None);
             };
+            table
+        };
 
-            let table = Arc::new(
-                MitoTable::open(table_name, &table_dir, region, self.object_store.clone()).await?,
-            );
+        // Holds the table mutex to register the table, so no table with the same name
+        // could be created or renamed to meanwhile.
+        let _lock = self.table_mutex.lock().await;
 
-            self.tables
-                .write()
-                .unwrap()
-                .insert(table_ref.to_string(), table.clone());
-            Some(table as _)
+        // The table might be renamed, so the name in its manifest is used as the key
+        // instead of the name to open.
+        let table_info = table.table_info();
+        let table_ref = TableReference {
+            catalog: catalog_name,
+            schema: schema_name,
+            table: &table_info.name,
         };
+        if let Some(table) = self.get_table(&table_ref) {
+            return Ok(Some(table));
+        }
 
-        logging::info!("Mito engine opened table {}", table_name);
+        self.tables
+            .write()
+            .unwrap()
+            .insert(table_ref.to_string(), table.clone());
+        Ok(Some(table))
+    }
 
-        Ok(table)
+    /// Opens the regions of the table and returns the opened table, returns `None` if
+    /// any region is not found.
+    async fn open_regions(
+        &self,
+        request: &OpenTableRequest,
+        table_dir: &str,
+        table_info: TableInfo,
+        manifest: TableManifest,
+    ) -> TableResult<Option<TableRef>> {
+        let table_name = &request.table_name;
+        let table_id = request.table_id;
+        let engine_ctx = StorageEngineContext::default();
+        let opts = OpenOptions {
+            parent_dir: table_dir.to_string(),
+            sst_format: sst_format(table_name, &table_info.meta.options)?,
+            parquet_options: parquet_options(table_name, &table_info.meta.options)?,
+            sst_index_columns: sst_index_columns(&table_info.meta.options),
+            dedup_policy: dedup_policy(table_name, &table_info.meta.options)?,
+        };
+
+        // Regions might be split or merged after the table is created, so the regions
+        // recorded in the manifest are preferred unless the requested regions are all in
+        // the manifest, e.g. a part of regions migrated from other nodes.
+        let manifest_regions = &table_info.meta.region_numbers;
+        let region_numbers = if manifest_regions.is_empty()
+            || (!request.region_numbers.is_empty()
+                && request
+                    .region_numbers
+                    .iter()
+                    .all(|region_number| manifest_regions.contains(region_number)))
+        {
+            &request.region_numbers
+        } else {
+            manifest_regions
+        };
+        // Regions of the table are recovered concurrently.
+        let opened = future::try_join_all(region_numbers.iter().map(|region_number| {
+            let region_name = region_name(table_id, *region_number);
+            let (engine_ctx, opts) = (&engine_ctx, &opts);
+            async move {
+                let region = self
+                    .storage_engine
+                    .open_region(engine_ctx, &region_name, opts)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(error::OpenRegionSnafu { region_name })?;
+                Ok::<_, error::Error>(region.map(|region| (*region_number, region)))
+            }
+        }))
+        .await?;
+        let mut regions = BTreeMap::new();
+        for region in opened {
+            let Some((region_number, region)) = region else {
+                return Ok(None);
+            };
+            let _ = regions.insert(region_number, region);
+        }
+
+        let table = MitoTable::open(table_name, table_info, regions, manifest)?;
+        Ok(Some(Arc::new(table)))
     }
 
     fn get_table(&self, table_ref: &TableReference) -> Option<TableRef> {
@@ -482,65 +929,382 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             .context(error::TableNotFoundSnafu { table_name })?;
 
         logging::info!("start altering table {} with request {:?}", table_name, req);
-        table
-            .alter(req)
-            .await
-            .context(error::AlterTableSnafu { table_name })?;
+        if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
+            let new_table_name = new_table_name.clone();
+            let new_table_ref = TableReference {
+                catalog: catalog_name,
+                schema: schema_name,
+                table: &new_table_name,
+            };
+            // Acquires the mutex so no table with the new name could be created or opened
+            // while renaming.
+            let _lock = self.table_mutex.lock().await;
+            ensure!(
+                self.get_table(&new_table_ref).is_none(),
+                error::TableExistsSnafu {
+                    table_name: new_table_ref.to_string(),
+                }
+            );
+
+            table
+                .alter(req)
+                .await
+                .context(error::AlterTableSnafu { table_name })?;
+
+            let mut tables = self.tables.write().unwrap();
+            tables.remove(&table_ref.to_string());
+            tables.insert(new_table_ref.to_string(), table.clone());
+        } else {
+            table
+                .alter(req)
+                .await
+                .context(error::AlterTableSnafu { table_name })?;
+        }
         Ok(table)
     }
 
-    /// Drop table. Returns whether a table is dropped (true) or not exist (false).
-    async fn drop_table(&self, req: DropTableRequest) -> Result<bool> {
-        let table_reference = TableReference {
-            catalog: &req.catalog_name,
-            schema: &req.schema_name,
-            table: &req.table_name,
+    async fn split_region(
+        &self,
+        table_ref: &TableReference<'_>,
+        region_number: RegionNumber,
+        split_key: Value,
+    ) -> Result<()> {
+        let table_name = table_ref.table;
+        let table = self
+            .get_table(table_ref)
+            .context(error::TableNotFoundSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        let region_not_found = error::RegionNotFoundSnafu {
+            table_name,
+            region_number,
         };
-        // todo(ruihang): reclaim persisted data
-        Ok(self
-            .tables
-            .write()
-            .unwrap()
-            .remove(&table_reference.to_string())
-            .is_some())
-    }
-}
+        let table = table
+            .as_any()
+            .downcast_ref::<MitoTable<S::Region>>()
+            .context(region_not_found)?;
 
-impl<S: StorageEngine> MitoEngineInner<S> {
-    fn new(_config: EngineConfig, storage_engine: S, object_store: ObjectStore) -> Self {
-        Self {
-            tables: RwLock::new(HashMap::default()),
-            storage_engine,
-            object_store,
-            table_mutex: Mutex::new(()),
-        }
-    }
-}
+        let _lock = table.alter_lock().lock().await;
+        let fenced = table.fence_writes().await;
+        let table_info = table.table_info();
+        let table_regions = table.table_regions();
+        let region = table_regions
+            .regions
+            .get(&region_number)
+            .context(region_not_found)?;
+        // A fenced region is being migrated.
+        ensure!(
+            !fenced.contains(&region_number),
+            error::RegionFencedSnafu {
+                table_name,
+                region_number,
+            }
+        );
+        let rule = match &table_regions.partition_rule {
+            Some(rule) => rule.clone(),
+            None => {
+                let schema = &table_info.meta.schema;
+                // Safety: A table with regions always has a time index.
+                let column_index = table_info
+                    .meta
+                    .primary_key_indices
+                    .first()
+                    .copied()
+                    .or_else(|| schema.timestamp_index())
+                    .unwrap();
+                let column_name = schema.column_name_by_index(column_index);
+                RegionPartitionRule::new(column_name, vec![], vec![region_number])
+            }
+        };
+        // Safety: The partition column is validated against the schema of the table.
+        let column_schema = table_info
+            .meta
+            .schema
+            .column_schema_by_name(rule.column_name())
+            .unwrap();
+        ensure!(
+            split_key.data_type() == column_schema.data_type,
+            error::InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!(
+                    "type of split key must be {}",
+                    column_schema.data_type.name()
+                ),
+            }
+        );
 
-#[cfg(test)]
-mod tests {
-    use common_query::physical_plan::SessionContext;
-    use common_recordbatch::util;
-    use datatypes::prelude::ConcreteDataType;
-    use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, SchemaBuilder};
-    use datatypes::value::Value;
-    use datatypes::vectors::{
-        Float64Vector, Int32Vector, StringVector, TimestampMillisecondVector, VectorRef,
-    };
-    use log_store::fs::noop::NoopLogStore;
-    use storage::config::EngineConfig as StorageEngineConfig;
-    use storage::EngineImpl;
-    use store_api::manifest::Manifest;
-    use store_api::storage::ReadContext;
-    use table::requests::{AddColumnRequest, AlterKind};
-    use tempdir::TempDir;
+        // Safety: A table has at least one region.
+        let max_region_number = *table_regions.regions.keys().last().unwrap();
+        let (left_number, right_number) = (max_region_number + 1, max_region_number + 2);
+        let new_rule = rule
+            .split_region(region_number, split_key.clone(), left_number, right_number)
+            .with_context(|| error::InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!(
+                    "split key {} is out of the range of region {}",
+                    split_key, region_number
+                ),
+            })?;
 
-    use super::*;
-    use crate::table::test_util;
-    use crate::table::test_util::{new_insert_request, MockRegion, TABLE_NAME};
+        let table_id = table_info.ident.table_id;
+        let request = SplitRegionRequest {
+            column_name: new_rule.column_name().to_string(),
+            split_key,
+            left_id: region_id(table_id, left_number),
+            left_name: region_name(table_id, left_number),
+            right_id: region_id(table_id, right_number),
+            right_name: region_name(table_id, right_number),
+        };
+        let opts = region_create_options(&table_info)?;
+        let (left, right) = self
+            .storage_engine
+            .split_region(&StorageEngineContext::default(), region, request, &opts)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::SplitRegionSnafu {
+                region_name: region.name(),
+            })?;
 
-    async fn setup_table_with_column_default_constraint() -> (TempDir, String, TableRef) {
-        let table_name = "test_default_constraint";
+        let mut regions = table_regions.regions.clone();
+        let _ = regions.remove(&region_number);
+        let _ = regions.insert(left_number, left);
+        let _ = regions.insert(right_number, right);
+        table.set_regions(regions, new_rule).await?;
+        drop(fenced);
+        self.close_regions(&table_regions, &[region_number]).await?;
+
+        logging::info!(
+            "Mito engine split region {} of table {} into {} and {}",
+            region_number,
+            table_name,
+            left_number,
+            right_number
+        );
+
+        Ok(())
+    }
+
+    async fn merge_regions(
+        &self,
+        table_ref: &TableReference<'_>,
+        left_number: RegionNumber,
+        right_number: RegionNumber,
+    ) -> Result<()> {
+        let table_name = table_ref.table;
+        let table = self
+            .get_table(table_ref)
+            .context(error::TableNotFoundSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        let table = table
+            .as_any()
+            .downcast_ref::<MitoTable<S::Region>>()
+            .context(error::RegionNotFoundSnafu {
+                table_name,
+                region_number: left_number,
+            })?;
+
+        let _lock = table.alter_lock().lock().await;
+        let fenced = table.fence_writes().await;
+        let table_info = table.table_info();
+        let table_regions = table.table_regions();
+        let get_region = |region_number| {
+            table_regions
+                .regions
+                .get(&region_number)
+                .context(error::RegionNotFoundSnafu {
+                    table_name,
+                    region_number,
+                })
+        };
+        let (left, right) = (get_region(left_number)?, get_region(right_number)?);
+        for region_number in [left_number, right_number] {
+            ensure!(
+                !fenced.contains(&region_number),
+                error::RegionFencedSnafu {
+                    table_name,
+                    region_number,
+                }
+            );
+        }
+
+        // Safety: A table has at least one region.
+        let merged_number = *table_regions.regions.keys().last().unwrap() + 1;
+        let new_rule = table_regions
+            .partition_rule
+            .as_ref()
+            .and_then(|rule| rule.merge_regions(left_number, right_number, merged_number))
+            .with_context(|| error::InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!(
+                    "region {} is not followed by region {}",
+                    left_number, right_number
+                ),
+            })?;
+
+        let table_id = table_info.ident.table_id;
+        let request = MergeRegionsRequest {
+            id: region_id(table_id, merged_number),
+            name: region_name(table_id, merged_number),
+        };
+        let opts = region_create_options(&table_info)?;
+        let merged = self
+            .storage_engine
+            .merge_regions(
+                &StorageEngineContext::default(),
+                left,
+                right,
+                request,
+                &opts,
+            )
+            .await
+            .map_err(BoxedError::new)
+            .context(error::MergeRegionsSnafu {
+                left: left.name(),
+                right: right.name(),
+            })?;
+
+        let mut regions = table_regions.regions.clone();
+        let _ = regions.remove(&left_number);
+        let _ = regions.remove(&right_number);
+        let _ = regions.insert(merged_number, merged);
+        table.set_regions(regions, new_rule).await?;
+        drop(fenced);
+        self.close_regions(&table_regions, &[left_number, right_number])
+            .await?;
+
+        logging::info!(
+            "Mito engine merged regions {} and {} of table {} into {}",
+            left_number,
+            right_number,
+            table_name,
+            merged_number
+        );
+
+        Ok(())
+    }
+
+    /// Closes the regions replaced by splitting or merging, which are released once
+    /// scans still reading them finish.
+    async fn close_regions(
+        &self,
+        table_regions: &TableRegions<S::Region>,
+        region_numbers: &[RegionNumber],
+    ) -> Result<()> {
+        let engine_ctx = StorageEngineContext::default();
+        for region_number in region_numbers {
+            // Safety: The regions are checked to exist before being replaced.
+            let region = table_regions.regions[region_number].clone();
+            let region_name = region.name().to_string();
+            self.storage_engine
+                .close_region(&engine_ctx, region)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::CloseRegionSnafu { region_name })?;
+        }
+        Ok(())
+    }
+
+    /// Drop table. Returns whether a table is dropped (true) or not exist (false).
+    async fn drop_table(&self, req: DropTableRequest) -> Result<bool> {
+        let table_reference = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        // todo(ruihang): reclaim persisted data
+        Ok(self
+            .tables
+            .write()
+            .unwrap()
+            .remove(&table_reference.to_string())
+            .is_some())
+    }
+
+    async fn close_table(&self, req: CloseTableRequest) -> Result<bool> {
+        let table_reference = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table = {
+            let _lock = self.table_mutex.lock().await;
+            self.tables
+                .write()
+                .unwrap()
+                .remove(&table_reference.to_string())
+        };
+        let Some(table) = table else {
+            return Ok(false);
+        };
+
+        if let Some(table) = table.as_any().downcast_ref::<MitoTable<S::Region>>() {
+            let engine_ctx = StorageEngineContext::default();
+            for region in table.regions().into_values() {
+                let region_name = region.name().to_string();
+                self.storage_engine
+                    .close_region(&engine_ctx, region)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(error::CloseRegionSnafu { region_name })?;
+            }
+        }
+        logging::info!("Mito engine closed table {}", req.table_name);
+
+        Ok(true)
+    }
+}
+
+impl<S: StorageEngine> MitoEngineInner<S> {
+    fn new(
+        _config: EngineConfig,
+        storage_engine: S,
+        object_store: ObjectStore,
+        file_store: Option<ObjectStore>,
+    ) -> Self {
+        Self {
+            tables: RwLock::new(HashMap::default()),
+            storage_engine,
+            object_store,
+            file_store,
+            table_mutex: Mutex::new(()),
+            opening_tables: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use common_query::logical_plan::Expr;
+    use common_query::physical_plan::{ColumnStatistics, SessionContext};
+    use common_recordbatch::util;
+    use common_time::{Timestamp, TimestampRange};
+    use datafusion::logical_expr::{col, lit};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, SchemaBuilder};
+    use datatypes::value::Value;
+    use datatypes::vectors::{
+        Float64Vector, Int32Vector, StringVector, TimestampMillisecondVector, VectorRef,
+    };
+    use log_store::fs::noop::NoopLogStore;
+    use storage::config::EngineConfig as StorageEngineConfig;
+    use storage::region::RegionImpl;
+    use storage::EngineImpl;
+    use store_api::manifest::Manifest;
+    use store_api::storage::{ReadContext, Region, RegionMeta, ScanOrder, SequenceNumber};
+    use table::metadata::FilterPushDownType;
+    use table::requests::{AddColumnRequest, AlterKind, DeleteRangeRequest};
+    use table::table::TableStatistics;
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::partition::{RegionPartitionRule, PARTITION_RULE_KEY};
+    use crate::table::test_util;
+    use crate::table::test_util::{new_insert_request, MockRegion, TABLE_NAME};
+
+    async fn setup_table_with_column_default_constraint() -> (TempDir, String, TableRef) {
+        let table_name = "test_default_constraint";
         let column_schemas = vec![
             ColumnSchema::new("name", ConcreteDataType::string_datatype(), false),
             ColumnSchema::new("n", ConcreteDataType::int32_datatype(), true)
@@ -655,6 +1419,34 @@ mod tests {
         assert_eq!(tss, *record.column(2));
     }
 
+    #[tokio::test]
+    async fn test_insert_invalid_columns() {
+        let (_dir, table_name, table) = setup_table_with_column_default_constraint().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let names: VectorRef = Arc::new(StringVector::from(vec![Some("first"), None]));
+        let nums: VectorRef = Arc::new(StringVector::from(vec!["1", "2"]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2]));
+
+        columns_values.insert("name".to_string(), names);
+        columns_values.insert("n".to_string(), nums);
+        columns_values.insert("ts".to_string(), tss);
+
+        let insert_req = new_insert_request(table_name.to_string(), columns_values);
+        let err = table.insert(insert_req).await.unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        // All invalid columns are reported.
+        let msg = err.to_string();
+        assert!(
+            msg.contains("column name is not nullable, but has 1 null values at rows [1]"),
+            "{msg}"
+        );
+        assert!(
+            msg.contains("column n expects type Int32, but is String"),
+            "{msg}"
+        );
+    }
+
     #[test]
     fn test_region_name() {
         assert_eq!("1_0000000000", region_name(1, 0));
@@ -710,6 +1502,19 @@ mod tests {
 
         request.primary_key_indices = vec![0];
         assert!(validate_create_table_request(&request).is_ok());
+
+        request.table_options =
+            HashMap::from([(SST_INDEX_COLUMNS_KEY.to_string(), "name, ts".to_string())]);
+        let err = validate_create_table_request(&request).unwrap_err();
+        assert!(err.to_string().contains("ts is not a tag column"));
+
+        request.table_options =
+            HashMap::from([(SST_INDEX_COLUMNS_KEY.to_string(), " name,".to_string())]);
+        assert!(validate_create_table_request(&request).is_ok());
+        assert_eq!(
+            vec!["name".to_string()],
+            sst_index_columns(&request.table_options)
+        );
     }
 
     #[tokio::test]
@@ -790,6 +1595,132 @@ mod tests {
         let record = &batches[0];
         assert_eq!(1, record.num_columns());
         assert_eq!(tss, *record.column(0));
+
+        // Scan with limit
+        let stream = table.scan(Some(&vec![3]), &[], Some(1)).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(tss.slice(0, 1), *batches[0].column(0));
+    }
+
+    #[tokio::test]
+    async fn test_delete_range() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2", "host3"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5, 66.6, 77.7]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64, 0f64]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 2000, 3000]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(3, table.insert(insert_req).await.unwrap());
+
+        // Bounds in seconds are converted to the unit of the time index.
+        let range =
+            TimestampRange::new(Timestamp::new_second(1), Timestamp::new_second(3)).unwrap();
+        let delete_req = DeleteRangeRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "demo".to_string(),
+            range,
+        };
+        table.delete_range(delete_req).await.unwrap();
+
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(Some(&vec![3]), &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        let expect: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![3000]));
+        assert_eq!(1, batches.len());
+        assert_eq!(expect, *batches[0].column(0));
+    }
+
+    #[tokio::test]
+    async fn test_scan_statistics() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let plan = table.scan(None, &[], None).await.unwrap();
+        let statistics = plan.statistics();
+        assert_eq!(Some(0), statistics.num_rows);
+        assert!(!statistics.is_exact);
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2", "host3"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5, 66.6, 77.7]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64, 0f64]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![2000, 1000, 3000]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(3, table.insert(insert_req).await.unwrap());
+
+        // Scan with projections: cpu and ts
+        let plan = table.scan(Some(&vec![1, 3]), &[], None).await.unwrap();
+        let statistics = plan.statistics();
+        assert_eq!(Some(3), statistics.num_rows);
+        assert!(statistics.total_byte_size.unwrap() > 0);
+        let column_statistics = statistics.column_statistics.unwrap();
+        assert_eq!(2, column_statistics.len());
+        assert_eq!(ColumnStatistics::default(), column_statistics[0]);
+        let expect_min = Value::Timestamp(Timestamp::new_millisecond(1000))
+            .try_to_scalar_value(&ConcreteDataType::timestamp_millisecond_datatype())
+            .unwrap();
+        let expect_max = Value::Timestamp(Timestamp::new_millisecond(3000))
+            .try_to_scalar_value(&ConcreteDataType::timestamp_millisecond_datatype())
+            .unwrap();
+        assert_eq!(Some(expect_min), column_statistics[1].min_value);
+        assert_eq!(Some(expect_max), column_statistics[1].max_value);
+    }
+
+    #[tokio::test]
+    async fn test_table_statistics() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let statistics = table.statistics().unwrap().unwrap();
+        assert_eq!(0, statistics.num_rows);
+        assert_eq!(4, statistics.column_statistics.len());
+        assert!(statistics
+            .column_statistics
+            .iter()
+            .all(|s| *s == table::table::ColumnStatistics::default()));
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5, 66.6]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![2000, 1000]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+
+        let check_statistics = |statistics: TableStatistics| {
+            assert_eq!(2, statistics.num_rows);
+            assert!(statistics.total_bytes > 0);
+            let ts_statistics = &statistics.column_statistics[3];
+            assert_eq!(
+                Some(Value::Timestamp(Timestamp::new_millisecond(1000))),
+                ts_statistics.min_value
+            );
+            assert_eq!(
+                Some(Value::Timestamp(Timestamp::new_millisecond(2000))),
+                ts_statistics.max_value
+            );
+        };
+        check_statistics(table.statistics().unwrap().unwrap());
+
+        // Statistics are computed from SSTs after flush.
+        table.flush().await.unwrap();
+        check_statistics(table.statistics().unwrap().unwrap());
     }
 
     #[tokio::test]
@@ -833,6 +1764,391 @@ mod tests {
         assert_eq!(test_batch_size, total);
     }
 
+    #[tokio::test]
+    async fn test_multi_region_table() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_multi_region_table").await;
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+        );
+
+        let schema = Arc::new(test_util::schema_for_test());
+        let mut request = CreateTableRequest {
+            id: 1,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: TABLE_NAME.to_string(),
+            desc: None,
+            schema,
+            region_numbers: vec![0, 1, 2],
+            create_if_not_exists: true,
+            primary_key_indices: vec![0],
+            table_options: HashMap::new(),
+        };
+        // Partition rule is required by table with multiple regions.
+        assert!(table_engine
+            .create_table(&EngineContext::default(), request.clone())
+            .await
+            .is_err());
+
+        let rule =
+            RegionPartitionRule::new("host", vec!["host2".into(), "host3".into()], vec![0, 1, 2]);
+        request
+            .table_options
+            .insert(PARTITION_RULE_KEY.to_string(), rule.to_option());
+        let table = table_engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+        assert_eq!(vec![0, 1, 2], table.table_info().meta.region_numbers);
+        assert!(table.scan_in_time_index_order());
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef =
+            Arc::new(StringVector::from(vec!["host1", "host3", "host2", "host4"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 3.0, 2.0, 4.0]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 3.0, 2.0, 4.0]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 3, 2, 4]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(4, table.insert(insert_req).await.unwrap());
+
+        let scan_hosts = |filters: Vec<Expr>| {
+            let table = table.clone();
+            async move {
+                let session_ctx = SessionContext::new();
+                let plan = table.scan(Some(&vec![0]), &filters, None).await.unwrap();
+                let stream = plan.execute(0, session_ctx.task_ctx()).unwrap();
+                let batches = util::collect(stream).await.unwrap();
+                let mut hosts: Vec<_> = batches
+                    .iter()
+                    .flat_map(|batch| {
+                        let column = batch.column(0);
+                        (0..column.len())
+                            .map(|i| column.get(i).to_string())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+                hosts.sort();
+                (hosts, plan.statistics().num_rows)
+            }
+        };
+
+        // Scans all regions.
+        let (hosts, num_rows) = scan_hosts(vec![]).await;
+        assert_eq!(vec!["host1", "host2", "host3", "host4"], hosts);
+        assert_eq!(Some(4), num_rows);
+
+        // Filters on the partition column prune regions, rows of other regions are not
+        // returned.
+        let (hosts, num_rows) = scan_hosts(vec![col("host").lt(lit("host3")).into()]).await;
+        assert_eq!(vec!["host1", "host2"], hosts);
+        assert_eq!(Some(2), num_rows);
+        let (hosts, _) = scan_hosts(vec![col("host").eq(lit("host3")).into()]).await;
+        assert_eq!(vec!["host3"], hosts);
+
+        // Rows of all regions are sorted by the time index and limited.
+        let scan_ordered = |filters: Vec<Expr>, order: ScanOrder| {
+            let table = table.clone();
+            async move {
+                let session_ctx = SessionContext::new();
+                let plan = table
+                    .scan_ordered(Some(&vec![3]), &filters, Some(2), order)
+                    .await
+                    .unwrap();
+                let stream = plan.execute(0, session_ctx.task_ctx()).unwrap();
+                let batches = util::collect(stream).await.unwrap();
+                batches
+                    .iter()
+                    .flat_map(|batch| {
+                        let column = batch.column(0);
+                        (0..column.len()).map(|i| column.get(i)).collect::<Vec<_>>()
+                    })
+                    .take(2)
+                    .collect::<Vec<_>>()
+            }
+        };
+        let ts = |v: i64| Value::Timestamp(Timestamp::new_millisecond(v));
+        assert_eq!(
+            vec![ts(1), ts(2)],
+            scan_ordered(vec![], ScanOrder::Asc).await
+        );
+        assert_eq!(
+            vec![ts(4), ts(3)],
+            scan_ordered(vec![], ScanOrder::Desc).await
+        );
+        let host_filter: Expr = col("host").lt(lit("host3")).into();
+        assert_eq!(
+            FilterPushDownType::Exact,
+            table.supports_filter_pushdown(&host_filter).unwrap()
+        );
+        assert_eq!(
+            vec![ts(2), ts(1)],
+            scan_ordered(vec![host_filter], ScanOrder::Desc).await
+        );
+
+        // Alters all regions.
+        let req = AlterTableRequest {
+            catalog_name: None,
+            schema_name: None,
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::DropColumns {
+                names: vec!["memory".to_string()],
+            },
+        };
+        let table = table_engine
+            .alter_table(&EngineContext::default(), req)
+            .await
+            .unwrap();
+        let table = table
+            .as_any()
+            .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+            .unwrap();
+        for region in table.regions().values() {
+            let region_meta = region.in_memory_metadata();
+            assert!(region_meta
+                .schema()
+                .column_index_by_name("memory")
+                .is_none());
+        }
+
+        // The partition column can't be dropped.
+        let req = AlterTableRequest {
+            catalog_name: None,
+            schema_name: None,
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::DropColumns {
+                names: vec!["host".to_string()],
+            },
+        };
+        let err = table_engine
+            .alter_table(&EngineContext::default(), req)
+            .await
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("partition column host can't be dropped"));
+    }
+
+    #[tokio::test]
+    async fn test_split_and_merge_regions() {
+        let (table_engine, table, _schema, dir) = test_util::setup_test_engine_and_table().await;
+        let table_ref = TableReference {
+            catalog: "greptime",
+            schema: "public",
+            table: TABLE_NAME,
+        };
+
+        let insert_hosts = |hosts: Vec<&str>| {
+            let table = table.clone();
+            let num_rows = hosts.len();
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            let values: Vec<_> = (0..num_rows).map(|i| i as f64).collect();
+            let tss: Vec<_> = (0..num_rows).map(|i| i as i64).collect();
+            columns_values.insert("host".to_string(), Arc::new(StringVector::from(hosts)));
+            columns_values.insert(
+                "cpu".to_string(),
+                Arc::new(Float64Vector::from_vec(values.clone())),
+            );
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(values)),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampMillisecondVector::from_vec(tss)),
+            );
+            let insert_req = new_insert_request(TABLE_NAME.to_string(), columns_values);
+            async move {
+                assert_eq!(num_rows, table.insert(insert_req).await.unwrap());
+            }
+        };
+        let scan_hosts = |table: TableRef, filters: Vec<Expr>| async move {
+            let session_ctx = SessionContext::new();
+            let plan = table.scan(Some(&vec![0]), &filters, None).await.unwrap();
+            let stream = plan.execute(0, session_ctx.task_ctx()).unwrap();
+            let batches = util::collect(stream).await.unwrap();
+            let mut hosts: Vec<_> = batches
+                .iter()
+                .flat_map(|batch| {
+                    let column = batch.column(0);
+                    (0..column.len())
+                        .map(|i| column.get(i).to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            hosts.sort();
+            hosts
+        };
+
+        insert_hosts(vec!["host1", "host2", "host3", "host4"]).await;
+        // The split key must have the type of the partition column.
+        assert!(table_engine
+            .split_region(&table_ref, 0, Value::from(3))
+            .await
+            .is_err());
+        table_engine
+            .split_region(&table_ref, 0, Value::from("host3"))
+            .await
+            .unwrap();
+        assert_eq!(vec![1, 2], table.table_info().meta.region_numbers);
+        let get_region = |region_number| {
+            table_engine
+                .inner
+                .storage_engine
+                .get_region(
+                    &StorageEngineContext::default(),
+                    &region_name(1, region_number),
+                )
+                .unwrap()
+        };
+        // The split region is closed.
+        assert!(get_region(0).is_none());
+        assert!(get_region(1).is_some());
+        assert_eq!(
+            vec!["host1", "host2", "host3", "host4"],
+            scan_hosts(table.clone(), vec![]).await
+        );
+        // Filters on the partition column prune regions.
+        assert_eq!(
+            vec!["host1", "host2"],
+            scan_hosts(table.clone(), vec![col("host").lt(lit("host3")).into()]).await
+        );
+
+        insert_hosts(vec!["host0", "host5"]).await;
+        // Only adjacent regions in order can be merged.
+        assert!(table_engine.merge_regions(&table_ref, 2, 1).await.is_err());
+        table_engine.merge_regions(&table_ref, 1, 2).await.unwrap();
+        assert_eq!(vec![3], table.table_info().meta.region_numbers);
+        assert!(get_region(1).is_none());
+        assert!(get_region(2).is_none());
+        let expect = vec!["host0", "host1", "host2", "host3", "host4", "host5"];
+        assert_eq!(expect, scan_hosts(table.clone(), vec![]).await);
+
+        // Reopens the table with the regions in its manifest.
+        let store_dir = dir.path().to_string_lossy();
+        let accessor = object_store::backend::fs::Builder::default()
+            .root(&store_dir)
+            .build()
+            .unwrap();
+        let object_store = ObjectStore::new(accessor);
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+        );
+        let open_req = OpenTableRequest {
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+        let reopened = table_engine
+            .open_table(&EngineContext::default(), open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![3], reopened.table_info().meta.region_numbers);
+        assert_eq!(expect, scan_hosts(reopened, vec![]).await);
+    }
+
+    #[tokio::test]
+    async fn test_fence_writes() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+        let mito_table = table
+            .as_any()
+            .downcast_ref::<MitoTable<RegionImpl<NoopLogStore>>>()
+            .unwrap();
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        columns_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1"])),
+        );
+        columns_values.insert(
+            "cpu".to_string(),
+            Arc::new(Float64Vector::from_vec(vec![1.0])),
+        );
+        columns_values.insert(
+            "memory".to_string(),
+            Arc::new(Float64Vector::from_vec(vec![1.0])),
+        );
+        columns_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1])),
+        );
+        let insert_req = || new_insert_request(TABLE_NAME.to_string(), columns_values.clone());
+
+        // Writes wait until the fence is released, e.g. after splitting a region.
+        let fence = mito_table.fence_writes().await;
+        let insert = table.insert(insert_req());
+        tokio::pin!(insert);
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(100), &mut insert)
+                .await
+                .is_err()
+        );
+        drop(fence);
+        assert_eq!(1, insert.await.unwrap());
+
+        // Writes to a fenced region are rejected until it's unfenced.
+        table.fence_region(0, true).await.unwrap();
+        let err = table.insert(insert_req()).await.unwrap_err();
+        assert_eq!(StatusCode::StorageUnavailable, err.status_code());
+        assert!(table.truncate().await.is_err());
+        table.fence_region(0, false).await.unwrap();
+        assert_eq!(1, table.insert(insert_req()).await.unwrap());
+        assert!(table.fence_region(1, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_at_sequence() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5, 66.6]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2]));
+
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss.clone());
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+
+        // Nothing is visible before the first write.
+        let stream = table.scan_at_sequence(None, 0).await.unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert!(batches.iter().all(|batch| batch.num_rows() == 0));
+
+        let stream = table
+            .scan_at_sequence(Some(&vec![3]), SequenceNumber::MAX)
+            .await
+            .unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(1, batches[0].num_columns());
+        assert_eq!(tss, *batches[0].column(0));
+    }
+
     #[tokio::test]
     async fn test_create_if_not_exists() {
         common_telemetry::init_default_ut_logging();
@@ -927,6 +2243,186 @@ mod tests {
         assert_eq!(reopened.manifest().last_version(), 1);
     }
 
+    #[tokio::test]
+    async fn test_open_table_concurrently() {
+        let ctx = EngineContext::default();
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: test_util::TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+
+        let (engine, _table_engine, _table, object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let table_engine = MitoEngine::new(EngineConfig::default(), engine, object_store);
+
+        let (first, second) = futures::join!(
+            table_engine.open_table(&ctx, open_req.clone()),
+            table_engine.open_table(&ctx, open_req.clone())
+        );
+        let first = first.unwrap().unwrap();
+        let second = second.unwrap().unwrap();
+        // The table is only opened once.
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(table_engine.inner.opening_tables.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_table_with_sst_format() {
+        fn list_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    list_files(&path, files);
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+
+        let (dir, object_store) =
+            test_util::new_test_object_store("test_table_with_sst_format").await;
+        let new_engine = || {
+            MitoEngine::new(
+                EngineConfig::default(),
+                EngineImpl::new(
+                    StorageEngineConfig::default(),
+                    Arc::new(NoopLogStore::default()),
+                    object_store.clone(),
+                ),
+                object_store.clone(),
+            )
+        };
+        let ctx = EngineContext::default();
+        let table_engine = new_engine();
+
+        let mut request = test_util::new_create_request(Arc::new(test_util::schema_for_test()));
+        let _ = request
+            .table_options
+            .insert(SST_FORMAT_KEY.to_string(), "orc".to_string());
+        assert!(table_engine
+            .create_table(&ctx, request.clone())
+            .await
+            .is_err());
+
+        let _ = request
+            .table_options
+            .insert(SST_FORMAT_KEY.to_string(), "arrow_ipc".to_string());
+        let table = table_engine.create_table(&ctx, request).await.unwrap();
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts: VectorRef = Arc::new(StringVector::from(vec!["host1", "host2"]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![55.5, 66.6]));
+        let memories: VectorRef = Arc::new(Float64Vector::from_vec(vec![1024f64, 4096f64]));
+        let tss: VectorRef = Arc::new(TimestampMillisecondVector::from_vec(vec![2000, 1000]));
+        columns_values.insert("host".to_string(), hosts);
+        columns_values.insert("cpu".to_string(), cpus);
+        columns_values.insert("memory".to_string(), memories);
+        columns_values.insert("ts".to_string(), tss);
+        let insert_req = new_insert_request(TABLE_NAME.to_string(), columns_values);
+        assert_eq!(2, table.insert(insert_req).await.unwrap());
+        table.flush().await.unwrap();
+
+        // The flushed SST is an Arrow IPC file.
+        let mut files = Vec::new();
+        list_files(dir.path(), &mut files);
+        let sst_files: Vec<_> = files
+            .iter()
+            .filter(|path| path.extension().map(|ext| ext == "arrow").unwrap_or(false))
+            .collect();
+        assert_eq!(1, sst_files.len());
+        assert!(std::fs::read(sst_files[0]).unwrap().starts_with(b"ARROW1"));
+
+        // Reads the file by the format recorded in the manifest after reopening.
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+        let table = new_engine()
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        let session_ctx = SessionContext::new();
+        let stream = table.scan(None, &[], None).await.unwrap();
+        let stream = stream.execute(0, session_ctx.task_ctx()).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(
+            2,
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_parquet_options() {
+        let options = HashMap::from([
+            (SST_COMPRESSION_KEY.to_string(), "lz4".to_string()),
+            (
+                SST_COLUMN_COMPRESSION_KEY.to_string(),
+                "host:none, cpu:SNAPPY".to_string(),
+            ),
+            (SST_DICTIONARY_KEY.to_string(), "false".to_string()),
+            (SST_ROW_GROUP_SIZE_KEY.to_string(), "1024".to_string()),
+            ("other".to_string(), "value".to_string()),
+        ]);
+        let parquet_options = parquet_options(TABLE_NAME, &options).unwrap();
+        assert_eq!(
+            ParquetOptions {
+                compression: Some(Compression::Lz4),
+                column_compression: HashMap::from([
+                    ("host".to_string(), Compression::Uncompressed),
+                    ("cpu".to_string(), Compression::Snappy),
+                ]),
+                dictionary_enabled: Some(false),
+                max_row_group_size: Some(1024),
+            },
+            parquet_options
+        );
+        assert_eq!(
+            ParquetOptions::default(),
+            parquet_options(TABLE_NAME, &HashMap::new()).unwrap()
+        );
+
+        for (key, value) in [
+            (SST_COMPRESSION_KEY, "gzip"),
+            (SST_COLUMN_COMPRESSION_KEY, "host"),
+            (SST_COLUMN_COMPRESSION_KEY, "host:gzip"),
+            (SST_DICTIONARY_KEY, "yes"),
+            (SST_ROW_GROUP_SIZE_KEY, "0"),
+        ] {
+            let options = HashMap::from([(key.to_string(), value.to_string())]);
+            assert!(matches!(
+                parquet_options(TABLE_NAME, &options),
+                Err(error::Error::InvalidSstOption { option, .. }) if option == key
+            ));
+        }
+    }
+
+    #[test]
+    fn test_dedup_policy() {
+        assert_eq!(
+            DedupPolicy::LastWriteWins,
+            dedup_policy(TABLE_NAME, &HashMap::new()).unwrap()
+        );
+
+        let options = HashMap::from([(DEDUP_POLICY_KEY.to_string(), "keep_all".to_string())]);
+        assert_eq!(
+            DedupPolicy::KeepAll,
+            dedup_policy(TABLE_NAME, &options).unwrap()
+        );
+
+        let options = HashMap::from([(DEDUP_POLICY_KEY.to_string(), "none".to_string())]);
+        assert!(matches!(
+            dedup_policy(TABLE_NAME, &options),
+            Err(error::Error::InvalidDedupPolicy { policy, .. }) if policy == "none"
+        ));
+    }
+
     #[test]
     fn test_region_id() {
         assert_eq!(1, region_id(0, 1));
@@ -1047,6 +2543,77 @@ mod tests {
         assert_eq!(new_schema.version(), old_schema.version() + 1);
     }
 
+    #[tokio::test]
+    async fn test_alter_table_rename() {
+        let (engine, table_engine, table, object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let ctx = EngineContext::default();
+        let old_info = table.table_info();
+
+        let another_table = "another_table";
+        let request = CreateTableRequest {
+            id: 2,
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: another_table.to_string(),
+            schema: old_info.meta.schema.clone(),
+            create_if_not_exists: false,
+            desc: None,
+            primary_key_indices: Vec::default(),
+            table_options: HashMap::new(),
+            region_numbers: vec![0],
+        };
+        table_engine.create_table(&ctx, request).await.unwrap();
+
+        let new_rename_req = |new_table_name: &str| AlterTableRequest {
+            catalog_name: None,
+            schema_name: None,
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::RenameTable {
+                new_table_name: new_table_name.to_string(),
+            },
+        };
+        // Can't rename to an existing table.
+        let err = table_engine
+            .alter_table(&ctx, new_rename_req(another_table))
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:?}").contains("Table already exists"),
+            "Actual error: {err:?}"
+        );
+
+        let new_table_name = "new_table";
+        let table = table_engine
+            .alter_table(&ctx, new_rename_req(new_table_name))
+            .await
+            .unwrap();
+        let new_info = table.table_info();
+        assert_eq!(new_table_name, new_info.name);
+        assert_eq!(old_info.ident.version + 1, new_info.ident.version);
+        assert_eq!(old_info.meta, new_info.meta);
+        assert!(!table_engine.table_exists(&ctx, &TableReference::bare(TABLE_NAME)));
+        assert!(table_engine.table_exists(&ctx, &TableReference::bare(new_table_name)));
+
+        // The table opened by its old name is keyed by the new name.
+        let table_engine = MitoEngine::new(EngineConfig::default(), engine, object_store);
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            table_id: old_info.ident.table_id,
+            region_numbers: vec![0],
+        };
+        let reopened = table_engine
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(new_info, reopened.table_info());
+        assert!(!table_engine.table_exists(&ctx, &TableReference::bare(TABLE_NAME)));
+        assert!(table_engine.table_exists(&ctx, &TableReference::bare(new_table_name)));
+    }
+
     #[tokio::test]
     async fn test_drop_table() {
         common_telemetry::init_default_ut_logging();
@@ -1111,4 +2678,47 @@ mod tests {
         table_engine.create_table(&ctx, request).await.unwrap();
         assert!(table_engine.table_exists(&engine_ctx, &table_reference));
     }
+
+    #[tokio::test]
+    async fn test_close_table() {
+        let ctx = EngineContext::default();
+        let (engine, table_engine, table, _object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let table_info = table.table_info();
+        let table_reference = TableReference {
+            catalog: DEFAULT_CATALOG_NAME,
+            schema: DEFAULT_SCHEMA_NAME,
+            table: &table_info.name,
+        };
+        let close_req = || CloseTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_info.name.clone(),
+        };
+
+        assert!(table_engine.close_table(&ctx, close_req()).await.unwrap());
+        assert!(!table_engine.table_exists(&ctx, &table_reference));
+        let region_name = region_name(table_info.ident.table_id, 0);
+        assert!(engine
+            .get_region(&StorageEngineContext::default(), &region_name)
+            .unwrap()
+            .is_none());
+        assert!(!table_engine.close_table(&ctx, close_req()).await.unwrap());
+
+        // The closed table could be opened again.
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: table_info.name.clone(),
+            table_id: table_info.ident.table_id,
+            region_numbers: vec![0],
+        };
+        let reopened = table_engine
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(table.schema(), reopened.schema());
+        assert!(table_engine.table_exists(&ctx, &table_reference));
+    }
 }
@@ -34,6 +34,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to close region, region: {}, source: {}", region_name, source))]
+    CloseRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display(
         "Failed to build table meta for table: {}, source: {}",
         table_name,
@@ -177,6 +184,181 @@ pub enum Error {
         #[snafu(backtrace)]
         source: table::metadata::ConvertError,
     },
+
+    #[snafu(display(
+        "Failed to parse partition rule of table {}, source: {}",
+        table_name,
+        source
+    ))]
+    ParsePartitionRule {
+        table_name: String,
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid partition rule of table {}, reason: {}", table_name, reason))]
+    InvalidPartitionRule {
+        table_name: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid SST format {} of table {}", format, table_name))]
+    InvalidSstFormat {
+        table_name: String,
+        format: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid dedup policy {} of table {}", policy, table_name))]
+    InvalidDedupPolicy {
+        table_name: String,
+        policy: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Invalid SST option {} of table {}, reason: {}",
+        option,
+        table_name,
+        reason
+    ))]
+    InvalidSstOption {
+        table_name: String,
+        option: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Missing partition column {} in request to table {}",
+        column_name,
+        table_name
+    ))]
+    MissingPartitionColumn {
+        table_name: String,
+        column_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Operation {} is not supported by table {} with multiple regions",
+        operation,
+        table_name
+    ))]
+    UnsupportedMultiRegions {
+        operation: String,
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Invalid option {} of external table {}, reason: {}",
+        option,
+        table_name,
+        reason
+    ))]
+    InvalidExternalTableOption {
+        table_name: String,
+        option: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "External table {} is not supported, no object store is configured for its files",
+        table_name
+    ))]
+    ExternalTableNotSupported {
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to list external files in {}, source: {}", location, source))]
+    ListExternalFiles {
+        location: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to read CSV file {}, source: {}", path, source))]
+    ReadCsvFile {
+        path: String,
+        source: datatypes::arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to read parquet file {}, source: {}", path, source))]
+    ReadParquetFile {
+        path: String,
+        source: parquet::errors::ParquetError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Column {} not found in external file {}", column, path))]
+    ExternalColumnNotFound {
+        path: String,
+        column: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to convert data of external file {}, source: {}", path, source))]
+    ConvertExternalData {
+        path: String,
+        source: datatypes::arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to convert record batch of external file {}, source: {}",
+        path,
+        source
+    ))]
+    ConvertExternalRecordBatch {
+        path: String,
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
+    #[snafu(display("Failed to build schema, msg: {}, source: {}", msg, source))]
+    SchemaBuild {
+        msg: String,
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Region {} of table {} not found", region_number, table_name))]
+    RegionNotFound {
+        table_name: String,
+        region_number: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Region {} of table {} is fenced from writes, e.g. being migrated",
+        region_number,
+        table_name
+    ))]
+    RegionFenced {
+        table_name: String,
+        region_number: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to split region {}, source: {}", region_name, source))]
+    SplitRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to merge region {} and {}, source: {}", left, right, source))]
+    MergeRegions {
+        left: String,
+        right: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
 }
 
 impl From<Error> for table::error::Error {
@@ -192,7 +374,11 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            CreateRegion { source, .. } | OpenRegion { source, .. } => source.status_code(),
+            CreateRegion { source, .. }
+            | OpenRegion { source, .. }
+            | CloseRegion { source, .. }
+            | SplitRegion { source, .. }
+            | MergeRegions { source, .. } => source.status_code(),
 
             AlterTable { source, .. } => source.status_code(),
 
@@ -202,15 +388,39 @@ impl ErrorExt for Error {
             | BuildTableMeta { .. }
             | BuildTableInfo { .. }
             | BuildRegionDescriptor { .. }
-            | TableExists { .. }
             | ProjectedColumnNotFound { .. }
             | InvalidPrimaryKey { .. }
             | MissingTimestampIndex { .. }
-            | TableNotFound { .. } => StatusCode::InvalidArguments,
+            | TableNotFound { .. }
+            | InvalidPartitionRule { .. }
+            | InvalidSstFormat { .. }
+            | InvalidDedupPolicy { .. }
+            | InvalidSstOption { .. }
+            | ParsePartitionRule { .. }
+            | MissingPartitionColumn { .. }
+            | InvalidExternalTableOption { .. }
+            | ExternalColumnNotFound { .. }
+            | ReadCsvFile { .. }
+            | ConvertExternalData { .. }
+            | RegionNotFound { .. } => StatusCode::InvalidArguments,
+
+            TableExists { .. } => StatusCode::TableAlreadyExists,
 
             TableInfoNotFound { .. } | ConvertRaw { .. } => StatusCode::Unexpected,
 
-            ScanTableManifest { .. } | UpdateTableManifest { .. } => StatusCode::StorageUnavailable,
+            UnsupportedMultiRegions { .. } | ExternalTableNotSupported { .. } => {
+                StatusCode::Unsupported
+            }
+
+            ConvertExternalRecordBatch { source, .. } => source.status_code(),
+
+            SchemaBuild { source, .. } => source.status_code(),
+
+            ScanTableManifest { .. }
+            | UpdateTableManifest { .. }
+            | ListExternalFiles { .. }
+            | ReadParquetFile { .. }
+            | RegionFenced { .. } => StatusCode::StorageUnavailable,
         }
     }
 
@@ -0,0 +1,730 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! External tables, read-only tables over CSV or Parquet files in the object store of
+//! files, which is separated from the object store of the table data.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{BufRead, Read};
+use std::sync::Arc;
+
+use async_compat::CompatExt;
+use async_stream::try_stream;
+use async_trait::async_trait;
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use common_telemetry::logging;
+use datatypes::arrow::array::ArrayRef;
+use datatypes::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use datatypes::arrow::error::ArrowError;
+use datatypes::arrow::record_batch::RecordBatch as DfRecordBatch;
+use datatypes::arrow::{compute, csv};
+use datatypes::schema::{Schema, SchemaRef};
+use futures::{StreamExt, TryStreamExt};
+use object_store::ObjectStore;
+use parquet::arrow::{ParquetRecordBatchStreamBuilder, ProjectionMask};
+use snafu::{OptionExt, ResultExt};
+use store_api::manifest::Manifest;
+use table::error::{Result as TableResult, UnsupportedSnafu};
+use table::metadata::{RawTableInfo, TableInfo, TableInfoRef, TableType};
+use table::requests::{AlterTableRequest, InsertRequest};
+use table::table::scan::SimpleTableScan;
+use table::Table;
+use tokio::io::BufReader;
+use tokio::sync::mpsc;
+use tokio_util::io::SyncIoBridge;
+
+use crate::error::{
+    self, ConvertExternalDataSnafu, ExternalColumnNotFoundSnafu, InvalidExternalTableOptionSnafu,
+    ListExternalFilesSnafu, ReadCsvFileSnafu, ReadParquetFileSnafu, Result,
+    UpdateTableManifestSnafu,
+};
+use crate::manifest::action::{TableChange, TableMetaAction, TableMetaActionList};
+use crate::manifest::TableManifest;
+use crate::table::{table_manifest_dir, ChunkStream};
+
+/// Table option of the format of the files of an external table, `csv` or `parquet`.
+pub const EXTERNAL_FORMAT_KEY: &str = "format";
+/// Table option of the path of the files of an external table in the object store of
+/// files, a path ending with `/` refers to all files under the directory.
+pub const EXTERNAL_LOCATION_KEY: &str = "location";
+/// Max number of rows in a record batch read from CSV files.
+const CSV_BATCH_SIZE: usize = 8192;
+
+/// Format of the files of external tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// CSV files with a header row, columns are matched by names in the header.
+    Csv,
+    Parquet,
+}
+
+impl FileFormat {
+    pub fn from_name(name: &str) -> Option<FileFormat> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(FileFormat::Csv),
+            "parquet" => Some(FileFormat::Parquet),
+            _ => None,
+        }
+    }
+
+    /// Infers the format from the extension of the `path`.
+    pub fn from_path(path: &str) -> Option<FileFormat> {
+        let (_, extension) = path.rsplit_once('.')?;
+        FileFormat::from_name(extension)
+    }
+}
+
+pub fn is_external_option(key: &str) -> bool {
+    key == EXTERNAL_FORMAT_KEY || key == EXTERNAL_LOCATION_KEY
+}
+
+/// Returns whether the table with `options` is an external table.
+pub fn is_external_table(options: &HashMap<String, String>) -> bool {
+    options.contains_key(EXTERNAL_LOCATION_KEY)
+}
+
+/// Checks the `location` of files is a relative path that stays inside the root of the
+/// object store of files, returns the reason if it isn't.
+pub fn check_location(location: &str) -> std::result::Result<(), &'static str> {
+    if location.is_empty() {
+        return Err("location is required");
+    }
+    if location.starts_with('/') || location.contains('\\') {
+        return Err("location must be a relative path separated by '/'");
+    }
+    let path = location.strip_suffix('/').unwrap_or(location);
+    if path
+        .split('/')
+        .any(|component| component.is_empty() || component == "." || component == "..")
+    {
+        return Err("location must not contain empty, '.' or '..' components");
+    }
+    Ok(())
+}
+
+/// Returns the format and location of the external table with `options`.
+pub(crate) fn external_options(
+    table_name: &str,
+    options: &HashMap<String, String>,
+) -> Result<(FileFormat, String)> {
+    let location = options
+        .get(EXTERNAL_LOCATION_KEY)
+        .map(String::as_str)
+        .unwrap_or_default();
+    check_location(location).map_err(|reason| {
+        InvalidExternalTableOptionSnafu {
+            table_name,
+            option: EXTERNAL_LOCATION_KEY,
+            reason,
+        }
+        .build()
+    })?;
+    let format = match options.get(EXTERNAL_FORMAT_KEY) {
+        Some(name) => FileFormat::from_name(name),
+        None => FileFormat::from_path(location),
+    }
+    .with_context(|| InvalidExternalTableOptionSnafu {
+        table_name,
+        option: EXTERNAL_FORMAT_KEY,
+        reason: format!(
+            "expect csv or parquet, found: {:?}",
+            options.get(EXTERNAL_FORMAT_KEY)
+        ),
+    })?;
+    Ok((format, location.to_string()))
+}
+
+/// A read-only [Table] whose rows are read from files in the object store of files on
+/// each scan.
+pub struct ExternalTable {
+    table_info: TableInfoRef,
+    format: FileFormat,
+    location: String,
+    file_store: ObjectStore,
+}
+
+impl ExternalTable {
+    /// Creates the table and persists its info into the manifest under `table_dir` of
+    /// the `object_store` of the table data.
+    pub(crate) async fn create(
+        table_dir: &str,
+        table_info: TableInfo,
+        object_store: ObjectStore,
+        file_store: ObjectStore,
+    ) -> Result<ExternalTable> {
+        let table_name = table_info.name.clone();
+        let table = ExternalTable::open(table_info, file_store)?;
+
+        let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
+        let _ = manifest
+            .update(TableMetaActionList::with_action(TableMetaAction::Change(
+                Box::new(TableChange {
+                    table_info: RawTableInfo::from(table.table_info.as_ref().clone()),
+                }),
+            )))
+            .await
+            .context(UpdateTableManifestSnafu {
+                table_name: &table_name,
+            })?;
+        Ok(table)
+    }
+
+    /// Opens the table with the recovered `table_info`, whose files are in `file_store`.
+    pub(crate) fn open(table_info: TableInfo, file_store: ObjectStore) -> Result<ExternalTable> {
+        let (format, location) = external_options(&table_info.name, &table_info.meta.options)?;
+        Ok(ExternalTable {
+            table_info: Arc::new(table_info),
+            format,
+            location,
+            file_store,
+        })
+    }
+}
+
+#[async_trait]
+impl Table for ExternalTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table_info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.table_info.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::External
+    }
+
+    async fn insert(&self, _request: InsertRequest) -> TableResult<usize> {
+        UnsupportedSnafu {
+            operation: "insert",
+            table_name: &self.table_info.name,
+        }
+        .fail()
+    }
+
+    async fn alter(&self, _request: AlterTableRequest) -> TableResult<()> {
+        UnsupportedSnafu {
+            operation: "alter",
+            table_name: &self.table_info.name,
+        }
+        .fail()
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        let schema = self.schema();
+        let schema = match projection {
+            Some(projection) => {
+                let column_schemas = projection
+                    .iter()
+                    .map(|index| schema.column_schemas()[*index].clone())
+                    .collect();
+                Arc::new(
+                    Schema::try_new(column_schemas).context(error::SchemaBuildSnafu {
+                        msg: "failed to project the schema of external table",
+                    })?,
+                )
+            }
+            None => schema,
+        };
+        let stream = read_files(
+            self.file_store.clone(),
+            self.location.clone(),
+            self.format,
+            schema,
+        );
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+}
+
+/// Reads the files at `location` as record batches of `schema`, columns of the files
+/// are matched by names and cast to the types in `schema`.
+///
+/// A `location` ending with `/` is a directory, the files under the directory are read
+/// in the order of their names.
+pub fn read_files(
+    object_store: ObjectStore,
+    location: String,
+    format: FileFormat,
+    schema: SchemaRef,
+) -> SendableRecordBatchStream {
+    let stream_schema = schema.clone();
+    let stream = try_stream! {
+        let paths = list_files(&object_store, &location)
+            .await
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+        for path in paths {
+            logging::debug!("Reading external file {}", path);
+            let mut batches = match format {
+                FileFormat::Csv => read_csv(&object_store, &path, &schema).await,
+                FileFormat::Parquet => read_parquet(&object_store, &path, &schema).await,
+            }
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu)?;
+            while let Some(batch) = batches.next().await {
+                yield batch.map_err(BoxedError::new).context(ExternalSnafu)?;
+            }
+        }
+    };
+
+    Box::pin(ChunkStream {
+        schema: stream_schema,
+        stream: Box::pin(stream),
+    })
+}
+
+async fn list_files(object_store: &ObjectStore, location: &str) -> Result<Vec<String>> {
+    if !location.ends_with('/') {
+        return Ok(vec![location.to_string()]);
+    }
+
+    let mut paths = object_store
+        .object(location)
+        .list()
+        .await
+        .context(ListExternalFilesSnafu { location })?
+        .try_filter_map(|object| async move {
+            let path = object.path();
+            Ok((!path.ends_with('/')).then(|| path.to_string()))
+        })
+        .try_collect::<Vec<_>>()
+        .await
+        .context(ListExternalFilesSnafu { location })?;
+    paths.sort_unstable();
+    Ok(paths)
+}
+
+type BatchStream = futures::stream::BoxStream<'static, Result<RecordBatch>>;
+
+/// Reads the CSV file in a blocking task, as the CSV reader of arrow is synchronous. The
+/// file is streamed from the object store, only a few batches are buffered at a time.
+async fn read_csv(
+    object_store: &ObjectStore,
+    path: &str,
+    schema: &SchemaRef,
+) -> Result<BatchStream> {
+    let reader = SyncIoBridge::new(object_store.object(path).seekable_reader(..).compat());
+    let path = path.to_string();
+    let schema = schema.clone();
+    let (tx, mut rx) = mpsc::channel(1);
+    let _handle = tokio::task::spawn_blocking(move || {
+        let reader = match build_csv_reader(reader, &path, &schema) {
+            Ok(reader) => reader,
+            Err(e) => {
+                let _ = tx.blocking_send(Err(e));
+                return;
+            }
+        };
+        for batch in reader {
+            let batch = batch
+                .context(ReadCsvFileSnafu { path: &path })
+                .and_then(|batch| to_table_batch(&path, &schema, batch));
+            let is_err = batch.is_err();
+            // Stops reading if the stream is dropped or fails.
+            if tx.blocking_send(batch).is_err() || is_err {
+                return;
+            }
+        }
+    });
+    Ok(futures::stream::poll_fn(move |cx| rx.poll_recv(cx)).boxed())
+}
+
+/// Builds the CSV reader of the columns in `schema` from the `reader` of the file.
+fn build_csv_reader<R: Read>(
+    reader: R,
+    path: &str,
+    schema: &SchemaRef,
+) -> Result<csv::Reader<impl Read>> {
+    // Only reads the header to get the names of the columns, the header is put back
+    // before the rest of the file for the CSV reader.
+    let mut reader = std::io::BufReader::new(reader);
+    let mut header_line = Vec::new();
+    let _ = reader
+        .read_until(b'\n', &mut header_line)
+        .map_err(ArrowError::from)
+        .context(ReadCsvFileSnafu { path })?;
+    let (header, _) = csv::reader::infer_reader_schema(&header_line[..], b',', Some(0), true)
+        .context(ReadCsvFileSnafu { path })?;
+    let fields = header
+        .fields()
+        .iter()
+        .map(|field| {
+            let data_type = schema
+                .column_schema_by_name(field.name())
+                .map(|column| column.data_type.as_arrow_type())
+                .unwrap_or(DataType::Utf8);
+            Field::new(field.name(), data_type, true)
+        })
+        .collect::<Vec<_>>();
+    let projection = schema
+        .column_schemas()
+        .iter()
+        .map(|column| {
+            header
+                .index_of(&column.name)
+                .ok()
+                .context(ExternalColumnNotFoundSnafu {
+                    path,
+                    column: &column.name,
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    csv::ReaderBuilder::new()
+        .with_schema(Arc::new(ArrowSchema::new(fields)))
+        .has_header(true)
+        .with_batch_size(CSV_BATCH_SIZE)
+        .with_projection(projection)
+        .build(std::io::Cursor::new(header_line).chain(reader))
+        .context(ReadCsvFileSnafu { path })
+}
+
+async fn read_parquet(
+    object_store: &ObjectStore,
+    path: &str,
+    schema: &SchemaRef,
+) -> Result<BatchStream> {
+    let reader = object_store.object(path).seekable_reader(..).compat();
+    let builder = ParquetRecordBatchStreamBuilder::new(BufReader::new(reader))
+        .await
+        .context(ReadParquetFileSnafu { path })?;
+
+    let file_schema = builder.schema().clone();
+    let indices = schema
+        .column_schemas()
+        .iter()
+        .map(|column| {
+            file_schema
+                .index_of(&column.name)
+                .ok()
+                .context(ExternalColumnNotFoundSnafu {
+                    path,
+                    column: &column.name,
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let projection =
+        ProjectionMask::roots(builder.metadata().file_metadata().schema_descr(), indices);
+    let stream = builder
+        .with_projection(projection)
+        .build()
+        .context(ReadParquetFileSnafu { path })?;
+
+    let path = path.to_string();
+    let schema = schema.clone();
+    Ok(stream
+        .map(move |batch| {
+            let batch = batch.context(ReadParquetFileSnafu { path: &path })?;
+            to_table_batch(&path, &schema, batch)
+        })
+        .boxed())
+}
+
+/// Converts the `batch` read from the file to a record batch of `schema`.
+fn to_table_batch(path: &str, schema: &SchemaRef, batch: DfRecordBatch) -> Result<RecordBatch> {
+    let columns = schema
+        .column_schemas()
+        .iter()
+        .map(|column| {
+            let array =
+                batch
+                    .column_by_name(&column.name)
+                    .context(ExternalColumnNotFoundSnafu {
+                        path,
+                        column: &column.name,
+                    })?;
+            let data_type = column.data_type.as_arrow_type();
+            if *array.data_type() == data_type {
+                return Ok(array.clone());
+            }
+            compute::cast(array, &data_type).context(ConvertExternalDataSnafu { path })
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+
+    let batch = DfRecordBatch::try_new(schema.arrow_schema().clone(), columns)
+        .context(ConvertExternalDataSnafu { path })?;
+    RecordBatch::try_from_df_record_batch(schema.clone(), batch)
+        .context(error::ConvertExternalRecordBatchSnafu { path })
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::physical_plan::SessionContext;
+    use common_recordbatch::util;
+    use datatypes::arrow::array::{Float32Array, Float64Array, Int64Array, StringArray};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector, VectorRef};
+    use log_store::fs::noop::NoopLogStore;
+    use parquet::arrow::ArrowWriter;
+    use storage::config::EngineConfig as StorageEngineConfig;
+    use storage::EngineImpl;
+    use table::engine::{EngineContext, TableEngine};
+    use table::requests::OpenTableRequest;
+    use table::TableRef;
+
+    use super::*;
+    use crate::config::EngineConfig;
+    use crate::engine::MitoEngine;
+    use crate::table::test_util::{self, new_create_request, schema_for_test};
+
+    const CSV_CONTENT: &str = "ts,host,extra,memory,cpu
+1970-01-01T00:00:01Z,host1,a,1024,0.5
+1970-01-01T00:00:02Z,host2,b,2048,
+";
+
+    fn new_engine(
+        object_store: ObjectStore,
+        file_store: ObjectStore,
+    ) -> MitoEngine<EngineImpl<NoopLogStore>> {
+        MitoEngine::with_file_store(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+            file_store,
+        )
+    }
+
+    fn parquet_content() -> Vec<u8> {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("host", DataType::Utf8, false),
+            Field::new("cpu", DataType::Float32, true),
+            Field::new("memory", DataType::Float64, true),
+            Field::new("ts", DataType::Int64, false),
+        ]));
+        let batch = DfRecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["host3"])),
+                Arc::new(Float32Array::from(vec![1.5])),
+                Arc::new(Float64Array::from(vec![4096.0])),
+                Arc::new(Int64Array::from(vec![3000])),
+            ],
+        )
+        .unwrap();
+
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        buf
+    }
+
+    async fn create_external_table(
+        engine: &MitoEngine<EngineImpl<NoopLogStore>>,
+        options: &[(&str, &str)],
+    ) -> table::Result<TableRef> {
+        let mut request = new_create_request(Arc::new(schema_for_test()));
+        request.table_options = options
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        engine
+            .create_table(&EngineContext::default(), request)
+            .await
+    }
+
+    async fn scan_all(table: &TableRef, projection: Option<&Vec<usize>>) -> Vec<RecordBatch> {
+        let session_ctx = SessionContext::new();
+        let plan = table.scan(projection, &[], None).await.unwrap();
+        let stream = plan.execute(0, session_ctx.task_ctx()).unwrap();
+        util::collect(stream).await.unwrap()
+    }
+
+    #[test]
+    fn test_file_format() {
+        assert_eq!(Some(FileFormat::Csv), FileFormat::from_name("CSV"));
+        assert_eq!(Some(FileFormat::Parquet), FileFormat::from_name("parquet"));
+        assert_eq!(None, FileFormat::from_name("json"));
+        assert_eq!(Some(FileFormat::Csv), FileFormat::from_path("data/a.csv"));
+        assert_eq!(
+            Some(FileFormat::Parquet),
+            FileFormat::from_path("data/a.parquet")
+        );
+        assert_eq!(None, FileFormat::from_path("data/"));
+    }
+
+    #[tokio::test]
+    async fn test_external_csv_table() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_external_csv_table").await;
+        let (_file_dir, file_store) =
+            test_util::new_test_object_store("test_external_csv_table_files").await;
+        file_store
+            .object("data/cpu.csv")
+            .write(CSV_CONTENT)
+            .await
+            .unwrap();
+        let engine = new_engine(object_store, file_store);
+
+        let table = create_external_table(&engine, &[(EXTERNAL_LOCATION_KEY, "data/cpu.csv")])
+            .await
+            .unwrap();
+        assert!(table.as_any().downcast_ref::<ExternalTable>().is_some());
+
+        let batches = scan_all(&table, Some(&vec![0, 1, 3])).await;
+        assert_eq!(1, batches.len());
+        let batch = &batches[0];
+        assert_eq!(
+            Arc::new(StringVector::from(vec!["host1", "host2"])) as VectorRef,
+            *batch.column(0)
+        );
+        assert_eq!(
+            Arc::new(Float64Vector::from(vec![Some(0.5), None])) as VectorRef,
+            *batch.column(1)
+        );
+        assert_eq!(
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1000, 2000])) as VectorRef,
+            *batch.column(2)
+        );
+
+        let err = table
+            .insert(test_util::new_insert_request(
+                test_util::TABLE_NAME.to_string(),
+                HashMap::new(),
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_external_parquet_dir_table() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_external_parquet_dir_table").await;
+        let (_file_dir, file_store) =
+            test_util::new_test_object_store("test_external_parquet_dir_table_files").await;
+        let parquet = parquet_content();
+        file_store
+            .object("data/b.parquet")
+            .write(parquet.clone())
+            .await
+            .unwrap();
+        file_store
+            .object("data/a.parquet")
+            .write(parquet)
+            .await
+            .unwrap();
+        let engine = new_engine(object_store.clone(), file_store.clone());
+
+        let table = create_external_table(
+            &engine,
+            &[
+                (EXTERNAL_LOCATION_KEY, "data/"),
+                (EXTERNAL_FORMAT_KEY, "parquet"),
+            ],
+        )
+        .await
+        .unwrap();
+        let batches = scan_all(&table, None).await;
+        assert_eq!(2, batches.len());
+        assert_eq!(4, batches[0].num_columns());
+        assert_eq!(
+            Arc::new(Float64Vector::from_vec(vec![1.5])) as VectorRef,
+            *batches[0].column(1)
+        );
+
+        // Opens the table by another engine.
+        let engine = new_engine(object_store, file_store);
+        let reopened = engine
+            .open_table(
+                &EngineContext::default(),
+                OpenTableRequest {
+                    catalog_name: "greptime".to_string(),
+                    schema_name: "public".to_string(),
+                    table_name: test_util::TABLE_NAME.to_string(),
+                    table_id: 1,
+                    region_numbers: vec![],
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(table.schema(), reopened.schema());
+        assert_eq!(2, scan_all(&reopened, None).await.len());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_external_table() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_invalid_external_table").await;
+        let engine = new_engine(object_store.clone(), object_store.clone());
+
+        let err = create_external_table(&engine, &[(EXTERNAL_LOCATION_KEY, "data/")])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("expect csv or parquet"), "{err}");
+
+        let err = create_external_table(
+            &engine,
+            &[
+                (EXTERNAL_LOCATION_KEY, "data/cpu.json"),
+                (EXTERNAL_FORMAT_KEY, "json"),
+            ],
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("expect csv or parquet"), "{err}");
+
+        for location in ["/etc/passwd", "../data/cpu.csv", "data/../../cpu.csv"] {
+            let err = create_external_table(&engine, &[(EXTERNAL_LOCATION_KEY, location)])
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("Invalid option location"), "{err}");
+        }
+
+        // External tables are not supported without the object store of files.
+        let engine = MitoEngine::new(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+        );
+        let err = create_external_table(&engine, &[(EXTERNAL_LOCATION_KEY, "data/cpu.csv")])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported"), "{err}");
+    }
+
+    #[test]
+    fn test_check_location() {
+        assert!(check_location("data/cpu.csv").is_ok());
+        assert!(check_location("data/").is_ok());
+        assert!(check_location("").is_err());
+        assert!(check_location("/data/cpu.csv").is_err());
+        assert!(check_location("data\\cpu.csv").is_err());
+        assert!(check_location("data//cpu.csv").is_err());
+        assert!(check_location("./data/").is_err());
+        assert!(check_location("data/../../cpu.csv").is_err());
+    }
+}
@@ -15,5 +15,7 @@
 pub mod config;
 pub mod engine;
 pub mod error;
+pub mod external;
 mod manifest;
+pub mod partition;
 pub mod table;
@@ -0,0 +1,538 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Partition rule of a table with multiple regions.
+
+use std::collections::{BTreeSet, HashMap};
+use std::mem;
+
+use common_query::logical_plan::Expr;
+use datafusion::logical_expr::{BinaryExpr, Expr as DfExpr, Operator};
+use datatypes::data_type::DataType;
+use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use datatypes::vectors::VectorRef;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::RegionNumber;
+
+use crate::error::{
+    InvalidPartitionRuleSnafu, MissingPartitionColumnSnafu, ParsePartitionRuleSnafu, Result,
+};
+
+/// Key of the table option holding the partition rule in json.
+pub const PARTITION_RULE_KEY: &str = "partition_rule";
+
+/// Loads the partition rule of the table from its `options` and validates the rule,
+/// the rule is required if the table has more than one region.
+pub(crate) fn load_partition_rule(
+    table_name: &str,
+    schema: &SchemaRef,
+    options: &HashMap<String, String>,
+    region_numbers: &[RegionNumber],
+) -> Result<Option<RegionPartitionRule>> {
+    let rule = RegionPartitionRule::from_options(table_name, options)?;
+    match &rule {
+        Some(rule) => rule.validate(table_name, schema, region_numbers)?,
+        None => ensure!(
+            region_numbers.len() == 1,
+            InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!(
+                    "partition rule is required by table with {} regions",
+                    region_numbers.len()
+                ),
+            }
+        ),
+    }
+    Ok(rule)
+}
+
+/// Range partition rule that routes rows to regions by the value of the partition
+/// column, like
+///
+/// ```sql
+/// PARTITION BY RANGE (column_name) (
+///     PARTITION r0 VALUES LESS THAN (10),
+///     PARTITION r1 VALUES LESS THAN (20),
+///     PARTITION r2 VALUES LESS THAN (MAXVALUE),
+/// )
+/// ```
+///
+/// whose `bounds` are `[10, 20]` and `regions` are `[0, 1, 2]`. Rows whose partition
+/// value is null are routed to the first region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionPartitionRule {
+    column_name: String,
+    /// Exclusive upper bounds of the regions, except the last region which is unbounded,
+    /// so the length of `bounds` is one less than `regions`.
+    bounds: Vec<Value>,
+    regions: Vec<RegionNumber>,
+}
+
+impl RegionPartitionRule {
+    pub fn new(
+        column_name: impl Into<String>,
+        bounds: Vec<Value>,
+        regions: Vec<RegionNumber>,
+    ) -> Self {
+        Self {
+            column_name: column_name.into(),
+            bounds,
+            regions,
+        }
+    }
+
+    /// Parses the partition rule from the table `options`, returns `None` if the
+    /// table has no partition rule.
+    pub fn from_options(
+        table_name: &str,
+        options: &HashMap<String, String>,
+    ) -> Result<Option<RegionPartitionRule>> {
+        options
+            .get(PARTITION_RULE_KEY)
+            .map(|rule| serde_json::from_str(rule).context(ParsePartitionRuleSnafu { table_name }))
+            .transpose()
+    }
+
+    /// Encodes the partition rule into the value of the table option [PARTITION_RULE_KEY].
+    pub fn to_option(&self) -> String {
+        // Safety: The rule only contains strings and values, which are always serializable.
+        serde_json::to_string(self).unwrap()
+    }
+
+    #[inline]
+    pub fn column_name(&self) -> &str {
+        &self.column_name
+    }
+
+    #[inline]
+    pub fn regions(&self) -> &[RegionNumber] {
+        &self.regions
+    }
+
+    /// Checks whether the rule is valid for the table with `schema` and `region_numbers`.
+    pub fn validate(
+        &self,
+        table_name: &str,
+        schema: &SchemaRef,
+        region_numbers: &[RegionNumber],
+    ) -> Result<()> {
+        let column_schema =
+            schema
+                .column_schema_by_name(&self.column_name)
+                .context(InvalidPartitionRuleSnafu {
+                    table_name,
+                    reason: format!("partition column {} not found", self.column_name),
+                })?;
+        ensure!(
+            self.bounds.len() + 1 == self.regions.len(),
+            InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!(
+                    "{} bounds can't partition {} regions",
+                    self.bounds.len(),
+                    self.regions.len()
+                ),
+            }
+        );
+        ensure!(
+            self.bounds
+                .iter()
+                .all(|bound| bound.data_type() == column_schema.data_type),
+            InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!("type of bounds must be {}", column_schema.data_type.name()),
+            }
+        );
+        ensure!(
+            self.bounds.windows(2).all(|w| w[0] < w[1]),
+            InvalidPartitionRuleSnafu {
+                table_name,
+                reason: "bounds must be strictly increasing",
+            }
+        );
+        let rule_regions: BTreeSet<_> = self.regions.iter().collect();
+        let table_regions: BTreeSet<_> = region_numbers.iter().collect();
+        ensure!(
+            rule_regions.len() == self.regions.len() && rule_regions == table_regions,
+            InvalidPartitionRuleSnafu {
+                table_name,
+                reason: format!(
+                    "regions {:?} don't match regions of the table {:?}",
+                    self.regions, region_numbers
+                ),
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Returns the rule after splitting `region` at `bound` into regions `left` and
+    /// `right`, or `None` if `bound` is not inside the range of the region. The `bound`
+    /// must have the type of the partition column.
+    pub fn split_region(
+        &self,
+        region: RegionNumber,
+        bound: Value,
+        left: RegionNumber,
+        right: RegionNumber,
+    ) -> Option<RegionPartitionRule> {
+        let index = self.regions.iter().position(|r| *r == region)?;
+        let above_lower = index == 0 || self.bounds[index - 1] < bound;
+        let below_upper = self
+            .bounds
+            .get(index)
+            .map(|upper| bound < *upper)
+            .unwrap_or(true);
+        if bound.is_null() || !above_lower || !below_upper {
+            return None;
+        }
+
+        let mut rule = self.clone();
+        rule.bounds.insert(index, bound);
+        let _ = rule.regions.splice(index..=index, [left, right]);
+        Some(rule)
+    }
+
+    /// Returns the rule after merging region `left` and its next region `right` into
+    /// region `merged`, or `None` if `right` doesn't follow `left`.
+    pub fn merge_regions(
+        &self,
+        left: RegionNumber,
+        right: RegionNumber,
+        merged: RegionNumber,
+    ) -> Option<RegionPartitionRule> {
+        let index = self.regions.iter().position(|r| *r == left)?;
+        if self.regions.get(index + 1) != Some(&right) {
+            return None;
+        }
+
+        let mut rule = self.clone();
+        let _ = rule.bounds.remove(index);
+        let _ = rule.regions.splice(index..=index + 1, [merged]);
+        Some(rule)
+    }
+
+    /// Returns the region the row with partition `value` belongs to.
+    pub fn find_region(&self, value: &Value) -> RegionNumber {
+        if value.is_null() {
+            return self.regions[0];
+        }
+        let index = self.bounds.partition_point(|bound| bound <= value);
+        self.regions[index]
+    }
+
+    /// Splits columns of rows to insert by the regions they belong to.
+    pub fn split(
+        &self,
+        table_name: &str,
+        columns_values: HashMap<String, VectorRef>,
+    ) -> Result<HashMap<RegionNumber, HashMap<String, VectorRef>>> {
+        let partition_column =
+            columns_values
+                .get(&self.column_name)
+                .context(MissingPartitionColumnSnafu {
+                    table_name,
+                    column_name: &self.column_name,
+                })?;
+
+        let mut region_rows: HashMap<RegionNumber, Vec<usize>> = HashMap::new();
+        for row in 0..partition_column.len() {
+            let region = self.find_region(&partition_column.get(row));
+            region_rows.entry(region).or_default().push(row);
+        }
+        if region_rows.len() == 1 {
+            // Fast path, all rows belong to the same region.
+            let region = region_rows.into_keys().next().unwrap();
+            return Ok(HashMap::from([(region, columns_values)]));
+        }
+
+        Ok(region_rows
+            .into_iter()
+            .map(|(region, rows)| {
+                let columns = columns_values
+                    .iter()
+                    .map(|(name, vector)| {
+                        let mut builder = vector.data_type().create_mutable_vector(rows.len());
+                        for row in &rows {
+                            // Safety: The builder is created from the type of the vector.
+                            builder.push_value_ref(vector.get_ref(*row)).unwrap();
+                        }
+                        (name.clone(), builder.to_vector())
+                    })
+                    .collect();
+                (region, columns)
+            })
+            .collect())
+    }
+
+    /// Returns regions that may contain rows matching all `filters`, the result is
+    /// in the same order as the regions of the rule.
+    pub fn find_regions_by_filters(&self, filters: &[Expr]) -> Vec<RegionNumber> {
+        let mut regions: BTreeSet<_> = self.regions.iter().copied().collect();
+        for filter in filters {
+            let matched = self.find_regions_by_expr(filter.df_expr());
+            regions.retain(|region| matched.contains(region));
+        }
+
+        self.regions
+            .iter()
+            .copied()
+            .filter(|region| regions.contains(region))
+            .collect()
+    }
+
+    fn find_regions_by_expr(&self, expr: &DfExpr) -> BTreeSet<RegionNumber> {
+        if let DfExpr::BinaryExpr(BinaryExpr { left, op, right }) = expr {
+            match op {
+                Operator::And => {
+                    let left = self.find_regions_by_expr(left);
+                    let right = self.find_regions_by_expr(right);
+                    return left.intersection(&right).copied().collect();
+                }
+                Operator::Or => {
+                    let left = self.find_regions_by_expr(left);
+                    let right = self.find_regions_by_expr(right);
+                    return left.union(&right).copied().collect();
+                }
+                _ => {
+                    let column_op_value = match (left.as_ref(), right.as_ref()) {
+                        (DfExpr::Column(c), DfExpr::Literal(v)) => Some((c, *op, v)),
+                        (DfExpr::Literal(v), DfExpr::Column(c)) => {
+                            reverse_operator(*op).map(|op| (c, op, v))
+                        }
+                        _ => None,
+                    };
+                    if let Some((column, op, scalar)) = column_op_value {
+                        if column.name == self.column_name {
+                            if let Ok(value) = Value::try_from(scalar.clone()) {
+                                if let Some(regions) = self.find_regions_by_value(op, &value) {
+                                    return regions.iter().copied().collect();
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Scans all regions for filters not able to prune regions.
+        self.regions.iter().copied().collect()
+    }
+
+    /// Returns regions that may contain values matching `column op value`, or `None`
+    /// if the regions can't be pruned by the expr.
+    fn find_regions_by_value(&self, op: Operator, value: &Value) -> Option<&[RegionNumber]> {
+        // Literals of other types are compared after casting, so we can't prune regions
+        // by them.
+        if value.is_null()
+            || self
+                .bounds
+                .first()
+                .map(|bound| mem::discriminant(bound) != mem::discriminant(value))
+                .unwrap_or(true)
+        {
+            return None;
+        }
+
+        let regions = match self.bounds.binary_search(value) {
+            Ok(i) => match op {
+                Operator::Lt => &self.regions[..=i],
+                Operator::LtEq => &self.regions[..=(i + 1)],
+                Operator::Eq => &self.regions[(i + 1)..=(i + 1)],
+                Operator::Gt | Operator::GtEq => &self.regions[(i + 1)..],
+                _ => return None,
+            },
+            Err(i) => match op {
+                Operator::Lt | Operator::LtEq => &self.regions[..=i],
+                Operator::Eq => &self.regions[i..=i],
+                Operator::Gt | Operator::GtEq => &self.regions[i..],
+                _ => return None,
+            },
+        };
+        Some(regions)
+    }
+}
+
+/// Returns the operator after swapping the operands, or `None` if the operator is
+/// not a comparison.
+fn reverse_operator(op: Operator) -> Option<Operator> {
+    match op {
+        Operator::Lt => Some(Operator::Gt),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::LtEq => Some(Operator::GtEq),
+        Operator::GtEq => Some(Operator::LtEq),
+        Operator::Eq => Some(Operator::Eq),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::logical_expr::{col, lit};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int32Vector, StringVector};
+
+    use super::*;
+
+    fn new_rule() -> RegionPartitionRule {
+        RegionPartitionRule::new("n", vec![10.into(), 20.into()], vec![0, 1, 2])
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("n", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+        ]));
+        let rule = new_rule();
+        rule.validate("demo", &schema, &[2, 1, 0]).unwrap();
+        assert!(rule.validate("demo", &schema, &[0, 1]).is_err());
+
+        let rule = RegionPartitionRule::new("host", vec![10.into(), 20.into()], vec![0, 1, 2]);
+        assert!(rule.validate("demo", &schema, &[0, 1, 2]).is_err());
+        let rule = RegionPartitionRule::new("n", vec![20.into(), 10.into()], vec![0, 1, 2]);
+        assert!(rule.validate("demo", &schema, &[0, 1, 2]).is_err());
+        let rule = RegionPartitionRule::new("n", vec![10.into()], vec![0, 1, 2]);
+        assert!(rule.validate("demo", &schema, &[0, 1, 2]).is_err());
+        let rule = RegionPartitionRule::new("unknown", vec![10.into()], vec![0, 1]);
+        assert!(rule.validate("demo", &schema, &[0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_rule_options() {
+        let rule = new_rule();
+        let options = HashMap::from([(PARTITION_RULE_KEY.to_string(), rule.to_option())]);
+        assert_eq!(
+            Some(rule),
+            RegionPartitionRule::from_options("demo", &options).unwrap()
+        );
+        assert_eq!(
+            None,
+            RegionPartitionRule::from_options("demo", &HashMap::new()).unwrap()
+        );
+
+        let options = HashMap::from([(PARTITION_RULE_KEY.to_string(), "{".to_string())]);
+        assert!(RegionPartitionRule::from_options("demo", &options).is_err());
+    }
+
+    #[test]
+    fn test_find_region() {
+        let rule = new_rule();
+        assert_eq!(0, rule.find_region(&Value::Null));
+        assert_eq!(0, rule.find_region(&Value::from(-1)));
+        assert_eq!(1, rule.find_region(&Value::from(10)));
+        assert_eq!(1, rule.find_region(&Value::from(19)));
+        assert_eq!(2, rule.find_region(&Value::from(20)));
+        assert_eq!(2, rule.find_region(&Value::from(i32::MAX)));
+    }
+
+    #[test]
+    fn test_split_and_merge_regions() {
+        let rule = new_rule();
+        let split = rule.split_region(1, 15.into(), 3, 4).unwrap();
+        assert_eq!(
+            RegionPartitionRule::new("n", vec![10.into(), 15.into(), 20.into()], vec![0, 3, 4, 2]),
+            split
+        );
+        assert_eq!(3, split.find_region(&Value::from(14)));
+        assert_eq!(4, split.find_region(&Value::from(15)));
+        // The bound must be inside the range of the region.
+        assert!(rule.split_region(1, 10.into(), 3, 4).is_none());
+        assert!(rule.split_region(1, 20.into(), 3, 4).is_none());
+        assert!(rule.split_region(0, Value::Null, 3, 4).is_none());
+        assert!(rule.split_region(5, 15.into(), 3, 4).is_none());
+
+        let merged = split.merge_regions(3, 4, 5).unwrap();
+        assert_eq!(
+            RegionPartitionRule::new("n", vec![10.into(), 20.into()], vec![0, 5, 2]),
+            merged
+        );
+        // Only adjacent regions in order can be merged.
+        assert!(split.merge_regions(4, 3, 5).is_none());
+        assert!(split.merge_regions(0, 4, 5).is_none());
+        assert!(split.merge_regions(2, 0, 5).is_none());
+
+        let single = RegionPartitionRule::new("n", vec![], vec![0]);
+        let split = single.split_region(0, 10.into(), 1, 2).unwrap();
+        assert_eq!(
+            RegionPartitionRule::new("n", vec![10.into()], vec![1, 2]),
+            split
+        );
+        assert_eq!(
+            RegionPartitionRule::new("n", vec![], vec![3]),
+            split.merge_regions(1, 2, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let rule = new_rule();
+        let columns_values = HashMap::from([
+            (
+                "n".to_string(),
+                Arc::new(Int32Vector::from(vec![Some(1), Some(25), None, Some(12)])) as _,
+            ),
+            (
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["a", "b", "c", "d"])) as _,
+            ),
+        ]);
+
+        let split = rule.split("demo", columns_values).unwrap();
+        assert_eq!(3, split.len());
+        let expect_hosts = [(0, vec!["a", "c"]), (1, vec!["d"]), (2, vec!["b"])];
+        for (region, hosts) in expect_hosts {
+            let expect: VectorRef = Arc::new(StringVector::from(hosts));
+            assert_eq!(expect, split[&region]["host"]);
+        }
+
+        let columns_values = HashMap::from([(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["a"])) as _,
+        )]);
+        assert!(rule.split("demo", columns_values).is_err());
+    }
+
+    #[test]
+    fn test_find_regions_by_filters() {
+        let rule = new_rule();
+        let find = |filters: Vec<DfExpr>| {
+            let filters: Vec<Expr> = filters.into_iter().map(Expr::from).collect();
+            rule.find_regions_by_filters(&filters)
+        };
+
+        assert_eq!(vec![0, 1, 2], find(vec![]));
+        assert_eq!(vec![0], find(vec![col("n").lt(lit(10))]));
+        assert_eq!(vec![0, 1], find(vec![col("n").lt_eq(lit(10))]));
+        assert_eq!(vec![1], find(vec![col("n").eq(lit(15))]));
+        assert_eq!(vec![2], find(vec![lit(20).lt_eq(col("n"))]));
+        assert_eq!(
+            vec![1, 2],
+            find(vec![col("n").gt_eq(lit(10)), col("host").eq(lit("a"))])
+        );
+        assert_eq!(
+            vec![0, 2],
+            find(vec![col("n").lt(lit(5)).or(col("n").gt(lit(30)))])
+        );
+        assert!(find(vec![col("n").lt(lit(5)).and(col("n").gt(lit(30)))]).is_empty());
+        // Literals of other types can't prune regions.
+        assert_eq!(vec![0, 1, 2], find(vec![col("n").eq(lit(15i64))]));
+        assert_eq!(vec![0, 1, 2], find(vec![col("n").not_eq(lit(15))]));
+    }
+}
@@ -16,6 +16,7 @@
 pub mod test_util;
 
 use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -23,48 +24,101 @@ use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_query::logical_plan::Expr;
-use common_query::physical_plan::PhysicalPlanRef;
+use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlanAdapter, PhysicalPlanRef};
+use common_query::DfPhysicalPlan;
 use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
-use common_recordbatch::{RecordBatch, RecordBatchStream};
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
 use common_telemetry::logging;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::union::UnionExec;
+use datatypes::value::Value;
+use datatypes::vectors::VectorRef;
 use futures::task::{Context, Poll};
-use futures::Stream;
+use futures::{future, Stream};
 use object_store::ObjectStore;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
     AddColumn, AlterOperation, AlterRequest, ChunkReader, ReadContext, Region, RegionMeta,
-    ScanRequest, SchemaRef, Snapshot, WriteContext, WriteRequest,
+    RegionMetrics, RegionNumber, ScanOrder, ScanRequest, SchemaRef, SequenceNumber, Snapshot,
+    SnapshotStatistics, WriteContext, WriteRequest,
 };
 use table::error::{Error as TableError, Result as TableResult};
 use table::metadata::{
     FilterPushDownType, RawTableInfo, TableInfo, TableInfoRef, TableMeta, TableType,
 };
-use table::requests::{AddColumnRequest, AlterKind, AlterTableRequest, InsertRequest};
-use table::table::scan::SimpleTableScan;
-use table::table::Table;
-use tokio::sync::Mutex;
+use table::predicate::ColumnFilter;
+use table::requests::{
+    AddColumnRequest, AlterKind, AlterTableRequest, DeleteRangeRequest, InsertRequest,
+};
+use table::table::scan::{time_index_ordering, SimpleTableScan};
+use table::table::{ColumnStatistics, Table, TableStatistics};
+use table::validate::validate_insert;
+use tokio::sync::{Mutex, RwLock, RwLockWriteGuard};
 
 use crate::error::{
-    self, ProjectedColumnNotFoundSnafu, Result, ScanTableManifestSnafu, TableInfoNotFoundSnafu,
-    UpdateTableManifestSnafu,
+    self, InvalidPartitionRuleSnafu, ProjectedColumnNotFoundSnafu, RegionFencedSnafu, Result,
+    ScanTableManifestSnafu, UnsupportedMultiRegionsSnafu, UpdateTableManifestSnafu,
 };
 use crate::manifest::action::*;
 use crate::manifest::TableManifest;
+use crate::partition::{load_partition_rule, RegionPartitionRule, PARTITION_RULE_KEY};
 
 #[inline]
-fn table_manifest_dir(table_dir: &str) -> String {
+pub(crate) fn table_manifest_dir(table_dir: &str) -> String {
     format!("{table_dir}/manifest/")
 }
 
 /// [Table] implementation.
+///
+/// A table may consist of multiple regions, rows are routed to the regions by the
+/// [RegionPartitionRule] of the table.
 pub struct MitoTable<R: Region> {
     manifest: TableManifest,
     // guarded by `self.alter_lock`
     table_info: ArcSwap<TableInfo>,
-    // TODO(dennis): a table contains multi regions
-    region: R,
+    // guarded by `self.alter_lock`
+    regions: ArcSwap<TableRegions<R>>,
     alter_lock: Mutex<()>,
+    /// Regions rejecting writes. Writes hold the read lock, splitting and merging
+    /// regions hold the write lock so no rows are written to the regions being replaced.
+    write_fence: RwLock<HashSet<RegionNumber>>,
+}
+
+/// Regions of a table and the rule to route rows to them, which are replaced together
+/// after splitting or merging regions.
+pub struct TableRegions<R: Region> {
+    pub regions: BTreeMap<RegionNumber, R>,
+    /// Partition rule of the table, `None` if the table only has one region.
+    pub partition_rule: Option<RegionPartitionRule>,
+}
+
+impl<R: Region> TableRegions<R> {
+    #[inline]
+    fn first_region(&self) -> &R {
+        // Safety: A table has at least one region.
+        self.regions.values().next().unwrap()
+    }
+
+    /// Returns regions that may contain rows matching the `filters`.
+    fn regions_to_scan(&self, filters: &[Expr]) -> Vec<&R> {
+        let Some(partition_rule) = &self.partition_rule else {
+            return vec![self.first_region()];
+        };
+
+        let regions: Vec<_> = partition_rule
+            .find_regions_by_filters(filters)
+            .iter()
+            .map(|region_number| &self.regions[region_number])
+            .collect();
+        if regions.is_empty() {
+            // No rows match the filters, but we still scan a region for the schema of
+            // the output, all rows of the region are filtered out later.
+            return vec![self.first_region()];
+        }
+        regions
+    }
 }
 
 #[async_trait]
@@ -82,29 +136,131 @@ impl<R: Region> Table for MitoTable<R> {
             return Ok(0);
         }
 
-        let mut write_request = self.region.write_request();
-
         let columns_values = request.columns_values;
         // columns_values is not empty, it's safe to unwrap
         let rows_num = columns_values.values().next().unwrap().len();
 
+        let table_info = self.table_info();
+        let table_name = &table_info.name;
+        // Reports all invalid columns instead of the first one found by the region.
+        validate_insert(table_name, &table_info.meta.schema, &columns_values)?;
         logging::trace!(
             "Insert into table {} with data: {:?}",
-            self.table_info().name,
+            table_name,
             columns_values
         );
 
-        write_request.put(columns_values).map_err(TableError::new)?;
+        let fenced = self.write_fence.read().await;
+        let table_regions = self.regions.load_full();
+        let Some(partition_rule) = &table_regions.partition_rule else {
+            // Safety: A table has at least one region.
+            let (region_number, region) = table_regions.regions.iter().next().unwrap();
+            ensure_not_fenced(table_name, &fenced, *region_number)?;
+            write_region(region, columns_values).await?;
+            return Ok(rows_num);
+        };
 
-        let _resp = self
-            .region
-            .write(&WriteContext::default(), write_request)
-            .await
-            .map_err(TableError::new)?;
+        let region_columns = partition_rule.split(table_name, columns_values)?;
+        for region_number in region_columns.keys() {
+            ensure_not_fenced(table_name, &fenced, *region_number)?;
+        }
+        let writes = region_columns
+            .into_iter()
+            .map(|(region_number, columns_values)| {
+                // Safety: The partition rule is validated against regions of the table.
+                let region = &table_regions.regions[&region_number];
+                write_region(region, columns_values)
+            });
+        let _ = future::try_join_all(writes).await?;
 
         Ok(rows_num)
     }
 
+    async fn delete_range(&self, request: DeleteRangeRequest) -> TableResult<()> {
+        logging::debug!(
+            "Delete range {:?} from table {}",
+            request.range,
+            self.table_info().name
+        );
+
+        // Rows of the time range may be in any region.
+        let range = request.range;
+        let fenced = self.write_fence.read().await;
+        let table_regions = self.regions.load_full();
+        for region_number in table_regions.regions.keys() {
+            ensure_not_fenced(&self.table_info().name, &fenced, *region_number)?;
+        }
+        let deletes = table_regions.regions.values().map(|region| async move {
+            let mut write_request = region.write_request();
+            write_request.delete_range(range).map_err(TableError::new)?;
+
+            let _resp = region
+                .write(&WriteContext::default(), write_request)
+                .await
+                .map_err(TableError::new)?;
+            Ok::<_, TableError>(())
+        });
+        let _ = future::try_join_all(deletes).await?;
+
+        Ok(())
+    }
+
+    async fn truncate(&self) -> TableResult<()> {
+        logging::info!("Truncate table {}", self.table_info().name);
+
+        let fenced = self.write_fence.read().await;
+        let table_regions = self.regions.load_full();
+        for region_number in table_regions.regions.keys() {
+            ensure_not_fenced(&self.table_info().name, &fenced, *region_number)?;
+        }
+        let truncates = table_regions
+            .regions
+            .values()
+            .map(|region| async move { region.truncate().await.map_err(TableError::new) });
+        let _ = future::try_join_all(truncates).await?;
+
+        Ok(())
+    }
+
+    async fn flush(&self) -> TableResult<()> {
+        logging::info!("Flush table {}", self.table_info().name);
+
+        let table_regions = self.regions.load_full();
+        let flushes = table_regions
+            .regions
+            .values()
+            .map(|region| async move { region.flush().await.map_err(TableError::new) });
+        let _ = future::try_join_all(flushes).await?;
+
+        Ok(())
+    }
+
+    async fn fence_region(&self, region_number: RegionNumber, fenced: bool) -> TableResult<()> {
+        let table_name = &self.table_info().name;
+        // Waits for the writes in progress.
+        let mut fenced_regions = self.write_fence.write().await;
+        if fenced {
+            ensure!(
+                self.regions.load().regions.contains_key(&region_number),
+                error::RegionNotFoundSnafu {
+                    table_name,
+                    region_number,
+                }
+            );
+            let _ = fenced_regions.insert(region_number);
+        } else {
+            let _ = fenced_regions.remove(&region_number);
+        }
+        logging::info!(
+            "Region {} of table {} is {}",
+            region_number,
+            table_name,
+            if fenced { "fenced" } else { "unfenced" }
+        );
+
+        Ok(())
+    }
+
     fn table_type(&self) -> TableType {
         self.table_info().table_type
     }
@@ -117,48 +273,76 @@ impl<R: Region> Table for MitoTable<R> {
         &self,
         projection: Option<&Vec<usize>>,
         filters: &[Expr],
-        _limit: Option<usize>,
+        limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
-        let read_ctx = ReadContext::default();
-        let snapshot = self.region.snapshot(&read_ctx).map_err(TableError::new)?;
+        self.scan_regions(projection, filters, limit, None).await
+    }
 
-        let projection = self.transform_projection(&self.region, projection.cloned())?;
-        let filters = filters.into();
-        let scan_request = ScanRequest {
-            projection,
-            filters,
-            ..Default::default()
-        };
-        let mut reader = snapshot
-            .scan(&read_ctx, scan_request)
+    async fn scan_ordered(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: ScanOrder,
+    ) -> TableResult<PhysicalPlanRef> {
+        self.scan_regions(projection, filters, limit, Some(order))
             .await
-            .map_err(TableError::new)?
-            .reader;
+    }
 
-        let schema = reader.schema().clone();
-        let stream_schema = schema.clone();
+    async fn scan_at_sequence(
+        &self,
+        projection: Option<&Vec<usize>>,
+        sequence: SequenceNumber,
+    ) -> TableResult<SendableRecordBatchStream> {
+        logging::info!(
+            "Scan table {} at sequence {}",
+            self.table_info().name,
+            sequence
+        );
+        self.scan_region(projection, &[], Some(sequence), None)
+            .await
+    }
 
-        let stream = Box::pin(async_stream::try_stream! {
-            while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
-                yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
-            }
-        });
+    fn statistics(&self) -> TableResult<Option<TableStatistics>> {
+        let read_ctx = ReadContext::default();
+        let table_regions = self.regions.load();
+        let mut region_statistics = Vec::with_capacity(table_regions.regions.len());
+        for region in table_regions.regions.values() {
+            let snapshot = region.snapshot(&read_ctx).map_err(TableError::new)?;
+            region_statistics.push(snapshot.statistics());
+        }
+        let statistics = region_statistics
+            .into_iter()
+            .reduce(merge_statistics)
+            .unwrap_or_default();
 
-        let stream = Box::pin(ChunkStream { schema, stream });
-        Ok(Arc::new(SimpleTableScan::new(stream)))
+        Ok(Some(to_table_statistics(&self.schema(), statistics)))
     }
 
-    /// Alter table changes the schemas of the table.
+    /// Alter table changes the schemas or the name of the table.
     async fn alter(&self, req: AlterTableRequest) -> TableResult<()> {
         let _lock = self.alter_lock.lock().await;
 
         let table_info = self.table_info();
         let table_name = &table_info.name;
         let table_meta = &table_info.meta;
+        let table_regions = self.regions.load_full();
+        if let (Some(rule), AlterKind::DropColumns { names }) =
+            (&table_regions.partition_rule, &req.alter_kind)
+        {
+            ensure!(
+                !names.iter().any(|name| name == rule.column_name()),
+                InvalidPartitionRuleSnafu {
+                    table_name,
+                    reason: format!("partition column {} can't be dropped", rule.column_name()),
+                }
+            );
+        }
         let mut new_meta = table_meta
             .builder_with_alter_kind(table_name, &req.alter_kind)?
             .build()
             .context(error::BuildTableMetaSnafu { table_name })?;
+        table_meta.ensure_compatible(table_name, &new_meta)?;
 
         let alter_op = create_alter_operation(table_name, &req.alter_kind, &mut new_meta)?;
 
@@ -166,6 +350,9 @@ impl<R: Region> Table for MitoTable<R> {
         // Increase version of the table.
         new_info.ident.version = table_info.ident.version + 1;
         new_info.meta = new_meta;
+        if let AlterKind::RenameTable { new_table_name } = &req.alter_kind {
+            new_info.name = new_table_name.clone();
+        }
 
         // Persist the alteration to the manifest.
         logging::debug!(
@@ -186,20 +373,24 @@ impl<R: Region> Table for MitoTable<R> {
 
         // TODO(yingwen): Error handling. Maybe the region need to provide a method to
         // validate the request first.
-        let region = self.region();
-        let region_meta = region.in_memory_metadata();
-        let alter_req = AlterRequest {
-            operation: alter_op,
-            version: region_meta.version(),
-        };
-        // Alter the region.
-        logging::debug!(
-            "start altering region {} of table {}, with request {:?}",
-            region.name(),
-            table_name,
-            alter_req,
-        );
-        region.alter(alter_req).await.map_err(TableError::new)?;
+        // Renaming the table doesn't alter the regions.
+        if let Some(alter_op) = alter_op {
+            for region in table_regions.regions.values() {
+                let region_meta = region.in_memory_metadata();
+                let alter_req = AlterRequest {
+                    operation: alter_op.clone(),
+                    version: region_meta.version(),
+                };
+                // Alter the region.
+                logging::debug!(
+                    "start altering region {} of table {}, with request {:?}",
+                    region.name(),
+                    table_name,
+                    alter_req,
+                );
+                region.alter(alter_req).await.map_err(TableError::new)?;
+            }
+        }
 
         // Update in memory metadata of the table.
         self.set_table_info(new_info);
@@ -207,14 +398,37 @@ impl<R: Region> Table for MitoTable<R> {
         Ok(())
     }
 
-    fn supports_filter_pushdown(&self, _filter: &Expr) -> table::error::Result<FilterPushDownType> {
-        Ok(FilterPushDownType::Inexact)
+    fn supports_filter_pushdown(&self, filter: &Expr) -> table::error::Result<FilterPushDownType> {
+        // Regions remove rows not matching filters on the row key before the limit,
+        // other filters are only used to prune data.
+        let table_info = self.table_info();
+        let meta = &table_info.meta;
+        if ColumnFilter::is_row_key_filter(filter, &meta.schema, &meta.primary_key_indices) {
+            Ok(FilterPushDownType::Exact)
+        } else {
+            Ok(FilterPushDownType::Inexact)
+        }
+    }
+
+    fn scan_in_time_index_order(&self) -> bool {
+        // Regions without a primary key return rows sorted by the time index, rows
+        // of other regions are sorted by the scan.
+        true
+    }
+
+    fn region_metrics(&self) -> Vec<(RegionNumber, RegionMetrics)> {
+        self.regions
+            .load()
+            .regions
+            .iter()
+            .map(|(region_number, region)| (*region_number, region.metrics()))
+            .collect()
     }
 }
 
-struct ChunkStream {
-    schema: SchemaRef,
-    stream: Pin<Box<dyn Stream<Item = RecordBatchResult<RecordBatch>> + Send>>,
+pub(crate) struct ChunkStream {
+    pub(crate) schema: SchemaRef,
+    pub(crate) stream: Pin<Box<dyn Stream<Item = RecordBatchResult<RecordBatch>> + Send>>,
 }
 
 impl RecordBatchStream for ChunkStream {
@@ -231,21 +445,294 @@ impl Stream for ChunkStream {
     }
 }
 
+fn ensure_not_fenced(
+    table_name: &str,
+    fenced: &HashSet<RegionNumber>,
+    region_number: RegionNumber,
+) -> Result<()> {
+    ensure!(
+        !fenced.contains(&region_number),
+        RegionFencedSnafu {
+            table_name,
+            region_number,
+        }
+    );
+    Ok(())
+}
+
+/// Writes `columns_values` to the `region`.
+async fn write_region<R: Region>(
+    region: &R,
+    columns_values: HashMap<String, VectorRef>,
+) -> TableResult<()> {
+    let mut write_request = region.write_request();
+    write_request.put(columns_values).map_err(TableError::new)?;
+
+    let _resp = region
+        .write(&WriteContext::default(), write_request)
+        .await
+        .map_err(TableError::new)?;
+    Ok(())
+}
+
+/// Merges statistics of snapshots of two regions.
+fn merge_statistics(
+    mut merged: SnapshotStatistics,
+    statistics: SnapshotStatistics,
+) -> SnapshotStatistics {
+    merged.time_range = match (merged.time_range, statistics.time_range) {
+        (Some((min1, max1)), Some((min2, max2))) => Some((min1.min(min2), max1.max(max2))),
+        // An empty region has no time range.
+        (time_range, None) if statistics.num_rows == 0 => time_range,
+        (None, time_range) if merged.num_rows == 0 => time_range,
+        _ => None,
+    };
+    merged.num_rows += statistics.num_rows;
+    merged.total_bytes += statistics.total_bytes;
+    merged
+}
+
+/// Converts statistics of the region snapshot into statistics of columns in `schema`.
+/// Only the time index column has column statistics.
+fn to_table_statistics(schema: &SchemaRef, statistics: SnapshotStatistics) -> TableStatistics {
+    let column_statistics = schema
+        .column_schemas()
+        .iter()
+        .map(|column_schema| match statistics.time_range {
+            Some((min, max)) if column_schema.is_time_index() => ColumnStatistics {
+                min_value: Some(Value::Timestamp(min)),
+                max_value: Some(Value::Timestamp(max)),
+            },
+            _ => ColumnStatistics::default(),
+        })
+        .collect();
+
+    TableStatistics {
+        num_rows: statistics.num_rows,
+        total_bytes: statistics.total_bytes,
+        column_statistics,
+    }
+}
+
 #[inline]
 fn column_qualified_name(table_name: &str, region_name: &str, column_name: &str) -> String {
     format!("{table_name}.{region_name}.{column_name}")
 }
 
 impl<R: Region> MitoTable<R> {
-    fn new(table_info: TableInfo, region: R, manifest: TableManifest) -> Self {
+    /// Creates a table with non-empty `regions`, the `partition_rule` must have been
+    /// validated against the regions.
+    fn new(
+        table_info: TableInfo,
+        regions: BTreeMap<RegionNumber, R>,
+        partition_rule: Option<RegionPartitionRule>,
+        manifest: TableManifest,
+    ) -> Self {
+        debug_assert!(!regions.is_empty());
         Self {
             table_info: ArcSwap::new(Arc::new(table_info)),
-            region,
+            regions: ArcSwap::new(Arc::new(TableRegions {
+                regions,
+                partition_rule,
+            })),
             manifest,
             alter_lock: Mutex::new(()),
+            write_fence: RwLock::new(HashSet::new()),
         }
     }
 
+    /// Scans all regions to read, rows are sorted by the time index in `order` if it's
+    /// not `None`.
+    async fn scan_regions(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: Option<ScanOrder>,
+    ) -> TableResult<PhysicalPlanRef> {
+        // Rows of a region are sorted by the row key, which consists of the time index
+        // only if the table has no primary key. Otherwise rows of each region are sorted
+        // by the time index after reading all of them.
+        let sorted_by_time_index = self.table_info().meta.primary_key_indices.is_empty();
+        let (region_order, region_limit) = match order {
+            Some(order) if sorted_by_time_index => (order, limit),
+            Some(_) => (ScanOrder::Asc, None),
+            None => (ScanOrder::Asc, limit),
+        };
+
+        let read_ctx = ReadContext::default();
+        let table_regions = self.regions.load_full();
+        let regions = table_regions.regions_to_scan(filters);
+        let mut streams = Vec::with_capacity(regions.len());
+        let mut region_statistics = Vec::with_capacity(regions.len());
+        for region in regions {
+            let snapshot = region.snapshot(&read_ctx).map_err(TableError::new)?;
+            let request = ScanRequest {
+                limit: region_limit,
+                order: region_order,
+                ..Default::default()
+            };
+            let stream = self
+                .scan_snapshot(region, &snapshot, &read_ctx, projection, filters, request)
+                .await?;
+            streams.push(stream);
+            region_statistics.push(snapshot.statistics());
+        }
+        // Safety: There is at least one region to scan.
+        let schema = streams[0].schema();
+        let output_ordering = if sorted_by_time_index {
+            time_index_ordering(&schema, region_order.is_desc())
+        } else {
+            None
+        };
+
+        if let (Some(order), false) = (order, sorted_by_time_index) {
+            // Sorts rows of each region and merges them, each region only needs to keep
+            // the first `limit` rows.
+            let Some(ordering) = time_index_ordering(&schema, order.is_desc()) else {
+                return table::error::UnsupportedSnafu {
+                    operation: "scan_ordered",
+                    table_name: &self.table_info().name,
+                }
+                .fail()
+                .map_err(Into::into);
+            };
+            let mut inputs = Vec::with_capacity(streams.len());
+            for (stream, statistics) in streams.into_iter().zip(region_statistics) {
+                let statistics =
+                    to_table_statistics(&schema, statistics).to_plan_statistics(&schema);
+                let scan = SimpleTableScan::new(stream).with_statistics(statistics);
+                let sort = SortExec::try_new(
+                    ordering.clone(),
+                    Arc::new(DfPhysicalPlanAdapter(Arc::new(scan))),
+                    limit,
+                )
+                .context(table::error::DatafusionSnafu)?;
+                inputs.push(Arc::new(sort) as _);
+            }
+            let plan: Arc<dyn DfPhysicalPlan> = if inputs.len() == 1 {
+                inputs.pop().unwrap()
+            } else {
+                Arc::new(SortPreservingMergeExec::new(
+                    ordering,
+                    Arc::new(UnionExec::new(inputs)),
+                ))
+            };
+            return Ok(Arc::new(PhysicalPlanAdapter::new(schema, plan)));
+        }
+
+        if let (Some(ordering), true) = (&output_ordering, streams.len() > 1) {
+            // Merges the sorted rows of all regions, so rows of the scan are still in
+            // time index order.
+            let inputs = streams
+                .into_iter()
+                .zip(region_statistics)
+                .map(|(stream, statistics)| {
+                    let statistics =
+                        to_table_statistics(&schema, statistics).to_plan_statistics(&schema);
+                    let scan = SimpleTableScan::new(stream)
+                        .with_statistics(statistics)
+                        .with_output_ordering(output_ordering.clone());
+                    Arc::new(DfPhysicalPlanAdapter(Arc::new(scan))) as _
+                })
+                .collect();
+            let merge =
+                SortPreservingMergeExec::new(ordering.clone(), Arc::new(UnionExec::new(inputs)));
+            return Ok(Arc::new(PhysicalPlanAdapter::new(schema, Arc::new(merge))));
+        }
+
+        let statistics = region_statistics
+            .into_iter()
+            .reduce(merge_statistics)
+            .unwrap_or_default();
+        let statistics = to_table_statistics(&schema, statistics).to_plan_statistics(&schema);
+
+        let stream = if streams.len() == 1 {
+            streams.pop().unwrap()
+        } else {
+            Box::pin(ChunkStream {
+                schema,
+                stream: Box::pin(futures::stream::select_all(streams)),
+            })
+        };
+
+        Ok(Arc::new(
+            SimpleTableScan::new(stream)
+                .with_statistics(statistics)
+                .with_output_ordering(output_ordering),
+        ))
+    }
+
+    /// Scan the region, only rows whose sequence is less than or equal to `sequence`
+    /// are visible, `None` for the latest committed sequence. The stream ends after
+    /// `limit` rows if `limit` is set.
+    ///
+    /// Sequences are maintained by each region, so only a table with one region
+    /// supports this.
+    async fn scan_region(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        sequence: Option<SequenceNumber>,
+        limit: Option<usize>,
+    ) -> TableResult<SendableRecordBatchStream> {
+        let table_regions = self.regions.load_full();
+        ensure!(
+            table_regions.regions.len() == 1,
+            UnsupportedMultiRegionsSnafu {
+                operation: "scan_region",
+                table_name: &self.table_info().name,
+            }
+        );
+
+        let region = table_regions.first_region();
+        let read_ctx = ReadContext::default();
+        let snapshot = region.snapshot(&read_ctx).map_err(TableError::new)?;
+        let request = ScanRequest {
+            sequence,
+            limit,
+            ..Default::default()
+        };
+        self.scan_snapshot(region, &snapshot, &read_ctx, projection, filters, request)
+            .await
+    }
+
+    /// Scan the `snapshot` of the `region` by the `request`, whose projection and
+    /// filters are replaced by `projection` and `filters` of the table, see
+    /// [MitoTable::scan_region].
+    async fn scan_snapshot(
+        &self,
+        region: &R,
+        snapshot: &R::Snapshot,
+        read_ctx: &ReadContext,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        request: ScanRequest,
+    ) -> TableResult<SendableRecordBatchStream> {
+        let projection = self.transform_projection(region, projection.cloned())?;
+        let scan_request = ScanRequest {
+            projection,
+            filters: filters.into(),
+            ..request
+        };
+        let mut reader = snapshot
+            .scan(read_ctx, scan_request)
+            .await
+            .map_err(TableError::new)?
+            .reader;
+
+        let schema = reader.schema().clone();
+        let stream_schema = schema.clone();
+
+        let stream = Box::pin(async_stream::try_stream! {
+            while let Some(chunk) = reader.next_chunk().await.map_err(BoxedError::new).context(ExternalSnafu)? {
+                yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
+            }
+        });
+
+        Ok(Box::pin(ChunkStream { schema, stream }))
+    }
+
     /// Transform projection which is based on table schema
     /// into projection based on region schema.
     fn transform_projection(
@@ -306,9 +793,16 @@ impl<R: Region> MitoTable<R> {
         table_name: &str,
         table_dir: &str,
         table_info: TableInfo,
-        region: R,
+        regions: BTreeMap<RegionNumber, R>,
         object_store: ObjectStore,
     ) -> Result<MitoTable<R>> {
+        let region_numbers: Vec<_> = regions.keys().copied().collect();
+        let partition_rule = load_partition_rule(
+            table_name,
+            &table_info.meta.schema,
+            &table_info.meta.options,
+            &region_numbers,
+        )?;
         let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
 
         // TODO(dennis): save manifest version into catalog?
@@ -321,22 +815,47 @@ impl<R: Region> MitoTable<R> {
             .await
             .context(UpdateTableManifestSnafu { table_name })?;
 
-        Ok(MitoTable::new(table_info, region, manifest))
+        Ok(MitoTable::new(
+            table_info,
+            regions,
+            partition_rule,
+            manifest,
+        ))
     }
 
-    pub async fn open(
+    /// Recovers the table info from the manifest of the table under `table_dir`, returns
+    /// `None` if the manifest has no table info.
+    pub(crate) async fn recover(
         table_name: &str,
         table_dir: &str,
-        region: R,
         object_store: ObjectStore,
-    ) -> Result<MitoTable<R>> {
+    ) -> Result<Option<(TableInfo, TableManifest)>> {
         let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
 
-        let mut table_info = Self::recover_table_info(table_name, &manifest)
-            .await?
-            .context(TableInfoNotFoundSnafu { table_name })?;
-        table_info.meta.region_numbers = vec![(region.id() & 0xFFFFFFFF) as u32];
-        Ok(MitoTable::new(table_info, region, manifest))
+        let table_info = Self::recover_table_info(table_name, &manifest).await?;
+        Ok(table_info.map(|table_info| (table_info, manifest)))
+    }
+
+    /// Opens the table with `table_info` and `manifest` returned by [MitoTable::recover].
+    pub(crate) fn open(
+        table_name: &str,
+        mut table_info: TableInfo,
+        regions: BTreeMap<RegionNumber, R>,
+        manifest: TableManifest,
+    ) -> Result<MitoTable<R>> {
+        table_info.meta.region_numbers = regions.keys().copied().collect();
+        let partition_rule = load_partition_rule(
+            table_name,
+            &table_info.meta.schema,
+            &table_info.meta.options,
+            &table_info.meta.region_numbers,
+        )?;
+        Ok(MitoTable::new(
+            table_info,
+            regions,
+            partition_rule,
+            manifest,
+        ))
     }
 
     async fn recover_table_info(
@@ -387,8 +906,73 @@ impl<R: Region> MitoTable<R> {
     }
 
     #[inline]
-    pub fn region(&self) -> &R {
-        &self.region
+    pub fn regions(&self) -> BTreeMap<RegionNumber, R> {
+        self.regions.load().regions.clone()
+    }
+
+    #[inline]
+    pub fn partition_rule(&self) -> Option<RegionPartitionRule> {
+        self.regions.load().partition_rule.clone()
+    }
+
+    #[inline]
+    pub fn table_regions(&self) -> Arc<TableRegions<R>> {
+        self.regions.load_full()
+    }
+
+    /// Returns the lock to hold while altering the table or its regions.
+    #[inline]
+    pub(crate) fn alter_lock(&self) -> &Mutex<()> {
+        &self.alter_lock
+    }
+
+    /// Blocks writes to the table until the returned guard is dropped. Splitting or
+    /// merging regions holds it from reading the source regions until the new regions
+    /// are set, otherwise rows written meanwhile are lost with the source regions.
+    pub(crate) async fn fence_writes(&self) -> RwLockWriteGuard<'_, HashSet<RegionNumber>> {
+        self.write_fence.write().await
+    }
+
+    /// Replaces the regions of the table after splitting or merging regions, and
+    /// persists the new region numbers and partition rule to the manifest. The caller
+    /// should hold the [alter lock](MitoTable::alter_lock) and
+    /// [fence writes](MitoTable::fence_writes).
+    pub(crate) async fn set_regions(
+        &self,
+        regions: BTreeMap<RegionNumber, R>,
+        partition_rule: RegionPartitionRule,
+    ) -> Result<()> {
+        let table_info = self.table_info();
+        let mut new_info = TableInfo::clone(&*table_info);
+        new_info.meta.region_numbers = regions.keys().copied().collect();
+        let _ = new_info
+            .meta
+            .options
+            .insert(PARTITION_RULE_KEY.to_string(), partition_rule.to_option());
+        partition_rule.validate(
+            &table_info.name,
+            &new_info.meta.schema,
+            &new_info.meta.region_numbers,
+        )?;
+
+        self.manifest
+            .update(TableMetaActionList::with_action(TableMetaAction::Change(
+                Box::new(TableChange {
+                    table_info: RawTableInfo::from(new_info.clone()),
+                }),
+            )))
+            .await
+            .context(UpdateTableManifestSnafu {
+                table_name: &table_info.name,
+            })?;
+
+        self.regions.store(Arc::new(TableRegions {
+            regions,
+            partition_rule: Some(partition_rule),
+        }));
+        self.set_table_info(new_info);
+
+        Ok(())
     }
 
     pub fn set_table_info(&self, table_info: TableInfo) {
@@ -406,19 +990,21 @@ impl<R: Region> MitoTable<R> {
     }
 }
 
-/// Create [`AlterOperation`] according to given `alter_kind`.
+/// Create [`AlterOperation`] according to given `alter_kind`, returns `None` if the
+/// regions don't need to be altered.
 fn create_alter_operation(
     table_name: &str,
     alter_kind: &AlterKind,
     table_meta: &mut TableMeta,
-) -> TableResult<AlterOperation> {
+) -> TableResult<Option<AlterOperation>> {
     match alter_kind {
         AlterKind::AddColumns { columns } => {
-            create_add_columns_operation(table_name, columns, table_meta)
+            create_add_columns_operation(table_name, columns, table_meta).map(Some)
         }
-        AlterKind::DropColumns { names } => Ok(AlterOperation::DropColumns {
+        AlterKind::DropColumns { names } => Ok(Some(AlterOperation::DropColumns {
             names: names.to_vec(),
-        }),
+        })),
+        AlterKind::RenameTable { .. } => Ok(None),
     }
 }
 
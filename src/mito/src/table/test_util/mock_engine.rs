@@ -27,8 +27,9 @@ use storage::metadata::{RegionMetaImpl, RegionMetadata};
 use storage::write_batch::WriteBatch;
 use store_api::storage::{
     AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, GetRequest, GetResponse,
-    OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest, ScanResponse,
-    SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
+    MergeRegionsRequest, OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, ScanRequest,
+    ScanResponse, SchemaRef, Snapshot, SnapshotStatistics, SplitRegionRequest, StorageEngine,
+    WriteContext, WriteResponse,
 };
 
 pub type Result<T> = std::result::Result<T, MockError>;
@@ -122,6 +123,14 @@ impl Snapshot for MockSnapshot {
     async fn get(&self, _ctx: &ReadContext, _request: GetRequest) -> Result<GetResponse> {
         Ok(GetResponse {})
     }
+
+    fn statistics(&self) -> SnapshotStatistics {
+        let memtable = self.region.memtable.read().unwrap();
+        SnapshotStatistics {
+            num_rows: memtable.values().next().map(|v| v.len()).unwrap_or(0),
+            ..Default::default()
+        }
+    }
 }
 
 // Clones a MockRegion is not cheap as we need to clone the string name, but for test
@@ -188,6 +197,18 @@ impl Region for MockRegion {
 
         Ok(())
     }
+
+    async fn flush(&self) -> Result<()> {
+        // Data of mock region is always in memory.
+        Ok(())
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        let mut memtable = self.inner.memtable.write().unwrap();
+        memtable.values_mut().for_each(|column| column.clear());
+
+        Ok(())
+    }
 }
 
 impl MockRegionInner {
@@ -275,8 +296,17 @@ impl StorageEngine for MockEngine {
         return Ok(None);
     }
 
-    async fn close_region(&self, _ctx: &EngineContext, _region: MockRegion) -> Result<()> {
-        unimplemented!()
+    async fn close_region(&self, _ctx: &EngineContext, region: MockRegion) -> Result<()> {
+        logging::info!("Mock engine close region, name: {}", region.inner.name);
+
+        let mut regions = self.regions.lock().unwrap();
+        if let Some(region) = regions.opened_regions.remove(&region.inner.name) {
+            regions
+                .closed_regions
+                .insert(region.inner.name.clone(), region);
+        }
+
+        Ok(())
     }
 
     async fn create_region(
@@ -310,4 +340,25 @@ impl StorageEngine for MockEngine {
         let regions = self.regions.lock().unwrap();
         Ok(regions.opened_regions.get(name).cloned())
     }
+
+    async fn split_region(
+        &self,
+        _ctx: &EngineContext,
+        _region: &MockRegion,
+        _request: SplitRegionRequest,
+        _opts: &CreateOptions,
+    ) -> Result<(MockRegion, MockRegion)> {
+        unimplemented!()
+    }
+
+    async fn merge_regions(
+        &self,
+        _ctx: &EngineContext,
+        _left: &MockRegion,
+        _right: &MockRegion,
+        _request: MergeRegionsRequest,
+        _opts: &CreateOptions,
+    ) -> Result<MockRegion> {
+        unimplemented!()
+    }
 }
@@ -14,5 +14,6 @@
 
 pub mod azblob;
 pub mod fs;
+pub mod gcs;
 pub mod memory;
 pub mod s3;
@@ -0,0 +1,24 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Layers wrapping the accessors of object stores, including the builtin layers of opendal.
+
+mod fault;
+mod rate_limit;
+mod timeout;
+
+pub use fault::{FaultInjectionLayer, WriteFault};
+pub use opendal::layers::*;
+pub use rate_limit::RateLimitLayer;
+pub use timeout::TimeoutLayer;
@@ -0,0 +1,280 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::io::Cursor;
+use futures::AsyncReadExt;
+use opendal::raw::*;
+use opendal::{Error, ErrorKind, Layer, Result};
+
+/// A fault injected into a write of [FaultInjectionLayer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteFault {
+    /// Fails the write without writing anything.
+    Fail,
+    /// Writes the first `n` bytes of the object then fails, like crashing in the middle
+    /// of writing a file. `n` is clamped to be less than the size of the object.
+    Torn(usize),
+    /// Writes the whole object but fails, so the caller can't tell whether the write
+    /// succeeded.
+    FailAfterWrite,
+}
+
+/// Injects faults into writes of an object store, for testing the crash consistency of
+/// components built on it.
+///
+/// Once a fault is injected, the store is considered crashed and all following writes
+/// and deletes fail until [FaultInjectionLayer::recover] is called. Reads are never
+/// affected. Object stores have no rename, files are written in place, so reordering
+/// renames isn't simulated.
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjectionLayer {
+    state: Arc<FaultState>,
+}
+
+#[derive(Debug, Default)]
+struct FaultState {
+    /// Only writes to paths containing it are counted and faulted if set.
+    path_filter: Option<String>,
+    /// Number of writes counted.
+    writes: AtomicUsize,
+    /// Faults to inject, keyed by the index of the write.
+    faults: Mutex<HashMap<usize, WriteFault>>,
+    crashed: AtomicBool,
+}
+
+impl FaultInjectionLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only counts and injects faults into writes to paths containing `filter`, other
+    /// writes still fail once the store is crashed.
+    pub fn with_path_filter(filter: impl Into<String>) -> Self {
+        Self {
+            state: Arc::new(FaultState {
+                path_filter: Some(filter.into()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Injects `fault` into the `nth` write counted from the creation of the layer,
+    /// starting from 0.
+    pub fn inject(&self, nth: usize, fault: WriteFault) {
+        self.state.faults.lock().unwrap().insert(nth, fault);
+    }
+
+    /// Returns the number of writes counted.
+    pub fn writes(&self) -> usize {
+        self.state.writes.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if a fault has been injected and the store has crashed.
+    pub fn crashed(&self) -> bool {
+        self.state.crashed.load(Ordering::Relaxed)
+    }
+
+    /// Clears the crashed state, faults not injected yet are kept.
+    pub fn recover(&self) {
+        self.state.crashed.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Layer for FaultInjectionLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(FaultInjectionAccessor {
+            inner,
+            state: self.state.clone(),
+        })
+    }
+}
+
+impl FaultState {
+    fn check_crashed(&self, op: Operation, path: &str) -> Result<()> {
+        if self.crashed.load(Ordering::Relaxed) {
+            return Err(Error::new(ErrorKind::Unexpected, "object store crashed")
+                .with_operation(op)
+                .with_context("path", path));
+        }
+        Ok(())
+    }
+
+    /// Counts a write to `path`, returns the fault to inject into it.
+    fn next_fault(&self, path: &str) -> Option<WriteFault> {
+        if let Some(filter) = &self.path_filter {
+            if !path.contains(filter.as_str()) {
+                return None;
+            }
+        }
+
+        let nth = self.writes.fetch_add(1, Ordering::Relaxed);
+        let fault = self.faults.lock().unwrap().remove(&nth)?;
+        self.crashed.store(true, Ordering::Relaxed);
+        Some(fault)
+    }
+}
+
+#[derive(Debug)]
+struct FaultInjectionAccessor {
+    inner: Arc<dyn Accessor>,
+    state: Arc<FaultState>,
+}
+
+fn injected_error(op: Operation, path: &str, fault: WriteFault) -> Error {
+    Error::new(ErrorKind::Unexpected, "injected fault")
+        .with_operation(op)
+        .with_context("path", path)
+        .with_context("fault", format!("{fault:?}"))
+}
+
+#[async_trait]
+impl Accessor for FaultInjectionAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.state.check_crashed(Operation::Create, path)?;
+        self.inner.create(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, BytesReader)> {
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, mut r: BytesReader) -> Result<RpWrite> {
+        self.state.check_crashed(Operation::Write, path)?;
+        let Some(fault) = self.state.next_fault(path) else {
+            return self.inner.write(path, args, r).await;
+        };
+
+        let mut buf = Vec::with_capacity(args.size() as usize);
+        r.read_to_end(&mut buf).await.map_err(|e| {
+            Error::new(ErrorKind::Unexpected, "failed to read the object to write")
+                .with_operation(Operation::Write)
+                .with_context("path", path)
+                .set_source(e)
+        })?;
+        match fault {
+            WriteFault::Fail => (),
+            WriteFault::Torn(n) => {
+                buf.truncate(n.min(buf.len().saturating_sub(1)));
+                self.inner
+                    .write(
+                        path,
+                        OpWrite::new(buf.len() as u64),
+                        Box::new(Cursor::new(buf)),
+                    )
+                    .await?;
+            }
+            WriteFault::FailAfterWrite => {
+                self.inner
+                    .write(path, args, Box::new(Cursor::new(buf)))
+                    .await?;
+            }
+        }
+        Err(injected_error(Operation::Write, path, fault))
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.state.check_crashed(Operation::Delete, path)?;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, ObjectPager)> {
+        self.inner.list(path, args).await
+    }
+
+    fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        self.inner.presign(path, args)
+    }
+
+    async fn create_multipart(
+        &self,
+        path: &str,
+        args: OpCreateMultipart,
+    ) -> Result<RpCreateMultipart> {
+        self.state.check_crashed(Operation::CreateMultipart, path)?;
+        self.inner.create_multipart(path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<RpWriteMultipart> {
+        self.state.check_crashed(Operation::WriteMultipart, path)?;
+        self.inner.write_multipart(path, args, r).await
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &str,
+        args: OpCompleteMultipart,
+    ) -> Result<RpCompleteMultipart> {
+        self.state
+            .check_crashed(Operation::CompleteMultipart, path)?;
+        self.inner.complete_multipart(path, args).await
+    }
+
+    async fn abort_multipart(
+        &self,
+        path: &str,
+        args: OpAbortMultipart,
+    ) -> Result<RpAbortMultipart> {
+        self.inner.abort_multipart(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.state.check_crashed(Operation::Create, path)?;
+        self.inner.blocking_create(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, BlockingBytesReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<RpWrite> {
+        self.state.check_crashed(Operation::Write, path)?;
+        self.inner.blocking_write(path, args, r)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.state.check_crashed(Operation::Delete, path)?;
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, BlockingObjectPager)> {
+        self.inner.blocking_list(path, args)
+    }
+}
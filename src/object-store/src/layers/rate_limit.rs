@@ -0,0 +1,260 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use opendal::raw::*;
+use opendal::{Layer, Result};
+
+/// Limits the rate of operations and the bandwidth of an object store with token buckets,
+/// so background jobs like compaction won't exhaust the quota of a shared object storage.
+///
+/// Bytes of a read are only limited if the range to read is bounded. Blocking operations
+/// aren't limited.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitLayer {
+    ops: Option<Arc<TokenBucket>>,
+    bytes: Option<Arc<TokenBucket>>,
+}
+
+impl RateLimitLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of operations per second.
+    pub fn with_ops_per_second(mut self, ops: u64) -> Self {
+        self.ops = Some(Arc::new(TokenBucket::new(ops)));
+        self
+    }
+
+    /// Limits the bytes read and written per second.
+    pub fn with_bytes_per_second(mut self, bytes: u64) -> Self {
+        self.bytes = Some(Arc::new(TokenBucket::new(bytes)));
+        self
+    }
+}
+
+impl Layer for RateLimitLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(RateLimitAccessor {
+            inner,
+            ops: self.ops.clone(),
+            bytes: self.bytes.clone(),
+        })
+    }
+}
+
+/// A token bucket refilled at `rate` tokens per second, holding at most tokens of one
+/// second.
+///
+/// Acquiring more tokens than available puts the bucket into debt, the caller waits until
+/// the debt is paid off, so acquiring tokens more than the capacity never blocks forever.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        // Zero rate would make callers wait forever.
+        let rate = rate.max(1) as f64;
+        Self {
+            rate,
+            state: Mutex::new(BucketState {
+                tokens: rate,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes `n` tokens, returns how long the caller needs to wait for them.
+    fn take(&self, n: u64) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.rate);
+        state.last_refill = now;
+
+        state.tokens -= n as f64;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+
+    async fn acquire(&self, n: u64) {
+        let wait = self.take(n);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RateLimitAccessor {
+    inner: Arc<dyn Accessor>,
+    ops: Option<Arc<TokenBucket>>,
+    bytes: Option<Arc<TokenBucket>>,
+}
+
+impl RateLimitAccessor {
+    async fn acquire(&self, bytes: Option<u64>) {
+        if let Some(ops) = &self.ops {
+            ops.acquire(1).await;
+        }
+        if let (Some(limit), Some(bytes)) = (&self.bytes, bytes) {
+            limit.acquire(bytes).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for RateLimitAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.acquire(None).await;
+        self.inner.create(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, BytesReader)> {
+        self.acquire(args.range().size()).await;
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<RpWrite> {
+        self.acquire(Some(args.size())).await;
+        self.inner.write(path, args, r).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.acquire(None).await;
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.acquire(None).await;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, ObjectPager)> {
+        self.acquire(None).await;
+        self.inner.list(path, args).await
+    }
+
+    fn presign(&self, path: &str, args: OpPresign) -> Result<RpPresign> {
+        self.inner.presign(path, args)
+    }
+
+    async fn create_multipart(
+        &self,
+        path: &str,
+        args: OpCreateMultipart,
+    ) -> Result<RpCreateMultipart> {
+        self.acquire(None).await;
+        self.inner.create_multipart(path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<RpWriteMultipart> {
+        self.acquire(Some(args.size())).await;
+        self.inner.write_multipart(path, args, r).await
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &str,
+        args: OpCompleteMultipart,
+    ) -> Result<RpCompleteMultipart> {
+        self.acquire(None).await;
+        self.inner.complete_multipart(path, args).await
+    }
+
+    async fn abort_multipart(
+        &self,
+        path: &str,
+        args: OpAbortMultipart,
+    ) -> Result<RpAbortMultipart> {
+        self.acquire(None).await;
+        self.inner.abort_multipart(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<RpCreate> {
+        self.inner.blocking_create(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<(RpRead, BlockingBytesReader)> {
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<RpWrite> {
+        self.inner.blocking_write(path, args, r)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<RpDelete> {
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<(RpList, BlockingObjectPager)> {
+        self.inner.blocking_list(path, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let bucket = TokenBucket::new(10);
+        assert_eq!(Duration::ZERO, bucket.take(10));
+
+        // Bucket is in debt of 5 tokens, waits for about half a second.
+        let wait = bucket.take(5);
+        assert!(wait > Duration::from_millis(400), "{wait:?}");
+        assert!(wait <= Duration::from_millis(500), "{wait:?}");
+
+        // Acquiring more than the capacity doesn't block forever.
+        let bucket = TokenBucket::new(1);
+        let wait = bucket.take(3);
+        assert!(wait > Duration::from_millis(1900), "{wait:?}");
+        assert!(wait <= Duration::from_secs(2), "{wait:?}");
+    }
+}
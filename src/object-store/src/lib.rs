@@ -14,9 +14,10 @@
 
 pub use opendal::raw::SeekableReader;
 pub use opendal::{
-    layers, services, Error, ErrorKind, Layer, Object, ObjectLister, ObjectMetadata, ObjectMode,
+    services, Error, ErrorKind, Layer, Object, ObjectLister, ObjectMetadata, ObjectMode,
     Operator as ObjectStore, Result,
 };
 pub mod backend;
+pub mod layers;
 pub mod test_util;
 pub mod util;
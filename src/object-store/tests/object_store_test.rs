@@ -13,10 +13,12 @@
 // limitations under the License.
 
 use std::env;
+use std::time::Duration;
 
 use anyhow::Result;
 use common_telemetry::logging;
-use object_store::backend::{fs, s3};
+use object_store::backend::{fs, memory, s3};
+use object_store::layers::{FaultInjectionLayer, RateLimitLayer, TimeoutLayer, WriteFault};
 use object_store::test_util::TempFolder;
 use object_store::{util, Object, ObjectLister, ObjectMode, ObjectStore};
 use tempdir::TempDir;
@@ -104,6 +106,63 @@ async fn test_fs_backend() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_layered_backend() -> Result<()> {
+    let store = ObjectStore::new(memory::Builder::default().build()?)
+        .layer(TimeoutLayer::new(Duration::from_secs(10)))
+        .layer(
+            RateLimitLayer::new()
+                .with_ops_per_second(1000)
+                .with_bytes_per_second(1024 * 1024),
+        );
+
+    test_object_crud(&store).await?;
+    test_object_list(&store).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fault_injection() -> Result<()> {
+    let layer = FaultInjectionLayer::with_path_filter("data/");
+    let store = ObjectStore::new(memory::Builder::default().build()?).layer(layer.clone());
+
+    // Writes to other paths are not counted.
+    store.object("meta/a").write("a").await?;
+    store.object("data/0").write("0").await?;
+    assert_eq!(1, layer.writes());
+
+    layer.inject(1, WriteFault::Torn(3));
+    assert!(store.object("data/1").write("Hello, World!").await.is_err());
+    assert!(layer.crashed());
+    assert_eq!(
+        "Hel",
+        String::from_utf8(store.object("data/1").read().await?)?
+    );
+    // All writes fail after crashed, reads are still served.
+    assert!(store.object("meta/b").write("b").await.is_err());
+    assert!(store.object("data/0").delete().await.is_err());
+    assert_eq!(
+        "a",
+        String::from_utf8(store.object("meta/a").read().await?)?
+    );
+
+    layer.recover();
+    layer.inject(2, WriteFault::Fail);
+    layer.inject(3, WriteFault::FailAfterWrite);
+    assert!(store.object("data/2").write("2").await.is_err());
+    assert!(store.object("data/2").read().await.is_err());
+    layer.recover();
+    assert!(store.object("data/3").write("3").await.is_err());
+    assert_eq!(
+        "3",
+        String::from_utf8(store.object("data/3").read().await?)?
+    );
+    assert_eq!(4, layer.writes());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_s3_backend() -> Result<()> {
     logging::init_default_ut_logging();
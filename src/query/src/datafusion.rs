@@ -24,12 +24,16 @@ use catalog::CatalogListRef;
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_function::scalars::udf::create_udf;
 use common_function::scalars::FunctionRef;
+use common_query::logical_plan::FunctionLimits;
 use common_query::physical_plan::{DfPhysicalPlanAdapter, PhysicalPlan, PhysicalPlanAdapter};
 use common_query::prelude::ScalarUdf;
 use common_query::Output;
 use common_recordbatch::adapter::RecordBatchStreamAdapter;
 use common_recordbatch::{EmptyRecordBatchStream, SendableRecordBatchStream};
 use common_telemetry::timer;
+use common_telemetry::tracing::{info_span, Instrument};
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizerRule;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion::physical_plan::ExecutionPlan;
 use session::context::QueryContextRef;
@@ -47,19 +51,38 @@ use crate::physical_optimizer::PhysicalOptimizer;
 use crate::physical_planner::PhysicalPlanner;
 use crate::plan::LogicalPlan;
 use crate::planner::Planner;
-use crate::query_engine::{QueryEngineContext, QueryEngineState};
+use crate::query_engine::plan_cache::PlanCacheKey;
+use crate::query_engine::{QueryEngineContext, QueryEngineState, QueryId, QueryStatus};
 use crate::{metric, QueryEngine};
 
 pub(crate) struct DatafusionQueryEngine {
     state: QueryEngineState,
+    /// Limits of executing user-defined functions.
+    function_limits: FunctionLimits,
 }
 
 impl DatafusionQueryEngine {
-    pub fn new(catalog_list: CatalogListRef) -> Self {
+    pub fn new(catalog_list: CatalogListRef, query_memory_limit: Option<usize>) -> Self {
         Self {
-            state: QueryEngineState::new(catalog_list.clone()),
+            state: QueryEngineState::new(catalog_list, query_memory_limit),
+            function_limits: FunctionLimits::default(),
         }
     }
+
+    /// Registers a builtin aggregate function, which is executed without limits.
+    pub(crate) fn register_builtin_aggregate_function(&self, func: AggregateFunctionMetaRef) {
+        self.state.register_aggregate_function(func);
+    }
+
+    async fn execute_plan(&self, plan: &LogicalPlan) -> Result<Output> {
+        let mut ctx = QueryEngineContext::new(self.state.clone());
+        let logical_plan = self.optimize_logical_plan(&mut ctx, plan)?;
+        let physical_plan = self.create_physical_plan(&mut ctx, &logical_plan).await?;
+        let physical_plan = self.optimize_physical_plan(&mut ctx, physical_plan)?;
+
+        let stream = self.execute_stream(&ctx, &physical_plan).await?;
+        Ok(Output::Stream(ctx.track_stream(stream)))
+    }
 }
 
 // TODO(LFC): Refactor consideration: extract a "Planner" that stores query context and execute queries inside.
@@ -81,10 +104,31 @@ impl QueryEngine for DatafusionQueryEngine {
         stmt: Statement,
         query_ctx: QueryContextRef,
     ) -> Result<LogicalPlan> {
+        // Only plans of queries are cached, others are cheap to plan or executed once.
+        let cache_key = match &stmt {
+            Statement::Query(query) if self.state.plan_cache_enabled() => Some(PlanCacheKey {
+                sql: query.inner.to_string(),
+                schema: query_ctx.current_schema(),
+                time_zone: query_ctx.time_zone().map(|tz| tz.to_string()),
+            }),
+            _ => None,
+        };
+        if let Some(plan) = cache_key
+            .as_ref()
+            .and_then(|key| self.state.cached_plan(key))
+        {
+            return Ok(plan);
+        }
+
+        let time_zone = query_ctx.time_zone();
         let context_provider = DfContextProviderAdapter::new(self.state.clone(), query_ctx);
-        let planner = DfPlanner::new(&context_provider);
+        let planner = DfPlanner::new(&context_provider, time_zone);
 
-        planner.statement_to_plan(stmt)
+        let plan = planner.statement_to_plan(stmt)?;
+        if let (Some(key), Some(tables)) = (cache_key, context_provider.referenced_tables()) {
+            self.state.cache_plan(key, plan.clone(), tables);
+        }
+        Ok(plan)
     }
 
     fn sql_to_plan(&self, sql: &str, query_ctx: QueryContextRef) -> Result<LogicalPlan> {
@@ -94,23 +138,20 @@ impl QueryEngine for DatafusionQueryEngine {
     }
 
     async fn execute(&self, plan: &LogicalPlan) -> Result<Output> {
-        let mut ctx = QueryEngineContext::new(self.state.clone());
-        let logical_plan = self.optimize_logical_plan(&mut ctx, plan)?;
-        let physical_plan = self.create_physical_plan(&mut ctx, &logical_plan).await?;
-        let physical_plan = self.optimize_physical_plan(&mut ctx, physical_plan)?;
-
-        Ok(Output::Stream(
-            self.execute_stream(&ctx, &physical_plan).await?,
-        ))
+        self.execute_plan(plan)
+            .instrument(info_span!("query_execute"))
+            .await
     }
 
     async fn execute_physical(&self, plan: &Arc<dyn PhysicalPlan>) -> Result<Output> {
         let ctx = QueryEngineContext::new(self.state.clone());
-        Ok(Output::Stream(self.execute_stream(&ctx, plan).await?))
+        let stream = self.execute_stream(&ctx, plan).await?;
+        Ok(Output::Stream(ctx.track_stream(stream)))
     }
 
     fn register_udf(&self, udf: ScalarUdf) {
-        self.state.register_udf(udf);
+        self.state
+            .register_udf(udf.with_limits(self.function_limits));
     }
 
     /// Note in SQL queries, aggregate names are looked up using
@@ -121,11 +162,37 @@ impl QueryEngine for DatafusionQueryEngine {
     ///
     /// So it's better to make UDAF name lowercase when creating one.
     fn register_aggregate_function(&self, func: AggregateFunctionMetaRef) {
+        let func = if func.limits().is_some() {
+            func
+        } else {
+            Arc::new((*func).clone().with_limits(self.function_limits))
+        };
         self.state.register_aggregate_function(func);
     }
 
     fn register_function(&self, func: FunctionRef) {
-        self.state.register_udf(create_udf(func));
+        self.state
+            .register_udf(create_udf(func).with_limits(self.function_limits));
+    }
+
+    fn register_optimizer_rule(&self, rule: Arc<dyn OptimizerRule + Send + Sync>) {
+        self.state.register_optimizer_rule(rule);
+    }
+
+    fn register_physical_optimizer_rule(&self, rule: Arc<dyn PhysicalOptimizerRule + Send + Sync>) {
+        self.state.register_physical_optimizer_rule(rule);
+    }
+
+    fn running_queries(&self) -> Vec<QueryStatus> {
+        self.state.query_tracker().running_queries()
+    }
+
+    fn cancel(&self, query_id: QueryId) -> Result<()> {
+        self.state.query_tracker().cancel(query_id)
+    }
+
+    fn invalidate_plan_cache(&self) {
+        self.state.clear_plan_cache();
     }
 }
 
@@ -213,10 +280,13 @@ impl QueryExecutor for DatafusionQueryEngine {
         plan: &Arc<dyn PhysicalPlan>,
     ) -> Result<SendableRecordBatchStream> {
         let _timer = timer!(metric::METRIC_EXEC_PLAN_ELAPSED);
+        let task_ctx = ctx.task_ctx().context(error::DatafusionSnafu {
+            msg: "Failed to create the task context of the query",
+        })?;
         match plan.output_partitioning().partition_count() {
             0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
             1 => Ok(plan
-                .execute(0, ctx.state().task_ctx())
+                .execute(0, task_ctx)
                 .context(error::ExecutePhysicalPlanSnafu)?),
             _ => {
                 // merge into a single partition
@@ -224,11 +294,9 @@ impl QueryExecutor for DatafusionQueryEngine {
                     CoalescePartitionsExec::new(Arc::new(DfPhysicalPlanAdapter(plan.clone())));
                 // CoalescePartitionsExec must produce a single partition
                 assert_eq!(1, plan.output_partitioning().partition_count());
-                let df_stream =
-                    plan.execute(0, ctx.state().task_ctx())
-                        .context(error::DatafusionSnafu {
-                            msg: "Failed to execute DataFusion merge exec",
-                        })?;
+                let df_stream = plan.execute(0, task_ctx).context(error::DatafusionSnafu {
+                    msg: "Failed to execute DataFusion merge exec",
+                })?;
                 let stream = RecordBatchStreamAdapter::try_new(df_stream)
                     .context(error::ConvertDfRecordBatchStreamSnafu)?;
                 Ok(Box::pin(stream))
@@ -239,13 +307,18 @@ impl QueryExecutor for DatafusionQueryEngine {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
     use catalog::local::{MemoryCatalogProvider, MemorySchemaProvider};
     use catalog::{CatalogList, CatalogProvider, SchemaProvider};
     use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use common_error::prelude::*;
     use common_query::Output;
     use common_recordbatch::util;
+    use datafusion::optimizer::optimizer::OptimizerRule;
+    use datafusion::optimizer::OptimizerConfig;
+    use datafusion_expr::LogicalPlan as DfLogicalPlan;
     use datatypes::vectors::{UInt64Vector, VectorRef};
     use session::context::QueryContext;
     use table::table::numbers::NumbersTable;
@@ -253,6 +326,12 @@ mod tests {
     use crate::query_engine::{QueryEngineFactory, QueryEngineRef};
 
     fn create_test_engine() -> QueryEngineRef {
+        create_test_engine_with_schema(None).0
+    }
+
+    fn create_test_engine_with_schema(
+        query_memory_limit: Option<usize>,
+    ) -> (QueryEngineRef, Arc<MemorySchemaProvider>) {
         let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
 
         let default_schema = Arc::new(MemorySchemaProvider::new());
@@ -261,13 +340,15 @@ mod tests {
             .unwrap();
         let default_catalog = Arc::new(MemoryCatalogProvider::new());
         default_catalog
-            .register_schema(DEFAULT_SCHEMA_NAME.to_string(), default_schema)
+            .register_schema(DEFAULT_SCHEMA_NAME.to_string(), default_schema.clone())
             .unwrap();
         catalog_list
             .register_catalog(DEFAULT_CATALOG_NAME.to_string(), default_catalog)
             .unwrap();
 
-        QueryEngineFactory::new(catalog_list).query_engine()
+        let engine = QueryEngineFactory::new_with_memory_limit(catalog_list, query_memory_limit)
+            .query_engine();
+        (engine, default_schema)
     }
 
     #[test]
@@ -289,6 +370,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_plan_cache() {
+        let (engine, schema) = create_test_engine_with_schema(None);
+        let query_ctx = Arc::new(QueryContext::new());
+        let plan = engine
+            .sql_to_plan("select number from numbers", query_ctx.clone())
+            .unwrap();
+        let cached = engine
+            .sql_to_plan("SELECT  number\nFROM numbers", query_ctx.clone())
+            .unwrap();
+        assert_eq!(format!("{plan:?}"), format!("{cached:?}"));
+
+        // The cached plan is stale once the table is dropped.
+        let _ = schema.deregister_table("numbers").unwrap();
+        assert!(engine
+            .sql_to_plan("select number from numbers", query_ctx.clone())
+            .is_err());
+
+        schema
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::new(42)))
+            .unwrap();
+        engine.invalidate_plan_cache();
+        let plan = engine
+            .sql_to_plan("select number from numbers", query_ctx)
+            .unwrap();
+        assert_eq!(format!("{cached:?}"), format!("{plan:?}"));
+    }
+
     #[tokio::test]
     async fn test_execute() {
         let engine = create_test_engine();
@@ -323,4 +432,96 @@ mod tests {
             _ => unreachable!(),
         }
     }
+    #[tokio::test]
+    async fn test_cancel_query() {
+        let engine = create_test_engine();
+        let sql = "select number from numbers";
+        let plan = engine
+            .sql_to_plan(sql, Arc::new(QueryContext::new()))
+            .unwrap();
+        assert!(engine.running_queries().is_empty());
+
+        let output = engine.execute(&plan).await.unwrap();
+        let running_queries = engine.running_queries();
+        assert_eq!(1, running_queries.len());
+        let query_id = running_queries[0].query_id;
+        engine.cancel(query_id).unwrap();
+
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let err = util::collect(stream).await.unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+        // The cancelled query is removed once its stream is dropped.
+        assert!(engine.running_queries().is_empty());
+        assert!(engine.cancel(query_id).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_memory_limit() {
+        let (engine, _) = create_test_engine_with_schema(Some(1));
+        let sql = "select number, count(*) from numbers group by number";
+        let plan = engine
+            .sql_to_plan(sql, Arc::new(QueryContext::new()))
+            .unwrap();
+        let Output::Stream(stream) = engine.execute(&plan).await.unwrap() else {
+            unreachable!()
+        };
+        let err = util::collect(stream).await.unwrap_err();
+        assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+        assert!(engine.running_queries().is_empty());
+
+        // Queries without buffering rows are not limited.
+        let sql = "select number from numbers";
+        let plan = engine
+            .sql_to_plan(sql, Arc::new(QueryContext::new()))
+            .unwrap();
+        let Output::Stream(stream) = engine.execute(&plan).await.unwrap() else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(100, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    }
+
+    /// Counts the plans it's applied to.
+    #[derive(Default)]
+    struct CountingRule {
+        count: AtomicUsize,
+    }
+
+    impl OptimizerRule for CountingRule {
+        fn try_optimize(
+            &self,
+            _plan: &DfLogicalPlan,
+            _config: &dyn OptimizerConfig,
+        ) -> datafusion_common::Result<Option<DfLogicalPlan>> {
+            self.count.fetch_add(1, Ordering::Relaxed);
+            Ok(None)
+        }
+
+        fn name(&self) -> &str {
+            "CountingRule"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_optimizer_rule() {
+        let engine = create_test_engine();
+        let rule = Arc::new(CountingRule::default());
+        engine.register_optimizer_rule(rule.clone());
+
+        let sql = "select number from numbers limit 5";
+        let plan = engine
+            .sql_to_plan(sql, Arc::new(QueryContext::new()))
+            .unwrap();
+        assert_eq!(0, rule.count.load(Ordering::Relaxed));
+
+        let output = engine.execute(&plan).await.unwrap();
+        let Output::Stream(stream) = output else {
+            unreachable!()
+        };
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(5, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+        assert!(rule.count.load(Ordering::Relaxed) > 0);
+    }
 }
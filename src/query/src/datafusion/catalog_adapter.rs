@@ -18,9 +18,11 @@ use std::any::Any;
 use std::sync::Arc;
 
 use catalog::error::Error;
+use catalog::information_schema::InformationSchemaProvider;
 use catalog::{
     CatalogListRef, CatalogProvider, CatalogProviderRef, SchemaProvider, SchemaProviderRef,
 };
+use common_catalog::consts::INFORMATION_SCHEMA_NAME;
 use datafusion::catalog::catalog::{
     CatalogList as DfCatalogList, CatalogProvider as DfCatalogProvider,
 };
@@ -57,9 +59,14 @@ impl DfCatalogList for DfCatalogListAdapter {
             df_catalog_provider: catalog,
         });
         self.catalog_list
-            .register_catalog(name, catalog_adapter)
+            .register_catalog(name.clone(), catalog_adapter)
             .expect("datafusion does not accept fallible catalog access") // TODO(hl): datafusion register catalog does not handles errors
-            .map(|catalog_provider| Arc::new(DfCatalogProviderAdapter { catalog_provider }) as _)
+            .map(|catalog_provider| {
+                Arc::new(DfCatalogProviderAdapter {
+                    catalog_name: name,
+                    catalog_provider,
+                }) as _
+            })
     }
 
     fn catalog_names(&self) -> Vec<String> {
@@ -73,7 +80,12 @@ impl DfCatalogList for DfCatalogListAdapter {
         self.catalog_list
             .catalog(name)
             .expect("datafusion does not accept fallible catalog access") // TODO(hl): datafusion register catalog does not handles errors
-            .map(|catalog_provider| Arc::new(DfCatalogProviderAdapter { catalog_provider }) as _)
+            .map(|catalog_provider| {
+                Arc::new(DfCatalogProviderAdapter {
+                    catalog_name: name.to_string(),
+                    catalog_provider,
+                }) as _
+            })
     }
 }
 
@@ -108,7 +120,11 @@ impl CatalogProvider for CatalogProviderAdapter {
 }
 
 ///Greptime CatalogProvider -> datafusion's CatalogProvider
+///
+/// Catalogs without their own `information_schema` are provided with the virtual
+/// one in [InformationSchemaProvider].
 struct DfCatalogProviderAdapter {
+    catalog_name: String,
     catalog_provider: CatalogProviderRef,
 }
 
@@ -118,16 +134,33 @@ impl DfCatalogProvider for DfCatalogProviderAdapter {
     }
 
     fn schema_names(&self) -> Vec<String> {
-        self.catalog_provider
+        let mut schema_names = self
+            .catalog_provider
             .schema_names()
-            .expect("datafusion does not accept fallible catalog access")
+            .expect("datafusion does not accept fallible catalog access");
+        if !schema_names
+            .iter()
+            .any(|name| name == INFORMATION_SCHEMA_NAME)
+        {
+            schema_names.push(INFORMATION_SCHEMA_NAME.to_string());
+        }
+        schema_names
     }
 
     fn schema(&self, name: &str) -> Option<Arc<dyn DfSchemaProvider>> {
-        self.catalog_provider
+        let schema_provider = self
+            .catalog_provider
             .schema(name)
-            .expect("datafusion does not accept fallible catalog access")
-            .map(|schema_provider| Arc::new(DfSchemaProviderAdapter { schema_provider }) as _)
+            .expect("datafusion does not accept fallible catalog access");
+        let schema_provider = match schema_provider {
+            Some(schema_provider) => schema_provider,
+            None if name == INFORMATION_SCHEMA_NAME => Arc::new(InformationSchemaProvider::new(
+                self.catalog_name.clone(),
+                self.catalog_provider.clone(),
+            )),
+            None => return None,
+        };
+        Some(Arc::new(DfSchemaProviderAdapter { schema_provider }))
     }
 }
 
@@ -249,6 +282,7 @@ impl SchemaProvider for SchemaProviderAdapter {
 #[cfg(test)]
 mod tests {
     use catalog::local::{new_memory_catalog_list, MemoryCatalogProvider, MemorySchemaProvider};
+    use common_catalog::consts::DEFAULT_CATALOG_NAME;
     use table::table::numbers::NumbersTable;
 
     use super::*;
@@ -296,6 +330,7 @@ mod tests {
             .register_catalog(
                 "test_catalog".to_string(),
                 Arc::new(DfCatalogProviderAdapter {
+                    catalog_name: "test_catalog".to_string(),
                     catalog_provider: Arc::new(MemoryCatalogProvider::new()),
                 }),
             )
@@ -303,4 +338,22 @@ mod tests {
 
         catalog_list.catalog("test_catalog").unwrap();
     }
+
+    #[test]
+    pub fn test_information_schema() {
+        let catalog_list = DfCatalogListAdapter {
+            catalog_list: new_memory_catalog_list().unwrap(),
+        };
+        let catalog = catalog_list.catalog(DEFAULT_CATALOG_NAME).unwrap();
+        assert!(catalog
+            .schema_names()
+            .contains(&INFORMATION_SCHEMA_NAME.to_string()));
+
+        let schema = catalog.schema(INFORMATION_SCHEMA_NAME).unwrap();
+        assert!(schema.table("tables").is_some());
+        assert!(schema.table("columns").is_some());
+        assert!(schema.table("engines").is_some());
+        assert!(schema.table("not_exists").is_none());
+        assert!(catalog.schema("not_exists").is_none());
+    }
 }
@@ -12,16 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::logical_plan::create_aggregate_function;
+use common_time::TimeZone;
 use datafusion::catalog::TableReference;
 use datafusion::error::Result as DfResult;
 use datafusion::physical_plan::udaf::AggregateUDF;
 use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::sql::planner::{ContextProvider, PlannerContext, SqlToRel};
 use datafusion_common::ScalarValue;
-use datafusion_expr::TableSource;
+use datafusion_expr::{LogicalPlan as DfLogicalPlan, TableSource};
 use datatypes::arrow::datatypes::DataType;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
@@ -31,19 +33,26 @@ use sql::statements::statement::Statement;
 
 use crate::datafusion::error;
 use crate::error::Result;
+use crate::optimizer::TypeConversionRule;
 use crate::plan::LogicalPlan;
 use crate::planner::Planner;
+use crate::query_engine::plan_cache::TableVersion;
 use crate::query_engine::QueryEngineState;
 
 pub struct DfPlanner<'a, S: ContextProvider> {
     sql_to_rel: SqlToRel<'a, S>,
+    /// Time zone of the session, timestamp strings in the query are in this time zone.
+    time_zone: Option<TimeZone>,
 }
 
 impl<'a, S: ContextProvider + Send + Sync> DfPlanner<'a, S> {
     /// Creates a DataFusion planner instance
-    pub fn new(schema_provider: &'a S) -> Self {
+    pub fn new(schema_provider: &'a S, time_zone: Option<TimeZone>) -> Self {
         let rel = SqlToRel::new(schema_provider);
-        Self { sql_to_rel: rel }
+        Self {
+            sql_to_rel: rel,
+            time_zone,
+        }
     }
 
     /// Converts QUERY statement to logical plan.
@@ -53,11 +62,22 @@ impl<'a, S: ContextProvider + Send + Sync> DfPlanner<'a, S> {
         let result = self
             .sql_to_rel
             .query_to_plan(query.inner, &mut PlannerContext::default())
+            .and_then(|plan| self.convert_timestamp_literals(plan))
             .context(error::PlanSqlSnafu { sql })?;
 
         Ok(LogicalPlan::DfPlan(result))
     }
 
+    /// Converts the timestamp strings in the plan in the time zone of the session, the
+    /// ones left are converted in the local time zone by the optimizer.
+    fn convert_timestamp_literals(&self, plan: DfLogicalPlan) -> DfResult<DfLogicalPlan> {
+        if self.time_zone.is_none() {
+            return Ok(plan);
+        }
+        let rule = TypeConversionRule::with_time_zone(self.time_zone);
+        Ok(rule.convert_plan(&plan)?.unwrap_or(plan))
+    }
+
     /// Converts EXPLAIN statement to logical plan.
     pub fn explain_to_plan(&self, explain: Explain) -> Result<LogicalPlan> {
         let result = self
@@ -83,31 +103,92 @@ where
             Statement::ShowTables(_)
             | Statement::ShowDatabases(_)
             | Statement::ShowCreateTable(_)
+            | Statement::ShowNodes(_)
             | Statement::DescribeTable(_)
             | Statement::CreateTable(_)
             | Statement::CreateDatabase(_)
             | Statement::Alter(_)
             | Statement::Insert(_)
+            | Statement::Delete(_)
+            | Statement::Copy(_)
+            | Statement::CopyTo(_)
             | Statement::DropTable(_)
-            | Statement::Use(_) => unreachable!(),
+            | Statement::CreateUser(_)
+            | Statement::DropUser(_)
+            | Statement::Grant(_)
+            | Statement::CreateTask(_)
+            | Statement::DropTask(_)
+            | Statement::Use(_)
+            | Statement::SetVariable(_)
+            | Statement::Truncate(_) => unreachable!(),
         }
     }
 }
 
+/// Config option of the time zone, e.g. of the timestamps with time zone.
+const TIME_ZONE_OPTION: &str = "datafusion.execution.time_zone";
+
 pub(crate) struct DfContextProviderAdapter {
     state: QueryEngineState,
     query_ctx: QueryContextRef,
+    /// Versions of the tables referenced by the plan, `None` if any of them is unknown.
+    tables: Mutex<Option<Vec<TableVersion>>>,
 }
 
 impl DfContextProviderAdapter {
     pub(crate) fn new(state: QueryEngineState, query_ctx: QueryContextRef) -> Self {
-        Self { state, query_ctx }
+        Self {
+            state,
+            query_ctx,
+            tables: Mutex::new(Some(Vec::new())),
+        }
+    }
+
+    /// Returns the tables referenced by the planned statement, or `None` if the plan
+    /// shouldn't be cached.
+    pub(crate) fn referenced_tables(&self) -> Option<Vec<TableVersion>> {
+        self.tables.lock().unwrap().clone()
+    }
+
+    /// Records the version of a referenced table. It's recorded before the table is
+    /// resolved, so a plan cached while the table is being altered is stale instead of
+    /// being considered up to date.
+    fn record_table(&self, current_schema: Option<&str>, name: TableReference) {
+        let (catalog, schema, table) = match name {
+            TableReference::Bare { table } => (
+                DEFAULT_CATALOG_NAME,
+                current_schema.unwrap_or(DEFAULT_SCHEMA_NAME),
+                table,
+            ),
+            TableReference::Partial { schema, table } => (DEFAULT_CATALOG_NAME, schema, table),
+            TableReference::Full {
+                catalog,
+                schema,
+                table,
+            } => (catalog, schema, table),
+        };
+
+        let mut tables = self.tables.lock().unwrap();
+        let Some(recorded) = tables.as_mut() else {
+            return;
+        };
+        match self.state.table_version(catalog, schema, table) {
+            Some((table_id, schema_version)) => recorded.push(TableVersion {
+                catalog: catalog.to_string(),
+                schema: schema.to_string(),
+                table: table.to_string(),
+                table_id,
+                schema_version,
+            }),
+            None => *tables = None,
+        }
     }
 }
 
 impl ContextProvider for DfContextProviderAdapter {
     fn get_table_provider(&self, name: TableReference) -> DfResult<Arc<dyn TableSource>> {
         let schema = self.query_ctx.current_schema();
+        self.record_table(schema.as_deref(), name);
         self.state.get_table_provider(schema.as_deref(), name)
     }
 
@@ -117,9 +198,12 @@ impl ContextProvider for DfContextProviderAdapter {
 
     fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
         self.state.aggregate_function(name).map(|func| {
-            Arc::new(
-                create_aggregate_function(func.name(), func.args_count(), func.create()).into(),
-            )
+            let udaf = create_aggregate_function(func.name(), func.args_count(), func.create());
+            let udaf = match func.limits() {
+                Some(limits) => udaf.with_limits(limits),
+                None => udaf,
+            };
+            Arc::new(udaf.into())
         })
     }
 
@@ -128,6 +212,37 @@ impl ContextProvider for DfContextProviderAdapter {
     }
 
     fn get_config_option(&self, variable: &str) -> Option<ScalarValue> {
+        // The time zone of the session overrides the default one.
+        if variable == TIME_ZONE_OPTION {
+            if let Some(time_zone) = self.query_ctx.time_zone() {
+                return Some(ScalarValue::Utf8(Some(time_zone.to_string())));
+            }
+        }
         self.state.get_config_option(variable)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+
+    use super::*;
+
+    #[test]
+    fn test_time_zone_option() {
+        let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
+        let state = QueryEngineState::new(catalog_list, None);
+        let query_ctx = Arc::new(QueryContext::new());
+        let adapter = DfContextProviderAdapter::new(state, query_ctx.clone());
+        let default = adapter.get_config_option(TIME_ZONE_OPTION);
+
+        query_ctx.set_time_zone(Some("+08:00".parse().unwrap()));
+        assert_eq!(
+            Some(ScalarValue::Utf8(Some("+08:00".to_string()))),
+            adapter.get_config_option(TIME_ZONE_OPTION)
+        );
+
+        query_ctx.set_time_zone(None);
+        assert_eq!(default, adapter.get_config_option(TIME_ZONE_OPTION));
+    }
+}
@@ -18,6 +18,8 @@ use common_error::prelude::*;
 use datafusion::error::DataFusionError;
 use snafu::{Backtrace, ErrorCompat, Snafu};
 
+use crate::query_engine::QueryId;
+
 common_error::define_opaque_error!(Error);
 
 #[derive(Debug, Snafu)]
@@ -58,6 +60,43 @@ pub enum InnerError {
         #[snafu(backtrace)]
         source: common_recordbatch::error::Error,
     },
+
+    #[snafu(display("Query not found, id: {}", query_id))]
+    QueryNotFound {
+        query_id: QueryId,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Query {} is cancelled", query_id))]
+    QueryCancelled {
+        query_id: QueryId,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Query {} exceeds memory limit {} bytes", query_id, limit))]
+    QueryMemoryExceeded {
+        query_id: QueryId,
+        limit: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to convert the schema of the plan, source: {}", source))]
+    ConvertPlanSchema {
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Failed to filter the output of SHOW statement, source: {}", source))]
+    FilterShowOutput {
+        source: DataFusionError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to restrict the time range of the plan, source: {}", source))]
+    RestrictTimeRange {
+        source: DataFusionError,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for InnerError {
@@ -68,10 +107,16 @@ impl ErrorExt for InnerError {
             UnsupportedExpr { .. }
             | CatalogNotFound { .. }
             | SchemaNotFound { .. }
-            | TableNotFound { .. } => StatusCode::InvalidArguments,
+            | TableNotFound { .. }
+            | QueryNotFound { .. } => StatusCode::InvalidArguments,
+            QueryCancelled { .. } => StatusCode::Cancelled,
+            QueryMemoryExceeded { .. } => StatusCode::RuntimeResourcesExhausted,
             Catalog { source } => source.status_code(),
             VectorComputation { source } => source.status_code(),
             CreateRecordBatch { source } => source.status_code(),
+            ConvertPlanSchema { source } => source.status_code(),
+            RestrictTimeRange { .. } => StatusCode::Internal,
+            FilterShowOutput { .. } => StatusCode::InvalidArguments,
         }
     }
 
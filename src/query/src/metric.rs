@@ -14,8 +14,48 @@
 
 //! query engine metrics
 
-pub static METRIC_PARSE_SQL_ELAPSED: &str = "query.parse_sql_elapsed";
-pub static METRIC_OPTIMIZE_LOGICAL_ELAPSED: &str = "query.optimize_logicalplan_elapsed";
-pub static METRIC_OPTIMIZE_PHYSICAL_ELAPSED: &str = "query.optimize_physicalplan_elapsed";
-pub static METRIC_CREATE_PHYSICAL_ELAPSED: &str = "query.create_physicalplan_elapsed";
-pub static METRIC_EXEC_PLAN_ELAPSED: &str = "query.execute_plan_elapsed";
+use common_telemetry::metric::{self, MetricDesc};
+
+pub const METRIC_PARSE_SQL_ELAPSED: &str = "query.parse_sql_elapsed";
+pub const METRIC_OPTIMIZE_LOGICAL_ELAPSED: &str = "query.optimize_logicalplan_elapsed";
+pub const METRIC_OPTIMIZE_PHYSICAL_ELAPSED: &str = "query.optimize_physicalplan_elapsed";
+pub const METRIC_CREATE_PHYSICAL_ELAPSED: &str = "query.create_physicalplan_elapsed";
+pub const METRIC_EXEC_PLAN_ELAPSED: &str = "query.execute_plan_elapsed";
+pub const METRIC_PLAN_CACHE_HITS_TOTAL: &str = "query.plan_cache_hits_total";
+pub const METRIC_PLAN_CACHE_MISSES_TOTAL: &str = "query.plan_cache_misses_total";
+
+const METRICS: &[MetricDesc] = &[
+    MetricDesc::histogram(
+        METRIC_PARSE_SQL_ELAPSED,
+        "Elapsed time of parsing SQL in seconds",
+    ),
+    MetricDesc::histogram(
+        METRIC_OPTIMIZE_LOGICAL_ELAPSED,
+        "Elapsed time of optimizing logical plans in seconds",
+    ),
+    MetricDesc::histogram(
+        METRIC_OPTIMIZE_PHYSICAL_ELAPSED,
+        "Elapsed time of optimizing physical plans in seconds",
+    ),
+    MetricDesc::histogram(
+        METRIC_CREATE_PHYSICAL_ELAPSED,
+        "Elapsed time of creating physical plans in seconds",
+    ),
+    MetricDesc::histogram(
+        METRIC_EXEC_PLAN_ELAPSED,
+        "Elapsed time of starting the execution of plans in seconds",
+    ),
+    MetricDesc::counter(
+        METRIC_PLAN_CACHE_HITS_TOTAL,
+        "Number of queries whose logical plans are found in the plan cache",
+    ),
+    MetricDesc::counter(
+        METRIC_PLAN_CACHE_MISSES_TOTAL,
+        "Number of queries whose logical plans are not found in the plan cache or stale",
+    ),
+];
+
+/// Registers the metrics of the query engine, it's called when the engine is created.
+pub(crate) fn register_metrics() {
+    metric::register_metrics(METRICS);
+}
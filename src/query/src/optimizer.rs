@@ -12,26 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::str::FromStr;
+mod limit;
+mod simplify;
+mod time_range;
+mod timestamp_arithmetic;
+
 use std::sync::Arc;
 
 use common_time::timestamp::{TimeUnit, Timestamp};
+use common_time::TimeZone;
 use datafusion::optimizer::optimizer::OptimizerRule;
 use datafusion::optimizer::OptimizerConfig;
 use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
 use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
 use datafusion_expr::{
-    Between, BinaryExpr, Expr, ExprSchemable, Filter, LogicalPlan, Operator, TableScan,
+    Between, BinaryExpr, Expr, ExprSchemable, Filter, LogicalPlan, Operator, Subquery, TableScan,
 };
 use datatypes::arrow::compute;
 use datatypes::arrow::datatypes::DataType;
+pub use limit::OrderedLimitPushDownRule;
+pub use simplify::PredicateSimplificationRule;
+pub use time_range::TimeRangeFilterPushDownRule;
+pub use timestamp_arithmetic::TimestampArithmeticFoldingRule;
 
 /// TypeConversionRule converts some literal values in logical plan to other types according
 /// to data type of corresponding columns.
 /// Specifically:
 /// - string literal of timestamp is converted to `Expr::Literal(ScalarValue::TimestampMillis)`
 /// - string literal of boolean is converted to `Expr::Literal(ScalarValue::Boolean)`
-pub struct TypeConversionRule;
+#[derive(Default)]
+pub struct TypeConversionRule {
+    /// Time zone of the timestamp strings without an explicit offset, they are in the
+    /// local time zone if it's `None`.
+    time_zone: Option<TimeZone>,
+}
 
 impl OptimizerRule for TypeConversionRule {
     fn try_optimize(
@@ -39,14 +53,33 @@ impl OptimizerRule for TypeConversionRule {
         plan: &LogicalPlan,
         _config: &dyn OptimizerConfig,
     ) -> Result<Option<LogicalPlan>> {
+        self.convert_plan(plan)
+    }
+
+    fn name(&self) -> &str {
+        "TypeConversionRule"
+    }
+}
+
+impl TypeConversionRule {
+    /// Creates a rule that converts the timestamp strings in the `time_zone`, e.g. the
+    /// time zone of the session.
+    pub fn with_time_zone(time_zone: Option<TimeZone>) -> Self {
+        Self { time_zone }
+    }
+
+    pub(crate) fn convert_plan(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
         let mut converter = TypeConverter {
             schemas: plan.all_schemas(),
+            time_zone: self.time_zone,
         };
 
         match plan {
             LogicalPlan::Filter(filter) => {
                 let rewritten = filter.predicate().clone().rewrite(&mut converter)?;
-                let Some(plan) = self.try_optimize(filter.input(), _config)? else { return Ok(None) };
+                let Some(plan) = self.convert_plan(filter.input())? else {
+                    return Ok(None);
+                };
                 Ok(Some(LogicalPlan::Filter(Filter::try_new(
                     rewritten,
                     Arc::new(plan),
@@ -74,8 +107,16 @@ impl OptimizerRule for TypeConversionRule {
                     fetch: *fetch,
                 })))
             }
+            // Window expressions are referenced by their names in the plans above, which
+            // would be changed by rewriting literals in them, so only the input is optimized.
+            LogicalPlan::Window { .. } => {
+                let inputs = plan.inputs();
+                let Some(input) = self.convert_plan(inputs[0])? else {
+                    return Ok(None);
+                };
+                datafusion_expr::utils::from_plan(plan, &plan.expressions(), &[input]).map(Some)
+            }
             LogicalPlan::Projection { .. }
-            | LogicalPlan::Window { .. }
             | LogicalPlan::Aggregate { .. }
             | LogicalPlan::Repartition { .. }
             | LogicalPlan::CreateExternalTable { .. }
@@ -92,11 +133,15 @@ impl OptimizerRule for TypeConversionRule {
             | LogicalPlan::Distinct { .. }
             | LogicalPlan::Values { .. }
             | LogicalPlan::SetVariable { .. }
-            | LogicalPlan::Analyze { .. } => {
+            | LogicalPlan::Analyze { .. }
+            | LogicalPlan::Subquery { .. }
+            | LogicalPlan::SubqueryAlias { .. } => {
                 let inputs = plan.inputs();
                 let mut new_inputs = Vec::with_capacity(inputs.len());
                 for input in inputs {
-                    let Some(plan) = self.try_optimize(input, _config)? else { return Ok(None) };
+                    let Some(plan) = self.convert_plan(input)? else {
+                        return Ok(None);
+                    };
                     new_inputs.push(plan);
                 }
 
@@ -109,9 +154,7 @@ impl OptimizerRule for TypeConversionRule {
                 datafusion_expr::utils::from_plan(plan, &expr, &new_inputs).map(Some)
             }
 
-            LogicalPlan::Subquery { .. }
-            | LogicalPlan::SubqueryAlias { .. }
-            | LogicalPlan::CreateView { .. }
+            LogicalPlan::CreateView { .. }
             | LogicalPlan::CreateCatalogSchema { .. }
             | LogicalPlan::CreateCatalog { .. }
             | LogicalPlan::EmptyRelation(_)
@@ -119,17 +162,30 @@ impl OptimizerRule for TypeConversionRule {
         }
     }
 
-    fn name(&self) -> &str {
-        "TypeConversionRule"
+    /// Converts literals in the plan of `subquery`, the plan is not visited when
+    /// rewriting the expression that contains the subquery.
+    fn convert_subquery(&self, subquery: Subquery) -> Result<Subquery> {
+        match self.convert_plan(&subquery.subquery)? {
+            Some(plan) => Ok(Subquery {
+                subquery: Arc::new(plan),
+            }),
+            None => Ok(subquery),
+        }
     }
 }
 
 struct TypeConverter<'a> {
     schemas: Vec<&'a DFSchemaRef>,
+    time_zone: Option<TimeZone>,
 }
 
 impl<'a> TypeConverter<'a> {
     fn column_type(&self, expr: &Expr) -> Option<DataType> {
+        // Time buckets have the type of the timestamp column, which allows literals
+        // compared with them to be pushed down as time ranges.
+        if let Some((ts, _)) = time_range::time_bucket_args(expr) {
+            return self.column_type(ts);
+        }
         if let Expr::Column(_) = expr {
             for schema in &self.schemas {
                 if let Ok(v) = expr.get_type(schema) {
@@ -140,9 +196,19 @@ impl<'a> TypeConverter<'a> {
         None
     }
 
-    fn cast_scalar_value(value: &ScalarValue, target_type: &DataType) -> Result<ScalarValue> {
+    fn rule(&self) -> TypeConversionRule {
+        TypeConversionRule::with_time_zone(self.time_zone)
+    }
+
+    fn cast_scalar_value(
+        &self,
+        value: &ScalarValue,
+        target_type: &DataType,
+    ) -> Result<ScalarValue> {
         match (target_type, value) {
-            (DataType::Timestamp(_, _), ScalarValue::Utf8(Some(v))) => string_to_timestamp_ms(v),
+            (DataType::Timestamp(_, _), ScalarValue::Utf8(Some(v))) => {
+                string_to_timestamp_ms(v, self.time_zone.as_ref())
+            }
             (DataType::Boolean, ScalarValue::Utf8(Some(v))) => match v.to_lowercase().as_str() {
                 "true" => Ok(ScalarValue::Boolean(Some(true))),
                 "false" => Ok(ScalarValue::Boolean(Some(false))),
@@ -178,7 +244,7 @@ impl<'a> TypeConverter<'a> {
 
         match (left, right) {
             (Expr::Column(col), Expr::Literal(value)) => {
-                let casted_right = Self::cast_scalar_value(value, left_type)?;
+                let casted_right = self.cast_scalar_value(value, left_type)?;
                 if casted_right.is_null() {
                     return Err(DataFusionError::Plan(format!(
                         "column:{col:?} value:{value:?} is invalid",
@@ -190,6 +256,16 @@ impl<'a> TypeConverter<'a> {
                     Ok((left.clone(), Expr::Literal(casted_right)))
                 }
             }
+            (bucket, Expr::Literal(value @ ScalarValue::Utf8(Some(_))))
+                if matches!(left_type, DataType::Timestamp(_, _)) =>
+            {
+                let casted_right = Expr::Literal(self.cast_scalar_value(value, left_type)?);
+                if reverse {
+                    Ok((casted_right, bucket.clone()))
+                } else {
+                    Ok((bucket.clone(), casted_right))
+                }
+            }
             _ => Ok((left.clone(), right.clone())),
         }
     }
@@ -245,6 +321,24 @@ impl<'a> ExprRewriter for TypeConverter<'a> {
                     negated,
                 }
             }
+            // Literals in subqueries are converted before DataFusion's type coercion
+            // rewrites them, as subqueries are only decorrelated into joins later.
+            Expr::ScalarSubquery(subquery) => {
+                Expr::ScalarSubquery(self.rule().convert_subquery(subquery)?)
+            }
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Expr::InSubquery {
+                expr,
+                subquery: self.rule().convert_subquery(subquery)?,
+                negated,
+            },
+            Expr::Exists { subquery, negated } => Expr::Exists {
+                subquery: self.rule().convert_subquery(subquery)?,
+                negated,
+            },
             Expr::Literal(value) => match value {
                 ScalarValue::TimestampSecond(Some(i), _) => {
                     timestamp_to_timestamp_ms_expr(i, TimeUnit::Second)
@@ -278,11 +372,11 @@ fn timestamp_to_timestamp_ms_expr(val: i64, unit: TimeUnit) -> Expr {
     Expr::Literal(ScalarValue::TimestampMillisecond(Some(timestamp), None))
 }
 
-fn string_to_timestamp_ms(string: &str) -> Result<ScalarValue> {
+fn string_to_timestamp_ms(string: &str, time_zone: Option<&TimeZone>) -> Result<ScalarValue> {
     Ok(ScalarValue::TimestampMillisecond(
         Some(
-            Timestamp::from_str(string)
-                .map(|t| t.value() / 1_000_000)
+            Timestamp::from_str_with_time_zone(string, time_zone)
+                .map(|t| t.convert_to(TimeUnit::Millisecond))
                 .map_err(|e| DataFusionError::External(Box::new(e)))?,
         ),
         None,
@@ -300,13 +394,27 @@ mod tests {
     #[test]
     fn test_string_to_timestamp_ms() {
         assert!(matches!(
-            string_to_timestamp_ms("2022-02-02 19:00:00+08:00").unwrap(),
+            string_to_timestamp_ms("2022-02-02 19:00:00+08:00", None).unwrap(),
             ScalarValue::TimestampMillisecond(Some(1643799600000), None)
         ));
         assert!(matches!(
-            string_to_timestamp_ms("2009-02-13 23:31:30Z").unwrap(),
+            string_to_timestamp_ms("2009-02-13 23:31:30Z", None).unwrap(),
             ScalarValue::TimestampMillisecond(Some(1234567890000), None)
         ));
+        assert!(matches!(
+            string_to_timestamp_ms("1234567890", None).unwrap(),
+            ScalarValue::TimestampMillisecond(Some(1234567890000), None)
+        ));
+        assert!(matches!(
+            string_to_timestamp_ms("1234567890.123", None).unwrap(),
+            ScalarValue::TimestampMillisecond(Some(1234567890123), None)
+        ));
+
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        assert!(matches!(
+            string_to_timestamp_ms("2022-02-02 19:00:00", Some(&time_zone)).unwrap(),
+            ScalarValue::TimestampMillisecond(Some(1643799600000), None)
+        ));
     }
 
     #[test]
@@ -364,6 +472,7 @@ mod tests {
         );
         let mut converter = TypeConverter {
             schemas: vec![&schema_ref],
+            time_zone: None,
         };
 
         assert_eq!(
@@ -378,6 +487,27 @@ mod tests {
                 )
                 .unwrap()
         );
+
+        let bucket = Expr::ScalarFunction {
+            fun: datafusion_expr::BuiltinScalarFunction::DateBin,
+            args: vec![
+                Expr::Literal(ScalarValue::IntervalDayTime(Some(60_000))),
+                Expr::Column(Column::from_name("ts")),
+                Expr::Literal(ScalarValue::TimestampMillisecond(Some(0), None)),
+            ],
+        };
+        assert_eq!(
+            Expr::Literal(ScalarValue::TimestampMillisecond(Some(1599514949000), None))
+                .lt_eq(bucket.clone()),
+            converter
+                .mutate(
+                    Expr::Literal(ScalarValue::Utf8(Some(
+                        "2020-09-08T05:42:29+08:00".to_string()
+                    )))
+                    .lt_eq(bucket)
+                )
+                .unwrap()
+        );
     }
 
     #[test]
@@ -392,6 +522,7 @@ mod tests {
         );
         let mut converter = TypeConverter {
             schemas: vec![&schema_ref],
+            time_zone: None,
         };
 
         assert_eq!(
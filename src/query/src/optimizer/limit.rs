@@ -0,0 +1,176 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion::datasource::DefaultTableSource;
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::optimizer::OptimizerConfig;
+use datafusion_common::Result;
+use datafusion_expr::{Expr, Limit, LogicalPlan, TableScan};
+use datatypes::schema::TIME_INDEX_KEY;
+use store_api::storage::ScanOrder;
+use table::metadata::FilterPushDownType;
+use table::table::adapter::DfTableProviderAdapter;
+
+/// OrderedLimitPushDownRule pushes the limit of a query sorted by the time index, e.g.
+/// `SELECT * FROM t ORDER BY ts DESC LIMIT 10 OFFSET 5`, down to the [TableScan] as its
+/// `fetch`, so the table could stop scanning after `skip + fetch` rows.
+///
+/// DataFusion never pushes a limit through a sort, since the scan may return rows
+/// in any order. This rule only pushes the limit if the table supports scanning rows
+/// in time index order (see [Table::scan_in_time_index_order]), the scan is then done
+/// by [Table::scan_ordered] in the order of the sort. The sort and limit are kept so
+/// the result is still correct. Filters the table can't evaluate exactly and sorting
+/// by other columns stop the push down.
+///
+/// [Table::scan_in_time_index_order]: table::Table::scan_in_time_index_order
+/// [Table::scan_ordered]: table::Table::scan_ordered
+pub struct OrderedLimitPushDownRule;
+
+impl OptimizerRule for OrderedLimitPushDownRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        if let LogicalPlan::Limit(Limit {
+            skip,
+            fetch: Some(fetch),
+            input,
+        }) = plan
+        {
+            if let Some(new_input) = push_down_sorted_limit(input, skip + fetch) {
+                return datafusion_expr::utils::from_plan(plan, &plan.expressions(), &[new_input])
+                    .map(Some);
+            }
+        }
+
+        let inputs = plan.inputs();
+        if inputs.is_empty() {
+            return Ok(Some(plan.clone()));
+        }
+        let mut new_inputs = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let Some(plan) = self.try_optimize(input, config)? else {
+                return Ok(None);
+            };
+            new_inputs.push(plan);
+        }
+        datafusion_expr::utils::from_plan(plan, &plan.expressions(), &new_inputs).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "OrderedLimitPushDownRule"
+    }
+}
+
+/// Pushes `fetch` down to the [TableScan] under the `plan` if the `plan` is a sort
+/// by the time index. Returns the new plan or `None` if the limit can't be pushed down.
+fn push_down_sorted_limit(plan: &LogicalPlan, fetch: usize) -> Option<LogicalPlan> {
+    let LogicalPlan::Sort(sort) = plan else {
+        return None;
+    };
+    let [Expr::Sort(sort_expr)] = sort.expr.as_slice() else {
+        return None;
+    };
+    let Expr::Column(column) = sort_expr.expr.as_ref() else {
+        return None;
+    };
+    // The time index has no null, so the position of nulls doesn't matter.
+    let order = if sort_expr.asc {
+        ScanOrder::Asc
+    } else {
+        ScanOrder::Desc
+    };
+
+    let new_input = push_down_fetch(&sort.input, &column.name, fetch, order)?;
+    datafusion_expr::utils::from_plan(plan, &plan.expressions(), &[new_input]).ok()
+}
+
+/// Sets the `fetch` of the [TableScan] and lets it scan rows in the `order` of
+/// `column`. Only projections that keep the `column` are allowed between the `plan`
+/// and the scan.
+fn push_down_fetch(
+    plan: &LogicalPlan,
+    column: &str,
+    fetch: usize,
+    order: ScanOrder,
+) -> Option<LogicalPlan> {
+    match plan {
+        LogicalPlan::Projection(projection) => {
+            let keeps_column = projection
+                .expr
+                .iter()
+                .any(|expr| matches!(expr, Expr::Column(c) if c.name == column));
+            if !keeps_column {
+                return None;
+            }
+            let new_input = push_down_fetch(&projection.input, column, fetch, order)?;
+            datafusion_expr::utils::from_plan(plan, &plan.expressions(), &[new_input]).ok()
+        }
+        LogicalPlan::TableScan(scan) => {
+            let adapter = table_adapter(scan)?;
+            let table = adapter.table();
+            if !table.scan_in_time_index_order() || !is_time_index(scan, column) {
+                return None;
+            }
+            // Rows returned by the scan are filtered again if a filter is inexact, so
+            // the scan can't stop early.
+            let all_exact = scan.filters.iter().all(|filter| {
+                matches!(
+                    table.supports_filter_pushdown(&filter.clone().into()),
+                    Ok(FilterPushDownType::Exact)
+                )
+            });
+            if !all_exact {
+                return None;
+            }
+            let fetch = scan.fetch.map(|f| f.min(fetch)).unwrap_or(fetch);
+            let provider = DfTableProviderAdapter::new(table).with_scan_order(Some(order));
+
+            Some(LogicalPlan::TableScan(TableScan {
+                table_name: scan.table_name.clone(),
+                source: Arc::new(DefaultTableSource::new(Arc::new(provider))),
+                projection: scan.projection.clone(),
+                projected_schema: scan.projected_schema.clone(),
+                filters: scan.filters.clone(),
+                fetch: Some(fetch),
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the [DfTableProviderAdapter] of the table to scan.
+fn table_adapter(scan: &TableScan) -> Option<&DfTableProviderAdapter> {
+    scan.source
+        .as_any()
+        .downcast_ref::<DefaultTableSource>()
+        .and_then(|source| {
+            source
+                .table_provider
+                .as_any()
+                .downcast_ref::<DfTableProviderAdapter>()
+        })
+}
+
+/// Returns true if `column` is the time index of the table.
+fn is_time_index(scan: &TableScan, column: &str) -> bool {
+    let schema = scan.source.schema();
+    schema
+        .fields()
+        .iter()
+        .any(|field| field.name() == column && field.metadata().contains_key(TIME_INDEX_KEY))
+}
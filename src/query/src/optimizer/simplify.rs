@@ -0,0 +1,343 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::optimizer::OptimizerConfig;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
+use datafusion_expr::{BinaryExpr, EmptyRelation, Expr, Filter, LogicalPlan, Operator};
+
+/// PredicateSimplificationRule simplifies filter predicates, so the rules after it
+/// see clean predicates:
+/// - comparisons and arithmetic between literals are folded, e.g. `1 = 1` becomes `true`
+/// - nested `AND`s and `OR`s are flattened, and the duplicated operands are removed
+/// - `true` and `false` operands of `AND` and `OR` are eliminated
+/// - filters with an always true predicate are removed, and filters with an always
+///   false predicate are replaced by an empty relation
+///
+/// `now()` is folded by [TimestampArithmeticFoldingRule](crate::optimizer::TimestampArithmeticFoldingRule)
+/// and the timestamp literals are converted by [TypeConversionRule](crate::optimizer::TypeConversionRule),
+/// so this rule should be applied between them.
+pub struct PredicateSimplificationRule;
+
+impl OptimizerRule for PredicateSimplificationRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        self.simplify_plan(plan).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "PredicateSimplificationRule"
+    }
+}
+
+impl PredicateSimplificationRule {
+    fn simplify_plan(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        let inputs = plan.inputs();
+        let mut new_inputs = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            new_inputs.push(self.simplify_plan(input)?);
+        }
+
+        match plan {
+            LogicalPlan::Filter(filter) => {
+                let input = new_inputs.swap_remove(0);
+                let predicate = filter.predicate().clone().rewrite(&mut Simplifier)?;
+                match predicate {
+                    Expr::Literal(ScalarValue::Boolean(Some(true))) => Ok(input),
+                    // Null is treated as false by filters.
+                    Expr::Literal(ScalarValue::Boolean(None | Some(false))) => {
+                        Ok(LogicalPlan::EmptyRelation(EmptyRelation {
+                            produce_one_row: false,
+                            schema: input.schema().clone(),
+                        }))
+                    }
+                    predicate => Ok(LogicalPlan::Filter(Filter::try_new(
+                        predicate,
+                        Arc::new(input),
+                    )?)),
+                }
+            }
+            _ if new_inputs.is_empty() => Ok(plan.clone()),
+            _ => datafusion_expr::utils::from_plan(plan, &plan.expressions(), &new_inputs),
+        }
+    }
+}
+
+/// Simplifies expressions bottom up, so the operands are always simplified when
+/// their parent is visited.
+struct Simplifier;
+
+impl ExprRewriter for Simplifier {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        let new_expr = match expr {
+            Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: op @ (Operator::And | Operator::Or),
+                right,
+            }) => simplify_junction(*left, op, *right),
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                match (left.as_ref(), right.as_ref()) {
+                    (Expr::Literal(l), Expr::Literal(r)) => match fold_literals(l, op, r) {
+                        Some(value) => Expr::Literal(value),
+                        None => Expr::BinaryExpr(BinaryExpr { left, op, right }),
+                    },
+                    _ => Expr::BinaryExpr(BinaryExpr { left, op, right }),
+                }
+            }
+            Expr::Not(expr) => match *expr {
+                Expr::Literal(ScalarValue::Boolean(v)) => {
+                    Expr::Literal(ScalarValue::Boolean(v.map(|v| !v)))
+                }
+                expr => Expr::Not(Box::new(expr)),
+            },
+            Expr::IsNull(expr) => match *expr {
+                Expr::Literal(v) => Expr::Literal(ScalarValue::Boolean(Some(v.is_null()))),
+                expr => Expr::IsNull(Box::new(expr)),
+            },
+            Expr::IsNotNull(expr) => match *expr {
+                Expr::Literal(v) => Expr::Literal(ScalarValue::Boolean(Some(!v.is_null()))),
+                expr => Expr::IsNotNull(Box::new(expr)),
+            },
+            expr => expr,
+        };
+        Ok(new_expr)
+    }
+}
+
+/// Flattens `left op right` into a list of operands, where `op` is either `AND` or
+/// `OR`, then removes the duplicated operands and the boolean literals.
+///
+/// For `AND`, `true` operands are removed and a `false` operand makes the whole
+/// expression `false`, vice versa for `OR`. Both hold under three-valued logic.
+fn simplify_junction(left: Expr, op: Operator, right: Expr) -> Expr {
+    let absorbing = op == Operator::Or;
+    let mut operands = Vec::new();
+    for expr in [left, right] {
+        flatten(expr, op, &mut operands);
+    }
+
+    let mut simplified: Vec<Expr> = Vec::with_capacity(operands.len());
+    for operand in operands {
+        match operand {
+            Expr::Literal(ScalarValue::Boolean(Some(v))) if v == absorbing => {
+                return Expr::Literal(ScalarValue::Boolean(Some(absorbing)));
+            }
+            Expr::Literal(ScalarValue::Boolean(Some(_))) => {}
+            operand if simplified.contains(&operand) => {}
+            operand => simplified.push(operand),
+        }
+    }
+
+    simplified
+        .into_iter()
+        .reduce(|acc, expr| Expr::BinaryExpr(BinaryExpr::new(Box::new(acc), op, Box::new(expr))))
+        // All operands are identities of the operator.
+        .unwrap_or(Expr::Literal(ScalarValue::Boolean(Some(!absorbing))))
+}
+
+fn flatten(expr: Expr, op: Operator, operands: &mut Vec<Expr>) {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: expr_op,
+            right,
+        }) if expr_op == op => {
+            flatten(*left, op, operands);
+            flatten(*right, op, operands);
+        }
+        expr => operands.push(expr),
+    }
+}
+
+/// Evaluates the comparison or arithmetic between two literals, returns `None` if
+/// it's not supported, e.g. the literals have different types or the arithmetic
+/// overflows.
+fn fold_literals(left: &ScalarValue, op: Operator, right: &ScalarValue) -> Option<ScalarValue> {
+    if left.get_datatype() != right.get_datatype() {
+        return None;
+    }
+
+    let compare = |f: fn(Ordering) -> bool| {
+        if left.is_null() || right.is_null() {
+            return Some(ScalarValue::Boolean(None));
+        }
+        left.partial_cmp(right)
+            .map(|ordering| ScalarValue::Boolean(Some(f(ordering))))
+    };
+    match op {
+        Operator::Eq => compare(Ordering::is_eq),
+        Operator::NotEq => compare(Ordering::is_ne),
+        Operator::Lt => compare(Ordering::is_lt),
+        Operator::LtEq => compare(Ordering::is_le),
+        Operator::Gt => compare(Ordering::is_gt),
+        Operator::GtEq => compare(Ordering::is_ge),
+        Operator::Plus | Operator::Minus | Operator::Multiply | Operator::Divide => {
+            fold_arithmetic(left, op, right)
+        }
+        _ => None,
+    }
+}
+
+macro_rules! fold_integers {
+    ($variant: ident, $l: expr, $op: expr, $r: expr) => {{
+        let v = match $op {
+            Operator::Plus => $l.checked_add(*$r),
+            Operator::Minus => $l.checked_sub(*$r),
+            Operator::Multiply => $l.checked_mul(*$r),
+            // Division by zero is left to the executor to report.
+            _ => $l.checked_div(*$r),
+        }?;
+        Some(ScalarValue::$variant(Some(v)))
+    }};
+}
+
+macro_rules! fold_floats {
+    ($variant: ident, $l: expr, $op: expr, $r: expr) => {{
+        let v = match $op {
+            Operator::Plus => $l + $r,
+            Operator::Minus => $l - $r,
+            Operator::Multiply => $l * $r,
+            _ => $l / $r,
+        };
+        Some(ScalarValue::$variant(Some(v)))
+    }};
+}
+
+fn fold_arithmetic(left: &ScalarValue, op: Operator, right: &ScalarValue) -> Option<ScalarValue> {
+    match (left, right) {
+        (ScalarValue::Int32(Some(l)), ScalarValue::Int32(Some(r))) => {
+            fold_integers!(Int32, l, op, r)
+        }
+        (ScalarValue::Int64(Some(l)), ScalarValue::Int64(Some(r))) => {
+            fold_integers!(Int64, l, op, r)
+        }
+        (ScalarValue::UInt32(Some(l)), ScalarValue::UInt32(Some(r))) => {
+            fold_integers!(UInt32, l, op, r)
+        }
+        (ScalarValue::UInt64(Some(l)), ScalarValue::UInt64(Some(r))) => {
+            fold_integers!(UInt64, l, op, r)
+        }
+        (ScalarValue::Float32(Some(l)), ScalarValue::Float32(Some(r))) => {
+            fold_floats!(Float32, l, op, r)
+        }
+        (ScalarValue::Float64(Some(l)), ScalarValue::Float64(Some(r))) => {
+            fold_floats!(Float64, l, op, r)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_expr::{col, lit, LogicalPlanBuilder};
+
+    use super::*;
+
+    fn simplify(expr: Expr) -> Expr {
+        expr.rewrite(&mut Simplifier).unwrap()
+    }
+
+    fn timestamp_millis(value: i64) -> Expr {
+        Expr::Literal(ScalarValue::TimestampMillisecond(Some(value), None))
+    }
+
+    #[test]
+    fn test_fold_literals() {
+        assert_eq!(lit(true), simplify(lit(1i64).eq(lit(1i64))));
+        assert_eq!(lit(false), simplify(lit(1i64).gt(lit(2i64))));
+        assert_eq!(lit(3i64), simplify(lit(1i64) + lit(2i64)));
+        assert_eq!(lit(2.5f64), simplify(lit(5.0f64) / lit(2.0f64)));
+        assert_eq!(
+            lit(true),
+            simplify(timestamp_millis(2000).gt_eq(timestamp_millis(1000)))
+        );
+        assert_eq!(
+            lit(false),
+            simplify(Expr::Not(Box::new(lit(1i64).eq(lit(1i64)))))
+        );
+        assert_eq!(
+            Expr::Literal(ScalarValue::Boolean(None)),
+            simplify(Expr::Literal(ScalarValue::Int64(None)).eq(lit(1i64)))
+        );
+
+        // Not folded.
+        let expr = lit(1i32).eq(lit(1i64));
+        assert_eq!(expr.clone(), simplify(expr));
+        let expr = lit(i64::MAX) + lit(1i64);
+        assert_eq!(expr.clone(), simplify(expr));
+        let expr = lit(1i64) / lit(0i64);
+        assert_eq!(expr.clone(), simplify(expr));
+    }
+
+    #[test]
+    fn test_simplify_junction() {
+        let a = || col("ts").gt_eq(timestamp_millis(1000));
+        let b = || col("ts").lt(timestamp_millis(2000));
+        let c = || col("host").eq(lit("a"));
+
+        assert_eq!(a(), simplify(lit(1i64).eq(lit(1i64)).and(a())));
+        assert_eq!(lit(false), simplify(a().and(lit(1i64).eq(lit(2i64)))));
+        assert_eq!(lit(true), simplify(a().or(lit(true))));
+        assert_eq!(a(), simplify(lit(false).or(a())));
+        assert_eq!(a(), simplify(a().and(a())));
+        assert_eq!(lit(true), simplify(lit(true).and(lit(true))));
+
+        // a AND (b AND (true AND c)) => a AND b AND c
+        assert_eq!(
+            a().and(b()).and(c()),
+            simplify(a().and(b().and(lit(true).and(c()))))
+        );
+        // (a OR b) AND (a OR b) => a OR b
+        assert_eq!(a().or(b()), simplify(a().or(b()).and(a().or(b().or(a())))));
+        // Different operators are not flattened.
+        let expr = a().and(b().or(c()));
+        assert_eq!(expr.clone(), simplify(expr));
+    }
+
+    #[test]
+    fn test_simplify_filter() {
+        let input = LogicalPlanBuilder::empty(true).build().unwrap();
+        let rule = PredicateSimplificationRule;
+
+        let plan = LogicalPlanBuilder::from(input.clone())
+            .filter(lit(1i64).eq(lit(1i64)))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            format!("{:?}", input),
+            format!("{:?}", rule.simplify_plan(&plan).unwrap())
+        );
+
+        let plan = LogicalPlanBuilder::from(input)
+            .filter(lit(false).and(lit(true)))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(matches!(
+            rule.simplify_plan(&plan).unwrap(),
+            LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: false,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,506 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_function::scalars::timestamp::{parse_interval, TIME_BUCKET};
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::optimizer::OptimizerConfig;
+use datafusion_common::{Column, Result, ScalarValue};
+use datafusion_expr::{
+    Between, BinaryExpr, BuiltinScalarFunction, Expr, Filter, LogicalPlan, Operator, TableScan,
+};
+use datatypes::schema::TIME_INDEX_KEY;
+
+/// TimeRangeFilterPushDownRule extracts the range of the time index column from
+/// the predicate of a [Filter] and attaches it to the [TableScan] below the filter,
+/// as `ts >= start` and `ts < end` expressions, so table providers can prune data
+/// by time.
+///
+/// Constraints combined by `AND` are intersected and constraints combined by `OR`
+/// are unioned. Only millisecond timestamp literals are recognized, which is the
+/// type [TypeConversionRule](crate::optimizer::TypeConversionRule) converts all
+/// timestamp literals to, so this rule should be applied after it.
+///
+/// Comparisons on time buckets of the time index, e.g. `time_bucket('5m', ts) >= t`,
+/// are also recognized as `ts` lies in `[bucket, bucket + interval)`.
+///
+/// The filter itself is kept as the pushed down range is inexact.
+pub struct TimeRangeFilterPushDownRule;
+
+impl OptimizerRule for TimeRangeFilterPushDownRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        if let LogicalPlan::Filter(filter) = plan {
+            if let LogicalPlan::TableScan(scan) = filter.input().as_ref() {
+                let Some(scan) = push_down_time_range(filter.predicate(), scan) else {
+                    return Ok(Some(plan.clone()));
+                };
+                return Ok(Some(LogicalPlan::Filter(Filter::try_new(
+                    filter.predicate().clone(),
+                    Arc::new(LogicalPlan::TableScan(scan)),
+                )?)));
+            }
+        }
+
+        let inputs = plan.inputs();
+        if inputs.is_empty() {
+            return Ok(Some(plan.clone()));
+        }
+        let mut new_inputs = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let Some(plan) = self.try_optimize(input, config)? else {
+                return Ok(None);
+            };
+            new_inputs.push(plan);
+        }
+        datafusion_expr::utils::from_plan(plan, &plan.expressions(), &new_inputs).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "TimeRangeFilterPushDownRule"
+    }
+}
+
+/// Returns a new [TableScan] with the time range in `predicate` attached, or `None`
+/// if the `predicate` does not constrain the time index.
+fn push_down_time_range(predicate: &Expr, scan: &TableScan) -> Option<TableScan> {
+    let schema = scan.source.schema();
+    let ts_name = schema
+        .fields()
+        .iter()
+        .find(|field| field.metadata().contains_key(TIME_INDEX_KEY))?
+        .name();
+
+    let extractor = TimeRangeExtractor { ts_name };
+    let (column, range) = extractor.extract(predicate)?;
+
+    let mut filters = scan.filters.clone();
+    for expr in range.to_exprs(column) {
+        if !filters.contains(&expr) {
+            filters.push(expr);
+        }
+    }
+    if filters.len() == scan.filters.len() {
+        return None;
+    }
+
+    Some(TableScan {
+        table_name: scan.table_name.clone(),
+        source: scan.source.clone(),
+        projection: scan.projection.clone(),
+        projected_schema: scan.projected_schema.clone(),
+        filters,
+        fetch: scan.fetch,
+    })
+}
+
+/// Range of timestamp in milliseconds, `None` bound means unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MillisRange {
+    /// Inclusive lower bound.
+    start: Option<i64>,
+    /// Exclusive upper bound.
+    end: Option<i64>,
+}
+
+impl MillisRange {
+    fn new(start: Option<i64>, end: Option<i64>) -> MillisRange {
+        MillisRange { start, end }
+    }
+
+    fn is_unbounded(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    fn intersect(&self, other: &MillisRange) -> MillisRange {
+        let start = match (self.start, other.start) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let end = match (self.end, other.end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        MillisRange::new(start, end)
+    }
+
+    fn union(&self, other: &MillisRange) -> MillisRange {
+        let start = match (self.start, other.start) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => None,
+        };
+        let end = match (self.end, other.end) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        };
+        MillisRange::new(start, end)
+    }
+
+    /// Converts the range of the starts of time buckets of `width` milliseconds to
+    /// the range of the timestamps in those buckets.
+    fn widen(self, width: Option<i64>) -> MillisRange {
+        match width {
+            Some(width) => {
+                MillisRange::new(self.start, self.end.and_then(|end| end.checked_add(width)))
+            }
+            None => self,
+        }
+    }
+
+    fn to_exprs(self, column: Column) -> Vec<Expr> {
+        let mut exprs = Vec::with_capacity(2);
+        if let Some(start) = self.start {
+            exprs.push(Expr::Column(column.clone()).gt_eq(timestamp_millis(start)));
+        }
+        if let Some(end) = self.end {
+            exprs.push(Expr::Column(column).lt(timestamp_millis(end)));
+        }
+        exprs
+    }
+}
+
+fn timestamp_millis(value: i64) -> Expr {
+    Expr::Literal(ScalarValue::TimestampMillisecond(Some(value), None))
+}
+
+struct TimeRangeExtractor<'a> {
+    ts_name: &'a str,
+}
+
+impl<'a> TimeRangeExtractor<'a> {
+    /// Extracts the time index column and its range from `expr`, returns `None`
+    /// if `expr` does not constrain the time index.
+    fn extract(&self, expr: &Expr) -> Option<(Column, MillisRange)> {
+        let mut column = None;
+        let range = self.extract_range(expr, &mut column);
+        if range.is_unbounded() {
+            return None;
+        }
+        column.map(|c| (c, range))
+    }
+
+    fn extract_range(&self, expr: &Expr, column: &mut Option<Column>) -> MillisRange {
+        let unbounded = MillisRange::new(None, None);
+        match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And => self
+                    .extract_range(left, column)
+                    .intersect(&self.extract_range(right, column)),
+                Operator::Or => self
+                    .extract_range(left, column)
+                    .union(&self.extract_range(right, column)),
+                _ => self
+                    .extract_comparison(left, *op, right, column)
+                    .unwrap_or(unbounded),
+            },
+            Expr::Between(Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            }) => {
+                let Some((ts, width)) = self.ts_operand(expr) else {
+                    return unbounded;
+                };
+                let (Some(low), Some(high)) = (millis_literal(low), millis_literal(high)) else {
+                    return unbounded;
+                };
+                *column = Some(ts);
+                MillisRange::new(Some(low), high.checked_add(1)).widen(width)
+            }
+            _ => unbounded,
+        }
+    }
+
+    fn extract_comparison(
+        &self,
+        left: &Expr,
+        op: Operator,
+        right: &Expr,
+        column: &mut Option<Column>,
+    ) -> Option<MillisRange> {
+        // Normalizes the comparison to `ts op value`.
+        let (ts, width, op, value) = match (self.ts_operand(left), self.ts_operand(right)) {
+            (Some((ts, width)), None) => (ts, width, op, millis_literal(right)?),
+            (None, Some((ts, width))) => (ts, width, swap_operator(op)?, millis_literal(left)?),
+            _ => return None,
+        };
+
+        let range = match op {
+            Operator::Eq => MillisRange::new(Some(value), value.checked_add(1)),
+            Operator::Gt => MillisRange::new(value.checked_add(1), None),
+            Operator::GtEq => MillisRange::new(Some(value), None),
+            Operator::Lt => MillisRange::new(None, Some(value)),
+            Operator::LtEq => MillisRange::new(None, value.checked_add(1)),
+            _ => return None,
+        };
+        *column = Some(ts);
+        Some(range.widen(width))
+    }
+
+    /// Returns the time index column `expr` refers to, and the width of the time
+    /// buckets in milliseconds if `expr` truncates the column to time buckets.
+    fn ts_operand(&self, expr: &Expr) -> Option<(Column, Option<i64>)> {
+        match time_bucket_args(expr) {
+            Some((ts, width)) => Some((self.ts_column(ts)?, Some(width))),
+            None => Some((self.ts_column(expr)?, None)),
+        }
+    }
+
+    fn ts_column(&self, expr: &Expr) -> Option<Column> {
+        match expr {
+            Expr::Column(column) if column.name == self.ts_name => Some(column.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Max change of the UTC offset of a time zone, e.g. by daylight saving time, so the
+/// length of a bucket of whole days in a time zone may differ from its interval.
+const MAX_OFFSET_CHANGE_MILLIS: i64 = 3 * 3_600_000;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Returns the timestamp argument and an upper bound of the bucket width in milliseconds
+/// if `expr` truncates timestamps to buckets of a constant interval, by `time_bucket`
+/// or `date_bin`.
+pub(crate) fn time_bucket_args(expr: &Expr) -> Option<(&Expr, i64)> {
+    match expr {
+        Expr::ScalarUDF { fun, args } if fun.name == TIME_BUCKET && args.len() >= 2 => {
+            let Expr::Literal(ScalarValue::Utf8(Some(interval))) = &args[0] else {
+                return None;
+            };
+            // Rounds up intervals finer than a millisecond.
+            let width = parse_interval(interval)?.checked_add(999_999)? / 1_000_000;
+            // Buckets of whole days without an origin are aligned to the local midnight
+            // in the time zone of the query.
+            let width = if args.len() == 2 && width % MILLIS_PER_DAY == 0 {
+                width.checked_add(MAX_OFFSET_CHANGE_MILLIS)?
+            } else {
+                width
+            };
+            Some((&args[1], width))
+        }
+        Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::DateBin,
+            args,
+        } if args.len() >= 2 => {
+            let width = match &args[0] {
+                Expr::Literal(ScalarValue::IntervalDayTime(Some(v))) => {
+                    let (days, millis) = ((*v >> 32) as i32, *v as i32);
+                    days as i64 * MILLIS_PER_DAY + millis as i64
+                }
+                Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(v))) => {
+                    let (months, days, nanos) = ((*v >> 96) as i32, (*v >> 64) as i32, *v as i64);
+                    if months != 0 {
+                        return None;
+                    }
+                    days as i64 * MILLIS_PER_DAY + (nanos + 999_999) / 1_000_000
+                }
+                _ => return None,
+            };
+            (width > 0).then_some((&args[1], width))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the operator to use after swapping the operands of a comparison.
+fn swap_operator(op: Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq => Some(Operator::Eq),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::GtEq => Some(Operator::LtEq),
+        Operator::Lt => Some(Operator::Gt),
+        Operator::LtEq => Some(Operator::GtEq),
+        _ => None,
+    }
+}
+
+fn millis_literal(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(ScalarValue::TimestampMillisecond(Some(v), _)) => Some(*v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_function::scalars::timestamp::TimeBucketFunction;
+    use common_function::scalars::udf::create_udf;
+    use datafusion_expr::col;
+
+    use super::*;
+
+    fn ts_lit(v: i64) -> Expr {
+        timestamp_millis(v)
+    }
+
+    fn extract(expr: Expr) -> Option<MillisRange> {
+        let extractor = TimeRangeExtractor { ts_name: "ts" };
+        extractor.extract(&expr).map(|(_, range)| range)
+    }
+
+    #[test]
+    fn test_extract_comparison() {
+        assert_eq!(
+            Some(MillisRange::new(Some(11), None)),
+            extract(col("ts").gt(ts_lit(10)))
+        );
+        assert_eq!(
+            Some(MillisRange::new(Some(10), None)),
+            extract(col("ts").gt_eq(ts_lit(10)))
+        );
+        assert_eq!(
+            Some(MillisRange::new(None, Some(10))),
+            extract(col("ts").lt(ts_lit(10)))
+        );
+        assert_eq!(
+            Some(MillisRange::new(None, Some(11))),
+            extract(col("ts").lt_eq(ts_lit(10)))
+        );
+        assert_eq!(
+            Some(MillisRange::new(Some(10), Some(11))),
+            extract(col("ts").eq(ts_lit(10)))
+        );
+        // Literal on the left side.
+        assert_eq!(
+            Some(MillisRange::new(None, Some(10))),
+            extract(ts_lit(10).gt(col("ts")))
+        );
+
+        assert_eq!(None, extract(col("ts").not_eq(ts_lit(10))));
+        assert_eq!(None, extract(col("host").gt(ts_lit(10))));
+        assert_eq!(
+            None,
+            extract(col("ts").gt(Expr::Literal(ScalarValue::Int64(Some(10)))))
+        );
+    }
+
+    #[test]
+    fn test_extract_between() {
+        assert_eq!(
+            Some(MillisRange::new(Some(10), Some(21))),
+            extract(col("ts").between(ts_lit(10), ts_lit(20)))
+        );
+        assert_eq!(None, extract(col("ts").not_between(ts_lit(10), ts_lit(20))));
+    }
+
+    #[test]
+    fn test_extract_and_or() {
+        assert_eq!(
+            Some(MillisRange::new(Some(10), Some(20))),
+            extract(
+                col("ts")
+                    .gt_eq(ts_lit(10))
+                    .and(col("ts").lt(ts_lit(20)))
+                    .and(col("host").eq(Expr::Literal(ScalarValue::Utf8(Some("a".to_string())))))
+            )
+        );
+        assert_eq!(
+            Some(MillisRange::new(Some(0), Some(30))),
+            extract(
+                col("ts")
+                    .between(ts_lit(0), ts_lit(9))
+                    .or(col("ts").between(ts_lit(20), ts_lit(29)))
+            )
+        );
+        // One side of OR is unbounded.
+        assert_eq!(
+            None,
+            extract(
+                col("ts")
+                    .gt(ts_lit(10))
+                    .or(col("host").eq(Expr::Literal(ScalarValue::Utf8(Some("a".to_string())))))
+            )
+        );
+    }
+
+    fn time_bucket(interval: &str, origin: Option<Expr>) -> Expr {
+        let mut args = vec![
+            Expr::Literal(ScalarValue::Utf8(Some(interval.to_string()))),
+            col("ts"),
+        ];
+        args.extend(origin);
+        Expr::ScalarUDF {
+            fun: Arc::new(create_udf(Arc::new(TimeBucketFunction::default())).into_df_udf()),
+            args,
+        }
+    }
+
+    #[test]
+    fn test_extract_time_bucket() {
+        let minute = 60_000;
+        assert_eq!(
+            Some(MillisRange::new(Some(10), None)),
+            extract(time_bucket("5m", None).gt_eq(ts_lit(10)))
+        );
+        assert_eq!(
+            Some(MillisRange::new(None, Some(10 + 5 * minute))),
+            extract(time_bucket("5m", None).lt(ts_lit(10)))
+        );
+        assert_eq!(
+            Some(MillisRange::new(Some(10), Some(11 + 5 * minute))),
+            extract(
+                time_bucket("5m", None)
+                    .between(ts_lit(0), ts_lit(10))
+                    .and(col("ts").gt_eq(ts_lit(10)))
+            )
+        );
+        assert_eq!(
+            Some(MillisRange::new(Some(10), Some(11 + 5 * minute))),
+            extract(ts_lit(10).eq(time_bucket("5m", Some(ts_lit(1)))))
+        );
+        // Buckets of days are aligned to the local midnight.
+        assert_eq!(
+            Some(MillisRange::new(
+                None,
+                Some(11 + MILLIS_PER_DAY + MAX_OFFSET_CHANGE_MILLIS)
+            )),
+            extract(time_bucket("1d", None).lt_eq(ts_lit(10)))
+        );
+        assert_eq!(None, extract(time_bucket("1 month", None).gt(ts_lit(10))));
+
+        let date_bin = Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::DateBin,
+            args: vec![
+                Expr::Literal(ScalarValue::IntervalDayTime(Some(5 * minute))),
+                col("ts"),
+                ts_lit(0),
+            ],
+        };
+        assert_eq!(
+            Some(MillisRange::new(Some(10), Some(11 + 5 * minute))),
+            extract(date_bin.eq(ts_lit(10)))
+        );
+    }
+
+    #[test]
+    fn test_range_to_exprs() {
+        let range = MillisRange::new(Some(10), Some(20));
+        assert_eq!(
+            vec![col("ts").gt_eq(ts_lit(10)), col("ts").lt(ts_lit(20))],
+            range.to_exprs(Column::from_name("ts"))
+        );
+        let range = MillisRange::new(None, Some(20));
+        assert_eq!(
+            vec![col("ts").lt(ts_lit(20))],
+            range.to_exprs(Column::from_name("ts"))
+        );
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use chrono::{Months, NaiveDateTime};
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::optimizer::OptimizerConfig;
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::expr_rewriter::{ExprRewritable, ExprRewriter};
+use datafusion_expr::{BinaryExpr, BuiltinScalarFunction, Expr, Filter, LogicalPlan, Operator};
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// TimestampArithmeticFoldingRule folds timestamp arithmetic in filter predicates into
+/// millisecond timestamp literals, e.g. `ts >= now() - INTERVAL '5 minutes'` becomes
+/// `ts >= 1672531200000`.
+///
+/// `now()` is bound to the start time of the query, which is the same for every
+/// occurrence in the plan. Folded literals can then be handled by the
+/// [TimeRangeFilterPushDownRule](crate::optimizer::TimeRangeFilterPushDownRule), so this
+/// rule should be applied before it.
+pub struct TimestampArithmeticFoldingRule;
+
+impl OptimizerRule for TimestampArithmeticFoldingRule {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let mut folder = TimestampFolder {
+            now_millis: config.query_execution_start_time().timestamp_millis(),
+        };
+        self.fold_plan(plan, &mut folder).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "TimestampArithmeticFoldingRule"
+    }
+}
+
+impl TimestampArithmeticFoldingRule {
+    fn fold_plan(&self, plan: &LogicalPlan, folder: &mut TimestampFolder) -> Result<LogicalPlan> {
+        let inputs = plan.inputs();
+        let mut new_inputs = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            new_inputs.push(self.fold_plan(input, folder)?);
+        }
+
+        // Only predicates are folded, folding projections would change the output schema
+        // of the plan, as `now()` returns timestamps in nanoseconds.
+        match plan {
+            LogicalPlan::Filter(filter) => {
+                let predicate = filter.predicate().clone().rewrite(folder)?;
+                Ok(LogicalPlan::Filter(Filter::try_new(
+                    predicate,
+                    Arc::new(new_inputs.swap_remove(0)),
+                )?))
+            }
+            _ if new_inputs.is_empty() => Ok(plan.clone()),
+            _ => datafusion_expr::utils::from_plan(plan, &plan.expressions(), &new_inputs),
+        }
+    }
+}
+
+struct TimestampFolder {
+    /// Value of `now()` in milliseconds.
+    now_millis: i64,
+}
+
+impl ExprRewriter for TimestampFolder {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        let new_expr = match expr {
+            Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Now,
+                args,
+            } if args.is_empty() => timestamp_millis(self.now_millis),
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                match fold_binary(&left, op, &right)? {
+                    Some(millis) => timestamp_millis(millis),
+                    None => Expr::BinaryExpr(BinaryExpr { left, op, right }),
+                }
+            }
+            expr => expr,
+        };
+        Ok(new_expr)
+    }
+}
+
+/// Evaluates `timestamp ± interval` or `interval + timestamp`, returns `None` if the
+/// expression is not one of them.
+fn fold_binary(left: &Expr, op: Operator, right: &Expr) -> Result<Option<i64>> {
+    let (Expr::Literal(left), Expr::Literal(right)) = (left, right) else {
+        return Ok(None);
+    };
+    let (millis, interval, negative) = match (op, millis_of(left), millis_of(right)) {
+        (Operator::Plus, Some(millis), None) => (millis, right, false),
+        (Operator::Plus, None, Some(millis)) => (millis, left, false),
+        (Operator::Minus, Some(millis), None) => (millis, left, true),
+        _ => return Ok(None),
+    };
+    let Some(interval) = Interval::from_scalar(interval) else {
+        return Ok(None);
+    };
+    let interval = if negative {
+        interval.negate()
+    } else {
+        interval
+    };
+
+    interval.add_to(millis).map(Some).ok_or_else(|| {
+        DataFusionError::Plan(format!(
+            "timestamp {millis} {op} interval {interval:?} is out of range",
+        ))
+    })
+}
+
+/// Converts a timestamp value to milliseconds.
+fn millis_of(value: &ScalarValue) -> Option<i64> {
+    match value {
+        ScalarValue::TimestampSecond(Some(v), _) => v.checked_mul(1_000),
+        ScalarValue::TimestampMillisecond(Some(v), _) => Some(*v),
+        ScalarValue::TimestampMicrosecond(Some(v), _) => Some(v.div_euclid(1_000)),
+        ScalarValue::TimestampNanosecond(Some(v), _) => Some(v.div_euclid(1_000_000)),
+        _ => None,
+    }
+}
+
+fn timestamp_millis(value: i64) -> Expr {
+    Expr::Literal(ScalarValue::TimestampMillisecond(Some(value), None))
+}
+
+/// An interval decoded from one of the arrow interval representations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Interval {
+    months: i32,
+    days: i32,
+    nanos: i64,
+}
+
+impl Interval {
+    fn from_scalar(value: &ScalarValue) -> Option<Interval> {
+        let interval = match value {
+            ScalarValue::IntervalYearMonth(Some(months)) => Interval {
+                months: *months,
+                days: 0,
+                nanos: 0,
+            },
+            // Days in the high 32 bits and milliseconds in the low 32 bits.
+            ScalarValue::IntervalDayTime(Some(v)) => Interval {
+                months: 0,
+                days: (*v >> 32) as i32,
+                nanos: (*v as i32) as i64 * 1_000_000,
+            },
+            // Months in the high 32 bits, then days in 32 bits and nanoseconds in the low 64 bits.
+            ScalarValue::IntervalMonthDayNano(Some(v)) => Interval {
+                months: (*v >> 96) as i32,
+                days: (*v >> 64) as i32,
+                nanos: *v as i64,
+            },
+            _ => return None,
+        };
+        Some(interval)
+    }
+
+    fn negate(self) -> Interval {
+        Interval {
+            months: -self.months,
+            days: -self.days,
+            nanos: -self.nanos,
+        }
+    }
+
+    /// Adds the interval to the timestamp `millis`, returns `None` on overflow.
+    ///
+    /// Months are added in calendar, so `2022-01-31 + 1 month` is `2022-02-28`.
+    fn add_to(&self, millis: i64) -> Option<i64> {
+        let mut millis = millis;
+        if self.months != 0 {
+            let datetime = NaiveDateTime::from_timestamp_opt(
+                millis.div_euclid(1_000),
+                (millis.rem_euclid(1_000) * 1_000_000) as u32,
+            )?;
+            let months = Months::new(self.months.unsigned_abs());
+            let datetime = if self.months > 0 {
+                datetime.checked_add_months(months)?
+            } else {
+                datetime.checked_sub_months(months)?
+            };
+            millis = datetime.timestamp_millis();
+        }
+        millis
+            .checked_add((self.days as i64).checked_mul(MILLIS_PER_DAY)?)?
+            .checked_add(self.nanos / 1_000_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_expr::{col, lit};
+
+    use super::*;
+
+    fn now() -> Expr {
+        Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::Now,
+            args: vec![],
+        }
+    }
+
+    fn day_time(days: i32, millis: i32) -> Expr {
+        let v = ((days as i64) << 32) | (millis as u32 as i64);
+        Expr::Literal(ScalarValue::IntervalDayTime(Some(v)))
+    }
+
+    fn month_day_nano(months: i32, days: i32, nanos: i64) -> Expr {
+        let v =
+            ((months as i128) << 96) | (((days as u32) as i128) << 64) | ((nanos as u64) as i128);
+        Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(v)))
+    }
+
+    fn fold(expr: Expr) -> Expr {
+        let mut folder = TimestampFolder {
+            now_millis: 1_000_000,
+        };
+        expr.rewrite(&mut folder).unwrap()
+    }
+
+    #[test]
+    fn test_fold_now() {
+        assert_eq!(timestamp_millis(1_000_000), fold(now()));
+        assert_eq!(
+            col("ts").gt_eq(timestamp_millis(1_000_000)),
+            fold(col("ts").gt_eq(now()))
+        );
+    }
+
+    #[test]
+    fn test_fold_interval() {
+        // now() - INTERVAL '5 minutes'
+        assert_eq!(
+            col("ts").gt_eq(timestamp_millis(700_000)),
+            fold(col("ts").gt_eq(now() - day_time(0, 300_000)))
+        );
+        // INTERVAL '1 day' + now()
+        assert_eq!(
+            timestamp_millis(1_000_000 + MILLIS_PER_DAY),
+            fold(day_time(1, 0) + now())
+        );
+        // Timestamp literal in seconds.
+        assert_eq!(
+            timestamp_millis(2_000),
+            fold(
+                Expr::Literal(ScalarValue::TimestampSecond(Some(1), None))
+                    + month_day_nano(0, 0, 1_000_000_000)
+            )
+        );
+        // 2022-01-31T00:00:00 + 1 month = 2022-02-28T00:00:00
+        assert_eq!(
+            timestamp_millis(1646006400000),
+            fold(timestamp_millis(1643587200000) + month_day_nano(1, 0, 0))
+        );
+        // 2022-02-28T00:00:00 - 1 month = 2022-01-28T00:00:00
+        assert_eq!(
+            timestamp_millis(1643328000000),
+            fold(
+                timestamp_millis(1646006400000)
+                    - Expr::Literal(ScalarValue::IntervalYearMonth(Some(1)))
+            )
+        );
+    }
+
+    #[test]
+    fn test_fold_unsupported() {
+        // Interval minus timestamp is not a timestamp.
+        let expr = day_time(0, 1) - now();
+        assert_eq!(day_time(0, 1) - timestamp_millis(1_000_000), fold(expr));
+
+        let expr = col("ts") - day_time(0, 1);
+        assert_eq!(expr.clone(), fold(expr));
+
+        let expr = timestamp_millis(1) + lit(1);
+        assert_eq!(expr.clone(), fold(expr));
+    }
+
+    #[test]
+    fn test_fold_overflow() {
+        let mut folder = TimestampFolder { now_millis: 0 };
+        assert!((timestamp_millis(i64::MAX) + day_time(1, 0))
+            .rewrite(&mut folder)
+            .is_err());
+    }
+
+    #[test]
+    fn test_millis_of_negative() {
+        // Timestamps before the epoch are floored instead of truncated towards zero.
+        assert_eq!(
+            Some(-1),
+            millis_of(&ScalarValue::TimestampMicrosecond(Some(-1), None))
+        );
+        assert_eq!(
+            Some(-2),
+            millis_of(&ScalarValue::TimestampNanosecond(Some(-1_500_000), None))
+        );
+        assert_eq!(
+            Some(1),
+            millis_of(&ScalarValue::TimestampNanosecond(Some(1_500_000), None))
+        );
+    }
+
+    #[test]
+    fn test_decode_interval() {
+        let Expr::Literal(value) = month_day_nano(-1, -2, -3) else {
+            unreachable!()
+        };
+        assert_eq!(
+            Some(Interval {
+                months: -1,
+                days: -2,
+                nanos: -3,
+            }),
+            Interval::from_scalar(&value)
+        );
+        let Expr::Literal(value) = day_time(-1, -2) else {
+            unreachable!()
+        };
+        assert_eq!(
+            Some(Interval {
+                months: 0,
+                days: -1,
+                nanos: -2_000_000,
+            }),
+            Interval::from_scalar(&value)
+        );
+    }
+}
@@ -12,9 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod sorted_input;
+mod top_k;
+
 use std::sync::Arc;
 
 use common_query::physical_plan::PhysicalPlan;
+pub use sorted_input::SortedInputRule;
+pub use top_k::{TopKExec, TopKRule, DEFAULT_TOP_K_MAX_FETCH};
 
 use crate::error::Result;
 use crate::query_engine::QueryEngineContext;
@@ -0,0 +1,250 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::SessionConfig;
+use datafusion::physical_expr::{EquivalenceProperties, PhysicalSortExpr};
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::expressions::Column;
+use datafusion::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datatypes::arrow::datatypes::Schema;
+
+/// SortedInputRule removes a [SortExec] whose input is already sorted in the expected
+/// order, e.g. the scan of a table returns rows in time index order for
+/// `ORDER BY ts`.
+///
+/// If the sort merges multiple sorted partitions, it's replaced by a
+/// [SortPreservingMergeExec] instead of sorting all rows. The `fetch` of the sort is
+/// kept by a limit.
+pub struct SortedInputRule;
+
+impl PhysicalOptimizerRule for SortedInputRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &SessionConfig,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        let children = plan
+            .children()
+            .into_iter()
+            .map(|child| self.optimize(child, config))
+            .collect::<DfResult<Vec<_>>>()?;
+        let plan = if children.is_empty() {
+            plan
+        } else {
+            plan.with_new_children(children)?
+        };
+
+        let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+            return Ok(plan);
+        };
+        let input = sort.input();
+        let new_plan: Arc<dyn ExecutionPlan> = if ordering_satisfy(input, sort.expr())
+            && input.output_partitioning().partition_count()
+                == sort.output_partitioning().partition_count()
+        {
+            input.clone()
+        } else if let Some(coalesce) = input.as_any().downcast_ref::<CoalescePartitionsExec>() {
+            if !ordering_satisfy(coalesce.input(), sort.expr()) {
+                return Ok(plan);
+            }
+            Arc::new(SortPreservingMergeExec::new(
+                sort.expr().to_vec(),
+                coalesce.input().clone(),
+            ))
+        } else {
+            return Ok(plan);
+        };
+
+        let Some(fetch) = sort.fetch() else {
+            return Ok(new_plan);
+        };
+        if new_plan.output_partitioning().partition_count() == 1 {
+            Ok(Arc::new(GlobalLimitExec::new(new_plan, 0, Some(fetch))))
+        } else {
+            Ok(Arc::new(LocalLimitExec::new(new_plan, fetch)))
+        }
+    }
+
+    fn name(&self) -> &str {
+        "SortedInputRule"
+    }
+}
+
+/// Returns true if the output of `plan` is sorted by `required`.
+fn ordering_satisfy(plan: &Arc<dyn ExecutionPlan>, required: &[PhysicalSortExpr]) -> bool {
+    let Some(provided) = plan.output_ordering() else {
+        return false;
+    };
+    let schema = plan.schema();
+    let equivalence = plan.equivalence_properties();
+    required.len() <= provided.len()
+        && required
+            .iter()
+            .zip(provided)
+            .all(|(required, provided)| sort_expr_equal(&schema, &equivalence, required, provided))
+}
+
+/// Returns true if `left` and `right` sort the same column, or equivalent columns, in
+/// the same order.
+///
+/// Columns are compared by name, as the index of a column changes after projection.
+fn sort_expr_equal(
+    schema: &Schema,
+    equivalence: &EquivalenceProperties,
+    left: &PhysicalSortExpr,
+    right: &PhysicalSortExpr,
+) -> bool {
+    let (Some(left_column), Some(right_column)) = (
+        left.expr.as_any().downcast_ref::<Column>(),
+        right.expr.as_any().downcast_ref::<Column>(),
+    ) else {
+        return false;
+    };
+    if left.options.descending != right.options.descending {
+        return false;
+    }
+    let same_column = left_column.name() == right_column.name()
+        || equivalence
+            .classes()
+            .iter()
+            .any(|class| class.contains(left_column) && class.contains(right_column));
+    if !same_column {
+        return false;
+    }
+
+    // Positions of nulls don't matter if the column has no null, e.g. the time index.
+    left.options.nulls_first == right.options.nulls_first
+        || schema
+            .field_with_name(left_column.name())
+            .map(|field| !field.is_nullable())
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::compute::SortOptions;
+    use datafusion::arrow::datatypes::{DataType, Field};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::physical_plan::collect;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::union::UnionExec;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    fn sort_expr(name: &str, index: usize, descending: bool) -> PhysicalSortExpr {
+        PhysicalSortExpr {
+            expr: Arc::new(Column::new(name, index)),
+            options: SortOptions {
+                descending,
+                nulls_first: descending,
+            },
+        }
+    }
+
+    /// Returns a scan of `partitions` sorted by `ts`.
+    fn sorted_scan(partitions: &[&[i64]]) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new("ts", DataType::Int64, false)]));
+        let inputs = partitions
+            .iter()
+            .map(|ts| {
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int64Array::from(ts.to_vec()))],
+                )
+                .unwrap();
+                let scan = MemoryExec::try_new(&[vec![batch]], schema.clone(), None)
+                    .unwrap()
+                    .with_sort_information(vec![sort_expr("ts", 0, false)]);
+                Arc::new(scan) as _
+            })
+            .collect();
+        Arc::new(UnionExec::new(inputs))
+    }
+
+    async fn collect_ts(plan: Arc<dyn ExecutionPlan>) -> Vec<i64> {
+        let session_ctx = SessionContext::new();
+        let batches = collect(plan, session_ctx.task_ctx()).await.unwrap();
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let ts = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                ts.values().to_vec()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_remove_sort() {
+        let config = SessionConfig::new();
+        let input = sorted_scan(&[&[1, 3, 5]]);
+
+        let sort = Arc::new(
+            SortExec::try_new(vec![sort_expr("ts", 0, false)], input.clone(), None).unwrap(),
+        );
+        let plan = SortedInputRule.optimize(sort, &config).unwrap();
+        assert!(plan.as_any().downcast_ref::<SortExec>().is_none());
+        assert_eq!(vec![1, 3, 5], collect_ts(plan).await);
+
+        let sort = Arc::new(
+            SortExec::try_new(vec![sort_expr("ts", 0, false)], input.clone(), Some(2)).unwrap(),
+        );
+        let plan = SortedInputRule.optimize(sort, &config).unwrap();
+        assert!(plan.as_any().downcast_ref::<GlobalLimitExec>().is_some());
+        assert_eq!(vec![1, 3], collect_ts(plan).await);
+
+        // Sorts in other orders are kept.
+        let sort = Arc::new(
+            SortExec::try_new(vec![sort_expr("ts", 0, true)], input.clone(), None).unwrap(),
+        );
+        let plan = SortedInputRule.optimize(sort, &config).unwrap();
+        assert!(plan.as_any().downcast_ref::<SortExec>().is_some());
+        assert_eq!(vec![5, 3, 1], collect_ts(plan).await);
+    }
+
+    #[tokio::test]
+    async fn test_merge_sorted_partitions() {
+        let config = SessionConfig::new();
+        let input = sorted_scan(&[&[1, 4, 5], &[2, 3, 6]]);
+        let sort = Arc::new(
+            SortExec::try_new(
+                vec![sort_expr("ts", 0, false)],
+                Arc::new(CoalescePartitionsExec::new(input)),
+                Some(4),
+            )
+            .unwrap(),
+        );
+
+        let plan = SortedInputRule.optimize(sort, &config).unwrap();
+        let limit = plan.as_any().downcast_ref::<GlobalLimitExec>().unwrap();
+        assert!(limit
+            .input()
+            .as_any()
+            .downcast_ref::<SortPreservingMergeExec>()
+            .is_some());
+        assert_eq!(vec![1, 2, 3, 4], collect_ts(plan).await);
+    }
+}
@@ -0,0 +1,439 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Top-k operator for queries like `SELECT * FROM t ORDER BY ts DESC LIMIT 10`.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::{SessionConfig, TaskContext};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
+    Statistics,
+};
+use datatypes::arrow::compute;
+use datatypes::arrow::datatypes::SchemaRef;
+use datatypes::arrow::error::Result as ArrowResult;
+use datatypes::arrow::record_batch::RecordBatch;
+use futures::{ready, Stream, StreamExt};
+
+/// Max `fetch` of a sort to replace by [TopKExec].
+pub const DEFAULT_TOP_K_MAX_FETCH: usize = 4096;
+
+/// TopKRule replaces a [SortExec] with a small `fetch` by a [TopKExec], which only
+/// keeps the first `fetch` rows of each partition instead of sorting all rows, e.g.
+/// for queries of the latest N points.
+pub struct TopKRule {
+    /// Sorts whose `fetch` is greater than this are kept.
+    max_fetch: usize,
+}
+
+impl Default for TopKRule {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOP_K_MAX_FETCH)
+    }
+}
+
+impl TopKRule {
+    pub fn new(max_fetch: usize) -> Self {
+        Self { max_fetch }
+    }
+}
+
+impl PhysicalOptimizerRule for TopKRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &SessionConfig,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        let children = plan
+            .children()
+            .into_iter()
+            .map(|child| self.optimize(child, config))
+            .collect::<DfResult<Vec<_>>>()?;
+        let plan = if children.is_empty() {
+            plan
+        } else {
+            plan.with_new_children(children)?
+        };
+
+        let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+            return Ok(plan);
+        };
+        let Some(fetch) = sort.fetch().filter(|fetch| *fetch <= self.max_fetch) else {
+            return Ok(plan);
+        };
+        // The top-k operator keeps the partitioning of its input, so a sort merging all
+        // partitions of its input is kept.
+        let input = sort.input();
+        if sort.output_partitioning().partition_count()
+            != input.output_partitioning().partition_count()
+        {
+            return Ok(plan);
+        }
+
+        Ok(Arc::new(TopKExec::new(
+            sort.expr().to_vec(),
+            input.clone(),
+            fetch,
+        )))
+    }
+
+    fn name(&self) -> &str {
+        "TopKRule"
+    }
+}
+
+/// Execution plan that returns the first `fetch` rows in the order of `expr` for each
+/// partition of its input.
+///
+/// Rows of each partition are kept in a buffer bounded by `fetch` rows plus an input
+/// batch, so the memory doesn't grow with the number of rows to sort.
+#[derive(Debug)]
+pub struct TopKExec {
+    expr: Vec<PhysicalSortExpr>,
+    input: Arc<dyn ExecutionPlan>,
+    fetch: usize,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl TopKExec {
+    pub fn new(expr: Vec<PhysicalSortExpr>, input: Arc<dyn ExecutionPlan>, fetch: usize) -> Self {
+        Self {
+            expr,
+            input,
+            fetch,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Returns the max number of rows to return for each partition.
+    pub fn fetch(&self) -> usize {
+        self.fetch
+    }
+}
+
+impl ExecutionPlan for TopKExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        Some(&self.expr)
+    }
+
+    fn maintains_input_order(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        assert_eq!(1, children.len());
+        Ok(Arc::new(Self::new(
+            self.expr.clone(),
+            children[0].clone(),
+            self.fetch,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        let schema = input.schema();
+        Ok(Box::pin(TopKStream {
+            top_k: TopK::new(schema.clone(), self.expr.clone(), self.fetch),
+            schema,
+            input,
+            finished: false,
+            metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                let expr: Vec<_> = self.expr.iter().map(|e| e.to_string()).collect();
+                write!(
+                    f,
+                    "TopKExec: fetch={}, expr=[{}]",
+                    self.fetch,
+                    expr.join(",")
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        let input = self.input.statistics();
+        Statistics {
+            num_rows: input.num_rows.map(|num_rows| num_rows.min(self.fetch)),
+            is_exact: input.is_exact,
+            ..Default::default()
+        }
+    }
+}
+
+/// Buffer of the first `fetch` rows of the batches pushed into it.
+struct TopK {
+    schema: SchemaRef,
+    expr: Vec<PhysicalSortExpr>,
+    fetch: usize,
+    /// Sorted rows, at most `fetch` rows.
+    rows: Option<RecordBatch>,
+}
+
+impl TopK {
+    fn new(schema: SchemaRef, expr: Vec<PhysicalSortExpr>, fetch: usize) -> Self {
+        Self {
+            schema,
+            expr,
+            fetch,
+            rows: None,
+        }
+    }
+
+    fn push(&mut self, batch: RecordBatch) -> ArrowResult<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+
+        let batch = match self.rows.take() {
+            Some(rows) => compute::concat_batches(&self.schema, &[rows, batch])?,
+            None => batch,
+        };
+        self.rows = Some(self.sort_and_limit(&batch)?);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        self.rows
+            .take()
+            .unwrap_or_else(|| RecordBatch::new_empty(self.schema.clone()))
+    }
+
+    /// Returns the first `fetch` rows of the `batch` after sorting.
+    fn sort_and_limit(&self, batch: &RecordBatch) -> ArrowResult<RecordBatch> {
+        let sort_columns = self
+            .expr
+            .iter()
+            .map(|expr| expr.evaluate_to_sort_column(batch))
+            .collect::<DfResult<Vec<_>>>()?;
+        let indices = compute::lexsort_to_indices(&sort_columns, Some(self.fetch))?;
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| compute::take(column.as_ref(), &indices, None))
+            .collect::<ArrowResult<Vec<_>>>()?;
+        RecordBatch::try_new(self.schema.clone(), columns)
+    }
+}
+
+struct TopKStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    top_k: TopK,
+    finished: bool,
+    metrics: BaselineMetrics,
+}
+
+impl RecordBatchStream for TopKStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for TopKStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.finished {
+            return Poll::Ready(None);
+        }
+
+        // Consumes all batches of the input before returning the only batch.
+        loop {
+            let poll = ready!(this.input.poll_next_unpin(cx));
+            let _timer = this.metrics.elapsed_compute().timer();
+            let result = match poll {
+                Some(Ok(batch)) => match this.top_k.push(batch) {
+                    Ok(()) => continue,
+                    Err(e) => Err(e),
+                },
+                Some(Err(e)) => Err(e),
+                None => Ok(this.top_k.finish()),
+            };
+            this.finished = true;
+            return this.metrics.record_poll(Poll::Ready(Some(result)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Int64Array, UInt32Array};
+    use datafusion::arrow::compute::SortOptions;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::physical_expr::expressions::col;
+    use datafusion::physical_plan::collect;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    fn new_batch(schema: &SchemaRef, ts: &[i64]) -> RecordBatch {
+        let values: Vec<_> = ts.iter().map(|ts| *ts as u32).collect();
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(ts.to_vec())),
+                Arc::new(UInt32Array::from(values)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn new_input(partitions: &[Vec<&[i64]>]) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", DataType::Int64, false),
+            Field::new("value", DataType::UInt32, false),
+        ]));
+        let partitions: Vec<Vec<_>> = partitions
+            .iter()
+            .map(|batches| batches.iter().map(|ts| new_batch(&schema, ts)).collect())
+            .collect();
+        Arc::new(MemoryExec::try_new(&partitions, schema, None).unwrap())
+    }
+
+    fn ts_desc(input: &Arc<dyn ExecutionPlan>) -> Vec<PhysicalSortExpr> {
+        vec![PhysicalSortExpr {
+            expr: col("ts", &input.schema()).unwrap(),
+            options: SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        }]
+    }
+
+    async fn collect_ts(plan: Arc<dyn ExecutionPlan>) -> Vec<i64> {
+        let session_ctx = SessionContext::new();
+        let batches = collect(plan, session_ctx.task_ctx()).await.unwrap();
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let ts = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                ts.values().to_vec()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_top_k_exec() {
+        let input = new_input(&[vec![&[3, 1, 8][..], &[], &[7, 2], &[9, 4, 5, 6]]]);
+        let expr = ts_desc(&input);
+
+        let top_k = Arc::new(TopKExec::new(expr.clone(), input.clone(), 3));
+        assert_eq!(vec![9, 8, 7], collect_ts(top_k).await);
+
+        let top_k = Arc::new(TopKExec::new(expr.clone(), input.clone(), 100));
+        assert_eq!(vec![9, 8, 7, 6, 5, 4, 3, 2, 1], collect_ts(top_k).await);
+
+        let top_k = Arc::new(TopKExec::new(expr, new_input(&[vec![]]), 3));
+        assert!(collect_ts(top_k).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_top_k_exec_partitions() {
+        let input = new_input(&[vec![&[3, 1, 8][..]], vec![&[7, 2][..], &[9, 4]]]);
+        let expr = ts_desc(&input);
+
+        let top_k = TopKExec::new(expr, input, 2);
+        assert_eq!(2, top_k.output_partitioning().partition_count());
+        let session_ctx = SessionContext::new();
+        let mut result = Vec::new();
+        for partition in 0..2 {
+            let stream = top_k.execute(partition, session_ctx.task_ctx()).unwrap();
+            let batches: Vec<_> = stream.collect().await;
+            let ts: Vec<_> = batches
+                .iter()
+                .flat_map(|batch| {
+                    let column = batch.as_ref().unwrap().column(0);
+                    let ts = column.as_any().downcast_ref::<Int64Array>().unwrap();
+                    ts.values().to_vec()
+                })
+                .collect();
+            result.push(ts);
+        }
+        assert_eq!(vec![vec![8, 3], vec![9, 7]], result);
+    }
+
+    #[tokio::test]
+    async fn test_top_k_rule() {
+        let rule = TopKRule::new(10);
+        let config = SessionConfig::new();
+        let input = new_input(&[vec![&[3, 1, 8][..], &[7, 2]]]);
+        let expr = ts_desc(&input);
+
+        let sort = Arc::new(SortExec::try_new(expr.clone(), input.clone(), Some(2)).unwrap());
+        let plan = rule.optimize(sort, &config).unwrap();
+        let top_k = plan.as_any().downcast_ref::<TopKExec>().unwrap();
+        assert_eq!(2, top_k.fetch());
+        assert_eq!(vec![8, 7], collect_ts(plan).await);
+
+        // Sorts without a small fetch are kept.
+        for fetch in [None, Some(11)] {
+            let sort = Arc::new(SortExec::try_new(expr.clone(), input.clone(), fetch).unwrap());
+            let plan = rule.optimize(sort, &config).unwrap();
+            assert!(plan.as_any().downcast_ref::<SortExec>().is_some());
+        }
+
+        // The sort merges partitions of its input.
+        let input = new_input(&[vec![&[3, 1, 8][..]], vec![&[7, 2][..]]]);
+        let sort = Arc::new(SortExec::try_new(expr, input, Some(2)).unwrap());
+        let plan = rule.optimize(sort, &config).unwrap();
+        assert!(plan.as_any().downcast_ref::<SortExec>().is_some());
+    }
+}
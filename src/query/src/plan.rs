@@ -13,8 +13,15 @@
 // limitations under the License.
 
 use std::fmt::Debug;
+use std::sync::Arc;
 
-use datafusion_expr::LogicalPlan as DfLogicalPlan;
+use datafusion_common::{Column, ScalarValue};
+use datafusion_expr::{col, lit, Expr, Filter, LogicalPlan as DfLogicalPlan};
+use datatypes::arrow::datatypes::{DataType, TimeUnit};
+use datatypes::schema::{Schema, TIME_INDEX_KEY};
+use snafu::ResultExt;
+
+use crate::error::{ConvertPlanSchemaSnafu, RestrictTimeRangeSnafu, Result};
 
 /// A LogicalPlan represents the different types of relational
 /// operators (such as Projection, Filter, etc) and can be created by
@@ -28,3 +35,96 @@ use datafusion_expr::LogicalPlan as DfLogicalPlan;
 pub enum LogicalPlan {
     DfPlan(DfLogicalPlan),
 }
+
+impl LogicalPlan {
+    /// Returns the schema of the output of the plan.
+    pub fn schema(&self) -> Result<Schema> {
+        match self {
+            LogicalPlan::DfPlan(plan) => {
+                let schema =
+                    Schema::try_from(plan.schema().clone()).context(ConvertPlanSchemaSnafu)?;
+                Ok(schema)
+            }
+        }
+    }
+
+    /// Restricts the rows the plan reads to those whose time index lies in
+    /// `[start, end)`, in milliseconds, by placing a filter above each table scan.
+    /// A `None` bound means unbounded.
+    pub fn with_time_range(&self, start: Option<i64>, end: Option<i64>) -> Result<LogicalPlan> {
+        match self {
+            LogicalPlan::DfPlan(plan) => {
+                let plan = restrict_time_range(plan, start, end).context(RestrictTimeRangeSnafu)?;
+                Ok(LogicalPlan::DfPlan(plan))
+            }
+        }
+    }
+}
+
+fn restrict_time_range(
+    plan: &DfLogicalPlan,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> datafusion_common::Result<DfLogicalPlan> {
+    if let DfLogicalPlan::TableScan(scan) = plan {
+        let schema = scan.source.schema();
+        let Some(field) = schema
+            .fields()
+            .iter()
+            .find(|field| field.metadata().contains_key(TIME_INDEX_KEY))
+        else {
+            return Ok(plan.clone());
+        };
+        let DataType::Timestamp(unit, tz) = field.data_type() else {
+            return Ok(plan.clone());
+        };
+        let column = Column::new(Some(scan.table_name.clone()), field.name());
+        let bounds = [
+            start.map(|start| col(column.clone()).gt_eq(timestamp_lit(start, unit, tz))),
+            end.map(|end| col(column.clone()).lt(timestamp_lit(end, unit, tz))),
+        ];
+        let Some(predicate) = bounds.into_iter().flatten().reduce(Expr::and) else {
+            return Ok(plan.clone());
+        };
+        return Ok(DfLogicalPlan::Filter(Filter::try_new(
+            predicate,
+            Arc::new(plan.clone()),
+        )?));
+    }
+
+    let inputs = plan.inputs();
+    if inputs.is_empty() {
+        return Ok(plan.clone());
+    }
+    let new_inputs = inputs
+        .into_iter()
+        .map(|input| restrict_time_range(input, start, end))
+        .collect::<datafusion_common::Result<Vec<_>>>()?;
+    datafusion_expr::utils::from_plan(plan, &plan.expressions(), &new_inputs)
+}
+
+/// Converts the bound in milliseconds to a literal in `unit`. Bounds of coarser units are
+/// rounded up, which keeps `ts >= bound` and `ts < bound` equivalent for any `ts`.
+fn timestamp_lit(millis: i64, unit: &TimeUnit, tz: &Option<String>) -> Expr {
+    let tz = tz.clone();
+    let value = match unit {
+        TimeUnit::Second => ScalarValue::TimestampSecond(Some(div_ceil(millis, 1000)), tz),
+        TimeUnit::Millisecond => ScalarValue::TimestampMillisecond(Some(millis), tz),
+        TimeUnit::Microsecond => {
+            ScalarValue::TimestampMicrosecond(Some(millis.saturating_mul(1000)), tz)
+        }
+        TimeUnit::Nanosecond => {
+            ScalarValue::TimestampNanosecond(Some(millis.saturating_mul(1_000_000)), tz)
+        }
+    };
+    lit(value)
+}
+
+fn div_ceil(value: i64, divisor: i64) -> i64 {
+    let quotient = value.div_euclid(divisor);
+    if value.rem_euclid(divisor) == 0 {
+        quotient
+    } else {
+        quotient + 1
+    }
+}
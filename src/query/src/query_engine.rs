@@ -13,7 +13,9 @@
 // limitations under the License.
 
 mod context;
+mod plan_cache;
 mod state;
+mod tracker;
 
 use std::sync::Arc;
 
@@ -23,14 +25,19 @@ use common_function::scalars::{FunctionRef, FUNCTION_REGISTRY};
 use common_query::physical_plan::PhysicalPlan;
 use common_query::prelude::ScalarUdf;
 use common_query::Output;
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizerRule;
 use session::context::QueryContextRef;
 use sql::statements::statement::Statement;
 
 use crate::datafusion::DatafusionQueryEngine;
 use crate::error::Result;
+use crate::metric;
 use crate::plan::LogicalPlan;
 pub use crate::query_engine::context::QueryEngineContext;
+pub use crate::query_engine::plan_cache::DEFAULT_PLAN_CACHE_CAPACITY;
 pub use crate::query_engine::state::QueryEngineState;
+pub use crate::query_engine::tracker::{QueryId, QueryStatus, QueryTracker, QueryTrackerRef};
 
 #[async_trait::async_trait]
 pub trait QueryEngine: Send + Sync {
@@ -47,11 +54,36 @@ pub trait QueryEngine: Send + Sync {
 
     async fn execute_physical(&self, plan: &Arc<dyn PhysicalPlan>) -> Result<Output>;
 
+    /// Registers a user-defined function, it's executed under the limits of the engine.
     fn register_udf(&self, udf: ScalarUdf);
 
+    /// Registers a user-defined aggregate function, its accumulators are executed under
+    /// the limits of the engine unless the function has its own limits.
     fn register_aggregate_function(&self, func: AggregateFunctionMetaRef);
 
     fn register_function(&self, func: FunctionRef);
+
+    /// Registers a logical optimizer rule, which is applied to the plans after the
+    /// builtin rules.
+    fn register_optimizer_rule(&self, rule: Arc<dyn OptimizerRule + Send + Sync>);
+
+    /// Registers a physical optimizer rule, which is applied to the plans after the
+    /// builtin rules.
+    fn register_physical_optimizer_rule(&self, rule: Arc<dyn PhysicalOptimizerRule + Send + Sync>);
+
+    /// Returns status of the queries that are still running, a query is running until
+    /// its output stream is dropped.
+    fn running_queries(&self) -> Vec<QueryStatus>;
+
+    /// Cancels the running query `query_id`. Tasks executing the query are aborted
+    /// and its output stream returns an error with [StatusCode::Cancelled].
+    ///
+    /// [StatusCode::Cancelled]: common_error::status_code::StatusCode::Cancelled
+    fn cancel(&self, query_id: QueryId) -> Result<()>;
+
+    /// Removes the cached logical plans, it should be called after the tables are
+    /// created, altered or dropped.
+    fn invalidate_plan_cache(&self);
 }
 
 pub struct QueryEngineFactory {
@@ -60,14 +92,24 @@ pub struct QueryEngineFactory {
 
 impl QueryEngineFactory {
     pub fn new(catalog_list: CatalogListRef) -> Self {
-        let query_engine = Arc::new(DatafusionQueryEngine::new(catalog_list));
+        Self::new_with_memory_limit(catalog_list, None)
+    }
+
+    /// Creates a query engine that fails a query once its operators use more than
+    /// `query_memory_limit` bytes of memory.
+    pub fn new_with_memory_limit(
+        catalog_list: CatalogListRef,
+        query_memory_limit: Option<usize>,
+    ) -> Self {
+        metric::register_metrics();
+        let query_engine = Arc::new(DatafusionQueryEngine::new(catalog_list, query_memory_limit));
 
         for func in FUNCTION_REGISTRY.functions() {
             query_engine.register_function(func);
         }
 
         for accumulator in FUNCTION_REGISTRY.aggregate_functions() {
-            query_engine.register_aggregate_function(accumulator);
+            query_engine.register_builtin_aggregate_function(accumulator);
         }
 
         Self { query_engine }
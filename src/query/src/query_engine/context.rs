@@ -13,20 +13,49 @@
 // limitations under the License.
 
 /// Query engine execution context
+use std::sync::Arc;
+
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::SendableRecordBatchStream;
+use datafusion::error::Result as DfResult;
+
 use crate::query_engine::state::QueryEngineState;
+use crate::query_engine::tracker::{QueryGuard, QueryId};
 
 #[derive(Debug)]
 pub struct QueryEngineContext {
     state: QueryEngineState,
+    /// Registration of the query in the query tracker of the engine.
+    query: QueryGuard,
 }
 
 impl QueryEngineContext {
+    /// Creates a context for a new query, the query is registered as running until the
+    /// context, or the stream returned by [QueryEngineContext::track_stream], is dropped.
     pub fn new(state: QueryEngineState) -> Self {
-        Self { state }
+        let query = state.query_tracker().register();
+        Self { state, query }
     }
 
     #[inline]
     pub fn state(&self) -> &QueryEngineState {
         &self.state
     }
+
+    #[inline]
+    pub fn query_id(&self) -> QueryId {
+        self.query.query_id()
+    }
+
+    /// Returns the task context to execute the query, memory used by the query is
+    /// tracked and limited by the query tracker.
+    pub fn task_ctx(&self) -> DfResult<Arc<TaskContext>> {
+        self.state.task_ctx(self.query.memory_pool())
+    }
+
+    /// Wraps the output `stream` of the query so the query could be cancelled by its id
+    /// and fails with a typed error once it exceeds the memory limit.
+    pub fn track_stream(self, stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+        Box::pin(self.query.track_stream(stream))
+    }
 }
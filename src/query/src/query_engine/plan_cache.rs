@@ -0,0 +1,218 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use metrics::increment_counter;
+use table::metadata::TableId;
+
+use crate::metric::{METRIC_PLAN_CACHE_HITS_TOTAL, METRIC_PLAN_CACHE_MISSES_TOTAL};
+use crate::plan::LogicalPlan;
+
+/// Default number of logical plans cached by the query engine.
+pub const DEFAULT_PLAN_CACHE_CAPACITY: usize = 1024;
+
+/// Key of a cached plan, plans of the same statement differ if they are planned under
+/// different sessions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct PlanCacheKey {
+    /// The statement, which is normalized by formatting its syntax tree.
+    pub(crate) sql: String,
+    pub(crate) schema: Option<String>,
+    pub(crate) time_zone: Option<String>,
+}
+
+/// A table referenced by a cached plan and its version when the plan was created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TableVersion {
+    pub(crate) catalog: String,
+    pub(crate) schema: String,
+    pub(crate) table: String,
+    pub(crate) table_id: TableId,
+    pub(crate) schema_version: u32,
+}
+
+struct CachedPlan {
+    plan: LogicalPlan,
+    tables: Vec<TableVersion>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    plans: HashMap<PlanCacheKey, CachedPlan>,
+    /// Increased on each access, to find the least recently used plan.
+    clock: u64,
+}
+
+/// LRU cache of the logical plans of queries. A cached plan is only valid while the
+/// tables it references are not recreated or altered, which is checked on each lookup.
+pub(crate) struct PlanCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl PlanCache {
+    /// Creates a cache holding at most `capacity` plans, which is disabled if it's 0.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the plan of `key` if the tables it references are of the same versions,
+    /// `current_version` returns the current version of a table or `None` if it's absent.
+    pub(crate) fn get(
+        &self,
+        key: &PlanCacheKey,
+        current_version: impl Fn(&TableVersion) -> Option<(TableId, u32)>,
+    ) -> Option<LogicalPlan> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let valid = inner.plans.get(key).map(|cached| {
+            cached
+                .tables
+                .iter()
+                .all(|table| current_version(table) == Some((table.table_id, table.schema_version)))
+        });
+        match valid {
+            Some(true) => {
+                increment_counter!(METRIC_PLAN_CACHE_HITS_TOTAL);
+                let cached = inner.plans.get_mut(key).unwrap();
+                cached.last_used = clock;
+                Some(cached.plan.clone())
+            }
+            Some(false) => {
+                increment_counter!(METRIC_PLAN_CACHE_MISSES_TOTAL);
+                let _ = inner.plans.remove(key);
+                None
+            }
+            None => {
+                increment_counter!(METRIC_PLAN_CACHE_MISSES_TOTAL);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&self, key: PlanCacheKey, plan: LogicalPlan, tables: Vec<TableVersion>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let last_used = inner.clock;
+        if inner.plans.len() >= self.capacity && !inner.plans.contains_key(&key) {
+            let lru = inner
+                .plans
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(lru) = lru {
+                let _ = inner.plans.remove(&lru);
+            }
+        }
+        let _ = inner.plans.insert(
+            key,
+            CachedPlan {
+                plan,
+                tables,
+                last_used,
+            },
+        );
+    }
+
+    /// Removes all the cached plans, e.g. after the tables are changed by DDL.
+    pub(crate) fn clear(&self) {
+        self.inner.lock().unwrap().plans.clear();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.inner.lock().unwrap().plans.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_expr::LogicalPlanBuilder;
+
+    use super::*;
+
+    fn key(sql: &str) -> PlanCacheKey {
+        PlanCacheKey {
+            sql: sql.to_string(),
+            schema: None,
+            time_zone: None,
+        }
+    }
+
+    fn plan() -> LogicalPlan {
+        LogicalPlan::DfPlan(LogicalPlanBuilder::empty(false).build().unwrap())
+    }
+
+    fn table(schema_version: u32) -> TableVersion {
+        TableVersion {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            table: "numbers".to_string(),
+            table_id: 1,
+            schema_version,
+        }
+    }
+
+    #[test]
+    fn test_lru() {
+        let cache = PlanCache::new(2);
+        let any_version = |t: &TableVersion| Some((t.table_id, t.schema_version));
+        cache.insert(key("a"), plan(), vec![]);
+        cache.insert(key("b"), plan(), vec![]);
+        assert!(cache.get(&key("a"), any_version).is_some());
+        // "b" is the least recently used.
+        cache.insert(key("c"), plan(), vec![]);
+        assert_eq!(2, cache.len());
+        assert!(cache.get(&key("b"), any_version).is_none());
+        assert!(cache.get(&key("a"), any_version).is_some());
+        assert!(cache.get(&key("c"), any_version).is_some());
+
+        cache.clear();
+        assert_eq!(0, cache.len());
+
+        let cache = PlanCache::new(0);
+        cache.insert(key("a"), plan(), vec![]);
+        assert_eq!(0, cache.len());
+    }
+
+    #[test]
+    fn test_table_version_changed() {
+        let cache = PlanCache::new(8);
+        cache.insert(key("a"), plan(), vec![table(0)]);
+        assert!(cache.get(&key("a"), |_| Some((1, 0))).is_some());
+        // The table is altered.
+        assert!(cache.get(&key("a"), |_| Some((1, 1))).is_none());
+        assert_eq!(0, cache.len());
+
+        // The table is recreated or dropped.
+        cache.insert(key("a"), plan(), vec![table(0)]);
+        assert!(cache.get(&key("a"), |_| Some((2, 0))).is_none());
+        cache.insert(key("a"), plan(), vec![table(0)]);
+        assert!(cache.get(&key("a"), |_| None).is_none());
+    }
+}
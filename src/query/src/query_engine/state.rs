@@ -24,7 +24,11 @@ use common_query::prelude::ScalarUdf;
 use datafusion::catalog::TableReference;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::{SessionConfig, SessionState};
-use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::execution::disk_manager::DiskManagerConfig;
+use datafusion::execution::memory_pool::MemoryPool;
+use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+use datafusion::optimizer::optimizer::OptimizerRule;
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizerRule;
 use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion_common::ScalarValue;
@@ -32,9 +36,19 @@ use datafusion_expr::{LogicalPlan as DfLogicalPlan, TableSource};
 use datafusion_optimizer::optimizer::Optimizer;
 use datafusion_sql::planner::ContextProvider;
 use datatypes::arrow::datatypes::DataType;
+use table::metadata::TableId;
 
 use crate::datafusion::DfCatalogListAdapter;
-use crate::optimizer::TypeConversionRule;
+use crate::optimizer::{
+    OrderedLimitPushDownRule, PredicateSimplificationRule, TimeRangeFilterPushDownRule,
+    TimestampArithmeticFoldingRule, TypeConversionRule,
+};
+use crate::physical_optimizer::{SortedInputRule, TopKRule};
+use crate::plan::LogicalPlan;
+use crate::query_engine::plan_cache::{
+    PlanCache, PlanCacheKey, TableVersion, DEFAULT_PLAN_CACHE_CAPACITY,
+};
+use crate::query_engine::tracker::{QueryTracker, QueryTrackerRef};
 
 /// Query engine global state
 // TODO(yingwen): This QueryEngineState still relies on datafusion, maybe we can define a trait for it,
@@ -45,6 +59,13 @@ pub struct QueryEngineState {
     df_context: SessionContext,
     catalog_list: CatalogListRef,
     aggregate_functions: Arc<RwLock<HashMap<String, AggregateFunctionMetaRef>>>,
+    query_tracker: QueryTrackerRef,
+    /// Logical optimizer rules registered by other crates, applied after the builtin rules.
+    extension_rules: Arc<RwLock<Vec<Arc<dyn OptimizerRule + Send + Sync>>>>,
+    /// Physical optimizer rules registered by other crates, applied after the builtin rules.
+    extension_physical_rules: Arc<RwLock<Vec<Arc<dyn PhysicalOptimizerRule + Send + Sync>>>>,
+    /// Logical plans of the queries, to skip planning the same statements.
+    plan_cache: Arc<PlanCache>,
 }
 
 impl fmt::Debug for QueryEngineState {
@@ -55,16 +76,42 @@ impl fmt::Debug for QueryEngineState {
 }
 
 impl QueryEngineState {
-    pub(crate) fn new(catalog_list: CatalogListRef) -> Self {
+    /// Creates the state of a query engine, `query_memory_limit` is the max memory a
+    /// query could use.
+    pub(crate) fn new(catalog_list: CatalogListRef, query_memory_limit: Option<usize>) -> Self {
         let runtime_env = Arc::new(RuntimeEnv::default());
         let session_config = SessionConfig::new()
             .with_default_catalog_and_schema(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME);
         let mut optimizer = Optimizer::new();
-        // Apply the type conversion rule first.
-        optimizer.rules.insert(0, Arc::new(TypeConversionRule {}));
+        // Fold timestamp arithmetic like `now() - INTERVAL '1 hour'` into literals first.
+        optimizer
+            .rules
+            .insert(0, Arc::new(TimestampArithmeticFoldingRule {}));
+        // Fold constants and clean up the predicates before converting their literals.
+        optimizer
+            .rules
+            .insert(1, Arc::new(PredicateSimplificationRule {}));
+        // Then apply the type conversion rule.
+        optimizer
+            .rules
+            .insert(2, Arc::new(TypeConversionRule::default()));
+        // Time range extraction relies on literals converted by the rules above.
+        optimizer
+            .rules
+            .insert(3, Arc::new(TimeRangeFilterPushDownRule {}));
+        // Limits under sorts are not handled by datafusion's limit push down.
+        optimizer.rules.push(Arc::new(OrderedLimitPushDownRule {}));
 
         let mut session_state = SessionState::with_config_rt(session_config, runtime_env);
         session_state.optimizer = optimizer;
+        // Removes sorts over inputs already in order, before they become top-k operators.
+        session_state
+            .physical_optimizers
+            .push(Arc::new(SortedInputRule));
+        // Replaces sorts with a small limit by the top-k operator.
+        session_state
+            .physical_optimizers
+            .push(Arc::new(TopKRule::default()));
         session_state.catalog_list = Arc::new(DfCatalogListAdapter::new(catalog_list.clone()));
 
         let df_context = SessionContext::with_state(session_state);
@@ -73,9 +120,28 @@ impl QueryEngineState {
             df_context,
             catalog_list,
             aggregate_functions: Arc::new(RwLock::new(HashMap::new())),
+            query_tracker: Arc::new(QueryTracker::new(query_memory_limit)),
+            extension_rules: Arc::new(RwLock::new(Vec::new())),
+            extension_physical_rules: Arc::new(RwLock::new(Vec::new())),
+            plan_cache: Arc::new(PlanCache::new(DEFAULT_PLAN_CACHE_CAPACITY)),
         }
     }
 
+    /// Registers a logical optimizer rule, which is applied after the builtin rules
+    /// and the rules registered before it.
+    pub fn register_optimizer_rule(&self, rule: Arc<dyn OptimizerRule + Send + Sync>) {
+        self.extension_rules.write().unwrap().push(rule);
+    }
+
+    /// Registers a physical optimizer rule, which is applied after the builtin rules
+    /// and the rules registered before it.
+    pub fn register_physical_optimizer_rule(
+        &self,
+        rule: Arc<dyn PhysicalOptimizerRule + Send + Sync>,
+    ) {
+        self.extension_physical_rules.write().unwrap().push(rule);
+    }
+
     /// Register a udf function
     // TODO(dennis): manage UDFs by ourself.
     pub fn register_udf(&self, udf: ScalarUdf) {
@@ -105,8 +171,64 @@ impl QueryEngineState {
     }
 
     #[inline]
-    pub(crate) fn task_ctx(&self) -> Arc<TaskContext> {
-        self.df_context.task_ctx()
+    pub fn query_tracker(&self) -> &QueryTrackerRef {
+        &self.query_tracker
+    }
+
+    #[inline]
+    pub(crate) fn plan_cache_enabled(&self) -> bool {
+        self.plan_cache.is_enabled()
+    }
+
+    /// Returns the cached plan of `key` unless the tables it references are changed.
+    pub(crate) fn cached_plan(&self, key: &PlanCacheKey) -> Option<LogicalPlan> {
+        self.plan_cache.get(key, |table| {
+            self.table_version(&table.catalog, &table.schema, &table.table)
+        })
+    }
+
+    pub(crate) fn cache_plan(
+        &self,
+        key: PlanCacheKey,
+        plan: LogicalPlan,
+        tables: Vec<TableVersion>,
+    ) {
+        self.plan_cache.insert(key, plan, tables);
+    }
+
+    pub fn clear_plan_cache(&self) {
+        self.plan_cache.clear();
+    }
+
+    /// Returns the id and the schema version of a table, or `None` if it's not found.
+    pub(crate) fn table_version(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+    ) -> Option<(TableId, u32)> {
+        let table = self
+            .catalog_list
+            .catalog(catalog)
+            .ok()??
+            .schema(schema)
+            .ok()??
+            .table(table)
+            .ok()??;
+        Some((table.table_info().ident.table_id, table.schema().version()))
+    }
+
+    /// Returns the task context to execute a query, operators of the query reserve
+    /// memory from `memory_pool` instead of the pool of the engine.
+    pub(crate) fn task_ctx(&self, memory_pool: Arc<dyn MemoryPool>) -> DfResult<Arc<TaskContext>> {
+        let mut state = self.df_context.state();
+        let runtime_config = RuntimeConfig::new()
+            .with_memory_pool(memory_pool)
+            .with_disk_manager(DiskManagerConfig::Existing(
+                state.runtime_env.disk_manager.clone(),
+            ));
+        state.runtime_env = Arc::new(RuntimeEnv::new(runtime_config)?);
+        Ok(Arc::new(TaskContext::from(&state)))
     }
 
     pub(crate) fn get_table_provider(
@@ -135,7 +257,14 @@ impl QueryEngineState {
     }
 
     pub(crate) fn optimize(&self, plan: &DfLogicalPlan) -> DfResult<DfLogicalPlan> {
-        self.df_context.optimize(plan)
+        let extension_rules = self.extension_rules.read().unwrap().clone();
+        if extension_rules.is_empty() {
+            return self.df_context.optimize(plan);
+        }
+
+        let mut state = self.df_context.state();
+        state.optimizer.rules.extend(extension_rules);
+        state.optimize(plan)
     }
 
     pub(crate) async fn create_physical_plan(
@@ -154,6 +283,10 @@ impl QueryEngineState {
         for optimizer in &state.physical_optimizers {
             plan = optimizer.optimize(plan, config)?;
         }
+        let extension_rules = self.extension_physical_rules.read().unwrap().clone();
+        for optimizer in &extension_rules {
+            plan = optimizer.optimize(plan, config)?;
+        }
 
         Ok(plan)
     }
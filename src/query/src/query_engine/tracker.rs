@@ -0,0 +1,418 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracks running queries, the memory used by them and cancels them.
+//!
+//! Each query has its own DataFusion [MemoryPool], operators of the query (e.g. sorts,
+//! aggregations and joins) reserve memory from the pool while they buffer rows. A query
+//! fails once its operators can't reserve more memory than the memory limit.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use common_error::ext::BoxedError;
+use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use datafusion::error::Result as DfResult;
+use datafusion::execution::memory_pool::{
+    GreedyMemoryPool, MemoryConsumer, MemoryPool, MemoryReservation, UnboundedMemoryPool,
+};
+use datatypes::schema::SchemaRef;
+use futures::task::AtomicWaker;
+use futures::{Stream, StreamExt};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Error, Result};
+
+/// Id of a query, unique in the query engine.
+pub type QueryId = u64;
+
+/// Status of a running query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryStatus {
+    pub query_id: QueryId,
+    /// Memory reserved by the operators of the query.
+    pub memory_used: usize,
+}
+
+/// Registry of the running queries of a query engine.
+#[derive(Debug, Default)]
+pub struct QueryTracker {
+    next_query_id: AtomicU64,
+    /// Max memory a query could use, `None` means no limit.
+    memory_limit: Option<usize>,
+    queries: Mutex<HashMap<QueryId, Arc<QueryHandle>>>,
+}
+
+pub type QueryTrackerRef = Arc<QueryTracker>;
+
+impl QueryTracker {
+    pub fn new(memory_limit: Option<usize>) -> Self {
+        Self {
+            next_query_id: AtomicU64::new(0),
+            memory_limit,
+            queries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[inline]
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
+
+    /// Registers a new query, the query is removed from the tracker once the returned
+    /// guard is dropped.
+    pub(crate) fn register(self: &Arc<Self>) -> QueryGuard {
+        let query_id = self.next_query_id.fetch_add(1, Ordering::Relaxed);
+        let handle = Arc::new(QueryHandle::new(query_id, self.memory_limit));
+        self.queries
+            .lock()
+            .unwrap()
+            .insert(query_id, handle.clone());
+
+        QueryGuard {
+            tracker: self.clone(),
+            handle,
+        }
+    }
+
+    /// Cancels the query `query_id`, its output stream returns a cancelled error the
+    /// next time it's polled.
+    pub fn cancel(&self, query_id: QueryId) -> Result<()> {
+        let queries = self.queries.lock().unwrap();
+        let handle = queries
+            .get(&query_id)
+            .context(error::QueryNotFoundSnafu { query_id })?;
+        handle.cancel();
+        Ok(())
+    }
+
+    /// Returns status of running queries, ordered by query id.
+    pub fn running_queries(&self) -> Vec<QueryStatus> {
+        let mut queries: Vec<_> = self
+            .queries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|handle| QueryStatus {
+                query_id: handle.query_id,
+                memory_used: handle.memory_pool.reserved(),
+            })
+            .collect();
+        queries.sort_unstable_by_key(|status| status.query_id);
+        queries
+    }
+}
+
+/// Shared state of a running query.
+#[derive(Debug)]
+pub(crate) struct QueryHandle {
+    query_id: QueryId,
+    memory_pool: Arc<QueryMemoryPool>,
+    cancelled: AtomicBool,
+    /// Wakes up the output stream of the query on cancellation.
+    waker: AtomicWaker,
+}
+
+impl QueryHandle {
+    fn new(query_id: QueryId, memory_limit: Option<usize>) -> Self {
+        Self {
+            query_id,
+            memory_pool: Arc::new(QueryMemoryPool::new(memory_limit)),
+            cancelled: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Memory pool of a query, remembers whether the query has failed to reserve memory.
+#[derive(Debug)]
+struct QueryMemoryPool {
+    inner: Arc<dyn MemoryPool>,
+    limit: Option<usize>,
+    exhausted: AtomicBool,
+}
+
+impl QueryMemoryPool {
+    fn new(limit: Option<usize>) -> Self {
+        let inner: Arc<dyn MemoryPool> = match limit {
+            Some(limit) => Arc::new(GreedyMemoryPool::new(limit)),
+            None => Arc::new(UnboundedMemoryPool::default()),
+        };
+        Self {
+            inner,
+            limit,
+            exhausted: AtomicBool::new(false),
+        }
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Acquire)
+    }
+}
+
+impl MemoryPool for QueryMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.inner.grow(reservation, additional)
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.inner.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> DfResult<()> {
+        self.inner.try_grow(reservation, additional).map_err(|e| {
+            self.exhausted.store(true, Ordering::Release);
+            e
+        })
+    }
+
+    fn reserved(&self) -> usize {
+        self.inner.reserved()
+    }
+}
+
+/// Registration of a running query, removes the query from the tracker on drop.
+#[derive(Debug)]
+pub(crate) struct QueryGuard {
+    tracker: QueryTrackerRef,
+    handle: Arc<QueryHandle>,
+}
+
+impl QueryGuard {
+    #[inline]
+    pub(crate) fn query_id(&self) -> QueryId {
+        self.handle.query_id
+    }
+
+    /// Returns the memory pool the operators of the query reserve memory from.
+    pub(crate) fn memory_pool(&self) -> Arc<dyn MemoryPool> {
+        self.handle.memory_pool.clone()
+    }
+
+    /// Wraps the output `stream` of the query, the query is kept running until the
+    /// returned stream is dropped.
+    pub(crate) fn track_stream(self, stream: SendableRecordBatchStream) -> TrackedStream {
+        TrackedStream {
+            schema: stream.schema(),
+            guard: self,
+            inner: Some(stream),
+        }
+    }
+}
+
+impl Drop for QueryGuard {
+    fn drop(&mut self) {
+        self.tracker
+            .queries
+            .lock()
+            .unwrap()
+            .remove(&self.handle.query_id);
+    }
+}
+
+/// A stream stops once the query is cancelled or exceeds the memory limit.
+///
+/// The inner stream is dropped once the query is stopped, which also aborts the tasks
+/// spawned by DataFusion to execute the plan.
+pub(crate) struct TrackedStream {
+    schema: SchemaRef,
+    guard: QueryGuard,
+    inner: Option<SendableRecordBatchStream>,
+}
+
+impl TrackedStream {
+    /// Stops the query with error `err`.
+    fn stop(&mut self, err: Error) -> Poll<Option<RecordBatchResult<RecordBatch>>> {
+        self.inner = None;
+        Poll::Ready(Some(Err(BoxedError::new(err)).context(ExternalSnafu)))
+    }
+}
+
+impl RecordBatchStream for TrackedStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for TrackedStream {
+    type Item = RecordBatchResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        let handle = this.guard.handle.clone();
+        handle.waker.register(cx.waker());
+        if handle.is_cancelled() {
+            return this.stop(
+                error::QueryCancelledSnafu {
+                    query_id: handle.query_id,
+                }
+                .build()
+                .into(),
+            );
+        }
+
+        match inner.poll_next_unpin(cx) {
+            // The operator fails the query with a DataFusion error once it can't reserve
+            // memory, reports the error as the query exceeds its memory limit.
+            Poll::Ready(Some(Err(_))) if handle.memory_pool.is_exhausted() => this.stop(
+                error::QueryMemoryExceededSnafu {
+                    query_id: handle.query_id,
+                    limit: handle.memory_pool.limit.unwrap_or_default(),
+                }
+                .build()
+                .into(),
+            ),
+            Poll::Ready(None) => {
+                this.inner = None;
+                Poll::Ready(None)
+            }
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::*;
+    use common_recordbatch::{util, RecordBatches};
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::UInt32Vector;
+
+    use super::*;
+
+    fn new_batches(num_batches: u32) -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "number",
+            ConcreteDataType::uint32_datatype(),
+            false,
+        )]));
+        let batches = (0..num_batches)
+            .map(|i| {
+                let column: VectorRef = Arc::new(UInt32Vector::from_slice([i; 16]));
+                RecordBatch::new(schema.clone(), vec![column]).unwrap()
+            })
+            .collect();
+        RecordBatches::try_new(schema, batches).unwrap()
+    }
+
+    fn new_stream(num_batches: u32) -> SendableRecordBatchStream {
+        new_batches(num_batches).as_stream()
+    }
+
+    #[tokio::test]
+    async fn test_track_stream() {
+        let tracker = Arc::new(QueryTracker::default());
+        let guard = tracker.register();
+        let query_id = guard.query_id();
+        assert_eq!(
+            vec![QueryStatus {
+                query_id,
+                memory_used: 0
+            }],
+            tracker.running_queries()
+        );
+
+        let stream = guard.track_stream(new_stream(3));
+        let batches = util::collect(Box::pin(stream)).await.unwrap();
+        assert_eq!(3, batches.len());
+        // The query is removed once its stream is dropped.
+        assert!(tracker.running_queries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_query() {
+        let tracker = Arc::new(QueryTracker::default());
+        let guard = tracker.register();
+        let query_id = guard.query_id();
+        let mut stream = guard.track_stream(new_stream(3));
+
+        assert!(stream.next().await.unwrap().is_ok());
+        tracker.cancel(query_id).unwrap();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+        assert!(stream.next().await.is_none());
+
+        let err = tracker.cancel(query_id + 1).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit() {
+        let tracker = Arc::new(QueryTracker::new(Some(1024)));
+        let guard = tracker.register();
+        let pool = guard.memory_pool();
+
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        reservation.try_grow(512).unwrap();
+        assert_eq!(512, tracker.running_queries()[0].memory_used);
+        assert!(reservation.try_grow(1024).is_err());
+
+        // Errors of the query are reported as exceeding the memory limit.
+        let mut stream = guard.track_stream(Box::pin(ErrorStream(new_batches(0).schema())));
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::RuntimeResourcesExhausted, err.status_code());
+        assert!(stream.next().await.is_none());
+
+        drop(reservation);
+        assert_eq!(0, pool.reserved());
+    }
+
+    /// A stream always returns an error.
+    struct ErrorStream(SchemaRef);
+
+    impl RecordBatchStream for ErrorStream {
+        fn schema(&self) -> SchemaRef {
+            self.0.clone()
+        }
+    }
+
+    impl Stream for ErrorStream {
+        type Item = RecordBatchResult<RecordBatch>;
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let err = error::QueryCancelledSnafu { query_id: 0u64 }.build();
+            Poll::Ready(Some(
+                Err(BoxedError::new(Error::from(err))).context(ExternalSnafu),
+            ))
+        }
+    }
+}
@@ -17,13 +17,17 @@ use std::sync::Arc;
 use catalog::CatalogManagerRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
-use common_recordbatch::RecordBatches;
+use common_recordbatch::{DfRecordBatch, RecordBatch, RecordBatches};
+use datafusion::arrow::datatypes::{Field, Schema as ArrowSchema};
+use datafusion::datasource::MemTable;
+use datafusion::error::DataFusionError;
+use datafusion::prelude::SessionContext;
 use datatypes::prelude::*;
-use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use datatypes::vectors::{Helper, StringVector};
 use once_cell::sync::Lazy;
 use session::context::QueryContextRef;
-use snafu::{ensure, OptionExt, ResultExt};
+use snafu::{OptionExt, ResultExt};
 use sql::statements::describe::DescribeTable;
 use sql::statements::explain::Explain;
 use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
@@ -34,6 +38,9 @@ use crate::QueryEngineRef;
 
 const SCHEMAS_COLUMN: &str = "Schemas";
 const TABLES_COLUMN: &str = "Tables";
+const TABLE_TYPE_COLUMN: &str = "Table_type";
+/// Name of the in-memory table to filter the output of SHOW statements.
+const SHOW_OUTPUT_TABLE: &str = "show_output";
 const COLUMN_NAME_COLUMN: &str = "Field";
 const COLUMN_TYPE_COLUMN: &str = "Type";
 const COLUMN_NULLABLE_COLUMN: &str = "Null";
@@ -77,15 +84,10 @@ static DESCRIBE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     ]))
 });
 
-pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -> Result<Output> {
-    // TODO(LFC): supports WHERE
-    ensure!(
-        matches!(stmt.kind, ShowKind::All | ShowKind::Like(_)),
-        error::UnsupportedExprSnafu {
-            name: stmt.kind.to_string(),
-        }
-    );
-
+pub async fn show_databases(
+    stmt: ShowDatabases,
+    catalog_manager: CatalogManagerRef,
+) -> Result<Output> {
     let catalog = catalog_manager
         .catalog(DEFAULT_CATALOG_NAME)
         .context(error::CatalogSnafu)?
@@ -94,7 +96,7 @@ pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -
         })?;
     let databases = catalog.schema_names().context(error::CatalogSnafu)?;
 
-    let databases = if let ShowKind::Like(ident) = stmt.kind {
+    let databases = if let ShowKind::Like(ident) = &stmt.kind {
         Helper::like_utf8(databases, &ident.value).context(error::VectorComputationSnafu)?
     } else {
         Arc::new(StringVector::from(databases))
@@ -105,24 +107,14 @@ pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -
         ConcreteDataType::string_datatype(),
         false,
     )]));
-    let records = RecordBatches::try_from_columns(schema, vec![databases])
-        .context(error::CreateRecordBatchSnafu)?;
-    Ok(Output::RecordBatches(records))
+    show_output(schema, vec![databases], &stmt.kind).await
 }
 
-pub fn show_tables(
+pub async fn show_tables(
     stmt: ShowTables,
     catalog_manager: CatalogManagerRef,
     query_ctx: QueryContextRef,
 ) -> Result<Output> {
-    // TODO(LFC): supports WHERE
-    ensure!(
-        matches!(stmt.kind, ShowKind::All | ShowKind::Like(_)),
-        error::UnsupportedExprSnafu {
-            name: stmt.kind.to_string(),
-        }
-    );
-
     let schema = if let Some(database) = stmt.database {
         database
     } else {
@@ -130,25 +122,111 @@ pub fn show_tables(
             .current_schema()
             .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string())
     };
-    let schema = catalog_manager
+    let schema_provider = catalog_manager
         .schema(DEFAULT_CATALOG_NAME, &schema)
         .context(error::CatalogSnafu)?
         .context(error::SchemaNotFoundSnafu { schema })?;
-    let tables = schema.table_names().context(error::CatalogSnafu)?;
+    let tables = schema_provider.table_names().context(error::CatalogSnafu)?;
 
-    let tables = if let ShowKind::Like(ident) = stmt.kind {
+    let tables = if let ShowKind::Like(ident) = &stmt.kind {
         Helper::like_utf8(tables, &ident.value).context(error::VectorComputationSnafu)?
     } else {
         Arc::new(StringVector::from(tables))
     };
 
-    let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+    let mut column_schemas = vec![ColumnSchema::new(
         TABLES_COLUMN,
         ConcreteDataType::string_datatype(),
         false,
-    )]));
-    let records = RecordBatches::try_from_columns(schema, vec![tables])
-        .context(error::CreateRecordBatchSnafu)?;
+    )];
+    let mut columns = vec![tables.clone()];
+    if stmt.full {
+        // Only tables matching the LIKE pattern are loaded to get their types.
+        let mut table_types = Vec::with_capacity(tables.len());
+        for i in 0..tables.len() {
+            let Value::String(name) = tables.get(i) else {
+                unreachable!()
+            };
+            let table_type = schema_provider
+                .table(name.as_utf8())
+                .context(error::CatalogSnafu)?
+                .map(|table| table.table_type().name());
+            table_types.push(table_type);
+        }
+
+        column_schemas.push(ColumnSchema::new(
+            TABLE_TYPE_COLUMN,
+            ConcreteDataType::string_datatype(),
+            true,
+        ));
+        columns.push(Arc::new(StringVector::from(table_types)));
+    }
+
+    let schema = Arc::new(Schema::new(column_schemas));
+    show_output(schema, columns, &stmt.kind).await
+}
+
+/// Builds the output of a SHOW statement from `columns`, rows are filtered by the
+/// `WHERE` clause of the statement if present.
+///
+/// The filter is executed as a plan over an in-memory table of the rows. Columns of
+/// the table are renamed to lower case, as unquoted column names in the filter are
+/// normalized to lower case by the planner.
+async fn show_output(
+    schema: SchemaRef,
+    columns: Vec<VectorRef>,
+    kind: &ShowKind,
+) -> Result<Output> {
+    let ShowKind::Where(filter) = kind else {
+        let records = RecordBatches::try_from_columns(schema, columns)
+            .context(error::CreateRecordBatchSnafu)?;
+        return Ok(Output::RecordBatches(records));
+    };
+
+    let fields = schema
+        .arrow_schema()
+        .fields()
+        .iter()
+        .map(|field| {
+            Field::new(
+                &field.name().to_lowercase(),
+                field.data_type().clone(),
+                field.is_nullable(),
+            )
+        })
+        .collect();
+    let arrow_schema = Arc::new(ArrowSchema::new(fields));
+    let arrays = columns
+        .iter()
+        .map(|column| column.to_arrow_array())
+        .collect();
+    let batch = DfRecordBatch::try_new(arrow_schema.clone(), arrays)
+        .map_err(DataFusionError::from)
+        .context(error::FilterShowOutputSnafu)?;
+    let table =
+        MemTable::try_new(arrow_schema, vec![vec![batch]]).context(error::FilterShowOutputSnafu)?;
+
+    let ctx = SessionContext::new();
+    let _ = ctx
+        .register_table(SHOW_OUTPUT_TABLE, Arc::new(table))
+        .context(error::FilterShowOutputSnafu)?;
+    let filtered = ctx
+        .sql(&format!("SELECT * FROM {SHOW_OUTPUT_TABLE} WHERE {filter}"))
+        .await
+        .context(error::FilterShowOutputSnafu)?
+        .collect()
+        .await
+        .context(error::FilterShowOutputSnafu)?;
+
+    let batches = filtered
+        .iter()
+        .map(|batch| {
+            let columns =
+                Helper::try_into_vectors(batch.columns()).context(error::VectorComputationSnafu)?;
+            RecordBatch::new(schema.clone(), columns).context(error::CreateRecordBatchSnafu)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let records = RecordBatches::try_new(schema, batches).context(error::CreateRecordBatchSnafu)?;
     Ok(Output::RecordBatches(records))
 }
 
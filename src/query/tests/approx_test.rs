@@ -0,0 +1,118 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod function;
+
+use std::sync::Arc;
+
+use common_query::Output;
+use common_recordbatch::error::Result as RecordResult;
+use common_recordbatch::{util, RecordBatch};
+use datatypes::for_all_primitive_types;
+use datatypes::prelude::*;
+use datatypes::value::OrderedFloat;
+use function::{create_query_engine, get_numbers_from_table};
+use num_traits::AsPrimitive;
+use query::error::Result;
+use query::QueryEngine;
+use session::context::QueryContext;
+
+#[tokio::test]
+async fn test_approx_aggregators() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let engine = create_query_engine();
+
+    macro_rules! test_approx {
+        ([], $( { $T:ty } ),*) => {
+            $(
+                let column_name = format!("{}_number", std::any::type_name::<$T>());
+                test_approx_count_distinct::<$T>(&column_name, "numbers", engine.clone()).await?;
+                test_approx_percentile::<$T>(&column_name, "numbers", engine.clone()).await?;
+            )*
+        }
+    }
+    for_all_primitive_types! { test_approx }
+    Ok(())
+}
+
+async fn test_approx_count_distinct<T>(
+    column_name: &str,
+    table_name: &str,
+    engine: Arc<dyn QueryEngine>,
+) -> Result<()>
+where
+    T: WrapperType + AsPrimitive<f64>,
+{
+    let sql = format!("select APPROX_COUNT_DISTINCT({column_name}) as cnt from {table_name}");
+    let result = execute(&sql, engine.clone()).await.unwrap();
+    let value = function::get_value_from_batches("cnt", result);
+
+    let numbers = get_numbers_from_table::<T>(column_name, table_name, engine.clone()).await;
+    let mut numbers = numbers.iter().map(|&n| n.as_()).collect::<Vec<f64>>();
+    numbers.sort_by(|a, b| a.total_cmp(b));
+    numbers.dedup();
+
+    // The estimate of a few values is almost exact.
+    let Value::UInt64(value) = value else {
+        unreachable!()
+    };
+    assert!(
+        value.abs_diff(numbers.len() as u64) <= 1,
+        "value: {value}, expect: {}",
+        numbers.len()
+    );
+    Ok(())
+}
+
+async fn test_approx_percentile<T>(
+    column_name: &str,
+    table_name: &str,
+    engine: Arc<dyn QueryEngine>,
+) -> Result<()>
+where
+    T: WrapperType + AsPrimitive<f64>,
+{
+    let sql = format!(
+        "select APPROX_PERCENTILE({column_name},50.0) as percentile, \
+        PERCENTILE({column_name},50.0) as expect from {table_name}"
+    );
+    let result = execute(&sql, engine).await.unwrap();
+    assert_eq!(1, result.len());
+    let batch = &result[0];
+
+    // The result is exact if there are only a few values.
+    let (Value::Float64(OrderedFloat(value)), Value::Float64(OrderedFloat(expect))) =
+        (batch.column(0).get(0), batch.column(1).get(0))
+    else {
+        unreachable!()
+    };
+    assert!(
+        (value - expect).abs() <= expect.abs().max(1.0) * 1e-9,
+        "value: {value}, expect: {expect}"
+    );
+    Ok(())
+}
+
+async fn execute(sql: &str, engine: Arc<dyn QueryEngine>) -> RecordResult<Vec<RecordBatch>> {
+    let plan = engine
+        .sql_to_plan(sql, Arc::new(QueryContext::new()))
+        .unwrap();
+
+    let output = engine.execute(&plan).await.unwrap();
+    let recordbatch_stream = match output {
+        Output::Stream(batch) => batch,
+        _ => unreachable!(),
+    };
+    util::collect(recordbatch_stream).await
+}
@@ -0,0 +1,67 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+#[allow(unused)]
+mod function;
+
+use datatypes::prelude::*;
+use datatypes::schema::ColumnSchema;
+use datatypes::vectors::{Float64Vector, TimestampMillisecondVector};
+use query::QueryEngine;
+
+fn create_query_engine() -> Arc<dyn QueryEngine> {
+    let column_schemas = vec![
+        ColumnSchema::new("counter", ConcreteDataType::float64_datatype(), true),
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+    ];
+    let columns: Vec<VectorRef> = vec![
+        // The counter is reset at the last sample.
+        Arc::new(Float64Vector::from_vec(vec![10.0, 20.0, 5.0])),
+        Arc::new(TimestampMillisecondVector::from_vec(vec![0, 1000, 2000])),
+    ];
+    let table = Arc::new(function::new_memtable("counters", column_schemas, columns));
+
+    function::create_query_engine_with_table(table)
+}
+
+async fn execute_counter_function(function: &str, engine: Arc<dyn QueryEngine>) -> Value {
+    let sql = format!("select {function}(counter, ts) as {function} from counters");
+    let batches = function::execute(&sql, &engine).await;
+    function::get_value_from_batches(function, batches)
+}
+
+#[tokio::test]
+async fn test_counter_aggregators() {
+    common_telemetry::init_default_ut_logging();
+    let engine = create_query_engine();
+
+    assert_eq!(
+        Value::from(22.5),
+        execute_counter_function("increase", engine.clone()).await
+    );
+    assert_eq!(
+        Value::from(7.5),
+        execute_counter_function("rate", engine.clone()).await
+    );
+    assert_eq!(
+        Value::from(-7.5),
+        execute_counter_function("delta", engine).await
+    );
+}
@@ -21,22 +21,21 @@ use catalog::{CatalogList, CatalogProvider, SchemaProvider};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
+use common_time::Timestamp;
 use datatypes::for_all_primitive_types;
 use datatypes::prelude::*;
 use datatypes::schema::{ColumnSchema, Schema};
 use datatypes::types::WrapperType;
-use datatypes::vectors::Helper;
+use datatypes::vectors::{Float64Vector, Helper, StringVector, TimestampMillisecondVector};
+use query::plan::LogicalPlan;
 use query::query_engine::QueryEngineFactory;
 use query::QueryEngine;
 use rand::Rng;
 use session::context::QueryContext;
 use table::test_util::MemTable;
+use table::TableRef;
 
 pub fn create_query_engine() -> Arc<dyn QueryEngine> {
-    let schema_provider = Arc::new(MemorySchemaProvider::new());
-    let catalog_provider = Arc::new(MemoryCatalogProvider::new());
-    let catalog_list = Arc::new(MemoryCatalogManager::default());
-
     let mut column_schemas = vec![];
     let mut columns = vec![];
     macro_rules! create_number_table {
@@ -56,16 +55,56 @@ pub fn create_query_engine() -> Arc<dyn QueryEngine> {
     }
     for_all_primitive_types! { create_number_table }
 
-    let schema = Arc::new(Schema::new(column_schemas.clone()));
+    let number_table = Arc::new(new_memtable("numbers", column_schemas, columns));
+    create_query_engine_with_table(number_table)
+}
+
+/// Creates a query engine with the table `metrics`, which has 5 rows of the columns
+/// `host`, `ts` and `cpu`.
+pub fn create_metrics_query_engine() -> Arc<dyn QueryEngine> {
+    let column_schemas = vec![
+        ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+    ];
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(vec!["b", "a", "b", "a", "a"])),
+        Arc::new(TimestampMillisecondVector::from_vec(vec![
+            2000, 3000, 1000, 1000, 2000,
+        ])),
+        Arc::new(Float64Vector::from_vec(vec![20.0, 3.0, 10.0, 1.0, 2.0])),
+    ];
+    let table = Arc::new(new_memtable("metrics", column_schemas, columns));
+    create_query_engine_with_table(table)
+}
+
+/// Creates a [MemTable] of the columns.
+pub fn new_memtable(
+    table_name: &str,
+    column_schemas: Vec<ColumnSchema>,
+    columns: Vec<VectorRef>,
+) -> MemTable {
+    let schema = Arc::new(Schema::new(column_schemas));
     let recordbatch = RecordBatch::new(schema, columns).unwrap();
-    let number_table = Arc::new(MemTable::new("numbers", recordbatch));
+    MemTable::new(table_name, recordbatch)
+}
+
+/// Creates a query engine with the `table` registered in the default schema.
+pub fn create_query_engine_with_table(table: TableRef) -> Arc<dyn QueryEngine> {
+    let schema_provider = Arc::new(MemorySchemaProvider::new());
     schema_provider
-        .register_table(number_table.table_name().to_string(), number_table)
+        .register_table(table.table_info().name.clone(), table)
         .unwrap();
-
+    let catalog_provider = Arc::new(MemoryCatalogProvider::new());
     catalog_provider
         .register_schema(DEFAULT_SCHEMA_NAME.to_string(), schema_provider)
         .unwrap();
+    let catalog_list = Arc::new(MemoryCatalogManager::default());
     catalog_list
         .register_catalog(DEFAULT_CATALOG_NAME.to_string(), catalog_provider)
         .unwrap();
@@ -73,6 +112,23 @@ pub fn create_query_engine() -> Arc<dyn QueryEngine> {
     QueryEngineFactory::new(catalog_list).query_engine()
 }
 
+/// Executes the `sql` and collects the record batches of the output.
+pub async fn execute(sql: &str, engine: &Arc<dyn QueryEngine>) -> Vec<RecordBatch> {
+    let plan = engine
+        .sql_to_plan(sql, Arc::new(QueryContext::new()))
+        .unwrap();
+    execute_plan(&plan, engine).await
+}
+
+pub async fn execute_plan(plan: &LogicalPlan, engine: &Arc<dyn QueryEngine>) -> Vec<RecordBatch> {
+    let output = engine.execute(plan).await.unwrap();
+    let recordbatch_stream = match output {
+        Output::Stream(batch) => batch,
+        _ => unreachable!(),
+    };
+    util::collect(recordbatch_stream).await.unwrap()
+}
+
 pub async fn get_numbers_from_table<'s, T>(
     column_name: &'s str,
     table_name: &'s str,
@@ -82,16 +138,7 @@ where
     T: WrapperType,
 {
     let sql = format!("SELECT {column_name} FROM {table_name}");
-    let plan = engine
-        .sql_to_plan(&sql, Arc::new(QueryContext::new()))
-        .unwrap();
-
-    let output = engine.execute(&plan).await.unwrap();
-    let recordbatch_stream = match output {
-        Output::Stream(batch) => batch,
-        _ => unreachable!(),
-    };
-    let numbers = util::collect(recordbatch_stream).await.unwrap();
+    let numbers = execute(&sql, &engine).await;
 
     let column = numbers[0].column(0);
     let column: &<T as Scalar>::VectorType = unsafe { Helper::static_cast(column) };
@@ -111,3 +158,25 @@ pub fn get_value_from_batches(column_name: &str, batches: Vec<RecordBatch>) -> V
     assert_eq!(1, v.len());
     v.get(0)
 }
+
+/// Collects the rows of the batches.
+pub fn collect_rows(batches: &[RecordBatch]) -> Vec<Vec<Value>> {
+    let mut rows = Vec::new();
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            rows.push(
+                batch
+                    .columns()
+                    .iter()
+                    .map(|column| column.get(row))
+                    .collect(),
+            );
+        }
+    }
+    rows
+}
+
+/// Returns the millisecond timestamp value.
+pub fn ts(millis: i64) -> Value {
+    Value::Timestamp(Timestamp::new_millisecond(millis))
+}
@@ -0,0 +1,302 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+#[allow(unused)]
+mod function;
+
+use async_trait::async_trait;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_query::prelude::Expr;
+use common_recordbatch::RecordBatch;
+use datatypes::arrow::array::UInt32Array;
+use datatypes::arrow::compute;
+use datatypes::arrow::record_batch::RecordBatch as DfRecordBatch;
+use datatypes::prelude::*;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{Int64Vector, StringVector, TimestampMillisecondVector};
+use query::QueryEngine;
+use store_api::storage::ScanOrder;
+use table::metadata::{FilterPushDownType, TableInfoRef};
+use table::predicate::ColumnFilter;
+use table::test_util::MemTable;
+use table::Table;
+
+/// A table with the tag `host` scans rows in time index order and records the limit
+/// and order of the last scan.
+struct OrderedTable {
+    info: TableInfoRef,
+    /// Rows sorted by the time index.
+    recordbatch: RecordBatch,
+    last_scan: Mutex<Option<(Option<usize>, Option<ScanOrder>)>>,
+}
+
+impl OrderedTable {
+    /// Scans rows matching all `filters` in `order`, then applies the `limit`.
+    async fn scan_rows(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: Option<ScanOrder>,
+    ) -> table::Result<PhysicalPlanRef> {
+        *self.last_scan.lock().unwrap() = Some((limit, order));
+
+        let schema = self.schema();
+        let filters = filters
+            .iter()
+            .filter_map(|filter| ColumnFilter::try_new(filter, &schema))
+            .collect::<Vec<_>>();
+        let columns = self.recordbatch.columns();
+        let mut rows = (0..self.recordbatch.num_rows())
+            .filter(|row| filters.iter().all(|filter| filter.matches(columns, *row)))
+            .map(|row| row as u32)
+            .collect::<Vec<_>>();
+        if order.map(|order| order.is_desc()).unwrap_or(false) {
+            rows.reverse();
+        }
+        rows.truncate(limit.unwrap_or(rows.len()));
+
+        let indices = UInt32Array::from(rows);
+        let df_recordbatch = self.recordbatch.df_record_batch();
+        let arrays = df_recordbatch
+            .columns()
+            .iter()
+            .map(|array| compute::take(array.as_ref(), &indices, None).unwrap())
+            .collect();
+        let df_recordbatch = DfRecordBatch::try_new(df_recordbatch.schema(), arrays).unwrap();
+        let recordbatch = RecordBatch::try_from_df_record_batch(schema, df_recordbatch).unwrap();
+        MemTable::new("metrics", recordbatch)
+            .scan(projection, &[], None)
+            .await
+    }
+}
+
+#[async_trait]
+impl Table for OrderedTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.info.meta.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.info.clone()
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.scan_rows(projection, filters, limit, None).await
+    }
+
+    async fn scan_ordered(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: ScanOrder,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.scan_rows(projection, filters, limit, Some(order))
+            .await
+    }
+
+    fn supports_filter_pushdown(&self, filter: &Expr) -> table::Result<FilterPushDownType> {
+        let meta = &self.info.meta;
+        if ColumnFilter::is_row_key_filter(filter, &meta.schema, &meta.primary_key_indices) {
+            Ok(FilterPushDownType::Exact)
+        } else {
+            Ok(FilterPushDownType::Inexact)
+        }
+    }
+
+    fn scan_in_time_index_order(&self) -> bool {
+        true
+    }
+}
+
+fn create_query_engine() -> (Arc<dyn QueryEngine>, Arc<OrderedTable>) {
+    let column_schemas = vec![
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+        ColumnSchema::new("value", ConcreteDataType::int64_datatype(), true),
+    ];
+    let hosts = (0..10)
+        .map(|i| format!("host{}", i % 2))
+        .collect::<Vec<_>>();
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(TimestampMillisecondVector::from_vec(
+            (0..10).map(|i| i * 1000).collect(),
+        )),
+        Arc::new(StringVector::from(hosts)),
+        Arc::new(Int64Vector::from_vec((0..10).rev().collect())),
+    ];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let recordbatch = RecordBatch::new(schema, columns).unwrap();
+
+    let mut info = (*MemTable::new("metrics", recordbatch.clone()).table_info()).clone();
+    info.meta.primary_key_indices = vec![1];
+    let table = Arc::new(OrderedTable {
+        info: Arc::new(info),
+        recordbatch,
+        last_scan: Mutex::new(None),
+    });
+
+    let engine = function::create_query_engine_with_table(table.clone());
+    (engine, table)
+}
+
+/// Executes the `sql` and returns the values of the `value` column and the limit and
+/// order passed to the scan.
+async fn execute(
+    sql: &str,
+    engine: &Arc<dyn QueryEngine>,
+    table: &OrderedTable,
+) -> (Vec<Value>, Option<usize>, Option<ScanOrder>) {
+    let batches = function::execute(sql, engine).await;
+
+    let mut values = Vec::new();
+    for batch in batches {
+        let column = batch.column_by_name("value").unwrap();
+        values.extend((0..column.len()).map(|i| column.get(i)));
+    }
+    let (limit, order) = table.last_scan.lock().unwrap().take().unwrap();
+    (values, limit, order)
+}
+
+#[tokio::test]
+async fn test_push_down_sorted_limit() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, table) = create_query_engine();
+
+    let (values, limit, order) =
+        execute("select * from metrics order by ts limit 3", &engine, &table).await;
+    assert_eq!(vec![Value::from(9i64), 8i64.into(), 7i64.into()], values);
+    assert_eq!(Some(3), limit);
+    assert_eq!(Some(ScanOrder::Asc), order);
+
+    let (values, limit, order) = execute(
+        "select value, ts from metrics order by ts asc limit 2 offset 3",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(6i64), 5i64.into()], values);
+    assert_eq!(Some(5), limit);
+    assert_eq!(Some(ScanOrder::Asc), order);
+}
+
+#[tokio::test]
+async fn test_push_down_sorted_limit_desc() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, table) = create_query_engine();
+
+    let (values, limit, order) = execute(
+        "select * from metrics order by ts desc limit 2",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(0i64), 1i64.into()], values);
+    assert_eq!(Some(2), limit);
+    assert_eq!(Some(ScanOrder::Desc), order);
+
+    let (values, limit, order) = execute(
+        "select value, ts from metrics order by ts desc limit 2 offset 1",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(1i64), 2i64.into()], values);
+    assert_eq!(Some(3), limit);
+    assert_eq!(Some(ScanOrder::Desc), order);
+}
+
+#[tokio::test]
+async fn test_push_down_sorted_limit_with_filters() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, table) = create_query_engine();
+
+    // Filters on the tag and the time index are evaluated by the scan.
+    let (values, limit, order) = execute(
+        "select * from metrics where host = 'host1' order by ts desc limit 2",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(0i64), 2i64.into()], values);
+    assert_eq!(Some(2), limit);
+    assert_eq!(Some(ScanOrder::Desc), order);
+
+    let (values, limit, order) = execute(
+        "select * from metrics where host in ('host0', 'host2') \
+         and ts > '1970-01-01 00:00:02+00:00' order by ts limit 2",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(5i64), 3i64.into()], values);
+    assert_eq!(Some(2), limit);
+    assert_eq!(Some(ScanOrder::Asc), order);
+}
+
+#[tokio::test]
+async fn test_not_push_down_sorted_limit() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, table) = create_query_engine();
+
+    // Not sorted by the time index.
+    let (values, limit, order) = execute(
+        "select * from metrics order by value limit 2",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(0i64), 1i64.into()], values);
+    assert_eq!(None, limit);
+    assert_eq!(None, order);
+
+    // Rows are filtered after scan.
+    let (values, limit, order) = execute(
+        "select * from metrics where value % 2 = 0 order by ts limit 2",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(8i64), 6i64.into()], values);
+    assert_eq!(None, limit);
+    assert_eq!(None, order);
+
+    // The filter on the field is not exact, even if the filter on the tag is.
+    let (values, limit, order) = execute(
+        "select * from metrics where host = 'host1' and value > 3 order by ts desc limit 2",
+        &engine,
+        &table,
+    )
+    .await;
+    assert_eq!(vec![Value::from(4i64), 6i64.into()], values);
+    assert_eq!(None, limit);
+    assert_eq!(None, order);
+}
@@ -227,7 +227,7 @@ where
     };
     let batches = util::collect_batches(recordbatch_stream).await.unwrap();
 
-    let pretty_print = batches.pretty_print().unwrap();
+    let pretty_print = batches.pretty_print(None).unwrap();
     assert_eq!(expected, pretty_print);
     Ok(())
 }
@@ -166,3 +166,38 @@ async fn test_udf() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_panicking_udf() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let catalog_list = catalog::local::new_memory_catalog_list()?;
+    let factory = QueryEngineFactory::new(catalog_list);
+    let engine = factory.query_engine();
+
+    let udf = create_udf(
+        "my_panic",
+        vec![ConcreteDataType::uint32_datatype()],
+        Arc::new(ConcreteDataType::uint32_datatype()),
+        Volatility::Immutable,
+        Arc::new(|_| panic!("buggy udf")),
+    );
+    engine.register_udf(udf);
+
+    let plan = engine.sql_to_plan(
+        "select my_panic(number) from numbers limit 10",
+        Arc::new(QueryContext::new()),
+    )?;
+
+    // The panic is converted into an error instead of crashing the executor.
+    let err = match engine.execute(&plan).await? {
+        Output::Stream(recordbatch) => util::collect(recordbatch).await.unwrap_err(),
+        _ => unreachable!(),
+    };
+    assert!(
+        err.to_string()
+            .contains("Function my_panic panicked: buggy udf"),
+        "{err}"
+    );
+
+    Ok(())
+}
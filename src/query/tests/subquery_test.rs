@@ -0,0 +1,93 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[allow(unused)]
+mod function;
+
+use datatypes::prelude::*;
+
+#[tokio::test]
+async fn test_scalar_subquery() {
+    common_telemetry::init_default_ut_logging();
+    let engine = function::create_metrics_query_engine();
+
+    let sql = "select host, ts, cpu from metrics \
+        where cpu > (select avg(cpu) from metrics) order by host, ts";
+    let batches = function::execute(sql, &engine).await;
+    let expected = vec![
+        vec![Value::from("b"), function::ts(1000), Value::from(10.0)],
+        vec![Value::from("b"), function::ts(2000), Value::from(20.0)],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+
+    // Correlated subquery.
+    let sql = "select host, ts, cpu from metrics \
+        where cpu = (select max(cpu) from metrics as m where m.host = metrics.host) \
+        order by host";
+    let batches = function::execute(sql, &engine).await;
+    let expected = vec![
+        vec![Value::from("a"), function::ts(3000), Value::from(3.0)],
+        vec![Value::from("b"), function::ts(2000), Value::from(20.0)],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+}
+
+#[tokio::test]
+async fn test_in_subquery() {
+    common_telemetry::init_default_ut_logging();
+    let engine = function::create_metrics_query_engine();
+
+    let sql = "select host, ts from metrics \
+        where host in (select host from metrics where cpu < 2) order by host, ts";
+    let batches = function::execute(sql, &engine).await;
+    let expected = vec![
+        vec![Value::from("a"), function::ts(1000)],
+        vec![Value::from("a"), function::ts(2000)],
+        vec![Value::from("a"), function::ts(3000)],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+
+    let sql = "select host, ts from metrics \
+        where host not in (select host from metrics where cpu > 10) order by host, ts";
+    let batches = function::execute(sql, &engine).await;
+    assert_eq!(expected, function::collect_rows(&batches));
+
+    // Timestamp literals in the subquery are converted like the outer query.
+    let sql = "select host, ts from metrics where ts in \
+        (select ts from metrics where host = 'a' and ts >= '1970-01-01 00:00:02+00:00') \
+        order by host, ts";
+    let batches = function::execute(sql, &engine).await;
+    let expected = vec![
+        vec![Value::from("a"), function::ts(2000)],
+        vec![Value::from("a"), function::ts(3000)],
+        vec![Value::from("b"), function::ts(2000)],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+}
+
+#[tokio::test]
+async fn test_derived_table() {
+    common_telemetry::init_default_ut_logging();
+    let engine = function::create_metrics_query_engine();
+
+    let sql = "select host, max(cpu) from \
+        (select host, cpu from metrics where ts < '1970-01-01 00:00:02+00:00') as t \
+        group by host order by host";
+    let batches = function::execute(sql, &engine).await;
+    let expected = vec![
+        vec![Value::from("a"), Value::from(1.0)],
+        vec![Value::from("b"), Value::from(10.0)],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+}
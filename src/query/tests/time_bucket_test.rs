@@ -0,0 +1,191 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+#[allow(unused)]
+mod function;
+
+use async_trait::async_trait;
+use common_query::physical_plan::PhysicalPlanRef;
+use common_query::prelude::Expr;
+use common_recordbatch::RecordBatch;
+use common_time::Timestamp;
+use datafusion_common::ScalarValue;
+use datafusion_expr::{col, Expr as DfExpr};
+use datatypes::prelude::*;
+use datatypes::schema::{ColumnSchema, SchemaRef};
+use datatypes::vectors::{Int64Vector, TimestampMillisecondVector};
+use query::plan::LogicalPlan;
+use query::QueryEngine;
+use session::context::QueryContext;
+use table::metadata::TableInfoRef;
+use table::test_util::MemTable;
+use table::Table;
+
+/// A table records the filters of the last scan.
+struct FilterRecordingTable {
+    inner: MemTable,
+    last_filters: Mutex<Vec<DfExpr>>,
+}
+
+#[async_trait]
+impl Table for FilterRecordingTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.inner.table_info()
+    }
+
+    async fn scan(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> table::Result<PhysicalPlanRef> {
+        *self.last_filters.lock().unwrap() = filters.iter().map(|f| f.df_expr().clone()).collect();
+        self.inner.scan(projection, filters, limit).await
+    }
+}
+
+fn create_query_engine() -> (Arc<dyn QueryEngine>, Arc<FilterRecordingTable>) {
+    let column_schemas = vec![
+        ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        )
+        .with_time_index(true),
+        ColumnSchema::new("value", ConcreteDataType::int64_datatype(), true),
+    ];
+    // One row per minute in the first 10 minutes.
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(TimestampMillisecondVector::from_vec(
+            (0..10).map(|i| i * 60_000).collect(),
+        )),
+        Arc::new(Int64Vector::from_vec((0..10).collect())),
+    ];
+    let table = Arc::new(FilterRecordingTable {
+        inner: function::new_memtable("metrics", column_schemas, columns),
+        last_filters: Mutex::new(Vec::new()),
+    });
+
+    let engine = function::create_query_engine_with_table(table.clone());
+    (engine, table)
+}
+
+/// Executes the `sql` and returns the rows of the `bucket` and `total` columns.
+async fn execute(sql: &str, engine: &Arc<dyn QueryEngine>) -> Vec<(Value, Value)> {
+    collect_buckets(function::execute(sql, engine).await)
+}
+
+async fn execute_plan(plan: &LogicalPlan, engine: &Arc<dyn QueryEngine>) -> Vec<(Value, Value)> {
+    collect_buckets(function::execute_plan(plan, engine).await)
+}
+
+fn collect_buckets(batches: Vec<RecordBatch>) -> Vec<(Value, Value)> {
+    let mut rows = Vec::new();
+    for batch in batches {
+        let buckets = batch.column_by_name("bucket").unwrap();
+        let totals = batch.column_by_name("total").unwrap();
+        rows.extend((0..buckets.len()).map(|i| (buckets.get(i), totals.get(i))));
+    }
+    rows
+}
+
+fn bucket(millis: i64, total: i64) -> (Value, Value) {
+    (
+        Value::Timestamp(Timestamp::new_millisecond(millis)),
+        Value::from(total),
+    )
+}
+
+#[tokio::test]
+async fn test_group_by_time_bucket() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, _) = create_query_engine();
+
+    let rows = execute(
+        "select time_bucket('5m', ts) as bucket, sum(value) as total from metrics \
+         group by time_bucket('5m', ts) order by bucket",
+        &engine,
+    )
+    .await;
+    assert_eq!(vec![bucket(0, 10), bucket(300_000, 35)], rows);
+
+    let rows = execute(
+        "select time_bucket('4m', ts, '1970-01-01 00:01:00Z') as bucket, sum(value) as total \
+         from metrics group by time_bucket('4m', ts, '1970-01-01 00:01:00Z') order by bucket",
+        &engine,
+    )
+    .await;
+    assert_eq!(
+        vec![
+            bucket(-180_000, 0),
+            bucket(60_000, 10),
+            bucket(300_000, 26),
+            bucket(540_000, 9)
+        ],
+        rows
+    );
+}
+
+#[tokio::test]
+async fn test_push_down_time_bucket_filter() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, table) = create_query_engine();
+
+    let rows = execute(
+        "select time_bucket('5m', ts) as bucket, sum(value) as total from metrics \
+         where time_bucket('5m', ts) >= '1970-01-01 00:05:00Z' \
+         group by time_bucket('5m', ts) order by bucket",
+        &engine,
+    )
+    .await;
+    assert_eq!(vec![bucket(300_000, 35)], rows);
+
+    let filters = table.last_filters.lock().unwrap();
+    let expect = col("ts").gt_eq(DfExpr::Literal(ScalarValue::TimestampMillisecond(
+        Some(300_000),
+        None,
+    )));
+    assert!(filters.contains(&expect), "filters: {filters:?}");
+}
+
+#[tokio::test]
+async fn test_restrict_time_range() {
+    common_telemetry::init_default_ut_logging();
+    let (engine, _) = create_query_engine();
+
+    let plan = engine
+        .sql_to_plan(
+            "select time_bucket('5m', ts) as bucket, sum(value) as total from metrics \
+             group by time_bucket('5m', ts) order by bucket",
+            Arc::new(QueryContext::new()),
+        )
+        .unwrap();
+
+    let restricted = plan.with_time_range(Some(120_000), Some(420_000)).unwrap();
+    let rows = execute_plan(&restricted, &engine).await;
+    assert_eq!(vec![bucket(0, 9), bucket(300_000, 11)], rows);
+
+    let restricted = plan.with_time_range(Some(300_000), None).unwrap();
+    let rows = execute_plan(&restricted, &engine).await;
+    assert_eq!(vec![bucket(300_000, 35)], rows);
+}
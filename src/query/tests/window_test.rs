@@ -0,0 +1,110 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[allow(unused)]
+mod function;
+
+use datatypes::prelude::*;
+
+#[tokio::test]
+async fn test_window_functions() {
+    common_telemetry::init_default_ut_logging();
+    let engine = function::create_metrics_query_engine();
+
+    let sql = "select host, ts, \
+        row_number() over (partition by host order by ts) as rn, \
+        lag(cpu) over (partition by host order by ts) as prev_cpu, \
+        lead(ts) over (partition by host order by ts) as next_ts \
+        from metrics order by host, ts";
+    let batches = function::execute(sql, &engine).await;
+
+    let schema = batches[0].schema.clone();
+    let expected_types = [
+        ("host", ConcreteDataType::string_datatype()),
+        ("ts", ConcreteDataType::timestamp_millisecond_datatype()),
+        ("rn", ConcreteDataType::uint64_datatype()),
+        ("prev_cpu", ConcreteDataType::float64_datatype()),
+        (
+            "next_ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+        ),
+    ];
+    assert_eq!(expected_types.len(), schema.num_columns());
+    for ((name, data_type), column_schema) in expected_types.iter().zip(schema.column_schemas()) {
+        assert_eq!(*name, column_schema.name);
+        assert_eq!(*data_type, column_schema.data_type);
+    }
+    // Timestamps computed by window functions are never the time index.
+    assert!(!schema
+        .column_schema_by_name("next_ts")
+        .unwrap()
+        .is_time_index());
+
+    let expected = vec![
+        vec![
+            Value::from("a"),
+            function::ts(1000),
+            Value::from(1u64),
+            Value::Null,
+            function::ts(2000),
+        ],
+        vec![
+            Value::from("a"),
+            function::ts(2000),
+            Value::from(2u64),
+            Value::from(1.0),
+            function::ts(3000),
+        ],
+        vec![
+            Value::from("a"),
+            function::ts(3000),
+            Value::from(3u64),
+            Value::from(2.0),
+            Value::Null,
+        ],
+        vec![
+            Value::from("b"),
+            function::ts(1000),
+            Value::from(1u64),
+            Value::Null,
+            function::ts(2000),
+        ],
+        vec![
+            Value::from("b"),
+            function::ts(2000),
+            Value::from(2u64),
+            Value::from(10.0),
+            Value::Null,
+        ],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+}
+
+#[tokio::test]
+async fn test_aggregate_over_window() {
+    common_telemetry::init_default_ut_logging();
+    let engine = function::create_metrics_query_engine();
+
+    let sql = "select host, ts, \
+        sum(cpu) over (partition by host order by ts) as running_sum \
+        from metrics where ts >= '1970-01-01 00:00:02+00:00' order by host, ts";
+    let batches = function::execute(sql, &engine).await;
+
+    let expected = vec![
+        vec![Value::from("a"), function::ts(2000), Value::from(2.0)],
+        vec![Value::from("a"), function::ts(3000), Value::from(5.0)],
+        vec![Value::from("b"), function::ts(2000), Value::from(20.0)],
+    ];
+    assert_eq!(expected, function::collect_rows(&batches));
+}
@@ -71,6 +71,15 @@ pub enum Error {
         source: query::error::Error,
     },
 
+    #[snafu(display("Failed to list scripts, source: {}", source))]
+    ListScripts {
+        #[snafu(backtrace)]
+        source: query::error::Error,
+    },
+
+    #[snafu(display("Script name {} conflicts with a builtin function", name))]
+    ScriptNameConflict { name: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to collect record batch, source: {}", source))]
     CollectRecords {
         #[snafu(backtrace)]
@@ -92,9 +101,9 @@ impl ErrorExt for Error {
             RegisterScriptsTable { source } | FindScriptsTable { source } => source.status_code(),
             InsertScript { source, .. } => source.status_code(),
             CompilePython { source, .. } | ExecutePython { source, .. } => source.status_code(),
-            FindScript { source, .. } => source.status_code(),
+            FindScript { source, .. } | ListScripts { source } => source.status_code(),
             CollectRecords { source } => source.status_code(),
-            ScriptNotFound { .. } => StatusCode::InvalidArguments,
+            ScriptNotFound { .. } | ScriptNameConflict { .. } => StatusCode::InvalidArguments,
         }
     }
 
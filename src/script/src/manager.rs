@@ -14,16 +14,21 @@
 
 //! Scripts manager
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
 use catalog::CatalogManagerRef;
+use common_function::scalars::function_registry::FUNCTION_REGISTRY;
 use common_query::Output;
 use common_telemetry::logging;
+use datafusion_expr::{AggregateFunction, BuiltinScalarFunction};
 use query::QueryEngineRef;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::engine::{CompileContext, EvalContext, Script, ScriptEngine};
-use crate::error::{CompilePythonSnafu, ExecutePythonSnafu, Result, ScriptNotFoundSnafu};
+use crate::error::{
+    CompilePythonSnafu, ExecutePythonSnafu, Result, ScriptNameConflictSnafu, ScriptNotFoundSnafu,
+};
 use crate::python::{PyEngine, PyScript};
 use crate::table::ScriptsTable;
 
@@ -45,25 +50,68 @@ impl ScriptManager {
         })
     }
 
+    /// Compiles all scripts in the scripts table and registers the functions among them,
+    /// should be called once the catalog is started.
+    ///
+    /// Scripts that fail to compile are skipped, they report the error once executed.
+    pub async fn start(&self) -> Result<()> {
+        let scripts = self.table.find_all_scripts().await?;
+        let total = scripts.len();
+        let mut loaded = 0;
+        for (name, script) in scripts {
+            match self.compile(&name, &script).await {
+                Ok(_) => loaded += 1,
+                Err(e) => logging::error!(e; "Failed to load script {}", name),
+            }
+        }
+        logging::info!("Loaded {} of {} scripts", loaded, total);
+
+        Ok(())
+    }
+
     async fn compile(&self, name: &str, script: &str) -> Result<Arc<PyScript>> {
-        let script = Arc::new(
-            self.py_engine
-                .compile(script, CompileContext::default())
-                .await
-                .context(CompilePythonSnafu { name })?,
-        );
+        let script = self.compile_script(name, script).await?;
+        self.register(name, script.clone())?;
+        Ok(script)
+    }
+
+    async fn compile_script(&self, name: &str, script: &str) -> Result<Arc<PyScript>> {
+        let script = self
+            .py_engine
+            .compile(script, CompileContext::default())
+            .await
+            .context(CompilePythonSnafu { name })?;
+        Ok(Arc::new(script))
+    }
+
+    /// Caches the compiled `script`, and registers it as function `name` if it is a
+    /// function.
+    fn register(&self, name: &str, script: Arc<PyScript>) -> Result<()> {
+        if script.is_function() {
+            script
+                .register_udf(name)
+                .context(CompilePythonSnafu { name })?;
+            logging::info!("Registered script {} as function", name);
+        }
 
         let mut compiled = self.compiled.write().unwrap();
-        compiled.insert(name.to_string(), script.clone());
+        compiled.insert(name.to_string(), script);
 
         logging::info!("Compiled and cached script: {}", name);
 
-        Ok(script)
+        Ok(())
     }
 
+    /// Compiles the script and persists it into the scripts table. A function script
+    /// is registered only after it's persisted, so it's still there after restart.
     pub async fn insert_and_compile(&self, name: &str, script: &str) -> Result<Arc<PyScript>> {
-        let compiled_script = self.compile(name, script).await?;
+        let compiled_script = self.compile_script(name, script).await?;
+        if compiled_script.is_function() {
+            ensure!(!is_builtin_function(name), ScriptNameConflictSnafu { name });
+        }
+
         self.table.insert(name, script).await?;
+        self.register(name, compiled_script.clone())?;
         Ok(compiled_script)
     }
 
@@ -93,14 +141,26 @@ impl ScriptManager {
     }
 }
 
+/// Returns true if `name` is the name of a builtin scalar or aggregate function. Names
+/// of functions are case insensitive in SQL unless quoted.
+fn is_builtin_function(name: &str) -> bool {
+    let name = name.to_lowercase();
+    FUNCTION_REGISTRY.get_function(&name).is_some()
+        || FUNCTION_REGISTRY.get_aggr_function(&name).is_some()
+        || BuiltinScalarFunction::from_str(&name).is_ok()
+        || AggregateFunction::from_str(&name).is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use catalog::CatalogManager;
     use mito::config::EngineConfig as TableEngineConfig;
     use mito::table::test_util::new_test_object_store;
     use query::QueryEngineFactory;
+    use session::context::QueryContext;
 
     use super::*;
+    use crate::error::Error;
     type DefaultEngine = MitoEngine<EngineImpl<LocalFileLogStore>>;
     use log_store::fs::config::LogConfig;
     use log_store::fs::log::LocalFileLogStore;
@@ -109,13 +169,11 @@ mod tests {
     use storage::EngineImpl;
     use tempdir::TempDir;
 
-    #[tokio::test]
-    async fn test_insert_find_compile_script() {
-        let wal_dir = TempDir::new("test_insert_find_compile_script_wal").unwrap();
+    async fn new_table_engine(name: &str) -> (TempDir, TempDir, Arc<DefaultEngine>) {
+        let wal_dir = TempDir::new(&format!("{name}_wal")).unwrap();
         let wal_dir_str = wal_dir.path().to_string_lossy();
 
-        common_telemetry::init_default_ut_logging();
-        let (_dir, object_store) = new_test_object_store("test_insert_find_compile_script").await;
+        let (data_dir, object_store) = new_test_object_store(name).await;
         let log_config = LogConfig {
             log_file_dir: wal_dir_str.to_string(),
             ..Default::default()
@@ -123,7 +181,7 @@ mod tests {
 
         let log_store = LocalFileLogStore::open(&log_config).await.unwrap();
 
-        let mock_engine = Arc::new(DefaultEngine::new(
+        let engine = Arc::new(DefaultEngine::new(
             TableEngineConfig::default(),
             EngineImpl::new(
                 StorageEngineConfig::default(),
@@ -132,19 +190,32 @@ mod tests {
             ),
             object_store,
         ));
+        (wal_dir, data_dir, engine)
+    }
 
+    /// Creates a script manager and starts its catalog.
+    async fn new_script_manager(engine: Arc<DefaultEngine>) -> (ScriptManager, QueryEngineRef) {
         let catalog_manager = Arc::new(
-            catalog::local::LocalCatalogManager::try_new(mock_engine.clone())
+            catalog::local::LocalCatalogManager::try_new(engine)
                 .await
                 .unwrap(),
         );
 
         let factory = QueryEngineFactory::new(catalog_manager.clone());
         let query_engine = factory.query_engine();
-        let mgr = ScriptManager::new(catalog_manager.clone(), query_engine)
+        let mgr = ScriptManager::new(catalog_manager.clone(), query_engine.clone())
             .await
             .unwrap();
         catalog_manager.start().await.unwrap();
+        (mgr, query_engine)
+    }
+
+    #[tokio::test]
+    async fn test_insert_find_compile_script() {
+        common_telemetry::init_default_ut_logging();
+        let (_wal_dir, _data_dir, engine) =
+            new_table_engine("test_insert_find_compile_script").await;
+        let (mgr, _) = new_script_manager(engine).await;
 
         let name = "test";
         mgr.table
@@ -173,4 +244,37 @@ def test(n):
             assert!(cached.get(name).is_some());
         }
     }
+
+    #[tokio::test]
+    async fn test_insert_and_load_function_script() {
+        common_telemetry::init_default_ut_logging();
+        let (_wal_dir, _data_dir, engine) =
+            new_table_engine("test_insert_and_load_function_script").await;
+        let (mgr, _) = new_script_manager(engine.clone()).await;
+
+        let script = r#"
+@copr(args=["n"], returns=["r"])
+def add_one(n) -> vector[f64]:
+    return n + 1
+"#;
+        // Builtin functions can't be overridden, the script is not persisted.
+        let err = mgr.insert_and_compile("ABS", script).await.err().unwrap();
+        assert!(matches!(err, Error::ScriptNameConflict { .. }));
+        assert!(mgr.table.find_script_by_name("ABS").await.is_err());
+
+        mgr.insert_and_compile("add_one", script).await.unwrap();
+        assert!(mgr.table.find_script_by_name("add_one").await.is_ok());
+
+        // Functions are registered again on restart.
+        let (mgr, query_engine) = new_script_manager(engine).await;
+        let sql = "select add_one(number) from numbers limit 1";
+        assert!(query_engine
+            .sql_to_plan(sql, Arc::new(QueryContext::new()))
+            .is_err());
+        mgr.start().await.unwrap();
+        assert!(mgr.compiled.read().unwrap().contains_key("add_one"));
+        assert!(query_engine
+            .sql_to_plan(sql, Arc::new(QueryContext::new()))
+            .is_ok());
+    }
 }
@@ -20,8 +20,10 @@ mod engine;
 pub mod error;
 #[cfg(test)]
 mod test;
+mod udf;
 pub(crate) mod utils;
 mod vector;
 
 pub use self::engine::{PyEngine, PyScript};
+pub use self::udf::PyUdf;
 pub use self::vector::PyVector;
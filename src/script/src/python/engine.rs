@@ -20,6 +20,7 @@ use std::task::{Context, Poll};
 
 use async_trait::async_trait;
 use common_error::prelude::BoxedError;
+use common_function::scalars::udf::create_udf;
 use common_query::Output;
 use common_recordbatch::error::{ExternalSnafu, Result as RecordBatchResult};
 use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
@@ -33,6 +34,7 @@ use sql::statements::statement::Statement;
 use crate::engine::{CompileContext, EvalContext, Script, ScriptEngine};
 use crate::python::coprocessor::{exec_parsed, parse, CoprocessorRef};
 use crate::python::error::{self, Result};
+use crate::python::udf::PyUdf;
 
 const PY_ENGINE: &str = "python";
 
@@ -41,6 +43,21 @@ pub struct PyScript {
     copr: CoprocessorRef,
 }
 
+impl PyScript {
+    /// Returns true if the coprocessor has no `sql` to query its arguments, such
+    /// a script could only be called as a function.
+    pub fn is_function(&self) -> bool {
+        self.copr.deco_args.sql.is_none()
+    }
+
+    /// Registers the script as a scalar function `name` to the query engine.
+    pub fn register_udf(&self, name: &str) -> Result<()> {
+        let udf = PyUdf::try_new(name, self.copr.clone())?;
+        self.query_engine.register_udf(create_udf(Arc::new(udf)));
+        Ok(())
+    }
+}
+
 pub struct CoprStream {
     stream: SendableRecordBatchStream,
     copr: CoprocessorRef,
@@ -249,4 +266,62 @@ def test(a):
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_register_udf() {
+        let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
+
+        let default_schema = Arc::new(MemorySchemaProvider::new());
+        default_schema
+            .register_table("numbers".to_string(), Arc::new(NumbersTable::default()))
+            .unwrap();
+        let default_catalog = Arc::new(MemoryCatalogProvider::new());
+        default_catalog
+            .register_schema(DEFAULT_SCHEMA_NAME.to_string(), default_schema)
+            .unwrap();
+        catalog_list
+            .register_catalog(DEFAULT_CATALOG_NAME.to_string(), default_catalog)
+            .unwrap();
+
+        let factory = QueryEngineFactory::new(catalog_list);
+        let query_engine = factory.query_engine();
+
+        let script_engine = PyEngine::new(query_engine.clone());
+        let script = r#"
+@copr(args=["n"], returns=["r"])
+def double(n) -> vector[f64]:
+    return n * 2
+"#;
+        let script = script_engine
+            .compile(script, CompileContext::default())
+            .await
+            .unwrap();
+        assert!(script.is_function());
+        script.register_udf("double").unwrap();
+
+        let plan = query_engine
+            .sql_to_plan(
+                "select double(number) from numbers limit 3",
+                Arc::new(QueryContext::new()),
+            )
+            .unwrap();
+        let output = query_engine.execute(&plan).await.unwrap();
+        match output {
+            Output::Stream(stream) => {
+                let batches = util::collect(stream).await.unwrap();
+
+                assert_eq!(1, batches.len());
+                let rows = batches[0]
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Float64Vector>()
+                    .unwrap();
+                assert_eq!(
+                    vec![Some(0f64), Some(2f64), Some(4f64)],
+                    rows.iter_data().collect::<Vec<_>>()
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -0,0 +1,240 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Python coprocessors as scalar functions.
+
+use std::fmt;
+use std::sync::Arc;
+
+use common_error::prelude::BoxedError;
+use common_function::scalars::function::{Function, FunctionContext};
+use common_query::error::{InvalidFuncArgsSnafu, Result as QueryResult};
+use common_query::prelude::{Signature, Volatility};
+use common_recordbatch::RecordBatch;
+use datatypes::data_type::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::vectors::VectorRef;
+use snafu::{OptionExt, ResultExt};
+
+use crate::python::coprocessor::{exec_parsed, CoprocessorRef};
+use crate::python::error::{ensure, CoprParseSnafu, NewRecordBatchSnafu, Result, TypeCastSnafu};
+
+/// A coprocessor that runs as a scalar function, e.g.
+///
+/// ```python
+/// @copr(args=["cpu", "mem"], returns=["usage"])
+/// def usage(cpu, mem) -> vector[f64]:
+///     return cpu * 0.5 + mem * 0.5
+/// ```
+///
+/// could be called by `SELECT usage(cpu, memory) FROM monitor`. Arguments of the
+/// function are bound to `args` of the coprocessor in order. The coprocessor must not
+/// have `sql` in its decorator and must return exactly one vector whose type is
+/// annotated, so the return type is known while planning the query.
+pub struct PyUdf {
+    name: String,
+    copr: CoprocessorRef,
+    return_type: ConcreteDataType,
+}
+
+impl PyUdf {
+    /// Creates a function `name` that executes the `copr`.
+    pub fn try_new(name: &str, copr: CoprocessorRef) -> Result<Self> {
+        ensure!(
+            copr.deco_args.sql.is_none(),
+            CoprParseSnafu {
+                reason: format!("Coprocessor {} with sql can't be used as a function", name),
+                loc: None,
+            }
+        );
+        ensure!(
+            copr.deco_args.ret_names.len() == 1,
+            CoprParseSnafu {
+                reason: format!(
+                    "Function {} must return exactly one vector, found {}",
+                    name,
+                    copr.deco_args.ret_names.len()
+                ),
+                loc: None,
+            }
+        );
+        let datatype = copr
+            .return_types
+            .first()
+            .and_then(|anno| anno.as_ref())
+            .and_then(|anno| anno.datatype.as_ref())
+            .context(CoprParseSnafu {
+                reason: format!("Function {} must annotate its return type", name),
+                loc: None,
+            })?;
+        let return_type = ConcreteDataType::try_from(datatype).context(TypeCastSnafu)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            copr,
+            return_type,
+        })
+    }
+
+    /// Assembles the arguments into a record batch, whose columns are named by `args`
+    /// of the coprocessor.
+    fn args_to_record_batch(&self, columns: &[VectorRef]) -> Result<RecordBatch> {
+        let column_schemas = self
+            .copr
+            .deco_args
+            .arg_names
+            .iter()
+            .zip(columns)
+            .enumerate()
+            .map(|(idx, (name, column))| {
+                // Arguments are nullable unless they are annotated as not nullable.
+                let is_nullable = self
+                    .copr
+                    .arg_types
+                    .get(idx)
+                    .and_then(|anno| anno.as_ref().map(|anno| anno.is_nullable))
+                    .unwrap_or(true);
+                ColumnSchema::new(name, column.data_type(), is_nullable)
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(column_schemas));
+
+        RecordBatch::new(schema, columns.to_vec()).context(NewRecordBatchSnafu)
+    }
+
+    fn eval_copr(&self, columns: &[VectorRef]) -> Result<VectorRef> {
+        let rb = self.args_to_record_batch(columns)?;
+        let result = exec_parsed(&self.copr, &rb)?;
+
+        Ok(result.column(0).clone())
+    }
+}
+
+impl fmt::Display for PyUdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PyUdf({})", self.name)
+    }
+}
+
+impl Function for PyUdf {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> QueryResult<ConcreteDataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::any(self.copr.deco_args.arg_names.len(), Volatility::Immutable)
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> QueryResult<VectorRef> {
+        ensure!(
+            columns.len() == self.copr.deco_args.arg_names.len(),
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect {}, have: {}",
+                    self.copr.deco_args.arg_names.len(),
+                    columns.len()
+                ),
+            }
+        );
+
+        Ok(self.eval_copr(columns).map_err(BoxedError::new)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::*;
+    use datatypes::vectors::{Float64Vector, Int64Vector};
+
+    use super::*;
+    use crate::python::coprocessor::parse::parse_and_compile_copr;
+
+    fn new_udf(script: &str) -> Result<PyUdf> {
+        let copr = Arc::new(parse_and_compile_copr(script).unwrap());
+        PyUdf::try_new("test", copr)
+    }
+
+    #[test]
+    fn test_py_udf() {
+        let udf = new_udf(
+            r#"
+@copr(args=["a", "b"], returns=["r"])
+def add(a, b) -> vector[f64]:
+    return a + b
+"#,
+        )
+        .unwrap();
+        assert_eq!("test", udf.name());
+        assert_eq!(
+            ConcreteDataType::float64_datatype(),
+            udf.return_type(&[]).unwrap()
+        );
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(Float64Vector::from_slice([1.0, 2.0, 3.0])),
+            Arc::new(Float64Vector::from_slice([0.5, 0.5, 0.5])),
+        ];
+        let result = udf.eval(FunctionContext::default(), &columns).unwrap();
+        let expect: VectorRef = Arc::new(Float64Vector::from_slice([1.5, 2.5, 3.5]));
+        assert_eq!(expect, result);
+
+        // Results are casted to the annotated type.
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(Int64Vector::from_slice([1, 2])),
+            Arc::new(Int64Vector::from_slice([3, 4])),
+        ];
+        let result = udf.eval(FunctionContext::default(), &columns).unwrap();
+        let expect: VectorRef = Arc::new(Float64Vector::from_slice([4.0, 6.0]));
+        assert_eq!(expect, result);
+
+        assert!(udf.eval(FunctionContext::default(), &columns[..1]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_py_udf() {
+        // With sql.
+        assert!(new_udf(
+            r#"
+@copr(args=["number"], returns=["r"], sql="select number from numbers")
+def f(number) -> vector[f64]:
+    return number
+"#
+        )
+        .is_err());
+
+        // Returns more than one vector.
+        assert!(new_udf(
+            r#"
+@copr(args=["a"], returns=["r", "s"])
+def f(a) -> (vector[f64], vector[f64]):
+    return a, a
+"#
+        )
+        .is_err());
+
+        // Without return type annotation.
+        assert!(new_udf(
+            r#"
+@copr(args=["a"], returns=["r"])
+def f(a):
+    return a
+"#
+        )
+        .is_err());
+    }
+}
@@ -29,6 +29,7 @@ use datatypes::arrow::datatypes::DataType as ArrowDataType;
 use datatypes::arrow::error::Result as ArrowResult;
 use datatypes::data_type::{ConcreteDataType, DataType};
 use datatypes::prelude::Value;
+use datatypes::types::{JsonType, UuidType};
 use datatypes::value::{self, OrderedFloat};
 use datatypes::vectors::{Helper, NullVector, VectorRef};
 use once_cell::sync::Lazy;
@@ -810,6 +811,27 @@ pub fn pyobj_try_to_typed_val(
                     None
                 }
             }
+            // JSON documents are converted from their text.
+            ConcreteDataType::Json(_) => {
+                if is_instance::<PyStr>(&obj, vm) {
+                    obj.try_into_value::<String>(vm)
+                        .ok()
+                        .and_then(|v| JsonType::parse_str(&v).ok())
+                        .map(|v| value::Value::Binary(v.into()))
+                } else {
+                    None
+                }
+            }
+            ConcreteDataType::Uuid(_) => {
+                if is_instance::<PyStr>(&obj, vm) {
+                    obj.try_into_value::<String>(vm)
+                        .ok()
+                        .and_then(|v| UuidType::parse_str(&v).ok())
+                        .map(|v| value::Value::Binary(v.as_slice().into()))
+                } else {
+                    None
+                }
+            }
             ConcreteDataType::List(_) => unreachable!(),
             ConcreteDataType::Date(_)
             | ConcreteDataType::DateTime(_)
@@ -32,7 +32,8 @@ use table::requests::{CreateTableRequest, InsertRequest};
 
 use crate::error::{
     CastTypeSnafu, CollectRecordsSnafu, FindScriptSnafu, FindScriptsTableSnafu, InsertScriptSnafu,
-    RegisterScriptsTableSnafu, Result, ScriptNotFoundSnafu, ScriptsTableNotFoundSnafu,
+    ListScriptsSnafu, RegisterScriptsTableSnafu, Result, ScriptNotFoundSnafu,
+    ScriptsTableNotFoundSnafu,
 };
 
 pub const SCRIPTS_TABLE_NAME: &str = "scripts";
@@ -168,27 +169,64 @@ impl ScriptsTable {
         assert_eq!(records.len(), 1);
         assert_eq!(records[0].num_columns(), 1);
 
-        let script_column = records[0].column(0);
-        let script_column = script_column
-            .as_any()
-            .downcast_ref::<StringVector>()
-            .with_context(|| CastTypeSnafu {
-                msg: format!(
-                    "can't downcast {:?} array into string vector",
-                    script_column.data_type()
-                ),
-            })?;
-
+        let script_column = as_string_vector(records[0].column(0))?;
         assert_eq!(script_column.len(), 1);
         Ok(script_column.get_data(0).unwrap().to_string())
     }
 
+    /// Returns names and contents of all scripts in the table.
+    pub async fn find_all_scripts(&self) -> Result<Vec<(String, String)>> {
+        let sql = format!("select name, script from {}", self.name());
+
+        let plan = self
+            .query_engine
+            .sql_to_plan(&sql, Arc::new(QueryContext::new()))
+            .context(ListScriptsSnafu)?;
+
+        let stream = match self
+            .query_engine
+            .execute(&plan)
+            .await
+            .context(ListScriptsSnafu)?
+        {
+            Output::Stream(stream) => stream,
+            _ => unreachable!(),
+        };
+        let records = record_util::collect(stream)
+            .await
+            .context(CollectRecordsSnafu)?;
+
+        let mut scripts = Vec::new();
+        for batch in &records {
+            let names = as_string_vector(batch.column(0))?;
+            let contents = as_string_vector(batch.column(1))?;
+            for (name, script) in names.iter_data().zip(contents.iter_data()) {
+                if let (Some(name), Some(script)) = (name, script) {
+                    scripts.push((name.to_string(), script.to_string()));
+                }
+            }
+        }
+        Ok(scripts)
+    }
+
     #[inline]
     pub fn name(&self) -> &str {
         &self.name
     }
 }
 
+fn as_string_vector(column: &VectorRef) -> Result<&StringVector> {
+    column
+        .as_any()
+        .downcast_ref::<StringVector>()
+        .with_context(|| CastTypeSnafu {
+            msg: format!(
+                "can't downcast {:?} array into string vector",
+                column.data_type()
+            ),
+        })
+}
+
 /// Build scripts table
 fn build_scripts_schema() -> Schema {
     let cols = vec![
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod user_provider;
+pub mod user_store;
 
 use std::sync::Arc;
 
@@ -23,12 +24,44 @@ use session::context::UserInfo;
 use snafu::{Backtrace, ErrorCompat, OptionExt, Snafu};
 
 use crate::auth::user_provider::StaticUserProvider;
+use crate::auth::user_store::UserStoreRef;
 
+/// Provider of the users, which authenticates the clients of all protocols. Implement
+/// it to plug in other user stores, e.g. LDAP.
 #[async_trait::async_trait]
 pub trait UserProvider: Send + Sync {
     fn name(&self) -> &str;
 
     async fn auth(&self, id: Identity<'_>, password: Password<'_>) -> Result<UserInfo>;
+
+    /// Authenticates by a bearer token, which is used by HTTP and gRPC clients.
+    async fn auth_token(&self, _token: &str) -> Result<UserInfo> {
+        UnsupportedPasswordTypeSnafu {
+            password_type: "bearer_token",
+        }
+        .fail()
+    }
+
+    /// Creates a user, for `CREATE USER`. Providers not managing the users by
+    /// themselves don't support it.
+    async fn create_user(&self, _username: &str, _password: &str) -> Result<()> {
+        UnsupportedUserManagementSnafu {
+            provider: self.name(),
+        }
+        .fail()
+    }
+
+    /// Drops a user created by `CREATE USER`, for `DROP USER`.
+    async fn drop_user(&self, _username: &str) -> Result<()> {
+        UnsupportedUserManagementSnafu {
+            provider: self.name(),
+        }
+        .fail()
+    }
+
+    /// Keeps the users created by SQL in `store`, e.g. the metadata shared by all
+    /// frontends in distributed mode.
+    fn set_user_store(&self, _store: UserStoreRef) {}
 }
 
 pub type UserProviderRef = Arc<dyn UserProvider>;
@@ -96,6 +129,30 @@ pub enum Error {
 
     #[snafu(display("Username and password does not match, username: {}", username))]
     UserPasswordMismatch { username: String },
+
+    #[snafu(display("Invalid bearer token"))]
+    InvalidToken {},
+
+    #[snafu(display("User already exists, username: {}", username))]
+    UserAlreadyExists { username: String },
+
+    #[snafu(display("User provider {} doesn't support managing users", provider))]
+    UnsupportedUserManagement { provider: String },
+
+    #[snafu(display(
+        "Invalid username: {:?}, expect 1 to 64 ASCII letters, digits, '_', '-' or '.'",
+        username
+    ))]
+    InvalidUsername { username: String },
+
+    #[snafu(display("User {} is defined in the config, which can't be dropped", username))]
+    ConfigUser { username: String },
+
+    #[snafu(display("Failed to encode or decode users in JSON, source: {}", source))]
+    UserJson {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for Error {
@@ -108,6 +165,13 @@ impl ErrorExt for Error {
             Error::UserNotFound { .. } => StatusCode::UserNotFound,
             Error::UnsupportedPasswordType { .. } => StatusCode::UnsupportedPasswordType,
             Error::UserPasswordMismatch { .. } => StatusCode::UserPasswordMismatch,
+            Error::InvalidToken { .. } => StatusCode::InvalidToken,
+            Error::UserAlreadyExists { .. } => StatusCode::UserAlreadyExists,
+            Error::UnsupportedUserManagement { .. } => StatusCode::Unsupported,
+            Error::InvalidUsername { .. } | Error::ConfigUser { .. } => {
+                StatusCode::InvalidArguments
+            }
+            Error::UserJson { .. } => StatusCode::Internal,
         }
     }
 
@@ -16,7 +16,8 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
 use digest;
@@ -25,13 +26,45 @@ use session::context::UserInfo;
 use sha1::Sha1;
 use snafu::{ensure, OptionExt, ResultExt};
 
+use crate::auth::user_store::{validate_username, MemoryUserStore, StoredUser, UserStoreRef};
 use crate::auth::{
-    Error, HashedPassword, Identity, InvalidConfigSnafu, IoSnafu, Password, Result, Salt,
-    UnsupportedPasswordTypeSnafu, UserNotFoundSnafu, UserPasswordMismatchSnafu, UserProvider,
+    ConfigUserSnafu, Error, HashedPassword, Identity, InvalidConfigSnafu, InvalidTokenSnafu,
+    IoSnafu, Password, Result, Salt, UnsupportedPasswordTypeSnafu, UserAlreadyExistsSnafu,
+    UserNotFoundSnafu, UserPasswordMismatchSnafu, UserProvider,
 };
 
 pub const STATIC_USER_PROVIDER: &str = "static_user_provider";
 
+/// Prefix of the credentials of bearer tokens, in format `token:<token>=<username>`.
+const TOKEN_PREFIX: &str = "token:";
+
+/// Users and tokens from the config of the provider, which can't be changed by SQL.
+#[derive(Clone, Default)]
+struct Credentials {
+    users: HashMap<String, Vec<u8>>,
+    /// Username of each token.
+    tokens: HashMap<String, String>,
+}
+
+impl Credentials {
+    fn insert(&mut self, key: &str, value: &str) {
+        match key.strip_prefix(TOKEN_PREFIX) {
+            Some(token) => {
+                let _ = self.tokens.insert(token.to_string(), value.to_string());
+            }
+            None => {
+                let _ = self
+                    .users
+                    .insert(key.to_string(), value.as_bytes().to_vec());
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.users.is_empty() && self.tokens.is_empty()
+    }
+}
+
 impl TryFrom<&str> for StaticUserProvider {
     type Error = Error;
 
@@ -50,36 +83,38 @@ impl TryFrom<&str> for StaticUserProvider {
                 });
 
                 let file = File::open(path).context(IoSnafu)?;
-                let credential = io::BufReader::new(file)
+                let mut credentials = Credentials::default();
+                io::BufReader::new(file)
                     .lines()
                     .filter_map(|line| line.ok())
-                    .filter_map(|line| {
+                    .for_each(|line| {
                         if let Some((k, v)) = line.split_once('=') {
-                            Some((k.to_string(), v.as_bytes().to_vec()))
-                        } else {
-                            None
+                            credentials.insert(k, v);
                         }
-                    })
-                    .collect::<HashMap<String, Vec<u8>>>();
+                    });
 
-                ensure!(!credential.is_empty(), InvalidConfigSnafu {
+                ensure!(!credentials.is_empty(), InvalidConfigSnafu {
                     value: content.to_string(),
                     msg: "StaticUserProviderOption file must contains at least one valid credential",
                 });
 
-                Ok(StaticUserProvider { users: credential, })
+                // Users created by `CREATE USER` are saved next to the credential file.
+                let store = MemoryUserStore::with_file(users_file_path(path))?;
+                Ok(StaticUserProvider::new(credentials, Arc::new(store)))
             }
             "cmd" => content
                 .split(',')
-                .map(|kv| {
+                .try_fold(Credentials::default(), |mut credentials, kv| {
                     let (k, v) = kv.split_once('=').context(InvalidConfigSnafu {
                         value: kv.to_string(),
                         msg: "StaticUserProviderOption cmd values must be in format `user=pwd[,user=pwd]`",
                     })?;
-                    Ok((k.to_string(), v.as_bytes().to_vec()))
+                    credentials.insert(k, v);
+                    Ok(credentials)
                 })
-                .collect::<Result<HashMap<String, Vec<u8>>>>()
-                .map(|users| StaticUserProvider { users }),
+                .map(|credentials| {
+                    StaticUserProvider::new(credentials, Arc::new(MemoryUserStore::default()))
+                }),
             _ => InvalidConfigSnafu {
                 value: mode.to_string(),
                 msg: "StaticUserProviderOption must be in format `file:<path>` or `cmd:<values>`",
@@ -89,8 +124,32 @@ impl TryFrom<&str> for StaticUserProvider {
     }
 }
 
+/// Returns the path of the JSON file of the users created by SQL, next to the credential
+/// file at `path`.
+fn users_file_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".users.json");
+    path.with_file_name(file_name)
+}
+
+/// User provider with users and tokens from the config, and users created by SQL in a
+/// [UserStore].
 pub struct StaticUserProvider {
-    users: HashMap<String, Vec<u8>>,
+    credentials: Credentials,
+    store: RwLock<UserStoreRef>,
+}
+
+impl StaticUserProvider {
+    fn new(credentials: Credentials, store: UserStoreRef) -> Self {
+        Self {
+            credentials,
+            store: RwLock::new(store),
+        }
+    }
+
+    fn store(&self) -> UserStoreRef {
+        self.store.read().unwrap().clone()
+    }
 }
 
 #[async_trait]
@@ -102,23 +161,44 @@ impl UserProvider for StaticUserProvider {
     async fn auth(&self, input_id: Identity<'_>, input_pwd: Password<'_>) -> Result<UserInfo> {
         match input_id {
             Identity::UserId(username, _) => {
-                let save_pwd = self.users.get(username).context(UserNotFoundSnafu {
-                    username: username.to_string(),
-                })?;
+                if let Some(save_pwd) = self.credentials.users.get(username) {
+                    return match input_pwd {
+                        Password::PlainText(pwd) => {
+                            ensure!(
+                                save_pwd == pwd.as_bytes(),
+                                UserPasswordMismatchSnafu { username }
+                            );
+                            Ok(UserInfo::new(username))
+                        }
+                        Password::MysqlNativePassword(auth_data, salt) => {
+                            auth_mysql(auth_data, salt, username, save_pwd)
+                                .map(|_| UserInfo::new(username))
+                        }
+                        Password::PgMD5(_, _) => UnsupportedPasswordTypeSnafu {
+                            password_type: "pg_md5",
+                        }
+                        .fail(),
+                    };
+                }
 
+                let user = self
+                    .store()
+                    .get(username)
+                    .await?
+                    .context(UserNotFoundSnafu { username })?;
                 match input_pwd {
                     Password::PlainText(pwd) => {
-                        return if save_pwd == pwd.as_bytes() {
-                            Ok(UserInfo::new(username))
-                        } else {
-                            UserPasswordMismatchSnafu {
-                                username: username.to_string(),
-                            }
-                            .fail()
-                        }
+                        ensure!(
+                            user.verify_password(pwd),
+                            UserPasswordMismatchSnafu { username }
+                        );
+                        Ok(UserInfo::new(username))
                     }
                     Password::MysqlNativePassword(auth_data, salt) => {
-                        auth_mysql(auth_data, salt, username, save_pwd)
+                        let hash_stage_2 = user
+                            .mysql_native_hash()
+                            .context(UserPasswordMismatchSnafu { username })?;
+                        auth_mysql_hash(auth_data, salt, username, &hash_stage_2)
                             .map(|_| UserInfo::new(username))
                     }
                     Password::PgMD5(_, _) => UnsupportedPasswordTypeSnafu {
@@ -129,6 +209,45 @@ impl UserProvider for StaticUserProvider {
             }
         }
     }
+
+    async fn auth_token(&self, token: &str) -> Result<UserInfo> {
+        let username = self
+            .credentials
+            .tokens
+            .get(token)
+            .context(InvalidTokenSnafu)?;
+        Ok(UserInfo::new(username))
+    }
+
+    async fn create_user(&self, username: &str, password: &str) -> Result<()> {
+        validate_username(username)?;
+        ensure!(
+            !self.credentials.users.contains_key(username),
+            UserAlreadyExistsSnafu { username }
+        );
+        let created = self
+            .store()
+            .create(username, StoredUser::new(password))
+            .await?;
+        ensure!(created, UserAlreadyExistsSnafu { username });
+        Ok(())
+    }
+
+    async fn drop_user(&self, username: &str) -> Result<()> {
+        ensure!(
+            !self.credentials.users.contains_key(username),
+            ConfigUserSnafu { username }
+        );
+        ensure!(
+            self.store().delete(username).await?,
+            UserNotFoundSnafu { username }
+        );
+        Ok(())
+    }
+
+    fn set_user_store(&self, store: UserStoreRef) {
+        *self.store.write().unwrap() = store;
+    }
 }
 
 pub fn auth_mysql(
@@ -136,10 +255,20 @@ pub fn auth_mysql(
     salt: Salt,
     username: &str,
     save_pwd: &[u8],
+) -> Result<()> {
+    auth_mysql_hash(auth_data, salt, username, &double_sha1(save_pwd))
+}
+
+/// Verifies the MySQL native password `auth_data` against `hash_stage_2`, which is
+/// `SHA1(SHA1(password))`.
+fn auth_mysql_hash(
+    auth_data: HashedPassword,
+    salt: Salt,
+    username: &str,
+    hash_stage_2: &[u8],
 ) -> Result<()> {
     // ref: https://github.com/mysql/mysql-server/blob/a246bad76b9271cb4333634e954040a970222e0a/sql/auth/password.cc#L62
-    let hash_stage_2 = double_sha1(save_pwd);
-    let tmp = sha1_two(salt, &hash_stage_2);
+    let tmp = sha1_two(salt, hash_stage_2);
     // xor auth_data and tmp
     let mut xor_result = [0u8; 20];
     for i in 0..20 {
@@ -169,7 +298,7 @@ fn sha1_one(data: &[u8]) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
-fn double_sha1(data: &[u8]) -> Vec<u8> {
+pub(crate) fn double_sha1(data: &[u8]) -> Vec<u8> {
     sha1_one(&sha1_one(data))
 }
 
@@ -177,11 +306,13 @@ fn double_sha1(data: &[u8]) -> Vec<u8> {
 pub mod test {
     use std::fs::File;
     use std::io::{LineWriter, Write};
+    use std::sync::Arc;
 
     use tempdir::TempDir;
 
     use crate::auth::user_provider::{double_sha1, sha1_one, sha1_two, StaticUserProvider};
-    use crate::auth::{Identity, Password, UserProvider};
+    use crate::auth::user_store::{MemoryUserStore, UserStore};
+    use crate::auth::{Error, Identity, Password, UserProvider};
 
     #[test]
     fn test_sha() {
@@ -248,4 +379,112 @@ admin=654321",
         test_auth(&provider, "root", "123456").await;
         test_auth(&provider, "admin", "654321").await;
     }
+
+    #[tokio::test]
+    async fn test_auth_token() {
+        let provider = StaticUserProvider::try_from("cmd:root=123456,token:abcdef=root").unwrap();
+        let user_info = provider.auth_token("abcdef").await.unwrap();
+        assert_eq!("root", user_info.username());
+
+        let err = provider.auth_token("123456").await.unwrap_err();
+        assert!(matches!(err, Error::InvalidToken { .. }), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_create_drop_user() {
+        let provider = StaticUserProvider::try_from("cmd:root=123456,token:abcdef=root").unwrap();
+        provider.create_user("greptime", "654321").await.unwrap();
+        test_auth(&provider, "greptime", "654321").await;
+        let salt = b"01234567890123456789";
+        let auth_data = mysql_auth_data(b"654321", salt);
+        assert!(provider
+            .auth(
+                Identity::UserId("greptime", None),
+                Password::MysqlNativePassword(&auth_data, salt)
+            )
+            .await
+            .is_ok());
+
+        let err = provider.create_user("root", "654321").await.unwrap_err();
+        assert!(matches!(err, Error::UserAlreadyExists { .. }), "{err:?}");
+        let err = provider
+            .create_user("greptime", "123456")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::UserAlreadyExists { .. }), "{err:?}");
+        test_auth(&provider, "greptime", "654321").await;
+
+        // Names that could be confused with tokens or the credential file are rejected.
+        for username in ["token:evil", "a=b", "a\nb", ""] {
+            let err = provider.create_user(username, "123456").await.unwrap_err();
+            assert!(matches!(err, Error::InvalidUsername { .. }), "{err:?}");
+        }
+
+        // Users in the config can't be dropped.
+        let err = provider.drop_user("root").await.unwrap_err();
+        assert!(matches!(err, Error::ConfigUser { .. }), "{err:?}");
+        test_auth(&provider, "root", "123456").await;
+        assert!(provider.auth_token("abcdef").await.is_ok());
+
+        provider.drop_user("greptime").await.unwrap();
+        assert!(provider
+            .auth(
+                Identity::UserId("greptime", None),
+                Password::PlainText("654321")
+            )
+            .await
+            .is_err());
+        let err = provider.drop_user("greptime").await.unwrap_err();
+        assert!(matches!(err, Error::UserNotFound { .. }), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_persist_users() {
+        let dir = TempDir::new("test_file_provider_persist_users").unwrap();
+        let file_path = format!("{}/users", dir.path().to_str().unwrap());
+        std::fs::write(&file_path, "root=123456\ntoken:abcdef=root\n").unwrap();
+
+        let param = format!("file:{file_path}");
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        provider.create_user("greptime", "654321").await.unwrap();
+        provider.create_user("admin", "654321").await.unwrap();
+        provider.drop_user("admin").await.unwrap();
+
+        // The credential file is never changed, the created users are kept in another file
+        // without their passwords.
+        assert_eq!(
+            "root=123456\ntoken:abcdef=root\n",
+            std::fs::read_to_string(&file_path).unwrap()
+        );
+        let users = std::fs::read_to_string(format!("{file_path}.users.json")).unwrap();
+        assert!(users.contains("greptime"), "{users}");
+        assert!(!users.contains("admin"), "{users}");
+        assert!(!users.contains("\"654321\""), "{users}");
+
+        let provider = StaticUserProvider::try_from(param.as_str()).unwrap();
+        test_auth(&provider, "greptime", "654321").await;
+        test_auth(&provider, "root", "123456").await;
+    }
+
+    #[tokio::test]
+    async fn test_set_user_store() {
+        let provider = StaticUserProvider::try_from("cmd:root=123456").unwrap();
+        let store = Arc::new(MemoryUserStore::default());
+        provider.set_user_store(store.clone());
+
+        provider.create_user("greptime", "654321").await.unwrap();
+        assert!(store.get("greptime").await.unwrap().is_some());
+
+        // Another provider sharing the store sees the user.
+        let another = StaticUserProvider::try_from("cmd:root=123456").unwrap();
+        another.set_user_store(store);
+        test_auth(&another, "greptime", "654321").await;
+    }
+
+    /// Computes the auth data of the MySQL native password authentication.
+    fn mysql_auth_data(password: &[u8], salt: &[u8]) -> Vec<u8> {
+        let stage_1 = sha1_one(password);
+        let tmp = sha1_two(salt, &double_sha1(password));
+        stage_1.iter().zip(tmp).map(|(a, b)| a ^ b).collect()
+    }
 }
@@ -0,0 +1,279 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stores of the users created by `CREATE USER`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use catalog::remote::KvBackendRef;
+use common_error::ext::BoxedError;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snafu::{ensure, ResultExt};
+
+use crate::auth::user_provider::double_sha1;
+use crate::auth::{AuthBackendSnafu, InvalidUsernameSnafu, IoSnafu, Result, UserJsonSnafu};
+
+/// Prefix of the keys of users in the metadata.
+const USER_KEY_PREFIX: &str = "__users/";
+/// Max length of usernames.
+const MAX_USERNAME_LEN: usize = 64;
+
+/// Checks the `username` is 1 to 64 ASCII letters, digits, `_`, `-` or `.`.
+pub fn validate_username(username: &str) -> Result<()> {
+    let valid = !username.is_empty()
+        && username.len() <= MAX_USERNAME_LEN
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    ensure!(valid, InvalidUsernameSnafu { username });
+    Ok(())
+}
+
+/// A user created by SQL. The password is never stored, only its hashes are.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredUser {
+    /// Random salt of `password_hash`, hex encoded.
+    salt: String,
+    /// `SHA256(salt + password)`, hex encoded.
+    password_hash: String,
+    /// `SHA1(SHA1(password))`, hex encoded. The MySQL native password authentication
+    /// verifies the scramble against it, so it can't be salted.
+    mysql_native_hash: String,
+}
+
+impl StoredUser {
+    pub fn new(password: &str) -> StoredUser {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        StoredUser {
+            salt: hex::encode(salt),
+            password_hash: hex::encode(salted_sha256(&salt, password.as_bytes())),
+            mysql_native_hash: hex::encode(double_sha1(password.as_bytes())),
+        }
+    }
+
+    pub fn verify_password(&self, password: &str) -> bool {
+        let Ok(salt) = hex::decode(&self.salt) else {
+            return false;
+        };
+        hex::encode(salted_sha256(&salt, password.as_bytes())) == self.password_hash
+    }
+
+    /// Returns `SHA1(SHA1(password))` for the MySQL native password authentication.
+    pub fn mysql_native_hash(&self) -> Option<Vec<u8>> {
+        hex::decode(&self.mysql_native_hash).ok()
+    }
+}
+
+fn salted_sha256(salt: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(data);
+    hasher.finalize().to_vec()
+}
+
+/// Store of the users created by `CREATE USER`.
+#[async_trait]
+pub trait UserStore: Send + Sync {
+    async fn get(&self, username: &str) -> Result<Option<StoredUser>>;
+
+    /// Creates the user, returns false if the user already exists.
+    async fn create(&self, username: &str, user: StoredUser) -> Result<bool>;
+
+    /// Deletes the user, returns false if the user doesn't exist.
+    async fn delete(&self, username: &str) -> Result<bool>;
+}
+
+pub type UserStoreRef = Arc<dyn UserStore>;
+
+/// Keeps the users in memory, optionally saved to a JSON file.
+#[derive(Default)]
+pub struct MemoryUserStore {
+    users: RwLock<BTreeMap<String, StoredUser>>,
+    file: Option<PathBuf>,
+}
+
+impl MemoryUserStore {
+    /// Creates the store saved to the JSON `file`, loads the users from it if it exists.
+    pub fn with_file(file: PathBuf) -> Result<MemoryUserStore> {
+        let users = if file.exists() {
+            let json = std::fs::read_to_string(&file).context(IoSnafu)?;
+            serde_json::from_str(&json).context(UserJsonSnafu)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(MemoryUserStore {
+            users: RwLock::new(users),
+            file: Some(file),
+        })
+    }
+
+    /// Applies `f` to the users, the change is discarded if the users can't be saved.
+    fn update<F>(&self, f: F) -> Result<bool>
+    where
+        F: FnOnce(&mut BTreeMap<String, StoredUser>) -> bool,
+    {
+        let mut users = self.users.write().unwrap();
+        let mut updated = users.clone();
+        if !f(&mut updated) {
+            return Ok(false);
+        }
+
+        if let Some(path) = &self.file {
+            let json = serde_json::to_string_pretty(&updated).context(UserJsonSnafu)?;
+            // Writes to a temporary file first, so the file is never half written.
+            let tmp_path = format!("{}.tmp", path.display());
+            std::fs::write(&tmp_path, json).context(IoSnafu)?;
+            std::fs::rename(&tmp_path, path).context(IoSnafu)?;
+        }
+        *users = updated;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl UserStore for MemoryUserStore {
+    async fn get(&self, username: &str) -> Result<Option<StoredUser>> {
+        Ok(self.users.read().unwrap().get(username).cloned())
+    }
+
+    async fn create(&self, username: &str, user: StoredUser) -> Result<bool> {
+        self.update(|users| {
+            if users.contains_key(username) {
+                return false;
+            }
+            let _ = users.insert(username.to_string(), user);
+            true
+        })
+    }
+
+    async fn delete(&self, username: &str) -> Result<bool> {
+        self.update(|users| users.remove(username).is_some())
+    }
+}
+
+/// Keeps the users in the metadata shared by all frontends in distributed mode.
+pub struct KvUserStore {
+    backend: KvBackendRef,
+}
+
+impl KvUserStore {
+    pub fn new(backend: KvBackendRef) -> Self {
+        Self { backend }
+    }
+
+    fn key(username: &str) -> String {
+        format!("{USER_KEY_PREFIX}{username}")
+    }
+}
+
+#[async_trait]
+impl UserStore for KvUserStore {
+    async fn get(&self, username: &str) -> Result<Option<StoredUser>> {
+        let kv = self
+            .backend
+            .get(Self::key(username).as_bytes())
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?;
+        kv.map(|kv| serde_json::from_slice(&kv.1).context(UserJsonSnafu))
+            .transpose()
+    }
+
+    async fn create(&self, username: &str, user: StoredUser) -> Result<bool> {
+        let value = serde_json::to_vec(&user).context(UserJsonSnafu)?;
+        // An empty expected value only sets the key if it's absent.
+        let result = self
+            .backend
+            .compare_and_set(Self::key(username).as_bytes(), &[], &value)
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?;
+        Ok(result.is_ok())
+    }
+
+    async fn delete(&self, username: &str) -> Result<bool> {
+        if self.get(username).await?.is_none() {
+            return Ok(false);
+        }
+        self.backend
+            .delete(Self::key(username).as_bytes())
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_username() {
+        for username in ["root", "greptime_1", "a.b-c"] {
+            assert!(validate_username(username).is_ok(), "{username}");
+        }
+        let too_long = "a".repeat(MAX_USERNAME_LEN + 1);
+        for username in ["", "token:evil", "a=b", "a\nb", "a b", too_long.as_str()] {
+            assert!(validate_username(username).is_err(), "{username}");
+        }
+    }
+
+    #[test]
+    fn test_stored_user() {
+        let user = StoredUser::new("123456");
+        assert!(user.verify_password("123456"));
+        assert!(!user.verify_password("654321"));
+        assert_eq!(Some(double_sha1(b"123456")), user.mysql_native_hash());
+        // Salted by random salts.
+        assert_ne!(user, StoredUser::new("123456"));
+    }
+
+    #[tokio::test]
+    async fn test_file_user_store() {
+        let dir = TempDir::new("test_file_user_store").unwrap();
+        let path = dir.path().join("users.json");
+        let store = MemoryUserStore::with_file(path.clone()).unwrap();
+        assert!(store
+            .create("root", StoredUser::new("123456"))
+            .await
+            .unwrap());
+        assert!(!store
+            .create("root", StoredUser::new("654321"))
+            .await
+            .unwrap());
+        assert!(store
+            .create("admin", StoredUser::new("654321"))
+            .await
+            .unwrap());
+        assert!(store.delete("admin").await.unwrap());
+        assert!(!store.delete("admin").await.unwrap());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("\"123456\""), "{content}");
+
+        let store = MemoryUserStore::with_file(path).unwrap();
+        let user = store.get("root").await.unwrap().unwrap();
+        assert!(user.verify_password("123456"));
+        assert!(store.get("admin").await.unwrap().is_none());
+    }
+}
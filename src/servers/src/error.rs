@@ -16,6 +16,7 @@ use std::any::Any;
 use std::net::SocketAddr;
 use std::string::FromUtf8Error;
 
+use aide::OperationOutput;
 use axum::http::StatusCode as HttpStatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -54,6 +55,17 @@ pub enum Error {
         source: common_recordbatch::error::Error,
     },
 
+    #[snafu(display(
+        "Failed to encode recordbatch in {} format, source: {}",
+        format,
+        source
+    ))]
+    EncodeRecordbatch {
+        format: String,
+        source: arrow::error::ArrowError,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to start HTTP server, source: {}", source))]
     StartHttp { source: hyper::Error },
 
@@ -85,6 +97,12 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to describe sql statement, source: {}", source))]
+    DescribeStatement {
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to execute insert: {}, source: {}", msg, source))]
     ExecuteInsert {
         msg: String,
@@ -134,6 +152,18 @@ pub enum Error {
         source: common_grpc::error::Error,
     },
 
+    #[snafu(display("Failed to write OpenTSDB data points, source: {}", source))]
+    OpentsdbDataPointsWrite {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
+    #[snafu(display("Failed to write OpenTelemetry metrics, source: {}", source))]
+    OtlpMetricsWrite {
+        #[snafu(backtrace)]
+        source: common_grpc::error::Error,
+    },
+
     #[snafu(display("Failed to convert time precision, name: {}", name))]
     TimePrecision { name: String, backtrace: Backtrace },
 
@@ -214,6 +244,13 @@ pub enum Error {
         source: auth::Error,
     },
 
+    #[snafu(display("User {} has no {} privilege on schema {}", user, privilege, schema))]
+    PermissionDenied {
+        user: String,
+        privilege: String,
+        schema: String,
+    },
+
     #[snafu(display("Not found http authorization header"))]
     NotFoundAuthHeader {},
 
@@ -262,6 +299,7 @@ impl ErrorExt for Error {
             | TokioIo { .. }
             | VectorConversion { .. }
             | CollectRecordbatch { .. }
+            | EncodeRecordbatch { .. }
             | StartHttp { .. }
             | StartGrpc { .. }
             | AlreadyStarted { .. }
@@ -275,6 +313,7 @@ impl ErrorExt for Error {
             | ExecuteScript { source, .. }
             | ExecuteQuery { source, .. }
             | ExecuteStatement { source, .. }
+            | DescribeStatement { source, .. }
             | ExecuteInsert { source, .. }
             | ExecuteAlter { source, .. }
             | PutOpentsdbDataPoint { source, .. } => source.status_code(),
@@ -291,14 +330,16 @@ impl ErrorExt for Error {
             | DecodeRegionNumber { .. }
             | TimePrecision { .. } => StatusCode::InvalidArguments,
 
-            InfluxdbLinesWrite { source, .. } | ConvertFlightMessage { source } => {
-                source.status_code()
-            }
+            InfluxdbLinesWrite { source, .. }
+            | OpentsdbDataPointsWrite { source, .. }
+            | OtlpMetricsWrite { source, .. }
+            | ConvertFlightMessage { source } => source.status_code(),
 
             Hyper { .. } => StatusCode::Unknown,
             TlsRequired { .. } => StatusCode::Unknown,
             StartFrontend { source, .. } => source.status_code(),
             Auth { source, .. } => source.status_code(),
+            PermissionDenied { .. } => StatusCode::AccessDenied,
 
             NotFoundAuthHeader { .. } => StatusCode::AuthHeaderNotFound,
             InvisibleASCII { .. }
@@ -320,7 +361,11 @@ impl ErrorExt for Error {
 
 impl From<Error> for tonic::Status {
     fn from(err: Error) -> Self {
-        tonic::Status::new(tonic::Code::Internal, err.to_string())
+        let code = match err.status_code() {
+            StatusCode::AccessDenied => tonic::Code::PermissionDenied,
+            _ => tonic::Code::Internal,
+        };
+        tonic::Status::new(code, err.to_string())
     }
 }
 
@@ -342,6 +387,7 @@ impl IntoResponse for Error {
             | Error::InvalidPromRemoteRequest { .. }
             | Error::InvalidQuery { .. }
             | Error::TimePrecision { .. } => (HttpStatusCode::BAD_REQUEST, self.to_string()),
+            Error::PermissionDenied { .. } => (HttpStatusCode::FORBIDDEN, self.to_string()),
             _ => (HttpStatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
         let body = Json(json!({
@@ -350,3 +396,7 @@ impl IntoResponse for Error {
         (status, body).into_response()
     }
 }
+
+impl OperationOutput for Error {
+    type Inner = Self;
+}
@@ -12,53 +12,148 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod authorize;
+pub mod flight;
 pub mod handler;
+pub mod limit;
+pub mod otlp;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::v1::{greptime_server, BatchRequest, BatchResponse};
+use arrow_flight::flight_service_server::FlightServiceServer;
 use async_trait::async_trait;
+use common_grpc::tracing::extract_trace_headers;
 use common_runtime::Runtime;
 use common_telemetry::logging::info;
+use common_telemetry::tracing::{info_span, Instrument};
+use common_telemetry::tracing_context;
 use futures::FutureExt;
+use hyper::Body;
+use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::MetricsServiceServer;
 use snafu::{ensure, ResultExt};
 use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::Mutex;
 use tokio_stream::wrappers::TcpListenerStream;
+use tonic::codec::CompressionEncoding;
 use tonic::{Request, Response, Status};
+use tower::util::MapRequestLayer;
 
+use crate::auth::UserProviderRef;
 use crate::error::{self, AlreadyStartedSnafu, Result, StartGrpcSnafu, TcpBindSnafu};
+use crate::grpc::flight::FlightHandler;
 use crate::grpc::handler::BatchHandler;
-use crate::query_handler::GrpcQueryHandlerRef;
+use crate::grpc::limit::{GrpcLimitOptions, RequestLimiter, RequestLimiterRef};
+use crate::grpc::otlp::OtlpMetricsService;
+use crate::metric;
+use crate::query_handler::{GrpcQueryHandlerRef, OpenTelemetryProtocolHandlerRef};
 use crate::server::Server;
+use crate::slow_query::{SlowQueryLog, SlowQueryLogRef};
+use crate::tls::{tls_incoming, ReloadableTlsServerConfig, TlsOption, GRPC_ALPN_PROTOCOLS};
 
 pub struct GrpcServer {
     query_handler: GrpcQueryHandlerRef,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     runtime: Arc<Runtime>,
+    tls: TlsOption,
+    user_provider: Option<UserProviderRef>,
+    otlp_handler: Option<OpenTelemetryProtocolHandlerRef>,
+    limiter: RequestLimiterRef,
+    slow_query_log: SlowQueryLogRef,
 }
 
 impl GrpcServer {
     pub fn new(query_handler: GrpcQueryHandlerRef, runtime: Arc<Runtime>) -> Self {
+        metric::register_metrics();
         Self {
             query_handler,
             shutdown_tx: Mutex::new(None),
             runtime,
+            tls: TlsOption::default(),
+            user_provider: None,
+            otlp_handler: None,
+            limiter: Arc::new(RequestLimiter::new(&GrpcLimitOptions::default())),
+            slow_query_log: Arc::new(SlowQueryLog::default()),
         }
     }
 
+    /// Serves TLS connections only unless the mode of `tls` is disable.
+    pub fn set_tls_option(&mut self, tls: TlsOption) {
+        self.tls = tls;
+    }
+
+    /// Requests must carry the `authorization` metadata to be authenticated by the
+    /// `user_provider`, with either a basic credential or a bearer token.
+    pub fn set_user_provider(&mut self, user_provider: UserProviderRef) {
+        self.user_provider = Some(user_provider);
+    }
+
+    /// Serves the OTLP/gRPC metrics service, which is not served by default.
+    pub fn set_otlp_handler(&mut self, otlp_handler: OpenTelemetryProtocolHandlerRef) {
+        self.otlp_handler = Some(otlp_handler);
+    }
+
+    pub fn set_limit_options(&mut self, opts: &GrpcLimitOptions) {
+        self.limiter.set_options(opts);
+    }
+
+    /// Queries slower than the `threshold` are logged, the slow query log is disabled if
+    /// it's `None`.
+    pub fn set_slow_query_threshold(&mut self, threshold: Option<Duration>) {
+        self.slow_query_log.set_threshold(threshold);
+    }
+
+    /// The limiter shared by the services, whose limits can be adjusted while serving.
+    pub fn limiter(&self) -> RequestLimiterRef {
+        self.limiter.clone()
+    }
+
+    /// The slow query log shared by the services, whose threshold can be adjusted while
+    /// serving.
+    pub fn slow_query_log(&self) -> SlowQueryLogRef {
+        self.slow_query_log.clone()
+    }
+
     pub fn create_service(&self) -> greptime_server::GreptimeServer<GrpcService> {
         let service = GrpcService {
-            handler: BatchHandler::new(self.query_handler.clone(), self.runtime.clone()),
+            handler: BatchHandler::new(self.query_handler.clone(), self.runtime.clone())
+                .with_slow_query_log(Some(self.slow_query_log.clone())),
+            user_provider: self.user_provider.clone(),
+            limiter: Some(self.limiter.clone()),
         };
+        // Responses are only compressed if the client accepts gzip.
         greptime_server::GreptimeServer::new(service)
+            .accept_compressed(CompressionEncoding::Gzip)
+            .send_compressed(CompressionEncoding::Gzip)
+    }
+
+    pub fn create_flight_service(&self) -> FlightServiceServer<FlightHandler> {
+        let handler = FlightHandler::new(self.query_handler.clone())
+            .with_user_provider(self.user_provider.clone())
+            .with_limiter(Some(self.limiter.clone()))
+            .with_slow_query_log(Some(self.slow_query_log.clone()));
+        FlightServiceServer::new(handler)
+            .accept_compressed(CompressionEncoding::Gzip)
+            .send_compressed(CompressionEncoding::Gzip)
+    }
+
+    pub fn create_otlp_metrics_service(&self) -> Option<MetricsServiceServer<OtlpMetricsService>> {
+        self.otlp_handler.as_ref().map(|handler| {
+            let service = OtlpMetricsService::new(handler.clone(), self.user_provider.clone());
+            MetricsServiceServer::new(service)
+                .accept_compressed(CompressionEncoding::Gzip)
+                .send_compressed(CompressionEncoding::Gzip)
+        })
     }
 }
 
 pub struct GrpcService {
     handler: BatchHandler,
+    user_provider: Option<UserProviderRef>,
+    limiter: Option<RequestLimiterRef>,
 }
 
 #[tonic::async_trait]
@@ -67,8 +162,15 @@ impl greptime_server::Greptime for GrpcService {
         &self,
         req: Request<BatchRequest>,
     ) -> std::result::Result<Response<BatchResponse>, Status> {
+        if let Some(limiter) = &self.limiter {
+            limiter.check(req.remote_addr())?;
+        }
+        let user_info = authorize::authorize(&self.user_provider, req.metadata()).await?;
+        let span = info_span!("grpc_batch");
+        tracing_context::set_remote_parent(&span, &extract_trace_headers(req.metadata()));
         let req = req.into_inner();
-        let res = self.handler.batch(req).await?;
+        let query_ctx = authorize::query_context(user_info);
+        let res = self.handler.batch(req, query_ctx).instrument(span).await?;
         Ok(Response::new(res))
     }
 }
@@ -88,6 +190,7 @@ impl Server for GrpcServer {
     }
 
     async fn start(&self, addr: SocketAddr) -> Result<SocketAddr> {
+        let tls_config = ReloadableTlsServerConfig::try_new(self.tls.clone(), GRPC_ALPN_PROTOCOLS)?;
         let (tx, rx) = oneshot::channel();
         let (listener, addr) = {
             let mut shutdown_tx = self.shutdown_tx.lock().await;
@@ -100,7 +203,11 @@ impl Server for GrpcServer {
                 .await
                 .context(TcpBindSnafu { addr })?;
             let addr = listener.local_addr().context(TcpBindSnafu { addr })?;
-            info!("GRPC server is bound to {}", addr);
+            info!(
+                "GRPC server is bound to {}, TLS enabled: {}",
+                addr,
+                tls_config.is_some()
+            );
 
             *shutdown_tx = Some(tx);
 
@@ -113,13 +220,30 @@ impl Server for GrpcServer {
             .build()
             .context(error::GrpcReflectionServiceSnafu)?;
 
-        // Would block to serve requests.
-        tonic::transport::Server::builder()
+        let limiter = self.limiter.clone();
+        let router = tonic::transport::Server::builder()
+            .layer(MapRequestLayer::new(move |req: hyper::Request<Body>| {
+                let (parts, body) = req.into_parts();
+                hyper::Request::from_parts(parts, limiter.limit_body(body))
+            }))
             .add_service(self.create_service())
-            .add_service(reflection_service)
-            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), rx.map(drop))
-            .await
-            .context(StartGrpcSnafu)?;
+            .add_service(self.create_flight_service())
+            .add_optional_service(self.create_otlp_metrics_service())
+            .add_service(reflection_service);
+        // Would block to serve requests.
+        match tls_config {
+            Some(tls_config) => {
+                router
+                    .serve_with_incoming_shutdown(tls_incoming(listener, tls_config), rx.map(drop))
+                    .await
+            }
+            None => {
+                router
+                    .serve_with_incoming_shutdown(TcpListenerStream::new(listener), rx.map(drop))
+                    .await
+            }
+        }
+        .context(StartGrpcSnafu)?;
 
         Ok(addr)
     }
@@ -0,0 +1,61 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_telemetry::error;
+use session::context::{QueryContext, QueryContextRef, UserInfo};
+use tonic::metadata::MetadataMap;
+use tonic::Status;
+
+use crate::auth::UserProviderRef;
+use crate::http::authorize::{authenticate, parse_auth_header};
+
+const AUTHORIZATION_METADATA: &str = "authorization";
+
+/// Authenticates the gRPC request by its `authorization` metadata, which is in the
+/// same format as the HTTP `Authorization` header, and returns the authenticated user.
+/// All requests are allowed as the default user if there is no user provider.
+pub(crate) async fn authorize(
+    user_provider: &Option<UserProviderRef>,
+    metadata: &MetadataMap,
+) -> Result<UserInfo, Status> {
+    let Some(user_provider) = user_provider else {
+        return Ok(UserInfo::default());
+    };
+
+    let auth_header = metadata
+        .get(AUTHORIZATION_METADATA)
+        .ok_or_else(|| Status::unauthenticated("authorization metadata not found"))?
+        .to_str()
+        .map_err(|_| Status::unauthenticated("invalid authorization metadata"))?;
+    let (scheme, credential) = parse_auth_header(auth_header)
+        .map_err(|e| Status::unauthenticated(format!("invalid authorization metadata: {e}")))?;
+
+    match authenticate(user_provider, scheme, credential).await {
+        Ok(user_info) => Ok(user_info),
+        Err(e) => {
+            error!("failed to auth grpc request, err: {:?}", e);
+            Err(Status::unauthenticated("authentication failed"))
+        }
+    }
+}
+
+/// Returns the context of the queries issued by the `user_info`, whose privileges are
+/// checked by the query handler.
+pub(crate) fn query_context(user_info: UserInfo) -> QueryContextRef {
+    let query_ctx = Arc::new(QueryContext::new());
+    query_ctx.set_current_user(user_info);
+    query_ctx
+}
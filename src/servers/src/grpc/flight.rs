@@ -0,0 +1,201 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+
+use api::v1::ObjectExpr;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, SchemaResult, Ticket,
+};
+use async_trait::async_trait;
+use common_grpc::flight;
+use common_grpc::tracing::extract_trace_headers;
+use common_telemetry::tracing::{info_span, Instrument};
+use common_telemetry::tracing_context;
+use futures::{Stream, StreamExt};
+use prost::Message;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::auth::UserProviderRef;
+use crate::grpc::authorize::{authorize, query_context};
+use crate::grpc::handler::describe_query;
+use crate::grpc::limit::RequestLimiterRef;
+use crate::metric;
+use crate::query_handler::{FlightDataStream, GrpcQueryHandlerRef, PutResultStream};
+use crate::slow_query::{SlowQueryLogRef, SlowQueryTimer};
+
+type TonicResult<T> = std::result::Result<T, Status>;
+type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync + 'static>>;
+
+/// Serves the Arrow Flight `DoGet` call, the ticket is an encoded [ObjectExpr] and its
+/// result is streamed back to the client as [FlightData] without being buffered. The
+/// `DoPut` call streams record batches into a table.
+pub struct FlightHandler {
+    query_handler: GrpcQueryHandlerRef,
+    user_provider: Option<UserProviderRef>,
+    limiter: Option<RequestLimiterRef>,
+    slow_query_log: Option<SlowQueryLogRef>,
+}
+
+impl FlightHandler {
+    pub fn new(query_handler: GrpcQueryHandlerRef) -> Self {
+        Self {
+            query_handler,
+            user_provider: None,
+            limiter: None,
+            slow_query_log: None,
+        }
+    }
+
+    pub fn with_user_provider(mut self, user_provider: Option<UserProviderRef>) -> Self {
+        self.user_provider = user_provider;
+        self
+    }
+
+    pub fn with_limiter(mut self, limiter: Option<RequestLimiterRef>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    pub fn with_slow_query_log(mut self, slow_query_log: Option<SlowQueryLogRef>) -> Self {
+        self.slow_query_log = slow_query_log;
+        self
+    }
+
+    fn check_limit<T>(&self, request: &Request<T>) -> TonicResult<()> {
+        match &self.limiter {
+            Some(limiter) => limiter.check(request.remote_addr()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl FlightService for FlightHandler {
+    type HandshakeStream = TonicStream<HandshakeResponse>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<Self::HandshakeStream>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    type ListFlightsStream = TonicStream<FlightInfo>;
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> TonicResult<Response<Self::ListFlightsStream>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<SchemaResult>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    type DoGetStream = FlightDataStream;
+
+    async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        self.check_limit(&request)?;
+        let user_info = authorize(&self.user_provider, request.metadata()).await?;
+        let span = info_span!("flight_do_get");
+        tracing_context::set_remote_parent(&span, &extract_trace_headers(request.metadata()));
+        let compressed = flight::is_compression_requested(request.metadata());
+        let ticket = request.into_inner().ticket;
+        let query = ObjectExpr::decode(ticket.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("Invalid flight ticket: {e}")))?;
+        // The timer is dropped with the stream, so the latency covers streaming the results.
+        let timer = self
+            .slow_query_log
+            .as_ref()
+            .filter(|log| log.is_enabled())
+            .and_then(|log| {
+                let (query, digest) = describe_query(&query)?;
+                Some(SlowQueryTimer::new(
+                    log.clone(),
+                    metric::PROTOCOL_GRPC,
+                    query,
+                    digest,
+                ))
+            });
+        let stream = self
+            .query_handler
+            .do_query_stream(query, compressed, query_context(user_info))
+            .instrument(span)
+            .await?;
+        let stream = match timer {
+            Some(timer) => Box::pin(stream.map(move |data| {
+                let _ = &timer;
+                data
+            })) as FlightDataStream,
+            None => stream,
+        };
+        Ok(Response::new(stream))
+    }
+
+    type DoPutStream = PutResultStream;
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<Self::DoPutStream>> {
+        self.check_limit(&request)?;
+        let user_info = authorize(&self.user_provider, request.metadata()).await?;
+        let stream = self
+            .query_handler
+            .do_put_stream(request.into_inner(), query_context(user_info))
+            .await?;
+        Ok(Response::new(stream))
+    }
+
+    type DoExchangeStream = TonicStream<FlightData>;
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<Self::DoExchangeStream>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    type DoActionStream = TonicStream<arrow_flight::Result>;
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> TonicResult<Response<Self::DoActionStream>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    type ListActionsStream = TonicStream<ActionType>;
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> TonicResult<Response<Self::ListActionsStream>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+}
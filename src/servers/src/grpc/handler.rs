@@ -13,18 +13,26 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Instant;
 
-use api::v1::{BatchRequest, BatchResponse, DatabaseResponse};
+use api::v1::object_expr::Request;
+use api::v1::query_request::Query;
+use api::v1::{BatchRequest, BatchResponse, DatabaseResponse, ObjectExpr};
 use common_runtime::Runtime;
+use common_telemetry::tracing::{Instrument, Span};
+use session::context::QueryContextRef;
 use tokio::sync::oneshot;
 
 use crate::error::Result;
+use crate::metric;
 use crate::query_handler::GrpcQueryHandlerRef;
+use crate::slow_query::{self, SlowQueryLogRef};
 
 #[derive(Clone)]
 pub struct BatchHandler {
     query_handler: GrpcQueryHandlerRef,
     runtime: Arc<Runtime>,
+    slow_query_log: Option<SlowQueryLogRef>,
 }
 
 impl BatchHandler {
@@ -32,12 +40,23 @@ impl BatchHandler {
         Self {
             query_handler,
             runtime,
+            slow_query_log: None,
         }
     }
 
-    pub async fn batch(&self, batch_req: BatchRequest) -> Result<BatchResponse> {
+    pub fn with_slow_query_log(mut self, slow_query_log: Option<SlowQueryLogRef>) -> Self {
+        self.slow_query_log = slow_query_log;
+        self
+    }
+
+    pub async fn batch(
+        &self,
+        batch_req: BatchRequest,
+        query_ctx: QueryContextRef,
+    ) -> Result<BatchResponse> {
         let (tx, rx) = oneshot::channel();
         let query_handler = self.query_handler.clone();
+        let slow_query_log = self.slow_query_log.clone().filter(|log| log.is_enabled());
 
         let future = async move {
             let mut batch_resp = BatchResponse::default();
@@ -47,7 +66,17 @@ impl BatchHandler {
                 db_resp.results.reserve(db_req.exprs.len());
 
                 for obj_expr in db_req.exprs {
-                    let object_resp = query_handler.do_query(obj_expr).await?;
+                    let query = slow_query_log
+                        .as_ref()
+                        .and_then(|_| describe_query(&obj_expr));
+                    let start = Instant::now();
+                    let object_resp = query_handler.do_query(obj_expr, query_ctx.clone()).await;
+                    let elapsed = start.elapsed();
+                    metric::observe_query(metric::PROTOCOL_GRPC, elapsed);
+                    if let (Some(log), Some((query, digest))) = (&slow_query_log, query) {
+                        let _ = log.record(metric::PROTOCOL_GRPC, &query, &digest, elapsed);
+                    }
+                    let object_resp = object_resp?;
 
                     db_resp.results.push(object_resp);
                 }
@@ -60,6 +89,7 @@ impl BatchHandler {
         // Executes requests in another runtime to
         // 1. prevent the execution from being cancelled unexpected by tonic runtime.
         // 2. avoid the handler blocks the gRPC runtime
+        let future = future.instrument(Span::current());
         self.runtime.spawn(async move {
             let result = future.await;
 
@@ -71,3 +101,15 @@ impl BatchHandler {
         rx.await.unwrap()
     }
 }
+
+/// Returns the statement and the plan digest of a query request for the slow query log,
+/// other requests are not recorded.
+pub(crate) fn describe_query(expr: &ObjectExpr) -> Option<(String, String)> {
+    let Some(Request::Query(query)) = &expr.request else {
+        return None;
+    };
+    match query.query.as_ref()? {
+        Query::Sql(sql) => Some((sql.clone(), slow_query::digest(sql.as_bytes()))),
+        Query::LogicalPlan(plan) => Some(("<logical plan>".to_string(), slow_query::digest(plan))),
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Limits of the gRPC server: the size of inbound messages and the request rates, which
+//! can be adjusted at runtime by the [RequestLimiter].
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use hyper::Body;
+use metrics::increment_counter;
+use serde::{Deserialize, Serialize};
+use tonic::Status;
+
+use crate::metric::METRIC_GRPC_REJECTED_REQUESTS_TOTAL;
+
+/// The size of the header of a gRPC message, a compressed flag and the length of the message.
+const GRPC_HEADER_SIZE: usize = 5;
+
+/// Idle connections are pruned once the number of tracked connections exceeds it.
+const MAX_TRACKED_CONNECTIONS: usize = 4096;
+
+/// Limits of the gRPC server, 0 stands for unlimited.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcLimitOptions {
+    /// Max size in bytes of an inbound message, which is the size on the wire, i.e. after
+    /// compression.
+    pub max_recv_message_size: usize,
+    /// Max number of requests per second served by the server.
+    pub max_requests_per_second: u64,
+    /// Max number of requests per second served for a connection.
+    pub max_requests_per_second_per_connection: u64,
+}
+
+pub type RequestLimiterRef = Arc<RequestLimiter>;
+
+/// Rejects the requests exceeding the limits with `RESOURCE_EXHAUSTED`. Request rates are
+/// limited by token buckets, which allow bursts up to the rate of a second.
+#[derive(Debug)]
+pub struct RequestLimiter {
+    max_recv_message_size: AtomicUsize,
+    max_requests_per_second: AtomicU64,
+    max_requests_per_second_per_connection: AtomicU64,
+    global: Mutex<TokenBucket>,
+    connections: Mutex<HashMap<SocketAddr, TokenBucket>>,
+}
+
+impl RequestLimiter {
+    pub fn new(opts: &GrpcLimitOptions) -> Self {
+        let now = Instant::now();
+        let limiter = Self {
+            max_recv_message_size: AtomicUsize::new(0),
+            max_requests_per_second: AtomicU64::new(0),
+            max_requests_per_second_per_connection: AtomicU64::new(0),
+            global: Mutex::new(TokenBucket::new(opts.max_requests_per_second, now)),
+            connections: Mutex::new(HashMap::new()),
+        };
+        limiter.set_options(opts);
+        limiter
+    }
+
+    pub fn options(&self) -> GrpcLimitOptions {
+        GrpcLimitOptions {
+            max_recv_message_size: self.max_recv_message_size(),
+            max_requests_per_second: self.max_requests_per_second.load(Ordering::Relaxed),
+            max_requests_per_second_per_connection: self
+                .max_requests_per_second_per_connection
+                .load(Ordering::Relaxed),
+        }
+    }
+
+    /// Updates all the limits, which take effect on the following requests.
+    pub fn set_options(&self, opts: &GrpcLimitOptions) {
+        self.set_max_recv_message_size(opts.max_recv_message_size);
+        self.set_max_requests_per_second(opts.max_requests_per_second);
+        self.set_max_requests_per_second_per_connection(
+            opts.max_requests_per_second_per_connection,
+        );
+    }
+
+    pub fn max_recv_message_size(&self) -> usize {
+        self.max_recv_message_size.load(Ordering::Relaxed)
+    }
+
+    /// The limit applies to the requests received afterwards, a request streaming messages
+    /// is not limited if the limit is disabled when it's received.
+    pub fn set_max_recv_message_size(&self, size: usize) {
+        self.max_recv_message_size.store(size, Ordering::Relaxed);
+    }
+
+    pub fn set_max_requests_per_second(&self, rate: u64) {
+        self.max_requests_per_second.store(rate, Ordering::Relaxed);
+    }
+
+    pub fn set_max_requests_per_second_per_connection(&self, rate: u64) {
+        self.max_requests_per_second_per_connection
+            .store(rate, Ordering::Relaxed);
+    }
+
+    /// Checks whether a request from `remote_addr` is allowed, only the global limit is
+    /// checked if the remote address is unknown.
+    pub fn check(&self, remote_addr: Option<SocketAddr>) -> Result<(), Status> {
+        self.check_at(remote_addr, Instant::now())
+    }
+
+    fn check_at(&self, remote_addr: Option<SocketAddr>, now: Instant) -> Result<(), Status> {
+        // Checks the connection first, so that requests rejected by the limit of a connection
+        // don't consume the tokens of others.
+        let rate = self
+            .max_requests_per_second_per_connection
+            .load(Ordering::Relaxed);
+        if let (Some(addr), true) = (remote_addr, rate > 0) {
+            let mut connections = self.connections.lock().unwrap();
+            if connections.len() >= MAX_TRACKED_CONNECTIONS && !connections.contains_key(&addr) {
+                connections.retain(|_, bucket| !bucket.is_full(now));
+            }
+            let acquired = connections
+                .entry(addr)
+                .or_insert_with(|| TokenBucket::new(rate, now))
+                .try_acquire(rate, now);
+            if !acquired {
+                increment_counter!(METRIC_GRPC_REJECTED_REQUESTS_TOTAL, "limit" => "connection");
+                return Err(Status::resource_exhausted(format!(
+                    "Too many requests from {addr}, the limit is {rate} requests per second"
+                )));
+            }
+        }
+
+        let rate = self.max_requests_per_second.load(Ordering::Relaxed);
+        if rate > 0 && !self.global.lock().unwrap().try_acquire(rate, now) {
+            increment_counter!(METRIC_GRPC_REJECTED_REQUESTS_TOTAL, "limit" => "global");
+            return Err(Status::resource_exhausted(format!(
+                "Too many requests, the limit is {rate} requests per second"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Limits the size of the messages in the body of a gRPC request.
+    pub(crate) fn limit_body(&self, body: Body) -> Body {
+        let max = self.max_recv_message_size();
+        if max == 0 {
+            return body;
+        }
+        let mut checker = MessageSizeChecker::new(max);
+        Body::wrap_stream(body.map(move |chunk| {
+            let chunk = chunk?;
+            checker.check(&chunk)?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(chunk)
+        }))
+    }
+}
+
+/// Token bucket whose capacity is the rate of a second.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        Self {
+            tokens: rate as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Refills the bucket at `rate` and acquires a token from it. The rate may change
+    /// between calls as it's adjustable.
+    fn try_acquire(&mut self, rate: u64, now: Instant) -> bool {
+        let capacity = rate as f64;
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * capacity).min(capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A bucket untouched for a second is full, which is the same as a new one.
+    fn is_full(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_refill) >= Duration::from_secs(1)
+    }
+}
+
+/// Checks the length in the header of each message while the body is streamed, the
+/// headers may be split across chunks.
+struct MessageSizeChecker {
+    max: usize,
+    header: [u8; GRPC_HEADER_SIZE],
+    header_len: usize,
+    /// Remaining bytes of the current message.
+    remaining: usize,
+}
+
+impl MessageSizeChecker {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            header: [0; GRPC_HEADER_SIZE],
+            header_len: 0,
+            remaining: 0,
+        }
+    }
+
+    fn check(&mut self, mut buf: &[u8]) -> Result<(), Status> {
+        while !buf.is_empty() {
+            if self.remaining > 0 {
+                let n = self.remaining.min(buf.len());
+                self.remaining -= n;
+                buf = &buf[n..];
+                continue;
+            }
+
+            let n = (GRPC_HEADER_SIZE - self.header_len).min(buf.len());
+            self.header[self.header_len..self.header_len + n].copy_from_slice(&buf[..n]);
+            self.header_len += n;
+            buf = &buf[n..];
+            if self.header_len == GRPC_HEADER_SIZE {
+                let mut len = [0; 4];
+                len.copy_from_slice(&self.header[1..]);
+                let len = u32::from_be_bytes(len) as usize;
+                if len > self.max {
+                    increment_counter!(
+                        METRIC_GRPC_REJECTED_REQUESTS_TOTAL,
+                        "limit" => "message_size"
+                    );
+                    return Err(Status::resource_exhausted(format!(
+                        "Message of {len} bytes exceeds the limit of {} bytes",
+                        self.max
+                    )));
+                }
+                self.header_len = 0;
+                self.remaining = len;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(len: usize) -> Vec<u8> {
+        let mut buf = vec![0];
+        buf.extend_from_slice(&(len as u32).to_be_bytes());
+        buf.resize(GRPC_HEADER_SIZE + len, 1);
+        buf
+    }
+
+    #[test]
+    fn test_message_size_checker() {
+        let mut checker = MessageSizeChecker::new(10);
+        let mut buf = message(10);
+        buf.extend(message(0));
+        buf.extend(message(3));
+        checker.check(&buf).unwrap();
+
+        // Headers split across chunks.
+        let buf = [message(4), message(11)].concat();
+        let mut checker = MessageSizeChecker::new(10);
+        for chunk in buf[..GRPC_HEADER_SIZE + 6].chunks(2) {
+            checker.check(chunk).unwrap();
+        }
+        let err = checker.check(&buf[GRPC_HEADER_SIZE + 6..]).unwrap_err();
+        assert_eq!(tonic::Code::ResourceExhausted, err.code());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let limiter = RequestLimiter::new(&GrpcLimitOptions {
+            max_requests_per_second: 3,
+            max_requests_per_second_per_connection: 2,
+            ..Default::default()
+        });
+        let a: SocketAddr = "127.0.0.1:10001".parse().unwrap();
+        let b: SocketAddr = "127.0.0.1:10002".parse().unwrap();
+        let now = Instant::now();
+
+        limiter.check_at(Some(a), now).unwrap();
+        limiter.check_at(Some(a), now).unwrap();
+        let err = limiter.check_at(Some(a), now).unwrap_err();
+        assert_eq!(tonic::Code::ResourceExhausted, err.code());
+        limiter.check_at(Some(b), now).unwrap();
+        // The global limit is reached.
+        assert!(limiter.check_at(Some(b), now).is_err());
+        assert!(limiter.check_at(None, now).is_err());
+
+        let now = now + Duration::from_millis(500);
+        limiter.check_at(Some(a), now).unwrap();
+        assert!(limiter.check_at(Some(a), now).is_err());
+
+        // Limits are adjustable.
+        limiter.set_options(&GrpcLimitOptions::default());
+        assert_eq!(GrpcLimitOptions::default(), limiter.options());
+        for _ in 0..10 {
+            limiter.check_at(Some(a), now).unwrap();
+        }
+    }
+}
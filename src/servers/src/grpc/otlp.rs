@@ -0,0 +1,88 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::MetricsService;
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+};
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+
+use crate::auth::UserProviderRef;
+use crate::grpc::authorize::authorize;
+use crate::query_handler::OpenTelemetryProtocolHandlerRef;
+
+/// The metadata to specify the database that metrics are written to, the default
+/// schema is used if it's absent.
+pub const DATABASE_METADATA: &str = "x-greptime-database";
+
+/// Receives metrics from OpenTelemetry exporters by the OTLP/gRPC `MetricsService`.
+pub struct OtlpMetricsService {
+    handler: OpenTelemetryProtocolHandlerRef,
+    user_provider: Option<UserProviderRef>,
+}
+
+impl OtlpMetricsService {
+    pub fn new(
+        handler: OpenTelemetryProtocolHandlerRef,
+        user_provider: Option<UserProviderRef>,
+    ) -> Self {
+        Self {
+            handler,
+            user_provider,
+        }
+    }
+}
+
+fn database(metadata: &MetadataMap) -> Result<String, Status> {
+    match metadata.get(DATABASE_METADATA) {
+        Some(db) => db
+            .to_str()
+            .map(|db| db.to_string())
+            .map_err(|_| Status::invalid_argument("invalid database metadata")),
+        None => Ok(DEFAULT_SCHEMA_NAME.to_string()),
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for OtlpMetricsService {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        authorize(&self.user_provider, request.metadata()).await?;
+        let db = database(request.metadata())?;
+        self.handler
+            .write_metrics(&db, request.into_inner())
+            .await?;
+        Ok(Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::metadata::MetadataValue;
+
+    use super::*;
+
+    #[test]
+    fn test_database_from_metadata() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(DEFAULT_SCHEMA_NAME, database(&metadata).unwrap());
+
+        let _ = metadata.insert(DATABASE_METADATA, MetadataValue::from_static("metrics"));
+        assert_eq!("metrics", database(&metadata).unwrap());
+    }
+}
@@ -12,35 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-mod authorize;
+pub(crate) mod authorize;
 pub mod handler;
 pub mod influxdb;
 pub mod opentsdb;
 pub mod prometheus;
 pub mod script;
+pub mod stream;
+pub mod types;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use aide::axum::{routing as apirouting, ApiRouter, IntoApiResponse};
-use aide::openapi::{Info, OpenApi, Server as OpenAPIServer};
+use aide::openapi::{Info, OpenApi};
 use async_trait::async_trait;
 use axum::body::BoxBody;
 use axum::error_handling::HandleErrorLayer;
 use axum::response::{Html, Json};
-use axum::{routing, BoxError, Extension, Router};
-use common_error::prelude::ErrorExt;
+use axum::{middleware, BoxError, Extension, Router};
 use common_error::status_code::StatusCode;
-use common_query::Output;
-use common_recordbatch::{util, RecordBatch};
 use common_telemetry::logging::info;
-use datatypes::data_type::DataType;
 use futures::FutureExt;
-use schemars::JsonSchema;
+use hyper::server::accept;
+use hyper::server::conn::AddrIncoming;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use snafu::{ensure, ResultExt};
+use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::Mutex;
 use tower::timeout::TimeoutLayer;
@@ -49,14 +48,17 @@ use tower_http::auth::AsyncRequireAuthorizationLayer;
 use tower_http::trace::TraceLayer;
 
 use self::authorize::HttpAuth;
-use self::influxdb::influxdb_write;
+// Types of responses used to be defined in this module.
+pub use self::types::{ColumnSchema, HttpRecordsOutput, JsonOutput, JsonResponse, Schema};
 use crate::auth::UserProviderRef;
-use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
+use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu, TcpBindSnafu};
+use crate::metric;
 use crate::query_handler::{
     InfluxdbLineProtocolHandlerRef, OpentsdbProtocolHandlerRef, PrometheusProtocolHandlerRef,
-    ScriptHandlerRef, SqlQueryHandlerRef,
+    ReadinessHandlerRef, ScriptHandlerRef, SqlQueryHandlerRef,
 };
 use crate::server::Server;
+use crate::tls::{tls_incoming, ReloadableTlsServerConfig, TlsOption, HTTP_ALPN_PROTOCOLS};
 
 const HTTP_API_VERSION: &str = "v1";
 
@@ -67,6 +69,7 @@ pub struct HttpServer {
     opentsdb_handler: Option<OpentsdbProtocolHandlerRef>,
     prom_handler: Option<PrometheusProtocolHandlerRef>,
     script_handler: Option<ScriptHandlerRef>,
+    readiness_handler: Option<ReadinessHandlerRef>,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     user_provider: Option<UserProviderRef>,
 }
@@ -76,6 +79,9 @@ pub struct HttpOptions {
     pub addr: String,
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// Serves HTTPS only unless the mode is disable.
+    #[serde(default)]
+    pub tls: TlsOption,
 }
 
 impl Default for HttpOptions {
@@ -83,225 +89,20 @@ impl Default for HttpOptions {
         Self {
             addr: "127.0.0.1:4000".to_string(),
             timeout: Duration::from_secs(30),
+            tls: TlsOption::default(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
-pub struct ColumnSchema {
-    name: String,
-    data_type: String,
-}
-
-impl ColumnSchema {
-    pub fn new(name: String, data_type: String) -> ColumnSchema {
-        ColumnSchema { name, data_type }
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
-pub struct Schema {
-    column_schemas: Vec<ColumnSchema>,
-}
-
-impl Schema {
-    pub fn new(columns: Vec<ColumnSchema>) -> Schema {
-        Schema {
-            column_schemas: columns,
-        }
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
-pub struct HttpRecordsOutput {
-    schema: Option<Schema>,
-    rows: Vec<Vec<Value>>,
-}
-
-impl HttpRecordsOutput {
-    pub fn num_rows(&self) -> usize {
-        self.rows.len()
-    }
-
-    pub fn num_cols(&self) -> usize {
-        self.schema
-            .as_ref()
-            .map(|x| x.column_schemas.len())
-            .unwrap_or(0)
-    }
-
-    pub fn schema(&self) -> Option<&Schema> {
-        self.schema.as_ref()
-    }
-
-    pub fn rows(&self) -> &Vec<Vec<Value>> {
-        &self.rows
-    }
-}
-
-impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
-    type Error = String;
-
-    // TODO(sunng87): use schema from recordstreams when #366 fixed
-    fn try_from(
-        recordbatches: Vec<RecordBatch>,
-    ) -> std::result::Result<HttpRecordsOutput, Self::Error> {
-        if recordbatches.is_empty() {
-            Ok(HttpRecordsOutput {
-                schema: None,
-                rows: vec![],
-            })
-        } else {
-            // safety ensured by previous empty check
-            let first = &recordbatches[0];
-            let schema = Schema {
-                column_schemas: first
-                    .schema
-                    .column_schemas()
-                    .iter()
-                    .map(|cs| ColumnSchema {
-                        name: cs.name.clone(),
-                        data_type: cs.data_type.name().to_owned(),
-                    })
-                    .collect(),
-            };
-
-            let mut rows =
-                Vec::with_capacity(recordbatches.iter().map(|r| r.num_rows()).sum::<usize>());
-
-            for recordbatch in recordbatches {
-                for row in recordbatch.rows() {
-                    let value_row = row
-                        .into_iter()
-                        .map(|f| Value::try_from(f).map_err(|err| err.to_string()))
-                        .collect::<std::result::Result<Vec<Value>, _>>()?;
-
-                    rows.push(value_row);
-                }
-            }
-
-            Ok(HttpRecordsOutput {
-                schema: Some(schema),
-                rows,
-            })
-        }
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum JsonOutput {
-    AffectedRows(usize),
-    Records(HttpRecordsOutput),
-}
-
-#[derive(Serialize, Deserialize, Debug, JsonSchema)]
-pub struct JsonResponse {
-    code: u32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    error: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    output: Option<Vec<JsonOutput>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    execution_time_ms: Option<u128>,
-}
-
-impl JsonResponse {
-    fn with_error(error: String, error_code: StatusCode) -> Self {
-        JsonResponse {
-            error: Some(error),
-            code: error_code as u32,
-            output: None,
-            execution_time_ms: None,
-        }
-    }
-
-    fn with_output(output: Option<Vec<JsonOutput>>) -> Self {
-        JsonResponse {
-            error: None,
-            code: StatusCode::Success as u32,
-            output,
-            execution_time_ms: None,
-        }
-    }
-
-    fn with_execution_time(mut self, execution_time: u128) -> Self {
-        self.execution_time_ms = Some(execution_time);
-        self
-    }
-
-    /// Create a json response from query result
-    async fn from_output(outputs: Vec<Result<Output>>) -> Self {
-        // TODO(sunng87): this api response structure cannot represent error
-        // well. It hides successful execution results from error response
-        let mut results = Vec::with_capacity(outputs.len());
-        for out in outputs {
-            match out {
-                Ok(Output::AffectedRows(rows)) => {
-                    results.push(JsonOutput::AffectedRows(rows));
-                }
-                Ok(Output::Stream(stream)) => {
-                    // TODO(sunng87): streaming response
-                    match util::collect(stream).await {
-                        Ok(rows) => match HttpRecordsOutput::try_from(rows) {
-                            Ok(rows) => {
-                                results.push(JsonOutput::Records(rows));
-                            }
-                            Err(err) => {
-                                return Self::with_error(err, StatusCode::Internal);
-                            }
-                        },
-
-                        Err(e) => {
-                            return Self::with_error(
-                                format!("Recordbatch error: {e}"),
-                                e.status_code(),
-                            );
-                        }
-                    }
-                }
-                Ok(Output::RecordBatches(rbs)) => match HttpRecordsOutput::try_from(rbs.take()) {
-                    Ok(rows) => {
-                        results.push(JsonOutput::Records(rows));
-                    }
-                    Err(err) => {
-                        return Self::with_error(err, StatusCode::Internal);
-                    }
-                },
-                Err(e) => {
-                    return Self::with_error(
-                        format!("Query engine output error: {e}"),
-                        e.status_code(),
-                    );
-                }
-            }
-        }
-        Self::with_output(Some(results))
-    }
-
-    pub fn code(&self) -> u32 {
-        self.code
-    }
-
-    pub fn success(&self) -> bool {
-        self.code == (StatusCode::Success as u32)
-    }
-
-    pub fn error(&self) -> Option<&String> {
-        self.error.as_ref()
-    }
+/// Path of the OpenAPI specification of the HTTP API.
+const API_SPEC_PATH: &str = "/api/spec";
+/// Path of the liveness probe.
+pub const HEALTH_PATH: &str = "/health";
+/// Path of the readiness probe.
+pub const READY_PATH: &str = "/ready";
 
-    pub fn output(&self) -> Option<&[JsonOutput]> {
-        self.output.as_deref()
-    }
-
-    pub fn execution_time_ms(&self) -> Option<u128> {
-        self.execution_time_ms
-    }
-}
-
-async fn serve_api(Extension(api): Extension<OpenApi>) -> impl IntoApiResponse {
-    Json(api)
+async fn serve_api(Extension(api): Extension<Arc<OpenApi>>) -> impl IntoApiResponse {
+    Json(api.as_ref().clone())
 }
 
 async fn serve_docs() -> Html<String> {
@@ -316,6 +117,7 @@ pub struct ApiState {
 
 impl HttpServer {
     pub fn new(sql_handler: SqlQueryHandlerRef, options: HttpOptions) -> Self {
+        metric::register_metrics();
         Self {
             sql_handler,
             options,
@@ -324,6 +126,7 @@ impl HttpServer {
             prom_handler: None,
             user_provider: None,
             script_handler: None,
+            readiness_handler: None,
             shutdown_tx: Mutex::new(None),
         }
     }
@@ -344,6 +147,14 @@ impl HttpServer {
         self.script_handler.get_or_insert(handler);
     }
 
+    pub fn set_readiness_handler(&mut self, handler: ReadinessHandlerRef) {
+        debug_assert!(
+            self.readiness_handler.is_none(),
+            "Readiness handler can be set only once!"
+        );
+        self.readiness_handler.get_or_insert(handler);
+    }
+
     pub fn set_influxdb_handler(&mut self, handler: InfluxdbLineProtocolHandlerRef) {
         debug_assert!(
             self.influxdb_handler.is_none(),
@@ -376,50 +187,31 @@ impl HttpServer {
                 version: HTTP_API_VERSION.to_string(),
                 ..Info::default()
             },
-            servers: vec![OpenAPIServer {
-                url: format!("/{HTTP_API_VERSION}"),
-                ..OpenAPIServer::default()
-            }],
             ..OpenApi::default()
         };
 
-        let sql_router = self
-            .route_sql(ApiState {
-                sql_handler: self.sql_handler.clone(),
-                script_handler: self.script_handler.clone(),
-            })
-            .finish_api(&mut api)
-            .layer(Extension(Arc::new(api)));
-
-        let mut router = Router::new().nest(&format!("/{HTTP_API_VERSION}"), sql_router);
+        let mut router = self.route_sql(ApiState {
+            sql_handler: self.sql_handler.clone(),
+            script_handler: self.script_handler.clone(),
+        });
 
         if let Some(opentsdb_handler) = self.opentsdb_handler.clone() {
-            router = router.nest(
-                &format!("/{HTTP_API_VERSION}/opentsdb"),
-                self.route_opentsdb(opentsdb_handler),
-            );
+            router = router.merge(self.route_opentsdb(opentsdb_handler));
         }
 
         if let Some(influxdb_handler) = self.influxdb_handler.clone() {
-            router = router.nest(
-                &format!("/{HTTP_API_VERSION}/influxdb"),
-                self.route_influxdb(influxdb_handler),
-            );
+            router = router.merge(self.route_influxdb(influxdb_handler));
         }
 
         if let Some(prom_handler) = self.prom_handler.clone() {
-            router = router.nest(
-                &format!("/{HTTP_API_VERSION}/prometheus"),
-                self.route_prom(prom_handler),
-            );
+            router = router.merge(self.route_prom(prom_handler));
         }
 
-        router = router.route("/metrics", routing::get(handler::metrics));
-
-        router = router.route(
-            "/health",
-            routing::get(handler::health).post(handler::health),
-        );
+        let router = router.merge(self.route_admin()).merge(self.route_ready());
+        let router = router
+            .finish_api(&mut api)
+            .layer(Extension(Arc::new(api)))
+            .route_layer(middleware::from_fn(metric::track_http_requests));
 
         router
             // middlewares
@@ -438,35 +230,87 @@ impl HttpServer {
     fn route_sql<S>(&self, api_state: ApiState) -> ApiRouter<S> {
         ApiRouter::new()
             .api_route(
-                "/sql",
+                &format!("/{HTTP_API_VERSION}/sql"),
                 apirouting::get_with(handler::sql, handler::sql_docs)
                     .post_with(handler::sql, handler::sql_docs),
             )
-            .api_route("/scripts", apirouting::post(script::scripts))
-            .api_route("/run-script", apirouting::post(script::run_script))
-            .route("/private/api.json", apirouting::get(serve_api))
-            .route("/private/docs", apirouting::get(serve_docs))
+            .api_route(
+                &format!("/{HTTP_API_VERSION}/scripts"),
+                apirouting::post_with(script::scripts, script::scripts_docs),
+            )
+            .api_route(
+                &format!("/{HTTP_API_VERSION}/run-script"),
+                apirouting::post_with(script::run_script, script::run_script_docs),
+            )
             .with_state(api_state)
     }
 
-    fn route_prom<S>(&self, prom_handler: PrometheusProtocolHandlerRef) -> Router<S> {
-        Router::new()
-            .route("/write", routing::post(prometheus::remote_write))
-            .route("/read", routing::post(prometheus::remote_read))
+    fn route_prom<S>(&self, prom_handler: PrometheusProtocolHandlerRef) -> ApiRouter<S> {
+        ApiRouter::new()
+            .api_route(
+                &format!("/{HTTP_API_VERSION}/prometheus/write"),
+                apirouting::post_with(prometheus::remote_write, prometheus::remote_write_docs),
+            )
+            .api_route(
+                &format!("/{HTTP_API_VERSION}/prometheus/read"),
+                apirouting::post_with(prometheus::remote_read, prometheus::remote_read_docs),
+            )
             .with_state(prom_handler)
     }
 
-    fn route_influxdb<S>(&self, influxdb_handler: InfluxdbLineProtocolHandlerRef) -> Router<S> {
-        Router::new()
-            .route("/write", routing::post(influxdb_write))
+    fn route_influxdb<S>(&self, influxdb_handler: InfluxdbLineProtocolHandlerRef) -> ApiRouter<S> {
+        ApiRouter::new()
+            .api_route(
+                &format!("/{HTTP_API_VERSION}/influxdb/write"),
+                apirouting::post_with(influxdb::influxdb_write, influxdb::influxdb_write_docs),
+            )
             .with_state(influxdb_handler)
     }
 
-    fn route_opentsdb<S>(&self, opentsdb_handler: OpentsdbProtocolHandlerRef) -> Router<S> {
-        Router::new()
-            .route("/api/put", routing::post(opentsdb::put))
+    fn route_opentsdb<S>(&self, opentsdb_handler: OpentsdbProtocolHandlerRef) -> ApiRouter<S> {
+        ApiRouter::new()
+            .api_route(
+                &format!("/{HTTP_API_VERSION}/opentsdb/api/put"),
+                apirouting::post_with(opentsdb::put, opentsdb::put_docs),
+            )
             .with_state(opentsdb_handler)
     }
+
+    /// Routes of metrics, health check and documents of the HTTP API.
+    fn route_admin<S>(&self) -> ApiRouter<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        ApiRouter::new()
+            .api_route(
+                "/metrics",
+                apirouting::get_with(handler::metrics, handler::metrics_docs),
+            )
+            .api_route(
+                HEALTH_PATH,
+                apirouting::get_with(handler::health, handler::health_docs)
+                    .post_with(handler::health, handler::health_docs),
+            )
+            .route(API_SPEC_PATH, apirouting::get(serve_api))
+            // The spec used to be served at this path.
+            .route(
+                &format!("/{HTTP_API_VERSION}/private/api.json"),
+                apirouting::get(serve_api),
+            )
+            .route(
+                &format!("/{HTTP_API_VERSION}/private/docs"),
+                apirouting::get(serve_docs),
+            )
+    }
+
+    fn route_ready<S>(&self) -> ApiRouter<S> {
+        ApiRouter::new()
+            .api_route(
+                READY_PATH,
+                apirouting::get_with(handler::ready, handler::ready_docs),
+            )
+            .with_state(self.readiness_handler.clone())
+    }
 }
 
 #[async_trait]
@@ -484,8 +328,10 @@ impl Server for HttpServer {
     }
 
     async fn start(&self, listening: SocketAddr) -> Result<SocketAddr> {
+        let tls_config =
+            ReloadableTlsServerConfig::try_new(self.options.tls.clone(), HTTP_ALPN_PROTOCOLS)?;
         let (tx, rx) = oneshot::channel();
-        let server = {
+        let (app, listener) = {
             let mut shutdown_tx = self.shutdown_tx.lock().await;
             ensure!(
                 shutdown_tx.is_none(),
@@ -493,17 +339,41 @@ impl Server for HttpServer {
             );
 
             let app = self.make_app();
-            let server = axum::Server::bind(&listening).serve(app.into_make_service());
+            let listener = TcpListener::bind(listening)
+                .await
+                .context(TcpBindSnafu { addr: listening })?;
 
             *shutdown_tx = Some(tx);
 
-            server
+            (app, listener)
         };
-        let listening = server.local_addr();
-        info!("HTTP server is bound to {}", listening);
+        let listening = listener
+            .local_addr()
+            .context(TcpBindSnafu { addr: listening })?;
+        info!(
+            "HTTP server is bound to {}, TLS enabled: {}",
+            listening,
+            tls_config.is_some()
+        );
 
-        let graceful = server.with_graceful_shutdown(rx.map(drop));
-        graceful.await.context(StartHttpSnafu)?;
+        let shutdown = rx.map(drop);
+        match tls_config {
+            Some(tls_config) => {
+                let incoming = accept::from_stream(tls_incoming(listener, tls_config));
+                axum::Server::builder(incoming)
+                    .serve(app.into_make_service())
+                    .with_graceful_shutdown(shutdown)
+                    .await
+            }
+            None => {
+                let incoming = AddrIncoming::from_listener(listener).context(StartHttpSnafu)?;
+                axum::Server::builder(incoming)
+                    .serve(app.into_make_service())
+                    .with_graceful_shutdown(shutdown)
+                    .await
+            }
+        }
+        .context(StartHttpSnafu)?;
 
         Ok(listening)
     }
@@ -526,10 +396,7 @@ mod test {
     use axum::http::StatusCode;
     use axum::routing::get;
     use axum_test_helper::TestClient;
-    use common_recordbatch::RecordBatches;
-    use datatypes::prelude::*;
-    use datatypes::schema::{ColumnSchema, Schema};
-    use datatypes::vectors::{StringVector, UInt32Vector};
+    use common_query::Output;
     use session::context::QueryContextRef;
     use tokio::sync::mpsc;
 
@@ -599,38 +466,47 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_recordbatches_conversion() {
-        let column_schemas = vec![
-            ColumnSchema::new("numbers", ConcreteDataType::uint32_datatype(), false),
-            ColumnSchema::new("strings", ConcreteDataType::string_datatype(), true),
-        ];
-        let schema = Arc::new(Schema::new(column_schemas));
-        let columns: Vec<VectorRef> = vec![
-            Arc::new(UInt32Vector::from_slice(vec![1, 2, 3, 4])),
-            Arc::new(StringVector::from(vec![
-                None,
-                Some("hello"),
-                Some("greptime"),
-                None,
-            ])),
-        ];
-        let recordbatch = RecordBatch::new(schema.clone(), columns).unwrap();
-        let recordbatches = RecordBatches::try_new(schema.clone(), vec![recordbatch]).unwrap();
-
-        let json_resp =
-            JsonResponse::from_output(vec![Ok(Output::RecordBatches(recordbatches))]).await;
-
-        let json_output = &json_resp.output.unwrap()[0];
-        if let JsonOutput::Records(r) = json_output {
-            assert_eq!(r.num_rows(), 4);
-            assert_eq!(r.num_cols(), 2);
-            let schema = r.schema.as_ref().unwrap();
-            assert_eq!(schema.column_schemas[0].name, "numbers");
-            assert_eq!(schema.column_schemas[0].data_type, "UInt32");
-            assert_eq!(r.rows[0][0], serde_json::Value::from(1));
-            assert_eq!(r.rows[0][1], serde_json::Value::Null);
-        } else {
-            panic!("invalid output type");
+    async fn test_http_request_metrics() {
+        common_telemetry::init_default_metrics_recorder();
+        let (tx, _rx) = mpsc::channel(100);
+        let app = make_test_app(tx);
+        let client = TestClient::new(app);
+        let res = client.get("/health").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = client.get("/metrics").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let text = res.text().await;
+        assert!(
+            text.contains("# HELP servers_http_requests_total"),
+            "{text}"
+        );
+        assert!(
+            text.contains(r#"servers_http_requests_total{method="GET",path="/health",code="200"}"#),
+            "{text}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_spec() {
+        let (tx, _rx) = mpsc::channel(100);
+        let app = make_test_app(tx);
+        let client = TestClient::new(app);
+        let res = client.get(API_SPEC_PATH).send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let spec: serde_json::Value = serde_json::from_str(&res.text().await).unwrap();
+        let paths = spec["paths"].as_object().unwrap();
+        for path in [
+            "/v1/sql",
+            "/v1/scripts",
+            "/v1/run-script",
+            "/metrics",
+            "/health",
+        ] {
+            assert!(paths.contains_key(path), "{path} not in {paths:?}");
         }
+        // Routes of the documents are not part of the API.
+        assert!(!paths.contains_key(API_SPEC_PATH));
     }
 }
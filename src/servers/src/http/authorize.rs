@@ -25,6 +25,7 @@ use tower_http::auth::AsyncAuthorizeRequest;
 
 use crate::auth::{Identity, UserProviderRef};
 use crate::error::{self, Result};
+use crate::http::{HEALTH_PATH, READY_PATH};
 
 pub struct HttpAuth<RespBody> {
     user_provider: Option<UserProviderRef>,
@@ -61,11 +62,14 @@ where
     fn authorize(&mut self, mut request: Request<B>) -> Self::Future {
         let user_provider = self.user_provider.clone();
         Box::pin(async move {
-            let user_provider = if let Some(user_provider) = &user_provider {
-                user_provider
-            } else {
-                request.extensions_mut().insert(UserInfo::default());
-                return Ok(request);
+            // Probes of the orchestrator, e.g. kubernetes, don't carry credentials.
+            let is_probe = matches!(request.uri().path(), HEALTH_PATH | READY_PATH);
+            let user_provider = match &user_provider {
+                Some(user_provider) if !is_probe => user_provider,
+                _ => {
+                    request.extensions_mut().insert(UserInfo::default());
+                    return Ok(request);
+                }
             };
 
             let (scheme, credential) = match auth_header(&request) {
@@ -76,31 +80,14 @@ where
                 }
             };
 
-            match scheme {
-                AuthScheme::Basic => {
-                    let (username, password) = match decode_basic(credential) {
-                        Ok(basic_auth) => basic_auth,
-                        Err(e) => {
-                            error!("failed to decode basic authorize, err: {:?}", e);
-                            return Err(unauthorized_resp());
-                        }
-                    };
-                    match user_provider
-                        .auth(
-                            Identity::UserId(&username, None),
-                            crate::auth::Password::PlainText(&password),
-                        )
-                        .await
-                    {
-                        Ok(user_info) => {
-                            request.extensions_mut().insert(user_info);
-                            Ok(request)
-                        }
-                        Err(e) => {
-                            error!("failed to auth, err: {:?}", e);
-                            Err(unauthorized_resp())
-                        }
-                    }
+            match authenticate(user_provider, scheme, credential).await {
+                Ok(user_info) => {
+                    request.extensions_mut().insert(user_info);
+                    Ok(request)
+                }
+                Err(e) => {
+                    error!("failed to auth, err: {:?}", e);
+                    Err(unauthorized_resp())
                 }
             }
         })
@@ -119,6 +106,7 @@ where
 #[derive(Debug)]
 pub enum AuthScheme {
     Basic,
+    Bearer,
 }
 
 impl TryFrom<&str> for AuthScheme {
@@ -127,6 +115,7 @@ impl TryFrom<&str> for AuthScheme {
     fn try_from(value: &str) -> Result<Self> {
         match value.to_lowercase().as_str() {
             "basic" => Ok(AuthScheme::Basic),
+            "bearer" => Ok(AuthScheme::Bearer),
             other => error::UnsupportedAuthSchemeSnafu { name: other }.fail(),
         }
     }
@@ -142,6 +131,11 @@ fn auth_header<B>(req: &Request<B>) -> Result<(AuthScheme, Credential)> {
         .to_str()
         .context(error::InvisibleASCIISnafu)?;
 
+    parse_auth_header(auth_header)
+}
+
+/// Parses the value of an `Authorization` header into the scheme and the credential.
+pub(crate) fn parse_auth_header(auth_header: &str) -> Result<(AuthScheme, &str)> {
     let (auth_scheme, encoded_credentials) = auth_header
         .split_once(' ')
         .context(error::InvalidAuthorizationHeaderSnafu)?;
@@ -153,6 +147,30 @@ fn auth_header<B>(req: &Request<B>) -> Result<(AuthScheme, Credential)> {
     Ok((auth_scheme.try_into()?, encoded_credentials))
 }
 
+/// Authenticates the credential of an `Authorization` header by the user provider.
+pub(crate) async fn authenticate(
+    user_provider: &UserProviderRef,
+    scheme: AuthScheme,
+    credential: &str,
+) -> Result<UserInfo> {
+    match scheme {
+        AuthScheme::Basic => {
+            let (username, password) = decode_basic(credential)?;
+            user_provider
+                .auth(
+                    Identity::UserId(&username, None),
+                    crate::auth::Password::PlainText(&password),
+                )
+                .await
+                .context(error::AuthSnafu)
+        }
+        AuthScheme::Bearer => user_provider
+            .auth_token(credential)
+            .await
+            .context(error::AuthSnafu),
+    }
+}
+
 type Username = String;
 type Password = String;
 
@@ -180,6 +198,7 @@ mod tests {
 
     use super::{auth_header, decode_basic, AuthScheme, HttpAuth};
     use crate::auth::test::MockUserProvider;
+    use crate::auth::user_provider::StaticUserProvider;
     use crate::auth::UserProvider;
     use crate::error;
     use crate::error::Result;
@@ -220,6 +239,26 @@ mod tests {
         let wrong_req = mock_http_request("Basic dXNlcm5hbWU6cGFzc3dvcmQ=").unwrap();
         let auth_res = http_auth.authorize(wrong_req).await;
         assert!(auth_res.is_err());
+
+        let token_provider =
+            StaticUserProvider::try_from("cmd:greptime=greptime,token:abcdef=greptime").unwrap();
+        let mut http_auth: HttpAuth<BoxBody> = HttpAuth::new(Some(Arc::new(token_provider)));
+        let req = mock_http_request("Bearer abcdef").unwrap();
+        let req = http_auth.authorize(req).await.unwrap();
+        let user_info: &UserInfo = req.extensions().get().unwrap();
+        assert_eq!("greptime", user_info.username());
+
+        let wrong_req = mock_http_request("Bearer 123456").unwrap();
+        let auth_res = http_auth.authorize(wrong_req).await;
+        assert!(auth_res.is_err());
+
+        // Probes don't need credentials.
+        for path in ["/health", "/ready"] {
+            let req = Request::builder().uri(path).body(()).unwrap();
+            assert!(http_auth.authorize(req).await.is_ok());
+        }
+        let req = Request::builder().uri("/v1/sql").body(()).unwrap();
+        assert!(http_auth.authorize(req).await.is_err());
     }
 
     #[test]
@@ -241,6 +280,9 @@ mod tests {
         let auth_scheme: AuthScheme = auth_scheme_str.try_into().unwrap();
         matches!(auth_scheme, AuthScheme::Basic);
 
+        let auth_scheme: AuthScheme = "Bearer".try_into().unwrap();
+        assert!(matches!(auth_scheme, AuthScheme::Bearer));
+
         let unsupported = "digest";
         let auth_scheme: Result<AuthScheme> = unsupported.try_into();
         assert!(auth_scheme.is_err());
@@ -18,53 +18,59 @@ use std::time::Instant;
 
 use aide::transform::TransformOperation;
 use axum::extract::{Json, Query, State};
+use axum::http::StatusCode as HttpStatusCode;
 use axum::Extension;
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_error::status_code::StatusCode;
+use common_query::Output;
 use common_telemetry::metric;
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 use session::context::{QueryContext, UserInfo};
 
-use crate::http::{ApiState, JsonResponse};
-
-#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
-pub struct SqlQuery {
-    pub database: Option<String>,
-    pub sql: Option<String>,
-}
+use crate::error::Result;
+use crate::http::stream::StreamingResponse;
+pub use crate::http::types::{HealthQuery, HealthResponse, SqlQuery};
+use crate::http::types::{JsonResponse, ReadinessResponse, ResponseFormat, SqlResponse};
+use crate::http::ApiState;
+use crate::query_handler::ReadinessHandlerRef;
 
 /// Handler to execute sql
 #[axum_macros::debug_handler]
 pub async fn sql(
     State(state): State<ApiState>,
     Query(params): Query<SqlQuery>,
-    // TODO(fys): pass _user_info into query context
-    _user_info: Extension<UserInfo>,
-) -> Json<JsonResponse> {
+    Extension(user_info): Extension<UserInfo>,
+) -> SqlResponse {
     let sql_handler = &state.sql_handler;
     let start = Instant::now();
     let resp = if let Some(sql) = &params.sql {
         let query_ctx = Arc::new(QueryContext::new());
+        query_ctx.set_current_user(user_info);
         if let Some(db) = &params.database {
             match sql_handler.is_valid_schema(DEFAULT_CATALOG_NAME, db) {
                 Ok(true) => query_ctx.set_current_schema(db),
                 Ok(false) => {
-                    return Json(JsonResponse::with_error(
+                    return SqlResponse::Json(Json(JsonResponse::with_error(
                         format!("Database not found: {db}"),
                         StatusCode::DatabaseNotFound,
-                    ));
+                    )));
                 }
                 Err(e) => {
-                    return Json(JsonResponse::with_error(
+                    return SqlResponse::Json(Json(JsonResponse::with_error(
                         format!("Error checking database: {db}, {e}"),
                         StatusCode::Internal,
-                    ));
+                    )));
                 }
             }
         }
 
-        JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
+        let outputs = sql_handler.do_query(sql, query_ctx).await;
+        match params.format.unwrap_or_default() {
+            ResponseFormat::Json => JsonResponse::from_output(outputs).await,
+            format => match stream_last_output(format, outputs) {
+                Ok(stream) => return SqlResponse::Stream(stream),
+                Err(outputs) => JsonResponse::from_output(outputs).await,
+            },
+        }
     } else {
         JsonResponse::with_error(
             "sql parameter is required.".to_string(),
@@ -72,11 +78,38 @@ pub async fn sql(
         )
     };
 
-    Json(resp.with_execution_time(start.elapsed().as_millis()))
+    SqlResponse::Json(Json(resp.with_execution_time(start.elapsed().as_millis())))
+}
+
+/// Streams the rows returned by the last statement in `format`. Gives back the
+/// `outputs` if any statement fails or the last one doesn't return rows.
+fn stream_last_output(
+    format: ResponseFormat,
+    mut outputs: Vec<Result<Output>>,
+) -> std::result::Result<StreamingResponse, Vec<Result<Output>>> {
+    if outputs.iter().any(|output| output.is_err()) {
+        return Err(outputs);
+    }
+    match outputs.pop() {
+        Some(Ok(Output::Stream(stream))) => Ok(StreamingResponse::new(format, stream)),
+        Some(Ok(Output::RecordBatches(batches))) => {
+            Ok(StreamingResponse::new(format, batches.as_stream()))
+        }
+        Some(output) => {
+            outputs.push(output);
+            Err(outputs)
+        }
+        None => Err(outputs),
+    }
 }
 
 pub(crate) fn sql_docs(op: TransformOperation) -> TransformOperation {
-    op.response::<200, Json<JsonResponse>>()
+    op.description(
+        "Executes SQL statements and returns the output of each statement. With `format` \
+        other than `json`, the rows returned by the last statement are streamed in CSV, \
+        newline delimited JSON or Arrow IPC format.",
+    )
+    .response::<200, Json<JsonResponse>>()
 }
 
 /// Handler to export metrics
@@ -89,11 +122,10 @@ pub async fn metrics(Query(_params): Query<HashMap<String, String>>) -> String {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
-pub struct HealthQuery {}
-
-#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
-pub struct HealthResponse {}
+pub(crate) fn metrics_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Exports metrics in the Prometheus text format.")
+        .response::<200, String>()
+}
 
 /// Handler to export healthy check
 ///
@@ -102,3 +134,37 @@ pub struct HealthResponse {}
 pub async fn health(Query(_params): Query<HealthQuery>) -> Json<HealthResponse> {
     Json(HealthResponse {})
 }
+
+pub(crate) fn health_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Checks whether the server is alive.")
+        .response::<200, Json<HealthResponse>>()
+}
+
+/// Handler to check whether the node is ready to serve requests, responds
+/// "503 Service Unavailable" if any check fails. The node is always ready if it
+/// has no readiness handler.
+#[axum_macros::debug_handler]
+pub async fn ready(
+    State(readiness_handler): State<Option<ReadinessHandlerRef>>,
+) -> (HttpStatusCode, Json<ReadinessResponse>) {
+    let checks = readiness_handler
+        .map(|handler| handler.readiness())
+        .unwrap_or_default();
+    let ready = checks.values().all(|passed| *passed);
+    let status = if ready {
+        HttpStatusCode::OK
+    } else {
+        HttpStatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(ReadinessResponse { ready, checks }))
+}
+
+pub(crate) fn ready_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Checks whether the server is ready to serve requests.")
+        .response_with::<200, Json<ReadinessResponse>, _>(|res| {
+            res.description("All readiness checks pass.")
+        })
+        .response_with::<503, Json<ReadinessResponse>, _>(|res| {
+            res.description("Some readiness checks fail.")
+        })
+}
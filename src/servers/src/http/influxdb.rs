@@ -12,30 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
-
+use aide::transform::TransformOperation;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use common_grpc::writer::Precision;
 
 use crate::error::{Result, TimePrecisionSnafu};
+use crate::http::types::InfluxdbWriteQuery;
 use crate::influxdb::InfluxdbRequest;
 use crate::query_handler::InfluxdbLineProtocolHandlerRef;
 
 #[axum_macros::debug_handler]
 pub async fn influxdb_write(
     State(handler): State<InfluxdbLineProtocolHandlerRef>,
-    Query(mut params): Query<HashMap<String, String>>,
+    Query(params): Query<InfluxdbWriteQuery>,
     lines: String,
 ) -> Result<(StatusCode, ())> {
-    let db = params
-        .remove("db")
-        .unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string());
+    let db = params.db.unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string());
 
     let precision = params
-        .get("precision")
-        .map(|val| parse_time_precision(val))
+        .precision
+        .as_deref()
+        .map(parse_time_precision)
         .transpose()?;
     let request = InfluxdbRequest {
         precision,
@@ -46,6 +45,11 @@ pub async fn influxdb_write(
     Ok((StatusCode::NO_CONTENT, ()))
 }
 
+pub(crate) fn influxdb_write_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Writes data points in the InfluxDB line protocol from the request body.")
+        .response_with::<204, (), _>(|res| res.description("Data points are written."))
+}
+
 fn parse_time_precision(value: &str) -> Result<Precision> {
     match value {
         "n" => Ok(Precision::Nanosecond),
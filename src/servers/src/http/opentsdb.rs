@@ -12,8 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
-
+use aide::transform::TransformOperation;
 use axum::extract::{Query, RawBody, State};
 use axum::http::StatusCode as HttpStatusCode;
 use axum::Json;
@@ -22,6 +21,8 @@ use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
 use crate::error::{self, Error, Result};
+pub use crate::http::types::{DataPointRequest, OpentsdbDebuggingResponse, OpentsdbPutResponse};
+use crate::http::types::{OpentsdbDetailError, OpentsdbPutQuery};
 use crate::opentsdb::codec::DataPoint;
 use crate::query_handler::OpentsdbProtocolHandlerRef;
 
@@ -41,14 +42,6 @@ impl<T> From<OneOrMany<T>> for Vec<T> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct DataPointRequest {
-    metric: String,
-    timestamp: i64,
-    value: f64,
-    tags: HashMap<String, String>,
-}
-
 impl From<DataPointRequest> for DataPoint {
     fn from(request: DataPointRequest) -> Self {
         let ts_millis = DataPoint::timestamp_to_millis(request.timestamp);
@@ -63,35 +56,30 @@ impl From<DataPointRequest> for DataPoint {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum OpentsdbPutResponse {
-    Empty,
-    Debug(OpentsdbDebuggingResponse),
-}
-
 // Please refer to the OpenTSDB documents of ["api/put"](http://opentsdb.net/docs/build/html/api_http/put.html)
 // for more details.
 #[axum_macros::debug_handler]
 pub async fn put(
     State(opentsdb_handler): State<OpentsdbProtocolHandlerRef>,
-    Query(params): Query<HashMap<String, String>>,
+    Query(params): Query<OpentsdbPutQuery>,
     RawBody(body): RawBody,
 ) -> Result<(HttpStatusCode, Json<OpentsdbPutResponse>)> {
-    let summary = params.contains_key("summary");
-    let details = params.contains_key("details");
+    let summary = params.summary.is_some();
+    let details = params.details.is_some();
 
     let data_points = parse_data_points(body).await?;
 
     let response = if !summary && !details {
-        for data_point in data_points.into_iter() {
-            if let Err(e) = opentsdb_handler.exec(&data_point.into()).await {
-                // Not debugging purpose, failed fast.
-                return error::InternalSnafu {
-                    err_msg: e.to_string(),
-                }
-                .fail();
+        let data_points = data_points
+            .into_iter()
+            .map(DataPoint::from)
+            .collect::<Vec<_>>();
+        if let Err(e) = opentsdb_handler.exec_batch(&data_points).await {
+            // Not debugging purpose, failed fast.
+            return error::InternalSnafu {
+                err_msg: e.to_string(),
             }
+            .fail();
         }
         (HttpStatusCode::NO_CONTENT, Json(OpentsdbPutResponse::Empty))
     } else {
@@ -122,6 +110,16 @@ pub async fn put(
     Ok(response)
 }
 
+pub(crate) fn put_docs(op: TransformOperation) -> TransformOperation {
+    op.description(
+        "Writes a data point or an array of data points in the OpenTSDB json format from the request body.",
+    )
+    .response_with::<204, (), _>(|res| res.description("Data points are written."))
+    .response_with::<200, Json<OpentsdbPutResponse>, _>(|res| {
+        res.description("Summary of the written data points if `summary` or `details` is present.")
+    })
+}
+
 async fn parse_data_points(body: Body) -> Result<Vec<DataPointRequest>> {
     let body = hyper::body::to_bytes(body)
         .await
@@ -131,20 +129,6 @@ async fn parse_data_points(body: Body) -> Result<Vec<DataPointRequest>> {
     Ok(data_points.into())
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct OpentsdbDetailError {
-    datapoint: DataPointRequest,
-    error: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct OpentsdbDebuggingResponse {
-    success: i32,
-    failed: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    errors: Option<Vec<OpentsdbDetailError>>,
-}
-
 impl OpentsdbDebuggingResponse {
     fn on_success(&mut self) {
         self.success += 1;
@@ -165,6 +149,8 @@ impl OpentsdbDebuggingResponse {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use super::*;
 
     #[test]
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use aide::transform::TransformOperation;
+use aide::OperationOutput;
 use api::prometheus::remote::{ReadRequest, WriteRequest};
 use axum::extract::{Query, RawBody, State};
 use axum::http::{header, StatusCode};
@@ -19,27 +21,13 @@ use axum::response::IntoResponse;
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
 use hyper::Body;
 use prost::Message;
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
 
 use crate::error::{self, Result};
+pub use crate::http::types::DatabaseQuery;
 use crate::prometheus::snappy_decompress;
 use crate::query_handler::{PrometheusProtocolHandlerRef, PrometheusResponse};
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
-pub struct DatabaseQuery {
-    pub db: Option<String>,
-}
-
-impl Default for DatabaseQuery {
-    fn default() -> DatabaseQuery {
-        Self {
-            db: Some(DEFAULT_SCHEMA_NAME.to_string()),
-        }
-    }
-}
-
 #[axum_macros::debug_handler]
 pub async fn remote_write(
     State(handler): State<PrometheusProtocolHandlerRef>,
@@ -55,6 +43,13 @@ pub async fn remote_write(
     Ok((StatusCode::NO_CONTENT, ()))
 }
 
+pub(crate) fn remote_write_docs(op: TransformOperation) -> TransformOperation {
+    op.description(
+        "Prometheus remote write API, the request body is a snappy compressed protobuf `WriteRequest`.",
+    )
+    .response_with::<204, (), _>(|res| res.description("Samples are written."))
+}
+
 impl IntoResponse for PrometheusResponse {
     fn into_response(self) -> axum::response::Response {
         (
@@ -68,6 +63,10 @@ impl IntoResponse for PrometheusResponse {
     }
 }
 
+impl OperationOutput for PrometheusResponse {
+    type Inner = Self;
+}
+
 #[axum_macros::debug_handler]
 pub async fn remote_read(
     State(handler): State<PrometheusProtocolHandlerRef>,
@@ -81,6 +80,12 @@ pub async fn remote_read(
         .await
 }
 
+pub(crate) fn remote_read_docs(op: TransformOperation) -> TransformOperation {
+    op.description(
+        "Prometheus remote read API, both the request and response bodies are snappy compressed protobuf messages.",
+    )
+}
+
 async fn decode_remote_write_request(body: Body) -> Result<WriteRequest> {
     let body = hyper::body::to_bytes(body)
         .await
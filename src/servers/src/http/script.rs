@@ -14,12 +14,13 @@
 
 use std::time::Instant;
 
+use aide::transform::TransformOperation;
 use axum::extract::{Json, Query, RawBody, State};
 use common_error::ext::ErrorExt;
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
 
-use crate::http::{ApiState, JsonResponse};
+use crate::http::types::JsonResponse;
+pub use crate::http::types::ScriptQuery;
+use crate::http::ApiState;
 
 macro_rules! json_err {
     ($e: expr) => {{
@@ -71,9 +72,9 @@ pub async fn scripts(
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
-pub struct ScriptQuery {
-    pub name: Option<String>,
+pub(crate) fn scripts_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Compiles the script in the request body and saves it with the given name.")
+        .response::<200, Json<JsonResponse>>()
 }
 
 /// Handler to execute script
@@ -98,3 +99,8 @@ pub async fn run_script(
         json_err!("Script execution not supported, missing script handler");
     }
 }
+
+pub(crate) fn run_script_docs(op: TransformOperation) -> TransformOperation {
+    op.description("Executes the script with the given name.")
+        .response::<200, Json<JsonResponse>>()
+}
@@ -0,0 +1,242 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Streams the rows of a query in the formats other than JSON.
+//!
+//! Rows are encoded batch by batch while polling the [SendableRecordBatchStream] and
+//! sent as chunks of the response body, so exporting a large result doesn't need to
+//! buffer it in the server.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use aide::OperationOutput;
+use arrow::csv;
+use arrow::datatypes::Schema as ArrowSchema;
+use arrow::ipc::writer::StreamWriter;
+use arrow::json::LineDelimitedWriter;
+use arrow::record_batch::RecordBatch as DfRecordBatch;
+use axum::body::{Bytes, StreamBody};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use common_recordbatch::SendableRecordBatchStream;
+use futures::{stream, StreamExt};
+use snafu::ResultExt;
+
+use crate::error::{CollectRecordbatchSnafu, EncodeRecordbatchSnafu, Result};
+use crate::http::types::ResponseFormat;
+
+/// A response that streams the rows of a [SendableRecordBatchStream] in a
+/// [ResponseFormat] other than JSON.
+pub struct StreamingResponse {
+    format: ResponseFormat,
+    stream: SendableRecordBatchStream,
+}
+
+impl StreamingResponse {
+    pub fn new(format: ResponseFormat, stream: SendableRecordBatchStream) -> StreamingResponse {
+        debug_assert_ne!(ResponseFormat::Json, format);
+        StreamingResponse { format, stream }
+    }
+}
+
+impl IntoResponse for StreamingResponse {
+    fn into_response(self) -> Response {
+        let schema = self.stream.schema();
+        let encoder = match BatchEncoder::try_new(self.format, schema.arrow_schema()) {
+            Ok(encoder) => encoder,
+            Err(e) => return e.into_response(),
+        };
+
+        // The stream stops after the first error, the client sees a truncated body
+        // since the status is already sent.
+        let body = stream::unfold(Some((self.stream, encoder)), |state| async move {
+            let (mut stream, mut encoder) = state?;
+            match stream.next().await {
+                Some(Ok(batch)) => {
+                    let chunk = encoder.encode(batch.df_record_batch());
+                    let next = chunk.is_ok().then_some((stream, encoder));
+                    Some((chunk, next))
+                }
+                Some(Err(e)) => Some((Err::<Bytes, _>(e).context(CollectRecordbatchSnafu), None)),
+                None => Some((encoder.finish(), None)),
+            }
+        });
+
+        (
+            [(header::CONTENT_TYPE, self.format.content_type())],
+            StreamBody::new(body),
+        )
+            .into_response()
+    }
+}
+
+impl OperationOutput for StreamingResponse {
+    type Inner = Self;
+}
+
+/// A buffer shared with the arrow writers, chunks are taken from the buffer after
+/// writing each batch.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Bytes {
+        Bytes::from(std::mem::take(&mut *self.0.lock().unwrap()))
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Writer {
+    Csv(csv::Writer<SharedBuffer>),
+    Ndjson(LineDelimitedWriter<SharedBuffer>),
+    Arrow(StreamWriter<SharedBuffer>),
+}
+
+/// Encodes record batches into chunks of the response body.
+struct BatchEncoder {
+    buffer: SharedBuffer,
+    writer: Writer,
+}
+
+impl BatchEncoder {
+    fn try_new(format: ResponseFormat, schema: &ArrowSchema) -> Result<BatchEncoder> {
+        let buffer = SharedBuffer::default();
+        let writer = match format {
+            ResponseFormat::Csv => Writer::Csv(csv::Writer::new(buffer.clone())),
+            ResponseFormat::Ndjson => Writer::Ndjson(LineDelimitedWriter::new(buffer.clone())),
+            ResponseFormat::Arrow => Writer::Arrow(
+                StreamWriter::try_new(buffer.clone(), schema)
+                    .context(EncodeRecordbatchSnafu { format: "arrow" })?,
+            ),
+            ResponseFormat::Json => unreachable!("JSON response is not streamed"),
+        };
+
+        Ok(BatchEncoder { buffer, writer })
+    }
+
+    /// Encodes the `batch` and returns the bytes written so far. The writers may
+    /// buffer some bytes internally, so the chunk might be empty.
+    fn encode(&mut self, batch: &DfRecordBatch) -> Result<Bytes> {
+        match &mut self.writer {
+            Writer::Csv(writer) => writer
+                .write(batch)
+                .context(EncodeRecordbatchSnafu { format: "csv" })?,
+            Writer::Ndjson(writer) => writer
+                .write_batches(std::slice::from_ref(batch))
+                .context(EncodeRecordbatchSnafu { format: "ndjson" })?,
+            Writer::Arrow(writer) => writer
+                .write(batch)
+                .context(EncodeRecordbatchSnafu { format: "arrow" })?,
+        }
+
+        Ok(self.buffer.take())
+    }
+
+    /// Finishes the writer and returns the remaining bytes.
+    fn finish(self) -> Result<Bytes> {
+        match self.writer {
+            // The csv writer flushes on drop.
+            Writer::Csv(writer) => drop(writer),
+            Writer::Ndjson(mut writer) => {
+                writer
+                    .finish()
+                    .context(EncodeRecordbatchSnafu { format: "ndjson" })?;
+            }
+            Writer::Arrow(mut writer) => {
+                writer
+                    .finish()
+                    .context(EncodeRecordbatchSnafu { format: "arrow" })?;
+            }
+        }
+
+        Ok(self.buffer.take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::ipc::reader::StreamReader;
+    use common_recordbatch::{RecordBatch, RecordBatches};
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{StringVector, UInt32Vector};
+
+    use super::*;
+
+    fn new_batches() -> RecordBatches {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("n", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("s", ConcreteDataType::string_datatype(), true),
+        ]));
+        let batches = (0..2)
+            .map(|i| {
+                let columns: Vec<VectorRef> = vec![
+                    Arc::new(UInt32Vector::from_slice([i * 2, i * 2 + 1])),
+                    Arc::new(StringVector::from(vec![Some("a"), None])),
+                ];
+                RecordBatch::new(schema.clone(), columns).unwrap()
+            })
+            .collect();
+        RecordBatches::try_new(schema, batches).unwrap()
+    }
+
+    fn encode(format: ResponseFormat) -> Vec<u8> {
+        let batches = new_batches();
+        let mut encoder = BatchEncoder::try_new(format, batches.schema().arrow_schema()).unwrap();
+        let mut output = Vec::new();
+        for batch in batches.iter() {
+            output.extend_from_slice(&encoder.encode(batch.df_record_batch()).unwrap());
+        }
+        output.extend_from_slice(&encoder.finish().unwrap());
+        output
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        let output = encode(ResponseFormat::Csv);
+        assert_eq!(
+            "n,s\n0,a\n1,\n2,a\n3,\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_ndjson() {
+        let output = encode(ResponseFormat::Ndjson);
+        assert_eq!(
+            "{\"n\":0,\"s\":\"a\"}\n{\"n\":1}\n{\"n\":2,\"s\":\"a\"}\n{\"n\":3}\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_arrow() {
+        let output = encode(ResponseFormat::Arrow);
+        let reader = StreamReader::try_new(output.as_slice(), None).unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(2, batches.len());
+        assert_eq!(4, batches.iter().map(|b| b.num_rows()).sum::<usize>());
+    }
+}
@@ -0,0 +1,444 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Request and response types of the HTTP API.
+//!
+//! These types are used by both the handlers and the OpenAPI specification served at
+//! `/api/spec`, so the specification always describes what the handlers accept and return.
+
+use std::collections::{BTreeMap, HashMap};
+
+use aide::OperationOutput;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch};
+use datatypes::data_type::DataType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::http::stream::StreamingResponse;
+
+/// Query parameters of the SQL API.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SqlQuery {
+    /// Database to execute the SQL in.
+    pub database: Option<String>,
+    /// SQL statements to execute, separated by `;`.
+    pub sql: Option<String>,
+    /// Format of the response, defaults to `json`.
+    ///
+    /// Other formats stream the rows returned by the last statement in chunks instead
+    /// of buffering them, the response falls back to `json` if the last statement
+    /// fails or doesn't return rows.
+    pub format: Option<ResponseFormat>,
+}
+
+/// Formats of the SQL API response.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    /// A JSON document that contains the outputs of all statements.
+    #[default]
+    Json,
+    /// CSV with a header line.
+    Csv,
+    /// Newline delimited JSON, one object per row.
+    Ndjson,
+    /// Arrow IPC streaming format.
+    Arrow,
+}
+
+impl ResponseFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::Json => "application/json",
+            ResponseFormat::Csv => "text/csv",
+            ResponseFormat::Ndjson => "application/x-ndjson",
+            ResponseFormat::Arrow => "application/vnd.apache.arrow.stream",
+        }
+    }
+}
+
+/// Query parameters of the script APIs.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptQuery {
+    /// Name of the script.
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
+pub struct ColumnSchema {
+    name: String,
+    data_type: String,
+}
+
+impl ColumnSchema {
+    pub fn new(name: String, data_type: String) -> ColumnSchema {
+        ColumnSchema { name, data_type }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
+pub struct Schema {
+    column_schemas: Vec<ColumnSchema>,
+}
+
+impl Schema {
+    pub fn new(columns: Vec<ColumnSchema>) -> Schema {
+        Schema {
+            column_schemas: columns,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq)]
+pub struct HttpRecordsOutput {
+    schema: Option<Schema>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl HttpRecordsOutput {
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.schema
+            .as_ref()
+            .map(|x| x.column_schemas.len())
+            .unwrap_or(0)
+    }
+
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+
+    pub fn rows(&self) -> &Vec<Vec<Value>> {
+        &self.rows
+    }
+}
+
+impl TryFrom<Vec<RecordBatch>> for HttpRecordsOutput {
+    type Error = String;
+
+    // TODO(sunng87): use schema from recordstreams when #366 fixed
+    fn try_from(
+        recordbatches: Vec<RecordBatch>,
+    ) -> std::result::Result<HttpRecordsOutput, Self::Error> {
+        if recordbatches.is_empty() {
+            Ok(HttpRecordsOutput {
+                schema: None,
+                rows: vec![],
+            })
+        } else {
+            // safety ensured by previous empty check
+            let first = &recordbatches[0];
+            let schema = Schema {
+                column_schemas: first
+                    .schema
+                    .column_schemas()
+                    .iter()
+                    .map(|cs| ColumnSchema {
+                        name: cs.name.clone(),
+                        data_type: cs.data_type.name().to_owned(),
+                    })
+                    .collect(),
+            };
+
+            let mut rows =
+                Vec::with_capacity(recordbatches.iter().map(|r| r.num_rows()).sum::<usize>());
+
+            for recordbatch in recordbatches {
+                for row in recordbatch.rows() {
+                    let value_row = row
+                        .into_iter()
+                        .map(|f| Value::try_from(f).map_err(|err| err.to_string()))
+                        .collect::<std::result::Result<Vec<Value>, _>>()?;
+
+                    rows.push(value_row);
+                }
+            }
+
+            Ok(HttpRecordsOutput {
+                schema: Some(schema),
+                rows,
+            })
+        }
+    }
+}
+
+/// Output of a statement.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonOutput {
+    AffectedRows(usize),
+    Records(HttpRecordsOutput),
+}
+
+/// Response of the SQL and script APIs.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct JsonResponse {
+    code: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<Vec<JsonOutput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    execution_time_ms: Option<u128>,
+}
+
+impl JsonResponse {
+    pub(crate) fn with_error(error: String, error_code: StatusCode) -> Self {
+        JsonResponse {
+            error: Some(error),
+            code: error_code as u32,
+            output: None,
+            execution_time_ms: None,
+        }
+    }
+
+    pub(crate) fn with_output(output: Option<Vec<JsonOutput>>) -> Self {
+        JsonResponse {
+            error: None,
+            code: StatusCode::Success as u32,
+            output,
+            execution_time_ms: None,
+        }
+    }
+
+    pub(crate) fn with_execution_time(mut self, execution_time: u128) -> Self {
+        self.execution_time_ms = Some(execution_time);
+        self
+    }
+
+    /// Create a json response from query result
+    pub(crate) async fn from_output(outputs: Vec<Result<Output>>) -> Self {
+        // TODO(sunng87): this api response structure cannot represent error
+        // well. It hides successful execution results from error response
+        let mut results = Vec::with_capacity(outputs.len());
+        for out in outputs {
+            match out {
+                Ok(Output::AffectedRows(rows)) => {
+                    results.push(JsonOutput::AffectedRows(rows));
+                }
+                Ok(Output::Stream(stream)) => {
+                    // TODO(sunng87): streaming response
+                    match util::collect(stream).await {
+                        Ok(rows) => match HttpRecordsOutput::try_from(rows) {
+                            Ok(rows) => {
+                                results.push(JsonOutput::Records(rows));
+                            }
+                            Err(err) => {
+                                return Self::with_error(err, StatusCode::Internal);
+                            }
+                        },
+
+                        Err(e) => {
+                            return Self::with_error(
+                                format!("Recordbatch error: {e}"),
+                                e.status_code(),
+                            );
+                        }
+                    }
+                }
+                Ok(Output::RecordBatches(rbs)) => match HttpRecordsOutput::try_from(rbs.take()) {
+                    Ok(rows) => {
+                        results.push(JsonOutput::Records(rows));
+                    }
+                    Err(err) => {
+                        return Self::with_error(err, StatusCode::Internal);
+                    }
+                },
+                Err(e) => {
+                    return Self::with_error(
+                        format!("Query engine output error: {e}"),
+                        e.status_code(),
+                    );
+                }
+            }
+        }
+        Self::with_output(Some(results))
+    }
+
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == (StatusCode::Success as u32)
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    pub fn output(&self) -> Option<&[JsonOutput]> {
+        self.output.as_deref()
+    }
+
+    pub fn execution_time_ms(&self) -> Option<u128> {
+        self.execution_time_ms
+    }
+}
+
+/// Response of the SQL API, rows are streamed if a format other than JSON is requested.
+pub enum SqlResponse {
+    Json(Json<JsonResponse>),
+    Stream(StreamingResponse),
+}
+
+impl IntoResponse for SqlResponse {
+    fn into_response(self) -> Response {
+        match self {
+            SqlResponse::Json(json) => json.into_response(),
+            SqlResponse::Stream(stream) => stream.into_response(),
+        }
+    }
+}
+
+impl OperationOutput for SqlResponse {
+    type Inner = JsonResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HealthQuery {}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct HealthResponse {}
+
+/// Response of the readiness check, the node is ready if all checks pass.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    /// Whether each check passes, keyed by the name of the check.
+    pub checks: BTreeMap<String, bool>,
+}
+
+/// Query parameters of the InfluxDB line protocol write API.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct InfluxdbWriteQuery {
+    /// Database to write to, defaults to `public`.
+    pub db: Option<String>,
+    /// Precision of the timestamps, one of `n`, `u`, `ms`, `s`, `m` and `h`.
+    pub precision: Option<String>,
+}
+
+/// Query parameters of the Prometheus remote write and read APIs.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DatabaseQuery {
+    /// Database to write to or read from, defaults to `public`.
+    pub db: Option<String>,
+}
+
+impl Default for DatabaseQuery {
+    fn default() -> DatabaseQuery {
+        Self {
+            db: Some(DEFAULT_SCHEMA_NAME.to_string()),
+        }
+    }
+}
+
+/// Query parameters of the OpenTSDB put API.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OpentsdbPutQuery {
+    /// Returns a summary of the written data points if present.
+    pub summary: Option<String>,
+    /// Returns the summary and the errors of the failed data points if present.
+    pub details: Option<String>,
+}
+
+/// A data point of the OpenTSDB put API.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DataPointRequest {
+    pub(crate) metric: String,
+    pub(crate) timestamp: i64,
+    pub(crate) value: f64,
+    pub(crate) tags: HashMap<String, String>,
+}
+
+/// Response of the OpenTSDB put API, it's empty unless `summary` or `details` is requested.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(untagged)]
+pub enum OpentsdbPutResponse {
+    Empty,
+    Debug(OpentsdbDebuggingResponse),
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct OpentsdbDetailError {
+    pub(crate) datapoint: DataPointRequest,
+    pub(crate) error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct OpentsdbDebuggingResponse {
+    pub(crate) success: i32,
+    pub(crate) failed: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) errors: Option<Vec<OpentsdbDetailError>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_recordbatch::RecordBatches;
+    use datatypes::prelude::*;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{StringVector, UInt32Vector};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recordbatches_conversion() {
+        let column_schemas = vec![
+            ColumnSchema::new("numbers", ConcreteDataType::uint32_datatype(), false),
+            ColumnSchema::new("strings", ConcreteDataType::string_datatype(), true),
+        ];
+        let schema = Arc::new(Schema::new(column_schemas));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_slice(vec![1, 2, 3, 4])),
+            Arc::new(StringVector::from(vec![
+                None,
+                Some("hello"),
+                Some("greptime"),
+                None,
+            ])),
+        ];
+        let recordbatch = RecordBatch::new(schema.clone(), columns).unwrap();
+        let recordbatches = RecordBatches::try_new(schema.clone(), vec![recordbatch]).unwrap();
+
+        let json_resp =
+            JsonResponse::from_output(vec![Ok(Output::RecordBatches(recordbatches))]).await;
+
+        let json_output = &json_resp.output.unwrap()[0];
+        if let JsonOutput::Records(r) = json_output {
+            assert_eq!(r.num_rows(), 4);
+            assert_eq!(r.num_cols(), 2);
+            let schema = r.schema.as_ref().unwrap();
+            assert_eq!(schema.column_schemas[0].name, "numbers");
+            assert_eq!(schema.column_schemas[0].data_type, "UInt32");
+            assert_eq!(r.rows[0][0], serde_json::Value::from(1));
+            assert_eq!(r.rows[0][1], serde_json::Value::Null);
+        } else {
+            panic!("invalid output type");
+        }
+    }
+}
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 
 use api::v1::InsertRequest as GrpcInsertRequest;
 use common_grpc::writer::{LinesWriter, Precision};
+use common_time::util::current_time_millis;
 use influxdb_line_protocol::{parse_lines, FieldValue};
 use snafu::ResultExt;
 use table::requests::InsertRequest;
@@ -34,6 +35,19 @@ pub struct InfluxdbRequest {
 
 type TableName = String;
 
+/// Returns the timestamp of a line and its precision. Lines without timestamp are
+/// written at `now` in milliseconds, the time the request is received, like InfluxDB.
+fn line_timestamp(
+    timestamp: Option<i64>,
+    precision: Option<Precision>,
+    now: i64,
+) -> (i64, Precision) {
+    match timestamp {
+        Some(timestamp) => (timestamp, precision.unwrap_or(DEFAULT_TIME_PRECISION)),
+        None => (now, Precision::Millisecond),
+    }
+}
+
 impl TryFrom<&InfluxdbRequest> for Vec<InsertRequest> {
     type Error = Error;
 
@@ -44,6 +58,7 @@ impl TryFrom<&InfluxdbRequest> for Vec<InsertRequest> {
         let line_len = lines.len();
         let mut writers: HashMap<TableName, LineWriter> = HashMap::new();
         let db = &value.db;
+        let now = current_time_millis();
 
         for line in lines {
             let table_name = line.series.measurement;
@@ -70,14 +85,10 @@ impl TryFrom<&InfluxdbRequest> for Vec<InsertRequest> {
                 }
             }
 
-            if let Some(timestamp) = line.timestamp {
-                let precision = if let Some(val) = &value.precision {
-                    *val
-                } else {
-                    DEFAULT_TIME_PRECISION
-                };
-                writer.write_ts(INFLUXDB_TIMESTAMP_COLUMN_NAME, (timestamp, precision));
-            }
+            writer.write_ts(
+                INFLUXDB_TIMESTAMP_COLUMN_NAME,
+                line_timestamp(line.timestamp, value.precision, now),
+            );
 
             writer.commit();
         }
@@ -97,6 +108,7 @@ impl TryFrom<&InfluxdbRequest> for Vec<GrpcInsertRequest> {
             .collect::<influxdb_line_protocol::Result<Vec<_>>>()
             .context(InfluxdbLineProtocolSnafu)?;
         let line_len = lines.len();
+        let now = current_time_millis();
 
         for line in lines {
             let table_name = line.series.measurement;
@@ -145,16 +157,12 @@ impl TryFrom<&InfluxdbRequest> for Vec<GrpcInsertRequest> {
                 }
             }
 
-            if let Some(timestamp) = line.timestamp {
-                let precision = if let Some(val) = &value.precision {
-                    *val
-                } else {
-                    DEFAULT_TIME_PRECISION
-                };
-                writer
-                    .write_ts(INFLUXDB_TIMESTAMP_COLUMN_NAME, (timestamp, precision))
-                    .context(InfluxdbLinesWriteSnafu)?;
-            }
+            writer
+                .write_ts(
+                    INFLUXDB_TIMESTAMP_COLUMN_NAME,
+                    line_timestamp(line.timestamp, value.precision, now),
+                )
+                .context(InfluxdbLinesWriteSnafu)?;
 
             writer.commit();
         }
@@ -244,6 +252,38 @@ monitor2,host=host4 cpu=66.3,memory=1029 1663840496400340003";
         }
     }
 
+    #[test]
+    fn test_line_without_timestamp() {
+        let influxdb_req = &InfluxdbRequest {
+            db: "public".to_string(),
+            precision: Some(Precision::Second),
+            lines: "monitor,host=host1 cpu=1.0 1663840496\nmonitor,host=host2 cpu=2.0".to_string(),
+        };
+
+        let before = current_time_millis();
+        let requests: Vec<GrpcInsertRequest> = influxdb_req.try_into().unwrap();
+        let after = current_time_millis();
+        assert_eq!(1, requests.len());
+        assert_eq!(2, requests[0].row_count);
+        let ts = requests[0]
+            .columns
+            .iter()
+            .find(|c| c.column_name == INFLUXDB_TIMESTAMP_COLUMN_NAME)
+            .unwrap();
+        let values = &ts.values.as_ref().unwrap().ts_millisecond_values;
+        assert_eq!(1663840496000, values[0]);
+        assert!(values[1] >= before && values[1] <= after);
+
+        assert_eq!(
+            (10, Precision::Nanosecond),
+            line_timestamp(Some(10), None, 20)
+        );
+        assert_eq!(
+            (20, Precision::Millisecond),
+            line_timestamp(None, Some(Precision::Second), 20)
+        );
+    }
+
     fn assert_table_1(insert_req: &InsertRequest) {
         let table_name = &insert_req.table_name;
         assert_eq!("monitor1", table_name);
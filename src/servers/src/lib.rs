@@ -23,12 +23,15 @@ pub mod http;
 pub mod influxdb;
 pub mod interceptor;
 pub mod line_writer;
+mod metric;
 pub mod mysql;
 pub mod opentsdb;
+pub mod otlp;
 pub mod postgres;
 pub mod prometheus;
 pub mod query_handler;
 pub mod server;
+pub mod slow_query;
 pub mod tls;
 
 mod shutdown;
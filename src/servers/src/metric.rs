@@ -0,0 +1,102 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! servers metrics
+
+use std::time::{Duration, Instant};
+
+use axum::extract::MatchedPath;
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+use common_telemetry::metric::{self, MetricDesc};
+use metrics::{histogram, increment_counter};
+
+pub const PROTOCOL_MYSQL: &str = "mysql";
+pub const PROTOCOL_POSTGRES: &str = "postgres";
+pub const PROTOCOL_GRPC: &str = "grpc";
+
+pub const METRIC_HTTP_REQUESTS_TOTAL: &str = "servers.http_requests_total";
+pub const METRIC_HTTP_REQUESTS_ELAPSED: &str = "servers.http_requests_elapsed";
+pub const METRIC_QUERIES_TOTAL: &str = "servers.queries_total";
+pub const METRIC_QUERY_ELAPSED: &str = "servers.query_elapsed";
+pub const METRIC_SLOW_QUERIES_TOTAL: &str = "servers.slow_queries_total";
+pub const METRIC_GRPC_REJECTED_REQUESTS_TOTAL: &str = "servers.grpc_rejected_requests_total";
+
+const METRICS: &[MetricDesc] = &[
+    MetricDesc::counter(
+        METRIC_HTTP_REQUESTS_TOTAL,
+        "Number of HTTP requests, by method, path and status code",
+    ),
+    MetricDesc::histogram(
+        METRIC_HTTP_REQUESTS_ELAPSED,
+        "Elapsed time of HTTP requests in seconds, by method and path",
+    ),
+    MetricDesc::counter(
+        METRIC_QUERIES_TOTAL,
+        "Number of queries received, by protocol",
+    ),
+    MetricDesc::histogram(
+        METRIC_QUERY_ELAPSED,
+        "Elapsed time of queries in seconds, by protocol",
+    ),
+    MetricDesc::counter(
+        METRIC_SLOW_QUERIES_TOTAL,
+        "Number of queries slower than the threshold of the slow query log, by protocol",
+    ),
+    MetricDesc::counter(
+        METRIC_GRPC_REJECTED_REQUESTS_TOTAL,
+        "Number of gRPC requests rejected by the rate limits, by the limit exceeded",
+    ),
+];
+
+/// Registers the metrics of servers, it's called when a server is created.
+pub(crate) fn register_metrics() {
+    metric::register_metrics(METRICS);
+}
+
+/// Records a query received by the server of `protocol`, which takes `elapsed` to execute.
+pub(crate) fn observe_query(protocol: &'static str, elapsed: Duration) {
+    increment_counter!(METRIC_QUERIES_TOTAL, "protocol" => protocol);
+    histogram!(METRIC_QUERY_ELAPSED, elapsed, "protocol" => protocol);
+}
+
+/// Middleware that records the HTTP requests, labelled by the matched route instead
+/// of the raw path to keep the cardinality of the labels bounded.
+pub(crate) async fn track_http_requests<B>(req: Request<B>, next: Next<B>) -> Response {
+    let start = Instant::now();
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let method = req.method().to_string();
+
+    let response = next.run(req).await;
+
+    let code = response.status().as_u16().to_string();
+    increment_counter!(
+        METRIC_HTTP_REQUESTS_TOTAL,
+        "method" => method.clone(),
+        "path" => path.clone(),
+        "code" => code
+    );
+    histogram!(
+        METRIC_HTTP_REQUESTS_ELAPSED,
+        start.elapsed(),
+        "method" => method,
+        "path" => path
+    );
+    response
+}
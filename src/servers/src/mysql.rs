@@ -15,4 +15,5 @@
 mod federated;
 pub mod handler;
 pub mod server;
+mod statement;
 pub mod writer;
@@ -26,7 +26,7 @@ use datatypes::vectors::StringVector;
 use once_cell::sync::Lazy;
 use regex::bytes::RegexSet;
 use regex::Regex;
-use session::context::QueryContextRef;
+use session::context::{QueryContextRef, TIME_ZONE_VARIABLE};
 
 // TODO(LFC): Include GreptimeDB's version and git commit tag etc.
 const MYSQL_VERSION: &str = "8.0.26";
@@ -168,7 +168,21 @@ fn show_variables(name: &str, value: &str) -> RecordBatches {
         .unwrap()
 }
 
-fn select_variable(query: &str) -> Option<Output> {
+/// Returns the value of the variable set in the session, or the default value if it's
+/// not set.
+fn variable_value(name: &str, query_ctx: &QueryContextRef) -> String {
+    let session_name = name.strip_prefix("session.").unwrap_or(name);
+    if session_name == TIME_ZONE_VARIABLE {
+        if let Some(time_zone) = query_ctx.time_zone() {
+            return time_zone.to_string();
+        }
+    }
+    query_ctx
+        .variable(session_name)
+        .unwrap_or_else(|| VAR_VALUES.get(name).unwrap_or(&"0").to_string())
+}
+
+fn select_variable(query: &str, query_ctx: &QueryContextRef) -> Option<Output> {
     let mut fields = vec![];
     let mut values = vec![];
 
@@ -194,8 +208,8 @@ fn select_variable(query: &str) -> Option<Output> {
         match var_as.len() {
             1 => {
                 // @@aa
-                let value = VAR_VALUES.get(var_as[0]).unwrap_or(&"0");
-                values.push(Arc::new(StringVector::from(vec![*value])) as _);
+                let value = variable_value(var_as[0], query_ctx);
+                values.push(Arc::new(StringVector::from(vec![value])) as _);
 
                 // field is '@@aa'
                 fields.push(ColumnSchema::new(
@@ -207,8 +221,8 @@ fn select_variable(query: &str) -> Option<Output> {
             2 => {
                 // @@bb as cc:
                 // var is 'bb'.
-                let value = VAR_VALUES.get(var_as[0]).unwrap_or(&"0");
-                values.push(Arc::new(StringVector::from(vec![*value])) as _);
+                let value = variable_value(var_as[0], query_ctx);
+                values.push(Arc::new(StringVector::from(vec![value])) as _);
 
                 // field is 'cc'.
                 fields.push(ColumnSchema::new(
@@ -227,12 +241,12 @@ fn select_variable(query: &str) -> Option<Output> {
     Some(Output::RecordBatches(batches))
 }
 
-fn check_select_variable(query: &str) -> Option<Output> {
+fn check_select_variable(query: &str, query_ctx: &QueryContextRef) -> Option<Output> {
     if vec![&SELECT_VAR_PATTERN, &MYSQL_CONN_JAVA_PATTERN]
         .iter()
         .any(|r| r.is_match(query))
     {
-        select_variable(query)
+        select_variable(query, query_ctx)
     } else {
         None
     }
@@ -279,7 +293,7 @@ fn check_others(query: &str, query_ctx: QueryContextRef) -> Option<Output> {
 // and return some faked results if there are any.
 pub(crate) fn check(query: &str, query_ctx: QueryContextRef) -> Option<Output> {
     // First to check the query is like "select @@variables".
-    let output = check_select_variable(query);
+    let output = check_select_variable(query, &query_ctx);
     if output.is_some() {
         return output;
     }
@@ -314,7 +328,7 @@ mod test {
             let output = check(query, Arc::new(QueryContext::new()));
             match output.unwrap() {
                 Output::RecordBatches(r) => {
-                    assert_eq!(&r.pretty_print().unwrap(), expected)
+                    assert_eq!(&r.pretty_print(None).unwrap(), expected)
                 }
                 _ => unreachable!(),
             }
@@ -391,4 +405,23 @@ mod test {
 +----------------------------------+";
         test(query, expected);
     }
+
+    #[test]
+    fn test_select_session_variables() {
+        let query_ctx = Arc::new(QueryContext::new());
+        query_ctx.set_time_zone(Some("Asia/Shanghai".parse().unwrap()));
+        query_ctx.set_variable("sql_mode", "ANSI".to_string());
+
+        let query = "select @@time_zone, @@session.sql_mode, @@system_time_zone";
+        let expected = "\
++---------------+--------------------+--------------------+
+| @@time_zone   | @@session.sql_mode | @@system_time_zone |
++---------------+--------------------+--------------------+
+| Asia/Shanghai | ANSI               | UTC                |
++---------------+--------------------+--------------------+";
+        match check(query, query_ctx).unwrap() {
+            Output::RecordBatches(r) => assert_eq!(expected, r.pretty_print(None).unwrap()),
+            _ => unreachable!(),
+        }
+    }
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Instant;
@@ -20,7 +21,8 @@ use async_trait::async_trait;
 use common_query::Output;
 use common_telemetry::{error, trace};
 use opensrv_mysql::{
-    AsyncMysqlShim, ErrorKind, InitWriter, ParamParser, QueryResultWriter, StatementMetaWriter,
+    AsyncMysqlShim, Column, ColumnFlags, ColumnType, ErrorKind, InitWriter, ParamParser,
+    QueryResultWriter, StatementMetaWriter,
 };
 use rand::RngCore;
 use session::context::Channel;
@@ -29,6 +31,8 @@ use tokio::io::AsyncWrite;
 
 use crate::auth::{Identity, Password, UserProviderRef};
 use crate::error::{self, Result};
+use crate::metric;
+use crate::mysql::statement::{self, PreparedStatement};
 use crate::mysql::writer::MysqlResultWriter;
 use crate::query_handler::SqlQueryHandlerRef;
 
@@ -38,6 +42,9 @@ pub struct MysqlInstanceShim {
     salt: [u8; 20],
     session: Arc<Session>,
     user_provider: Option<UserProviderRef>,
+    // Statements prepared in this connection, by statement id.
+    prepared_stmts: HashMap<u32, PreparedStatement>,
+    next_stmt_id: u32,
 }
 
 impl MysqlInstanceShim {
@@ -64,6 +71,8 @@ impl MysqlInstanceShim {
             salt: scramble,
             session: Arc::new(Session::new(client_addr, Channel::Mysql)),
             user_provider,
+            prepared_stmts: HashMap::new(),
+            next_stmt_id: 1,
         }
     }
 
@@ -83,10 +92,12 @@ impl MysqlInstanceShim {
                     .await
             };
 
+        let elapsed = start.elapsed();
+        metric::observe_query(metric::PROTOCOL_MYSQL, elapsed);
         trace!(
             "Finished executing query: '{}', total time costs in microseconds: {}",
             query,
-            start.elapsed().as_micros()
+            elapsed.as_micros()
         );
         output
     }
@@ -139,34 +150,93 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         true
     }
 
-    async fn on_prepare<'a>(&'a mut self, _: &'a str, w: StatementMetaWriter<'a, W>) -> Result<()> {
-        w.error(
-            ErrorKind::ER_UNKNOWN_ERROR,
-            b"prepare statement is not supported yet",
-        )
-        .await?;
+    async fn on_prepare<'a>(
+        &'a mut self,
+        query: &'a str,
+        w: StatementMetaWriter<'a, W>,
+    ) -> Result<()> {
+        let stmt = match PreparedStatement::new(query) {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                w.error(ErrorKind::ER_UNKNOWN_ERROR, e.to_string().as_bytes())
+                    .await?;
+                return Ok(());
+            }
+        };
+        let params = (0..stmt.num_params())
+            .map(|_| Column {
+                table: "".to_string(),
+                column: "?".to_string(),
+                coltype: ColumnType::MYSQL_TYPE_VAR_STRING,
+                colflags: ColumnFlags::empty(),
+            })
+            .collect::<Vec<_>>();
+
+        let stmt_id = self.next_stmt_id;
+        self.next_stmt_id = self.next_stmt_id.wrapping_add(1);
+        self.prepared_stmts.insert(stmt_id, stmt);
+        trace!("Prepared statement {}: '{}'", stmt_id, query);
+
+        // Columns of the result are unknown until the statement is executed, they are
+        // sent along with the result set.
+        w.reply(stmt_id, &params, &[]).await?;
         Ok(())
     }
 
     async fn on_execute<'a>(
         &'a mut self,
-        _: u32,
-        _: ParamParser<'a>,
+        stmt_id: u32,
+        params: ParamParser<'a>,
         w: QueryResultWriter<'a, W>,
     ) -> Result<()> {
-        w.error(
-            ErrorKind::ER_UNKNOWN_ERROR,
-            b"prepare statement is not supported yet",
-        )
-        .await?;
-        Ok(())
+        let Some(stmt) = self.prepared_stmts.get(&stmt_id) else {
+            w.error(
+                ErrorKind::ER_UNKNOWN_STMT_HANDLER,
+                format!("unknown prepared statement {stmt_id}").as_bytes(),
+            )
+            .await?;
+            return Ok(());
+        };
+
+        let mut writer = MysqlResultWriter::new_binary(w, self.session.context());
+        if stmt.num_params() == 0 {
+            let query = stmt.query().to_string();
+            for output in self.do_query(&query).await {
+                writer.write(&query, output).await?;
+            }
+            return Ok(());
+        }
+
+        let bound = params
+            .into_iter()
+            .map(|param| statement::to_sql_value(param.value.into_inner(), param.coltype))
+            .collect::<Result<Vec<_>>>()
+            .and_then(|values| stmt.bind(values));
+        let query = stmt.query().to_string();
+        let bound = match bound {
+            Ok(bound) => bound,
+            Err(e) => return writer.write(&query, Err(e)).await,
+        };
+
+        trace!(
+            "Start executing prepared statement {}: '{}'",
+            stmt_id,
+            query
+        );
+        let start = Instant::now();
+        let output = self
+            .query_handler
+            .do_statement_query(bound, self.session.context())
+            .await;
+        metric::observe_query(metric::PROTOCOL_MYSQL, start.elapsed());
+        writer.write(&query, output).await
     }
 
-    async fn on_close<'a>(&'a mut self, _stmt_id: u32)
+    async fn on_close<'a>(&'a mut self, stmt_id: u32)
     where
         W: 'async_trait,
     {
-        // do nothing because we haven't implemented prepare statement
+        let _ = self.prepared_stmts.remove(&stmt_id);
     }
 
     async fn on_query<'a>(
@@ -175,7 +245,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         writer: QueryResultWriter<'a, W>,
     ) -> Result<()> {
         let outputs = self.do_query(query).await;
-        let mut writer = MysqlResultWriter::new(writer);
+        let mut writer = MysqlResultWriter::new(writer, self.session.context());
         for output in outputs {
             writer.write(query, output).await?;
         }
@@ -26,14 +26,14 @@ use opensrv_mysql::{
 use tokio;
 use tokio::io::BufWriter;
 use tokio::net::TcpStream;
-use tokio_rustls::rustls::ServerConfig;
 
 use crate::auth::UserProviderRef;
 use crate::error::{Error, Result};
+use crate::metric;
 use crate::mysql::handler::MysqlInstanceShim;
 use crate::query_handler::SqlQueryHandlerRef;
 use crate::server::{AbortableStream, BaseTcpServer, Server};
-use crate::tls::TlsOption;
+use crate::tls::{ReloadableTlsServerConfig, ReloadableTlsServerConfigRef, TlsOption};
 
 // Default size of ResultSet write buffer: 100KB
 const DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE: usize = 100 * 1024;
@@ -52,6 +52,7 @@ impl MysqlServer {
         tls: TlsOption,
         user_provider: Option<UserProviderRef>,
     ) -> Box<dyn Server> {
+        metric::register_metrics();
         Box::new(MysqlServer {
             base_server: BaseTcpServer::create_server("MySQL", io_runtime),
             query_handler,
@@ -64,7 +65,7 @@ impl MysqlServer {
         &self,
         io_runtime: Arc<Runtime>,
         stream: AbortableStream,
-        tls_conf: Option<Arc<ServerConfig>>,
+        tls_conf: Option<ReloadableTlsServerConfigRef>,
     ) -> impl Future<Output = ()> {
         let query_handler = self.query_handler.clone();
         let user_provider = self.user_provider.clone();
@@ -103,7 +104,7 @@ impl MysqlServer {
         stream: TcpStream,
         io_runtime: Arc<Runtime>,
         query_handler: SqlQueryHandlerRef,
-        tls_conf: Option<Arc<ServerConfig>>,
+        tls_conf: Option<ReloadableTlsServerConfigRef>,
         force_tls: bool,
         user_provider: Option<UserProviderRef>,
     ) -> Result<()> {
@@ -123,7 +124,7 @@ impl MysqlServer {
     async fn do_handle(
         stream: TcpStream,
         query_handler: SqlQueryHandlerRef,
-        tls_conf: Option<Arc<ServerConfig>>,
+        tls_conf: Option<ReloadableTlsServerConfigRef>,
         force_tls: bool,
         user_provider: Option<UserProviderRef>,
     ) -> Result<()> {
@@ -132,8 +133,10 @@ impl MysqlServer {
         let mut w = BufWriter::with_capacity(DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE, w);
         let ops = IntermediaryOptions::default();
 
+        let server_config = tls_conf.as_ref().map(|c| c.get_server_config());
         let (client_tls, init_params) =
-            AsyncMysqlIntermediary::init_before_ssl(&mut shim, &mut r, &mut w, &tls_conf).await?;
+            AsyncMysqlIntermediary::init_before_ssl(&mut shim, &mut r, &mut w, &server_config)
+                .await?;
 
         if force_tls && !client_tls {
             return Err(Error::TlsRequired {
@@ -141,9 +144,9 @@ impl MysqlServer {
             });
         }
 
-        match tls_conf {
-            Some(tls_conf) if client_tls => {
-                secure_run_with_options(shim, w, ops, tls_conf, init_params).await
+        match server_config {
+            Some(server_config) if client_tls => {
+                secure_run_with_options(shim, w, ops, server_config, init_params).await
             }
             _ => plain_run_with_options(shim, w, ops, init_params).await,
         }
@@ -161,7 +164,7 @@ impl Server for MysqlServer {
 
         let io_runtime = self.base_server.io_runtime();
 
-        let tls_conf = self.tls.setup()?.map(Arc::new);
+        let tls_conf = ReloadableTlsServerConfig::try_new(self.tls.clone(), &[])?;
 
         let join_handle = tokio::spawn(self.accept(io_runtime, stream, tls_conf));
         self.base_server.start_with(join_handle).await?;
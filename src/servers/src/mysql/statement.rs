@@ -0,0 +1,269 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statements prepared by the MySQL binary protocol (`COM_STMT_PREPARE`).
+
+use opensrv_mysql::{ColumnType, ValueInner};
+use snafu::ensure;
+use sql::ast::{Expr, Value};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::placeholder;
+use sql::statements::statement::Statement;
+
+use crate::error::{self, Result};
+
+/// A statement prepared by `COM_STMT_PREPARE`.
+///
+/// The query is parsed once when it's prepared, executing the statement replaces the
+/// `?` placeholders in the parsed statement by the values of the parameters.
+#[derive(Debug, Clone)]
+pub(crate) struct PreparedStatement {
+    query: String,
+    /// The parsed statement, `None` if the query has no placeholders, then it's executed
+    /// like a plain query.
+    stmt: Option<Statement>,
+    num_params: usize,
+}
+
+impl PreparedStatement {
+    pub(crate) fn new(query: &str) -> Result<Self> {
+        let num_params = placeholder::count_placeholders(query, &GenericDialect {})
+            .map_err(|e| invalid_query(e.to_string()))?;
+        if num_params == 0 {
+            return Ok(Self {
+                query: query.to_string(),
+                stmt: None,
+                num_params,
+            });
+        }
+
+        let mut stmts = ParserContext::create_with_dialect(query, &GenericDialect {})
+            .map_err(|e| invalid_query(e.to_string()))?;
+        ensure!(
+            stmts.len() == 1,
+            error::InvalidQuerySnafu {
+                reason: format!("expect 1 statement to prepare, but got {}", stmts.len()),
+            }
+        );
+        let mut stmt = stmts.remove(0);
+        ensure!(
+            placeholder::placeholders_mut(&mut stmt).len() == num_params,
+            error::NotSupportedSnafu {
+                feat: format!("placeholders in statement '{query}'"),
+            }
+        );
+
+        Ok(Self {
+            query: query.to_string(),
+            stmt: Some(stmt),
+            num_params,
+        })
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the number of placeholders in the statement.
+    pub(crate) fn num_params(&self) -> usize {
+        self.num_params
+    }
+
+    /// Returns the statement with each placeholder replaced by the value at the
+    /// same position, statements without placeholders should be executed by the
+    /// [query](Self::query) instead.
+    pub(crate) fn bind(&self, values: Vec<Value>) -> Result<Statement> {
+        ensure!(
+            values.len() == self.num_params,
+            error::InvalidQuerySnafu {
+                reason: format!(
+                    "expect {} parameters, but got {}",
+                    self.num_params,
+                    values.len()
+                ),
+            }
+        );
+
+        let Some(stmt) = &self.stmt else {
+            return error::NotSupportedSnafu {
+                feat: format!("binding statement '{}' without placeholders", self.query),
+            }
+            .fail();
+        };
+        let mut stmt = stmt.clone();
+        for (placeholder, value) in placeholder::placeholders_mut(&mut stmt)
+            .into_iter()
+            .zip(values)
+        {
+            *placeholder = Expr::Value(value);
+        }
+        Ok(stmt)
+    }
+}
+
+fn invalid_query(reason: String) -> error::Error {
+    error::InvalidQuerySnafu { reason }.build()
+}
+
+/// Converts a parameter of `COM_STMT_EXECUTE` into a SQL value.
+pub(crate) fn to_sql_value(value: ValueInner, coltype: ColumnType) -> Result<Value> {
+    let value = match value {
+        ValueInner::NULL => Value::Null,
+        ValueInner::Int(v) => Value::Number(v.to_string(), false),
+        ValueInner::UInt(v) => Value::Number(v.to_string(), false),
+        ValueInner::Double(v) => {
+            ensure!(
+                v.is_finite(),
+                error::InvalidQuerySnafu {
+                    reason: format!("unsupported parameter value {v}"),
+                }
+            );
+            // Keeps the number a float even if it has no fractional part.
+            Value::Number(format!("{v:?}"), false)
+        }
+        ValueInner::Bytes(bytes) => {
+            let s = std::str::from_utf8(bytes).map_err(|_| {
+                invalid_query(format!("parameter of type {coltype:?} is not valid UTF-8"))
+            })?;
+            Value::SingleQuotedString(s.to_string())
+        }
+        ValueInner::Date(bytes) | ValueInner::Datetime(bytes) => {
+            Value::SingleQuotedString(decode_datetime(bytes)?)
+        }
+        ValueInner::Time(_) => {
+            return error::NotSupportedSnafu {
+                feat: format!("parameter of type {coltype:?}"),
+            }
+            .fail()
+        }
+    };
+    Ok(value)
+}
+
+/// Decodes a `DATE` or `DATETIME` value of the binary protocol, the value contains
+/// the year, month and day, optionally followed by the hour, minute, second and
+/// microsecond.
+fn decode_datetime(bytes: &[u8]) -> Result<String> {
+    let (year, month, day) = match bytes.len() {
+        0 => (0, 0, 0),
+        4 | 7 | 11 => (u16::from_le_bytes([bytes[0], bytes[1]]), bytes[2], bytes[3]),
+        len => {
+            return error::InvalidQuerySnafu {
+                reason: format!("invalid length {len} of datetime parameter"),
+            }
+            .fail()
+        }
+    };
+    let (hour, minute, second) = if bytes.len() >= 7 {
+        (bytes[4], bytes[5], bytes[6])
+    } else {
+        (0, 0, 0)
+    };
+    let mut datetime = format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}");
+    if bytes.len() == 11 {
+        let micros = u32::from_le_bytes([bytes[7], bytes[8], bytes[9], bytes[10]]);
+        datetime.push_str(&format!(".{micros:06}"));
+    }
+    Ok(datetime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind() {
+        let stmt = PreparedStatement::new("SELECT * FROM t WHERE a = ? AND b = ?").unwrap();
+        assert_eq!(2, stmt.num_params());
+        let Statement::Query(query) = stmt
+            .bind(vec![
+                Value::Number("1".to_string(), false),
+                Value::SingleQuotedString("it's".to_string()),
+            ])
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT * FROM t WHERE a = 1 AND b = 'it''s'",
+            query.inner.to_string()
+        );
+        assert!(stmt.bind(vec![Value::Null]).is_err());
+
+        let stmt =
+            PreparedStatement::new("SELECT '?', \"?\" -- ?\n FROM t /* ? */ WHERE a = ?").unwrap();
+        assert_eq!(1, stmt.num_params());
+        let Statement::Query(query) = stmt.bind(vec![Value::Null]).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT '?', \"?\" FROM t WHERE a = NULL",
+            query.inner.to_string()
+        );
+
+        let stmt = PreparedStatement::new("SELECT 1").unwrap();
+        assert_eq!(0, stmt.num_params());
+        assert_eq!("SELECT 1", stmt.query());
+
+        assert!(PreparedStatement::new("SELECT ?; SELECT ?").is_err());
+        assert!(PreparedStatement::new("SELECT * FROM t(?)").is_err());
+    }
+
+    #[test]
+    fn test_to_sql_value() {
+        let string_type = ColumnType::MYSQL_TYPE_VAR_STRING;
+        assert_eq!(
+            Value::Null,
+            to_sql_value(ValueInner::NULL, ColumnType::MYSQL_TYPE_NULL).unwrap()
+        );
+        assert_eq!(
+            Value::Number("-1".to_string(), false),
+            to_sql_value(ValueInner::Int(-1), ColumnType::MYSQL_TYPE_LONGLONG).unwrap()
+        );
+        assert_eq!(
+            Value::Number("18446744073709551615".to_string(), false),
+            to_sql_value(ValueInner::UInt(u64::MAX), ColumnType::MYSQL_TYPE_LONGLONG).unwrap()
+        );
+        assert_eq!(
+            Value::Number("2.0".to_string(), false),
+            to_sql_value(ValueInner::Double(2.0), ColumnType::MYSQL_TYPE_DOUBLE).unwrap()
+        );
+        assert!(to_sql_value(ValueInner::Double(f64::NAN), ColumnType::MYSQL_TYPE_DOUBLE).is_err());
+        assert_eq!(
+            Value::SingleQuotedString("it's".to_string()),
+            to_sql_value(ValueInner::Bytes(b"it's"), string_type).unwrap()
+        );
+        assert!(to_sql_value(ValueInner::Bytes(&[0xff]), string_type).is_err());
+
+        let datetime = [0xe6, 0x07, 10, 1, 12, 30, 5, 0x40, 0xe2, 0x01, 0x00];
+        assert_eq!(
+            Value::SingleQuotedString("2022-10-01 12:30:05.123456".to_string()),
+            to_sql_value(
+                ValueInner::Datetime(&datetime),
+                ColumnType::MYSQL_TYPE_DATETIME
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Value::SingleQuotedString("2022-10-01 00:00:00".to_string()),
+            to_sql_value(
+                ValueInner::Date(&datetime[..4]),
+                ColumnType::MYSQL_TYPE_DATE
+            )
+            .unwrap()
+        );
+        assert!(to_sql_value(ValueInner::Time(&[]), ColumnType::MYSQL_TYPE_TIME).is_err());
+    }
+}
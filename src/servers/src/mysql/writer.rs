@@ -14,16 +14,21 @@
 
 use std::ops::Deref;
 
+use chrono::NaiveDateTime;
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
 use common_telemetry::error;
 use common_time::datetime::DateTime;
 use common_time::timestamp::TimeUnit;
+use common_time::TimeZone;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{ColumnSchema, SchemaRef};
+use datatypes::types::UuidType;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
 };
+use session::context::QueryContextRef;
 use snafu::prelude::*;
 use tokio::io::AsyncWrite;
 
@@ -38,17 +43,43 @@ pub struct MysqlResultWriter<'a, W: AsyncWrite + Unpin> {
     // `QueryResultWriter` will be consumed when the write completed (see
     // QueryResultWriter::completed), thus we use an option to wrap it.
     inner: Option<QueryResultWriter<'a, W>>,
+    // Whether the result is written in the binary protocol, i.e. the result of
+    // executing a prepared statement.
+    binary: bool,
+    // Timestamps are written in the time zone of the session.
+    query_ctx: QueryContextRef,
 }
 
 impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
-    pub fn new(inner: QueryResultWriter<'a, W>) -> MysqlResultWriter<'a, W> {
-        MysqlResultWriter::<'a, W> { inner: Some(inner) }
+    pub fn new(
+        inner: QueryResultWriter<'a, W>,
+        query_ctx: QueryContextRef,
+    ) -> MysqlResultWriter<'a, W> {
+        MysqlResultWriter::<'a, W> {
+            inner: Some(inner),
+            binary: false,
+            query_ctx,
+        }
+    }
+
+    /// Creates a writer that writes the result of a prepared statement.
+    pub fn new_binary(
+        inner: QueryResultWriter<'a, W>,
+        query_ctx: QueryContextRef,
+    ) -> MysqlResultWriter<'a, W> {
+        MysqlResultWriter::<'a, W> {
+            inner: Some(inner),
+            binary: true,
+            query_ctx,
+        }
     }
 
     pub async fn write(&mut self, query: &str, output: Result<Output>) -> Result<()> {
         let writer = self.inner.take().context(error::InternalSnafu {
             err_msg: "inner MySQL writer is consumed",
         })?;
+        // The time zone may be changed by the previous statements of the query.
+        let time_zone = self.query_ctx.time_zone();
         match output {
             Ok(output) => match output {
                 Output::Stream(stream) => {
@@ -60,14 +91,16 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                         recordbatches,
                         schema,
                     };
-                    Self::write_query_result(query, query_result, writer).await?
+                    Self::write_query_result(query, query_result, writer, self.binary, time_zone)
+                        .await?
                 }
                 Output::RecordBatches(recordbatches) => {
                     let query_result = QueryResult {
                         schema: recordbatches.schema(),
                         recordbatches: recordbatches.take(),
                     };
-                    Self::write_query_result(query, query_result, writer).await?
+                    Self::write_query_result(query, query_result, writer, self.binary, time_zone)
+                        .await?
                 }
                 Output::AffectedRows(rows) => Self::write_affected_rows(writer, rows).await?,
             },
@@ -89,12 +122,15 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         query: &str,
         query_result: QueryResult,
         writer: QueryResultWriter<'a, W>,
+        binary: bool,
+        time_zone: Option<TimeZone>,
     ) -> Result<()> {
         match create_mysql_column_def(&query_result.schema) {
             Ok(column_def) => {
                 let mut row_writer = writer.start(&column_def).await?;
                 for recordbatch in &query_result.recordbatches {
-                    Self::write_recordbatch(&mut row_writer, recordbatch).await?;
+                    Self::write_recordbatch(&mut row_writer, recordbatch, binary, time_zone)
+                        .await?;
                 }
                 row_writer.finish().await?;
                 Ok(())
@@ -106,9 +142,26 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     async fn write_recordbatch(
         row_writer: &mut RowWriter<'_, W>,
         recordbatch: &RecordBatch,
+        binary: bool,
+        time_zone: Option<TimeZone>,
     ) -> Result<()> {
+        // Timestamps are written in the time zone of the session, or the time zone of the
+        // column if the session doesn't set one.
+        let time_zones = recordbatch
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column| time_zone.or_else(|| column.time_zone()))
+            .collect::<Vec<_>>();
+        let uuid_columns = recordbatch
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.data_type.is_uuid())
+            .collect::<Vec<_>>();
         for row in recordbatch.rows() {
-            for value in row.into_iter() {
+            for ((value, time_zone), is_uuid) in row.into_iter().zip(&time_zones).zip(&uuid_columns)
+            {
                 match value {
                     Value::Null => row_writer.write_col(None::<u8>)?,
                     Value::Boolean(v) => row_writer.write_col(v as i8)?,
@@ -123,11 +176,31 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     Value::Float32(v) => row_writer.write_col(v.0)?,
                     Value::Float64(v) => row_writer.write_col(v.0)?,
                     Value::String(v) => row_writer.write_col(v.as_utf8())?,
+                    // UUIDs are written as their text.
+                    Value::Binary(v) if *is_uuid => row_writer
+                        .write_col(UuidType::format(&v).context(error::VectorConversionSnafu)?)?,
                     Value::Binary(v) => row_writer.write_col(v.deref())?,
                     Value::Date(v) => row_writer.write_col(v.val())?,
                     Value::DateTime(v) => row_writer.write_col(v.val())?,
-                    Value::Timestamp(v) => row_writer
-                        .write_col(DateTime::new(v.convert_to(TimeUnit::Second)).to_string())?,
+                    Value::Timestamp(v) => {
+                        let seconds = v.convert_to(TimeUnit::Second);
+                        let datetime =
+                            NaiveDateTime::from_timestamp_opt(seconds, 0).map(|datetime| {
+                                match time_zone {
+                                    Some(time_zone) => time_zone.to_local(&datetime),
+                                    None => datetime,
+                                }
+                            });
+                        // The binary protocol encodes datetime as its components
+                        // instead of a string.
+                        match datetime {
+                            Some(datetime) if binary => row_writer.write_col(datetime)?,
+                            Some(datetime) => {
+                                row_writer.write_col(datetime.format("%F %T").to_string())?
+                            }
+                            None => row_writer.write_col(DateTime::new(seconds).to_string())?,
+                        }
+                    }
                     Value::List(_) => {
                         return Err(Error::Internal {
                             err_msg: format!(
@@ -150,7 +223,10 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     ) -> Result<()> {
         error!(error; "Failed to execute query '{}'", query);
 
-        let kind = ErrorKind::ER_INTERNAL_ERROR;
+        let kind = match error.status_code() {
+            StatusCode::AccessDenied => ErrorKind::ER_DBACCESS_DENIED_ERROR,
+            _ => ErrorKind::ER_INTERNAL_ERROR,
+        };
         w.error(kind, error.to_string().as_bytes()).await?;
         Ok(())
     }
@@ -169,12 +245,13 @@ fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
         ConcreteDataType::Int64(_) | ConcreteDataType::UInt64(_) => {
             Ok(ColumnType::MYSQL_TYPE_LONGLONG)
         }
-        ConcreteDataType::Float32(_) | ConcreteDataType::Float64(_) => {
-            Ok(ColumnType::MYSQL_TYPE_FLOAT)
-        }
-        ConcreteDataType::Binary(_) | ConcreteDataType::String(_) => {
+        ConcreteDataType::Float32(_) => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+        ConcreteDataType::Float64(_) => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
+        ConcreteDataType::Binary(_) | ConcreteDataType::String(_) | ConcreteDataType::Uuid(_) => {
             Ok(ColumnType::MYSQL_TYPE_VARCHAR)
         }
+        // JSON documents are written as their text.
+        ConcreteDataType::Json(_) => Ok(ColumnType::MYSQL_TYPE_JSON),
         ConcreteDataType::Timestamp(_) => Ok(ColumnType::MYSQL_TYPE_DATETIME),
         _ => error::InternalSnafu {
             err_msg: format!(
@@ -184,14 +261,20 @@ fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
         }
         .fail(),
     };
+    // The binary protocol encodes unsigned integers only if the column is unsigned.
+    let colflags = if column_schema.data_type.is_unsigned() {
+        ColumnFlags::UNSIGNED_FLAG
+    } else {
+        ColumnFlags::empty()
+    };
     column_type.map(|column_type| Column {
         column: column_schema.name.clone(),
         coltype: column_type,
 
-        // TODO(LFC): Currently "table" and "colflags" are not relevant in MySQL server
-        //   implementation, will revisit them again in the future.
+        // TODO(LFC): Currently "table" is not relevant in MySQL server implementation,
+        //   will revisit it again in the future.
         table: "".to_string(),
-        colflags: ColumnFlags::empty(),
+        colflags,
     })
 }
 
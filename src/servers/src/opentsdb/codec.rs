@@ -12,10 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use api::v1::column::SemanticType;
 use api::v1::{column, Column, ColumnDataType, InsertRequest as GrpcInsertRequest};
 use common_catalog::consts::DEFAULT_SCHEMA_NAME;
-use common_grpc::writer::Precision;
+use common_grpc::writer::{LinesWriter, Precision};
+use snafu::ResultExt;
 use table::requests::InsertRequest;
 
 use crate::error::{self, Result};
@@ -196,6 +199,46 @@ impl DataPoint {
         }
     }
 
+    /// Converts `data_points` into one insert request per metric, so data points of
+    /// the same metric are inserted in batch.
+    pub fn to_grpc_inserts(data_points: &[DataPoint]) -> Result<Vec<GrpcInsertRequest>> {
+        let mut writers: HashMap<&str, LinesWriter> = HashMap::new();
+        for data_point in data_points {
+            let writer = writers
+                .entry(&data_point.metric)
+                .or_insert_with(|| LinesWriter::with_lines(data_points.len()));
+            writer
+                .write_ts(
+                    OPENTSDB_TIMESTAMP_COLUMN_NAME,
+                    (data_point.ts_millis, Precision::Millisecond),
+                )
+                .context(error::OpentsdbDataPointsWriteSnafu)?;
+            writer
+                .write_f64(OPENTSDB_VALUE_COLUMN_NAME, data_point.value)
+                .context(error::OpentsdbDataPointsWriteSnafu)?;
+            for (tagk, tagv) in data_point.tags.iter() {
+                writer
+                    .write_tag(tagk, tagv)
+                    .context(error::OpentsdbDataPointsWriteSnafu)?;
+            }
+            writer.commit();
+        }
+
+        Ok(writers
+            .into_iter()
+            .map(|(metric, writer)| {
+                let (columns, row_count) = writer.finish();
+                GrpcInsertRequest {
+                    schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+                    table_name: metric.to_string(),
+                    region_number: 0,
+                    columns,
+                    row_count,
+                }
+            })
+            .collect())
+    }
+
     pub fn timestamp_to_millis(t: i64) -> i64 {
         // 9999999999999 (13 digits) is of date "Sat Nov 20 2286 17:46:39 UTC",
         // 999999999999 (12 digits) is "Sun Sep 09 2001 01:46:39 UTC",
@@ -356,4 +399,67 @@ mod test {
             vec!["tagv2"]
         );
     }
+
+    #[test]
+    fn test_to_grpc_inserts() {
+        let data_points = vec![
+            DataPoint::new(
+                "my_metric_1".to_string(),
+                1000,
+                1.0,
+                vec![("tagk1".to_string(), "tagv1".to_string())],
+            ),
+            DataPoint::new("my_metric_2".to_string(), 1000, 2.0, vec![]),
+            DataPoint::new(
+                "my_metric_1".to_string(),
+                2000,
+                3.0,
+                vec![("tagk2".to_string(), "tagv2".to_string())],
+            ),
+        ];
+
+        let mut inserts = DataPoint::to_grpc_inserts(&data_points).unwrap();
+        inserts.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+        assert_eq!(2, inserts.len());
+
+        let insert = &inserts[0];
+        assert_eq!("my_metric_1", insert.table_name);
+        assert_eq!(2, insert.row_count);
+        let column_names: Vec<_> = insert.columns.iter().map(|c| &c.column_name).collect();
+        assert_eq!(
+            vec![
+                OPENTSDB_TIMESTAMP_COLUMN_NAME,
+                OPENTSDB_VALUE_COLUMN_NAME,
+                "tagk1",
+                "tagk2"
+            ],
+            column_names
+        );
+        assert_eq!(
+            vec![1000, 2000],
+            insert.columns[0]
+                .values
+                .as_ref()
+                .unwrap()
+                .ts_millisecond_values
+        );
+        assert_eq!(
+            vec![1.0, 3.0],
+            insert.columns[1].values.as_ref().unwrap().f64_values
+        );
+        // Tags missing in some data points are null.
+        assert_eq!(
+            vec!["tagv1"],
+            insert.columns[2].values.as_ref().unwrap().string_values
+        );
+        assert_eq!(
+            vec!["tagv2"],
+            insert.columns[3].values.as_ref().unwrap().string_values
+        );
+
+        let insert = &inserts[1];
+        assert_eq!("my_metric_2", insert.table_name);
+        assert_eq!(1, insert.row_count);
+        assert_eq!(2, insert.columns.len());
+    }
 }
@@ -38,7 +38,8 @@ impl<S: AsyncWrite + AsyncRead + Unpin> Connection<S> {
 
     /// Read one line from the underlying stream.
     ///
-    /// The function waits until it has retrieved enough data to parse a line (terminated by \r\n).
+    /// The function waits until it has retrieved enough data to parse a line (terminated by \n or
+    /// \r\n).
     /// Any data remaining in the read buffer after the line has been parsed is kept there for the
     /// next call to `read_line`.
     ///
@@ -69,6 +70,12 @@ impl<S: AsyncWrite + AsyncRead + Unpin> Connection<S> {
         }
     }
 
+    /// Returns a line already in the read buffer without reading from the underlying stream, or
+    /// `None` if there is no complete line buffered.
+    pub(crate) fn read_buffered_line(&mut self) -> Result<Option<Line>> {
+        self.parse_line()
+    }
+
     /// Tries to parse a line from the buffer.
     ///
     /// If the buffer contains enough data, the line is returned and the buffered data is removed.
@@ -80,10 +87,15 @@ impl<S: AsyncWrite + AsyncRead + Unpin> Connection<S> {
         }
 
         let buf = &self.buffer[..];
-        if let Some(pos) = buf.windows(2).position(|w| w == [b'\r', b'\n']) {
-            let line = buf[0..pos].to_vec();
+        if let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+            let end = if pos > 0 && buf[pos - 1] == b'\r' {
+                pos - 1
+            } else {
+                pos
+            };
+            let line = buf[0..end].to_vec();
 
-            self.buffer.advance(pos + 2);
+            self.buffer.advance(pos + 1);
 
             Ok(Some(
                 String::from_utf8(line).context(error::InvalidOpentsdbLineSnafu)?,
@@ -180,6 +192,17 @@ mod tests {
             " another line's remaining data"
         );
 
+        // lines terminated by \n are accepted too
+        {
+            let buffer = &mut conn.buffer;
+            buffer.writer().write_all(b"\nthe next line\n").unwrap();
+            let line = conn.parse_line().unwrap();
+            assert_eq!(line, Some(" another line's remaining data".to_string()));
+            let line = conn.read_buffered_line().unwrap();
+            assert_eq!(line, Some("the next line".to_string()));
+            assert_matches!(conn.read_buffered_line(), Ok(None));
+        }
+
         // expected failed on not valid utf-8 line
         let buffer = &mut conn.buffer;
         buffer.writer().write_all(b"Hello Wor\xffld.\r\n").unwrap();
@@ -22,6 +22,9 @@ use crate::opentsdb::connection::Connection;
 use crate::query_handler::OpentsdbProtocolHandlerRef;
 use crate::shutdown::Shutdown;
 
+/// Max number of lines written in one batch.
+const MAX_BATCH_SIZE: usize = 1024;
+
 /// Per-connection handler. Reads requests from `connection` and applies the OpenTSDB metric to
 /// [OpentsdbLineProtocolHandler].
 pub(crate) struct Handler<S: AsyncWrite + AsyncRead + Unpin> {
@@ -77,26 +80,44 @@ impl<S: AsyncWrite + AsyncRead + Unpin> Handler<S> {
                 None => return Ok(()),
             };
 
-            // Close connection upon receiving "quit" line. With actual OpenTSDB, telnet just won't
-            // quit, the connection to OpenTSDB server can be closed only via terminating telnet
-            // session manually, for example, close the terminal window. That is a little annoying,
-            // so I added "quit" command to the line protocol, to make telnet client able to quit
-            // gracefully.
-            if line.trim().eq_ignore_ascii_case("quit") {
-                return Ok(());
+            // Lines already received are written in batch with this line.
+            let mut lines = vec![line];
+            while lines.len() < MAX_BATCH_SIZE {
+                match self.connection.read_buffered_line()? {
+                    Some(line) => lines.push(line),
+                    None => break,
+                }
             }
 
-            match DataPoint::try_create(&line) {
-                Ok(data_point) => {
-                    let result = self.query_handler.exec(&data_point).await;
-                    if let Err(e) = result {
+            let mut data_points = Vec::with_capacity(lines.len());
+            let mut quit = false;
+            for line in lines {
+                // Close connection upon receiving "quit" line. With actual OpenTSDB, telnet just
+                // won't quit, the connection to OpenTSDB server can be closed only via terminating
+                // telnet session manually, for example, close the terminal window. That is a little
+                // annoying, so I added "quit" command to the line protocol, to make telnet client
+                // able to quit gracefully.
+                if line.trim().eq_ignore_ascii_case("quit") {
+                    quit = true;
+                    break;
+                }
+
+                match DataPoint::try_create(&line) {
+                    Ok(data_point) => data_points.push(data_point),
+                    Err(e) => {
                         self.connection.write_line(e.to_string()).await?;
                     }
                 }
-                Err(e) => {
+            }
+
+            if !data_points.is_empty() {
+                if let Err(e) = self.query_handler.exec_batch(&data_points).await {
                     self.connection.write_line(e.to_string()).await?;
                 }
             }
+            if quit {
+                return Ok(());
+            }
         }
         Ok(())
     }
@@ -170,6 +191,23 @@ mod tests {
             resp,
             Some("Invalid query: unknown command get.".to_string())
         );
+
+        // Lines sent together are written in batch, invalid lines are reported one by one.
+        client
+            .write_line(
+                "put my_metric_3 1000 1.0 host=web01\nget\nput my_metric_4 1000 1.0\r\nquit"
+                    .to_string(),
+            )
+            .await
+            .unwrap();
+        let resp = client.read_line().await.unwrap();
+        assert_eq!(
+            resp,
+            Some("Invalid query: unknown command get.".to_string())
+        );
+        assert_eq!(rx.recv().await.unwrap(), "my_metric_3");
+        assert_eq!(rx.recv().await.unwrap(), "my_metric_4");
+        assert_eq!(client.read_line().await.unwrap(), None);
     }
 
     async fn start_server(
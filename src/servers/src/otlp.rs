@@ -0,0 +1,523 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenTelemetry protocol (OTLP) metrics supportings
+use std::collections::{BTreeMap, HashMap};
+
+use api::v1::InsertRequest as GrpcInsertRequest;
+use common_grpc::writer::{LinesWriter, Precision};
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+use opentelemetry_proto::tonic::metrics::v1::{
+    metric, number_data_point, ExponentialHistogramDataPoint, HistogramDataPoint, NumberDataPoint,
+    SummaryDataPoint,
+};
+use snafu::ResultExt;
+
+use crate::error::{OtlpMetricsWriteSnafu, Result};
+
+pub const TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
+pub const VALUE_COLUMN_NAME: &str = "greptime_value";
+pub const COUNT_COLUMN_NAME: &str = "greptime_count";
+pub const SUM_COLUMN_NAME: &str = "greptime_sum";
+const BUCKET_COLUMN_PREFIX: &str = "greptime_le_";
+const INF_BUCKET_COLUMN_NAME: &str = "greptime_le_inf";
+const QUANTILE_COLUMN_PREFIX: &str = "greptime_quantile_";
+
+type TableName = String;
+type Tags = BTreeMap<String, String>;
+
+/// Converts an OTLP metrics export request to insert requests of the `database`.
+///
+/// Each metric is written to the table named after the metric, one row per data point.
+/// Resource attributes and data point attributes are written as tags, the latter take
+/// precedence on conflicts. Gauges and sums are written to the value column, while
+/// histograms and summaries are spread over multiple columns:
+/// - histogram: count, sum and the cumulative count of each bucket, named by its upper
+///   bound, e.g. `greptime_le_0.5`, `greptime_le_inf`.
+/// - summary: count, sum and the value of each quantile, e.g. `greptime_quantile_0.99`.
+/// - exponential histogram: count and sum only.
+pub fn to_grpc_insert_requests(
+    database: &str,
+    request: ExportMetricsServiceRequest,
+) -> Result<Vec<GrpcInsertRequest>> {
+    let mut writers: HashMap<TableName, LinesWriter> = HashMap::new();
+
+    for resource_metrics in request.resource_metrics {
+        let resource_tags = resource_metrics
+            .resource
+            .map(|resource| to_tags(&Tags::new(), &resource.attributes))
+            .unwrap_or_default();
+
+        for metric in resource_metrics
+            .scope_metrics
+            .into_iter()
+            .flat_map(|scope_metrics| scope_metrics.metrics)
+        {
+            let Some(data) = metric.data else {
+                continue;
+            };
+            let writer = writers
+                .entry(normalize_metric_name(&metric.name))
+                .or_insert_with(|| LinesWriter::with_lines(data_points_len(&data)));
+
+            match data {
+                metric::Data::Gauge(gauge) => {
+                    for data_point in &gauge.data_points {
+                        write_number_data_point(writer, &resource_tags, data_point)?;
+                    }
+                }
+                metric::Data::Sum(sum) => {
+                    for data_point in &sum.data_points {
+                        write_number_data_point(writer, &resource_tags, data_point)?;
+                    }
+                }
+                metric::Data::Histogram(histogram) => {
+                    for data_point in &histogram.data_points {
+                        write_histogram_data_point(writer, &resource_tags, data_point)?;
+                    }
+                }
+                metric::Data::ExponentialHistogram(histogram) => {
+                    for data_point in &histogram.data_points {
+                        write_exponential_histogram_data_point(writer, &resource_tags, data_point)?;
+                    }
+                }
+                metric::Data::Summary(summary) => {
+                    for data_point in &summary.data_points {
+                        write_summary_data_point(writer, &resource_tags, data_point)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(writers
+        .into_iter()
+        .filter_map(|(table_name, writer)| {
+            let (columns, row_count) = writer.finish();
+            (row_count > 0).then(|| GrpcInsertRequest {
+                schema_name: database.to_string(),
+                table_name,
+                region_number: 0,
+                columns,
+                row_count,
+            })
+        })
+        .collect())
+}
+
+/// Replaces the characters that are not allowed in a Prometheus metric name with `_`,
+/// e.g. `http.server.duration` becomes `http_server_duration`.
+fn normalize_metric_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn data_points_len(data: &metric::Data) -> usize {
+    match data {
+        metric::Data::Gauge(gauge) => gauge.data_points.len(),
+        metric::Data::Sum(sum) => sum.data_points.len(),
+        metric::Data::Histogram(histogram) => histogram.data_points.len(),
+        metric::Data::ExponentialHistogram(histogram) => histogram.data_points.len(),
+        metric::Data::Summary(summary) => summary.data_points.len(),
+    }
+}
+
+fn to_tags(base: &Tags, attributes: &[KeyValue]) -> Tags {
+    let mut tags = base.clone();
+    for attribute in attributes {
+        if let Some(value) = attribute.value.as_ref().and_then(any_value_to_string) {
+            let _ = tags.insert(attribute.key.clone(), value);
+        }
+    }
+    tags
+}
+
+fn any_value_to_string(value: &AnyValue) -> Option<String> {
+    let value = match value.value.as_ref()? {
+        any_value::Value::StringValue(v) => v.clone(),
+        any_value::Value::BoolValue(v) => v.to_string(),
+        any_value::Value::IntValue(v) => v.to_string(),
+        any_value::Value::DoubleValue(v) => v.to_string(),
+        any_value::Value::BytesValue(v) => String::from_utf8_lossy(v).to_string(),
+        any_value::Value::ArrayValue(v) => format!(
+            "[{}]",
+            v.values
+                .iter()
+                .filter_map(any_value_to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        any_value::Value::KvlistValue(v) => format!(
+            "{{{}}}",
+            v.values
+                .iter()
+                .filter_map(|kv| kv
+                    .value
+                    .as_ref()
+                    .and_then(any_value_to_string)
+                    .map(|value| format!("{}={}", kv.key, value)))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    };
+    Some(value)
+}
+
+/// Writes the tags and the timestamp of a data point, which are shared by all metric types.
+fn write_tags_and_ts(
+    writer: &mut LinesWriter,
+    resource_tags: &Tags,
+    attributes: &[KeyValue],
+    time_unix_nano: u64,
+) -> Result<()> {
+    for (k, v) in to_tags(resource_tags, attributes) {
+        writer.write_tag(&k, &v).context(OtlpMetricsWriteSnafu)?;
+    }
+    writer
+        .write_ts(
+            TIMESTAMP_COLUMN_NAME,
+            (time_unix_nano as i64, Precision::Nanosecond),
+        )
+        .context(OtlpMetricsWriteSnafu)
+}
+
+fn write_number_data_point(
+    writer: &mut LinesWriter,
+    resource_tags: &Tags,
+    data_point: &NumberDataPoint,
+) -> Result<()> {
+    let Some(value) = data_point.value.as_ref() else {
+        return Ok(());
+    };
+    let value = match value {
+        number_data_point::Value::AsDouble(v) => *v,
+        number_data_point::Value::AsInt(v) => *v as f64,
+    };
+
+    write_tags_and_ts(
+        writer,
+        resource_tags,
+        &data_point.attributes,
+        data_point.time_unix_nano,
+    )?;
+    writer
+        .write_f64(VALUE_COLUMN_NAME, value)
+        .context(OtlpMetricsWriteSnafu)?;
+    writer.commit();
+    Ok(())
+}
+
+fn write_histogram_data_point(
+    writer: &mut LinesWriter,
+    resource_tags: &Tags,
+    data_point: &HistogramDataPoint,
+) -> Result<()> {
+    write_tags_and_ts(
+        writer,
+        resource_tags,
+        &data_point.attributes,
+        data_point.time_unix_nano,
+    )?;
+    write_count_and_sum(writer, data_point.count, data_point.sum)?;
+
+    // Bucket counts of OTLP are not cumulative, while the upper bound of the last
+    // bucket is +Inf and not in the explicit bounds.
+    let mut cumulative_count = 0;
+    for (i, bucket_count) in data_point.bucket_counts.iter().enumerate() {
+        cumulative_count += bucket_count;
+        let column_name = match data_point.explicit_bounds.get(i) {
+            Some(bound) => format!("{BUCKET_COLUMN_PREFIX}{bound}"),
+            None => INF_BUCKET_COLUMN_NAME.to_string(),
+        };
+        writer
+            .write_u64(&column_name, cumulative_count)
+            .context(OtlpMetricsWriteSnafu)?;
+    }
+    writer.commit();
+    Ok(())
+}
+
+fn write_exponential_histogram_data_point(
+    writer: &mut LinesWriter,
+    resource_tags: &Tags,
+    data_point: &ExponentialHistogramDataPoint,
+) -> Result<()> {
+    write_tags_and_ts(
+        writer,
+        resource_tags,
+        &data_point.attributes,
+        data_point.time_unix_nano,
+    )?;
+    write_count_and_sum(writer, data_point.count, data_point.sum)?;
+    writer.commit();
+    Ok(())
+}
+
+fn write_summary_data_point(
+    writer: &mut LinesWriter,
+    resource_tags: &Tags,
+    data_point: &SummaryDataPoint,
+) -> Result<()> {
+    write_tags_and_ts(
+        writer,
+        resource_tags,
+        &data_point.attributes,
+        data_point.time_unix_nano,
+    )?;
+    write_count_and_sum(writer, data_point.count, Some(data_point.sum))?;
+    for quantile in &data_point.quantile_values {
+        writer
+            .write_f64(
+                &format!("{QUANTILE_COLUMN_PREFIX}{}", quantile.quantile),
+                quantile.value,
+            )
+            .context(OtlpMetricsWriteSnafu)?;
+    }
+    writer.commit();
+    Ok(())
+}
+
+fn write_count_and_sum(writer: &mut LinesWriter, count: u64, sum: Option<f64>) -> Result<()> {
+    writer
+        .write_u64(COUNT_COLUMN_NAME, count)
+        .context(OtlpMetricsWriteSnafu)?;
+    if let Some(sum) = sum {
+        writer
+            .write_f64(SUM_COLUMN_NAME, sum)
+            .context(OtlpMetricsWriteSnafu)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::column::SemanticType;
+    use api::v1::Column;
+    use opentelemetry_proto::tonic::metrics::v1::summary_data_point::ValueAtQuantile;
+    use opentelemetry_proto::tonic::metrics::v1::{
+        Gauge, Histogram, Metric, ResourceMetrics, ScopeMetrics, Summary,
+    };
+    use opentelemetry_proto::tonic::resource::v1::Resource;
+
+    use super::*;
+
+    fn string_attribute(key: &str, value: &str) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        }
+    }
+
+    fn request(metrics: Vec<Metric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![
+                        string_attribute("service.name", "app"),
+                        string_attribute("host", "resource_host"),
+                    ],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    metrics,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn find_column<'a>(columns: &'a [Column], name: &str) -> &'a Column {
+        columns
+            .iter()
+            .find(|c| c.column_name == name)
+            .unwrap_or_else(|| panic!("column {name} not found"))
+    }
+
+    #[test]
+    fn test_normalize_metric_name() {
+        assert_eq!(
+            "http_server_duration",
+            normalize_metric_name("http.server.duration")
+        );
+        assert_eq!("cpu_usage", normalize_metric_name("cpu_usage"));
+        assert_eq!("a_b_c", normalize_metric_name("a-b/c"));
+    }
+
+    #[test]
+    fn test_gauge_to_insert_requests() {
+        let metric = Metric {
+            name: "system.cpu.usage".to_string(),
+            data: Some(metric::Data::Gauge(Gauge {
+                data_points: vec![
+                    NumberDataPoint {
+                        attributes: vec![string_attribute("host", "host1")],
+                        time_unix_nano: 1_000_000_000,
+                        value: Some(number_data_point::Value::AsDouble(0.5)),
+                        ..Default::default()
+                    },
+                    NumberDataPoint {
+                        attributes: vec![string_attribute("cpu", "0")],
+                        time_unix_nano: 2_000_000_000,
+                        value: Some(number_data_point::Value::AsInt(2)),
+                        ..Default::default()
+                    },
+                ],
+            })),
+            ..Default::default()
+        };
+
+        let requests = to_grpc_insert_requests("public", request(vec![metric])).unwrap();
+        assert_eq!(1, requests.len());
+        let request = &requests[0];
+        assert_eq!("public", request.schema_name);
+        assert_eq!("system_cpu_usage", request.table_name);
+        assert_eq!(2, request.row_count);
+
+        let columns = &request.columns;
+        let ts = find_column(columns, TIMESTAMP_COLUMN_NAME);
+        assert_eq!(SemanticType::Timestamp as i32, ts.semantic_type);
+        assert_eq!(
+            vec![1000, 2000],
+            ts.values.as_ref().unwrap().ts_millisecond_values
+        );
+
+        let value = find_column(columns, VALUE_COLUMN_NAME);
+        assert_eq!(SemanticType::Field as i32, value.semantic_type);
+        assert_eq!(vec![0.5, 2.0], value.values.as_ref().unwrap().f64_values);
+
+        let service = find_column(columns, "service.name");
+        assert_eq!(SemanticType::Tag as i32, service.semantic_type);
+        assert_eq!(
+            vec!["app", "app"],
+            service.values.as_ref().unwrap().string_values
+        );
+        // Data point attributes override the resource attributes.
+        let host = find_column(columns, "host");
+        assert_eq!(
+            vec!["host1", "resource_host"],
+            host.values.as_ref().unwrap().string_values
+        );
+        // The first data point has no cpu attribute.
+        let cpu = find_column(columns, "cpu");
+        assert_eq!(vec!["0"], cpu.values.as_ref().unwrap().string_values);
+        assert_eq!(vec![0b0000_0001], cpu.null_mask);
+    }
+
+    #[test]
+    fn test_histogram_to_insert_requests() {
+        let metric = Metric {
+            name: "http.server.duration".to_string(),
+            data: Some(metric::Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    time_unix_nano: 1_000_000_000,
+                    count: 6,
+                    sum: Some(3.5),
+                    bucket_counts: vec![1, 2, 3],
+                    explicit_bounds: vec![0.1, 1.0],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let requests = to_grpc_insert_requests("public", request(vec![metric])).unwrap();
+        assert_eq!(1, requests.len());
+        let columns = &requests[0].columns;
+        assert_eq!("http_server_duration", requests[0].table_name);
+
+        let u64_values = |name| {
+            find_column(columns, name)
+                .values
+                .as_ref()
+                .unwrap()
+                .u64_values
+                .clone()
+        };
+        assert_eq!(vec![6], u64_values(COUNT_COLUMN_NAME));
+        assert_eq!(vec![1], u64_values("greptime_le_0.1"));
+        assert_eq!(vec![3], u64_values("greptime_le_1"));
+        assert_eq!(vec![6], u64_values(INF_BUCKET_COLUMN_NAME));
+        assert_eq!(
+            vec![3.5],
+            find_column(columns, SUM_COLUMN_NAME)
+                .values
+                .as_ref()
+                .unwrap()
+                .f64_values
+        );
+    }
+
+    #[test]
+    fn test_summary_to_insert_requests() {
+        let metrics = vec![
+            Metric {
+                name: "rpc.latency".to_string(),
+                data: Some(metric::Data::Summary(Summary {
+                    data_points: vec![SummaryDataPoint {
+                        time_unix_nano: 1_000_000_000,
+                        count: 10,
+                        sum: 20.0,
+                        quantile_values: vec![
+                            ValueAtQuantile {
+                                quantile: 0.5,
+                                value: 1.5,
+                            },
+                            ValueAtQuantile {
+                                quantile: 0.99,
+                                value: 4.0,
+                            },
+                        ],
+                        ..Default::default()
+                    }],
+                })),
+                ..Default::default()
+            },
+            // Metrics without data are skipped.
+            Metric {
+                name: "empty".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let requests = to_grpc_insert_requests("public", request(metrics)).unwrap();
+        assert_eq!(1, requests.len());
+        assert_eq!("rpc_latency", requests[0].table_name);
+        let columns = &requests[0].columns;
+        let f64_values = |name| {
+            find_column(columns, name)
+                .values
+                .as_ref()
+                .unwrap()
+                .f64_values
+                .clone()
+        };
+        assert_eq!(vec![20.0], f64_values(SUM_COLUMN_NAME));
+        assert_eq!(vec![1.5], f64_values("greptime_quantile_0.5"));
+        assert_eq!(vec![4.0], f64_values("greptime_quantile_0.99"));
+        assert_eq!(
+            vec![10],
+            find_column(columns, COUNT_COLUMN_NAME)
+                .values
+                .as_ref()
+                .unwrap()
+                .u64_values
+        );
+    }
+}
@@ -66,7 +66,8 @@ impl PgPwdVerifier {
                 None => return Ok(false),
             };
 
-            // TODO(fys): pass user_info to context
+            // The authenticated user is passed to the query context by the user name
+            // saved in the client metadata.
             let _user_info = user_provider
                 .auth(
                     Identity::UserId(&user_name, None),
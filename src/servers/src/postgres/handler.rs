@@ -14,31 +14,68 @@
 
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use common_error::prelude::{ErrorExt, StatusCode};
 use common_query::Output;
 use common_recordbatch::error::Result as RecordBatchResult;
 use common_recordbatch::RecordBatch;
+use common_time::timestamp::TimeUnit;
+use common_time::{Date, TimeZone, Timestamp};
 use datatypes::prelude::{ConcreteDataType, Value};
-use datatypes::schema::SchemaRef;
+use datatypes::schema::Schema;
+use datatypes::types::UuidType;
 use futures::{future, stream, Stream, StreamExt};
-use pgwire::api::portal::Portal;
-use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
-use pgwire::api::results::{text_query_response, FieldInfo, Response, Tag, TextDataRowEncoder};
+use once_cell::sync::OnceCell;
+use pgwire::api::portal::{Format, Portal};
+use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler, StatementOrPortal};
+use pgwire::api::results::{
+    DataRowEncoder, DescribeResponse, FieldInfo, QueryResponse, Response, Tag,
+};
+use pgwire::api::stmt::QueryParser;
+use pgwire::api::store::MemPortalStore;
 use pgwire::api::{ClientInfo, Type};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
-use session::context::QueryContext;
+use session::context::{QueryContext, QueryContextRef, UserInfo};
+use sql::ast::{Expr, Value as SqlValue};
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::placeholder;
+use sql::statements::statement::Statement;
 
 use crate::error::{self, Error, Result};
+use crate::metric;
 use crate::query_handler::SqlQueryHandlerRef;
 
+/// Handler of the queries of a connection.
 pub struct PostgresServerHandler {
     query_handler: SqlQueryHandlerRef,
+    portal_store: Arc<MemPortalStore<String>>,
+    query_parser: Arc<PgQueryParser>,
+    /// Context of the queries in the connection, initialized by the startup parameters
+    /// on the first query, so the session states like `USE` and `SET` are kept.
+    query_ctx: OnceCell<QueryContextRef>,
 }
 
 impl PostgresServerHandler {
     pub fn new(query_handler: SqlQueryHandlerRef) -> Self {
-        PostgresServerHandler { query_handler }
+        PostgresServerHandler {
+            query_handler,
+            portal_store: Arc::new(MemPortalStore::new()),
+            query_parser: Arc::new(PgQueryParser),
+            query_ctx: OnceCell::new(),
+        }
+    }
+
+    fn query_context<C>(&self, client: &C) -> QueryContextRef
+    where
+        C: ClientInfo,
+    {
+        self.query_ctx
+            .get_or_init(|| query_context_from_client_info(client))
+            .clone()
     }
 }
 
@@ -50,6 +87,10 @@ where
     if let Some(current_schema) = client.metadata().get(super::METADATA_DATABASE) {
         query_context.set_current_schema(current_schema);
     }
+    // The user is authenticated on startup if there is a user provider.
+    if let Some(user) = client.metadata().get(super::METADATA_USER) {
+        query_context.set_current_user(UserInfo::new(user));
+    }
 
     Arc::new(query_context)
 }
@@ -60,33 +101,16 @@ impl SimpleQueryHandler for PostgresServerHandler {
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        let query_ctx = query_context_from_client_info(client);
-        let outputs = self.query_handler.do_query(query, query_ctx).await;
+        let query_ctx = self.query_context(client);
+        let start = Instant::now();
+        let outputs = self.query_handler.do_query(query, query_ctx.clone()).await;
+        metric::observe_query(metric::PROTOCOL_POSTGRES, start.elapsed());
 
+        let time_zone = query_ctx.time_zone();
         let mut results = Vec::with_capacity(outputs.len());
 
         for output in outputs {
-            let resp = match output {
-                Ok(Output::AffectedRows(rows)) => {
-                    Response::Execution(Tag::new_for_execution("OK", Some(rows)))
-                }
-                Ok(Output::Stream(record_stream)) => {
-                    let schema = record_stream.schema();
-                    recordbatches_to_query_response(record_stream, schema)?
-                }
-                Ok(Output::RecordBatches(recordbatches)) => {
-                    let schema = recordbatches.schema();
-                    recordbatches_to_query_response(
-                        stream::iter(recordbatches.take().into_iter().map(Ok)),
-                        schema,
-                    )?
-                }
-                Err(e) => Response::Error(Box::new(ErrorInfo::new(
-                    "ERROR".to_string(),
-                    "XX000".to_string(),
-                    e.to_string(),
-                ))),
-            };
+            let resp = output_to_query_response(output, &Format::UnifiedText, time_zone)?;
             results.push(resp);
         }
 
@@ -94,15 +118,69 @@ impl SimpleQueryHandler for PostgresServerHandler {
     }
 }
 
+fn output_to_query_response(
+    output: Result<Output>,
+    field_format: &Format,
+    time_zone: Option<TimeZone>,
+) -> PgWireResult<Response> {
+    match output {
+        Ok(Output::AffectedRows(rows)) => Ok(Response::Execution(Tag::new_for_execution(
+            "OK",
+            Some(rows),
+        ))),
+        Ok(Output::Stream(record_stream)) => {
+            let schema = record_stream.schema();
+            recordbatches_to_query_response(record_stream, &schema, field_format, time_zone)
+        }
+        Ok(Output::RecordBatches(recordbatches)) => {
+            let schema = recordbatches.schema();
+            recordbatches_to_query_response(
+                stream::iter(recordbatches.take().into_iter().map(Ok)),
+                &schema,
+                field_format,
+                time_zone,
+            )
+        }
+        Err(e) => {
+            let code = match e.status_code() {
+                // insufficient_privilege
+                StatusCode::AccessDenied => "42501",
+                _ => "XX000",
+            };
+            Ok(Response::Error(Box::new(ErrorInfo::new(
+                "ERROR".to_string(),
+                code.to_string(),
+                e.to_string(),
+            ))))
+        }
+    }
+}
+
 fn recordbatches_to_query_response<S>(
     recordbatches_stream: S,
-    schema: SchemaRef,
+    schema: &Schema,
+    field_format: &Format,
+    time_zone: Option<TimeZone>,
 ) -> PgWireResult<Response>
 where
     S: Stream<Item = RecordBatchResult<RecordBatch>> + Send + Unpin + 'static,
 {
-    let pg_schema = schema_to_pg(schema).map_err(|e| PgWireError::ApiError(Box::new(e)))?;
-    let ncols = pg_schema.len();
+    let pg_schema = Arc::new(
+        schema_to_pg(schema, field_format).map_err(|e| PgWireError::ApiError(Box::new(e)))?,
+    );
+    let pg_schema_ref = pg_schema.clone();
+    // Timestamps are encoded in the time zone of the session, or the time zone of the
+    // column if the session doesn't set one.
+    let time_zones = schema
+        .column_schemas()
+        .iter()
+        .map(|column| time_zone.or_else(|| column.time_zone()))
+        .collect::<Vec<_>>();
+    let data_types = schema
+        .column_schemas()
+        .iter()
+        .map(|column| column.data_type.clone())
+        .collect::<Vec<_>>();
 
     let data_row_stream = recordbatches_stream
         .map(|record_batch_result| match record_batch_result {
@@ -117,54 +195,91 @@ where
         .flatten() // flatten into stream<result<row>>
         .map(move |row| {
             row.and_then(|row| {
-                let mut encoder = TextDataRowEncoder::new(ncols);
-                for value in row.into_iter() {
-                    encode_value(&value, &mut encoder)?;
+                let mut encoder = DataRowEncoder::new(pg_schema_ref.clone());
+                for ((value, time_zone), data_type) in row.iter().zip(&time_zones).zip(&data_types)
+                {
+                    // JSON documents and UUIDs are encoded as their text.
+                    match (data_type, value) {
+                        (ConcreteDataType::Json(_), Value::Binary(doc)) => {
+                            encoder.encode_field(&String::from_utf8_lossy(doc).as_ref())?
+                        }
+                        (ConcreteDataType::Uuid(_), Value::Binary(uuid)) => {
+                            let uuid = UuidType::format(uuid)
+                                .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+                            encoder.encode_field(&uuid)?
+                        }
+                        _ => encode_value(value, &mut encoder, *time_zone)?,
+                    }
                 }
                 encoder.finish()
             })
         });
 
-    Ok(Response::Query(text_query_response(
+    Ok(Response::Query(QueryResponse::new(
         pg_schema,
         data_row_stream,
     )))
 }
 
-fn schema_to_pg(origin: SchemaRef) -> Result<Vec<FieldInfo>> {
+fn schema_to_pg(origin: &Schema, field_format: &Format) -> Result<Vec<FieldInfo>> {
     origin
         .column_schemas()
         .iter()
-        .map(|col| {
+        .enumerate()
+        .map(|(idx, col)| {
             Ok(FieldInfo::new(
                 col.name.clone(),
                 None,
                 None,
                 type_translate(&col.data_type)?,
+                field_format.format_for(idx),
             ))
         })
         .collect::<Result<Vec<FieldInfo>>>()
 }
 
-fn encode_value(value: &Value, builder: &mut TextDataRowEncoder) -> PgWireResult<()> {
+/// Encodes the `value` into the field of the pg type returned by [type_translate], in
+/// the text or binary format of the field. Timestamps are encoded in the `time_zone`.
+fn encode_value(
+    value: &Value,
+    builder: &mut DataRowEncoder,
+    time_zone: Option<TimeZone>,
+) -> PgWireResult<()> {
     match value {
-        Value::Null => builder.append_field(None::<&i8>),
-        Value::Boolean(v) => builder.append_field(Some(v)),
-        Value::UInt8(v) => builder.append_field(Some(v)),
-        Value::UInt16(v) => builder.append_field(Some(v)),
-        Value::UInt32(v) => builder.append_field(Some(v)),
-        Value::UInt64(v) => builder.append_field(Some(v)),
-        Value::Int8(v) => builder.append_field(Some(v)),
-        Value::Int16(v) => builder.append_field(Some(v)),
-        Value::Int32(v) => builder.append_field(Some(v)),
-        Value::Int64(v) => builder.append_field(Some(v)),
-        Value::Float32(v) => builder.append_field(Some(&v.0)),
-        Value::Float64(v) => builder.append_field(Some(&v.0)),
-        Value::String(v) => builder.append_field(Some(&v.as_utf8())),
-        Value::Binary(v) => builder.append_field(Some(&hex::encode(v.deref()))),
-        Value::Date(v) => builder.append_field(Some(&v.to_string())),
-        Value::DateTime(v) => builder.append_field(Some(&v.to_string())),
-        Value::Timestamp(v) => builder.append_field(Some(&v.to_iso8601_string())),
+        Value::Null => builder.encode_field(&None::<&i8>),
+        Value::Boolean(v) => builder.encode_field(v),
+        Value::UInt8(v) => builder.encode_field(&(*v as i16)),
+        Value::UInt16(v) => builder.encode_field(&(*v as i32)),
+        Value::UInt32(v) => builder.encode_field(&(*v as i64)),
+        Value::UInt64(v) => {
+            let v = i64::try_from(*v).map_err(|_| {
+                PgWireError::ApiError(Box::new(Error::Internal {
+                    err_msg: format!("cannot write value {v} in postgres protocol: overflow"),
+                }))
+            })?;
+            builder.encode_field(&v)
+        }
+        Value::Int8(v) => builder.encode_field(v),
+        Value::Int16(v) => builder.encode_field(v),
+        Value::Int32(v) => builder.encode_field(v),
+        Value::Int64(v) => builder.encode_field(v),
+        Value::Float32(v) => builder.encode_field(&v.0),
+        Value::Float64(v) => builder.encode_field(&v.0),
+        Value::String(v) => builder.encode_field(&v.as_utf8()),
+        Value::Binary(v) => builder.encode_field(&v.deref()),
+        Value::Date(v) => builder.encode_field(&date_to_chrono(*v)?),
+        Value::DateTime(v) => builder.encode_field(&datetime_to_chrono(v.val(), 0)?),
+        Value::Timestamp(v) => {
+            let millis = v.convert_to(TimeUnit::Millisecond);
+            let datetime = datetime_to_chrono(
+                millis.div_euclid(1000),
+                (millis.rem_euclid(1000) * 1_000_000) as u32,
+            )?;
+            match time_zone {
+                Some(time_zone) => builder.encode_field(&time_zone.to_local(&datetime)),
+                None => builder.encode_field(&datetime),
+            }
+        }
         Value::List(_) => Err(PgWireError::ApiError(Box::new(Error::Internal {
             err_msg: format!(
                 "cannot write value {:?} in postgres protocol: unimplemented",
@@ -174,17 +289,44 @@ fn encode_value(value: &Value, builder: &mut TextDataRowEncoder) -> PgWireResult
     }
 }
 
+fn unix_epoch() -> NaiveDate {
+    // Safety: 1970-01-01 is a valid date.
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+fn date_to_chrono(date: Date) -> PgWireResult<NaiveDate> {
+    unix_epoch()
+        .checked_add_signed(chrono::Duration::days(date.val() as i64))
+        .ok_or_else(|| {
+            PgWireError::ApiError(Box::new(Error::Internal {
+                err_msg: format!("date {date} is out of range"),
+            }))
+        })
+}
+
+fn datetime_to_chrono(secs: i64, nsecs: u32) -> PgWireResult<NaiveDateTime> {
+    NaiveDateTime::from_timestamp_opt(secs, nsecs).ok_or_else(|| {
+        PgWireError::ApiError(Box::new(Error::Internal {
+            err_msg: format!("datetime of {secs} seconds is out of range"),
+        }))
+    })
+}
+
 fn type_translate(origin: &ConcreteDataType) -> Result<Type> {
     match origin {
         &ConcreteDataType::Null(_) => Ok(Type::UNKNOWN),
         &ConcreteDataType::Boolean(_) => Ok(Type::BOOL),
-        &ConcreteDataType::Int8(_) | &ConcreteDataType::UInt8(_) => Ok(Type::CHAR),
-        &ConcreteDataType::Int16(_) | &ConcreteDataType::UInt16(_) => Ok(Type::INT2),
-        &ConcreteDataType::Int32(_) | &ConcreteDataType::UInt32(_) => Ok(Type::INT4),
-        &ConcreteDataType::Int64(_) | &ConcreteDataType::UInt64(_) => Ok(Type::INT8),
+        &ConcreteDataType::Int8(_) => Ok(Type::CHAR),
+        &ConcreteDataType::Int16(_) | &ConcreteDataType::UInt8(_) => Ok(Type::INT2),
+        &ConcreteDataType::Int32(_) | &ConcreteDataType::UInt16(_) => Ok(Type::INT4),
+        &ConcreteDataType::Int64(_)
+        | &ConcreteDataType::UInt32(_)
+        | &ConcreteDataType::UInt64(_) => Ok(Type::INT8),
         &ConcreteDataType::Float32(_) => Ok(Type::FLOAT4),
         &ConcreteDataType::Float64(_) => Ok(Type::FLOAT8),
         &ConcreteDataType::Binary(_) => Ok(Type::BYTEA),
+        &ConcreteDataType::Json(_) => Ok(Type::JSON),
+        &ConcreteDataType::Uuid(_) => Ok(Type::UUID),
         &ConcreteDataType::String(_) => Ok(Type::VARCHAR),
         &ConcreteDataType::Date(_) => Ok(Type::DATE),
         &ConcreteDataType::DateTime(_) => Ok(Type::TIMESTAMP),
@@ -196,18 +338,255 @@ fn type_translate(origin: &ConcreteDataType) -> Result<Type> {
     }
 }
 
+/// Parser of the `Parse` message, keeps the SQL as the prepared statement.
+///
+/// The statement is parsed and planned after the parameters are bound, so we don't
+/// need to infer the types of the parameters from the statement.
+#[derive(Debug, Default)]
+pub struct PgQueryParser;
+
+impl QueryParser for PgQueryParser {
+    type Statement = String;
+
+    fn parse_sql(&self, sql: &str, _types: &[Type]) -> PgWireResult<Self::Statement> {
+        Ok(sql.to_string())
+    }
+}
+
 #[async_trait]
 impl ExtendedQueryHandler for PostgresServerHandler {
+    type Statement = String;
+    type PortalStore = MemPortalStore<Self::Statement>;
+    type QueryParser = PgQueryParser;
+
+    fn portal_store(&self) -> Arc<Self::PortalStore> {
+        self.portal_store.clone()
+    }
+
+    fn query_parser(&self) -> Arc<Self::QueryParser> {
+        self.query_parser.clone()
+    }
+
     async fn do_query<C>(
         &self,
-        _client: &mut C,
-        _portal: &Portal,
+        client: &mut C,
+        portal: &Portal<Self::Statement>,
         _max_rows: usize,
     ) -> PgWireResult<Response>
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        unimplemented!()
+        let sql = portal.statement().statement();
+        let query_ctx = self.query_context(client);
+        let start = Instant::now();
+        let output = if portal.parameter_len() == 0 {
+            // Extended query protocol only allows one statement in a query.
+            self.query_handler
+                .do_query(sql, query_ctx.clone())
+                .await
+                .into_iter()
+                .next()
+        } else {
+            let stmt = bind_parameters(portal)?;
+            let output = self
+                .query_handler
+                .do_statement_query(stmt, query_ctx.clone())
+                .await;
+            Some(output)
+        };
+        metric::observe_query(metric::PROTOCOL_POSTGRES, start.elapsed());
+        let Some(output) = output else {
+            return Ok(Response::EmptyQuery);
+        };
+
+        output_to_query_response(output, portal.result_column_format(), query_ctx.time_zone())
+    }
+
+    async fn do_describe<C>(
+        &self,
+        client: &mut C,
+        target: StatementOrPortal<'_, Self::Statement>,
+    ) -> PgWireResult<DescribeResponse>
+    where
+        C: ClientInfo + Unpin + Send + Sync,
+    {
+        let (sql, param_types, field_format) = match target {
+            StatementOrPortal::Statement(stmt) => (
+                stmt.statement(),
+                Some(stmt.parameter_types()),
+                // Formats of the result are unknown until the portal is bound.
+                &Format::UnifiedText,
+            ),
+            StatementOrPortal::Portal(portal) => (
+                portal.statement().statement(),
+                None,
+                portal.result_column_format(),
+            ),
+        };
+
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+        if stmts.len() != 1 {
+            return Ok(DescribeResponse::new(param_types.cloned(), vec![]));
+        }
+        let mut stmt = stmts.remove(0);
+
+        // Binds NULL to all parameters, the schema of the output doesn't depend on the
+        // values of the parameters.
+        let num_params = check_placeholders(sql, &mut stmt)?;
+        for placeholder in placeholder::placeholders_mut(&mut stmt) {
+            *placeholder = Expr::Value(SqlValue::Null);
+        }
+        // Parameters without specified types are sent as text.
+        let param_types = param_types.map(|types| {
+            (0..num_params)
+                .map(|idx| types.get(idx).cloned().unwrap_or(Type::UNKNOWN))
+                .collect()
+        });
+
+        let query_ctx = self.query_context(client);
+        let fields = match self
+            .query_handler
+            .do_describe(stmt, query_ctx)
+            .await
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))?
+        {
+            Some(schema) => schema_to_pg(&schema, field_format)
+                .map_err(|e| PgWireError::ApiError(Box::new(e)))?,
+            None => vec![],
+        };
+        Ok(DescribeResponse::new(param_types, fields))
+    }
+}
+
+/// Parses the statement of the `portal` and replaces each placeholder `$n` in it by the
+/// value of the `n`th parameter.
+fn bind_parameters(portal: &Portal<String>) -> PgWireResult<Statement> {
+    let stmt = portal.statement();
+    let sql = stmt.statement();
+    let values = (0..portal.parameter_len())
+        .map(|idx| {
+            let param_type = stmt.parameter_types().get(idx).unwrap_or(&Type::UNKNOWN);
+            parameter_to_value(portal, idx, param_type).map(value_to_sql_value)
+        })
+        .collect::<PgWireResult<Vec<_>>>()?;
+
+    let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {})
+        .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+    if stmts.len() != 1 {
+        return Err(user_error(
+            "42601",
+            format!("expect 1 statement to bind, but got {}", stmts.len()),
+        ));
+    }
+    let mut stmt = stmts.remove(0);
+    check_placeholders(sql, &mut stmt)?;
+    for placeholder in placeholder::placeholders_mut(&mut stmt) {
+        let index = placeholder_index(placeholder)?;
+        let value = values
+            .get(index - 1)
+            .cloned()
+            .ok_or_else(|| user_error("08P01", format!("there is no parameter ${index}")))?;
+        *placeholder = Expr::Value(value);
+    }
+    Ok(stmt)
+}
+
+/// Checks that all placeholders of `sql` could be bound in the parsed `stmt`, returns
+/// the number of parameters, i.e. the max index of the placeholders `$n`.
+fn check_placeholders(sql: &str, stmt: &mut Statement) -> PgWireResult<usize> {
+    let count = placeholder::count_placeholders(sql, &GenericDialect {})
+        .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+    let placeholders = placeholder::placeholders_mut(stmt);
+    if placeholders.len() != count {
+        return Err(user_error(
+            "0A000",
+            format!("placeholders in statement '{sql}' are not supported"),
+        ));
+    }
+    placeholders
+        .into_iter()
+        .map(|placeholder| placeholder_index(placeholder))
+        .try_fold(0, |max, index| index.map(|index| max.max(index)))
+}
+
+/// Returns the index `n` of the placeholder `$n`, which starts from 1.
+fn placeholder_index(placeholder: &Expr) -> PgWireResult<usize> {
+    match placeholder {
+        Expr::Value(SqlValue::Placeholder(name)) => name
+            .strip_prefix('$')
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|index| *index > 0)
+            .ok_or_else(|| user_error("42P02", format!("invalid parameter {name}"))),
+        _ => unreachable!("placeholders should be values"),
+    }
+}
+
+fn user_error(code: &str, message: String) -> PgWireError {
+    PgWireError::UserError(Box::new(ErrorInfo::new(
+        "ERROR".to_string(),
+        code.to_string(),
+        message,
+    )))
+}
+
+/// Decodes the parameter at `idx` of the `portal` into our [Value].
+fn parameter_to_value(
+    portal: &Portal<String>,
+    idx: usize,
+    param_type: &Type,
+) -> PgWireResult<Value> {
+    let value = match param_type {
+        &Type::BOOL => portal.parameter::<bool>(idx, param_type)?.into(),
+        &Type::CHAR => portal.parameter::<i8>(idx, param_type)?.into(),
+        &Type::INT2 => portal.parameter::<i16>(idx, param_type)?.into(),
+        &Type::INT4 => portal.parameter::<i32>(idx, param_type)?.into(),
+        &Type::INT8 => portal.parameter::<i64>(idx, param_type)?.into(),
+        &Type::FLOAT4 => portal.parameter::<f32>(idx, param_type)?.into(),
+        &Type::FLOAT8 => portal.parameter::<f64>(idx, param_type)?.into(),
+        &Type::VARCHAR | &Type::TEXT | &Type::BPCHAR | &Type::UNKNOWN => portal
+            .parameter::<String>(idx, param_type)?
+            .map(Value::from)
+            .unwrap_or(Value::Null),
+        &Type::BYTEA => portal
+            .parameter::<Vec<u8>>(idx, param_type)?
+            .map(Value::from)
+            .unwrap_or(Value::Null),
+        &Type::TIMESTAMP => portal
+            .parameter::<NaiveDateTime>(idx, param_type)?
+            .map(|v| Value::Timestamp(Timestamp::new_millisecond(v.timestamp_millis())))
+            .unwrap_or(Value::Null),
+        &Type::DATE => portal
+            .parameter::<NaiveDate>(idx, param_type)?
+            .map(|v| {
+                let days = v.signed_duration_since(unix_epoch()).num_days();
+                Value::Date(Date::new(days as i32))
+            })
+            .unwrap_or(Value::Null),
+        _ => {
+            return Err(user_error(
+                "22023",
+                format!("unsupported type {param_type} of parameter ${}", idx + 1),
+            ))
+        }
+    };
+    Ok(value)
+}
+
+/// Converts the value of a parameter into a SQL value.
+fn value_to_sql_value(value: Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Boolean(v) => SqlValue::Boolean(v),
+        Value::String(v) => SqlValue::SingleQuotedString(v.as_utf8().to_string()),
+        Value::Binary(v) => SqlValue::HexStringLiteral(hex::encode_upper(&*v)),
+        Value::Date(_) | Value::DateTime(_) | Value::Timestamp(_) => {
+            SqlValue::SingleQuotedString(value.to_string())
+        }
+        // Keeps the number a float even if it has no fractional part.
+        Value::Float32(v) => SqlValue::Number(format!("{:?}", v.0), false),
+        Value::Float64(v) => SqlValue::Number(format!("{:?}", v.0), false),
+        _ => SqlValue::Number(value.to_string(), false),
     }
 }
 
@@ -217,7 +596,7 @@ mod test {
 
     use datatypes::schema::{ColumnSchema, Schema};
     use datatypes::value::ListValue;
-    use pgwire::api::results::FieldInfo;
+    use pgwire::api::results::{FieldFormat, FieldInfo};
     use pgwire::api::Type;
 
     use super::*;
@@ -247,56 +626,146 @@ mod test {
             ColumnSchema::new("dates", ConcreteDataType::date_datatype(), true),
         ];
         let pg_field_info = vec![
-            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN),
-            FieldInfo::new("bools".into(), None, None, Type::BOOL),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int16s".into(), None, None, Type::INT2),
-            FieldInfo::new("int32s".into(), None, None, Type::INT4),
-            FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("uint8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("uint16s".into(), None, None, Type::INT2),
-            FieldInfo::new("uint32s".into(), None, None, Type::INT4),
-            FieldInfo::new("uint64s".into(), None, None, Type::INT8),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("binaries".into(), None, None, Type::BYTEA),
-            FieldInfo::new("strings".into(), None, None, Type::VARCHAR),
-            FieldInfo::new("timestamps".into(), None, None, Type::TIMESTAMP),
-            FieldInfo::new("dates".into(), None, None, Type::DATE),
+            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN, FieldFormat::Text),
+            FieldInfo::new("bools".into(), None, None, Type::BOOL, FieldFormat::Text),
+            FieldInfo::new("int8s".into(), None, None, Type::CHAR, FieldFormat::Text),
+            FieldInfo::new("int16s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("int32s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("int64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("uint8s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("uint16s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("uint32s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("uint64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "binaries".into(),
+                None,
+                None,
+                Type::BYTEA,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "strings".into(),
+                None,
+                None,
+                Type::VARCHAR,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "timestamps".into(),
+                None,
+                None,
+                Type::TIMESTAMP,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new("dates".into(), None, None, Type::DATE, FieldFormat::Text),
         ];
-        let schema = Arc::new(Schema::new(column_schemas));
-        let fs = schema_to_pg(schema).unwrap();
+        let schema = Schema::new(column_schemas);
+        let fs = schema_to_pg(&schema, &Format::UnifiedText).unwrap();
         assert_eq!(fs, pg_field_info);
     }
 
     #[test]
     fn test_encode_text_format_data() {
         let schema = vec![
-            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN),
-            FieldInfo::new("bools".into(), None, None, Type::BOOL),
-            FieldInfo::new("uint8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("uint16s".into(), None, None, Type::INT2),
-            FieldInfo::new("uint32s".into(), None, None, Type::INT4),
-            FieldInfo::new("uint64s".into(), None, None, Type::INT8),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int16s".into(), None, None, Type::INT2),
-            FieldInfo::new("int16s".into(), None, None, Type::INT2),
-            FieldInfo::new("int32s".into(), None, None, Type::INT4),
-            FieldInfo::new("int32s".into(), None, None, Type::INT4),
-            FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("strings".into(), None, None, Type::VARCHAR),
-            FieldInfo::new("binaries".into(), None, None, Type::BYTEA),
-            FieldInfo::new("dates".into(), None, None, Type::DATE),
-            FieldInfo::new("datetimes".into(), None, None, Type::TIMESTAMP),
-            FieldInfo::new("timestamps".into(), None, None, Type::TIMESTAMP),
+            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN, FieldFormat::Text),
+            FieldInfo::new("bools".into(), None, None, Type::BOOL, FieldFormat::Text),
+            FieldInfo::new("uint8s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("uint16s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("uint32s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("uint64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("int8s".into(), None, None, Type::CHAR, FieldFormat::Text),
+            FieldInfo::new("int8s".into(), None, None, Type::CHAR, FieldFormat::Text),
+            FieldInfo::new("int16s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("int16s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("int32s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("int32s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("int64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("int64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "strings".into(),
+                None,
+                None,
+                Type::VARCHAR,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "binaries".into(),
+                None,
+                None,
+                Type::BYTEA,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new("dates".into(), None, None, Type::DATE, FieldFormat::Text),
+            FieldInfo::new(
+                "datetimes".into(),
+                None,
+                None,
+                Type::TIMESTAMP,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "timestamps".into(),
+                None,
+                None,
+                Type::TIMESTAMP,
+                FieldFormat::Text,
+            ),
         ];
 
         let values = vec![
@@ -305,7 +774,7 @@ mod test {
             Value::UInt8(u8::MAX),
             Value::UInt16(u16::MAX),
             Value::UInt32(u32::MAX),
-            Value::UInt64(u64::MAX),
+            Value::UInt64(i64::MAX as u64),
             Value::Int8(i8::MAX),
             Value::Int8(i8::MIN),
             Value::Int16(i16::MAX),
@@ -326,17 +795,21 @@ mod test {
             Value::DateTime(1000001i64.into()),
             Value::Timestamp(1000001i64.into()),
         ];
-        let mut builder = TextDataRowEncoder::new(schema.len());
+        let mut builder = DataRowEncoder::new(Arc::new(schema));
         for i in values {
-            assert!(encode_value(&i, &mut builder).is_ok());
+            assert!(encode_value(&i, &mut builder, None).is_ok());
         }
 
+        let err = encode_value(&Value::UInt64(u64::MAX), &mut builder, None).unwrap_err();
+        assert!(matches!(err, PgWireError::ApiError(_)));
+
         let err = encode_value(
             &Value::List(ListValue::new(
                 Some(Box::default()),
                 ConcreteDataType::int8_datatype(),
             )),
             &mut builder,
+            None,
         )
         .unwrap_err();
         match err {
@@ -348,4 +821,50 @@ mod test {
             }
         }
     }
+
+    fn parse(sql: &str) -> Statement {
+        ParserContext::create_with_dialect(sql, &GenericDialect {})
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_check_placeholders() {
+        let sql = "SELECT * FROM t WHERE a = $2 AND b = $1 OR c = $2";
+        assert_eq!(2, check_placeholders(sql, &mut parse(sql)).unwrap());
+
+        let sql = "SELECT '$1', \"$1\", 'it''s $1' -- $1\n FROM t /* $1 */ WHERE a = $1";
+        assert_eq!(1, check_placeholders(sql, &mut parse(sql)).unwrap());
+
+        assert_eq!(
+            0,
+            check_placeholders("SELECT 1", &mut parse("SELECT 1")).unwrap()
+        );
+
+        // Placeholders in table functions can't be bound.
+        let sql = "SELECT * FROM t($1)";
+        assert!(check_placeholders(sql, &mut parse(sql)).is_err());
+        let sql = "SELECT * FROM t WHERE a = $0";
+        assert!(check_placeholders(sql, &mut parse(sql)).is_err());
+    }
+
+    #[test]
+    fn test_value_to_sql_value() {
+        let to_string = |value| value_to_sql_value(value).to_string();
+        assert_eq!("NULL", to_string(Value::Null));
+        assert_eq!("true", to_string(Value::Boolean(true)));
+        assert_eq!("-1", to_string(Value::Int32(-1)));
+        assert_eq!("1.0", to_string(Value::Float64(1.0.into())));
+        assert_eq!("'it''s'", to_string(Value::from("it's")));
+        // Binary values are kept as is, even if they are not valid UTF-8.
+        assert_eq!(
+            "X'00FF27'",
+            to_string(Value::from(vec![0x00u8, 0xff, b'\'']))
+        );
+        assert_eq!("'1970-01-02'", to_string(Value::Date(Date::new(1))));
+        assert_eq!(
+            "'1970-01-01 00:00:01+0000'",
+            to_string(Value::Timestamp(Timestamp::new_millisecond(1000)))
+        );
+    }
 }
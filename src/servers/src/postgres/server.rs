@@ -23,20 +23,20 @@ use common_telemetry::{debug, warn};
 use futures::StreamExt;
 use pgwire::tokio::process_socket;
 use tokio;
-use tokio_rustls::TlsAcceptor;
 
 use crate::auth::UserProviderRef;
 use crate::error::Result;
+use crate::metric;
 use crate::postgres::auth_handler::PgAuthStartupHandler;
 use crate::postgres::handler::PostgresServerHandler;
 use crate::query_handler::SqlQueryHandlerRef;
 use crate::server::{AbortableStream, BaseTcpServer, Server};
-use crate::tls::TlsOption;
+use crate::tls::{ReloadableTlsServerConfig, ReloadableTlsServerConfigRef, TlsOption};
 
 pub struct PostgresServer {
     base_server: BaseTcpServer,
     auth_handler: Arc<PgAuthStartupHandler>,
-    query_handler: Arc<PostgresServerHandler>,
+    query_handler: SqlQueryHandlerRef,
     tls: TlsOption,
 }
 
@@ -48,16 +48,16 @@ impl PostgresServer {
         io_runtime: Arc<Runtime>,
         user_provider: Option<UserProviderRef>,
     ) -> PostgresServer {
-        let postgres_handler = Arc::new(PostgresServerHandler::new(query_handler.clone()));
+        metric::register_metrics();
         let startup_handler = Arc::new(PgAuthStartupHandler::new(
             user_provider,
             tls.should_force_tls(),
-            query_handler,
+            query_handler.clone(),
         ));
         PostgresServer {
             base_server: BaseTcpServer::create_server("Postgres", io_runtime),
             auth_handler: startup_handler,
-            query_handler: postgres_handler,
+            query_handler,
             tls,
         }
     }
@@ -66,7 +66,7 @@ impl PostgresServer {
         &self,
         io_runtime: Arc<Runtime>,
         accepting_stream: AbortableStream,
-        tls_acceptor: Option<Arc<TlsAcceptor>>,
+        tls_conf: Option<ReloadableTlsServerConfigRef>,
     ) -> impl Future<Output = ()> {
         let auth_handler = self.auth_handler.clone();
         let query_handler = self.query_handler.clone();
@@ -75,7 +75,7 @@ impl PostgresServer {
             let io_runtime = io_runtime.clone();
             let auth_handler = auth_handler.clone();
             let query_handler = query_handler.clone();
-            let tls_acceptor = tls_acceptor.clone();
+            let tls_conf = tls_conf.clone();
 
             async move {
                 match tcp_stream {
@@ -86,12 +86,15 @@ impl PostgresServer {
                             Err(e) => warn!("Failed to get PostgreSQL client addr, err: {}", e),
                         }
 
+                        // Each connection has its own handler to keep the session states.
+                        let postgres_handler =
+                            Arc::new(PostgresServerHandler::new(query_handler.clone()));
                         io_runtime.spawn(process_socket(
                             io_stream,
-                            tls_acceptor.clone(),
+                            tls_conf.map(|c| Arc::new(c.acceptor())),
                             auth_handler.clone(),
-                            query_handler.clone(),
-                            query_handler.clone(),
+                            postgres_handler.clone(),
+                            postgres_handler,
                         ));
                     }
                 };
@@ -110,13 +113,10 @@ impl Server for PostgresServer {
         let (stream, addr) = self.base_server.bind(listening).await?;
 
         debug!("Starting PostgreSQL with TLS option: {:?}", self.tls);
-        let tls_acceptor = self
-            .tls
-            .setup()?
-            .map(|server_conf| Arc::new(TlsAcceptor::from(Arc::new(server_conf))));
+        let tls_conf = ReloadableTlsServerConfig::try_new(self.tls.clone(), &[])?;
 
         let io_runtime = self.base_server.io_runtime();
-        let join_handle = tokio::spawn(self.accept(io_runtime, stream, tls_acceptor));
+        let join_handle = tokio::spawn(self.accept(io_runtime, stream, tls_conf));
 
         self.base_server.start_with(join_handle).await?;
         Ok(addr)
@@ -43,7 +43,10 @@ pub struct Metrics {
 }
 
 /// Generate a sql from a remote request query
-/// TODO(dennis): maybe use logical plan in future to prevent sql injection
+///
+/// Names are quoted and values are escaped so they are always parsed as identifiers
+/// and string literals. Regex matchers are anchored like Prometheus does.
+/// TODO(dennis): maybe use logical plan in future
 pub fn query_to_sql(db: &str, q: &Query) -> Result<(String, String)> {
     let start_timestamp_ms = q.start_timestamp_ms;
     let end_timestamp_ms = q.end_timestamp_ms;
@@ -69,13 +72,12 @@ pub fn query_to_sql(db: &str, q: &Query) -> Result<(String, String)> {
     ));
 
     for m in label_matches {
-        let name = &m.name;
-
-        if name == METRIC_NAME_LABEL {
+        if m.name == METRIC_NAME_LABEL {
             continue;
         }
 
-        let value = &m.value;
+        let name = quote_ident(&m.name);
+        let value = escape_literal(&m.value);
         let m_type =
             MatcherType::from_i32(m.r#type).context(error::InvalidPromRemoteRequestSnafu {
                 msg: format!("invalid LabelMatcher type: {}", m.r#type),
@@ -90,11 +92,11 @@ pub fn query_to_sql(db: &str, q: &Query) -> Result<(String, String)> {
             }
             // Case sensitive regexp match
             MatcherType::Re => {
-                conditions.push(format!("{name}~'{value}'"));
+                conditions.push(format!("{name}~'^(?:{value})$'"));
             }
             // Case sensitive regexp not match
             MatcherType::Nre => {
-                conditions.push(format!("{name}!~'{value}'"));
+                conditions.push(format!("{name}!~'^(?:{value})$'"));
             }
         }
     }
@@ -104,11 +106,23 @@ pub fn query_to_sql(db: &str, q: &Query) -> Result<(String, String)> {
     Ok((
         table_name.to_string(),
         format!(
-            "select * from {db}.{table_name} where {conditions} order by {TIMESTAMP_COLUMN_NAME}",
+            "select * from {}.{} where {conditions} order by {TIMESTAMP_COLUMN_NAME}",
+            quote_ident(db),
+            quote_ident(&table_name),
         ),
     ))
 }
 
+#[inline]
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+#[inline]
+fn escape_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
 #[inline]
 fn new_label(name: String, value: String) -> Label {
     Label { name, value }
@@ -541,7 +555,10 @@ mod tests {
         };
         let (table, sql) = query_to_sql("public", &q).unwrap();
         assert_eq!("test", table);
-        assert_eq!("select * from public.test where greptime_timestamp>=1000 AND greptime_timestamp<=2000 order by greptime_timestamp", sql);
+        assert_eq!(
+            r#"select * from "public"."test" where greptime_timestamp>=1000 AND greptime_timestamp<=2000 order by greptime_timestamp"#,
+            sql
+        );
 
         let q = Query {
             start_timestamp_ms: 1000,
@@ -567,7 +584,35 @@ mod tests {
         };
         let (table, sql) = query_to_sql("public", &q).unwrap();
         assert_eq!("test", table);
-        assert_eq!("select * from public.test where greptime_timestamp>=1000 AND greptime_timestamp<=2000 AND job~'*prom*' AND instance!='localhost' order by greptime_timestamp", sql);
+        assert_eq!(
+            r#"select * from "public"."test" where greptime_timestamp>=1000 AND greptime_timestamp<=2000 AND "job"~'^(?:*prom*)$' AND "instance"!='localhost' order by greptime_timestamp"#,
+            sql
+        );
+
+        // Quotes in names and values are escaped.
+        let q = Query {
+            start_timestamp_ms: 1000,
+            end_timestamp_ms: 2000,
+            matchers: vec![
+                LabelMatcher {
+                    name: METRIC_NAME_LABEL.to_string(),
+                    value: "te\"st".to_string(),
+                    r#type: EQ_TYPE,
+                },
+                LabelMatcher {
+                    name: "job".to_string(),
+                    value: "' OR '1'='1".to_string(),
+                    r#type: EQ_TYPE,
+                },
+            ],
+            ..Default::default()
+        };
+        let (table, sql) = query_to_sql("public", &q).unwrap();
+        assert_eq!("te\"st", table);
+        assert_eq!(
+            r#"select * from "public"."te""st" where greptime_timestamp>=1000 AND greptime_timestamp<=2000 AND "job"=''' OR ''1''=''1' order by greptime_timestamp"#,
+            sql
+        );
     }
 
     #[test]
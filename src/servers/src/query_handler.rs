@@ -12,16 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use api::prometheus::remote::{ReadRequest, WriteRequest};
 use api::v1::{ObjectExpr, ObjectResult};
+use arrow_flight::{FlightData, PutResult};
 use async_trait::async_trait;
 use common_query::Output;
+use datatypes::schema::Schema;
+use futures::Stream;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
 use session::context::QueryContextRef;
 use sql::statements::statement::Statement;
+use tonic::Streaming;
 
-use crate::error::Result;
+use crate::error::{NotSupportedSnafu, Result};
 use crate::influxdb::InfluxdbRequest;
 use crate::opentsdb::codec::DataPoint;
 use crate::prometheus::Metrics;
@@ -41,7 +48,14 @@ pub type GrpcQueryHandlerRef = Arc<dyn GrpcQueryHandler + Send + Sync>;
 pub type OpentsdbProtocolHandlerRef = Arc<dyn OpentsdbProtocolHandler + Send + Sync>;
 pub type InfluxdbLineProtocolHandlerRef = Arc<dyn InfluxdbLineProtocolHandler + Send + Sync>;
 pub type PrometheusProtocolHandlerRef = Arc<dyn PrometheusProtocolHandler + Send + Sync>;
+pub type OpenTelemetryProtocolHandlerRef = Arc<dyn OpenTelemetryProtocolHandler + Send + Sync>;
 pub type ScriptHandlerRef = Arc<dyn ScriptHandler + Send + Sync>;
+pub type ReadinessHandlerRef = Arc<dyn ReadinessHandler + Send + Sync>;
+
+pub type FlightDataStream =
+    Pin<Box<dyn Stream<Item = std::result::Result<FlightData, tonic::Status>> + Send + Sync>>;
+pub type PutResultStream =
+    Pin<Box<dyn Stream<Item = std::result::Result<PutResult, tonic::Status>> + Send + Sync>>;
 
 #[async_trait]
 pub trait SqlQueryHandler {
@@ -53,6 +67,16 @@ pub trait SqlQueryHandler {
         query_ctx: QueryContextRef,
     ) -> Result<Output>;
 
+    /// Returns the schema of the output of `stmt` without executing it, or `None` if the
+    /// statement doesn't return rows.
+    async fn do_describe(
+        &self,
+        _stmt: Statement,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Option<Schema>> {
+        Ok(None)
+    }
+
     /// check if schema is valid
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool>;
 }
@@ -65,7 +89,37 @@ pub trait ScriptHandler {
 
 #[async_trait]
 pub trait GrpcQueryHandler {
-    async fn do_query(&self, query: ObjectExpr) -> Result<ObjectResult>;
+    /// Executes the `query`, the `query_ctx` carries the user who issued it.
+    async fn do_query(&self, query: ObjectExpr, query_ctx: QueryContextRef)
+        -> Result<ObjectResult>;
+
+    /// Executes the `query` and streams the result as Arrow Flight data, the first
+    /// message is the schema of the result. Record batches in the result are compressed
+    /// if `compressed` is true.
+    async fn do_query_stream(
+        &self,
+        _query: ObjectExpr,
+        _compressed: bool,
+        _query_ctx: QueryContextRef,
+    ) -> Result<FlightDataStream> {
+        NotSupportedSnafu {
+            feat: "Streaming query results",
+        }
+        .fail()
+    }
+
+    /// Writes the record batches in the `stream` of Arrow Flight data into a table, each
+    /// record batch is acknowledged by a [PutResult].
+    async fn do_put_stream(
+        &self,
+        _stream: Streaming<FlightData>,
+        _query_ctx: QueryContextRef,
+    ) -> Result<PutResultStream> {
+        NotSupportedSnafu {
+            feat: "Putting Flight data",
+        }
+        .fail()
+    }
 }
 
 #[async_trait]
@@ -80,6 +134,14 @@ pub trait OpentsdbProtocolHandler {
     /// A successful request will not return a response.
     /// Only on error will the socket return a line of data.
     async fn exec(&self, data_point: &DataPoint) -> Result<()>;
+
+    /// Writes `data_points` in batch, stops at the first error.
+    async fn exec_batch(&self, data_points: &[DataPoint]) -> Result<()> {
+        for data_point in data_points {
+            self.exec(data_point).await?;
+        }
+        Ok(())
+    }
 }
 
 pub struct PrometheusResponse {
@@ -97,3 +159,20 @@ pub trait PrometheusProtocolHandler {
     /// Handling push gateway requests
     async fn ingest_metrics(&self, metrics: Metrics) -> Result<()>;
 }
+
+#[async_trait]
+pub trait OpenTelemetryProtocolHandler {
+    /// Handling OTLP metrics export requests
+    async fn write_metrics(
+        &self,
+        database: &str,
+        request: ExportMetricsServiceRequest,
+    ) -> Result<()>;
+}
+
+/// Reports whether the node is ready to serve requests, e.g. for the readiness probe
+/// of kubernetes.
+pub trait ReadinessHandler {
+    /// Returns whether each readiness check passes, keyed by the name of the check.
+    fn readiness(&self) -> BTreeMap<String, bool>;
+}
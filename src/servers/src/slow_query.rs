@@ -0,0 +1,169 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Slow query log, which records queries taking longer than a threshold.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use common_telemetry::logging::warn;
+use metrics::increment_counter;
+use sha1::{Digest, Sha1};
+
+use crate::metric::METRIC_SLOW_QUERIES_TOTAL;
+
+pub type SlowQueryLogRef = Arc<SlowQueryLog>;
+
+/// Logs the statement, plan digest and latency of queries slower than the threshold
+/// under the `slow_query` target. The threshold can be adjusted at runtime, and the
+/// log is disabled if it's not set.
+#[derive(Debug, Default)]
+pub struct SlowQueryLog {
+    /// Threshold in milliseconds, 0 if disabled.
+    threshold_millis: AtomicU64,
+}
+
+impl SlowQueryLog {
+    pub fn new(threshold: Option<Duration>) -> Self {
+        let log = Self::default();
+        log.set_threshold(threshold);
+        log
+    }
+
+    pub fn threshold(&self) -> Option<Duration> {
+        match self.threshold_millis.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(Duration::from_millis(millis)),
+        }
+    }
+
+    /// Sets the threshold of slow queries, `None` disables the log.
+    pub fn set_threshold(&self, threshold: Option<Duration>) {
+        // A threshold less than 1ms is rounded up, as 0 stands for disabled.
+        let millis = threshold.map_or(0, |t| (t.as_millis() as u64).max(1));
+        self.threshold_millis.store(millis, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.threshold_millis.load(Ordering::Relaxed) > 0
+    }
+
+    pub fn is_slow(&self, elapsed: Duration) -> bool {
+        self.threshold().map_or(false, |t| elapsed >= t)
+    }
+
+    /// Records the query received by the server of `protocol` if it's slow, returns
+    /// whether it's recorded.
+    pub fn record(
+        &self,
+        protocol: &'static str,
+        query: &str,
+        plan_digest: &str,
+        elapsed: Duration,
+    ) -> bool {
+        if !self.is_slow(elapsed) {
+            return false;
+        }
+        increment_counter!(METRIC_SLOW_QUERIES_TOTAL, "protocol" => protocol);
+        warn!(
+            target: "slow_query",
+            "Slow query, protocol: {}, elapsed: {:?}, plan digest: {}, query: {}",
+            protocol, elapsed, plan_digest, query
+        );
+        true
+    }
+}
+
+/// Digest of a query, which is the same for the same statements or logical plans.
+pub fn digest(query: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(query);
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Records the query to the slow query log when it's dropped, for queries whose results
+/// are streamed so that the latency covers the whole execution.
+pub struct SlowQueryTimer {
+    log: SlowQueryLogRef,
+    protocol: &'static str,
+    query: String,
+    plan_digest: String,
+    start: Instant,
+}
+
+impl SlowQueryTimer {
+    pub fn new(
+        log: SlowQueryLogRef,
+        protocol: &'static str,
+        query: String,
+        plan_digest: String,
+    ) -> Self {
+        Self {
+            log,
+            protocol,
+            query,
+            plan_digest,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for SlowQueryTimer {
+    fn drop(&mut self) {
+        let _ = self.log.record(
+            self.protocol,
+            &self.query,
+            &self.plan_digest,
+            self.start.elapsed(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold() {
+        let log = SlowQueryLog::default();
+        assert!(!log.is_enabled());
+        assert!(!log.is_slow(Duration::from_secs(3600)));
+        assert!(!log.record("grpc", "select 1", "", Duration::from_secs(3600)));
+
+        log.set_threshold(Some(Duration::from_millis(100)));
+        assert_eq!(Some(Duration::from_millis(100)), log.threshold());
+        assert!(!log.is_slow(Duration::from_millis(99)));
+        assert!(log.is_slow(Duration::from_millis(100)));
+        assert!(log.record("grpc", "select 1", "", Duration::from_millis(200)));
+
+        log.set_threshold(Some(Duration::from_micros(10)));
+        assert_eq!(Some(Duration::from_millis(1)), log.threshold());
+
+        log.set_threshold(None);
+        assert!(!log.is_enabled());
+        assert_eq!(None, SlowQueryLog::new(None).threshold());
+    }
+
+    #[test]
+    fn test_digest() {
+        let d = digest(b"select 1");
+        assert_eq!(40, d.len());
+        assert_eq!(d, digest(b"select 1"));
+        assert_ne!(d, digest(b"select 2"));
+    }
+}
@@ -14,13 +14,36 @@
 
 use std::fs::File;
 use std::io::{BufReader, Error, ErrorKind};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, SystemTime};
 
-use rustls::{Certificate, PrivateKey, ServerConfig};
+use common_telemetry::{info, warn};
+use futures::{future, Stream, StreamExt};
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
 use rustls_pemfile::{certs, pkcs8_private_keys};
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::TcpListenerStream;
+
+/// Interval to check whether the certificate files are changed.
+const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+/// Max number of TLS handshakes in progress on a listener.
+const MAX_CONCURRENT_HANDSHAKES: usize = 128;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// ALPN protocols of gRPC listeners.
+pub const GRPC_ALPN_PROTOCOLS: &[&[u8]] = &[b"h2"];
+/// ALPN protocols of HTTP listeners.
+pub const HTTP_ALPN_PROTOCOLS: &[&[u8]] = &[b"h2", b"http/1.1"];
 
 /// TlsMode is used for Mysql and Postgres server start up.
+///
+/// HTTP and gRPC listeners can't negotiate TLS on the same port, they serve TLS
+/// connections only unless the mode is [TlsMode::Disable].
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, EnumString)]
 #[serde(rename_all = "snake_case")]
 pub enum TlsMode {
@@ -51,6 +74,13 @@ pub struct TlsOption {
     pub cert_path: String,
     #[serde(default)]
     pub key_path: String,
+    /// CA certificates to verify the certificates of clients. Clients must present
+    /// a certificate signed by them if it's set, a.k.a. mutual TLS.
+    #[serde(default)]
+    pub ca_cert_path: String,
+    /// Reloads the certificates and the key once the files are changed.
+    #[serde(default)]
+    pub watch: bool,
 }
 
 impl TlsOption {
@@ -76,9 +106,7 @@ impl TlsOption {
         if let TlsMode::Disable = self.mode {
             return Ok(None);
         }
-        let cert = certs(&mut BufReader::new(File::open(&self.cert_path)?))
-            .map_err(|_| Error::new(ErrorKind::InvalidInput, "invalid cert"))
-            .map(|mut certs| certs.drain(..).map(Certificate).collect())?;
+        let cert = load_certs(&self.cert_path)?;
 
         // TODO(SSebo): support more private key types
         let key = pkcs8_private_keys(&mut BufReader::new(File::open(&self.key_path)?))
@@ -86,10 +114,19 @@ impl TlsOption {
             .map(|mut keys| keys.drain(..).map(PrivateKey).next())?
             .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "invalid key"))?;
 
-        // TODO(SSebo): with_client_cert_verifier if TlsMode is Required.
-        let config = ServerConfig::builder()
-            .with_safe_defaults()
-            .with_no_client_auth()
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = if self.ca_cert_path.is_empty() {
+            builder.with_no_client_auth()
+        } else {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(&self.ca_cert_path)? {
+                roots.add(&cert).map_err(|e| {
+                    Error::new(ErrorKind::InvalidInput, format!("invalid ca cert: {e}"))
+                })?;
+            }
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+        };
+        let config = builder
             .with_single_cert(cert, key)
             .map_err(|err| std::io::Error::new(ErrorKind::InvalidInput, err))?;
 
@@ -99,6 +136,154 @@ impl TlsOption {
     pub fn should_force_tls(&self) -> bool {
         !matches!(self.mode, TlsMode::Disable | TlsMode::Prefer)
     }
+
+    /// Returns the last modified time of the certificate and key files.
+    fn modified_times(&self) -> Vec<Option<SystemTime>> {
+        [&self.cert_path, &self.key_path, &self.ca_cert_path]
+            .into_iter()
+            .filter(|path| !path.is_empty())
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>, Error> {
+    certs(&mut BufReader::new(File::open(path)?))
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "invalid cert"))
+        .map(|mut certs| certs.drain(..).map(Certificate).collect())
+}
+
+pub type ReloadableTlsServerConfigRef = Arc<ReloadableTlsServerConfig>;
+
+/// TLS server config of a listener, which is reloaded from the files once they are
+/// changed if [TlsOption::watch] is set. Connections accepted before the reload keep
+/// using the old config.
+pub struct ReloadableTlsServerConfig {
+    tls_option: TlsOption,
+    alpn_protocols: Vec<Vec<u8>>,
+    config: RwLock<Arc<ServerConfig>>,
+}
+
+impl ReloadableTlsServerConfig {
+    /// Loads the server config advertising `alpn_protocols`, returns `None` if TLS is
+    /// disabled.
+    pub fn try_new(
+        tls_option: TlsOption,
+        alpn_protocols: &[&[u8]],
+    ) -> Result<Option<ReloadableTlsServerConfigRef>, Error> {
+        // Takes the modified times before loading, so changes made during the loading
+        // are reloaded later.
+        let modified_times = tls_option.modified_times();
+        let alpn_protocols = alpn_protocols.iter().map(|p| p.to_vec()).collect();
+        let Some(config) = Self::load(&tls_option, &alpn_protocols)? else {
+            return Ok(None);
+        };
+
+        let config = Arc::new(Self {
+            tls_option,
+            alpn_protocols,
+            config: RwLock::new(Arc::new(config)),
+        });
+        if config.tls_option.watch {
+            watch_files(Arc::downgrade(&config), modified_times);
+        }
+        Ok(Some(config))
+    }
+
+    fn load(
+        tls_option: &TlsOption,
+        alpn_protocols: &[Vec<u8>],
+    ) -> Result<Option<ServerConfig>, Error> {
+        Ok(tls_option.setup()?.map(|mut config| {
+            config.alpn_protocols = alpn_protocols.to_vec();
+            config
+        }))
+    }
+
+    pub fn tls_option(&self) -> &TlsOption {
+        &self.tls_option
+    }
+
+    pub fn get_server_config(&self) -> Arc<ServerConfig> {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.get_server_config())
+    }
+
+    /// Reloads the config from the files, the current config is kept on failure.
+    pub fn reload(&self) -> Result<(), Error> {
+        if let Some(config) = Self::load(&self.tls_option, &self.alpn_protocols)? {
+            *self.config.write().unwrap() = Arc::new(config);
+        }
+        Ok(())
+    }
+}
+
+/// Checks the files of the config periodically and reloads the config once they are
+/// changed, until the config is dropped.
+fn watch_files(
+    config: Weak<ReloadableTlsServerConfig>,
+    mut modified_times: Vec<Option<SystemTime>>,
+) {
+    common_runtime::spawn_bg(async move {
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(config) = config.upgrade() else {
+                break;
+            };
+
+            let current = config.tls_option.modified_times();
+            if current == modified_times {
+                continue;
+            }
+            // The files may be in the middle of replacing, retries in next round.
+            match config.reload() {
+                Ok(()) => {
+                    info!(
+                        "TLS certificates reloaded from {}",
+                        config.tls_option.cert_path
+                    );
+                    modified_times = current;
+                }
+                Err(e) => warn!("Failed to reload TLS certificates, error: {}", e),
+            }
+        }
+    });
+}
+
+/// Accepts TLS connections from the `listener`. Handshakes are done concurrently so a
+/// slow client doesn't block the others, connections failing the handshake are dropped.
+pub fn tls_incoming(
+    listener: TcpListener,
+    config: ReloadableTlsServerConfigRef,
+) -> impl Stream<Item = Result<TlsStream<TcpStream>, Error>> {
+    TcpListenerStream::new(listener)
+        .map(move |stream| {
+            let acceptor = config.acceptor();
+            async move {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => return Some(Err(e)),
+                };
+                let peer_addr = stream.peer_addr().ok();
+                match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => Some(Ok(stream)),
+                    Ok(Err(e)) => {
+                        warn!("TLS handshake with {:?} failed, error: {}", peer_addr, e);
+                        None
+                    }
+                    Err(_) => {
+                        warn!("TLS handshake with {:?} timed out", peer_addr);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_HANDSHAKES)
+        .filter_map(future::ready)
 }
 
 #[cfg(test)]
@@ -121,6 +306,7 @@ mod tests {
                 mode: Disable,
                 cert_path: "/path/to/cert_path".to_string(),
                 key_path: "/path/to/key_path".to_string(),
+                ..Default::default()
             },
             TlsOption::new(
                 Some(Disable),
@@ -227,4 +413,60 @@ mod tests {
         assert!(!t.key_path.is_empty());
         assert!(!t.cert_path.is_empty());
     }
+
+    #[test]
+    fn test_tls_option_mutual_tls() {
+        let s = r#"
+        {
+            "mode": "require",
+            "cert_path": "tests/ssl/server.crt",
+            "key_path": "tests/ssl/server.key",
+            "ca_cert_path": "tests/ssl/server.crt"
+        }
+        "#;
+
+        let t: TlsOption = serde_json::from_str(s).unwrap();
+        assert!(!t.watch);
+        assert_eq!("tests/ssl/server.crt", t.ca_cert_path);
+        assert!(t.setup().unwrap().is_some());
+
+        let t = TlsOption {
+            ca_cert_path: "tests/ssl/not_exist.crt".to_string(),
+            ..t
+        };
+        assert!(t.setup().is_err());
+    }
+
+    #[test]
+    fn test_reloadable_tls_server_config() {
+        let disabled = ReloadableTlsServerConfig::try_new(TlsOption::default(), &[]).unwrap();
+        assert!(disabled.is_none());
+
+        let dir = tempdir::TempDir::new("test_reloadable_tls_server_config").unwrap();
+        let cert_path = dir.path().join("server.crt");
+        let key_path = dir.path().join("server.key");
+        std::fs::copy("tests/ssl/server.crt", &cert_path).unwrap();
+        std::fs::copy("tests/ssl/server.key", &key_path).unwrap();
+
+        let t = TlsOption {
+            mode: TlsMode::Require,
+            cert_path: cert_path.to_str().unwrap().to_string(),
+            key_path: key_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        let config = ReloadableTlsServerConfig::try_new(t, GRPC_ALPN_PROTOCOLS)
+            .unwrap()
+            .unwrap();
+        let old = config.get_server_config();
+        assert_eq!(vec![b"h2".to_vec()], old.alpn_protocols);
+
+        config.reload().unwrap();
+        assert!(!Arc::ptr_eq(&old, &config.get_server_config()));
+
+        // Keeps the current config if the files are broken.
+        std::fs::write(&key_path, "broken").unwrap();
+        let current = config.get_server_config();
+        assert!(config.reload().is_err());
+        assert!(Arc::ptr_eq(&current, &config.get_server_config()));
+    }
 }
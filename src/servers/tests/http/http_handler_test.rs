@@ -12,13 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 use axum::body::Body;
 use axum::extract::{Json, Query, RawBody, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
 use common_telemetry::metric;
 use metrics::counter;
-use servers::http::{handler as http_handler, script as script_handler, ApiState, JsonOutput};
+use servers::http::types::{
+    HealthQuery, HealthResponse, JsonOutput, ReadinessResponse, ResponseFormat, ScriptQuery,
+    SqlQuery, SqlResponse,
+};
+use servers::http::{handler as http_handler, script as script_handler, ApiState};
+use servers::query_handler::{ReadinessHandler, ReadinessHandlerRef};
 use session::context::UserInfo;
 use table::test_util::MemTable;
 
@@ -27,15 +35,18 @@ use crate::{create_testing_script_handler, create_testing_sql_query_handler};
 #[tokio::test]
 async fn test_sql_not_provided() {
     let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
-    let Json(json) = http_handler::sql(
+    let SqlResponse::Json(Json(json)) = http_handler::sql(
         State(ApiState {
             sql_handler,
             script_handler: None,
         }),
-        Query(http_handler::SqlQuery::default()),
+        Query(SqlQuery::default()),
         axum::Extension(UserInfo::default()),
     )
-    .await;
+    .await
+    else {
+        unreachable!()
+    };
     assert!(!json.success());
     assert_eq!(
         Some(&"sql parameter is required.".to_string()),
@@ -51,7 +62,7 @@ async fn test_sql_output_rows() {
     let query = create_query();
     let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
 
-    let Json(json) = http_handler::sql(
+    let SqlResponse::Json(Json(json)) = http_handler::sql(
         State(ApiState {
             sql_handler,
             script_handler: None,
@@ -59,7 +70,10 @@ async fn test_sql_output_rows() {
         query,
         axum::Extension(UserInfo::default()),
     )
-    .await;
+    .await
+    else {
+        unreachable!()
+    };
     assert!(json.success(), "{json:?}");
     assert!(json.error().is_none());
     match &json.output().expect("assertion failed")[0] {
@@ -70,6 +84,57 @@ async fn test_sql_output_rows() {
     }
 }
 
+#[tokio::test]
+async fn test_sql_output_csv() {
+    common_telemetry::init_default_ut_logging();
+
+    let sql_handler = create_testing_sql_query_handler(MemTable::default_numbers_table());
+    let query = Query(SqlQuery {
+        sql: Some("select uint32s from numbers where uint32s < 3 order by uint32s".to_string()),
+        database: None,
+        format: Some(ResponseFormat::Csv),
+    });
+    let SqlResponse::Stream(stream) = http_handler::sql(
+        State(ApiState {
+            sql_handler: sql_handler.clone(),
+            script_handler: None,
+        }),
+        query,
+        axum::Extension(UserInfo::default()),
+    )
+    .await
+    else {
+        unreachable!()
+    };
+    let response = stream.into_response();
+    assert_eq!("text/csv", response.headers()[header::CONTENT_TYPE]);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(
+        "uint32s\n0\n1\n2\n",
+        String::from_utf8(body.to_vec()).unwrap()
+    );
+
+    // Falls back to JSON if the statement fails.
+    let query = Query(SqlQuery {
+        sql: Some("select * from not_exists".to_string()),
+        database: None,
+        format: Some(ResponseFormat::Ndjson),
+    });
+    let SqlResponse::Json(Json(json)) = http_handler::sql(
+        State(ApiState {
+            sql_handler,
+            script_handler: None,
+        }),
+        query,
+        axum::Extension(UserInfo::default()),
+    )
+    .await
+    else {
+        unreachable!()
+    };
+    assert!(!json.success());
+}
+
 #[tokio::test]
 async fn test_metrics() {
     metric::init_default_metrics_recorder();
@@ -122,30 +187,31 @@ def test(n):
     assert!(json.output().is_none());
 }
 
-fn create_script_query() -> Query<script_handler::ScriptQuery> {
-    Query(script_handler::ScriptQuery {
+fn create_script_query() -> Query<ScriptQuery> {
+    Query(ScriptQuery {
         name: Some("test".to_string()),
     })
 }
 
-fn create_invalid_script_query() -> Query<script_handler::ScriptQuery> {
-    Query(script_handler::ScriptQuery { name: None })
+fn create_invalid_script_query() -> Query<ScriptQuery> {
+    Query(ScriptQuery { name: None })
 }
 
-fn create_query() -> Query<http_handler::SqlQuery> {
-    Query(http_handler::SqlQuery {
+fn create_query() -> Query<SqlQuery> {
+    Query(SqlQuery {
         sql: Some("select sum(uint32s) from numbers limit 20".to_string()),
         database: None,
+        format: None,
     })
 }
 
 /// Currently the payload of response should be simply an empty json "{}";
 #[tokio::test]
 async fn test_health() {
-    let expected_json = http_handler::HealthResponse {};
+    let expected_json = HealthResponse {};
     let expected_json_str = "{}".to_string();
 
-    let query = http_handler::HealthQuery {};
+    let query = HealthQuery {};
     let Json(json) = http_handler::health(Query(query)).await;
     assert_eq!(json, expected_json);
     assert_eq!(
@@ -153,3 +219,44 @@ async fn test_health() {
         expected_json_str
     );
 }
+
+struct MockReadinessHandler {
+    checks: BTreeMap<String, bool>,
+}
+
+impl ReadinessHandler for MockReadinessHandler {
+    fn readiness(&self) -> BTreeMap<String, bool> {
+        self.checks.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_ready() {
+    // Always ready without readiness handler.
+    let (status, Json(json)) = http_handler::ready(State(None)).await;
+    assert_eq!(StatusCode::OK, status);
+    assert_eq!(
+        ReadinessResponse {
+            ready: true,
+            checks: BTreeMap::new(),
+        },
+        json
+    );
+
+    let checks = BTreeMap::from([
+        ("catalog_loaded".to_string(), true),
+        ("heartbeat_established".to_string(), false),
+    ]);
+    let handler: ReadinessHandlerRef = Arc::new(MockReadinessHandler {
+        checks: checks.clone(),
+    });
+    let (status, Json(json)) = http_handler::ready(State(Some(handler))).await;
+    assert_eq!(StatusCode::SERVICE_UNAVAILABLE, status);
+    assert_eq!(
+        ReadinessResponse {
+            ready: false,
+            checks
+        },
+        json
+    );
+}
@@ -20,6 +20,7 @@ use catalog::local::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaPr
 use catalog::{CatalogList, CatalogProvider, SchemaProvider};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
+use datatypes::schema::Schema;
 use query::{QueryEngineFactory, QueryEngineRef};
 use servers::error::Result;
 use servers::query_handler::{
@@ -63,10 +64,26 @@ impl SqlQueryHandler for DummyInstance {
 
     async fn do_statement_query(
         &self,
-        _stmt: sql::statements::statement::Statement,
-        _query_ctx: QueryContextRef,
+        stmt: sql::statements::statement::Statement,
+        query_ctx: QueryContextRef,
     ) -> Result<Output> {
-        unimplemented!()
+        let plan = self
+            .query_engine
+            .statement_to_plan(stmt, query_ctx)
+            .unwrap();
+        Ok(self.query_engine.execute(&plan).await.unwrap())
+    }
+
+    async fn do_describe(
+        &self,
+        stmt: sql::statements::statement::Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Option<Schema>> {
+        let plan = self
+            .query_engine
+            .statement_to_plan(stmt, query_ctx)
+            .unwrap();
+        Ok(Some(plan.schema().unwrap()))
     }
 
     fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
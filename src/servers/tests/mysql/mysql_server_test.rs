@@ -138,6 +138,7 @@ async fn test_server_prefer_secure_client_plain() -> Result<()> {
         mode: servers::tls::TlsMode::Prefer,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
 
     let client_tls = false;
@@ -151,6 +152,7 @@ async fn test_server_prefer_secure_client_secure() -> Result<()> {
         mode: servers::tls::TlsMode::Prefer,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
 
     let client_tls = true;
@@ -164,6 +166,7 @@ async fn test_server_require_secure_client_secure() -> Result<()> {
         mode: servers::tls::TlsMode::Require,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
 
     let client_tls = true;
@@ -177,6 +180,7 @@ async fn test_server_required_secure_client_plain() -> Result<()> {
         mode: servers::tls::TlsMode::Require,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
 
     let client_tls = false;
@@ -243,6 +247,52 @@ async fn do_test_query_all_datatypes(server_tls: TlsOption, client_tls: bool) ->
     Ok(())
 }
 
+#[tokio::test]
+async fn test_prepared_statement() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+    let TestingData {
+        column_schemas,
+        columns,
+        ..
+    } = all_datatype_testing_data();
+    let schema = Arc::new(Schema::new(column_schemas));
+    let recordbatch = RecordBatch::new(schema, columns).unwrap();
+    let table = MemTable::new("all_datatypes", recordbatch);
+
+    let mysql_server = create_mysql_server(table, Default::default())?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = mysql_server.start(listening).await.unwrap();
+    let mut connection = create_connection(server_addr.port(), false).await.unwrap();
+
+    // Results of prepared statements are written in the binary protocol.
+    let row: Option<(i64, u64, f64, Option<String>)> = connection
+        .exec_first(
+            "SELECT int64s, uint64s, float64s, strings FROM all_datatypes WHERE int64s = ?",
+            (i64::MAX,),
+        )
+        .await
+        .unwrap();
+    assert_eq!(Some((i64::MAX, u64::MAX, 10.654321, None)), row);
+
+    let stmt = connection
+        .prep("SELECT int8s FROM all_datatypes WHERE strings = ? OR int16s = ?")
+        .await
+        .unwrap();
+    assert_eq!(2, stmt.num_params());
+    for (string, expected) in [("hola", vec![i8::MIN, i8::MAX]), ("it's", vec![i8::MAX])] {
+        let rows: Vec<i8> = connection.exec(&stmt, (string, i16::MAX)).await.unwrap();
+        assert_eq!(expected, rows);
+    }
+    connection.close(stmt).await.unwrap();
+
+    // Float parameters are bound as float literals.
+    let result: mysql_async::Result<Vec<i8>> = connection
+        .exec("SELECT int8s FROM all_datatypes WHERE int8s = ?", (1.5,))
+        .await;
+    assert!(result.unwrap().is_empty());
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_query_concurrently() -> Result<()> {
     common_telemetry::init_default_ut_logging();
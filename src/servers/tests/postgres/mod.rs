@@ -29,6 +29,7 @@ use servers::postgres::PostgresServer;
 use servers::server::Server;
 use servers::tls::TlsOption;
 use table::test_util::MemTable;
+use tokio_postgres::types::Type;
 use tokio_postgres::{Client, Error as PgError, NoTls, SimpleQueryMessage};
 
 use crate::create_testing_instance;
@@ -197,6 +198,7 @@ async fn test_server_secure_prefer_client_plain() -> Result<()> {
         mode: servers::tls::TlsMode::Prefer,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
 
     let client_tls = false;
@@ -212,6 +214,7 @@ async fn test_server_secure_require_client_plain() -> Result<()> {
         mode: servers::tls::TlsMode::Require,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
     let server_port = start_test_server(server_tls).await?;
     let r = create_plain_connection(server_port, false).await;
@@ -227,6 +230,7 @@ async fn test_server_secure_require_client_secure() -> Result<()> {
         mode: servers::tls::TlsMode::Require,
         cert_path: "tests/ssl/server.crt".to_owned(),
         key_path: "tests/ssl/server.key".to_owned(),
+        ..Default::default()
     };
 
     let client_tls = true;
@@ -252,6 +256,40 @@ async fn test_using_db() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_extended_query() -> Result<()> {
+    let server_port = start_test_server(TlsOption::default()).await?;
+    let client = create_plain_connection(server_port, false).await.unwrap();
+
+    let stmt = client
+        .prepare_typed(
+            "SELECT uint32s FROM numbers WHERE uint32s > $1 ORDER BY uint32s LIMIT 3",
+            &[Type::INT8],
+        )
+        .await
+        .unwrap();
+    assert_eq!(&[Type::INT8], stmt.params());
+    assert_eq!(1, stmt.columns().len());
+    assert_eq!("uint32s", stmt.columns()[0].name());
+    assert_eq!(&Type::INT8, stmt.columns()[0].type_());
+
+    // The statement is executed with values bound to the parameters.
+    for (param, expected) in [(10i64, vec![11i64, 12, 13]), (98, vec![99]), (99, vec![])] {
+        let rows = client.query(&stmt, &[&param]).await.unwrap();
+        let values = rows.iter().map(|row| row.get(0)).collect::<Vec<i64>>();
+        assert_eq!(expected, values);
+    }
+
+    // Parameters of unspecified types are sent as text.
+    let rows = client
+        .query("SELECT uint32s FROM numbers WHERE uint32s = $1", &[&"42"])
+        .await
+        .unwrap();
+    assert_eq!(1, rows.len());
+    assert_eq!(42i64, rows[0].get::<_, i64>(0));
+    Ok(())
+}
+
 async fn start_test_server(server_tls: TlsOption) -> Result<u16> {
     common_telemetry::init_default_ut_logging();
     let table = MemTable::default_numbers_table();
@@ -12,17 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use arc_swap::ArcSwapOption;
 use common_telemetry::info;
+use common_time::TimeZone;
 
 pub type QueryContextRef = Arc<QueryContext>;
+
+/// Name of the variable of the session time zone.
+pub const TIME_ZONE_VARIABLE: &str = "time_zone";
 pub type ConnInfoRef = Arc<ConnInfo>;
 
 pub struct QueryContext {
     current_schema: ArcSwapOption<String>,
+    current_user: ArcSwapOption<UserInfo>,
+    time_zone: ArcSwapOption<TimeZone>,
+    /// Variables set by `SET`, keyed by the lowercase names.
+    variables: RwLock<HashMap<String, String>>,
 }
 
 impl Default for QueryContext {
@@ -39,13 +48,52 @@ impl QueryContext {
     pub fn new() -> Self {
         Self {
             current_schema: ArcSwapOption::new(None),
+            current_user: ArcSwapOption::new(None),
+            time_zone: ArcSwapOption::new(None),
+            variables: RwLock::new(HashMap::new()),
         }
     }
 
     pub fn with_current_schema(schema: String) -> Self {
-        Self {
-            current_schema: ArcSwapOption::new(Some(Arc::new(schema))),
-        }
+        let ctx = Self::new();
+        ctx.current_schema.store(Some(Arc::new(schema)));
+        ctx
+    }
+
+    /// Returns the authenticated user running the query, `None` if the query is not
+    /// from a client session, e.g. the internal queries.
+    pub fn current_user(&self) -> Option<Arc<UserInfo>> {
+        self.current_user.load_full()
+    }
+
+    pub fn set_current_user(&self, user: UserInfo) {
+        self.current_user.store(Some(Arc::new(user)));
+    }
+
+    /// Returns the time zone in which the timestamps are rendered, `None` means UTC.
+    pub fn time_zone(&self) -> Option<TimeZone> {
+        self.time_zone.load().as_deref().copied()
+    }
+
+    pub fn set_time_zone(&self, time_zone: Option<TimeZone>) {
+        self.time_zone.store(time_zone.map(Arc::new));
+    }
+
+    /// Returns the value of the session variable, the name is case insensitive.
+    pub fn variable(&self, name: &str) -> Option<String> {
+        self.variables
+            .read()
+            .unwrap()
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
+    pub fn set_variable(&self, name: &str, value: String) {
+        let _ = self
+            .variables
+            .write()
+            .unwrap()
+            .insert(name.to_lowercase(), value);
     }
 
     pub fn current_schema(&self) -> Option<String> {
@@ -115,7 +163,7 @@ pub enum Channel {
 
 #[cfg(test)]
 mod test {
-    use crate::context::{Channel, UserInfo};
+    use crate::context::{Channel, QueryContext, UserInfo};
     use crate::Session;
 
     #[test]
@@ -134,4 +182,17 @@ mod test {
         );
         assert_eq!(session.conn_info().client_host.port(), 9000);
     }
+
+    #[test]
+    fn test_session_variables() {
+        let ctx = QueryContext::new();
+        assert!(ctx.time_zone().is_none());
+        let tz = "+08:00".parse().unwrap();
+        ctx.set_time_zone(Some(tz));
+        assert_eq!(Some(tz), ctx.time_zone());
+
+        assert!(ctx.variable("sql_mode").is_none());
+        ctx.set_variable("SQL_MODE", "ANSI".to_string());
+        assert_eq!(Some("ANSI".to_string()), ctx.variable("sql_mode"));
+    }
 }
@@ -46,6 +46,7 @@ impl Session {
         self.user_info.load().clone()
     }
     pub fn set_user_info(&self, user_info: UserInfo) {
+        self.query_ctx.set_current_user(user_info.clone());
         self.user_info.store(Arc::new(user_info));
     }
 }
@@ -100,6 +100,13 @@ pub enum Error {
     #[snafu(display("Invalid table name: {}", name))]
     InvalidTableName { name: String, backtrace: Backtrace },
 
+    #[snafu(display("Invalid table option '{}': {}", option, reason))]
+    InvalidTableOption {
+        option: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Invalid default constraint, column: {}, source: {}", column, source))]
     InvalidDefault {
         column: String,
@@ -107,6 +114,31 @@ pub enum Error {
         source: datatypes::error::Error,
     },
 
+    #[snafu(display(
+        "Invalid value of column {} in row {} of VALUES, source: {}",
+        column_name,
+        row,
+        source
+    ))]
+    InsertValue {
+        column_name: String,
+        row: usize,
+        #[snafu(source(from(Error, Box::new)))]
+        source: Box<Error>,
+    },
+
+    #[snafu(display("Column {} is not nullable", column_name))]
+    ColumnNotNull {
+        column_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Column {} has no default value", column_name))]
+    NoDefaultValue {
+        column_name: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Unsupported ALTER TABLE statement: {}", msg))]
     UnsupportedAlterTableStatement { msg: String, backtrace: Backtrace },
 
@@ -141,9 +173,13 @@ impl ErrorExt for Error {
             | SqlTypeNotSupported { .. }
             | InvalidDefault { .. } => StatusCode::InvalidSyntax,
 
-            InvalidDatabaseName { .. } | ColumnTypeMismatch { .. } | InvalidTableName { .. } => {
-                StatusCode::InvalidArguments
-            }
+            InvalidDatabaseName { .. }
+            | ColumnTypeMismatch { .. }
+            | InvalidTableName { .. }
+            | InvalidTableOption { .. }
+            | ColumnNotNull { .. }
+            | NoDefaultValue { .. } => StatusCode::InvalidArguments,
+            InsertValue { source, .. } => source.status_code(),
             UnsupportedAlterTableStatement { .. } => StatusCode::InvalidSyntax,
             SerializeColumnDefaultConstraint { source, .. } => source.status_code(),
             ConvertToGrpcDataType { source, .. } => source.status_code(),
@@ -22,9 +22,9 @@ use crate::error::{
     self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu, TokenizerSnafu,
 };
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropTable, DropTask, DropUser};
 use crate::statements::explain::Explain;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
+use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowNodes, ShowTables};
 use crate::statements::statement::Statement;
 use crate::statements::table_idents_to_full_name;
 
@@ -96,12 +96,22 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::INSERT => self.parse_insert(),
 
+                    Keyword::DELETE => self.parse_delete(),
+
+                    Keyword::COPY => self.parse_copy(),
+
                     Keyword::SELECT | Keyword::WITH | Keyword::VALUES => self.parse_query(),
 
                     Keyword::ALTER => self.parse_alter(),
 
                     Keyword::DROP => self.parse_drop(),
 
+                    Keyword::GRANT | Keyword::REVOKE => self.parse_grant(),
+
+                    Keyword::SET => self.parse_set(),
+
+                    Keyword::TRUNCATE => self.parse_truncate(),
+
                     Keyword::USE => {
                         self.parser.next_token();
 
@@ -141,7 +151,16 @@ impl<'a> ParserContext<'a> {
             self.parse_show_databases()
         } else if self.matches_keyword(Keyword::TABLES) {
             self.parser.next_token();
-            self.parse_show_tables()
+            self.parse_show_tables(false)
+        } else if self.consume_token("FULL") {
+            if self.matches_keyword(Keyword::TABLES) {
+                self.parser.next_token();
+                self.parse_show_tables(true)
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
+        } else if self.consume_token("NODES") {
+            Ok(Statement::ShowNodes(ShowNodes))
         } else if self.consume_token("CREATE") {
             if self.consume_token("TABLE") {
                 self.parse_show_create_table()
@@ -174,12 +193,14 @@ impl<'a> ParserContext<'a> {
         }))
     }
 
-    fn parse_show_tables(&mut self) -> Result<Statement> {
+    /// Parses `SHOW [FULL] TABLES` statement, the `SHOW [FULL] TABLES` is already consumed.
+    fn parse_show_tables(&mut self, full: bool) -> Result<Statement> {
         let database = match self.parser.peek_token() {
             Token::EOF | Token::SemiColon => {
                 return Ok(Statement::ShowTables(ShowTables {
                     kind: ShowKind::All,
                     database: None,
+                    full,
                 }));
             }
 
@@ -239,7 +260,11 @@ impl<'a> ParserContext<'a> {
             _ => return self.unsupported(self.peek_token_as_string()),
         };
 
-        Ok(Statement::ShowTables(ShowTables { kind, database }))
+        Ok(Statement::ShowTables(ShowTables {
+            kind,
+            database,
+            full,
+        }))
     }
 
     /// Parses DESCRIBE statements
@@ -290,6 +315,12 @@ impl<'a> ParserContext<'a> {
 
     fn parse_drop(&mut self) -> Result<Statement> {
         self.parser.next_token();
+        if self.consume_token("USER") {
+            return self.parse_drop_user();
+        }
+        if self.consume_token("TASK") {
+            return self.parse_drop_task();
+        }
         if !self.matches_keyword(Keyword::TABLE) {
             return self.unsupported(self.peek_token_as_string());
         }
@@ -318,6 +349,34 @@ impl<'a> ParserContext<'a> {
         }))
     }
 
+    /// Parses `DROP USER` statement, the `DROP USER` is already consumed.
+    fn parse_drop_user(&mut self) -> Result<Statement> {
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self
+            .parser
+            .parse_literal_string()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a user name",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Statement::DropUser(DropUser { name, if_exists }))
+    }
+
+    /// Parses `DROP TASK` statement, the `DROP TASK` is already consumed.
+    fn parse_drop_task(&mut self) -> Result<Statement> {
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a task name",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Statement::DropTask(DropTask { name, if_exists }))
+    }
+
     // Report unexpected token
     pub(crate) fn expected<T>(&self, expected: &str, found: Token) -> Result<T> {
         Err(ParserError::ParserError(format!(
@@ -454,6 +513,7 @@ mod tests {
             Statement::ShowTables(ShowTables {
                 kind: ShowKind::All,
                 database: None,
+                full: false,
             })
         );
     }
@@ -473,6 +533,7 @@ mod tests {
                     quote_style: None,
                 }),
                 database: None,
+                full: false,
             })
         );
 
@@ -489,6 +550,7 @@ mod tests {
                     quote_style: None,
                 }),
                 database: Some(_),
+                full: false,
             })
         );
     }
@@ -505,6 +567,7 @@ mod tests {
             Statement::ShowTables(ShowTables {
                 kind: ShowKind::Where(sqlparser::ast::Expr::Like { .. }),
                 database: None,
+                full: false,
             })
         );
 
@@ -518,10 +581,28 @@ mod tests {
             Statement::ShowTables(ShowTables {
                 kind: ShowKind::Where(sqlparser::ast::Expr::Like { .. }),
                 database: Some(_),
+                full: false,
             })
         );
     }
 
+    #[test]
+    pub fn test_show_full_tables() {
+        let sql = "SHOW FULL TABLES IN test_db LIKE 'test%'";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+
+        let Statement::ShowTables(show) = stmts.remove(0) else {
+            unreachable!()
+        };
+        assert!(show.full);
+        assert_eq!(Some("test_db".to_string()), show.database);
+        assert_eq!("LIKE 'test%'", show.kind.to_string());
+
+        let sql = "SHOW FULL DATABASES";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
     #[test]
     pub fn test_explain() {
         let sql = "EXPLAIN select * from foo";
@@ -615,4 +696,50 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    pub fn test_drop_user() {
+        let sql = "DROP USER greptime";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropUser(DropUser {
+                name: "greptime".to_string(),
+                if_exists: false,
+            })
+        );
+
+        let sql = "DROP USER IF EXISTS 'greptime'";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::DropUser(DropUser {
+                name: "greptime".to_string(),
+                if_exists: true,
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_drop_task() {
+        let sql = "DROP TASK downsample";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(
+            stmts.pop().unwrap(),
+            Statement::DropTask(DropTask {
+                name,
+                if_exists: false,
+            }) if name.to_string() == "downsample"
+        );
+
+        let sql = "DROP TASK IF EXISTS public.downsample";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(
+            stmts.pop().unwrap(),
+            Statement::DropTask(DropTask {
+                name,
+                if_exists: true,
+            }) if name.to_string() == "public.downsample"
+        );
+    }
 }
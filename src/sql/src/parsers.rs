@@ -13,6 +13,11 @@
 // limitations under the License.
 
 mod alter_parser;
+pub(crate) mod copy_parser;
 pub(crate) mod create_parser;
+pub(crate) mod delete_parser;
+pub(crate) mod grant_parser;
 pub(crate) mod insert_parser;
 pub(crate) mod query_parser;
+pub(crate) mod set_parser;
+pub(crate) mod truncate_parser;
@@ -52,6 +52,7 @@ impl<'a> ParserContext<'a> {
                 )));
             }
         } else if parser.parse_keyword(Keyword::RENAME) {
+            let _ = parser.parse_keyword(Keyword::TO);
             let new_table_name_obj = parser.parse_object_name()?;
             let new_table_name = match &new_table_name_obj.0[..] {
                 [table] => table.value.clone(),
@@ -150,26 +151,30 @@ mod tests {
             .to_string()
             .contains("expect keyword ADD or DROP or RENAME after ALTER TABLE"));
 
-        let sql = "ALTER TABLE test_table RENAME table_t";
-        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
-        assert_eq!(1, result.len());
-
-        let statement = result.remove(0);
-        assert_matches!(statement, Statement::Alter { .. });
-        match statement {
-            Statement::Alter(alter_table) => {
-                assert_eq!("test_table", alter_table.table_name().0[0].value);
-
-                let alter_operation = alter_table.alter_operation();
-                assert_matches!(alter_operation, AlterTableOperation::RenameTable { .. });
-                match alter_operation {
-                    AlterTableOperation::RenameTable { new_table_name } => {
-                        assert_eq!("table_t", new_table_name);
+        for sql in [
+            "ALTER TABLE test_table RENAME table_t",
+            "ALTER TABLE test_table RENAME TO table_t",
+        ] {
+            let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+            assert_eq!(1, result.len());
+
+            let statement = result.remove(0);
+            assert_matches!(statement, Statement::Alter { .. });
+            match statement {
+                Statement::Alter(alter_table) => {
+                    assert_eq!("test_table", alter_table.table_name().0[0].value);
+
+                    let alter_operation = alter_table.alter_operation();
+                    assert_matches!(alter_operation, AlterTableOperation::RenameTable { .. });
+                    match alter_operation {
+                        AlterTableOperation::RenameTable { new_table_name } => {
+                            assert_eq!("table_t", new_table_name);
+                        }
+                        _ => unreachable!(),
                     }
-                    _ => unreachable!(),
                 }
+                _ => unreachable!(),
             }
-            _ => unreachable!(),
         }
     }
 }
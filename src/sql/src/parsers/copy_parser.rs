@@ -0,0 +1,270 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+
+use mito::external::{FileFormat, EXTERNAL_FORMAT_KEY};
+use snafu::{ensure, ResultExt};
+use sqlparser::ast::{ObjectName, Value};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::copy::{CopyQueryTo, CopyTable};
+use crate::statements::query::Query;
+use crate::statements::statement::Statement;
+
+const MAX_FILE_SIZE_OPTION: &str = "max_file_size";
+const TIME_BUCKET_OPTION: &str = "time_bucket";
+
+/// COPY statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_copy(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        if self.parser.consume_token(&Token::LParen) {
+            let query = self
+                .parser
+                .parse_query()
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            self.parser
+                .expect_token(&Token::RParen)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            self.parser
+                .expect_keyword(Keyword::TO)
+                .context(error::SyntaxSnafu { sql: self.sql })?;
+            return self.parse_copy_to(Query::try_from(query)?);
+        }
+
+        let table_name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a table name or a query in parentheses",
+                actual: self.peek_token_as_string(),
+            })?;
+        if self.parser.parse_keyword(Keyword::TO) {
+            let query = self.query_all_rows(&table_name)?;
+            return self.parse_copy_to(query);
+        }
+
+        self.parser
+            .expect_keyword(Keyword::FROM)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let location = self.parse_copy_location()?;
+        let mut options = self.parse_copy_options(&[EXTERNAL_FORMAT_KEY])?;
+        let format = options.remove(EXTERNAL_FORMAT_KEY);
+        ensure_file_format(format.as_deref(), &location)?;
+
+        Ok(Statement::Copy(CopyTable {
+            table_name,
+            location,
+            format,
+        }))
+    }
+
+    fn parse_copy_to(&mut self, query: Query) -> Result<Statement> {
+        let location = self.parse_copy_location()?;
+        let mut options = self.parse_copy_options(&[
+            EXTERNAL_FORMAT_KEY,
+            MAX_FILE_SIZE_OPTION,
+            TIME_BUCKET_OPTION,
+        ])?;
+        let format = options.remove(EXTERNAL_FORMAT_KEY);
+        // The files are in parquet by default if the location is a directory.
+        if !location.ends_with('/') || format.is_some() {
+            ensure_file_format(format.as_deref(), &location)?;
+        }
+
+        Ok(Statement::CopyTo(CopyQueryTo {
+            query: Box::new(query),
+            location,
+            format,
+            max_file_size: options.remove(MAX_FILE_SIZE_OPTION),
+            time_bucket: options.remove(TIME_BUCKET_OPTION),
+        }))
+    }
+
+    fn parse_copy_location(&mut self) -> Result<String> {
+        self.parser
+            .parse_literal_string()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a quoted location of the files",
+                actual: self.peek_token_as_string(),
+            })
+    }
+
+    /// Parses options in `WITH`, only the options in `allowed` could be specified, and
+    /// their values must be quoted strings.
+    fn parse_copy_options(&mut self, allowed: &[&str]) -> Result<HashMap<String, String>> {
+        let options = self
+            .parser
+            .parse_options(Keyword::WITH)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let mut values = HashMap::with_capacity(options.len());
+        for option in options {
+            let name = option.name.value.to_lowercase();
+            ensure!(
+                allowed.contains(&name.as_str()),
+                error::InvalidTableOptionSnafu {
+                    option: &name,
+                    reason: "option is not supported by COPY",
+                }
+            );
+            let Value::SingleQuotedString(value) = option.value else {
+                return error::InvalidTableOptionSnafu {
+                    option: &name,
+                    reason: format!("expect a quoted string, found: {}", option.value),
+                }
+                .fail();
+            };
+            ensure!(
+                values.insert(name.clone(), value).is_none(),
+                error::InvalidTableOptionSnafu {
+                    option: &name,
+                    reason: "option is specified more than once",
+                }
+            );
+        }
+        Ok(values)
+    }
+
+    /// Returns the query `SELECT * FROM <table_name>`.
+    fn query_all_rows(&self, table_name: &ObjectName) -> Result<Query> {
+        let sql = format!("SELECT * FROM {table_name}");
+        let query = Parser::new(&GenericDialect {})
+            .try_with_sql(&sql)
+            .and_then(|mut parser| parser.parse_query())
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        Query::try_from(query)
+    }
+}
+
+fn ensure_file_format(format: Option<&str>, location: &str) -> Result<()> {
+    let file_format = match format {
+        Some(format) => FileFormat::from_name(format),
+        None => FileFormat::from_path(location),
+    };
+    ensure!(
+        file_format.is_some(),
+        error::InvalidTableOptionSnafu {
+            option: EXTERNAL_FORMAT_KEY,
+            reason: "expect csv or parquet",
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_copy() {
+        let sql = "COPY my_schema.monitor FROM 'data/monitor.csv'";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Copy(copy) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("my_schema.monitor", copy.table_name.to_string());
+        assert_eq!("data/monitor.csv", copy.location);
+        assert_eq!(None, copy.format);
+
+        let sql = "copy monitor from 'data/' with (format = 'parquet')";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Copy(copy) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("data/", copy.location);
+        assert_eq!(Some("parquet".to_string()), copy.format);
+    }
+
+    #[test]
+    fn test_parse_invalid_copy() {
+        let parse = |sql| ParserContext::create_with_dialect(sql, &GenericDialect {});
+
+        assert!(parse("COPY monitor FROM data").is_err());
+        assert_matches!(
+            parse("COPY monitor FROM 'data/'"),
+            Err(error::Error::InvalidTableOption { option, .. }) if option == "format"
+        );
+        assert_matches!(
+            parse("COPY monitor FROM 'data/a.csv' WITH (delimiter = ';')"),
+            Err(error::Error::InvalidTableOption { option, .. }) if option == "delimiter"
+        );
+        assert_matches!(
+            parse("COPY monitor FROM 'data/a.csv' WITH (max_file_size = '1MB')"),
+            Err(error::Error::InvalidTableOption { option, .. }) if option == "max_file_size"
+        );
+    }
+
+    #[test]
+    fn test_parse_copy_to() {
+        let sql = "COPY (SELECT host, cpu FROM monitor WHERE ts > 1000) TO 'export/' \
+                   WITH (format = 'csv', max_file_size = '64MB', time_bucket = '1d')";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::CopyTo(copy) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT host, cpu FROM monitor WHERE ts > 1000",
+            copy.query.inner.to_string()
+        );
+        assert_eq!("export/", copy.location);
+        assert_eq!(Some("csv".to_string()), copy.format);
+        assert_eq!(Some("64MB".to_string()), copy.max_file_size);
+        assert_eq!(Some("1d".to_string()), copy.time_bucket);
+
+        let sql = "copy my_schema.monitor to 'export/monitor.parquet'";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::CopyTo(copy) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT * FROM my_schema.monitor",
+            copy.query.inner.to_string()
+        );
+        assert_eq!(None, copy.format);
+        assert_eq!(None, copy.max_file_size);
+
+        // Exports to a directory in parquet by default.
+        let sql = "copy monitor to 'export/'";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_ok());
+    }
+
+    #[test]
+    fn test_parse_invalid_copy_to() {
+        let parse = |sql| ParserContext::create_with_dialect(sql, &GenericDialect {});
+
+        assert!(parse("COPY (SELECT * FROM monitor) FROM 'data/a.csv'").is_err());
+        assert!(parse("COPY (SELECT * FROM monitor TO 'data/a.csv'").is_err());
+        assert_matches!(
+            parse("COPY monitor TO 'export/a.json'"),
+            Err(error::Error::InvalidTableOption { option, .. }) if option == "format"
+        );
+        assert_matches!(
+            parse("COPY monitor TO 'export/' WITH (compression = 'zstd')"),
+            Err(error::Error::InvalidTableOption { option, .. }) if option == "compression"
+        );
+        assert_matches!(
+            parse("COPY monitor TO 'export/' WITH (max_file_size = 1024)"),
+            Err(error::Error::InvalidTableOption { option, .. }) if option == "max_file_size"
+        );
+    }
+}
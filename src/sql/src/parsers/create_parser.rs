@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::collections::HashSet;
 
 use itertools::Itertools;
 use mito::engine;
+use mito::external::{self, FileFormat};
 use once_cell::sync::Lazy;
 use snafu::{ensure, OptionExt, ResultExt};
 use sqlparser::ast::ColumnOption::NotNull;
@@ -23,18 +25,27 @@ use sqlparser::ast::{ColumnOptionDef, DataType, Value};
 use sqlparser::dialect::keywords::Keyword;
 use sqlparser::parser::IsOptional::Mandatory;
 use sqlparser::tokenizer::{Token, Word};
+use store_api::storage::{DedupPolicy, ParquetOptions, SstFormat};
 
-use crate::ast::{ColumnDef, Ident, TableConstraint, Value as SqlValue};
+use crate::ast::{ColumnDef, Ident, SqlOption, TableConstraint, Value as SqlValue};
 use crate::error::{self, InvalidTimeIndexSnafu, Result, SyntaxSnafu};
 use crate::parser::ParserContext;
 use crate::statements::create::{
-    CreateDatabase, CreateTable, PartitionEntry, Partitions, TIME_INDEX,
+    CreateDatabase, CreateTable, CreateTask, CreateUser, PartitionEntry, Partitions, TIME_INDEX,
 };
+use crate::statements::query::Query;
 use crate::statements::statement::Statement;
 use crate::statements::{sql_data_type_to_concrete_data_type, sql_value_to_value};
 
 const ENGINE: &str = "ENGINE";
 const MAXVALUE: &str = "MAXVALUE";
+/// Number of regions of the table, a positive integer.
+const REGIONS_OPTION: &str = "regions";
+/// Format of SST files of the table, a quoted format name like 'parquet'.
+const SST_FORMAT_OPTION: &str = engine::SST_FORMAT_KEY;
+/// How rows with the same keys and timestamp are deduplicated, a quoted policy name like
+/// 'keep_all'.
+const DEDUP_POLICY_OPTION: &str = engine::DEDUP_POLICY_KEY;
 
 static LESS: Lazy<Token> = Lazy::new(|| Token::make_keyword("LESS"));
 static THAN: Lazy<Token> = Lazy::new(|| Token::make_keyword("THAN"));
@@ -44,10 +55,14 @@ impl<'a> ParserContext<'a> {
     pub(crate) fn parse_create(&mut self) -> Result<Statement> {
         match self.parser.peek_token() {
             Token::Word(w) => match w.keyword {
-                Keyword::TABLE => self.parse_create_table(),
+                Keyword::TABLE | Keyword::EXTERNAL => self.parse_create_table(),
 
                 Keyword::DATABASE => self.parse_create_database(),
 
+                _ if w.value.eq_ignore_ascii_case("USER") => self.parse_create_user(),
+
+                _ if w.value.eq_ignore_ascii_case("TASK") => self.parse_create_task(),
+
                 _ => self.unsupported(w.to_string()),
             },
             unexpected => self.unsupported(unexpected.to_string()),
@@ -71,12 +86,100 @@ impl<'a> ParserContext<'a> {
         }))
     }
 
-    fn parse_create_table(&mut self) -> Result<Statement> {
+    fn parse_create_user(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let name = self
+            .parser
+            .parse_literal_string()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a user name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        if !self.consume_token("IDENTIFIED") {
+            return self.expected("IDENTIFIED", self.parser.peek_token());
+        }
+        self.parser
+            .expect_keyword(Keyword::BY)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let password = match self.parser.next_token() {
+            Token::SingleQuotedString(password) => password,
+            unexpected => return self.expected("a quoted password", unexpected),
+        };
+
+        Ok(Statement::CreateUser(CreateUser {
+            name,
+            password,
+            if_not_exists,
+        }))
+    }
+
+    fn parse_create_task(&mut self) -> Result<Statement> {
         self.parser.next_token();
         let if_not_exists =
             self.parser
                 .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
 
+        let name = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a task name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        if !self.consume_token("EVERY") {
+            return self.expected("EVERY", self.parser.peek_token());
+        }
+        let interval = match self.parser.next_token() {
+            Token::SingleQuotedString(interval) => interval,
+            unexpected => return self.expected("a quoted interval", unexpected),
+        };
+
+        self.parser
+            .expect_keyword(Keyword::INTO)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let sink_table = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a table name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        self.parser
+            .expect_keyword(Keyword::AS)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let query = self
+            .parser
+            .parse_query()
+            .context(SyntaxSnafu { sql: self.sql })?;
+
+        Ok(Statement::CreateTask(CreateTask {
+            name,
+            interval,
+            sink_table,
+            query: Box::new(Query::try_from(query)?),
+            if_not_exists,
+        }))
+    }
+
+    fn parse_create_table(&mut self) -> Result<Statement> {
+        let external = self.parser.parse_keyword(Keyword::EXTERNAL);
+        self.parser
+            .expect_keyword(Keyword::TABLE)
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
         let table_name = self
             .parser
             .parse_object_name()
@@ -105,6 +208,7 @@ impl<'a> ParserContext<'a> {
             options,
             table_id: 0, // table id is assigned by catalog manager
             partitions,
+            external,
         };
         validate_create(&create_table)?;
 
@@ -403,8 +507,160 @@ impl<'a> ParserContext<'a> {
 
 fn validate_create(create_table: &CreateTable) -> Result<()> {
     if let Some(partitions) = &create_table.partitions {
+        ensure!(
+            !create_table.external,
+            error::InvalidSqlSnafu {
+                msg: "external table can't be partitioned",
+            }
+        );
         validate_partitions(&create_table.columns, partitions)?;
     }
+    if create_table.external {
+        validate_external_options(&create_table.options)?;
+    } else {
+        validate_options(&create_table.options)?;
+    }
+    Ok(())
+}
+
+/// Validates options of external tables, only the location and format of the files are
+/// allowed.
+fn validate_external_options(options: &[SqlOption]) -> Result<()> {
+    let mut location = None;
+    let mut format = None;
+    for option in options {
+        let name = option.name.value.to_lowercase();
+        let value = match &option.value {
+            SqlValue::SingleQuotedString(s) => s,
+            value => {
+                return error::InvalidTableOptionSnafu {
+                    option: &name,
+                    reason: format!("expect a quoted string, found: {value}"),
+                }
+                .fail();
+            }
+        };
+        let slot = match name.as_str() {
+            external::EXTERNAL_LOCATION_KEY => &mut location,
+            external::EXTERNAL_FORMAT_KEY => &mut format,
+            _ => {
+                return error::InvalidTableOptionSnafu {
+                    option: &name,
+                    reason: "option is not supported by external tables",
+                }
+                .fail();
+            }
+        };
+        ensure!(
+            slot.replace(value).is_none(),
+            error::InvalidTableOptionSnafu {
+                option: &name,
+                reason: "option is specified more than once",
+            }
+        );
+    }
+
+    let location = location.filter(|location| !location.is_empty()).context(
+        error::InvalidTableOptionSnafu {
+            option: external::EXTERNAL_LOCATION_KEY,
+            reason: "location of the files is required",
+        },
+    )?;
+    let format = match format {
+        Some(format) => FileFormat::from_name(format),
+        None => FileFormat::from_path(location),
+    };
+    ensure!(
+        format.is_some(),
+        error::InvalidTableOptionSnafu {
+            option: external::EXTERNAL_FORMAT_KEY,
+            reason: "expect csv or parquet",
+        }
+    );
+    Ok(())
+}
+
+/// Validates table options in `WITH`, so an invalid option is reported with its name
+/// when parsing the statement instead of failing while creating the table.
+fn validate_options(options: &[SqlOption]) -> Result<()> {
+    let mut names = HashSet::with_capacity(options.len());
+    for option in options {
+        let name = option.name.value.to_lowercase();
+        ensure!(
+            names.insert(name.clone()),
+            error::InvalidTableOptionSnafu {
+                option: &name,
+                reason: "option is specified more than once",
+            }
+        );
+
+        match name.as_str() {
+            REGIONS_OPTION => {
+                let is_positive_integer = matches!(
+                    &option.value,
+                    SqlValue::Number(n, _) if n.parse::<u32>().map(|n| n > 0).unwrap_or(false)
+                );
+                ensure!(
+                    is_positive_integer,
+                    error::InvalidTableOptionSnafu {
+                        option: &name,
+                        reason: format!("expect a positive integer, found: {}", option.value),
+                    }
+                );
+            }
+            SST_FORMAT_OPTION => {
+                let is_valid_format = matches!(
+                    &option.value,
+                    SqlValue::SingleQuotedString(s) if SstFormat::from_name(s).is_some()
+                );
+                ensure!(
+                    is_valid_format,
+                    error::InvalidTableOptionSnafu {
+                        option: &name,
+                        reason: format!("unknown SST format: {}", option.value),
+                    }
+                );
+            }
+            DEDUP_POLICY_OPTION => {
+                let is_valid_policy = matches!(
+                    &option.value,
+                    SqlValue::SingleQuotedString(s) if DedupPolicy::from_name(s).is_some()
+                );
+                ensure!(
+                    is_valid_policy,
+                    error::InvalidTableOptionSnafu {
+                        option: &name,
+                        reason: format!(
+                            "expect 'last_write_wins' or 'keep_all', found: {}",
+                            option.value
+                        ),
+                    }
+                );
+            }
+            _ if engine::is_sst_option(&name) => {
+                let value = match &option.value {
+                    SqlValue::SingleQuotedString(s) => s.clone(),
+                    value => value.to_string(),
+                };
+                if let Err(reason) =
+                    engine::set_parquet_option(&mut ParquetOptions::default(), &name, &value)
+                {
+                    return error::InvalidTableOptionSnafu {
+                        option: &name,
+                        reason,
+                    }
+                    .fail();
+                }
+            }
+            _ => {
+                return error::InvalidTableOptionSnafu {
+                    option: &name,
+                    reason: "unknown option",
+                }
+                .fail();
+            }
+        }
+    }
     Ok(())
 }
 
@@ -473,8 +729,8 @@ fn ensure_value_lists_strictly_increased<'a>(
                 (false, false) => {
                     let column_name = &column.name.value;
                     let cdt = sql_data_type_to_concrete_data_type(&column.data_type)?;
-                    let x = sql_value_to_value(column_name, &cdt, x)?;
-                    let y = sql_value_to_value(column_name, &cdt, y)?;
+                    let x = sql_value_to_value(column_name, &cdt, x, None)?;
+                    let y = sql_value_to_value(column_name, &cdt, y, None)?;
                     match x.cmp(&y) {
                         Ordering::Less => break,
                         Ordering::Equal => equal_tuples += 1,
@@ -585,6 +841,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_create_user() {
+        let sql = "CREATE USER greptime IDENTIFIED BY 'secret'";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::CreateUser(CreateUser {
+                name: "greptime".to_string(),
+                password: "secret".to_string(),
+                if_not_exists: false,
+            }),
+            stmts[0]
+        );
+        assert!(!format!("{:?}", stmts[0]).contains("secret"));
+
+        let sql = "create user if not exists 'greptime' identified by 'secret'";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(
+            &stmts[0],
+            Statement::CreateUser(CreateUser {
+                if_not_exists: true,
+                ..
+            })
+        );
+
+        let sql = "CREATE USER greptime";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        let sql = "CREATE USER greptime IDENTIFIED BY secret";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
+    #[test]
+    fn test_parse_create_task() {
+        let sql = "CREATE TASK downsample EVERY '5m' INTO metrics_5m AS \
+                   SELECT time_bucket('5m', ts) AS ts, host, avg(cpu) AS cpu FROM metrics \
+                   GROUP BY time_bucket('5m', ts), host";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        match &stmts[0] {
+            Statement::CreateTask(c) => {
+                assert_eq!("downsample", c.name.to_string());
+                assert_eq!("5m", c.interval);
+                assert_eq!("metrics_5m", c.sink_table.to_string());
+                assert_eq!(
+                    "metrics",
+                    c.query
+                        .table_names()
+                        .unwrap()
+                        .iter()
+                        .map(|name| name.to_string())
+                        .join(",")
+                );
+                assert!(!c.if_not_exists);
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "create task if not exists public.t every '1h' into s as select * from m";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(
+            &stmts[0],
+            Statement::CreateTask(CreateTask {
+                if_not_exists: true,
+                ..
+            })
+        );
+
+        let sql = "CREATE TASK t INTO s AS SELECT * FROM m";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        let sql = "CREATE TASK t EVERY 5m INTO s AS SELECT * FROM m";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        let sql = "CREATE TASK t EVERY '5m' INTO s SELECT * FROM m";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
     #[test]
     fn test_validate_create() {
         let sql = r"
@@ -1038,4 +1367,91 @@ ENGINE=mito";
         assert!(result.is_err());
         assert_matches!(result, Err(crate::error::Error::InvalidTimeIndex { .. }));
     }
+
+    #[test]
+    fn test_validate_table_options() {
+        let parse = |options: &str| {
+            let sql = format!(
+                "create table demo(ts timestamp time index, cpu double) engine=mito with({options})"
+            );
+            ParserContext::create_with_dialect(&sql, &GenericDialect {})
+        };
+
+        assert!(parse("regions=1").is_ok());
+        assert!(parse("REGIONS=3").is_ok());
+
+        let assert_invalid_option = |options: &str, expect: &str| {
+            let result = parse(options);
+            assert_matches!(
+                result,
+                Err(crate::error::Error::InvalidTableOption { option, .. }) if option == expect
+            );
+        };
+        assert_invalid_option("regions=0", "regions");
+        assert_invalid_option("regions='abc'", "regions");
+        assert_invalid_option("regions=1, regions=2", "regions");
+        assert_invalid_option("regions=1, ttl='7d'", "ttl");
+
+        assert!(parse("sst_format='parquet'").is_ok());
+        assert!(parse("regions=1, sst_format='arrow_ipc'").is_ok());
+        assert_invalid_option("sst_format='orc'", "sst_format");
+        assert_invalid_option("sst_format=1", "sst_format");
+
+        assert!(parse("dedup_policy='keep_all'").is_ok());
+        assert!(parse("dedup_policy='LAST_WRITE_WINS'").is_ok());
+        assert_invalid_option("dedup_policy='none'", "dedup_policy");
+
+        assert!(parse(
+            "sst_compression='lz4', sst_column_compression='cpu:none', sst_dictionary=false, sst_row_group_size=1024"
+        )
+        .is_ok());
+        assert_invalid_option("sst_compression='gzip'", "sst_compression");
+        assert_invalid_option("sst_column_compression='cpu'", "sst_column_compression");
+        assert_invalid_option("sst_dictionary='on'", "sst_dictionary");
+        assert_invalid_option("sst_row_group_size=0", "sst_row_group_size");
+    }
+
+    #[test]
+    fn test_parse_create_external_table() {
+        let parse = |options: &str| {
+            let sql = format!(
+                "create external table demo(ts timestamp time index, cpu double) with({options})"
+            );
+            ParserContext::create_with_dialect(&sql, &GenericDialect {})
+        };
+
+        let stmts = parse("location='data/demo.csv'").unwrap();
+        assert_matches!(
+            &stmts[0],
+            Statement::CreateTable(CreateTable { external: true, .. })
+        );
+        assert!(parse("location='data/', format='parquet'").is_ok());
+        assert!(parse("LOCATION='data/', FORMAT='CSV'").is_ok());
+
+        let assert_invalid_option = |options: &str, expect: &str| {
+            let result = parse(options);
+            assert_matches!(
+                result,
+                Err(crate::error::Error::InvalidTableOption { option, .. }) if option == expect
+            );
+        };
+        assert_invalid_option("format='csv'", "location");
+        assert_invalid_option("location=''", "location");
+        assert_invalid_option("location='data/'", "format");
+        assert_invalid_option("location='data/a.csv', format='json'", "format");
+        assert_invalid_option("location='data/a.csv', regions=2", "regions");
+        assert_invalid_option("location='a.csv', location='b.csv'", "location");
+
+        // Options of external tables are not allowed by normal tables.
+        let sql = "create table demo(ts timestamp time index) with(location='data/demo.csv')";
+        assert_matches!(
+            ParserContext::create_with_dialect(sql, &GenericDialect {}),
+            Err(crate::error::Error::InvalidTableOption { option, .. }) if option == "location"
+        );
+
+        let sql = "create external table demo(ts timestamp time index, a int) \
+                   partition by range columns (a) (partition r0 values less than (maxvalue)) \
+                   with(location='data/demo.csv')";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
 }
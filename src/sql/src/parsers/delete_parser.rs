@@ -0,0 +1,78 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::ast::{Statement as SpStatement, TableFactor};
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::delete::Delete;
+use crate::statements::statement::Statement;
+
+/// DELETE statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_delete(&mut self) -> Result<Statement> {
+        self.parser.next_token();
+        let spstatement = self
+            .parser
+            .parse_delete()
+            .context(error::SyntaxSnafu { sql: self.sql })?;
+
+        match spstatement {
+            SpStatement::Delete {
+                table_name: TableFactor::Table { name, .. },
+                selection,
+                ..
+            } => Ok(Statement::Delete(Box::new(Delete {
+                table_name: name,
+                selection,
+            }))),
+            unexp => error::UnsupportedSnafu {
+                sql: self.sql.to_string(),
+                keyword: unexp.to_string(),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    #[test]
+    pub fn test_parse_delete() {
+        let sql = "DELETE FROM my_schema.monitor WHERE ts BETWEEN 1000 AND 2000";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let Statement::Delete(delete) = result.remove(0) else {
+            unreachable!()
+        };
+        assert_eq!("my_schema.monitor", delete.table_name.to_string());
+        assert_eq!(
+            "ts BETWEEN 1000 AND 2000",
+            delete.selection.unwrap().to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_parse_invalid_delete() {
+        let sql = "DELETE FROM WHERE ts > 1000"; // intentionally a bad sql
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+        assert!(result.is_err(), "result is: {result:?}");
+    }
+}
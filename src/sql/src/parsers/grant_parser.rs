@@ -0,0 +1,157 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use snafu::ResultExt;
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result, SyntaxSnafu};
+use crate::parser::ParserContext;
+use crate::statements::grant::{Grant, Privilege};
+use crate::statements::statement::Statement;
+
+/// GRANT and REVOKE statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_grant(&mut self) -> Result<Statement> {
+        let revoke = self.parser.parse_keyword(Keyword::REVOKE);
+        if !revoke {
+            self.parser
+                .expect_keyword(Keyword::GRANT)
+                .context(SyntaxSnafu { sql: self.sql })?;
+        }
+
+        let privileges = self.parse_privileges()?;
+        self.parser
+            .expect_keyword(Keyword::ON)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let _ = self
+            .parser
+            .parse_one_of_keywords(&[Keyword::SCHEMA, Keyword::DATABASE]);
+        let schema = self
+            .parser
+            .parse_object_name()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a schema name",
+                actual: self.peek_token_as_string(),
+            })?;
+        let (catalog_name, schema_name) = match &schema.0[..] {
+            [schema] => (DEFAULT_CATALOG_NAME.to_string(), schema.value.clone()),
+            [catalog, schema] => (catalog.value.clone(), schema.value.clone()),
+            _ => {
+                return error::InvalidDatabaseNameSnafu {
+                    name: schema.to_string(),
+                }
+                .fail()
+            }
+        };
+
+        let grantee_keyword = if revoke { Keyword::FROM } else { Keyword::TO };
+        self.parser
+            .expect_keyword(grantee_keyword)
+            .context(SyntaxSnafu { sql: self.sql })?;
+        let user = self
+            .parser
+            .parse_literal_string()
+            .context(error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a user name",
+                actual: self.peek_token_as_string(),
+            })?;
+
+        Ok(Statement::Grant(Grant {
+            privileges,
+            catalog_name,
+            schema_name,
+            user,
+            revoke,
+        }))
+    }
+
+    /// Parses `ALL [PRIVILEGES]` or a list of `READ`, `WRITE` and `DDL`.
+    fn parse_privileges(&mut self) -> Result<Vec<Privilege>> {
+        if self.parser.parse_keyword(Keyword::ALL) {
+            let _ = self.parser.parse_keyword(Keyword::PRIVILEGES);
+            return Ok(Privilege::ALL.to_vec());
+        }
+
+        let mut privileges = Vec::new();
+        loop {
+            let privilege = match self.parser.next_token() {
+                Token::Word(w) if w.value.eq_ignore_ascii_case("READ") => Privilege::Read,
+                Token::Word(w) if w.value.eq_ignore_ascii_case("WRITE") => Privilege::Write,
+                Token::Word(w) if w.value.eq_ignore_ascii_case("DDL") => Privilege::Ddl,
+                unexpected => return self.expected("READ, WRITE, DDL or ALL", unexpected),
+            };
+            privileges.push(privilege);
+            if !self.parser.consume_token(&Token::Comma) {
+                return Ok(privileges);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    fn parse_grant(sql: &str) -> Grant {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::Grant(grant) = stmts.remove(0) else {
+            unreachable!()
+        };
+        grant
+    }
+
+    #[test]
+    fn test_parse_grant() {
+        assert_eq!(
+            Grant {
+                privileges: vec![Privilege::Read, Privilege::Write],
+                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+                schema_name: "my_schema".to_string(),
+                user: "foo".to_string(),
+                revoke: false,
+            },
+            parse_grant("GRANT READ, WRITE ON my_schema TO foo")
+        );
+
+        let grant = parse_grant("grant all privileges on schema my_catalog.my_schema to 'foo'");
+        assert_eq!(Privilege::ALL.to_vec(), grant.privileges);
+        assert_eq!("my_catalog", grant.catalog_name);
+        assert_eq!("my_schema", grant.schema_name);
+
+        let grant = parse_grant("REVOKE DDL ON DATABASE my_schema FROM foo");
+        assert_eq!(vec![Privilege::Ddl], grant.privileges);
+        assert!(grant.revoke);
+    }
+
+    #[test]
+    fn test_parse_invalid_grant() {
+        for sql in [
+            "GRANT SELECT ON my_schema TO foo",
+            "GRANT READ ON my_schema FROM foo",
+            "REVOKE READ ON my_schema TO foo",
+            "GRANT READ ON a.b.c TO foo",
+            "GRANT READ ON my_schema",
+        ] {
+            let result = ParserContext::create_with_dialect(sql, &GenericDialect {});
+            assert!(result.is_err(), "sql: {sql}, result: {result:?}");
+        }
+    }
+}
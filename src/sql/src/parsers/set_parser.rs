@@ -0,0 +1,105 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::set::SetVariable;
+use crate::statements::statement::Statement;
+
+/// SET statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_set(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        // All variables are session scoped.
+        let _ = self
+            .parser
+            .parse_one_of_keywords(&[Keyword::SESSION, Keyword::LOCAL]);
+
+        let name = if self.parser.parse_keywords(&[Keyword::TIME, Keyword::ZONE]) {
+            "time_zone".to_string()
+        } else {
+            let name = self
+                .parser
+                .parse_object_name()
+                .context(error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a variable name",
+                    actual: self.peek_token_as_string(),
+                })?;
+            if !self.parser.consume_token(&Token::Eq) && !self.parser.parse_keyword(Keyword::TO) {
+                return self.expected("= or TO", self.parser.peek_token());
+            }
+            name.to_string().to_lowercase()
+        };
+
+        let value = match self.parser.next_token() {
+            Token::SingleQuotedString(s) | Token::DoubleQuotedString(s) => s,
+            Token::Number(n, _) => n,
+            Token::Word(w) => w.value,
+            unexpected => return self.expected("a variable value", unexpected),
+        };
+
+        Ok(Statement::SetVariable(SetVariable { name, value }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    fn parse_set(sql: &str) -> SetVariable {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        let Statement::SetVariable(set) = stmts.remove(0) else {
+            unreachable!()
+        };
+        set
+    }
+
+    fn set_variable(name: &str, value: &str) -> SetVariable {
+        SetVariable {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_set() {
+        assert_eq!(
+            set_variable("time_zone", "+08:00"),
+            parse_set("SET time_zone = '+08:00'")
+        );
+        assert_eq!(
+            set_variable("time_zone", "Asia/Shanghai"),
+            parse_set("SET TIME ZONE 'Asia/Shanghai'")
+        );
+        assert_eq!(
+            set_variable("time_zone", "UTC"),
+            parse_set("SET SESSION TIME_ZONE TO UTC")
+        );
+        assert_eq!(
+            set_variable("sql_select_limit", "100"),
+            parse_set("set local SQL_SELECT_LIMIT = 100")
+        );
+
+        let sql = "SET time_zone";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+}
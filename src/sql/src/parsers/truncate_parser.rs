@@ -0,0 +1,75 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::{ensure, ResultExt};
+use sqlparser::dialect::keywords::Keyword;
+
+use crate::error::{self, InvalidTableNameSnafu, Result};
+use crate::parser::ParserContext;
+use crate::statements::statement::Statement;
+use crate::statements::truncate::TruncateTable;
+
+/// TRUNCATE statement parser implementation
+impl<'a> ParserContext<'a> {
+    pub(crate) fn parse_truncate(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let _ = self.parser.parse_keyword(Keyword::TABLE);
+
+        let table_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_name.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_name.to_string()
+            }
+        );
+
+        Ok(Statement::Truncate(TruncateTable { table_name }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    #[test]
+    pub fn test_parse_truncate() {
+        for sql in [
+            "TRUNCATE TABLE my_schema.monitor",
+            "TRUNCATE my_schema.monitor",
+        ] {
+            let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+            assert_eq!(1, result.len());
+
+            let Statement::Truncate(truncate) = result.remove(0) else {
+                unreachable!()
+            };
+            assert_eq!("my_schema.monitor", truncate.table_name.to_string());
+        }
+    }
+
+    #[test]
+    pub fn test_parse_invalid_truncate() {
+        let result = ParserContext::create_with_dialect("TRUNCATE TABLE", &GenericDialect {});
+        assert!(result.is_err(), "result is: {result:?}");
+    }
+}
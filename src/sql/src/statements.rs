@@ -13,30 +13,36 @@
 // limitations under the License.
 
 pub mod alter;
+pub mod copy;
 pub mod create;
+pub mod delete;
 pub mod describe;
 pub mod drop;
 pub mod explain;
+pub mod grant;
 pub mod insert;
+pub mod placeholder;
 pub mod query;
+pub mod set;
 pub mod show;
 pub mod statement;
+pub mod truncate;
 use std::str::FromStr;
 
 use api::helper::ColumnDataTypeWrapper;
 use common_base::bytes::Bytes;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
-use common_time::Timestamp;
+use common_time::{TimeZone, Timestamp};
 use datatypes::data_type::DataType;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
-use datatypes::types::DateTimeType;
+use datatypes::types::{DateTimeType, JsonType, UuidType};
 use datatypes::value::Value;
 use snafu::{ensure, ResultExt};
 
 use crate::ast::{
     ColumnDef, ColumnOption, ColumnOptionDef, DataType as SqlDataType, Expr, ObjectName,
-    Value as SqlValue,
+    UnaryOperator, Value as SqlValue,
 };
 use crate::error::{
     self, ColumnTypeMismatchSnafu, ConvertToGrpcDataTypeSnafu, ParseSqlValueSnafu, Result,
@@ -77,9 +83,10 @@ fn parse_string_to_value(
     column_name: &str,
     s: String,
     data_type: &ConcreteDataType,
+    time_zone: Option<&TimeZone>,
 ) -> Result<Value> {
     ensure!(
-        data_type.is_stringifiable(),
+        data_type.is_stringifiable() || data_type.is_json(),
         ColumnTypeMismatchSnafu {
             column_name,
             expect: data_type.clone(),
@@ -89,6 +96,20 @@ fn parse_string_to_value(
 
     match data_type {
         ConcreteDataType::String(_) => Ok(Value::String(s.into())),
+        ConcreteDataType::Json(_) => match JsonType::parse_str(&s) {
+            Ok(doc) => Ok(Value::Binary(Bytes::from(doc))),
+            Err(e) => ParseSqlValueSnafu {
+                msg: format!("Failed to parse {s} to Json value, {e}"),
+            }
+            .fail(),
+        },
+        ConcreteDataType::Uuid(_) => match UuidType::parse_str(&s) {
+            Ok(uuid) => Ok(Value::Binary(Bytes::from(uuid.as_slice()))),
+            Err(e) => ParseSqlValueSnafu {
+                msg: format!("Failed to parse {s} to Uuid value, {e}"),
+            }
+            .fail(),
+        },
         ConcreteDataType::Date(_) => {
             if let Ok(date) = common_time::date::Date::from_str(&s) {
                 Ok(Value::Date(date))
@@ -110,7 +131,7 @@ fn parse_string_to_value(
             }
         }
         ConcreteDataType::Timestamp(t) => {
-            if let Ok(ts) = Timestamp::from_str(&s) {
+            if let Ok(ts) = Timestamp::from_str_with_time_zone(&s, time_zone) {
                 Ok(Value::Timestamp(Timestamp::new(
                     ts.convert_to(t.unit()),
                     t.unit(),
@@ -198,10 +219,13 @@ where
     }
 }
 
+/// Converts a sql value into a value of the `data_type`, timestamp strings without an
+/// explicit offset are in the `time_zone`, or in the local time zone if it's `None`.
 pub fn sql_value_to_value(
     column_name: &str,
     data_type: &ConcreteDataType,
     sql_val: &SqlValue,
+    time_zone: Option<&TimeZone>,
 ) -> Result<Value> {
     Ok(match sql_val {
         SqlValue::Number(n, _) => sql_number_to_value(data_type, n)?,
@@ -219,13 +243,48 @@ pub fn sql_value_to_value(
             (*b).into()
         }
         SqlValue::DoubleQuotedString(s) | SqlValue::SingleQuotedString(s) => {
-            parse_string_to_value(column_name, s.to_owned(), data_type)?
+            parse_string_to_value(column_name, s.to_owned(), data_type, time_zone)?
         }
         SqlValue::HexStringLiteral(s) => parse_hex_string(s)?,
         _ => todo!("Other sql value"),
     })
 }
 
+/// Evaluates a constant expression, a literal value optionally with signs and
+/// parentheses, e.g. `-1` and `(2.5)`.
+fn constant_expr_to_value(
+    column_name: &str,
+    data_type: &ConcreteDataType,
+    expr: &Expr,
+) -> Result<Value> {
+    match expr {
+        Expr::Value(v) => sql_value_to_value(column_name, data_type, v, None),
+        Expr::Nested(expr)
+        | Expr::UnaryOp {
+            op: UnaryOperator::Plus,
+            expr,
+        } => constant_expr_to_value(column_name, data_type, expr),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: inner,
+        } => match &**inner {
+            // Negates the literal instead of the parsed value, so the minimum value of
+            // signed integers is allowed.
+            Expr::Value(SqlValue::Number(n, _)) => sql_number_to_value(data_type, &format!("-{n}")),
+            _ => UnsupportedDefaultValueSnafu {
+                column_name,
+                expr: expr.clone(),
+            }
+            .fail(),
+        },
+        _ => UnsupportedDefaultValueSnafu {
+            column_name,
+            expr: expr.clone(),
+        }
+        .fail(),
+    }
+}
+
 fn parse_column_default_constraint(
     column_name: &str,
     data_type: &ConcreteDataType,
@@ -235,23 +294,17 @@ fn parse_column_default_constraint(
         .iter()
         .find(|o| matches!(o.option, ColumnOption::Default(_)))
     {
-        let default_constraint = match &opt.option {
-            ColumnOption::Default(Expr::Value(v)) => {
-                ColumnDefaultConstraint::Value(sql_value_to_value(column_name, data_type, v)?)
-            }
-            ColumnOption::Default(Expr::Function(func)) => {
-                // Always use lowercase for function expression
-                ColumnDefaultConstraint::Function(format!("{func}").to_lowercase())
-            }
-            ColumnOption::Default(expr) => {
-                return UnsupportedDefaultValueSnafu {
-                    column_name,
-                    expr: expr.clone(),
+        let default_constraint =
+            match &opt.option {
+                ColumnOption::Default(Expr::Function(func)) => {
+                    // Always use lowercase for function expression
+                    ColumnDefaultConstraint::Function(format!("{func}").to_lowercase())
                 }
-                .fail();
-            }
-            _ => unreachable!(),
-        };
+                ColumnOption::Default(expr) => ColumnDefaultConstraint::Value(
+                    constant_expr_to_value(column_name, data_type, expr)?,
+                ),
+                _ => unreachable!(),
+            };
 
         Ok(Some(default_constraint))
     } else {
@@ -305,6 +358,7 @@ pub fn sql_column_def_to_grpc_column_def(col: ColumnDef) -> Result<api::v1::Colu
         datatype: data_type,
         is_nullable,
         default_constraint: default_constraint.unwrap_or_default(),
+        semantic_type: None,
     })
 }
 
@@ -322,6 +376,7 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
         SqlDataType::Boolean => Ok(ConcreteDataType::boolean_datatype()),
         SqlDataType::Date => Ok(ConcreteDataType::date_datatype()),
         SqlDataType::Varbinary(_) => Ok(ConcreteDataType::binary_datatype()),
+        SqlDataType::Uuid => Ok(ConcreteDataType::uuid_datatype()),
         SqlDataType::Custom(obj_name, _) => match &obj_name.0[..] {
             [type_name] => {
                 if type_name
@@ -329,6 +384,11 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
                     .eq_ignore_ascii_case(DateTimeType::default().name())
                 {
                     Ok(ConcreteDataType::datetime_datatype())
+                } else if type_name
+                    .value
+                    .eq_ignore_ascii_case(JsonType::default().name())
+                {
+                    Ok(ConcreteDataType::json_datatype())
                 } else {
                     error::SqlTypeNotSupportedSnafu {
                         t: data_type.clone(),
@@ -398,6 +458,11 @@ mod tests {
             SqlDataType::Custom(ObjectName(vec![Ident::new("datetime")]), vec![]),
             ConcreteDataType::datetime_datatype(),
         );
+        check_type(
+            SqlDataType::Custom(ObjectName(vec![Ident::new("JSON")]), vec![]),
+            ConcreteDataType::json_datatype(),
+        );
+        check_type(SqlDataType::Uuid, ConcreteDataType::uuid_datatype());
         check_type(
             SqlDataType::Timestamp(None, TimezoneInfo::None),
             ConcreteDataType::timestamp_millisecond_datatype(),
@@ -425,29 +490,29 @@ mod tests {
         let sql_val = SqlValue::Null;
         assert_eq!(
             Value::Null,
-            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val).unwrap()
+            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val, None).unwrap()
         );
 
         let sql_val = SqlValue::Boolean(true);
         assert_eq!(
             Value::Boolean(true),
-            sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val).unwrap()
+            sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val, None).unwrap()
         );
 
         let sql_val = SqlValue::Number("3.0".to_string(), false);
         assert_eq!(
             Value::Float64(OrderedFloat(3.0)),
-            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val).unwrap()
+            sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val, None).unwrap()
         );
 
         let sql_val = SqlValue::Number("3.0".to_string(), false);
-        let v = sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::boolean_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(format!("{v:?}")
             .contains("Fail to parse number 3.0, invalid column type: Boolean(BooleanType)"));
 
         let sql_val = SqlValue::Boolean(true);
-        let v = sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::float64_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(
             format!("{v:?}").contains(
@@ -457,11 +522,12 @@ mod tests {
         );
 
         let sql_val = SqlValue::HexStringLiteral("48656c6c6f20776f726c6421".to_string());
-        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val).unwrap();
+        let v =
+            sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None).unwrap();
         assert_eq!(Value::Binary(Bytes::from(b"Hello world!".as_slice())), v);
 
         let sql_val = SqlValue::HexStringLiteral("9AF".to_string());
-        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(
             format!("{v:?}").contains("odd number of digits"),
@@ -469,17 +535,68 @@ mod tests {
         );
 
         let sql_val = SqlValue::HexStringLiteral("AG".to_string());
-        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val);
+        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val, None);
         assert!(v.is_err());
         assert!(format!("{v:?}").contains("invalid character"), "v is {v:?}",);
     }
 
+    #[test]
+    pub fn test_parse_json_literal() {
+        let value = sql_value_to_value(
+            "payload",
+            &ConcreteDataType::json_datatype(),
+            &SqlValue::SingleQuotedString(r#"{"a": [1, 2]}"#.to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Binary(Bytes::from(br#"{"a":[1,2]}"#.as_slice())),
+            value
+        );
+
+        let v = sql_value_to_value(
+            "payload",
+            &ConcreteDataType::json_datatype(),
+            &SqlValue::SingleQuotedString("{".to_string()),
+            None,
+        );
+        assert!(format!("{v:?}").contains("Failed to parse { to Json value"));
+    }
+
+    #[test]
+    pub fn test_parse_uuid_literal() {
+        let value = sql_value_to_value(
+            "trace_id",
+            &ConcreteDataType::uuid_datatype(),
+            &SqlValue::SingleQuotedString("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Binary(Bytes::from(
+                UuidType::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8")
+                    .unwrap()
+                    .as_slice()
+            )),
+            value
+        );
+
+        let v = sql_value_to_value(
+            "trace_id",
+            &ConcreteDataType::uuid_datatype(),
+            &SqlValue::SingleQuotedString("67e55044".to_string()),
+            None,
+        );
+        assert!(format!("{v:?}").contains("Failed to parse 67e55044 to Uuid value"));
+    }
+
     #[test]
     pub fn test_parse_date_literal() {
         let value = sql_value_to_value(
             "date",
             &ConcreteDataType::date_datatype(),
             &SqlValue::DoubleQuotedString("2022-02-22".to_string()),
+            None,
         )
         .unwrap();
         assert_eq!(ConcreteDataType::date_datatype(), value.data_type());
@@ -496,6 +613,7 @@ mod tests {
             "datetime_col",
             &ConcreteDataType::datetime_datatype(),
             &SqlValue::DoubleQuotedString("2022-02-22 00:01:03".to_string()),
+            None,
         )
         .unwrap();
         assert_eq!(ConcreteDataType::datetime_datatype(), value.data_type());
@@ -512,6 +630,7 @@ mod tests {
             "datetime_col",
             &ConcreteDataType::datetime_datatype(),
             &SqlValue::DoubleQuotedString("2022-02-22 00:01:61".to_string()),
+            None,
         )
         .is_err());
     }
@@ -522,6 +641,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_millisecond_datatype(),
+            None,
         )
         .unwrap()
         {
@@ -538,6 +658,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Second),
+            None,
         )
         .unwrap()
         {
@@ -554,6 +675,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Microsecond),
+            None,
         )
         .unwrap()
         {
@@ -570,6 +692,7 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08:00".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+            None,
         )
         .unwrap()
         {
@@ -586,10 +709,40 @@ mod tests {
             "timestamp_col",
             "2022-02-22T00:01:01+08".to_string(),
             &ConcreteDataType::timestamp_datatype(TimeUnit::Nanosecond),
+            None,
         )
         .is_err());
     }
 
+    #[test]
+    fn test_parse_timestamp_literal_with_time_zone() {
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        let value = sql_value_to_value(
+            "ts",
+            &ConcreteDataType::timestamp_millisecond_datatype(),
+            &SqlValue::SingleQuotedString("2022-02-22 00:01:01".to_string()),
+            Some(&time_zone),
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_millisecond(1645459261000)),
+            value
+        );
+
+        // The explicit offset takes precedence.
+        let value = sql_value_to_value(
+            "ts",
+            &ConcreteDataType::timestamp_millisecond_datatype(),
+            &SqlValue::SingleQuotedString("2022-02-21 16:01:01Z".to_string()),
+            Some(&time_zone),
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_millisecond(1645459261000)),
+            value
+        );
+    }
+
     #[test]
     pub fn test_parse_column_default_constraint() {
         let bool_value = sqlparser::ast::Value::Boolean(true);
@@ -615,6 +768,47 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_parse_column_default_constant_expr() {
+        let parse = |expr: Expr, data_type: ConcreteDataType| {
+            let opts = vec![ColumnOptionDef {
+                name: None,
+                option: ColumnOption::Default(expr),
+            }];
+            parse_column_default_constraint("col", &data_type, &opts)
+        };
+        let number = |n: &str| Expr::Value(SqlValue::Number(n.to_string(), false));
+        let negative = |n: &str| Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: Box::new(number(n)),
+        };
+
+        assert_eq!(
+            Some(ColumnDefaultConstraint::Value(Value::Int8(i8::MIN))),
+            parse(negative("128"), ConcreteDataType::int8_datatype()).unwrap()
+        );
+        assert_eq!(
+            Some(ColumnDefaultConstraint::Value(Value::Float64(
+                OrderedFloat(-2.5)
+            ))),
+            parse(
+                Expr::Nested(Box::new(negative("2.5"))),
+                ConcreteDataType::float64_datatype()
+            )
+            .unwrap()
+        );
+        assert!(parse(negative("1"), ConcreteDataType::uint32_datatype()).is_err());
+
+        let not_constant = Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: Box::new(Expr::Identifier(Ident::new("a"))),
+        };
+        assert_matches!(
+            parse(not_constant, ConcreteDataType::int32_datatype()).unwrap_err(),
+            error::Error::UnsupportedDefaultValue { .. }
+        );
+    }
+
     #[test]
     pub fn test_sql_column_def_to_grpc_column_def() {
         // test basic
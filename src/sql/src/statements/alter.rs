@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api::v1::{alter_expr, AddColumn, AlterExpr, DropColumn};
+use api::v1::{alter_expr, AddColumn, AlterExpr, DropColumn, RenameTable};
 use sqlparser::ast::{ColumnDef, Ident, ObjectName, TableConstraint};
 
 use crate::error::UnsupportedAlterTableStatementSnafu;
@@ -49,7 +49,7 @@ pub enum AlterTableOperation {
     AddColumn { column_def: ColumnDef },
     /// `DROP COLUMN <name>`
     DropColumn { name: Ident },
-    /// `RENAME <new_table_name>`
+    /// `RENAME [TO] <new_table_name>`
     RenameTable { new_table_name: String },
 }
 
@@ -80,12 +80,8 @@ impl TryFrom<AlterTable> for AlterExpr {
                     drop_columns: vec![DropColumn { name: name.value }],
                 })
             }
-            AlterTableOperation::RenameTable { .. } => {
-                // TODO update proto to support alter table name
-                return UnsupportedAlterTableStatementSnafu {
-                    msg: "rename table not supported yet",
-                }
-                .fail();
+            AlterTableOperation::RenameTable { new_table_name } => {
+                alter_expr::Kind::RenameTable(RenameTable { new_table_name })
             }
         };
         let expr = AlterExpr {
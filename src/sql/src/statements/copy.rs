@@ -0,0 +1,47 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use sqlparser::ast::ObjectName;
+
+use crate::statements::query::Query;
+
+/// `COPY <table> FROM '<location>' [WITH (format = 'csv')]`
+///
+/// Imports rows from the files at the location in the object store into the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyTable {
+    pub table_name: ObjectName,
+    /// Path of the files, a path ending with `/` refers to all files under the directory.
+    pub location: String,
+    /// Format of the files, inferred from the extension of the location if absent.
+    pub format: Option<String>,
+}
+
+/// `COPY { <table> | (<query>) } TO '<location>' [WITH (format = 'parquet', ...)]`
+///
+/// Exports the results of the query into files at the location in the object store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyQueryTo {
+    /// Query whose results are exported, `COPY <table> TO` exports all rows of the table.
+    pub query: Box<Query>,
+    /// Path of the file, or the directory of the files if it ends with `/`.
+    pub location: String,
+    /// Format of the files, inferred from the extension of the location if absent.
+    pub format: Option<String>,
+    /// Approximate max size of each file, e.g. `64MB`, the results are split into
+    /// multiple files if present.
+    pub max_file_size: Option<String>,
+    /// Interval of the time buckets, e.g. `1d`, the results are partitioned into
+    /// directories by the time bucket of their first timestamp column if present.
+    pub time_bucket: Option<String>,
+}
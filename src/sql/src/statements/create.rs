@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
+
 use crate::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint, Value as SqlValue};
+use crate::statements::query::Query;
 
 /// Time index name, used in table constraints.
 pub const TIME_INDEX: &str = "__time_index";
@@ -30,6 +33,9 @@ pub struct CreateTable {
     /// Table options in `WITH`.
     pub options: Vec<SqlOption>,
     pub partitions: Option<Partitions>,
+    /// Whether the table is an external table created by `CREATE EXTERNAL TABLE`, whose
+    /// rows are read from the files specified by the options.
+    pub external: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -48,3 +54,36 @@ pub struct PartitionEntry {
 pub struct CreateDatabase {
     pub name: ObjectName,
 }
+
+/// `CREATE TASK [IF NOT EXISTS] <name> EVERY '<interval>' INTO <sink table> AS <query>`
+///
+/// The task runs the query over the data arrived since its last run once per interval,
+/// and writes the results into the sink table.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CreateTask {
+    pub name: ObjectName,
+    /// Interval between the runs, e.g. `5m`.
+    pub interval: String,
+    pub sink_table: ObjectName,
+    pub query: Box<Query>,
+    pub if_not_exists: bool,
+}
+
+/// `CREATE USER [IF NOT EXISTS] <name> IDENTIFIED BY '<password>'`
+#[derive(PartialEq, Eq, Clone)]
+pub struct CreateUser {
+    pub name: String,
+    pub password: String,
+    pub if_not_exists: bool,
+}
+
+// The password is never printed, e.g. in the logs of the statements.
+impl fmt::Debug for CreateUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateUser")
+            .field("name", &self.name)
+            .field("password", &"<redacted>")
+            .field("if_not_exists", &self.if_not_exists)
+            .finish()
+    }
+}
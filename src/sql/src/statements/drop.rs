@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::ast::ObjectName;
+
 /// DROP TABLE statement.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DropTable {
@@ -20,6 +22,20 @@ pub struct DropTable {
     pub table_name: String,
 }
 
+/// `DROP USER [IF EXISTS] <name>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropUser {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+/// `DROP TASK [IF EXISTS] <name>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropTask {
+    pub name: ObjectName,
+    pub if_exists: bool,
+}
+
 impl DropTable {
     /// Creates a statement for `DROP TABLE`
     pub fn new(catalog_name: String, schema_name: String, table_name: String) -> Self {
@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sqlparser::ast::Statement as SpStatement;
+use sqlparser::ast::{ObjectName, Statement as SpStatement};
 
 use crate::error::Error;
+use crate::statements::query::collect_query;
 
 /// Explain statement.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,6 +23,23 @@ pub struct Explain {
     pub inner: SpStatement,
 }
 
+impl Explain {
+    /// Returns the names of the tables referenced by the explained query, `None` if the
+    /// explained statement is not a query or the tables can't be collected, see
+    /// [Query::table_names](crate::statements::query::Query::table_names).
+    pub fn table_names(&self) -> Option<Vec<&ObjectName>> {
+        let SpStatement::Explain { statement, .. } = &self.inner else {
+            return None;
+        };
+        let SpStatement::Query(query) = statement.as_ref() else {
+            return None;
+        };
+        let mut names = Vec::new();
+        collect_query(query, &mut names)?;
+        Some(names)
+    }
+}
+
 impl TryFrom<SpStatement> for Explain {
     type Error = Error;
 
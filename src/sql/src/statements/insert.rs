@@ -12,12 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sqlparser::ast::{ObjectName, SetExpr, Statement, UnaryOperator, Values};
+use common_time::TimeZone;
+use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
+use datatypes::value::Value;
+use snafu::{ensure, OptionExt, ResultExt};
+use sqlparser::ast::{BinaryOperator, ObjectName, SetExpr, Statement, UnaryOperator, Values};
 use sqlparser::parser::ParserError;
 
-use crate::ast::{Expr, Value};
+use crate::ast::{Expr, Value as SqlValue};
 use crate::error::{self, Result};
-use crate::statements::table_idents_to_full_name;
+use crate::statements::{sql_number_to_value, sql_value_to_value, table_idents_to_full_name};
+
+const DEFAULT_KEYWORD: &str = "DEFAULT";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Insert {
@@ -47,53 +53,199 @@ impl Insert {
         }
     }
 
-    pub fn values(&self) -> Result<Vec<Vec<Value>>> {
-        let values = match &self.inner {
+    /// Returns the rows of expressions in the `VALUES` list, use [value_of_column] to
+    /// convert them into values.
+    pub fn rows(&self) -> Result<&[Vec<Expr>]> {
+        match &self.inner {
             Statement::Insert { source, .. } => match &*source.body {
-                SetExpr::Values(Values { rows, .. }) => sql_exprs_to_values(rows)?,
-                _ => unreachable!(),
+                SetExpr::Values(Values { rows, .. }) => Ok(rows),
+                body => error::InvalidSqlSnafu {
+                    msg: format!("Only VALUES is supported in insert, actual: {body}"),
+                }
+                .fail(),
             },
             _ => unreachable!(),
-        };
-        Ok(values)
+        }
     }
 }
 
-fn sql_exprs_to_values(exprs: &Vec<Vec<Expr>>) -> Result<Vec<Vec<Value>>> {
-    let mut values = Vec::with_capacity(exprs.len());
-    for es in exprs.iter() {
-        let mut vs = Vec::with_capacity(es.len());
-        for expr in es.iter() {
-            vs.push(match expr {
-                Expr::Value(v) => v.clone(),
-                Expr::Identifier(ident) => Value::SingleQuotedString(ident.value.clone()),
-                Expr::UnaryOp { op, expr }
-                    if matches!(op, UnaryOperator::Minus | UnaryOperator::Plus) =>
-                {
-                    if let Expr::Value(Value::Number(s, b)) = &**expr {
-                        match op {
-                            UnaryOperator::Minus => Value::Number(format!("-{s}"), *b),
-                            UnaryOperator::Plus => Value::Number(s.to_string(), *b),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        return error::ParseSqlValueSnafu {
-                            msg: format!("{expr:?}"),
-                        }
-                        .fail();
+/// Converts the `expr` in the `row`-th (starting from 0) row of the `VALUES` list
+/// into a value of the column.
+///
+/// Constant expressions, such as `1 + 2` and `now()`, are evaluated, and `DEFAULT`
+/// is converted into the default value of the column. Timestamp strings without an
+/// explicit offset are in the `time_zone` of the session.
+pub fn value_of_column(
+    row: usize,
+    column_schema: &ColumnSchema,
+    expr: &Expr,
+    time_zone: Option<&TimeZone>,
+) -> Result<Value> {
+    do_value_of_column(column_schema, expr, time_zone).context(error::InsertValueSnafu {
+        column_name: &column_schema.name,
+        row,
+    })
+}
+
+fn do_value_of_column(
+    column_schema: &ColumnSchema,
+    expr: &Expr,
+    time_zone: Option<&TimeZone>,
+) -> Result<Value> {
+    let value = expr_to_value(column_schema, expr, time_zone)?;
+    ensure!(
+        column_schema.is_nullable() || !value.is_null(),
+        error::ColumnNotNullSnafu {
+            column_name: &column_schema.name,
+        }
+    );
+    Ok(value)
+}
+
+fn expr_to_value(
+    column_schema: &ColumnSchema,
+    expr: &Expr,
+    time_zone: Option<&TimeZone>,
+) -> Result<Value> {
+    let column_name = &column_schema.name;
+    let data_type = &column_schema.data_type;
+    match expr {
+        Expr::Identifier(ident)
+            if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case(DEFAULT_KEYWORD) =>
+        {
+            default_value(column_schema)
+        }
+        // Double quoted strings are parsed as identifiers in some dialects.
+        Expr::Identifier(ident) => sql_value_to_value(
+            column_name,
+            data_type,
+            &SqlValue::SingleQuotedString(ident.value.clone()),
+            time_zone,
+        ),
+        Expr::Value(v) => sql_value_to_value(column_name, data_type, v, time_zone),
+        Expr::Nested(expr) => expr_to_value(column_schema, expr, time_zone),
+        Expr::Function(func) => {
+            // Evaluates the function the same as the default constraint of the column.
+            let constraint = ColumnDefaultConstraint::Function(format!("{func}").to_lowercase());
+            let vector = constraint
+                .create_default_vector(data_type, column_schema.is_nullable(), 1)
+                .context(error::InvalidDefaultSnafu {
+                    column: column_name,
+                })?;
+            Ok(vector.get(0))
+        }
+        _ => {
+            let n = eval_number(expr)?;
+            sql_number_to_value(data_type, &n.to_string())
+        }
+    }
+}
+
+fn default_value(column_schema: &ColumnSchema) -> Result<Value> {
+    let vector = column_schema
+        .create_default_vector(1)
+        .context(error::InvalidDefaultSnafu {
+            column: &column_schema.name,
+        })?
+        .context(error::NoDefaultValueSnafu {
+            column_name: &column_schema.name,
+        })?;
+    Ok(vector.get(0))
+}
+
+/// Result of evaluating a numeric constant expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Int(v) => write!(f, "{v}"),
+            Number::Float(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl Number {
+    fn as_f64(&self) -> f64 {
+        match self {
+            Number::Int(v) => *v as f64,
+            Number::Float(v) => *v,
+        }
+    }
+}
+
+/// Evaluates a numeric constant expression of number literals, signs and arithmetic
+/// operators.
+fn eval_number(expr: &Expr) -> Result<Number> {
+    let unsupported = || {
+        error::ParseSqlValueSnafu {
+            msg: format!("Unsupported expression in insert: {expr}"),
+        }
+        .fail()
+    };
+
+    match expr {
+        Expr::Value(SqlValue::Number(n, _)) => {
+            if let Ok(v) = n.parse::<i128>() {
+                Ok(Number::Int(v))
+            } else {
+                n.parse::<f64>().map(Number::Float).map_err(|e| {
+                    error::ParseSqlValueSnafu {
+                        msg: format!("Fail to parse number {n}, {e:?}"),
                     }
+                    .build()
+                })
+            }
+        }
+        Expr::Nested(expr) => eval_number(expr),
+        Expr::UnaryOp { op, expr } => match (op, eval_number(expr)?) {
+            (UnaryOperator::Plus, n) => Ok(n),
+            (UnaryOperator::Minus, Number::Int(v)) => v
+                .checked_neg()
+                .map(Number::Int)
+                .with_context(|| error::ParseSqlValueSnafu {
+                    msg: format!("Overflow in {expr}"),
+                }),
+            (UnaryOperator::Minus, Number::Float(v)) => Ok(Number::Float(-v)),
+            _ => unsupported(),
+        },
+        Expr::BinaryOp { left, op, right } => {
+            let (left, right) = (eval_number(left)?, eval_number(right)?);
+            match (left, right) {
+                (Number::Int(l), Number::Int(r)) => {
+                    let v = match op {
+                        BinaryOperator::Plus => l.checked_add(r),
+                        BinaryOperator::Minus => l.checked_sub(r),
+                        BinaryOperator::Multiply => l.checked_mul(r),
+                        BinaryOperator::Divide => l.checked_div(r),
+                        BinaryOperator::Modulo => l.checked_rem(r),
+                        _ => return unsupported(),
+                    };
+                    v.map(Number::Int)
+                        .with_context(|| error::ParseSqlValueSnafu {
+                            msg: format!("Overflow or division by zero in {expr}"),
+                        })
                 }
-                _ => {
-                    return error::ParseSqlValueSnafu {
-                        msg: format!("{expr:?}"),
-                    }
-                    .fail()
+                (l, r) => {
+                    let (l, r) = (l.as_f64(), r.as_f64());
+                    let v = match op {
+                        BinaryOperator::Plus => l + r,
+                        BinaryOperator::Minus => l - r,
+                        BinaryOperator::Multiply => l * r,
+                        BinaryOperator::Divide => l / r,
+                        BinaryOperator::Modulo => l % r,
+                        _ => return unsupported(),
+                    };
+                    Ok(Number::Float(v))
                 }
-            });
+            }
         }
-        values.push(vs);
+        _ => unsupported(),
     }
-    Ok(values)
 }
 
 impl TryFrom<Statement> for Insert {
@@ -111,39 +263,129 @@ impl TryFrom<Statement> for Insert {
 
 #[cfg(test)]
 mod tests {
+    use common_time::Timestamp;
+    use datatypes::prelude::ConcreteDataType;
     use sqlparser::dialect::GenericDialect;
 
     use super::*;
     use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
 
-    #[test]
-    fn test_insert_value_with_unary_op() {
-        use crate::statements::statement::Statement;
-
-        // insert "-1"
-        let sql = "INSERT INTO my_table VALUES(-1)";
+    fn parse_rows(sql: &str) -> Vec<Vec<Expr>> {
         let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
             .unwrap()
             .remove(0);
         match stmt {
-            Statement::Insert(insert) => {
-                let values = insert.values().unwrap();
-                assert_eq!(values, vec![vec![Value::Number("-1".to_string(), false)]]);
-            }
+            Statement::Insert(insert) => insert.rows().unwrap().to_vec(),
             _ => unreachable!(),
         }
+    }
 
-        // insert "+1"
-        let sql = "INSERT INTO my_table VALUES(+1)";
-        let stmt = ParserContext::create_with_dialect(sql, &GenericDialect {})
+    fn int_column(nullable: bool) -> ColumnSchema {
+        ColumnSchema::new("n", ConcreteDataType::int64_datatype(), nullable)
+    }
+
+    #[test]
+    fn test_insert_value_with_unary_op() {
+        let column = int_column(false);
+
+        let rows = parse_rows("INSERT INTO my_table VALUES(-1), (+1)");
+        assert_eq!(2, rows.len());
+        assert_eq!(
+            Value::Int64(-1),
+            value_of_column(0, &column, &rows[0][0], None).unwrap()
+        );
+        assert_eq!(
+            Value::Int64(1),
+            value_of_column(1, &column, &rows[1][0], None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_insert_value_with_constant_expr() {
+        let column = int_column(false);
+        let rows = parse_rows("INSERT INTO my_table VALUES(1 + 2), (-(3 * 4) % 5), ((10 - 4) / 3)");
+        let values = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| value_of_column(i, &column, &row[0], None).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![Value::Int64(3), Value::Int64(-2), Value::Int64(2)],
+            values
+        );
+
+        let column = ColumnSchema::new("f", ConcreteDataType::float64_datatype(), false);
+        let rows = parse_rows("INSERT INTO my_table VALUES(1 + 0.5)");
+        assert_eq!(
+            Value::Float64(1.5.into()),
+            value_of_column(0, &column, &rows[0][0], None).unwrap()
+        );
+
+        let column = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        );
+        let rows = parse_rows("INSERT INTO my_table VALUES(now())");
+        let value = value_of_column(0, &column, &rows[0][0], None).unwrap();
+        assert!(matches!(value, Value::Timestamp(_)), "{value:?}");
+
+        let rows = parse_rows("INSERT INTO my_table VALUES(1), (1 / 0)");
+        let column = int_column(false);
+        let err = value_of_column(1, &column, &rows[1][0], None).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Invalid value of column n in row 1 of VALUES"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_insert_timestamp_with_time_zone() {
+        let column = ColumnSchema::new(
+            "ts",
+            ConcreteDataType::timestamp_millisecond_datatype(),
+            false,
+        );
+        let rows = parse_rows("INSERT INTO my_table VALUES('1970-01-01 08:00:00')");
+        let time_zone: TimeZone = "+08:00".parse().unwrap();
+        assert_eq!(
+            Value::Timestamp(Timestamp::new_millisecond(0)),
+            value_of_column(0, &column, &rows[0][0], Some(&time_zone)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_insert_value_with_null_and_default() {
+        let rows = parse_rows("INSERT INTO my_table VALUES(NULL), (DEFAULT)");
+
+        let column = int_column(true);
+        assert!(value_of_column(0, &column, &rows[0][0], None)
             .unwrap()
-            .remove(0);
-        match stmt {
-            Statement::Insert(insert) => {
-                let values = insert.values().unwrap();
-                assert_eq!(values, vec![vec![Value::Number("1".to_string(), false)]]);
-            }
-            _ => unreachable!(),
-        }
+            .is_null());
+        assert!(value_of_column(1, &column, &rows[1][0], None)
+            .unwrap()
+            .is_null());
+
+        let column = int_column(false)
+            .with_default_constraint(Some(ColumnDefaultConstraint::Value(Value::Int64(7))))
+            .unwrap();
+        assert_eq!(
+            Value::Int64(7),
+            value_of_column(1, &column, &rows[1][0], None).unwrap()
+        );
+        let err = value_of_column(0, &column, &rows[0][0], None).unwrap_err();
+        assert_eq!(
+            "Invalid value of column n in row 0 of VALUES, source: Column n is not nullable",
+            err.to_string()
+        );
+
+        let column = int_column(false);
+        let err = value_of_column(1, &column, &rows[1][0], None).unwrap_err();
+        assert_eq!(
+            "Invalid value of column n in row 1 of VALUES, source: Column n has no default value",
+            err.to_string()
+        );
     }
 }
@@ -0,0 +1,345 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholders (e.g. `?`) of prepared statements.
+
+use snafu::ResultExt;
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, JoinConstraint, JoinOperator, OrderByExpr,
+    Query as SpQuery, SelectItem, SetExpr, Statement as SpStatement, TableFactor, TableWithJoins,
+    Value, WindowFrameBound,
+};
+use sqlparser::dialect::Dialect;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::error::{Result, TokenizerSnafu};
+use crate::statements::statement::Statement;
+
+/// Returns the number of placeholder tokens in `sql`.
+pub fn count_placeholders(sql: &str, dialect: &dyn Dialect) -> Result<usize> {
+    let tokens = Tokenizer::new(dialect, sql)
+        .tokenize()
+        .context(TokenizerSnafu { sql })?;
+    Ok(tokens
+        .iter()
+        .filter(|token| matches!(token, Token::Placeholder(_)))
+        .count())
+}
+
+/// Returns the placeholder expressions of `stmt` in the order they appear in the SQL,
+/// so they could be replaced by the values of the parameters.
+///
+/// Only placeholders in queries, inserts and deletes are returned, and placeholders in
+/// the constructs the walker doesn't know are skipped. Callers could compare the
+/// number with [count_placeholders] to detect the skipped ones.
+pub fn placeholders_mut(stmt: &mut Statement) -> Vec<&mut Expr> {
+    let mut placeholders = Vec::new();
+    match stmt {
+        Statement::Query(query) => collect_query(&mut query.inner, &mut placeholders),
+        Statement::Insert(insert) => {
+            if let SpStatement::Insert { source, .. } = &mut insert.inner {
+                collect_query(source, &mut placeholders);
+            }
+        }
+        Statement::Delete(delete) => {
+            if let Some(selection) = &mut delete.selection {
+                collect_expr(selection, &mut placeholders);
+            }
+        }
+        _ => {}
+    }
+    placeholders
+}
+
+fn collect_query<'a>(query: &'a mut SpQuery, placeholders: &mut Vec<&'a mut Expr>) {
+    if let Some(with) = &mut query.with {
+        for cte in &mut with.cte_tables {
+            collect_query(&mut cte.query, placeholders);
+        }
+    }
+    collect_set_expr(&mut query.body, placeholders);
+    collect_order_by(&mut query.order_by, placeholders);
+    if let Some(limit) = &mut query.limit {
+        collect_expr(limit, placeholders);
+    }
+    if let Some(offset) = &mut query.offset {
+        collect_expr(&mut offset.value, placeholders);
+    }
+    if let Some(quantity) = query
+        .fetch
+        .as_mut()
+        .and_then(|fetch| fetch.quantity.as_mut())
+    {
+        collect_expr(quantity, placeholders);
+    }
+}
+
+fn collect_set_expr<'a>(set_expr: &'a mut SetExpr, placeholders: &mut Vec<&'a mut Expr>) {
+    match set_expr {
+        SetExpr::Select(select) => {
+            for item in &mut select.projection {
+                if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item
+                {
+                    collect_expr(expr, placeholders);
+                }
+            }
+            for table in &mut select.from {
+                collect_table_with_joins(table, placeholders);
+            }
+            for view in &mut select.lateral_views {
+                collect_expr(&mut view.lateral_view, placeholders);
+            }
+            let exprs = select
+                .selection
+                .iter_mut()
+                .chain(&mut select.group_by)
+                .chain(&mut select.cluster_by)
+                .chain(&mut select.distribute_by)
+                .chain(&mut select.sort_by)
+                .chain(&mut select.having)
+                .chain(&mut select.qualify);
+            collect_exprs(exprs, placeholders);
+        }
+        SetExpr::Query(query) => collect_query(query, placeholders),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr(left, placeholders);
+            collect_set_expr(right, placeholders);
+        }
+        SetExpr::Values(values) => collect_exprs(values.rows.iter_mut().flatten(), placeholders),
+        _ => {}
+    }
+}
+
+fn collect_table_with_joins<'a>(
+    table: &'a mut TableWithJoins,
+    placeholders: &mut Vec<&'a mut Expr>,
+) {
+    collect_table_factor(&mut table.relation, placeholders);
+    for join in &mut table.joins {
+        collect_table_factor(&mut join.relation, placeholders);
+        let constraint = match &mut join.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => constraint,
+            _ => continue,
+        };
+        if let JoinConstraint::On(expr) = constraint {
+            collect_expr(expr, placeholders);
+        }
+    }
+}
+
+fn collect_table_factor<'a>(factor: &'a mut TableFactor, placeholders: &mut Vec<&'a mut Expr>) {
+    match factor {
+        TableFactor::Derived { subquery, .. } => collect_query(subquery, placeholders),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => collect_table_with_joins(table_with_joins, placeholders),
+        _ => {}
+    }
+}
+
+fn collect_order_by<'a>(order_by: &'a mut [OrderByExpr], placeholders: &mut Vec<&'a mut Expr>) {
+    collect_exprs(
+        order_by.iter_mut().map(|order_by| &mut order_by.expr),
+        placeholders,
+    )
+}
+
+fn collect_exprs<'a>(
+    exprs: impl IntoIterator<Item = &'a mut Expr>,
+    placeholders: &mut Vec<&'a mut Expr>,
+) {
+    for expr in exprs {
+        collect_expr(expr, placeholders);
+    }
+}
+
+/// Collects the placeholders in `expr`, children are visited in the order they appear
+/// in the SQL.
+fn collect_expr<'a>(expr: &'a mut Expr, placeholders: &mut Vec<&'a mut Expr>) {
+    if matches!(expr, Expr::Value(Value::Placeholder(_))) {
+        placeholders.push(expr);
+        return;
+    }
+
+    match expr {
+        Expr::Subquery(query)
+        | Expr::Exists {
+            subquery: query, ..
+        } => collect_query(query, placeholders),
+        Expr::InSubquery { expr, subquery, .. } => {
+            collect_expr(expr, placeholders);
+            collect_query(subquery, placeholders);
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            collect_expr(left, placeholders);
+            collect_expr(right, placeholders);
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::TryCast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Interval { value: expr, .. } => collect_expr(expr, placeholders),
+        Expr::InList { expr, list, .. } => {
+            collect_expr(expr, placeholders);
+            collect_exprs(list, placeholders);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            collect_expr(expr, placeholders);
+            collect_expr(low, placeholders);
+            collect_expr(high, placeholders);
+        }
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            collect_expr(expr, placeholders);
+            collect_expr(pattern, placeholders);
+        }
+        Expr::Substring {
+            expr,
+            substring_from,
+            substring_for,
+        } => {
+            collect_expr(expr, placeholders);
+            collect_exprs(
+                substring_from
+                    .iter_mut()
+                    .chain(substring_for)
+                    .map(|expr| &mut **expr),
+                placeholders,
+            );
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                collect_expr(operand, placeholders);
+            }
+            for (condition, result) in conditions.iter_mut().zip(results) {
+                collect_expr(condition, placeholders);
+                collect_expr(result, placeholders);
+            }
+            if let Some(else_result) = else_result {
+                collect_expr(else_result, placeholders);
+            }
+        }
+        Expr::Tuple(exprs) => collect_exprs(exprs, placeholders),
+        Expr::Function(function) => {
+            for arg in &mut function.args {
+                let (FunctionArg::Named { arg, .. } | FunctionArg::Unnamed(arg)) = arg;
+                if let FunctionArgExpr::Expr(expr) = arg {
+                    collect_expr(expr, placeholders);
+                }
+            }
+            if let Some(over) = &mut function.over {
+                collect_exprs(&mut over.partition_by, placeholders);
+                collect_order_by(&mut over.order_by, placeholders);
+                if let Some(frame) = &mut over.window_frame {
+                    for bound in [Some(&mut frame.start_bound), frame.end_bound.as_mut()]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if let WindowFrameBound::Preceding(Some(expr))
+                        | WindowFrameBound::Following(Some(expr)) = bound
+                        {
+                            collect_expr(expr, placeholders);
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+    use crate::parser::ParserContext;
+
+    fn bind(sql: &str) -> Statement {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let mut stmt = stmts.remove(0);
+        let placeholders = placeholders_mut(&mut stmt);
+        assert_eq!(
+            count_placeholders(sql, &GenericDialect {}).unwrap(),
+            placeholders.len()
+        );
+        for (i, placeholder) in placeholders.into_iter().enumerate() {
+            *placeholder = Expr::Value(Value::Number((i + 1).to_string(), false));
+        }
+        stmt
+    }
+
+    #[test]
+    fn test_bind_query() {
+        let Statement::Query(query) = bind(
+            "SELECT ? + a, CASE WHEN b > ? THEN ? ELSE ? END FROM t \
+             JOIN (SELECT * FROM u WHERE c = ?) v ON t.d = v.d + ? \
+             WHERE e IN (?, ?) AND f BETWEEN ? AND ? AND g LIKE ? \
+             ORDER BY h + ? LIMIT ?",
+        ) else {
+            unreachable!()
+        };
+        assert_eq!(
+            "SELECT 1 + a, CASE WHEN b > 2 THEN 3 ELSE 4 END FROM t \
+             JOIN (SELECT * FROM u WHERE c = 5) AS v ON t.d = v.d + 6 \
+             WHERE e IN (7, 8) AND f BETWEEN 9 AND 10 AND g LIKE 11 \
+             ORDER BY h + 12 LIMIT 13",
+            query.inner.to_string()
+        );
+    }
+
+    #[test]
+    fn test_bind_insert_and_delete() {
+        let Statement::Insert(insert) = bind("INSERT INTO t (a, b) VALUES (?, 'x'), (?, ?)")
+        else {
+            unreachable!()
+        };
+        let rows = insert
+            .rows()
+            .unwrap()
+            .iter()
+            .map(|row| row.iter().map(ToString::to_string).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![vec!["1", "'x'"], vec!["2", "3"]], rows);
+
+        let Statement::Delete(delete) = bind("DELETE FROM t WHERE a = ? AND b > ?") else {
+            unreachable!()
+        };
+        assert_eq!("a = 1 AND b > 2", delete.selection.unwrap().to_string());
+    }
+
+    #[test]
+    fn test_count_placeholders() {
+        let sql = "SELECT '?', \"?\" FROM t WHERE a = ? -- ?\n AND b = ?";
+        assert_eq!(2, count_placeholders(sql, &GenericDialect {}).unwrap());
+
+        // Placeholders in table functions are not collected.
+        let sql = "SELECT * FROM t(?)";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert!(placeholders_mut(&mut stmts[0]).is_empty());
+        assert_eq!(1, count_placeholders(sql, &GenericDialect {}).unwrap());
+    }
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sqlparser::ast::Query as SpQuery;
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, JoinConstraint, JoinOperator, ObjectName, OrderByExpr,
+    Query as SpQuery, SelectItem, SetExpr, TableFactor, TableWithJoins, WindowFrameBound,
+};
 
 use crate::error::Error;
 
@@ -22,6 +25,235 @@ pub struct Query {
     pub inner: SpQuery,
 }
 
+impl Query {
+    /// Returns the names of the tables referenced by the query, including the ones in
+    /// the common table expressions and the subqueries of all clauses. References to
+    /// the common table expressions are returned as table names too.
+    ///
+    /// Returns `None` if the query contains a construct the collector doesn't know, e.g.
+    /// a table function, so the callers checking the privileges could reject it instead
+    /// of missing some tables.
+    pub fn table_names(&self) -> Option<Vec<&ObjectName>> {
+        let mut names = Vec::new();
+        collect_query(&self.inner, &mut names)?;
+        Some(names)
+    }
+}
+
+/// Collects the names of the tables referenced by `query` into `names`, returns `None`
+/// if the query contains an unknown construct.
+pub(crate) fn collect_query<'a>(query: &'a SpQuery, names: &mut Vec<&'a ObjectName>) -> Option<()> {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            collect_query(&cte.query, names)?;
+        }
+    }
+    collect_set_expr(&query.body, names)?;
+    collect_order_by(&query.order_by, names)?;
+    if let Some(limit) = &query.limit {
+        collect_expr(limit, names)?;
+    }
+    if let Some(offset) = &query.offset {
+        collect_expr(&offset.value, names)?;
+    }
+    if let Some(quantity) = query
+        .fetch
+        .as_ref()
+        .and_then(|fetch| fetch.quantity.as_ref())
+    {
+        collect_expr(quantity, names)?;
+    }
+    Some(())
+}
+
+fn collect_set_expr<'a>(set_expr: &'a SetExpr, names: &mut Vec<&'a ObjectName>) -> Option<()> {
+    match set_expr {
+        SetExpr::Select(select) => {
+            for table in &select.from {
+                collect_table_with_joins(table, names)?;
+            }
+            for item in &select.projection {
+                if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item
+                {
+                    collect_expr(expr, names)?;
+                }
+            }
+            for view in &select.lateral_views {
+                collect_expr(&view.lateral_view, names)?;
+            }
+            let exprs = select
+                .selection
+                .iter()
+                .chain(&select.group_by)
+                .chain(&select.cluster_by)
+                .chain(&select.distribute_by)
+                .chain(&select.sort_by)
+                .chain(&select.having)
+                .chain(&select.qualify);
+            for expr in exprs {
+                collect_expr(expr, names)?;
+            }
+            Some(())
+        }
+        SetExpr::Query(query) => collect_query(query, names),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr(left, names)?;
+            collect_set_expr(right, names)
+        }
+        SetExpr::Values(values) => collect_exprs(values.rows.iter().flatten(), names),
+        _ => None,
+    }
+}
+
+fn collect_table_with_joins<'a>(
+    table: &'a TableWithJoins,
+    names: &mut Vec<&'a ObjectName>,
+) -> Option<()> {
+    collect_table_factor(&table.relation, names)?;
+    for join in &table.joins {
+        collect_table_factor(&join.relation, names)?;
+        let constraint = match &join.join_operator {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => constraint,
+            JoinOperator::CrossJoin => continue,
+            _ => return None,
+        };
+        if let JoinConstraint::On(expr) = constraint {
+            collect_expr(expr, names)?;
+        }
+    }
+    Some(())
+}
+
+fn collect_table_factor<'a>(
+    factor: &'a TableFactor,
+    names: &mut Vec<&'a ObjectName>,
+) -> Option<()> {
+    match factor {
+        // Arguments of the table are only given to table-valued functions.
+        TableFactor::Table { name, args, .. } if args.is_none() => {
+            names.push(name);
+            Some(())
+        }
+        TableFactor::Derived { subquery, .. } => collect_query(subquery, names),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => collect_table_with_joins(table_with_joins, names),
+        _ => None,
+    }
+}
+
+fn collect_order_by<'a>(
+    order_by: &'a [OrderByExpr],
+    names: &mut Vec<&'a ObjectName>,
+) -> Option<()> {
+    collect_exprs(order_by.iter().map(|order_by| &order_by.expr), names)
+}
+
+fn collect_exprs<'a>(
+    exprs: impl IntoIterator<Item = &'a Expr>,
+    names: &mut Vec<&'a ObjectName>,
+) -> Option<()> {
+    for expr in exprs {
+        collect_expr(expr, names)?;
+    }
+    Some(())
+}
+
+/// Collects the tables in the subqueries of `expr`. Unknown expressions are rejected as
+/// they may contain subqueries.
+fn collect_expr<'a>(expr: &'a Expr, names: &mut Vec<&'a ObjectName>) -> Option<()> {
+    match expr {
+        Expr::Identifier(_)
+        | Expr::CompoundIdentifier(_)
+        | Expr::Value(_)
+        | Expr::TypedString { .. } => Some(()),
+        Expr::Subquery(query)
+        | Expr::Exists {
+            subquery: query, ..
+        } => collect_query(query, names),
+        Expr::InSubquery { expr, subquery, .. } => {
+            collect_expr(expr, names)?;
+            collect_query(subquery, names)
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            collect_expr(left, names)?;
+            collect_expr(right, names)
+        }
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::TryCast { expr, .. }
+        | Expr::Extract { expr, .. }
+        | Expr::Interval { value: expr, .. } => collect_expr(expr, names),
+        Expr::InList { expr, list, .. } => {
+            collect_expr(expr, names)?;
+            collect_exprs(list, names)
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => collect_exprs([expr, low, high].map(AsRef::as_ref), names),
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            collect_expr(expr, names)?;
+            collect_expr(pattern, names)
+        }
+        Expr::Substring {
+            expr,
+            substring_from,
+            substring_for,
+        } => {
+            collect_expr(expr, names)?;
+            collect_exprs(
+                substring_from
+                    .iter()
+                    .chain(substring_for)
+                    .map(AsRef::as_ref),
+                names,
+            )
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            collect_exprs(operand.iter().chain(else_result).map(AsRef::as_ref), names)?;
+            collect_exprs(conditions.iter().chain(results), names)
+        }
+        Expr::Tuple(exprs) => collect_exprs(exprs, names),
+        Expr::Function(function) => {
+            for arg in &function.args {
+                let (FunctionArg::Named { arg, .. } | FunctionArg::Unnamed(arg)) = arg;
+                if let FunctionArgExpr::Expr(expr) = arg {
+                    collect_expr(expr, names)?;
+                }
+            }
+            if let Some(over) = &function.over {
+                collect_exprs(&over.partition_by, names)?;
+                collect_order_by(&over.order_by, names)?;
+                if let Some(frame) = &over.window_frame {
+                    for bound in [Some(&frame.start_bound), frame.end_bound.as_ref()]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if let WindowFrameBound::Preceding(Some(expr))
+                        | WindowFrameBound::Following(Some(expr)) = bound
+                        {
+                            collect_expr(expr, names)?;
+                        }
+                    }
+                }
+            }
+            Some(())
+        }
+        _ => None,
+    }
+}
+
 /// Automatically converts from sqlparser Query instance to SqlQuery.
 impl TryFrom<SpQuery> for Query {
     type Error = Error;
@@ -38,3 +270,68 @@ impl TryFrom<Query> for SpQuery {
         Ok(value.inner)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::GenericDialect;
+
+    use crate::parser::ParserContext;
+    use crate::statements::statement::Statement;
+
+    fn table_names(sql: &str) -> Option<Vec<String>> {
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        let Statement::Query(query) = stmts.remove(0) else {
+            unreachable!()
+        };
+        let names = query.table_names()?;
+        Some(names.into_iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn test_table_names() {
+        assert_eq!(Some(vec![]), table_names("SELECT 1"));
+        assert_eq!(
+            Some(vec!["a", "s.b", "c.s.c"]),
+            table_names("SELECT * FROM a JOIN s.b ON a.x = b.x, c.s.c")
+        );
+        assert_eq!(
+            Some(vec!["t", "w", "s.u", "v"]),
+            table_names(
+                "WITH w AS (SELECT * FROM t) SELECT (SELECT max(x) FROM s.u) FROM w WHERE y IN (SELECT y FROM v)"
+            )
+        );
+        assert_eq!(
+            Some(vec!["a", "b"]),
+            table_names("SELECT x FROM (SELECT x FROM a) UNION SELECT x FROM b")
+        );
+    }
+
+    #[test]
+    fn test_table_names_in_all_clauses() {
+        assert_eq!(
+            Some(vec!["a", "b", "c", "d", "e", "f", "g"]),
+            table_names(
+                "SELECT count(*), CASE WHEN x > 0 THEN (SELECT 1 FROM d) END \
+                 FROM a JOIN b ON a.x IN (SELECT x FROM c) \
+                 GROUP BY coalesce(x, (SELECT x FROM e)) \
+                 HAVING count(*) > (SELECT count(*) FROM f) \
+                 ORDER BY abs((SELECT max(x) FROM g))"
+            )
+        );
+        assert_eq!(
+            Some(vec!["a", "b"]),
+            table_names("SELECT CAST((SELECT x FROM b) AS INT) BETWEEN 1 AND 2 FROM a")
+        );
+    }
+
+    #[test]
+    fn test_table_names_of_unknown_constructs() {
+        // Table functions and expressions the collector doesn't know are rejected,
+        // instead of missing the tables in them.
+        assert_eq!(None, table_names("SELECT * FROM t(1)"));
+        assert_eq!(
+            None,
+            table_names("SELECT POSITION((SELECT 'a' FROM b) IN 'abc') FROM a")
+        );
+    }
+}
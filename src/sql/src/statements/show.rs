@@ -47,13 +47,19 @@ impl ShowDatabases {
     }
 }
 
-/// SQL structure for `SHOW TABLES`.
+/// SQL structure for `SHOW [FULL] TABLES`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ShowTables {
     pub kind: ShowKind,
     pub database: Option<String>,
+    /// Whether to also show the type of the tables.
+    pub full: bool,
 }
 
+/// SQL structure for `SHOW NODES`, which lists the datanodes of the cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowNodes;
+
 /// SQL structure for `SHOW CREATE TABLE`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ShowCreateTable {
@@ -115,6 +121,14 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_show_nodes() {
+        let sql = "SHOW NODES";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        assert_matches!(&stmts[0], Statement::ShowNodes(ShowNodes));
+    }
+
     #[test]
     pub fn test_show_create_table() {
         let sql = "SHOW CREATE TABLE test";
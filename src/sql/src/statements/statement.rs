@@ -13,13 +13,18 @@
 // limitations under the License.
 
 use crate::statements::alter::AlterTable;
-use crate::statements::create::{CreateDatabase, CreateTable};
+use crate::statements::copy::{CopyQueryTo, CopyTable};
+use crate::statements::create::{CreateDatabase, CreateTable, CreateTask, CreateUser};
+use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
-use crate::statements::drop::DropTable;
+use crate::statements::drop::{DropTable, DropTask, DropUser};
 use crate::statements::explain::Explain;
+use crate::statements::grant::Grant;
 use crate::statements::insert::Insert;
 use crate::statements::query::Query;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::set::SetVariable;
+use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowNodes, ShowTables};
+use crate::statements::truncate::TruncateTable;
 
 /// Tokens parsed by `DFParser` are converted into these values.
 #[allow(clippy::large_enum_variant)]
@@ -29,12 +34,28 @@ pub enum Statement {
     Query(Box<Query>),
     // Insert
     Insert(Box<Insert>),
+    // Delete
+    Delete(Box<Delete>),
+    /// COPY FROM
+    Copy(CopyTable),
+    /// COPY TO
+    CopyTo(CopyQueryTo),
     /// CREATE TABLE
     CreateTable(CreateTable),
     // DROP TABLE
     DropTable(DropTable),
     // CREATE DATABASE
     CreateDatabase(CreateDatabase),
+    /// CREATE USER
+    CreateUser(CreateUser),
+    /// DROP USER
+    DropUser(DropUser),
+    /// GRANT or REVOKE
+    Grant(Grant),
+    /// CREATE TASK
+    CreateTask(CreateTask),
+    /// DROP TASK
+    DropTask(DropTask),
     /// ALTER TABLE
     Alter(AlterTable),
     // Databases.
@@ -43,11 +64,17 @@ pub enum Statement {
     ShowTables(ShowTables),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    /// SHOW NODES
+    ShowNodes(ShowNodes),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
     Explain(Explain),
     Use(String),
+    /// SET variable
+    SetVariable(SetVariable),
+    /// TRUNCATE TABLE
+    Truncate(TruncateTable),
 }
 
 /// Comment hints from SQL.
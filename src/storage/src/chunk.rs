@@ -16,15 +16,18 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
+use common_telemetry::logging;
 use snafu::ResultExt;
-use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
+use store_api::storage::{Chunk, ChunkReader, DedupPolicy, ScanOrder, SchemaRef, SequenceNumber};
 use table::predicate::Predicate;
 
 use crate::error::{self, Error, Result};
+use crate::key_range::KeyRange;
 use crate::memtable::{IterContext, MemtableRef};
-use crate::read::{BoxedBatchReader, DedupReader, MergeReaderBuilder};
+use crate::read::{BoxedBatchReader, DedupReader, MergeReaderBuilder, ReverseReader};
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
 use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
+use crate::tombstone::{RangeTombstones, RangeTombstonesRef};
 
 /// Chunk reader implementation.
 // Now we use async-trait to implement the chunk reader, which is easier to implement than
@@ -33,6 +36,8 @@ use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
 pub struct ChunkReaderImpl {
     schema: ProjectedSchemaRef,
     batch_reader: BoxedBatchReader,
+    /// Number of rows still allowed to return, `None` if the reader is unlimited.
+    remaining: Option<usize>,
 }
 
 #[async_trait]
@@ -44,10 +49,21 @@ impl ChunkReader for ChunkReaderImpl {
     }
 
     async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
-        let batch = match self.batch_reader.next_batch().await? {
+        // Stop reading from the underlying sources once the limit is reached.
+        if self.remaining == Some(0) {
+            return Ok(None);
+        }
+
+        let mut batch = match self.batch_reader.next_batch().await? {
             Some(b) => b,
             None => return Ok(None),
         };
+        if let Some(remaining) = &mut self.remaining {
+            if batch.num_rows() > *remaining {
+                batch = batch.slice(0, *remaining);
+            }
+            *remaining -= batch.num_rows();
+        }
 
         let chunk = self.schema.batch_to_chunk(&batch);
 
@@ -60,8 +76,15 @@ impl ChunkReaderImpl {
         ChunkReaderImpl {
             schema,
             batch_reader,
+            remaining: None,
         }
     }
+
+    /// Returns at most `limit` rows, `None` for no limit.
+    pub fn with_limit(mut self, limit: Option<usize>) -> ChunkReaderImpl {
+        self.remaining = limit;
+        self
+    }
 }
 
 /// Builder to create a new [ChunkReaderImpl] from scan request.
@@ -69,6 +92,10 @@ pub struct ChunkReaderBuilder {
     schema: RegionSchemaRef,
     projection: Option<Vec<usize>>,
     filters: Vec<Expr>,
+    limit: Option<usize>,
+    order: ScanOrder,
+    range_tombstones: RangeTombstonesRef,
+    key_range: Option<KeyRange>,
     sst_layer: AccessLayerRef,
     iter_ctx: IterContext,
     memtables: Vec<MemtableRef>,
@@ -81,6 +108,10 @@ impl ChunkReaderBuilder {
             schema,
             projection: None,
             filters: vec![],
+            limit: None,
+            order: ScanOrder::Asc,
+            range_tombstones: Arc::new(RangeTombstones::default()),
+            key_range: None,
             sst_layer,
             iter_ctx: IterContext::default(),
             memtables: Vec::new(),
@@ -104,6 +135,31 @@ impl ChunkReaderBuilder {
         self
     }
 
+    /// Limits the number of rows to read, the limit is applied to the merged and
+    /// deduplicated rows of all memtables and SSTs.
+    pub fn limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets the order of the row key to return rows in.
+    pub fn order(mut self, order: ScanOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets range tombstones visible to this read, rows masked by them are removed.
+    pub fn range_tombstones(mut self, range_tombstones: RangeTombstonesRef) -> Self {
+        self.range_tombstones = range_tombstones;
+        self
+    }
+
+    /// Sets the range of keys owned by the region, rows out of the range are removed.
+    pub fn key_range(mut self, key_range: Option<KeyRange>) -> Self {
+        self.key_range = key_range;
+        self
+    }
+
     pub fn batch_size(mut self, batch_size: usize) -> Self {
         self.iter_ctx.batch_size = batch_size;
         self
@@ -114,6 +170,12 @@ impl ChunkReaderBuilder {
         self
     }
 
+    /// Sets how rows with the same keys and timestamp are deduplicated.
+    pub fn dedup_policy(mut self, dedup_policy: DedupPolicy) -> Self {
+        self.iter_ctx.dedup_policy = dedup_policy;
+        self
+    }
+
     pub fn pick_memtables(mut self, memtables: MemtableRef) -> Self {
         self.memtables.push(memtables);
         self
@@ -125,40 +187,88 @@ impl ChunkReaderBuilder {
         Ok(self)
     }
 
-    pub async fn build(mut self) -> Result<ChunkReaderImpl> {
+    /// Picks the SST `files` to read.
+    pub fn pick_files(mut self, files: &[FileHandle]) -> Self {
+        self.files_to_read.extend_from_slice(files);
+        self
+    }
+
+    pub async fn build(self) -> Result<ChunkReaderImpl> {
+        let limit = self.limit;
+        let (schema, reader) = self.build_batch_reader().await?;
+
+        Ok(ChunkReaderImpl::new(schema, reader).with_limit(limit))
+    }
+
+    /// Builds the reader of merged and deduplicated batches, which contain the internal
+    /// columns and ignore the limit.
+    pub async fn build_batch_reader(mut self) -> Result<(ProjectedSchemaRef, BoxedBatchReader)> {
+        let region_schema = self.schema.clone();
         let schema = Arc::new(
             ProjectedSchema::new(self.schema, self.projection)
                 .context(error::InvalidProjectionSnafu)?,
         );
 
+        let reverse = self.order.is_desc();
         let num_sources = self.memtables.len() + self.files_to_read.len();
         let mut reader_builder = MergeReaderBuilder::with_capacity(schema.clone(), num_sources)
-            .batch_size(self.iter_ctx.batch_size);
+            .batch_size(self.iter_ctx.batch_size)
+            .reverse(reverse);
 
         self.iter_ctx.projected_schema = Some(schema.clone());
+        self.iter_ctx.reverse = reverse;
         for mem in self.memtables {
             let iter = mem.iter(&self.iter_ctx)?;
             reader_builder = reader_builder.push_batch_iter(iter);
         }
 
-        let read_opts = ReadOptions {
-            batch_size: self.iter_ctx.batch_size,
-            projected_schema: schema.clone(),
-            predicate: Predicate::new(self.filters),
-        };
         for file in &self.files_to_read {
-            let reader = self
-                .sst_layer
-                .read_sst(file.file_name(), &read_opts)
-                .await?;
+            let row_ranges =
+                file.meta().index.as_ref().and_then(|index| {
+                    index.select_rows(region_schema.user_schema(), &self.filters)
+                });
+            if matches!(&row_ranges, Some(ranges) if ranges.is_empty()) {
+                // No row in the file matches the filters.
+                continue;
+            }
+            if !may_contain_keys(
+                file,
+                region_schema.user_schema(),
+                &self.filters,
+                &self.sst_layer,
+            )
+            .await
+            {
+                continue;
+            }
+
+            let read_opts = ReadOptions {
+                batch_size: self.iter_ctx.batch_size,
+                projected_schema: schema.clone(),
+                predicate: Predicate::new(self.filters.clone()),
+                row_ranges,
+                reverse,
+            };
+            let reader = self.sst_layer.read_sst(file.meta(), &read_opts).await?;
 
             reader_builder = reader_builder.push_batch_reader(reader);
         }
 
         let reader = reader_builder.build();
-        let reader = DedupReader::new(schema.clone(), reader);
+        let reader: BoxedBatchReader = if reverse {
+            // Sources return versions of a key in reverse order too, so they are reordered
+            // by sequence in descending order before dedup.
+            Box::new(ReverseReader::new(schema.clone(), reader))
+        } else {
+            Box::new(reader)
+        };
+        let reader = DedupReader::new(schema.clone(), reader)
+            .with_dedup_policy(self.iter_ctx.dedup_policy)
+            .with_range_tombstones(self.range_tombstones)
+            .with_key_range(self.key_range)
+            .with_filters(&self.filters);
 
-        Ok(ChunkReaderImpl::new(schema, Box::new(reader)))
+        Ok((schema, Box::new(reader)))
     }
 }
 
@@ -177,3 +287,33 @@ impl Visitor for ChunkReaderBuilder {
         Ok(())
     }
 }
+
+/// Returns `false` if the bloom filter of the `file` shows no row in the file could
+/// match the `filters`.
+async fn may_contain_keys(
+    file: &FileHandle,
+    schema: &SchemaRef,
+    filters: &[Expr],
+    sst_layer: &AccessLayerRef,
+) -> bool {
+    let Some(bloom_meta) = &file.meta().bloom_filter else {
+        return true;
+    };
+    let Some(keys) = bloom_meta.probe_keys(schema, filters, file.meta().time_range) else {
+        return true;
+    };
+
+    match file.bloom_filter(sst_layer).await {
+        Ok(Some(filter)) => keys.iter().any(|key| filter.contains(*key)),
+        Ok(None) => true,
+        Err(e) => {
+            // Reads the file if the filter is unavailable.
+            logging::warn!(
+                "Failed to read bloom filter of file {}, err: {:?}",
+                file.file_name(),
+                e
+            );
+            true
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compaction of SSTs.
+
+use common_telemetry::logging;
+use metrics::counter;
+use store_api::logstore::LogStore;
+use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
+use store_api::storage::DedupPolicy;
+
+use crate::background::Context;
+use crate::chunk::ChunkReaderBuilder;
+use crate::error::{CancelledSnafu, Result};
+use crate::flush::{BufferedBatchIterator, FlushJob};
+use crate::manifest::action::RegionEdit;
+use crate::manifest::region::RegionManifest;
+use crate::memtable::RowOrdering;
+use crate::metric::METRIC_COMPACTION_FILES_TOTAL;
+use crate::read::BatchReader;
+use crate::region::{RegionWriterRef, SharedDataRef};
+use crate::sst::{AccessLayerRef, FileHandle, FileMeta};
+use crate::wal::Wal;
+
+/// Max number of rows in a SST written by compaction, rows are written to more SSTs
+/// if there are more rows.
+const MAX_ROWS_PER_COMPACTED_SST: usize = 1024 * 1024;
+
+/// Job to compact SSTs of a region into new SSTs.
+///
+/// Compacted SSTs are written by the access layer as flushed SSTs, so their inverted
+/// indexes, bloom filters and statistics are rebuilt from the compacted rows. The
+/// handles of the input SSTs, together with the bloom filters cached in them, are
+/// dropped from the version once the compacted SSTs replace them.
+pub struct CompactionJob<S: LogStore> {
+    /// SSTs to compact.
+    pub files: Vec<FileHandle>,
+    /// How rows with the same keys and timestamp are deduplicated.
+    pub dedup_policy: DedupPolicy,
+    pub shared: SharedDataRef,
+    pub sst_layer: AccessLayerRef,
+    pub writer: RegionWriterRef,
+    pub wal: Wal<S>,
+    pub manifest: RegionManifest,
+}
+
+impl<S: LogStore> CompactionJob<S> {
+    /// Compacts the SSTs and replaces them with the compacted SSTs in the region.
+    ///
+    /// The caller should ensure no other job adds or removes SSTs of the region
+    /// until the compaction is finished.
+    pub async fn compact(&self, ctx: &Context) -> Result<()> {
+        let metas = self.write_compacted_ssts(ctx).await?;
+        let num_files = metas.len();
+        let version = self.shared.version_control.current();
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: version.flushed_sequence(),
+            files_to_add: metas,
+            files_to_remove: self.files.iter().map(|file| file.meta().clone()).collect(),
+            range_tombstones: Vec::new(),
+        };
+        self.writer
+            .write_edit_and_apply(&self.wal, &self.shared, &self.manifest, edit, None)
+            .await?;
+
+        counter!(METRIC_COMPACTION_FILES_TOTAL, num_files as u64);
+        logging::info!(
+            "Region {} compacted {} files into {} files",
+            self.shared.name(),
+            self.files.len(),
+            num_files,
+        );
+        Ok(())
+    }
+
+    /// Merges rows of the SSTs and writes them to new SSTs in level 0.
+    async fn write_compacted_ssts(&self, ctx: &Context) -> Result<Vec<FileMeta>> {
+        let version = self.shared.version_control.current();
+        let flushed_sequence = version.flushed_sequence();
+        // Rows in SSTs are all flushed, so all range tombstones could be applied and
+        // rows masked by them are dropped.
+        let (schema, mut reader) =
+            ChunkReaderBuilder::new(version.schema().clone(), self.sst_layer.clone())
+                .batch_size(WRITE_ROW_GROUP_SIZE)
+                .dedup_policy(self.dedup_policy)
+                .range_tombstones(version.range_tombstones().clone())
+                .key_range(version.key_range().cloned())
+                .pick_files(&self.files)
+                .build_batch_reader()
+                .await?;
+
+        let format = self.sst_layer.sst_format();
+        let mut metas = Vec::new();
+        let mut batches = Vec::new();
+        let mut num_rows = 0;
+        loop {
+            if ctx.is_cancelled() {
+                return CancelledSnafu {}.fail();
+            }
+
+            let batch = reader.next_batch().await?;
+            let is_end = batch.is_none();
+            if let Some(batch) = batch {
+                num_rows += batch.num_rows();
+                batches.push(batch);
+            }
+            if num_rows < MAX_ROWS_PER_COMPACTED_SST && !is_end {
+                continue;
+            }
+
+            if num_rows > 0 {
+                let iter = BufferedBatchIterator::new(
+                    schema.clone(),
+                    RowOrdering::Key,
+                    std::mem::take(&mut batches),
+                );
+                let file_name = FlushJob::<S>::generate_sst_file_name(format);
+                let info = self
+                    .sst_layer
+                    .write_sst(&file_name, Box::new(iter), self.sst_layer.write_options())
+                    .await?;
+                metas.push(FileMeta {
+                    file_name,
+                    level: 0,
+                    time_range: info.time_range,
+                    num_rows: info.num_rows,
+                    file_size: info.file_size,
+                    format,
+                    index: info.index,
+                    bloom_filter: info.bloom_filter,
+                    source_region: None,
+                    flushed_sequence: Some(flushed_sequence),
+                });
+                num_rows = 0;
+            }
+            if is_end {
+                return Ok(metas);
+            }
+        }
+    }
+}
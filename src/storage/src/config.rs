@@ -14,5 +14,76 @@
 
 //! storage engine config
 
-#[derive(Debug, Default, Clone)]
-pub struct EngineConfig {}
+use std::time::Duration;
+
+use store_api::storage::Compression;
+
+/// Default write buffer size of a region (32M).
+pub const DEFAULT_MAX_WRITE_BUFFER_SIZE: usize = 32 * 1024 * 1024;
+/// Default duration of the time buckets of out-of-order rows (1 hour).
+pub const DEFAULT_OUT_OF_ORDER_BUCKET: Duration = Duration::from_secs(60 * 60);
+/// Default max number of SSTs of a region before compaction.
+pub const DEFAULT_MAX_FILES_IN_LEVEL0: usize = 8;
+
+/// Options of bloom filters on row keys of SST files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilterConfig {
+    /// Expected false positive rate of the filter.
+    pub false_positive_rate: f64,
+    /// Max size of the filter of a SST file, the false positive rate of files with
+    /// too many keys is higher than expected.
+    pub max_bytes: usize,
+    /// Duration of the time buckets, the row key in the filter is made up of the tags
+    /// and the time bucket of the timestamp of a row.
+    pub time_bucket: Duration,
+}
+
+impl Default for BloomFilterConfig {
+    fn default() -> BloomFilterConfig {
+        BloomFilterConfig {
+            false_positive_rate: 0.01,
+            max_bytes: 1024 * 1024,
+            time_bucket: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Default compression codec of parquet SST files.
+    pub sst_compression: Compression,
+    /// Whether dictionary encoding of parquet SST files is enabled by default.
+    pub sst_dictionary_enabled: bool,
+    /// Default max number of rows in a row group of parquet SST files.
+    pub sst_max_row_group_size: usize,
+    /// Max size of memtables of a region, the region is flushed once it's exceeded.
+    pub max_write_buffer_size: usize,
+    /// Duration of the time buckets that out-of-order rows are flushed into.
+    ///
+    /// Rows older than the flushed rows of a region are buffered in a separate memtable,
+    /// which is flushed into one SST per time bucket. Out-of-order rows are written to
+    /// the mutable memtable as other rows if it's `None`.
+    pub out_of_order_bucket: Option<Duration>,
+    /// Max number of SSTs of a region, SSTs are compacted into new SSTs once a flush
+    /// makes the region exceed it. No compaction if it's `None`.
+    ///
+    /// Inverted indexes, bloom filters and statistics of the compacted SSTs are built
+    /// while writing them, like flushed SSTs.
+    pub max_files_in_level0: Option<usize>,
+    /// Options of bloom filters on row keys of SST files, no filter is built if it's `None`.
+    pub sst_bloom_filter: Option<BloomFilterConfig>,
+}
+
+impl Default for EngineConfig {
+    fn default() -> EngineConfig {
+        EngineConfig {
+            sst_compression: Compression::Zstd,
+            sst_dictionary_enabled: true,
+            sst_max_row_group_size: 4096,
+            max_write_buffer_size: DEFAULT_MAX_WRITE_BUFFER_SIZE,
+            out_of_order_bucket: Some(DEFAULT_OUT_OF_ORDER_BUCKET),
+            max_files_in_level0: Some(DEFAULT_MAX_FILES_IN_LEVEL0),
+            sst_bloom_filter: Some(BloomFilterConfig::default()),
+        }
+    }
+}
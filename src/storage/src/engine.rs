@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use common_telemetry::logging::info;
@@ -21,18 +22,21 @@ use object_store::{util, ObjectStore};
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::storage::{
-    CreateOptions, EngineContext, OpenOptions, RegionDescriptor, StorageEngine,
+    CreateOptions, DedupPolicy, EngineContext, MergeRegionsRequest, OpenOptions, ParquetOptions,
+    Region, RegionDescriptor, RegionId, SplitRegionRequest, SstFormat, StorageEngine,
 };
 
 use crate::background::JobPoolImpl;
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
-use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy};
+use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, SizeBasedStrategy};
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
 use crate::metadata::RegionMetadata;
-use crate::region::{RegionImpl, StoreConfig};
-use crate::sst::FsAccessLayer;
+use crate::metric;
+use crate::region::{RegionImpl, RegionToCreate, StoreConfig};
+use crate::replication::WalReplicatorRef;
+use crate::sst::{FsAccessLayer, WriteOptions};
 
 /// [StorageEngine] implementation.
 pub struct EngineImpl<S: LogStore> {
@@ -61,8 +65,9 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
         self.inner.open_region(name, opts).await
     }
 
-    async fn close_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn close_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
+        self.inner.close_region(&region);
+        Ok(())
     }
 
     async fn create_region(
@@ -81,14 +86,67 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<Self::Region>> {
         Ok(self.inner.get_region(name))
     }
+
+    async fn split_region(
+        &self,
+        _ctx: &EngineContext,
+        region: &Self::Region,
+        request: SplitRegionRequest,
+        opts: &CreateOptions,
+    ) -> Result<(Self::Region, Self::Region)> {
+        self.inner.split_region(region, request, opts).await
+    }
+
+    async fn merge_regions(
+        &self,
+        _ctx: &EngineContext,
+        left: &Self::Region,
+        right: &Self::Region,
+        request: MergeRegionsRequest,
+        opts: &CreateOptions,
+    ) -> Result<Self::Region> {
+        self.inner.merge_regions(left, right, request, opts).await
+    }
 }
 
 impl<S: LogStore> EngineImpl<S> {
     pub fn new(config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
+        metric::register_metrics();
+        Self {
+            inner: Arc::new(EngineInner::new(config, log_store, object_store, None)),
+        }
+    }
+
+    /// Creates an engine that ships the WAL of its regions to standby regions by the
+    /// `wal_replicator`.
+    pub fn with_wal_replicator(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        wal_replicator: WalReplicatorRef,
+    ) -> Self {
+        metric::register_metrics();
         Self {
-            inner: Arc::new(EngineInner::new(config, log_store, object_store)),
+            inner: Arc::new(EngineInner::new(
+                config,
+                log_store,
+                object_store,
+                Some(wal_replicator),
+            )),
         }
     }
+
+    /// Sets the write buffer size of regions, takes effect on the next write of each
+    /// region.
+    pub fn set_max_write_buffer_size(&self, max_write_buffer_size: usize) {
+        info!(
+            "Set max write buffer size of storage engine to {}",
+            max_write_buffer_size
+        );
+        self.inner
+            .flush_strategy
+            .set_max_write_buffer_size(max_write_buffer_size);
+    }
 }
 
 /// Generate region sst path,
@@ -209,11 +267,21 @@ struct EngineInner<S: LogStore> {
     regions: RwLock<RegionMap<S>>,
     memtable_builder: MemtableBuilderRef,
     flush_scheduler: FlushSchedulerRef,
-    flush_strategy: FlushStrategyRef,
+    flush_strategy: Arc<SizeBasedStrategy>,
+    /// Default options to write SST files.
+    sst_write_options: WriteOptions,
+    out_of_order_bucket: Option<Duration>,
+    max_files_in_level0: Option<usize>,
+    wal_replicator: Option<WalReplicatorRef>,
 }
 
 impl<S: LogStore> EngineInner<S> {
-    pub fn new(_config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
+    pub fn new(
+        config: EngineConfig,
+        log_store: Arc<S>,
+        object_store: ObjectStore,
+        wal_replicator: Option<WalReplicatorRef>,
+    ) -> Self {
         let job_pool = Arc::new(JobPoolImpl {});
         let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
 
@@ -223,7 +291,11 @@ impl<S: LogStore> EngineInner<S> {
             regions: RwLock::new(Default::default()),
             memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
             flush_scheduler,
-            flush_strategy: Arc::new(SizeBasedStrategy::default()),
+            flush_strategy: Arc::new(SizeBasedStrategy::new(config.max_write_buffer_size)),
+            sst_write_options: WriteOptions::from_config(&config),
+            out_of_order_bucket: config.out_of_order_bucket,
+            max_files_in_level0: config.max_files_in_level0,
+            wal_replicator,
         }
     }
 
@@ -259,7 +331,14 @@ impl<S: LogStore> EngineInner<S> {
 
         let mut guard = SlotGuard::new(name, &self.regions);
 
-        let store_config = self.region_store_config(&opts.parent_dir, name);
+        let store_config = self.region_store_config(
+            &opts.parent_dir,
+            name,
+            opts.sst_format,
+            &opts.parquet_options,
+            &opts.sst_index_columns,
+            opts.dedup_policy,
+        );
 
         let region = match RegionImpl::open(name.to_string(), store_config, opts).await? {
             None => return Ok(None),
@@ -289,7 +368,14 @@ impl<S: LogStore> EngineInner<S> {
                 .context(error::InvalidRegionDescSnafu {
                     region: &region_name,
                 })?;
-        let store_config = self.region_store_config(&opts.parent_dir, &region_name);
+        let store_config = self.region_store_config(
+            &opts.parent_dir,
+            &region_name,
+            opts.sst_format,
+            &opts.parquet_options,
+            &opts.sst_index_columns,
+            opts.dedup_policy,
+        );
 
         let region = RegionImpl::create(metadata, store_config).await?;
 
@@ -305,11 +391,126 @@ impl<S: LogStore> EngineInner<S> {
         slot.get_ready_region()
     }
 
-    fn region_store_config(&self, parent_dir: &str, region_name: &str) -> StoreConfig<S> {
+    /// Removes the region from the engine, so the region is opened from the object store
+    /// next time. The region is released once all its references are dropped.
+    fn close_region(&self, region: &RegionImpl<S>) {
+        let mut regions = self.regions.write().unwrap();
+        // The slot is left as it is if the region is being created or opened.
+        if matches!(regions.get(region.name()), Some(RegionSlot::Ready(_))) {
+            regions.remove(region.name());
+            info!("Storage engine closed region {}", region.name());
+        }
+    }
+
+    async fn split_region(
+        &self,
+        region: &RegionImpl<S>,
+        request: SplitRegionRequest,
+        opts: &CreateOptions,
+    ) -> Result<(RegionImpl<S>, RegionImpl<S>)> {
+        let mut left_guard = self.occupy_creating_slot(&request.left_name)?;
+        let mut right_guard = self.occupy_creating_slot(&request.right_name)?;
+
+        let left = self.region_to_create(request.left_id, &request.left_name, opts);
+        let right = self.region_to_create(request.right_id, &request.right_name, opts);
+        let (left, right) = region
+            .split(&request.column_name, request.split_key, left, right)
+            .await?;
+
+        left_guard.update(RegionSlot::Ready(left.clone()));
+        right_guard.update(RegionSlot::Ready(right.clone()));
+
+        info!(
+            "Storage engine split region {} into {} and {}",
+            region.id(),
+            left.id(),
+            right.id()
+        );
+
+        Ok((left, right))
+    }
+
+    async fn merge_regions(
+        &self,
+        left: &RegionImpl<S>,
+        right: &RegionImpl<S>,
+        request: MergeRegionsRequest,
+        opts: &CreateOptions,
+    ) -> Result<RegionImpl<S>> {
+        let mut guard = self.occupy_creating_slot(&request.name)?;
+
+        let target = self.region_to_create(request.id, &request.name, opts);
+        let region = left.merge(right, target).await?;
+
+        guard.update(RegionSlot::Ready(region.clone()));
+
+        info!(
+            "Storage engine merge regions {} and {} into {}",
+            left.id(),
+            right.id(),
+            region.id()
+        );
+
+        Ok(region)
+    }
+
+    /// Occupies the slot of the region to create, returns error if the region already
+    /// exists.
+    fn occupy_creating_slot<'a>(&'a self, name: &'a str) -> Result<SlotGuard<'a, S>> {
+        if let Some(slot) = self.get_or_occupy_slot(name, RegionSlot::Creating) {
+            return error::InvalidRegionStateSnafu {
+                state: slot.state_name(),
+            }
+            .fail();
+        }
+
+        Ok(SlotGuard::new(name, &self.regions))
+    }
+
+    fn region_to_create(
+        &self,
+        id: RegionId,
+        name: &str,
+        opts: &CreateOptions,
+    ) -> RegionToCreate<S> {
+        let store_config = self.region_store_config(
+            &opts.parent_dir,
+            name,
+            opts.sst_format,
+            &opts.parquet_options,
+            &opts.sst_index_columns,
+            opts.dedup_policy,
+        );
+
+        RegionToCreate {
+            id,
+            name: name.to_string(),
+            store_config,
+        }
+    }
+
+    fn region_store_config(
+        &self,
+        parent_dir: &str,
+        region_name: &str,
+        sst_format: SstFormat,
+        parquet_options: &ParquetOptions,
+        sst_index_columns: &[String],
+        dedup_policy: DedupPolicy,
+    ) -> StoreConfig<S> {
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
+        let write_options = self
+            .sst_write_options
+            .clone()
+            .with_parquet_options(parquet_options)
+            .with_index_columns(sst_index_columns.to_vec());
+        let sst_layer = Arc::new(
+            FsAccessLayer::new(sst_dir, self.object_store.clone())
+                .with_sst_format(sst_format)
+                .with_write_options(write_options),
+        );
         let manifest_dir = region_manifest_dir(&parent_dir, region_name);
         let manifest = RegionManifest::new(&manifest_dir, self.object_store.clone());
 
@@ -320,6 +521,10 @@ impl<S: LogStore> EngineInner<S> {
             memtable_builder: self.memtable_builder.clone(),
             flush_scheduler: self.flush_scheduler.clone(),
             flush_strategy: self.flush_strategy.clone(),
+            out_of_order_bucket: self.out_of_order_bucket,
+            max_files_in_level0: self.max_files_in_level0,
+            wal_replicator: self.wal_replicator.clone(),
+            dedup_policy,
         }
     }
 }
@@ -367,4 +572,39 @@ mod tests {
 
         assert!(engine.get_region(&ctx, "no such region").unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_close_region() {
+        let (log_store, _tmp) =
+            log_store_util::create_tmp_local_file_log_store("test_engine_wal").await;
+        let dir = TempDir::new("test_close_region").unwrap();
+        let store_dir = dir.path().to_string_lossy();
+
+        let accessor = Builder::default().root(&store_dir).build().unwrap();
+        let object_store = ObjectStore::new(accessor);
+        let engine = EngineImpl::new(EngineConfig::default(), Arc::new(log_store), object_store);
+
+        let region_name = "region-0";
+        let desc = RegionDescBuilder::new(region_name)
+            .push_key_column(("k1", LogicalTypeId::Int32, false))
+            .push_value_column(("v1", LogicalTypeId::Float32, true))
+            .build();
+        let ctx = EngineContext::default();
+        let region = engine
+            .create_region(&ctx, desc, &CreateOptions::default())
+            .await
+            .unwrap();
+
+        engine.close_region(&ctx, region).await.unwrap();
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_none());
+
+        // The closed region could be opened again.
+        let region = engine
+            .open_region(&ctx, region_name, &OpenOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(region_name, region.name());
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_some());
+    }
 }
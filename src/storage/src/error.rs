@@ -191,6 +191,20 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to write Arrow IPC file: {}, source: {}", file, source))]
+    WriteArrowIpc {
+        file: String,
+        source: ArrowError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to read Arrow IPC file: {}, source: {}", file, source))]
+    ReadArrowIpc {
+        file: String,
+        source: ArrowError,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Region is under {} state, cannot proceed operation", state))]
     InvalidRegionState {
         state: &'static str,
@@ -222,6 +236,26 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to replicate WAL, region_id: {}, {}", region_id, reason))]
+    ReplicateWal {
+        region_id: RegionId,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Missing replicated WAL entries, region_id: {}, expect sequence: {}, actual: {}",
+        region_id,
+        expected,
+        actual
+    ))]
+    WalReplicationGap {
+        region_id: RegionId,
+        expected: SequenceNumber,
+        actual: SequenceNumber,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Region version not found in manifest, the region: {}", region_name))]
     VersionNotFound {
         region_name: String,
@@ -429,6 +463,9 @@ pub enum Error {
         source: ArrowError,
     },
 
+    #[snafu(display("Bloom filter file is corrupted, path: {}", path))]
+    CorruptedBloomFilter { path: String, backtrace: Backtrace },
+
     #[snafu(display("Failed to parse schema, source: {}", source))]
     ParseSchema {
         backtrace: Backtrace,
@@ -437,6 +474,21 @@ pub enum Error {
 
     #[snafu(display("More columns than expected in the request"))]
     MoreColumnThanExpected { backtrace: Backtrace },
+
+    #[snafu(display("Failed to split region {}, {}", region, reason))]
+    InvalidSplit {
+        region: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to merge region {} and {}, {}", left, right, reason))]
+    InvalidMerge {
+        left: String,
+        right: String,
+        reason: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -459,7 +511,9 @@ impl ErrorExt for Error {
             | TypeMismatch { .. }
             | HasNull { .. }
             | UnequalLengths { .. }
-            | MoreColumnThanExpected { .. } => StatusCode::InvalidArguments,
+            | MoreColumnThanExpected { .. }
+            | InvalidSplit { .. }
+            | InvalidMerge { .. } => StatusCode::InvalidArguments,
 
             Utf8 { .. }
             | EncodeJson { .. }
@@ -488,6 +542,7 @@ impl ErrorExt for Error {
             | WriteParquet { .. }
             | ReadObject { .. }
             | WriteObject { .. }
+            | CorruptedBloomFilter { .. }
             | ListObjects { .. }
             | DeleteObject { .. }
             | WriteWal { .. }
@@ -497,8 +552,12 @@ impl ErrorExt for Error {
             | ManifestProtocolForbidWrite { .. }
             | ReadParquet { .. }
             | ReadParquetIo { .. }
+            | WriteArrowIpc { .. }
+            | ReadArrowIpc { .. }
             | InvalidRegionState { .. }
-            | ReadWal { .. } => StatusCode::StorageUnavailable,
+            | ReadWal { .. }
+            | ReplicateWal { .. }
+            | WalReplicationGap { .. } => StatusCode::StorageUnavailable,
 
             UnknownColumn { .. } => StatusCode::TableColumnNotFound,
 
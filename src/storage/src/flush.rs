@@ -12,26 +12,45 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use common_telemetry::logging;
+use common_telemetry::{logging, timer};
+use common_time::timestamp::TimeUnit;
+use datatypes::value::ValueRef;
+use datatypes::vectors::BooleanVector;
+use metrics::{counter, increment_counter};
 use store_api::logstore::LogStore;
 use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
-use store_api::storage::SequenceNumber;
+use store_api::storage::{DedupPolicy, SequenceNumber, SstFormat};
 use uuid::Uuid;
 
 use crate::background::{Context, Job, JobHandle, JobPoolRef};
+use crate::compaction::CompactionJob;
+use crate::config::DEFAULT_MAX_WRITE_BUFFER_SIZE;
 use crate::error::{CancelledSnafu, Result};
 use crate::manifest::action::*;
 use crate::manifest::region::RegionManifest;
-use crate::memtable::{IterContext, MemtableId, MemtableRef};
+use crate::memtable::{
+    BatchIterator, BoxedBatchIterator, IterContext, MemtableId, MemtableRef, RowOrdering,
+};
+use crate::metric::{
+    METRIC_COMPACTION_ERRORS_TOTAL, METRIC_FLUSH_BYTES_TOTAL, METRIC_FLUSH_ELAPSED,
+    METRIC_FLUSH_ERRORS_TOTAL, METRIC_FLUSH_FILES_TOTAL,
+};
+use crate::read::{Batch, BatchOp};
 use crate::region::{RegionWriterRef, SharedDataRef};
-use crate::sst::{AccessLayerRef, FileMeta, WriteOptions};
+use crate::schema::ProjectedSchemaRef;
+use crate::sst::{self, AccessLayerRef, FileMeta};
+use crate::tombstone::{MaskedBatchIterator, RangeTombstone, RangeTombstonesRef};
 use crate::wal::Wal;
 
-/// Default write buffer size (32M).
-const DEFAULT_WRITE_BUFFER_SIZE: usize = 32 * 1024 * 1024;
+/// Max number of SSTs an out-of-order memtable is flushed into, adjacent time buckets
+/// are written to the same SST if there are more buckets.
+const MAX_BUCKETS_PER_FLUSH: usize = 16;
 
 pub trait FlushStrategy: Send + Sync + std::fmt::Debug {
     fn should_flush(
@@ -44,12 +63,12 @@ pub trait FlushStrategy: Send + Sync + std::fmt::Debug {
 
 pub type FlushStrategyRef = Arc<dyn FlushStrategy>;
 
+/// Flushes the region once the memtables exceed the write buffer size, which can be
+/// changed at runtime.
 #[derive(Debug)]
 pub struct SizeBasedStrategy {
     /// Write buffer size of memtable.
-    max_write_buffer_size: usize,
-    /// Mutable memtable memory size limitation
-    mutable_limitation: usize,
+    max_write_buffer_size: AtomicUsize,
 }
 
 #[inline]
@@ -61,12 +80,26 @@ fn get_mutable_limitation(max_write_buffer_size: usize) -> usize {
 
 impl Default for SizeBasedStrategy {
     fn default() -> Self {
-        let max_write_buffer_size = DEFAULT_WRITE_BUFFER_SIZE;
+        Self::new(DEFAULT_MAX_WRITE_BUFFER_SIZE)
+    }
+}
+
+impl SizeBasedStrategy {
+    pub fn new(max_write_buffer_size: usize) -> Self {
         Self {
-            max_write_buffer_size,
-            mutable_limitation: get_mutable_limitation(max_write_buffer_size),
+            max_write_buffer_size: AtomicUsize::new(max_write_buffer_size),
         }
     }
+
+    pub fn max_write_buffer_size(&self) -> usize {
+        self.max_write_buffer_size.load(Ordering::Relaxed)
+    }
+
+    /// Sets the write buffer size, takes effect on the next write of each region.
+    pub fn set_max_write_buffer_size(&self, max_write_buffer_size: usize) {
+        self.max_write_buffer_size
+            .store(max_write_buffer_size, Ordering::Relaxed);
+    }
 }
 
 impl FlushStrategy for SizeBasedStrategy {
@@ -79,22 +112,22 @@ impl FlushStrategy for SizeBasedStrategy {
         // Insipired by RocksDB flush strategy
         // https://github.com/facebook/rocksdb/blob/main/include/rocksdb/write_buffer_manager.h#L94
 
-        if bytes_mutable > self.mutable_limitation {
+        let buffer_size = self.max_write_buffer_size();
+        let mutable_limitation = get_mutable_limitation(buffer_size);
+        if bytes_mutable > mutable_limitation {
             logging::info!(
                 "Region should flush, region: {}, bytes_mutable: {}, mutable_limitation: {}, \
                  bytes_total: {}, max_write_buffer_size: {} .",
                 shared.name(),
                 bytes_mutable,
-                self.mutable_limitation,
+                mutable_limitation,
                 bytes_total,
-                self.max_write_buffer_size
+                buffer_size
             );
 
             return true;
         }
 
-        let buffer_size = self.max_write_buffer_size;
-
         // If the memory exceeds the buffer size, we trigger more aggressive
         // flush. But if already more than half memory is being flushed,
         // triggering more flush may not help. We will hold it instead.
@@ -106,7 +139,7 @@ impl FlushStrategy for SizeBasedStrategy {
                  bytes_total: {}, max_write_buffer_size: {} .",
                 shared.name(),
                 bytes_mutable,
-                self.mutable_limitation,
+                mutable_limitation,
                 bytes_total,
                 buffer_size
             );
@@ -148,8 +181,18 @@ pub struct FlushJob<S: LogStore> {
     pub max_memtable_id: MemtableId,
     /// Memtables to be flushed.
     pub memtables: Vec<MemtableRef>,
+    /// Out-of-order memtables to be flushed, rows of them are flushed into one SST
+    /// per time bucket.
+    pub out_of_order_memtables: Vec<MemtableRef>,
+    /// Duration of the time buckets of out-of-order rows, out-of-order memtables are
+    /// flushed as other memtables if it's `None`.
+    pub out_of_order_bucket: Option<Duration>,
     /// Last sequence of data to be flushed.
     pub flush_sequence: SequenceNumber,
+    /// Range tombstones visible at `flush_sequence`, rows masked by them are not flushed.
+    pub range_tombstones: RangeTombstonesRef,
+    /// Range tombstones not persisted to the manifest yet.
+    pub tombstones_to_persist: Vec<RangeTombstone>,
     /// Shared data of region to be flushed.
     pub shared: SharedDataRef,
     /// Sst access layer of the region.
@@ -160,6 +203,11 @@ pub struct FlushJob<S: LogStore> {
     pub wal: Wal<S>,
     /// Region manifest service, used to persist metadata.
     pub manifest: RegionManifest,
+    /// SSTs of the region are compacted after the flush once there are more SSTs than
+    /// this, no compaction if it's `None`.
+    pub max_files_in_level0: Option<usize>,
+    /// How rows with the same keys and timestamp are deduplicated by compaction.
+    pub dedup_policy: DedupPolicy,
 }
 
 impl<S: LogStore> FlushJob<S> {
@@ -168,51 +216,135 @@ impl<S: LogStore> FlushJob<S> {
             return CancelledSnafu {}.fail();
         }
 
-        let mut futures = Vec::with_capacity(self.memtables.len());
         let iter_ctx = IterContext {
             for_flush: true,
             // TODO(ruihang): dynamic row group size based on content (#412)
             batch_size: WRITE_ROW_GROUP_SIZE,
             ..Default::default()
         };
+        let mut iters: Vec<BoxedBatchIterator> = Vec::with_capacity(self.memtables.len());
         for m in &self.memtables {
             // skip empty memtable
             if m.num_rows() == 0 {
                 continue;
             }
+            iters.push(self.memtable_iter(m, &iter_ctx)?);
+        }
+        for m in &self.out_of_order_memtables {
+            if m.num_rows() == 0 {
+                continue;
+            }
+            let Some(duration) = self.out_of_order_bucket else {
+                iters.push(self.memtable_iter(m, &iter_ctx)?);
+                continue;
+            };
+            // Flushes rows in each time bucket to a separate SST, so SSTs of out-of-order
+            // rows don't overlap with SSTs in other buckets.
+            let buckets = TimeBuckets::new(duration);
+            iters.extend(buckets.split(self.memtable_iter(m, &iter_ctx)?)?);
+        }
 
-            let file_name = Self::generate_sst_file_name();
+        let mut futures = Vec::with_capacity(iters.len());
+        for iter in iters {
+            let format = self.sst_layer.sst_format();
+            let file_name = Self::generate_sst_file_name(format);
             // TODO(hl): Check if random file name already exists in meta.
-            let iter = m.iter(&iter_ctx)?;
             futures.push(async move {
-                self.sst_layer
-                    .write_sst(&file_name, iter, &WriteOptions::default())
+                let info = self
+                    .sst_layer
+                    .write_sst(&file_name, iter, self.sst_layer.write_options())
                     .await?;
 
                 Ok(FileMeta {
                     file_name,
                     level: 0,
+                    time_range: info.time_range,
+                    num_rows: info.num_rows,
+                    file_size: info.file_size,
+                    format,
+                    index: info.index,
+                    bloom_filter: info.bloom_filter,
+                    source_region: None,
+                    flushed_sequence: Some(self.flush_sequence),
                 })
             });
         }
 
-        let metas = futures_util::future::join_all(futures)
+        let metas: Vec<FileMeta> = futures_util::future::join_all(futures)
             .await
             .into_iter()
             .collect::<Result<Vec<_>>>()?
             .into_iter()
             .collect();
 
+        counter!(METRIC_FLUSH_FILES_TOTAL, metas.len() as u64);
+        counter!(
+            METRIC_FLUSH_BYTES_TOTAL,
+            metas.iter().map(|m| m.file_size as u64).sum::<u64>()
+        );
         logging::info!("Successfully flush memtables to files: {:?}", metas);
         Ok(metas)
     }
 
+    /// Returns the iterator to flush the memtable `m`, rows masked by range tombstones
+    /// are skipped.
+    fn memtable_iter(&self, m: &MemtableRef, iter_ctx: &IterContext) -> Result<BoxedBatchIterator> {
+        let iter = m.iter(iter_ctx)?;
+        if self.range_tombstones.is_empty() {
+            return Ok(iter);
+        }
+
+        Ok(Box::new(MaskedBatchIterator::new(
+            iter,
+            self.range_tombstones.clone(),
+        )))
+    }
+
+    async fn flush(&self, ctx: &Context) -> Result<()> {
+        let file_metas = self.write_memtables_to_layer(ctx).await?;
+        self.write_manifest_and_apply(&file_metas).await?;
+        self.maybe_compact(ctx).await;
+        Ok(())
+    }
+
+    /// Compacts SSTs of the region if there are too many SSTs after the flush.
+    ///
+    /// The compaction runs in the flush job, so the next flush, or truncation of the
+    /// region, waits until it's finished. Failure of the compaction doesn't fail the
+    /// flush since the flushed SSTs are already applied.
+    async fn maybe_compact(&self, ctx: &Context) {
+        let Some(max_files) = self.max_files_in_level0 else {
+            return;
+        };
+        let files = self.shared.version_control.current().ssts().levels()[0]
+            .files()
+            .to_vec();
+        if files.len() <= max_files {
+            return;
+        }
+
+        let job = CompactionJob {
+            files,
+            dedup_policy: self.dedup_policy,
+            shared: self.shared.clone(),
+            sst_layer: self.sst_layer.clone(),
+            writer: self.writer.clone(),
+            wal: self.wal.clone(),
+            manifest: self.manifest.clone(),
+        };
+        if let Err(e) = job.compact(ctx).await {
+            increment_counter!(METRIC_COMPACTION_ERRORS_TOTAL);
+            logging::error!(e; "Failed to compact region: {}", self.shared.name());
+        }
+    }
+
     async fn write_manifest_and_apply(&self, file_metas: &[FileMeta]) -> Result<()> {
         let edit = RegionEdit {
             region_version: self.shared.version_control.metadata().version(),
             flushed_sequence: self.flush_sequence,
             files_to_add: file_metas.to_vec(),
             files_to_remove: Vec::default(),
+            range_tombstones: self.tombstones_to_persist.clone(),
         };
 
         self.writer
@@ -221,15 +353,167 @@ impl<S: LogStore> FlushJob<S> {
                 &self.shared,
                 &self.manifest,
                 edit,
-                self.max_memtable_id,
+                Some(self.max_memtable_id),
             )
             .await?;
         self.wal.obsolete(self.flush_sequence).await
     }
 
-    /// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$`
-    fn generate_sst_file_name() -> String {
-        format!("{}.parquet", Uuid::new_v4().hyphenated())
+    /// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.{ext}$`,
+    /// the extension `ext` depends on the `format` of the file.
+    pub(crate) fn generate_sst_file_name(format: SstFormat) -> String {
+        format!(
+            "{}.{}",
+            Uuid::new_v4().hyphenated(),
+            sst::file_extension(format)
+        )
+    }
+}
+
+/// Time buckets of fixed duration, aligned to the unix epoch.
+#[derive(Debug, Clone, Copy)]
+struct TimeBuckets {
+    /// Duration of each bucket in milliseconds.
+    duration_millis: i64,
+}
+
+impl TimeBuckets {
+    fn new(duration: Duration) -> TimeBuckets {
+        TimeBuckets {
+            duration_millis: (duration.as_millis() as i64).max(1),
+        }
+    }
+
+    /// Returns the bucket of the timestamp `value`, or `None` if it's not a timestamp.
+    fn bucket_of(&self, value: ValueRef) -> Option<i64> {
+        match value {
+            ValueRef::Timestamp(ts) => Some(
+                ts.convert_to(TimeUnit::Millisecond)
+                    .div_euclid(self.duration_millis),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Reads the `iter` once and splits its rows into one iterator per time bucket,
+    /// ordered by time. Adjacent buckets are coalesced if there are more than
+    /// [MAX_BUCKETS_PER_FLUSH] buckets.
+    ///
+    /// Rows are buffered in memory until all rows are read, which is bounded by the
+    /// size of the out-of-order memtable.
+    fn split(&self, iter: BoxedBatchIterator) -> Result<Vec<BoxedBatchIterator>> {
+        let schema = iter.schema();
+        let ordering = iter.ordering();
+        let Some(timestamps) = timestamp_index(&schema) else {
+            return Ok(vec![iter]);
+        };
+
+        let mut batches = Vec::new();
+        let mut buckets = BTreeSet::new();
+        for batch in iter {
+            let batch = batch?;
+            if batch.is_empty() {
+                continue;
+            }
+            let column = batch.column(timestamps);
+            let row_buckets: Vec<_> = (0..column.len())
+                .map(|i| self.bucket_of(column.get_ref(i)))
+                .collect();
+            buckets.extend(row_buckets.iter().flatten());
+            batches.push((batch, row_buckets));
+        }
+        if buckets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Index of the output of each bucket, `buckets_per_output` adjacent buckets are
+        // written to the same output.
+        let buckets_per_output =
+            (buckets.len() + MAX_BUCKETS_PER_FLUSH - 1) / MAX_BUCKETS_PER_FLUSH;
+        let outputs: HashMap<i64, usize> = buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| (*bucket, i / buckets_per_output))
+            .collect();
+        let num_outputs = (buckets.len() + buckets_per_output - 1) / buckets_per_output;
+
+        let mut output_batches: Vec<Vec<Batch>> = (0..num_outputs).map(|_| Vec::new()).collect();
+        for (batch, row_buckets) in batches {
+            let row_outputs: Vec<_> = row_buckets
+                .iter()
+                .map(|bucket| bucket.map(|b| outputs[&b]))
+                .collect();
+            let first = row_outputs[0];
+            if row_outputs.iter().all(|output| *output == first) {
+                if let Some(output) = first {
+                    output_batches[output].push(batch);
+                }
+                continue;
+            }
+
+            let batch_outputs: BTreeSet<_> = row_outputs.iter().flatten().copied().collect();
+            for output in batch_outputs {
+                let selected: Vec<bool> = row_outputs
+                    .iter()
+                    .map(|row_output| *row_output == Some(output))
+                    .collect();
+                output_batches[output].push(schema.filter(&batch, &BooleanVector::from(selected))?);
+            }
+        }
+
+        Ok(output_batches
+            .into_iter()
+            .map(|batches| {
+                Box::new(BufferedBatchIterator::new(
+                    schema.clone(),
+                    ordering,
+                    batches,
+                )) as _
+            })
+            .collect())
+    }
+}
+
+fn timestamp_index(schema: &ProjectedSchemaRef) -> Option<usize> {
+    schema.schema_to_read().schema().timestamp_index()
+}
+
+/// Iterator over batches buffered in memory.
+pub(crate) struct BufferedBatchIterator {
+    schema: ProjectedSchemaRef,
+    ordering: RowOrdering,
+    batches: std::vec::IntoIter<Batch>,
+}
+
+impl BufferedBatchIterator {
+    pub(crate) fn new(
+        schema: ProjectedSchemaRef,
+        ordering: RowOrdering,
+        batches: Vec<Batch>,
+    ) -> BufferedBatchIterator {
+        BufferedBatchIterator {
+            schema,
+            ordering,
+            batches: batches.into_iter(),
+        }
+    }
+}
+
+impl BatchIterator for BufferedBatchIterator {
+    fn schema(&self) -> ProjectedSchemaRef {
+        self.schema.clone()
+    }
+
+    fn ordering(&self) -> RowOrdering {
+        self.ordering
+    }
+}
+
+impl Iterator for BufferedBatchIterator {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Result<Batch>> {
+        self.batches.next().map(Ok)
     }
 }
 
@@ -237,9 +521,12 @@ impl<S: LogStore> FlushJob<S> {
 impl<S: LogStore> Job for FlushJob<S> {
     // TODO(yingwen): [flush] Support in-job parallelism (Flush memtables concurrently)
     async fn run(&mut self, ctx: &Context) -> Result<()> {
-        let file_metas = self.write_memtables_to_layer(ctx).await?;
-        self.write_manifest_and_apply(&file_metas).await?;
-        Ok(())
+        let _timer = timer!(METRIC_FLUSH_ELAPSED);
+        let result = self.flush(ctx).await;
+        if result.is_err() {
+            increment_counter!(METRIC_FLUSH_ERRORS_TOTAL);
+        }
+        result
     }
 }
 
@@ -257,13 +544,32 @@ mod tests {
         assert_eq!(56, get_mutable_limitation(64));
     }
 
+    #[test]
+    fn test_set_max_write_buffer_size() {
+        let strategy = SizeBasedStrategy::default();
+        assert_eq!(
+            DEFAULT_MAX_WRITE_BUFFER_SIZE,
+            strategy.max_write_buffer_size()
+        );
+
+        strategy.set_max_write_buffer_size(1024);
+        assert_eq!(1024, strategy.max_write_buffer_size());
+    }
+
     #[test]
     pub fn test_uuid_generate() {
-        let file_name = FlushJob::<NoopLogStore>::generate_sst_file_name();
+        let file_name = FlushJob::<NoopLogStore>::generate_sst_file_name(SstFormat::Parquet);
         let regex = Regex::new(r"^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$").unwrap();
         assert!(
             regex.is_match(&file_name),
             "Illegal sst file name: {file_name}",
         );
+
+        let file_name = FlushJob::<NoopLogStore>::generate_sst_file_name(SstFormat::ArrowIpc);
+        let regex = Regex::new(r"^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.arrow$").unwrap();
+        assert!(
+            regex.is_match(&file_name),
+            "Illegal sst file name: {file_name}",
+        );
     }
 }
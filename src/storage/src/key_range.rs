@@ -0,0 +1,176 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key ranges of regions created by splitting or merging other regions.
+//!
+//! Such regions reference the SSTs of their source regions instead of copying them,
+//! so a file may contain rows out of the range of the region. These rows are
+//! removed at read time, like rows masked by [range tombstones](crate::tombstone).
+
+use common_base::BitVec;
+use datatypes::value::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::read::Batch;
+
+/// Range `[start, end)` of values of a row key column owned by a region, an absent
+/// bound is unbounded.
+///
+/// Nulls are less than any other value, so they always belong to the region whose
+/// range has no start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRange {
+    /// Name of the row key column.
+    pub column: String,
+    /// Inclusive start of the range.
+    pub start: Option<Value>,
+    /// Exclusive end of the range.
+    pub end: Option<Value>,
+}
+
+impl KeyRange {
+    /// Returns the range covering all values of the `column`.
+    pub fn full(column: impl Into<String>) -> KeyRange {
+        KeyRange {
+            column: column.into(),
+            start: None,
+            end: None,
+        }
+    }
+
+    /// Returns true if `value` is in the range.
+    pub fn contains(&self, value: &Value) -> bool {
+        self.start
+            .as_ref()
+            .map(|start| value >= start)
+            .unwrap_or(true)
+            && self.end.as_ref().map(|end| value < end).unwrap_or(true)
+    }
+
+    /// Splits the range at `key` into `[start, key)` and `[key, end)`, returns `None`
+    /// if either part would be empty.
+    pub fn split(&self, key: &Value) -> Option<(KeyRange, KeyRange)> {
+        if key.is_null() || !self.contains(key) || self.start.as_ref() == Some(key) {
+            return None;
+        }
+
+        let left = KeyRange {
+            column: self.column.clone(),
+            start: self.start.clone(),
+            end: Some(key.clone()),
+        };
+        let right = KeyRange {
+            column: self.column.clone(),
+            start: Some(key.clone()),
+            end: self.end.clone(),
+        };
+        Some((left, right))
+    }
+
+    /// Merges this range with the adjacent range `right` on the same column, returns
+    /// `None` if they aren't adjacent.
+    pub fn merge(&self, right: &KeyRange) -> Option<KeyRange> {
+        if self.column != right.column || self.end.is_none() || self.end != right.start {
+            return None;
+        }
+
+        Some(KeyRange {
+            column: self.column.clone(),
+            start: self.start.clone(),
+            end: right.end.clone(),
+        })
+    }
+
+    /// Marks rows in `batch` out of the range as unselected, `column_index` is the
+    /// index of the key column in the batch.
+    pub fn unselect_out_of_range(&self, batch: &Batch, column_index: usize, selected: &mut BitVec) {
+        if self.start.is_none() && self.end.is_none() {
+            return;
+        }
+
+        let keys = batch.column(column_index);
+        for i in 0..keys.len() {
+            if !self.contains(&keys.get(i)) {
+                selected.set(i, false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::{StringVector, VectorRef};
+
+    use super::*;
+
+    fn new_range(start: Option<&str>, end: Option<&str>) -> KeyRange {
+        KeyRange {
+            column: "k".to_string(),
+            start: start.map(Value::from),
+            end: end.map(Value::from),
+        }
+    }
+
+    #[test]
+    fn test_key_range_contains() {
+        let range = new_range(Some("b"), Some("d"));
+        assert!(!range.contains(&Value::Null));
+        assert!(!range.contains(&Value::from("a")));
+        assert!(range.contains(&Value::from("b")));
+        assert!(range.contains(&Value::from("c")));
+        assert!(!range.contains(&Value::from("d")));
+
+        let full = KeyRange::full("k");
+        assert!(full.contains(&Value::Null));
+        assert!(full.contains(&Value::from("z")));
+    }
+
+    #[test]
+    fn test_split_and_merge_key_range() {
+        let full = KeyRange::full("k");
+        let (left, right) = full.split(&Value::from("m")).unwrap();
+        assert_eq!(new_range(None, Some("m")), left);
+        assert_eq!(new_range(Some("m"), None), right);
+
+        // The key must be inside the range and can't be the start.
+        assert!(right.split(&Value::from("m")).is_none());
+        assert!(right.split(&Value::from("a")).is_none());
+        assert!(full.split(&Value::Null).is_none());
+
+        assert_eq!(full, left.merge(&right).unwrap());
+        assert!(right.merge(&left).is_none());
+        let (a, _) = left.split(&Value::from("c")).unwrap();
+        assert!(a.merge(&right).is_none());
+    }
+
+    #[test]
+    fn test_unselect_out_of_range() {
+        let keys: VectorRef = Arc::new(StringVector::from(vec![
+            None,
+            Some("a"),
+            Some("m"),
+            Some("z"),
+        ]));
+        let batch = Batch::new(vec![keys]);
+        let mut selected = BitVec::repeat(true, 4);
+
+        new_range(Some("b"), None).unselect_out_of_range(&batch, 0, &mut selected);
+        assert_eq!(
+            vec![false, false, true, true],
+            selected.iter().by_vals().collect::<Vec<_>>()
+        );
+    }
+}
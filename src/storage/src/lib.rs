@@ -17,22 +17,27 @@
 mod background;
 mod chunk;
 pub mod codec;
+mod compaction;
 pub mod config;
 mod engine;
 pub mod error;
 mod flush;
+mod key_range;
 pub mod manifest;
 pub mod memtable;
 pub mod metadata;
+mod metric;
 pub mod proto;
 pub mod read;
 pub mod region;
+pub mod replication;
 pub mod schema;
 mod snapshot;
 mod sst;
 mod sync;
 #[cfg(test)]
 mod test_util;
+mod tombstone;
 mod version;
 mod wal;
 pub mod write_batch;
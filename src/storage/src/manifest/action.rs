@@ -25,9 +25,11 @@ use crate::error::{
     self, DecodeJsonSnafu, DecodeMetaActionListSnafu, ManifestProtocolForbidReadSnafu,
     ReadlineSnafu, Result,
 };
+use crate::key_range::KeyRange;
 use crate::manifest::helper;
 use crate::metadata::{ColumnFamilyMetadata, ColumnMetadata, VersionNumber};
 use crate::sst::FileMeta;
+use crate::tombstone::RangeTombstone;
 
 /// Minimal data that could be used to persist and recover [RegionMetadata](crate::metadata::RegionMetadata).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -76,6 +78,38 @@ pub struct RegionEdit {
     pub flushed_sequence: SequenceNumber,
     pub files_to_add: Vec<FileMeta>,
     pub files_to_remove: Vec<FileMeta>,
+    /// Range tombstones whose sequences are not greater than `flushed_sequence`, they
+    /// must be persisted before the WAL entries are purged.
+    #[serde(default)]
+    pub range_tombstones: Vec<RangeTombstone>,
+}
+
+/// Initial state of a region created by splitting another region, the SSTs of the
+/// parent region are referenced by both regions split from it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionSplit {
+    /// Name of the region being split.
+    pub parent: String,
+    pub key_range: KeyRange,
+    /// Flushed sequence of the parent region, the sequence of the new region starts
+    /// from it.
+    pub flushed_sequence: SequenceNumber,
+    pub files: Vec<FileMeta>,
+    pub range_tombstones: Vec<RangeTombstone>,
+}
+
+/// Initial state of a region created by merging two adjacent regions, SSTs of both
+/// regions are referenced by the new region.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionMerge {
+    /// Names of the regions being merged.
+    pub sources: Vec<String>,
+    pub key_range: KeyRange,
+    /// Max flushed sequence of the source regions.
+    pub flushed_sequence: SequenceNumber,
+    pub files: Vec<FileMeta>,
+    /// Range tombstones shared by the source regions.
+    pub range_tombstones: Vec<RangeTombstone>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -84,6 +118,8 @@ pub enum RegionMetaAction {
     Change(RegionChange),
     Remove(RegionRemove),
     Edit(RegionEdit),
+    Split(RegionSplit),
+    Merge(RegionMerge),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -221,4 +257,43 @@ mod tests {
         assert_eq!(decode_list, action_list);
         assert_eq!(p.unwrap(), protocol);
     }
+
+    #[test]
+    fn test_encode_decode_split_merge() {
+        let edit = test_utils::build_region_edit(10, &["test1"], &[]);
+        let files: Vec<_> = edit
+            .files_to_add
+            .iter()
+            .map(|file| file.referenced_from("parent"))
+            .collect();
+        let key_range = KeyRange {
+            column: "k".to_string(),
+            start: Some("m".into()),
+            end: None,
+        };
+        let action_list = RegionMetaActionList::new(vec![
+            RegionMetaAction::Split(RegionSplit {
+                parent: "parent".to_string(),
+                key_range: key_range.clone(),
+                flushed_sequence: 10,
+                files: files.clone(),
+                range_tombstones: vec![RangeTombstone {
+                    start: 0,
+                    end: 10,
+                    sequence: 5,
+                }],
+            }),
+            RegionMetaAction::Merge(RegionMerge {
+                sources: vec!["left".to_string(), "right".to_string()],
+                key_range,
+                flushed_sequence: 10,
+                files,
+                range_tombstones: vec![],
+            }),
+        ]);
+
+        let bs = action_list.encode().unwrap();
+        let (decode_list, _) = RegionMetaActionList::decode(&bs, 0).unwrap();
+        assert_eq!(decode_list, action_list);
+    }
 }
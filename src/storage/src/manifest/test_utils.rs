@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use datatypes::type_id::LogicalTypeId;
-use store_api::storage::SequenceNumber;
+use store_api::storage::{SequenceNumber, SstFormat};
 
 use crate::manifest::action::*;
 use crate::metadata::RegionMetadata;
@@ -43,6 +43,14 @@ pub fn build_region_edit(
             .map(|f| FileMeta {
                 file_name: f.to_string(),
                 level: 0,
+                time_range: None,
+                num_rows: 0,
+                file_size: 0,
+                format: SstFormat::default(),
+                index: None,
+                bloom_filter: None,
+                source_region: None,
+                flushed_sequence: None,
             })
             .collect(),
         files_to_remove: files_to_remove
@@ -50,7 +58,16 @@ pub fn build_region_edit(
             .map(|f| FileMeta {
                 file_name: f.to_string(),
                 level: 0,
+                time_range: None,
+                num_rows: 0,
+                file_size: 0,
+                format: SstFormat::default(),
+                index: None,
+                bloom_filter: None,
+                source_region: None,
+                flushed_sequence: None,
             })
             .collect(),
+        range_tombstones: Vec::new(),
     }
 }
@@ -21,8 +21,9 @@ mod version;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
+use common_time::Timestamp;
 use datatypes::vectors::VectorRef;
-use store_api::storage::{consts, OpType, SequenceNumber};
+use store_api::storage::{consts, DedupPolicy, OpType, SequenceNumber};
 
 use crate::error::Result;
 use crate::memtable::btree::BTreeMemtable;
@@ -58,6 +59,10 @@ pub trait Memtable: Send + Sync + std::fmt::Debug {
 
     /// Return the number of rows contained in this memtable.
     fn num_rows(&self) -> usize;
+
+    /// Returns the min and max (inclusive) timestamp of rows written to this memtable,
+    /// or `None` if the memtable is empty.
+    fn time_range(&self) -> Option<(Timestamp, Timestamp)>;
 }
 
 pub type MemtableRef = Arc<dyn Memtable>;
@@ -77,10 +82,18 @@ pub struct IterContext {
     /// Returns all rows, ignores sequence visibility and key duplication.
     pub for_flush: bool,
 
+    /// How rows with the same keys and timestamp are deduplicated, ignored if
+    /// `for_flush` is true.
+    pub dedup_policy: DedupPolicy,
+
     /// Schema the reader expect to read.
     ///
     /// Set to `None` to read all columns.
     pub projected_schema: Option<ProjectedSchemaRef>,
+
+    /// Returns rows in the reverse order of the key, so older versions of a key are
+    /// returned first. Versions of the same key are not deduplicated in this order.
+    pub reverse: bool,
 }
 
 impl Default for IterContext {
@@ -90,13 +103,15 @@ impl Default for IterContext {
             // All data in memory is visible by default.
             visible_sequence: SequenceNumber::MAX,
             for_flush: false,
+            dedup_policy: DedupPolicy::default(),
             projected_schema: None,
+            reverse: false,
         }
     }
 }
 
 /// The ordering of the iterator output.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RowOrdering {
     /// The output rows are unordered.
     Unordered,
@@ -16,13 +16,14 @@ use std::cmp::Ordering;
 use std::collections::{btree_map, BTreeMap};
 use std::ops::Bound;
 use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
+use common_time::Timestamp;
 use datatypes::data_type::DataType;
 use datatypes::prelude::*;
 use datatypes::value::Value;
 use datatypes::vectors::{UInt64Vector, UInt64VectorBuilder, UInt8Vector, UInt8VectorBuilder};
-use store_api::storage::{OpType, SequenceNumber};
+use store_api::storage::{DedupPolicy, OpType, SequenceNumber};
 
 use crate::error::Result;
 use crate::memtable::{
@@ -31,6 +32,7 @@ use crate::memtable::{
 use crate::read::Batch;
 use crate::schema::compat::ReadAdapter;
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
+use crate::sst;
 
 type RwLockMap = RwLock<BTreeMap<InnerKey, RowValue>>;
 
@@ -43,6 +45,7 @@ pub struct BTreeMemtable {
     schema: RegionSchemaRef,
     map: Arc<RwLockMap>,
     estimated_bytes: AtomicUsize,
+    time_range: Mutex<Option<(Timestamp, Timestamp)>>,
 }
 
 impl BTreeMemtable {
@@ -52,8 +55,25 @@ impl BTreeMemtable {
             schema,
             map: Arc::new(RwLock::new(BTreeMap::new())),
             estimated_bytes: AtomicUsize::new(0),
+            time_range: Mutex::new(None),
         }
     }
+
+    fn update_time_range(&self, kvs: &KeyValues) {
+        let Some(range) = kvs
+            .keys
+            .get(self.schema.timestamp_key_index())
+            .and_then(sst::timestamp_range)
+        else {
+            return;
+        };
+
+        let mut time_range = self.time_range.lock().unwrap();
+        *time_range = Some(match *time_range {
+            Some(current) => sst::merge_time_range(current, range),
+            None => range,
+        });
+    }
 }
 
 impl Memtable for BTreeMemtable {
@@ -74,6 +94,7 @@ impl Memtable for BTreeMemtable {
         for (inner_key, row_value) in iter_row {
             map.insert(inner_key, row_value);
         }
+        self.update_time_range(kvs);
 
         Ok(())
     }
@@ -93,6 +114,10 @@ impl Memtable for BTreeMemtable {
     fn num_rows(&self) -> usize {
         self.map.read().unwrap().len()
     }
+
+    fn time_range(&self) -> Option<(Timestamp, Timestamp)> {
+        *self.time_range.lock().unwrap()
+    }
 }
 
 struct BTreeIterator {
@@ -148,17 +173,31 @@ impl BTreeIterator {
 
     fn next_batch(&mut self) -> Result<Option<Batch>> {
         let map = self.map.read().unwrap();
-        let iter = if let Some(last_key) = &self.last_key {
-            map.range((Bound::Excluded(last_key), Bound::Unbounded))
-        } else {
-            map.range(..)
+        let iter = match (&self.last_key, self.ctx.reverse) {
+            (Some(last_key), false) => map.range((Bound::Excluded(last_key), Bound::Unbounded)),
+            (Some(last_key), true) => map.range((Bound::Unbounded, Bound::Excluded(last_key))),
+            (None, _) => map.range(..),
         };
 
-        let (keys, sequences, op_types, values) = if self.ctx.for_flush {
+        // Versions of the same key are only skipped while deduplicating rows. The newest
+        // version of a key is the last one in reverse order, so it is left to the reader
+        // to deduplicate them.
+        let dedup = !self.ctx.for_flush
+            && !self.ctx.reverse
+            && self.ctx.dedup_policy == DedupPolicy::LastWriteWins;
+        let (keys, sequences, op_types, values) = if self.ctx.reverse {
+            let visible_sequence = self.ctx.visible_sequence;
+            let iter = iter.rev().filter(|(k, _)| k.is_visible(visible_sequence));
             collect_iter(iter, self.ctx.batch_size)
-        } else {
+        } else if self.ctx.for_flush {
+            collect_iter(iter, self.ctx.batch_size)
+        } else if dedup {
             let iter = MapIterWrapper::new(iter, self.ctx.visible_sequence);
             collect_iter(iter, self.ctx.batch_size)
+        } else {
+            let visible_sequence = self.ctx.visible_sequence;
+            let iter = iter.filter(|(k, _)| k.is_visible(visible_sequence));
+            collect_iter(iter, self.ctx.batch_size)
         };
 
         if keys.is_empty() {
@@ -166,7 +205,9 @@ impl BTreeIterator {
         }
         self.last_key = keys.last().map(|k| {
             let mut last_key = (*k).clone();
-            last_key.reset_for_seek();
+            if dedup {
+                last_key.reset_for_seek();
+            }
             last_key
         });
 
@@ -12,10 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_time::timestamp::Timestamp;
+use datatypes::value::ValueRef;
+use datatypes::vectors::BooleanVector;
+use snafu::ResultExt;
 use store_api::storage::{OpType, SequenceNumber};
 
 use super::MemtableRef;
-use crate::error::Result;
+use crate::error::{self, Result};
 use crate::memtable::KeyValues;
 use crate::write_batch::{Mutation, Payload};
 
@@ -65,34 +69,116 @@ impl Inserter {
         Ok(())
     }
 
+    /// Insert write batch payload into memtables, rows older than `watermark` are inserted
+    /// into the `out_of_order` memtable and other rows are inserted into `memtable`.
+    ///
+    /// Both memtables should have the same schema as the `payload`.
+    pub fn insert_memtables(
+        &mut self,
+        payload: &Payload,
+        memtable: &MemtableRef,
+        out_of_order: &MemtableRef,
+        watermark: Timestamp,
+    ) -> Result<()> {
+        if payload.is_empty() {
+            return Ok(());
+        }
+
+        validate_input_and_memtable_schemas(payload, memtable);
+        validate_input_and_memtable_schemas(payload, out_of_order);
+
+        let total_column_num = payload.schema.num_columns();
+        let mut kvs = KeyValues {
+            sequence: self.sequence,
+            op_type: OpType::Put,
+            start_index_in_batch: self.index_in_batch,
+            keys: Vec::with_capacity(total_column_num),
+            values: Vec::with_capacity(total_column_num),
+        };
+
+        for mutation in &payload.mutations {
+            let late = late_rows(mutation, memtable, watermark);
+            if late.iter().all(|is_late| !is_late) {
+                self.write_one_mutation(mutation, memtable, &mut kvs)?;
+            } else if late.iter().all(|is_late| *is_late) {
+                self.write_one_mutation(mutation, out_of_order, &mut kvs)?;
+            } else {
+                let on_time = BooleanVector::from(late.iter().map(|v| !v).collect::<Vec<_>>());
+                let late = BooleanVector::from(late);
+                // Rows written to different memtables never have the same key, so they could
+                // share the same start index.
+                self.write_rows(mutation, out_of_order, Some(&late), &mut kvs)?;
+                self.write_rows(mutation, memtable, Some(&on_time), &mut kvs)?;
+                self.index_in_batch += mutation.record_batch.num_rows();
+            }
+        }
+
+        Ok(())
+    }
+
     fn write_one_mutation(
         &mut self,
         mutation: &Mutation,
         memtable: &MemtableRef,
         kvs: &mut KeyValues,
+    ) -> Result<()> {
+        self.write_rows(mutation, memtable, None, kvs)?;
+
+        self.index_in_batch += mutation.record_batch.num_rows();
+
+        Ok(())
+    }
+
+    /// Writes rows of the `mutation` selected by `filter` to the `memtable`, writes all
+    /// rows if `filter` is `None`.
+    fn write_rows(
+        &self,
+        mutation: &Mutation,
+        memtable: &MemtableRef,
+        filter: Option<&BooleanVector>,
+        kvs: &mut KeyValues,
     ) -> Result<()> {
         let schema = memtable.schema();
-        let num_rows = mutation.record_batch.num_rows();
 
         kvs.reset(mutation.op_type, self.index_in_batch);
 
+        let column = |idx: usize| {
+            let column = mutation.record_batch.column(idx);
+            match filter {
+                Some(filter) => column.filter(filter).context(error::FilterColumnSnafu {
+                    name: &mutation.record_batch.schema.column_schemas()[idx].name,
+                }),
+                None => Ok(column.clone()),
+            }
+        };
+
         for key_idx in schema.row_key_indices() {
-            kvs.keys.push(mutation.record_batch.column(key_idx).clone());
+            kvs.keys.push(column(key_idx)?);
         }
 
         for value_idx in schema.value_indices() {
-            kvs.values
-                .push(mutation.record_batch.column(value_idx).clone());
+            kvs.values.push(column(value_idx)?);
         }
 
-        memtable.write(kvs)?;
-
-        self.index_in_batch += num_rows;
-
-        Ok(())
+        memtable.write(kvs)
     }
 }
 
+/// Returns whether each row of the `mutation` is older than the `watermark`.
+fn late_rows(mutation: &Mutation, memtable: &MemtableRef, watermark: Timestamp) -> Vec<bool> {
+    let schema = memtable.schema();
+    // Safety: The region schema always has a timestamp key column.
+    let timestamp_idx = schema
+        .row_key_indices()
+        .nth(schema.timestamp_key_index())
+        .unwrap();
+    let timestamps = mutation.record_batch.column(timestamp_idx);
+
+    (0..timestamps.len())
+        .map(|i| matches!(timestamps.get_ref(i), ValueRef::Timestamp(ts) if ts < watermark))
+        .collect()
+}
+
 fn validate_input_and_memtable_schemas(payload: &Payload, memtable: &MemtableRef) {
     if cfg!(debug_assertions) {
         let payload_schema = &payload.schema;
@@ -236,4 +322,41 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_inserter_put_out_of_order() {
+        let sequence = 11111;
+        let memtable_schema = new_region_schema();
+        let memtable_builder = DefaultMemtableBuilder::default();
+        let mutable_memtable = memtable_builder.build(memtable_schema.clone());
+        let out_of_order = memtable_builder.build(memtable_schema);
+        let mut inserter = Inserter::new(sequence);
+
+        let mut batch = new_test_write_batch();
+        // All rows are in order.
+        put_batch(&mut batch, &[(101, Some(101)), (102, None)]);
+        // Some rows are out of order.
+        put_batch(&mut batch, &[(2, Some(2)), (201, Some(201)), (1, None)]);
+        // All rows are out of order.
+        put_batch(&mut batch, &[(3, Some(3))]);
+
+        inserter
+            .insert_memtables(
+                batch.payload(),
+                &mutable_memtable,
+                &out_of_order,
+                Timestamp::new_millisecond(100),
+            )
+            .unwrap();
+        check_memtable_content(
+            &mutable_memtable,
+            sequence,
+            &[(101, Some(101)), (102, None), (201, Some(201))],
+        );
+        check_memtable_content(
+            &out_of_order,
+            sequence,
+            &[(1, None), (2, Some(2)), (3, Some(3))],
+        );
+    }
 }
@@ -473,6 +473,7 @@ fn test_sequence_visibility() {
                 batch_size: 1,
                 visible_sequence: 9,
                 for_flush: false,
+                dedup_policy: DedupPolicy::LastWriteWins,
                 projected_schema: None,
             };
 
@@ -491,6 +492,7 @@ fn test_sequence_visibility() {
                 batch_size: 1,
                 visible_sequence: 10,
                 for_flush: false,
+                dedup_policy: DedupPolicy::LastWriteWins,
                 projected_schema: None,
             };
 
@@ -509,6 +511,7 @@ fn test_sequence_visibility() {
                 batch_size: 1,
                 visible_sequence: 11,
                 for_flush: false,
+                dedup_policy: DedupPolicy::LastWriteWins,
                 projected_schema: None,
             };
 
@@ -524,6 +527,83 @@ fn test_sequence_visibility() {
     });
 }
 
+#[test]
+fn test_keep_all_versions() {
+    let tester = MemtableTester::default();
+    tester.run_testcase(|ctx| {
+        for sequence in [10, 11, 12] {
+            let value = Some(sequence);
+            write_kvs(
+                &*ctx.memtable,
+                sequence,
+                OpType::Put,
+                &[(1000, 1), (1000, 2)], // keys
+                &[(value, None), (value, None)],
+            );
+        }
+
+        // Iterates one row per batch to ensure versions of the same key across batches
+        // are not skipped.
+        let iter_ctx = IterContext {
+            batch_size: 1,
+            visible_sequence: 11,
+            dedup_policy: DedupPolicy::KeepAll,
+            ..Default::default()
+        };
+        let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
+        check_iter_content(
+            &mut *iter,
+            &[(1000, 1), (1000, 1), (1000, 2), (1000, 2)], // keys
+            &[11, 10, 11, 10],                             // sequences
+            &[OpType::Put, OpType::Put, OpType::Put, OpType::Put], // op_types
+            &[
+                (Some(11), None),
+                (Some(10), None),
+                (Some(11), None),
+                (Some(10), None),
+            ], // values
+        );
+    });
+}
+
+#[test]
+fn test_reverse_iter() {
+    let tester = MemtableTester::default();
+    tester.run_testcase(|ctx| {
+        for sequence in [10, 11, 12] {
+            let value = Some(sequence);
+            write_kvs(
+                &*ctx.memtable,
+                sequence,
+                OpType::Put,
+                &[(1000, 1), (1001, 2)], // keys
+                &[(value, None), (value, None)],
+            );
+        }
+
+        // Versions of the same key are returned from the oldest one and not deduplicated.
+        let iter_ctx = IterContext {
+            batch_size: 1,
+            visible_sequence: 11,
+            reverse: true,
+            ..Default::default()
+        };
+        let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
+        check_iter_content(
+            &mut *iter,
+            &[(1001, 2), (1001, 2), (1000, 1), (1000, 1)], // keys
+            &[10, 11, 10, 11],                             // sequences
+            &[OpType::Put, OpType::Put, OpType::Put, OpType::Put], // op_types
+            &[
+                (Some(10), None),
+                (Some(11), None),
+                (Some(10), None),
+                (Some(11), None),
+            ], // values
+        );
+    });
+}
+
 #[test]
 fn test_iter_after_none() {
     let tester = MemtableTester::default();
@@ -592,3 +672,39 @@ fn test_memtable_projection() {
         assert_eq!(op_types, *batch.column(4));
     });
 }
+
+#[test]
+fn test_memtable_time_range() {
+    let memtable = DefaultMemtableBuilder::default().build(schema_for_test());
+    assert_eq!(None, memtable.time_range());
+
+    write_kvs(
+        &*memtable,
+        10, // sequence
+        OpType::Put,
+        &[(1000, 1), (3000, 1), (2000, 2)], // keys
+        &[(Some(1), None), (Some(2), None), (Some(3), None)], // values
+    );
+    assert_eq!(
+        Some((
+            Timestamp::new_millisecond(1000),
+            Timestamp::new_millisecond(3000)
+        )),
+        memtable.time_range()
+    );
+
+    write_kvs(
+        &*memtable,
+        11, // sequence
+        OpType::Delete,
+        &[(500, 1)],     // keys
+        &[(None, None)], // values
+    );
+    assert_eq!(
+        Some((
+            Timestamp::new_millisecond(500),
+            Timestamp::new_millisecond(3000)
+        )),
+        memtable.time_range()
+    );
+}
@@ -14,6 +14,7 @@
 
 use std::cmp::Ordering;
 
+use common_time::timestamp::Timestamp;
 use common_time::RangeMillis;
 
 use crate::memtable::{MemtableId, MemtableRef};
@@ -21,18 +22,33 @@ use crate::memtable::{MemtableId, MemtableRef};
 /// A version of all memtables.
 ///
 /// This structure is immutable now.
-#[derive(Debug)]
+/// Memtables of a region.
+///
+/// Rows older than the [watermark](MemtableVersion::watermark) arrive out of order, they
+/// are buffered in a separate out-of-order memtable instead of the mutable memtable, so
+/// SSTs flushed from the mutable memtables are ordered by time and cheap to prune, and
+/// the out-of-order rows are flushed into time bucketed SSTs.
+#[derive(Debug, Clone)]
 pub struct MemtableVersion {
     mutable: MemtableRef,
+    /// Mutable memtable of the out-of-order rows, created on the first out-of-order write.
+    out_of_order: Option<MemtableRef>,
     /// Immutable memtables.
     immutables: Vec<MemtableRef>,
+    /// Immutable out-of-order memtables.
+    out_of_order_immutables: Vec<MemtableRef>,
+    /// Max timestamp of the rows in frozen or flushed memtables.
+    watermark: Option<Timestamp>,
 }
 
 impl MemtableVersion {
     pub fn new(mutable: MemtableRef) -> MemtableVersion {
         Self {
             mutable,
+            out_of_order: None,
             immutables: vec![],
+            out_of_order_immutables: vec![],
+            watermark: None,
         }
     }
 
@@ -41,14 +57,58 @@ impl MemtableVersion {
         &self.mutable
     }
 
+    #[inline]
+    pub fn out_of_order_memtable(&self) -> Option<&MemtableRef> {
+        self.out_of_order.as_ref()
+    }
+
     #[inline]
     pub fn immutable_memtables(&self) -> &[MemtableRef] {
         &self.immutables
     }
 
+    #[inline]
+    pub fn out_of_order_immutables(&self) -> &[MemtableRef] {
+        &self.out_of_order_immutables
+    }
+
+    /// Returns all mutable and immutable memtables.
+    pub fn memtables(&self) -> impl Iterator<Item = &MemtableRef> {
+        std::iter::once(&self.mutable)
+            .chain(self.out_of_order.iter())
+            .chain(self.immutables.iter())
+            .chain(self.out_of_order_immutables.iter())
+    }
+
     pub fn num_memtables(&self) -> usize {
         // the last `1` is for `mutable`
-        self.immutable_memtables().len() + 1
+        self.immutables.len()
+            + self.out_of_order_immutables.len()
+            + usize::from(self.out_of_order.is_some())
+            + 1
+    }
+
+    /// Returns the watermark of the time index, rows older than it are out of order.
+    #[inline]
+    pub fn watermark(&self) -> Option<Timestamp> {
+        self.watermark
+    }
+
+    /// Clone current memtable version and set the out-of-order memtable to `out_of_order`.
+    pub fn with_out_of_order(&self, out_of_order: MemtableRef) -> MemtableVersion {
+        MemtableVersion {
+            out_of_order: Some(out_of_order),
+            ..self.clone()
+        }
+    }
+
+    /// Clone current memtable version and advance the watermark to `timestamp` if it's
+    /// newer.
+    pub fn advance_watermark(&self, timestamp: Timestamp) -> MemtableVersion {
+        MemtableVersion {
+            watermark: Some(self.watermark.map_or(timestamp, |w| w.max(timestamp))),
+            ..self.clone()
+        }
     }
 
     /// Clone current memtable version and freeze its mutable memtables, which moves
@@ -56,46 +116,70 @@ impl MemtableVersion {
     pub fn freeze_mutable(&self, new_mutable: MemtableRef) -> MemtableVersion {
         let mut immutables = self.immutables.clone();
         immutables.push(self.mutable.clone());
+        let mut out_of_order_immutables = self.out_of_order_immutables.clone();
+        out_of_order_immutables.extend(self.out_of_order.clone());
 
-        MemtableVersion {
+        let frozen = MemtableVersion {
             mutable: new_mutable,
+            out_of_order: None,
             immutables,
+            out_of_order_immutables,
+            watermark: self.watermark,
+        };
+        // Rows of the frozen mutable memtable will be flushed, rows older than them
+        // are out of order from now on.
+        match self.mutable.time_range() {
+            Some((_, max)) => frozen.advance_watermark(max),
+            None => frozen,
         }
     }
 
+    /// Returns bytes allocated by the mutable and out-of-order mutable memtables.
     pub fn mutable_bytes_allocated(&self) -> usize {
         self.mutable.bytes_allocated()
+            + self
+                .out_of_order
+                .as_ref()
+                .map_or(0, |m| m.bytes_allocated())
     }
 
     pub fn total_bytes_allocated(&self) -> usize {
-        self.immutables
-            .iter()
-            .map(|m| m.bytes_allocated())
-            .sum::<usize>()
-            + self.mutable.bytes_allocated()
+        self.memtables().map(|m| m.bytes_allocated()).sum()
     }
 
     /// Creates a new `MemtableVersion` that removes immutable memtables
     /// less than or equal to max_memtable_id.
     pub fn remove_immutables(&self, max_memtable_id: MemtableId) -> MemtableVersion {
-        let immutables = self
-            .immutables
-            .iter()
-            .filter(|immem| immem.id() > max_memtable_id)
-            .cloned()
-            .collect();
+        let retain = |memtables: &[MemtableRef]| {
+            memtables
+                .iter()
+                .filter(|immem| immem.id() > max_memtable_id)
+                .cloned()
+                .collect()
+        };
 
         MemtableVersion {
-            mutable: self.mutable.clone(),
-            immutables,
+            immutables: retain(&self.immutables),
+            out_of_order_immutables: retain(&self.out_of_order_immutables),
+            ..self.clone()
         }
     }
 
-    pub fn memtables_to_flush(&self) -> (Option<MemtableId>, Vec<MemtableRef>) {
-        let max_memtable_id = self.immutables.iter().map(|immem| immem.id()).max();
-        let memtables = self.immutables.clone();
+    /// Returns the max id of the immutable memtables to flush, the immutable memtables
+    /// and the immutable out-of-order memtables.
+    pub fn memtables_to_flush(&self) -> (Option<MemtableId>, Vec<MemtableRef>, Vec<MemtableRef>) {
+        let max_memtable_id = self
+            .immutables
+            .iter()
+            .chain(self.out_of_order_immutables.iter())
+            .map(|immem| immem.id())
+            .max();
 
-        (max_memtable_id, memtables)
+        (
+            max_memtable_id,
+            self.immutables.clone(),
+            self.out_of_order_immutables.clone(),
+        )
     }
 }
 
@@ -122,7 +206,10 @@ impl PartialOrd for RangeKey {
 mod tests {
     use std::sync::Arc;
 
+    use store_api::storage::OpType;
+
     use super::*;
+    use crate::memtable::tests::{schema_for_test, write_kvs};
     use crate::memtable::{DefaultMemtableBuilder, MemtableBuilder};
     use crate::test_util::schema_util;
 
@@ -147,9 +234,10 @@ mod tests {
         // Add another one and check immutable memtables that need flush
         let memtable_3 = memtable_builder.build(region_schema);
         let v3 = v2.freeze_mutable(memtable_3);
-        let (max_table_id, immutables) = v3.memtables_to_flush();
+        let (max_table_id, immutables, out_of_order) = v3.memtables_to_flush();
         assert_eq!(1, max_table_id.unwrap());
         assert_eq!(2, immutables.len());
+        assert!(out_of_order.is_empty());
 
         // Remove memtables
         let v4 = v3.remove_immutables(1);
@@ -157,4 +245,55 @@ mod tests {
         assert_eq!(0, v4.immutable_memtables().len());
         assert_eq!(2, v4.mutable_memtable().id());
     }
+
+    #[test]
+    fn test_out_of_order_memtable() {
+        let memtable_builder = DefaultMemtableBuilder::default();
+        let schema = schema_for_test();
+
+        let mutable = memtable_builder.build(schema.clone());
+        write_kvs(
+            &*mutable,
+            1,
+            OpType::Put,
+            &[(1000, 1), (2000, 1)],
+            &[(None, None); 2],
+        );
+        let v1 = MemtableVersion::new(mutable);
+        assert_eq!(None, v1.watermark());
+        assert!(v1.out_of_order_memtable().is_none());
+
+        // Freezing the mutable memtable advances the watermark.
+        let v2 = v1.freeze_mutable(memtable_builder.build(schema.clone()));
+        assert_eq!(Some(Timestamp::new_millisecond(2000)), v2.watermark());
+
+        let out_of_order = memtable_builder.build(schema.clone());
+        write_kvs(&*out_of_order, 2, OpType::Put, &[(500, 1)], &[(None, None)]);
+        let v3 = v2.with_out_of_order(out_of_order);
+        assert_eq!(2, v3.out_of_order_memtable().unwrap().id());
+        assert_eq!(3, v3.num_memtables());
+        assert_eq!(3, v3.memtables().count());
+        assert_eq!(
+            v3.mutable_memtable().bytes_allocated()
+                + v3.out_of_order_memtable().unwrap().bytes_allocated(),
+            v3.mutable_bytes_allocated()
+        );
+
+        // The mutable memtable is empty, so the watermark is unchanged.
+        let v4 = v3.freeze_mutable(memtable_builder.build(schema));
+        assert_eq!(Some(Timestamp::new_millisecond(2000)), v4.watermark());
+        assert!(v4.out_of_order_memtable().is_none());
+        let (max_memtable_id, immutables, out_of_order) = v4.memtables_to_flush();
+        assert_eq!(Some(2), max_memtable_id);
+        assert_eq!(2, immutables.len());
+        assert_eq!(1, out_of_order.len());
+
+        let v5 = v4.remove_immutables(2);
+        assert!(v5.immutable_memtables().is_empty());
+        assert!(v5.out_of_order_immutables().is_empty());
+
+        // The watermark never goes back.
+        let v6 = v5.advance_watermark(Timestamp::new_millisecond(100));
+        assert_eq!(Some(Timestamp::new_millisecond(2000)), v6.watermark());
+    }
 }
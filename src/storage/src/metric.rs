@@ -0,0 +1,50 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! storage metrics
+
+use common_telemetry::metric::{self, MetricDesc};
+
+pub const METRIC_FLUSH_ELAPSED: &str = "storage.flush_elapsed";
+pub const METRIC_FLUSH_ERRORS_TOTAL: &str = "storage.flush_errors_total";
+pub const METRIC_FLUSH_FILES_TOTAL: &str = "storage.flush_files_total";
+pub const METRIC_FLUSH_BYTES_TOTAL: &str = "storage.flush_bytes_total";
+pub const METRIC_COMPACTION_ERRORS_TOTAL: &str = "storage.compaction_errors_total";
+pub const METRIC_COMPACTION_FILES_TOTAL: &str = "storage.compaction_files_total";
+
+const METRICS: &[MetricDesc] = &[
+    MetricDesc::histogram(
+        METRIC_FLUSH_ELAPSED,
+        "Elapsed time of flush jobs in seconds",
+    ),
+    MetricDesc::counter(METRIC_FLUSH_ERRORS_TOTAL, "Number of failed flush jobs"),
+    MetricDesc::counter(METRIC_FLUSH_FILES_TOTAL, "Number of SST files flushed"),
+    MetricDesc::counter(
+        METRIC_FLUSH_BYTES_TOTAL,
+        "Size of SST files flushed in bytes",
+    ),
+    MetricDesc::counter(
+        METRIC_COMPACTION_ERRORS_TOTAL,
+        "Number of failed compactions",
+    ),
+    MetricDesc::counter(
+        METRIC_COMPACTION_FILES_TOTAL,
+        "Number of SST files written by compactions",
+    ),
+];
+
+/// Registers the metrics of the storage engine, it's called when the engine is created.
+pub(crate) fn register_metrics() {
+    metric::register_metrics(METRICS);
+}
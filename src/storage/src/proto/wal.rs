@@ -15,6 +15,7 @@
 #![allow(clippy::all)]
 tonic::include_proto!("greptime.storage.wal.v1");
 
+use common_time::range::TimeRange;
 use store_api::storage::OpType;
 
 use crate::write_batch::Payload;
@@ -30,6 +31,24 @@ pub fn gen_mutation_types(payload: &Payload) -> Vec<i32> {
         .collect::<Vec<_>>()
 }
 
+pub fn gen_delete_ranges(payload: &Payload) -> Vec<DeleteRange> {
+    payload
+        .delete_ranges
+        .iter()
+        .map(|range| DeleteRange {
+            start: *range.start(),
+            end: *range.end(),
+        })
+        .collect()
+}
+
+impl DeleteRange {
+    /// Returns the time range to delete, `None` if the range is invalid.
+    pub fn to_time_range(&self) -> Option<TimeRange<i64>> {
+        TimeRange::new(self.start, self.end)
+    }
+}
+
 impl WalHeader {
     pub fn with_last_manifest_version(last_manifest_version: u64) -> Self {
         Self {
@@ -16,6 +16,7 @@
 
 mod dedup;
 mod merge;
+mod reverse;
 
 use std::cmp::Ordering;
 
@@ -26,6 +27,7 @@ use datatypes::prelude::ConcreteDataType;
 use datatypes::vectors::{BooleanVector, MutableVector, VectorRef};
 pub use dedup::DedupReader;
 pub use merge::{MergeReader, MergeReaderBuilder};
+pub use reverse::ReverseReader;
 use snafu::{ensure, ResultExt};
 
 use crate::error::{self, Result};
@@ -81,7 +83,7 @@ impl Batch {
     ///
     /// # Panics
     /// Panics if `offset + length > self.num_rows()`.
-    fn slice(&self, offset: usize, length: usize) -> Batch {
+    pub fn slice(&self, offset: usize, length: usize) -> Batch {
         let columns = self
             .columns
             .iter()
@@ -259,6 +261,13 @@ pub trait BatchReader: Send {
 /// Pointer to [BatchReader].
 pub type BoxedBatchReader = Box<dyn BatchReader>;
 
+#[async_trait]
+impl<T: BatchReader + ?Sized> BatchReader for Box<T> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        (**self).next_batch().await
+    }
+}
+
 /// Concat reader inputs.
 pub struct ConcatReader {
     readers: Vec<BoxedBatchReader>,
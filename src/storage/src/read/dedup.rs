@@ -14,12 +14,17 @@
 
 use async_trait::async_trait;
 use common_base::BitVec;
-use datatypes::prelude::ScalarVector;
-use datatypes::vectors::BooleanVector;
+use common_query::logical_plan::Expr;
+use datatypes::prelude::{ScalarVector, Vector};
+use datatypes::vectors::{BooleanVector, UInt8Vector};
+use store_api::storage::{DedupPolicy, OpType};
+use table::predicate::ColumnFilter;
 
 use crate::error::Result;
+use crate::key_range::KeyRange;
 use crate::read::{Batch, BatchOp, BatchReader};
 use crate::schema::ProjectedSchemaRef;
+use crate::tombstone::RangeTombstonesRef;
 
 /// A reader that dedup rows from inner reader.
 pub struct DedupReader<R> {
@@ -31,6 +36,17 @@ pub struct DedupReader<R> {
     prev_batch: Option<Batch>,
     /// Reused bitmap buffer.
     selected: BitVec,
+    /// Range tombstones to mask deleted rows.
+    range_tombstones: Option<RangeTombstonesRef>,
+    /// Range of keys to return and the index of the key column in the batch.
+    key_range: Option<(KeyRange, usize)>,
+    /// Filters on row key columns to remove unmatched rows.
+    filters: Vec<ColumnFilter>,
+    /// How rows with the same keys are deduplicated.
+    dedup_policy: DedupPolicy,
+    /// Whether older rows of the last key in `prev_batch` are masked by a deletion, only
+    /// used if all rows are kept.
+    prev_deleted: bool,
 }
 
 impl<R> DedupReader<R> {
@@ -40,9 +56,58 @@ impl<R> DedupReader<R> {
             reader,
             prev_batch: None,
             selected: BitVec::default(),
+            range_tombstones: None,
+            key_range: None,
+            filters: Vec::new(),
+            dedup_policy: DedupPolicy::default(),
+            prev_deleted: false,
         }
     }
 
+    /// Keeps all rows with the same keys if `dedup_policy` is [DedupPolicy::KeepAll].
+    pub fn with_dedup_policy(mut self, dedup_policy: DedupPolicy) -> Self {
+        self.dedup_policy = dedup_policy;
+        self
+    }
+
+    /// Removes rows masked by `range_tombstones` after dedup.
+    pub fn with_range_tombstones(mut self, range_tombstones: RangeTombstonesRef) -> Self {
+        if !range_tombstones.is_empty() {
+            self.range_tombstones = Some(range_tombstones);
+        }
+        self
+    }
+
+    /// Removes rows out of the `key_range` after dedup.
+    pub fn with_key_range(mut self, key_range: Option<KeyRange>) -> Self {
+        self.key_range = key_range.and_then(|key_range| {
+            // The key column is a row key column, which is always read.
+            let index = self
+                .schema
+                .schema_to_read()
+                .schema()
+                .column_index_by_name(&key_range.column)?;
+            Some((key_range, index))
+        });
+        self
+    }
+
+    /// Removes rows not matching `filters` after dedup, so readers could return the
+    /// exact number of rows a limit asks for.
+    ///
+    /// Only filters on row key columns are applied, other filters are ignored. Value
+    /// columns are not always read and a deleted row may have a different value.
+    pub fn with_filters(mut self, filters: &[Expr]) -> Self {
+        let schema_to_read = self.schema.schema_to_read();
+        let row_key_end = schema_to_read.row_key_indices().count();
+        self.filters = filters
+            .iter()
+            .filter_map(|expr| ColumnFilter::try_new(expr, schema_to_read.schema()))
+            .filter(|filter| filter.columns().iter().all(|idx| *idx < row_key_end))
+            .collect();
+        self
+    }
+
     /// Take `batch` and then returns a new batch with no duplicated rows.
     ///
     /// This method may returns empty `Batch`.
@@ -66,13 +131,63 @@ impl<R> DedupReader<R> {
             .get_or_insert_with(Batch::default)
             .clone_from(&batch); // Use `clone_from` to reuse allocated memory if possible.
 
-        // Find all rows whose op_types are `OpType::Delete`, mark their `selected` to false.
-        self.schema.unselect_deleted(&batch, &mut self.selected);
+        match self.dedup_policy {
+            // Find all rows whose op_types are `OpType::Delete`, mark their `selected` to false.
+            DedupPolicy::LastWriteWins => self.schema.unselect_deleted(&batch, &mut self.selected),
+            DedupPolicy::KeepAll => self.unselect_masked_by_deletion(&batch),
+        }
+        // Find all rows masked by range tombstones. Checking them after dedup is enough since
+        // all versions of a row have the same timestamp, older versions are masked if the
+        // newest one is masked.
+        if let Some(range_tombstones) = &self.range_tombstones {
+            let schema_to_read = self.schema.schema_to_read();
+            if let Some(timestamp_index) = schema_to_read.schema().timestamp_index() {
+                range_tombstones.unselect_masked(
+                    &batch,
+                    timestamp_index,
+                    schema_to_read.sequence_index(),
+                    &mut self.selected,
+                );
+            }
+        }
+
+        // Rows of SSTs shared with other regions may be out of the key range.
+        if let Some((key_range, index)) = &self.key_range {
+            key_range.unselect_out_of_range(&batch, *index, &mut self.selected);
+        }
+
+        if !self.filters.is_empty() {
+            let columns = batch.columns();
+            for i in 0..batch.num_rows() {
+                if self.selected[i] && !self.filters.iter().all(|f| f.matches(columns, i)) {
+                    self.selected.set(i, false);
+                }
+            }
+        }
 
         let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
         // Filter duplicate rows.
         self.schema.filter(&batch, &filter)
     }
+
+    /// Selects all rows of `batch` except deletions and rows older than a deletion of
+    /// the same key, expects `selected` marks the newest row of each key.
+    fn unselect_masked_by_deletion(&mut self, batch: &Batch) {
+        let op_types = batch.column(self.schema.schema_to_read().op_type_index());
+        // Safety: The op_type column is always UInt8.
+        let op_types = op_types.as_any().downcast_ref::<UInt8Vector>().unwrap();
+        for (i, op_type) in op_types.iter_data().enumerate() {
+            // Rows of the same key are sorted by sequence in desc order, so a new key
+            // starts without deletion.
+            if self.selected[i] {
+                self.prev_deleted = false;
+            }
+            if op_type == Some(OpType::Delete.as_u8()) {
+                self.prev_deleted = true;
+            }
+            self.selected.set(i, !self.prev_deleted);
+        }
+    }
 }
 
 #[async_trait]
@@ -92,6 +207,8 @@ impl<R: BatchReader> BatchReader for DedupReader<R> {
 
 #[cfg(test)]
 mod tests {
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::{col, lit};
     use store_api::storage::OpType;
 
     use super::*;
@@ -178,4 +295,66 @@ mod tests {
         let expect = [(100, Some(1)), (101, Some(1)), (102, Some(12))];
         assert_eq!(&expect, &result[..]);
     }
+
+    #[tokio::test]
+    async fn test_dedup_keep_all() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Put),
+                (100, 2, 999, OpType::Put),
+                (101, 1, 1000, OpType::Put),
+                (101, 2, 999, OpType::Delete),
+            ],
+            // Rows older than the deletion in the previous batch are masked.
+            &[(101, 3, 998, OpType::Put), (102, 12, 1000, OpType::Delete)],
+            &[
+                (102, 13, 999, OpType::Put),
+                (103, 13, 1000, OpType::Put),
+                (103, 13, 1000, OpType::Put),
+            ],
+        ]);
+        let mut reader = DedupReader::new(schema, reader).with_dedup_policy(DedupPolicy::KeepAll);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [
+            (100, Some(1)),
+            (100, Some(2)),
+            (101, Some(1)),
+            (103, Some(13)),
+            (103, Some(13)),
+        ];
+        assert_eq!(&expect, &result[..]);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_with_filters() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Put),
+                (100, 2, 999, OpType::Put),
+                (101, 1, 1000, OpType::Put),
+            ],
+            &[(102, 2, 1000, OpType::Put), (103, 3, 1000, OpType::Put)],
+        ]);
+        let ts = |v: i64| lit(ScalarValue::TimestampMillisecond(Some(v), None));
+        let filters: Vec<Expr> = vec![
+            col(crate::test_util::TIMESTAMP_NAME)
+                .gt(ts(100))
+                .and(col(crate::test_util::TIMESTAMP_NAME).not_eq(ts(102))),
+            // Filters on value columns are ignored.
+            col("v0").gt(lit(10i64)),
+        ]
+        .into_iter()
+        .map(Expr::from)
+        .collect();
+        let mut reader = DedupReader::new(schema, reader).with_filters(&filters);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [(101, Some(1)), (103, Some(3))];
+        assert_eq!(&expect, &result[..]);
+    }
 }
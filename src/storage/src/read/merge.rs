@@ -224,6 +224,8 @@ struct Node {
     ///
     /// `None` means the `source` has reached EOF.
     cursor: Option<BatchCursor>,
+    /// Whether rows are sorted in reverse order.
+    reverse: bool,
 }
 
 impl fmt::Debug for Node {
@@ -236,12 +238,13 @@ impl fmt::Debug for Node {
 }
 
 impl Node {
-    async fn new(schema: ProjectedSchemaRef, mut source: Source) -> Result<Node> {
+    async fn new(schema: ProjectedSchemaRef, mut source: Source, reverse: bool) -> Result<Node> {
         let cursor = source.next_non_empty_batch().await?.map(BatchCursor::new);
         Ok(Node {
             schema,
             source,
             cursor,
+            reverse,
         })
     }
 
@@ -275,7 +278,17 @@ impl Node {
     /// Panics if
     /// - either `self` or `other` is EOF.
     fn compare_first_row(&self, other: &Node) -> Ordering {
-        self.first_row().compare(&self.schema, &other.first_row())
+        self.compare_rows(&self.first_row(), &other.first_row())
+    }
+
+    /// Compare two rows in the order of rows in the sources.
+    fn compare_rows(&self, left: &RowCursor, right: &RowCursor) -> Ordering {
+        let ordering = left.compare(&self.schema, right);
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
     }
 
     /// Returns true if no more batch could be fetched from this node.
@@ -294,7 +307,7 @@ impl Node {
         let last = other.last_row();
         // `self` is after `other` if min (first) row of `self` is greater than
         // max (last) row of `other`.
-        first.compare(&self.schema, &last) == Ordering::Greater
+        self.compare_rows(&first, &last) == Ordering::Greater
     }
 
     /// Fetch next batch and reset its cursor if `self` isn't EOF and the cursor
@@ -405,6 +418,8 @@ pub struct MergeReader {
     batch_size: usize,
     /// Buffered batch.
     batch_builder: BatchBuilder,
+    /// Whether rows of sources are sorted in reverse order.
+    reverse: bool,
 }
 
 #[async_trait]
@@ -418,6 +433,7 @@ pub struct MergeReaderBuilder {
     schema: ProjectedSchemaRef,
     sources: Vec<Source>,
     batch_size: usize,
+    reverse: bool,
 }
 
 impl MergeReaderBuilder {
@@ -430,6 +446,7 @@ impl MergeReaderBuilder {
             schema,
             sources: Vec::with_capacity(capacity),
             batch_size: consts::READ_BATCH_SIZE,
+            reverse: false,
         }
     }
 
@@ -448,6 +465,13 @@ impl MergeReaderBuilder {
         self
     }
 
+    /// Merges sources whose rows are sorted in reverse order, rows are returned in
+    /// reverse order then.
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
     pub fn build(self) -> MergeReader {
         let num_sources = self.sources.len();
         let column_schemas = self.schema.schema_to_read().schema().column_schemas();
@@ -464,6 +488,7 @@ impl MergeReaderBuilder {
             cold: BinaryHeap::with_capacity(num_sources),
             batch_size: self.batch_size,
             batch_builder,
+            reverse: self.reverse,
         }
     }
 }
@@ -481,7 +506,7 @@ impl MergeReader {
         }
 
         for source in self.sources.drain(..) {
-            let node = Node::new(self.schema.clone(), source).await?;
+            let node = Node::new(self.schema.clone(), source, self.reverse).await?;
 
             if !node.is_eof() {
                 self.cold.push(node);
@@ -625,12 +650,12 @@ mod tests {
     async fn test_node() {
         let schema = read_util::new_projected_schema();
         let left_source = read_util::build_boxed_iter(&[&[(1, None), (3, None), (5, None)]]);
-        let mut left = Node::new(schema.clone(), Source::Iter(left_source))
+        let mut left = Node::new(schema.clone(), Source::Iter(left_source), false)
             .await
             .unwrap();
 
         let right_source = read_util::build_boxed_reader(&[&[(2, None), (3, None), (6, None)]]);
-        let mut right = Node::new(schema.clone(), Source::Reader(right_source))
+        let mut right = Node::new(schema.clone(), Source::Reader(right_source), false)
             .await
             .unwrap();
 
@@ -825,4 +850,28 @@ mod tests {
         let reader = build_merge_reader(input, 2, 2);
         check_merge_reader_result(reader, input).await;
     }
+
+    #[tokio::test]
+    async fn test_merge_reverse() {
+        let input: &[Batches] = &[
+            &[&[(9, Some(9)), (5, Some(5))], &[(1, Some(1))]],
+            &[&[(8, Some(8)), (3, Some(3)), (2, Some(2))]],
+            &[&[(12, Some(12)), (7, Some(7))]],
+        ];
+        let schema = read_util::new_projected_schema();
+        let mut builder = MergeReaderBuilder::with_capacity(schema, input.len())
+            .batch_size(2)
+            .reverse(true);
+        for source in input {
+            builder = builder.push_batch_reader(read_util::build_boxed_reader(source));
+        }
+        let mut reader = builder.build();
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect: Vec<_> = [12, 9, 8, 7, 5, 3, 2, 1]
+            .into_iter()
+            .map(|k| (k, Some(k)))
+            .collect();
+        assert_eq!(expect, result);
+    }
 }
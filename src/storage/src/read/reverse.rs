@@ -0,0 +1,157 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::read::{Batch, BatchBuilder, BatchReader};
+use crate::schema::ProjectedSchemaRef;
+
+/// A reader that reorders rows merged in reverse order, so keys are still in
+/// descending order but versions of each key are sorted by sequence in descending
+/// order, as the [DedupReader](crate::read::DedupReader) expects.
+///
+/// Versions of the last key in a batch may continue in the next batch, so they are
+/// held until the next batch is read.
+pub struct ReverseReader<R> {
+    schema: ProjectedSchemaRef,
+    reader: R,
+    /// Rows of the last key read from the `reader`.
+    pending: Option<Batch>,
+}
+
+impl<R> ReverseReader<R> {
+    pub fn new(schema: ProjectedSchemaRef, reader: R) -> ReverseReader<R> {
+        ReverseReader {
+            schema,
+            reader,
+            pending: None,
+        }
+    }
+
+    fn new_builder(&self, capacity: usize) -> BatchBuilder {
+        let column_schemas = self.schema.schema_to_read().schema().column_schemas();
+        BatchBuilder::with_capacity(column_schemas.iter().map(|c| &c.data_type), capacity)
+    }
+
+    /// Returns true if the row keys of the `i-th` and `j-th` rows in `batch` are equal.
+    fn is_key_equal(&self, batch: &Batch, i: usize, j: usize) -> bool {
+        self.schema
+            .schema_to_read()
+            .row_key_indices()
+            .all(|idx| batch.column(idx).get_ref(i) == batch.column(idx).get_ref(j))
+    }
+
+    /// Prepends pending rows to the `batch`.
+    fn concat_pending(&mut self, batch: Batch) -> Result<Batch> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(batch);
+        };
+
+        let mut builder = self.new_builder(pending.num_rows() + batch.num_rows());
+        builder.extend_slice_of(&pending, 0, pending.num_rows())?;
+        builder.extend_slice_of(&batch, 0, batch.num_rows())?;
+        builder.build()
+    }
+
+    /// Reverses rows of each key in `batch`.
+    fn reorder(&self, batch: Batch) -> Result<Batch> {
+        let num_rows = batch.num_rows();
+        if (1..num_rows).all(|i| !self.is_key_equal(&batch, i - 1, i)) {
+            // Each key has only one version.
+            return Ok(batch);
+        }
+
+        let mut builder = self.new_builder(num_rows);
+        let mut start = 0;
+        while start < num_rows {
+            let mut end = start + 1;
+            while end < num_rows && self.is_key_equal(&batch, start, end) {
+                end += 1;
+            }
+            for i in (start..end).rev() {
+                builder.push_row_of(&batch, i)?;
+            }
+            start = end;
+        }
+        builder.build()
+    }
+}
+
+#[async_trait]
+impl<R: BatchReader> BatchReader for ReverseReader<R> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(batch) = self.reader.next_batch().await? {
+            if batch.is_empty() {
+                continue;
+            }
+
+            let batch = self.concat_pending(batch)?;
+            let last = batch.num_rows() - 1;
+            let mut start = last;
+            while start > 0 && self.is_key_equal(&batch, start - 1, last) {
+                start -= 1;
+            }
+            self.pending = Some(batch.slice(start, batch.num_rows() - start));
+            if start > 0 {
+                return self.reorder(batch.slice(0, start)).map(Some);
+            }
+        }
+
+        self.pending
+            .take()
+            .map(|pending| self.reorder(pending))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use store_api::storage::OpType;
+
+    use super::*;
+    use crate::test_util::read_util;
+
+    #[tokio::test]
+    async fn test_reverse_reader() {
+        let schema = read_util::new_projected_schema();
+        // Rows merged in reverse order, versions of the same key are sorted by sequence
+        // in ascending order.
+        let reader = read_util::build_full_vec_reader(&[
+            // key, value, sequence, op_type
+            &[
+                (100, 1, 1000, OpType::Put),
+                (99, 1, 1, OpType::Put),
+                (99, 2, 2, OpType::Delete),
+            ],
+            &[(99, 3, 3, OpType::Put), (98, 1, 1, OpType::Put)],
+            &[],
+            &[(97, 1, 1, OpType::Put), (97, 2, 2, OpType::Put)],
+        ]);
+        let mut reader = ReverseReader::new(schema, reader);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [
+            (100, Some(1)),
+            (99, Some(3)),
+            (99, Some(2)),
+            (99, Some(1)),
+            (98, Some(1)),
+            (97, Some(2)),
+            (97, Some(1)),
+        ];
+        assert_eq!(&expect, &result[..]);
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+}
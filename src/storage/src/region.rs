@@ -12,31 +12,39 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod metrics;
 #[cfg(test)]
 mod tests;
 mod writer;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use common_telemetry::logging;
-use snafu::ResultExt;
+use common_telemetry::tracing::{info_span, Instrument};
+use datatypes::value::Value;
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AlterRequest, OpenOptions, ReadContext, Region, RegionId, SequenceNumber, WriteContext,
-    WriteResponse,
+    AlterRequest, DedupPolicy, OpenOptions, ReadContext, Region, RegionId, RegionMetrics,
+    SequenceNumber, WriteContext, WriteResponse,
 };
 
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerRef, FlushStrategyRef};
+use crate::key_range::KeyRange;
 use crate::manifest::action::{
-    RawRegionMetadata, RegionChange, RegionMetaAction, RegionMetaActionList,
+    RawRegionMetadata, RegionChange, RegionMerge, RegionMetaAction, RegionMetaActionList,
+    RegionSplit,
 };
 use crate::manifest::region::RegionManifest;
 use crate::memtable::MemtableBuilderRef;
 use crate::metadata::{RegionMetaImpl, RegionMetadata, RegionMetadataRef};
+pub use crate::region::metrics::{RegionMetricsRecorder, RegionMetricsRecorderRef};
 pub use crate::region::writer::{AlterContext, RegionWriter, RegionWriterRef, WriterContext};
+use crate::replication::{ReplicatedEntryStream, WalReplicatorRef};
 use crate::schema::compat::CompatWrite;
 use crate::snapshot::SnapshotImpl;
 use crate::sst::AccessLayerRef;
@@ -80,10 +88,16 @@ impl<S: LogStore> Region for RegionImpl<S> {
     }
 
     async fn write(&self, ctx: &WriteContext, mut request: WriteBatch) -> Result<WriteResponse> {
+        let span = info_span!("region_write", region = self.name());
         // Compat the schema of the write batch outside of the write lock.
-        self.inner.compat_write_batch(&mut request)?;
+        span.in_scope(|| self.inner.compat_write_batch(&mut request))?;
 
-        self.inner.write(ctx, request).await
+        let num_rows = request.num_rows_to_mutate();
+        let start = Instant::now();
+        let response = self.inner.write(ctx, request).instrument(span).await?;
+        self.inner.metrics.on_write(num_rows, start.elapsed());
+
+        Ok(response)
     }
 
     fn snapshot(&self, _ctx: &ReadContext) -> Result<SnapshotImpl> {
@@ -101,6 +115,21 @@ impl<S: LogStore> Region for RegionImpl<S> {
     async fn alter(&self, request: AlterRequest) -> Result<()> {
         self.inner.alter(request).await
     }
+
+    async fn flush(&self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        self.inner.truncate().await
+    }
+
+    fn metrics(&self) -> RegionMetrics {
+        let version = self.inner.version_control().current();
+        self.inner
+            .metrics
+            .metrics(&version, self.inner.wal.bytes_written())
+    }
 }
 
 /// Storage related config for region.
@@ -114,6 +143,24 @@ pub struct StoreConfig<S> {
     pub memtable_builder: MemtableBuilderRef,
     pub flush_scheduler: FlushSchedulerRef,
     pub flush_strategy: FlushStrategyRef,
+    /// Duration of the time buckets of out-of-order rows, see
+    /// [EngineConfig::out_of_order_bucket](crate::config::EngineConfig::out_of_order_bucket).
+    pub out_of_order_bucket: Option<Duration>,
+    /// Max number of SSTs of the region before compaction, see
+    /// [EngineConfig::max_files_in_level0](crate::config::EngineConfig::max_files_in_level0).
+    pub max_files_in_level0: Option<usize>,
+    /// Ships the WAL of the region to its standby region, `None` if the region has
+    /// no standby.
+    pub wal_replicator: Option<WalReplicatorRef>,
+    /// How rows with the same keys and timestamp are deduplicated on read.
+    pub dedup_policy: DedupPolicy,
+}
+
+/// Id, name and storage config of a region to create from existing regions.
+pub struct RegionToCreate<S> {
+    pub id: RegionId,
+    pub name: String,
+    pub store_config: StoreConfig<S>,
 }
 
 pub type RecoverdMetadata = (SequenceNumber, (ManifestVersion, RawRegionMetadata));
@@ -155,7 +202,7 @@ impl<S: LogStore> RegionImpl<S> {
         let id = metadata.id();
         let name = metadata.name().to_string();
         let version_control = VersionControl::with_version(version);
-        let wal = Wal::new(id, store_config.log_store);
+        let wal = Wal::new(id, store_config.log_store).with_replicator(store_config.wal_replicator);
 
         let inner = Arc::new(RegionInner {
             shared: Arc::new(SharedData {
@@ -163,12 +210,19 @@ impl<S: LogStore> RegionImpl<S> {
                 name,
                 version_control: Arc::new(version_control),
             }),
-            writer: Arc::new(RegionWriter::new(store_config.memtable_builder)),
+            writer: Arc::new(RegionWriter::new(
+                store_config.memtable_builder,
+                store_config.out_of_order_bucket,
+                store_config.max_files_in_level0,
+                store_config.dedup_policy,
+            )),
             wal,
             flush_strategy: store_config.flush_strategy,
             flush_scheduler: store_config.flush_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            metrics: Arc::new(RegionMetricsRecorder::default()),
+            dedup_policy: store_config.dedup_policy,
         });
 
         RegionImpl { inner }
@@ -228,7 +282,8 @@ impl<S: LogStore> RegionImpl<S> {
             );
         }
 
-        let wal = Wal::new(metadata.id(), store_config.log_store);
+        let wal = Wal::new(metadata.id(), store_config.log_store)
+            .with_replicator(store_config.wal_replicator);
         wal.obsolete(flushed_sequence).await?;
         let shared = Arc::new(SharedData {
             id: metadata.id(),
@@ -236,7 +291,12 @@ impl<S: LogStore> RegionImpl<S> {
             version_control,
         });
 
-        let writer = Arc::new(RegionWriter::new(store_config.memtable_builder));
+        let writer = Arc::new(RegionWriter::new(
+            store_config.memtable_builder,
+            store_config.out_of_order_bucket,
+            store_config.max_files_in_level0,
+            store_config.dedup_policy,
+        ));
         let writer_ctx = WriterContext {
             shared: &shared,
             flush_strategy: &store_config.flush_strategy,
@@ -259,6 +319,8 @@ impl<S: LogStore> RegionImpl<S> {
             flush_scheduler: store_config.flush_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            metrics: Arc::new(RegionMetricsRecorder::default()),
+            dedup_policy: store_config.dedup_policy,
         });
 
         Ok(Some(RegionImpl { inner }))
@@ -269,6 +331,254 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.shared.id()
     }
 
+    /// Streams the WAL entries of this region from `start_seq`, so the standby region
+    /// could catch up after it falls behind.
+    pub async fn read_wal_entries(
+        &self,
+        start_seq: SequenceNumber,
+    ) -> Result<ReplicatedEntryStream<'_>> {
+        self.inner.wal.read_entries(start_seq).await
+    }
+
+    /// Returns the WAL sequence acknowledged by the standby region, `None` if this region
+    /// has no standby.
+    pub fn replicated_sequence(&self) -> Option<SequenceNumber> {
+        self.inner.wal.replicated_sequence()
+    }
+
+    /// Split this region into two regions at `split_key` of the row key column
+    /// `column_name`.
+    ///
+    /// The region is flushed first, then both new regions reference all SSTs of this
+    /// region and only read keys inside their own key range, so no data is copied. Rows
+    /// written after the flush aren't in the new regions, so the caller fences writes to
+    /// this region until the new regions replace it, as the mito table does.
+    pub async fn split(
+        &self,
+        column_name: &str,
+        split_key: Value,
+        left: RegionToCreate<S>,
+        right: RegionToCreate<S>,
+    ) -> Result<(RegionImpl<S>, RegionImpl<S>)> {
+        self.inner.flush().await?;
+
+        let region = self.name();
+        let version = self.inner.version_control().current();
+        ensure!(
+            !has_unflushed_rows(&version),
+            error::InvalidSplitSnafu {
+                region,
+                reason: "region still has unflushed rows",
+            }
+        );
+
+        let metadata = version.metadata();
+        let column = metadata
+            .columns
+            .iter_row_key_columns()
+            .find(|column| column.name() == column_name)
+            .with_context(|| error::InvalidSplitSnafu {
+                region,
+                reason: format!("{} is not a row key column", column_name),
+            })?;
+        ensure!(
+            split_key.data_type() == column.desc.data_type,
+            error::InvalidSplitSnafu {
+                region,
+                reason: format!(
+                    "split key {:?} does not match type {:?} of column {}",
+                    split_key, column.desc.data_type, column_name
+                ),
+            }
+        );
+
+        let key_range = match version.key_range() {
+            Some(key_range) => {
+                ensure!(
+                    key_range.column == column_name,
+                    error::InvalidSplitSnafu {
+                        region,
+                        reason: format!("region is already split by column {}", key_range.column),
+                    }
+                );
+                key_range.clone()
+            }
+            None => KeyRange::full(column_name),
+        };
+        let (left_range, right_range) =
+            key_range
+                .split(&split_key)
+                .with_context(|| error::InvalidSplitSnafu {
+                    region,
+                    reason: format!("split key {:?} is not inside the region", split_key),
+                })?;
+
+        let sequence = self.inner.version_control().committed_sequence();
+        let files: Vec<_> = version
+            .ssts()
+            .files()
+            .map(|file| file.meta().referenced_from(region))
+            .collect();
+        let range_tombstones: Vec<_> = version.range_tombstones().iter().cloned().collect();
+        let split_action = |key_range| {
+            RegionMetaAction::Split(RegionSplit {
+                parent: region.to_string(),
+                key_range,
+                flushed_sequence: sequence,
+                files: files.clone(),
+                range_tombstones: range_tombstones.clone(),
+            })
+        };
+
+        let left = Self::create_derived(metadata, left, split_action(left_range), sequence).await?;
+        let right =
+            Self::create_derived(metadata, right, split_action(right_range), sequence).await?;
+
+        logging::info!(
+            "Region {} is split into {} and {} at key {:?}, num_files: {}",
+            region,
+            left.name(),
+            right.name(),
+            split_key,
+            files.len(),
+        );
+
+        Ok((left, right))
+    }
+
+    /// Merge this region and its adjacent region `right` into a new region.
+    ///
+    /// Both regions are flushed first and the new region references SSTs of both
+    /// regions. Rows written after the flush aren't in the new region, so the caller
+    /// fences writes to both regions until the new region replaces them, as the mito
+    /// table does.
+    pub async fn merge(
+        &self,
+        right: &RegionImpl<S>,
+        target: RegionToCreate<S>,
+    ) -> Result<RegionImpl<S>> {
+        self.inner.flush().await?;
+        right.inner.flush().await?;
+
+        let (left_name, right_name) = (self.name(), right.name());
+        let left_version = self.inner.version_control().current();
+        let right_version = right.inner.version_control().current();
+        let invalid_merge = |reason: &'static str| error::InvalidMergeSnafu {
+            left: left_name,
+            right: right_name,
+            reason,
+        };
+
+        ensure!(
+            !has_unflushed_rows(&left_version) && !has_unflushed_rows(&right_version),
+            invalid_merge("regions still have unflushed rows")
+        );
+        ensure!(
+            left_version.metadata().version() == right_version.metadata().version(),
+            invalid_merge("regions have different schema versions")
+        );
+        // Tombstones only have a time range, so tombstones of one region would also
+        // delete rows of the other region after merging.
+        ensure!(
+            !masks_rows_of(&left_version, &right_version)
+                && !masks_rows_of(&right_version, &left_version),
+            invalid_merge("regions have different range tombstones")
+        );
+        let key_range = left_version
+            .key_range()
+            .zip(right_version.key_range())
+            .and_then(|(left, right)| left.merge(right))
+            .with_context(|| invalid_merge("regions are not adjacent"))?;
+
+        let mut referenced = HashSet::new();
+        let files: Vec<_> = left_version
+            .ssts()
+            .files()
+            .map(|file| file.meta().referenced_from(left_name))
+            .chain(
+                right_version
+                    .ssts()
+                    .files()
+                    .map(|file| file.meta().referenced_from(right_name)),
+            )
+            // Both regions may reference the same SST of the region they are split from.
+            .filter(|meta| referenced.insert((meta.source_region.clone(), meta.file_name.clone())))
+            .collect();
+        let sequence = self
+            .inner
+            .version_control()
+            .committed_sequence()
+            .max(right.inner.version_control().committed_sequence());
+        let action = RegionMetaAction::Merge(RegionMerge {
+            sources: vec![left_name.to_string(), right_name.to_string()],
+            key_range,
+            flushed_sequence: sequence,
+            files,
+            range_tombstones: left_version
+                .range_tombstones()
+                .merge(right_version.range_tombstones().iter().copied())
+                .iter()
+                .copied()
+                .collect(),
+        });
+
+        let region =
+            Self::create_derived(left_version.metadata(), target, action, sequence).await?;
+
+        logging::info!(
+            "Regions {} and {} are merged into {}",
+            left_name,
+            right_name,
+            region.name(),
+        );
+
+        Ok(region)
+    }
+
+    /// Create a region with the schema of `metadata` and initial state from the split
+    /// or merge `action`, and persist them to the manifest of the new region.
+    async fn create_derived(
+        metadata: &RegionMetadataRef,
+        target: RegionToCreate<S>,
+        action: RegionMetaAction,
+        committed_sequence: SequenceNumber,
+    ) -> Result<RegionImpl<S>> {
+        let mut raw_metadata = RawRegionMetadata::from(metadata.as_ref());
+        raw_metadata.id = target.id;
+        raw_metadata.name = target.name.clone();
+        let metadata: RegionMetadataRef = Arc::new(raw_metadata.try_into().context(
+            error::InvalidRawRegionSnafu {
+                region: &target.name,
+            },
+        )?);
+
+        let store_config = target.store_config;
+        let manifest_version = store_config
+            .manifest
+            .update(RegionMetaActionList::new(vec![
+                RegionMetaAction::Change(RegionChange {
+                    metadata: metadata.as_ref().into(),
+                    committed_sequence: INIT_COMMITTED_SEQUENCE,
+                }),
+                action.clone(),
+            ]))
+            .await?;
+
+        let mutable_memtable = store_config
+            .memtable_builder
+            .build(metadata.schema().clone());
+        let version = Version::with_manifest_version(metadata, manifest_version, mutable_memtable);
+        // Replaying an action to an existing version always returns a version.
+        let version = Self::replay_edit(manifest_version, action, Some(version)).unwrap();
+        let region = RegionImpl::new(version, store_config);
+        region
+            .inner
+            .version_control()
+            .set_committed_sequence(committed_sequence);
+
+        Ok(region)
+    }
+
     async fn recover_from_manifest(
         manifest: &RegionManifest,
         memtable_builder: &MemtableBuilderRef,
@@ -341,23 +651,78 @@ impl<S: LogStore> RegionImpl<S> {
         action: RegionMetaAction,
         version: Option<Version>,
     ) -> Option<Version> {
-        if let RegionMetaAction::Edit(e) = action {
-            let edit = VersionEdit {
-                files_to_add: e.files_to_add,
-                flushed_sequence: Some(e.flushed_sequence),
-                manifest_version,
-                max_memtable_id: None,
-            };
-            version.map(|mut v| {
-                v.apply_edit(edit);
-                v
-            })
-        } else {
-            version
-        }
+        let (edit, key_range) = match action {
+            RegionMetaAction::Edit(e) => {
+                let edit = VersionEdit {
+                    files_to_add: e.files_to_add,
+                    files_to_remove: e.files_to_remove,
+                    flushed_sequence: Some(e.flushed_sequence),
+                    manifest_version,
+                    max_memtable_id: None,
+                    range_tombstones: e.range_tombstones,
+                };
+                (edit, None)
+            }
+            RegionMetaAction::Split(s) => {
+                let edit = VersionEdit {
+                    files_to_add: s.files,
+                    files_to_remove: Vec::new(),
+                    flushed_sequence: Some(s.flushed_sequence),
+                    manifest_version,
+                    max_memtable_id: None,
+                    range_tombstones: s.range_tombstones,
+                };
+                (edit, Some(s.key_range))
+            }
+            RegionMetaAction::Merge(m) => {
+                let edit = VersionEdit {
+                    files_to_add: m.files,
+                    files_to_remove: Vec::new(),
+                    flushed_sequence: Some(m.flushed_sequence),
+                    manifest_version,
+                    max_memtable_id: None,
+                    range_tombstones: m.range_tombstones,
+                };
+                (edit, Some(m.key_range))
+            }
+            _ => return version,
+        };
+
+        version.map(|mut v| {
+            v.apply_edit(edit);
+            if let Some(key_range) = key_range {
+                v.set_key_range(key_range);
+            }
+            v
+        })
     }
 }
 
+/// Returns true if any memtable of the `version` still has rows.
+fn has_unflushed_rows(version: &Version) -> bool {
+    version
+        .memtables()
+        .memtables()
+        .any(|memtable| memtable.num_rows() > 0)
+}
+
+/// Returns true if range tombstones of the `version` that the `other` version doesn't
+/// have might mask rows in SSTs of the `other` version. Tombstones are pruned from a
+/// version once they can't mask its rows, so versions may have different tombstones.
+fn masks_rows_of(version: &Version, other: &Version) -> bool {
+    let other_tombstones = other.range_tombstones();
+    version
+        .range_tombstones()
+        .iter()
+        .filter(|t| !other_tombstones.iter().any(|other| other == *t))
+        .any(|t| {
+            other
+                .ssts()
+                .files()
+                .any(|file| file.meta().may_be_masked_by(t))
+        })
+}
+
 // Private methods for tests.
 #[cfg(test)]
 impl<S: LogStore> RegionImpl<S> {
@@ -370,6 +735,10 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.version_control().current_manifest_version()
     }
 
+    fn version(&self) -> crate::version::VersionRef {
+        self.inner.version_control().current()
+    }
+
     async fn wait_flush_done(&self) -> Result<()> {
         self.inner.writer.wait_flush_done().await
     }
@@ -430,6 +799,8 @@ struct RegionInner<S: LogStore> {
     flush_scheduler: FlushSchedulerRef,
     sst_layer: AccessLayerRef,
     manifest: RegionManifest,
+    metrics: RegionMetricsRecorderRef,
+    dedup_policy: DedupPolicy,
 }
 
 impl<S: LogStore> RegionInner<S> {
@@ -448,7 +819,13 @@ impl<S: LogStore> RegionInner<S> {
         let version = self.version_control().current();
         let sequence = self.version_control().committed_sequence();
 
-        SnapshotImpl::new(version, sequence, self.sst_layer.clone())
+        SnapshotImpl::new(
+            version,
+            sequence,
+            self.sst_layer.clone(),
+            self.metrics.clone(),
+        )
+        .with_dedup_policy(self.dedup_policy)
     }
 
     fn compat_write_batch(&self, request: &mut WriteBatch) -> Result<()> {
@@ -492,4 +869,38 @@ impl<S: LogStore> RegionInner<S> {
 
         self.writer.alter(alter_ctx, request).await
     }
+
+    async fn flush(&self) -> Result<()> {
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+
+        self.writer.flush(writer_ctx).await
+    }
+
+    async fn truncate(&self) -> Result<()> {
+        logging::info!(
+            "Truncate region {}, name: {}",
+            self.shared.id,
+            self.shared.name
+        );
+
+        let writer_ctx = WriterContext {
+            shared: &self.shared,
+            flush_strategy: &self.flush_strategy,
+            flush_scheduler: &self.flush_scheduler,
+            sst_layer: &self.sst_layer,
+            wal: &self.wal,
+            writer: &self.writer,
+            manifest: &self.manifest,
+        };
+
+        self.writer.truncate(writer_ctx).await
+    }
 }
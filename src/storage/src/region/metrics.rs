@@ -0,0 +1,102 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Counters of the read and write path of a region.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use store_api::storage::RegionMetrics;
+
+use crate::sst::MAX_LEVEL;
+use crate::version::Version;
+
+/// Records counters of the read and write path of a region.
+#[derive(Debug, Default)]
+pub struct RegionMetricsRecorder {
+    write_rows: AtomicU64,
+    write_batches: AtomicU64,
+    write_elapsed_micros: AtomicU64,
+    scans: AtomicU64,
+    scan_elapsed_micros: AtomicU64,
+}
+
+pub type RegionMetricsRecorderRef = Arc<RegionMetricsRecorder>;
+
+impl RegionMetricsRecorder {
+    /// Records a write batch of `num_rows` rows that takes `elapsed` to write.
+    pub fn on_write(&self, num_rows: usize, elapsed: Duration) {
+        self.write_rows
+            .fetch_add(num_rows as u64, Ordering::Relaxed);
+        self.write_batches.fetch_add(1, Ordering::Relaxed);
+        self.write_elapsed_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Records a scan that takes `elapsed` to open.
+    pub fn on_scan(&self, elapsed: Duration) {
+        self.scans.fetch_add(1, Ordering::Relaxed);
+        self.scan_elapsed_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the metrics of the region whose current version is `version`.
+    pub fn metrics(&self, version: &Version, wal_bytes: u64) -> RegionMetrics {
+        let mut metrics = RegionMetrics {
+            write_rows: self.write_rows.load(Ordering::Relaxed),
+            write_batches: self.write_batches.load(Ordering::Relaxed),
+            write_elapsed_micros: self.write_elapsed_micros.load(Ordering::Relaxed),
+            scans: self.scans.load(Ordering::Relaxed),
+            scan_elapsed_micros: self.scan_elapsed_micros.load(Ordering::Relaxed),
+            sst_files_per_level: vec![0; MAX_LEVEL],
+            wal_bytes,
+            ..Default::default()
+        };
+
+        let memtable_version = version.memtables();
+        for memtable in memtable_version.memtables() {
+            metrics.memtable_bytes += memtable.bytes_allocated() as u64;
+            metrics.num_rows += memtable.num_rows() as u64;
+        }
+
+        for file in version.ssts().files() {
+            let meta = file.meta();
+            metrics.sst_files_per_level[file.level_index()] += 1;
+            metrics.sst_bytes += meta.file_size as u64;
+            metrics.num_rows += meta.num_rows as u64;
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_region_metrics() {
+        let recorder = RegionMetricsRecorder::default();
+        recorder.on_write(10, Duration::from_micros(100));
+        recorder.on_write(5, Duration::from_micros(50));
+        recorder.on_scan(Duration::from_micros(30));
+
+        assert_eq!(15, recorder.write_rows.load(Ordering::Relaxed));
+        assert_eq!(2, recorder.write_batches.load(Ordering::Relaxed));
+        assert_eq!(150, recorder.write_elapsed_micros.load(Ordering::Relaxed));
+        assert_eq!(1, recorder.scans.load(Ordering::Relaxed));
+        assert_eq!(30, recorder.scan_elapsed_micros.load(Ordering::Relaxed));
+    }
+}
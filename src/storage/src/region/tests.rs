@@ -16,12 +16,16 @@
 
 mod alter;
 mod basic;
+mod crash;
+mod dedup;
 mod flush;
 mod projection;
+mod split_merge;
 
 use std::collections::HashMap;
 
 use common_telemetry::logging;
+use common_time::{Timestamp, TimestampRange};
 use datatypes::prelude::{ScalarVector, WrapperType};
 use datatypes::timestamp::TimestampMillisecond;
 use datatypes::type_id::LogicalTypeId;
@@ -71,6 +75,11 @@ impl<S: LogStore> TesterBase<S> {
     ///
     /// Format of data: (timestamp, v0), timestamp is key, v0 is value.
     pub async fn put(&self, data: &[(i64, Option<i64>)]) -> WriteResponse {
+        self.try_put(data).await.unwrap()
+    }
+
+    /// Put without version specified, returns the error of the write.
+    pub async fn try_put(&self, data: &[(i64, Option<i64>)]) -> Result<WriteResponse> {
         let data: Vec<(TimestampMillisecond, Option<i64>)> =
             data.iter().map(|(l, r)| ((*l).into(), *r)).collect();
         // Build a batch without version.
@@ -78,7 +87,7 @@ impl<S: LogStore> TesterBase<S> {
         let put_data = new_put_data(&data);
         batch.put(put_data).unwrap();
 
-        self.region.write(&self.write_ctx, batch).await.unwrap()
+        self.region.write(&self.write_ctx, batch).await
     }
 
     /// Put without version specified directly to inner writer.
@@ -102,12 +111,14 @@ impl<S: LogStore> TesterBase<S> {
     /// Scan all data.
     pub async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
         logging::info!("Full scan with ctx {:?}", self.read_ctx);
+        self.scan(ScanRequest::default()).await
+    }
+
+    /// Scan data by `request`.
+    pub async fn scan(&self, request: ScanRequest) -> Vec<(i64, Option<i64>)> {
         let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
 
-        let resp = snapshot
-            .scan(&self.read_ctx, ScanRequest::default())
-            .await
-            .unwrap();
+        let resp = snapshot.scan(&self.read_ctx, request).await.unwrap();
         let mut reader = resp.reader;
 
         let metadata = self.region.in_memory_metadata();
@@ -127,12 +138,30 @@ impl<S: LogStore> TesterBase<S> {
 
     /// Delete by keys (timestamp).
     pub async fn delete(&self, keys: &[i64]) -> WriteResponse {
+        self.try_delete(keys).await.unwrap()
+    }
+
+    /// Delete by keys (timestamp), returns the error of the write.
+    pub async fn try_delete(&self, keys: &[i64]) -> Result<WriteResponse> {
         let keys: Vec<TimestampMillisecond> = keys.iter().map(|v| (*v).into()).collect();
         // Build a batch without version.
         let mut batch = new_write_batch_for_test(false);
         let keys = new_delete_data(&keys);
         batch.delete(keys).unwrap();
 
+        self.region.write(&self.write_ctx, batch).await
+    }
+
+    /// Delete rows whose timestamp is in `[start, end)`.
+    pub async fn delete_range(&self, start: i64, end: i64) -> WriteResponse {
+        let range = TimestampRange::new(
+            Timestamp::new_millisecond(start),
+            Timestamp::new_millisecond(end),
+        )
+        .unwrap();
+        let mut batch = new_write_batch_for_test(false);
+        batch.delete_range(range).unwrap();
+
         self.region.write(&self.write_ctx, batch).await.unwrap()
     }
 }
@@ -0,0 +1,266 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Crash consistency tests, which inject faults into the WAL, manifest and SST files
+//! of a region running random workloads, crash and reopen the region, then check the
+//! recovered data is consistent with the acknowledged writes.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use common_telemetry::logging;
+use log_store::fs::fault::{self, AppendFault, FaultLogStore};
+use log_store::fs::log::LocalFileLogStore;
+use object_store::backend::fs::Builder;
+use object_store::layers::{FaultInjectionLayer, WriteFault};
+use object_store::ObjectStore;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::{Rng, SeedableRng};
+use store_api::storage::{OpenOptions, Region};
+use tempdir::TempDir;
+
+use crate::error::Result;
+use crate::region::tests::{self, TesterBase};
+use crate::region::{RegionImpl, StoreConfig};
+use crate::test_util::config_util;
+
+const REGION_NAME: &str = "region-crash-0";
+/// Keys of rows are in `[0, KEY_RANGE)`, so writes overwrite and delete existing rows.
+const KEY_RANGE: usize = 64;
+const ROUNDS: usize = 30;
+const MAX_OPS_PER_ROUND: usize = 16;
+
+type FaultStore = FaultLogStore<LocalFileLogStore>;
+
+/// Fault injectors of the storage of a region.
+struct Faults {
+    wal: Arc<FaultStore>,
+    sst: FaultInjectionLayer,
+    manifest: FaultInjectionLayer,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Fault {
+    Wal(AppendFault),
+    Sst(WriteFault),
+    Manifest(WriteFault),
+}
+
+impl Fault {
+    fn random(rng: &mut StdRng) -> Fault {
+        // Manifest files are not torn, as object storage services write objects
+        // atomically.
+        match rng.gen_range(0..7) {
+            0 => Fault::Wal(AppendFault::Fail),
+            1 => Fault::Wal(AppendFault::Torn(rng.gen_range(0..128))),
+            2 => Fault::Sst(WriteFault::Fail),
+            3 => Fault::Sst(WriteFault::Torn(rng.gen_range(0..4096))),
+            4 => Fault::Sst(WriteFault::FailAfterWrite),
+            5 => Fault::Manifest(WriteFault::Fail),
+            _ => Fault::Manifest(WriteFault::FailAfterWrite),
+        }
+    }
+}
+
+impl Faults {
+    /// Injects `fault` into the `nth` write from now on.
+    fn inject(&self, fault: Fault, nth: usize) {
+        match fault {
+            Fault::Wal(fault) => self.wal.inject(self.wal.appends() + nth, fault),
+            Fault::Sst(fault) => self.sst.inject(self.sst.writes() + nth, fault),
+            Fault::Manifest(fault) => self.manifest.inject(self.manifest.writes() + nth, fault),
+        }
+    }
+}
+
+async fn new_store_config(store_dir: &str) -> (StoreConfig<FaultStore>, Faults) {
+    let sst = FaultInjectionLayer::with_path_filter(".parquet");
+    let manifest = FaultInjectionLayer::with_path_filter("manifest/");
+    let accessor = Builder::default().root(store_dir).build().unwrap();
+    let object_store = ObjectStore::new(accessor)
+        .layer(sst.clone())
+        .layer(manifest.clone());
+
+    let config =
+        config_util::new_store_config_with_object_store(REGION_NAME, store_dir, object_store).await;
+    let wal = Arc::new(FaultLogStore::new(
+        Arc::try_unwrap(config.log_store).unwrap(),
+    ));
+    let config = StoreConfig {
+        log_store: wal.clone(),
+        sst_layer: config.sst_layer,
+        manifest: config.manifest,
+        memtable_builder: config.memtable_builder,
+        flush_scheduler: config.flush_scheduler,
+        flush_strategy: config.flush_strategy,
+        out_of_order_bucket: config.out_of_order_bucket,
+        max_files_in_level0: config.max_files_in_level0,
+        wal_replicator: config.wal_replicator,
+        dedup_policy: config.dedup_policy,
+    };
+    (config, Faults { wal, sst, manifest })
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Put(Vec<(i64, Option<i64>)>),
+    Delete(Vec<i64>),
+    Flush,
+}
+
+impl Op {
+    fn random(rng: &mut StdRng) -> Op {
+        match rng.gen_range(0..10) {
+            0..=5 => {
+                let rows = random_keys(rng)
+                    .into_iter()
+                    .map(|key| (key, rng.gen_bool(0.9).then(|| rng.gen_range(0..1000))))
+                    .collect();
+                Op::Put(rows)
+            }
+            6..=7 => Op::Delete(random_keys(rng)),
+            _ => Op::Flush,
+        }
+    }
+
+    /// Applies the operation to the `expect` data.
+    fn apply(&self, expect: &mut BTreeMap<i64, Option<i64>>) {
+        match self {
+            Op::Put(rows) => expect.extend(rows.iter().copied()),
+            Op::Delete(keys) => {
+                for key in keys {
+                    expect.remove(key);
+                }
+            }
+            Op::Flush => (),
+        }
+    }
+}
+
+/// Returns distinct random keys.
+fn random_keys(rng: &mut StdRng) -> Vec<i64> {
+    let amount = rng.gen_range(1..=8);
+    index::sample(rng, KEY_RANGE, amount)
+        .into_iter()
+        .map(|key| key as i64)
+        .collect()
+}
+
+/// Tester crashing and reopening a region.
+struct CrashTester {
+    store_dir: String,
+    base: Option<TesterBase<FaultStore>>,
+    faults: Faults,
+}
+
+impl CrashTester {
+    async fn new(store_dir: &str) -> CrashTester {
+        let metadata = tests::new_metadata(REGION_NAME, false);
+        let (store_config, faults) = new_store_config(store_dir).await;
+        let region = RegionImpl::create(metadata, store_config).await.unwrap();
+
+        CrashTester {
+            store_dir: store_dir.to_string(),
+            base: Some(TesterBase::with_region(region)),
+            faults,
+        }
+    }
+
+    fn base(&self) -> &TesterBase<FaultStore> {
+        self.base.as_ref().unwrap()
+    }
+
+    async fn execute(&self, op: &Op) -> Result<()> {
+        match op {
+            Op::Put(rows) => self.base().try_put(rows).await.map(|_| ()),
+            Op::Delete(keys) => self.base().try_delete(keys).await.map(|_| ()),
+            Op::Flush => self.base().region.flush().await,
+        }
+    }
+
+    /// Drops the region without closing it, then reopens it without faults.
+    async fn crash_and_reopen(&mut self) {
+        self.base = None;
+        if let Some(torn) = self.faults.wal.take_torn_bytes() {
+            fault::append_to_last_file(config_util::log_store_dir(&self.store_dir), &torn);
+        }
+
+        let (store_config, faults) = new_store_config(&self.store_dir).await;
+        let region = RegionImpl::open(
+            REGION_NAME.to_string(),
+            store_config,
+            &OpenOptions::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        self.base = Some(TesterBase::with_region(region));
+        self.faults = faults;
+    }
+
+    async fn scan(&self) -> BTreeMap<i64, Option<i64>> {
+        self.base().full_scan().await.into_iter().collect()
+    }
+}
+
+#[tokio::test]
+async fn test_recover_from_crashes() {
+    common_telemetry::init_default_ut_logging();
+
+    // Set `CRASH_TEST_SEED` to reproduce a failure.
+    let seed = std::env::var("CRASH_TEST_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or_else(rand::random::<u64>);
+    logging::info!("Crash test seed: {}", seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let dir = TempDir::new("crash").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = CrashTester::new(store_dir).await;
+    // Data of acknowledged writes.
+    let mut expect = BTreeMap::new();
+
+    for round in 0..ROUNDS {
+        let fault = Fault::random(&mut rng);
+        tester.faults.inject(fault, rng.gen_range(0..4));
+
+        let mut failed = None;
+        for _ in 0..rng.gen_range(1..=MAX_OPS_PER_ROUND) {
+            let op = Op::random(&mut rng);
+            if let Err(e) = tester.execute(&op).await {
+                logging::info!("Round {} failed to execute {:?}, err: {}", round, op, e);
+                failed = Some(op);
+                break;
+            }
+            op.apply(&mut expect);
+        }
+
+        tester.crash_and_reopen().await;
+        let actual = tester.scan().await;
+
+        // The failed operation may or may not be persisted, but never partially.
+        let mut expect_applied = expect.clone();
+        if let Some(op) = &failed {
+            op.apply(&mut expect_applied);
+        }
+        assert!(
+            actual == expect || actual == expect_applied,
+            "seed: {seed}, round: {round}, fault: {fault:?}, failed op: {failed:?}, \
+             expect: {expect:?}, actual: {actual:?}"
+        );
+        expect = actual;
+    }
+}
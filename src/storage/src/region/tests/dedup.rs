@@ -0,0 +1,139 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region dedup policy tests.
+
+use log_store::fs::log::LocalFileLogStore;
+use store_api::storage::{DedupPolicy, OpenOptions, Region, WriteResponse};
+use tempdir::TempDir;
+
+use crate::region::tests::{self, FileTesterBase};
+use crate::region::RegionImpl;
+use crate::test_util::config_util;
+
+const REGION_NAME: &str = "region-dedup-0";
+
+/// Tester for region dedup policy.
+struct DedupTester {
+    base: Option<FileTesterBase>,
+    store_dir: String,
+    dedup_policy: DedupPolicy,
+}
+
+impl DedupTester {
+    async fn new(store_dir: &str, dedup_policy: DedupPolicy) -> DedupTester {
+        let metadata = tests::new_metadata(REGION_NAME, false);
+        let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+        store_config.dedup_policy = dedup_policy;
+        let region = RegionImpl::create(metadata, store_config).await.unwrap();
+
+        DedupTester {
+            base: Some(FileTesterBase::with_region(region)),
+            store_dir: store_dir.to_string(),
+            dedup_policy,
+        }
+    }
+
+    async fn reopen(&mut self) {
+        // Close the old region.
+        self.base = None;
+        // Reopen the region.
+        let mut store_config = config_util::new_store_config(REGION_NAME, &self.store_dir).await;
+        store_config.dedup_policy = self.dedup_policy;
+        let opts = OpenOptions::default();
+        let region = RegionImpl::open(REGION_NAME.to_string(), store_config, &opts)
+            .await
+            .unwrap()
+            .unwrap();
+        self.base = Some(FileTesterBase::with_region(region));
+    }
+
+    #[inline]
+    fn base(&self) -> &FileTesterBase {
+        self.base.as_ref().unwrap()
+    }
+
+    async fn put(&self, data: &[(i64, Option<i64>)]) -> WriteResponse {
+        self.base().put(data).await
+    }
+
+    async fn delete(&self, keys: &[i64]) -> WriteResponse {
+        self.base().delete(keys).await
+    }
+
+    async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
+        self.base().full_scan().await
+    }
+
+    async fn flush(&self) {
+        self.base().region.flush().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_last_write_wins() {
+    let dir = TempDir::new("last-write-wins").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = DedupTester::new(store_dir, DedupPolicy::LastWriteWins).await;
+
+    tester.put(&[(1000, Some(1)), (1001, Some(1))]).await;
+    tester.flush().await;
+    // Overwrites the flushed point.
+    tester.put(&[(1000, Some(2))]).await;
+
+    let expect = vec![(1000, Some(2)), (1001, Some(1))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_keep_all() {
+    let dir = TempDir::new("keep-all").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = DedupTester::new(store_dir, DedupPolicy::KeepAll).await;
+
+    tester.put(&[(1000, Some(1)), (1001, Some(1))]).await;
+    tester.put(&[(1000, Some(2))]).await;
+    // Newer rows of the same key are returned first.
+    let expect = vec![(1000, Some(2)), (1000, Some(1)), (1001, Some(1))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    // All versions are kept in the SST.
+    tester.flush().await;
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.put(&[(1000, Some(3))]).await;
+    let expect = vec![
+        (1000, Some(3)),
+        (1000, Some(2)),
+        (1000, Some(1)),
+        (1001, Some(1)),
+    ];
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+
+    // A deletion removes all rows written before it.
+    tester.delete(&[1000]).await;
+    tester.put(&[(1000, Some(4))]).await;
+    let expect = vec![(1000, Some(4)), (1001, Some(1))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.flush().await;
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+}
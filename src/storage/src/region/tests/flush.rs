@@ -17,15 +17,23 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use common_query::logical_plan::Expr;
+use common_time::Timestamp;
+use datafusion_common::ScalarValue;
+use datafusion_expr::{col, lit};
 use log_store::fs::log::LocalFileLogStore;
-use store_api::storage::{OpenOptions, WriteResponse};
+use store_api::storage::{
+    OpenOptions, ReadContext, Region, ScanOrder, ScanRequest, Snapshot, SnapshotStatistics,
+    WriteResponse,
+};
 use tempdir::TempDir;
 
 use crate::engine;
 use crate::flush::{FlushStrategy, FlushStrategyRef};
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::{RegionImpl, SharedDataRef};
-use crate::test_util::config_util;
+use crate::sst::BLOOM_FILTER_EXTENSION;
+use crate::test_util::{self, config_util};
 
 const REGION_NAME: &str = "region-flush-0";
 
@@ -84,6 +92,10 @@ impl FlushTester {
         self.base().put(data).await
     }
 
+    async fn delete_range(&self, start: i64, end: i64) -> WriteResponse {
+        self.base().delete_range(start, end).await
+    }
+
     async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
         self.base().full_scan().await
     }
@@ -91,6 +103,15 @@ impl FlushTester {
     async fn wait_flush_done(&self) {
         self.base().region.wait_flush_done().await.unwrap();
     }
+
+    fn statistics(&self) -> SnapshotStatistics {
+        let snapshot = self
+            .base()
+            .region
+            .snapshot(&ReadContext::default())
+            .unwrap();
+        snapshot.statistics()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -119,7 +140,7 @@ fn has_parquet_file(sst_dir: &str) -> bool {
     for entry in std::fs::read_dir(sst_dir).unwrap() {
         let entry = entry.unwrap();
         let path = entry.path();
-        if !path.is_dir() {
+        if !path.is_dir() && path.extension().unwrap() != BLOOM_FILTER_EXTENSION {
             assert_eq!("parquet", path.extension().unwrap());
             return true;
         }
@@ -258,6 +279,28 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 
+    // The limit is applied to the merged rows of memtables and SSTs.
+    for limit in 0..=expect.len() + 1 {
+        let request = ScanRequest {
+            limit: Some(limit),
+            ..Default::default()
+        };
+        let output = tester.base().scan(request).await;
+        assert_eq!(&expect[..limit.min(expect.len())], output);
+    }
+
+    // Rows are merged in reverse order, the newest version of each key is still returned.
+    let reversed: Vec<_> = expect.iter().rev().cloned().collect();
+    for limit in 0..=expect.len() + 1 {
+        let request = ScanRequest {
+            limit: Some(limit),
+            order: ScanOrder::Desc,
+            ..Default::default()
+        };
+        let output = tester.base().scan(request).await;
+        assert_eq!(&reversed[..limit.min(expect.len())], output);
+    }
+
     // Reopen
     let mut tester = tester;
     tester.reopen().await;
@@ -266,3 +309,424 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_delete_range_after_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("delete-range-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester
+        .put(&[(1000, Some(100)), (2000, Some(200)), (3000, Some(300))])
+        .await;
+    tester.delete_range(1500, 2500).await;
+    let output = tester.full_scan().await;
+    assert_eq!(vec![(1000, Some(100)), (3000, Some(300))], output);
+
+    // Flush the memtable with the tombstone.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(4000, Some(400))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+    // Rows masked by the tombstone are dropped while flushing, so it is pruned.
+    let version = tester.base().region.version();
+    assert!(version.range_tombstones().is_empty());
+
+    // Rows written after the tombstone are visible.
+    tester.put(&[(2000, Some(201))]).await;
+    let expect = vec![
+        (1000, Some(100)),
+        (2000, Some(201)),
+        (3000, Some(300)),
+        (4000, Some(400)),
+    ];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // This tombstone is only in the WAL and masks rows in both memtable and SST.
+    tester.delete_range(3000, 5000).await;
+    let expect = vec![(1000, Some(100)), (2000, Some(201))];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // Tombstones are recovered from the manifest and the WAL.
+    let mut tester = tester;
+    tester.reopen().await;
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_flush_out_of_order() {
+    const HOUR: i64 = 60 * 60 * 1000;
+
+    let dir = TempDir::new("flush-out-of-order").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(2 * HOUR, Some(1))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2 * HOUR + 1000, Some(2))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+
+    // Rows older than the flushed rows are out of order.
+    tester
+        .put(&[
+            (1000, Some(3)),
+            (HOUR + 1000, Some(4)),
+            (2 * HOUR + 2000, Some(5)),
+        ])
+        .await;
+    let version = tester.base().region.version();
+    let memtables = version.memtables();
+    assert_eq!(
+        Some(Timestamp::new_millisecond(2 * HOUR)),
+        memtables.watermark()
+    );
+    assert_eq!(2, memtables.mutable_memtable().num_rows());
+    assert_eq!(2, memtables.out_of_order_memtable().unwrap().num_rows());
+
+    // Trigger flush again.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(3 * HOUR, Some(6))]).await;
+    tester.wait_flush_done().await;
+
+    // Rows of the mutable memtable are flushed into one SST, and out-of-order rows are
+    // flushed into one SST per hour.
+    let version = tester.base().region.version();
+    let mut time_ranges: Vec<_> = version
+        .ssts()
+        .files()
+        .map(|file| file.meta().time_range.unwrap())
+        .collect();
+    time_ranges.sort_unstable();
+    let expect_ranges: Vec<_> = [
+        (1000, 1000),
+        (HOUR + 1000, HOUR + 1000),
+        (2 * HOUR, 2 * HOUR),
+        (2 * HOUR + 1000, 2 * HOUR + 2000),
+    ]
+    .into_iter()
+    .map(|(start, end)| {
+        (
+            Timestamp::new_millisecond(start),
+            Timestamp::new_millisecond(end),
+        )
+    })
+    .collect();
+    assert_eq!(expect_ranges, time_ranges);
+
+    let expect = vec![
+        (1000, Some(3)),
+        (HOUR + 1000, Some(4)),
+        (2 * HOUR, Some(1)),
+        (2 * HOUR + 1000, Some(2)),
+        (2 * HOUR + 2000, Some(5)),
+        (3 * HOUR, Some(6)),
+    ];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    let mut tester = tester;
+    tester.reopen().await;
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_flush_out_of_order_coalesce_buckets() {
+    const HOUR: i64 = 60 * 60 * 1000;
+
+    let dir = TempDir::new("flush-out-of-order-coalesce").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(100 * HOUR, Some(1))]).await;
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(100 * HOUR + 1000, Some(2))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+
+    // One out-of-order row per hour, more buckets than a flush could write.
+    let rows: Vec<_> = (0..20).map(|i| (i * HOUR, Some(i))).collect();
+    tester.put(&rows).await;
+    flush_switch.set_should_flush(true);
+    tester.put(&[(101 * HOUR, Some(101))]).await;
+    tester.wait_flush_done().await;
+
+    // Every two adjacent hours are coalesced into one SST.
+    let version = tester.base().region.version();
+    let mut time_ranges: Vec<_> = version
+        .ssts()
+        .files()
+        .map(|file| file.meta().time_range.unwrap())
+        .filter(|(_, end)| end.value() < 100 * HOUR)
+        .collect();
+    time_ranges.sort_unstable();
+    let expect_ranges: Vec<_> = (0..10)
+        .map(|i| {
+            (
+                Timestamp::new_millisecond(2 * i * HOUR),
+                Timestamp::new_millisecond((2 * i + 1) * HOUR),
+            )
+        })
+        .collect();
+    assert_eq!(expect_ranges, time_ranges);
+
+    let output = tester.full_scan().await;
+    assert_eq!(23, output.len());
+    assert_eq!(rows[..], output[..20]);
+}
+
+#[tokio::test]
+async fn test_statistics_after_flush() {
+    let dir = TempDir::new("statistics-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    let statistics = tester.statistics();
+    assert_eq!(SnapshotStatistics::default(), statistics);
+
+    tester.put(&[(2000, Some(200)), (1000, Some(100))]).await;
+    let statistics = tester.statistics();
+    assert_eq!(2, statistics.num_rows);
+    assert!(statistics.total_bytes > 0);
+    assert_eq!(
+        Some((
+            Timestamp::new_millisecond(1000),
+            Timestamp::new_millisecond(2000)
+        )),
+        statistics.time_range
+    );
+
+    // Flush the memtable.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(3000, Some(300))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+    tester.put(&[(500, Some(50))]).await;
+
+    // Statistics of the SST and the memtable are merged.
+    let expect_range = Some((
+        Timestamp::new_millisecond(500),
+        Timestamp::new_millisecond(3000),
+    ));
+    let statistics = tester.statistics();
+    assert_eq!(4, statistics.num_rows);
+    assert_eq!(expect_range, statistics.time_range);
+
+    // Statistics of SSTs are recovered from the manifest.
+    let mut tester = tester;
+    tester.reopen().await;
+    let statistics = tester.statistics();
+    assert_eq!(4, statistics.num_rows);
+    assert_eq!(expect_range, statistics.time_range);
+}
+
+#[tokio::test]
+async fn test_region_metrics_after_flush() {
+    let dir = TempDir::new("metrics-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    let metrics = tester.base().region.metrics();
+    assert_eq!(2, metrics.write_rows);
+    assert_eq!(1, metrics.write_batches);
+    assert_eq!(2, metrics.num_rows);
+    assert!(metrics.memtable_bytes > 0);
+    assert!(metrics.wal_bytes > 0);
+    assert_eq!(vec![0], metrics.sst_files_per_level);
+
+    // Flush the memtable.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(3000, Some(300))]).await;
+    tester.wait_flush_done().await;
+    tester.full_scan().await;
+
+    let metrics = tester.base().region.metrics();
+    assert_eq!(3, metrics.write_rows);
+    assert_eq!(2, metrics.write_batches);
+    assert_eq!(1, metrics.scans);
+    assert_eq!(vec![1], metrics.sst_files_per_level);
+    assert!(metrics.sst_bytes > 0);
+}
+
+#[tokio::test]
+async fn test_manual_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("manual-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch).await;
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+
+    // Flush an empty region does nothing.
+    tester.base().region.flush().await.unwrap();
+    assert!(!has_parquet_file(&sst_dir));
+
+    let expect = vec![(1000, Some(100)), (2000, Some(200))];
+    tester.put(&expect).await;
+    // The flush strategy never flushes the region, but the data is flushed manually.
+    tester.base().region.flush().await.unwrap();
+    assert!(has_parquet_file(&sst_dir));
+    assert_eq!(expect, tester.full_scan().await);
+
+    let mut tester = tester;
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_truncate() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("truncate").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch).await;
+
+    // Rows in both SSTs and memtables are removed.
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.base().region.flush().await.unwrap();
+    tester.put(&[(3000, Some(300))]).await;
+    tester.base().region.truncate().await.unwrap();
+    assert!(tester.full_scan().await.is_empty());
+    assert_eq!(0, tester.statistics().num_rows);
+
+    let expect = vec![(4000, Some(400))];
+    tester.put(&expect).await;
+    assert_eq!(expect, tester.full_scan().await);
+
+    // The truncated rows are neither replayed from the WAL nor read from SSTs.
+    let mut tester = tester;
+    tester.reopen().await;
+    assert_eq!(expect, tester.full_scan().await);
+}
+
+#[tokio::test]
+async fn test_scan_with_bloom_filter() {
+    const HOUR: i64 = 60 * 60 * 1000;
+
+    let dir = TempDir::new("flush-bloom-filter").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester
+        .put(&[(1000, Some(1)), (3 * HOUR + 1000, Some(2))])
+        .await;
+    // Flush the memtable.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(4 * HOUR, Some(3))]).await;
+    tester.wait_flush_done().await;
+
+    let version = tester.base().region.version();
+    let file = version.ssts().files().next().unwrap();
+    let bloom_meta = file.meta().bloom_filter.as_ref().unwrap();
+    assert_eq!(2, bloom_meta.num_keys);
+
+    let scan_by_ts = |ts: i64| {
+        let filter = col(test_util::TIMESTAMP_NAME)
+            .eq(lit(ScalarValue::TimestampMillisecond(Some(ts), None)));
+        ScanRequest {
+            filters: vec![Expr::from(filter)],
+            ..Default::default()
+        }
+    };
+    // The SST only contains rows in the first and the fourth hour.
+    let output = tester.base().scan(scan_by_ts(3 * HOUR + 1000)).await;
+    assert_eq!(
+        vec![
+            (1000, Some(1)),
+            (3 * HOUR + 1000, Some(2)),
+            (4 * HOUR, Some(3))
+        ],
+        output
+    );
+    let output = tester.base().scan(scan_by_ts(2 * HOUR)).await;
+    assert_eq!(vec![(4 * HOUR, Some(3))], output);
+}
+
+#[tokio::test]
+async fn test_compact_after_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("compact-after-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let new_store_config = move || async move {
+        let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+        store_config.flush_strategy = Arc::new(FlushSwitch::default());
+        store_config.out_of_order_bucket = None;
+        store_config.max_files_in_level0 = Some(2);
+        store_config
+    };
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let region = RegionImpl::create(metadata, new_store_config().await)
+        .await
+        .unwrap();
+    let base = FileTesterBase::with_region(region);
+
+    base.put(&[(1000, Some(1)), (2000, Some(2))]).await;
+    base.region.flush().await.unwrap();
+    base.put(&[(2000, Some(20)), (3000, Some(3))]).await;
+    base.delete(&[1000]).await;
+    base.region.flush().await.unwrap();
+    assert_eq!(2, base.region.version().ssts().files().count());
+
+    // The third SST makes the region exceed the max number of files.
+    base.put(&[(4000, Some(4))]).await;
+    base.region.flush().await.unwrap();
+    let version = base.region.version();
+    let files: Vec<_> = version.ssts().files().collect();
+    assert_eq!(1, files.len());
+    // Overwritten and deleted rows are dropped, and the statistics and the bloom
+    // filter are built from the compacted rows.
+    let meta = files[0].meta();
+    assert_eq!(3, meta.num_rows);
+    assert_eq!(
+        Some((
+            Timestamp::new_millisecond(2000),
+            Timestamp::new_millisecond(4000)
+        )),
+        meta.time_range
+    );
+    assert_eq!(1, meta.bloom_filter.as_ref().unwrap().num_keys);
+
+    let expect = vec![(2000, Some(20)), (3000, Some(3)), (4000, Some(4))];
+    assert_eq!(expect, base.full_scan().await);
+
+    // The compacted SST is recovered from the manifest.
+    drop(base);
+    let region = RegionImpl::open(
+        REGION_NAME.to_string(),
+        new_store_config().await,
+        &OpenOptions::default(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let base = FileTesterBase::with_region(region);
+    assert_eq!(1, base.region.version().ssts().files().count());
+    assert_eq!(expect, base.full_scan().await);
+}
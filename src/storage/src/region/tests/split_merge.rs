@@ -0,0 +1,160 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region split and merge tests.
+
+use std::sync::Arc;
+
+use common_time::Timestamp;
+use datatypes::type_id::LogicalTypeId;
+use datatypes::value::Value;
+use log_store::fs::log::LocalFileLogStore;
+use log_store::test_util::log_store_util;
+use object_store::backend::fs::Builder;
+use object_store::ObjectStore;
+use store_api::storage::{
+    CreateOptions, EngineContext, MergeRegionsRequest, OpenOptions, Region, SplitRegionRequest,
+    StorageEngine,
+};
+use tempdir::TempDir;
+
+use crate::config::EngineConfig;
+use crate::engine::EngineImpl;
+use crate::region::tests::FileTesterBase;
+use crate::test_util::descriptor_util::RegionDescBuilder;
+use crate::test_util::TIMESTAMP_NAME;
+
+const REGION_NAME: &str = "region-split-0";
+
+fn split_request(split_key: i64) -> SplitRegionRequest {
+    SplitRegionRequest {
+        column_name: TIMESTAMP_NAME.to_string(),
+        split_key: Value::Timestamp(Timestamp::new_millisecond(split_key)),
+        left_id: 1,
+        left_name: "region-split-1".to_string(),
+        right_id: 2,
+        right_name: "region-split-2".to_string(),
+    }
+}
+
+async fn new_engine(store_dir: &str) -> (EngineImpl<LocalFileLogStore>, TempDir) {
+    let (log_store, log_dir) =
+        log_store_util::create_tmp_local_file_log_store("test_split_merge_wal").await;
+    let accessor = Builder::default().root(store_dir).build().unwrap();
+    let object_store = ObjectStore::new(accessor);
+    let engine = EngineImpl::new(EngineConfig::default(), Arc::new(log_store), object_store);
+
+    (engine, log_dir)
+}
+
+async fn create_region(engine: &EngineImpl<LocalFileLogStore>) -> FileTesterBase {
+    let desc = RegionDescBuilder::new(REGION_NAME)
+        .push_value_column(("v0", LogicalTypeId::Int64, true))
+        .build();
+    let region = engine
+        .create_region(&EngineContext::default(), desc, &CreateOptions::default())
+        .await
+        .unwrap();
+
+    FileTesterBase::with_region(region)
+}
+
+#[tokio::test]
+async fn test_split_and_merge_region() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("split-merge").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let (engine, _log_dir) = new_engine(store_dir).await;
+    let tester = create_region(&engine).await;
+    let ctx = EngineContext::default();
+    let opts = CreateOptions::default();
+
+    tester
+        .put(&[(1, Some(1)), (3, Some(3)), (5, Some(5))])
+        .await;
+    tester.region.flush().await.unwrap();
+    // The split also flushes data in memtables.
+    tester
+        .put(&[(2, Some(2)), (4, Some(4)), (6, Some(6))])
+        .await;
+
+    let (left, right) = engine
+        .split_region(&ctx, &tester.region, split_request(4), &opts)
+        .await
+        .unwrap();
+    let left = FileTesterBase::with_region(left);
+    let right = FileTesterBase::with_region(right);
+    assert_eq!(tester.committed_sequence(), left.committed_sequence());
+    assert_eq!(tester.committed_sequence(), right.committed_sequence());
+    assert_eq!(
+        vec![(1, Some(1)), (2, Some(2)), (3, Some(3))],
+        left.full_scan().await
+    );
+    assert_eq!(
+        vec![(4, Some(4)), (5, Some(5)), (6, Some(6))],
+        right.full_scan().await
+    );
+
+    // Rows written to the new regions are also filtered by their key range.
+    right.put(&[(5, Some(50)), (7, Some(7))]).await;
+    assert_eq!(
+        vec![(4, Some(4)), (5, Some(50)), (6, Some(6)), (7, Some(7))],
+        right.full_scan().await
+    );
+
+    // Splitting the same region again is rejected as the new regions already exist.
+    assert!(engine
+        .split_region(&ctx, &tester.region, split_request(3), &opts)
+        .await
+        .is_err());
+
+    let request = MergeRegionsRequest {
+        id: 3,
+        name: "region-split-3".to_string(),
+    };
+    // Only adjacent regions in order can be merged.
+    assert!(engine
+        .merge_regions(&ctx, &right.region, &left.region, request.clone(), &opts)
+        .await
+        .is_err());
+    let merged = engine
+        .merge_regions(&ctx, &left.region, &right.region, request, &opts)
+        .await
+        .unwrap();
+    let merged = FileTesterBase::with_region(merged);
+    assert_eq!(right.committed_sequence(), merged.committed_sequence());
+    let expect = vec![
+        (1, Some(1)),
+        (2, Some(2)),
+        (3, Some(3)),
+        (4, Some(4)),
+        (5, Some(50)),
+        (6, Some(6)),
+        (7, Some(7)),
+    ];
+    assert_eq!(expect, merged.full_scan().await);
+
+    // Reopen the merged region from its manifest.
+    let merged_name = merged.region.name().to_string();
+    drop(merged);
+    let (engine, _log_dir) = new_engine(store_dir).await;
+    let reopened = engine
+        .open_region(&ctx, &merged_name, &OpenOptions::default())
+        .await
+        .unwrap()
+        .unwrap();
+    let reopened = FileTesterBase::with_region(reopened);
+    assert_eq!(expect, reopened.full_scan().await);
+}
@@ -13,13 +13,14 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_telemetry::logging;
 use futures::TryStreamExt;
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::manifest::{Manifest, ManifestVersion, MetaAction};
-use store_api::storage::{AlterRequest, SequenceNumber, WriteContext, WriteResponse};
+use store_api::storage::{AlterRequest, DedupPolicy, SequenceNumber, WriteContext, WriteResponse};
 use tokio::sync::Mutex;
 
 use crate::background::JobHandle;
@@ -34,9 +35,10 @@ use crate::proto::wal::WalHeader;
 use crate::region::{RecoverdMetadata, RecoveredMetadataMap, RegionManifest, SharedDataRef};
 use crate::schema::compat::CompatWrite;
 use crate::sst::AccessLayerRef;
+use crate::tombstone::RangeTombstone;
 use crate::version::{VersionControl, VersionControlRef, VersionEdit};
 use crate::wal::Wal;
-use crate::write_batch::WriteBatch;
+use crate::write_batch::{Payload, WriteBatch};
 
 pub type RegionWriterRef = Arc<RegionWriter>;
 
@@ -56,9 +58,19 @@ pub struct RegionWriter {
 }
 
 impl RegionWriter {
-    pub fn new(memtable_builder: MemtableBuilderRef) -> RegionWriter {
+    pub fn new(
+        memtable_builder: MemtableBuilderRef,
+        out_of_order_bucket: Option<Duration>,
+        max_files_in_level0: Option<usize>,
+        dedup_policy: DedupPolicy,
+    ) -> RegionWriter {
         RegionWriter {
-            inner: Mutex::new(WriterInner::new(memtable_builder)),
+            inner: Mutex::new(WriterInner::new(
+                memtable_builder,
+                out_of_order_bucket,
+                max_files_in_level0,
+                dedup_policy,
+            )),
             version_mutex: Mutex::new(()),
         }
     }
@@ -76,6 +88,13 @@ impl RegionWriter {
             .await
     }
 
+    /// Flush data in the mutable memtable and wait until all flush jobs of the
+    /// region are finished.
+    pub async fn flush<S: LogStore>(&self, writer_ctx: WriterContext<'_, S>) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        inner.flush(&writer_ctx).await
+    }
+
     /// Replay data to memtables.
     pub async fn replay<S: LogStore>(
         &self,
@@ -88,14 +107,15 @@ impl RegionWriter {
             .await
     }
 
-    /// Write and apply the region edit.
+    /// Write and apply the region edit, immutable memtables whose ids are not greater
+    /// than `max_memtable_id` are removed.
     pub(crate) async fn write_edit_and_apply<S: LogStore>(
         &self,
         wal: &Wal<S>,
         shared: &SharedDataRef,
         manifest: &RegionManifest,
         edit: RegionEdit,
-        max_memtable_id: MemtableId,
+        max_memtable_id: Option<MemtableId>,
     ) -> Result<()> {
         let _lock = self.version_mutex.lock().await;
         // HACK: We won't acquire the write lock here because write stall would hold
@@ -112,7 +132,9 @@ impl RegionWriter {
         );
 
         let files_to_add = edit.files_to_add.clone();
+        let files_to_remove = edit.files_to_remove.clone();
         let flushed_sequence = edit.flushed_sequence;
+        let range_tombstones = edit.range_tombstones.clone();
 
         // Persist the meta action.
         let mut action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit));
@@ -121,9 +143,11 @@ impl RegionWriter {
 
         let version_edit = VersionEdit {
             files_to_add,
+            files_to_remove,
             flushed_sequence: Some(flushed_sequence),
             manifest_version,
-            max_memtable_id: Some(max_memtable_id),
+            max_memtable_id,
+            range_tombstones,
         };
 
         // We could tolerate failure during persisting manifest version to the WAL, since it won't
@@ -138,6 +162,65 @@ impl RegionWriter {
             .await
     }
 
+    /// Remove all data in memtables and SSTs of the region.
+    ///
+    /// The SSTs are marked as removed in the manifest and the flushed sequence moves to
+    /// the committed sequence, so the truncated data won't be replayed from the WAL.
+    /// The sequence itself keeps increasing since the WAL requires increasing sequences.
+    pub async fn truncate<S: LogStore>(&self, writer_ctx: WriterContext<'_, S>) -> Result<()> {
+        // Hold the write lock to stop writers until the region is truncated.
+        let mut inner = self.inner.lock().await;
+        // Wait for the running flush job, otherwise it may add SSTs of the truncated data
+        // to the region after truncating.
+        if let Some(flush_handle) = inner.flush_handle.take() {
+            flush_handle.join().await?;
+        }
+
+        let _lock = self.version_mutex.lock().await;
+        let version_control = writer_ctx.version_control();
+        let current_version = version_control.current();
+        let committed_sequence = version_control.committed_sequence();
+        let files_to_remove: Vec<_> = current_version
+            .ssts()
+            .files()
+            .map(|file| file.meta().clone())
+            .collect();
+
+        let edit = RegionEdit {
+            region_version: current_version.metadata().version(),
+            flushed_sequence: committed_sequence,
+            files_to_add: Vec::new(),
+            files_to_remove: files_to_remove.clone(),
+            range_tombstones: Vec::new(),
+        };
+        let mut action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit));
+        action_list.set_prev_version(version_control.current_manifest_version());
+        let manifest_version = writer_ctx.manifest.update(action_list).await?;
+
+        let num_removed = files_to_remove.len();
+        let version_edit = VersionEdit {
+            files_to_add: Vec::new(),
+            files_to_remove,
+            flushed_sequence: Some(committed_sequence),
+            manifest_version,
+            max_memtable_id: None,
+            range_tombstones: Vec::new(),
+        };
+        let new_mutable = inner.alloc_memtable(version_control);
+        version_control.reset_memtables_and_apply_edit(new_mutable, version_edit);
+
+        logging::info!(
+            "Region {} is truncated, flushed sequence: {}, removed files: {}",
+            writer_ctx.shared.name,
+            committed_sequence,
+            num_removed,
+        );
+
+        self.persist_manifest_version(writer_ctx.wal, version_control, manifest_version)
+            .await?;
+        writer_ctx.wal.obsolete(committed_sequence).await
+    }
+
     /// Alter schema of the region.
     pub async fn alter<S: LogStore>(
         &self,
@@ -269,13 +352,29 @@ impl<'a, S: LogStore> AlterContext<'a, S> {
 struct WriterInner {
     memtable_builder: MemtableBuilderRef,
     flush_handle: Option<JobHandle>,
+    /// Duration of the time buckets of out-of-order rows, out-of-order rows are not
+    /// buffered separately if it's `None`.
+    out_of_order_bucket: Option<Duration>,
+    /// SSTs are compacted after a flush once there are more SSTs than this, no
+    /// compaction if it's `None`.
+    max_files_in_level0: Option<usize>,
+    /// How rows with the same keys and timestamp are deduplicated by compaction.
+    dedup_policy: DedupPolicy,
 }
 
 impl WriterInner {
-    fn new(memtable_builder: MemtableBuilderRef) -> WriterInner {
+    fn new(
+        memtable_builder: MemtableBuilderRef,
+        out_of_order_bucket: Option<Duration>,
+        max_files_in_level0: Option<usize>,
+        dedup_policy: DedupPolicy,
+    ) -> WriterInner {
         WriterInner {
             memtable_builder,
             flush_handle: None,
+            out_of_order_bucket,
+            max_files_in_level0,
+            dedup_policy,
         }
     }
 
@@ -314,8 +413,8 @@ impl WriterInner {
             .await?;
 
         // Insert batch into memtable.
-        let mut inserter = Inserter::new(next_sequence);
-        inserter.insert_memtable(request.payload(), version.mutable_memtable())?;
+        self.insert_memtables(version_control, request.payload(), next_sequence)?;
+        add_range_tombstones(version_control, request.payload(), next_sequence);
 
         // Update committed_sequence to make current batch visible. The `&mut self` of WriterInner
         // guarantees the writer is exclusive.
@@ -367,9 +466,6 @@ impl WriterInner {
 
                 if let Some(payload) = payload {
                     num_requests += 1;
-                    // Note that memtables of `Version` may be updated during replay.
-                    let version = version_control.current();
-
                     if req_sequence > last_sequence {
                         last_sequence = req_sequence;
                     } else {
@@ -392,8 +488,9 @@ impl WriterInner {
                     }
                     // TODO(yingwen): Trigger flush if the size of memtables reach the flush threshold to avoid
                     // out of memory during replay, but we need to do it carefully to avoid dead lock.
-                    let mut inserter = Inserter::new(last_sequence);
-                    inserter.insert_memtable(&payload, version.mutable_memtable())?;
+                    // Note that memtables of `Version` may be updated during replay.
+                    self.insert_memtables(version_control, &payload, last_sequence)?;
+                    add_range_tombstones(version_control, &payload, last_sequence);
                 }
             }
 
@@ -469,6 +566,44 @@ impl WriterInner {
         Ok(())
     }
 
+    /// Inserts the `payload` into memtables of current version.
+    ///
+    /// Rows older than the watermark of the memtables are inserted into the out-of-order
+    /// memtable, which is created if absent, so rows flushed from the mutable memtables
+    /// are ordered by time.
+    fn insert_memtables(
+        &self,
+        version_control: &VersionControlRef,
+        payload: &Payload,
+        sequence: SequenceNumber,
+    ) -> Result<()> {
+        let version = version_control.current();
+        let memtables = version.memtables();
+        let mut inserter = Inserter::new(sequence);
+
+        let watermark = memtables
+            .watermark()
+            .filter(|_| self.out_of_order_bucket.is_some());
+        let Some(watermark) = watermark else {
+            return inserter.insert_memtable(payload, memtables.mutable_memtable());
+        };
+
+        let out_of_order = match memtables.out_of_order_memtable() {
+            Some(memtable) => memtable.clone(),
+            None => {
+                let memtable = self.memtable_builder.build(version.schema().clone());
+                version_control.set_out_of_order_memtable(memtable.clone());
+                memtable
+            }
+        };
+        inserter.insert_memtables(
+            payload,
+            memtables.mutable_memtable(),
+            &out_of_order,
+            watermark,
+        )
+    }
+
     /// Preprocess before write.
     ///
     /// Creates needed mutable memtables, ensures there is enough capacity in memtable and trigger
@@ -510,6 +645,28 @@ impl WriterInner {
         flush_strategy.should_flush(shared, mutable_bytes_allocated, total_bytes_allocated)
     }
 
+    async fn flush<S: LogStore>(&mut self, ctx: &WriterContext<'_, S>) -> Result<()> {
+        let mutable_bytes_allocated = ctx
+            .version_control()
+            .current()
+            .memtables()
+            .mutable_bytes_allocated();
+        // Nothing to freeze if the mutable memtable is empty, but we still need to wait
+        // for the running flush job.
+        if mutable_bytes_allocated > 0 {
+            self.trigger_flush(ctx).await?;
+        }
+
+        if let Some(flush_handle) = self.flush_handle.take() {
+            flush_handle.join().await.map_err(|e| {
+                logging::error!(e; "Failed to flush region: {}", ctx.shared.name);
+                e
+            })?;
+        }
+
+        Ok(())
+    }
+
     async fn trigger_flush<S: LogStore>(&mut self, ctx: &WriterContext<'_, S>) -> Result<()> {
         let version_control = &ctx.shared.version_control;
         let new_mutable = self.alloc_memtable(version_control);
@@ -532,23 +689,35 @@ impl WriterInner {
         }
 
         let current_version = version_control.current();
-        let (max_memtable_id, mem_to_flush) = current_version.memtables().memtables_to_flush();
+        let (max_memtable_id, mem_to_flush, out_of_order_to_flush) =
+            current_version.memtables().memtables_to_flush();
 
         if max_memtable_id.is_none() {
             logging::info!("No memtables to flush in region: {}", ctx.shared.name);
             return Ok(());
         }
 
+        // In write thread, safe to use current committed sequence.
+        let flush_sequence = version_control.committed_sequence();
+        let range_tombstones = current_version.range_tombstones();
         let flush_req = FlushJob {
             max_memtable_id: max_memtable_id.unwrap(),
             memtables: mem_to_flush,
-            // In write thread, safe to use current committed sequence.
-            flush_sequence: version_control.committed_sequence(),
+            out_of_order_memtables: out_of_order_to_flush,
+            out_of_order_bucket: self.out_of_order_bucket,
+            flush_sequence,
+            range_tombstones: Arc::new(range_tombstones.visible_at(flush_sequence)),
+            // The previous flush job is finished, so tombstones before the flushed
+            // sequence are already persisted.
+            tombstones_to_persist: range_tombstones
+                .between(current_version.flushed_sequence(), flush_sequence),
             shared: ctx.shared.clone(),
             sst_layer: ctx.sst_layer.clone(),
             writer: ctx.writer.clone(),
             wal: ctx.wal.clone(),
             manifest: ctx.manifest.clone(),
+            max_files_in_level0: self.max_files_in_level0,
+            dedup_policy: self.dedup_policy,
         };
 
         let flush_handle = ctx
@@ -560,3 +729,22 @@ impl WriterInner {
         Ok(())
     }
 }
+
+/// Adds time ranges deleted by `payload` to the version as range tombstones with
+/// `sequence`.
+fn add_range_tombstones(
+    version_control: &VersionControl,
+    payload: &Payload,
+    sequence: SequenceNumber,
+) {
+    if payload.delete_ranges.is_empty() {
+        return;
+    }
+
+    let tombstones = payload.delete_ranges.iter().map(|range| RangeTombstone {
+        start: *range.start(),
+        end: *range.end(),
+        sequence,
+    });
+    version_control.add_range_tombstones(tombstones);
+}
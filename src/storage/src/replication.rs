@@ -0,0 +1,250 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replication of region WAL to standby regions.
+//!
+//! The primary region ships every entry appended to its WAL through a [WalReplicator]
+//! asynchronously, the standby datanode persists the entries into its local log store
+//! by [StandbyWal] and acknowledges the applied sequence. A standby falls behind (e.g.
+//! entries are dropped on the way) could catch up by streaming the entries from its
+//! applied sequence via [RegionImpl::read_wal_entries](crate::region::RegionImpl::read_wal_entries).
+//!
+//! On failover, the region is opened on the standby datanode and replays the entries
+//! from its local WAL.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use futures::Stream;
+use snafu::ensure;
+use store_api::logstore::LogStore;
+use store_api::storage::{RegionId, SequenceNumber};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+use crate::error::{ReplicateWalSnafu, Result, WalReplicationGapSnafu};
+use crate::wal::Wal;
+
+/// An encoded entry of the region WAL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicatedEntry {
+    pub region_id: RegionId,
+    pub sequence: SequenceNumber,
+    pub data: Vec<u8>,
+}
+
+pub type ReplicatedEntryStream<'a> =
+    Pin<Box<dyn Stream<Item = Result<ReplicatedEntry>> + Send + 'a>>;
+
+/// Ships WAL entries of regions to their standby regions.
+///
+/// The replication is asynchronous, the entry is shipped after it is appended to the
+/// local WAL and the write doesn't wait for the standby to acknowledge it.
+#[async_trait]
+pub trait WalReplicator: Send + Sync + std::fmt::Debug {
+    /// Ships the `entry` to the standby region.
+    async fn replicate(&self, entry: ReplicatedEntry) -> Result<()>;
+
+    /// Returns the sequence acknowledged by the standby of the region, all entries
+    /// before (and including) this sequence have been persisted by the standby.
+    ///
+    /// Returns `None` if the region has no standby.
+    fn acked_sequence(&self, region_id: RegionId) -> Option<SequenceNumber>;
+}
+
+pub type WalReplicatorRef = Arc<dyn WalReplicator>;
+
+/// A [WalReplicator] that sends the entries to a bounded channel, the receiver side
+/// is responsible for delivering them to the standby datanode and calling [ack](Self::ack)
+/// once they are applied.
+///
+/// Entries are rejected instead of blocking the write path if the channel is full, the
+/// standby needs to catch up from its applied sequence then.
+#[derive(Debug)]
+pub struct ChannelReplicator {
+    sender: Sender<ReplicatedEntry>,
+    acked: RwLock<HashMap<RegionId, SequenceNumber>>,
+}
+
+impl ChannelReplicator {
+    pub fn new(capacity: usize) -> (ChannelReplicator, Receiver<ReplicatedEntry>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let replicator = ChannelReplicator {
+            sender,
+            acked: RwLock::new(HashMap::new()),
+        };
+
+        (replicator, receiver)
+    }
+
+    /// Advances the acknowledged sequence of the region, stale acks are ignored.
+    pub fn ack(&self, region_id: RegionId, sequence: SequenceNumber) {
+        let mut acked = self.acked.write().unwrap();
+        let current = acked.entry(region_id).or_default();
+        *current = (*current).max(sequence);
+    }
+}
+
+#[async_trait]
+impl WalReplicator for ChannelReplicator {
+    async fn replicate(&self, entry: ReplicatedEntry) -> Result<()> {
+        let region_id = entry.region_id;
+        // The region has a standby once it starts shipping entries, nothing is
+        // acknowledged yet.
+        self.acked.write().unwrap().entry(region_id).or_default();
+
+        match self.sender.try_send(entry) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => ReplicateWalSnafu {
+                region_id,
+                reason: "replication channel is full",
+            }
+            .fail(),
+            Err(TrySendError::Closed(_)) => ReplicateWalSnafu {
+                region_id,
+                reason: "replication channel is closed",
+            }
+            .fail(),
+        }
+    }
+
+    fn acked_sequence(&self, region_id: RegionId) -> Option<SequenceNumber> {
+        self.acked.read().unwrap().get(&region_id).copied()
+    }
+}
+
+/// Applies the replicated entries to the local log store of the standby datanode.
+///
+/// Entries of the same region should be applied sequentially.
+#[derive(Debug)]
+pub struct StandbyWal<S: LogStore> {
+    store: Arc<S>,
+    applied: RwLock<HashMap<RegionId, SequenceNumber>>,
+}
+
+impl<S: LogStore> StandbyWal<S> {
+    pub fn new(store: Arc<S>) -> StandbyWal<S> {
+        StandbyWal {
+            store,
+            applied: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Persists the `entry` to the local WAL of its region and returns the applied
+    /// sequence of the region, which could be acknowledged to the primary.
+    ///
+    /// Entries already applied are skipped, so it's safe to resend entries while
+    /// catching up. Returns error if some entries before the `entry` are missing, the
+    /// caller should catch up from the applied sequence.
+    pub async fn apply(&self, entry: ReplicatedEntry) -> Result<SequenceNumber> {
+        let region_id = entry.region_id;
+        if let Some(applied) = self.applied_sequence(region_id) {
+            if entry.sequence <= applied {
+                return Ok(applied);
+            }
+            ensure!(
+                entry.sequence == applied + 1,
+                WalReplicationGapSnafu {
+                    region_id,
+                    expected: applied + 1,
+                    actual: entry.sequence,
+                }
+            );
+        }
+
+        let wal = Wal::new(region_id, self.store.clone());
+        wal.write(entry.sequence, &entry.data).await?;
+        self.applied
+            .write()
+            .unwrap()
+            .insert(region_id, entry.sequence);
+
+        Ok(entry.sequence)
+    }
+
+    /// Returns the last sequence applied to the region, `None` if nothing is applied
+    /// since the standby starts.
+    pub fn applied_sequence(&self, region_id: RegionId) -> Option<SequenceNumber> {
+        self.applied.read().unwrap().get(&region_id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use log_store::test_util::log_store_util;
+
+    use super::*;
+    use crate::error::Error;
+
+    fn new_entry(region_id: RegionId, sequence: SequenceNumber) -> ReplicatedEntry {
+        ReplicatedEntry {
+            region_id,
+            sequence,
+            data: format!("entry-{sequence}").into_bytes(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channel_replicator() {
+        let (replicator, mut receiver) = ChannelReplicator::new(1);
+        assert_eq!(None, replicator.acked_sequence(1));
+
+        replicator.replicate(new_entry(1, 1)).await.unwrap();
+        assert_eq!(Some(0), replicator.acked_sequence(1));
+        // The channel is full.
+        let err = replicator.replicate(new_entry(1, 2)).await.unwrap_err();
+        assert!(matches!(err, Error::ReplicateWal { .. }));
+
+        assert_eq!(new_entry(1, 1), receiver.recv().await.unwrap());
+        replicator.ack(1, 1);
+        assert_eq!(Some(1), replicator.acked_sequence(1));
+        // Stale ack.
+        replicator.ack(1, 0);
+        assert_eq!(Some(1), replicator.acked_sequence(1));
+
+        drop(receiver);
+        let err = replicator.replicate(new_entry(1, 2)).await.unwrap_err();
+        assert!(matches!(err, Error::ReplicateWal { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_standby_wal_apply() {
+        let (log_store, _tmp) =
+            log_store_util::create_tmp_local_file_log_store("standby_wal_test").await;
+        let log_store = Arc::new(log_store);
+        let standby = StandbyWal::new(log_store.clone());
+
+        assert_eq!(3, standby.apply(new_entry(1, 3)).await.unwrap());
+        assert_eq!(4, standby.apply(new_entry(1, 4)).await.unwrap());
+        // Applied entry is skipped.
+        assert_eq!(4, standby.apply(new_entry(1, 3)).await.unwrap());
+        let err = standby.apply(new_entry(1, 6)).await.unwrap_err();
+        assert!(matches!(err, Error::WalReplicationGap { .. }));
+        assert_eq!(Some(4), standby.applied_sequence(1));
+        assert_eq!(None, standby.applied_sequence(2));
+
+        let wal = Wal::new(1, log_store);
+        let entries: Vec<_> = wal
+            .read_entries(0)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(vec![new_entry(1, 3), new_entry(1, 4)], entries);
+    }
+}
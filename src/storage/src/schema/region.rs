@@ -106,6 +106,11 @@ impl RegionSchema {
         self.columns.row_key_end()
     }
 
+    #[inline]
+    pub(crate) fn timestamp_key_index(&self) -> usize {
+        self.columns.timestamp_key_index()
+    }
+
     #[inline]
     pub(crate) fn sequence_index(&self) -> usize {
         self.store_schema.sequence_index()
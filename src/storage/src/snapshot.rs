@@ -13,16 +13,20 @@
 // limitations under the License.
 
 use std::cmp;
+use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
+use common_telemetry::tracing::{info_span, Instrument};
 use store_api::storage::{
-    GetRequest, GetResponse, ReadContext, ScanRequest, ScanResponse, SchemaRef, SequenceNumber,
-    Snapshot,
+    DedupPolicy, GetRequest, GetResponse, ReadContext, ScanRequest, ScanResponse, SchemaRef,
+    SequenceNumber, Snapshot, SnapshotStatistics,
 };
 
 use crate::chunk::{ChunkReaderBuilder, ChunkReaderImpl};
 use crate::error::{Error, Result};
-use crate::sst::AccessLayerRef;
+use crate::region::RegionMetricsRecorderRef;
+use crate::sst::{self, AccessLayerRef};
 use crate::version::VersionRef;
 
 /// [Snapshot] implementation.
@@ -31,6 +35,8 @@ pub struct SnapshotImpl {
     /// Max sequence number (inclusive) visible to user.
     visible_sequence: SequenceNumber,
     sst_layer: AccessLayerRef,
+    metrics: RegionMetricsRecorderRef,
+    dedup_policy: DedupPolicy,
 }
 
 #[async_trait]
@@ -47,26 +53,35 @@ impl Snapshot for SnapshotImpl {
         ctx: &ReadContext,
         request: ScanRequest,
     ) -> Result<ScanResponse<ChunkReaderImpl>> {
+        let start = Instant::now();
         let visible_sequence = self.sequence_to_read(request.sequence);
         let memtable_version = self.version.memtables();
 
-        let mutables = memtable_version.mutable_memtable();
-        let immutables = memtable_version.immutable_memtables();
-
         let mut builder =
             ChunkReaderBuilder::new(self.version.schema().clone(), self.sst_layer.clone())
                 .reserve_num_memtables(memtable_version.num_memtables())
                 .projection(request.projection)
                 .filters(request.filters)
+                .limit(request.limit)
+                .order(request.order)
                 .batch_size(ctx.batch_size)
                 .visible_sequence(visible_sequence)
-                .pick_memtables(mutables.clone());
+                .dedup_policy(self.dedup_policy)
+                .range_tombstones(Arc::new(
+                    self.version.range_tombstones().visible_at(visible_sequence),
+                ))
+                .key_range(self.version.key_range().cloned());
 
-        for memtable in immutables {
+        for memtable in memtable_version.memtables() {
             builder = builder.pick_memtables(memtable.clone());
         }
 
-        let reader = builder.pick_ssts(self.version.ssts())?.build().await?;
+        let reader = builder
+            .pick_ssts(self.version.ssts())?
+            .build()
+            .instrument(info_span!("region_scan", visible_sequence))
+            .await?;
+        self.metrics.on_scan(start.elapsed());
 
         Ok(ScanResponse { reader })
     }
@@ -74,6 +89,38 @@ impl Snapshot for SnapshotImpl {
     async fn get(&self, _ctx: &ReadContext, _request: GetRequest) -> Result<GetResponse> {
         unimplemented!()
     }
+
+    fn statistics(&self) -> SnapshotStatistics {
+        let mut statistics = SnapshotStatistics::default();
+        let mut time_ranges = Vec::new();
+
+        let memtable_version = self.version.memtables();
+        for memtable in memtable_version.memtables() {
+            let num_rows = memtable.num_rows();
+            if num_rows == 0 {
+                continue;
+            }
+            statistics.num_rows += num_rows;
+            statistics.total_bytes += memtable.bytes_allocated();
+            time_ranges.push(memtable.time_range());
+        }
+
+        for file in self.version.ssts().files() {
+            let meta = file.meta();
+            statistics.num_rows += meta.num_rows;
+            statistics.total_bytes += meta.file_size;
+            time_ranges.push(meta.time_range);
+        }
+
+        // The time range is unknown if the range of any memtable or file is unknown, e.g.
+        // files written by an older version.
+        statistics.time_range = time_ranges
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .and_then(|ranges| ranges.into_iter().reduce(sst::merge_time_range));
+
+        statistics
+    }
 }
 
 impl SnapshotImpl {
@@ -81,14 +128,23 @@ impl SnapshotImpl {
         version: VersionRef,
         visible_sequence: SequenceNumber,
         sst_layer: AccessLayerRef,
+        metrics: RegionMetricsRecorderRef,
     ) -> SnapshotImpl {
         SnapshotImpl {
             version,
             visible_sequence,
             sst_layer,
+            metrics,
+            dedup_policy: DedupPolicy::default(),
         }
     }
 
+    /// Sets how rows with the same keys and timestamp are deduplicated.
+    pub fn with_dedup_policy(mut self, dedup_policy: DedupPolicy) -> SnapshotImpl {
+        self.dedup_policy = dedup_policy;
+        self
+    }
+
     #[inline]
     fn sequence_to_read(&self, request_sequence: Option<SequenceNumber>) -> SequenceNumber {
         request_sequence
@@ -12,20 +12,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod arrow_ipc;
+mod bloom;
+mod index;
 mod parquet;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_time::Timestamp;
+use datatypes::prelude::VectorRef;
+use datatypes::value::ValueRef;
 use object_store::{util, ObjectStore};
 use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt};
+use store_api::storage::{Compression, ParquetOptions, SequenceNumber, SstFormat};
 use table::predicate::Predicate;
+use tokio::sync::OnceCell;
 
-use crate::error::Result;
+use crate::config::{BloomFilterConfig, EngineConfig};
+use crate::engine::region_sst_dir;
+use crate::error::{CorruptedBloomFilterSnafu, ReadObjectSnafu, Result};
 use crate::memtable::BoxedBatchIterator;
-use crate::read::BoxedBatchReader;
+use crate::read::{Batch, BoxedBatchReader};
 use crate::schema::ProjectedSchemaRef;
-use crate::sst::parquet::{ParquetReader, ParquetWriter};
+use crate::sst::arrow_ipc::ArrowIpcFormat;
+use crate::sst::bloom::bloom_filter_file_name;
+pub use crate::sst::bloom::{BloomFilter, BloomFilterMeta, BLOOM_FILTER_EXTENSION};
+pub use crate::sst::index::{RowRanges, SstIndex, MAX_INDEXED_VALUES};
+use crate::sst::parquet::ParquetFormat;
+use crate::tombstone::RangeTombstone;
 
 /// Maximum level of SSTs.
 pub const MAX_LEVEL: usize = 1;
@@ -62,7 +79,11 @@ impl LevelMetas {
     ///
     /// # Panics
     /// Panics if level of [FileHandle] is greater than [MAX_LEVEL].
-    pub fn merge(&self, files_to_add: impl Iterator<Item = FileHandle>) -> LevelMetas {
+    pub fn merge(
+        &self,
+        files_to_add: impl Iterator<Item = FileHandle>,
+        files_to_remove: &[FileMeta],
+    ) -> LevelMetas {
         let mut merged = self.clone();
         for file in files_to_add {
             let level = file.level_index();
@@ -70,7 +91,11 @@ impl LevelMetas {
             merged.levels[level].add_file(file);
         }
 
-        // TODO(yingwen): Support file removal.
+        for file in files_to_remove {
+            if let Some(level) = merged.levels.get_mut(usize::from(file.level)) {
+                level.remove_file(&file.file_name);
+            }
+        }
 
         merged
     }
@@ -87,6 +112,11 @@ impl LevelMetas {
         Ok(())
     }
 
+    /// Returns all SST files.
+    pub fn files(&self) -> impl Iterator<Item = &FileHandle> {
+        self.levels.iter().flat_map(|level| level.files.iter())
+    }
+
     #[cfg(test)]
     pub fn levels(&self) -> &[LevelMeta] {
         &self.levels
@@ -114,6 +144,10 @@ impl LevelMeta {
         self.files.push(file);
     }
 
+    fn remove_file(&mut self, file_name: &str) {
+        self.files.retain(|file| file.file_name() != file_name);
+    }
+
     fn visit_level<V: Visitor>(&self, visitor: &mut V) -> Result<()> {
         visitor.visit(self.level.into(), &self.files)
     }
@@ -156,6 +190,33 @@ impl FileHandle {
     pub fn file_name(&self) -> &str {
         &self.inner.meta.file_name
     }
+
+    #[inline]
+    pub fn meta(&self) -> &FileMeta {
+        &self.inner.meta
+    }
+
+    /// Returns the bloom filter of the file, or `None` if the file has no bloom filter.
+    ///
+    /// The filter is read from `sst_layer` at the first access and then cached in
+    /// the handle.
+    pub async fn bloom_filter(
+        &self,
+        sst_layer: &AccessLayerRef,
+    ) -> Result<Option<Arc<BloomFilter>>> {
+        if self.inner.meta.bloom_filter.is_none() {
+            return Ok(None);
+        }
+
+        let filter = self
+            .inner
+            .bloom_filter
+            .get_or_try_init(|| async {
+                sst_layer.read_bloom_filter(self.meta()).await.map(Arc::new)
+            })
+            .await?;
+        Ok(Some(filter.clone()))
+    }
 }
 
 /// Actually data of [FileHandle].
@@ -164,11 +225,16 @@ impl FileHandle {
 #[derive(Debug)]
 struct FileHandleInner {
     meta: FileMeta,
+    /// Bloom filter of the file, loaded lazily.
+    bloom_filter: OnceCell<Arc<BloomFilter>>,
 }
 
 impl FileHandleInner {
     fn new(meta: FileMeta) -> FileHandleInner {
-        FileHandleInner { meta }
+        FileHandleInner {
+            meta,
+            bloom_filter: OnceCell::new(),
+        }
     }
 }
 
@@ -178,11 +244,164 @@ pub struct FileMeta {
     pub file_name: String,
     /// SST level of the file.
     pub level: u8,
+    /// Min and max (inclusive) timestamp of rows in the file, `None` if the file has
+    /// no row or is written by an older version without statistics.
+    #[serde(default)]
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    /// Number of rows in the file.
+    #[serde(default)]
+    pub num_rows: usize,
+    /// Size of the file in bytes.
+    #[serde(default)]
+    pub file_size: usize,
+    /// Format of the file, files written by an older version are in parquet format.
+    #[serde(default)]
+    pub format: SstFormat,
+    /// Inverted index of tag columns in the file, `None` if no column is indexed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<SstIndex>,
+    /// Bloom filter on row keys of the file, `None` if the file has no bloom filter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bloom_filter: Option<BloomFilterMeta>,
+    /// Name of the region whose directory holds the file, `None` if the file is in the
+    /// directory of the region itself. Regions created by split or merge reference
+    /// files of their source regions instead of copying them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_region: Option<String>,
+    /// Sequence of the flush that wrote the file, rows masked by range tombstones whose
+    /// sequence isn't greater than it are already dropped from the file. `None` if the
+    /// file is written by an older version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flushed_sequence: Option<SequenceNumber>,
+}
+
+impl FileMeta {
+    /// Returns the meta of the file referenced by a region created from the region
+    /// named `region`, which holds the file or references it from its own source.
+    pub fn referenced_from(&self, region: &str) -> FileMeta {
+        let mut meta = self.clone();
+        meta.source_region.get_or_insert_with(|| region.to_string());
+        meta
+    }
+
+    /// Returns whether the file might contain rows masked by the `tombstone`.
+    pub fn may_be_masked_by(&self, tombstone: &RangeTombstone) -> bool {
+        let written_before = self
+            .flushed_sequence
+            .map_or(true, |sequence| sequence < tombstone.sequence);
+        let overlaps = self.time_range.map_or(true, |(min, max)| {
+            min.value() < tombstone.end && max.value() >= tombstone.start
+        });
+        written_before && overlaps
+    }
+}
+
+/// Statistics of a SST file collected while writing it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SstInfo {
+    /// Min and max (inclusive) timestamp of rows in the file.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    pub num_rows: usize,
+    pub file_size: usize,
+    pub index: Option<SstIndex>,
+    pub bloom_filter: Option<BloomFilterMeta>,
+}
+
+impl SstInfo {
+    /// Updates statistics with a `batch` written to the file, `timestamp_index` is
+    /// the index of the timestamp column in the batch.
+    fn update(&mut self, batch: &Batch, timestamp_index: Option<usize>) {
+        self.num_rows += batch.num_rows();
+        let range = timestamp_index.and_then(|i| timestamp_range(batch.column(i)));
+        if let Some(range) = range {
+            self.time_range = Some(match self.time_range {
+                Some(time_range) => merge_time_range(time_range, range),
+                None => range,
+            });
+        }
+    }
+}
+
+/// Returns the min and max timestamp in `vector`, or `None` if the vector is empty or
+/// is not a timestamp vector.
+pub(crate) fn timestamp_range(vector: &VectorRef) -> Option<(Timestamp, Timestamp)> {
+    (0..vector.len())
+        .filter_map(|i| match vector.get_ref(i) {
+            ValueRef::Timestamp(ts) => Some((ts, ts)),
+            _ => None,
+        })
+        .reduce(merge_time_range)
+}
+
+/// Returns the smallest range that contains both `a` and `b`.
+pub(crate) fn merge_time_range(
+    a: (Timestamp, Timestamp),
+    b: (Timestamp, Timestamp),
+) -> (Timestamp, Timestamp) {
+    (a.0.min(b.0), a.1.max(b.1))
 }
 
-#[derive(Debug, Default)]
+/// Options to write SST files.
+#[derive(Debug, Clone, PartialEq)]
 pub struct WriteOptions {
-    // TODO(yingwen): [flush] row group size.
+    /// Compression codec of columns.
+    pub compression: Compression,
+    /// Compression codec of specific columns, overrides `compression`.
+    pub column_compression: HashMap<String, Compression>,
+    /// Whether dictionary encoding is enabled.
+    pub dictionary_enabled: bool,
+    /// Max number of rows in a row group.
+    pub max_row_group_size: usize,
+    /// Tag columns to build inverted index for.
+    pub index_columns: Vec<String>,
+    /// Options of the bloom filter on row keys, no filter is built if it's `None`.
+    pub bloom_filter: Option<BloomFilterConfig>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> WriteOptions {
+        WriteOptions::from_config(&EngineConfig::default())
+    }
+}
+
+impl WriteOptions {
+    /// Returns options with defaults in the engine `config`.
+    pub fn from_config(config: &EngineConfig) -> WriteOptions {
+        WriteOptions {
+            compression: config.sst_compression,
+            column_compression: HashMap::new(),
+            dictionary_enabled: config.sst_dictionary_enabled,
+            max_row_group_size: config.sst_max_row_group_size,
+            index_columns: Vec::new(),
+            bloom_filter: config.sst_bloom_filter.clone(),
+        }
+    }
+
+    /// Sets tag columns to build inverted index for.
+    pub fn with_index_columns(mut self, index_columns: Vec<String>) -> WriteOptions {
+        self.index_columns = index_columns;
+        self
+    }
+
+    /// Overrides options set in `parquet_options`.
+    pub fn with_parquet_options(mut self, parquet_options: &ParquetOptions) -> WriteOptions {
+        if let Some(compression) = parquet_options.compression {
+            self.compression = compression;
+        }
+        self.column_compression.extend(
+            parquet_options
+                .column_compression
+                .iter()
+                .map(|(column, compression)| (column.clone(), *compression)),
+        );
+        if let Some(dictionary_enabled) = parquet_options.dictionary_enabled {
+            self.dictionary_enabled = dictionary_enabled;
+        }
+        if let Some(max_row_group_size) = parquet_options.max_row_group_size {
+            self.max_row_group_size = max_row_group_size;
+        }
+        self
+    }
 }
 
 pub struct ReadOptions {
@@ -193,21 +412,73 @@ pub struct ReadOptions {
     pub projected_schema: ProjectedSchemaRef,
 
     pub predicate: Predicate,
+    /// Rows to read, rows out of the ranges could be skipped. Reads all rows if it's
+    /// `None`.
+    pub row_ranges: Option<RowRanges>,
+    /// Reads rows in the reverse order of the file.
+    pub reverse: bool,
+}
+
+/// Writer and reader of a SST file format.
+#[async_trait]
+trait FileFormat: Send + Sync {
+    /// Writes rows from `iter` to the file at `file_path` and returns statistics of
+    /// the file.
+    async fn write_sst(
+        &self,
+        file_path: &str,
+        iter: BoxedBatchIterator,
+        object_store: ObjectStore,
+        opts: &WriteOptions,
+    ) -> Result<SstInfo>;
+
+    /// Reads the file at `file_path`.
+    async fn read_sst(
+        &self,
+        file_path: &str,
+        object_store: ObjectStore,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader>;
+}
+
+fn file_format(format: SstFormat) -> &'static dyn FileFormat {
+    match format {
+        SstFormat::Parquet => &ParquetFormat,
+        SstFormat::ArrowIpc => &ArrowIpcFormat,
+    }
+}
+
+/// Returns the extension of SST file names in `format`.
+pub(crate) fn file_extension(format: SstFormat) -> &'static str {
+    match format {
+        SstFormat::Parquet => "parquet",
+        SstFormat::ArrowIpc => "arrow",
+    }
 }
 
 /// SST access layer.
 #[async_trait]
 pub trait AccessLayer: Send + Sync + std::fmt::Debug {
-    /// Writes SST file with given `file_name`.
+    /// Returns the format of SST files written by this layer.
+    fn sst_format(&self) -> SstFormat;
+
+    /// Returns the default options to write SST files.
+    fn write_options(&self) -> &WriteOptions;
+
+    /// Writes SST file with given `file_name` and returns statistics of the file.
     async fn write_sst(
         &self,
         file_name: &str,
         iter: BoxedBatchIterator,
         opts: &WriteOptions,
-    ) -> Result<()>;
+    ) -> Result<SstInfo>;
 
-    /// Read SST file with given `file_name` and schema.
-    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader>;
+    /// Reads the SST `file` with given schema, the file might be in the directory of
+    /// another region.
+    async fn read_sst(&self, file: &FileMeta, opts: &ReadOptions) -> Result<BoxedBatchReader>;
+
+    /// Reads the bloom filter of the SST `file`.
+    async fn read_bloom_filter(&self, file: &FileMeta) -> Result<BloomFilter>;
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -216,50 +487,95 @@ pub type AccessLayerRef = Arc<dyn AccessLayer>;
 #[derive(Debug)]
 pub struct FsAccessLayer {
     sst_dir: String,
+    /// Parent directory of the SST directories of all regions in the table.
+    parent_dir: String,
     object_store: ObjectStore,
+    /// Format of new SST files.
+    sst_format: SstFormat,
+    /// Default options to write SST files.
+    write_options: WriteOptions,
 }
 
 impl FsAccessLayer {
     pub fn new(sst_dir: &str, object_store: ObjectStore) -> FsAccessLayer {
+        let sst_dir = util::normalize_dir(sst_dir);
+        let parent_dir = match sst_dir.trim_end_matches('/').rsplit_once('/') {
+            Some((parent, _)) => format!("{parent}/"),
+            None => String::new(),
+        };
         FsAccessLayer {
-            sst_dir: util::normalize_dir(sst_dir),
+            sst_dir,
+            parent_dir,
             object_store,
+            sst_format: SstFormat::default(),
+            write_options: WriteOptions::default(),
         }
     }
 
+    /// Sets the format of SST files written by this layer.
+    pub fn with_sst_format(mut self, sst_format: SstFormat) -> FsAccessLayer {
+        self.sst_format = sst_format;
+        self
+    }
+
+    /// Sets the default options to write SST files.
+    pub fn with_write_options(mut self, write_options: WriteOptions) -> FsAccessLayer {
+        self.write_options = write_options;
+        self
+    }
+
     #[inline]
     fn sst_file_path(&self, file_name: &str) -> String {
         format!("{}{}", self.sst_dir, file_name)
     }
+
+    /// Returns the path of the `file`, which is in the directory of its source region
+    /// if it has one.
+    fn file_path(&self, file: &FileMeta, file_name: &str) -> String {
+        match &file.source_region {
+            Some(region) => format!("{}{}", region_sst_dir(&self.parent_dir, region), file_name),
+            None => self.sst_file_path(file_name),
+        }
+    }
 }
 
 #[async_trait]
 impl AccessLayer for FsAccessLayer {
+    fn sst_format(&self) -> SstFormat {
+        self.sst_format
+    }
+
+    fn write_options(&self) -> &WriteOptions {
+        &self.write_options
+    }
+
     async fn write_sst(
         &self,
         file_name: &str,
         iter: BoxedBatchIterator,
         opts: &WriteOptions,
-    ) -> Result<()> {
-        // Now we only supports parquet format. We may allow caller to specific SST format in
-        // WriteOptions in the future.
+    ) -> Result<SstInfo> {
         let file_path = self.sst_file_path(file_name);
-        let writer = ParquetWriter::new(&file_path, iter, self.object_store.clone());
-
-        writer.write_sst(opts).await?;
-        Ok(())
+        file_format(self.sst_format)
+            .write_sst(&file_path, iter, self.object_store.clone(), opts)
+            .await
     }
 
-    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader> {
-        let file_path = self.sst_file_path(file_name);
-        let reader = ParquetReader::new(
-            &file_path,
-            self.object_store.clone(),
-            opts.projected_schema.clone(),
-            opts.predicate.clone(),
-        );
+    async fn read_sst(&self, file: &FileMeta, opts: &ReadOptions) -> Result<BoxedBatchReader> {
+        let file_path = self.file_path(file, &file.file_name);
+        file_format(file.format)
+            .read_sst(&file_path, self.object_store.clone(), opts)
+            .await
+    }
 
-        let stream = reader.chunk_stream().await?;
-        Ok(Box::new(stream))
+    async fn read_bloom_filter(&self, file: &FileMeta) -> Result<BloomFilter> {
+        let path = self.file_path(file, &bloom_filter_file_name(&file.file_name));
+        let data = self
+            .object_store
+            .object(&path)
+            .read()
+            .await
+            .context(ReadObjectSnafu { path: &path })?;
+        BloomFilter::decode(&data).context(CorruptedBloomFilterSnafu { path })
     }
 }
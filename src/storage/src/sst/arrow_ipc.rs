@@ -0,0 +1,276 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Arrow IPC sst format.
+//!
+//! Files are written in the Arrow IPC file format. Unlike parquet, the format has
+//! no row group statistics, so the predicate is not used to prune data.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datatypes::arrow::ipc::reader::FileReader;
+use datatypes::arrow::ipc::writer::FileWriter;
+use datatypes::arrow::record_batch::RecordBatch;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+
+use crate::error::{
+    self, NewRecordBatchSnafu, ReadArrowIpcSnafu, ReadObjectSnafu, Result, WriteArrowIpcSnafu,
+    WriteObjectSnafu,
+};
+use crate::memtable::BoxedBatchIterator;
+use crate::read::BoxedBatchReader;
+use crate::schema::compat::ReadAdapter;
+use crate::schema::{ProjectedSchemaRef, StoreSchema};
+use crate::sst::index::IndexBuilder;
+use crate::sst::parquet::ChunkStream;
+use crate::sst::{FileFormat, ReadOptions, SstInfo, WriteOptions};
+
+/// Arrow IPC sst format.
+pub struct ArrowIpcFormat;
+
+#[async_trait]
+impl FileFormat for ArrowIpcFormat {
+    async fn write_sst(
+        &self,
+        file_path: &str,
+        iter: BoxedBatchIterator,
+        object_store: ObjectStore,
+        opts: &WriteOptions,
+    ) -> Result<SstInfo> {
+        let writer = ArrowIpcWriter::new(file_path, iter, object_store);
+
+        writer.write_sst(opts).await
+    }
+
+    async fn read_sst(
+        &self,
+        file_path: &str,
+        object_store: ObjectStore,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
+        let reader = ArrowIpcReader::new(file_path, object_store, opts.projected_schema.clone())
+            .with_reverse(opts.reverse);
+
+        let stream = reader.chunk_stream().await?;
+        Ok(Box::new(stream))
+    }
+}
+
+/// Arrow IPC sst writer.
+pub struct ArrowIpcWriter<'a> {
+    file_path: &'a str,
+    iter: BoxedBatchIterator,
+    object_store: ObjectStore,
+}
+
+impl<'a> ArrowIpcWriter<'a> {
+    pub fn new(
+        file_path: &'a str,
+        iter: BoxedBatchIterator,
+        object_store: ObjectStore,
+    ) -> ArrowIpcWriter {
+        ArrowIpcWriter {
+            file_path,
+            iter,
+            object_store,
+        }
+    }
+
+    pub async fn write_sst(self, opts: &WriteOptions) -> Result<SstInfo> {
+        let projected_schema = self.iter.schema();
+        let store_schema = projected_schema.schema_to_read();
+        let schema = store_schema.arrow_schema().clone();
+        let timestamp_index = store_schema.schema().timestamp_index();
+        let object = self.object_store.object(self.file_path);
+
+        // Same as the parquet writer, buffers the whole file in memory and writes it to
+        // the object store at a time.
+        let mut buf = vec![];
+        let mut info = SstInfo::default();
+        let mut index_builder = IndexBuilder::new(store_schema.schema(), &opts.index_columns);
+        let mut bloom_builder = BloomFilterBuilder::new(store_schema, opts.bloom_filter.as_ref());
+        {
+            let mut writer =
+                FileWriter::try_new(&mut buf, &schema).context(WriteArrowIpcSnafu {
+                    file: self.file_path,
+                })?;
+            for batch in self.iter {
+                let batch = batch?;
+                info.update(&batch, timestamp_index);
+                index_builder.update(&batch);
+                if let Some(bloom_builder) = &mut bloom_builder {
+                    bloom_builder.update(&batch);
+                }
+                let arrow_batch = RecordBatch::try_new(
+                    schema.clone(),
+                    batch
+                        .columns()
+                        .iter()
+                        .map(|v| v.to_arrow_array())
+                        .collect::<Vec<_>>(),
+                )
+                .context(NewRecordBatchSnafu)?;
+                writer.write(&arrow_batch).context(WriteArrowIpcSnafu {
+                    file: self.file_path,
+                })?;
+            }
+            writer.finish().context(WriteArrowIpcSnafu {
+                file: self.file_path,
+            })?;
+        }
+        info.file_size = buf.len();
+        info.index = index_builder.build();
+        object.write(buf).await.context(WriteObjectSnafu {
+            path: object.path(),
+        })?;
+        if let Some(bloom_builder) = bloom_builder {
+            info.bloom_filter = Some(
+                bloom_builder
+                    .finish(self.file_path, &self.object_store)
+                    .await?,
+            );
+        }
+        Ok(info)
+    }
+}
+
+pub struct ArrowIpcReader<'a> {
+    file_path: &'a str,
+    object_store: ObjectStore,
+    projected_schema: ProjectedSchemaRef,
+    reverse: bool,
+}
+
+impl<'a> ArrowIpcReader<'a> {
+    pub fn new(
+        file_path: &'a str,
+        object_store: ObjectStore,
+        projected_schema: ProjectedSchemaRef,
+    ) -> ArrowIpcReader {
+        ArrowIpcReader {
+            file_path,
+            object_store,
+            projected_schema,
+            reverse: false,
+        }
+    }
+
+    /// Reads rows in the reverse order of the file if `reverse` is true.
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    pub async fn chunk_stream(&self) -> Result<ChunkStream> {
+        let object = self.object_store.object(self.file_path);
+        let bytes = object.read().await.context(ReadObjectSnafu {
+            path: self.file_path,
+        })?;
+        let reader = FileReader::try_new(Cursor::new(bytes), None).context(ReadArrowIpcSnafu {
+            file: self.file_path,
+        })?;
+
+        let store_schema = Arc::new(StoreSchema::try_from(reader.schema()).context(
+            error::ConvertStoreSchemaSnafu {
+                file: self.file_path,
+            },
+        )?);
+        let adapter = ReadAdapter::new(store_schema, self.projected_schema.clone())?;
+
+        let fields_to_read = adapter.fields_to_read();
+        let file_name = self.file_path.to_string();
+        let batches = reader.map(move |record_batch| {
+            record_batch
+                .and_then(|record_batch| record_batch.project(&fields_to_read))
+                .context(ReadArrowIpcSnafu { file: &file_name })
+        });
+
+        if self.reverse {
+            // The whole file is already in memory, so batches are collected and
+            // returned from the last one.
+            let mut batches: Vec<_> = batches.collect();
+            batches.reverse();
+            return Ok(
+                ChunkStream::new(adapter, Box::pin(futures_util::stream::iter(batches)))?
+                    .with_reverse(true),
+            );
+        }
+
+        ChunkStream::new(adapter, Box::pin(futures_util::stream::iter(batches)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_time::Timestamp;
+    use object_store::backend::fs::Builder;
+    use store_api::storage::OpType;
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::memtable::{
+        tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
+    };
+    use crate::read::BatchReader;
+    use crate::schema::ProjectedSchema;
+
+    #[tokio::test]
+    async fn test_arrow_ipc_write_and_read() {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema.clone());
+
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1), (1001, 1)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), Some(1234)),
+                (Some(3), Some(1234)),
+            ], // values
+        );
+
+        let dir = TempDir::new("write_arrow_ipc").unwrap();
+        let path = dir.path().to_str().unwrap();
+        let backend = Builder::default().root(path).build().unwrap();
+        let object_store = ObjectStore::new(backend);
+        let sst_file_name = "test-flush.arrow";
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ArrowIpcWriter::new(sst_file_name, iter, object_store.clone());
+
+        let info = writer.write_sst(&WriteOptions::default()).await.unwrap();
+        assert_eq!(4, info.num_rows);
+        assert_eq!(
+            Some((
+                Timestamp::new_millisecond(1000),
+                Timestamp::new_millisecond(2002)
+            )),
+            info.time_range
+        );
+        assert!(info.file_size > 0);
+
+        // Only reads the v0 column.
+        let projected_schema = Arc::new(ProjectedSchema::new(schema, Some(vec![1])).unwrap());
+        let reader = ArrowIpcReader::new(sst_file_name, object_store, projected_schema);
+        let mut stream = reader.chunk_stream().await.unwrap();
+        let batch = stream.next_batch().await.unwrap().unwrap();
+        assert_eq!(4, batch.num_rows());
+        assert!(stream.next_batch().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,643 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bloom filters on row keys of SST files.
+//!
+//! The row key in the filter is made up of the tags and the time bucket of the timestamp
+//! of a row, so reads and compactions could skip files that never contain some keys
+//! without reading them. The filter of a SST file is stored in a separate file alongside
+//! the SST file, and its metadata is recorded in the manifest.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use common_query::logical_plan::{DfExpr, Expr};
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
+use datafusion_expr::expr::BinaryExpr;
+use datafusion_expr::Operator;
+use datatypes::data_type::ConcreteDataType;
+use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::config::BloomFilterConfig;
+use crate::error::{Result, WriteObjectSnafu};
+use crate::read::Batch;
+use crate::schema::StoreSchema;
+
+/// Extension of bloom filter files.
+pub const BLOOM_FILTER_EXTENSION: &str = "bloom";
+
+/// Max number of keys to look up in the filter for a read, the filter isn't used if the
+/// read might hit more keys.
+const MAX_PROBE_KEYS: usize = 64;
+/// Max number of hash functions of a filter.
+const MAX_NUM_HASHES: u32 = 16;
+/// Version of the encoded filter.
+const FORMAT_VERSION: u8 = 1;
+/// Size of the header of the encoded filter, including the version and number of hashes.
+const HEADER_SIZE: usize = 5;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Metadata of the bloom filter of a SST file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BloomFilterMeta {
+    /// Tag columns in the row key, in the order they are hashed.
+    pub tag_columns: Vec<String>,
+    /// Timestamp column in the row key.
+    pub timestamp_column: String,
+    /// Duration of the time buckets in milliseconds.
+    pub time_bucket_millis: i64,
+    /// Number of distinct keys in the filter.
+    pub num_keys: usize,
+    /// Number of bits of the filter.
+    pub num_bits: usize,
+    /// Number of hash functions of the filter.
+    pub num_hashes: u32,
+    /// Size of the filter file in bytes.
+    pub file_size: usize,
+}
+
+/// Returns the name of the bloom filter file of the SST file `sst_file_name`.
+pub fn bloom_filter_file_name(sst_file_name: &str) -> String {
+    format!("{sst_file_name}.{BLOOM_FILTER_EXTENSION}")
+}
+
+/// A bloom filter of hashes of row keys.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    num_hashes: u32,
+    bits: Vec<u64>,
+}
+
+impl fmt::Debug for BloomFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BloomFilter")
+            .field("num_hashes", &self.num_hashes)
+            .field("num_bits", &self.num_bits())
+            .finish()
+    }
+}
+
+impl BloomFilter {
+    /// Creates a filter holding `num_keys` keys with expected `false_positive_rate`, the
+    /// size of the filter is limited to `max_bytes`.
+    pub fn with_capacity(
+        num_keys: usize,
+        false_positive_rate: f64,
+        max_bytes: usize,
+    ) -> BloomFilter {
+        let num_keys = num_keys.max(1) as f64;
+        let false_positive_rate = false_positive_rate.clamp(f64::MIN_POSITIVE, 1.0);
+        let ln2 = std::f64::consts::LN_2;
+        let optimal_bits = -num_keys * false_positive_rate.ln() / (ln2 * ln2);
+        let max_words = (max_bytes / 8).max(1);
+        let num_words = ((optimal_bits / 64.0).ceil() as usize).clamp(1, max_words);
+        let num_bits = (num_words * 64) as f64;
+        let num_hashes = ((num_bits / num_keys * ln2).round() as u32).clamp(1, MAX_NUM_HASHES);
+
+        BloomFilter {
+            num_hashes,
+            bits: vec![0; num_words],
+        }
+    }
+
+    /// Returns the number of bits of the filter.
+    pub fn num_bits(&self) -> usize {
+        self.bits.len() * 64
+    }
+
+    /// Returns the number of hash functions of the filter.
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Inserts the key with `hash` into the filter.
+    pub fn insert(&mut self, hash: u64) {
+        for bit in self.bit_indices(hash) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `false` if the key with `hash` is definitely not in the filter.
+    pub fn contains(&self, hash: u64) -> bool {
+        self.bit_indices(hash)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Encodes the filter into bytes.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(HEADER_SIZE + self.bits.len() * 8);
+        buf.push(FORMAT_VERSION);
+        buf.extend_from_slice(&self.num_hashes.to_le_bytes());
+        for word in &self.bits {
+            buf.extend_from_slice(&word.to_le_bytes());
+        }
+        buf
+    }
+
+    /// Decodes the filter from `data`, returns `None` if the data is corrupted.
+    pub fn decode(data: &[u8]) -> Option<BloomFilter> {
+        if data.len() <= HEADER_SIZE
+            || data[0] != FORMAT_VERSION
+            || (data.len() - HEADER_SIZE) % 8 != 0
+        {
+            return None;
+        }
+        let num_hashes = u32::from_le_bytes(data[1..HEADER_SIZE].try_into().ok()?);
+        if num_hashes == 0 || num_hashes > MAX_NUM_HASHES {
+            return None;
+        }
+        let bits = data[HEADER_SIZE..]
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+
+        Some(BloomFilter { num_hashes, bits })
+    }
+
+    /// Returns the bits of the key with `hash`, using double hashing to simulate
+    /// multiple hash functions.
+    fn bit_indices(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let num_bits = self.num_bits() as u64;
+        let delta = mix(hash) | 1;
+        (0..u64::from(self.num_hashes))
+            .map(move |i| (hash.wrapping_add(i.wrapping_mul(delta)) % num_bits) as usize)
+    }
+}
+
+/// Returns the hash of the row key made up of `tags` and time `bucket`.
+///
+/// The hash is persisted in filter files, so it must be stable across versions.
+pub fn hash_row_key<'a>(tags: impl IntoIterator<Item = &'a Value>, bucket: i64) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+    for tag in tags {
+        if tag.is_null() {
+            write(&[0]);
+        } else {
+            let tag = tag.to_string();
+            write(&[1]);
+            write(&(tag.len() as u64).to_le_bytes());
+            write(tag.as_bytes());
+        }
+    }
+    write(&bucket.to_le_bytes());
+
+    mix(hash)
+}
+
+/// Returns the time bucket of `timestamp`.
+pub fn time_bucket(timestamp: Timestamp, time_bucket_millis: i64) -> i64 {
+    timestamp
+        .convert_to(TimeUnit::Millisecond)
+        .div_euclid(time_bucket_millis)
+}
+
+/// Finalizer of splitmix64, spreads bits of the FNV hash.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+impl BloomFilterMeta {
+    /// Returns hashes of row keys of rows that might match all `filters`, or `None` if
+    /// the filters don't restrict rows to a few keys. Rows in the file with other keys
+    /// never match the filters.
+    ///
+    /// `time_range` is the time range of rows in the file, which is used to enumerate
+    /// time buckets if the filters don't specify the timestamp.
+    pub(crate) fn probe_keys(
+        &self,
+        schema: &SchemaRef,
+        filters: &[Expr],
+        time_range: Option<(Timestamp, Timestamp)>,
+    ) -> Option<Vec<u64>> {
+        let mut column_values = HashMap::new();
+        for filter in filters {
+            collect_values(schema, filter.df_expr(), &mut column_values);
+        }
+
+        let buckets: BTreeSet<_> = match column_values.get(self.timestamp_column.as_str()) {
+            Some(values) => values
+                .iter()
+                .filter_map(|value| match value {
+                    Value::Timestamp(ts) => Some(time_bucket(*ts, self.time_bucket_millis)),
+                    _ => None,
+                })
+                .collect(),
+            None => {
+                let (start, end) = time_range?;
+                let start = time_bucket(start, self.time_bucket_millis);
+                let end = time_bucket(end, self.time_bucket_millis);
+                if end.checked_sub(start)? >= MAX_PROBE_KEYS as i64 {
+                    return None;
+                }
+                (start..=end).collect()
+            }
+        };
+
+        let mut num_keys = buckets.len();
+        let mut tags: Vec<Vec<&Value>> = vec![Vec::new()];
+        for column in &self.tag_columns {
+            let values = column_values.get(column.as_str())?;
+            num_keys = num_keys.checked_mul(values.len())?;
+            if num_keys > MAX_PROBE_KEYS {
+                return None;
+            }
+            tags = tags
+                .iter()
+                .flat_map(|key| {
+                    values.iter().map(move |value| {
+                        let mut key = key.clone();
+                        key.push(value);
+                        key
+                    })
+                })
+                .collect();
+        }
+
+        Some(
+            tags.iter()
+                .flat_map(|key| {
+                    buckets
+                        .iter()
+                        .map(move |bucket| hash_row_key(key.iter().copied(), *bucket))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Collects values of columns that rows matching `expr` must have into `column_values`.
+///
+/// Only the first expr of a column is collected, as rows matching all exprs must match
+/// any of them.
+fn collect_values<'a>(
+    schema: &SchemaRef,
+    expr: &'a DfExpr,
+    column_values: &mut HashMap<&'a str, Vec<Value>>,
+) {
+    let (column, literals) = match expr {
+        DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+            Operator::And => {
+                collect_values(schema, left, column_values);
+                collect_values(schema, right, column_values);
+                return;
+            }
+            Operator::Eq => match (left.as_ref(), right.as_ref()) {
+                (DfExpr::Column(c), DfExpr::Literal(v))
+                | (DfExpr::Literal(v), DfExpr::Column(c)) => (c, vec![v]),
+                _ => return,
+            },
+            _ => return,
+        },
+        DfExpr::InList {
+            expr,
+            list,
+            negated: false,
+        } => {
+            let DfExpr::Column(c) = expr.as_ref() else {
+                return;
+            };
+            let Some(literals) = list
+                .iter()
+                .map(|e| match e {
+                    DfExpr::Literal(v) => Some(v),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+            else {
+                return;
+            };
+            (c, literals)
+        }
+        _ => return,
+    };
+    if column_values.contains_key(column.name.as_str()) {
+        return;
+    }
+    let Some(column_schema) = schema.column_schema_by_name(&column.name) else {
+        return;
+    };
+
+    let mut values = Vec::with_capacity(literals.len());
+    for literal in literals {
+        let Ok(value) = Value::try_from(literal.clone()) else {
+            return;
+        };
+        // Null never equals to any value.
+        if value.is_null() {
+            continue;
+        }
+        // Values in other types might be displayed differently, so they can't be
+        // looked up in the filter. Timestamps are converted to the same unit.
+        let matches_type = match value {
+            Value::Timestamp(_) => {
+                matches!(column_schema.data_type, ConcreteDataType::Timestamp(_))
+            }
+            _ => value.data_type() == column_schema.data_type,
+        };
+        if !matches_type {
+            return;
+        }
+        values.push(value);
+    }
+    column_values.insert(column.name.as_str(), values);
+}
+
+/// Builds the bloom filter of batches written to a SST file.
+pub(crate) struct BloomFilterBuilder {
+    config: BloomFilterConfig,
+    /// Indices of tag columns in batches.
+    tag_indices: Vec<usize>,
+    timestamp_index: usize,
+    meta: BloomFilterMeta,
+    /// Hashes of distinct keys in batches.
+    hashes: HashSet<u64>,
+}
+
+impl BloomFilterBuilder {
+    /// Creates a builder for batches in `store_schema`, returns `None` if the filter
+    /// isn't enabled by `config` or the schema has no timestamp column.
+    pub(crate) fn new(
+        store_schema: &StoreSchema,
+        config: Option<&BloomFilterConfig>,
+    ) -> Option<BloomFilterBuilder> {
+        let config = config?;
+        let schema = store_schema.schema();
+        let timestamp_index = schema.timestamp_index()?;
+        if !matches!(
+            schema.column_schemas()[timestamp_index].data_type,
+            ConcreteDataType::Timestamp(_)
+        ) {
+            return None;
+        }
+        let tag_indices: Vec<_> = (0..store_schema.row_key_end())
+            .filter(|i| *i != timestamp_index)
+            .collect();
+        let column_name = |i: usize| schema.column_schemas()[i].name.clone();
+        let meta = BloomFilterMeta {
+            tag_columns: tag_indices.iter().map(|i| column_name(*i)).collect(),
+            timestamp_column: column_name(timestamp_index),
+            time_bucket_millis: (config.time_bucket.as_millis() as i64).max(1),
+            num_keys: 0,
+            num_bits: 0,
+            num_hashes: 0,
+            file_size: 0,
+        };
+
+        Some(BloomFilterBuilder {
+            config: config.clone(),
+            tag_indices,
+            timestamp_index,
+            meta,
+            hashes: HashSet::new(),
+        })
+    }
+
+    pub(crate) fn update(&mut self, batch: &Batch) {
+        let timestamps = batch.column(self.timestamp_index);
+        let tags: Vec<_> = self.tag_indices.iter().map(|i| batch.column(*i)).collect();
+        for row in 0..batch.num_rows() {
+            let Value::Timestamp(ts) = timestamps.get(row) else {
+                continue;
+            };
+            let tag_values: Vec<_> = tags.iter().map(|vector| vector.get(row)).collect();
+            let bucket = time_bucket(ts, self.meta.time_bucket_millis);
+            self.hashes.insert(hash_row_key(&tag_values, bucket));
+        }
+    }
+
+    /// Builds the filter and writes it alongside the SST file at `sst_path`.
+    pub(crate) async fn finish(
+        mut self,
+        sst_path: &str,
+        object_store: &ObjectStore,
+    ) -> Result<BloomFilterMeta> {
+        let mut filter = BloomFilter::with_capacity(
+            self.hashes.len(),
+            self.config.false_positive_rate,
+            self.config.max_bytes,
+        );
+        for hash in &self.hashes {
+            filter.insert(*hash);
+        }
+        let buf = filter.encode();
+
+        self.meta.num_keys = self.hashes.len();
+        self.meta.num_bits = filter.num_bits();
+        self.meta.num_hashes = filter.num_hashes();
+        self.meta.file_size = buf.len();
+
+        let object = object_store.object(&bloom_filter_file_name(sst_path));
+        object.write(buf).await.context(WriteObjectSnafu {
+            path: object.path(),
+        })?;
+
+        Ok(self.meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::{col, lit};
+    use datatypes::schema::{ColumnSchema, Schema};
+
+    use super::*;
+
+    fn new_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("idc", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("v", ConcreteDataType::int64_datatype(), true),
+        ]))
+    }
+
+    fn new_meta() -> BloomFilterMeta {
+        BloomFilterMeta {
+            tag_columns: vec!["host".to_string(), "idc".to_string()],
+            timestamp_column: "ts".to_string(),
+            time_bucket_millis: 1000,
+            num_keys: 0,
+            num_bits: 0,
+            num_hashes: 0,
+            file_size: 0,
+        }
+    }
+
+    fn ts_lit(value: i64) -> DfExpr {
+        lit(ScalarValue::TimestampMillisecond(Some(value), None))
+    }
+
+    fn key(host: &str, idc: &str, bucket: i64) -> u64 {
+        hash_row_key(&[Value::from(host), Value::from(idc)], bucket)
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut filter = BloomFilter::with_capacity(1000, 0.01, 1024 * 1024);
+        assert!(filter.num_hashes() > 1);
+        for i in 0..1000 {
+            filter.insert(hash_row_key(&[Value::from(i)], 0));
+        }
+        for i in 0..1000 {
+            assert!(filter.contains(hash_row_key(&[Value::from(i)], 0)));
+        }
+        let false_positives = (1000..11000)
+            .filter(|i| filter.contains(hash_row_key(&[Value::from(*i)], 0)))
+            .count();
+        assert!(false_positives < 300, "false positives: {false_positives}");
+
+        let decoded = BloomFilter::decode(&filter.encode()).unwrap();
+        assert_eq!(filter, decoded);
+        assert!(BloomFilter::decode(&[]).is_none());
+        assert!(BloomFilter::decode(&filter.encode()[..HEADER_SIZE + 4]).is_none());
+
+        // The size of the filter is limited.
+        let filter = BloomFilter::with_capacity(1000, 0.01, 64);
+        assert_eq!(512, filter.num_bits());
+    }
+
+    #[test]
+    fn test_hash_row_key() {
+        assert_eq!(key("a", "b", 1), key("a", "b", 1));
+        assert_ne!(key("a", "b", 1), key("a", "b", 2));
+        assert_ne!(key("a", "b", 1), key("b", "a", 1));
+        assert_ne!(key("ab", "", 1), key("a", "b", 1));
+        assert_ne!(
+            hash_row_key(&[Value::Null], 0),
+            hash_row_key(&[Value::from("")], 0)
+        );
+
+        assert_eq!(-1, time_bucket(Timestamp::new_millisecond(-1), 1000));
+        assert_eq!(2, time_bucket(Timestamp::new_second(2), 1000));
+    }
+
+    #[test]
+    fn test_probe_keys() {
+        let meta = new_meta();
+        let schema = new_schema();
+        let probe = |exprs: Vec<DfExpr>, time_range: Option<(i64, i64)>| {
+            let filters: Vec<Expr> = exprs.into_iter().map(Expr::from).collect();
+            let time_range = time_range.map(|(start, end)| {
+                (
+                    Timestamp::new_millisecond(start),
+                    Timestamp::new_millisecond(end),
+                )
+            });
+            meta.probe_keys(&schema, &filters, time_range)
+                .map(|mut keys| {
+                    keys.sort_unstable();
+                    keys
+                })
+        };
+        let sorted = |mut keys: Vec<u64>| {
+            keys.sort_unstable();
+            keys
+        };
+
+        assert_eq!(
+            Some(vec![key("a", "x", 1)]),
+            probe(
+                vec![
+                    col("host").eq(lit("a")),
+                    lit("x").eq(col("idc")).and(col("ts").eq(ts_lit(1500))),
+                ],
+                None,
+            )
+        );
+        assert_eq!(
+            Some(sorted(vec![
+                key("a", "x", 1),
+                key("b", "x", 1),
+                key("a", "x", 2),
+                key("b", "x", 2),
+            ])),
+            probe(
+                vec![
+                    col("host").in_list(vec![lit("a"), lit("b")], false),
+                    col("idc").eq(lit("x")),
+                    col("v").gt(lit(1i64)),
+                ],
+                Some((1000, 2999)),
+            )
+        );
+        // Null never matches.
+        assert_eq!(
+            Some(vec![]),
+            probe(
+                vec![
+                    col("host").eq(lit(ScalarValue::Utf8(None))),
+                    col("idc").eq(lit("x")),
+                ],
+                Some((1000, 1000)),
+            )
+        );
+
+        // Filters don't restrict rows to a few keys.
+        assert_eq!(None, probe(vec![col("host").eq(lit("a"))], Some((0, 0))));
+        assert_eq!(
+            None,
+            probe(
+                vec![col("host").eq(lit("a")), col("idc").eq(lit("x"))],
+                None,
+            )
+        );
+        assert_eq!(
+            None,
+            probe(
+                vec![col("host").eq(lit("a")), col("idc").eq(lit("x"))],
+                Some((0, 1000 * MAX_PROBE_KEYS as i64)),
+            )
+        );
+        assert_eq!(
+            None,
+            probe(
+                vec![
+                    col("host").eq(lit("a")).or(col("host").eq(lit("b"))),
+                    col("idc").eq(lit("x")),
+                ],
+                Some((0, 0)),
+            )
+        );
+        // The type of the literal doesn't match the column.
+        assert_eq!(
+            None,
+            probe(
+                vec![col("host").eq(lit(1i64)), col("idc").eq(lit("x"))],
+                Some((0, 0)),
+            )
+        );
+    }
+}
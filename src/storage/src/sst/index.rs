@@ -0,0 +1,361 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inverted index from values of tag columns to rows of a SST file.
+
+use std::collections::BTreeMap;
+
+use common_query::logical_plan::{DfExpr, Expr};
+use datafusion_common::ScalarValue;
+use datafusion_expr::expr::BinaryExpr;
+use datafusion_expr::Operator;
+use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::read::Batch;
+
+/// Max number of distinct values of a column to index in a SST file. The column isn't
+/// indexed in files with more values, as the index is kept in the manifest.
+pub const MAX_INDEXED_VALUES: usize = 4096;
+
+/// Sorted and non-overlapping ranges `[start, end)` of rows.
+pub type RowRanges = Vec<(usize, usize)>;
+
+/// Inverted index of some tag columns in a SST file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SstIndex {
+    /// Ranges of rows of each value by column name, values are keyed by their display
+    /// string.
+    columns: BTreeMap<String, BTreeMap<String, RowRanges>>,
+}
+
+impl SstIndex {
+    /// Returns whether the `column` is indexed.
+    pub fn contains_column(&self, column: &str) -> bool {
+        self.columns.contains_key(column)
+    }
+
+    /// Returns ranges of rows that might match all `filters`, or `None` if the index
+    /// can't filter rows by them. Rows out of the ranges never match the filters.
+    pub fn select_rows(&self, schema: &SchemaRef, filters: &[Expr]) -> Option<RowRanges> {
+        filters
+            .iter()
+            .filter_map(|filter| self.select_by_expr(schema, filter.df_expr()))
+            .reduce(|a, b| intersect_ranges(&a, &b))
+    }
+
+    fn select_by_expr(&self, schema: &SchemaRef, expr: &DfExpr) -> Option<RowRanges> {
+        match expr {
+            DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And => {
+                    match (
+                        self.select_by_expr(schema, left),
+                        self.select_by_expr(schema, right),
+                    ) {
+                        (Some(a), Some(b)) => Some(intersect_ranges(&a, &b)),
+                        (a, b) => a.or(b),
+                    }
+                }
+                Operator::Or => Some(union_ranges(
+                    &self.select_by_expr(schema, left)?,
+                    &self.select_by_expr(schema, right)?,
+                )),
+                Operator::Eq => match (left.as_ref(), right.as_ref()) {
+                    (DfExpr::Column(c), DfExpr::Literal(v))
+                    | (DfExpr::Literal(v), DfExpr::Column(c)) => {
+                        self.select_by_values(schema, &c.name, std::slice::from_ref(v))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            DfExpr::InList {
+                expr,
+                list,
+                negated: false,
+            } => {
+                let DfExpr::Column(c) = expr.as_ref() else {
+                    return None;
+                };
+                let values = list
+                    .iter()
+                    .map(|e| match e {
+                        DfExpr::Literal(v) => Some(v.clone()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                self.select_by_values(schema, &c.name, &values)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns rows whose value of `column` is one of `values`.
+    fn select_by_values(
+        &self,
+        schema: &SchemaRef,
+        column: &str,
+        values: &[ScalarValue],
+    ) -> Option<RowRanges> {
+        let index = self.columns.get(column)?;
+        let data_type = &schema.column_schema_by_name(column)?.data_type;
+        let mut selected = RowRanges::new();
+        for value in values {
+            let value = Value::try_from(value.clone()).ok()?;
+            // Null never equals to any value.
+            if value.is_null() {
+                continue;
+            }
+            // Values in other types might be displayed differently, so they can't be
+            // looked up in the index.
+            if value.data_type() != *data_type {
+                return None;
+            }
+            if let Some(ranges) = index.get(&value.to_string()) {
+                selected = union_ranges(&selected, ranges);
+            }
+        }
+        Some(selected)
+    }
+}
+
+/// Builds the [SstIndex] of batches written to a SST file.
+pub(crate) struct IndexBuilder {
+    /// Names of columns to index and their indices in batches.
+    columns: Vec<(String, usize)>,
+    /// Index of each column, `None` if there are too many values to index.
+    values: Vec<Option<BTreeMap<String, RowRanges>>>,
+    num_rows: usize,
+}
+
+impl IndexBuilder {
+    /// Creates a builder to index `index_columns` of batches in `schema`, columns not in
+    /// the schema are ignored.
+    pub(crate) fn new(schema: &SchemaRef, index_columns: &[String]) -> IndexBuilder {
+        let columns: Vec<_> = index_columns
+            .iter()
+            .filter_map(|name| {
+                schema
+                    .column_index_by_name(name)
+                    .map(|index| (name.clone(), index))
+            })
+            .collect();
+        let values = vec![Some(BTreeMap::new()); columns.len()];
+        IndexBuilder {
+            columns,
+            values,
+            num_rows: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, batch: &Batch) {
+        for ((_, column_index), values) in self.columns.iter().zip(self.values.iter_mut()) {
+            let Some(index) = values else {
+                continue;
+            };
+            let vector = batch.column(*column_index);
+            for i in 0..vector.len() {
+                let row = self.num_rows + i;
+                let ranges = index.entry(vector.get(i).to_string()).or_default();
+                match ranges.last_mut() {
+                    Some((_, end)) if *end == row => *end = row + 1,
+                    _ => ranges.push((row, row + 1)),
+                }
+            }
+            if index.len() > MAX_INDEXED_VALUES {
+                *values = None;
+            }
+        }
+        self.num_rows += batch.num_rows();
+    }
+
+    /// Returns the index, or `None` if no column is indexed.
+    pub(crate) fn build(self) -> Option<SstIndex> {
+        let columns: BTreeMap<_, _> = self
+            .columns
+            .into_iter()
+            .zip(self.values)
+            .filter_map(|((name, _), values)| values.map(|values| (name, values)))
+            .collect();
+        if columns.is_empty() {
+            None
+        } else {
+            Some(SstIndex { columns })
+        }
+    }
+}
+
+/// Returns rows in both `a` and `b`.
+pub(crate) fn intersect_ranges(a: &[(usize, usize)], b: &[(usize, usize)]) -> RowRanges {
+    let mut result = RowRanges::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start < end {
+            result.push((start, end));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+/// Returns rows in either `a` or `b`.
+pub(crate) fn union_ranges(a: &[(usize, usize)], b: &[(usize, usize)]) -> RowRanges {
+    let mut all: Vec<_> = a.iter().chain(b).copied().collect();
+    all.sort_unstable();
+    let mut result: RowRanges = Vec::with_capacity(all.len());
+    for (start, end) in all {
+        match result.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+            _ => result.push((start, end)),
+        }
+    }
+    result
+}
+
+/// Returns whether any row in `[start, end)` is in `ranges`.
+pub(crate) fn overlaps(ranges: &[(usize, usize)], start: usize, end: usize) -> bool {
+    ranges.iter().any(|(s, e)| *s < end && start < *e)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_expr::{col, lit};
+    use datatypes::data_type::ConcreteDataType;
+    use datatypes::prelude::VectorRef;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int64Vector, StringVector};
+
+    use super::*;
+
+    fn new_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("v", ConcreteDataType::int64_datatype(), true),
+        ]))
+    }
+
+    fn new_batch(hosts: &[&str], values: &[i64]) -> Batch {
+        Batch::new(vec![
+            Arc::new(StringVector::from_slice(hosts)) as VectorRef,
+            Arc::new(Int64Vector::from_slice(values)) as VectorRef,
+        ])
+    }
+
+    fn new_index() -> SstIndex {
+        let schema = new_schema();
+        let mut builder = IndexBuilder::new(&schema, &["host".to_string(), "unknown".to_string()]);
+        builder.update(&new_batch(&["a", "a", "b"], &[1, 2, 3]));
+        builder.update(&new_batch(&["b", "c", "a"], &[4, 5, 6]));
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_build_index() {
+        let index = new_index();
+        assert!(index.contains_column("host"));
+        assert!(!index.contains_column("v"));
+        assert!(!index.contains_column("unknown"));
+        let host = &index.columns["host"];
+        assert_eq!(vec![(0, 2), (5, 6)], host["a"]);
+        assert_eq!(vec![(2, 4)], host["b"]);
+        assert_eq!(vec![(4, 5)], host["c"]);
+
+        let schema = new_schema();
+        assert!(IndexBuilder::new(&schema, &[]).build().is_none());
+    }
+
+    #[test]
+    fn test_too_many_values() {
+        let schema = new_schema();
+        let mut builder = IndexBuilder::new(&schema, &["host".to_string()]);
+        let hosts: Vec<_> = (0..=MAX_INDEXED_VALUES).map(|i| i.to_string()).collect();
+        let hosts: Vec<_> = hosts.iter().map(|h| h.as_str()).collect();
+        let values = vec![0; hosts.len()];
+        builder.update(&new_batch(&hosts, &values));
+        assert!(builder.build().is_none());
+    }
+
+    #[test]
+    fn test_select_rows() {
+        let index = new_index();
+        let schema = new_schema();
+        let select = |exprs: Vec<DfExpr>| {
+            let filters: Vec<Expr> = exprs.into_iter().map(Expr::from).collect();
+            index.select_rows(&schema, &filters)
+        };
+
+        assert_eq!(
+            Some(vec![(0, 2), (5, 6)]),
+            select(vec![col("host").eq(lit("a"))])
+        );
+        assert_eq!(Some(vec![]), select(vec![col("host").eq(lit("d"))]));
+        assert_eq!(
+            Some(vec![(0, 4), (5, 6)]),
+            select(vec![col("host").in_list(vec![lit("a"), lit("b")], false)])
+        );
+        assert_eq!(
+            Some(vec![(0, 2), (4, 6)]),
+            select(vec![lit("a").eq(col("host")).or(col("host").eq(lit("c")))])
+        );
+        assert_eq!(
+            Some(vec![(0, 2), (5, 6)]),
+            select(vec![
+                col("host").in_list(vec![lit("a"), lit("c")], false),
+                col("host").eq(lit("a")).and(col("v").gt(lit(3i64))),
+                col("v").gt(lit(5i64)),
+            ])
+        );
+
+        // Filters can't be evaluated by the index.
+        assert_eq!(None, select(vec![]));
+        assert_eq!(None, select(vec![col("v").eq(lit(1i64))]));
+        assert_eq!(None, select(vec![col("host").not_eq(lit("a"))]));
+        assert_eq!(
+            None,
+            select(vec![col("host").eq(lit("a")).or(col("v").eq(lit(1i64)))])
+        );
+        assert_eq!(
+            None,
+            select(vec![col("host").in_list(vec![lit("a")], true)])
+        );
+        // The type of the literal doesn't match the column.
+        assert_eq!(None, select(vec![col("host").eq(lit(1i64))]));
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            vec![(1, 2), (4, 5), (5, 6), (8, 9)],
+            intersect_ranges(&[(0, 2), (4, 6), (8, 10)], &[(1, 5), (5, 9)])
+        );
+        assert!(intersect_ranges(&[(0, 2)], &[(2, 4)]).is_empty());
+        assert_eq!(
+            vec![(0, 6), (8, 10)],
+            union_ranges(&[(0, 2), (4, 6), (8, 10)], &[(1, 5)])
+        );
+        assert_eq!(vec![(0, 4)], union_ranges(&[(0, 2)], &[(2, 4)]));
+
+        assert!(overlaps(&[(0, 2), (4, 6)], 1, 3));
+        assert!(!overlaps(&[(0, 2), (4, 6)], 2, 4));
+    }
+}
@@ -21,6 +21,8 @@ use std::sync::Arc;
 use async_compat::CompatExt;
 use async_stream::try_stream;
 use async_trait::async_trait;
+use datatypes::arrow::array::UInt32Array;
+use datatypes::arrow::compute;
 use datatypes::arrow::record_batch::RecordBatch;
 use futures_util::{Stream, StreamExt, TryStreamExt};
 use object_store::ObjectStore;
@@ -28,7 +30,9 @@ use parquet::arrow::{ArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMas
 use parquet::basic::{Compression, Encoding};
 use parquet::file::metadata::KeyValue;
 use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
 use snafu::ResultExt;
+use store_api::storage::Compression as SstCompression;
 use table::predicate::Predicate;
 use tokio::io::BufReader;
 
@@ -36,17 +40,55 @@ use crate::error::{
     self, NewRecordBatchSnafu, ReadParquetSnafu, Result, WriteObjectSnafu, WriteParquetSnafu,
 };
 use crate::memtable::BoxedBatchIterator;
-use crate::read::{Batch, BatchReader};
+use crate::read::{Batch, BatchReader, BoxedBatchReader};
 use crate::schema::compat::ReadAdapter;
 use crate::schema::{ProjectedSchemaRef, StoreSchema};
-use crate::sst;
+use crate::sst::bloom::BloomFilterBuilder;
+use crate::sst::index::{self, IndexBuilder};
+use crate::sst::{self, FileFormat, ReadOptions, RowRanges, SstInfo};
+
+/// Parquet sst format.
+pub struct ParquetFormat;
+
+#[async_trait]
+impl FileFormat for ParquetFormat {
+    async fn write_sst(
+        &self,
+        file_path: &str,
+        iter: BoxedBatchIterator,
+        object_store: ObjectStore,
+        opts: &sst::WriteOptions,
+    ) -> Result<SstInfo> {
+        let writer = ParquetWriter::new(file_path, iter, object_store);
+
+        writer.write_sst(opts).await
+    }
+
+    async fn read_sst(
+        &self,
+        file_path: &str,
+        object_store: ObjectStore,
+        opts: &ReadOptions,
+    ) -> Result<BoxedBatchReader> {
+        let reader = ParquetReader::new(
+            file_path,
+            object_store,
+            opts.projected_schema.clone(),
+            opts.predicate.clone(),
+        )
+        .with_row_ranges(opts.row_ranges.clone())
+        .with_reverse(opts.reverse);
+
+        let stream = reader.chunk_stream().await?;
+        Ok(Box::new(stream))
+    }
+}
 
 /// Parquet sst writer.
 pub struct ParquetWriter<'a> {
     file_path: &'a str,
     iter: BoxedBatchIterator,
     object_store: ObjectStore,
-    max_row_group_size: usize,
 }
 
 impl<'a> ParquetWriter<'a> {
@@ -59,33 +101,44 @@ impl<'a> ParquetWriter<'a> {
             file_path,
             iter,
             object_store,
-            max_row_group_size: 4096, // TODO(hl): make this configurable
         }
     }
 
-    pub async fn write_sst(self, _opts: &sst::WriteOptions) -> Result<()> {
-        self.write_rows(None).await
+    pub async fn write_sst(self, opts: &sst::WriteOptions) -> Result<SstInfo> {
+        self.write_rows(opts, None).await
     }
 
     /// Iterates memtable and writes rows to Parquet file.
     /// A chunk of records yielded from each iteration with a size given
     /// in config will be written to a single row group.
-    async fn write_rows(self, extra_meta: Option<HashMap<String, String>>) -> Result<()> {
+    async fn write_rows(
+        self,
+        opts: &sst::WriteOptions,
+        extra_meta: Option<HashMap<String, String>>,
+    ) -> Result<SstInfo> {
         let projected_schema = self.iter.schema();
         let store_schema = projected_schema.schema_to_read();
         let schema = store_schema.arrow_schema().clone();
+        let timestamp_index = store_schema.schema().timestamp_index();
         let object = self.object_store.object(self.file_path);
 
-        let writer_props = WriterProperties::builder()
-            .set_compression(Compression::ZSTD)
+        let mut props_builder = WriterProperties::builder()
+            .set_compression(to_parquet_compression(opts.compression))
             .set_encoding(Encoding::PLAIN)
-            .set_max_row_group_size(self.max_row_group_size)
+            .set_dictionary_enabled(opts.dictionary_enabled)
+            .set_max_row_group_size(opts.max_row_group_size)
             .set_key_value_metadata(extra_meta.map(|map| {
                 map.iter()
                     .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
                     .collect::<Vec<_>>()
-            }))
-            .build();
+            }));
+        for (column, compression) in &opts.column_compression {
+            props_builder = props_builder.set_column_compression(
+                ColumnPath::from(column.as_str()),
+                to_parquet_compression(*compression),
+            );
+        }
+        let writer_props = props_builder.build();
 
         // TODO(hl): Since OpenDAL's writer is async and ArrowWriter requires a `std::io::Write`,
         // here we use a Vec<u8> to buffer all parquet bytes in memory and write to object store
@@ -93,8 +146,16 @@ impl<'a> ParquetWriter<'a> {
         let mut buf = vec![];
         let mut arrow_writer = ArrowWriter::try_new(&mut buf, schema.clone(), Some(writer_props))
             .context(WriteParquetSnafu)?;
+        let mut info = SstInfo::default();
+        let mut index_builder = IndexBuilder::new(store_schema.schema(), &opts.index_columns);
+        let mut bloom_builder = BloomFilterBuilder::new(store_schema, opts.bloom_filter.as_ref());
         for batch in self.iter {
             let batch = batch?;
+            info.update(&batch, timestamp_index);
+            index_builder.update(&batch);
+            if let Some(bloom_builder) = &mut bloom_builder {
+                bloom_builder.update(&batch);
+            }
             let arrow_batch = RecordBatch::try_new(
                 schema.clone(),
                 batch
@@ -109,10 +170,28 @@ impl<'a> ParquetWriter<'a> {
                 .context(WriteParquetSnafu)?;
         }
         arrow_writer.close().context(WriteParquetSnafu)?;
+        info.file_size = buf.len();
+        info.index = index_builder.build();
         object.write(buf).await.context(WriteObjectSnafu {
             path: object.path(),
         })?;
-        Ok(())
+        if let Some(bloom_builder) = bloom_builder {
+            info.bloom_filter = Some(
+                bloom_builder
+                    .finish(self.file_path, &self.object_store)
+                    .await?,
+            );
+        }
+        Ok(info)
+    }
+}
+
+fn to_parquet_compression(compression: SstCompression) -> Compression {
+    match compression {
+        SstCompression::Uncompressed => Compression::UNCOMPRESSED,
+        SstCompression::Snappy => Compression::SNAPPY,
+        SstCompression::Lz4 => Compression::LZ4_RAW,
+        SstCompression::Zstd => Compression::ZSTD,
     }
 }
 
@@ -121,6 +200,8 @@ pub struct ParquetReader<'a> {
     object_store: ObjectStore,
     projected_schema: ProjectedSchemaRef,
     predicate: Predicate,
+    row_ranges: Option<RowRanges>,
+    reverse: bool,
 }
 
 impl<'a> ParquetReader<'a> {
@@ -135,9 +216,23 @@ impl<'a> ParquetReader<'a> {
             object_store,
             projected_schema,
             predicate,
+            row_ranges: None,
+            reverse: false,
         }
     }
 
+    /// Only reads row groups containing rows in `row_ranges`.
+    pub fn with_row_ranges(mut self, row_ranges: Option<RowRanges>) -> Self {
+        self.row_ranges = row_ranges;
+        self
+    }
+
+    /// Reads rows in the reverse order of the file if `reverse` is true.
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
     pub async fn chunk_stream(&self) -> Result<ChunkStream> {
         let operator = self.object_store.clone();
         let reader = operator.object(self.file_path).seekable_reader(..).compat();
@@ -157,16 +252,77 @@ impl<'a> ParquetReader<'a> {
 
         let adapter = ReadAdapter::new(store_schema.clone(), self.projected_schema.clone())?;
 
-        let pruned_row_groups = self.predicate.prune_row_groups(
+        let mut pruned_row_groups = self.predicate.prune_row_groups(
             store_schema.schema().clone(),
             builder.metadata().row_groups(),
         );
+        if let Some(row_ranges) = &self.row_ranges {
+            let mut start = 0;
+            for (row_group, valid) in builder
+                .metadata()
+                .row_groups()
+                .iter()
+                .zip(pruned_row_groups.iter_mut())
+            {
+                let end = start + row_group.num_rows() as usize;
+                *valid &= index::overlaps(row_ranges, start, end);
+                start = end;
+            }
+        }
 
         let projection = ProjectionMask::roots(
             builder.metadata().file_metadata().schema_descr(),
             adapter.fields_to_read(),
         );
 
+        let file_name = self.file_path.to_string();
+        if self.reverse {
+            // Reads valid row groups from the last one, rows of each row group are
+            // reversed by the chunk stream after reversing its batches.
+            let row_groups: Vec<_> = pruned_row_groups
+                .iter()
+                .enumerate()
+                .filter(|(_, valid)| **valid)
+                .map(|(i, _)| i)
+                .rev()
+                .collect();
+            let row_group_sizes: Vec<_> = row_groups
+                .iter()
+                .map(|i| builder.metadata().row_group(*i).num_rows() as usize)
+                .collect();
+            let mut stream = builder
+                .with_projection(projection)
+                .with_row_groups(row_groups)
+                .build()
+                .context(ReadParquetSnafu {
+                    file: self.file_path,
+                })?;
+
+            let chunk_stream = try_stream!({
+                for row_group_size in row_group_sizes {
+                    // Batches never span row groups.
+                    let mut batches = Vec::new();
+                    let mut num_rows = 0;
+                    while num_rows < row_group_size {
+                        match stream.next().await {
+                            Some(record_batch) => {
+                                let record_batch =
+                                    record_batch.context(ReadParquetSnafu { file: &file_name })?;
+                                num_rows += record_batch.num_rows();
+                                batches.push(record_batch);
+                            }
+                            None => break,
+                        }
+                    }
+                    for record_batch in batches.into_iter().rev() {
+                        yield record_batch;
+                    }
+                }
+            });
+
+            return Ok(ChunkStream::new(adapter, Box::pin(chunk_stream))?.with_reverse(true));
+        }
+
         let mut masked_stream = builder
             .with_projection(projection)
             .build()
@@ -175,7 +331,6 @@ impl<'a> ParquetReader<'a> {
             })?
             .zip(futures_util::stream::iter(pruned_row_groups.into_iter()));
 
-        let file_name = self.file_path.to_string();
         let chunk_stream = try_stream!({
             while let Some((record_batch, valid)) = masked_stream.next().await {
                 if valid {
@@ -193,22 +348,53 @@ pub type SendableChunkStream = Pin<Box<dyn Stream<Item = Result<RecordBatch>> +
 pub struct ChunkStream {
     adapter: ReadAdapter,
     stream: SendableChunkStream,
+    /// Whether to reverse rows of each record batch.
+    reverse: bool,
 }
 
 impl ChunkStream {
     pub fn new(adapter: ReadAdapter, stream: SendableChunkStream) -> Result<Self> {
-        Ok(Self { adapter, stream })
+        Ok(Self {
+            adapter,
+            stream,
+            reverse: false,
+        })
+    }
+
+    /// Reverses rows of each record batch from the stream if `reverse` is true.
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
     }
 }
 
+/// Returns a record batch with rows of `record_batch` in reverse order.
+fn reverse_record_batch(record_batch: &RecordBatch) -> Result<RecordBatch> {
+    let num_rows = record_batch.num_rows() as u32;
+    let indices = UInt32Array::from_iter_values((0..num_rows).rev());
+    let columns = record_batch
+        .columns()
+        .iter()
+        .map(|column| compute::take(column, &indices, None))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context(NewRecordBatchSnafu)?;
+    RecordBatch::try_new(record_batch.schema(), columns).context(NewRecordBatchSnafu)
+}
+
 #[async_trait]
 impl BatchReader for ChunkStream {
     async fn next_batch(&mut self) -> Result<Option<Batch>> {
-        self.stream
-            .try_next()
-            .await?
-            .map(|rb| self.adapter.arrow_record_batch_to_batch(&rb))
-            .transpose()
+        let Some(record_batch) = self.stream.try_next().await? else {
+            return Ok(None);
+        };
+        let record_batch = if self.reverse {
+            reverse_record_batch(&record_batch)?
+        } else {
+            record_batch
+        };
+        self.adapter
+            .arrow_record_batch_to_batch(&record_batch)
+            .map(Some)
     }
 }
 
@@ -216,8 +402,10 @@ impl BatchReader for ChunkStream {
 mod tests {
     use std::sync::Arc;
 
+    use common_time::Timestamp;
     use datatypes::arrow::array::{Array, ArrayRef, UInt64Array, UInt8Array};
     use datatypes::prelude::Vector;
+    use datatypes::value::Value;
     use datatypes::vectors::TimestampMillisecondVector;
     use object_store::backend::fs::Builder;
     use store_api::storage::OpType;
@@ -228,6 +416,7 @@ mod tests {
         tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
     };
     use crate::schema::ProjectedSchema;
+    use crate::sst::bloom;
 
     #[tokio::test]
     async fn test_parquet_writer() {
@@ -264,10 +453,33 @@ mod tests {
         let iter = memtable.iter(&IterContext::default()).unwrap();
         let writer = ParquetWriter::new(sst_file_name, iter, object_store.clone());
 
-        writer
+        let info = writer
             .write_sst(&sst::WriteOptions::default())
             .await
             .unwrap();
+        assert_eq!(6, info.num_rows);
+        assert_eq!(
+            Some((
+                Timestamp::new_millisecond(1000),
+                Timestamp::new_millisecond(2003)
+            )),
+            info.time_range
+        );
+        assert!(info.file_size > 0);
+
+        // All rows are in the same time bucket, so keys only differ in versions.
+        let bloom_meta = info.bloom_filter.unwrap();
+        assert_eq!(3, bloom_meta.num_keys);
+        let data = object_store
+            .object(&bloom::bloom_filter_file_name(sst_file_name))
+            .read()
+            .await
+            .unwrap();
+        assert_eq!(bloom_meta.file_size, data.len());
+        let filter = sst::BloomFilter::decode(&data).unwrap();
+        for version in [1u64, 2, 5] {
+            assert!(filter.contains(bloom::hash_row_key(&[Value::from(version)], 0)));
+        }
 
         // verify parquet file
         let reader = BufReader::new(
@@ -329,6 +541,65 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_parquet_writer_with_options() {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1), (2003, 1), (2003, 5)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), Some(1234)),
+                (Some(8), Some(1234)),
+                (Some(9), Some(1234)),
+            ], // values
+        );
+
+        let dir = TempDir::new("write_parquet_with_options").unwrap();
+        let path = dir.path().to_str().unwrap();
+        let backend = Builder::default().root(path).build().unwrap();
+        let object_store = ObjectStore::new(backend);
+        let sst_file_name = "test-options.parquet";
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        let writer = ParquetWriter::new(sst_file_name, iter, object_store.clone());
+
+        let opts = sst::WriteOptions {
+            compression: SstCompression::Snappy,
+            column_compression: HashMap::from([("v1".to_string(), SstCompression::Uncompressed)]),
+            dictionary_enabled: false,
+            max_row_group_size: 2,
+            index_columns: Vec::new(),
+            bloom_filter: None,
+        };
+        writer.write_sst(&opts).await.unwrap();
+
+        let reader = BufReader::new(
+            object_store
+                .object(sst_file_name)
+                .seekable_reader(..)
+                .compat(),
+        );
+        let builder = ParquetRecordBatchStreamBuilder::new(reader).await.unwrap();
+        let row_groups = builder.metadata().row_groups();
+        assert_eq!(3, row_groups.len());
+        for row_group in row_groups {
+            for column in row_group.columns() {
+                let expect = if column.column_path().string() == "v1" {
+                    Compression::UNCOMPRESSED
+                } else {
+                    Compression::SNAPPY
+                };
+                assert_eq!(expect, column.compression());
+                assert!(column.dictionary_page_offset().is_none());
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_parquet_reader() {
         common_telemetry::init_default_ut_logging();
@@ -18,8 +18,10 @@ use log_store::fs::config::LogConfig;
 use log_store::fs::log::LocalFileLogStore;
 use object_store::backend::fs::Builder;
 use object_store::ObjectStore;
+use store_api::storage::DedupPolicy;
 
 use crate::background::JobPoolImpl;
+use crate::config::DEFAULT_OUT_OF_ORDER_BUCKET;
 use crate::engine;
 use crate::flush::{FlushSchedulerImpl, SizeBasedStrategy};
 use crate::manifest::region::RegionManifest;
@@ -27,7 +29,7 @@ use crate::memtable::DefaultMemtableBuilder;
 use crate::region::StoreConfig;
 use crate::sst::FsAccessLayer;
 
-fn log_store_dir(store_dir: &str) -> String {
+pub fn log_store_dir(store_dir: &str) -> String {
     format!("{store_dir}/logstore")
 }
 
@@ -35,13 +37,23 @@ fn log_store_dir(store_dir: &str) -> String {
 pub async fn new_store_config(
     region_name: &str,
     store_dir: &str,
+) -> StoreConfig<LocalFileLogStore> {
+    let accessor = Builder::default().root(store_dir).build().unwrap();
+    let object_store = ObjectStore::new(accessor);
+    new_store_config_with_object_store(region_name, store_dir, object_store).await
+}
+
+/// Create a new StoreConfig for test, whose SST files and manifest are stored in
+/// `object_store`.
+pub async fn new_store_config_with_object_store(
+    region_name: &str,
+    store_dir: &str,
+    object_store: ObjectStore,
 ) -> StoreConfig<LocalFileLogStore> {
     let parent_dir = "";
     let sst_dir = engine::region_sst_dir(parent_dir, region_name);
     let manifest_dir = engine::region_manifest_dir(parent_dir, region_name);
 
-    let accessor = Builder::default().root(store_dir).build().unwrap();
-    let object_store = ObjectStore::new(accessor);
     let sst_layer = Arc::new(FsAccessLayer::new(&sst_dir, object_store.clone()));
     let manifest = RegionManifest::new(&manifest_dir, object_store);
     let job_pool = Arc::new(JobPoolImpl {});
@@ -59,5 +71,9 @@ pub async fn new_store_config(
         memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
         flush_scheduler,
         flush_strategy: Arc::new(SizeBasedStrategy::default()),
+        out_of_order_bucket: Some(DEFAULT_OUT_OF_ORDER_BUCKET),
+        max_files_in_level0: None,
+        wal_replicator: None,
+        dedup_policy: DedupPolicy::default(),
     }
 }
@@ -0,0 +1,279 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Range tombstones that delete rows by time range.
+//!
+//! A range tombstone is written to the WAL like other mutations, but instead of
+//! inserting a delete marker for each key, it is kept in the [Version](crate::version::Version)
+//! and masks the rows at read time. Tombstones are persisted to the manifest once the
+//! data they cover are flushed, rows of the flushed memtables masked by the tombstones
+//! are dropped during flush.
+//!
+//! Once all rows older than a tombstone are flushed and no SST written before the
+//! tombstone overlaps its range, the tombstone can't mask any row, so it is removed
+//! from the version and from later snapshots of the manifest.
+
+use std::sync::Arc;
+
+use common_base::BitVec;
+use datatypes::prelude::ScalarVector;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{BooleanVector, UInt64Vector};
+use serde::{Deserialize, Serialize};
+use store_api::storage::SequenceNumber;
+
+use crate::error::Result;
+use crate::memtable::{BatchIterator, BoxedBatchIterator, RowOrdering};
+use crate::read::{Batch, BatchOp};
+use crate::schema::ProjectedSchemaRef;
+
+/// A tombstone deletes all rows whose timestamp is in `[start, end)` and whose
+/// sequence is less than the sequence of the tombstone.
+///
+/// Timestamps are in the unit of the time index column of the region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeTombstone {
+    /// Inclusive start of the time range.
+    pub start: i64,
+    /// Exclusive end of the time range.
+    pub end: i64,
+    /// Sequence of the write batch that contains this tombstone.
+    pub sequence: SequenceNumber,
+}
+
+impl RangeTombstone {
+    /// Returns true if the row with `timestamp` and `sequence` is deleted by this
+    /// tombstone.
+    #[inline]
+    pub fn masks(&self, timestamp: i64, sequence: SequenceNumber) -> bool {
+        sequence < self.sequence && timestamp >= self.start && timestamp < self.end
+    }
+}
+
+/// Range tombstones of a region, ordered by sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeTombstones {
+    tombstones: Vec<RangeTombstone>,
+}
+
+pub type RangeTombstonesRef = Arc<RangeTombstones>;
+
+impl RangeTombstones {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tombstones.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tombstones.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RangeTombstone> {
+        self.tombstones.iter()
+    }
+
+    /// Returns a new [RangeTombstones] with `tombstones` added, tombstones already
+    /// in this set are ignored.
+    pub fn merge(&self, tombstones: impl IntoIterator<Item = RangeTombstone>) -> RangeTombstones {
+        let mut merged = self.tombstones.clone();
+        for tombstone in tombstones {
+            if !merged.contains(&tombstone) {
+                merged.push(tombstone);
+            }
+        }
+        merged.sort_unstable_by_key(|t| t.sequence);
+
+        RangeTombstones { tombstones: merged }
+    }
+
+    /// Returns tombstones whose sequence is in `(after, until]`.
+    pub fn between(&self, after: SequenceNumber, until: SequenceNumber) -> Vec<RangeTombstone> {
+        self.tombstones
+            .iter()
+            .filter(|t| t.sequence > after && t.sequence <= until)
+            .copied()
+            .collect()
+    }
+
+    /// Returns tombstones visible to a reader that reads data at `sequence`.
+    pub fn visible_at(&self, sequence: SequenceNumber) -> RangeTombstones {
+        self.filter(|t| t.sequence <= sequence)
+    }
+
+    /// Returns tombstones that satisfy the `predicate`.
+    pub fn filter(&self, mut predicate: impl FnMut(&RangeTombstone) -> bool) -> RangeTombstones {
+        RangeTombstones {
+            tombstones: self
+                .tombstones
+                .iter()
+                .filter(|t| predicate(t))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Marks rows in `batch` masked by the tombstones as unselected.
+    ///
+    /// The `batch` must contain the time index and sequence column, their indices are
+    /// given by `timestamp_index` and `sequence_index`.
+    pub fn unselect_masked(
+        &self,
+        batch: &Batch,
+        timestamp_index: usize,
+        sequence_index: usize,
+        selected: &mut BitVec,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+
+        let timestamps = batch.column(timestamp_index);
+        // Safety: The sequence column is always UInt64.
+        let sequences = batch
+            .column(sequence_index)
+            .as_any()
+            .downcast_ref::<UInt64Vector>()
+            .unwrap();
+        for (i, sequence) in sequences.iter_data().enumerate() {
+            let timestamp = timestamp_value(timestamps.get_ref(i));
+            if let (Some(timestamp), Some(sequence)) = (timestamp, sequence) {
+                if self.tombstones.iter().any(|t| t.masks(timestamp, sequence)) {
+                    selected.set(i, false);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the raw value of the time index.
+fn timestamp_value(value: ValueRef) -> Option<i64> {
+    match value {
+        ValueRef::Timestamp(ts) => Some(ts.value()),
+        ValueRef::Int64(v) => Some(v),
+        _ => None,
+    }
+}
+
+/// A [BatchIterator] that removes rows masked by range tombstones, used to drop
+/// deleted rows while flushing memtables.
+pub struct MaskedBatchIterator {
+    inner: BoxedBatchIterator,
+    tombstones: RangeTombstonesRef,
+    selected: BitVec,
+}
+
+impl MaskedBatchIterator {
+    pub fn new(inner: BoxedBatchIterator, tombstones: RangeTombstonesRef) -> MaskedBatchIterator {
+        MaskedBatchIterator {
+            inner,
+            tombstones,
+            selected: BitVec::default(),
+        }
+    }
+
+    fn mask_batch(&mut self, batch: Batch) -> Result<Batch> {
+        let schema = self.inner.schema();
+        let schema_to_read = schema.schema_to_read();
+        let Some(timestamp_index) = schema_to_read.schema().timestamp_index() else {
+            return Ok(batch);
+        };
+
+        self.selected.clear();
+        self.selected.resize(batch.num_rows(), true);
+        self.tombstones.unselect_masked(
+            &batch,
+            timestamp_index,
+            schema_to_read.sequence_index(),
+            &mut self.selected,
+        );
+        if self.selected.all() {
+            return Ok(batch);
+        }
+
+        let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
+        schema.filter(&batch, &filter)
+    }
+}
+
+impl BatchIterator for MaskedBatchIterator {
+    fn schema(&self) -> ProjectedSchemaRef {
+        self.inner.schema()
+    }
+
+    fn ordering(&self) -> RowOrdering {
+        self.inner.ordering()
+    }
+}
+
+impl Iterator for MaskedBatchIterator {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Result<Batch>> {
+        loop {
+            let batch = match self.inner.next()?.and_then(|batch| self.mask_batch(batch)) {
+                Ok(batch) => batch,
+                Err(e) => return Some(Err(e)),
+            };
+            // Skip batches whose rows are all deleted.
+            if !batch.is_empty() {
+                return Some(Ok(batch));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_tombstone(start: i64, end: i64, sequence: SequenceNumber) -> RangeTombstone {
+        RangeTombstone {
+            start,
+            end,
+            sequence,
+        }
+    }
+
+    #[test]
+    fn test_tombstone_masks() {
+        let tombstone = new_tombstone(10, 20, 5);
+        assert!(tombstone.masks(10, 4));
+        assert!(tombstone.masks(19, 0));
+        assert!(!tombstone.masks(20, 4));
+        assert!(!tombstone.masks(9, 4));
+        // Rows written after the tombstone are not masked.
+        assert!(!tombstone.masks(15, 5));
+        assert!(!tombstone.masks(15, 6));
+    }
+
+    #[test]
+    fn test_merge_tombstones() {
+        let tombstones = RangeTombstones::default();
+        assert!(tombstones.is_empty());
+
+        let tombstones = tombstones.merge([new_tombstone(0, 10, 3), new_tombstone(5, 8, 1)]);
+        assert_eq!(2, tombstones.len());
+        let sequences: Vec<_> = tombstones.iter().map(|t| t.sequence).collect();
+        assert_eq!(vec![1, 3], sequences);
+
+        // Existing tombstones are ignored.
+        let tombstones = tombstones.merge([new_tombstone(0, 10, 3), new_tombstone(0, 10, 7)]);
+        assert_eq!(3, tombstones.len());
+
+        assert_eq!(vec![new_tombstone(0, 10, 3)], tombstones.between(1, 3));
+        assert_eq!(2, tombstones.visible_at(6).len());
+        assert!(tombstones.visible_at(0).is_empty());
+    }
+}
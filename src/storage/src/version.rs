@@ -24,14 +24,17 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+use common_telemetry::logging;
 use store_api::manifest::ManifestVersion;
 use store_api::storage::{SchemaRef, SequenceNumber};
 
+use crate::key_range::KeyRange;
 use crate::memtable::{MemtableId, MemtableRef, MemtableVersion};
 use crate::metadata::RegionMetadataRef;
 use crate::schema::RegionSchemaRef;
 use crate::sst::{FileHandle, FileMeta, LevelMetas};
 use crate::sync::CowCell;
+use crate::tombstone::{RangeTombstone, RangeTombstones, RangeTombstonesRef};
 
 pub const INIT_COMMITTED_SEQUENCE: u64 = 0;
 
@@ -99,6 +102,27 @@ impl VersionControl {
         version_to_update.commit();
     }
 
+    /// Sets the mutable out-of-order memtable to `memtable`.
+    ///
+    /// External synchronization is required to ensure rows are written to the memtable
+    /// before committing their sequence.
+    pub fn set_out_of_order_memtable(&self, memtable: MemtableRef) {
+        let mut version_to_update = self.version.lock();
+        version_to_update.memtables =
+            Arc::new(version_to_update.memtables.with_out_of_order(memtable));
+        version_to_update.commit();
+    }
+
+    /// Adds `tombstones` to the version.
+    ///
+    /// External synchronization is required to ensure tombstones are visible before
+    /// committing their sequence.
+    pub fn add_range_tombstones(&self, tombstones: impl IntoIterator<Item = RangeTombstone>) {
+        let mut version_to_update = self.version.lock();
+        version_to_update.add_range_tombstones(tombstones);
+        version_to_update.commit();
+    }
+
     /// Apply [VersionEdit] to the version.
     pub fn apply_edit(&self, edit: VersionEdit) {
         let mut version_to_update = self.version.lock();
@@ -106,6 +130,15 @@ impl VersionControl {
         version_to_update.commit();
     }
 
+    /// Replace all memtables by the empty `mutable_memtable` and apply the `edit` in one
+    /// version switch, so readers see either all data of the region or nothing.
+    pub fn reset_memtables_and_apply_edit(&self, mutable_memtable: MemtableRef, edit: VersionEdit) {
+        let mut version_to_update = self.version.lock();
+        version_to_update.memtables = Arc::new(MemtableVersion::new(mutable_memtable));
+        version_to_update.apply_edit(edit);
+        version_to_update.commit();
+    }
+
     /// Freeze all mutable memtables and then apply the new metadata to the version.
     pub fn freeze_mutable_and_apply_metadata(
         &self,
@@ -129,9 +162,11 @@ impl VersionControl {
 #[derive(Debug)]
 pub struct VersionEdit {
     pub files_to_add: Vec<FileMeta>,
+    pub files_to_remove: Vec<FileMeta>,
     pub flushed_sequence: Option<SequenceNumber>,
     pub manifest_version: ManifestVersion,
     pub max_memtable_id: Option<MemtableId>,
+    pub range_tombstones: Vec<RangeTombstone>,
 }
 
 pub type VersionControlRef = Arc<VersionControl>;
@@ -157,6 +192,11 @@ pub struct Version {
     flushed_sequence: SequenceNumber,
     /// Current version of manifest.
     manifest_version: ManifestVersion,
+    /// Range tombstones of the region, including tombstones persisted in manifest and
+    /// tombstones recovered from WAL.
+    range_tombstones: RangeTombstonesRef,
+    /// Range of keys owned by the region, `None` if the region owns all keys.
+    key_range: Option<KeyRange>,
     // TODO(yingwen): Maybe also store last sequence to this version when switching
     // version, so we can know the newest data can read from this version.
 }
@@ -180,6 +220,8 @@ impl Version {
             ssts: Arc::new(LevelMetas::new()),
             flushed_sequence: 0,
             manifest_version,
+            range_tombstones: Arc::new(RangeTombstones::default()),
+            key_range: None,
         }
     }
 
@@ -218,6 +260,26 @@ impl Version {
         self.flushed_sequence
     }
 
+    #[inline]
+    pub fn range_tombstones(&self) -> &RangeTombstonesRef {
+        &self.range_tombstones
+    }
+
+    #[inline]
+    pub fn key_range(&self) -> Option<&KeyRange> {
+        self.key_range.as_ref()
+    }
+
+    /// Sets the range of keys owned by the region, rows of SSTs out of the range are
+    /// invisible.
+    pub fn set_key_range(&mut self, key_range: KeyRange) {
+        self.key_range = Some(key_range);
+    }
+
+    pub fn add_range_tombstones(&mut self, tombstones: impl IntoIterator<Item = RangeTombstone>) {
+        self.range_tombstones = Arc::new(self.range_tombstones.merge(tombstones));
+    }
+
     pub fn apply_edit(&mut self, edit: VersionEdit) {
         let flushed_sequence = edit.flushed_sequence.unwrap_or(self.flushed_sequence);
         if self.flushed_sequence < flushed_sequence {
@@ -234,10 +296,48 @@ impl Version {
             self.memtables = Arc::new(removed);
         }
 
+        // Rows older than the flushed files are out of order.
+        if let Some(max) = edit
+            .files_to_add
+            .iter()
+            .filter_map(|meta| meta.time_range.map(|(_, max)| max))
+            .max()
+        {
+            self.memtables = Arc::new(self.memtables.advance_watermark(max));
+        }
+
         let handles_to_add = edit.files_to_add.into_iter().map(FileHandle::new);
-        let merged_ssts = self.ssts.merge(handles_to_add);
+        let merged_ssts = self.ssts.merge(handles_to_add, &edit.files_to_remove);
 
         self.ssts = Arc::new(merged_ssts);
+
+        if !edit.range_tombstones.is_empty() {
+            self.add_range_tombstones(edit.range_tombstones);
+        }
+        self.prune_range_tombstones();
+    }
+
+    /// Removes range tombstones that can't mask any row, i.e. rows older than them are
+    /// flushed and no SST written before them overlaps their range.
+    fn prune_range_tombstones(&mut self) {
+        if self.range_tombstones.is_empty() {
+            return;
+        }
+
+        let flushed_sequence = self.flushed_sequence;
+        let files: Vec<_> = self.ssts.files().map(|file| file.meta()).collect();
+        let tombstones = self.range_tombstones.filter(|t| {
+            t.sequence > flushed_sequence || files.iter().any(|file| file.may_be_masked_by(t))
+        });
+        if tombstones.len() != self.range_tombstones.len() {
+            logging::debug!(
+                "Prune range tombstones of region {}, remaining: {}, removed: {}",
+                self.metadata.name(),
+                tombstones.len(),
+                self.range_tombstones.len() - tombstones.len(),
+            );
+            self.range_tombstones = Arc::new(tombstones);
+        }
     }
 
     /// Updates metadata of the version.
@@ -271,6 +371,9 @@ impl Version {
 
 #[cfg(test)]
 mod tests {
+    use common_time::Timestamp;
+    use store_api::storage::SstFormat;
+
     use super::*;
     use crate::memtable::{DefaultMemtableBuilder, MemtableBuilder};
     use crate::test_util::descriptor_util::RegionDescBuilder;
@@ -294,4 +397,99 @@ mod tests {
         version_control.set_committed_sequence(12345);
         assert_eq!(12345, version_control.committed_sequence());
     }
+
+    fn new_file_meta(
+        file_name: &str,
+        time_range: (i64, i64),
+        flushed_sequence: SequenceNumber,
+    ) -> FileMeta {
+        FileMeta {
+            file_name: file_name.to_string(),
+            level: 0,
+            time_range: Some((
+                Timestamp::new_millisecond(time_range.0),
+                Timestamp::new_millisecond(time_range.1),
+            )),
+            num_rows: 1,
+            file_size: 1,
+            format: SstFormat::default(),
+            index: None,
+            bloom_filter: None,
+            source_region: None,
+            flushed_sequence: Some(flushed_sequence),
+        }
+    }
+
+    fn new_edit(
+        files_to_add: Vec<FileMeta>,
+        files_to_remove: Vec<FileMeta>,
+        flushed_sequence: SequenceNumber,
+        range_tombstones: Vec<RangeTombstone>,
+    ) -> VersionEdit {
+        VersionEdit {
+            files_to_add,
+            files_to_remove,
+            flushed_sequence: Some(flushed_sequence),
+            manifest_version: 1,
+            max_memtable_id: None,
+            range_tombstones,
+        }
+    }
+
+    #[test]
+    fn test_add_range_tombstones() {
+        let version_control = new_version_control();
+        assert!(version_control.current().range_tombstones().is_empty());
+
+        let tombstone = RangeTombstone {
+            start: 0,
+            end: 100,
+            sequence: 3,
+        };
+        // The file flushed before the tombstone still has rows masked by it.
+        let file = new_file_meta("a.parquet", (10, 20), 2);
+        version_control.apply_edit(new_edit(vec![file], Vec::new(), 2, Vec::new()));
+        version_control.add_range_tombstones([tombstone]);
+        version_control.apply_edit(new_edit(Vec::new(), Vec::new(), 3, vec![tombstone]));
+
+        let tombstones = version_control.current().range_tombstones().clone();
+        assert_eq!(
+            vec![tombstone],
+            tombstones.iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_prune_range_tombstones() {
+        let version_control = new_version_control();
+        let tombstone = RangeTombstone {
+            start: 0,
+            end: 100,
+            sequence: 3,
+        };
+        let old_file = new_file_meta("a.parquet", (10, 20), 2);
+        let other_file = new_file_meta("b.parquet", (200, 300), 2);
+        version_control.apply_edit(new_edit(
+            vec![old_file.clone(), other_file],
+            Vec::new(),
+            2,
+            Vec::new(),
+        ));
+
+        // Rows older than the tombstone are not flushed yet.
+        version_control.add_range_tombstones([tombstone]);
+        version_control.apply_edit(new_edit(Vec::new(), Vec::new(), 2, Vec::new()));
+        assert_eq!(1, version_control.current().range_tombstones().len());
+
+        // The old file overlapping the tombstone keeps it, files flushed after the
+        // tombstone or out of its range don't.
+        let new_file = new_file_meta("c.parquet", (50, 60), 3);
+        version_control.apply_edit(new_edit(vec![new_file], Vec::new(), 3, vec![tombstone]));
+        assert_eq!(1, version_control.current().range_tombstones().len());
+
+        // Rewriting the old file removes the tombstone.
+        let rewritten = new_file_meta("d.parquet", (10, 20), 3);
+        version_control.apply_edit(new_edit(vec![rewritten], vec![old_file], 3, Vec::new()));
+        assert!(version_control.current().range_tombstones().is_empty());
+    }
 }
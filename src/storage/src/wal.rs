@@ -13,12 +13,14 @@
 // limitations under the License.
 
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use common_error::prelude::BoxedError;
+use common_telemetry::logging;
 use futures::{stream, Stream, TryStreamExt};
 use prost::Message;
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::entry::Entry;
 use store_api::logstore::{AppendResponse, LogStore};
 use store_api::storage::{RegionId, SequenceNumber};
@@ -29,6 +31,7 @@ use crate::error::{
     WalDataCorruptedSnafu, WriteWalSnafu,
 };
 use crate::proto::wal::{self, WalHeader};
+use crate::replication::{ReplicatedEntry, ReplicatedEntryStream, WalReplicatorRef};
 use crate::write_batch::codec::{PayloadDecoder, PayloadEncoder};
 use crate::write_batch::Payload;
 
@@ -37,6 +40,10 @@ pub struct Wal<S: LogStore> {
     region_id: RegionId,
     namespace: S::Namespace,
     store: Arc<S>,
+    /// Bytes appended to the wal of the region.
+    bytes_written: Arc<AtomicU64>,
+    /// Ships the appended entries to the standby region, if any.
+    replicator: Option<WalReplicatorRef>,
 }
 
 pub type PayloadStream<'a> =
@@ -49,6 +56,8 @@ impl<S: LogStore> Clone for Wal<S> {
             region_id: self.region_id,
             namespace: self.namespace.clone(),
             store: self.store.clone(),
+            bytes_written: self.bytes_written.clone(),
+            replicator: self.replicator.clone(),
         }
     }
 }
@@ -60,10 +69,24 @@ impl<S: LogStore> Wal<S> {
             region_id,
             namespace,
             store,
+            bytes_written: Arc::new(AtomicU64::new(0)),
+            replicator: None,
         }
     }
 
+    pub fn with_replicator(mut self, replicator: Option<WalReplicatorRef>) -> Self {
+        self.replicator = replicator;
+        self
+    }
+
     pub async fn obsolete(&self, seq: SequenceNumber) -> Result<()> {
+        // Keep the entries not acknowledged by the standby region, so it could still
+        // catch up from them.
+        let seq = match self.replicated_sequence() {
+            Some(acked) => seq.min(acked),
+            None => seq,
+        };
+
         self.store
             .obsolete(self.namespace.clone(), seq)
             .await
@@ -77,6 +100,20 @@ impl<S: LogStore> Wal<S> {
     pub fn region_id(&self) -> RegionId {
         self.region_id
     }
+
+    /// Returns bytes appended to the wal since the wal is created.
+    #[inline]
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// Returns the sequence acknowledged by the standby region, `None` if the region
+    /// has no standby.
+    pub fn replicated_sequence(&self) -> Option<SequenceNumber> {
+        self.replicator
+            .as_ref()
+            .and_then(|r| r.acked_sequence(self.region_id))
+    }
 }
 
 impl<S: LogStore> Wal<S> {
@@ -102,6 +139,7 @@ impl<S: LogStore> Wal<S> {
     ) -> Result<(u64, usize)> {
         if let Some(p) = payload {
             header.mutation_types = wal::gen_mutation_types(p);
+            header.delete_ranges = wal::gen_delete_ranges(p);
         }
 
         let mut buf = vec![];
@@ -150,7 +188,40 @@ impl<S: LogStore> Wal<S> {
         Ok(Box::pin(stream))
     }
 
-    async fn write(&self, seq: SequenceNumber, bytes: &[u8]) -> Result<(u64, usize)> {
+    /// Reads the encoded entries from `start_seq`, used to catch up the standby region.
+    pub async fn read_entries(
+        &self,
+        start_seq: SequenceNumber,
+    ) -> Result<ReplicatedEntryStream<'_>> {
+        let stream = self
+            .store
+            .read(&self.namespace, start_seq)
+            .await
+            .map_err(BoxedError::new)
+            .context(ReadWalSnafu {
+                region_id: self.region_id(),
+            })?
+            .map_err(|e| Error::ReadWal {
+                region_id: self.region_id(),
+                source: BoxedError::new(e),
+            })
+            .and_then(|entries| async {
+                let iter = entries.into_iter().map(|x| {
+                    Ok::<_, Error>(ReplicatedEntry {
+                        region_id: self.region_id(),
+                        sequence: x.id(),
+                        data: x.data().to_vec(),
+                    })
+                });
+
+                Ok(stream::iter(iter))
+            })
+            .try_flatten();
+
+        Ok(Box::pin(stream))
+    }
+
+    pub(crate) async fn write(&self, seq: SequenceNumber, bytes: &[u8]) -> Result<(u64, usize)> {
         let e = self.store.entry(bytes, seq, self.namespace.clone());
 
         let res = self
@@ -161,6 +232,26 @@ impl<S: LogStore> Wal<S> {
             .context(WriteWalSnafu {
                 region_id: self.region_id(),
             })?;
+        self.bytes_written
+            .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+
+        if let Some(replicator) = &self.replicator {
+            let entry = ReplicatedEntry {
+                region_id: self.region_id,
+                sequence: seq,
+                data: bytes.to_vec(),
+            };
+            // The write has been persisted locally, the standby could catch up from the
+            // local WAL if it misses this entry.
+            if let Err(e) = replicator.replicate(entry).await {
+                logging::warn!(
+                    "Failed to replicate WAL entry, region_id: {}, sequence: {}, err: {}",
+                    self.region_id,
+                    seq,
+                    e
+                );
+            }
+        }
 
         Ok((res.entry_id(), res.offset()))
     }
@@ -187,17 +278,24 @@ impl<S: LogStore> Wal<S> {
             }
         );
 
-        if header.mutation_types.is_empty() {
+        if header.mutation_types.is_empty() && header.delete_ranges.is_empty() {
             return Ok((seq_num, header, None));
         }
 
         let decoder = PayloadDecoder::new(&header.mutation_types);
-        let payload = decoder
+        let mut payload = decoder
             .decode(&input[data_pos..])
             .map_err(BoxedError::new)
             .context(ReadWalSnafu {
                 region_id: self.region_id(),
             })?;
+        for range in &header.delete_ranges {
+            let time_range = range.to_time_range().context(WalDataCorruptedSnafu {
+                region_id: self.region_id(),
+                message: format!("Invalid delete range {range:?}"),
+            })?;
+            payload.delete_ranges.push(time_range);
+        }
 
         Ok((seq_num, header, Some(payload)))
     }
@@ -241,6 +339,7 @@ mod tests {
     use log_store::test_util;
 
     use super::*;
+    use crate::replication::ChannelReplicator;
 
     #[tokio::test]
     pub async fn test_write_wal() {
@@ -257,6 +356,7 @@ mod tests {
 
         assert_eq!(1, res.0);
         assert_eq!(5 + 32, res.1);
+        assert_eq!(10, wal.bytes_written());
     }
 
     #[tokio::test]
@@ -284,11 +384,43 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn test_write_wal_with_replicator() {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
+        let (replicator, mut receiver) = ChannelReplicator::new(8);
+        let replicator = Arc::new(replicator);
+        let wal = Wal::new(0, Arc::new(log_store)).with_replicator(Some(replicator.clone()));
+        assert_eq!(None, wal.replicated_sequence());
+
+        wal.write(1, b"test1").await.unwrap();
+        wal.write(2, b"test2").await.unwrap();
+        assert_eq!(Some(0), wal.replicated_sequence());
+
+        let entry = receiver.recv().await.unwrap();
+        assert_eq!(1, entry.sequence);
+        assert_eq!(b"test1", &entry.data[..]);
+        replicator.ack(0, entry.sequence);
+        assert_eq!(Some(1), wal.replicated_sequence());
+
+        // The standby catches up from the acknowledged sequence.
+        let entries: Vec<_> = wal
+            .read_entries(2)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(1, entries.len());
+        assert_eq!(receiver.recv().await.unwrap(), entries[0]);
+    }
+
     #[test]
     pub fn test_wal_header_codec() {
         let wal_header = WalHeader {
             last_manifest_version: 99999999,
             mutation_types: vec![],
+            delete_ranges: vec![],
         };
 
         let mut buf: Vec<u8> = vec![];
@@ -18,15 +18,19 @@ mod compat;
 use std::collections::HashMap;
 
 use common_recordbatch::RecordBatch;
+use common_time::range::TimeRange;
+use common_time::timestamp::{TimeUnit, Timestamp};
+use common_time::TimestampRange;
 use datatypes::data_type::{ConcreteDataType, DataType};
 use datatypes::schema::{ColumnSchema, SchemaRef};
 use datatypes::value::ValueRef;
 use datatypes::vectors::VectorRef;
 use snafu::{ensure, OptionExt, ResultExt};
-use store_api::storage::{OpType, WriteRequest};
+use store_api::storage::{OpType, SchemaCheckMode, WriteRequest};
 
 use crate::error::{
-    BatchMissingColumnSnafu, CreateDefaultSnafu, CreateRecordBatchSnafu, Error, HasNullSnafu,
+    BatchMissingColumnSnafu, BatchMissingTimestampSnafu, CreateDefaultSnafu,
+    CreateRecordBatchSnafu, Error, HasNullSnafu, IllegalTimestampColumnTypeSnafu,
     MoreColumnThanExpectedSnafu, RequestTooLargeSnafu, Result, TypeMismatchSnafu,
     UnequalLengthsSnafu, UnknownColumnSnafu,
 };
@@ -45,6 +49,8 @@ pub struct Payload {
     /// This schema doesn't contain internal columns.
     pub schema: SchemaRef,
     pub mutations: Vec<Mutation>,
+    /// Time ranges to delete, bounds are in the unit of the time index column.
+    pub delete_ranges: Vec<TimeRange<i64>>,
 }
 
 impl Payload {
@@ -53,13 +59,14 @@ impl Payload {
         Payload {
             schema,
             mutations: Vec::new(),
+            delete_ranges: Vec::new(),
         }
     }
 
-    /// Returns true if there is no mutation in the payload.
+    /// Returns true if there is no mutation and no time range to delete in the payload.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.mutations.is_empty()
+        self.mutations.is_empty() && self.delete_ranges.is_empty()
     }
 }
 
@@ -85,6 +92,8 @@ pub struct WriteBatch {
     /// The `WriteBatch` use this index to locate all row key columns from
     /// the schema.
     row_key_end: usize,
+    /// Mode to check the data to put against the schema.
+    schema_check_mode: SchemaCheckMode,
 }
 
 impl WriteRequest for WriteBatch {
@@ -123,6 +132,21 @@ impl WriteRequest for WriteBatch {
 
         Ok(())
     }
+
+    fn delete_range(&mut self, range: TimestampRange) -> Result<()> {
+        let range = self.to_time_index_range(&range)?;
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        self.payload.delete_ranges.push(range);
+
+        Ok(())
+    }
+
+    fn set_schema_check_mode(&mut self, mode: SchemaCheckMode) {
+        self.schema_check_mode = mode;
+    }
 }
 
 // WriteBatch pub methods.
@@ -141,6 +165,7 @@ impl WriteBatch {
             payload: Payload::new(schema),
             num_rows_to_mutate: 0,
             row_key_end,
+            schema_check_mode: SchemaCheckMode::default(),
         }
     }
 
@@ -153,12 +178,19 @@ impl WriteBatch {
     pub fn payload(&self) -> &Payload {
         &self.payload
     }
+
+    /// Returns the number of rows to put or delete.
+    #[inline]
+    pub fn num_rows_to_mutate(&self) -> usize {
+        self.num_rows_to_mutate
+    }
 }
 
 impl WriteBatch {
     /// Validates `data` and converts it into a [RecordBatch].
     ///
-    /// It fills missing columns by schema's default values.
+    /// It fills missing columns by schema's default values. Columns not in the schema
+    /// are rejected or ignored according to the [SchemaCheckMode].
     fn process_put_data(&self, data: NameToVector) -> Result<RecordBatch> {
         let num_rows = data.num_rows();
         let mut columns = Vec::with_capacity(self.schema().num_columns());
@@ -179,11 +211,13 @@ impl WriteBatch {
 
         // Check all columns in data also exists in schema, which means we
         // are not inserting unknown columns.
-        for name in data.0.keys() {
-            ensure!(
-                self.schema().contains_column(name),
-                UnknownColumnSnafu { name }
-            );
+        if self.schema_check_mode == SchemaCheckMode::Strict {
+            for name in data.0.keys() {
+                ensure!(
+                    self.schema().contains_column(name),
+                    UnknownColumnSnafu { name }
+                );
+            }
         }
 
         RecordBatch::new(self.schema().clone(), columns).context(CreateRecordBatchSnafu)
@@ -239,12 +273,56 @@ impl WriteBatch {
         Ok(())
     }
 
+    /// Converts `range` into a range of raw values of the time index column.
+    ///
+    /// Bounds are rounded up if the time index has a coarser unit, so the converted
+    /// range contains the same timestamps as `range`.
+    fn to_time_index_range(&self, range: &TimestampRange) -> Result<TimeRange<i64>> {
+        let column_schema = self
+            .schema()
+            .timestamp_column()
+            .context(BatchMissingTimestampSnafu)?;
+        let (start, end) = match &column_schema.data_type {
+            ConcreteDataType::Timestamp(t) => (
+                convert_ceil(range.start(), t.unit()),
+                convert_ceil(range.end(), t.unit()),
+            ),
+            ConcreteDataType::Int64(_) => (range.start().value(), range.end().value()),
+            data_type => {
+                return IllegalTimestampColumnTypeSnafu {
+                    data_type: data_type.clone(),
+                }
+                .fail()
+            }
+        };
+
+        // Safety: Rounding up keeps the order of bounds, so `start <= end` still holds.
+        Ok(TimeRange::new(start, end).unwrap())
+    }
+
     /// Returns all row key columns in the schema.
     fn row_key_column_schemas(&self) -> &[ColumnSchema] {
         &self.payload.schema.column_schemas()[..self.row_key_end]
     }
 }
 
+/// Converts `ts` to `unit`, rounds up if `unit` is coarser than the unit of `ts`.
+fn convert_ceil(ts: &Timestamp, unit: TimeUnit) -> i64 {
+    let (from, to) = (ts.unit().factor(), unit.factor());
+    let value = ts.value();
+    if to <= from {
+        return value.saturating_mul(from / to);
+    }
+
+    let factor = to / from;
+    // Integer division rounds toward zero, which already rounds up negative values.
+    if value > 0 && value % factor != 0 {
+        value / factor + 1
+    } else {
+        value / factor
+    }
+}
+
 /// Returns the length of the first vector in `data`.
 fn first_vector_len(data: &HashMap<String, VectorRef>) -> usize {
     data.values().next().map(|col| col.len()).unwrap_or(0)
@@ -525,6 +603,35 @@ mod tests {
         assert_eq!(StatusCode::TableColumnNotFound, err.status_code());
     }
 
+    #[test]
+    fn test_put_relaxed() {
+        let intv = Arc::new(UInt64Vector::from_slice(&[1, 2, 3])) as VectorRef;
+        let tsv = Arc::new(TimestampMillisecondVector::from_slice(&[0, 0, 0])) as VectorRef;
+        let boolv = Arc::new(BooleanVector::from(vec![true, false, true])) as VectorRef;
+
+        // Missing nullable column v1 and an unknown column v2.
+        let mut put_data = HashMap::new();
+        put_data.insert("k1".to_string(), intv.clone());
+        put_data.insert(consts::VERSION_COLUMN_NAME.to_string(), intv);
+        put_data.insert("ts".to_string(), tsv);
+        put_data.insert("v2".to_string(), boolv);
+
+        let mut batch = new_test_batch();
+        batch.set_schema_check_mode(SchemaCheckMode::Relaxed);
+        batch.put(put_data.clone()).unwrap();
+
+        let record_batch = &batch.payload().mutations[0].record_batch;
+        assert_eq!(batch.schema(), &record_batch.schema);
+        assert_eq!(3, record_batch.num_rows());
+        assert_eq!(3, record_batch.column_by_name("v1").unwrap().null_count());
+
+        // Unknown column is still rejected in strict mode.
+        batch.set_schema_check_mode(SchemaCheckMode::Strict);
+        let err = batch.put(put_data).unwrap_err();
+        assert_eq!(StatusCode::TableColumnNotFound, err.status_code());
+        assert_eq!(1, batch.payload().mutations.len());
+    }
+
     #[test]
     fn test_put_empty() {
         let mut batch = new_test_batch();
@@ -559,6 +666,39 @@ mod tests {
         assert!(v1.only_null());
     }
 
+    #[test]
+    fn test_write_batch_delete_range() {
+        let mut batch = new_test_batch();
+        let range =
+            TimestampRange::new(Timestamp::new_second(1), Timestamp::new_second(2)).unwrap();
+        batch.delete_range(range).unwrap();
+        // Bounds finer than the time index are rounded up.
+        let range = TimestampRange::new(
+            Timestamp::new(-1_500_000, TimeUnit::Nanosecond),
+            Timestamp::new(2_500_001, TimeUnit::Nanosecond),
+        )
+        .unwrap();
+        batch.delete_range(range).unwrap();
+        // Empty after rounding.
+        let range = TimestampRange::new(
+            Timestamp::new(1, TimeUnit::Nanosecond),
+            Timestamp::new(999_999, TimeUnit::Nanosecond),
+        )
+        .unwrap();
+        batch.delete_range(range).unwrap();
+
+        let payload = batch.payload();
+        assert!(!payload.is_empty());
+        assert!(payload.mutations.is_empty());
+        assert_eq!(
+            vec![
+                TimeRange::new(1000, 2000).unwrap(),
+                TimeRange::new(-1, 3).unwrap()
+            ],
+            payload.delete_ranges
+        );
+    }
+
     #[test]
     fn test_delete_missing_column() {
         let intv = Arc::new(UInt64Vector::from_slice(&[1, 2, 3])) as VectorRef;
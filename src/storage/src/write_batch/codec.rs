@@ -123,7 +123,12 @@ impl<'a> Decoder for PayloadDecoder<'a> {
             }
         );
 
-        Ok(Payload { schema, mutations })
+        Ok(Payload {
+            schema,
+            mutations,
+            // Deleted time ranges are stored in the header of the WAL entry.
+            delete_ranges: Vec::new(),
+        })
     }
 }
 
@@ -32,12 +32,16 @@ pub use datatypes::schema::{
 
 pub use self::chunk::{Chunk, ChunkReader};
 pub use self::descriptors::*;
-pub use self::engine::{CreateOptions, EngineContext, OpenOptions, StorageEngine};
+pub use self::engine::{
+    Compression, CreateOptions, DedupPolicy, EngineContext, OpenOptions, ParquetOptions, SstFormat,
+    StorageEngine,
+};
 pub use self::metadata::RegionMeta;
-pub use self::region::{Region, WriteContext};
+pub use self::region::{Region, RegionMetrics, WriteContext};
 pub use self::requests::{
-    AddColumn, AlterOperation, AlterRequest, GetRequest, ScanRequest, WriteRequest,
+    AddColumn, AlterOperation, AlterRequest, GetRequest, MergeRegionsRequest, ScanOrder,
+    ScanRequest, SchemaCheckMode, SplitRegionRequest, WriteRequest,
 };
 pub use self::responses::{GetResponse, ScanResponse, WriteResponse};
-pub use self::snapshot::{ReadContext, Snapshot};
+pub use self::snapshot::{ReadContext, Snapshot, SnapshotStatistics};
 pub use self::types::{OpType, SequenceNumber};
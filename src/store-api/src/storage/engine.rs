@@ -18,11 +18,16 @@
 //! a [`StorageEngine`] instance manages a bunch of storage unit called [`Region`], which holds
 //! chunks of rows, support operations like PUT/DELETE/SCAN.
 
+use std::collections::HashMap;
+use std::fmt;
+
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use serde::{Deserialize, Serialize};
 
 use crate::storage::descriptors::RegionDescriptor;
 use crate::storage::region::Region;
+use crate::storage::requests::{MergeRegionsRequest, SplitRegionRequest};
 
 /// Storage engine provides primitive operations to store and access data.
 #[async_trait]
@@ -71,6 +76,32 @@ pub trait StorageEngine: Send + Sync + Clone + 'static {
         ctx: &EngineContext,
         name: &str,
     ) -> Result<Option<Self::Region>, Self::Error>;
+
+    /// Splits the `region` into two new regions, returns the left and the right one.
+    ///
+    /// The new regions reference the data of `region` without copying it, so `region`
+    /// should not be written anymore.
+    async fn split_region(
+        &self,
+        ctx: &EngineContext,
+        region: &Self::Region,
+        request: SplitRegionRequest,
+        opts: &CreateOptions,
+    ) -> Result<(Self::Region, Self::Region), Self::Error>;
+
+    /// Merges the adjacent regions `left` and `right` into a new region, keys of
+    /// `left` must be less than keys of `right`.
+    ///
+    /// The new region references the data of both regions without copying it, so
+    /// they should not be written anymore.
+    async fn merge_regions(
+        &self,
+        ctx: &EngineContext,
+        left: &Self::Region,
+        right: &Self::Region,
+        request: MergeRegionsRequest,
+        opts: &CreateOptions,
+    ) -> Result<Self::Region, Self::Error>;
 }
 
 /// Storage engine context.
@@ -82,6 +113,14 @@ pub struct EngineContext {}
 pub struct CreateOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Format of SST files written by the region.
+    pub sst_format: SstFormat,
+    /// Options of parquet SST files written by the region.
+    pub parquet_options: ParquetOptions,
+    /// Tag columns to build inverted index for in SST files.
+    pub sst_index_columns: Vec<String>,
+    /// How rows with the same keys and timestamp are deduplicated.
+    pub dedup_policy: DedupPolicy,
 }
 
 /// Options to open a region.
@@ -89,4 +128,187 @@ pub struct CreateOptions {
 pub struct OpenOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Format of SST files written by the region, files already written are still
+    /// read in their own format.
+    pub sst_format: SstFormat,
+    /// Options of parquet SST files written by the region.
+    pub parquet_options: ParquetOptions,
+    /// Tag columns to build inverted index for in SST files.
+    pub sst_index_columns: Vec<String>,
+    /// How rows with the same keys and timestamp are deduplicated.
+    pub dedup_policy: DedupPolicy,
+}
+
+/// Options of parquet SST files, options not set fall back to the defaults of the
+/// storage engine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParquetOptions {
+    /// Compression codec of columns.
+    pub compression: Option<Compression>,
+    /// Compression codec of specific columns, overrides `compression`.
+    pub column_compression: HashMap<String, Compression>,
+    /// Whether dictionary encoding is enabled.
+    pub dictionary_enabled: Option<bool>,
+    /// Max number of rows in a row group.
+    pub max_row_group_size: Option<usize>,
+}
+
+/// Compression codec of SST files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Uncompressed,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the name of the codec.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Uncompressed => "none",
+            Compression::Snappy => "snappy",
+            Compression::Lz4 => "lz4",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the codec with given `name` (case insensitive), `None` if the codec
+    /// is unknown.
+    pub fn from_name(name: &str) -> Option<Compression> {
+        match name.to_lowercase().as_str() {
+            "none" => Some(Compression::Uncompressed),
+            "snappy" => Some(Compression::Snappy),
+            "lz4" => Some(Compression::Lz4),
+            "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// On-disk format of SST files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SstFormat {
+    /// Apache Parquet.
+    #[default]
+    Parquet,
+    /// Arrow IPC file format.
+    ArrowIpc,
+}
+
+impl SstFormat {
+    /// Returns the name of the format.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SstFormat::Parquet => "parquet",
+            SstFormat::ArrowIpc => "arrow_ipc",
+        }
+    }
+
+    /// Returns the format with given `name` (case insensitive), `None` if the format
+    /// is unknown.
+    pub fn from_name(name: &str) -> Option<SstFormat> {
+        match name.to_lowercase().as_str() {
+            "parquet" => Some(SstFormat::Parquet),
+            "arrow_ipc" => Some(SstFormat::ArrowIpc),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SstFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Policy to deduplicate rows with the same row keys and timestamp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DedupPolicy {
+    /// Only the row written last is visible, so a point could be overwritten.
+    #[default]
+    LastWriteWins,
+    /// All rows written are kept, a deletion still removes all rows written before it.
+    KeepAll,
+}
+
+impl DedupPolicy {
+    /// Returns the name of the policy.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DedupPolicy::LastWriteWins => "last_write_wins",
+            DedupPolicy::KeepAll => "keep_all",
+        }
+    }
+
+    /// Returns the policy with given `name` (case insensitive), `None` if the policy
+    /// is unknown.
+    pub fn from_name(name: &str) -> Option<DedupPolicy> {
+        match name.to_lowercase().as_str() {
+            "last_write_wins" => Some(DedupPolicy::LastWriteWins),
+            "keep_all" => Some(DedupPolicy::KeepAll),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DedupPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sst_format_name() {
+        for format in [SstFormat::Parquet, SstFormat::ArrowIpc] {
+            assert_eq!(Some(format), SstFormat::from_name(format.as_str()));
+            assert_eq!(format.as_str(), format.to_string());
+        }
+        assert_eq!(Some(SstFormat::ArrowIpc), SstFormat::from_name("ARROW_IPC"));
+        assert_eq!(None, SstFormat::from_name("orc"));
+        assert_eq!(SstFormat::Parquet, SstFormat::default());
+    }
+
+    #[test]
+    fn test_dedup_policy_name() {
+        for policy in [DedupPolicy::LastWriteWins, DedupPolicy::KeepAll] {
+            assert_eq!(Some(policy), DedupPolicy::from_name(policy.as_str()));
+            assert_eq!(policy.as_str(), policy.to_string());
+        }
+        assert_eq!(
+            Some(DedupPolicy::KeepAll),
+            DedupPolicy::from_name("KEEP_ALL")
+        );
+        assert_eq!(None, DedupPolicy::from_name("first_write_wins"));
+        assert_eq!(DedupPolicy::LastWriteWins, DedupPolicy::default());
+    }
+
+    #[test]
+    fn test_compression_name() {
+        for compression in [
+            Compression::Uncompressed,
+            Compression::Snappy,
+            Compression::Lz4,
+            Compression::Zstd,
+        ] {
+            assert_eq!(
+                Some(compression),
+                Compression::from_name(compression.as_str())
+            );
+            assert_eq!(compression.as_str(), compression.to_string());
+        }
+        assert_eq!(Some(Compression::Zstd), Compression::from_name("ZSTD"));
+        assert_eq!(None, Compression::from_name("gzip"));
+    }
 }
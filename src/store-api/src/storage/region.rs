@@ -72,6 +72,56 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
     fn write_request(&self) -> Self::WriteRequest;
 
     async fn alter(&self, request: AlterRequest) -> Result<(), Self::Error>;
+
+    /// Flush all data in memtables of the region to storage and wait until the
+    /// flush is done.
+    async fn flush(&self) -> Result<(), Self::Error>;
+
+    /// Remove all data of the region but keep its metadata, writes after truncating
+    /// are still visible.
+    async fn truncate(&self) -> Result<(), Self::Error>;
+
+    /// Returns metrics of the read and write path of the region, regions that don't
+    /// collect metrics return the default metrics.
+    fn metrics(&self) -> RegionMetrics {
+        RegionMetrics::default()
+    }
+}
+
+/// Metrics of the read and write path of a region.
+///
+/// Counters are accumulated since the region is opened, so rates are computed by
+/// the caller from the difference of two samples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionMetrics {
+    /// Number of rows written.
+    pub write_rows: u64,
+    /// Number of write batches written.
+    pub write_batches: u64,
+    /// Total time spent in writing batches, in microseconds.
+    pub write_elapsed_micros: u64,
+    /// Number of scans.
+    pub scans: u64,
+    /// Total time spent in opening scans, in microseconds.
+    pub scan_elapsed_micros: u64,
+    /// Bytes allocated by the memtables, including the immutable ones.
+    pub memtable_bytes: u64,
+    /// Number of rows in the memtables and SSTs, rows deleted or overwritten but not
+    /// yet removed are also counted.
+    pub num_rows: u64,
+    /// Number of SST files in each level.
+    pub sst_files_per_level: Vec<usize>,
+    /// Total size of the SST files.
+    pub sst_bytes: u64,
+    /// Bytes appended to the WAL.
+    pub wal_bytes: u64,
+}
+
+impl RegionMetrics {
+    /// Returns the estimated size of data in the region.
+    pub fn approximate_bytes(&self) -> u64 {
+        self.memtable_bytes + self.sst_bytes
+    }
 }
 
 /// Context for write operations.
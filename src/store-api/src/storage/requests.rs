@@ -16,9 +16,11 @@ use std::collections::{HashMap, HashSet};
 
 use common_error::ext::ErrorExt;
 use common_query::logical_plan::Expr;
+use common_time::TimestampRange;
+use datatypes::value::Value;
 use datatypes::vectors::VectorRef;
 
-use crate::storage::{ColumnDescriptor, RegionDescriptor, SequenceNumber};
+use crate::storage::{ColumnDescriptor, RegionDescriptor, RegionId, SequenceNumber};
 
 /// Write request holds a collection of updates to apply to a region.
 ///
@@ -36,6 +38,29 @@ pub trait WriteRequest: Send {
     ///
     /// `keys` are the row keys, in columnar format, of the rows to delete.
     fn delete(&mut self, keys: HashMap<String, VectorRef>) -> Result<(), Self::Error>;
+
+    /// Delete all rows whose timestamp is in the `range`.
+    ///
+    /// Unlike [WriteRequest::delete], the rows to delete don't need to be enumerated,
+    /// the range is recorded as a tombstone that masks rows written before it.
+    fn delete_range(&mut self, range: TimestampRange) -> Result<(), Self::Error>;
+
+    /// Sets the mode to check the data of following puts against the schema.
+    fn set_schema_check_mode(&mut self, mode: SchemaCheckMode);
+}
+
+/// Mode to check the data to put against the schema of the region.
+///
+/// In both modes, a column of the schema missing from the data is filled by its
+/// default value, or by null if it's nullable and has no default value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaCheckMode {
+    /// Rejects the data if it has columns not in the schema.
+    #[default]
+    Strict,
+    /// Ignores the columns not in the schema, so protocols with sparse fields (e.g.
+    /// InfluxDB line protocol) could write the data without padding it.
+    Relaxed,
 }
 
 #[derive(Default)]
@@ -49,13 +74,37 @@ pub struct ScanRequest {
     pub projection: Option<Vec<usize>>,
     /// Filters pushed down
     pub filters: Vec<Expr>,
+    /// Max number of rows to return, `None` to return all rows.
+    ///
+    /// Rows are returned in the `order` of the row key, so the reader stops after
+    /// the first `limit` rows in that order.
+    pub limit: Option<usize>,
+    /// Order of the row key to return rows in.
+    pub order: ScanOrder,
+}
+
+/// Order of rows returned by a scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScanOrder {
+    /// Returns rows in ascending order.
+    #[default]
+    Asc,
+    /// Returns rows in descending order, e.g. to read the latest rows first.
+    Desc,
+}
+
+impl ScanOrder {
+    /// Returns true if rows are returned in descending order.
+    pub fn is_desc(&self) -> bool {
+        *self == ScanOrder::Desc
+    }
 }
 
 #[derive(Debug)]
 pub struct GetRequest {}
 
 /// Operation to add a column.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AddColumn {
     /// Descriptor of the column to add.
     pub desc: ColumnDescriptor,
@@ -64,7 +113,7 @@ pub struct AddColumn {
 }
 
 /// Operation to alter a region.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AlterOperation {
     /// Add columns to the region.
     AddColumns {
@@ -130,6 +179,27 @@ pub struct AlterRequest {
     pub version: u32,
 }
 
+/// Request to split a region into two new regions by a row key column.
+#[derive(Debug, Clone)]
+pub struct SplitRegionRequest {
+    /// Name of the row key column to split by.
+    pub column_name: String,
+    /// Rows whose key is less than the split key belong to the left region, others
+    /// belong to the right region.
+    pub split_key: Value,
+    pub left_id: RegionId,
+    pub left_name: String,
+    pub right_id: RegionId,
+    pub right_name: String,
+}
+
+/// Request to merge two adjacent regions into a new region.
+#[derive(Debug, Clone)]
+pub struct MergeRegionsRequest {
+    pub id: RegionId,
+    pub name: String,
+}
+
 #[cfg(test)]
 mod tests {
     use datatypes::prelude::*;
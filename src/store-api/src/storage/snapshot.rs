@@ -14,6 +14,7 @@
 
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use common_time::Timestamp;
 use datatypes::schema::SchemaRef;
 
 use crate::storage::chunk::ChunkReader;
@@ -37,6 +38,23 @@ pub trait Snapshot: Send + Sync {
 
     async fn get(&self, ctx: &ReadContext, request: GetRequest)
         -> Result<GetResponse, Self::Error>;
+
+    /// Returns estimated statistics of data in this snapshot.
+    fn statistics(&self) -> SnapshotStatistics;
+}
+
+/// Estimated statistics of data in a snapshot.
+///
+/// Rows deleted or overwritten but not yet removed by the storage engine are also
+/// counted, so statistics are never exact.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotStatistics {
+    /// Number of rows.
+    pub num_rows: usize,
+    /// Estimated size of data in bytes.
+    pub total_bytes: usize,
+    /// Min and max (inclusive) value of the timestamp column, `None` if unknown.
+    pub time_range: Option<(Timestamp, Timestamp)>,
 }
 
 /// Context for read.
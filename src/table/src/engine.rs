@@ -16,7 +16,9 @@ use std::fmt::{self, Display};
 use std::sync::Arc;
 
 use crate::error::Result;
-use crate::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
+use crate::requests::{
+    AlterTableRequest, CloseTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
+};
 use crate::TableRef;
 
 /// Represents a resolved path to a table of the form “catalog.schema.table”
@@ -97,6 +99,11 @@ pub trait TableEngine: Send + Sync {
 
     /// Drops the given table. Return true if the table is dropped, or false if the table doesn't exist.
     async fn drop_table(&self, ctx: &EngineContext, request: DropTableRequest) -> Result<bool>;
+
+    /// Closes the given table and keeps its data, so the table could be opened again, e.g.
+    /// by another node sharing the storage. Return true if the table is closed, or false if
+    /// the table isn't opened.
+    async fn close_table(&self, ctx: &EngineContext, request: CloseTableRequest) -> Result<bool>;
 }
 
 pub type TableEngineRef = Arc<dyn TableEngine>;
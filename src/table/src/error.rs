@@ -19,6 +19,8 @@ use common_recordbatch::error::Error as RecordBatchError;
 use datafusion::error::DataFusionError;
 use datatypes::arrow::error::ArrowError;
 
+use crate::validate::InsertViolations;
+
 common_error::define_opaque_error!(Error);
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -63,6 +65,12 @@ pub enum InnerError {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to allocate table id, source: {}", source))]
+    AllocateTableId {
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Column {} already exists in table {}", column_name, table_name))]
     ColumnExists {
         column_name: String,
@@ -107,6 +115,31 @@ pub enum InnerError {
         column_name: String,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Operation {} not supported by table {}", operation, table_name))]
+    Unsupported {
+        operation: String,
+        table_name: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Incompatible schema change of table {}, changes: {}",
+        table_name,
+        changes
+    ))]
+    IncompatibleSchema {
+        table_name: String,
+        changes: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid insert into table {}, violations: {}", table_name, violations))]
+    InvalidInsert {
+        table_name: String,
+        violations: InsertViolations,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for InnerError {
@@ -116,13 +149,16 @@ impl ErrorExt for InnerError {
             | InnerError::PollStream { .. }
             | InnerError::SchemaConversion { .. }
             | InnerError::TableProjection { .. } => StatusCode::EngineExecuteQuery,
-            InnerError::RemoveColumnInIndex { .. } | InnerError::BuildColumnDescriptor { .. } => {
-                StatusCode::InvalidArguments
-            }
+            InnerError::RemoveColumnInIndex { .. }
+            | InnerError::BuildColumnDescriptor { .. }
+            | InnerError::IncompatibleSchema { .. }
+            | InnerError::InvalidInsert { .. } => StatusCode::InvalidArguments,
             InnerError::TablesRecordBatch { .. } => StatusCode::Unexpected,
             InnerError::ColumnExists { .. } => StatusCode::TableColumnExists,
             InnerError::SchemaBuild { source, .. } => source.status_code(),
+            InnerError::AllocateTableId { source } => source.status_code(),
             InnerError::ColumnNotExists { .. } => StatusCode::TableColumnNotFound,
+            InnerError::Unsupported { .. } => StatusCode::Unsupported,
         }
     }
 
@@ -19,6 +19,7 @@ pub mod predicate;
 pub mod requests;
 pub mod table;
 pub mod test_util;
+pub mod validate;
 
 pub use crate::error::{Error, Result};
 pub use crate::table::{Table, TableRef};
@@ -18,6 +18,9 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 pub use datatypes::error::{Error as ConvertError, Result as ConvertResult};
+use datatypes::schema::compat::{
+    check_schema_compat, CompatReport, SchemaChange, SchemaChangeKind,
+};
 use datatypes::schema::{ColumnSchema, RawSchema, Schema, SchemaBuilder, SchemaRef};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -56,6 +59,20 @@ pub enum TableType {
     View,
     /// A transient table.
     Temporary,
+    /// A read-only table whose data is stored in files outside of the database.
+    External,
+}
+
+impl TableType {
+    /// Returns the name of the table type in the SQL standard.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TableType::Base => "BASE TABLE",
+            TableType::View => "VIEW",
+            TableType::Temporary => "LOCAL TEMPORARY",
+            TableType::External => "EXTERNAL TABLE",
+        }
+    }
 }
 
 /// Identifier of the table.
@@ -132,7 +149,61 @@ impl TableMeta {
         match alter_kind {
             AlterKind::AddColumns { columns } => self.add_columns(table_name, columns),
             AlterKind::DropColumns { names } => self.remove_columns(table_name, names),
+            // Renaming the table keeps its meta unchanged.
+            AlterKind::RenameTable { .. } => {
+                let mut meta_builder = self.new_meta_builder();
+                meta_builder
+                    .schema(self.schema.clone())
+                    .primary_key_indices(self.primary_key_indices.clone())
+                    .value_indices(self.value_indices.clone())
+                    .region_numbers(self.region_numbers.clone());
+                Ok(meta_builder)
+            }
+        }
+    }
+
+    /// Compares this meta with the `new` meta of the table and reports changes between them.
+    pub fn check_compat(&self, new: &TableMeta) -> CompatReport {
+        let mut report = check_schema_compat(&self.schema, &new.schema);
+
+        let old_keys: Vec<_> = self.row_key_column_names().collect();
+        let new_keys: Vec<_> = new.row_key_column_names().collect();
+        for name in &old_keys {
+            if !new_keys.contains(name) && new.schema.contains_column(name) {
+                report.push(SchemaChange::new(
+                    *name,
+                    SchemaChangeKind::PrimaryKeyChanged { is_key: false },
+                ));
+            }
+        }
+        for name in &new_keys {
+            // Adding a new column to the primary key is allowed.
+            if !old_keys.contains(name) && self.schema.contains_column(name) {
+                report.push(SchemaChange::new(
+                    *name,
+                    SchemaChangeKind::PrimaryKeyChanged { is_key: true },
+                ));
+            }
         }
+
+        report
+    }
+
+    /// Returns error if altering this meta to the `new` meta introduces breaking changes.
+    pub fn ensure_compatible(&self, table_name: &str, new: &TableMeta) -> Result<()> {
+        let report = self.check_compat(new);
+        ensure!(
+            report.is_compatible(),
+            error::IncompatibleSchemaSnafu {
+                table_name,
+                changes: report
+                    .breaking_changes()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }
+        );
+        Ok(())
     }
 
     /// Allocate a new column for the table.
@@ -593,6 +664,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rename_table() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .region_numbers(vec![0, 1])
+            .next_column_id(3)
+            .build()
+            .unwrap();
+
+        let alter_kind = AlterKind::RenameTable {
+            new_table_name: "new_table".to_string(),
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(meta, new_meta);
+    }
+
+    #[test]
+    fn test_check_compat() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema.clone())
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+
+        // Adding nullable columns is compatible.
+        let new_meta = add_columns_to_meta(&meta);
+        assert!(meta.check_compat(&new_meta).is_compatible());
+        meta.ensure_compatible("my_table", &new_meta).unwrap();
+
+        // Moving col2 into the primary key is a breaking change.
+        let new_meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0, 2])
+            .engine("engine")
+            .next_column_id(3)
+            .build()
+            .unwrap();
+        let report = meta.check_compat(&new_meta);
+        assert_eq!(
+            vec![SchemaChange::new(
+                "col2",
+                SchemaChangeKind::PrimaryKeyChanged { is_key: true }
+            )],
+            report.changes
+        );
+        let err = meta.ensure_compatible("my_table", &new_meta).err().unwrap();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
     #[test]
     fn test_remove_multiple_columns_before_timestamp() {
         let column_schemas = vec![
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod filter;
 mod stats;
 
 use common_query::logical_plan::Expr;
@@ -20,6 +21,7 @@ use datafusion::parquet::file::metadata::RowGroupMetaData;
 use datafusion::physical_optimizer::pruning::PruningPredicate;
 use datatypes::schema::SchemaRef;
 
+pub use crate::predicate::filter::ColumnFilter;
 use crate::predicate::stats::RowGroupPruningStatistics;
 
 #[derive(Default, Clone)]
@@ -0,0 +1,429 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+
+use common_query::logical_plan::{DfExpr, Expr};
+use datafusion_common::ScalarValue;
+use datafusion_expr::expr::BinaryExpr;
+use datafusion_expr::{Between, Operator};
+use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use datatypes::vectors::VectorRef;
+
+/// A filter that could be evaluated exactly against values of columns, so readers
+/// applying it return the same rows as the query filtering them again.
+///
+/// Only comparisons between a column and literals of the same type, `IN` lists,
+/// `BETWEEN` and `IS [NOT] NULL` combined by `AND` and `OR` are supported. Rows are
+/// matched by SQL semantics: a comparison with null never matches, and there is no
+/// `NOT` to turn an unknown result into a match.
+#[derive(Debug, Clone)]
+pub struct ColumnFilter {
+    expr: FilterExpr,
+    /// Indices of columns referenced by the filter, sorted and deduplicated.
+    columns: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum FilterExpr {
+    Compare {
+        column: usize,
+        op: Operator,
+        value: Value,
+    },
+    InList {
+        column: usize,
+        values: Vec<Value>,
+        negated: bool,
+    },
+    IsNull {
+        column: usize,
+        negated: bool,
+    },
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+}
+
+impl ColumnFilter {
+    /// Creates a filter from `expr` on columns of `schema`, returns `None` if the `expr`
+    /// can't be evaluated exactly.
+    pub fn try_new(expr: &Expr, schema: &SchemaRef) -> Option<ColumnFilter> {
+        let expr = FilterExpr::try_new(expr.df_expr(), schema)?;
+        let mut columns = Vec::new();
+        expr.collect_columns(&mut columns);
+        columns.sort_unstable();
+        columns.dedup();
+
+        Some(ColumnFilter { expr, columns })
+    }
+
+    /// Returns indices of columns referenced by the filter in the schema.
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+
+    /// Returns true if `expr` could be evaluated exactly and only references the time
+    /// index and the `primary_key_indices` of `schema`, i.e. columns of the row key.
+    pub fn is_row_key_filter(
+        expr: &Expr,
+        schema: &SchemaRef,
+        primary_key_indices: &[usize],
+    ) -> bool {
+        let Some(filter) = ColumnFilter::try_new(expr, schema) else {
+            return false;
+        };
+        let timestamp_index = schema.timestamp_index();
+        filter
+            .columns()
+            .iter()
+            .all(|idx| Some(*idx) == timestamp_index || primary_key_indices.contains(idx))
+    }
+
+    /// Returns true if the `row` of `columns` matches the filter. The `columns` must
+    /// have the same schema the filter is created from.
+    pub fn matches(&self, columns: &[VectorRef], row: usize) -> bool {
+        self.expr.matches(columns, row)
+    }
+}
+
+impl FilterExpr {
+    fn try_new(expr: &DfExpr, schema: &SchemaRef) -> Option<FilterExpr> {
+        match expr {
+            DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And | Operator::Or => {
+                    let left = Box::new(FilterExpr::try_new(left, schema)?);
+                    let right = Box::new(FilterExpr::try_new(right, schema)?);
+                    if *op == Operator::And {
+                        Some(FilterExpr::And(left, right))
+                    } else {
+                        Some(FilterExpr::Or(left, right))
+                    }
+                }
+                Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq => match (left.as_ref(), right.as_ref()) {
+                    (DfExpr::Column(c), DfExpr::Literal(v)) => {
+                        FilterExpr::new_compare(schema, &c.name, *op, v)
+                    }
+                    // Swaps the operands so the column is always on the left.
+                    (DfExpr::Literal(v), DfExpr::Column(c)) => {
+                        FilterExpr::new_compare(schema, &c.name, swap_operator(*op), v)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            DfExpr::Between(Between {
+                expr,
+                negated,
+                low,
+                high,
+            }) => {
+                let (DfExpr::Column(c), DfExpr::Literal(low), DfExpr::Literal(high)) =
+                    (expr.as_ref(), low.as_ref(), high.as_ref())
+                else {
+                    return None;
+                };
+                let (low_op, high_op) = if *negated {
+                    (Operator::Lt, Operator::Gt)
+                } else {
+                    (Operator::GtEq, Operator::LtEq)
+                };
+                let low = Box::new(FilterExpr::new_compare(schema, &c.name, low_op, low)?);
+                let high = Box::new(FilterExpr::new_compare(schema, &c.name, high_op, high)?);
+                if *negated {
+                    Some(FilterExpr::Or(low, high))
+                } else {
+                    Some(FilterExpr::And(low, high))
+                }
+            }
+            DfExpr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let DfExpr::Column(c) = expr.as_ref() else {
+                    return None;
+                };
+                let column = column_index(schema, &c.name)?;
+                let values = list
+                    .iter()
+                    .map(|e| match e {
+                        DfExpr::Literal(v) => literal_value(schema, column, v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(FilterExpr::InList {
+                    column,
+                    values,
+                    negated: *negated,
+                })
+            }
+            DfExpr::IsNull(inner) | DfExpr::IsNotNull(inner) => {
+                let DfExpr::Column(c) = inner.as_ref() else {
+                    return None;
+                };
+                Some(FilterExpr::IsNull {
+                    column: column_index(schema, &c.name)?,
+                    negated: matches!(expr, DfExpr::IsNotNull(_)),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn new_compare(
+        schema: &SchemaRef,
+        column: &str,
+        op: Operator,
+        value: &ScalarValue,
+    ) -> Option<FilterExpr> {
+        let column = column_index(schema, column)?;
+        let value = literal_value(schema, column, value)?;
+        Some(FilterExpr::Compare { column, op, value })
+    }
+
+    fn collect_columns(&self, columns: &mut Vec<usize>) {
+        match self {
+            FilterExpr::Compare { column, .. }
+            | FilterExpr::InList { column, .. }
+            | FilterExpr::IsNull { column, .. } => columns.push(*column),
+            FilterExpr::And(left, right) | FilterExpr::Or(left, right) => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+        }
+    }
+
+    fn matches(&self, columns: &[VectorRef], row: usize) -> bool {
+        match self {
+            FilterExpr::Compare { column, op, value } => {
+                let current = columns[*column].get_ref(row);
+                if current.is_null() || value.is_null() {
+                    return false;
+                }
+                let ordering = current.cmp(&value.as_value_ref());
+                match op {
+                    Operator::Eq => ordering == Ordering::Equal,
+                    Operator::NotEq => ordering != Ordering::Equal,
+                    Operator::Lt => ordering == Ordering::Less,
+                    Operator::LtEq => ordering != Ordering::Greater,
+                    Operator::Gt => ordering == Ordering::Greater,
+                    Operator::GtEq => ordering != Ordering::Less,
+                    _ => unreachable!("Unexpected operator {op} in filter"),
+                }
+            }
+            FilterExpr::InList {
+                column,
+                values,
+                negated,
+            } => {
+                let current = columns[*column].get_ref(row);
+                if current.is_null() {
+                    return false;
+                }
+                let found = values.iter().any(|v| v.as_value_ref() == current);
+                // `NOT IN` a list with null is unknown if the value isn't in the list.
+                if *negated {
+                    !found && !values.iter().any(Value::is_null)
+                } else {
+                    found
+                }
+            }
+            FilterExpr::IsNull { column, negated } => {
+                columns[*column].get_ref(row).is_null() != *negated
+            }
+            FilterExpr::And(left, right) => {
+                left.matches(columns, row) && right.matches(columns, row)
+            }
+            FilterExpr::Or(left, right) => {
+                left.matches(columns, row) || right.matches(columns, row)
+            }
+        }
+    }
+}
+
+fn column_index(schema: &SchemaRef, name: &str) -> Option<usize> {
+    let index = schema.column_index_by_name(name)?;
+    let data_type = &schema.column_schemas()[index].data_type;
+    // Floats are ordered differently from DataFusion (e.g. NaN), so they are
+    // filtered by the query instead.
+    if data_type.is_float() {
+        return None;
+    }
+    Some(index)
+}
+
+/// Converts the literal `value` to the type of the `column`, returns `None` if they
+/// have different types.
+fn literal_value(schema: &SchemaRef, column: usize, value: &ScalarValue) -> Option<Value> {
+    let value = Value::try_from(value.clone()).ok()?;
+    if !value.is_null() && value.data_type() != schema.column_schemas()[column].data_type {
+        return None;
+    }
+    Some(value)
+}
+
+fn swap_operator(op: Operator) -> Operator {
+    match op {
+        Operator::Lt => Operator::Gt,
+        Operator::LtEq => Operator::GtEq,
+        Operator::Gt => Operator::Lt,
+        Operator::GtEq => Operator::LtEq,
+        op => op,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_expr::{col, lit};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, SchemaBuilder};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
+
+    use super::*;
+
+    fn new_schema() -> SchemaRef {
+        Arc::new(
+            SchemaBuilder::try_from(vec![
+                ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+                ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                )
+                .with_time_index(true),
+                ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ])
+            .unwrap()
+            .build()
+            .unwrap(),
+        )
+    }
+
+    fn new_columns() -> Vec<VectorRef> {
+        vec![
+            Arc::new(StringVector::from(vec![
+                Some("a"),
+                Some("b"),
+                None,
+                Some("c"),
+            ])),
+            Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2, 3, 4])),
+            Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0, 4.0])),
+        ]
+    }
+
+    fn ts(value: i64) -> DfExpr {
+        lit(ScalarValue::TimestampMillisecond(Some(value), None))
+    }
+
+    /// Returns rows matching the `expr`, or `None` if the `expr` isn't supported.
+    fn select(expr: DfExpr) -> Option<Vec<usize>> {
+        let filter = ColumnFilter::try_new(&expr.into(), &new_schema())?;
+        let columns = new_columns();
+        Some((0..4).filter(|i| filter.matches(&columns, *i)).collect())
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(Some(vec![1]), select(col("host").eq(lit("b"))));
+        assert_eq!(Some(vec![0, 3]), select(col("host").not_eq(lit("b"))));
+        assert_eq!(Some(vec![2, 3]), select(col("ts").gt(ts(2))));
+        assert_eq!(Some(vec![0, 1]), select(ts(3).gt(col("ts"))));
+        assert_eq!(Some(vec![0, 1, 2]), select(col("ts").lt_eq(ts(3))));
+        assert_eq!(Some(vec![1, 2, 3]), select(col("ts").gt_eq(ts(2))));
+        // Comparing with null never matches.
+        assert_eq!(
+            Some(vec![]),
+            select(col("host").not_eq(lit(ScalarValue::Utf8(None))))
+        );
+    }
+
+    #[test]
+    fn test_in_list_and_between() {
+        assert_eq!(
+            Some(vec![0, 3]),
+            select(col("host").in_list(vec![lit("a"), lit("c")], false))
+        );
+        assert_eq!(
+            Some(vec![1]),
+            select(col("host").in_list(vec![lit("a"), lit("c")], true))
+        );
+        assert_eq!(
+            Some(vec![]),
+            select(col("host").in_list(vec![lit("a"), lit(ScalarValue::Utf8(None))], true))
+        );
+        assert_eq!(Some(vec![1, 2]), select(col("ts").between(ts(2), ts(3))));
+        assert_eq!(
+            Some(vec![0, 3]),
+            select(col("ts").not_between(ts(2), ts(3)))
+        );
+    }
+
+    #[test]
+    fn test_combined() {
+        assert_eq!(
+            Some(vec![1]),
+            select(col("host").eq(lit("b")).and(col("ts").gt(ts(1))))
+        );
+        assert_eq!(
+            Some(vec![0, 2, 3]),
+            select(col("host").eq(lit("a")).or(col("ts").gt(ts(2))))
+        );
+        assert_eq!(Some(vec![2]), select(col("host").is_null()));
+        assert_eq!(Some(vec![0, 1, 3]), select(col("host").is_not_null()));
+
+        let filter = ColumnFilter::try_new(
+            &col("ts").gt(ts(1)).and(col("host").eq(lit("b"))).into(),
+            &new_schema(),
+        )
+        .unwrap();
+        assert_eq!(&[0, 1], filter.columns());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        // Types of the column and the literal are different.
+        assert!(select(col("ts").gt(lit(1i64))).is_none());
+        // Floats are not supported.
+        assert!(select(col("cpu").gt(lit(1.0f64))).is_none());
+        assert!(select(col("unknown").eq(lit("a"))).is_none());
+        assert!(select(DfExpr::Not(Box::new(col("host").eq(lit("a"))))).is_none());
+        assert!(select(col("host").eq(lit("a")).or(col("cpu").gt(lit(1.0f64)))).is_none());
+        assert!(select(col("ts").eq(col("ts"))).is_none());
+    }
+
+    #[test]
+    fn test_is_row_key_filter() {
+        let schema = new_schema();
+        let is_row_key_filter = |expr: DfExpr, primary_key_indices: &[usize]| {
+            ColumnFilter::is_row_key_filter(&expr.into(), &schema, primary_key_indices)
+        };
+
+        assert!(is_row_key_filter(col("ts").gt(ts(2)), &[]));
+        assert!(is_row_key_filter(
+            col("host").eq(lit("a")).and(col("ts").gt(ts(2))),
+            &[0]
+        ));
+        assert!(!is_row_key_filter(col("host").eq(lit("a")), &[]));
+        assert!(!is_row_key_filter(col("cpu").gt(lit(1.0f64)), &[2]));
+    }
+}
@@ -15,6 +15,7 @@
 //! Table and TableEngine requests
 use std::collections::HashMap;
 
+use common_time::TimestampRange;
 use datatypes::prelude::VectorRef;
 use datatypes::schema::{ColumnSchema, SchemaRef};
 use store_api::storage::RegionNumber;
@@ -30,6 +31,16 @@ pub struct InsertRequest {
     pub columns_values: HashMap<String, VectorRef>,
 }
 
+/// Delete request that deletes rows by the time index
+#[derive(Debug)]
+pub struct DeleteRangeRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+    /// Rows whose time index is in this range are deleted.
+    pub range: TimestampRange,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreateDatabaseRequest {
     pub db_name: String,
@@ -80,6 +91,7 @@ pub struct AddColumnRequest {
 pub enum AlterKind {
     AddColumns { columns: Vec<AddColumnRequest> },
     DropColumns { names: Vec<String> },
+    RenameTable { new_table_name: String },
 }
 
 /// Drop table request
@@ -89,3 +101,19 @@ pub struct DropTableRequest {
     pub schema_name: String,
     pub table_name: String,
 }
+
+/// Truncate table request
+#[derive(Debug)]
+pub struct TruncateTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
+
+/// Close table request
+#[derive(Debug)]
+pub struct CloseTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
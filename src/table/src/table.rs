@@ -21,12 +21,15 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_query::logical_plan::Expr;
-use common_query::physical_plan::PhysicalPlanRef;
+use common_query::physical_plan::{self, PhysicalPlanRef, Statistics};
+use common_recordbatch::SendableRecordBatchStream;
 use datatypes::schema::SchemaRef;
+use datatypes::value::Value;
+use store_api::storage::{RegionMetrics, RegionNumber, ScanOrder, SequenceNumber};
 
-use crate::error::Result;
+use crate::error::{Result, UnsupportedSnafu};
 use crate::metadata::{FilterPushDownType, TableId, TableInfoRef, TableType};
-use crate::requests::{AlterTableRequest, InsertRequest};
+use crate::requests::{AlterTableRequest, DeleteRangeRequest, InsertRequest};
 
 /// Table abstraction.
 #[async_trait]
@@ -51,6 +54,27 @@ pub trait Table: Send + Sync {
         unimplemented!();
     }
 
+    /// Delete all rows whose time index is in the range of the `request`.
+    async fn delete_range(&self, request: DeleteRangeRequest) -> Result<()> {
+        let _ = request;
+        UnsupportedSnafu {
+            operation: "delete_range",
+            table_name: &self.table_info().name,
+        }
+        .fail()
+        .map_err(Into::into)
+    }
+
+    /// Remove all rows of the table but keep its schema.
+    async fn truncate(&self) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "truncate",
+            table_name: &self.table_info().name,
+        }
+        .fail()
+        .map_err(Into::into)
+    }
+
     /// Scan the table and returns a SendableRecordBatchStream.
     async fn scan(
         &self,
@@ -63,20 +87,151 @@ pub trait Table: Send + Sync {
         limit: Option<usize>,
     ) -> Result<PhysicalPlanRef>;
 
+    /// Scan the table as of the committed `sequence`, only rows written at or before
+    /// the `sequence` are returned.
+    ///
+    /// This is a diagnostic operation for administrators, e.g. debugging ingestion
+    /// issues or verifying replication, so it is not exposed via SQL.
+    async fn scan_at_sequence(
+        &self,
+        projection: Option<&Vec<usize>>,
+        sequence: SequenceNumber,
+    ) -> Result<SendableRecordBatchStream> {
+        let _ = (projection, sequence);
+        UnsupportedSnafu {
+            operation: "scan_at_sequence",
+            table_name: &self.table_info().name,
+        }
+        .fail()
+        .map_err(Into::into)
+    }
+
     /// Tests whether the table provider can make use of a filter expression
     /// to optimise data retrieval.
     fn supports_filter_pushdown(&self, _filter: &Expr) -> Result<FilterPushDownType> {
         Ok(FilterPushDownType::Unsupported)
     }
 
+    /// Scan the table and returns rows sorted by the time index in `order`.
+    ///
+    /// The `limit` is applied to the sorted rows matching all `filters` whose push
+    /// down type is [FilterPushDownType::Exact], so the scan could stop early for a
+    /// query like `SELECT * FROM t ORDER BY ts DESC LIMIT 10`.
+    async fn scan_ordered(
+        &self,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        order: ScanOrder,
+    ) -> Result<PhysicalPlanRef> {
+        let _ = (projection, filters, limit, order);
+        UnsupportedSnafu {
+            operation: "scan_ordered",
+            table_name: &self.table_info().name,
+        }
+        .fail()
+        .map_err(Into::into)
+    }
+
+    /// Returns true if the table supports [Table::scan_ordered], so the limit of a
+    /// query ordered by the time index can be pushed down to the scan.
+    fn scan_in_time_index_order(&self) -> bool {
+        false
+    }
+
+    /// Returns estimated statistics of data in the table, `None` if the table
+    /// doesn't know its statistics.
+    fn statistics(&self) -> Result<Option<TableStatistics>> {
+        Ok(None)
+    }
+
+    /// Returns metrics of the read and write path of each region of the table, tables
+    /// without regions return nothing.
+    fn region_metrics(&self) -> Vec<(RegionNumber, RegionMetrics)> {
+        Vec::new()
+    }
+
     async fn alter(&self, request: AlterTableRequest) -> Result<()> {
         let _ = request;
         unimplemented!()
     }
+
+    /// Rejects writes to the region if `fenced` is true, or accepts them again. Writes in
+    /// progress are finished before the region is fenced, e.g. so the region could be
+    /// flushed and migrated to another node without losing rows.
+    async fn fence_region(&self, region_number: RegionNumber, fenced: bool) -> Result<()> {
+        let _ = (region_number, fenced);
+        UnsupportedSnafu {
+            operation: "fence_region",
+            table_name: &self.table_info().name,
+        }
+        .fail()
+        .map_err(Into::into)
+    }
+
+    /// Flush data buffered in memory to storage, tables that don't buffer data do
+    /// nothing.
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
 
+/// Estimated statistics of a table.
+///
+/// Rows deleted or overwritten but not yet removed by the storage are also counted,
+/// so statistics must not be used to answer queries directly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableStatistics {
+    /// Number of rows.
+    pub num_rows: usize,
+    /// Estimated size of data in bytes.
+    pub total_bytes: usize,
+    /// Statistics of each column, in the same order as columns of the schema.
+    pub column_statistics: Vec<ColumnStatistics>,
+}
+
+/// Estimated statistics of a column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnStatistics {
+    /// Min value of the column, `None` if unknown.
+    pub min_value: Option<Value>,
+    /// Max value of the column, `None` if unknown.
+    pub max_value: Option<Value>,
+}
+
+impl TableStatistics {
+    /// Converts to statistics of a physical plan whose output schema is `schema`,
+    /// which must have the same columns as `column_statistics`.
+    pub fn to_plan_statistics(&self, schema: &SchemaRef) -> Statistics {
+        let column_statistics = schema
+            .column_schemas()
+            .iter()
+            .zip(&self.column_statistics)
+            .map(|(column_schema, statistics)| {
+                let to_scalar = |value: &Option<Value>| {
+                    value
+                        .as_ref()
+                        .and_then(|v| v.try_to_scalar_value(&column_schema.data_type).ok())
+                };
+                physical_plan::ColumnStatistics {
+                    min_value: to_scalar(&statistics.min_value),
+                    max_value: to_scalar(&statistics.max_value),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Statistics {
+            num_rows: Some(self.num_rows),
+            total_byte_size: Some(self.total_bytes),
+            column_statistics: Some(column_statistics),
+            is_exact: false,
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait TableIdProvider {
     async fn next_table_id(&self) -> Result<TableId>;
@@ -28,6 +28,7 @@ use datafusion::prelude::SessionContext;
 use datafusion_expr::expr::Expr as DfExpr;
 use datatypes::schema::{SchemaRef as TableSchemaRef, SchemaRef};
 use snafu::prelude::*;
+use store_api::storage::ScanOrder;
 
 use crate::error::{self, Result};
 use crate::metadata::TableInfoRef;
@@ -36,16 +37,32 @@ use crate::table::{FilterPushDownType, Table, TableRef, TableType};
 /// Greptime Table ->  datafusion TableProvider
 pub struct DfTableProviderAdapter {
     table: TableRef,
+    /// Order of the time index to scan rows in, rows are returned in any order if
+    /// it's `None`.
+    scan_order: Option<ScanOrder>,
 }
 
 impl DfTableProviderAdapter {
     pub fn new(table: TableRef) -> Self {
-        Self { table }
+        Self {
+            table,
+            scan_order: None,
+        }
+    }
+
+    /// Scans rows by [Table::scan_ordered] in `scan_order`, the table must support it.
+    pub fn with_scan_order(mut self, scan_order: Option<ScanOrder>) -> Self {
+        self.scan_order = scan_order;
+        self
     }
 
     pub fn table(&self) -> TableRef {
         self.table.clone()
     }
+
+    pub fn scan_order(&self) -> Option<ScanOrder> {
+        self.scan_order
+    }
 }
 
 #[async_trait::async_trait]
@@ -60,7 +77,7 @@ impl TableProvider for DfTableProviderAdapter {
 
     fn table_type(&self) -> DfTableType {
         match self.table.table_type() {
-            TableType::Base => DfTableType::Base,
+            TableType::Base | TableType::External => DfTableType::Base,
             TableType::View => DfTableType::View,
             TableType::Temporary => DfTableType::Temporary,
         }
@@ -74,7 +91,14 @@ impl TableProvider for DfTableProviderAdapter {
         limit: Option<usize>,
     ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
         let filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
-        let inner = self.table.scan(projection, &filters, limit).await?;
+        let inner = match self.scan_order {
+            Some(order) => {
+                self.table
+                    .scan_ordered(projection, &filters, limit, order)
+                    .await?
+            }
+            None => self.table.scan(projection, &filters, limit).await?,
+        };
         Ok(Arc::new(DfPhysicalPlanAdapter(inner)))
     }
 
@@ -18,15 +18,19 @@ use std::sync::{Arc, Mutex};
 
 use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
-use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef};
+use common_query::physical_plan::{Partitioning, PhysicalPlan, PhysicalPlanRef, Statistics};
 use common_recordbatch::SendableRecordBatchStream;
+use datafusion::arrow::compute::SortOptions;
 use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::expressions::{Column, PhysicalSortExpr};
 use datatypes::schema::SchemaRef;
 use snafu::OptionExt;
 
 pub struct SimpleTableScan {
     stream: Mutex<Option<SendableRecordBatchStream>>,
     schema: SchemaRef,
+    statistics: Statistics,
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
 }
 
 impl Debug for SimpleTableScan {
@@ -34,6 +38,8 @@ impl Debug for SimpleTableScan {
         f.debug_struct("SimpleTableScan")
             .field("stream", &"<SendableRecordBatchStream>")
             .field("schema", &self.schema)
+            .field("statistics", &self.statistics)
+            .field("output_ordering", &self.output_ordering)
             .finish()
     }
 }
@@ -44,8 +50,37 @@ impl SimpleTableScan {
         Self {
             stream: Mutex::new(Some(stream)),
             schema,
+            statistics: Statistics::default(),
+            output_ordering: None,
         }
     }
+
+    /// Sets the estimated statistics of rows returned by the scan.
+    pub fn with_statistics(mut self, statistics: Statistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    /// Sets the order of rows returned by the stream.
+    pub fn with_output_ordering(mut self, output_ordering: Option<Vec<PhysicalSortExpr>>) -> Self {
+        self.output_ordering = output_ordering;
+        self
+    }
+}
+
+/// Returns the order of the time index in `schema`, `None` if the schema has no
+/// time index.
+pub fn time_index_ordering(schema: &SchemaRef, descending: bool) -> Option<Vec<PhysicalSortExpr>> {
+    let index = schema.timestamp_index()?;
+    let column = &schema.column_schemas()[index];
+    Some(vec![PhysicalSortExpr {
+        expr: Arc::new(Column::new(&column.name, index)),
+        options: SortOptions {
+            descending,
+            // Same as the default of `ORDER BY`, the time index is never null.
+            nulls_first: descending,
+        },
+    }])
 }
 
 impl PhysicalPlan for SimpleTableScan {
@@ -61,6 +96,10 @@ impl PhysicalPlan for SimpleTableScan {
         Partitioning::UnknownPartitioning(1)
     }
 
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_ordering.as_deref()
+    }
+
     fn children(&self) -> Vec<PhysicalPlanRef> {
         vec![]
     }
@@ -77,6 +116,10 @@ impl PhysicalPlan for SimpleTableScan {
         let mut stream = self.stream.lock().unwrap();
         stream.take().context(query_error::ExecuteRepeatedlySnafu)
     }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics.clone()
+    }
 }
 
 #[cfg(test)]
@@ -84,7 +127,7 @@ mod test {
     use common_recordbatch::{util, RecordBatch, RecordBatches};
     use datafusion::prelude::SessionContext;
     use datatypes::data_type::ConcreteDataType;
-    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::schema::{ColumnSchema, Schema, SchemaBuilder};
     use datatypes::vectors::Int32Vector;
 
     use super::*;
@@ -113,9 +156,15 @@ mod test {
             RecordBatches::try_new(schema.clone(), vec![batch1.clone(), batch2.clone()]).unwrap();
         let stream = recordbatches.as_stream();
 
-        let scan = SimpleTableScan::new(stream);
+        let statistics = Statistics {
+            num_rows: Some(5),
+            ..Default::default()
+        };
+        let scan = SimpleTableScan::new(stream).with_statistics(statistics.clone());
 
         assert_eq!(scan.schema(), schema);
+        assert_eq!(statistics, scan.statistics());
+        assert!(scan.output_ordering().is_none());
 
         let stream = scan.execute(0, ctx.task_ctx()).unwrap();
         let recordbatches = util::collect(stream).await.unwrap();
@@ -131,4 +180,37 @@ mod test {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_time_index_ordering() {
+        let schema = Arc::new(Schema::new(vec![ColumnSchema::new(
+            "a",
+            ConcreteDataType::int32_datatype(),
+            false,
+        )]));
+        assert!(time_index_ordering(&schema, false).is_none());
+
+        let schema = Arc::new(
+            SchemaBuilder::try_from(vec![
+                ColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
+                ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                )
+                .with_time_index(true),
+            ])
+            .unwrap()
+            .build()
+            .unwrap(),
+        );
+        let ordering = time_index_ordering(&schema, false).unwrap();
+        assert_eq!(1, ordering.len());
+        assert_eq!("ts@1", ordering[0].expr.to_string());
+        assert!(!ordering[0].options.descending);
+
+        let ordering = time_index_ordering(&schema, true).unwrap();
+        assert!(ordering[0].options.descending);
+        assert!(ordering[0].options.nulls_first);
+    }
 }
@@ -19,7 +19,9 @@ use async_trait::async_trait;
 use tokio::sync::Mutex;
 
 use crate::engine::{EngineContext, TableEngine, TableReference};
-use crate::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
+use crate::requests::{
+    AlterTableRequest, CloseTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
+};
 use crate::test_util::EmptyTable;
 use crate::{Result, TableRef};
 
@@ -96,4 +98,8 @@ impl TableEngine for MockTableEngine {
     async fn drop_table(&self, _ctx: &EngineContext, _request: DropTableRequest) -> Result<bool> {
         unimplemented!()
     }
+
+    async fn close_table(&self, _ctx: &EngineContext, _request: CloseTableRequest) -> Result<bool> {
+        unimplemented!()
+    }
 }
@@ -0,0 +1,375 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of the data to insert against the schema of a table.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use datatypes::data_type::{ConcreteDataType, DataType};
+use datatypes::prelude::VectorRef;
+use datatypes::schema::Schema;
+use snafu::ensure;
+
+use crate::error::{InvalidInsertSnafu, Result};
+
+/// Default max number of violations reported, which is also the max number of rows
+/// reported for each column with null values.
+pub const DEFAULT_MAX_VIOLATIONS: usize = 16;
+
+/// A column of the data to insert that violates the schema of the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnViolation {
+    /// The column is not in the schema.
+    UnknownColumn { column: String },
+    /// The column is not provided, but it's not nullable and has no default value.
+    MissingColumn { column: String },
+    /// Data type of the column differs from the schema.
+    TypeMismatch {
+        column: String,
+        expected: ConcreteDataType,
+        actual: ConcreteDataType,
+    },
+    /// The number of rows of the column differs from other columns.
+    UnequalLength {
+        column: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// The column is not nullable but has `null_count` null values, `rows` are the
+    /// indexes of the leading rows with null values.
+    NullValue {
+        column: String,
+        null_count: usize,
+        rows: Vec<usize>,
+    },
+}
+
+impl ColumnViolation {
+    /// Returns the name of the column.
+    pub fn column(&self) -> &str {
+        match self {
+            ColumnViolation::UnknownColumn { column }
+            | ColumnViolation::MissingColumn { column }
+            | ColumnViolation::TypeMismatch { column, .. }
+            | ColumnViolation::UnequalLength { column, .. }
+            | ColumnViolation::NullValue { column, .. } => column,
+        }
+    }
+}
+
+impl fmt::Display for ColumnViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnViolation::UnknownColumn { column } => {
+                write!(f, "column {column} is not in the table")
+            }
+            ColumnViolation::MissingColumn { column } => {
+                write!(
+                    f,
+                    "column {column} is missing, it's not nullable and has no default value"
+                )
+            }
+            ColumnViolation::TypeMismatch {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column {column} expects type {}, but is {}",
+                expected.name(),
+                actual.name()
+            ),
+            ColumnViolation::UnequalLength {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column {column} expects {expected} rows, but has {actual} rows"
+            ),
+            ColumnViolation::NullValue {
+                column,
+                null_count,
+                rows,
+            } => {
+                write!(
+                    f,
+                    "column {column} is not nullable, but has {null_count} null values at rows {rows:?}"
+                )?;
+                if rows.len() < *null_count {
+                    write!(f, " and more")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Violations of the data to insert.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InsertViolations {
+    pub violations: Vec<ColumnViolation>,
+    /// Whether there are more violations than reported.
+    pub truncated: bool,
+}
+
+impl InsertViolations {
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for InsertViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{violation}")?;
+        }
+        if self.truncated {
+            write!(f, "; and more violations")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates `columns_values` to insert into table `table_name` against its `schema`,
+/// the error reports all violations, up to [DEFAULT_MAX_VIOLATIONS].
+pub fn validate_insert(
+    table_name: &str,
+    schema: &Schema,
+    columns_values: &HashMap<String, VectorRef>,
+) -> Result<()> {
+    let violations = find_insert_violations(schema, columns_values, DEFAULT_MAX_VIOLATIONS);
+    ensure!(
+        violations.is_empty(),
+        InvalidInsertSnafu {
+            table_name,
+            violations,
+        }
+    );
+    Ok(())
+}
+
+/// Finds at most `max_violations` violations of `columns_values` against `schema`.
+///
+/// Columns are checked in the order of the schema, then the unknown columns in the
+/// order of their names.
+pub fn find_insert_violations(
+    schema: &Schema,
+    columns_values: &HashMap<String, VectorRef>,
+    max_violations: usize,
+) -> InsertViolations {
+    let mut violations = Vec::new();
+
+    // Takes the number of rows of the first provided column as the expected one.
+    let num_rows = schema
+        .column_schemas()
+        .iter()
+        .find_map(|column_schema| columns_values.get(&column_schema.name))
+        .or_else(|| columns_values.values().next())
+        .map(|vector| vector.len())
+        .unwrap_or(0);
+
+    for column_schema in schema.column_schemas() {
+        let column = &column_schema.name;
+        let Some(vector) = columns_values.get(column) else {
+            if !column_schema.is_nullable() && column_schema.default_constraint().is_none() {
+                violations.push(ColumnViolation::MissingColumn {
+                    column: column.clone(),
+                });
+            }
+            continue;
+        };
+
+        if vector.len() != num_rows {
+            violations.push(ColumnViolation::UnequalLength {
+                column: column.clone(),
+                expected: num_rows,
+                actual: vector.len(),
+            });
+        }
+
+        let actual = vector.data_type();
+        // A NullVector could be inserted into columns of any type.
+        if !actual.is_null() && actual != column_schema.data_type {
+            violations.push(ColumnViolation::TypeMismatch {
+                column: column.clone(),
+                expected: column_schema.data_type.clone(),
+                actual,
+            });
+        }
+
+        let null_count = vector.null_count();
+        if !column_schema.is_nullable() && null_count > 0 {
+            let rows = (0..vector.len())
+                .filter(|row| vector.is_null(*row))
+                .take(max_violations)
+                .collect();
+            violations.push(ColumnViolation::NullValue {
+                column: column.clone(),
+                null_count,
+                rows,
+            });
+        }
+    }
+
+    let mut unknown_columns: Vec<_> = columns_values
+        .keys()
+        .filter(|column| !schema.contains_column(column))
+        .collect();
+    unknown_columns.sort();
+    violations.extend(
+        unknown_columns
+            .into_iter()
+            .map(|column| ColumnViolation::UnknownColumn {
+                column: column.clone(),
+            }),
+    );
+
+    let truncated = violations.len() > max_violations;
+    violations.truncate(max_violations);
+    InsertViolations {
+        violations,
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
+    use datatypes::value::Value;
+    use datatypes::vectors::{Int32Vector, NullVector, StringVector};
+
+    use super::*;
+
+    fn new_schema() -> Schema {
+        Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new("cpu", ConcreteDataType::int32_datatype(), true),
+            ColumnSchema::new("memory", ConcreteDataType::int32_datatype(), false)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value(Value::Int32(0))))
+                .unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_valid_insert() {
+        let schema = new_schema();
+        let columns_values = HashMap::from([
+            (
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["a", "b"])) as _,
+            ),
+            (
+                "cpu".to_string(),
+                Arc::new(Int32Vector::from(vec![Some(1), None])) as _,
+            ),
+        ]);
+        assert!(find_insert_violations(&schema, &columns_values, 10).is_empty());
+
+        // Null vector is allowed for nullable columns.
+        let columns_values = HashMap::from([
+            (
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["a", "b"])) as _,
+            ),
+            ("cpu".to_string(), Arc::new(NullVector::new(2)) as _),
+        ]);
+        assert!(validate_insert("demo", &schema, &columns_values).is_ok());
+    }
+
+    #[test]
+    fn test_insert_violations() {
+        let schema = new_schema();
+        let columns_values = HashMap::from([
+            (
+                "cpu".to_string(),
+                Arc::new(StringVector::from(vec!["a", "b"])) as _,
+            ),
+            (
+                "memory".to_string(),
+                Arc::new(Int32Vector::from(vec![None, Some(1), None])) as _,
+            ),
+            (
+                "disk".to_string(),
+                Arc::new(Int32Vector::from_slice(&[1, 2])) as _,
+            ),
+        ]);
+
+        let violations = find_insert_violations(&schema, &columns_values, 10);
+        assert!(!violations.truncated);
+        assert_eq!(
+            vec![
+                ColumnViolation::MissingColumn {
+                    column: "host".to_string()
+                },
+                ColumnViolation::TypeMismatch {
+                    column: "cpu".to_string(),
+                    expected: ConcreteDataType::int32_datatype(),
+                    actual: ConcreteDataType::string_datatype(),
+                },
+                ColumnViolation::UnequalLength {
+                    column: "memory".to_string(),
+                    expected: 2,
+                    actual: 3,
+                },
+                ColumnViolation::NullValue {
+                    column: "memory".to_string(),
+                    null_count: 2,
+                    rows: vec![0, 2],
+                },
+                ColumnViolation::UnknownColumn {
+                    column: "disk".to_string()
+                },
+            ],
+            violations.violations
+        );
+
+        let violations = find_insert_violations(&schema, &columns_values, 1);
+        assert!(violations.truncated);
+        assert_eq!(
+            "column host is missing, it's not nullable and has no default value; and more violations",
+            violations.to_string()
+        );
+
+        let err = validate_insert("demo", &schema, &columns_values).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("Invalid insert into table demo"), "{msg}");
+        assert!(
+            msg.contains("column cpu expects type Int32, but is String"),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn test_null_rows_limit() {
+        let schema = new_schema();
+        let columns_values = HashMap::from([(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec![None, Some("a"), None])) as _,
+        )]);
+
+        let violations = find_insert_violations(&schema, &columns_values, 1);
+        assert!(!violations.truncated);
+        assert_eq!(
+            "column host is not nullable, but has 2 null values at rows [0] and more",
+            violations.to_string()
+        );
+    }
+}
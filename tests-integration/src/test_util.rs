@@ -97,8 +97,11 @@ fn get_test_store_config(
             let config = ObjectStoreConfig::S3 {
                 root,
                 bucket,
-                access_key_id: key_id,
-                secret_access_key: secret_key,
+                access_key_id: Some(key_id),
+                secret_access_key: Some(secret_key),
+                endpoint: None,
+                region: None,
+                enable_virtual_host_style: false,
             };
 
             let store = ObjectStore::new(accessor);
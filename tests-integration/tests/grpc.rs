@@ -17,8 +17,9 @@ use api::v1::{
     column, AddColumn, AddColumns, AlterExpr, Column, ColumnDataType, ColumnDef, CreateTableExpr,
     InsertRequest, TableId,
 };
-use client::{Client, Database, RpcOutput};
+use client::{Client, Database, InsertBuilder, Precision, Row, RpcOutput};
 use common_catalog::consts::MIN_USER_TABLE_ID;
+use common_recordbatch::RecordBatches;
 use servers::server::Server;
 use tests_integration::test_util::{setup_grpc_server, StorageType};
 
@@ -54,6 +55,7 @@ macro_rules! grpc_tests {
 
                 test_auto_create_table,
                 test_insert_and_select,
+                test_bulk_insert,
             );
         )*
     };
@@ -167,6 +169,50 @@ pub async fn test_insert_and_select(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_bulk_insert(store_type: StorageType) {
+    let (addr, mut guard, fe_grpc_server) = setup_grpc_server(store_type, "bulk_insert").await;
+
+    let grpc_client = Client::with_urls(vec![addr]);
+    let db = Database::new("greptime", grpc_client);
+
+    let mut builder = InsertBuilder::new("public", "metrics").max_rows_per_request(2);
+    for i in 0..5 {
+        let row = Row::new()
+            .tag("host", format!("host{i}"))
+            .field("cpu", i as f64)
+            .timestamp("ts", i, Precision::Second);
+        builder.push(row).unwrap();
+    }
+    let affected_rows = db.bulk_insert(builder).await.unwrap();
+    assert_eq!(vec![2, 2, 1], affected_rows);
+
+    let result = db
+        .sql("SELECT host, cpu, ts FROM metrics ORDER BY ts")
+        .await
+        .unwrap();
+    match result {
+        RpcOutput::RecordBatches(recordbatches) => {
+            let pretty = recordbatches.pretty_print(None).unwrap();
+            let expected = "\
++-------+-----+---------------------+
+| host  | cpu | ts                  |
++-------+-----+---------------------+
+| host0 | 0   | 1970-01-01T00:00:00 |
+| host1 | 1   | 1970-01-01T00:00:01 |
+| host2 | 2   | 1970-01-01T00:00:02 |
+| host3 | 3   | 1970-01-01T00:00:03 |
+| host4 | 4   | 1970-01-01T00:00:04 |
++-------+-----+---------------------+\
+";
+            assert_eq!(pretty, expected);
+        }
+        _ => unreachable!(),
+    }
+
+    let _ = fe_grpc_server.shutdown().await;
+    guard.remove_all().await;
+}
+
 async fn insert_and_assert(db: &Database) {
     // testing data:
     let (expected_host_col, expected_cpu_col, expected_mem_col, expected_ts_col) = expect_data();
@@ -197,14 +243,7 @@ async fn insert_and_assert(db: &Database) {
     assert!(matches!(result, RpcOutput::AffectedRows(2)));
 
     // select
-    let result = db
-        .sql("SELECT host, cpu, memory, ts FROM demo")
-        .await
-        .unwrap();
-    match result {
-        RpcOutput::RecordBatches(recordbatches) => {
-            let pretty = recordbatches.pretty_print().unwrap();
-            let expected = "\
+    let expected = "\
 +-------+------+--------+-------------------------+
 | host  | cpu  | memory | ts                      |
 +-------+------+--------+-------------------------+
@@ -216,10 +255,31 @@ async fn insert_and_assert(db: &Database) {
 | host6 | 88.8 | 333.3  | 2022-12-28T04:17:08     |
 +-------+------+--------+-------------------------+\
 ";
+    let result = db
+        .sql("SELECT host, cpu, memory, ts FROM demo")
+        .await
+        .unwrap();
+    match result {
+        RpcOutput::RecordBatches(recordbatches) => {
+            let pretty = recordbatches.pretty_print(None).unwrap();
             assert_eq!(pretty, expected);
         }
         _ => unreachable!(),
     }
+
+    // select in stream
+    let stream = db
+        .sql_stream("SELECT host, cpu, memory, ts FROM demo")
+        .await
+        .unwrap();
+    let recordbatches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(recordbatches.pretty_print(None).unwrap(), expected);
+
+    // Only queries returning record batches could be streamed.
+    assert!(db
+        .sql_stream("INSERT INTO demo(host, cpu, memory, ts) VALUES ('host7', 1.0, 1.0, 1)")
+        .await
+        .is_err());
 }
 
 fn testing_create_expr() -> CreateTableExpr {
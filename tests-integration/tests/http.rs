@@ -16,8 +16,7 @@ use axum::http::StatusCode;
 use axum_test_helper::TestClient;
 use common_error::status_code::StatusCode as ErrorCode;
 use serde_json::json;
-use servers::http::handler::HealthResponse;
-use servers::http::{JsonOutput, JsonResponse};
+use servers::http::types::{HealthResponse, JsonOutput, JsonResponse};
 use tests_integration::test_util::{setup_test_app, setup_test_app_with_frontend, StorageType};
 
 #[macro_export]
@@ -53,6 +52,7 @@ macro_rules! http_tests {
                 test_metrics_api,
                 test_scripts_api,
                 test_health_api,
+                test_api_spec,
             );
         )*
     };
@@ -307,3 +307,20 @@ pub async fn test_health_api(store_type: StorageType) {
     let body = serde_json::from_str::<HealthResponse>(&body_text).unwrap();
     assert_eq!(body, HealthResponse {});
 }
+
+pub async fn test_api_spec(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, _guard) = setup_test_app_with_frontend(store_type, "api_spec").await;
+    let client = TestClient::new(app);
+
+    let res = client.get("/api/spec").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body_text = res.text().await;
+    let spec = serde_json::from_str::<serde_json::Value>(&body_text).unwrap();
+    assert!(spec["paths"].get("/v1/sql").is_some());
+
+    // The spec is still served at the old path.
+    let res = client.get("/v1/private/api.json").send().await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(body_text, res.text().await);
+}
@@ -128,7 +128,7 @@ impl Display for ResultDisplayer {
                     write!(f, "Affected Rows: {rows}")
                 }
                 RpcOutput::RecordBatches(recordbatches) => {
-                    let pretty = recordbatches.pretty_print().map_err(|e| e.to_string());
+                    let pretty = recordbatches.pretty_print(None).map_err(|e| e.to_string());
                     match pretty {
                         Ok(s) => write!(f, "{s}"),
                         Err(e) => {